@@ -4,8 +4,9 @@
 //!
 //! ## Pinned against the bot crate's sources
 //!
-//! Console line format is `(<LEVEL>) <target>: <message>`
-//! (screeps-ibex/src/logging.rs:32, the fern dispatch format). Markers:
+//! Console line format is `(<LEVEL>) <target>: <message>`, as
+//! `IbexLogger::log` writes it to the console (screeps-ibex/src/logging.rs).
+//! Markers:
 //! - **panic**: the panic hook formats `PanicHookInfo` Display — the
 //!   message contains `panicked at` — and logs it via `log::error!`
 //!   (screeps-ibex/src/panic.rs `panic_hook`).
@@ -34,7 +35,7 @@ pub const PANIC_MARKER: &str = "panicked at";
 pub const DESER_FAILURE_MARKERS: &[&str] =
     &["Failed deserialization:", "Failed to decode stats history"];
 
-/// `(ERROR) <target>: ...` — the fern console format (logging.rs:32).
+/// `(ERROR) <target>: ...` — `IbexLogger`'s console line (logging.rs).
 pub const ERROR_LOG_PREFIX: &str = "(ERROR)";
 
 /// The bot's live-stats segment (segments.rs, `LIVE_STATS_SEGMENT`).
@@ -88,7 +89,7 @@ mod tests {
     use screeps_server_kit::capture::{ConsoleCounters, ConsoleKind, ConsoleLine};
 
     /// The panic hook output (panic.rs — std PanicHookInfo Display)
-    /// through the fern console format (logging.rs:32).
+    /// through `IbexLogger`'s console line format (logging.rs).
     #[test]
     fn panic_marker_matches_hook_output() {
         let spec = marker_spec();
@@ -8,7 +8,7 @@
 //!
 //! - [`gates`] — the smoke-gate markers pinned against the bot crate's
 //!   sources (panic-hook output, deserialization-failure log lines, the
-//!   fern log format, the live-stats segment), packaged as the
+//!   `IbexLogger` line format, the live-stats segment), packaged as the
 //!   [`screeps_server_kit::capture::CaptureSpec`] the kit consumes.
 //! - [`smoke`] — the one-command loop: server up → bootstrap --reset →
 //!   deploy → run --ticks K → hard-zero gate verdict.
@@ -94,8 +94,9 @@ pub struct CpuModelMetrics {
     pub samples: u32,
 }
 
-/// Per-tick guarded-intent counts + the order-sensitive stream digest
-/// (the P1.C5 shadow-dispatch parity instrument).
+/// Per-tick intent counts + the order-sensitive stream digest (the P1.C5
+/// shadow-dispatch parity instrument). The digest covers the guarded
+/// combat intents only; the economy counts feed the intent CPU budget.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub struct IntentMetrics {
     #[serde(default)]
@@ -108,6 +109,43 @@ pub struct IntentMetrics {
     pub heal: u32,
     #[serde(default)]
     pub ranged_heal: u32,
+    /// Creep moves: the movement requests the rover didn't give up on (an
+    /// upper bound — holds and arrivals count too).
+    #[serde(default)]
+    pub moves: u32,
+    #[serde(default)]
+    pub harvest: u32,
+    /// Transfer, withdraw, pickup and link transfers.
+    #[serde(default)]
+    pub transfer: u32,
+    #[serde(default)]
+    pub build: u32,
+    /// Creep and tower repairs.
+    #[serde(default)]
+    pub repair: u32,
+    #[serde(default)]
+    pub upgrade: u32,
+    #[serde(default)]
+    pub dismantle: u32,
+    /// Claim, reserve, attack and sign controller.
+    #[serde(default)]
+    pub controller: u32,
+    /// Spawn, renew and recycle.
+    #[serde(default)]
+    pub spawn: u32,
+    /// Tower attack and heal.
+    #[serde(default)]
+    pub tower: u32,
+    /// Labs, power spawns, terminals, observers and safe mode.
+    #[serde(default)]
+    pub structure: u32,
+    /// `creep.say` debug codes.
+    #[serde(default)]
+    pub say: u32,
+    /// Every counted intent; at 0.2 CPU each, `total / 5` is the tick's
+    /// intent CPU.
+    #[serde(default)]
+    pub total: u32,
     /// Chained FNV-1a over the tick's intent tuples, hex-encoded.
     #[serde(default)]
     pub digest: String,
@@ -0,0 +1,254 @@
+use screeps::*;
+
+/// How many MOVE parts a template adds for its other parts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveRatio {
+    /// MOVE parts come only from the template itself.
+    Explicit,
+    /// One MOVE per `n` non-MOVE parts: 1 keeps full speed off-road, 2 keeps
+    /// full speed on roads.
+    PerParts(u32),
+}
+
+/// A declarative creep body: a fixed prefix and suffix around a group of
+/// parts repeated as many times as the energy budget allows, with MOVE parts
+/// added to hold a target ratio.
+///
+/// ```ignore
+/// // Remote hauler: one WORK for road repair, then CARRY, at full off-road speed.
+/// let body = BodyTemplate::new(&[Part::Carry]).prefix(&[Part::Work]).plains().max_repeat(20).build(energy);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BodyTemplate {
+    prefix: Vec<Part>,
+    repeat: Vec<Part>,
+    suffix: Vec<Part>,
+    move_ratio: MoveRatio,
+    min_repeat: usize,
+    max_repeat: Option<usize>,
+    part_limits: Vec<(Part, usize)>,
+    combat_order: bool,
+}
+
+impl BodyTemplate {
+    pub fn new(repeat: &[Part]) -> BodyTemplate {
+        BodyTemplate {
+            prefix: Vec::new(),
+            repeat: repeat.to_vec(),
+            suffix: Vec::new(),
+            move_ratio: MoveRatio::Explicit,
+            min_repeat: 1,
+            max_repeat: None,
+            part_limits: Vec::new(),
+            combat_order: false,
+        }
+    }
+
+    /// Parts every body gets ahead of the repeated group.
+    pub fn prefix(mut self, parts: &[Part]) -> BodyTemplate {
+        self.prefix = parts.to_vec();
+        self
+    }
+
+    /// Parts every body gets after the repeated group.
+    pub fn suffix(mut self, parts: &[Part]) -> BodyTemplate {
+        self.suffix = parts.to_vec();
+        self
+    }
+
+    pub fn move_ratio(mut self, ratio: MoveRatio) -> BodyTemplate {
+        self.move_ratio = ratio;
+        self
+    }
+
+    /// One MOVE per part: full speed over plains.
+    pub fn plains(self) -> BodyTemplate {
+        self.move_ratio(MoveRatio::PerParts(1))
+    }
+
+    /// One MOVE per two parts: full speed on roads.
+    pub fn roads(self) -> BodyTemplate {
+        self.move_ratio(MoveRatio::PerParts(2))
+    }
+
+    /// Fewest repeats of the group a body may have; below that nothing is built.
+    pub fn min_repeat(mut self, repeat: usize) -> BodyTemplate {
+        self.min_repeat = repeat;
+        self
+    }
+
+    pub fn max_repeat(mut self, repeat: usize) -> BodyTemplate {
+        self.max_repeat = Some(repeat);
+        self
+    }
+
+    /// Stop adding `part` from the repeated group once the body has `count`.
+    pub fn max_parts(mut self, part: Part, count: usize) -> BodyTemplate {
+        self.part_limits.retain(|(limited, _)| *limited != part);
+        self.part_limits.push((part, count));
+        self
+    }
+
+    /// Order the body TOUGH first and HEAL last, so incoming damage strips
+    /// armour before anything useful and the healing goes last.
+    pub fn combat(mut self) -> BodyTemplate {
+        self.combat_order = true;
+        self
+    }
+
+    /// The largest body the template allows within `energy`, or `None` if even
+    /// the minimum body costs more or runs past the creep part limit.
+    pub fn build(&self, energy: u32) -> Option<Vec<Part>> {
+        let max_repeat = self.max_repeat.unwrap_or(MAX_CREEP_SIZE as usize);
+
+        let mut best = None;
+
+        for repeat in self.min_repeat..=max_repeat.max(self.min_repeat) {
+            let body = self.body(repeat);
+
+            let fits = body.len() <= MAX_CREEP_SIZE as usize && body.iter().map(|part| part.cost()).sum::<u32>() <= energy;
+
+            if !fits {
+                break;
+            }
+
+            // Every repeated part is capped: more repeats change nothing.
+            let grown = best.as_ref().is_none_or(|previous: &Vec<Part>| previous.len() < body.len());
+
+            if !grown {
+                break;
+            }
+
+            best = Some(body);
+        }
+
+        best
+    }
+
+    fn body(&self, repeat: usize) -> Vec<Part> {
+        let mut parts = self.prefix.clone();
+
+        for _ in 0..repeat {
+            for part in &self.repeat {
+                let limit = self
+                    .part_limits
+                    .iter()
+                    .find(|(limited, _)| limited == part)
+                    .map(|(_, count)| *count);
+
+                if limit.is_none_or(|limit| parts.iter().filter(|p| *p == part).count() < limit) {
+                    parts.push(*part);
+                }
+            }
+        }
+
+        parts.extend_from_slice(&self.suffix);
+
+        if let MoveRatio::PerParts(per) = self.move_ratio {
+            let others = parts.iter().filter(|part| **part != Part::Move).count() as u32;
+            let present = parts.iter().filter(|part| **part == Part::Move).count() as u32;
+
+            for _ in present..others.div_ceil(per.max(1)) {
+                parts.push(Part::Move);
+            }
+        }
+
+        if self.combat_order {
+            // Stable: everything else keeps its template order.
+            parts.sort_by_key(|part| match part {
+                Part::Tough => 0,
+                Part::Heal => 2,
+                _ => 1,
+            });
+        }
+
+        parts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(body: &[Part], part: Part) -> usize {
+        body.iter().filter(|p| **p == part).count()
+    }
+
+    fn cost(body: &[Part]) -> u32 {
+        body.iter().map(|part| part.cost()).sum()
+    }
+
+    #[test]
+    fn remote_hauler_scales_with_energy_and_keeps_its_work_part() {
+        let template = BodyTemplate::new(&[Part::Carry]).prefix(&[Part::Work]).plains().max_repeat(20);
+
+        // WORK + MOVE is 150, each CARRY + MOVE 100 more.
+        assert_eq!(template.build(200), None);
+        assert_eq!(template.build(300), Some(vec![Part::Work, Part::Carry, Part::Move, Part::Move]));
+
+        let body = template.build(800).unwrap();
+        assert_eq!(count(&body, Part::Work), 1);
+        assert_eq!(count(&body, Part::Carry), 6);
+        assert_eq!(count(&body, Part::Move), 7);
+        assert!(cost(&body) <= 800);
+
+        // Capped at 20 repeats however much energy there is.
+        let body = template.build(10_000).unwrap();
+        assert_eq!(count(&body, Part::Carry), 20);
+        assert_eq!(count(&body, Part::Move), count(&body, Part::Work) + count(&body, Part::Carry));
+    }
+
+    #[test]
+    fn road_ratio_halves_the_move_parts() {
+        let template = BodyTemplate::new(&[Part::Carry, Part::Carry]).roads();
+
+        let body = template.build(1_500).unwrap();
+        assert_eq!(count(&body, Part::Carry), 20);
+        assert_eq!(count(&body, Part::Move), 10);
+
+        // An odd part count rounds the MOVE parts up.
+        let template = BodyTemplate::new(&[Part::Work]).roads();
+        assert_eq!(template.build(249), Some(vec![Part::Work, Part::Move]));
+        assert_eq!(template.build(250), Some(vec![Part::Work, Part::Work, Part::Move]));
+    }
+
+    #[test]
+    fn part_limits_stop_the_capped_part_only() {
+        let template = BodyTemplate::new(&[Part::Work, Part::Carry])
+            .max_parts(Part::Work, 2)
+            .plains()
+            .max_repeat(4);
+
+        let body = template.build(10_000).unwrap();
+        assert_eq!(count(&body, Part::Work), 2);
+        assert_eq!(count(&body, Part::Carry), 4);
+        assert_eq!(count(&body, Part::Move), 6);
+
+        // Once every repeated part is capped, more energy builds the same body.
+        let capped = BodyTemplate::new(&[Part::Work]).max_parts(Part::Work, 3);
+        assert_eq!(capped.build(10_000), Some(vec![Part::Work; 3]));
+    }
+
+    #[test]
+    fn combat_bodies_put_tough_first_and_heal_last() {
+        let template = BodyTemplate::new(&[Part::Heal, Part::RangedAttack, Part::Tough]).plains().combat();
+
+        let body = template.build(1_300).unwrap();
+        assert_eq!(body.first(), Some(&Part::Tough));
+        assert_eq!(body.last(), Some(&Part::Heal));
+        assert!(body.iter().skip_while(|p| **p == Part::Tough).all(|p| *p != Part::Tough));
+        assert!(body.iter().rev().skip_while(|p| **p == Part::Heal).all(|p| *p != Part::Heal));
+        assert!(cost(&body) <= 1_300);
+    }
+
+    #[test]
+    fn min_repeat_zero_allows_a_prefix_only_body() {
+        let template = BodyTemplate::new(&[Part::Work, Part::Move])
+            .prefix(&[Part::Work, Part::Carry, Part::Move, Part::Move])
+            .min_repeat(0);
+
+        assert_eq!(template.build(249), None);
+        assert_eq!(template.build(250).map(|body| body.len()), Some(4));
+        assert_eq!(template.build(400).map(|body| body.len()), Some(6));
+    }
+}
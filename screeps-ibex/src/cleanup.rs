@@ -1,3 +1,4 @@
+use crate::entitymappingsystem::EntityMappingData;
 use crate::jobs::data::JobData;
 use crate::military::squad::SquadContext;
 use crate::missions::data::*;
@@ -104,9 +105,13 @@ impl<'a> System<'a> for EntityCleanupSystem {
         ReadStorage<'a, JobData>,
         WriteStorage<'a, SquadContext>,
         Write<'a, EntityCleanupQueue>,
+        Write<'a, EntityMappingData>,
     );
 
-    fn run(&mut self, (entities, missions, mut operations, mut room_data, jobs, mut squad_contexts, mut queue): Self::SystemData) {
+    fn run(
+        &mut self,
+        (entities, missions, mut operations, mut room_data, jobs, mut squad_contexts, mut queue, mut mapping): Self::SystemData,
+    ) {
         if queue.is_empty() {
             return;
         }
@@ -116,6 +121,7 @@ impl<'a> System<'a> for EntityCleanupSystem {
         // For each CreepCleanup entry:
         //   - Notify every live mission via remove_creep().
         //   - Notify the owning SquadContext (if any) by removing the member.
+        //   - Drop it from the creep name/position lookups.
         //   - Delete the creep entity.
 
         let all_entries = queue.drain();
@@ -154,6 +160,8 @@ impl<'a> System<'a> for EntityCleanupSystem {
 
             // Delete creep entities.
             for creep in &creep_entries {
+                mapping.remove_creep(creep.entity);
+
                 if let Err(err) = entities.delete(creep.entity) {
                     warn!("EntityCleanupSystem: failed to delete creep {:?}: {}", creep.entity, err);
                 }
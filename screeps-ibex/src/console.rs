@@ -0,0 +1,592 @@
+//! Operator console commands.
+//!
+//! The bot has no hook into the Screeps console, so commands travel through
+//! Memory the same way the `_features` flags do: push a line onto
+//! `Memory._commands` from the console (e.g.
+//! `Memory._commands = ['pause_room W1N1']`) and [`ConsoleCommandSystem`]
+//! drains and runs it on the next tick. Outcomes go to the log.
+//!
+//! - `pause_mission <entity id>` / `resume_mission <entity id>`
+//! - `pause_room <room>` / `resume_room <room>` — every mission in the room
+//! - `spawn_report <room>` — log what the room's spawn queue would spawn at
+//!   each RCL's energy capacity, without spawning anything
+//! - `set_hub <room>` / `set_hub none` — the room other terminals ship their
+//!   surplus resources to (`_features.consolidation.hub`)
+//! - `log_level <spec>` / `log_level none` — per-module log levels
+//!   (`_features.logging.levels`), e.g. `log_level missions::attack=debug,transfer=warn`;
+//!   the spec can't contain spaces
+//! - `log_dump` — print the buffered recent warnings and errors
+//! - `show_plan <room> <rcl>` / `show_plan <room> none` — draw the room's
+//!   stored plan as it stands at that RCL, dimming what unlocks later
+//!   (needs `_features.visualize.on`)
+//! - `resources` — recount the empire's stores and print each resource's
+//!   total, target, deficit and holders (see `resource_overview`)
+//! - `declare_war <player|room>` / `cease_fire <player|room>` — attack a
+//!   player's weakest room or one room, or stand it down; a room name is a
+//!   room, anything else a player (see `military::wartargets`)
+//! - `wars` — list the standing declarations and the rooms standing down
+//! - `show_missions <room> <categories>` — the mission categories the
+//!   room's Missions panel lists: a comma-separated list of `economy`,
+//!   `military` and `construction`, or `all`, `none`, or `default` to
+//!   inherit `_features.visualize` again. Room paging and the summary depth
+//!   cap are `_features.visualize.page_rooms` / `page_ticks` / `max_depth`
+//! - `posture <room> <peaceful|alert|lockdown>` / `posture <room> auto` —
+//!   pin the room's defense posture (`_features.rooms.<room>.posture`), or
+//!   hand it back to automatic escalation (see `military::posture`)
+//!
+//! Pausing cascades to child missions via `Mission::get_children`, so
+//! freezing a coordinator (local supply, mining outpost) freezes the
+//! missions it spawned too.
+
+use crate::entitymappingsystem::EntityMappingData;
+use crate::military::posture::PostureLevel;
+use crate::military::wartargets::{WarTarget, WarTargets};
+use crate::missions::data::*;
+use crate::resource_overview::ResourceOverview;
+use crate::room::data::*;
+use crate::room::roomplanvisualizesystem::PlanPreview;
+use crate::spawnsystem::SpawnReportRequests;
+use crate::visualization::MissionCategory;
+use log::*;
+use screeps::{game, RoomName};
+use specs::prelude::*;
+use wasm_bindgen::JsValue;
+
+const COMMANDS_PATH: &str = "_commands";
+const CONSOLIDATION_HUB_PATH: &str = "_features.consolidation.hub";
+const LOG_LEVELS_PATH: &str = "_features.logging.levels";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConsoleCommand {
+    PauseMission {
+        id: u32,
+        paused: bool,
+    },
+    PauseRoom {
+        room: RoomName,
+        paused: bool,
+    },
+    SpawnReport {
+        room: RoomName,
+    },
+    SetHub {
+        room: Option<RoomName>,
+    },
+    SetLogLevels {
+        spec: String,
+    },
+    DumpLogs,
+    ResourceReport,
+    ShowPlan {
+        room: RoomName,
+        rcl: Option<u8>,
+    },
+    DeclareWar {
+        target: WarTarget,
+    },
+    CeaseFire {
+        target: WarTarget,
+    },
+    ListWars,
+    /// `None` inherits the global flags again.
+    ShowMissions {
+        room: RoomName,
+        categories: Option<Vec<MissionCategory>>,
+    },
+    /// `None` returns the room to automatic escalation.
+    SetPosture {
+        room: RoomName,
+        level: Option<PostureLevel>,
+    },
+}
+
+/// Parse one command line.
+pub fn parse_command(line: &str) -> Result<ConsoleCommand, String> {
+    let mut words = line.split_whitespace();
+    let verb = words.next().ok_or_else(|| "empty command".to_string())?;
+
+    if verb == "log_dump" || verb == "resources" || verb == "wars" {
+        return match words.next() {
+            Some(extra) => Err(format!("{}: unexpected argument '{}'", verb, extra)),
+            None if verb == "resources" => Ok(ConsoleCommand::ResourceReport),
+            None if verb == "wars" => Ok(ConsoleCommand::ListWars),
+            None => Ok(ConsoleCommand::DumpLogs),
+        };
+    }
+
+    let arg = words.next().ok_or_else(|| format!("{}: missing argument", verb))?;
+
+    if verb == "show_plan" {
+        let room = RoomName::new(arg).map_err(|_| format!("{}: '{}' is not a room name", verb, arg))?;
+        let stage = words.next().ok_or_else(|| format!("{}: missing RCL", verb))?;
+
+        if let Some(extra) = words.next() {
+            return Err(format!("{}: unexpected argument '{}'", verb, extra));
+        }
+
+        let rcl = match stage {
+            "none" => None,
+            _ => Some(
+                stage
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|rcl| (1..=8).contains(rcl))
+                    .ok_or_else(|| format!("{}: '{}' is not an RCL", verb, stage))?,
+            ),
+        };
+
+        return Ok(ConsoleCommand::ShowPlan { room, rcl });
+    }
+
+    if verb == "show_missions" {
+        let room = RoomName::new(arg).map_err(|_| format!("{}: '{}' is not a room name", verb, arg))?;
+        let which = words.next().ok_or_else(|| format!("{}: missing categories", verb))?;
+
+        if let Some(extra) = words.next() {
+            return Err(format!("{}: unexpected argument '{}'", verb, extra));
+        }
+
+        let categories = match which {
+            "default" => None,
+            "all" => Some(MissionCategory::ALL.to_vec()),
+            "none" => Some(Vec::new()),
+            _ => Some(
+                which
+                    .split(',')
+                    .map(|name| MissionCategory::parse(name).ok_or_else(|| format!("{}: '{}' is not a mission category", verb, name)))
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+        };
+
+        return Ok(ConsoleCommand::ShowMissions { room, categories });
+    }
+
+    if verb == "posture" {
+        let room = RoomName::new(arg).map_err(|_| format!("{}: '{}' is not a room name", verb, arg))?;
+        let which = words.next().ok_or_else(|| format!("{}: missing level", verb))?;
+
+        if let Some(extra) = words.next() {
+            return Err(format!("{}: unexpected argument '{}'", verb, extra));
+        }
+
+        let level = match which {
+            "auto" => None,
+            _ => Some(PostureLevel::parse(which).ok_or_else(|| format!("{}: '{}' is not a posture level", verb, which))?),
+        };
+
+        return Ok(ConsoleCommand::SetPosture { room, level });
+    }
+
+    if let Some(extra) = words.next() {
+        return Err(format!("{}: unexpected argument '{}'", verb, extra));
+    }
+
+    let parse_id = || arg.parse::<u32>().map_err(|_| format!("{}: '{}' is not an entity id", verb, arg));
+    let parse_room = || RoomName::new(arg).map_err(|_| format!("{}: '{}' is not a room name", verb, arg));
+
+    match verb {
+        "pause_mission" => Ok(ConsoleCommand::PauseMission {
+            id: parse_id()?,
+            paused: true,
+        }),
+        "resume_mission" => Ok(ConsoleCommand::PauseMission {
+            id: parse_id()?,
+            paused: false,
+        }),
+        "pause_room" => Ok(ConsoleCommand::PauseRoom {
+            room: parse_room()?,
+            paused: true,
+        }),
+        "resume_room" => Ok(ConsoleCommand::PauseRoom {
+            room: parse_room()?,
+            paused: false,
+        }),
+        "spawn_report" => Ok(ConsoleCommand::SpawnReport { room: parse_room()? }),
+        "set_hub" if arg == "none" => Ok(ConsoleCommand::SetHub { room: None }),
+        "set_hub" => Ok(ConsoleCommand::SetHub { room: Some(parse_room()?) }),
+        "log_level" if arg == "none" => Ok(ConsoleCommand::SetLogLevels { spec: String::new() }),
+        "log_level" => {
+            crate::logging::LogLevels::parse(arg, crate::logging::Info).map_err(|err| format!("{}: {}", verb, err))?;
+            Ok(ConsoleCommand::SetLogLevels { spec: arg.to_string() })
+        }
+        "declare_war" => Ok(ConsoleCommand::DeclareWar {
+            target: WarTarget::parse(arg),
+        }),
+        "cease_fire" => Ok(ConsoleCommand::CeaseFire {
+            target: WarTarget::parse(arg),
+        }),
+        _ => Err(format!("unknown command '{}'", verb)),
+    }
+}
+
+/// Take every pending command line out of `Memory._commands`, leaving an
+/// empty list behind. Non-string entries are dropped with a warning.
+fn drain_commands() -> Vec<String> {
+    let pending = crate::memory_helper::path_get(COMMANDS_PATH);
+
+    if !js_sys::Array::is_array(&pending) {
+        return Vec::new();
+    }
+
+    let lines = js_sys::Array::from(&pending)
+        .iter()
+        .filter_map(|line| {
+            let text = line.as_string();
+            if text.is_none() {
+                warn!("Ignoring non-string console command: {:?}", line);
+            }
+            text
+        })
+        .collect();
+
+    crate::memory_helper::path_set(COMMANDS_PATH, JsValue::from(js_sys::Array::new()));
+
+    lines
+}
+
+/// Set the pause flag on `root` and every mission below it. Returns the
+/// missions that were touched, root first.
+fn set_paused_tree(missions: &ReadStorage<MissionData>, root: Entity, paused: bool) -> Vec<Entity> {
+    let mut touched = Vec::new();
+    let mut pending = vec![root];
+
+    while let Some(entity) = pending.pop() {
+        if touched.contains(&entity) {
+            continue;
+        }
+
+        if let Some(mission_data) = missions.get(entity) {
+            let mut mission = mission_data.as_mission_mut();
+            if paused {
+                mission.pause();
+            } else {
+                mission.resume();
+            }
+            pending.extend(mission.get_children());
+            touched.push(entity);
+        }
+    }
+
+    touched
+}
+
+#[derive(SystemData)]
+pub struct ConsoleCommandSystemData<'a> {
+    entities: Entities<'a>,
+    missions: ReadStorage<'a, MissionData>,
+    room_data: ReadStorage<'a, RoomData>,
+    mapping: Read<'a, EntityMappingData>,
+    spawn_reports: Write<'a, SpawnReportRequests>,
+    plan_preview: Write<'a, PlanPreview>,
+    resource_overview: Write<'a, ResourceOverview>,
+    war_targets: Write<'a, WarTargets>,
+}
+
+/// Drains `Memory._commands` once per tick and applies each command.
+pub struct ConsoleCommandSystem;
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+impl<'a> System<'a> for ConsoleCommandSystem {
+    type SystemData = ConsoleCommandSystemData<'a>;
+
+    fn run(&mut self, mut data: Self::SystemData) {
+        for line in drain_commands() {
+            let command = match parse_command(&line) {
+                Ok(command) => command,
+                Err(err) => {
+                    warn!("Console command '{}' rejected: {}", line, err);
+                    continue;
+                }
+            };
+
+            let (roots, target, paused) = match command {
+                ConsoleCommand::PauseMission { id, paused } => {
+                    let entity = data.entities.entity(id);
+                    if !data.entities.is_alive(entity) || data.missions.get(entity).is_none() {
+                        warn!("Console command '{}': no mission with entity id {}", line, id);
+                        continue;
+                    }
+                    (vec![entity], format!("under mission {}", id), paused)
+                }
+                ConsoleCommand::PauseRoom { room, paused } => {
+                    let room_missions = data
+                        .mapping
+                        .get_room(&room)
+                        .and_then(|room_entity| data.room_data.get(room_entity))
+                        .map(|room_data| room_data.get_missions().iter().copied().collect::<Vec<_>>());
+
+                    match room_missions {
+                        Some(room_missions) => (room_missions, format!("in room {}", room), paused),
+                        None => {
+                            warn!("Console command '{}': no room data for {}", line, room);
+                            continue;
+                        }
+                    }
+                }
+                ConsoleCommand::SpawnReport { room } => {
+                    // The queue is only filled later in the tick; the spawn
+                    // system replays it when it runs.
+                    match data
+                        .mapping
+                        .get_room(&room)
+                        .filter(|room_entity| data.room_data.get(*room_entity).is_some())
+                    {
+                        Some(room_entity) => {
+                            data.spawn_reports.request(room_entity);
+                            info!("Console: spawn report for {} queued", room);
+                        }
+                        None => warn!("Console command '{}': no room data for {}", line, room),
+                    }
+                    continue;
+                }
+                ConsoleCommand::SetHub { room } => {
+                    // Picked up with the rest of `_features` next tick.
+                    let hub = room.map(|room| JsValue::from_str(&room.to_string())).unwrap_or(JsValue::NULL);
+                    crate::memory_helper::path_set(CONSOLIDATION_HUB_PATH, hub);
+
+                    match room {
+                        Some(room) => info!("Console: consolidating resources to {}", room),
+                        None => info!("Console: resource consolidation off"),
+                    }
+                    continue;
+                }
+                ConsoleCommand::SetLogLevels { spec } => {
+                    // Picked up with the rest of `_features` next tick.
+                    crate::memory_helper::path_set(LOG_LEVELS_PATH, JsValue::from_str(&spec));
+                    info!("Console: log levels '{}'", spec);
+                    continue;
+                }
+                ConsoleCommand::DumpLogs => {
+                    crate::logging::dump_recent();
+                    continue;
+                }
+                ConsoleCommand::ResourceReport => {
+                    // Answered by the overview system later this tick.
+                    data.resource_overview.request_report();
+                    continue;
+                }
+                ConsoleCommand::ShowPlan { room, rcl } => {
+                    data.plan_preview.show(room, rcl);
+
+                    match rcl {
+                        Some(rcl) => info!("Console: showing the plan for {} at RCL {}", room, rcl),
+                        None => info!("Console: plan preview for {} off", room),
+                    }
+                    continue;
+                }
+                ConsoleCommand::DeclareWar { target } => {
+                    if data.war_targets.declare(target.clone()) {
+                        data.war_targets.save();
+                        info!("Console: declared war on {}", target);
+                    } else {
+                        info!("Console: already at war with {}", target);
+                    }
+                    continue;
+                }
+                ConsoleCommand::CeaseFire { target } => {
+                    match data.war_targets.cease_fire(&target, game::time()) {
+                        Some(rooms) => {
+                            data.war_targets.save();
+                            let rooms: Vec<String> = rooms.iter().map(|room| room.to_string()).collect();
+                            info!("Console: cease-fire with {}; standing down: {}", target, rooms.join(", "));
+                        }
+                        None => warn!("Console command '{}': not at war with {}", line, target),
+                    }
+                    continue;
+                }
+                ConsoleCommand::ShowMissions { room, categories } => {
+                    // Picked up with the rest of `_features` next tick.
+                    for category in MissionCategory::ALL {
+                        let path = format!("_features.rooms.{}.{}_missions", room, category.name());
+                        let shown = categories
+                            .as_ref()
+                            .map(|categories| JsValue::from_bool(categories.contains(&category)))
+                            .unwrap_or(JsValue::NULL);
+
+                        crate::memory_helper::path_set(&path, shown);
+                    }
+
+                    match categories {
+                        Some(categories) => {
+                            let names: Vec<&str> = categories.iter().map(|category| category.name()).collect();
+                            info!("Console: {} lists {} missions", room, names.join(", "));
+                        }
+                        None => info!("Console: {} lists the default mission categories", room),
+                    }
+                    continue;
+                }
+                ConsoleCommand::SetPosture { room, level } => {
+                    // Picked up with the rest of `_features` next tick.
+                    let path = format!("_features.rooms.{}.posture", room);
+                    let pinned = level.map(|level| JsValue::from_str(level.name())).unwrap_or(JsValue::NULL);
+
+                    crate::memory_helper::path_set(&path, pinned);
+
+                    match level {
+                        Some(level) => info!("Console: {} posture pinned to {}", room, level.name()),
+                        None => info!("Console: {} posture is automatic again", room),
+                    }
+                    continue;
+                }
+                ConsoleCommand::ListWars => {
+                    let declared: Vec<String> = data.war_targets.declarations().iter().map(|t| t.to_string()).collect();
+                    let mut standing_down: Vec<RoomName> = data.war_targets.standing_down().collect();
+                    standing_down.sort();
+                    let standing_down: Vec<String> = standing_down.iter().map(|room| room.to_string()).collect();
+
+                    info!("Console: at war with: {}", declared.join(", "));
+                    info!("Console: standing down: {}", standing_down.join(", "));
+                    continue;
+                }
+            };
+
+            let mut touched: Vec<Entity> = Vec::new();
+            for root in roots {
+                for entity in set_paused_tree(&data.missions, root, paused) {
+                    if !touched.contains(&entity) {
+                        touched.push(entity);
+                    }
+                }
+            }
+
+            let names: Vec<String> = touched
+                .iter()
+                .filter_map(|entity| data.missions.get(*entity).map(|m| format!("{} #{}", m.type_name(), entity.id())))
+                .collect();
+
+            info!(
+                "Console: {} {} mission(s) {}: {}",
+                if paused { "paused" } else { "resumed" },
+                touched.len(),
+                target,
+                names.join(", ")
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pause_and_resume() {
+        assert_eq!(
+            parse_command("pause_mission 42"),
+            Ok(ConsoleCommand::PauseMission { id: 42, paused: true })
+        );
+        assert_eq!(
+            parse_command("  resume_room   W1N1 "),
+            Ok(ConsoleCommand::PauseRoom {
+                room: RoomName::new("W1N1").unwrap(),
+                paused: false,
+            })
+        );
+        assert_eq!(
+            parse_command("spawn_report W1N1"),
+            Ok(ConsoleCommand::SpawnReport {
+                room: RoomName::new("W1N1").unwrap(),
+            })
+        );
+        assert_eq!(
+            parse_command("set_hub W1N1"),
+            Ok(ConsoleCommand::SetHub {
+                room: Some(RoomName::new("W1N1").unwrap()),
+            })
+        );
+        assert_eq!(parse_command("set_hub none"), Ok(ConsoleCommand::SetHub { room: None }));
+        assert_eq!(
+            parse_command("log_level missions::attack=debug,transfer=warn"),
+            Ok(ConsoleCommand::SetLogLevels {
+                spec: "missions::attack=debug,transfer=warn".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_command("log_level none"),
+            Ok(ConsoleCommand::SetLogLevels { spec: String::new() })
+        );
+        assert_eq!(parse_command("log_dump"), Ok(ConsoleCommand::DumpLogs));
+        assert_eq!(parse_command("resources"), Ok(ConsoleCommand::ResourceReport));
+        assert_eq!(parse_command("wars"), Ok(ConsoleCommand::ListWars));
+        assert_eq!(
+            parse_command("show_missions W1N1 economy,construction"),
+            Ok(ConsoleCommand::ShowMissions {
+                room: RoomName::new("W1N1").unwrap(),
+                categories: Some(vec![MissionCategory::Economy, MissionCategory::Construction]),
+            })
+        );
+        assert_eq!(
+            parse_command("show_missions W1N1 default"),
+            Ok(ConsoleCommand::ShowMissions {
+                room: RoomName::new("W1N1").unwrap(),
+                categories: None,
+            })
+        );
+        assert_eq!(
+            parse_command("declare_war Enemy"),
+            Ok(ConsoleCommand::DeclareWar {
+                target: WarTarget::Player("Enemy".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_command("cease_fire W1N1"),
+            Ok(ConsoleCommand::CeaseFire {
+                target: WarTarget::Room(RoomName::new("W1N1").unwrap()),
+            })
+        );
+        assert_eq!(
+            parse_command("show_plan W1N1 4"),
+            Ok(ConsoleCommand::ShowPlan {
+                room: RoomName::new("W1N1").unwrap(),
+                rcl: Some(4),
+            })
+        );
+        assert_eq!(
+            parse_command("show_plan W1N1 none"),
+            Ok(ConsoleCommand::ShowPlan {
+                room: RoomName::new("W1N1").unwrap(),
+                rcl: None,
+            })
+        );
+        assert_eq!(
+            parse_command("posture W1N1 lockdown"),
+            Ok(ConsoleCommand::SetPosture {
+                room: RoomName::new("W1N1").unwrap(),
+                level: Some(PostureLevel::Lockdown),
+            })
+        );
+        assert_eq!(
+            parse_command("posture W1N1 auto"),
+            Ok(ConsoleCommand::SetPosture {
+                room: RoomName::new("W1N1").unwrap(),
+                level: None,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_commands() {
+        assert!(parse_command("").is_err());
+        assert!(parse_command("pause_mission").is_err());
+        assert!(parse_command("pause_mission abc").is_err());
+        assert!(parse_command("pause_room nowhere").is_err());
+        assert!(parse_command("pause_room W1N1 W2N2").is_err());
+        assert!(parse_command("delete_mission 3").is_err());
+        assert!(parse_command("spawn_report").is_err());
+        assert!(parse_command("set_hub nowhere").is_err());
+        assert!(parse_command("log_level transfer=loud").is_err());
+        assert!(parse_command("log_dump now").is_err());
+        assert!(parse_command("resources W1N1").is_err());
+        assert!(parse_command("declare_war").is_err());
+        assert!(parse_command("cease_fire Enemy W1N1").is_err());
+        assert!(parse_command("wars now").is_err());
+        assert!(parse_command("show_missions W1N1").is_err());
+        assert!(parse_command("show_missions W1N1 economy,logistics").is_err());
+        assert!(parse_command("show_plan W1N1").is_err());
+        assert!(parse_command("show_plan W1N1 9").is_err());
+        assert!(parse_command("show_plan nowhere 3").is_err());
+        assert!(parse_command("show_plan W1N1 3 4").is_err());
+        assert!(parse_command("posture W1N1").is_err());
+        assert!(parse_command("posture W1N1 panic").is_err());
+    }
+}
@@ -1 +1,2 @@
 pub const ROOM_SIGN: &str = "Rusty robots!";
+pub const ATTACK_SIGN: &str = "Resistance is futile. Rusty robots are coming.";
@@ -0,0 +1,187 @@
+//! Lightweight per-system / per-mission / per-job CPU accounting.
+//!
+//! The `profile` feature dumps full traces; this is the cheap production
+//! counterpart: a rolling breakdown of where each tick's CPU went, keyed by
+//! system label and mission/job type name. Controlled by
+//! `features.cpu_accounting.on` — when off, the dispatch sites never call
+//! `game::cpu::get_used()` at all (the gate wraps the measurement, not just
+//! the reporting).
+//!
+//! [`CpuAccounting`] is a specs Resource. `metrics::tick_start` rolls the
+//! in-progress tick into [`CpuAccounting::last`] and re-arms it, so the
+//! stats export and the visualizer always read one COMPLETE tick (the
+//! previous one) instead of a partially measured current tick.
+
+use std::collections::HashMap;
+
+/// One tick's accumulated CPU, split by accounting category.
+#[derive(Debug, Default, Clone)]
+pub struct CpuBreakdown {
+    pub systems: HashMap<&'static str, f64>,
+    pub missions: HashMap<&'static str, f64>,
+    pub jobs: HashMap<&'static str, f64>,
+}
+
+impl CpuBreakdown {
+    fn is_empty(&self) -> bool {
+        self.systems.is_empty() && self.missions.is_empty() && self.jobs.is_empty()
+    }
+
+    /// The `n` most expensive entries across all categories, descending by
+    /// CPU. Labels are prefixed with their category (`sys:`, `mission:`,
+    /// `job:`) so a mission and a system of the same name can't collide.
+    /// Ties break by label so the rendered order is stable tick to tick.
+    pub fn top(&self, n: usize) -> Vec<(String, f64)> {
+        let mut entries: Vec<(String, f64)> = self
+            .systems
+            .iter()
+            .map(|(k, v)| (format!("sys:{}", k), *v))
+            .chain(self.missions.iter().map(|(k, v)| (format!("mission:{}", k), *v)))
+            .chain(self.jobs.iter().map(|(k, v)| (format!("job:{}", k), *v)))
+            .collect();
+
+        entries.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.0.cmp(&b.0))
+        });
+        entries.truncate(n);
+        entries
+    }
+}
+
+/// Tick-scoped CPU accounting Resource. `enabled` is latched from the
+/// feature flag at tick start; every recording site checks it before
+/// touching the CPU clock.
+#[derive(Debug, Default)]
+pub struct CpuAccounting {
+    enabled: bool,
+    current: CpuBreakdown,
+    last: Option<CpuBreakdown>,
+}
+
+impl CpuAccounting {
+    /// Tick-start rollover: publish the finished tick as `last` and start a
+    /// fresh accumulator. Disabling drops the published breakdown so stale
+    /// numbers never linger in the stats export.
+    pub fn begin_tick(&mut self, enabled: bool) {
+        let finished = std::mem::take(&mut self.current);
+        self.last = if enabled && self.enabled && !finished.is_empty() {
+            Some(finished)
+        } else {
+            None
+        };
+        self.enabled = enabled;
+    }
+
+    #[inline]
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Start a measurement: `Some(now)` when accounting is on, `None`
+    /// (no CPU clock read) when off.
+    #[inline]
+    pub fn start(&self) -> Option<f64> {
+        if self.enabled {
+            Some(screeps::game::cpu::get_used())
+        } else {
+            None
+        }
+    }
+
+    pub fn record_system(&mut self, label: &'static str, cpu: f64) {
+        *self.current.systems.entry(label).or_insert(0.0) += cpu;
+    }
+
+    pub fn record_mission(&mut self, type_name: &'static str, cpu: f64) {
+        *self.current.missions.entry(type_name).or_insert(0.0) += cpu;
+    }
+
+    pub fn record_job(&mut self, type_name: &'static str, cpu: f64) {
+        *self.current.jobs.entry(type_name).or_insert(0.0) += cpu;
+    }
+
+    /// Close a mission measurement opened with [`start`](Self::start).
+    #[inline]
+    pub fn finish_mission(&mut self, start: Option<f64>, type_name: &'static str) {
+        if let Some(start) = start {
+            self.record_mission(type_name, screeps::game::cpu::get_used() - start);
+        }
+    }
+
+    /// Close a job measurement opened with [`start`](Self::start).
+    #[inline]
+    pub fn finish_job(&mut self, start: Option<f64>, type_name: &'static str) {
+        if let Some(start) = start {
+            self.record_job(type_name, screeps::game::cpu::get_used() - start);
+        }
+    }
+
+    /// The previous tick's complete breakdown (`None` while disabled or on
+    /// the first measured tick).
+    pub fn last(&self) -> Option<&CpuBreakdown> {
+        self.last.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A tick only publishes once it has been fully measured: the first
+    /// enabled tick has nothing to show, the next one shows its predecessor.
+    #[test]
+    fn rollover_publishes_previous_tick() {
+        let mut acct = CpuAccounting::default();
+        acct.begin_tick(true);
+        assert!(acct.last().is_none());
+        acct.record_system("run_missions", 1.5);
+        acct.record_mission("Haul", 0.5);
+        acct.record_mission("Haul", 0.25);
+
+        acct.begin_tick(true);
+        let last = acct.last().expect("published");
+        assert_eq!(last.systems.get("run_missions"), Some(&1.5));
+        assert_eq!(last.missions.get("Haul"), Some(&0.75));
+        assert!(acct.current.is_empty());
+    }
+
+    /// Turning the flag off drops the published breakdown, and a tick that
+    /// started disabled is never published (it was never measured).
+    #[test]
+    fn disabling_clears_published_breakdown() {
+        let mut acct = CpuAccounting::default();
+        acct.begin_tick(true);
+        acct.record_job("Harvest", 2.0);
+        acct.begin_tick(false);
+        assert!(acct.last().is_none());
+        assert!(!acct.enabled());
+        assert!(acct.start().is_none());
+
+        acct.begin_tick(true);
+        assert!(acct.last().is_none());
+    }
+
+    /// Top-N is descending by CPU across categories with stable label
+    /// tie-breaks, and category prefixes keep same-named entries apart.
+    #[test]
+    fn top_sorts_across_categories() {
+        let mut breakdown = CpuBreakdown::default();
+        breakdown.systems.insert("movement", 3.0);
+        breakdown.missions.insert("Haul", 1.0);
+        breakdown.jobs.insert("Haul", 1.0);
+        breakdown.jobs.insert("Harvest", 5.0);
+
+        let top = breakdown.top(3);
+        assert_eq!(
+            top,
+            vec![
+                ("job:Harvest".to_string(), 5.0),
+                ("sys:movement".to_string(), 3.0),
+                ("job:Haul".to_string(), 1.0),
+            ]
+        );
+        assert_eq!(breakdown.top(10).len(), 4);
+    }
+}
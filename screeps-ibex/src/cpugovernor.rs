@@ -75,6 +75,41 @@ pub fn compute_tier(bucket: i32, trend: f64) -> Tier {
     }
 }
 
+/// Ticks per Conserve rotation cycle: each rotation group gets one tick
+/// in four. Groups are numbered `0..CONSERVE_ROTATION`.
+pub const CONSERVE_ROTATION: u32 = 4;
+
+/// Pure rotation kernel for the scheduler's bucket-aware class: every
+/// tick at Normal, only on the group's slot under Conserve, never under
+/// Critical.
+pub fn rotation_due(tier: Tier, tick: u32, slot: u32) -> bool {
+    match tier {
+        Tier::Normal => true,
+        Tier::Conserve => tick % CONSERVE_ROTATION == slot % CONSERVE_ROTATION,
+        Tier::Critical => false,
+    }
+}
+
+/// Share of `game::cpu::limit()` held back at the mid-tick checkpoints for
+/// the never-shed tail (movement, memory arbiter, integrity repair,
+/// `serialize_world`).
+pub const CHECKPOINT_SAFETY_FRACTION: f64 = 0.15;
+
+/// Least CPU held back, so a small limit still leaves room for the save.
+pub const CHECKPOINT_MIN_MARGIN: f64 = 3.0;
+
+/// CPU held back below `limit` at the checkpoints.
+pub fn checkpoint_margin(limit: f64) -> f64 {
+    (limit * CHECKPOINT_SAFETY_FRACTION).max(CHECKPOINT_MIN_MARGIN)
+}
+
+/// Pure mid-tick checkpoint kernel: once `used` is within
+/// [`checkpoint_margin`] of the per-tick CPU `limit`, the remaining
+/// sheddable systems are skipped so persistence still fits.
+pub fn checkpoint_exceeded(used: f64, limit: f64) -> bool {
+    used >= limit - checkpoint_margin(limit)
+}
+
 /// The tick's CPU-pressure view: written once at tick start
 /// (`metrics::tick_start`), read everywhere. `Copy` so execution-data
 /// structs carry it by value.
@@ -167,6 +202,40 @@ mod tests {
         assert!(snap.can_execute_cpu(CpuBar::IdlePriority));
     }
 
+    /// Under Conserve each group runs exactly once per cycle and no two
+    /// groups share a tick; Normal runs everything, Critical nothing.
+    #[test]
+    fn rotation_spreads_groups_under_conserve() {
+        for tick in 100..100 + CONSERVE_ROTATION {
+            let due: Vec<u32> = (0..CONSERVE_ROTATION)
+                .filter(|slot| rotation_due(Tier::Conserve, tick, *slot))
+                .collect();
+            assert_eq!(due.len(), 1);
+        }
+        for slot in 0..CONSERVE_ROTATION {
+            let runs = (0..CONSERVE_ROTATION * 3)
+                .filter(|tick| rotation_due(Tier::Conserve, *tick, slot))
+                .count();
+            assert_eq!(runs, 3);
+        }
+        assert!(rotation_due(Tier::Normal, 7, 2));
+        assert!(!rotation_due(Tier::Critical, 8, 0));
+    }
+
+    /// The checkpoint trips inside the margin, not at the limit itself.
+    #[test]
+    fn checkpoint_reserves_safety_margin() {
+        assert!(!checkpoint_exceeded(50.0, 100.0));
+        assert!(!checkpoint_exceeded(84.9, 100.0));
+        assert!(checkpoint_exceeded(85.0, 100.0));
+        assert!(checkpoint_exceeded(120.0, 100.0));
+
+        // A small limit still holds back the floor.
+        assert_eq!(checkpoint_margin(10.0), CHECKPOINT_MIN_MARGIN);
+        assert!(!checkpoint_exceeded(6.9, 10.0));
+        assert!(checkpoint_exceeded(7.0, 10.0));
+    }
+
     /// The legacy bar formula, now per-instance (no process-global
     /// state — two snapshots coexist, the M1 test-isolation payoff).
     #[test]
@@ -1,12 +1,20 @@
 use crate::cleanup::EntityCleanupQueue;
+use crate::entitymappingsystem::EntityMappingData;
+use crate::jobs::data::JobData;
+use crate::jobs::haul::HaulJob;
+use crate::jobs::scout::ScoutJob;
+use crate::jobs::upgrade::UpgradeJob;
+use crate::memorysystem::MemoryRecovery;
 use crate::serialize::*;
 use log::*;
 use screeps::*;
 use serde::{Deserialize, Serialize};
 use specs::saveload::*;
 use specs::*;
+use std::collections::HashSet;
 
 #[derive(Clone, Component, Serialize, Deserialize)]
+#[storage(FlaggedStorage)]
 pub struct CreepOwner {
     pub owner: ObjectId<Creep>,
 }
@@ -22,6 +30,7 @@ impl CreepOwner {
 }
 
 #[derive(Clone, Component, Serialize, Deserialize)]
+#[storage(FlaggedStorage)]
 pub struct CreepSpawning {
     pub name: String,
 }
@@ -101,6 +110,133 @@ impl<'a> System<'a> for CleanupCreepsSystem {
     }
 }
 
+/// Job family a creep left without an entity (memory recovery) is put back
+/// to work as, inferred from its body. Creep names carry no role, so the
+/// body is the only durable signal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdoptedRole {
+    /// WORK + CARRY: upgrades its home controller.
+    Worker,
+    /// CARRY without WORK: hauls within its home room.
+    Hauler,
+    /// MOVE only: scouts.
+    Scout,
+}
+
+/// Infer the job family for an orphaned creep. Combat, healer and claim
+/// bodies belong to squads and claim operations that a rebuilt world can't
+/// reconstruct, and a WORK-only static miner needs its container — those
+/// return `None` and are left to expire.
+pub fn infer_adopted_role(parts: &[Part]) -> Option<AdoptedRole> {
+    let has = |part: Part| parts.contains(&part);
+
+    if has(Part::Attack) || has(Part::RangedAttack) || has(Part::Heal) || has(Part::Claim) {
+        return None;
+    }
+
+    match (has(Part::Work), has(Part::Carry)) {
+        (true, true) => Some(AdoptedRole::Worker),
+        (false, true) => Some(AdoptedRole::Hauler),
+        (false, false) if !parts.is_empty() && parts.iter().all(|p| *p == Part::Move) => Some(AdoptedRole::Scout),
+        _ => None,
+    }
+}
+
+#[derive(SystemData)]
+pub struct AdoptOrphanCreepsSystemData<'a> {
+    entities: Entities<'a>,
+    creep_owner: ReadStorage<'a, CreepOwner>,
+    creep_spawning: ReadStorage<'a, CreepSpawning>,
+    mapping: Read<'a, EntityMappingData>,
+    recovery: Write<'a, MemoryRecovery>,
+    updater: Read<'a, LazyUpdate>,
+}
+
+/// After a memory recovery, give every live creep that has no entity a job
+/// inferred from its body ([`infer_adopted_role`]) so the colony keeps its
+/// workforce while the rebuilt operations ramp up. Adopted creeps belong to
+/// no mission; they work their job until they expire. Runs once per
+/// recovery, after `EntityMappingSystem` so this tick's rediscovered rooms
+/// resolve.
+pub struct AdoptOrphanCreepsSystem;
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+impl<'a> System<'a> for AdoptOrphanCreepsSystem {
+    type SystemData = AdoptOrphanCreepsSystemData<'a>;
+
+    fn run(&mut self, mut data: Self::SystemData) {
+        if !data.recovery.adopt_orphans {
+            return;
+        }
+
+        data.recovery.adopt_orphans = false;
+
+        let owned: HashSet<ObjectId<Creep>> = data.creep_owner.join().map(|o| o.id()).collect();
+        let pending: HashSet<&str> = data.creep_spawning.join().map(|s| s.name.as_str()).collect();
+
+        let owned_rooms: Vec<(RoomName, Entity)> = game::rooms()
+            .values()
+            .filter(|room| room.controller().map(|c| c.my()).unwrap_or(false))
+            .filter_map(|room| data.mapping.get_room(&room.name()).map(|entity| (room.name(), entity)))
+            .collect();
+
+        let mut adopted = 0;
+        let mut abandoned = 0;
+
+        for creep in game::creeps().values() {
+            if creep.spawning() || pending.contains(creep.name().as_str()) {
+                continue;
+            }
+
+            let id = match creep.try_id() {
+                Some(id) if !owned.contains(&id) => id,
+                _ => continue,
+            };
+
+            let parts: Vec<Part> = creep.body().iter().map(|p| p.part()).collect();
+
+            // Home is the owned room the creep stands in, else the nearest one.
+            let creep_room = creep.pos().room_name();
+            let home = owned_rooms
+                .iter()
+                .min_by_key(|(name, _)| {
+                    if *name == creep_room {
+                        0
+                    } else {
+                        1 + game::map::get_room_linear_distance(creep_room, *name, false)
+                    }
+                })
+                .map(|(_, entity)| *entity);
+
+            let job = match (infer_adopted_role(&parts), home) {
+                (Some(AdoptedRole::Worker), Some(home)) => JobData::Upgrade(UpgradeJob::new(home)),
+                (Some(AdoptedRole::Hauler), Some(home)) => JobData::Haul(HaulJob::new(&[home], &[home], true, false)),
+                (Some(AdoptedRole::Scout), _) => JobData::Scout(ScoutJob::new(None)),
+                _ => {
+                    abandoned += 1;
+                    continue;
+                }
+            };
+
+            info!("Adopting orphaned creep {} as {}", creep.name(), job.type_name());
+
+            data.updater
+                .create_entity(&data.entities)
+                .marked::<SerializeMarker>()
+                .with(CreepOwner::new(id))
+                .with(job)
+                .build();
+
+            adopted += 1;
+        }
+
+        warn!(
+            "Memory recovery: adopted {} orphaned creep(s), left {} without a job to expire",
+            adopted, abandoned
+        );
+    }
+}
+
 // `SpawnBodyDefinition` + `create_body` are pure body-construction; they now live in
 // `screeps-combat-decision::spawning` so the sim/eval can build the bot's real bodies without
 // depending on the whole bot. Re-exported here so existing `crate::creep::SpawnBodyDefinition` /
@@ -111,6 +247,7 @@ pub mod spawning {
     use super::*;
 
     // The pure body builder lives in the shared decision crate; re-export at the original path.
+    pub use crate::bodytemplate::{BodyTemplate, MoveRatio};
     pub use screeps_combat_decision::spawning::create_body;
 
     pub fn build<B>(builder: B, name: &str) -> B
@@ -123,3 +260,32 @@ pub mod spawning {
 
 // The `create_body` clamping pins moved with the code to
 // `screeps_combat_decision::spawning` (see that module's tests).
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn economy_bodies_map_to_their_job_family() {
+        assert_eq!(
+            infer_adopted_role(&[Part::Work, Part::Carry, Part::Move]),
+            Some(AdoptedRole::Worker)
+        );
+        assert_eq!(
+            infer_adopted_role(&[Part::Carry, Part::Carry, Part::Move]),
+            Some(AdoptedRole::Hauler)
+        );
+        assert_eq!(infer_adopted_role(&[Part::Move]), Some(AdoptedRole::Scout));
+    }
+
+    /// Bodies whose context can't be rebuilt are never adopted.
+    #[test]
+    fn squad_claim_and_static_bodies_are_left_alone() {
+        assert_eq!(infer_adopted_role(&[Part::Attack, Part::Carry, Part::Move]), None);
+        assert_eq!(infer_adopted_role(&[Part::Heal, Part::Move]), None);
+        assert_eq!(infer_adopted_role(&[Part::Claim, Part::Move]), None);
+        assert_eq!(infer_adopted_role(&[Part::Work, Part::Work, Part::Move]), None);
+        assert_eq!(infer_adopted_role(&[Part::Tough, Part::Move]), None);
+        assert_eq!(infer_adopted_role(&[]), None);
+    }
+}
@@ -0,0 +1,204 @@
+//! Native creep memory (`Memory.creeps`) audit.
+//!
+//! All per-creep state lives in ECS components and is serialized with the
+//! world; nothing should read or write `creep.memory`. The engine still
+//! creates entries (the `creep.memory` getter makes one on first touch), and
+//! every entry costs `Memory` parse time each tick, so every
+//! [`CREEP_MEMORY_CLEANUP_INTERVAL`] ticks [`CreepMemoryCleanupSystem`]
+//! deletes the entries of dead creeps and strips those of living ones,
+//! warning once per key it finds there, as each names state that leaked out
+//! of ECS.
+//!
+//! Built with the `memory-audit` feature, [`install_write_trap`] wraps the
+//! `creep.memory` property at startup so every access logs the JS stack
+//! that made it.
+
+use log::*;
+use screeps::*;
+use specs::prelude::*;
+use std::collections::{BTreeSet, HashSet};
+
+/// Ticks between sweeps of `Memory.creeps`.
+pub const CREEP_MEMORY_CLEANUP_INTERVAL: u32 = 100;
+
+/// What one sweep of `Memory.creeps` removes.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CreepMemorySweep {
+    /// Entries of creeps that are gone.
+    pub dead: Vec<String>,
+    /// Entries of living creeps that held anything.
+    pub stripped: Vec<String>,
+    /// Keys found in living creeps' entries.
+    pub leaked_keys: BTreeSet<String>,
+}
+
+/// Plan a sweep of `entries` (creep name and the keys of its entry) given
+/// the names of the living creeps. Empty entries of living creeps are left
+/// alone: the engine recreates them, so deleting them only churns.
+pub fn plan_sweep(entries: &[(String, Vec<String>)], alive: &HashSet<String>) -> CreepMemorySweep {
+    let mut sweep = CreepMemorySweep::default();
+
+    for (name, keys) in entries {
+        if !alive.contains(name) {
+            sweep.dead.push(name.clone());
+        } else if !keys.is_empty() {
+            sweep.stripped.push(name.clone());
+            sweep.leaked_keys.extend(keys.iter().cloned());
+        }
+    }
+
+    sweep
+}
+
+/// Runtime resource: when `Memory.creeps` was last swept, and the leaked
+/// keys already warned about.
+#[derive(Default)]
+pub struct CreepMemoryAudit {
+    swept_at: Option<u32>,
+    warned_keys: HashSet<String>,
+}
+
+impl CreepMemoryAudit {
+    pub fn needs_sweep(&self, now: u32) -> bool {
+        self.swept_at
+            .map(|swept_at| now.saturating_sub(swept_at) >= CREEP_MEMORY_CLEANUP_INTERVAL)
+            .unwrap_or(true)
+    }
+
+    /// Record a sweep at `now`, returning the leaked keys not seen before.
+    pub fn record(&mut self, sweep: &CreepMemorySweep, now: u32) -> Vec<String> {
+        self.swept_at = Some(now);
+
+        sweep
+            .leaked_keys
+            .iter()
+            .filter(|key| self.warned_keys.insert((*key).clone()))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Deletes dead creeps' `Memory.creeps` entries and strips living ones on
+/// the cleanup interval.
+pub struct CreepMemoryCleanupSystem;
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+impl<'a> System<'a> for CreepMemoryCleanupSystem {
+    type SystemData = Write<'a, CreepMemoryAudit>;
+
+    fn run(&mut self, mut audit: Self::SystemData) {
+        let now = game::time();
+
+        if !audit.needs_sweep(now) {
+            return;
+        }
+
+        let Some(creeps_memory) = crate::memory_helper::dict("creeps") else {
+            audit.record(&CreepMemorySweep::default(), now);
+
+            return;
+        };
+
+        let alive: HashSet<String> = game::creeps().keys().map(|name| name.to_string()).collect();
+
+        let entries: Vec<(String, Vec<String>)> = crate::memory_helper::keys(&creeps_memory)
+            .into_iter()
+            .map(|name| {
+                let entry = js_sys::Reflect::get(&creeps_memory, &wasm_bindgen::JsValue::from_str(&name))
+                    .unwrap_or(wasm_bindgen::JsValue::UNDEFINED);
+                let keys = crate::memory_helper::keys(&entry);
+
+                (name, keys)
+            })
+            .collect();
+
+        let sweep = plan_sweep(&entries, &alive);
+
+        for name in sweep.dead.iter().chain(sweep.stripped.iter()) {
+            crate::memory_helper::del(&creeps_memory, name);
+        }
+
+        for key in audit.record(&sweep, now) {
+            warn!("[CreepMemory] native creep memory holds `{}`; per-creep state belongs in ECS", key);
+        }
+
+        if !sweep.dead.is_empty() || !sweep.stripped.is_empty() {
+            debug!(
+                "[CreepMemory] removed {} dead and stripped {} living creep entries",
+                sweep.dead.len(),
+                sweep.stripped.len()
+            );
+        }
+    }
+}
+
+/// Wraps `Creep.prototype.memory` so every read or write logs its JS stack.
+/// Any access is a write in effect, as the getter creates the entry.
+#[cfg(feature = "memory-audit")]
+const WRITE_TRAP: &str = r#"
+const descriptor = Object.getOwnPropertyDescriptor(Creep.prototype, 'memory');
+if (!descriptor || descriptor.get.__ibexAudit) {
+    return;
+}
+const report = (creep, access) => console.log(`[CreepMemory] native memory ${access} for ${creep.name}\n${new Error().stack}`);
+const get = function () {
+    report(this, 'read');
+    return descriptor.get.call(this);
+};
+get.__ibexAudit = true;
+Object.defineProperty(Creep.prototype, 'memory', {
+    configurable: true,
+    get,
+    set(value) {
+        report(this, 'write');
+        descriptor.set.call(this, value);
+    },
+});
+"#;
+
+/// Install the `creep.memory` access trap; see [`WRITE_TRAP`].
+#[cfg(feature = "memory-audit")]
+pub fn install_write_trap() {
+    if let Err(err) = js_sys::Function::new_no_args(WRITE_TRAP).call0(&wasm_bindgen::JsValue::UNDEFINED) {
+        warn!("[CreepMemory] failed to install the creep memory trap: {:?}", err);
+    } else {
+        info!("[CreepMemory] auditing native creep memory access");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, keys: &[&str]) -> (String, Vec<String>) {
+        (name.to_string(), keys.iter().map(|key| key.to_string()).collect())
+    }
+
+    #[test]
+    fn sweep_removes_dead_entries_and_strips_living_ones_that_hold_state() {
+        let entries = vec![entry("dead", &["role"]), entry("idle", &[]), entry("leaky", &["_move", "role"])];
+        let alive: HashSet<String> = ["idle", "leaky"].iter().map(|name| name.to_string()).collect();
+
+        let sweep = plan_sweep(&entries, &alive);
+
+        assert_eq!(sweep.dead, vec!["dead".to_string()]);
+        assert_eq!(sweep.stripped, vec!["leaky".to_string()]);
+        assert_eq!(
+            sweep.leaked_keys.into_iter().collect::<Vec<_>>(),
+            vec!["_move".to_string(), "role".to_string()]
+        );
+    }
+
+    #[test]
+    fn audit_warns_once_per_key_and_sweeps_on_its_interval() {
+        let mut audit = CreepMemoryAudit::default();
+        let sweep = plan_sweep(&[entry("leaky", &["role"])], &HashSet::from(["leaky".to_string()]));
+
+        assert!(audit.needs_sweep(0));
+        assert_eq!(audit.record(&sweep, 10), vec!["role".to_string()]);
+        assert!(audit.record(&sweep, 10 + CREEP_MEMORY_CLEANUP_INTERVAL).is_empty());
+
+        assert!(!audit.needs_sweep(10 + 2 * CREEP_MEMORY_CLEANUP_INTERVAL - 1));
+        assert!(audit.needs_sweep(10 + 2 * CREEP_MEMORY_CLEANUP_INTERVAL));
+    }
+}
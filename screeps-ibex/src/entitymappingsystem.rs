@@ -1,17 +1,98 @@
+use crate::creep::*;
 use crate::room::data::*;
 use screeps::*;
 use specs::prelude::*;
 use std::collections::HashMap;
 
+/// Side of a creep spatial bucket, in tiles.
+pub const CREEP_BUCKET_SIZE: u8 = 5;
+
+type CreepBucket = (RoomName, u8, u8);
+
+fn creep_bucket(pos: Position) -> CreepBucket {
+    (pos.room_name(), pos.x().u8() / CREEP_BUCKET_SIZE, pos.y().u8() / CREEP_BUCKET_SIZE)
+}
+
+/// Our creeps' entities by name, and by position in coarse per-room buckets so proximity queries
+/// only look at nearby creeps. Rebuilt each tick by `EntityMappingSystem`; entities deleted later in
+/// the tick are dropped by `EntityCleanupSystem`.
+#[derive(Default)]
+pub struct CreepIndex {
+    names: HashMap<String, Entity>,
+    buckets: HashMap<CreepBucket, Vec<(Entity, Position)>>,
+}
+
+impl CreepIndex {
+    pub fn clear(&mut self) {
+        self.names.clear();
+        self.buckets.clear();
+    }
+
+    /// Index a creep. Creeps still spawning have no position yet.
+    pub fn insert(&mut self, name: String, entity: Entity, pos: Option<Position>) {
+        self.names.insert(name, entity);
+
+        if let Some(pos) = pos {
+            self.buckets.entry(creep_bucket(pos)).or_default().push((entity, pos));
+        }
+    }
+
+    pub fn remove_entity(&mut self, entity: Entity) {
+        self.names.retain(|_, e| *e != entity);
+
+        for creeps in self.buckets.values_mut() {
+            creeps.retain(|(e, _)| *e != entity);
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<Entity> {
+        self.names.get(name).copied()
+    }
+
+    /// Creeps within `range` of `pos`, in the same room.
+    pub fn within(&self, pos: Position, range: u32) -> impl Iterator<Item = Entity> + '_ {
+        let range = range.min(ROOM_SIZE as u32) as u8;
+        let bucket = |coord: u8, offset: i16| ((coord as i16 + offset).clamp(0, ROOM_SIZE as i16 - 1) as u8) / CREEP_BUCKET_SIZE;
+
+        let (x, y) = (pos.x().u8(), pos.y().u8());
+        let (min_x, max_x) = (bucket(x, -(range as i16)), bucket(x, range as i16));
+        let (min_y, max_y) = (bucket(y, -(range as i16)), bucket(y, range as i16));
+        let room_name = pos.room_name();
+
+        (min_x..=max_x)
+            .flat_map(move |bx| (min_y..=max_y).map(move |by| (room_name, bx, by)))
+            .filter_map(|bucket| self.buckets.get(&bucket))
+            .flatten()
+            .filter(move |(_, creep_pos)| creep_pos.get_range_to(pos) <= range as u32)
+            .map(|(entity, _)| *entity)
+    }
+}
+
 #[derive(Default)]
 pub struct EntityMappingData {
     rooms: HashMap<RoomName, Entity>,
+    creeps: CreepIndex,
 }
 
 impl EntityMappingData {
     pub fn get_room(&self, room_name: &RoomName) -> Option<Entity> {
         self.rooms.get(room_name).cloned()
     }
+
+    /// The entity of our creep with this name, spawned or still spawning.
+    pub fn get_creep(&self, name: &str) -> Option<Entity> {
+        self.creeps.get(name)
+    }
+
+    /// Our spawned creeps within `range` of `pos`, in the same room, as of the start of the tick.
+    pub fn creeps_within(&self, pos: Position, range: u32) -> impl Iterator<Item = Entity> + '_ {
+        self.creeps.within(pos, range)
+    }
+
+    /// Drop a deleted creep entity from the creep lookups.
+    pub fn remove_creep(&mut self, entity: Entity) {
+        self.creeps.remove_entity(entity);
+    }
 }
 
 #[derive(SystemData)]
@@ -19,6 +100,8 @@ pub struct EntityMappingSystemData<'a> {
     mapping: Write<'a, EntityMappingData>,
     entities: Entities<'a>,
     room_data: ReadStorage<'a, RoomData>,
+    creep_owner: ReadStorage<'a, CreepOwner>,
+    creep_spawning: ReadStorage<'a, CreepSpawning>,
 }
 
 pub struct EntityMappingSystem;
@@ -32,5 +115,92 @@ impl<'a> System<'a> for EntityMappingSystem {
             .join()
             .map(|(entity, room_data)| (room_data.name, entity))
             .collect::<HashMap<RoomName, Entity>>();
+
+        let creep_ids: HashMap<ObjectId<Creep>, Entity> = (&data.entities, &data.creep_owner)
+            .join()
+            .map(|(entity, owner)| (owner.id(), entity))
+            .collect();
+
+        let creeps = &mut data.mapping.creeps;
+
+        creeps.clear();
+
+        for creep in game::creeps().values() {
+            if let Some(entity) = creep.try_id().and_then(|id| creep_ids.get(&id)) {
+                creeps.insert(creep.name(), *entity, Some(creep.pos()));
+            }
+        }
+
+        for (entity, spawning) in (&data.entities, &data.creep_spawning).join() {
+            creeps.insert(spawning.name.clone(), entity, None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(x: u8, y: u8) -> Position {
+        Position::new(
+            RoomCoordinate::new(x).expect("valid coordinate"),
+            RoomCoordinate::new(y).expect("valid coordinate"),
+            "W1N1".parse().expect("valid room name"),
+        )
+    }
+
+    fn index(world: &mut World, creeps: &[(&str, Option<Position>)]) -> (CreepIndex, Vec<Entity>) {
+        let mut index = CreepIndex::default();
+        let mut entities = Vec::new();
+
+        for (name, pos) in creeps {
+            let entity = world.create_entity().build();
+            index.insert(name.to_string(), entity, *pos);
+            entities.push(entity);
+        }
+
+        (index, entities)
+    }
+
+    #[test]
+    fn finds_creeps_by_name() {
+        let mut world = World::new();
+        let (index, entities) = index(&mut world, &[("a", Some(pos(10, 10))), ("b", None)]);
+
+        assert_eq!(index.get("a"), Some(entities[0]));
+        assert_eq!(index.get("b"), Some(entities[1]));
+        assert_eq!(index.get("c"), None);
+    }
+
+    /// Range is checked per creep, across bucket boundaries, and spawning creeps have no position.
+    #[test]
+    fn proximity_spans_buckets() {
+        let mut world = World::new();
+        let (index, entities) = index(
+            &mut world,
+            &[
+                ("near", Some(pos(14, 10))),
+                ("across", Some(pos(16, 12))),
+                ("far", Some(pos(20, 10))),
+                ("spawning", None),
+            ],
+        );
+
+        let mut found: Vec<Entity> = index.within(pos(13, 10), 3).collect();
+        found.sort();
+
+        assert_eq!(found, vec![entities[0], entities[1]]);
+        assert_eq!(index.within(pos(0, 0), 3).count(), 0);
+    }
+
+    #[test]
+    fn deleted_entities_are_dropped() {
+        let mut world = World::new();
+        let (mut index, entities) = index(&mut world, &[("a", Some(pos(10, 10))), ("b", Some(pos(11, 10)))]);
+
+        index.remove_entity(entities[0]);
+
+        assert_eq!(index.get("a"), None);
+        assert_eq!(index.within(pos(10, 10), 1).collect::<Vec<_>>(), vec![entities[1]]);
     }
 }
@@ -1,4 +1,8 @@
+use crate::military::posture::PostureLevel;
+use log::*;
+use screeps::{ResourceType, RoomName};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use wasm_bindgen::JsValue;
 
 // ─── Reset flags (separate from feature flags) ────────────────────────────────
@@ -49,11 +53,45 @@ pub struct ResetFlags {
 #[serde(default)]
 pub struct VisualizeFeatures {
     pub on: bool,
+    /// Room dashboard (`ui::RoomSidebar`) in the top-left of owned rooms.
+    pub sidebar: bool,
+    /// Operation -> mission hierarchy drawn on the world map, one block per
+    /// room (`mission_map`). Off by default: `describe_state` runs for every
+    /// mission while it is on.
+    pub mission_map: bool,
+    /// Creeps `say` a short code for what their job is doing each tick, e.g.
+    /// `H>S` for a hauler delivering to storage. Off by default: every say is
+    /// an intent. Usually switched on for one room through its overrides.
+    pub say: bool,
+    /// Mission categories listed in a room's Missions panel (see
+    /// `visualization::MissionCategory`). A hidden category isn't summarized
+    /// at all. Usually switched per room through its overrides.
+    pub economy_missions: bool,
+    pub military_missions: bool,
+    pub construction_missions: bool,
+    /// Deepest level of a mission summary drawn; a deeper level collapses
+    /// into a count. 0 draws every level.
+    pub max_depth: u8,
+    /// Rooms drawn at once. The known rooms take turns a page at a time,
+    /// the next page every `page_ticks`. 0 draws every room each tick.
+    pub page_rooms: u32,
+    pub page_ticks: u32,
 }
 
 impl Default for VisualizeFeatures {
     fn default() -> Self {
-        Self { on: true }
+        Self {
+            on: true,
+            sidebar: true,
+            mission_map: false,
+            say: false,
+            economy_missions: true,
+            military_missions: true,
+            construction_missions: true,
+            max_depth: 0,
+            page_rooms: 0,
+            page_ticks: 10,
+        }
     }
 }
 
@@ -143,6 +181,21 @@ pub struct MarketFeatures {
     pub credit_reserve: f64,
     pub buy_energy: bool,
     pub buy_minerals: bool,
+    /// Deal surplus energy for credits, and cheap energy into short rooms
+    /// (see `transfer::energyarbitrage`).
+    pub energy_arbitrage: bool,
+    /// Fewest credits per energy spent, fee included, a sale must bring.
+    pub energy_sell_floor: f64,
+    /// The sale floor for a room whose storage overflows (see
+    /// `missions::overflow`), where the energy is otherwise wasted.
+    pub overflow_sell_floor: f64,
+    /// Most credits per energy landed, fee included, a purchase may cost.
+    pub energy_buy_ceiling: f64,
+    /// Arbitrage deals made in one tick, across all rooms.
+    pub energy_deals_per_tick: u32,
+    /// Buy what the empire is short of its resource targets (see
+    /// `resource_overview`), into the room with the most terminal space.
+    pub buy_deficits: bool,
 }
 
 impl Default for MarketFeatures {
@@ -153,6 +206,12 @@ impl Default for MarketFeatures {
             credit_reserve: 10_000_000.0,
             buy_energy: false,
             buy_minerals: false,
+            energy_arbitrage: false,
+            energy_sell_floor: 10.0,
+            overflow_sell_floor: 1.0,
+            energy_buy_ceiling: 2.0,
+            energy_deals_per_tick: 2,
+            buy_deficits: false,
         }
     }
 }
@@ -186,6 +245,33 @@ impl TransferVisualizeFeatures {
 #[derive(Default)]
 pub struct TransferFeatures {
     pub visualize: TransferVisualizeFeatures,
+    /// Draw this tick's matched pickup -> delivery pairs as arrows, colored by
+    /// resource and sized by amount (requires `visualize.on`).
+    pub visualize_flows: bool,
+    pub aging: TransferAgingFeatures,
+}
+
+/// Promotion of starved Low deposits (see `transfer::transfersystem`).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TransferAgingFeatures {
+    pub on: bool,
+    /// Ticks a target's Low deposit demand goes unserviced before haulers
+    /// select it as Medium.
+    pub promote_after: u32,
+    /// Ticks a starved target's age is kept while its room's demand isn't
+    /// generated.
+    pub forget_after: u32,
+}
+
+impl Default for TransferAgingFeatures {
+    fn default() -> Self {
+        Self {
+            on: true,
+            promote_after: 300,
+            forget_after: 50,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -193,6 +279,13 @@ pub struct TransferFeatures {
 pub struct RemoteMineFeatures {
     pub harvest: bool,
     pub reserve: bool,
+    /// Lay road construction sites from the home storage to each remote
+    /// source. Roads already laid are still repaired when this is off.
+    pub roads: bool,
+    /// Rampart and road the narrowest line inside the home exit the remote's
+    /// haulers leave by, so defenders have a prepared position there. Off by
+    /// default: it costs energy a small colony shouldn't spend.
+    pub exit_chokes: bool,
 }
 
 impl Default for RemoteMineFeatures {
@@ -200,6 +293,8 @@ impl Default for RemoteMineFeatures {
         Self {
             harvest: true,
             reserve: true,
+            roads: true,
+            exit_chokes: false,
         }
     }
 }
@@ -345,18 +440,33 @@ pub struct MilitaryFeatures {
     /// Allow attacking invader cores, strongholds, and invader bases.
     /// Requires `offense` to be enabled.
     pub attack_invaders: bool,
+    /// Send CLAIM creeps to `attackController` the controllers of player rooms
+    /// we are sieging, timed to the upgrade-block cooldown. Requires `offense`
+    /// and `attack_players`. Default: true.
+    pub downgrade: bool,
     /// Request boosts for military creeps.
     pub boost_military: bool,
     /// Allow safe mode activation as last resort.
     pub safe_mode: bool,
     /// Enable nuke defense mission.
     pub nuke_defense: bool,
+    /// During a siege, rampart the plan tile that closes a hostile path to
+    /// the spawns and storage, and spawn a builder for it. Default: true.
+    pub emergency_ramparts: bool,
+    /// Ticks between the arrivals of a multi-squad force's squads: the drain
+    /// squad reaches the target this long before the strike squad, so the
+    /// towers are dry when it engages. Default: 30.
+    pub wave_window_ticks: u32,
     /// Enable verbose debug logging for war system (target selection, threat
     /// intel, defense decisions). Useful for diagnosing why attacks are or
     /// aren't being launched.
     pub debug_log: bool,
     /// Visualization settings.
     pub visualize: MilitaryVisualizeFeatures,
+    /// Draw the incoming-DPS heat map of threatened rooms and highlight
+    /// rampart perimeter gaps hostiles can walk through (requires
+    /// `visualize.on`).
+    pub visualize_threat: bool,
 }
 
 impl Default for MilitaryFeatures {
@@ -370,11 +480,15 @@ impl Default for MilitaryFeatures {
             offense: true,
             attack_players: false,
             attack_invaders: true,
+            downgrade: true,
             boost_military: false,
             safe_mode: true,
             nuke_defense: true,
+            emergency_ramparts: true,
+            wave_window_ticks: 30,
             debug_log: false,
             visualize: MilitaryVisualizeFeatures::default(),
+            visualize_threat: false,
         }
     }
 }
@@ -658,6 +772,79 @@ pub struct VisibilityFeatures {
     pub visualize: bool,
 }
 
+/// Per-system / per-mission / per-job CPU accounting (`cpu_accounting`).
+/// Off by default: when off, no dispatch site reads the CPU clock.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CpuAccountingFeatures {
+    /// Measure CPU around system, mission and job dispatch and export the
+    /// previous tick's breakdown through the stats segment.
+    pub on: bool,
+    /// Render the top-10 breakdown as a panel (requires `on` and the global
+    /// `visualize.on`). Default: true.
+    pub visualize: bool,
+}
+
+impl Default for CpuAccountingFeatures {
+    fn default() -> Self {
+        Self {
+            on: false,
+            visualize: true,
+        }
+    }
+}
+
+impl CpuAccountingFeatures {
+    /// Returns `on && visualize && global visualize.on`.
+    pub fn visualize(&self, global_visualize: bool) -> bool {
+        self.on && self.visualize && global_visualize
+    }
+}
+
+/// Soft intent caps under a low bucket (`intents`). Each accepted intent
+/// costs 0.2 CPU; below the normal governor tier optional repairs (drive-by
+/// and tower) stop once the tick has issued this many repair intents.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IntentFeatures {
+    /// Repair intents per tick at the Conserve tier. Default: 20.
+    pub repair_cap_conserve: u32,
+    /// Repair intents per tick at the Critical tier. Default: 5.
+    pub repair_cap_critical: u32,
+}
+
+impl Default for IntentFeatures {
+    fn default() -> Self {
+        Self {
+            repair_cap_conserve: 20,
+            repair_cap_critical: 5,
+        }
+    }
+}
+
+/// World-save strategy (`serialize`).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SerializeFeatures {
+    /// Reuse the previous tick's encoded record for entities whose
+    /// components haven't changed. Turn off to re-encode every entity every
+    /// tick (the escape hatch if a cached record is ever suspected stale).
+    /// Default: true.
+    pub incremental: bool,
+    /// Ticks between forced full re-encodes while `incremental` is on.
+    /// Default: 100.
+    pub full_interval: u32,
+}
+
+impl Default for SerializeFeatures {
+    fn default() -> Self {
+        Self {
+            incremental: true,
+            full_interval: 100,
+        }
+    }
+}
+
 /// Harness-only knobs (P1.A5): set from the eval harness via console
 /// injection (`Memory._features.eval.* = …`), never by gameplay code.
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
@@ -677,6 +864,20 @@ pub struct EvalFeatures {
     pub panic_at_tick: u32,
 }
 
+/// Overrides for the detected `server::ServerCapabilities`. `None` keeps
+/// what was detected.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerFeatures {
+    /// `Some(false)` stops all market use. A server without a market can't
+    /// be given one.
+    pub market: Option<bool>,
+    /// Treat the server as an official shard (or not).
+    pub official: Option<bool>,
+    /// Write the screepspl.us stats segment. Defaults to `official`.
+    pub stats_export: Option<bool>,
+}
+
 // ─── Top-level features ────────────────────────────────────────────────────────
 
 /// All feature flags, loaded once per tick from `Memory._features`.
@@ -706,11 +907,25 @@ pub struct Features {
     /// Allow the dismantler role in salvage missions; semantics as `raid`.
     /// Default: true.
     pub dismantle: bool,
+    /// Allow the spawn queue to spawn and renew creeps. Requests still queue
+    /// (and show in the spawn-queue panel) while off. Default: true.
+    pub spawning: bool,
+    /// Create, level, spawn and run operator power creeps. Requested powers
+    /// go unused while off. Default: true.
+    pub power_creeps: bool,
     /// Log per-system CPU timing for each ECS system in the game loop.
     /// When enabled, each system's CPU cost is measured and logged at info level.
     pub system_timing: bool,
+    /// Rolling CPU breakdown for the stats export and visualizer.
+    pub cpu_accounting: CpuAccountingFeatures,
+    /// Soft per-category intent caps under a low bucket.
+    pub intents: IntentFeatures,
+    /// World-save strategy.
+    pub serialize: SerializeFeatures,
     /// Harness-only fault-injection knobs (P1.A5).
     pub eval: EvalFeatures,
+    /// Server capability overrides.
+    pub server: ServerFeatures,
 }
 
 impl Default for Features {
@@ -730,9 +945,317 @@ impl Default for Features {
             source_keeper: SourceKeeperFeatures::default(),
             visibility: VisibilityFeatures::default(),
             dismantle: true,
+            spawning: true,
+            power_creeps: true,
             system_timing: false,
+            cpu_accounting: CpuAccountingFeatures::default(),
+            intents: IntentFeatures::default(),
+            serialize: SerializeFeatures::default(),
             eval: EvalFeatures::default(),
+            server: ServerFeatures::default(),
+        }
+    }
+}
+
+impl Features {
+    /// The flags as seen from `room`: the global values with that room's
+    /// overrides applied.
+    pub fn for_room(&self, overrides: &FeatureOverrides, room: RoomName) -> Features {
+        let room_overrides = overrides.room(room);
+        let mut features = *self;
+
+        if let Some(on) = room_overrides.visualize {
+            features.visualize.on = on;
+        }
+
+        if let Some(on) = room_overrides.sidebar {
+            features.visualize.sidebar = on;
+        }
+
+        if let Some(on) = room_overrides.say {
+            features.visualize.say = on;
+        }
+
+        if let Some(on) = room_overrides.economy_missions {
+            features.visualize.economy_missions = on;
+        }
+
+        if let Some(on) = room_overrides.military_missions {
+            features.visualize.military_missions = on;
+        }
+
+        if let Some(on) = room_overrides.construction_missions {
+            features.visualize.construction_missions = on;
+        }
+
+        if let Some(on) = room_overrides.spawning {
+            features.spawning = on;
+        }
+
+        if let Some(on) = room_overrides.remote_mine {
+            features.remote_mine.harvest = on;
+            features.remote_mine.reserve = on;
+        }
+
+        if let Some(on) = room_overrides.defense {
+            features.military.defense = on;
+        }
+
+        features
+    }
+}
+
+// ─── Controller signs ──────────────────────────────────────────────────────────
+//
+// `Memory._features.signs` holds the text our creeps sign controllers with,
+// e.g. `Memory._features.signs.attack = "Surrender"`. Strings aren't `Copy`,
+// so the signs live beside `Features` rather than in it.
+
+/// Which kind of room a controller sign is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignPurpose {
+    /// A room we own or are claiming.
+    Owned,
+    /// A remote we reserve.
+    Reserved,
+    /// A hostile room we're attacking.
+    Attack,
+}
+
+/// Sign text per purpose, loaded once per tick alongside [`Features`] and
+/// inserted into the world as a Resource.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SignFeatures {
+    pub owned: String,
+    pub reserved: String,
+    pub attack: String,
+}
+
+impl Default for SignFeatures {
+    fn default() -> Self {
+        Self {
+            owned: crate::constants::ROOM_SIGN.to_string(),
+            reserved: crate::constants::ROOM_SIGN.to_string(),
+            attack: crate::constants::ATTACK_SIGN.to_string(),
+        }
+    }
+}
+
+impl SignFeatures {
+    pub fn message(&self, purpose: SignPurpose) -> &str {
+        match purpose {
+            SignPurpose::Owned => &self.owned,
+            SignPurpose::Reserved => &self.reserved,
+            SignPurpose::Attack => &self.attack,
+        }
+    }
+}
+
+// ─── Resource consolidation ────────────────────────────────────────────────────
+//
+// `Memory._features.consolidation` names the hub room every other terminal
+// ships its surplus minerals and compounds to, e.g.
+// `Memory._features.consolidation.hub = "W1N1"` (or the `set_hub` console
+// command). Like the signs it isn't `Copy`, so it lives beside `Features`.
+
+/// Where surplus resources are consolidated, and what each room keeps.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConsolidationFeatures {
+    /// The hub room; `None` turns consolidation off.
+    pub hub: Option<RoomName>,
+    /// Amount of each resource a room keeps back from the hub.
+    pub reserve: u32,
+    /// Smallest send, so each transaction and terminal cooldown moves a
+    /// worthwhile batch.
+    pub min_batch: u32,
+    /// Resources that never leave their room. Defaults to the defender boosts.
+    pub exclude: Vec<ResourceType>,
+}
+
+impl Default for ConsolidationFeatures {
+    fn default() -> Self {
+        Self {
+            hub: None,
+            reserve: 3_000,
+            min_batch: 2_000,
+            exclude: crate::military::damage::DEFENDER_BOOSTS
+                .iter()
+                .map(|(_, compound)| *compound)
+                .collect(),
+        }
+    }
+}
+
+impl ConsolidationFeatures {
+    /// The hub `room` should ship `resource` to, if it should.
+    pub fn hub_for(&self, room: RoomName, resource: ResourceType) -> Option<RoomName> {
+        self.hub
+            .filter(|hub| *hub != room)
+            .filter(|_| resource != ResourceType::Energy && !self.exclude.contains(&resource))
+    }
+}
+
+// ─── Resource targets ──────────────────────────────────────────────────────────
+//
+// `Memory._features.resources.targets` holds how much of each resource the
+// empire wants on hand across all its rooms, e.g.
+// `Memory._features.resources.targets = { XGHO2: 10000 }`. The shortfalls
+// drive lab production and, with `market.buy_deficits`, purchases; see
+// `resource_overview`.
+
+/// Empire-wide resource targets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ResourceFeatures {
+    pub targets: HashMap<ResourceType, u32>,
+}
+
+// ─── Logging ───────────────────────────────────────────────────────────────────
+//
+// `Memory._features.logging.levels` sets the per-module log levels, e.g.
+// `Memory._features.logging.levels = "missions::attack=debug, transfer=warn"`
+// (or the `log_level` console command). See `logging` for the syntax.
+
+/// Per-module log levels.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct LoggingFeatures {
+    /// Level spec; empty logs everything at the default `info`.
+    pub levels: String,
+}
+
+// ─── Per-room overrides ────────────────────────────────────────────────────────
+//
+// `Memory._features.rooms.<room name>` holds the overrides for one room, e.g.
+// `Memory._features.rooms.W1N1 = { spawning: false }`. A missing key inherits
+// the global flag. For remote mining the room is the remote (outpost) room;
+// for spawning, defense and visualization it is the room itself.
+
+/// Overrides for one room. `None` inherits the global flag.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RoomFeatureOverrides {
+    /// `visualize.on` for this room's visuals.
+    pub visualize: Option<bool>,
+    /// `visualize.sidebar` for this room's dashboard.
+    pub sidebar: Option<bool>,
+    /// `visualize.say` for creeps standing in this room.
+    pub say: Option<bool>,
+    /// `visualize.economy_missions` etc. for this room's Missions panel.
+    pub economy_missions: Option<bool>,
+    pub military_missions: Option<bool>,
+    pub construction_missions: Option<bool>,
+    /// `spawning` for this room's spawns.
+    pub spawning: Option<bool>,
+    /// Both `remote_mine.harvest` and `remote_mine.reserve` when this room is
+    /// an outpost.
+    pub remote_mine: Option<bool>,
+    /// `military.defense` for this room: owned-room defense (defenders,
+    /// safe mode, nuke defense, wall repair), invader defense when it's a
+    /// reserved remote, and `defend` flags placed in it.
+    pub defense: Option<bool>,
+    /// Defense posture pinned from the console; `None` leaves it automatic.
+    pub posture: Option<PostureLevel>,
+}
+
+impl RoomFeatureOverrides {
+    /// The set overrides as `flag=on|off` (and `posture=<level>`), space separated.
+    pub fn describe(&self) -> String {
+        let flags = [
+            ("visualize", self.visualize),
+            ("sidebar", self.sidebar),
+            ("say", self.say),
+            ("economy_missions", self.economy_missions),
+            ("military_missions", self.military_missions),
+            ("construction_missions", self.construction_missions),
+            ("spawning", self.spawning),
+            ("remote_mine", self.remote_mine),
+            ("defense", self.defense),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|on| format!("{}={}", name, if on { "on" } else { "off" })));
+
+        flags
+            .chain(self.posture.map(|level| format!("posture={}", level.name())))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Every room's overrides, loaded once per tick alongside [`Features`] and
+/// inserted into the world as a Resource.
+#[derive(Debug, Clone, Default)]
+pub struct FeatureOverrides {
+    rooms: HashMap<RoomName, RoomFeatureOverrides>,
+}
+
+impl FeatureOverrides {
+    pub fn new(rooms: HashMap<RoomName, RoomFeatureOverrides>) -> FeatureOverrides {
+        FeatureOverrides { rooms }
+    }
+
+    pub fn room(&self, room: RoomName) -> RoomFeatureOverrides {
+        self.rooms.get(&room).copied().unwrap_or_default()
+    }
+
+    /// Whether any room's overrides satisfy `predicate` — e.g. a room that
+    /// switches on something the global flag has off.
+    pub fn any<F>(&self, predicate: F) -> bool
+    where
+        F: Fn(&RoomFeatureOverrides) -> bool,
+    {
+        self.rooms.values().any(predicate)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&RoomName, &RoomFeatureOverrides)> {
+        self.rooms.iter()
+    }
+}
+
+/// Every flag that differs from its default as `path=value` (the dotted path
+/// under `Memory._features`), followed by one `room flag=… …` line per room
+/// with overrides. Feeds the UI panel that makes a toggled-off flag obvious.
+pub fn non_default_flags(features: &Features, overrides: &FeatureOverrides) -> Vec<String> {
+    let mut flags = Vec::new();
+
+    if let (Ok(current), Ok(default)) = (serde_json::to_value(features), serde_json::to_value(Features::default())) {
+        diff_flags("", &current, &default, &mut flags);
+    }
+
+    flags.sort();
+
+    let mut rooms: Vec<(String, String)> = overrides
+        .iter()
+        .map(|(room, room_overrides)| (room.to_string(), room_overrides.describe()))
+        .filter(|(_, described)| !described.is_empty())
+        .collect();
+    rooms.sort();
+
+    flags.extend(rooms.into_iter().map(|(room, described)| format!("{} {}", room, described)));
+
+    flags
+}
+
+fn diff_flags(path: &str, current: &serde_json::Value, default: &serde_json::Value, out: &mut Vec<String>) {
+    match (current, default) {
+        (serde_json::Value::Object(current), serde_json::Value::Object(default)) => {
+            for (key, value) in current {
+                let child = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+
+                match default.get(key) {
+                    Some(default_value) => diff_flags(&child, value, default_value, out),
+                    None => out.push(format!("{}={}", child, value)),
+                }
+            }
         }
+        _ if current != default => out.push(format!("{}={}", path, current)),
+        _ => {}
     }
 }
 
@@ -761,6 +1284,54 @@ fn features_from_memory() -> Features {
     }
 }
 
+/// Deserialize `_features.signs` from Memory, defaulting any missing or
+/// malformed message.
+fn signs_from_memory() -> SignFeatures {
+    let signs = js_get(&js_get(&crate::memory_helper::root(), "_features"), "signs");
+
+    if signs.is_undefined() || signs.is_null() {
+        SignFeatures::default()
+    } else {
+        serde_wasm_bindgen::from_value(signs).unwrap_or_default()
+    }
+}
+
+/// Deserialize `_features.consolidation` from Memory, defaulting any missing
+/// or malformed key.
+fn consolidation_from_memory() -> ConsolidationFeatures {
+    let consolidation = js_get(&js_get(&crate::memory_helper::root(), "_features"), "consolidation");
+
+    if consolidation.is_undefined() || consolidation.is_null() {
+        ConsolidationFeatures::default()
+    } else {
+        serde_wasm_bindgen::from_value(consolidation).unwrap_or_default()
+    }
+}
+
+/// Deserialize `_features.resources` from Memory, defaulting a missing or
+/// malformed key.
+fn resources_from_memory() -> ResourceFeatures {
+    let resources = js_get(&js_get(&crate::memory_helper::root(), "_features"), "resources");
+
+    if resources.is_undefined() || resources.is_null() {
+        ResourceFeatures::default()
+    } else {
+        serde_wasm_bindgen::from_value(resources).unwrap_or_default()
+    }
+}
+
+/// Deserialize `_features.logging` from Memory, defaulting a missing or
+/// malformed spec.
+fn logging_from_memory() -> LoggingFeatures {
+    let logging = js_get(&js_get(&crate::memory_helper::root(), "_features"), "logging");
+
+    if logging.is_undefined() || logging.is_null() {
+        LoggingFeatures::default()
+    } else {
+        serde_wasm_bindgen::from_value(logging).unwrap_or_default()
+    }
+}
+
 // ─── Prepare / Load ────────────────────────────────────────────────────────────
 
 /// Ensure `Memory._features` exists with sensible defaults.
@@ -792,9 +1363,32 @@ pub fn load() -> Features {
     let root = crate::memory_helper::root();
     let flags = features_from_memory();
 
+    // The per-room overrides aren't part of `Features`; carry them across
+    // the write-back (an empty object on first run so they're discoverable).
+    let rooms = js_get(&js_get(&root, "_features"), "rooms");
+    let rooms = if rooms.is_undefined() || rooms.is_null() {
+        js_sys::Object::new().into()
+    } else {
+        rooms
+    };
+
+    // The signs are carried the same way, resolved so their defaults show.
+    let signs = serde_wasm_bindgen::to_value(&signs_from_memory()).unwrap_or(JsValue::UNDEFINED);
+    let consolidation = serde_wasm_bindgen::to_value(&consolidation_from_memory()).unwrap_or(JsValue::UNDEFINED);
+    let logging = serde_wasm_bindgen::to_value(&logging_from_memory()).unwrap_or(JsValue::UNDEFINED);
+    // The targets are a map, which only round-trips Memory as a plain object.
+    let resources = resources_from_memory()
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .unwrap_or(JsValue::UNDEFINED);
+
     // Write the fully-resolved struct back so new/missing keys are visible in
     // Memory for the user to inspect and modify between ticks.
     if let Ok(js_val) = serde_wasm_bindgen::to_value(&flags) {
+        let _ = js_sys::Reflect::set(&js_val, &JsValue::from_str("rooms"), &rooms);
+        let _ = js_sys::Reflect::set(&js_val, &JsValue::from_str("signs"), &signs);
+        let _ = js_sys::Reflect::set(&js_val, &JsValue::from_str("consolidation"), &consolidation);
+        let _ = js_sys::Reflect::set(&js_val, &JsValue::from_str("logging"), &logging);
+        let _ = js_sys::Reflect::set(&js_val, &JsValue::from_str("resources"), &resources);
         let _ = js_sys::Reflect::set(&root, &JsValue::from_str("_features"), &js_val);
     }
 
@@ -811,3 +1405,203 @@ pub fn load() -> Features {
 
     flags
 }
+
+/// Load the per-room overrides from `Memory._features.rooms`. Called right
+/// after [`load`] each tick. A room name that doesn't parse or an entry that
+/// doesn't deserialize is skipped with a warning rather than discarding the
+/// rest.
+#[must_use]
+pub fn load_overrides() -> FeatureOverrides {
+    let rooms = js_get(&js_get(&crate::memory_helper::root(), "_features"), "rooms");
+    let mut overrides = HashMap::new();
+
+    for key in crate::memory_helper::keys(&rooms) {
+        let room = match RoomName::new(&key) {
+            Ok(room) => room,
+            Err(_) => {
+                warn!("Ignoring feature overrides for invalid room name: {}", key);
+                continue;
+            }
+        };
+
+        match serde_wasm_bindgen::from_value::<RoomFeatureOverrides>(js_get(&rooms, &key)) {
+            Ok(room_overrides) => {
+                overrides.insert(room, room_overrides);
+            }
+            Err(err) => warn!("Ignoring malformed feature overrides for room {}: {}", key, err),
+        }
+    }
+
+    FeatureOverrides::new(overrides)
+}
+
+/// Load the controller sign text from `Memory._features.signs`. Called
+/// right after [`load`] each tick, which has already filled in defaults.
+#[must_use]
+pub fn load_signs() -> SignFeatures {
+    signs_from_memory()
+}
+
+/// Load the consolidation hub settings from `Memory._features.consolidation`.
+/// Called right after [`load`] each tick, like [`load_signs`].
+#[must_use]
+pub fn load_consolidation() -> ConsolidationFeatures {
+    consolidation_from_memory()
+}
+
+/// Load the empire's resource targets from `Memory._features.resources`.
+/// Called right after [`load`] each tick, like [`load_signs`].
+#[must_use]
+pub fn load_resources() -> ResourceFeatures {
+    resources_from_memory()
+}
+
+/// Load the per-module log levels from `Memory._features.logging`. Called
+/// right after [`load`] each tick, like [`load_signs`].
+#[must_use]
+pub fn load_logging() -> LoggingFeatures {
+    logging_from_memory()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn room(name: &str) -> RoomName {
+        RoomName::new(name).unwrap()
+    }
+
+    /// An override replaces the global value for its room only.
+    #[test]
+    fn room_overrides_apply_to_their_room_only() {
+        let mut rooms = HashMap::new();
+        rooms.insert(
+            room("W1N1"),
+            RoomFeatureOverrides {
+                spawning: Some(false),
+                remote_mine: Some(false),
+                ..Default::default()
+            },
+        );
+        let overrides = FeatureOverrides::new(rooms);
+        let features = Features::default();
+
+        let overridden = features.for_room(&overrides, room("W1N1"));
+        assert!(!overridden.spawning);
+        assert!(!overridden.remote_mine.harvest && !overridden.remote_mine.reserve);
+        assert!(overridden.military.defense);
+
+        let other = features.for_room(&overrides, room("W2N2"));
+        assert!(other.spawning && other.remote_mine.harvest);
+    }
+
+    /// A room can switch on what the global flag has off.
+    #[test]
+    fn room_override_can_enable_a_globally_disabled_flag() {
+        let mut rooms = HashMap::new();
+        rooms.insert(
+            room("W1N1"),
+            RoomFeatureOverrides {
+                defense: Some(true),
+                ..Default::default()
+            },
+        );
+        let overrides = FeatureOverrides::new(rooms);
+        let mut features = Features::default();
+        features.military.defense = false;
+
+        assert!(features.for_room(&overrides, room("W1N1")).military.defense);
+        assert!(overrides.any(|o| o.defense == Some(true)));
+    }
+
+    /// Creep say codes cost an intent each, so they stay off unless a room
+    /// asks for them.
+    #[test]
+    fn say_is_off_unless_a_room_enables_it() {
+        let mut rooms = HashMap::new();
+        rooms.insert(
+            room("W1N1"),
+            RoomFeatureOverrides {
+                say: Some(true),
+                ..Default::default()
+            },
+        );
+        let overrides = FeatureOverrides::new(rooms);
+        let features = Features::default();
+
+        assert!(!features.visualize.say);
+        assert!(features.for_room(&overrides, room("W1N1")).visualize.say);
+        assert!(!features.for_room(&overrides, room("W2N2")).visualize.say);
+        assert_eq!(overrides.room(room("W1N1")).describe(), "say=on");
+    }
+
+    #[test]
+    fn defaults_report_no_flags() {
+        assert!(non_default_flags(&Features::default(), &FeatureOverrides::default()).is_empty());
+    }
+
+    /// Changed flags report by dotted path, then room overrides sorted by room.
+    #[test]
+    fn non_default_flags_lists_paths_then_rooms() {
+        let mut features = Features::default();
+        features.visualize.on = false;
+        features.military.offense = false;
+
+        let mut rooms = HashMap::new();
+        rooms.insert(
+            room("W2N1"),
+            RoomFeatureOverrides {
+                defense: Some(false),
+                ..Default::default()
+            },
+        );
+        rooms.insert(
+            room("E1S1"),
+            RoomFeatureOverrides {
+                visualize: Some(true),
+                spawning: Some(false),
+                ..Default::default()
+            },
+        );
+        rooms.insert(room("W9N9"), RoomFeatureOverrides::default());
+
+        assert_eq!(
+            non_default_flags(&features, &FeatureOverrides::new(rooms)),
+            vec![
+                "military.offense=false".to_string(),
+                "visualize.on=false".to_string(),
+                "E1S1 visualize=on spawning=off".to_string(),
+                "W2N1 defense=off".to_string(),
+            ]
+        );
+    }
+
+    /// A partial `signs` object keeps the defaults for the messages it omits.
+    #[test]
+    fn signs_default_per_purpose() {
+        let signs: SignFeatures = serde_json::from_str(r#"{ "attack": "Surrender" }"#).unwrap();
+
+        assert_eq!(signs.message(SignPurpose::Attack), "Surrender");
+        assert_eq!(signs.message(SignPurpose::Owned), crate::constants::ROOM_SIGN);
+        assert_eq!(signs.message(SignPurpose::Reserved), crate::constants::ROOM_SIGN);
+    }
+
+    /// Energy, excluded resources and the hub itself never consolidate.
+    #[test]
+    fn consolidation_skips_energy_exclusions_and_the_hub() {
+        let consolidation = ConsolidationFeatures {
+            hub: Some(room("W1N1")),
+            ..Default::default()
+        };
+
+        assert_eq!(consolidation.hub_for(room("W2N1"), ResourceType::Oxygen), Some(room("W1N1")));
+        assert_eq!(consolidation.hub_for(room("W1N1"), ResourceType::Oxygen), None);
+        assert_eq!(consolidation.hub_for(room("W2N1"), ResourceType::Energy), None);
+        assert_eq!(
+            consolidation.hub_for(room("W2N1"), ResourceType::CatalyzedGhodiumAlkalide),
+            None,
+            "defender boosts stay local by default"
+        );
+        assert_eq!(ConsolidationFeatures::default().hub_for(room("W2N1"), ResourceType::Oxygen), None);
+    }
+}
@@ -1,20 +1,190 @@
-//! Linear (Chebyshev) nearest-selection iterator helpers — pure math,
+//! Nearest- and best-target selection iterator helpers — pure math,
 //! no pathfinding, no budget. The budgeted by-real-path selection that
 //! used to live here is [`crate::pathing::pathfinderservice`]'s
-//! `nearest_by_path` (statics-review M4).
+//! `nearest_by_path` (statics-review M4); a [`Distance::Path`] source
+//! backed by its `cached_path_length` lets [`find_best_by`] weigh path
+//! length instead of range without this module doing any searching.
+//!
+//! [`find_best_by`]: FindNearestItertools::find_best_by
 
 use screeps::local::Position;
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// How [`FindNearestItertools::find_best_by`] measures the distance to a
+/// candidate.
+pub enum Distance<'a> {
+    /// Chebyshev range: free, but blind to walls.
+    Linear,
+    /// Path length from `(from, to)`, falling back to the range for the
+    /// candidates the source has no length for.
+    Path(&'a mut dyn FnMut(Position, Position) -> Option<u32>),
+}
+
+impl Distance<'_> {
+    fn measure(&mut self, from: Position, to: Position) -> u32 {
+        match self {
+            Distance::Linear => from.get_range_to(to),
+            Distance::Path(path_length) => path_length(from, to).unwrap_or_else(|| from.get_range_to(to)),
+        }
+    }
+}
+
+/// Which of several equally scored candidates wins.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TieBreak {
+    /// The first in iteration order.
+    First,
+    /// A pseudo-random pick keyed on the seed and the candidate's position.
+    /// A seed always picks the same candidate, so a creep seeded by its name
+    /// keeps its choice from tick to tick while other creeps spread over
+    /// the tie.
+    Seeded(u64),
+}
+
+impl TieBreak {
+    pub fn seeded_by<T: Hash + ?Sized>(value: &T) -> TieBreak {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+
+        TieBreak::Seeded(hasher.finish())
+    }
+
+    fn key(&self, pos: Position) -> u64 {
+        match self {
+            TieBreak::First => 0,
+            TieBreak::Seeded(seed) => {
+                let mut hasher = DefaultHasher::new();
+                (seed, pos).hash(&mut hasher);
+
+                hasher.finish()
+            }
+        }
+    }
+}
 
 pub trait FindNearestItertools: Iterator {
+    /// The candidate with the lowest `score(distance, candidate)`, the
+    /// distance measured from `other_pos` by `distance`. Ties go by
+    /// `tie_break`; candidates whose score doesn't compare (NaN) never win.
+    fn find_best_by<P, S, K, V>(
+        self,
+        other_pos: Position,
+        mut distance: Distance,
+        tie_break: TieBreak,
+        pos_generator: P,
+        score: S,
+    ) -> Option<V>
+    where
+        Self: Iterator<Item = V> + Sized,
+        P: Fn(&V) -> Position,
+        S: Fn(u32, &V) -> K,
+        K: PartialOrd,
+    {
+        let mut best: Option<(K, u64, V)> = None;
+
+        for candidate in self {
+            let pos = pos_generator(&candidate);
+            let key = score(distance.measure(other_pos, pos), &candidate);
+            let tie = tie_break.key(pos);
+
+            let better = match &best {
+                None => key.partial_cmp(&key).is_some(),
+                Some((best_key, best_tie, _)) => match key.partial_cmp(best_key) {
+                    Some(Ordering::Less) => true,
+                    Some(Ordering::Equal) => tie < *best_tie,
+                    _ => false,
+                },
+            };
+
+            if better {
+                best = Some((key, tie, candidate));
+            }
+        }
+
+        best.map(|(_, _, candidate)| candidate)
+    }
+
     fn find_nearest_linear_by<F, V>(self, other_pos: Position, pos_generator: F) -> Option<V>
     where
         Self: Iterator<Item = V> + Sized,
         F: Fn(&V) -> Position,
     {
-        self.map(|pos_object| (other_pos.get_range_to(pos_generator(&pos_object)), pos_object))
-            .min_by_key(|(length, _)| *length)
-            .map(|(_, pos_object)| pos_object)
+        self.find_best_by(other_pos, Distance::Linear, TieBreak::First, pos_generator, |range, _| range)
     }
 }
 
 impl<T: ?Sized> FindNearestItertools for T where T: Iterator {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::position;
+    use screeps::RoomName;
+    use std::collections::HashSet;
+
+    fn room() -> RoomName {
+        "W1N1".parse().expect("valid room name")
+    }
+
+    #[test]
+    fn nearest_keeps_the_first_of_equally_near_targets() {
+        let origin = position(room(), 25, 25);
+        let targets = [position(room(), 30, 25), position(room(), 20, 25), position(room(), 22, 25)];
+
+        assert_eq!(targets.iter().find_nearest_linear_by(origin, |pos| **pos), Some(&targets[2]));
+        assert_eq!(targets[..2].iter().find_nearest_linear_by(origin, |pos| **pos), Some(&targets[0]));
+    }
+
+    #[test]
+    fn score_can_outweigh_range() {
+        let origin = position(room(), 25, 25);
+        // (position, fill): the nearest target is nearly empty.
+        let targets = [(position(room(), 26, 25), 0.1), (position(room(), 30, 25), 1.0)];
+
+        let best = targets.iter().find_best_by(
+            origin,
+            Distance::Linear,
+            TieBreak::First,
+            |(pos, _)| *pos,
+            |range, (_, fill)| range as f32 + 10.0 * (1.0 - fill),
+        );
+
+        assert_eq!(best, Some(&targets[1]));
+    }
+
+    #[test]
+    fn path_length_replaces_range_where_known() {
+        let origin = position(room(), 25, 25);
+        // Behind a wall: near by range, far by path.
+        let walled = position(room(), 25, 23);
+        let open = position(room(), 25, 29);
+
+        let mut path_length = |_: Position, to: Position| (to == walled).then_some(20);
+        let best = [walled, open].iter().find_best_by(
+            origin,
+            Distance::Path(&mut path_length),
+            TieBreak::First,
+            |pos| **pos,
+            |range, _| range,
+        );
+
+        assert_eq!(best, Some(&open));
+    }
+
+    #[test]
+    fn seeded_ties_are_stable_per_seed_and_spread_across_seeds() {
+        let origin = position(room(), 25, 25);
+        let ring: Vec<Position> = (20..=30).map(|x| position(room(), x, 20)).collect();
+
+        let pick = |seed: u64| {
+            ring.iter()
+                .find_best_by(origin, Distance::Linear, TieBreak::Seeded(seed), |pos| **pos, |_, _| 0)
+                .copied()
+        };
+
+        assert_eq!(pick(7), pick(7));
+        assert!((0..16).map(pick).collect::<HashSet<_>>().len() > 1);
+    }
+}
@@ -1,6 +1,10 @@
 use crate::cleanup::*;
+use crate::console::ConsoleCommandSystem;
 use crate::creep::*;
+use crate::creep_memory::CreepMemoryCleanupSystem;
 use crate::entitymappingsystem::*;
+use crate::integrity::*;
+use crate::jobs::buildclaimvisualizesystem::*;
 use crate::jobs::data::*;
 use crate::jobs::jobsystem::*;
 use crate::memorysystem::*;
@@ -8,9 +12,11 @@ use crate::metrics::MetricsSystem;
 use crate::military::boostqueue::*;
 use crate::military::economy::*;
 use crate::military::objective_queue::*;
+use crate::military::posture::DefensePostureSystem;
 use crate::military::squad::*;
 use crate::military::squad_manager::*;
 use crate::military::threatmap::*;
+use crate::military::threatmapvisualizesystem::*;
 use crate::missions::data::*;
 use crate::missions::missionsystem::*;
 use crate::operations::data::*;
@@ -18,7 +24,9 @@ use crate::operations::managersystem::*;
 use crate::operations::operationsystem::*;
 use crate::pathing::costmatrixsystem::*;
 use crate::pathing::movementsystem::*;
+use crate::powercreepsystem::*;
 use crate::repairqueue::RepairQueueClearSystem;
+use crate::resource_overview::ResourceOverviewSystem;
 use crate::room::createroomsystem::*;
 use crate::room::data::*;
 use crate::room::room_status_cache::{RoomStatusCache, RoomStatusCacheClearSystem};
@@ -33,18 +41,19 @@ use crate::stats_history::StatsHistorySystem;
 use crate::statssystem::*;
 use crate::transfer::ordersystem::*;
 use crate::transfer::transfersystem::*;
+use crate::upgrade_focus::UpgradeFocusSystem;
 use crate::visualization::{
     AggregateSummarySystem, ClearVisualizationSystem, CpuHistory, CpuTrackingSystem, RenderSystem, SummarizeJobSystem,
     SummarizeMissionSystem, SummarizeOperationSystem, SummarizeRoomVisibilitySystem, VisualizationData,
 };
 use crate::visualize::*;
-use bincode::{DefaultOptions, Deserializer, Serializer};
+use crate::worldformat::*;
 use log::*;
 use screeps::*;
 use screeps_rover::*;
 use specs::{
     prelude::*,
-    saveload::{DeserializeComponents, SerializeComponents},
+    saveload::{ConvertSaveload, DeserializeComponents, Marker, MarkerAllocator, SerializeComponents},
 };
 use std::cell::RefCell;
 use std::collections::HashSet;
@@ -66,10 +75,20 @@ macro_rules! for_each_system {
         // whose absence for a few ticks is harmless by design
         // (visual/observational output, resumable planning). Adding a
         // system without thinking about its class is impossible now —
-        // that's the seam's point.
+        // that's the seam's point. `Rotate(group)` is the bucket-aware
+        // middle class: full service at Normal, one group per tick under
+        // Conserve, shed under Critical.
         // === Pre-pass (inputs for everything incl. defense) ===
         $op!(WaitForSpawnSystem, "wait_for_spawn", StageClass::Always);
         $op!(CleanupCreepsSystem, "cleanup_creeps", StageClass::Always);
+        // Sweeps `Memory.creeps` on its interval; a skipped sweep just waits.
+        $op!(
+            CreepMemoryCleanupSystem,
+            "creep_memory_cleanup",
+            StageClass::SkipUnderCritical
+        );
+        // Structural link check on a cadence; queues orphans for the cleanup below.
+        $op!(EntityIntegritySystem, "entity_integrity", StageClass::Always);
         // Flush creep deaths immediately so missions see accurate counts.
         // The system is a no-op when the queue is empty, so the second
         // invocation after RunJobSystem costs nothing when there are no
@@ -78,8 +97,18 @@ macro_rules! for_each_system {
         $op!(CreateRoomDataSystem, "create_room_data", StageClass::Always);
         $op!(UpdateRoomDataSystem, "update_room_data", StageClass::Always);
         $op!(EntityMappingSystem, "entity_mapping", StageClass::Always);
+        // No-op unless a memory recovery left live creeps without entities.
+        $op!(AdoptOrphanCreepsSystem, "adopt_orphan_creeps", StageClass::Always);
+        // Drains `Memory._commands` (mission pause/resume) before missions run.
+        $op!(ConsoleCommandSystem, "console_commands", StageClass::Always);
         $op!(ThreatAssessmentSystem, "threat_assessment", StageClass::Always);
+        // Escalates or relaxes each owned room's defense posture from the fresh threat data.
+        $op!(DefensePostureSystem, "defense_posture", StageClass::Always);
         $op!(EconomyAssessmentSystem, "economy_assessment", StageClass::Always);
+        // Recounts the empire's stores on its interval, or for the `resources` command.
+        $op!(ResourceOverviewSystem, "resource_overview", StageClass::Always);
+        // Projects controller progress and picks the upgrade focus room on its interval.
+        $op!(UpgradeFocusSystem, "upgrade_focus", StageClass::Always);
         // === Main-pass: Cleanup ===
         $op!(RepairQueueClearSystem, "repair_queue_clear", StageClass::Always);
         $op!(ClearVisualizationSystem, "clear_visualization", StageClass::Always);
@@ -105,42 +134,82 @@ macro_rules! for_each_system {
         $op!(EntityCleanupSystem, "entity_cleanup", StageClass::Always);
         $op!(MovementUpdateSystem, "movement", StageClass::Always);
         // === Main-pass: Observer (intel — shed-first class, ADR 0004) ===
-        $op!(ObserverSystem, "observer", StageClass::SkipUnderCritical);
+        $op!(ObserverSystem, "observer", StageClass::Rotate(RotationGroup::Intel));
         // === Main-pass: Summarization (feeds visualization only) ===
         $op!(
             SummarizeOperationSystem,
             "summarize_operations",
-            StageClass::SkipUnderCritical
+            StageClass::Rotate(RotationGroup::Visualization)
+        );
+        $op!(
+            SummarizeMissionSystem,
+            "summarize_missions",
+            StageClass::Rotate(RotationGroup::Visualization)
+        );
+        $op!(
+            SummarizeJobSystem,
+            "summarize_jobs",
+            StageClass::Rotate(RotationGroup::Visualization)
         );
-        $op!(SummarizeMissionSystem, "summarize_missions", StageClass::SkipUnderCritical);
-        $op!(SummarizeJobSystem, "summarize_jobs", StageClass::SkipUnderCritical);
         $op!(
             SummarizeRoomVisibilitySystem,
             "summarize_room_visibility",
-            StageClass::SkipUnderCritical
+            StageClass::Rotate(RotationGroup::Visualization)
+        );
+        $op!(
+            VisibilityVisualizationSystem,
+            "visibility_viz",
+            StageClass::Rotate(RotationGroup::Visualization)
         );
-        $op!(VisibilityVisualizationSystem, "visibility_viz", StageClass::SkipUnderCritical);
         $op!(
             TransferStatsSnapshotSystem,
             "transfer_stats_snapshot",
-            StageClass::SkipUnderCritical
+            StageClass::Rotate(RotationGroup::Visualization)
+        );
+        $op!(
+            AggregateSummarySystem,
+            "aggregate_summary",
+            StageClass::Rotate(RotationGroup::Visualization)
         );
-        $op!(AggregateSummarySystem, "aggregate_summary", StageClass::SkipUnderCritical);
         // === Main-pass: Queues (spawn/haul — never shed) ===
         $op!(SpawnQueueSystem, "spawn_queue", StageClass::Always);
+        // After the spawn queue, which asks for operate_spawn; before the
+        // transfer queue update, as operators pick ops from this tick's nodes.
+        $op!(PowerCreepSystem, "power_creeps", StageClass::Always);
         $op!(TransferQueueUpdateSystem, "transfer_queue", StageClass::Always);
         $op!(OrderQueueSystem, "order_queue", StageClass::Always);
         // === Main-pass: Room Planning (resumable by design — seg-60) ===
-        $op!(RoomPlanSystem, "room_plan", StageClass::SkipUnderCritical);
-        $op!(RoomPlanVisualizeSystem, "room_plan_visualize", StageClass::SkipUnderCritical);
-        // === Main-pass: Stats and Visualization (telemetry NEVER sheds
-        // — the governor is blind without it; render is visual-only) ===
-        $op!(StatsSystem, "stats", StageClass::Always);
-        $op!(StatsHistorySystem, "stats_history", StageClass::Always);
+        $op!(RoomPlanSystem, "room_plan", StageClass::Rotate(RotationGroup::Planning));
+        // Draws into the Visualizer, so it rotates with the pipeline that applies it.
+        $op!(
+            RoomPlanVisualizeSystem,
+            "room_plan_visualize",
+            StageClass::Rotate(RotationGroup::Visualization)
+        );
+        $op!(
+            BuildClaimVisualizeSystem,
+            "build_claim_visualize",
+            StageClass::Rotate(RotationGroup::Visualization)
+        );
+        $op!(
+            ThreatMapVisualizeSystem,
+            "threat_map_visualize",
+            StageClass::Rotate(RotationGroup::Visualization)
+        );
+        // === Main-pass: Stats and Visualization (governor telemetry —
+        // cpu_tracking and seg-57 metrics — NEVER sheds, the governor is
+        // blind without it; the seg-99 stats export and its history are
+        // dashboard-only and rotate; render is visual-only) ===
+        $op!(StatsSystem, "stats", StageClass::Rotate(RotationGroup::Stats));
+        $op!(StatsHistorySystem, "stats_history", StageClass::Rotate(RotationGroup::Stats));
         $op!(CpuTrackingSystem, "cpu_tracking", StageClass::Always);
         $op!(MetricsSystem, "metrics", StageClass::Always);
-        $op!(RenderSystem, "render", StageClass::SkipUnderCritical);
-        $op!(ApplyVisualsSystem, "apply_visuals", StageClass::SkipUnderCritical);
+        $op!(RenderSystem, "render", StageClass::Rotate(RotationGroup::Visualization));
+        $op!(
+            ApplyVisualsSystem,
+            "apply_visuals",
+            StageClass::Rotate(RotationGroup::Visualization)
+        );
         // === Main-pass: Persistence (never shed) ===
         $op!(VisibilityQueueSyncSystem, "visibility_sync", StageClass::Always);
         $op!(CombatObjectiveSyncSystem, "combat_objective_sync", StageClass::Always);
@@ -152,23 +221,47 @@ macro_rules! for_each_system {
 /// Shed class for the scheduler seam (P1.C5). `Always` = the ADR 0004
 /// never-shed set (defense, spawn, haul, movement, persistence) plus
 /// their inputs and the telemetry the governor itself depends on.
-/// `SkipUnderCritical` = work whose absence is harmless by design:
-/// visual/observational output and seg-60-resumable planning.
+/// `SkipUnderCritical` = work whose absence is harmless by design.
+/// `Rotate` = low-value work that takes turns under Conserve (see
+/// [`crate::cpugovernor::rotation_due`]) so a draining bucket isn't
+/// spent on it every tick; shed outright under Critical.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum StageClass {
     Always,
+    Rotate(RotationGroup),
     SkipUnderCritical,
 }
 
+/// Systems that must run on the same tick to be useful together (the
+/// visualization pipeline only renders what its summaries gathered)
+/// share a rotation group; the discriminant is the group's slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RotationGroup {
+    Intel = 0,
+    Planning = 1,
+    Visualization = 2,
+    Stats = 3,
+}
+
 impl StageClass {
-    fn runs(self, tier: crate::cpugovernor::Tier) -> bool {
+    fn runs(self, tier: crate::cpugovernor::Tier, tick: u32) -> bool {
         match self {
             StageClass::Always => true,
+            StageClass::Rotate(group) => crate::cpugovernor::rotation_due(tier, tick, group as u32),
             StageClass::SkipUnderCritical => tier != crate::cpugovernor::Tier::Critical,
         }
     }
 }
 
+/// The tier the scheduler last logged a shedding transition for. Under
+/// Conserve the rotation sheds a different set every tick; logging only
+/// on tier transitions keeps the console readable while still marking
+/// when (and at what tier) shedding started and stopped.
+#[derive(Default)]
+struct SchedulerShedLog {
+    last_tier: Option<crate::cpugovernor::Tier>,
+}
+
 /// Call `RunNow::setup` for every system in the tick list (shed class
 /// irrelevant at setup — every system's resources must register).
 fn setup_systems(world: &mut World) {
@@ -184,20 +277,46 @@ fn setup_systems(world: &mut World) {
 /// system followed by `world.maintain()`, skipping systems whose shed
 /// class doesn't run at the tick's governor tier. The tier is read
 /// ONCE so the whole tick sees a consistent shedding decision.
-/// When `timing` is true, per-system CPU cost is measured and logged.
-fn run_systems(world: &mut World, timing: bool) {
+/// When `timing` is true, per-system CPU cost is measured and logged; when
+/// CPU accounting is enabled it is also folded into the `CpuAccounting`
+/// Resource under the system's label.
+///
+/// Mid-tick checkpoint: before each sheddable (non-`Always`) system the
+/// CPU used so far is checked against `game::cpu::limit()`, less a safety
+/// margin ([`crate::cpugovernor::checkpoint_exceeded`]); once tripped, every
+/// remaining sheddable system is skipped so the never-shed tail and
+/// persistence still fit. Returns the labels skipped that way.
+fn run_systems(world: &mut World, timing: bool) -> Vec<&'static str> {
     let tier = world.read_resource::<crate::cpugovernor::GovernorSnapshot>().tier;
-    let mut shed_count = 0u32;
+    let tick = game::time();
+    let accounting = world.read_resource::<crate::cpu_accounting::CpuAccounting>().enabled();
+    let cpu_limit = game::cpu::limit() as f64;
+    let mut shed: Vec<&'static str> = Vec::new();
+    let mut over_budget = false;
+    let mut skipped: Vec<&'static str> = Vec::new();
     macro_rules! do_run {
         ($sys:expr, $label:expr, $class:expr) => {
-            if !$class.runs(tier) {
-                shed_count += 1;
-            } else if timing {
+            let sheddable = $class != StageClass::Always;
+            if sheddable && !over_budget && $class.runs(tier, tick) {
+                over_budget = crate::cpugovernor::checkpoint_exceeded(game::cpu::get_used(), cpu_limit);
+            }
+            if !$class.runs(tier, tick) {
+                shed.push($label);
+            } else if sheddable && over_budget {
+                skipped.push($label);
+            } else if timing || accounting {
                 let before = game::cpu::get_used();
                 $sys.run_now(world);
                 world.maintain();
                 let after = game::cpu::get_used();
-                info!("[timing] {}: {:.2} cpu", $label, after - before);
+                if timing {
+                    info!("[timing] {}: {:.2} cpu", $label, after - before);
+                }
+                if accounting {
+                    world
+                        .write_resource::<crate::cpu_accounting::CpuAccounting>()
+                        .record_system($label, after - before);
+                }
             } else {
                 $sys.run_now(world);
                 world.maintain();
@@ -205,9 +324,24 @@ fn run_systems(world: &mut World, timing: bool) {
         };
     }
     for_each_system!(do_run);
-    if shed_count > 0 {
-        debug!("scheduler: shed {} system(s) under {:?}", shed_count, tier);
+
+    if !shed.is_empty() {
+        debug!("scheduler: shed {} system(s) under {:?}: {}", shed.len(), tier, shed.join(", "));
     }
+
+    let mut shed_log = world.entry::<SchedulerShedLog>().or_insert_with(SchedulerShedLog::default);
+    if shed_log.last_tier != Some(tier) {
+        if shed_log.last_tier.is_some() || tier != crate::cpugovernor::Tier::Normal {
+            if shed.is_empty() {
+                info!("scheduler: {} tier — full service", tier.as_str());
+            } else {
+                info!("scheduler: {} tier — shedding (this tick): {}", tier.as_str(), shed.join(", "));
+            }
+        }
+        shed_log.last_tier = Some(tier);
+    }
+
+    skipped
 }
 
 /// Pre-serialization integrity check and repair.
@@ -239,7 +373,18 @@ fn repair_entity_integrity(world: &mut World) {
         let is_valid = |e: Entity| -> bool { entities.is_alive(e) && markers.get(e).is_some() };
 
         // ── RoomData.missions: remove dead entries ──────────────────────
-        for (entity, rd) in (&entities, &mut room_data_storage).join() {
+        // Only rooms that actually hold a dead entry are touched mutably
+        // (a mutable join would mark every room dirty for the save).
+        let damaged_rooms: Vec<Entity> = (&entities, &room_data_storage)
+            .join()
+            .filter(|(_, rd)| rd.get_missions().iter().any(|e| !is_valid(*e)))
+            .map(|(entity, _)| entity)
+            .collect();
+
+        for entity in damaged_rooms {
+            let Some(rd) = room_data_storage.get_mut(entity) else {
+                continue;
+            };
             let before = rd.get_missions().len();
             rd.retain_missions(|e| {
                 let ok = is_valid(e);
@@ -455,15 +600,124 @@ fn repair_entity_integrity(world: &mut World) {
     }
 }
 
+/// Hash of the chunk last written to each component segment. Components
+/// mutate through `EntityRefCell` behind shared storage borrows, so specs
+/// change flags can't say which ones are dirty; dirtiness is detected on
+/// the encoded payload instead, per segment-sized chunk.
+#[derive(Default)]
+struct SerializedChunkHashes {
+    hashes: std::collections::HashMap<u32, u64>,
+}
+
+fn chunk_hash(chunk: &[u8]) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    chunk.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Change-event readers on the storages the incremental save tracks.
+struct SaveDirtyReaders {
+    creep_spawnings: ReaderId<ComponentEvent>,
+    creep_owners: ReaderId<ComponentEvent>,
+    room_data: ReaderId<ComponentEvent>,
+    room_plan_data: ReaderId<ComponentEvent>,
+    room_threat_data: ReaderId<ComponentEvent>,
+}
+
+/// Per-entity record cache for the incremental world save
+/// (`features.serialize`). Tracked components (`FlaggedStorage`: creep
+/// spawn/owner, room, plan and threat data) mark their entity dirty on a
+/// mutable access, insert or remove. Entities carrying an untracked
+/// component (movement, jobs, missions, operations, squads, queues —
+/// mutated every tick or through `EntityRefCell`) are re-encoded every
+/// normal save; their records are cached only for the dirty-only save.
+struct SerializeCache {
+    readers: SaveDirtyReaders,
+    records: std::collections::HashMap<Entity, Vec<u8>>,
+    /// Records of entities with an untracked component, reused only by
+    /// the dirty-only save.
+    untracked_records: std::collections::HashMap<Entity, Vec<u8>>,
+    last_full: u32,
+}
+
+impl SerializeCache {
+    fn new(world: &mut World) -> SerializeCache {
+        let readers = SaveDirtyReaders {
+            creep_spawnings: world.write_storage::<CreepSpawning>().register_reader(),
+            creep_owners: world.write_storage::<CreepOwner>().register_reader(),
+            room_data: world.write_storage::<RoomData>().register_reader(),
+            room_plan_data: world.write_storage::<RoomPlanData>().register_reader(),
+            room_threat_data: world.write_storage::<RoomThreatData>().register_reader(),
+        };
+
+        SerializeCache {
+            readers,
+            records: std::collections::HashMap::new(),
+            untracked_records: std::collections::HashMap::new(),
+            last_full: 0,
+        }
+    }
+
+    /// Start a save at `tick`: whether it re-encodes every entity, dropping
+    /// the kept records if so.
+    fn begin_save(&mut self, tick: u32, deleted: bool, save: crate::features::SerializeFeatures) -> bool {
+        let full = !save.incremental || deleted || self.records.is_empty() || tick.saturating_sub(self.last_full) >= save.full_interval;
+
+        if full {
+            self.records.clear();
+            self.untracked_records.clear();
+            self.last_full = tick;
+        }
+
+        full
+    }
+}
+
+fn drain_dirty<T>(storage: &ReadStorage<T>, reader: &mut ReaderId<ComponentEvent>, dirty: &mut BitSet)
+where
+    T: Component,
+    T::Storage: Tracked,
+{
+    for event in storage.channel().read(reader) {
+        match event {
+            ComponentEvent::Inserted(id) | ComponentEvent::Modified(id) | ComponentEvent::Removed(id) => {
+                dirty.add(*id);
+            }
+        }
+    }
+}
+
+/// Serialize the world into `segments`.
+///
+/// With `save.incremental`, clean entities reuse last save's record (see
+/// [`SerializeCache`]); every `save.full_interval` ticks, and whenever a
+/// cached entity was deleted, every entity is re-encoded instead.
+///
+/// With `only_dirty` (a tick whose mid-tick CPU checkpoint tripped) only
+/// entities with a changed tracked component, and entities new since the
+/// last save, are encoded. Everything else keeps its last record, so
+/// missions, jobs and operations are saved as of the last normal tick; a
+/// reload right after loses that one tick of their changes, and the
+/// integrity pass clears any child that tick created. The interval re-encode
+/// still comes due on such a tick, so a run of them can't leave those
+/// records stale, and segments whose chunk is unchanged are left unwritten.
+/// Without `save.incremental` there are no records to keep and the whole
+/// world is encoded.
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
-fn serialize_world(world: &World, segments: &[u32]) {
+fn serialize_world(world: &World, segments: &[u32], only_dirty: bool, save: crate::features::SerializeFeatures) {
     struct Serialize<'a> {
         segments: &'a [u32],
+        only_dirty: bool,
+        save: crate::features::SerializeFeatures,
     }
 
     #[derive(SystemData)]
     struct SerializeSystemData<'a> {
         memory_arbiter: WriteExpect<'a, MemoryArbiter>,
+        chunk_hashes: WriteExpect<'a, SerializedChunkHashes>,
+        cache: WriteExpect<'a, SerializeCache>,
+        save_stats: Write<'a, WorldSaveStats>,
         metrics: Write<'a, crate::metrics::MetricsState>,
         entities: Entities<'a>,
         marker_allocator: Write<'a, SerializeMarkerAllocator>,
@@ -486,33 +740,138 @@ fn serialize_world(world: &World, segments: &[u32]) {
         type SystemData = SerializeSystemData<'a>;
 
         fn run(&mut self, mut data: Self::SystemData) {
-            let mut serialized_data = Vec::<u8>::with_capacity(1024 * 50);
-            serialized_data.extend_from_slice(&WORLD_FORMAT_VERSION.to_le_bytes());
-
-            let mut serializer = Serializer::new(&mut serialized_data, DefaultOptions::new());
-
-            SerializeComponents::<std::convert::Infallible, SerializeMarker>::serialize(
-                &(
-                    &data.creep_spawnings,
-                    &data.creep_owners,
-                    &data.creep_movement_data,
-                    &data.room_data,
-                    &data.room_plan_data,
-                    &data.job_data,
-                    &data.operation_data,
-                    &data.mission_data,
-                    &data.squad_context,
-                    &data.visibility_queue_data,
-                    &data.combat_objective_data,
-                    &data.room_threat_data,
-                ),
-                &data.entities,
-                &data.markers,
-                &mut serializer,
-            )
-            .map(|_| ())
-            .map_err(|e| e.to_string())
-            .unwrap_or_else(|e| error!("Failed serialization: {}", e));
+            let storages = (
+                &data.creep_spawnings,
+                &data.creep_owners,
+                &data.creep_movement_data,
+                &data.room_data,
+                &data.room_plan_data,
+                &data.job_data,
+                &data.operation_data,
+                &data.mission_data,
+                &data.squad_context,
+                &data.visibility_queue_data,
+                &data.combat_objective_data,
+                &data.room_threat_data,
+            );
+
+            let markers = &data.markers;
+            let mut writer = WorldWriter::new(WORLD_FORMAT_VERSION);
+
+            let cache = &mut *data.cache;
+            let mut dirty = BitSet::new();
+            drain_dirty(&data.creep_spawnings, &mut cache.readers.creep_spawnings, &mut dirty);
+            drain_dirty(&data.creep_owners, &mut cache.readers.creep_owners, &mut dirty);
+            drain_dirty(&data.room_data, &mut cache.readers.room_data, &mut dirty);
+            drain_dirty(&data.room_plan_data, &mut cache.readers.room_plan_data, &mut dirty);
+            drain_dirty(&data.room_threat_data, &mut cache.readers.room_threat_data, &mut dirty);
+
+            let tick = game::time();
+            let gone = |entity: &Entity| !data.entities.is_alive(*entity) || !markers.contains(*entity);
+            // A kept record may name an entity deleted since it was encoded. Untracked
+            // records are only kept by the dirty-only save, so only it has to check them.
+            let deleted = cache.records.keys().any(gone) || (self.only_dirty && cache.untracked_records.keys().any(gone));
+            let full = cache.begin_save(tick, deleted, self.save);
+
+            if !full && !self.only_dirty {
+                cache.untracked_records.retain(|entity, _| !gone(entity));
+            }
+
+            let mut encoded = 0;
+            let mut reused = 0;
+            let encode_start = game::cpu::get_used();
+
+            for (entity, marker) in (&data.entities, markers).join() {
+                let untracked = data.creep_movement_data.contains(entity)
+                    || data.job_data.contains(entity)
+                    || data.operation_data.contains(entity)
+                    || data.mission_data.contains(entity)
+                    || data.squad_context.contains(entity)
+                    || data.visibility_queue_data.contains(entity)
+                    || data.combat_objective_data.contains(entity);
+
+                let kept = if untracked {
+                    cache.untracked_records.get(&entity).filter(|_| self.only_dirty)
+                } else {
+                    cache.records.get(&entity)
+                };
+
+                if !full && !dirty.contains(entity.id()) {
+                    if let Some(frame) = kept {
+                        writer.push_framed(frame);
+                        reused += 1;
+                        continue;
+                    }
+                }
+
+                let components =
+                    SerializeComponents::<std::convert::Infallible, SerializeMarker>::serialize_entity(&storages, entity, |e| {
+                        markers.get(e).cloned()
+                    })
+                    .unwrap_or_else(|e| match e {});
+
+                let (
+                    creep_spawning,
+                    creep_owner,
+                    creep_movement,
+                    room_data,
+                    room_plan,
+                    job,
+                    operation,
+                    mission,
+                    squad_context,
+                    visibility_queue,
+                    combat_objective,
+                    room_threat,
+                ) = components;
+
+                // Slot order is the wire order — append new components,
+                // never reorder (see `worldformat`).
+                let slots: Result<Vec<_>, String> = [
+                    encode_slot(&creep_spawning),
+                    encode_slot(&creep_owner),
+                    encode_slot(&creep_movement),
+                    encode_slot(&room_data),
+                    encode_slot(&room_plan),
+                    encode_slot(&job),
+                    encode_slot(&operation),
+                    encode_slot(&mission),
+                    encode_slot(&squad_context),
+                    encode_slot(&visibility_queue),
+                    encode_slot(&combat_objective),
+                    encode_slot(&room_threat),
+                ]
+                .into_iter()
+                .collect();
+
+                let record = slots.and_then(|components| {
+                    Ok(EntityRecord {
+                        marker: encode_value(marker)?,
+                        components,
+                    })
+                });
+
+                match record {
+                    Ok(record) => {
+                        let frame = frame_record(&record);
+                        writer.push_framed(&frame);
+                        encoded += 1;
+
+                        if self.save.incremental {
+                            if untracked {
+                                cache.untracked_records.insert(entity, frame);
+                            } else {
+                                cache.records.insert(entity, frame);
+                            }
+                        }
+                    }
+                    Err(e) => error!("Failed serialization: entity {:?} left out of the save: {}", entity, e),
+                }
+            }
+
+            data.save_stats.record(full, encoded, reused, game::cpu::get_used() - encode_start);
+
+            let serialized_data = writer.finish();
 
             let encoded_data = match encode_buffer_to_string(&serialized_data) {
                 Ok(s) => s,
@@ -545,9 +904,16 @@ fn serialize_world(world: &World, segments: &[u32]) {
             }
 
             let mut segments = self.segments.iter();
+            let mut clean_segments = 0;
 
             for chunk in encoded_data.as_bytes().chunks(1024 * 50) {
                 if let Some(segment) = segments.next() {
+                    let hash = chunk_hash(chunk);
+                    if data.chunk_hashes.hashes.insert(*segment, hash) == Some(hash) && self.only_dirty {
+                        clean_segments += 1;
+                        continue;
+                    }
+
                     //
                     // NOTE: This relies on not using multi-byte characters for encoding. (This is valid from base64 encoding.)
                     //
@@ -564,23 +930,45 @@ fn serialize_world(world: &World, segments: &[u32]) {
             }
 
             for segment in segments {
+                let hash = chunk_hash(&[]);
+                if data.chunk_hashes.hashes.insert(*segment, hash) == Some(hash) && self.only_dirty {
+                    clean_segments += 1;
+                    continue;
+                }
+
                 data.memory_arbiter.set(*segment, "");
             }
+
+            if self.only_dirty {
+                debug!(
+                    "Serialized world state (dirty only): {} record(s) reused, {} of {} segment(s) unchanged",
+                    reused,
+                    clean_segments,
+                    self.segments.len()
+                );
+            }
         }
     }
 
-    let mut sys = Serialize { segments };
+    let mut sys = Serialize {
+        segments,
+        only_dirty,
+        save,
+    };
 
     sys.run_now(world);
 }
 
-/// Wire-format fingerprint prepended to the serialized world payload. The
-/// component stream is bincode — enum variants are encoded by ORDINAL — so
-/// any shape change to a serialized component (variants added, removed or
-/// reordered; fields changed) makes old payloads decode as misaligned
-/// garbage rather than fail. Every such change MUST bump this constant;
-/// a mismatch is rejected wholesale with one loud error and a clean empty
-/// world (EP-5.1 reset-anytime, EP-3.1 loudness).
+/// Format version written into the serialized world payload. Components
+/// are bincode — enum variants are encoded by ORDINAL — so any shape
+/// change to a serialized component (variants added, removed or
+/// reordered; fields changed) makes old bytes decode as garbage or fail.
+/// Every such change MUST bump this constant. Since v25 the payload is
+/// framed per entity and per component (`worldformat`), so a bad entity
+/// drops alone and a bump can later be bridged by a step in
+/// [`world_migrations`]. Until then (EP-5.1) a payload at any other
+/// version is rejected wholesale with one loud error and a clean empty
+/// world (EP-3.1 loudness).
 ///
 /// History: 2 = derelict-rooms M1-M5 (RoomDynamicVisibilityData intel fields,
 /// MiningOutpostState::Cleanup removed, Salvage operation/mission added);
@@ -684,12 +1072,88 @@ fn serialize_world(world: &World, segments: &[u32]) {
 /// v23 payload saved before those fields would misalign silently at the tip. One loud
 /// reset instead (folds into the pending MMO deploy reset). Found by the 2026-07-01
 /// reconciliation review (REC-001, docs/reviews/reconciliation-2026-07-01.md).
-const WORLD_FORMAT_VERSION: u32 = 24;
+/// 25 = versioned per-entity framing (`worldformat`): each entity is a
+/// length-prefixed record of length-prefixed component slots, so a bad
+/// entity drops alone. The framing itself is new, so v24 payloads reset.
+/// 26 = every mission gained a trailing `paused: bool` (operator pause via
+/// `console`).
+/// 27 = `SquadContext` gained a trailing `energy_invested: u32` (the
+/// home-room budget a squad drew on).
+/// 28 = `SquadCombatJobContext` gained `boosts` and `boost_started` (boosted
+/// defender escalation).
+/// 29 = `RoomDynamicVisibilityData` gained `entry_segment` and
+/// `tower_layout_hash` (the tower-fire entry segment and the layout it was
+/// picked for).
+/// 30 = `SquadContext` gained a trailing `casualties: Vec<SquadCasualty>`
+/// (what the last wave lost, for sizing the next one).
+/// 31 = `SquadMember` dropped `tick_orders` (now the unsaved `SquadOrders`
+/// resource), reshaping the saved `SquadContext` members.
+/// 32 = `RoomStaticVisibilityData` gained `room_class` and `exit_tiles`.
+/// 33 = `UpgradeMission` gained `seats` (controller-link seating).
+/// 34 = `RemoteBuildMission` gained `haulers` (energy ferried in from other
+/// homes).
+/// 35 = `SalvageMission` gained `loot_estimate`.
+/// 36 = `MiningOutpostMission` gained `invader_watch` (remote invader
+/// prediction).
+/// 37 = `MiningOutpostMission` gained `roads` (the planned remote road
+/// set).
+/// 38 = `SquadPath` gained the route `waypoint` (room-by-room anchor
+/// routing), reshaping the saved `SquadContext`.
+/// 39 = `WallRepairMission` gained `builders` (breach rush builders).
+/// 40 = `MiningOutpostOperation` gained `outposts` and `rescan`, and
+/// `WarOperation` a trailing `missions` (reaping lost child missions).
+/// 41 = `RemoteRoads`, a mining outpost's `roads`, gained `exits` (the home
+/// exits whose chokes get fortified).
+/// 42 = `TerminalMission` gained `schedule` (scored terminal actions).
+/// 43 = `RoomDynamicVisibilityData` gained a trailing `reservation_end` and
+/// `ReserveMission` gained `reservation_level`.
+/// 44 = `MiningOutpostMission` gained `winding_down` and
+/// `MiningOutpostOperation` a trailing `resized_at` (remote set sizing).
+/// 45 = `ReserveMission` gained `reserved_until` (hostile claimer
+/// interception).
+const WORLD_FORMAT_VERSION: u32 = 45;
+
+/// Oldest payload version [`world_migrations`] brings forward. EP-5.1
+/// builds no migration paths until the Inc-7 serialization pass, so this
+/// moves with every [`WORLD_FORMAT_VERSION`] bump and older saves reset.
+const MIGRATE_FROM_VERSION: u32 = WORLD_FORMAT_VERSION;
+
+/// Per-component migration steps for [`WORLD_FORMAT_VERSION`] bumps, keyed
+/// by the component's type name and the version the step migrates FROM.
+/// Built on load only (EP-1.1 — no static registry). Empty until Inc 7
+/// (EP-5.1).
+fn world_migrations() -> MigrationRegistry {
+    MigrationRegistry::default()
+}
+
+/// `MissionData::Upgrade`'s variant tag: its position in the enum, one
+/// varint byte.
+const UPGRADE_MISSION_TAG: u8 = 1;
+
+/// Migrate and decode one component slot written at `version`.
+fn decode_component<C>(
+    name: &'static str,
+    slot: Option<Vec<u8>>,
+    version: u32,
+    migrations: &MigrationRegistry,
+) -> Result<Option<C::Data>, String>
+where
+    C: ConvertSaveload<SerializeMarker>,
+{
+    slot.map(|bytes| {
+        let bytes = migrations.migrate(name, version, WORLD_FORMAT_VERSION, bytes)?;
+        decode_value(&bytes).map_err(|e| format!("{}: {}", name, e))
+    })
+    .transpose()
+}
 
-/// Loads world state from RawMemory segments. Old/foreign payloads are
-/// rejected by the [`WORLD_FORMAT_VERSION`] fingerprint; a mid-stream decode
-/// failure clears all partially loaded component storages — either way the
-/// outcome is a loud, clean reset, never a silent partial world.
+/// Loads world state from RawMemory segments. Payloads the framing can't
+/// read (older than [`MIGRATE_FROM_VERSION`], newer than this build, or a
+/// broken envelope) are discarded wholesale and enter recovery
+/// ([`MemoryRecovery`]): the raw payload is backed up and the tick runs on
+/// a fresh world. Within a readable payload each
+/// entity is migrated and decoded on its own; one that fails is logged and
+/// dropped while the rest of the world loads.
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 fn deserialize_world(world: &World, segments: &[u32]) {
     struct Deserialize<'a> {
@@ -700,6 +1164,7 @@ fn deserialize_world(world: &World, segments: &[u32]) {
     struct DeserializeSystemData<'a> {
         memory_arbiter: WriteExpect<'a, MemoryArbiter>,
         metrics: Write<'a, crate::metrics::MetricsState>,
+        recovery: Write<'a, MemoryRecovery>,
         entities: Entities<'a>,
         marker_alloc: Write<'a, SerializeMarkerAllocator>,
         markers: WriteStorage<'a, SerializeMarker>,
@@ -738,77 +1203,146 @@ fn deserialize_world(world: &World, segments: &[u32]) {
                 // a spontaneous empty world). Loud + counted now
                 // (P1.A1 / Inc-2 rescope); falling through to an empty
                 // payload is the reset itself — sanctioned, with a cause.
+                let mut failure = None;
+
                 let decoded_data = decode_buffer_from_string(&encoded_data).unwrap_or_else(|e| {
                     error!("Failed deserialization: segment decode failed, resetting world state: {}", e);
-                    data.metrics.record_deser_failure();
+                    failure = Some(format!("segment decode failed: {}", e));
                     Vec::new()
                 });
 
-                // Fingerprint check: an old-format payload must be rejected
-                // wholesale — bincode would otherwise decode reshaped enums
-                // as misaligned garbage instead of failing.
-                let payload: &[u8] = if decoded_data.is_empty() {
-                    &[]
-                } else if decoded_data.len() >= 4 && decoded_data[..4] == WORLD_FORMAT_VERSION.to_le_bytes() {
-                    &decoded_data[4..]
-                } else {
-                    error!(
-                        "Failed deserialization: world format fingerprint mismatch (expected version {}), resetting world state",
-                        WORLD_FORMAT_VERSION
-                    );
-                    data.metrics.record_deser_failure();
-                    &[]
+                // Version check: framed payloads from
+                // MIGRATE_FROM_VERSION on migrate forward; anything
+                // older has no migration path and anything newer is a
+                // downgrade — both are rejected wholesale.
+                let version = read_version(&decoded_data);
+                let payload = match version {
+                    _ if decoded_data.is_empty() => None,
+                    Some(version) if (MIGRATE_FROM_VERSION..=WORLD_FORMAT_VERSION).contains(&version) => match read_world(&decoded_data) {
+                        Ok((_, records)) => Some((version, records)),
+                        Err(e) => {
+                            error!("Failed deserialization: {}, resetting world state", e);
+                            failure = Some(e);
+                            None
+                        }
+                    },
+                    _ => {
+                        error!(
+                            "Failed deserialization: world format version {:?} can't be migrated to {} (oldest migratable: {}), resetting world state",
+                            version, WORLD_FORMAT_VERSION, MIGRATE_FROM_VERSION
+                        );
+                        failure = Some(format!("unmigratable world format version {:?}", version));
+                        None
+                    }
                 };
 
-                if !payload.is_empty() {
-                    let mut deserializer = Deserializer::from_slice(payload, DefaultOptions::new());
-
-                    let result = DeserializeComponents::<std::convert::Infallible, SerializeMarker>::deserialize(
-                        &mut (
-                            &mut data.creep_spawnings,
-                            &mut data.creep_owners,
-                            &mut data.creep_movement_data,
-                            &mut data.room_data,
-                            &mut data.room_plan_data,
-                            &mut data.job_data,
-                            &mut data.operation_data,
-                            &mut data.mission_data,
-                            &mut data.squad_context,
-                            &mut data.visibility_queue_data,
-                            &mut data.combat_objective_data,
-                            &mut data.room_threat_data,
-                        ),
-                        &data.entities,
-                        &mut data.markers,
-                        &mut data.marker_alloc,
-                        &mut deserializer,
-                    )
-                    .map(|_| ())
-                    .map_err(|e| e.to_string());
-
-                    if let Err(e) = result {
-                        // A mid-stream failure leaves a partially loaded
-                        // world — clear it so the documented contract (the
-                        // only supported recovery is a full reset) actually
-                        // holds rather than leaning on orphan repair to
-                        // converge from an arbitrary prefix.
-                        error!("Failed deserialization: {} - clearing partially loaded world state", e);
+                // A payload rejected wholesale leaves the world empty —
+                // enter recovery (backup, Memory flag, orphan adoption).
+                if let Some(cause) = failure {
+                    data.metrics.record_deser_failure();
+                    data.recovery.recover(&mut data.memory_arbiter, &encoded_data, &cause);
+                }
+
+                if let Some((version, records)) = payload {
+                    if version != WORLD_FORMAT_VERSION {
+                        info!("Migrating world state from format version {} to {}", version, WORLD_FORMAT_VERSION);
+                    }
+
+                    let migrations = world_migrations();
+
+                    let mut storages = (
+                        &mut data.creep_spawnings,
+                        &mut data.creep_owners,
+                        &mut data.creep_movement_data,
+                        &mut data.room_data,
+                        &mut data.room_plan_data,
+                        &mut data.job_data,
+                        &mut data.operation_data,
+                        &mut data.mission_data,
+                        &mut data.squad_context,
+                        &mut data.visibility_queue_data,
+                        &mut data.combat_objective_data,
+                        &mut data.room_threat_data,
+                    );
+
+                    let mut loaded = HashSet::new();
+                    let mut dropped = 0;
+
+                    for record in records {
+                        // A record that fails to frame, migrate or decode
+                        // drops just that entity.
+                        let decoded = record.and_then(|record| {
+                            let marker: SerializeMarker = decode_value(&record.marker)?;
+                            let mut slots = record.components.into_iter();
+                            let mut next = || slots.next().flatten();
+
+                            // Slot order mirrors `serialize_world`.
+                            let components = (
+                                decode_component::<CreepSpawning>("CreepSpawning", next(), version, &migrations)?,
+                                decode_component::<CreepOwner>("CreepOwner", next(), version, &migrations)?,
+                                decode_component::<CreepRoverData>("CreepRoverData", next(), version, &migrations)?,
+                                decode_component::<RoomData>("RoomData", next(), version, &migrations)?,
+                                decode_component::<RoomPlanData>("RoomPlanData", next(), version, &migrations)?,
+                                decode_component::<JobData>("JobData", next(), version, &migrations)?,
+                                decode_component::<OperationData>("OperationData", next(), version, &migrations)?,
+                                decode_component::<MissionData>("MissionData", next(), version, &migrations)?,
+                                decode_component::<SquadContext>("SquadContext", next(), version, &migrations)?,
+                                decode_component::<VisibilityQueueData>("VisibilityQueueData", next(), version, &migrations)?,
+                                decode_component::<CombatObjectiveData>("CombatObjectiveData", next(), version, &migrations)?,
+                                decode_component::<RoomThreatData>("RoomThreatData", next(), version, &migrations)?,
+                            );
+
+                            Ok((marker, components))
+                        });
+
+                        match decoded {
+                            Ok((marker, components)) => {
+                                let entity = data.marker_alloc.retrieve_entity(marker, &mut data.markers, &data.entities);
+                                let marker_alloc = &mut data.marker_alloc;
+                                let markers = &mut data.markers;
+                                let entities = &data.entities;
+
+                                DeserializeComponents::<std::convert::Infallible, SerializeMarker>::deserialize_entity(
+                                    &mut storages,
+                                    entity,
+                                    components,
+                                    |m| Some(marker_alloc.retrieve_entity(m, markers, entities)),
+                                )
+                                .unwrap_or_else(|e| match e {});
+
+                                loaded.insert(marker.id());
+                            }
+                            Err(e) => {
+                                error!("Failed deserialization: dropping entity: {}", e);
+                                dropped += 1;
+                            }
+                        }
+                    }
+
+                    if dropped > 0 {
+                        // Entities that were only referenced by surviving
+                        // records got a placeholder with no components;
+                        // delete them so references to them read as dead
+                        // and the integrity repair clears them.
                         data.metrics.record_deser_failure();
 
-                        data.creep_spawnings.clear();
-                        data.creep_owners.clear();
-                        data.creep_movement_data.clear();
-                        data.room_data.clear();
-                        data.room_plan_data.clear();
-                        data.job_data.clear();
-                        data.operation_data.clear();
-                        data.mission_data.clear();
-                        data.squad_context.clear();
-                        data.visibility_queue_data.clear();
-                        data.combat_objective_data.clear();
-                        data.room_threat_data.clear();
-                        data.markers.clear();
-                        *data.marker_alloc = Default::default();
+                        let placeholders: Vec<Entity> = (&data.entities, &data.markers)
+                            .join()
+                            .filter(|(_, marker)| !loaded.contains(&marker.id()))
+                            .map(|(entity, _)| entity)
+                            .collect();
+
+                        for entity in placeholders.iter() {
+                            if let Err(err) = data.entities.delete(*entity) {
+                                warn!("Failed to delete placeholder entity {:?}: {}", entity, err);
+                            }
+                        }
+
+                        error!(
+                            "Failed deserialization: dropped {} entity record(s) and {} placeholder(s); the rest of the world loaded",
+                            dropped,
+                            placeholders.len()
+                        );
                     }
                 }
             }
@@ -881,8 +1415,11 @@ fn create_environment() -> GameEnvironment {
     );
 
     world.insert(arbiter);
+    // On-demand segments share the slots the requirements above leave free.
+    world.insert(SegmentManager::default());
 
     world.insert(SerializeMarkerAllocator::new());
+    world.insert(SerializedChunkHashes::default());
     world.register::<SerializeMarker>();
 
     let cost_matrix_cache = crate::pathing::costmatrixsystem::load_cost_matrix_cache(COST_MATRIX_SEGMENT);
@@ -905,21 +1442,42 @@ fn create_environment() -> GameEnvironment {
     // before deserialize_world's run_now, whose SystemData is never
     // setup() (M3 — deser failures count into it).
     world.insert(crate::metrics::MetricsState::default());
+    // Same for recovery bookkeeping, seeded from the durable Memory flag.
+    world.insert(MemoryRecovery::load());
+    // Mission outcome counts, kept in Memory across VM restarts.
+    world.insert(crate::missions::missionstats::MissionStats::load());
+    // War declarations, likewise.
+    world.insert(crate::military::wartargets::WarTargets::load());
+    // Transfer ages, likewise; the rest of the queue is rebuilt every tick.
+    world.insert(TransferQueue::load());
     world.insert(RoomStatusCache::new());
+    world.insert(crate::server::ServerCapabilities::detect());
     world.register::<SquadContext>();
 
     // Repair queue (ephemeral -- rebuilt each tick by missions).
     world.insert(crate::repairqueue::RepairQueue::default());
 
+    // Power requests (ephemeral -- drained each tick by PowerCreepSystem).
+    world.insert(PowerRequests::default());
+
     // Entity cleanup queue (ephemeral -- drained each tick by EntityCleanupSystem).
     world.insert(EntityCleanupQueue::default());
 
+    // Entity integrity cadence (ephemeral -- a fresh world is checked on its first tick).
+    world.insert(EntityIntegrityState::default());
+
     // Per-room supply structure cache (ephemeral -- lazily populated each tick).
     world.insert(crate::missions::localsupply::structure_data::SupplyStructureCache::new());
 
     // Register components and resources for every system in the tick list.
     setup_systems(&mut world);
 
+    // Incremental world save: change readers must exist before the first
+    // load so nothing mutated afterwards is missed.
+    let serialize_cache = SerializeCache::new(&mut world);
+    world.insert(serialize_cache);
+    world.insert(WorldSaveStats::default());
+
     GameEnvironment {
         world,
         loaded: false,
@@ -965,16 +1523,36 @@ pub fn tick() {
     //
     // Load feature flags from Memory (after resets, so the result
     // reflects any prepare() defaults). Inserted into the world below
-    // as the per-tick Features Resource (M5).
+    // as the per-tick Features Resource (M5), with the per-room
+    // FeatureOverrides, the controller SignFeatures, the
+    // ConsolidationFeatures and the ResourceFeatures beside it.
     //
 
     let features = crate::features::load();
+    let feature_overrides = crate::features::load_overrides();
+    let sign_features = crate::features::load_signs();
+    let consolidation_features = crate::features::load_consolidation();
+    let resource_features = crate::features::load_resources();
+
+    crate::logging::configure(&crate::features::load_logging().levels);
 
     ENVIRONMENT.with(|env_cell| {
         let mut env_ref = env_cell.borrow_mut();
         let env = env_ref.get_or_insert_with(create_environment);
 
         env.world.insert(features);
+        env.world
+            .write_resource::<crate::server::ServerCapabilities>()
+            .apply(&features.server);
+
+        // A room can switch visuals on while the global flag is off, so the
+        // visualizer exists whenever anything will draw.
+        let visualize = features.visualize.on || feature_overrides.any(|o| o.visualize == Some(true));
+
+        env.world.insert(feature_overrides);
+        env.world.insert(sign_features);
+        env.world.insert(consolidation_features);
+        env.world.insert(resource_features);
 
         //
         // Memory reset — clear all registered segments.
@@ -1014,11 +1592,13 @@ pub fn tick() {
 
         run_pending_segment_loads(&mut env.world);
 
+        capture_managed_segments(&env.world);
+
         //
         // Add dynamic resources.
         //
 
-        if features.visualize.on {
+        if visualize {
             // Visualizer and VisualizationData are recreated each tick (ephemeral draw state).
             env.world.insert(Visualizer::new());
             env.world.insert(VisualizationData::new());
@@ -1127,14 +1707,29 @@ pub fn tick() {
         // Execution — systems run sequentially with maintain() after each.
         //
 
-        run_systems(&mut env.world, features.system_timing);
+        let checkpoint_skipped = run_systems(&mut env.world, features.system_timing);
 
         //
-        // Cleanup memory.
+        // Degraded tail: if the mid-tick checkpoint tripped (or trips
+        // now), only dirty entities are encoded and only changed segments
+        // written (see `serialize_world`).
+        // `Memory.creeps` is swept by `CreepMemoryCleanupSystem`.
         //
 
-        if let Err(e) = cleanup_memory() {
-            warn!("cleanup_memory: {}", e);
+        let cpu_limit = game::cpu::limit() as f64;
+        let degraded = !checkpoint_skipped.is_empty() || crate::cpugovernor::checkpoint_exceeded(game::cpu::get_used(), cpu_limit);
+
+        if degraded {
+            warn!(
+                "CPU checkpoint: {:.1} of {:.1} limit used — skipped: {}",
+                game::cpu::get_used(),
+                cpu_limit,
+                if checkpoint_skipped.is_empty() {
+                    "none".to_string()
+                } else {
+                    checkpoint_skipped.join(", ")
+                }
+            );
         }
 
         //
@@ -1148,7 +1743,7 @@ pub fn tick() {
         // Serialize world state.
         //
 
-        serialize_world(&env.world, COMPONENT_SEGMENTS);
+        serialize_world(&env.world, COMPONENT_SEGMENTS, degraded, features.serialize);
 
         // The tick is committed only once its state is serialized
         // (P1.C2 — see the note at the old advance site).
@@ -1156,22 +1751,55 @@ pub fn tick() {
     });
 }
 
-fn cleanup_memory() -> Result<(), Box<dyn ::std::error::Error>> {
-    let alive_creeps: HashSet<String> = screeps::game::creeps().keys().map(|k| k.to_string()).collect();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn save_world() -> (World, SerializeCache) {
+        let mut world = World::new();
+        world.register::<CreepSpawning>();
+        world.register::<CreepOwner>();
+        world.register::<RoomData>();
+        world.register::<RoomPlanData>();
+        world.register::<RoomThreatData>();
+        let cache = SerializeCache::new(&mut world);
+        (world, cache)
+    }
 
-    let screeps_memory = match crate::memory_helper::dict("creeps") {
-        Some(v) => v,
-        None => {
-            return Ok(());
+    /// A run of dirty-only saves, each keeping the records the last one left,
+    /// still re-encodes everything every `full_interval` ticks.
+    #[test]
+    fn consecutive_dirty_only_saves_still_go_full_on_the_interval() {
+        let (mut world, mut cache) = save_world();
+        let (room, mission) = (world.create_entity().build(), world.create_entity().build());
+        let save = crate::features::SerializeFeatures::default();
+
+        let mut full_ticks = Vec::new();
+        for tick in 1..=250u32 {
+            if cache.begin_save(tick, false, save) {
+                full_ticks.push(tick);
+            }
+            cache.records.entry(room).or_insert_with(|| vec![0]);
+            cache
+                .untracked_records
+                .entry(mission)
+                .or_insert_with(|| tick.to_le_bytes().to_vec());
         }
-    };
 
-    for mem_name in crate::memory_helper::keys(&screeps_memory) {
-        if !alive_creeps.contains(&mem_name) {
-            debug!("cleaning up creep memory of dead creep {}", mem_name);
-            crate::memory_helper::del(&screeps_memory, &mem_name);
-        }
+        assert_eq!(full_ticks, vec![1, 101, 201]);
+        assert_eq!(cache.untracked_records[&mission], 201u32.to_le_bytes().to_vec());
     }
 
-    Ok(())
+    #[test]
+    fn a_deleted_entity_forces_a_full_save() {
+        let (mut world, mut cache) = save_world();
+        let room = world.create_entity().build();
+        let save = crate::features::SerializeFeatures::default();
+
+        assert!(cache.begin_save(1, false, save));
+        cache.records.insert(room, vec![0]);
+        assert!(!cache.begin_save(2, false, save));
+        assert!(cache.begin_save(3, true, save));
+        assert!(cache.records.is_empty());
+    }
 }
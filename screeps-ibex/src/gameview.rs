@@ -10,16 +10,37 @@
 //!
 //! Growth rule: add a method here when a consumer is MIGRATED to the
 //! trait, never speculatively.
+//!
+//! Migrated decision paths: the squad manager's home gathering, the
+//! squad pre-run's dead-member prune (the wave-wipe signal) and
+//! `LocalSupplyMission::ensure_children`. Their tests drive the seam
+//! with `testing::MockGame` over a `testing::TestWorld`.
+
+use screeps::{Creep, Mineral, ObjectId, RoomName};
 
-/// The reads the Phase-1 telemetry/governor path performs against the
-/// game API. `LiveGame` is the wasm-side passthrough; tests provide
-/// [`FixedGameView`].
+/// A room's spawn energy: `Room.energyAvailable` and
+/// `Room.energyCapacityAvailable`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RoomEnergy {
+    pub available: u32,
+    pub capacity: u32,
+}
+
+/// The reads the Phase-1 telemetry/governor path and the migrated
+/// decision paths perform against the game API. `LiveGame` is the
+/// wasm-side passthrough; tests provide [`FixedGameView`].
 pub trait GameView {
     fn time(&self) -> u32;
     fn cpu_used(&self) -> f64;
     fn cpu_limit(&self) -> f64;
     fn cpu_tick_limit(&self) -> f64;
     fn bucket(&self) -> i32;
+    /// `None` for a room we have no vision of.
+    fn room_energy(&self, room: RoomName) -> Option<RoomEnergy>;
+    /// Whether the creep still resolves, i.e. is alive and visible.
+    fn creep_exists(&self, creep: ObjectId<Creep>) -> bool;
+    /// `None` when the mineral is not visible.
+    fn mineral_amount(&self, mineral: ObjectId<Mineral>) -> Option<u32>;
 }
 
 /// The live game API (wasm runtime only — the calls trap on a host
@@ -42,10 +63,23 @@ impl GameView for LiveGame {
     fn bucket(&self) -> i32 {
         screeps::game::cpu::bucket()
     }
+    fn room_energy(&self, room: RoomName) -> Option<RoomEnergy> {
+        screeps::game::rooms().get(room).map(|r| RoomEnergy {
+            available: r.energy_available(),
+            capacity: r.energy_capacity_available(),
+        })
+    }
+    fn creep_exists(&self, creep: ObjectId<Creep>) -> bool {
+        creep.resolve().is_some()
+    }
+    fn mineral_amount(&self, mineral: ObjectId<Mineral>) -> Option<u32> {
+        mineral.resolve().map(|m| m.mineral_amount())
+    }
 }
 
 /// Host-side double: fixed values, good enough for kernel tests of
-/// anything that migrates onto the seam.
+/// anything that migrates onto the seam. It sees no rooms and no game
+/// objects; tests that need some use `testing::MockGame`.
 #[derive(Debug, Clone, Copy)]
 pub struct FixedGameView {
     pub time: u32,
@@ -83,6 +117,15 @@ impl GameView for FixedGameView {
     fn bucket(&self) -> i32 {
         self.bucket
     }
+    fn room_energy(&self, _room: RoomName) -> Option<RoomEnergy> {
+        None
+    }
+    fn creep_exists(&self, _creep: ObjectId<Creep>) -> bool {
+        false
+    }
+    fn mineral_amount(&self, _mineral: ObjectId<Mineral>) -> Option<u32> {
+        None
+    }
 }
 
 #[cfg(test)]
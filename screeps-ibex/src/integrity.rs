@@ -0,0 +1,289 @@
+//! Periodic structural check of the operation → mission → child and
+//! room → mission links.
+//!
+//! The pre-serialize `repair_entity_integrity` pass only scrubs references to
+//! dead entities. Links between live entities can still disagree: a room can
+//! list a mission filed under another room, a mission can drop out of its
+//! room's list, or a parent and child can disagree on who owns whom. Those
+//! survive every save and load unnoticed. This pass walks each link from both
+//! ends, on a cadence and on the first tick after a load, and repairs them:
+//!
+//! - Room mission lists lose dead, duplicate and misfiled entries.
+//! - A mission missing from the list of the room it names is put back.
+//! - Parent child lists (missions, and operations that keep one) lose dead
+//!   entries and missions that name another owner.
+//! - A mission whose owner is gone, or whose parent mission no longer lists
+//!   it, is deleted through the cleanup queue, children and all. So is one
+//!   whose room is gone.
+//!
+//! Each pass that repairs anything logs an [`IntegrityReport`], so the bug
+//! that desynced the links shows up in the logs.
+
+use crate::cleanup::*;
+use crate::missions::data::*;
+use crate::missions::missionstats::MissionStats;
+use crate::missions::missionsystem::{MissionError, MissionFailure};
+use crate::operations::data::*;
+use crate::room::data::*;
+use log::*;
+use screeps::*;
+use specs::prelude::*;
+use std::collections::HashSet;
+use std::fmt;
+
+/// Ticks between passes once the world has been checked after a load.
+const INTEGRITY_INTERVAL: u32 = 500;
+
+/// When the last pass ran. Not serialized, so a fresh environment always
+/// checks the world it just loaded.
+#[derive(Default)]
+pub struct EntityIntegrityState {
+    last_run: Option<u32>,
+}
+
+/// What one pass repaired. Link pairs are `(holder, entry)`: the room or
+/// parent whose list was changed, and the mission it concerned.
+#[derive(Debug, Default)]
+pub struct IntegrityReport {
+    pub dead_room_missions: Vec<(Entity, Entity)>,
+    pub duplicate_room_missions: Vec<(Entity, Entity)>,
+    pub misfiled_room_missions: Vec<(Entity, Entity)>,
+    pub reattached_missions: Vec<(Entity, Entity)>,
+    pub dead_children: Vec<(Entity, Entity)>,
+    pub disowned_children: Vec<(Entity, Entity)>,
+    pub orphaned_missions: Vec<Entity>,
+}
+
+impl IntegrityReport {
+    pub fn is_clean(&self) -> bool {
+        self.dead_room_missions.is_empty()
+            && self.duplicate_room_missions.is_empty()
+            && self.misfiled_room_missions.is_empty()
+            && self.reattached_missions.is_empty()
+            && self.dead_children.is_empty()
+            && self.disowned_children.is_empty()
+            && self.orphaned_missions.is_empty()
+    }
+}
+
+impl fmt::Display for IntegrityReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "room_missions(dead={} duplicate={} misfiled={}) reattached={} children(dead={} disowned={}) orphaned={}",
+            self.dead_room_missions.len(),
+            self.duplicate_room_missions.len(),
+            self.misfiled_room_missions.len(),
+            self.reattached_missions.len(),
+            self.dead_children.len(),
+            self.disowned_children.len(),
+            self.orphaned_missions.len()
+        )
+    }
+}
+
+#[derive(SystemData)]
+pub struct EntityIntegritySystemData<'a> {
+    entities: Entities<'a>,
+    missions: WriteStorage<'a, MissionData>,
+    operations: WriteStorage<'a, OperationData>,
+    room_data: WriteStorage<'a, RoomData>,
+    cleanup_queue: Write<'a, EntityCleanupQueue>,
+    state: Write<'a, EntityIntegrityState>,
+    mission_stats: Write<'a, MissionStats>,
+}
+
+pub struct EntityIntegritySystem;
+
+impl EntityIntegritySystem {
+    fn check_rooms(data: &mut EntityIntegritySystemData, report: &mut IntegrityReport) {
+        let entities = &data.entities;
+        let missions = &data.missions;
+
+        let is_mission = |e: Entity| entities.is_alive(e) && missions.get(e).is_some();
+        let filed_under = |mission: Entity, room: Entity| missions.get(mission).and_then(|md| md.as_mission().get_room()) == Some(room);
+
+        // Only rooms that need a repair are touched mutably; a mutable join
+        // would mark every room dirty for the save.
+        let damaged_rooms: Vec<Entity> = (entities, &data.room_data)
+            .join()
+            .filter(|(room, rd)| {
+                let list = rd.get_missions();
+                let unique: HashSet<&Entity> = list.iter().collect();
+                unique.len() != list.len() || list.iter().any(|m| !is_mission(*m) || !filed_under(*m, *room))
+            })
+            .map(|(room, _)| room)
+            .collect();
+
+        for room in damaged_rooms {
+            let Some(rd) = data.room_data.get_mut(room) else {
+                continue;
+            };
+
+            let mut seen = HashSet::new();
+            rd.retain_missions(|mission| {
+                if !is_mission(mission) {
+                    report.dead_room_missions.push((room, mission));
+                    false
+                } else if !filed_under(mission, room) {
+                    report.misfiled_room_missions.push((room, mission));
+                    false
+                } else if !seen.insert(mission) {
+                    report.duplicate_room_missions.push((room, mission));
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+    }
+
+    fn check_missions(data: &mut EntityIntegritySystemData, report: &mut IntegrityReport) {
+        let mut reattach = Vec::new();
+        let mut disowned = Vec::new();
+
+        for (entity, md) in (&data.entities, &data.missions).join() {
+            let mission = md.as_mission();
+
+            if let Some(room) = mission.get_room() {
+                match data.room_data.get(room).filter(|_| data.entities.is_alive(room)) {
+                    Some(rd) if !rd.get_missions().contains(&entity) => reattach.push((room, entity)),
+                    Some(_) => {}
+                    None => {
+                        report.orphaned_missions.push(entity);
+                        continue;
+                    }
+                }
+            }
+
+            if let Some(owner) = *mission.get_owner() {
+                let claimed = if !data.entities.is_alive(owner) {
+                    false
+                } else if let Some(parent) = data.missions.get(owner) {
+                    parent.as_mission().get_children().contains(&entity)
+                } else {
+                    // Operations find most of their missions through the rooms,
+                    // so a missing list entry doesn't orphan one.
+                    data.operations.get(owner).is_some()
+                };
+
+                if !claimed {
+                    report.orphaned_missions.push(entity);
+                    continue;
+                }
+            }
+
+            // Children that aren't missions (e.g. squad entities) carry no
+            // owner to check against.
+            for child in mission.get_children() {
+                if !data.entities.is_alive(child) {
+                    disowned.push((entity, child, true));
+                } else if let Some(child_md) = data.missions.get(child) {
+                    if *child_md.as_mission().get_owner() != Some(entity) {
+                        disowned.push((entity, child, false));
+                    }
+                }
+            }
+        }
+
+        for (parent, child, dead) in disowned {
+            if let Some(md) = data.missions.get(parent) {
+                md.as_mission_mut().child_complete(child);
+            }
+            if dead {
+                report.dead_children.push((parent, child));
+            } else {
+                report.disowned_children.push((parent, child));
+            }
+        }
+
+        for (room, mission) in reattach {
+            if let Some(rd) = data.room_data.get_mut(room) {
+                rd.add_mission(mission);
+                report.reattached_missions.push((room, mission));
+            }
+        }
+    }
+
+    fn check_operations(data: &mut EntityIntegritySystemData, report: &mut IntegrityReport) {
+        let entities = &data.entities;
+        let missions = &data.missions;
+
+        for (entity, od) in (entities, &mut data.operations).join() {
+            let operation = od.as_operation();
+
+            for child in operation.get_children() {
+                if !entities.is_alive(child) {
+                    operation.child_complete(child);
+                    report.dead_children.push((entity, child));
+                } else if missions.get(child).is_some_and(|md| *md.as_mission().get_owner() != Some(entity)) {
+                    operation.child_complete(child);
+                    report.disowned_children.push((entity, child));
+                }
+            }
+        }
+    }
+}
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+impl<'a> System<'a> for EntityIntegritySystem {
+    type SystemData = EntityIntegritySystemData<'a>;
+
+    fn run(&mut self, mut data: Self::SystemData) {
+        let now = game::time();
+
+        if data
+            .state
+            .last_run
+            .is_some_and(|last| now.saturating_sub(last) < INTEGRITY_INTERVAL)
+        {
+            return;
+        }
+
+        data.state.last_run = Some(now);
+
+        let mut report = IntegrityReport::default();
+
+        Self::check_rooms(&mut data, &mut report);
+        Self::check_missions(&mut data, &mut report);
+        Self::check_operations(&mut data, &mut report);
+
+        for mission in &report.orphaned_missions {
+            if let Some(cleanup) = extract_mission_cleanup(*mission, &data.missions) {
+                data.cleanup_queue.delete_mission(cleanup);
+            }
+            if let Some(md) = data.missions.get(*mission) {
+                let error = MissionError::new(MissionFailure::IntegrityRepair, "Orphaned mission deleted by the integrity check");
+                data.mission_stats.record_end(*mission, md.type_name(), Err(&error), now);
+            }
+        }
+
+        if report.is_clean() {
+            return;
+        }
+
+        warn!("INTEGRITY: repaired {}", report);
+
+        for (room, mission) in &report.dead_room_missions {
+            warn!("INTEGRITY: dead mission {:?} dropped from room {:?}", mission, room);
+        }
+        for (room, mission) in &report.duplicate_room_missions {
+            warn!("INTEGRITY: duplicate mission {:?} dropped from room {:?}", mission, room);
+        }
+        for (room, mission) in &report.misfiled_room_missions {
+            warn!("INTEGRITY: mission {:?} of another room dropped from room {:?}", mission, room);
+        }
+        for (room, mission) in &report.reattached_missions {
+            warn!("INTEGRITY: mission {:?} reattached to room {:?}", mission, room);
+        }
+        for (parent, child) in &report.dead_children {
+            warn!("INTEGRITY: dead child {:?} dropped from {:?}", child, parent);
+        }
+        for (parent, child) in &report.disowned_children {
+            warn!("INTEGRITY: child {:?} owned elsewhere dropped from {:?}", child, parent);
+        }
+        for mission in &report.orphaned_missions {
+            warn!("INTEGRITY: orphaned mission {:?} deleted", mission);
+        }
+    }
+}
@@ -30,7 +30,25 @@
 //! client-side (first caller wins — OUR priority order, deliberate and
 //! diffable, instead of the engine's), and every issued intent is
 //! recorded.
+//!
+//! ## The budget tally (everything else)
+//!
+//! Every accepted intent costs 0.2 CPU on top of its JS cost, and a
+//! large empire spends tens of CPU a tick on them without any one
+//! system looking expensive. The economy intents (harvest, transfer,
+//! build, repair, spawn, tower, …) go through [`IntentRecorder::issued`],
+//! which counts the ones the engine accepted. They are counted only —
+//! not folded into the digest — so the combat parity stream is
+//! unchanged. Moves are issued inside the rover and tallied from the
+//! movement system.
+//!
+//! Under a `Conserve`/`Critical` governor tier the recorder carries
+//! soft per-category caps (`features.intents`): optional work — drive-by
+//! and tower repairs — asks [`IntentRecorder::allow`] first and is
+//! skipped once its category is spent. Nothing else is capped.
 
+use crate::cpugovernor::Tier;
+use crate::features::IntentFeatures;
 use crate::jobs::actions::SimultaneousActionFlags;
 use screeps::prelude::*;
 use screeps::{Attackable, Creep, Healable, Position};
@@ -42,9 +60,28 @@ pub enum IntentCategory {
     RangedMassAttack = 2,
     Heal = 3,
     RangedHeal = 4,
+    Move = 5,
+    Harvest = 6,
+    /// Creep transfer, withdraw and pickup, and link transfers.
+    Transfer = 7,
+    Build = 8,
+    /// Creep and tower repairs.
+    Repair = 9,
+    Upgrade = 10,
+    Dismantle = 11,
+    /// Claim, reserve, attack and sign controller.
+    Controller = 12,
+    /// Spawn, renew and recycle.
+    Spawn = 13,
+    /// Tower attack and heal.
+    Tower = 14,
+    /// Labs, power spawns, terminals, observers and safe mode.
+    Structure = 15,
+    /// `creep.say` debug codes (`visualize.say`).
+    Say = 16,
 }
 
-const CATEGORY_COUNT: usize = 5;
+pub const CATEGORY_COUNT: usize = 17;
 
 const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
 const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
@@ -65,6 +102,7 @@ fn fnv_fold(mut hash: u64, bytes: &[u8]) -> u64 {
 pub struct IntentRecorder {
     counts: [u32; CATEGORY_COUNT],
     digest: u64,
+    caps: [Option<u32>; CATEGORY_COUNT],
 }
 
 impl Default for IntentRecorder {
@@ -72,16 +110,52 @@ impl Default for IntentRecorder {
         IntentRecorder {
             counts: [0; CATEGORY_COUNT],
             digest: FNV_OFFSET,
+            caps: [None; CATEGORY_COUNT],
         }
     }
 }
 
 impl IntentRecorder {
-    /// Tick-start reset.
+    /// Tick-start reset. Clears the soft caps too; `apply_soft_caps`
+    /// re-arms them once the tick's governor tier is known.
     pub fn reset(&mut self) {
         *self = IntentRecorder::default();
     }
 
+    /// Arm the tick's soft caps for the governor `tier`.
+    pub fn apply_soft_caps(&mut self, features: &IntentFeatures, tier: Tier) {
+        self.caps[IntentCategory::Repair as usize] = match tier {
+            Tier::Normal => None,
+            Tier::Conserve => Some(features.repair_cap_conserve),
+            Tier::Critical => Some(features.repair_cap_critical),
+        };
+    }
+
+    /// Whether an optional intent of `category` still fits under the
+    /// tick's soft cap. Required work does not ask.
+    pub fn allow(&self, category: IntentCategory) -> bool {
+        self.caps[category as usize].is_none_or(|cap| self.counts[category as usize] < cap)
+    }
+
+    /// Count an economy intent if the engine accepted it (only accepted
+    /// intents are charged), passing the result through.
+    pub fn issued<T, E>(&mut self, category: IntentCategory, result: Result<T, E>) -> Result<T, E> {
+        if result.is_ok() {
+            self.counts[category as usize] += 1;
+        }
+        result
+    }
+
+    /// Count `count` intents issued outside the sink (moves).
+    pub fn tally(&mut self, category: IntentCategory, count: u32) {
+        self.counts[category as usize] += count;
+    }
+
+    /// Every intent counted this tick.
+    pub fn total(&self) -> u32 {
+        self.counts.iter().sum()
+    }
+
     /// Fold one issued intent into the tick's counts + digest.
     pub fn record(&mut self, category: IntentCategory, creep_name: &str, target_pos: Option<Position>) {
         self.counts[category as usize] += 1;
@@ -232,4 +306,48 @@ mod tests {
         // …but a second A intent is suppressed.
         assert!(!flags.consume(F::HARVEST));
     }
+
+    /// Only accepted intents are counted, and they stay out of the
+    /// combat digest.
+    #[test]
+    fn issued_counts_accepted_intents_only() {
+        let mut recorder = IntentRecorder::default();
+        let _ = recorder.issued(IntentCategory::Harvest, Ok::<(), ()>(()));
+        let _ = recorder.issued(IntentCategory::Harvest, Err::<(), ()>(()));
+        recorder.tally(IntentCategory::Move, 3);
+
+        let (counts, digest) = recorder.snapshot();
+        assert_eq!(counts[IntentCategory::Harvest as usize], 1);
+        assert_eq!(counts[IntentCategory::Move as usize], 3);
+        assert_eq!(recorder.total(), 4);
+        assert_eq!(digest, IntentRecorder::default().snapshot().1);
+    }
+
+    /// Repairs are capped below the normal tier only, and the reset
+    /// clears the caps.
+    #[test]
+    fn soft_caps_follow_the_governor_tier() {
+        let features = IntentFeatures {
+            repair_cap_conserve: 2,
+            repair_cap_critical: 0,
+        };
+        let mut recorder = IntentRecorder::default();
+
+        recorder.apply_soft_caps(&features, Tier::Normal);
+        recorder.tally(IntentCategory::Repair, 5);
+        assert!(recorder.allow(IntentCategory::Repair));
+
+        recorder.reset();
+        recorder.apply_soft_caps(&features, Tier::Conserve);
+        recorder.tally(IntentCategory::Repair, 1);
+        assert!(recorder.allow(IntentCategory::Repair));
+        recorder.tally(IntentCategory::Repair, 1);
+        assert!(!recorder.allow(IntentCategory::Repair));
+        assert!(recorder.allow(IntentCategory::Build));
+
+        recorder.reset();
+        assert!(recorder.allow(IntentCategory::Repair));
+        recorder.apply_soft_caps(&features, Tier::Critical);
+        assert!(!recorder.allow(IntentCategory::Repair));
+    }
 }
@@ -5,8 +5,10 @@ use super::utility::buildbehavior::*;
 use super::utility::harvestbehavior::*;
 use super::utility::haulbehavior::*;
 use super::utility::movebehavior::*;
+use super::utility::parkingbehavior::*;
 use super::utility::repair::*;
 use super::utility::repairbehavior::*;
+use super::utility::saybehavior::*;
 use super::utility::waitbehavior::*;
 use crate::remoteobjectid::*;
 use crate::structureidentifier::*;
@@ -47,7 +49,7 @@ machine!(
 
         Idle, FinishedPickup, Harvest, Build, Repair, Wait => fn visualize(&self, _system_data: &JobExecutionSystemData, _describe_data: &mut JobDescribeData) {}
 
-        Idle, FinishedPickup, Harvest, Build, Repair, Wait => fn gather_data(&self, _system_data: &JobExecutionSystemData, _runtime_data: &mut JobExecutionRuntimeData) {}
+        Idle, FinishedPickup, Harvest, Repair => fn gather_data(&self, _system_data: &JobExecutionSystemData, _runtime_data: &mut JobExecutionRuntimeData) {}
 
         _ => fn tick(&mut self, state_context: &mut BuildJobContext, tick_context: &mut JobTickContext) -> Option<BuildState>;
     }
@@ -57,49 +59,84 @@ impl Idle {
     pub fn tick(&mut self, state_context: &BuildJobContext, tick_context: &mut JobTickContext) -> Option<BuildState> {
         let creep = tick_context.runtime_data.owner;
         let build_room_data = tick_context.system_data.room_data.get(state_context.build_room)?;
+        let emergency_rampart = tick_context
+            .system_data
+            .emergency_ramparts
+            .site(build_room_data.name)
+            .filter(|pos| {
+                build_room_data
+                    .get_construction_sites()
+                    .map(|sites| sites.iter().any(|s| s.pos() == *pos))
+                    .unwrap_or(false)
+            });
 
-        get_new_repair_state(
-            creep,
-            build_room_data,
-            tick_context.system_data.repair_queue,
-            Some(RepairPriority::High),
-            BuildState::repair,
-        )
-        .or_else(|| get_new_build_state(creep, build_room_data, BuildState::build))
-        .or_else(|| {
-            get_new_repair_state(
-                creep,
-                build_room_data,
-                tick_context.system_data.repair_queue,
-                None,
-                BuildState::repair,
-            )
-        })
-        .or_else(|| {
-            let transfer_queue_data = TransferQueueGeneratorData {
-                cause: "Build Idle",
-                room_data: tick_context.system_data.room_data,
-            };
-
-            get_new_pickup_state_fill_resource(
-                creep,
-                &transfer_queue_data,
-                &[build_room_data],
-                TransferPriorityFlags::ALL,
-                TransferTypeFlags::HAUL | TransferTypeFlags::USE,
-                ResourceType::Energy,
-                tick_context.runtime_data.transfer_queue,
-                BuildState::pickup,
-            )
-        })
-        .or_else(|| {
-            if state_context.allow_harvest {
-                get_new_harvest_state(creep, build_room_data, BuildState::harvest)
-            } else {
-                None
-            }
-        })
-        .or_else(|| Some(BuildState::wait(5)))
+        // A breach in the rampart ring comes before even high priority repairs.
+        emergency_rampart
+            .and_then(|_| {
+                get_new_build_state(
+                    creep,
+                    tick_context.runtime_data.creep_entity,
+                    build_room_data,
+                    emergency_rampart,
+                    tick_context.runtime_data.build_claims,
+                    tick_context.runtime_data.pathfinder,
+                    BuildState::build,
+                )
+            })
+            .or_else(|| {
+                get_new_repair_state(
+                    creep,
+                    build_room_data,
+                    tick_context.system_data.repair_queue,
+                    Some(RepairPriority::High),
+                    BuildState::repair,
+                )
+            })
+            .or_else(|| {
+                get_new_build_state(
+                    creep,
+                    tick_context.runtime_data.creep_entity,
+                    build_room_data,
+                    emergency_rampart,
+                    tick_context.runtime_data.build_claims,
+                    tick_context.runtime_data.pathfinder,
+                    BuildState::build,
+                )
+            })
+            .or_else(|| {
+                get_new_repair_state(
+                    creep,
+                    build_room_data,
+                    tick_context.system_data.repair_queue,
+                    None,
+                    BuildState::repair,
+                )
+            })
+            .or_else(|| {
+                let transfer_queue_data = TransferQueueGeneratorData {
+                    cause: "Build Idle",
+                    room_data: tick_context.system_data.room_data,
+                };
+
+                get_new_pickup_state_fill_resource(
+                    creep,
+                    &transfer_queue_data,
+                    &[build_room_data],
+                    TransferPriorityFlags::ALL,
+                    TransferTypeFlags::HAUL | TransferTypeFlags::USE,
+                    ResourceType::Energy,
+                    tick_context.runtime_data.transfer_queue,
+                    BuildState::pickup,
+                )
+            })
+            .or_else(|| {
+                if state_context.allow_harvest {
+                    get_new_harvest_state(creep, build_room_data, BuildState::harvest)
+                } else {
+                    None
+                }
+            })
+            .or_else(|| Some(BuildState::wait(5)))
     }
 }
 
@@ -152,6 +189,10 @@ impl Harvest {
 }
 
 impl Build {
+    fn gather_data(&self, _system_data: &JobExecutionSystemData, runtime_data: &mut JobExecutionRuntimeData) {
+        runtime_data.build_claims.claim(self.target, runtime_data.creep_entity);
+    }
+
     pub fn tick(&mut self, _state_context: &mut BuildJobContext, tick_context: &mut JobTickContext) -> Option<BuildState> {
         tick_build(tick_context, self.target, BuildState::idle)
     }
@@ -164,8 +205,12 @@ impl Repair {
 }
 
 impl Wait {
+    fn gather_data(&self, _system_data: &JobExecutionSystemData, runtime_data: &mut JobExecutionRuntimeData) {
+        runtime_data.parking.hold(runtime_data.creep_entity);
+    }
+
     pub fn tick(&mut self, _state_context: &BuildJobContext, tick_context: &mut JobTickContext) -> Option<BuildState> {
-        mark_idle(tick_context);
+        park_idle(tick_context);
         tick_wait(&mut self.ticks, BuildState::idle)
     }
 }
@@ -196,6 +241,19 @@ impl Job for BuildJob {
         crate::visualization::SummaryContent::Text(format!("Build - {}", self.state.status_description()))
     }
 
+    fn say_code(&self, _system_data: &JobExecutionSystemData, _runtime_data: &JobExecutionRuntimeData) -> Option<String> {
+        let code = match &self.state {
+            BuildState::Build(_) => "B!".to_string(),
+            BuildState::Repair(_) => "BR".to_string(),
+            BuildState::Pickup(state) => format!("B←{}", target_code(state.ticket.target())),
+            BuildState::Harvest(_) => "Bh".to_string(),
+            BuildState::Wait(_) => "Bz".to_string(),
+            _ => "B?".to_string(),
+        };
+
+        Some(code)
+    }
+
     fn pre_run_job(&mut self, system_data: &JobExecutionSystemData, runtime_data: &mut JobExecutionRuntimeData) {
         self.state.gather_data(system_data, runtime_data);
     }
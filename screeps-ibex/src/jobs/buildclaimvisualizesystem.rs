@@ -0,0 +1,66 @@
+use super::utility::buildbehavior::*;
+use crate::creep::CreepOwner;
+use crate::visualize::*;
+use screeps::*;
+use specs::prelude::*;
+
+// ---------------------------------------------------------------------------
+// BuildClaimVisualizeSystem — which builders hold each construction site
+// ---------------------------------------------------------------------------
+
+const COLOR_CLAIM: &str = "#e3b341";
+
+#[derive(SystemData)]
+pub struct BuildClaimVisualizeSystemData<'a> {
+    creep_owners: ReadStorage<'a, CreepOwner>,
+    build_claims: Read<'a, BuildClaims>,
+    visualizer: Option<Write<'a, Visualizer>>,
+    features: Read<'a, crate::features::Features>,
+}
+
+/// Labels each claimed construction site with the names of the builders
+/// working it.
+///
+/// Only runs when the `construction.visualize.on` feature flag is enabled.
+pub struct BuildClaimVisualizeSystem;
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+impl<'a> System<'a> for BuildClaimVisualizeSystem {
+    type SystemData = BuildClaimVisualizeSystemData<'a>;
+
+    fn run(&mut self, mut data: Self::SystemData) {
+        if !data.features.construction.visualize.on {
+            return;
+        }
+
+        let Some(visualizer) = data.visualizer.as_deref_mut() else {
+            return;
+        };
+
+        let style = TextStyle::default()
+            .color(COLOR_CLAIM)
+            .font(0.4)
+            .stroke("#000000")
+            .stroke_width(0.05);
+
+        for (pos, builders) in data.build_claims.iter() {
+            let names: Vec<String> = builders
+                .iter()
+                .filter_map(|builder| data.creep_owners.get(*builder))
+                .filter_map(|owner| owner.owner.resolve())
+                .map(|creep| creep.name())
+                .collect();
+
+            if names.is_empty() {
+                continue;
+            }
+
+            visualizer.get_room(pos.room_name(), VisualLayer::Creeps).text(
+                pos.x().u8() as f32,
+                pos.y().u8() as f32 - 0.6,
+                names.join(", "),
+                Some(style.clone()),
+            );
+        }
+    }
+}
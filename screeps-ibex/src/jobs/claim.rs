@@ -3,7 +3,9 @@ use super::context::*;
 use super::jobsystem::*;
 use super::utility::controllerbehavior::*;
 use super::utility::movebehavior::*;
+use super::utility::saybehavior::*;
 use super::utility::waitbehavior::*;
+use crate::features::SignPurpose;
 use crate::remoteobjectid::*;
 use screeps::*;
 use screeps_machine::*;
@@ -19,7 +21,9 @@ machine!(
     enum ClaimState {
         MoveToController,
         ClaimController,
-        Wait { ticks: u32 }
+        Wait { ticks: u32 },
+        // Appended: saved jobs encode the state by position.
+        SignController
     }
 
     impl {
@@ -44,11 +48,19 @@ impl MoveToController {
             state_context.claim_target.pos().into(),
             1,
             None,
-            ClaimState::claim_controller,
+            ClaimState::sign_controller,
         )
     }
 }
 
+impl SignController {
+    fn tick(&mut self, state_context: &mut ClaimJobContext, tick_context: &mut JobTickContext) -> Option<ClaimState> {
+        let message = tick_context.system_data.signs.message(SignPurpose::Owned);
+
+        tick_sign(tick_context, state_context.claim_target, message, ClaimState::claim_controller)
+    }
+}
+
 impl ClaimController {
     fn tick(&mut self, state_context: &mut ClaimJobContext, tick_context: &mut JobTickContext) -> Option<ClaimState> {
         tick_claim(tick_context, state_context.claim_target, || ClaimState::wait(5))
@@ -86,6 +98,17 @@ impl Job for ClaimJob {
         crate::visualization::SummaryContent::Text(format!("Claim - {}", self.state.status_description()))
     }
 
+    fn say_code(&self, _system_data: &JobExecutionSystemData, _runtime_data: &JobExecutionRuntimeData) -> Option<String> {
+        let code = match &self.state {
+            ClaimState::ClaimController(_) => "Cl!",
+            ClaimState::MoveToController(_) => "Cl»",
+            ClaimState::SignController(_) => "Cl✎",
+            _ => "Clz",
+        };
+
+        Some(code.to_string())
+    }
+
     fn pre_run_job(&mut self, system_data: &JobExecutionSystemData, runtime_data: &mut JobExecutionRuntimeData) {
         self.state.gather_data(system_data, runtime_data);
     }
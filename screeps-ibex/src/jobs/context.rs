@@ -1,8 +1,89 @@
 use super::actions::*;
 use super::jobsystem::*;
+use super::utility::haulbehavior::*;
+use super::utility::movebehavior::*;
+use crate::transfer::transfersystem::*;
+use screeps::*;
+use screeps_rover::*;
 
 pub struct JobTickContext<'a, 'b, 'c> {
     pub system_data: &'a JobExecutionSystemData<'b>,
     pub runtime_data: &'a mut JobExecutionRuntimeData<'c>,
     pub action_flags: SimultaneousActionFlags,
 }
+
+/// Where a [`JobTickContext::move_to_cached`] walk stands this tick.
+pub enum MoveOutcome {
+    /// Within range of the target; no move was requested.
+    Arrived,
+    /// A move was requested, or the move slot was already spent this tick.
+    Moving,
+    /// The rover gave up on last tick's move. No move was requested, so the
+    /// job picks what to do next.
+    Failed(MovementFailure),
+}
+
+impl JobTickContext<'_, '_, '_> {
+    /// Walk toward `target` until within `range`.
+    ///
+    /// The rover keeps a creep's path for as long as its requests name the
+    /// same destination, so a job that asks for the same tile every tick
+    /// walks one cached path instead of repathing. A stuck creep is reported
+    /// as `Failed` once the rover's own recovery runs out, rather than being
+    /// asked to walk into the same block forever.
+    pub fn move_to_cached(&mut self, target: Position, range: u32) -> MoveOutcome {
+        if self.runtime_data.owner.pos().in_range_to(target, range) {
+            return MoveOutcome::Arrived;
+        }
+
+        if let Some(failure) = check_movement_failure(self) {
+            return MoveOutcome::Failed(failure);
+        }
+
+        if self.action_flags.consume(SimultaneousActionFlags::MOVE) {
+            self.runtime_data
+                .movement
+                .move_to(self.runtime_data.creep_entity, target)
+                .range(range);
+        }
+
+        MoveOutcome::Moving
+    }
+
+    /// Run a pickup ticket: walk to the target and withdraw. Moves to
+    /// `next_state` once the ticket is spent or invalid, and to `stuck_state`
+    /// when the walk there fails. Dropping the ticket releases its
+    /// reservation, as `gather_data` no longer registers it.
+    pub fn run_pickup_ticket<F, S, R>(&mut self, ticket: &mut TransferWithdrawTicket, next_state: F, stuck_state: S) -> Option<R>
+    where
+        F: FnOnce() -> R,
+        S: FnOnce() -> R,
+    {
+        if check_movement_failure(self).is_some() {
+            return Some(stuck_state());
+        }
+
+        tick_pickup(self, ticket, next_state)
+    }
+
+    /// Run delivery tickets in order, as [`run_pickup_ticket`](Self::run_pickup_ticket)
+    /// does for a pickup. The move bids the carried cargo's value on the
+    /// numeric priority lane (see `tick_delivery`).
+    pub fn run_delivery_ticket<F, S, R>(&mut self, tickets: &mut Vec<TransferDepositTicket>, next_state: F, stuck_state: S) -> Option<R>
+    where
+        F: Fn() -> R,
+        S: FnOnce() -> R,
+    {
+        if check_movement_failure(self).is_some() {
+            return Some(stuck_state());
+        }
+
+        tick_delivery(self, tickets, true, next_state)
+    }
+
+    /// Whether the creep has fewer than `ttl` ticks left. A creep still
+    /// spawning has no TTL and is never near death.
+    pub fn near_death(&self, ttl: u32) -> bool {
+        self.runtime_data.owner.ticks_to_live().is_some_and(|left| left < ttl)
+    }
+}
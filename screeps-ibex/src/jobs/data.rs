@@ -20,9 +20,30 @@ pub enum JobData {
     Dismantle(super::dismantle::DismantleJob),
     Declaim(super::declaim::DeclaimJob),
     SquadCombat(super::squad_combat::SquadCombatJob),
+    Recycle(super::recycle::RecycleJob),
 }
 
 impl JobData {
+    /// Stable type name of the concrete job (the variant name). Used as the
+    /// CPU accounting key.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            JobData::Harvest(_) => "Harvest",
+            JobData::Upgrade(_) => "Upgrade",
+            JobData::Build(_) => "Build",
+            JobData::StaticMine(_) => "StaticMine",
+            JobData::LinkMine(_) => "LinkMine",
+            JobData::Haul(_) => "Haul",
+            JobData::Scout(_) => "Scout",
+            JobData::Reserve(_) => "Reserve",
+            JobData::Claim(_) => "Claim",
+            JobData::Dismantle(_) => "Dismantle",
+            JobData::Declaim(_) => "Declaim",
+            JobData::SquadCombat(_) => "SquadCombat",
+            JobData::Recycle(_) => "Recycle",
+        }
+    }
+
     /// Dispatch summarize() to the concrete job type (read-only).
     pub fn summarize(&self) -> SummaryContent {
         match self {
@@ -38,6 +59,7 @@ impl JobData {
             JobData::Dismantle(ref data) => data.summarize(),
             JobData::Declaim(ref data) => data.summarize(),
             JobData::SquadCombat(ref data) => data.summarize(),
+            JobData::Recycle(ref data) => data.summarize(),
         }
     }
 
@@ -73,7 +95,8 @@ impl JobData {
             | JobData::Haul(_)
             | JobData::Scout(_)
             | JobData::Reserve(_)
-            | JobData::Claim(_) => false,
+            | JobData::Claim(_)
+            | JobData::Recycle(_) => false,
         }
     }
 
@@ -91,6 +114,7 @@ impl JobData {
             JobData::Dismantle(ref mut data) => data,
             JobData::Declaim(ref mut data) => data,
             JobData::SquadCombat(ref mut data) => data,
+            JobData::Recycle(ref mut data) => data,
         }
     }
 }
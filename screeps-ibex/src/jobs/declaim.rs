@@ -3,7 +3,9 @@ use super::context::*;
 use super::jobsystem::*;
 use super::utility::controllerbehavior::*;
 use super::utility::movebehavior::*;
+use super::utility::saybehavior::*;
 use super::utility::waitbehavior::*;
+use crate::features::SignPurpose;
 use crate::remoteobjectid::*;
 use screeps::*;
 use screeps_machine::*;
@@ -42,7 +44,11 @@ impl AttackController {
         // rejects the next attackController (engine-mechanics §2.12). A CLAIM
         // body lives only 600 ticks, so each body lands ~one strike then idles
         // out its life; `Wait` re-checks periodically so the moment the block
-        // clears (or the controller goes neutral) it acts.
+        // clears (or the controller goes neutral) it acts. Signing is a
+        // separate pipeline, so it rides along with the strike.
+        let message = tick_context.system_data.signs.message(SignPurpose::Attack);
+        sign_controller_if_stale(tick_context, state_context.declaim_target, message);
+
         tick_attack_controller(tick_context, state_context.declaim_target, || DeclaimState::wait(25))
     }
 }
@@ -78,6 +84,15 @@ impl Job for DeclaimJob {
         crate::visualization::SummaryContent::Text(format!("Declaim - {}", self.state.status_description()))
     }
 
+    fn say_code(&self, _system_data: &JobExecutionSystemData, _runtime_data: &JobExecutionRuntimeData) -> Option<String> {
+        let code = match &self.state {
+            DeclaimState::AttackController(_) => "Dc!",
+            _ => "Dcz",
+        };
+
+        Some(code.to_string())
+    }
+
     fn pre_run_job(&mut self, system_data: &JobExecutionSystemData, runtime_data: &mut JobExecutionRuntimeData) {
         self.state.gather_data(system_data, runtime_data);
     }
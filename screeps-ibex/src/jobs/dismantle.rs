@@ -4,6 +4,7 @@ use super::jobsystem::*;
 use super::utility::dismantlebehavior::*;
 use super::utility::haulbehavior::*;
 use super::utility::movebehavior::*;
+use super::utility::saybehavior::*;
 use super::utility::waitbehavior::*;
 use crate::structureidentifier::*;
 use crate::transfer::transfersystem::*;
@@ -216,6 +217,18 @@ impl Job for DismantleJob {
         crate::visualization::SummaryContent::Text(format!("Dismantle - {}", self.state.status_description()))
     }
 
+    fn say_code(&self, _system_data: &JobExecutionSystemData, _runtime_data: &JobExecutionRuntimeData) -> Option<String> {
+        let code = match &self.state {
+            DismantleState::Dismantle(_) => "D!".to_string(),
+            DismantleState::Delivery(state) => deposit_code("D→", &state.deposits),
+            DismantleState::MoveToRoom(state) => format!("D»{}", state.room_name),
+            DismantleState::Wait(_) => "Dz".to_string(),
+            _ => "D?".to_string(),
+        };
+
+        Some(code)
+    }
+
     fn pre_run_job(&mut self, system_data: &JobExecutionSystemData, runtime_data: &mut JobExecutionRuntimeData) {
         self.state.gather_data(system_data, runtime_data);
     }
@@ -6,8 +6,10 @@ use super::utility::controllerbehavior::*;
 use super::utility::harvestbehavior::*;
 use super::utility::haulbehavior::*;
 use super::utility::movebehavior::*;
+use super::utility::parkingbehavior::*;
 use super::utility::repair::*;
 use super::utility::repairbehavior::*;
+use super::utility::saybehavior::*;
 use super::utility::waitbehavior::*;
 use crate::remoteobjectid::*;
 use crate::structureidentifier::*;
@@ -57,7 +59,7 @@ machine!(
 
         Idle, Harvest, FinishedDelivery, Build, FinishedBuild, Repair, FinishedRepair, Upgrade, MoveToRoom, Wait, Flee => fn visualize(&self, _system_data: &JobExecutionSystemData, _describe_data: &mut JobDescribeData) {}
 
-        Idle, Harvest, FinishedDelivery, Build, FinishedBuild, Repair, FinishedRepair, Upgrade, MoveToRoom, Wait, Flee => fn gather_data(&self, _system_data: &JobExecutionSystemData, _runtime_data: &mut JobExecutionRuntimeData) {}
+        Idle, Harvest, FinishedDelivery, FinishedBuild, Repair, FinishedRepair, Upgrade, MoveToRoom, Flee => fn gather_data(&self, _system_data: &JobExecutionSystemData, _runtime_data: &mut JobExecutionRuntimeData) {}
 
         _ => fn tick(&mut self, state_context: &mut HarvestJobContext, tick_context: &mut JobTickContext) -> Option<HarvestState>;
     }
@@ -128,7 +130,15 @@ impl Idle {
         };
 
         if in_harvest_room && !in_delivery_room {
-            if let Some(state) = get_new_build_state(creep, harvest_room_data, HarvestState::build) {
+            if let Some(state) = get_new_build_state(
+                creep,
+                tick_context.runtime_data.creep_entity,
+                harvest_room_data,
+                tick_context.system_data.emergency_ramparts.site(harvest_room_data.name),
+                tick_context.runtime_data.build_claims,
+                tick_context.runtime_data.pathfinder,
+                HarvestState::build,
+            ) {
                 return Some(state);
             }
         }
@@ -173,7 +183,17 @@ impl Idle {
                 HarvestState::delivery,
             )
             .or_else(|| get_new_upgrade_state(creep, delivery_room_data, HarvestState::upgrade, Some(2)))
-            .or_else(|| get_new_build_state(creep, delivery_room_data, HarvestState::build))
+            .or_else(|| {
+                get_new_build_state(
+                    creep,
+                    tick_context.runtime_data.creep_entity,
+                    delivery_room_data,
+                    tick_context.system_data.emergency_ramparts.site(delivery_room_data.name),
+                    tick_context.runtime_data.build_claims,
+                    tick_context.runtime_data.pathfinder,
+                    HarvestState::build,
+                )
+            })
             .or_else(|| {
                 get_new_repair_state(
                     creep,
@@ -301,6 +321,10 @@ impl FinishedDelivery {
 }
 
 impl Build {
+    fn gather_data(&self, _system_data: &JobExecutionSystemData, runtime_data: &mut JobExecutionRuntimeData) {
+        runtime_data.build_claims.claim(self.target, runtime_data.creep_entity);
+    }
+
     fn tick(&mut self, _state_context: &mut HarvestJobContext, tick_context: &mut JobTickContext) -> Option<HarvestState> {
         tick_build(tick_context, self.target, HarvestState::finished_build)
     }
@@ -312,7 +336,16 @@ impl FinishedBuild {
 
         let creep = tick_context.runtime_data.owner;
 
-        get_new_build_state(creep, delivery_room_data, HarvestState::build).or(Some(HarvestState::idle()))
+        get_new_build_state(
+            creep,
+            tick_context.runtime_data.creep_entity,
+            delivery_room_data,
+            tick_context.system_data.emergency_ramparts.site(delivery_room_data.name),
+            tick_context.runtime_data.build_claims,
+            tick_context.runtime_data.pathfinder,
+            HarvestState::build,
+        )
+        .or(Some(HarvestState::idle()))
     }
 }
 
@@ -357,8 +390,12 @@ impl MoveToRoom {
 }
 
 impl Wait {
+    fn gather_data(&self, _system_data: &JobExecutionSystemData, runtime_data: &mut JobExecutionRuntimeData) {
+        runtime_data.parking.hold(runtime_data.creep_entity);
+    }
+
     fn tick(&mut self, _state_context: &mut HarvestJobContext, tick_context: &mut JobTickContext) -> Option<HarvestState> {
-        mark_idle(tick_context);
+        park_idle(tick_context);
         tick_wait(&mut self.ticks, HarvestState::idle)
     }
 }
@@ -397,6 +434,23 @@ impl Job for HarvestJob {
         crate::visualization::SummaryContent::Text(format!("Harvest - {}", self.state.status_description()))
     }
 
+    fn say_code(&self, _system_data: &JobExecutionSystemData, _runtime_data: &JobExecutionRuntimeData) -> Option<String> {
+        let code = match &self.state {
+            HarvestState::Harvest(_) => "h!".to_string(),
+            HarvestState::Pickup(state) => format!("h←{}", target_code(state.withdrawl.target())),
+            HarvestState::Delivery(state) => deposit_code("h→", &state.deposits),
+            HarvestState::Build(_) => "hB".to_string(),
+            HarvestState::Repair(_) => "hR".to_string(),
+            HarvestState::Upgrade(_) => "hU".to_string(),
+            HarvestState::MoveToRoom(state) => format!("h»{}", state.room_name),
+            HarvestState::Wait(_) => "hz".to_string(),
+            HarvestState::Flee(_) => "h⚠".to_string(),
+            _ => "h?".to_string(),
+        };
+
+        Some(code)
+    }
+
     fn pre_run_job(&mut self, system_data: &JobExecutionSystemData, runtime_data: &mut JobExecutionRuntimeData) {
         self.state.gather_data(system_data, runtime_data);
     }
@@ -1,10 +1,13 @@
 use super::actions::*;
 use super::context::*;
 use super::jobsystem::*;
+use super::utility::buildbehavior::*;
 use super::utility::haulbehavior::*;
 use super::utility::movebehavior::*;
+use super::utility::parkingbehavior::*;
 use super::utility::repair::*;
 use super::utility::repairbehavior::*;
+use super::utility::saybehavior::*;
 use super::utility::waitbehavior::*;
 use crate::serialize::*;
 use crate::transfer::transfersystem::*;
@@ -17,6 +20,14 @@ use specs::error::NoError;
 use specs::saveload::*;
 use specs::*;
 
+/// A hauler with fewer ticks left takes no new pickups: the round trip would
+/// outlive it and the cargo would drop wherever it dies.
+const MIN_PICKUP_TTL: u32 = 100;
+
+/// Ticks to stand aside after a walk fails before picking new tickets, so
+/// the jam can clear.
+const STUCK_WAIT_TICKS: u32 = 3;
+
 #[derive(Clone, ConvertSaveload)]
 pub struct HaulJobContext {
     pickup_rooms: EntityVec<Entity>,
@@ -47,7 +58,7 @@ machine!(
 
         Idle, MoveToRoom, Wait, Flee => fn visualize(&self, _system_data: &JobExecutionSystemData, _describe_data: &mut JobDescribeData) {}
 
-        Idle, MoveToRoom, Wait, Flee => fn gather_data(&self, _system_data: &JobExecutionSystemData, _runtime_data: &mut JobExecutionRuntimeData) {}
+        Idle, MoveToRoom, Flee => fn gather_data(&self, _system_data: &JobExecutionSystemData, _runtime_data: &mut JobExecutionRuntimeData) {}
 
         _ => fn tick(&mut self, state_context: &mut HaulJobContext, tick_context: &mut JobTickContext) -> Option<HaulState>;
     }
@@ -59,7 +70,14 @@ impl Idle {
         if is_threatened(tick_context) {
             return Some(HaulState::flee());
         }
+        // A new trip may take another opportunistic pickup.
+        tick_context
+            .runtime_data
+            .opportunistic_pickups
+            .begin_trip(tick_context.runtime_data.creep_entity);
+
         let creep = tick_context.runtime_data.owner;
+        let near_death = tick_context.near_death(MIN_PICKUP_TTL);
         let pickup_rooms = state_context
             .pickup_rooms
             .iter()
@@ -106,6 +124,10 @@ impl Idle {
             )
         })
         .or_else(|| {
+            if near_death {
+                return None;
+            }
+
             let transfer_queue_data = TransferQueueGeneratorData {
                 cause: "Haul Idle",
                 room_data: tick_context.system_data.room_data,
@@ -156,6 +178,18 @@ impl Pickup {
         if is_threatened(tick_context) {
             return Some(HaulState::flee());
         }
+        let delivery_rooms = state_context
+            .delivery_rooms
+            .iter()
+            .filter_map(|e| tick_context.system_data.room_data.get(*e))
+            .collect_vec();
+
+        let target_filter = if state_context.storage_delivery_only {
+            target_filters::storage
+        } else {
+            target_filters::all
+        };
+
         //
         // NOTE: All haulers run this at the same time so that transfer data is only hydrated on this tick.
         //
@@ -168,12 +202,6 @@ impl Pickup {
                 room_data: tick_context.system_data.room_data,
             };
 
-            let delivery_rooms = state_context
-                .delivery_rooms
-                .iter()
-                .filter_map(|e| tick_context.system_data.room_data.get(*e))
-                .collect_vec();
-
             // get_used_capacity(None) is a memoized single sum in the current
             // engine -- safe on general stores (engine-mechanics folklore row 26).
             let free_capacity = creep.store().get_free_capacity(None).max(0) as u32;
@@ -186,12 +214,6 @@ impl Pickup {
                 }
             }
 
-            let target_filter = if state_context.storage_delivery_only {
-                target_filters::storage
-            } else {
-                target_filters::all
-            };
-
             get_additional_deliveries(
                 &transfer_queue_data,
                 &delivery_rooms,
@@ -206,9 +228,24 @@ impl Pickup {
             );
         }
 
+        let reserved = self.withdrawl.resources().values().flatten().map(|entry| entry.amount()).sum();
+
+        tick_opportunistic_pickup(
+            tick_context,
+            &delivery_rooms,
+            reserved,
+            Some(self.withdrawl.target()),
+            &mut self.deposits,
+            target_filter,
+        );
+
         let deposits = &self.deposits;
 
-        tick_pickup(tick_context, &mut self.withdrawl, move || HaulState::delivery(deposits.clone()))
+        tick_context.run_pickup_ticket(
+            &mut self.withdrawl,
+            move || HaulState::delivery(deposits.clone()),
+            || HaulState::wait(STUCK_WAIT_TICKS),
+        )
     }
 }
 
@@ -227,13 +264,30 @@ impl Delivery {
             return Some(HaulState::flee());
         }
         if state_context.allow_repair {
-            if let Some(consumed_energy) = tick_opportunistic_repair(tick_context, Some(RepairPriority::Low)) {
+            let consumed_energy =
+                tick_opportunistic_repair(tick_context, Some(RepairPriority::Low)).or_else(|| tick_opportunistic_road_build(tick_context));
+
+            if let Some(consumed_energy) = consumed_energy {
                 consume_resource_from_deposits(&mut self.deposits, ResourceType::Energy, consumed_energy);
             }
         }
 
+        let delivery_rooms = state_context
+            .delivery_rooms
+            .iter()
+            .filter_map(|e| tick_context.system_data.room_data.get(*e))
+            .collect_vec();
+
+        let target_filter = if state_context.storage_delivery_only {
+            target_filters::storage
+        } else {
+            target_filters::all
+        };
+
+        tick_opportunistic_pickup(tick_context, &delivery_rooms, 0, None, &mut self.deposits, target_filter);
+
         // Civilian: the delivery leg bids its carried-cargo rate on the numeric lane (decision (4)).
-        tick_delivery(tick_context, &mut self.deposits, true, HaulState::idle)
+        tick_context.run_delivery_ticket(&mut self.deposits, HaulState::idle, || HaulState::wait(STUCK_WAIT_TICKS))
     }
 }
 
@@ -244,7 +298,9 @@ impl MoveToRoom {
             return Some(HaulState::flee());
         }
         if state_context.allow_repair {
-            tick_opportunistic_repair(tick_context, Some(RepairPriority::Low));
+            if tick_opportunistic_repair(tick_context, Some(RepairPriority::Low)).is_none() {
+                tick_opportunistic_road_build(tick_context);
+            }
         }
 
         tick_move_to_room(tick_context, self.room_name, None, HaulState::idle)
@@ -252,11 +308,15 @@ impl MoveToRoom {
 }
 
 impl Wait {
+    fn gather_data(&self, _system_data: &JobExecutionSystemData, runtime_data: &mut JobExecutionRuntimeData) {
+        runtime_data.parking.hold(runtime_data.creep_entity);
+    }
+
     pub fn tick(&mut self, _state_context: &HaulJobContext, tick_context: &mut JobTickContext) -> Option<HaulState> {
         if is_threatened(tick_context) {
             return Some(HaulState::flee());
         }
-        mark_idle(tick_context);
+        park_idle(tick_context);
         tick_wait(&mut self.ticks, HaulState::idle)
     }
 }
@@ -291,6 +351,22 @@ impl HaulJob {
             state: HaulState::idle(),
         }
     }
+
+    /// Whether the job already hauls between exactly these rooms.
+    pub fn serves(&self, pickup_rooms: &[Entity], delivery_rooms: &[Entity]) -> bool {
+        self.context.pickup_rooms.as_slice() == pickup_rooms && self.context.delivery_rooms.as_slice() == delivery_rooms
+    }
+
+    /// Point the job at new rooms. Tickets already held are finished first;
+    /// the new rooms apply from the next trip.
+    pub fn reassign(&mut self, pickup_rooms: &[Entity], delivery_rooms: &[Entity], allow_repair: bool, storage_delivery_only: bool) {
+        self.context = HaulJobContext {
+            pickup_rooms: pickup_rooms.into(),
+            delivery_rooms: delivery_rooms.into(),
+            allow_repair,
+            storage_delivery_only,
+        };
+    }
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -299,6 +375,19 @@ impl Job for HaulJob {
         crate::visualization::SummaryContent::Text(format!("Haul - {}", self.state.status_description()))
     }
 
+    fn say_code(&self, _system_data: &JobExecutionSystemData, _runtime_data: &JobExecutionRuntimeData) -> Option<String> {
+        let code = match &self.state {
+            HaulState::Pickup(state) => format!("H←{}", target_code(state.withdrawl.target())),
+            HaulState::Delivery(state) => deposit_code("H→", &state.deposits),
+            HaulState::Wait(_) => "Hz".to_string(),
+            HaulState::MoveToRoom(state) => format!("H»{}", state.room_name),
+            HaulState::Flee(_) => "H⚠".to_string(),
+            _ => "H?".to_string(),
+        };
+
+        Some(code)
+    }
+
     fn pre_run_job(&mut self, system_data: &JobExecutionSystemData, runtime_data: &mut JobExecutionRuntimeData) {
         self.state.gather_data(system_data, runtime_data);
     }
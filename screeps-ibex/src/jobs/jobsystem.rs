@@ -1,9 +1,20 @@
 use super::data::JobData;
+use super::utility::buildbehavior::BuildClaims;
+use super::utility::controllerbehavior::SignCooldowns;
 use super::utility::dismantlebehavior::BreachPlanCache;
+use super::utility::haulbehavior::OpportunisticPickups;
+use super::utility::parkingbehavior::ParkingRegistry;
+use super::utility::saybehavior::say_job_code;
 use crate::creep::CreepOwner;
 use crate::entitymappingsystem::*;
+use crate::features::{FeatureOverrides, Features, SignFeatures};
 use crate::intents::IntentRecorder;
-use crate::military::squad::SquadContext;
+use crate::military::boostqueue::BoostQueue;
+use crate::military::squad::{SquadContext, SquadOrders};
+use crate::military::threatmap::BreachForecasts;
+use crate::military::wartargets::WarTargets;
+use crate::missions::upgrade::UpgradeSeating;
+use crate::missions::wall_repair::EmergencyRamparts;
 use crate::pathing::pathfinderservice::PathfinderService;
 use crate::repairqueue::RepairQueue;
 use crate::room::data::*;
@@ -26,11 +37,26 @@ pub struct JobSystemData<'a> {
     movement_results: ReadExpect<'a, MovementResults<Entity>>,
     mapping: Read<'a, EntityMappingData>,
     squad_contexts: WriteStorage<'a, SquadContext>,
+    squad_orders: Read<'a, SquadOrders>,
+    upgrade_seating: Read<'a, UpgradeSeating>,
+    emergency_ramparts: Read<'a, EmergencyRamparts>,
     repair_queue: Read<'a, RepairQueue>,
+    signs: Read<'a, SignFeatures>,
+    war_targets: Read<'a, WarTargets>,
+    breach_forecasts: Read<'a, BreachForecasts>,
     visibility_queue: Write<'a, VisibilityQueue>,
     pathfinder: Write<'a, PathfinderService>,
     intent_recorder: Write<'a, IntentRecorder>,
     breach_cache: Write<'a, BreachPlanCache>,
+    sign_cooldowns: Write<'a, SignCooldowns>,
+    build_claims: Write<'a, BuildClaims>,
+    parking: Write<'a, ParkingRegistry>,
+    opportunistic_pickups: Write<'a, OpportunisticPickups>,
+    boost_queue: Write<'a, BoostQueue>,
+    ledger: Write<'a, crate::ledger::ResourceLedger>,
+    cpu_accounting: Write<'a, crate::cpu_accounting::CpuAccounting>,
+    features: Read<'a, Features>,
+    feature_overrides: Read<'a, FeatureOverrides>,
 }
 
 pub struct JobExecutionSystemData<'a> {
@@ -38,7 +64,19 @@ pub struct JobExecutionSystemData<'a> {
     pub entities: &'a Entities<'a>,
     pub room_data: &'a ReadStorage<'a, RoomData>,
     pub squad_contexts: &'a WriteStorage<'a, SquadContext>,
+    /// This tick's squad member orders, from the squad manager.
+    pub squad_orders: &'a SquadOrders,
+    /// This tick's upgrader seats, from the upgrade missions.
+    pub upgrade_seating: &'a UpgradeSeating,
+    /// This tick's emergency rampart per breached room, from the wall repair missions.
+    pub emergency_ramparts: &'a EmergencyRamparts,
     pub repair_queue: &'a RepairQueue,
+    /// Controller sign text per purpose.
+    pub signs: &'a SignFeatures,
+    /// Rooms standing down after a cease-fire; a retired squad's members there walk home even under fire.
+    pub war_targets: &'a WarTargets,
+    /// Likely breach paths into besieged rooms, from the wall repair missions; melee defenders hold them.
+    pub breach_forecasts: &'a BreachForecasts,
 }
 
 pub struct JobExecutionRuntimeData<'a> {
@@ -52,6 +90,16 @@ pub struct JobExecutionRuntimeData<'a> {
     pub pathfinder: &'a mut PathfinderService,
     pub intent_recorder: &'a mut IntentRecorder,
     pub breach_cache: &'a mut BreachPlanCache,
+    pub sign_cooldowns: &'a mut SignCooldowns,
+    /// This tick's construction site claims, renewed in `pre_run_job`.
+    pub build_claims: &'a mut BuildClaims,
+    /// Idle parking spots, renewed in `pre_run_job` like `build_claims`.
+    pub parking: &'a mut ParkingRegistry,
+    /// Haulers that took their opportunistic pickup this trip.
+    pub opportunistic_pickups: &'a mut OpportunisticPickups,
+    /// Unboost requests of boosted creeps on their way to be recycled.
+    pub boost_queue: &'a mut BoostQueue,
+    pub ledger: &'a mut crate::ledger::ResourceLedger,
 }
 
 pub struct JobDescribeData<'a> {
@@ -71,6 +119,13 @@ pub trait Job {
     fn pre_run_job(&mut self, _system_data: &JobExecutionSystemData, _runtime_data: &mut JobExecutionRuntimeData) {}
 
     fn run_job(&mut self, system_data: &JobExecutionSystemData, runtime_data: &mut JobExecutionRuntimeData);
+
+    /// Compact code for what the job is doing, said by the creep when the
+    /// `visualize.say` debug mode is on for its room, e.g. `H→S` or `M!`.
+    /// Cut to fit by `say_text`.
+    fn say_code(&self, _system_data: &JobExecutionSystemData, _runtime_data: &JobExecutionRuntimeData) -> Option<String> {
+        None
+    }
 }
 
 pub struct PreRunJobSystem;
@@ -80,12 +135,27 @@ impl<'a> System<'a> for PreRunJobSystem {
     type SystemData = JobSystemData<'a>;
 
     fn run(&mut self, mut data: Self::SystemData) {
+        // Claims and parking spots are renewed by the jobs still building or
+        // idling.
+        data.build_claims.clear();
+        data.parking.begin_tick();
+
+        // Creeps that died mid-trip are forgotten.
+        let entities = &data.entities;
+        data.opportunistic_pickups.retain(|creep| entities.is_alive(creep));
+
         let system_data = JobExecutionSystemData {
             updater: &data.updater,
             entities: &data.entities,
             room_data: &data.room_data,
             squad_contexts: &data.squad_contexts,
+            squad_orders: &data.squad_orders,
+            upgrade_seating: &data.upgrade_seating,
+            emergency_ramparts: &data.emergency_ramparts,
             repair_queue: &data.repair_queue,
+            signs: &data.signs,
+            war_targets: &data.war_targets,
+            breach_forecasts: &data.breach_forecasts,
         };
 
         for (creep_entity, creep, job_data) in (&data.entities, &data.creep_owners, &mut data.jobs).join() {
@@ -101,9 +171,17 @@ impl<'a> System<'a> for PreRunJobSystem {
                     pathfinder: &mut data.pathfinder,
                     intent_recorder: &mut data.intent_recorder,
                     breach_cache: &mut data.breach_cache,
+                    sign_cooldowns: &mut data.sign_cooldowns,
+                    build_claims: &mut data.build_claims,
+                    parking: &mut data.parking,
+                    opportunistic_pickups: &mut data.opportunistic_pickups,
+                    boost_queue: &mut data.boost_queue,
+                    ledger: &mut data.ledger,
                 };
 
+                let cpu_start = data.cpu_accounting.start();
                 job_data.as_job().pre_run_job(&system_data, &mut runtime_data);
+                data.cpu_accounting.finish_job(cpu_start, job_data.type_name());
             }
         }
     }
@@ -116,12 +194,22 @@ impl<'a> System<'a> for RunJobSystem {
     type SystemData = JobSystemData<'a>;
 
     fn run(&mut self, mut data: Self::SystemData) {
+        // The labs served last tick's unboost requests; the creeps still
+        // boosted ask again.
+        data.boost_queue.renew_unboost_requests();
+
         let system_data = JobExecutionSystemData {
             updater: &data.updater,
             entities: &data.entities,
             room_data: &data.room_data,
             squad_contexts: &data.squad_contexts,
+            squad_orders: &data.squad_orders,
+            upgrade_seating: &data.upgrade_seating,
+            emergency_ramparts: &data.emergency_ramparts,
             repair_queue: &data.repair_queue,
+            signs: &data.signs,
+            war_targets: &data.war_targets,
+            breach_forecasts: &data.breach_forecasts,
         };
 
         for (creep_entity, creep, job_data) in (&data.entities, &data.creep_owners, &mut data.jobs).join() {
@@ -137,9 +225,27 @@ impl<'a> System<'a> for RunJobSystem {
                     pathfinder: &mut data.pathfinder,
                     intent_recorder: &mut data.intent_recorder,
                     breach_cache: &mut data.breach_cache,
+                    sign_cooldowns: &mut data.sign_cooldowns,
+                    build_claims: &mut data.build_claims,
+                    parking: &mut data.parking,
+                    opportunistic_pickups: &mut data.opportunistic_pickups,
+                    boost_queue: &mut data.boost_queue,
+                    ledger: &mut data.ledger,
                 };
 
+                let cpu_start = data.cpu_accounting.start();
                 job_data.as_job().run_job(&system_data, &mut runtime_data);
+
+                if data
+                    .features
+                    .for_room(&data.feature_overrides, owner.pos().room_name())
+                    .visualize
+                    .say
+                {
+                    say_job_code(job_data.as_job(), &system_data, &mut runtime_data);
+                }
+
+                data.cpu_accounting.finish_job(cpu_start, job_data.type_name());
             }
         }
     }
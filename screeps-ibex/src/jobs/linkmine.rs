@@ -4,6 +4,7 @@ use super::jobsystem::*;
 use super::utility::harvestbehavior::*;
 use super::utility::haulbehavior::*;
 use super::utility::movebehavior::{self, *};
+use super::utility::saybehavior::*;
 use super::utility::waitbehavior::*;
 use crate::remoteobjectid::*;
 use crate::transfer::transfersystem::*;
@@ -320,6 +321,20 @@ impl Job for LinkMineJob {
         crate::visualization::SummaryContent::Text(format!("LinkMine - {}", self.state.status_description()))
     }
 
+    fn say_code(&self, _system_data: &JobExecutionSystemData, _runtime_data: &JobExecutionRuntimeData) -> Option<String> {
+        let code = match &self.state {
+            LinkMineState::Harvest(_) => "LM!",
+            LinkMineState::DepositLink(_) => "LM→L",
+            LinkMineState::DepositContainer(_) => "LM→C",
+            LinkMineState::MoveToPosition(_) => "LM»",
+            LinkMineState::Wait(_) => "LMz",
+            LinkMineState::Flee(_) => "LM⚠",
+            _ => "LM?",
+        };
+
+        Some(code.to_string())
+    }
+
     fn pre_run_job(&mut self, system_data: &JobExecutionSystemData, runtime_data: &mut JobExecutionRuntimeData) {
         self.state.gather_data(system_data, runtime_data);
     }
@@ -1,5 +1,6 @@
 pub mod actions;
 pub mod build;
+pub mod buildclaimvisualizesystem;
 pub mod claim;
 pub mod context;
 pub mod data;
@@ -9,6 +10,7 @@ pub mod harvest;
 pub mod haul;
 pub mod jobsystem;
 pub mod linkmine;
+pub mod recycle;
 pub mod reserve;
 pub mod scout;
 pub mod squad_combat;
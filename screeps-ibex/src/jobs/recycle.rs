@@ -0,0 +1,173 @@
+use super::actions::*;
+use super::context::*;
+use super::jobsystem::*;
+use super::utility::movebehavior::*;
+use super::utility::saybehavior::*;
+use super::utility::unboostbehavior::*;
+use crate::intents::IntentCategory;
+use crate::remoteobjectid::*;
+use screeps::*;
+use screeps_machine::*;
+use serde::*;
+
+/// A creep its mission released (see `missions::missionsystem::release_creep`): it walks to the
+/// nearest owned spawn and is recycled there, returning part of its body cost. A boosted creep
+/// stops at a lab on the way to be unboosted (see `utility::unboostbehavior`).
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RecycleJobContext {
+    spawn_target: Option<RemoteObjectId<StructureSpawn>>,
+}
+
+machine!(
+    #[derive(Clone, Serialize, Deserialize)]
+    enum RecycleState {
+        PickSpawn,
+        MoveToSpawn,
+        Recycle,
+    }
+
+    impl {
+        * => fn describe(&self, _system_data: &JobExecutionSystemData, _describe_data: &mut JobDescribeData) {}
+
+        * => fn status_description(&self) -> String {
+            std::any::type_name::<Self>().to_string()
+        }
+
+        * => fn visualize(&self, _system_data: &JobExecutionSystemData, _describe_data: &mut JobDescribeData) {}
+
+        * => fn gather_data(&self, _system_data: &JobExecutionSystemData, _runtime_data: &mut JobExecutionRuntimeData) {}
+
+        _ => fn tick(&mut self, state_context: &mut RecycleJobContext, tick_context: &mut JobTickContext) -> Option<RecycleState>;
+    }
+);
+
+impl PickSpawn {
+    fn tick(&mut self, state_context: &mut RecycleJobContext, tick_context: &mut JobTickContext) -> Option<RecycleState> {
+        let creep = tick_context.runtime_data.owner;
+        let creep_pos = creep.pos();
+
+        match game::spawns()
+            .values()
+            .filter(|s| s.is_active())
+            .min_by_key(|s| creep_pos.get_range_to(s.pos()))
+        {
+            Some(spawn) => {
+                state_context.spawn_target = Some(spawn.remote_id());
+
+                Some(RecycleState::move_to_spawn())
+            }
+            None => {
+                // Nowhere to recycle; the creep has no further use.
+                let _ = creep.suicide();
+
+                None
+            }
+        }
+    }
+}
+
+impl MoveToSpawn {
+    fn tick(&mut self, state_context: &mut RecycleJobContext, tick_context: &mut JobTickContext) -> Option<RecycleState> {
+        let spawn_pos = match state_context.spawn_target {
+            Some(spawn) => spawn.pos(),
+            None => return Some(RecycleState::pick_spawn()),
+        };
+
+        if check_movement_failure(tick_context).is_some() {
+            state_context.spawn_target = None;
+
+            return Some(RecycleState::pick_spawn());
+        }
+
+        // A boosted creep has its boosts stripped at a lab on the way.
+        if tick_unboost(tick_context, spawn_pos.room_name()) {
+            return None;
+        }
+
+        tick_move_to_position(tick_context, spawn_pos.into(), 1, None, RecycleState::recycle)
+    }
+}
+
+impl Recycle {
+    fn tick(&mut self, state_context: &mut RecycleJobContext, tick_context: &mut JobTickContext) -> Option<RecycleState> {
+        let creep = tick_context.runtime_data.owner;
+
+        let spawn = match state_context.spawn_target.and_then(|s| s.resolve()) {
+            Some(spawn) => spawn,
+            None => {
+                state_context.spawn_target = None;
+
+                return Some(RecycleState::pick_spawn());
+            }
+        };
+
+        if !creep.pos().in_range_to(spawn.pos(), 1) {
+            return Some(RecycleState::move_to_spawn());
+        }
+
+        // Recycling doesn't need the spawn to be idle.
+        let _ = tick_context
+            .runtime_data
+            .intent_recorder
+            .issued(IntentCategory::Spawn, spawn.recycle_creep(creep));
+
+        mark_immovable(tick_context);
+
+        None
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RecycleJob {
+    context: RecycleJobContext,
+    state: RecycleState,
+}
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+impl RecycleJob {
+    pub fn new() -> RecycleJob {
+        RecycleJob {
+            context: RecycleJobContext { spawn_target: None },
+            state: RecycleState::pick_spawn(),
+        }
+    }
+}
+
+impl Default for RecycleJob {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+impl Job for RecycleJob {
+    fn summarize(&self) -> crate::visualization::SummaryContent {
+        crate::visualization::SummaryContent::Text(format!("Recycle - {}", self.state.status_description()))
+    }
+
+    fn say_code(&self, _system_data: &JobExecutionSystemData, _runtime_data: &JobExecutionRuntimeData) -> Option<String> {
+        let code = match &self.state {
+            RecycleState::MoveToSpawn(_) => "♻»",
+            RecycleState::Recycle(_) => "♻!",
+            _ => "♻?",
+        };
+
+        Some(code.to_string())
+    }
+
+    fn pre_run_job(&mut self, system_data: &JobExecutionSystemData, runtime_data: &mut JobExecutionRuntimeData) {
+        self.state.gather_data(system_data, runtime_data);
+    }
+
+    fn run_job(&mut self, system_data: &JobExecutionSystemData, runtime_data: &mut JobExecutionRuntimeData) {
+        let mut tick_context = JobTickContext {
+            system_data,
+            runtime_data,
+            action_flags: SimultaneousActionFlags::UNSET,
+        };
+
+        crate::machine_tick::run_state_machine(&mut self.state, "RecycleJob", |state| {
+            state.tick(&mut self.context, &mut tick_context)
+        });
+    }
+}
@@ -3,8 +3,9 @@ use super::context::*;
 use super::jobsystem::*;
 use super::utility::controllerbehavior::*;
 use super::utility::movebehavior::*;
+use super::utility::saybehavior::*;
 use super::utility::waitbehavior::*;
-use crate::constants::*;
+use crate::features::SignPurpose;
 use crate::remoteobjectid::*;
 use screeps::*;
 use screeps_machine::*;
@@ -59,10 +60,12 @@ impl ReserveController {
 
 impl SignController {
     pub fn tick(&mut self, state_context: &mut ReserveJobContext, tick_context: &mut JobTickContext) -> Option<ReserveState> {
+        let message = tick_context.system_data.signs.message(SignPurpose::Reserved);
+
         tick_sign(
             tick_context,
             state_context.reserve_target,
-            ROOM_SIGN,
+            message,
             ReserveState::reserve_controller,
         )
     }
@@ -99,6 +102,17 @@ impl Job for ReserveJob {
         crate::visualization::SummaryContent::Text(format!("Reserve - {}", self.state.status_description()))
     }
 
+    fn say_code(&self, _system_data: &JobExecutionSystemData, _runtime_data: &JobExecutionRuntimeData) -> Option<String> {
+        let code = match &self.state {
+            ReserveState::ReserveController(_) => "Rs!",
+            ReserveState::MoveToController(_) => "Rs»",
+            ReserveState::SignController(_) => "Rs✎",
+            _ => "Rsz",
+        };
+
+        Some(code.to_string())
+    }
+
     fn pre_run_job(&mut self, system_data: &JobExecutionSystemData, runtime_data: &mut JobExecutionRuntimeData) {
         self.state.gather_data(system_data, runtime_data);
     }
@@ -2,6 +2,7 @@ use super::actions::*;
 use super::context::*;
 use super::jobsystem::*;
 use super::utility::movebehavior::*;
+use super::utility::saybehavior::*;
 use crate::room::data::RoomDynamicVisibilityData;
 use crate::room::visibilitysystem::*;
 use log::*;
@@ -147,6 +148,16 @@ impl Job for ScoutJob {
         crate::visualization::SummaryContent::Text(format!("Scout -> {} - {}", target, self.state.status_description()))
     }
 
+    fn say_code(&self, _system_data: &JobExecutionSystemData, _runtime_data: &JobExecutionRuntimeData) -> Option<String> {
+        let code = match (&self.state, self.context.room_target) {
+            (ScoutState::MoveToRoom(_), Some(room)) => format!("Sc»{}", room),
+            (ScoutState::Idle(_), _) => "Scz".to_string(),
+            _ => "Sc?".to_string(),
+        };
+
+        Some(code)
+    }
+
     fn pre_run_job(&mut self, system_data: &JobExecutionSystemData, runtime_data: &mut JobExecutionRuntimeData) {
         // Claim maintenance: re-affirm or clear stale claims.
         if let Some(room_target) = self.context.room_target {
@@ -2,7 +2,12 @@ use super::actions::*;
 use super::context::*;
 use super::jobsystem::*;
 use super::utility::movebehavior::*;
+use super::utility::saybehavior::*;
+use super::utility::unboostbehavior::tick_unboost;
+use crate::intents::IntentCategory;
+use crate::military::escort::{ESCORT_FOLLOW_RANGE, ESCORT_LEASH_RANGE};
 use crate::military::formation::virtual_anchor_target;
+use crate::military::rampartdefense::{hostiles_fleeing, RampartIndex, Tile};
 use crate::military::squad::*;
 use crate::visualization::SummaryContent;
 use screeps::*;
@@ -10,6 +15,7 @@ use screeps_machine::*;
 use screeps_rover::*;
 use serde::*;
 use specs::Entity;
+use std::collections::HashSet;
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct SquadCombatJobContext {
@@ -20,11 +26,20 @@ pub struct SquadCombatJobContext {
     /// Tick when we entered the combat response state (for timeout).
     #[serde(default)]
     combat_response_start: Option<u32>,
+    /// Compounds to take at the labs before travelling (a boosted defense member).
+    #[serde(default)]
+    boosts: Vec<ResourceType>,
+    /// Tick the member started waiting on the labs (for timeout).
+    #[serde(default)]
+    boost_started: Option<u32>,
 }
 
 /// Maximum ticks to spend in combat response before resuming objective.
 const COMBAT_RESPONSE_TIMEOUT: u32 = 50;
 
+/// Maximum ticks a spawned member waits on the labs for its boosts before fighting unboosted.
+const BOOST_WAIT_TIMEOUT: u32 = 150;
+
 // The pure transition table of this state machine is mirrored in `screeps_combat_decision::squad_fsm`
 // (`next_state`, K2 / ADR 0028) — the canonical, unit-tested spec the offline lifecycle harness drives.
 // The `return Some(state)` decisions in each `*::tick` below MUST stay in step with that kernel; the ECS
@@ -43,7 +58,10 @@ machine!(
         /// At the objective room, actively fighting.
         Engaged,
         /// Withdrawing from combat due to low HP or squad retreat signal.
-        Retreating
+        Retreating,
+        /// Freshly spawned member of a boosted defense: taking its boosts at the home labs before it
+        /// travels. Gives up and fights unboosted if the labs aren't stocked in time.
+        Boosting
     }
 
     impl {
@@ -125,7 +143,7 @@ impl MoveToRoom {
         // the nearest home spawn and recycle, so a surplus/orphan is never stranded mid-travel on a room edge.
         if should_recall_to_recycle(state_context.squad_entity, creep_entity, tick_context) {
             let hostiles = get_hostile_creeps(creep_pos.room_name(), tick_context);
-            if hostiles.is_empty() {
+            if hostiles.is_empty() || standing_down(state_context.target_room, tick_context) {
                 Engaged::recall_to_recycle(creep, creep_pos, creep_entity, tick_context);
                 return None;
             }
@@ -134,8 +152,20 @@ impl MoveToRoom {
         // ADR 0027 v1.1 P2: a DECLAIM squad member that has reached the target room transitions to Engaged
         // (which runs the declaim drive — move-to-controller + strike). A declaimer carries no combat parts,
         // so it does not need the formation-assault path; reaching the room is enough to start striking.
-        if squad_attack_controller_pos(state_context.squad_entity, tick_context).is_some()
-            && creep_pos.room_name() == state_context.target_room
+        // While the target is in safe mode it holds next door instead (see `hold_out_of_safe_mode`).
+        if squad_attack_controller_pos(state_context.squad_entity, tick_context).is_some() {
+            if hold_out_of_safe_mode(state_context.target_room, tick_context) {
+                return None;
+            }
+            if creep_pos.room_name() == state_context.target_room {
+                return Some(SquadCombatState::engaged());
+            }
+        }
+
+        // A drain member works both sides of the exit, and a harass patrol or a caravan escort roams rooms
+        // other than the target room, so any of those orders is Engaged's to run wherever the member is.
+        if get_tick_orders(state_context.squad_entity, creep_entity, tick_context)
+            .is_some_and(|orders| matches!(orders.movement, TickMovement::Drain(_) | TickMovement::Patrol(_) | TickMovement::Follow(_)))
         {
             return Some(SquadCombatState::engaged());
         }
@@ -216,7 +246,7 @@ impl MoveToRoom {
                     // still gathering at a room boundary.
                     if creep_pos.room_name() == state_context.target_room {
                         let squad_ready = get_squad_state(state_context.squad_entity, tick_context)
-                            .map(|s| s >= SquadState::Moving)
+                            .map(|s| s >= SquadState::Moving && !s.is_falling_back())
                             .unwrap_or(true);
                         if squad_ready {
                             return Some(SquadCombatState::engaged());
@@ -247,9 +277,9 @@ impl CombatResponse {
         let creep_pos = creep.pos();
         let creep_entity = tick_context.runtime_data.creep_entity;
 
-        // Check squad retreat signal.
+        // Check squad retreat signal (a reinforcing squad is falling back too).
         if let Some(squad_state) = get_squad_state(state_context.squad_entity, tick_context) {
-            if squad_state == SquadState::Retreating {
+            if squad_state.is_falling_back() {
                 return Some(SquadCombatState::retreating());
             }
         }
@@ -394,6 +424,30 @@ impl CombatResponse {
                         .range(1)
                         .priority(MovementPriority::High);
                 }
+                TickMovement::Kite(pos) | TickMovement::Drain(pos) => {
+                    tick_context
+                        .runtime_data
+                        .movement
+                        .move_to(creep_entity, *pos)
+                        .range(0)
+                        .priority(MovementPriority::High);
+                }
+                TickMovement::Patrol(pos) => {
+                    tick_context
+                        .runtime_data
+                        .movement
+                        .move_to(creep_entity, *pos)
+                        .range(5)
+                        .priority(MovementPriority::High);
+                }
+                TickMovement::Follow(pos) => {
+                    tick_context
+                        .runtime_data
+                        .movement
+                        .move_to(creep_entity, *pos)
+                        .range(ESCORT_FOLLOW_RANGE)
+                        .priority(MovementPriority::High);
+                }
             }
         } else {
             Self::kite_toward_objective(tick_context, state_context);
@@ -429,18 +483,75 @@ impl Engaged {
         // Read tick orders from squad context if available.
         let tick_orders = get_tick_orders(state_context.squad_entity, creep_entity, tick_context);
 
-        // Check squad retreat signal.
+        // Check squad retreat signal (a reinforcing squad is falling back too).
         if let Some(squad_state) = get_squad_state(state_context.squad_entity, tick_context) {
-            if squad_state == SquadState::Retreating {
+            if squad_state.is_falling_back() {
                 return Some(SquadCombatState::retreating());
             }
         }
 
+        // Drain post: soak tower fire on the inside tile, heal on the outside one. Runs ahead of the
+        // low-HP retreat and the left-the-room check, which would undo the step out the manager ordered.
+        if let Some(TickMovement::Drain(post)) = tick_orders.as_ref().map(|orders| &orders.movement) {
+            let post = *post;
+            Self::execute_combat_via_seam(creep, creep_pos, tick_orders.as_ref(), tick_context);
+            tick_context
+                .runtime_data
+                .movement
+                .move_to(creep_entity, post)
+                .range(0)
+                .priority(MovementPriority::High);
+            return None;
+        }
+
         // Retreat if HP drops below 50%.
         if creep.hits() < creep.hits_max() / 2 {
             return Some(SquadCombatState::retreating());
         }
 
+        // Harass patrol: the manager rotates the squad through several rooms, so skip the left-the-room
+        // check. Close on the focus target when there is one, else walk the patrol waypoint.
+        if let Some(TickMovement::Patrol(waypoint)) = tick_orders.as_ref().map(|orders| &orders.movement) {
+            let waypoint = *waypoint;
+            Self::execute_combat_via_seam(creep, creep_pos, tick_orders.as_ref(), tick_context);
+            let chase = tick_orders.as_ref().and_then(|o| o.attack_target.as_ref()).and_then(|t| t.pos());
+            let (goal, range) = match chase {
+                Some(pos) if has_active_part(creep, Part::RangedAttack) => (pos, 3),
+                Some(pos) => (pos, 1),
+                None => (waypoint, 5),
+            };
+            tick_context
+                .runtime_data
+                .movement
+                .move_to(creep_entity, goal)
+                .range(range)
+                .priority(MovementPriority::High);
+            return None;
+        }
+
+        // Caravan escort: stay with the hauler wherever it walks, breaking off only to close on a focus
+        // target that is threatening it.
+        if let Some(TickMovement::Follow(escorted)) = tick_orders.as_ref().map(|orders| &orders.movement) {
+            let escorted = *escorted;
+            Self::execute_combat_via_seam(creep, creep_pos, tick_orders.as_ref(), tick_context);
+            let chase = tick_orders
+                .as_ref()
+                .and_then(|o| o.attack_target.as_ref())
+                .and_then(|t| t.pos())
+                .filter(|pos| pos.get_range_to(escorted) <= ESCORT_LEASH_RANGE);
+            let (goal, range) = match chase {
+                Some(pos) => (pos, 3),
+                None => (escorted, ESCORT_FOLLOW_RANGE),
+            };
+            tick_context
+                .runtime_data
+                .movement
+                .move_to(creep_entity, goal)
+                .range(range)
+                .priority(MovementPriority::High);
+            return None;
+        }
+
         // If we've left the target room, move back.
         if creep_pos.room_name() != state_context.target_room {
             return Some(SquadCombatState::move_to_room());
@@ -454,7 +565,9 @@ impl Engaged {
         // the cadence; see `declaiming` in squad_manager). Inert for every combat squad (returns early only
         // when the squad target is `AttackController`).
         if let Some(controller_pos) = squad_attack_controller_pos(state_context.squad_entity, tick_context) {
-            drive_declaim(controller_pos, tick_context);
+            if !hold_out_of_safe_mode(state_context.target_room, tick_context) {
+                drive_declaim(controller_pos, tick_context);
+            }
             return None;
         }
 
@@ -465,6 +578,27 @@ impl Engaged {
         // P2.H2). Movement stays below (it rides P2.M2).
         Self::execute_combat_via_seam(creep, creep_pos, tick_orders.as_ref(), tick_context);
 
+        // ── Rampart defense ──
+
+        // In our own rooms fight from the ramparts instead of chasing into the open, where boosted
+        // melee shreds defenders. A manager flee still wins; with no rampart in reach of a hostile, or
+        // the hostiles making for the edge, the normal movement below takes over.
+        let flee_ordered = matches!(tick_orders.as_ref().map(|orders| &orders.movement), Some(TickMovement::Flee));
+        if !flee_ordered {
+            if let Some(post) = rampart_post(creep, creep_pos, tick_context) {
+                if post == creep_pos {
+                    strike_from_rampart(creep, creep_pos, tick_context);
+                }
+                tick_context
+                    .runtime_data
+                    .movement
+                    .move_to(creep_entity, post)
+                    .range(0)
+                    .priority(MovementPriority::High);
+                return None;
+            }
+        }
+
         // ── Movement ──
 
         if let Some(ref orders) = tick_orders {
@@ -490,10 +624,21 @@ impl Engaged {
                         .range(1)
                         .priority(MovementPriority::High);
                 }
+                // Ranged-only kite step (or a drain post, normally handled above) chosen by the
+                // manager: one exact tile, overriding the formation anchor / decide_movement for this tick.
+                TickMovement::Kite(pos) | TickMovement::Drain(pos) => {
+                    tick_context
+                        .runtime_data
+                        .movement
+                        .move_to(creep_entity, *pos)
+                        .range(0)
+                        .priority(MovementPriority::High);
+                }
                 TickMovement::Flee => {
                     flee_from_hostiles(tick_context);
                 }
-                TickMovement::Hold => {}
+                // Handled above, ahead of the left-the-room check.
+                TickMovement::Hold | TickMovement::Patrol(_) | TickMovement::Follow(_) => {}
             }
         } else {
             Self::fallback_movement(creep, creep_pos, creep_entity, tick_context, state_context);
@@ -510,7 +655,7 @@ impl Engaged {
     // replaces the old inline `execute_*_with_orders` / `fallback_*` (attack + heal). Movement is
     // handled separately below and rides P2.M2.
     fn execute_combat_via_seam(creep: &Creep, creep_pos: Position, tick_orders: Option<&TickOrders>, tick_context: &mut JobTickContext) {
-        use crate::combat::{decide_combat, CombatView, CreepOrders, FocusTarget, SquadMovement, SquadStateDto};
+        use crate::combat::{decide_combat, CombatIntent, CombatView, CreepOrders, FocusTarget, SquadMovement, SquadStateDto};
 
         let room = creep_pos.room_name();
         let hostiles_raw = get_hostile_creeps(room, tick_context);
@@ -547,6 +692,17 @@ impl Engaged {
             decide_combat(&view)
         };
 
+        // The manager flags ranged members with 2+ hostiles in range 1-3: swap the single-target ranged
+        // attack for a mass attack (same RANGED_ATTACK pipeline, so at most one of the two fires).
+        let mass_attack = tick_orders.is_some_and(|o| o.ranged_mass_attack);
+        let intents: Vec<CombatIntent> = intents
+            .into_iter()
+            .map(|intent| match intent {
+                CombatIntent::RangedAttack { .. } if mass_attack => CombatIntent::RangedMassAttack,
+                other => other,
+            })
+            .collect();
+
         Self::translate_intents(creep, &intents, &structures_raw, tick_context);
     }
 
@@ -693,9 +849,10 @@ impl Engaged {
         // (ADR 0032 v2), and there is nothing to fight here. Rather than idling in place (the observed "stuck
         // on a room edge" scatter), recall to the nearest home spawn and recycle, reclaiming part of the body
         // energy. A LIVE squad's rostered member is never recalled — `should_recall_to_recycle` requires
-        // either an unresolvable squad or this creep being absent from its `members`.
+        // either an unresolvable squad or this creep being absent from its `members`. Under a cease-fire the
+        // recall goes ahead with hostiles about: the squad retreats rather than fighting on in place.
         let orphaned = should_recall_to_recycle(state_context.squad_entity, creep_entity, tick_context);
-        if orphaned && hostiles.is_empty() {
+        if orphaned && (hostiles.is_empty() || standing_down(state_context.target_room, tick_context)) {
             Self::recall_to_recycle(creep, creep_pos, creep_entity, tick_context);
             return;
         }
@@ -773,11 +930,21 @@ impl Engaged {
     }
 
     /// P-OBJ #23: send an orphaned squad creep home to recycle rather than letting it idle/scatter where
-    /// its squad was retired. Moves to the nearest of our spawns and, once adjacent, recycles (reclaiming
-    /// part of the body energy); if we somehow have no spawn at all, suicides rather than leaving a
-    /// permanently idle creep. Called only for a creep whose squad has vanished (see `fallback_movement`).
+    /// its squad was retired. Moves to the nearest of our spawns (by way of a lab to unboost, when that
+    /// recovers more) and, once adjacent, recycles (reclaiming part of the body energy); if we somehow
+    /// have no spawn at all, suicides rather than leaving a permanently idle creep. Called only for a
+    /// creep whose squad has vanished (see `fallback_movement`).
     fn recall_to_recycle(creep: &Creep, creep_pos: Position, creep_entity: Entity, tick_context: &mut JobTickContext) {
-        match game::spawns().values().min_by_key(|s| creep_pos.get_range_to(s.pos())) {
+        let spawn = game::spawns().values().min_by_key(|s| creep_pos.get_range_to(s.pos()));
+
+        // A boosted member has its boosts stripped at a lab on the way (`unboostbehavior`).
+        if let Some(spawn) = &spawn {
+            if tick_unboost(tick_context, spawn.pos().room_name()) {
+                return;
+            }
+        }
+
+        match spawn {
             Some(spawn) if creep_pos.get_range_to(spawn.pos()) > 1 => {
                 tick_context
                     .runtime_data
@@ -787,7 +954,7 @@ impl Engaged {
                     .priority(MovementPriority::Normal);
             }
             Some(spawn) => {
-                let _ = spawn.recycle_creep(creep);
+                let _ = tick_context.runtime_data.intent_recorder.issued(IntentCategory::Spawn, spawn.recycle_creep(creep));
             }
             None => {
                 let _ = creep.suicide();
@@ -817,7 +984,8 @@ impl Retreating {
         // Engaged), hitting the 20-transition guard and never actually
         // retreating. Stay retreating until the squad clears the signal (e.g.
         // the Lanchester re-engage band against an unwinnable target).
-        let squad_retreating = squad_state.map(|s| s == SquadState::Retreating).unwrap_or(false);
+        // A reinforcing squad holds its members back at the rally until it has re-formed.
+        let squad_retreating = squad_state.is_some_and(SquadState::is_falling_back);
         if !squad_retreating
             && (creep.hits() > creep.hits_max() * 4 / 5 || (squad_wants_engage && creep.hits() > creep.hits_max() * 3 / 5))
         {
@@ -901,6 +1069,72 @@ impl Retreating {
     }
 }
 
+// ─── Boosting ───────────────────────────────────────────────────────────────
+
+impl Boosting {
+    pub fn tick(&mut self, state_context: &mut SquadCombatJobContext, tick_context: &mut JobTickContext) -> Option<SquadCombatState> {
+        let creep = tick_context.runtime_data.owner;
+        if creep.spawning() {
+            return None;
+        }
+        let creep_pos = creep.pos();
+        let creep_entity = tick_context.runtime_data.creep_entity;
+        let now = game::time();
+        let started = *state_context.boost_started.get_or_insert(now);
+
+        // Compounds whose parts on this body are still unboosted.
+        let body = creep.body();
+        let pending: Vec<ResourceType> = crate::military::damage::DEFENDER_BOOSTS
+            .iter()
+            .filter(|(part, compound)| {
+                state_context.boosts.contains(compound) && body.iter().any(|p| p.part() == *part && p.boost().is_none())
+            })
+            .map(|(_, compound)| *compound)
+            .collect();
+
+        if pending.is_empty() || should_recall_to_recycle(state_context.squad_entity, creep_entity, tick_context) {
+            return Some(SquadCombatState::move_to_room());
+        }
+        if now.saturating_sub(started) > BOOST_WAIT_TIMEOUT {
+            log::info!(
+                "[SquadTrace] BOOST-TIMEOUT creep={:?} room={} pending={:?} (labs not stocked — engaging unboosted)",
+                creep_entity,
+                creep_pos.room_name(),
+                pending
+            );
+            return Some(SquadCombatState::move_to_room());
+        }
+
+        // The nearest lab here that holds enough of a pending compound, and the energy, for one part.
+        let lab = get_labs(creep_pos.room_name(), tick_context)
+            .into_iter()
+            .filter(|lab| {
+                let store = lab.store();
+                store.get_used_capacity(Some(ResourceType::Energy)) >= LAB_BOOST_ENERGY
+                    && pending.iter().any(|c| store.get_used_capacity(Some(*c)) >= LAB_BOOST_MINERAL)
+            })
+            .min_by_key(|lab| creep_pos.get_range_to(lab.pos()));
+
+        // Nothing stocked yet — wait for the haulers to load a lab.
+        let lab = lab?;
+
+        if creep_pos.is_near_to(lab.pos()) {
+            if let Err(err) = lab.boost_creep(creep, None) {
+                log::warn!("[SquadTrace] BOOST-FAILED creep={:?} lab={:?} err={:?}", creep_entity, lab.pos(), err);
+            }
+        } else {
+            tick_context
+                .runtime_data
+                .movement
+                .move_to(creep_entity, lab.pos())
+                .range(1)
+                .priority(MovementPriority::High);
+        }
+
+        None
+    }
+}
+
 // ─── Shared helpers ─────────────────────────────────────────────────────────
 
 /// Heal the best nearby target: prefer adjacent damaged squad member, then
@@ -1111,6 +1345,8 @@ impl SquadCombatJob {
                 target_room,
                 squad_entity: None,
                 combat_response_start: None,
+                boosts: Vec::new(),
+                boost_started: None,
             },
             state: SquadCombatState::move_to_room(),
         }
@@ -1122,11 +1358,22 @@ impl SquadCombatJob {
                 target_room,
                 squad_entity: Some(SquadRef::from_entity(squad_entity)),
                 combat_response_start: None,
+                boosts: Vec::new(),
+                boost_started: None,
             },
             state: SquadCombatState::move_to_room(),
         }
     }
 
+    /// Send the member to the labs for these boosts before it travels (a boosted defense). No-op when empty.
+    pub fn with_boosts(mut self, boosts: Vec<ResourceType>) -> SquadCombatJob {
+        if !boosts.is_empty() {
+            self.context.boosts = boosts;
+            self.state = SquadCombatState::boosting();
+        }
+        self
+    }
+
     /// ADR 0032 v2 / ADR 0027 — REBIND this creep's job to a new squad (the merge/transfer receiver) + its
     /// target room, and reset the FSM to MoveToRoom so the transferred creep re-gathers at the receiver's
     /// rally (the receiver owns rally/lease/renew — ADR 0027 line 277). This only rewrites the already-
@@ -1178,6 +1425,11 @@ fn should_recall_to_recycle(squad: Option<SquadRef>, creep_entity: Entity, tick_
     recall_decision(has_squad_ref, squad_resolves, creep_is_rostered)
 }
 
+/// Whether `target_room` is under a cease-fire (`military::wartargets`), so its retired squads withdraw.
+fn standing_down(target_room: RoomName, tick_context: &JobTickContext) -> bool {
+    tick_context.system_data.war_targets.is_standing_down(target_room)
+}
+
 /// ADR 0027 v1.1 P2: the controller TILE this squad must `attackController` (de-claim), if its objective is a
 /// `SquadTarget::AttackController`. `None` for every combat squad — so the declaim drive below is inert for
 /// all existing objectives. The position is read off the squad's shared `SquadContext.target` (set by the
@@ -1225,7 +1477,168 @@ fn drive_declaim(controller_pos: Position, tick_context: &mut JobTickContext) {
         let owned_or_reserved = controller.owner().is_some() || controller.reservation().is_some();
         let upgrade_blocked = controller.upgrade_blocked().unwrap_or(0) > 0;
         if owned_or_reserved && !upgrade_blocked && tick_context.action_flags.consume(SimultaneousActionFlags::ATTACK_CONTROLLER) {
-            let _ = creep.attack_controller(&controller);
+            let _ = tick_context.runtime_data.intent_recorder.issued(IntentCategory::Controller, creep.attack_controller(&controller));
+        }
+    }
+}
+
+/// A controller in safe mode can't be attacked, so a declaimer waits the timer out in a room next to the
+/// target rather than in reach of its towers: the neighbour it already stands in, else the exit room nearest
+/// it. Returns `true` while it holds.
+fn hold_out_of_safe_mode(target_room: RoomName, tick_context: &mut JobTickContext) -> bool {
+    let safe_mode = tick_context
+        .runtime_data
+        .mapping
+        .get_room(&target_room)
+        .and_then(|entity| tick_context.system_data.room_data.get(entity))
+        .and_then(|room_data| room_data.get_dynamic_visibility_data())
+        .is_some_and(|data| data.safe_mode_active());
+
+    if !safe_mode {
+        return false;
+    }
+
+    let creep_pos = tick_context.runtime_data.owner.pos();
+    let creep_room = creep_pos.room_name();
+    let neighbours: Vec<RoomName> = game::map::describe_exits(target_room).values().collect();
+
+    let holding_room = if neighbours.contains(&creep_room) {
+        creep_room
+    } else {
+        match neighbours
+            .iter()
+            .copied()
+            .min_by_key(|room| game::map::get_room_linear_distance(creep_room, *room, false))
+        {
+            Some(room) => room,
+            None => return false,
+        }
+    };
+
+    let holding_pos = Position::new(RoomCoordinate::new(25).unwrap(), RoomCoordinate::new(25).unwrap(), holding_room);
+
+    if creep_pos.in_range_to(holding_pos, 20) {
+        mark_idle(tick_context);
+    } else {
+        tick_context
+            .runtime_data
+            .movement
+            .move_to(tick_context.runtime_data.creep_entity, holding_pos)
+            .range(20)
+            .priority(MovementPriority::Low);
+    }
+
+    true
+}
+
+/// The rampart this creep should fight from (see `military::rampartdefense`): one in reach of a hostile
+/// in one of our own rooms. A melee creep no rampart puts in reach holds the likely breach instead (see
+/// `military::threatmap::BreachForecast`): a free rampart on it, else the tile just inside it. `None`
+/// elsewhere, for a creep without weapons, while the hostiles are fleeing, or when neither applies.
+fn rampart_post(creep: &Creep, creep_pos: Position, tick_context: &JobTickContext) -> Option<Position> {
+    let reach = if has_active_part(creep, Part::Attack) {
+        1
+    } else if has_active_part(creep, Part::RangedAttack) {
+        3
+    } else {
+        return None;
+    };
+
+    let room_name = creep_pos.room_name();
+    let room_entity = tick_context.runtime_data.mapping.get_room(&room_name)?;
+    let room_data = tick_context.system_data.room_data.get(room_entity)?;
+
+    if !room_data.get_dynamic_visibility_data().is_some_and(|data| data.owner().mine()) {
+        return None;
+    }
+
+    let tile = |pos: Position| -> Tile { (pos.x().u8(), pos.y().u8()) };
+
+    let hostiles: Vec<Tile> = get_hostile_creeps(room_name, tick_context)
+        .iter()
+        .map(|hostile| tile(hostile.pos()))
+        .collect();
+
+    if hostiles.is_empty() || hostiles_fleeing(&hostiles) {
+        return None;
+    }
+
+    let index = RampartIndex::from_room(room_data);
+
+    if index.is_empty() {
+        return None;
+    }
+
+    let me = tile(creep_pos);
+    let occupied: HashSet<Tile> = get_friendly_creeps(room_name, tick_context)
+        .iter()
+        .map(|friend| tile(friend.pos()))
+        .filter(|friend| *friend != me)
+        .collect();
+
+    let (x, y) = match index.post(me, reach, &hostiles, &occupied) {
+        Some(post) => post,
+        None if reach == 1 => breach_post(room_name, &index, me, &occupied, tick_context)?,
+        None => return None,
+    };
+
+    Some(Position::new(RoomCoordinate::new(x).ok()?, RoomCoordinate::new(y).ok()?, room_name))
+}
+
+/// Where a melee defender waits for attackers to break in: on the cheapest forecast breach path, a free
+/// rampart of its barrier segment, else the open tile just inside the segment.
+fn breach_post(
+    room_name: RoomName,
+    index: &RampartIndex,
+    me: Tile,
+    occupied: &HashSet<Tile>,
+    tick_context: &JobTickContext,
+) -> Option<Tile> {
+    let path = tick_context.system_data.breach_forecasts.get(room_name)?.paths.first()?;
+    let free = |tile: &Tile| *tile == me || !occupied.contains(tile);
+
+    path.barriers
+        .iter()
+        .rev()
+        .copied()
+        .find(|tile| index.contains(*tile) && free(tile))
+        .or(path.inside.filter(free))
+}
+
+/// A defender on its rampart hits the weakest hostile in reach with whatever pipeline the seam left
+/// free: the squad focus is often out of reach from the rampart line.
+fn strike_from_rampart(creep: &Creep, creep_pos: Position, tick_context: &mut JobTickContext) {
+    let hostiles = get_hostile_creeps(creep_pos.room_name(), tick_context);
+
+    if has_active_part(creep, Part::Attack) {
+        if let Some(target) = hostiles
+            .iter()
+            .filter(|hostile| creep_pos.is_near_to(hostile.pos()))
+            .min_by_key(|hostile| hostile.hits())
+        {
+            crate::intents::attack(
+                creep,
+                &mut tick_context.action_flags,
+                tick_context.runtime_data.intent_recorder,
+                target,
+                target.pos(),
+            );
+        }
+    }
+
+    if has_active_part(creep, Part::RangedAttack) {
+        if let Some(target) = hostiles
+            .iter()
+            .filter(|hostile| creep_pos.in_range_to(hostile.pos(), 3))
+            .min_by_key(|hostile| hostile.hits())
+        {
+            crate::intents::ranged_attack(
+                creep,
+                &mut tick_context.action_flags,
+                tick_context.runtime_data.intent_recorder,
+                target,
+                target.pos(),
+            );
         }
     }
 }
@@ -1272,6 +1685,18 @@ fn get_friendly_creeps(room_name: RoomName, tick_context: &JobTickContext) -> Ve
         .unwrap_or_default()
 }
 
+/// Our labs in the given room, from the cached structure data.
+fn get_labs(room_name: RoomName, tick_context: &JobTickContext) -> Vec<StructureLab> {
+    tick_context
+        .runtime_data
+        .mapping
+        .get_room(&room_name)
+        .and_then(|room_entity| tick_context.system_data.room_data.get(room_entity))
+        .and_then(|room_data| room_data.get_structures())
+        .map(|structures| structures.labs().iter().filter(|lab| lab.my()).cloned().collect())
+        .unwrap_or_default()
+}
+
 /// Get cached hostile structures in the given room from dynamic visibility data.
 fn get_hostile_structures(room_name: RoomName, tick_context: &JobTickContext) -> Vec<StructureObject> {
     if let Some(room_entity) = tick_context.runtime_data.mapping.get_room(&room_name) {
@@ -1292,12 +1717,12 @@ fn get_hostile_structures(room_name: RoomName, tick_context: &JobTickContext) ->
         .unwrap_or_default()
 }
 
-/// Look up tick orders for a specific creep from the squad context.
+/// Look up this tick's orders for a creep that is still a member of its squad.
 fn get_tick_orders(squad: Option<SquadRef>, creep_entity: Entity, tick_context: &JobTickContext) -> Option<TickOrders> {
     let entity = squad?.resolve(tick_context.system_data.entities)?;
     let squad_ctx = tick_context.system_data.squad_contexts.get(entity)?;
-    let member = squad_ctx.get_member(creep_entity)?;
-    member.tick_orders.clone()
+    squad_ctx.get_member(creep_entity)?;
+    tick_context.system_data.squad_orders.get(creep_entity).cloned()
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -1306,6 +1731,51 @@ impl Job for SquadCombatJob {
         SummaryContent::Text(format!("SquadCombat - {}", self.state.status_description()))
     }
 
+    /// State glyph, formation slot and this tick's order, e.g. `⚔2KT`:
+    /// engaged in slot 2, kiting, with a focus target.
+    fn say_code(&self, system_data: &JobExecutionSystemData, runtime_data: &JobExecutionRuntimeData) -> Option<String> {
+        let state = match &self.state {
+            SquadCombatState::MoveToRoom(_) => "»",
+            SquadCombatState::CombatResponse(_) => "⚡",
+            SquadCombatState::Engaged(_) => "⚔",
+            SquadCombatState::Retreating(_) => "↩",
+            _ => "B",
+        };
+
+        let creep_entity = runtime_data.creep_entity;
+        let slot = self
+            .context
+            .squad_entity
+            .and_then(|squad| squad.resolve(system_data.entities))
+            .and_then(|entity| system_data.squad_contexts.get(entity))
+            .and_then(|squad_ctx| squad_ctx.get_member(creep_entity))
+            .map(|member| member.formation_slot.to_string())
+            .unwrap_or_default();
+
+        let orders = system_data
+            .squad_orders
+            .get(creep_entity)
+            .map(|orders| {
+                let movement = match orders.movement {
+                    TickMovement::Formation => "F",
+                    TickMovement::MoveTo(_) => "M",
+                    TickMovement::Flee => "!",
+                    TickMovement::Hold => "H",
+                    TickMovement::Kite(_) => "K",
+                    TickMovement::Drain(_) => "D",
+                    TickMovement::Patrol(_) => "P",
+                    TickMovement::Follow(_) => "f",
+                };
+                let attack = if orders.attack_target.is_some() { "T" } else { "" };
+                let heal = if orders.heal_target.is_some() { "+" } else { "" };
+
+                format!("{}{}{}", movement, attack, heal)
+            })
+            .unwrap_or_default();
+
+        Some(format!("{}{}{}", state, slot, orders))
+    }
+
     fn pre_run_job(&mut self, system_data: &JobExecutionSystemData, runtime_data: &mut JobExecutionRuntimeData) {
         self.state.gather_data(system_data, runtime_data);
     }
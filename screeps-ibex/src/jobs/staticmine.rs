@@ -4,7 +4,10 @@ use super::jobsystem::*;
 use super::utility::movebehavior::*;
 use super::utility::repair::*;
 use super::utility::repairbehavior::*;
+use super::utility::saybehavior::*;
 use super::utility::waitbehavior::*;
+use crate::intents::IntentCategory;
+use crate::ledger::LedgerCategory;
 use crate::remoteobjectid::*;
 use screeps::*;
 use screeps_machine::*;
@@ -125,13 +128,13 @@ impl MoveToContainer {
             // Found a new container — fall through to move to it.
         }
 
-        tick_move_to_position(
-            tick_context,
-            state_context.container_target.pos().into(),
-            0,
-            None,
-            StaticMineState::harvest,
-        )
+        match tick_context.move_to_cached(state_context.container_target.pos(), 0) {
+            MoveOutcome::Arrived => Some(StaticMineState::harvest()),
+            MoveOutcome::Moving => None,
+            // Something holds the container tile. `Harvest` mines from
+            // wherever reaches the source and keeps asking for the tile back.
+            MoveOutcome::Failed(_) => Some(StaticMineState::harvest()),
+        }
     }
 }
 
@@ -149,8 +152,20 @@ fn try_harvest_mine_target(creep: &Creep, mine_target: &StaticMineTarget, tick_c
                 }
 
                 if tick_context.action_flags.consume(SimultaneousActionFlags::HARVEST) {
-                    match creep.harvest(&source) {
-                        Ok(()) => None,
+                    match tick_context
+                        .runtime_data
+                        .intent_recorder
+                        .issued(IntentCategory::Harvest, creep.harvest(&source))
+                    {
+                        Ok(()) => {
+                            let work_parts = creep.body().iter().filter(|b| b.part() == Part::Work).count() as u32;
+                            let harvest_amount = (work_parts * HARVEST_POWER).min(source.energy());
+                            tick_context
+                                .runtime_data
+                                .ledger
+                                .add(source.pos().room_name(), LedgerCategory::Harvest, harvest_amount);
+                            None
+                        }
                         Err(_) => Some(StaticMineState::wait(1)),
                     }
                 } else {
@@ -167,7 +182,11 @@ fn try_harvest_mine_target(creep: &Creep, mine_target: &StaticMineTarget, tick_c
                 }
 
                 if tick_context.action_flags.consume(SimultaneousActionFlags::HARVEST) {
-                    match creep.harvest(&mineral) {
+                    match tick_context
+                        .runtime_data
+                        .intent_recorder
+                        .issued(IntentCategory::Harvest, creep.harvest(&mineral))
+                    {
                         Ok(()) => None,
                         Err(_) => Some(StaticMineState::wait(1)),
                     }
@@ -206,18 +225,16 @@ impl Harvest {
                 mark_stationed(tick_context);
             }
 
-            // Check container capacity before harvesting (only when on the
-            // container — when displaced, resources drop on the ground and
-            // will be picked up, which is better than idling).
-            if !displaced {
+            // Check container capacity before harvesting a mineral (only when
+            // on the container — when displaced, resources drop on the ground
+            // and will be picked up, which is better than idling). A source
+            // regenerates on a timer whether it is mined or not, so a source
+            // miner keeps harvesting into a full container: the overflow drops
+            // on the container tile for the haulers, where idling loses it.
+            if !displaced && matches!(state_context.mine_target, StaticMineTarget::Mineral(_, _)) {
                 let work_parts = creep.body().iter().filter(|p| p.part() == Part::Work).count() as u32;
 
-                let mining_power = match state_context.mine_target {
-                    StaticMineTarget::Source(_) => HARVEST_POWER,
-                    StaticMineTarget::Mineral(_, _) => HARVEST_MINERAL_POWER,
-                };
-
-                let resources_harvested = work_parts * mining_power;
+                let resources_harvested = work_parts * HARVEST_MINERAL_POWER;
 
                 if resources_harvested as i32 > container.store().get_free_capacity(None) {
                     return Some(StaticMineState::wait(1));
@@ -244,16 +261,10 @@ impl Harvest {
             // regenerates on a timer so it should always be collected.
             let mine_pos = state_context.mine_target.pos();
 
-            if !creep.pos().is_near_to(mine_pos) {
-                // Walk toward the source (range 1).
-                if tick_context.action_flags.consume(SimultaneousActionFlags::MOVE) {
-                    tick_context
-                        .runtime_data
-                        .movement
-                        .move_to(tick_context.runtime_data.creep_entity, mine_pos)
-                        .range(1);
-                }
-                return None;
+            match tick_context.move_to_cached(mine_pos, 1) {
+                MoveOutcome::Arrived => {}
+                MoveOutcome::Moving => return None,
+                MoveOutcome::Failed(_) => return Some(StaticMineState::wait(1)),
             }
 
             // Near the source without a container — allow shoving within
@@ -347,6 +358,18 @@ impl Job for StaticMineJob {
         crate::visualization::SummaryContent::Text(format!("StaticMine - {}", self.state.status_description()))
     }
 
+    fn say_code(&self, _system_data: &JobExecutionSystemData, _runtime_data: &JobExecutionRuntimeData) -> Option<String> {
+        let code = match &self.state {
+            StaticMineState::Harvest(_) => "M!",
+            StaticMineState::MoveToContainer(_) => "M»",
+            StaticMineState::Wait(_) => "Mz",
+            StaticMineState::Flee(_) => "M⚠",
+            _ => "M?",
+        };
+
+        Some(code.to_string())
+    }
+
     fn pre_run_job(&mut self, system_data: &JobExecutionSystemData, runtime_data: &mut JobExecutionRuntimeData) {
         self.state.gather_data(system_data, runtime_data);
     }
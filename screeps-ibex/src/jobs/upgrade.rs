@@ -5,8 +5,9 @@ use super::utility::controllerbehavior::*;
 use super::utility::harvestbehavior::*;
 use super::utility::haulbehavior::*;
 use super::utility::movebehavior::*;
+use super::utility::saybehavior::*;
 use super::utility::waitbehavior::*;
-use crate::constants::*;
+use crate::features::SignPurpose;
 use crate::remoteobjectid::*;
 use crate::room::data::*;
 use crate::transfer::transfersystem::*;
@@ -81,7 +82,10 @@ machine!(
         FinishedPickup,
         Sign { target: RemoteObjectId<StructureController> },
         Upgrade { target: RemoteObjectId<StructureController> },
-        Wait { ticks: u32 }
+        Wait { ticks: u32 },
+        /// Working from the seat the upgrade mission assigned. Appended: saved
+        /// jobs encode the state by position.
+        Seated
     }
 
     impl {
@@ -91,9 +95,9 @@ machine!(
             std::any::type_name::<Self>().to_string()
         }
 
-        Idle, Harvest, FinishedPickup, Sign, Upgrade, Wait => fn visualize(&self, _system_data: &JobExecutionSystemData, _describe_data: &mut JobDescribeData) {}
+        Idle, Harvest, FinishedPickup, Sign, Upgrade, Wait, Seated => fn visualize(&self, _system_data: &JobExecutionSystemData, _describe_data: &mut JobDescribeData) {}
 
-        Idle, Harvest, FinishedPickup, Sign, Upgrade, Wait => fn gather_data(&self, _system_data: &JobExecutionSystemData, _runtime_data: &mut JobExecutionRuntimeData) {}
+        Idle, Harvest, FinishedPickup, Sign, Upgrade, Wait, Seated => fn gather_data(&self, _system_data: &JobExecutionSystemData, _runtime_data: &mut JobExecutionRuntimeData) {}
 
         _ => fn tick(&mut self, state_context: &mut UpgradeJobContext, tick_context: &mut JobTickContext) -> Option<UpgradeState>;
     }
@@ -104,6 +108,22 @@ impl Idle {
         let home_room_data = tick_context.system_data.room_data.get(state_context.home_room)?;
         let creep = tick_context.runtime_data.owner;
 
+        // A seated upgrader refills from its feed; it only leaves to sign.
+        if tick_context
+            .system_data
+            .upgrade_seating
+            .get(tick_context.runtime_data.creep_entity)
+            .is_some()
+        {
+            return get_new_sign_state(
+                home_room_data,
+                tick_context.system_data.signs.message(SignPurpose::Owned),
+                tick_context.runtime_data.sign_cooldowns,
+                UpgradeState::sign,
+            )
+            .or_else(|| Some(UpgradeState::seated()));
+        }
+
         let transfer_queue_data = TransferQueueGeneratorData {
             cause: "Upgrade Idle",
             room_data: tick_context.system_data.room_data,
@@ -127,7 +147,14 @@ impl Idle {
                 None
             }
         })
-        .or_else(|| get_new_sign_state(home_room_data, UpgradeState::sign))
+        .or_else(|| {
+            get_new_sign_state(
+                home_room_data,
+                tick_context.system_data.signs.message(SignPurpose::Owned),
+                tick_context.runtime_data.sign_cooldowns,
+                UpgradeState::sign,
+            )
+        })
         .or_else(|| get_new_upgrade_state(creep, home_room_data, UpgradeState::upgrade, None))
         .or_else(|| Some(UpgradeState::wait(5)))
     }
@@ -185,7 +212,9 @@ impl FinishedPickup {
 
 impl Sign {
     pub fn tick(&mut self, _state_context: &UpgradeJobContext, tick_context: &mut JobTickContext) -> Option<UpgradeState> {
-        tick_sign(tick_context, self.target, ROOM_SIGN, UpgradeState::idle)
+        let message = tick_context.system_data.signs.message(SignPurpose::Owned);
+
+        tick_sign(tick_context, self.target, message, UpgradeState::idle)
     }
 }
 
@@ -202,6 +231,28 @@ impl Wait {
     }
 }
 
+impl Seated {
+    pub fn tick(&mut self, state_context: &UpgradeJobContext, tick_context: &mut JobTickContext) -> Option<UpgradeState> {
+        // The mission stopped seating us (feed gone, seat lost): fetch
+        // energy the usual way.
+        let Some(order) = tick_context
+            .system_data
+            .upgrade_seating
+            .get(tick_context.runtime_data.creep_entity)
+            .copied()
+        else {
+            return Some(UpgradeState::idle());
+        };
+
+        let home_room_data = tick_context.system_data.room_data.get(state_context.home_room)?;
+        let controller = *home_room_data.get_static_visibility_data()?.controller()?;
+
+        // Back off a tick on a failed upgrade so Idle doesn't re-seat us straight
+        // into the same failure.
+        tick_upgrade_seated(tick_context, controller, order.seat, order.feed, || UpgradeState::wait(1))
+    }
+}
+
 #[derive(Clone, ConvertSaveload)]
 pub struct UpgradeJob {
     context: UpgradeJobContext,
@@ -224,6 +275,20 @@ impl Job for UpgradeJob {
         crate::visualization::SummaryContent::Text(format!("Upgrade - {}", self.state.status_description()))
     }
 
+    fn say_code(&self, _system_data: &JobExecutionSystemData, _runtime_data: &JobExecutionRuntimeData) -> Option<String> {
+        let code = match &self.state {
+            UpgradeState::Upgrade(_) => "U!".to_string(),
+            UpgradeState::Seated(_) => "U#".to_string(),
+            UpgradeState::Pickup(state) => format!("U←{}", target_code(state.ticket.target())),
+            UpgradeState::Harvest(_) => "Uh".to_string(),
+            UpgradeState::Sign(_) => "U✎".to_string(),
+            UpgradeState::Wait(_) => "Uz".to_string(),
+            _ => "U?".to_string(),
+        };
+
+        Some(code)
+    }
+
     fn pre_run_job(&mut self, system_data: &JobExecutionSystemData, runtime_data: &mut JobExecutionRuntimeData) {
         self.state.gather_data(system_data, runtime_data);
     }
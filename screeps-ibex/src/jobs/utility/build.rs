@@ -1,19 +1,154 @@
+use crate::findnearest::*;
 use screeps::*;
-use screeps_foreman::planner::get_build_priority;
+use std::cmp::Ordering;
 
-#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
-pub fn select_construction_site(creep: &Creep, construction_sites: &[ConstructionSite], current_rcl: u32) -> Option<ConstructionSite> {
-    let creep_pos = creep.pos();
+/// How much a finished structure of this type is worth to the room, highest
+/// first: spawn > tower > extension > storage > container > link > road >
+/// rampart/wall, with anything else between links and roads.
+pub fn construction_tier(structure_type: StructureType) -> u8 {
+    match structure_type {
+        StructureType::Spawn => 9,
+        StructureType::Tower => 8,
+        StructureType::Extension => 7,
+        StructureType::Storage => 6,
+        StructureType::Container => 5,
+        StructureType::Link => 4,
+        StructureType::Road => 2,
+        StructureType::Rampart | StructureType::Wall => 1,
+        _ => 3,
+    }
+}
+
+/// What a builder weighs when picking a construction site.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SiteRank {
+    /// The room's emergency rampart (see `missions::wall_repair`).
+    pub urgent: bool,
+    /// Other builders already working the site.
+    pub claimants: usize,
+    pub tier: u8,
+    pub progress: u32,
+    pub progress_total: u32,
+    /// Range from the builder.
+    pub range: u32,
+}
+
+impl SiteRank {
+    /// `Greater` when `self` is the better site: an emergency rampart first,
+    /// then fewer other builders on it, then the higher tier, then the closer
+    /// to complete, then the nearer.
+    pub fn compare(&self, other: &SiteRank) -> Ordering {
+        let completion = |rank: &SiteRank, by: &SiteRank| rank.progress as u64 * by.progress_total.max(1) as u64;
+
+        self.urgent
+            .cmp(&other.urgent)
+            .then_with(|| other.claimants.cmp(&self.claimants))
+            .then_with(|| self.tier.cmp(&other.tier))
+            .then_with(|| completion(self, other).cmp(&completion(other, self)))
+            .then_with(|| other.range.cmp(&self.range))
+    }
+}
+
+/// A [`SiteRank`] as a `find_best_by` score: the better site scores lower.
+struct SiteScore(SiteRank);
+
+impl PartialEq for SiteScore {
+    fn eq(&self, other: &SiteScore) -> bool {
+        self.0.compare(&other.0) == Ordering::Equal
+    }
+}
+
+impl PartialOrd for SiteScore {
+    fn partial_cmp(&self, other: &SiteScore) -> Option<Ordering> {
+        Some(other.0.compare(&self.0))
+    }
+}
 
+/// The best of `construction_sites` by [`SiteRank::compare`], its range
+/// measured by `distance` (path length keeps a builder from picking the
+/// site just over a wall).
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn select_construction_site<F>(
+    creep: &Creep,
+    construction_sites: &[ConstructionSite],
+    urgent: Option<Position>,
+    distance: Distance,
+    claimants: F,
+) -> Option<ConstructionSite>
+where
+    F: Fn(&ConstructionSite) -> usize,
+{
     construction_sites
         .iter()
         .filter(|s| s.my())
-        .map(|s| (s, get_build_priority(s.structure_type(), current_rcl)))
-        .max_by(|(a, a_priority), (b, b_priority)| {
-            a_priority
-                .cmp(b_priority)
-                .then_with(|| a.progress().cmp(&b.progress()))
-                .then_with(|| creep_pos.get_range_to(a.pos()).cmp(&creep_pos.get_range_to(b.pos())).reverse())
-        })
-        .map(|(s, _)| s.clone())
+        .find_best_by(
+            creep.pos(),
+            distance,
+            TieBreak::First,
+            |s| s.pos(),
+            |range, s| {
+                SiteScore(SiteRank {
+                    urgent: urgent == Some(s.pos()),
+                    claimants: claimants(s),
+                    tier: construction_tier(s.structure_type()),
+                    progress: s.progress(),
+                    progress_total: s.progress_total(),
+                    range,
+                })
+            },
+        )
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rank(claimants: usize, structure_type: StructureType, progress: u32, progress_total: u32, range: u32) -> SiteRank {
+        SiteRank {
+            urgent: false,
+            claimants,
+            tier: construction_tier(structure_type),
+            progress,
+            progress_total,
+            range,
+        }
+    }
+
+    #[test]
+    fn spawn_outranks_a_nearer_nearly_finished_road() {
+        let spawn = rank(0, StructureType::Spawn, 0, 15_000, 20);
+        let road = rank(0, StructureType::Road, 290, 300, 1);
+
+        assert_eq!(spawn.compare(&road), Ordering::Greater);
+    }
+
+    #[test]
+    fn within_a_tier_the_nearly_complete_site_goes_first_then_the_nearer() {
+        let half = rank(0, StructureType::Extension, 1_500, 3_000, 2);
+        let most = rank(0, StructureType::Extension, 2_700, 3_000, 10);
+        let most_nearer = rank(0, StructureType::Extension, 2_700, 3_000, 4);
+
+        assert_eq!(most.compare(&half), Ordering::Greater);
+        assert_eq!(most_nearer.compare(&most), Ordering::Greater);
+    }
+
+    #[test]
+    fn an_unclaimed_site_beats_one_another_builder_holds() {
+        let claimed_tower = rank(1, StructureType::Tower, 4_000, 5_000, 1);
+        let open_extension = rank(0, StructureType::Extension, 0, 3_000, 15);
+
+        assert_eq!(open_extension.compare(&claimed_tower), Ordering::Greater);
+    }
+
+    #[test]
+    fn emergency_rampart_outranks_a_spawn_others_are_building() {
+        let spawn = rank(0, StructureType::Spawn, 14_000, 15_000, 1);
+        let rampart = SiteRank {
+            urgent: true,
+            ..rank(2, StructureType::Rampart, 0, 1, 20)
+        };
+
+        assert_eq!(rampart.compare(&spawn), Ordering::Greater);
+    }
 }
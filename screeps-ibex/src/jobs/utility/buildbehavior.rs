@@ -1,32 +1,84 @@
 use super::build::*;
+use crate::findnearest::*;
+use crate::intents::IntentCategory;
 use crate::jobs::actions::*;
 use crate::jobs::context::*;
 use crate::jobs::utility::movebehavior::mark_working;
+use crate::ledger::LedgerCategory;
+use crate::pathing::pathfinderservice::PathfinderService;
 use crate::remoteobjectid::*;
 use crate::room::data::*;
 use screeps::*;
+use specs::Entity;
+use std::collections::HashMap;
 
+/// The construction sites builders are working this tick. Rebuilt every
+/// tick: a job in a build state re-claims its site from `gather_data`, so a
+/// builder that dies or goes off for energy drops its claim by not renewing
+/// it.
+#[derive(Default)]
+pub struct BuildClaims {
+    sites: HashMap<ObjectId<ConstructionSite>, (Position, Vec<Entity>)>,
+}
+
+impl BuildClaims {
+    pub fn clear(&mut self) {
+        self.sites.clear();
+    }
+
+    pub fn claim(&mut self, site: RemoteObjectId<ConstructionSite>, builder: Entity) {
+        let (_, builders) = self.sites.entry(site.id()).or_insert_with(|| (site.pos(), Vec::new()));
+
+        if !builders.contains(&builder) {
+            builders.push(builder);
+        }
+    }
+
+    /// Builders other than `builder` working `site`.
+    pub fn claimants(&self, site: ObjectId<ConstructionSite>, builder: Entity) -> usize {
+        self.sites
+            .get(&site)
+            .map(|(_, builders)| builders.iter().filter(|other| **other != builder).count())
+            .unwrap_or(0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &[Entity])> {
+        self.sites.values().map(|(pos, builders)| (*pos, builders.as_slice()))
+    }
+}
+
+/// Pick the best construction site in `build_room` for a builder carrying
+/// energy and claim it, steering clear of sites other builders hold. The
+/// site at `urgent` (the room's emergency rampart) goes before all others.
+/// Sites in the builder's room are weighed by cached path length.
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
-pub fn get_new_build_state<F, R>(creep: &Creep, build_room: &RoomData, state_map: F) -> Option<R>
+pub fn get_new_build_state<F, R>(
+    creep: &Creep,
+    creep_entity: Entity,
+    build_room: &RoomData,
+    urgent: Option<Position>,
+    claims: &mut BuildClaims,
+    pathfinder: &mut PathfinderService,
+    state_map: F,
+) -> Option<R>
 where
     F: Fn(RemoteObjectId<ConstructionSite>) -> R,
 {
     if creep.store().get_used_capacity(Some(ResourceType::Energy)) > 0 {
-        let current_rcl = build_room
-            .get_structures()
-            .iter()
-            .flat_map(|s| s.controllers())
-            .map(|c| c.level())
-            .max()
-            .unwrap_or(0);
+        let now = game::time();
+        let mut path_length = |from: Position, to: Position| pathfinder.cached_path_length(from, to, 3, now);
 
         //TODO: This requires visibility and could fail?
-        if let Some(construction_site) = build_room
-            .get_construction_sites()
-            .and_then(|construction_sites| select_construction_site(creep, &construction_sites, current_rcl.into()))
-        {
+        if let Some(construction_site) = build_room.get_construction_sites().and_then(|construction_sites| {
+            select_construction_site(creep, &construction_sites, urgent, Distance::Path(&mut path_length), |site| {
+                site.try_id().map(|id| claims.claimants(id, creep_entity)).unwrap_or(0)
+            })
+        }) {
             if let Some(id) = construction_site.try_id() {
-                return Some(state_map(RemoteObjectId::new_from_components(id, construction_site.pos())));
+                let site = RemoteObjectId::new_from_components(id, construction_site.pos());
+                claims.claim(site, creep_entity);
+
+                return Some(state_map(site));
             }
         }
     }
@@ -80,8 +132,22 @@ where
 
     if let Some(construction_site) = construction_site {
         if tick_context.action_flags.consume(SimultaneousActionFlags::BUILD) {
-            match creep.build(&construction_site) {
-                Ok(()) => None,
+            match tick_context
+                .runtime_data
+                .intent_recorder
+                .issued(IntentCategory::Build, creep.build(&construction_site))
+            {
+                Ok(()) => {
+                    let work_parts = creep.body().iter().filter(|p| p.part() == Part::Work).count() as u32;
+                    let carried = creep.store().get_used_capacity(Some(ResourceType::Energy));
+                    let remaining = construction_site.progress_total().saturating_sub(construction_site.progress());
+                    let spent = (work_parts * BUILD_POWER).min(carried).min(remaining);
+                    tick_context
+                        .runtime_data
+                        .ledger
+                        .add(target_position.room_name(), LedgerCategory::Build, spent);
+                    None
+                }
                 Err(_) => Some(next_state()),
             }
         } else {
@@ -91,3 +157,60 @@ where
         Some(next_state())
     }
 }
+
+/// Build a road site within reach while passing through a room that isn't
+/// ours, for haulers laying a remote's roads on their way. Returns the energy
+/// spent, if any. Owned rooms are left to their builders.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn tick_opportunistic_road_build(tick_context: &mut JobTickContext) -> Option<u32> {
+    if tick_context.action_flags.intersects(SimultaneousActionFlags::BUILD)
+        || !tick_context.runtime_data.intent_recorder.allow(IntentCategory::Build)
+    {
+        return None;
+    }
+
+    let creep = tick_context.runtime_data.owner;
+
+    let available_energy = creep.store().get_used_capacity(Some(ResourceType::Energy));
+    let work_parts = creep.body().iter().filter(|p| p.part() == Part::Work).count() as u32;
+
+    if available_energy == 0 || work_parts == 0 {
+        return None;
+    }
+
+    let creep_pos = creep.pos();
+
+    let room_entity = tick_context.runtime_data.mapping.get_room(&creep_pos.room_name())?;
+    let room_data = tick_context.system_data.room_data.get(room_entity)?;
+
+    if room_data.get_dynamic_visibility_data().map(|v| v.owner().mine()).unwrap_or(true) {
+        return None;
+    }
+
+    let construction_site = room_data
+        .get_construction_sites()?
+        .iter()
+        .filter(|site| site.my() && site.structure_type() == StructureType::Road && site.pos().in_range_to(creep_pos, 3))
+        .min_by_key(|site| site.progress_total().saturating_sub(site.progress()))?
+        .clone();
+
+    if !tick_context.action_flags.consume(SimultaneousActionFlags::BUILD) {
+        return None;
+    }
+
+    tick_context
+        .runtime_data
+        .intent_recorder
+        .issued(IntentCategory::Build, creep.build(&construction_site))
+        .ok()?;
+
+    let remaining = construction_site.progress_total().saturating_sub(construction_site.progress());
+    let spent = (work_parts * BUILD_POWER).min(available_energy).min(remaining);
+
+    tick_context
+        .runtime_data
+        .ledger
+        .add(creep_pos.room_name(), LedgerCategory::Build, spent);
+
+    Some(spent)
+}
@@ -1,10 +1,45 @@
+use crate::intents::IntentCategory;
 use crate::jobs::actions::*;
 use crate::jobs::context::*;
-use crate::jobs::utility::movebehavior::mark_working;
+use crate::jobs::utility::movebehavior::{mark_stationed, mark_working};
+use crate::ledger::LedgerCategory;
 use crate::remoteobjectid::*;
 use crate::room::data::*;
+use crate::transfer::transfersystem::TransferTarget;
 use screeps::*;
 use screeps_rover::*;
+use std::collections::HashMap;
+
+/// Ticks to hold off re-signing a controller after `signController` was
+/// rejected, so a creep parked at it doesn't retry every tick.
+const SIGN_RETRY_TICKS: u32 = 100;
+
+/// Rooms whose last controller sign was rejected, and the tick each may be
+/// tried again. Session-only: a VM reload just allows one early retry.
+#[derive(Default)]
+pub struct SignCooldowns {
+    retry_at: HashMap<RoomName, u32>,
+}
+
+impl SignCooldowns {
+    pub fn ready(&self, room_name: RoomName, now: u32) -> bool {
+        self.retry_at.get(&room_name).is_none_or(|retry_at| now >= *retry_at)
+    }
+
+    fn record(&mut self, room_name: RoomName, accepted: bool, now: u32) {
+        if accepted {
+            self.retry_at.remove(&room_name);
+        } else {
+            self.retry_at.insert(room_name, now + SIGN_RETRY_TICKS);
+        }
+    }
+}
+
+/// Whether a controller carrying `sign` (signer, text) still needs signing
+/// by `username` with `message`.
+fn needs_sign(sign: Option<(&str, &str)>, username: &str, message: &str) -> bool {
+    sign.is_none_or(|(signer, text)| signer != username || text != message)
+}
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 pub fn get_new_upgrade_state<F, R>(creep: &Creep, upgrade_room: &RoomData, state_map: F, max_rcl: Option<u32>) -> Option<R>
@@ -29,13 +64,17 @@ where
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
-pub fn get_new_sign_state<F, R>(sign_room: &RoomData, state_map: F) -> Option<R>
+pub fn get_new_sign_state<F, R>(sign_room: &RoomData, message: &str, cooldowns: &SignCooldowns, state_map: F) -> Option<R>
 where
     F: Fn(RemoteObjectId<StructureController>) -> R,
 {
     let dynamic_visibility_data = sign_room.get_dynamic_visibility_data()?;
+    let stale = dynamic_visibility_data
+        .sign()
+        .as_ref()
+        .is_none_or(|s| !s.user().mine() || s.message() != message);
 
-    if dynamic_visibility_data.updated_within(1000) && dynamic_visibility_data.sign().as_ref().map(|s| !s.user().mine()).unwrap_or(true) {
+    if dynamic_visibility_data.updated_within(1000) && stale && cooldowns.ready(sign_room.name, game::time()) {
         let static_visibility_data = sign_room.get_static_visibility_data()?;
         let controller = static_visibility_data.controller()?;
 
@@ -100,7 +139,11 @@ where
 
     if tick_context.action_flags.consume(SimultaneousActionFlags::UPGRADE_CONTROLLER) {
         if let Some(controller) = controller_id.resolve() {
-            match creep.upgrade_controller(&controller) {
+            match tick_context
+                .runtime_data
+                .intent_recorder
+                .issued(IntentCategory::Upgrade, creep.upgrade_controller(&controller))
+            {
                 // The upgrade intent (pipeline E) succeeded this tick. If the creep
                 // is about to run dry, keep the state-machine cascade going so the
                 // refill pickup's withdraw (pipeline D) is issued THIS tick rather
@@ -110,6 +153,13 @@ where
                 // rides along with no move; otherwise this just starts the refill
                 // trip one tick early, exactly as the dry-tick path did before.
                 Ok(()) => {
+                    let work_parts = creep.body().iter().filter(|p| p.part() == Part::Work).count() as u32;
+                    let carried = creep.store().get_used_capacity(Some(ResourceType::Energy));
+                    tick_context.runtime_data.ledger.add(
+                        target_position.room_name(),
+                        LedgerCategory::Upgrade,
+                        (work_parts * UPGRADE_CONTROLLER_POWER).min(carried),
+                    );
                     if refill_when_draining && upgrade_about_to_run_dry(creep) {
                         Some(next_state())
                     } else {
@@ -126,6 +176,79 @@ where
     }
 }
 
+/// Upgrade from a fixed `seat` next to `feed`, refilling from the feed in
+/// place instead of walking for energy. The withdraw rides along with the
+/// upgrade once the creep is about to run dry, so a fed seat never idles.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn tick_upgrade_seated<F, R>(
+    tick_context: &mut JobTickContext,
+    controller_id: RemoteObjectId<StructureController>,
+    seat: Position,
+    feed: TransferTarget,
+    next_state: F,
+) -> Option<R>
+where
+    F: Fn() -> R,
+{
+    let creep = tick_context.runtime_data.owner;
+    let creep_pos = creep.pos();
+
+    if creep_pos != seat {
+        if tick_context.action_flags.consume(SimultaneousActionFlags::MOVE) {
+            tick_context
+                .runtime_data
+                .movement
+                .move_to(tick_context.runtime_data.creep_entity, seat)
+                .range(0);
+        }
+    } else {
+        mark_stationed(tick_context);
+    }
+
+    let energy = creep.store().get_used_capacity(Some(ResourceType::Energy));
+
+    if creep_pos.is_near_to(Position::from(feed.pos()))
+        && (energy == 0 || upgrade_about_to_run_dry(creep))
+        && tick_context.action_flags.consume(SimultaneousActionFlags::WITHDRAW)
+    {
+        // Safe on general stores (engine-mechanics folklore row 26).
+        let free = creep.store().get_free_capacity(Some(ResourceType::Energy)).max(0) as u32;
+
+        let _ = tick_context.runtime_data.intent_recorder.issued(
+            IntentCategory::Transfer,
+            feed.withdraw_resource_amount(creep, ResourceType::Energy, free),
+        );
+    }
+
+    if energy == 0 || !creep_pos.in_range_to(controller_id.pos(), 3) {
+        return None;
+    }
+
+    if tick_context.action_flags.consume(SimultaneousActionFlags::UPGRADE_CONTROLLER) {
+        let Some(controller) = controller_id.resolve() else {
+            return Some(next_state());
+        };
+
+        match tick_context
+            .runtime_data
+            .intent_recorder
+            .issued(IntentCategory::Upgrade, creep.upgrade_controller(&controller))
+        {
+            Ok(()) => {
+                let work_parts = creep.body().iter().filter(|p| p.part() == Part::Work).count() as u32;
+                tick_context.runtime_data.ledger.add(
+                    controller_id.pos().room_name(),
+                    LedgerCategory::Upgrade,
+                    (work_parts * UPGRADE_CONTROLLER_POWER).min(energy),
+                );
+            }
+            Err(_) => return Some(next_state()),
+        }
+    }
+
+    None
+}
+
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 pub fn tick_claim<F, R>(tick_context: &mut JobTickContext, controller_id: RemoteObjectId<StructureController>, next_state: F) -> Option<R>
 where
@@ -158,11 +281,18 @@ where
         // the reservation ticks before attempting to claim. claimController()
         // fails on reserved controllers.
         if controller.reservation().is_some() {
-            let _ = creep.attack_controller(&controller);
+            let _ = tick_context
+                .runtime_data
+                .intent_recorder
+                .issued(IntentCategory::Controller, creep.attack_controller(&controller));
             return None;
         }
 
-        match creep.claim_controller(&controller) {
+        match tick_context
+            .runtime_data
+            .intent_recorder
+            .issued(IntentCategory::Controller, creep.claim_controller(&controller))
+        {
             Ok(()) => None,
             Err(_) => Some(next_state()),
         }
@@ -200,6 +330,16 @@ where
 
     if let Some(controller) = controller_id.resolve() {
         if let Some(reservation) = controller.reservation() {
+            // Another player's reservation blocks reserveController; wear it down instead (the reserve
+            // mission sends a larger reserver to a contested remote).
+            if reservation.username() != creep.owner().username() {
+                let _ = tick_context
+                    .runtime_data
+                    .intent_recorder
+                    .issued(IntentCategory::Controller, creep.attack_controller(&controller));
+                return None;
+            }
+
             let body = creep.body();
             let claim_parts = body.iter().filter(|b| b.part() == Part::Claim).count();
             let claim_amount = claim_parts as u32 * CONTROLLER_RESERVE;
@@ -209,7 +349,11 @@ where
             }
         }
 
-        match creep.reserve_controller(&controller) {
+        match tick_context
+            .runtime_data
+            .intent_recorder
+            .issued(IntentCategory::Controller, creep.reserve_controller(&controller))
+        {
             Ok(()) => None,
             Err(_) => Some(next_state()),
         }
@@ -272,7 +416,10 @@ where
         }
 
         if tick_context.action_flags.consume(SimultaneousActionFlags::ATTACK_CONTROLLER) {
-            let _ = creep.attack_controller(&controller);
+            let _ = tick_context
+                .runtime_data
+                .intent_recorder
+                .issued(IntentCategory::Controller, creep.attack_controller(&controller));
         }
 
         Some(next_state())
@@ -281,6 +428,53 @@ where
     }
 }
 
+/// Sign the controller with `message` when the creep is next to it and the
+/// sign on it isn't already ours with that text. Signing shares pipeline F
+/// with claim and reserve; a rejected sign holds off retries in that room for
+/// [`SIGN_RETRY_TICKS`]. Returns whether a sign was issued.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn sign_controller_if_stale(
+    tick_context: &mut JobTickContext,
+    controller_id: RemoteObjectId<StructureController>,
+    message: &str,
+) -> bool {
+    let creep = tick_context.runtime_data.owner;
+    let room_name = controller_id.pos().room_name();
+    let now = game::time();
+
+    if !creep.pos().is_near_to(controller_id.pos()) || !tick_context.runtime_data.sign_cooldowns.ready(room_name, now) {
+        return false;
+    }
+
+    let Some(controller) = controller_id.resolve() else {
+        return false;
+    };
+
+    let sign = controller.sign().map(|s| (s.username(), s.text()));
+    let username = creep.owner().username();
+
+    if !needs_sign(
+        sign.as_ref().map(|(signer, text)| (signer.as_str(), text.as_str())),
+        &username,
+        message,
+    ) {
+        return false;
+    }
+
+    if !tick_context.action_flags.consume(SimultaneousActionFlags::SIGN) {
+        return false;
+    }
+
+    let accepted = tick_context
+        .runtime_data
+        .intent_recorder
+        .issued(IntentCategory::Controller, creep.sign_controller(&controller, message))
+        .is_ok();
+    tick_context.runtime_data.sign_cooldowns.record(room_name, accepted, now);
+
+    accepted
+}
+
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 pub fn tick_sign<F, R>(
     tick_context: &mut JobTickContext,
@@ -292,7 +486,6 @@ where
     F: Fn() -> R,
 {
     let creep = tick_context.runtime_data.owner;
-    let action_flags = &mut tick_context.action_flags;
 
     let creep_pos = creep.pos();
     let target_position = controller_id.pos();
@@ -300,7 +493,7 @@ where
     //TODO: Check visibility cache and cancel if controller doesn't exist or is owned?
 
     if !creep_pos.is_near_to(target_position) {
-        if action_flags.consume(SimultaneousActionFlags::MOVE) {
+        if tick_context.action_flags.consume(SimultaneousActionFlags::MOVE) {
             tick_context
                 .runtime_data
                 .movement
@@ -311,11 +504,37 @@ where
         return None;
     }
 
-    if let Some(controller) = controller_id.resolve() {
-        if action_flags.consume(SimultaneousActionFlags::SIGN) {
-            let _ = creep.sign_controller(&controller, message);
-        }
-    }
+    sign_controller_if_stale(tick_context, controller_id, message);
 
     Some(next_state())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_a_matching_sign_of_ours_is_left_alone() {
+        let message = "Rusty robots!";
+
+        assert!(needs_sign(None, "me", message));
+        assert!(needs_sign(Some(("enemy", message)), "me", message));
+        assert!(needs_sign(Some(("me", "an old message")), "me", message));
+        assert!(!needs_sign(Some(("me", message)), "me", message));
+    }
+
+    #[test]
+    fn a_rejected_sign_waits_out_the_retry_window() {
+        let room = RoomName::new("W1N1").unwrap();
+        let mut cooldowns = SignCooldowns::default();
+        assert!(cooldowns.ready(room, 100));
+
+        cooldowns.record(room, false, 100);
+        assert!(!cooldowns.ready(room, 100 + SIGN_RETRY_TICKS - 1));
+        assert!(cooldowns.ready(room, 100 + SIGN_RETRY_TICKS));
+        assert!(cooldowns.ready(RoomName::new("W2N1").unwrap(), 101), "other rooms are unaffected");
+
+        cooldowns.record(room, true, 150);
+        assert!(cooldowns.ready(room, 150));
+    }
+}
@@ -201,6 +201,24 @@ pub fn breach_path_blockers(
     blockers: &std::collections::HashMap<(u8, u8), BreachBlocker>,
     start: (u8, u8),
     goal: (u8, u8),
+) -> Option<Vec<(u8, u8)>> {
+    let path = breach_path(is_wall, blockers, start, goal)?;
+
+    Some(
+        path.into_iter()
+            .filter(|tile| matches!(blockers.get(tile), Some(BreachBlocker::Dismantlable(_))))
+            .collect(),
+    )
+}
+
+/// The whole walk of the [`breach_path_blockers`] corridor, every tile from
+/// `start` to range 1 of `goal` and not just the blockers, for callers that
+/// need to know what lies either side of a blocker.
+pub fn breach_path(
+    is_wall: &dyn Fn(u8, u8) -> bool,
+    blockers: &std::collections::HashMap<(u8, u8), BreachBlocker>,
+    start: (u8, u8),
+    goal: (u8, u8),
 ) -> Option<Vec<(u8, u8)>> {
     let enter_cost = |x: u8, y: u8| -> Option<u64> {
         if is_wall(x, y) {
@@ -214,13 +232,7 @@ pub fn breach_path_blockers(
         }
     };
 
-    let path = screeps_rover::room_grid_dijkstra(&enter_cost, start, goal, 1)?;
-
-    Some(
-        path.into_iter()
-            .filter(|tile| matches!(blockers.get(tile), Some(BreachBlocker::Dismantlable(_))))
-            .collect(),
-    )
+    screeps_rover::room_grid_dijkstra(&enter_cost, start, goal, 1)
 }
 
 /// Breach-corridor blockers on the cheapest corridor from `start` OUT to the
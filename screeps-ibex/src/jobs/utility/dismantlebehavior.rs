@@ -1,4 +1,5 @@
 use super::dismantle::*;
+use crate::intents::IntentCategory;
 use crate::jobs::actions::*;
 use crate::jobs::context::*;
 use crate::jobs::utility::movebehavior::mark_working;
@@ -511,7 +512,7 @@ where
     if let Some(structure) = dismantle_target.as_ref() {
         if tick_context.action_flags.consume(SimultaneousActionFlags::DISMANTLE) {
             if let Some(dismantleable) = structure.as_dismantleable() {
-                match creep.dismantle(dismantleable) {
+                match tick_context.runtime_data.intent_recorder.issued(IntentCategory::Dismantle, creep.dismantle(dismantleable)) {
                     Ok(()) => None,
                     Err(_) => Some(next_state()),
                 }
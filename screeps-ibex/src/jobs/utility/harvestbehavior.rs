@@ -1,7 +1,9 @@
 use crate::findnearest::*;
+use crate::intents::IntentCategory;
 use crate::jobs::actions::*;
 use crate::jobs::context::*;
 use crate::jobs::utility::movebehavior::mark_working;
+use crate::ledger::LedgerCategory;
 use crate::remoteobjectid::*;
 use crate::room::data::*;
 use screeps::*;
@@ -48,18 +50,29 @@ where
 
 pub trait HarvestableResource {
     fn get_harvestable_amount(&self) -> u32;
+
+    /// Whether harvesting this yields energy (reported to the ledger).
+    fn yields_energy(&self) -> bool;
 }
 
 impl HarvestableResource for Source {
     fn get_harvestable_amount(&self) -> u32 {
         self.energy()
     }
+
+    fn yields_energy(&self) -> bool {
+        true
+    }
 }
 
 impl HarvestableResource for Mineral {
     fn get_harvestable_amount(&self) -> u32 {
         self.mineral_amount()
     }
+
+    fn yields_energy(&self) -> bool {
+        false
+    }
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -106,13 +119,24 @@ where
 
     if let Some(harvest_target) = target_id.resolve() {
         if tick_context.action_flags.consume(SimultaneousActionFlags::HARVEST) {
-            match creep.harvest(&harvest_target) {
+            match tick_context
+                .runtime_data
+                .intent_recorder
+                .issued(IntentCategory::Harvest, creep.harvest(&harvest_target))
+            {
                 Ok(()) => {
-                    if optimistic_completion {
-                        let body = creep.body();
-                        let work_parts = body.iter().filter(|b| b.part() == Part::Work).count();
-                        let harvest_amount = (work_parts as u32 * HARVEST_POWER).min(harvest_target.get_harvestable_amount());
+                    let body = creep.body();
+                    let work_parts = body.iter().filter(|b| b.part() == Part::Work).count();
+                    let harvest_amount = (work_parts as u32 * HARVEST_POWER).min(harvest_target.get_harvestable_amount());
+
+                    if harvest_target.yields_energy() {
+                        tick_context
+                            .runtime_data
+                            .ledger
+                            .add(target_position.room_name(), LedgerCategory::Harvest, harvest_amount);
+                    }
 
+                    if optimistic_completion {
                         if harvest_amount as i32 >= creep.store().get_free_capacity(Some(ResourceType::Energy)) {
                             Some(next_state())
                         } else {
@@ -1,4 +1,5 @@
 use crate::findnearest::*;
+use crate::intents::IntentCategory;
 use crate::jobs::actions::*;
 use crate::jobs::context::*;
 use crate::jobs::jobsystem::*;
@@ -6,7 +7,26 @@ use crate::room::data::*;
 use crate::transfer::transfersystem::*;
 use itertools::*;
 use screeps::*;
-use std::collections::HashMap;
+use specs::Entity;
+use std::collections::{HashMap, HashSet};
+
+/// Tiles of extra walk a full load is worth over a pickup that fills
+/// nothing, so haulers pass a nearly drained container for a fuller one a
+/// little farther instead of all crowding the nearest.
+const EMPTY_PICKUP_RANGE: f32 = 10.0;
+
+/// Score of a pickup `range` away offering `amount` to a creep with
+/// `free_capacity`, lowest best: the range plus a share of
+/// [`EMPTY_PICKUP_RANGE`] for the part of the load it leaves empty.
+fn pickup_score(range: u32, amount: u32, free_capacity: u32) -> f32 {
+    let fill = (amount as f32 / free_capacity.max(1) as f32).min(1.0);
+
+    range as f32 + EMPTY_PICKUP_RANGE * (1.0 - fill)
+}
+
+fn ticket_amount(ticket: &TransferWithdrawTicket) -> u32 {
+    ticket.resources().values().flatten().map(|entry| entry.amount()).sum()
+}
 
 #[allow(clippy::too_many_arguments)]
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -40,12 +60,16 @@ where
             transfer_types,
             &desired_resources,
             TransferCapacity::Infinite,
+            false,
         );
 
-        if let Some(pickup) = pickups
-            .into_iter()
-            .find_nearest_linear_by(creep.pos(), |ticket| ticket.target().pos().into())
-        {
+        if let Some(pickup) = pickups.into_iter().find_best_by(
+            creep.pos(),
+            Distance::Linear,
+            TieBreak::seeded_by(&creep.name()),
+            |ticket| ticket.target().pos().into(),
+            |range, ticket| pickup_score(range, ticket_amount(ticket), free_capacity),
+        ) {
             transfer_queue.register_pickup(&pickup);
 
             return Some(state_map(pickup));
@@ -98,6 +122,7 @@ where
             transfer_types,
             &desired_resources,
             TransferCapacity::Infinite,
+            false,
         );
 
         let creep_pos = creep.pos();
@@ -111,10 +136,13 @@ where
             pickups
         };
 
-        if let Some(pickup) = filtered
-            .into_iter()
-            .find_nearest_linear_by(creep_pos, |ticket| ticket.target().pos().into())
-        {
+        if let Some(pickup) = filtered.into_iter().find_best_by(
+            creep_pos,
+            Distance::Linear,
+            TieBreak::seeded_by(&creep.name()),
+            |ticket| ticket.target().pos().into(),
+            |range, ticket| pickup_score(range, ticket_amount(ticket), free_capacity),
+        ) {
             transfer_queue.register_pickup(&pickup);
 
             return Some(state_map(pickup));
@@ -161,10 +189,13 @@ where
             target_filter,
         );
 
-        if let Some(delivery) = deliveries
-            .into_iter()
-            .find_nearest_linear_by(creep.pos(), |ticket| ticket.target().pos().into())
-        {
+        if let Some(delivery) = deliveries.into_iter().find_best_by(
+            creep.pos(),
+            Distance::Linear,
+            TieBreak::seeded_by(&creep.name()),
+            |ticket| ticket.target().pos().into(),
+            |range, _| range,
+        ) {
             transfer_queue.register_delivery(&delivery);
 
             let deliveries = vec![delivery];
@@ -238,6 +269,8 @@ where
                 allowed_secondary_range,
             );
 
+            transfer_queue.record_flows(&pickup, &deliveries);
+
             return Some(state_map(pickup, deliveries));
         }
     }
@@ -401,13 +434,150 @@ where
     )
 }
 
+/// Haulers that took their opportunistic pickup this trip (see
+/// [`tick_opportunistic_pickup`]). Session-only: a VM reload just allows a
+/// second one on the trip in flight.
+#[derive(Default)]
+pub struct OpportunisticPickups {
+    taken: HashSet<Entity>,
+}
+
+impl OpportunisticPickups {
+    /// Start a new trip for `creep`, allowing it another pickup.
+    pub fn begin_trip(&mut self, creep: Entity) {
+        self.taken.remove(&creep);
+    }
+
+    /// Forget the creeps `alive` rejects.
+    pub fn retain<F>(&mut self, alive: F)
+    where
+        F: Fn(Entity) -> bool,
+    {
+        self.taken.retain(|creep| alive(*creep));
+    }
+}
+
+/// Top up from a node the creep is walking past. When it stands next to a
+/// target with an active withdrawl of a resource it is already delivering, it
+/// withdraws now and the matching delivery is merged into `deposits`, so the
+/// extra cargo goes where the creep was headed anyway.
+///
+/// `reserved` is the capacity promised to the pickup in flight, and `exclude`
+/// that pickup's target. Only nodes the transfer queue has already generated
+/// this tick are looked at, a node offering several resources is left alone
+/// (one withdraw per tick), and a creep takes one such pickup per trip.
+/// Returns whether a withdraw was issued.
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn tick_opportunistic_pickup<TF>(
+    tick_context: &mut JobTickContext,
+    delivery_rooms: &[&RoomData],
+    reserved: u32,
+    exclude: Option<&TransferTarget>,
+    deposits: &mut Vec<TransferDepositTicket>,
+    target_filter: TF,
+) -> bool
+where
+    TF: Fn(&TransferTarget) -> bool,
+{
+    let creep_entity = tick_context.runtime_data.creep_entity;
+
+    if deposits.is_empty()
+        || tick_context.action_flags.intersects(SimultaneousActionFlags::TRANSFER)
+        || tick_context.runtime_data.opportunistic_pickups.taken.contains(&creep_entity)
+    {
+        return false;
+    }
+
+    let creep = tick_context.runtime_data.owner;
+
+    // Safe on general stores (engine-mechanics folklore row 26).
+    let free_capacity = (creep.store().get_free_capacity(None).max(0) as u32).saturating_sub(reserved);
+
+    if free_capacity == 0 {
+        return false;
+    }
+
+    let creep_pos = creep.pos();
+    let delivery_resources: HashSet<ResourceType> = deposits.iter().flat_map(|d| d.resources().keys().copied()).collect();
+
+    let Some(target) = tick_context
+        .runtime_data
+        .transfer_queue
+        .try_get_room_no_flush(creep_pos.room_name())
+        .and_then(|room| {
+            room.nodes_in_range(creep_pos, 1)
+                .filter(|(target, _)| Some(*target) != exclude && !deposits.iter().any(|d| d.target() == *target))
+                .find(|(_, node)| {
+                    node.get_available_withdrawl_totals(TransferTypeFlags::HAUL, TransferPriorityFlags::ACTIVE)
+                        .keys()
+                        .any(|resource| delivery_resources.contains(resource))
+                })
+                .map(|(target, _)| *target)
+        })
+    else {
+        return false;
+    };
+
+    let transfer_queue_data = TransferQueueGeneratorData {
+        cause: "Opportunistic Pickup",
+        room_data: tick_context.system_data.room_data,
+    };
+
+    let delivery_room_names = delivery_rooms.iter().map(|r| r.name).collect_vec();
+    let deposit_targets = deposits.iter().map(|d| *d.target()).collect_vec();
+
+    let Some((pickup, delivery)) = tick_context.runtime_data.transfer_queue.get_delivery_from_target(
+        &transfer_queue_data,
+        &delivery_room_names,
+        &target,
+        TransferPriorityFlags::ACTIVE,
+        TransferPriorityFlags::ALL,
+        TransferType::Haul,
+        TransferCapacity::Finite(free_capacity),
+        target.pos(),
+        |delivery_target| target_filter(delivery_target) && deposit_targets.contains(delivery_target),
+    ) else {
+        return false;
+    };
+
+    if pickup.resources().len() != 1 {
+        return false;
+    }
+
+    let Some((resource, amount)) = pickup.get_next_withdrawl() else {
+        return false;
+    };
+
+    if tick_context
+        .runtime_data
+        .intent_recorder
+        .issued(IntentCategory::Transfer, target.withdraw_resource_amount(creep, resource, amount))
+        .is_err()
+    {
+        return false;
+    }
+
+    tick_context.action_flags.insert(SimultaneousActionFlags::TRANSFER);
+    tick_context.runtime_data.opportunistic_pickups.taken.insert(creep_entity);
+
+    tick_context.runtime_data.transfer_queue.register_pickup(&pickup);
+    tick_context.runtime_data.transfer_queue.register_delivery(&delivery);
+
+    match deposits.iter_mut().find(|d| d.target() == delivery.target()) {
+        Some(deposit) => deposit.combine_with(&delivery),
+        None => deposits.push(delivery),
+    }
+
+    true
+}
+
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 pub fn tick_pickup<F, R>(tick_context: &mut JobTickContext, ticket: &mut TransferWithdrawTicket, next_state: F) -> Option<R>
 where
     F: FnOnce() -> R,
 {
     //TODO: Use visibility to query if target should be visible.
-    if !ticket.target().is_valid() || ticket.get_next_withdrawl().is_none() {
+    if !ticket.revalidate_target() || ticket.get_next_withdrawl().is_none() {
         return Some(next_state());
     }
 
@@ -438,7 +608,15 @@ where
             if !action_flags.intersects(SimultaneousActionFlags::TRANSFER) {
                 ticket.consume_withdrawl(resource, amount);
 
-                if ticket.target().withdraw_resource_amount(creep, resource, amount).is_ok() {
+                if tick_context
+                    .runtime_data
+                    .intent_recorder
+                    .issued(
+                        IntentCategory::Transfer,
+                        ticket.target().withdraw_resource_amount(creep, resource, amount),
+                    )
+                    .is_ok()
+                {
                     action_flags.insert(SimultaneousActionFlags::TRANSFER);
                     break None;
                 }
@@ -529,7 +707,7 @@ where
 
     while let Some(ticket) = tickets.first_mut() {
         //TODO: Use visibility to query if target should be visible.
-        if ticket.target().is_valid() && ticket.get_next_deposit().is_some() {
+        if ticket.revalidate_target() && ticket.get_next_deposit().is_some() {
             let pos: screeps::Position = ticket.target().pos().into();
 
             if !creep_pos.is_near_to(pos) {
@@ -551,7 +729,15 @@ where
                 if !tick_context.action_flags.intersects(SimultaneousActionFlags::TRANSFER) {
                     ticket.consume_deposit(resource, amount);
 
-                    if ticket.target().creep_transfer_resource_amount(creep, resource, amount).is_ok() {
+                    if tick_context
+                        .runtime_data
+                        .intent_recorder
+                        .issued(
+                            IntentCategory::Transfer,
+                            ticket.target().creep_transfer_resource_amount(creep, resource, amount),
+                        )
+                        .is_ok()
+                    {
                         tick_context.action_flags.insert(SimultaneousActionFlags::TRANSFER);
 
                         transfered = true;
@@ -586,11 +772,11 @@ pub fn visualize_delivery_from(_describe_data: &mut JobDescribeData, _tickets: &
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
-pub fn tick_deposit_all_resources_state<F, R>(tick_context: &mut JobTickContext, target: TransferTarget, next_state: F) -> Option<R>
+pub fn tick_deposit_all_resources_state<F, R>(tick_context: &mut JobTickContext, mut target: TransferTarget, next_state: F) -> Option<R>
 where
     F: FnOnce() -> R,
 {
-    if target.is_valid() {
+    if target.revalidate() {
         let creep = tick_context.runtime_data.owner;
         let creep_pos = creep.pos();
 
@@ -618,7 +804,15 @@ where
             if tick_context.action_flags.consume(SimultaneousActionFlags::TRANSFER) {
                 let amount = creep.store().get_used_capacity(Some(*resource));
 
-                if target.creep_transfer_resource_amount(creep, *resource, amount).is_ok() {
+                if tick_context
+                    .runtime_data
+                    .intent_recorder
+                    .issued(
+                        IntentCategory::Transfer,
+                        target.creep_transfer_resource_amount(creep, *resource, amount),
+                    )
+                    .is_ok()
+                {
                     if store_types.len() == 1 {
                         return Some(next_state());
                     } else {
@@ -653,6 +847,18 @@ mod tests {
         )
     }
 
+    #[test]
+    fn a_full_load_a_little_farther_beats_a_nearly_drained_pickup() {
+        let drained = pickup_score(2, 100, 1_000);
+        let full = pickup_score(8, 1_000, 1_000);
+
+        assert!(full < drained);
+        // Far enough out, the nearer partial load wins again.
+        assert!(pickup_score(12, 1_000, 1_000) > drained);
+        // Offering more than the creep holds is no better than filling it.
+        assert_eq!(pickup_score(5, 5_000, 1_000), pickup_score(5, 1_000, 1_000));
+    }
+
     #[test]
     fn anchor_range_keeps_upgrade_container_eligible() {
         assert!(within_anchor_range(pos(36, 9), pos(39, 12), 5));
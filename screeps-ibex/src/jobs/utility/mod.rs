@@ -6,6 +6,9 @@ pub mod dismantlebehavior;
 pub mod harvestbehavior;
 pub mod haulbehavior;
 pub mod movebehavior;
+pub mod parkingbehavior;
 pub mod repair;
 pub mod repairbehavior;
+pub mod saybehavior;
+pub mod unboostbehavior;
 pub mod waitbehavior;
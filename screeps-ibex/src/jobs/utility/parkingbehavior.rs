@@ -0,0 +1,265 @@
+use super::movebehavior::mark_idle;
+use crate::jobs::context::*;
+use crate::room::data::*;
+use screeps::*;
+use screeps_foreman::constants::*;
+use screeps_rover::*;
+use specs::Entity;
+use std::collections::{HashMap, HashSet};
+
+/// Parking tiles are kept off the storage/spawn service ring but close enough
+/// that an idle creep is a short walk from its next pickup.
+const PARKING_MIN_RANGE: u8 = 2;
+const PARKING_MAX_RANGE: u8 = 5;
+const MAX_PARKING_SPOTS: usize = 12;
+/// Structures change slowly; re-derive a room's spots this often.
+const PARKING_REFRESH_TICKS: u32 = 500;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ParkingTile {
+    Open,
+    Road,
+    Blocked,
+}
+
+/// Pick parking tiles around `anchor`: open tiles in the parking ring, away
+/// from the room edge, preferring the ones with the fewest roads beside them
+/// and then the ones nearest the anchor.
+pub fn parking_tiles<F>(anchor: (u8, u8), tile: F) -> Vec<(u8, u8)>
+where
+    F: Fn(u8, u8) -> ParkingTile,
+{
+    let (ax, ay) = anchor;
+    let min = |c: u8| c.saturating_sub(PARKING_MAX_RANGE).max(2);
+
+    let mut candidates = Vec::new();
+
+    for x in min(ax)..=(ax + PARKING_MAX_RANGE).min(ROOM_WIDTH - 3) {
+        for y in min(ay)..=(ay + PARKING_MAX_RANGE).min(ROOM_HEIGHT - 3) {
+            let range = ax.abs_diff(x).max(ay.abs_diff(y));
+
+            if range < PARKING_MIN_RANGE || tile(x, y) != ParkingTile::Open {
+                continue;
+            }
+
+            let adjacent_roads = (x - 1..=x + 1)
+                .flat_map(|nx| (y - 1..=y + 1).map(move |ny| (nx, ny)))
+                .filter(|(nx, ny)| tile(*nx, *ny) == ParkingTile::Road)
+                .count();
+
+            candidates.push((adjacent_roads, range, (x, y)));
+        }
+    }
+
+    candidates.sort();
+
+    candidates.into_iter().take(MAX_PARKING_SPOTS).map(|(_, _, xy)| xy).collect()
+}
+
+struct ParkingLot {
+    spots: Vec<Position>,
+    computed_at: u32,
+}
+
+impl ParkingLot {
+    fn compute(room_data: &RoomData) -> ParkingLot {
+        ParkingLot {
+            spots: Self::spots(room_data).unwrap_or_default(),
+            computed_at: game::time(),
+        }
+    }
+
+    fn spots(room_data: &RoomData) -> Option<Vec<Position>> {
+        let structures = room_data.get_structures()?;
+        let terrain = game::map::get_room_terrain(room_data.name)?;
+
+        let anchor = structures
+            .storages()
+            .first()
+            .map(|storage| storage.pos())
+            .or_else(|| structures.spawns().first().map(|spawn| spawn.pos()))?;
+
+        let roads: HashSet<_> = structures
+            .roads()
+            .iter()
+            .map(|road| (road.pos().x().u8(), road.pos().y().u8()))
+            .collect();
+
+        let mut blocked: HashSet<_> = structures
+            .all()
+            .iter()
+            .map(|structure| structure.pos())
+            .chain(
+                room_data
+                    .get_construction_sites()
+                    .iter()
+                    .flat_map(|sites| sites.iter().map(|site| site.pos()).collect::<Vec<_>>()),
+            )
+            .map(|pos| (pos.x().u8(), pos.y().u8()))
+            .filter(|xy| !roads.contains(xy))
+            .collect();
+
+        // Keep miners', upgraders' and container tiles free: nothing parks
+        // next to a source, mineral, controller or container.
+        let static_data = room_data.get_static_visibility_data();
+        let keep_clear = static_data
+            .into_iter()
+            .flat_map(|data| {
+                data.sources()
+                    .iter()
+                    .map(|source| source.pos())
+                    .chain(data.minerals().iter().map(|mineral| mineral.pos()))
+                    .chain(data.controller().map(|controller| controller.pos()))
+            })
+            .chain(structures.containers().iter().map(|container| container.pos()));
+
+        for pos in keep_clear {
+            let (x, y) = (pos.x().u8(), pos.y().u8());
+
+            for nx in x.saturating_sub(1)..=(x + 1).min(ROOM_WIDTH - 1) {
+                for ny in y.saturating_sub(1)..=(y + 1).min(ROOM_HEIGHT - 1) {
+                    blocked.insert((nx, ny));
+                }
+            }
+        }
+
+        let tiles = parking_tiles((anchor.x().u8(), anchor.y().u8()), |x, y| {
+            if terrain.get(x, y) == Terrain::Wall || blocked.contains(&(x, y)) {
+                ParkingTile::Blocked
+            } else if roads.contains(&(x, y)) {
+                ParkingTile::Road
+            } else {
+                ParkingTile::Open
+            }
+        });
+
+        Some(
+            tiles
+                .into_iter()
+                .filter_map(|(x, y)| {
+                    Some(Position::new(
+                        RoomCoordinate::new(x).ok()?,
+                        RoomCoordinate::new(y).ok()?,
+                        room_data.name,
+                    ))
+                })
+                .collect(),
+        )
+    }
+
+    fn stale(&self) -> bool {
+        game::time().saturating_sub(self.computed_at) >= PARKING_REFRESH_TICKS
+    }
+}
+
+/// Where idle creeps wait, per room. Assignments live for one tick: a job
+/// still idling renews its spot from `gather_data`, so a creep that goes back
+/// to work or dies gives its spot up by not renewing it.
+#[derive(Default)]
+pub struct ParkingRegistry {
+    rooms: HashMap<RoomName, ParkingLot>,
+    assigned: HashMap<Entity, Position>,
+    previous: HashMap<Entity, Position>,
+}
+
+impl ParkingRegistry {
+    pub fn begin_tick(&mut self) {
+        self.previous = std::mem::take(&mut self.assigned);
+    }
+
+    /// Keep the spot `creep` held last tick.
+    pub fn hold(&mut self, creep: Entity) {
+        if let Some(spot) = self.previous.get(&creep) {
+            self.assigned.insert(creep, *spot);
+        }
+    }
+
+    /// The spot `creep` should idle on in `room_data`, assigning the free
+    /// spot nearest `creep_pos` if it doesn't hold one there yet.
+    pub fn assign(&mut self, creep: Entity, creep_pos: Position, room_data: &RoomData) -> Option<Position> {
+        if let Some(spot) = self.assigned.get(&creep).filter(|spot| spot.room_name() == room_data.name) {
+            return Some(*spot);
+        }
+
+        let lot = self.rooms.entry(room_data.name).or_insert_with(|| ParkingLot::compute(room_data));
+
+        if lot.stale() {
+            *lot = ParkingLot::compute(room_data);
+        }
+
+        let taken: HashSet<_> = self.assigned.values().collect();
+
+        let spot = lot
+            .spots
+            .iter()
+            .filter(|spot| !taken.contains(spot))
+            .min_by_key(|spot| spot.get_range_to(creep_pos))
+            .copied()?;
+
+        self.assigned.insert(creep, spot);
+
+        Some(spot)
+    }
+}
+
+/// Idle on a parking spot in the creep's current room, or in place where the
+/// room has none (no storage or spawn, or every spot taken).
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+pub fn park_idle(tick_context: &mut JobTickContext) {
+    let creep_entity = tick_context.runtime_data.creep_entity;
+    let creep_pos = tick_context.runtime_data.owner.pos();
+
+    let spot = tick_context
+        .runtime_data
+        .mapping
+        .get_room(&creep_pos.room_name())
+        .and_then(|room_entity| tick_context.system_data.room_data.get(room_entity))
+        .and_then(|room_data| tick_context.runtime_data.parking.assign(creep_entity, creep_pos, room_data));
+
+    match spot {
+        Some(spot) if spot != creep_pos => {
+            tick_context
+                .runtime_data
+                .movement
+                .move_to(creep_entity, spot)
+                .range(0)
+                .priority(MovementPriority::Low)
+                .allow_shove(true)
+                .allow_swap(true);
+        }
+        _ => mark_idle(tick_context),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parking_stays_in_the_ring_around_the_anchor() {
+        let tiles = parking_tiles((25, 25), |_, _| ParkingTile::Open);
+
+        assert_eq!(tiles.len(), MAX_PARKING_SPOTS);
+        assert!(tiles.iter().all(|(x, y)| {
+            let range = 25u8.abs_diff(*x).max(25u8.abs_diff(*y));
+            (PARKING_MIN_RANGE..=PARKING_MAX_RANGE).contains(&range)
+        }));
+    }
+
+    #[test]
+    fn parking_avoids_roads_and_prefers_tiles_away_from_them() {
+        // A road runs along column 27.
+        let tile = |x: u8, _y: u8| if x == 27 { ParkingTile::Road } else { ParkingTile::Open };
+        let tiles = parking_tiles((25, 25), tile);
+
+        assert!(tiles.iter().all(|(x, _)| *x != 26 && *x != 27 && *x != 28));
+    }
+
+    #[test]
+    fn parking_keeps_off_the_room_edge() {
+        let tiles = parking_tiles((2, 2), |_, _| ParkingTile::Open);
+
+        assert!(tiles.iter().all(|(x, y)| *x >= 2 && *y >= 2));
+        assert!(!tiles.is_empty());
+    }
+}
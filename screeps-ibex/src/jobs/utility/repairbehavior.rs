@@ -1,7 +1,9 @@
 use super::repair::*;
+use crate::intents::IntentCategory;
 use crate::jobs::actions::*;
 use crate::jobs::context::*;
 use crate::jobs::utility::movebehavior::mark_working;
+use crate::ledger::LedgerCategory;
 use crate::repairqueue::RepairQueue;
 use crate::room::data::*;
 use crate::structureidentifier::*;
@@ -87,8 +89,21 @@ where
 
         if tick_context.action_flags.consume(SimultaneousActionFlags::REPAIR) {
             if let Some(repairable) = structure.as_repairable() {
-                match creep.repair(repairable) {
-                    Ok(()) => None,
+                match tick_context
+                    .runtime_data
+                    .intent_recorder
+                    .issued(IntentCategory::Repair, creep.repair(repairable))
+                {
+                    Ok(()) => {
+                        // One energy per WORK part per tick (REPAIR_COST · REPAIR_POWER).
+                        let work_parts = creep.body().iter().filter(|p| p.part() == Part::Work).count() as u32;
+                        let carried = creep.store().get_used_capacity(Some(ResourceType::Energy));
+                        tick_context
+                            .runtime_data
+                            .ledger
+                            .add(target_position.room_name(), LedgerCategory::Repair, work_parts.min(carried));
+                        None
+                    }
                     Err(_) => Some(next_state()),
                 }
             } else {
@@ -106,10 +121,13 @@ where
 /// (e.g. hauling, harvesting, moving). Checks the repair queue for in-range
 /// mission-requested repairs first, then falls back to a room scan.
 ///
-/// Returns the amount of energy consumed if a repair was performed.
+/// Returns the amount of energy consumed if a repair was performed. Skipped
+/// once the tick's repair intents reach the low-bucket soft cap.
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 pub fn tick_opportunistic_repair(tick_context: &mut JobTickContext, minimum_priority: Option<RepairPriority>) -> Option<u32> {
-    if !tick_context.action_flags.intersects(SimultaneousActionFlags::REPAIR) {
+    if !tick_context.action_flags.intersects(SimultaneousActionFlags::REPAIR)
+        && tick_context.runtime_data.intent_recorder.allow(IntentCategory::Repair)
+    {
         let creep = tick_context.runtime_data.owner;
 
         let available_energy = creep.store().get(ResourceType::Energy).unwrap_or(0);
@@ -139,7 +157,11 @@ pub fn tick_opportunistic_repair(tick_context: &mut JobTickContext, minimum_prio
                     if let Some(structure) = target_id.resolve() {
                         if tick_context.action_flags.consume(SimultaneousActionFlags::REPAIR) {
                             if let Some(repairable) = structure.as_repairable() {
-                                match creep.repair(repairable) {
+                                match tick_context
+                                    .runtime_data
+                                    .intent_recorder
+                                    .issued(IntentCategory::Repair, creep.repair(repairable))
+                                {
                                     Ok(()) => {
                                         let max_energy_consumed = work_body_parts.min(available_energy);
                                         let (hits, hits_max) =
@@ -147,6 +169,12 @@ pub fn tick_opportunistic_repair(tick_context: &mut JobTickContext, minimum_prio
                                         let max_repair_energy = ((hits_max - hits) as f32 / REPAIR_POWER as f32).ceil() as u32;
                                         let energy_consumed = max_energy_consumed.min(max_repair_energy);
 
+                                        tick_context.runtime_data.ledger.add(
+                                            creep_pos.room_name(),
+                                            LedgerCategory::Repair,
+                                            energy_consumed,
+                                        );
+
                                         return Some(energy_consumed);
                                     }
                                     Err(err) => {
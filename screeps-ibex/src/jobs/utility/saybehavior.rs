@@ -0,0 +1,88 @@
+use super::super::jobsystem::*;
+use super::movebehavior::STUCK_REPORT_THRESHOLD;
+use crate::intents::IntentCategory;
+use crate::transfer::transfersystem::*;
+use screeps::*;
+use screeps_rover::*;
+
+/// The most characters a creep can say; longer text is cut by the engine.
+pub const SAY_MAX_CHARS: usize = 10;
+
+/// Code said by a creep whose move failed or has been stuck, whatever its job.
+pub const STUCK_CODE: &str = "stk";
+
+/// Cut `code` to what fits in a say bubble. Counts characters, not bytes, so
+/// arrows and other symbols are never split.
+pub fn say_text(code: &str) -> String {
+    code.chars().take(SAY_MAX_CHARS).collect()
+}
+
+/// Short name for a transfer target, e.g. `S` for storage, for codes such as
+/// `H→S`.
+pub fn target_code(target: &TransferTarget) -> &'static str {
+    match target {
+        TransferTarget::Container(_) => "C",
+        TransferTarget::Spawn(_) => "Sp",
+        TransferTarget::Extension(_) => "E",
+        TransferTarget::Storage(_) => "S",
+        TransferTarget::Tower(_) => "T",
+        TransferTarget::Link(_) => "L",
+        TransferTarget::Ruin(_) => "R",
+        TransferTarget::Tombstone(_) => "Tb",
+        TransferTarget::Resource(_) => "Dr",
+        TransferTarget::Terminal(_) => "Tm",
+        TransferTarget::Lab(_) => "Lb",
+        TransferTarget::Factory(_) => "F",
+        TransferTarget::Nuker(_) => "N",
+        TransferTarget::PowerSpawn(_) => "PS",
+        #[cfg(feature = "season")]
+        TransferTarget::ScoreContainer(_) => "Sc",
+        #[cfg(feature = "season")]
+        TransferTarget::ScoreCollector(_) => "Sk",
+    }
+}
+
+/// `prefix` followed by the first deposit's target, e.g. `H→S`.
+pub fn deposit_code(prefix: &str, deposits: &[TransferDepositTicket]) -> String {
+    match deposits.first() {
+        Some(ticket) => format!("{}{}", prefix, target_code(ticket.target())),
+        None => prefix.to_string(),
+    }
+}
+
+/// Whether the rover reported the creep's last move as failed or stuck.
+fn is_stuck(runtime_data: &JobExecutionRuntimeData) -> bool {
+    match runtime_data.movement_results.get(&runtime_data.creep_entity) {
+        Some(MovementResult::Failed(_)) => true,
+        Some(MovementResult::Stuck { ticks }) => *ticks >= STUCK_REPORT_THRESHOLD,
+        _ => false,
+    }
+}
+
+/// Have the creep say its job's code for this tick. A stuck creep says
+/// [`STUCK_CODE`] instead, as that is what a watcher needs to know first.
+pub fn say_job_code(job: &dyn Job, system_data: &JobExecutionSystemData, runtime_data: &mut JobExecutionRuntimeData) {
+    let code = if is_stuck(runtime_data) {
+        Some(STUCK_CODE.to_string())
+    } else {
+        job.say_code(system_data, runtime_data)
+    };
+
+    if let Some(code) = code {
+        let _ = runtime_data
+            .intent_recorder
+            .issued(IntentCategory::Say, runtime_data.owner.say(&say_text(&code), false));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn say_text_cuts_by_characters() {
+        assert_eq!(say_text("H→S"), "H→S");
+        assert_eq!(say_text("⚔⚔⚔⚔⚔⚔⚔⚔⚔⚔⚔⚔"), "⚔⚔⚔⚔⚔⚔⚔⚔⚔⚔");
+        assert_eq!(say_text("»W12N34abcdef").chars().count(), SAY_MAX_CHARS);
+    }
+}
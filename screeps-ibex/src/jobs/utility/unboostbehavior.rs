@@ -0,0 +1,78 @@
+//! Stripping a boosted creep's boosts at a lab on its way to be recycled.
+//!
+//! Recycling returns a creep's boost compounds in proportion to the life it
+//! has left; `unboostCreep` drops half of them under the creep whatever its
+//! age. So a boosted creep with less than half its life left and time to
+//! reach a lab asks the labs of the room it recycles in for one, every tick
+//! (`BoostQueue::request_unboost`). Once the labs mission gives it a lab off
+//! cooldown it walks there and waits, and the mission unboosts it
+//! (`missions::labs`); with no lab free it carries on to its spawn. The
+//! dropped compounds are collected like any resource dropped in an owned
+//! room (`missions::localsupply::room_transfer`).
+
+use super::movebehavior::mark_immovable;
+use crate::jobs::context::*;
+use crate::military::boostqueue::UnboostRequest;
+use screeps::*;
+
+/// Ticks to live a creep needs left for the detour to a lab.
+const UNBOOST_MIN_TTL: u32 = 100;
+
+/// Whether unboosting a creep with `ticks_to_live` left recovers more
+/// compounds than recycling it would, with time to spare for the detour.
+fn unboost_pays(ticks_to_live: u32) -> bool {
+    ticks_to_live >= UNBOOST_MIN_TTL && ticks_to_live < CREEP_LIFE_TIME / 2
+}
+
+/// Route a boosted creep on its way to be recycled in `home_room` through a
+/// lab there. Returns true while the creep is walking to or waiting at its
+/// lab; false when it should carry on to its spawn (not boosted, unboosting
+/// doesn't pay, or no lab is free).
+pub fn tick_unboost(tick_context: &mut JobTickContext, home_room: RoomName) -> bool {
+    let creep = tick_context.runtime_data.owner;
+    let creep_entity = tick_context.runtime_data.creep_entity;
+
+    let boosted = creep.body().iter().any(|part| part.boost().is_some());
+
+    if !boosted || !unboost_pays(creep.ticks_to_live().unwrap_or(0)) {
+        return false;
+    }
+
+    let Some(creep_id) = creep.try_id() else {
+        return false;
+    };
+
+    let boost_queue = &mut tick_context.runtime_data.boost_queue;
+
+    boost_queue.request_unboost(UnboostRequest {
+        requester: creep_entity,
+        creep: creep_id,
+        room: home_room,
+    });
+
+    let Some(lab) = boost_queue.unboost_lab(creep_entity).and_then(|lab| lab.resolve()) else {
+        return false;
+    };
+
+    if creep.pos().is_near_to(lab.pos()) {
+        mark_immovable(tick_context);
+    } else {
+        tick_context.runtime_data.movement.move_to(creep_entity, lab.pos()).range(1);
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unboosting_pays_once_recycling_would_return_less_than_half() {
+        assert!(!unboost_pays(CREEP_LIFE_TIME - 1));
+        assert!(!unboost_pays(CREEP_LIFE_TIME / 2));
+        assert!(unboost_pays(CREEP_LIFE_TIME / 2 - 1));
+        assert!(unboost_pays(UNBOOST_MIN_TTL));
+        assert!(!unboost_pays(UNBOOST_MIN_TTL - 1));
+    }
+}
@@ -0,0 +1,260 @@
+//! Per-room energy income/expense ledger.
+//!
+//! Jobs, missions and the spawn system report energy as it moves through
+//! `ResourceLedger::add(room, category, amount)` at the point the intent is
+//! issued (an `Ok` from the intent call). Amounts are the engine-formula
+//! estimate of what that intent moves this tick — harvest/build/upgrade are
+//! `WORK` parts × power, clamped to what the target can absorb — so the
+//! ledger answers "is this room net-positive" to within intent-level
+//! accuracy, not to the exact energy unit.
+//!
+//! Entries are keyed by the room where the energy event happened (a remote
+//! source's harvest lands on the remote room, not the home room).
+//!
+//! Rolling averages cover [`LEDGER_WINDOW`] ticks, kept as fixed-size
+//! [`BUCKET_TICKS`] buckets so a room costs `LEDGER_WINDOW / BUCKET_TICKS`
+//! small arrays regardless of how often it reports. The ledger is
+//! ephemeral (not serialized): a VM reload restarts the window and the
+//! averages ramp back up over the observed span.
+
+use screeps::RoomName;
+use std::collections::{HashMap, VecDeque};
+
+/// Rolling-average window in ticks (one creep lifetime).
+pub const LEDGER_WINDOW: u32 = 1500;
+/// Granularity of the rolling window.
+const BUCKET_TICKS: u32 = 100;
+
+/// Where energy came from or went to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LedgerCategory {
+    Harvest,
+    Spawn,
+    Renew,
+    Build,
+    Repair,
+    Upgrade,
+    Tower,
+    TerminalFee,
+    LinkLoss,
+}
+
+const CATEGORY_COUNT: usize = 9;
+
+impl LedgerCategory {
+    pub const ALL: [LedgerCategory; CATEGORY_COUNT] = [
+        LedgerCategory::Harvest,
+        LedgerCategory::Spawn,
+        LedgerCategory::Renew,
+        LedgerCategory::Build,
+        LedgerCategory::Repair,
+        LedgerCategory::Upgrade,
+        LedgerCategory::Tower,
+        LedgerCategory::TerminalFee,
+        LedgerCategory::LinkLoss,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LedgerCategory::Harvest => "harvest",
+            LedgerCategory::Spawn => "spawn",
+            LedgerCategory::Renew => "renew",
+            LedgerCategory::Build => "build",
+            LedgerCategory::Repair => "repair",
+            LedgerCategory::Upgrade => "upgrade",
+            LedgerCategory::Tower => "tower",
+            LedgerCategory::TerminalFee => "terminal_fee",
+            LedgerCategory::LinkLoss => "link_loss",
+        }
+    }
+
+    /// Harvest is the only income category; everything else is spend.
+    pub fn is_income(self) -> bool {
+        matches!(self, LedgerCategory::Harvest)
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Debug, Clone)]
+struct LedgerBucket {
+    start: u32,
+    amounts: [u64; CATEGORY_COUNT],
+}
+
+/// One room's bucketed history.
+#[derive(Debug, Default, Clone)]
+pub struct RoomLedger {
+    /// Oldest first; bucket starts are multiples of [`BUCKET_TICKS`].
+    buckets: VecDeque<LedgerBucket>,
+    /// First tick this room reported — the averaging denominator until a
+    /// full window has been observed.
+    first_tick: u32,
+}
+
+impl RoomLedger {
+    fn add(&mut self, tick: u32, category: LedgerCategory, amount: u32) {
+        let start = tick - tick % BUCKET_TICKS;
+
+        if self.buckets.is_empty() {
+            self.first_tick = tick;
+        }
+
+        if self.buckets.back().map(|b| b.start != start).unwrap_or(true) {
+            self.buckets.push_back(LedgerBucket {
+                start,
+                amounts: [0; CATEGORY_COUNT],
+            });
+        }
+
+        if let Some(bucket) = self.buckets.back_mut() {
+            bucket.amounts[category.index()] += amount as u64;
+        }
+    }
+
+    /// Drop buckets that fell entirely out of the window ending at `tick`.
+    fn prune(&mut self, tick: u32) {
+        while let Some(front) = self.buckets.front() {
+            if tick.saturating_sub(front.start) >= LEDGER_WINDOW {
+                self.buckets.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Ticks the averages divide by: the full window once observed that
+    /// long, otherwise the span since the first report.
+    fn span(&self, tick: u32) -> u32 {
+        (tick.saturating_sub(self.first_tick) + 1).min(LEDGER_WINDOW)
+    }
+
+    /// Average energy per tick for `category` over the window ending at `tick`.
+    pub fn average(&self, tick: u32, category: LedgerCategory) -> f64 {
+        let total: u64 = self.buckets.iter().map(|b| b.amounts[category.index()]).sum();
+        total as f64 / self.span(tick) as f64
+    }
+
+    pub fn averages(&self, tick: u32) -> LedgerAverages {
+        let mut averages = LedgerAverages::default();
+
+        for category in LedgerCategory::ALL.iter() {
+            let average = self.average(tick, *category);
+            averages.per_category[category.index()] = average;
+            if category.is_income() {
+                averages.income += average;
+            } else {
+                averages.expense += average;
+            }
+        }
+
+        averages
+    }
+}
+
+/// Rolling per-tick averages for one room.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LedgerAverages {
+    pub income: f64,
+    pub expense: f64,
+    per_category: [f64; CATEGORY_COUNT],
+}
+
+impl LedgerAverages {
+    pub fn net(&self) -> f64 {
+        self.income - self.expense
+    }
+
+    pub fn get(&self, category: LedgerCategory) -> f64 {
+        self.per_category[category.index()]
+    }
+}
+
+/// Ephemeral per-room energy ledger Resource. `metrics::tick_start` latches
+/// the tick and prunes expired buckets; reporting sites call [`add`](Self::add).
+#[derive(Debug, Default)]
+pub struct ResourceLedger {
+    tick: u32,
+    rooms: HashMap<RoomName, RoomLedger>,
+}
+
+impl ResourceLedger {
+    pub fn begin_tick(&mut self, tick: u32) {
+        self.tick = tick;
+
+        for room in self.rooms.values_mut() {
+            room.prune(tick);
+        }
+
+        self.rooms.retain(|_, room| !room.buckets.is_empty());
+    }
+
+    pub fn add(&mut self, room: RoomName, category: LedgerCategory, amount: u32) {
+        if amount == 0 {
+            return;
+        }
+
+        let tick = self.tick;
+
+        self.rooms.entry(room).or_default().add(tick, category, amount);
+    }
+
+    pub fn averages(&self, room: RoomName) -> Option<LedgerAverages> {
+        self.rooms.get(&room).map(|r| r.averages(self.tick))
+    }
+
+    pub fn rooms(&self) -> impl Iterator<Item = (&RoomName, LedgerAverages)> {
+        self.rooms.iter().map(move |(name, r)| (name, r.averages(self.tick)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn room() -> RoomName {
+        RoomName::new("W1N1").unwrap()
+    }
+
+    /// Averages divide by the observed span until a full window exists.
+    #[test]
+    fn averages_ramp_over_observed_span() {
+        let mut ledger = ResourceLedger::default();
+        ledger.begin_tick(1000);
+        ledger.add(room(), LedgerCategory::Harvest, 10);
+        ledger.add(room(), LedgerCategory::Upgrade, 4);
+
+        ledger.begin_tick(1001);
+        ledger.add(room(), LedgerCategory::Harvest, 10);
+
+        let avg = ledger.averages(room()).unwrap();
+        assert_eq!(avg.income, 10.0);
+        assert_eq!(avg.expense, 2.0);
+        assert_eq!(avg.net(), 8.0);
+        assert_eq!(avg.get(LedgerCategory::Upgrade), 2.0);
+    }
+
+    /// Buckets older than the window drop out, and a room with nothing
+    /// left in the window is forgotten entirely.
+    #[test]
+    fn expired_buckets_are_pruned() {
+        let mut ledger = ResourceLedger::default();
+        ledger.begin_tick(100);
+        ledger.add(room(), LedgerCategory::Spawn, 300);
+
+        ledger.begin_tick(100 + LEDGER_WINDOW - 1);
+        ledger.add(room(), LedgerCategory::Harvest, 15);
+        let avg = ledger.averages(room()).unwrap();
+        assert_eq!(avg.get(LedgerCategory::Spawn), 300.0 / LEDGER_WINDOW as f64);
+
+        ledger.begin_tick(200 + LEDGER_WINDOW);
+        let avg = ledger.averages(room()).unwrap();
+        assert_eq!(avg.get(LedgerCategory::Spawn), 0.0);
+        assert_eq!(avg.get(LedgerCategory::Harvest), 15.0 / LEDGER_WINDOW as f64);
+
+        ledger.begin_tick(100 + 3 * LEDGER_WINDOW);
+        assert!(ledger.averages(room()).is_none());
+    }
+}
@@ -6,6 +6,7 @@
 #[global_allocator]
 static ALLOC: talc::TalckWasm = unsafe { talc::TalckWasm::new_global() };
 
+mod bodytemplate;
 mod cleanup;
 // The JS-free tactical seam + pure combat decisions (ADR 0006 §B.2 / S17) live in their own member
 // crate `screeps-combat-decision`, so the host-side sim (`screeps-combat-agent`) depends on that
@@ -13,9 +14,12 @@ mod cleanup;
 // `jobs::squad_combat` / `missions::attack_mission` (the only `game::*` users) keep their paths.
 pub use screeps_combat_decision as combat;
 mod claim_economics;
+mod console;
 mod constants;
+mod cpu_accounting;
 mod cpugovernor;
 mod creep;
+mod creep_memory;
 mod entitymappingsystem;
 mod expansion;
 mod features;
@@ -23,32 +27,42 @@ mod findnearest;
 mod game_loop;
 mod gameview;
 mod identity;
+mod integrity;
 mod intents;
 mod jobs;
+mod ledger;
 mod logging;
 mod machine_tick;
 mod memory_helper;
 mod memorysystem;
 mod metrics;
 mod military;
+mod mission_map;
 mod missions;
 mod operations;
 mod panic;
 mod pathing;
+mod powercreepsystem;
 mod remoteobjectid;
 mod repairqueue;
+mod resource_overview;
 mod room;
 mod room_economics;
 mod segments;
 mod serialize;
+mod server;
 mod spawnsystem;
 mod stats_history;
 mod statssystem;
 mod structureidentifier;
+#[cfg(test)]
+mod testing;
 mod transfer;
 mod ui;
+mod upgrade_focus;
 mod visualization;
 mod visualize;
+mod worldformat;
 
 use log::*;
 use wasm_bindgen::prelude::*;
@@ -57,6 +71,9 @@ use wasm_bindgen::prelude::*;
 pub fn setup() {
     logging::setup_logging(logging::Info);
     panic::setup_panic_hook();
+
+    #[cfg(feature = "memory-audit")]
+    creep_memory::install_write_trap();
 }
 
 #[wasm_bindgen(js_name = game_loop)]
@@ -1,45 +1,279 @@
+//! Logger with per-module levels and a ring buffer of recent warnings.
+//!
+//! Levels come from `Memory._features.logging.levels` (or the `log_level`
+//! console command): a comma-separated list of `module=level` entries with an
+//! optional bare default, e.g. `info, missions::attack=debug, transfer=warn`.
+//! Modules are paths inside the crate and the longest matching one wins.
+//!
+//! The global max level is the most verbose level configured anywhere, so the
+//! `log` macros drop anything below it before formatting; the per-module check
+//! runs before the record is formatted too. Suppressed records cost a level
+//! compare.
+//!
+//! Warnings and errors also go to `game::notify` and into a ring of the last
+//! [`RECENT_CAPACITY`] records, dumped with the `log_dump` console command and
+//! exported in the live stats segment.
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::VecDeque;
 use wasm_bindgen::JsValue;
 
 pub use log::LevelFilter::*;
 
-struct JsLog;
-struct JsNotify;
+pub const RECENT_CAPACITY: usize = 32;
+
+const CRATE_PREFIX: &str = concat!(env!("CARGO_CRATE_NAME"), "::");
 
-impl log::Log for JsLog {
-    fn enabled(&self, _: &log::Metadata) -> bool {
-        true
+/// A default level plus per-module overrides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLevels {
+    default: LevelFilter,
+    modules: Vec<(String, LevelFilter)>,
+}
+
+impl LogLevels {
+    pub fn new(default: LevelFilter) -> LogLevels {
+        LogLevels {
+            default,
+            modules: Vec::new(),
+        }
     }
-    fn log(&self, record: &log::Record) {
-        let message = format!("{}", record.args());
-        web_sys::console::log_1(&JsValue::from_str(&message));
+
+    /// Parse a level spec on top of `default`. An empty spec keeps the default.
+    pub fn parse(spec: &str, default: LevelFilter) -> Result<LogLevels, String> {
+        let mut levels = LogLevels::new(default);
+
+        for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let parse_level = |text: &str| {
+                text.trim()
+                    .parse::<LevelFilter>()
+                    .map_err(|_| format!("'{}' is not a log level", text.trim()))
+            };
+
+            match entry.split_once('=') {
+                Some((module, level)) => {
+                    let module = module.trim().trim_start_matches(CRATE_PREFIX).trim_end_matches("::");
+                    if module.is_empty() {
+                        return Err(format!("'{}' has no module", entry));
+                    }
+                    levels.modules.retain(|(existing, _)| existing != module);
+                    levels.modules.push((module.to_string(), parse_level(level)?));
+                }
+                None => levels.default = parse_level(entry)?,
+            }
+        }
+
+        Ok(levels)
     }
-    fn flush(&self) {}
+
+    /// The level for a record target (a module path).
+    pub fn level_for(&self, target: &str) -> LevelFilter {
+        let module = target.strip_prefix(CRATE_PREFIX).unwrap_or(target);
+
+        self.modules
+            .iter()
+            .filter(|(prefix, _)| {
+                module
+                    .strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default)
+    }
+
+    /// The most verbose level any module can log at.
+    pub fn max_level(&self) -> LevelFilter {
+        self.modules.iter().map(|(_, level)| *level).fold(self.default, Ord::max)
+    }
+}
+
+/// One warning or error kept for the dump and the stats export.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RecentLog {
+    pub tick: u32,
+    pub level: &'static str,
+    pub target: String,
+    pub message: String,
 }
-impl log::Log for JsNotify {
-    fn enabled(&self, _: &log::Metadata) -> bool {
-        true
+
+/// The last `capacity` warnings and errors, oldest first.
+#[derive(Debug)]
+pub struct RecentLogs {
+    entries: VecDeque<RecentLog>,
+    capacity: usize,
+}
+
+impl RecentLogs {
+    pub fn new(capacity: usize) -> RecentLogs {
+        RecentLogs {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, entry: RecentLog) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
     }
-    fn log(&self, record: &log::Record) {
+
+    pub fn iter(&self) -> impl Iterator<Item = &RecentLog> {
+        self.entries.iter()
+    }
+}
+
+struct LoggerState {
+    base: LevelFilter,
+    spec: String,
+    levels: LogLevels,
+    recent: RecentLogs,
+}
+
+thread_local! {
+    static STATE: RefCell<LoggerState> = RefCell::new(LoggerState {
+        base: LevelFilter::Info,
+        spec: String::new(),
+        levels: LogLevels::new(LevelFilter::Info),
+        recent: RecentLogs::new(RECENT_CAPACITY),
+    });
+}
+
+struct IbexLogger;
+
+static LOGGER: IbexLogger = IbexLogger;
+
+impl Log for IbexLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        STATE.with(|state| metadata.level() <= state.borrow().levels.level_for(metadata.target()))
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
         let message = format!("{}", record.args());
-        screeps::game::notify(&message, None);
+
+        web_sys::console::log_1(&JsValue::from_str(&format!(
+            "({}) {}: {}",
+            record.level(),
+            record.target(),
+            message
+        )));
+
+        if record.level() <= Level::Warn {
+            let tick = screeps::game::time();
+
+            screeps::game::notify(&format!("[{}] {}", tick, message), None);
+
+            STATE.with(|state| {
+                state.borrow_mut().recent.push(RecentLog {
+                    tick,
+                    level: record.level().as_str(),
+                    target: record.target().to_string(),
+                    message,
+                })
+            });
+        }
     }
+
     fn flush(&self) {}
 }
 
-pub fn setup_logging(verbosity: log::LevelFilter) {
-    fern::Dispatch::new()
-        .level(verbosity)
-        .format(|out, message, record| out.finish(format_args!("({}) {}: {}", record.level(), record.target(), message)))
-        .chain(Box::new(JsLog) as Box<dyn log::Log>)
-        .chain(
-            fern::Dispatch::new()
-                .level(log::LevelFilter::Warn)
-                .format(|out, message, _record| {
-                    let time = screeps::game::time();
-                    out.finish(format_args!("[{}] {}", time, message))
-                })
-                .chain(Box::new(JsNotify) as Box<dyn log::Log>),
-        )
-        .apply()
-        .expect("expected setup_logging to only ever be called once per instance");
+pub fn setup_logging(verbosity: LevelFilter) {
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        state.base = verbosity;
+        state.levels = LogLevels::new(verbosity);
+    });
+
+    log::set_logger(&LOGGER).expect("expected setup_logging to only ever be called once per instance");
+    log::set_max_level(verbosity);
+}
+
+/// Apply a level spec (see the module docs). Called each tick with
+/// `_features.logging.levels`; only a changed spec is re-parsed. A spec that
+/// doesn't parse keeps the current levels and is reported once.
+pub fn configure(spec: &str) {
+    let parsed = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+
+        if state.spec == spec {
+            return None;
+        }
+
+        state.spec = spec.to_string();
+
+        Some(LogLevels::parse(spec, state.base).map(|levels| {
+            log::set_max_level(levels.max_level());
+            state.levels = levels;
+        }))
+    });
+
+    // Logged outside the state borrow: the logger reads it.
+    if let Some(Err(err)) = parsed {
+        log::warn!("Ignoring log levels '{}': {}", spec, err);
+    }
+}
+
+/// The buffered warnings and errors, oldest first.
+pub fn recent() -> Vec<RecentLog> {
+    STATE.with(|state| state.borrow().recent.iter().cloned().collect())
+}
+
+/// Print the buffered warnings and errors straight to the console.
+pub fn dump_recent() {
+    for entry in recent() {
+        let line = format!("[{}] ({}) {}: {}", entry.tick, entry.level, entry.target, entry.message);
+        web_sys::console::log_1(&JsValue::from_str(&line));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn longest_module_prefix_wins() {
+        let levels = LogLevels::parse("missions=warn, missions::attack=debug, transfer=error", Info).unwrap();
+
+        assert_eq!(levels.level_for(&format!("{}missions::attack", CRATE_PREFIX)), Debug);
+        assert_eq!(levels.level_for("missions::attack::squad"), Debug);
+        assert_eq!(levels.level_for("missions::upgrade"), Warn);
+        assert_eq!(levels.level_for("transfer::transfersystem"), Error);
+        // Prefixes stop at path boundaries.
+        assert_eq!(levels.level_for("missionsystem"), Info);
+        assert_eq!(levels.max_level(), Debug);
+    }
+
+    #[test]
+    fn bare_level_sets_the_default_and_bad_specs_are_rejected() {
+        let levels = LogLevels::parse(" debug , room=off", Info).unwrap();
+        assert_eq!(levels.level_for("jobs::haul"), Debug);
+        assert_eq!(levels.level_for("room::data"), Off);
+
+        assert_eq!(LogLevels::parse("", Warn).unwrap(), LogLevels::new(Warn));
+        assert!(LogLevels::parse("transfer=loud", Info).is_err());
+        assert!(LogLevels::parse("=warn", Info).is_err());
+    }
+
+    #[test]
+    fn recent_logs_keep_the_newest_entries() {
+        let mut recent = RecentLogs::new(2);
+
+        for tick in 0..3 {
+            recent.push(RecentLog {
+                tick,
+                level: "WARN",
+                target: "test".to_string(),
+                message: tick.to_string(),
+            });
+        }
+
+        assert_eq!(recent.iter().map(|entry| entry.tick).collect::<Vec<_>>(), vec![1, 2]);
+    }
 }
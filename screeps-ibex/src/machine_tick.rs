@@ -20,9 +20,9 @@ where
     }
 }
 
-pub fn run_state_machine_result<S, F>(state: &mut S, label: &str, mut tick_fn: F) -> Result<(), String>
+pub fn run_state_machine_result<S, E, F>(state: &mut S, label: &str, mut tick_fn: F) -> Result<(), E>
 where
-    F: FnMut(&mut S) -> Result<Option<S>, String>,
+    F: FnMut(&mut S) -> Result<Option<S>, E>,
 {
     let mut transitions = 0u32;
     while let Some(new_state) = tick_fn(state)? {
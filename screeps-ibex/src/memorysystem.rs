@@ -1,7 +1,7 @@
 use log::*;
 use screeps::*;
 use specs::prelude::*;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 // ─── Segment requirements ────────────────────────────────────────────────────
 
@@ -307,6 +307,156 @@ impl MemoryArbiter {
         self.requirements[index].on_load = Some(cb);
         self.requirements[index].loaded = true;
     }
+
+    /// Active-segment slots left next tick once this tick's requests and
+    /// queued-write reservations are counted.
+    fn free_slots(&self) -> usize {
+        let taken = self.requests.len() + self.pending_writes.keys().filter(|s| !self.requests.contains(s)).count();
+
+        MAX_ACTIVE_SEGMENTS.saturating_sub(taken)
+    }
+}
+
+// ─── SegmentManager ──────────────────────────────────────────────────────────
+
+/// Most segments the engine makes active (and accepts writes to) per tick.
+const MAX_ACTIVE_SEGMENTS: usize = 10;
+
+/// Ticks a read interest lasts without a `read` renewing it.
+pub const SEGMENT_INTEREST_TTL: u32 = 100;
+
+/// One managed segment: who wants it, and what it holds.
+#[derive(Default)]
+struct SegmentInterest {
+    /// Last tick a reader asked for the segment; `None` for write-only use.
+    read_at: Option<u32>,
+    /// Newest payload waiting for the segment to be active.
+    pending_write: Option<String>,
+    /// Contents as of the last tick the segment was active.
+    contents: Option<String>,
+    /// Last tick the segment was scheduled active; `None` if never.
+    scheduled_at: Option<u32>,
+}
+
+impl SegmentInterest {
+    fn is_reading(&self, now: u32) -> bool {
+        self.read_at
+            .map(|read_at| now.saturating_sub(read_at) < SEGMENT_INTEREST_TTL)
+            .unwrap_or(false)
+    }
+
+    fn is_wanted(&self, now: u32) -> bool {
+        self.pending_write.is_some() || self.is_reading(now)
+    }
+}
+
+/// Shares the active-segment slots the arbiter's registered and ad-hoc
+/// segments leave free between segments used on demand.
+///
+/// Readers call [`read`](Self::read) every tick they want a segment: the
+/// first call registers interest and returns `None`, and once the segment
+/// has been active its contents are returned from then on (from the tick
+/// after the request, when a slot is free). Writers call
+/// [`write`](Self::write); the payload is buffered until the segment is
+/// active and a newer payload replaces it. When more segments are wanted
+/// than there are free slots, the ones waiting longest are scheduled
+/// first, so every interest is served in rotation. Read interests not
+/// renewed for [`SEGMENT_INTEREST_TTL`] ticks are dropped.
+///
+/// Segment ids still come from the `segments` registry.
+#[derive(Default)]
+pub struct SegmentManager {
+    interests: HashMap<u32, SegmentInterest>,
+}
+
+impl SegmentManager {
+    /// The segment's contents as last seen active, or the payload waiting
+    /// to be written. Registers (or renews) the read interest.
+    pub fn read(&mut self, segment: u32, now: u32) -> Option<&str> {
+        let interest = self.interests.entry(segment).or_default();
+        interest.read_at = Some(now);
+
+        interest.pending_write.as_deref().or(interest.contents.as_deref())
+    }
+
+    /// Buffer `data` for `segment` until it is active.
+    pub fn write(&mut self, segment: u32, data: String) {
+        self.interests.entry(segment).or_default().pending_write = Some(data);
+    }
+
+    /// Whether a write to `segment` is still waiting for its slot.
+    pub fn has_pending_write(&self, segment: u32) -> bool {
+        self.interests.get(&segment).map(|i| i.pending_write.is_some()).unwrap_or(false)
+    }
+
+    /// Read the contents of every wanted segment the runtime made active
+    /// this tick. Runs before the systems, so a read sees them this tick.
+    fn capture(&mut self, arbiter: &mut MemoryArbiter, now: u32) {
+        for (segment, interest) in self.interests.iter_mut() {
+            if interest.is_reading(now) && arbiter.is_active(*segment) {
+                interest.contents = Some(arbiter.get(*segment).unwrap_or_default());
+            }
+        }
+    }
+
+    /// Write every buffered payload whose segment is active this tick.
+    fn land_writes(&mut self, arbiter: &mut MemoryArbiter) {
+        for (segment, interest) in self.interests.iter_mut() {
+            if interest.pending_write.is_some() && arbiter.is_active(*segment) {
+                if let Some(data) = interest.pending_write.take() {
+                    arbiter.set(*segment, &data);
+                    if interest.read_at.is_some() {
+                        interest.contents = Some(data);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Forget the segments nobody has wanted for [`SEGMENT_INTEREST_TTL`].
+    /// A segment stays known a while after its last write lands, so a
+    /// writer coming back every tick keeps its place in the rotation.
+    fn evict(&mut self, now: u32) {
+        self.interests.retain(|_, interest| {
+            interest.is_wanted(now)
+                || interest
+                    .scheduled_at
+                    .map(|scheduled_at| now.saturating_sub(scheduled_at) < SEGMENT_INTEREST_TTL)
+                    .unwrap_or(false)
+        });
+    }
+
+    /// Pick up to `slots` wanted segments to make active next tick: never
+    /// scheduled first, then the longest since it was. Segments `requested`
+    /// anyway (registered or ad-hoc) are active without a slot.
+    fn schedule(&mut self, slots: usize, requested: &HashSet<u32>, now: u32) -> Vec<u32> {
+        let mut wanted: Vec<(Option<u32>, u32)> = self
+            .interests
+            .iter()
+            .filter(|(segment, interest)| interest.is_wanted(now) && !requested.contains(segment))
+            .map(|(segment, interest)| (interest.scheduled_at, *segment))
+            .collect();
+
+        wanted.sort_unstable();
+
+        let scheduled: Vec<u32> = wanted.into_iter().take(slots).map(|(_, segment)| segment).collect();
+
+        for segment in &scheduled {
+            if let Some(interest) = self.interests.get_mut(segment) {
+                interest.scheduled_at = Some(now);
+            }
+        }
+
+        scheduled
+    }
+}
+
+/// Capture the managed segments the runtime made active this tick, so
+/// `SegmentManager::read` sees them. Call after the segment pre-pass.
+pub fn capture_managed_segments(world: &World) {
+    let mut arbiter = world.write_resource::<MemoryArbiter>();
+
+    world.write_resource::<SegmentManager>().capture(&mut arbiter, game::time());
 }
 
 // ─── Load-callback orchestration ─────────────────────────────────────────────
@@ -354,11 +504,97 @@ fn plan_active_set(requests: &HashSet<u32>, pending_writes: &[u32]) -> (Vec<u8>,
     (ids.into_iter().map(|id| id as u8).collect(), displaced)
 }
 
+// ─── Corrupted-world recovery ────────────────────────────────────────────────
+
+/// Most bytes the engine accepts in one segment.
+const SEGMENT_CAPACITY: usize = 50 * 1024;
+
+/// Recovery bookkeeping for a world save that could not be read.
+///
+/// `deserialize_world` rejects an unreadable payload (bad encoding, broken
+/// envelope, unmigratable version) wholesale, and the tick goes on with the
+/// freshly-created, empty world: operations, missions and jobs are gone,
+/// `CreateRoomDataSystem` rediscovers every visible room and the operation
+/// manager bootstraps the colony operations again. What that leaves behind
+/// is the live creeps, which no longer have an entity — `adopt_orphans`
+/// asks `AdoptOrphanCreepsSystem` to reattach them to inferred jobs.
+///
+/// The durable flag is `Memory._recovery` (`count`, `tick`, `cause`);
+/// it survives VM restarts and stays until an operator deletes it.
+#[derive(Default)]
+pub struct MemoryRecovery {
+    /// Recoveries recorded in `Memory._recovery.count`.
+    pub count: u32,
+    pub last_tick: Option<u32>,
+    pub last_cause: Option<String>,
+    /// Orphaned creeps still need adopting (cleared after one pass).
+    pub adopt_orphans: bool,
+}
+
+impl MemoryRecovery {
+    /// Read the durable recovery flag back from Memory.
+    pub fn load() -> MemoryRecovery {
+        let cause = crate::memory_helper::path_get("_recovery.cause").as_string();
+
+        MemoryRecovery {
+            count: crate::memory_helper::path_f64("_recovery.count").unwrap_or(0.0) as u32,
+            last_tick: crate::memory_helper::path_f64("_recovery.tick").map(|t| t as u32),
+            last_cause: cause,
+            adopt_orphans: false,
+        }
+    }
+
+    /// Enter recovery: back up the unreadable payload, set the Memory flag
+    /// and request orphan adoption. The caller has already discarded the
+    /// world state.
+    pub fn recover(&mut self, arbiter: &mut MemoryArbiter, raw: &str, cause: &str) {
+        let tick = game::time();
+
+        let backed_up = backup_corrupt_world(arbiter, raw);
+
+        self.count += 1;
+        self.last_tick = Some(tick);
+        self.last_cause = Some(cause.to_owned());
+        self.adopt_orphans = true;
+
+        crate::memory_helper::path_set("_recovery.count", self.count as f64);
+        crate::memory_helper::path_set("_recovery.tick", tick as f64);
+        crate::memory_helper::path_set("_recovery.cause", cause);
+
+        error!("==================== MEMORY RECOVERY ====================");
+        error!("World state could not be read: {}", cause);
+        error!(
+            "Backed up {} of {} bytes to segment {}; bootstrapping a fresh world (recovery #{})",
+            backed_up,
+            raw.len(),
+            crate::segments::RECOVERY_BACKUP_SEGMENT,
+            self.count
+        );
+        error!("=========================================================");
+    }
+}
+
+/// Queue the raw (still-encoded) world payload for the recovery backup
+/// segment. A payload spanning several component segments keeps only its
+/// first segment's worth — the version and envelope that decide whether it
+/// was readable. Returns the number of bytes kept.
+fn backup_corrupt_world(arbiter: &mut MemoryArbiter, raw: &str) -> usize {
+    let mut len = raw.len().min(SEGMENT_CAPACITY);
+    while !raw.is_char_boundary(len) {
+        len -= 1;
+    }
+
+    arbiter.queue_write(crate::segments::RECOVERY_BACKUP_SEGMENT, raw[..len].to_owned());
+
+    len
+}
+
 // ─── System ──────────────────────────────────────────────────────────────────
 
 #[derive(SystemData)]
 pub struct MemoryArbiterSystemData<'a> {
     memory_arbiter: WriteExpect<'a, MemoryArbiter>,
+    segment_manager: WriteExpect<'a, SegmentManager>,
 }
 
 pub struct MemoryArbiterSystem;
@@ -368,6 +604,20 @@ impl<'a> System<'a> for MemoryArbiterSystem {
     type SystemData = MemoryArbiterSystemData<'a>;
 
     fn run(&mut self, mut data: Self::SystemData) {
+        let now = game::time();
+
+        // Managed segments take the slots the arbiter's own requests leave.
+        // Scheduled before the writes land, so a writer that writes every
+        // tick finds its segment active again next tick.
+        data.segment_manager.evict(now);
+
+        let slots = data.memory_arbiter.free_slots();
+        for segment in data.segment_manager.schedule(slots, &data.memory_arbiter.requests, now) {
+            data.memory_arbiter.request(segment);
+        }
+
+        data.segment_manager.land_writes(&mut data.memory_arbiter);
+
         data.memory_arbiter.flush();
     }
 }
@@ -453,4 +703,102 @@ mod arbiter_double_tests {
         assert_eq!(arbiter.pending_writes.len(), 1);
         assert_eq!(arbiter.pending_writes.get(&58).map(String::as_str), Some("new"));
     }
+
+    /// Twelve wanted segments and ten free slots: the two left out go
+    /// first next tick, and segments the arbiter requests anyway take none.
+    #[test]
+    fn segment_manager_rotates_when_more_segments_are_wanted_than_slots() {
+        let mut manager = SegmentManager::default();
+        for segment in 70..82 {
+            manager.read(segment, 1);
+        }
+        let requested: HashSet<u32> = [70].into_iter().collect();
+
+        let first = manager.schedule(MAX_ACTIVE_SEGMENTS, &HashSet::new(), 1);
+        assert_eq!(first, (70..80).collect::<Vec<_>>());
+
+        let second = manager.schedule(3, &requested, 2);
+        assert_eq!(second, vec![80, 81, 71]);
+
+        let third = manager.schedule(MAX_ACTIVE_SEGMENTS, &requested, 3);
+        assert_eq!(third.len(), MAX_ACTIVE_SEGMENTS);
+        assert!(!third.contains(&70));
+        assert_eq!(&third[..8], &[72, 73, 74, 75, 76, 77, 78, 79]);
+    }
+
+    /// A read registers interest; the contents arrive once the runtime has
+    /// made the segment active, the tick after it was scheduled.
+    #[test]
+    fn segment_manager_reads_arrive_the_tick_after_the_request() {
+        let mut arbiter = MemoryArbiter::test_double();
+        arbiter.set(70, "plan");
+        arbiter.active = Some(HashSet::new());
+
+        let mut manager = SegmentManager::default();
+        assert_eq!(manager.read(70, 1), None);
+        assert_eq!(manager.schedule(1, &HashSet::new(), 1), vec![70]);
+
+        arbiter.active = Some([70].into_iter().collect());
+        manager.capture(&mut arbiter, 2);
+        assert_eq!(manager.read(70, 2), Some("plan"));
+
+        // Still readable while the segment rotates out.
+        arbiter.active = Some(HashSet::new());
+        manager.capture(&mut arbiter, 3);
+        assert_eq!(manager.read(70, 3), Some("plan"));
+    }
+
+    /// Writes wait for the segment to be active; a newer payload replaces
+    /// a waiting one, and readers see it straight away.
+    #[test]
+    fn segment_manager_buffers_writes_until_the_segment_is_active() {
+        let mut arbiter = MemoryArbiter::test_double();
+        arbiter.active = Some(HashSet::new());
+
+        let mut manager = SegmentManager::default();
+        manager.write(71, "old".to_string());
+        manager.write(71, "new".to_string());
+        assert_eq!(manager.read(71, 1), Some("new"));
+
+        manager.land_writes(&mut arbiter);
+        assert!(manager.has_pending_write(71));
+        assert_eq!(arbiter.get(71), None);
+
+        arbiter.active = Some([71].into_iter().collect());
+        manager.land_writes(&mut arbiter);
+        assert!(!manager.has_pending_write(71));
+        assert_eq!(arbiter.get(71).as_deref(), Some("new"));
+        assert_eq!(manager.read(71, 2), Some("new"));
+    }
+
+    /// A read interest nobody renews stops being scheduled, and is
+    /// forgotten once its last slot is as old.
+    #[test]
+    fn segment_manager_evicts_stale_interests() {
+        let mut manager = SegmentManager::default();
+        manager.read(72, 1);
+        manager.write(73, "pending".to_string());
+        assert_eq!(manager.schedule(MAX_ACTIVE_SEGMENTS, &HashSet::new(), 1), vec![72, 73]);
+
+        let later = 1 + SEGMENT_INTEREST_TTL;
+        manager.evict(later);
+        assert_eq!(manager.schedule(MAX_ACTIVE_SEGMENTS, &HashSet::new(), later), vec![73]);
+        assert!(!manager.interests.contains_key(&72));
+        assert!(manager.has_pending_write(73));
+    }
+
+    /// An oversized corrupt payload is cut to one segment; a small one is
+    /// backed up whole.
+    #[test]
+    fn recovery_backup_fits_one_segment() {
+        let segment = crate::segments::RECOVERY_BACKUP_SEGMENT;
+        let mut arbiter = MemoryArbiter::test_double();
+
+        let big = "x".repeat(SEGMENT_CAPACITY * 3);
+        assert_eq!(backup_corrupt_world(&mut arbiter, &big), SEGMENT_CAPACITY);
+        assert_eq!(arbiter.pending_writes.get(&segment).map(String::len), Some(SEGMENT_CAPACITY));
+
+        assert_eq!(backup_corrupt_world(&mut arbiter, "garbage"), 7);
+        assert_eq!(arbiter.pending_writes.get(&segment).map(String::as_str), Some("garbage"));
+    }
 }
@@ -24,7 +24,8 @@
 //! (ADR 0004); the game loop refreshes the governor snapshot from this
 //! window at tick start via [`bucket_window_trend`].
 
-use crate::cpugovernor::GovernorSnapshot;
+use crate::cpugovernor::{GovernorSnapshot, Tier};
+use crate::intents::IntentCategory;
 use crate::memorysystem::*;
 use crate::missions::data::MissionData;
 use crate::operations::data::OperationData;
@@ -208,9 +209,9 @@ impl MetricsState {
     }
 }
 
-/// The tick's expansion CPU budget, derived at `tick_start` from the CPU
-/// model and the per-tick limit. Room-count-independent (the claim op
-/// divides by its own owned-room count), so it is safe to compute before
+/// The tick's CPU budget, derived at `tick_start` from the CPU model, the
+/// per-tick limit and the governor tier. Room-count-independent (the claim
+/// op divides by its own owned-room count), so it is safe to compute before
 /// dispatch. A specs `Resource`, read like [`GovernorSnapshot`].
 #[derive(Debug, Clone, Copy)]
 pub struct CpuBudget {
@@ -219,6 +220,10 @@ pub struct CpuBudget {
     /// `game::cpu::limit()` — the sustainable per-tick budget (NOT
     /// `tick_limit`, which folds in the burst bucket).
     pub cpu_limit: f64,
+    /// Total CPU this tick may use at the governor's tier (see
+    /// [`tick_allowance`]); [`remaining`](Self::remaining) measures
+    /// against it.
+    pub tick_allowance: f64,
 }
 
 impl Default for CpuBudget {
@@ -226,10 +231,33 @@ impl Default for CpuBudget {
         CpuBudget {
             cpu_used_estimate: None,
             cpu_limit: 0.0,
+            tick_allowance: 0.0,
         }
     }
 }
 
+impl CpuBudget {
+    /// CPU left this tick before the tier's allowance is spent. Bail-early
+    /// checks for resumable work (planner batches, mission pathfinding).
+    pub fn remaining(&self) -> f64 {
+        (self.tick_allowance - game::cpu::get_used()).max(0.0)
+    }
+}
+
+/// Pure allowance kernel: Normal may burst up to the engine's
+/// `tick_limit` (the bucket pays); Conserve holds to the sustainable
+/// `cpu_limit`; Critical runs under it so the bucket refills. Never above
+/// `tick_limit` — past that the engine kills the tick.
+pub fn tick_allowance(tier: Tier, cpu_limit: f64, tick_limit: f64) -> f64 {
+    const CRITICAL_FRACTION: f64 = 0.8;
+    let allowance = match tier {
+        Tier::Normal => tick_limit,
+        Tier::Conserve => cpu_limit,
+        Tier::Critical => cpu_limit * CRITICAL_FRACTION,
+    };
+    allowance.min(tick_limit)
+}
+
 /// Load callback (registered on `METRICS_SEGMENT`): re-seed the CPU cost
 /// model from the last persisted block so the dynamic room cap survives VM
 /// resets. `MemoryArbiter` is out of the world during callbacks, so read the
@@ -261,6 +289,15 @@ pub fn tick_start(world: &mut World) {
         .entry::<crate::intents::IntentRecorder>()
         .or_insert_with(Default::default)
         .reset();
+    let accounting_on = world.read_resource::<crate::features::Features>().cpu_accounting.on;
+    world
+        .entry::<crate::cpu_accounting::CpuAccounting>()
+        .or_insert_with(Default::default)
+        .begin_tick(accounting_on);
+    world
+        .entry::<crate::ledger::ResourceLedger>()
+        .or_insert_with(Default::default)
+        .begin_tick(game::time());
     let (bucket, trend) = {
         let mut state = world.entry::<MetricsState>().or_insert_with(MetricsState::default);
         if state.fresh && state.vm_starts == 0 {
@@ -278,6 +315,10 @@ pub fn tick_start(world: &mut World) {
     };
     let snapshot = GovernorSnapshot::compute(bucket, trend, game::cpu::tick_limit());
     world.insert(snapshot);
+    let intent_features = world.read_resource::<crate::features::Features>().intents;
+    world
+        .write_resource::<crate::intents::IntentRecorder>()
+        .apply_soft_caps(&intent_features, snapshot.tier);
     world
         .entry::<PathfinderService>()
         .or_insert_with(PathfinderService::default)
@@ -290,10 +331,14 @@ pub fn tick_start(world: &mut World) {
         .entry::<MetricsState>()
         .or_insert_with(MetricsState::default)
         .cpu_used_estimate();
+    let cpu_limit = game::cpu::limit() as f64;
+    let allowance = tick_allowance(snapshot.tier, cpu_limit, snapshot.tick_limit);
     world.insert(CpuBudget {
         cpu_used_estimate,
-        cpu_limit: game::cpu::limit() as f64,
+        cpu_limit,
+        tick_allowance: allowance,
     });
+    world.write_resource::<PathfinderService>().set_cpu_allowance(Some(allowance));
 }
 
 #[derive(SystemData)]
@@ -308,6 +353,7 @@ pub struct MetricsSystemData<'a> {
     pathfinder: Read<'a, PathfinderService>,
     intents: Read<'a, crate::intents::IntentRecorder>,
     squad_contexts: ReadStorage<'a, crate::military::squad::SquadContext>,
+    capabilities: Read<'a, crate::server::ServerCapabilities>,
 }
 
 pub struct MetricsSystem;
@@ -377,7 +423,7 @@ impl MetricsSystem {
                 progress: game::gpl::progress(),
                 progress_total: game::gpl::progress_total(),
             },
-            credits: game::market::credits(),
+            credits: if data.capabilities.market { game::market::credits() } else { 0.0 },
             creeps: game::creeps().keys().count() as u32,
             missions: data.mission_data.join().count() as u32,
             operations: data.operation_data.join().count() as u32,
@@ -400,11 +446,24 @@ impl MetricsSystem {
             intents: Some({
                 let (counts, digest) = data.intents.snapshot();
                 IntentMetrics {
-                    attack: counts[0],
-                    ranged_attack: counts[1],
-                    ranged_mass_attack: counts[2],
-                    heal: counts[3],
-                    ranged_heal: counts[4],
+                    attack: counts[IntentCategory::Attack as usize],
+                    ranged_attack: counts[IntentCategory::RangedAttack as usize],
+                    ranged_mass_attack: counts[IntentCategory::RangedMassAttack as usize],
+                    heal: counts[IntentCategory::Heal as usize],
+                    ranged_heal: counts[IntentCategory::RangedHeal as usize],
+                    moves: counts[IntentCategory::Move as usize],
+                    harvest: counts[IntentCategory::Harvest as usize],
+                    transfer: counts[IntentCategory::Transfer as usize],
+                    build: counts[IntentCategory::Build as usize],
+                    repair: counts[IntentCategory::Repair as usize],
+                    upgrade: counts[IntentCategory::Upgrade as usize],
+                    dismantle: counts[IntentCategory::Dismantle as usize],
+                    controller: counts[IntentCategory::Controller as usize],
+                    spawn: counts[IntentCategory::Spawn as usize],
+                    tower: counts[IntentCategory::Tower as usize],
+                    structure: counts[IntentCategory::Structure as usize],
+                    say: counts[IntentCategory::Say as usize],
+                    total: data.intents.total(),
                     digest: format!("{digest:016x}"),
                 }
             }),
@@ -580,4 +639,15 @@ mod tests {
         // A second instance is unaffected.
         assert_eq!(MetricsState::default().fault_counters().deser_failures, 0);
     }
+
+    /// The allowance tightens with the tier and never exceeds the
+    /// engine's hard `tick_limit`.
+    #[test]
+    fn tick_allowance_tightens_with_tier() {
+        assert_eq!(tick_allowance(Tier::Normal, 100.0, 500.0), 500.0);
+        assert_eq!(tick_allowance(Tier::Conserve, 100.0, 500.0), 100.0);
+        assert_eq!(tick_allowance(Tier::Critical, 100.0, 500.0), 80.0);
+        // A near-empty bucket drops tick_limit below cpu_limit.
+        assert_eq!(tick_allowance(Tier::Conserve, 100.0, 60.0), 60.0);
+    }
 }
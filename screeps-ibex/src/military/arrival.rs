@@ -0,0 +1,180 @@
+//! Squad arrival timing.
+//!
+//! A coordinated force only works if its squads reach the target in order:
+//! the drain squad has to be soaking tower fire a little before the strike
+//! squad engages, or the strike walks into full towers. Each squad's arrival
+//! is estimated from how long its members take to spawn on the spawns in
+//! range ([`CREEP_SPAWN_TIME`] per part) plus the trip to the target room, and
+//! the squads' spawn starts are staggered so they arrive in force order, one
+//! window apart. The plan rides on the objective's runtime entry so the war
+//! summary can show it and the manager can log squads that miss it.
+
+use screeps::constants::CREEP_SPAWN_TIME;
+
+/// Estimated ticks to cross one room (plains-speed bodies, exit to exit with some detour).
+pub const TRAVEL_TICKS_PER_ROOM: u32 = 50;
+/// An arrival further than this from its planned tick is logged as a deviation.
+pub const ARRIVAL_DEVIATION_TICKS: u32 = 50;
+
+/// Ticks to spawn members of the given part counts on `spawns` spawns. Each member goes to whichever spawn
+/// frees up first, largest body first, so the answer is the tick the last spawn finishes.
+pub fn spawn_ticks(member_parts: &[u32], spawns: u32) -> u32 {
+    let mut parts = member_parts.to_vec();
+    parts.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut busy_until = vec![0u32; spawns.max(1) as usize];
+    for member in parts {
+        if let Some(spawn) = busy_until.iter_mut().min() {
+            *spawn += member * CREEP_SPAWN_TIME;
+        }
+    }
+
+    busy_until.into_iter().max().unwrap_or(0)
+}
+
+/// How long one squad takes from its first spawn to reaching the target room.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ArrivalEstimate {
+    pub spawn_ticks: u32,
+    pub travel_ticks: u32,
+}
+
+impl ArrivalEstimate {
+    /// Estimate a squad of `member_parts` spawned on `spawns` spawns, `rooms` rooms from its target.
+    pub fn new(member_parts: &[u32], spawns: u32, rooms: u32) -> Self {
+        Self {
+            spawn_ticks: spawn_ticks(member_parts, spawns),
+            travel_ticks: rooms * TRAVEL_TICKS_PER_ROOM,
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.spawn_ticks + self.travel_ticks
+    }
+}
+
+/// Spawn start offsets that land squads of the given durations in order, each `window` ticks after the one
+/// before. The slowest squad relative to its slot starts at once; nobody starts earlier than needed.
+pub fn stagger_starts(durations: &[u32], window: u32) -> Vec<u32> {
+    let first_arrival = durations
+        .iter()
+        .enumerate()
+        .map(|(index, duration)| duration.saturating_sub(index as u32 * window))
+        .max()
+        .unwrap_or(0);
+
+    durations
+        .iter()
+        .enumerate()
+        .map(|(index, duration)| (first_arrival + index as u32 * window).saturating_sub(*duration))
+        .collect()
+}
+
+/// One squad's place in an [`ArrivalPlan`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlannedArrival {
+    pub estimate: ArrivalEstimate,
+    /// Ticks after the plan was made that this squad starts spawning.
+    pub start_offset: u32,
+}
+
+/// The arrival timeline of a force's squads, in force order (the drain squad leads).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArrivalPlan {
+    pub planned_at: u32,
+    pub window: u32,
+    pub squads: Vec<PlannedArrival>,
+    /// The tick the lead squad reached the target room, once it has.
+    pub arrived_at: Option<u32>,
+}
+
+impl ArrivalPlan {
+    pub fn new(planned_at: u32, estimates: &[ArrivalEstimate], window: u32) -> Self {
+        let durations: Vec<u32> = estimates.iter().map(|estimate| estimate.total()).collect();
+        let squads = estimates
+            .iter()
+            .zip(stagger_starts(&durations, window))
+            .map(|(estimate, start_offset)| PlannedArrival {
+                estimate: *estimate,
+                start_offset,
+            })
+            .collect();
+
+        Self {
+            planned_at,
+            window,
+            squads,
+            arrived_at: None,
+        }
+    }
+
+    /// The tick squad `index` may start spawning.
+    pub fn start_at(&self, index: usize) -> u32 {
+        self.planned_at + self.squads.get(index).map(|squad| squad.start_offset).unwrap_or(0)
+    }
+
+    /// The tick squad `index` is expected in the target room.
+    pub fn expected_arrival(&self, index: usize) -> Option<u32> {
+        self.squads.get(index).map(|squad| self.start_at(index) + squad.estimate.total())
+    }
+
+    /// How late (positive) or early (negative) an arrival at `now` is against squad `index`'s plan.
+    pub fn deviation(&self, index: usize, now: u32) -> Option<i64> {
+        self.expected_arrival(index).map(|expected| now as i64 - expected as i64)
+    }
+
+    /// One line per squad: its spawn start, spawn and travel estimates and expected arrival.
+    pub fn describe(&self) -> Vec<String> {
+        self.squads
+            .iter()
+            .enumerate()
+            .map(|(index, squad)| {
+                format!(
+                    "squad {}: start +{} spawn {} travel {} -> arrive {}",
+                    index,
+                    squad.start_offset,
+                    squad.estimate.spawn_ticks,
+                    squad.estimate.travel_ticks,
+                    self.expected_arrival(index).unwrap_or(0)
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spawn_time_spreads_members_across_spawns() {
+        assert_eq!(spawn_ticks(&[50, 50], 1), 300);
+        assert_eq!(spawn_ticks(&[50, 50], 2), 150);
+        assert_eq!(spawn_ticks(&[50, 30, 20], 2), 150);
+        assert_eq!(spawn_ticks(&[], 3), 0);
+        assert_eq!(spawn_ticks(&[10], 0), 30);
+    }
+
+    #[test]
+    fn staggered_squads_arrive_in_order_one_window_apart() {
+        let drain = ArrivalEstimate::new(&[50, 50], 1, 2);
+        let strike = ArrivalEstimate::new(&[40, 40, 40, 40], 2, 2);
+        let plan = ArrivalPlan::new(1_000, &[drain, strike], 30);
+
+        let first = plan.expected_arrival(0).unwrap();
+        let second = plan.expected_arrival(1).unwrap();
+        assert_eq!(second - first, 30);
+        assert!(plan.squads.iter().any(|squad| squad.start_offset == 0));
+
+        assert_eq!(plan.deviation(0, first + 10), Some(10));
+        assert_eq!(plan.deviation(0, first - 5), Some(-5));
+        assert_eq!(plan.deviation(2, first), None);
+    }
+
+    #[test]
+    fn slower_lead_squad_holds_back_the_rest() {
+        assert_eq!(stagger_starts(&[400, 100], 20), vec![0, 320]);
+        assert_eq!(stagger_starts(&[100, 400], 20), vec![280, 0]);
+        assert_eq!(stagger_starts(&[250], 20), vec![0]);
+    }
+}
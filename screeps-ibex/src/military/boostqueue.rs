@@ -16,22 +16,25 @@ pub enum BoostPriority {
 /// A request for boost compounds from the military system.
 #[derive(Clone, Debug)]
 pub struct BoostRequest {
-    /// The mission entity requesting the boost.
+    /// The entity requesting the boost (the creep to be boosted).
     pub requester: Entity,
     /// The compound type needed (e.g., XGHO2, XLHO2).
     pub compound: ResourceType,
     /// Number of body parts to boost (determines amount needed: 30 per part).
     pub parts_to_boost: u32,
+    /// Room whose labs should serve the request (where the creep is).
+    pub room: RoomName,
     /// Priority of this request.
     pub priority: BoostPriority,
 }
 
 impl BoostRequest {
-    pub fn new(requester: Entity, compound: ResourceType, parts_to_boost: u32, priority: BoostPriority) -> Self {
+    pub fn new(requester: Entity, compound: ResourceType, parts_to_boost: u32, room: RoomName, priority: BoostPriority) -> Self {
         BoostRequest {
             requester,
             compound,
             parts_to_boost,
+            room,
             priority,
         }
     }
@@ -53,6 +56,16 @@ pub struct BoostAllocation {
     pub room: RoomName,
 }
 
+/// A boosted creep on its way to be recycled, asking a lab to strip its
+/// boosts first (see `jobs::utility::unboostbehavior`).
+#[derive(Clone, Debug)]
+pub struct UnboostRequest {
+    pub requester: Entity,
+    pub creep: ObjectId<Creep>,
+    /// Room whose labs should serve the request (where the creep recycles).
+    pub room: RoomName,
+}
+
 /// Global boost request/fulfillment queue.
 /// Ephemeral -- rebuilt each tick, not serialized.
 #[derive(Default)]
@@ -61,6 +74,11 @@ pub struct BoostQueue {
     pub requests: Vec<BoostRequest>,
     /// Fulfilled allocations, keyed by requester entity.
     pub ready: HashMap<Entity, Vec<BoostAllocation>>,
+    /// Unboost requests, re-posted every tick by the creeps still asking.
+    /// The labs serve the ones posted the tick before.
+    pub unboost_requests: Vec<UnboostRequest>,
+    /// The lab each unboost requester was sent to by its room's labs.
+    pub unboost_labs: HashMap<Entity, ObjectId<StructureLab>>,
 }
 
 impl BoostQueue {
@@ -68,14 +86,39 @@ impl BoostQueue {
         BoostQueue {
             requests: Vec::new(),
             ready: HashMap::new(),
+            unboost_requests: Vec::new(),
+            unboost_labs: HashMap::new(),
         }
     }
 
+    /// Clear the boost requests and allocations. Unboost requests are
+    /// renewed separately by [`Self::renew_unboost_requests`].
     pub fn clear(&mut self) {
         self.requests.clear();
         self.ready.clear();
     }
 
+    /// Ask for a creep's boosts to be stripped.
+    pub fn request_unboost(&mut self, request: UnboostRequest) {
+        self.unboost_requests.push(request);
+    }
+
+    /// The lab an unboost requester should go to, once its labs have one free.
+    pub fn unboost_lab(&self, requester: Entity) -> Option<ObjectId<StructureLab>> {
+        self.unboost_labs.get(&requester).copied()
+    }
+
+    /// Start a new round of unboost requests (`RunJobSystem`, after the labs
+    /// have served the last), forgetting the lab of any creep that stopped
+    /// asking.
+    pub fn renew_unboost_requests(&mut self) {
+        let requests = &self.unboost_requests;
+
+        self.unboost_labs
+            .retain(|requester, _| requests.iter().any(|request| request.requester == *requester));
+        self.unboost_requests.clear();
+    }
+
     /// Add a boost request.
     pub fn request(&mut self, request: BoostRequest) {
         self.requests.push(request);
@@ -122,3 +165,93 @@ impl BoostQueue {
         sorted
     }
 }
+
+/// A lab for each unboost requester (entity, position, lab it has): its own
+/// lab while that is still in `free_labs`, else the nearest free lab no one
+/// else was given. Requesters left without one get no entry.
+pub fn assign_unboost_labs(
+    requesters: &[(Entity, Position, Option<ObjectId<StructureLab>>)],
+    free_labs: &[(ObjectId<StructureLab>, Position)],
+) -> Vec<(Entity, ObjectId<StructureLab>)> {
+    let mut available: Vec<_> = free_labs.to_vec();
+    let mut assignments = Vec::new();
+
+    for (requester, _, current) in requesters {
+        if let Some(index) = current.and_then(|current| available.iter().position(|(lab, _)| *lab == current)) {
+            assignments.push((*requester, available.remove(index).0));
+        }
+    }
+
+    for (requester, pos, _) in requesters {
+        if assignments.iter().any(|(assigned, _)| assigned == requester) {
+            continue;
+        }
+
+        let nearest = available
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, (_, lab_pos))| pos.get_range_to(*lab_pos))
+            .map(|(index, _)| index);
+
+        if let Some(index) = nearest {
+            assignments.push((*requester, available.remove(index).0));
+        }
+    }
+
+    assignments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{object_id, position};
+
+    #[test]
+    fn unboosting_creeps_keep_their_lab_and_share_out_the_rest() {
+        let mut world = World::new();
+        let (first, second, third) = (
+            world.create_entity().build(),
+            world.create_entity().build(),
+            world.create_entity().build(),
+        );
+        let room: RoomName = "W1N1".parse().expect("valid room name");
+        let (near, far, busy) = (object_id(1), object_id(2), object_id(3));
+        let free_labs = [(near, position(room, 10, 10)), (far, position(room, 30, 30))];
+
+        // The first creep keeps the far lab it was given though the near one
+        // is closer; the second takes the near one; the third, whose lab went
+        // on cooldown, finds none left.
+        let requesters = [
+            (first, position(room, 11, 11), Some(far)),
+            (second, position(room, 29, 29), None),
+            (third, position(room, 12, 12), Some(busy)),
+        ];
+
+        assert_eq!(assign_unboost_labs(&requesters, &free_labs), vec![(first, far), (second, near)]);
+    }
+
+    #[test]
+    fn renewing_forgets_the_labs_of_creeps_that_stopped_asking() {
+        let mut world = World::new();
+        let (asking, done) = (world.create_entity().build(), world.create_entity().build());
+        let room: RoomName = "W1N1".parse().expect("valid room name");
+        let mut queue = BoostQueue::new();
+
+        queue.request_unboost(UnboostRequest {
+            requester: asking,
+            creep: object_id(10),
+            room,
+        });
+        queue.unboost_labs.insert(asking, object_id(1));
+        queue.unboost_labs.insert(done, object_id(2));
+
+        queue.renew_unboost_requests();
+
+        assert_eq!(queue.unboost_lab(asking), Some(object_id(1)));
+        assert_eq!(queue.unboost_lab(done), None);
+        assert!(queue.unboost_requests.is_empty());
+
+        queue.clear();
+        assert_eq!(queue.unboost_lab(asking), Some(object_id(1)));
+    }
+}
@@ -0,0 +1,253 @@
+//! Border watch — hostiles closing on one of our rooms from a visible neighbour.
+//!
+//! The defense scan reacts to hostiles already inside an owned room. The border
+//! watch looks one room further out: armed hostiles in a neighbour that shares an
+//! exit with an owned room, standing within [`BORDER_WATCH_RANGE`] tiles of that
+//! exit, are predicted to cross it. Each prediction records where they will come
+//! in and the earliest tick they can, so the war operation can field defenders
+//! early and stage them on our side of the exit, and the tower mission can top
+//! its towers off before the fight starts.
+//!
+//! Refreshed wholesale by every defense scan from the dynamic visibility of the
+//! neighbouring rooms. Ephemeral (a runtime resource, never serialized): a VM
+//! reload simply waits for the next scan.
+
+use screeps::*;
+use std::collections::BTreeMap;
+
+/// Armed hostiles this close (in tiles) to the exit leading into an owned room are treated as inbound.
+pub const BORDER_WATCH_RANGE: u32 = 10;
+
+/// How far inside the entry exit defenders stage, so they hold the choke without standing on the edge.
+const STAGE_DEPTH: u8 = 3;
+
+/// A hostile group predicted to enter an owned room.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InboundThreat {
+    /// The neighbour the group is in now.
+    pub from_room: RoomName,
+    /// The edge tile of the owned room the nearest member will step onto.
+    pub entry: Position,
+    /// Earliest tick the nearest member can be on `entry` (one tile per tick, no fatigue).
+    pub arrival_tick: u32,
+    /// Summed attack + ranged damage per tick of the group.
+    pub dps: f32,
+    /// Summed heal per tick of the group.
+    pub heal: f32,
+    pub count: u32,
+    pub boosted: bool,
+}
+
+/// Inbound threats per owned room. Runtime resource; see the module docs.
+#[derive(Default)]
+pub struct BorderWatch {
+    inbound: BTreeMap<RoomName, InboundThreat>,
+}
+
+impl BorderWatch {
+    /// Replace the watch with this scan's predictions. Where several neighbours feed one owned room the
+    /// earliest arrival wins the entry, and the groups' forces are summed (they all end up in the room).
+    pub fn refresh(&mut self, predictions: Vec<(RoomName, InboundThreat)>) {
+        self.inbound.clear();
+        for (room, threat) in predictions {
+            match self.inbound.get_mut(&room) {
+                Some(existing) => {
+                    let (dps, heal, count) = (
+                        existing.dps + threat.dps,
+                        existing.heal + threat.heal,
+                        existing.count + threat.count,
+                    );
+                    let boosted = existing.boosted || threat.boosted;
+                    if threat.arrival_tick < existing.arrival_tick {
+                        *existing = threat;
+                    }
+                    existing.dps = dps;
+                    existing.heal = heal;
+                    existing.count = count;
+                    existing.boosted = boosted;
+                }
+                None => {
+                    self.inbound.insert(room, threat);
+                }
+            }
+        }
+    }
+
+    /// The group predicted to enter `room`, if any.
+    pub fn inbound(&self, room: RoomName) -> Option<&InboundThreat> {
+        self.inbound.get(&room)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&RoomName, &InboundThreat)> {
+        self.inbound.iter()
+    }
+}
+
+/// A hostile as the border watch sees it: where it stands, its live parts, and whether any are boosted.
+pub struct WatchedHostile {
+    pub pos: Position,
+    pub parts: Vec<Part>,
+    pub boosted: bool,
+}
+
+fn is_armed(parts: &[Part]) -> bool {
+    parts
+        .iter()
+        .any(|p| matches!(p, Part::Attack | Part::RangedAttack | Part::Work | Part::Claim))
+}
+
+/// Predict the hostiles in `neighbour` that will cross into `owned`. `None` unless the rooms share an exit
+/// (orthogonally adjacent) and at least one armed hostile is within [`BORDER_WATCH_RANGE`] of it. Healers
+/// travelling with the group count toward its force but don't trigger the prediction on their own.
+pub fn predict_inbound(neighbour: RoomName, owned: RoomName, hostiles: &[WatchedHostile], now: u32) -> Option<InboundThreat> {
+    let last = ROOM_SIZE - 1;
+    // Tiles from `pos` to the shared exit edge, and the owned-room edge tile it would step onto.
+    let crossing = |pos: Position| -> (u32, u8, u8) {
+        let (x, y) = (pos.x().u8(), pos.y().u8());
+        match owned - neighbour {
+            (1, 0) => ((last - x) as u32, 0, y),
+            (-1, 0) => (x as u32, last, y),
+            (0, 1) => ((last - y) as u32, x, 0),
+            _ => (y as u32, x, last),
+        }
+    };
+    if !matches!(owned - neighbour, (1, 0) | (-1, 0) | (0, 1) | (0, -1)) {
+        return None;
+    }
+
+    let group: Vec<&WatchedHostile> = hostiles
+        .iter()
+        .filter(|h| h.pos.room_name() == neighbour && crossing(h.pos).0 <= BORDER_WATCH_RANGE)
+        .collect();
+    let lead = group.iter().filter(|h| is_armed(&h.parts)).min_by_key(|h| crossing(h.pos).0)?;
+
+    let (distance, ex, ey) = crossing(lead.pos);
+    let clamp = |c: u8| c.clamp(1, last - 1);
+    let (ex, ey) = if ex == 0 || ex == last { (ex, clamp(ey)) } else { (clamp(ex), ey) };
+
+    let parts = group.iter().flat_map(|h| h.parts.iter());
+    let dps = parts.clone().map(|p| match p {
+        Part::Attack => ATTACK_POWER,
+        Part::RangedAttack => RANGED_ATTACK_POWER,
+        _ => 0,
+    });
+    let heal = parts.filter(|p| **p == Part::Heal).count() as u32 * HEAL_POWER;
+
+    Some(InboundThreat {
+        from_room: neighbour,
+        entry: Position::new(RoomCoordinate::new(ex).ok()?, RoomCoordinate::new(ey).ok()?, owned),
+        arrival_tick: now + distance + 1,
+        dps: dps.sum::<u32>() as f32,
+        heal: heal as f32,
+        count: group.len() as u32,
+        boosted: group.iter().any(|h| h.boosted),
+    })
+}
+
+/// The tile defenders hold for an inbound group: [`STAGE_DEPTH`] tiles straight in from `entry`, stepping
+/// shallower when that tile is a wall. Falls back to the entry tile itself.
+pub fn staging_tile(entry: Position, is_wall: impl Fn(Position) -> bool) -> Position {
+    let last = ROOM_SIZE - 1;
+    let (x, y) = (entry.x().u8(), entry.y().u8());
+    (1..=STAGE_DEPTH)
+        .rev()
+        .filter_map(|depth| {
+            let (sx, sy) = match (x, y) {
+                (0, _) => (depth, y),
+                (_, 0) => (x, depth),
+                (xx, _) if xx == last => (last - depth, y),
+                _ => (x, last - depth),
+            };
+            Some(Position::new(
+                RoomCoordinate::new(sx).ok()?,
+                RoomCoordinate::new(sy).ok()?,
+                entry.room_name(),
+            ))
+        })
+        .find(|tile| !is_wall(*tile))
+        .unwrap_or(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(x: u8, y: u8, room: &str) -> Position {
+        Position::new(
+            RoomCoordinate::new(x).unwrap(),
+            RoomCoordinate::new(y).unwrap(),
+            room.parse().unwrap(),
+        )
+    }
+
+    fn hostile(at: Position, parts: &[Part]) -> WatchedHostile {
+        WatchedHostile {
+            pos: at,
+            parts: parts.to_vec(),
+            boosted: false,
+        }
+    }
+
+    /// W2N1 lies west of W1N1, so a group near W2N1's east edge is heading our way and enters on x = 0.
+    #[test]
+    fn armed_group_near_the_shared_exit_is_inbound() {
+        let (neighbour, owned) = ("W2N1".parse().unwrap(), "W1N1".parse().unwrap());
+        let group = [
+            hostile(pos(45, 20, "W2N1"), &[Part::Attack, Part::Attack, Part::Move]),
+            hostile(pos(44, 21, "W2N1"), &[Part::Heal, Part::Move]),
+            hostile(pos(10, 10, "W2N1"), &[Part::RangedAttack]),
+        ];
+
+        let threat = predict_inbound(neighbour, owned, &group, 1000).expect("inbound");
+        assert_eq!(threat.entry, pos(0, 20, "W1N1"));
+        assert_eq!(threat.arrival_tick, 1000 + 4 + 1);
+        assert_eq!(threat.count, 2, "the far ranged creep is not part of the inbound group");
+        assert_eq!(threat.dps, 2.0 * ATTACK_POWER as f32);
+        assert_eq!(threat.heal, HEAL_POWER as f32);
+    }
+
+    #[test]
+    fn unarmed_far_or_diagonal_hostiles_are_not_inbound() {
+        let (neighbour, owned) = ("W2N1".parse().unwrap(), "W1N1".parse().unwrap());
+        assert!(predict_inbound(neighbour, owned, &[hostile(pos(48, 20, "W2N1"), &[Part::Carry, Part::Move])], 0).is_none());
+        assert!(predict_inbound(neighbour, owned, &[hostile(pos(30, 20, "W2N1"), &[Part::Attack])], 0).is_none());
+
+        let diagonal = "W2N2".parse().unwrap();
+        assert!(predict_inbound(diagonal, owned, &[hostile(pos(48, 48, "W2N2"), &[Part::Attack])], 0).is_none());
+    }
+
+    #[test]
+    fn refresh_sums_groups_and_keeps_the_earliest_entry() {
+        let owned: RoomName = "W1N1".parse().unwrap();
+        let threat = |from: &str, entry: Position, arrival_tick: u32| InboundThreat {
+            from_room: from.parse().unwrap(),
+            entry,
+            arrival_tick,
+            dps: 30.0,
+            heal: 0.0,
+            count: 1,
+            boosted: false,
+        };
+
+        let mut watch = BorderWatch::default();
+        watch.refresh(vec![
+            (owned, threat("W2N1", pos(0, 20, "W1N1"), 120)),
+            (owned, threat("W1N2", pos(25, 0, "W1N1"), 105)),
+        ]);
+        let inbound = watch.inbound(owned).expect("watched");
+        assert_eq!(inbound.entry, pos(25, 0, "W1N1"));
+        assert_eq!((inbound.dps, inbound.count), (60.0, 2));
+
+        watch.refresh(Vec::new());
+        assert!(watch.inbound(owned).is_none());
+    }
+
+    #[test]
+    fn staging_tile_steps_inward_around_walls() {
+        let entry = pos(0, 20, "W1N1");
+        assert_eq!(staging_tile(entry, |_| false), pos(3, 20, "W1N1"));
+        assert_eq!(staging_tile(entry, |t| t.x().u8() == 3), pos(2, 20, "W1N1"));
+        assert_eq!(staging_tile(entry, |_| true), entry);
+        assert_eq!(staging_tile(pos(25, 49, "W1N1"), |_| false), pos(25, 46, "W1N1"));
+    }
+}
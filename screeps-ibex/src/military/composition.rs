@@ -0,0 +1,427 @@
+//! Spawn-cost accounting for the forces combat objectives request, and the
+//! escalation applied to an attack after a wave of it is wiped.
+//!
+//! The compositions themselves come from the decision crate; this is the
+//! bot-side sum a producer checks against the economy before it asks the
+//! squad manager to field a force.
+//!
+//! Re-sizing a room after a lost wave yields the same force that just died, so
+//! the squad manager files a [`WaveReport`] for each wiped wave (what it
+//! fielded, the towers and healers it met, what killed each member) and the war
+//! operation runs every fresh plan through [`escalate_force`]: one step per lost
+//! wave — drain the towers first, add a healer, or boost the bodies — capped by
+//! what the home rooms can build and have in stock.
+
+use super::damage::DEFENDER_BOOSTS;
+use super::objective_queue::ForceRequirement;
+use super::squad::{CauseOfDeath, SquadCasualty};
+use screeps::*;
+use screeps_combat_decision::bodies::{CombatBodySpec, MoveProfile};
+use screeps_combat_decision::composition::{BodyType, CompositionParams, SquadComposition, SquadRole, SquadSlot, PREFERRED_MEMBER_ENERGY};
+use screeps_combat_decision::doctrine;
+use screeps_combat_decision::force_sizing::{AssaultMode, DefenseProfile};
+use std::collections::HashMap;
+use std::fmt;
+
+/// T3 compound for the assault part the defender boosts don't cover: XKHO2 ranged attack.
+pub const ASSAULT_BOOSTS: [(Part, ResourceType); 1] = [(Part::RangedAttack, ResourceType::CatalyzedKeaniumAlkalide)];
+/// Most healers escalation adds to an attack across its waves.
+pub const MAX_ESCALATION_HEALERS: u32 = 2;
+/// HEAL parts on an added healer, when the homes can build that many.
+const ESCALATION_HEALER_HEAL: u32 = 10;
+
+/// Energy to spawn every squad in `plan`, with each member sized the way the
+/// squad manager builds it: to `energy_capacity`, capped at
+/// `PREFERRED_MEMBER_ENERGY`.
+pub fn force_plan_cost(plan: &ForceRequirement, energy_capacity: u32) -> u32 {
+    let member_energy = energy_capacity.min(PREFERRED_MEMBER_ENERGY);
+
+    plan.squads.iter().map(|squad| squad.estimated_cost(member_energy)).sum()
+}
+
+/// The CLAIM squad a `Declaim` objective asks for at `member_energy`, as the
+/// always-field `DeclaimAttack` doctrine plans it. `None` when no declaimer can
+/// be built at that energy.
+pub fn declaim_composition(member_energy: u32) -> Option<SquadComposition> {
+    // A declaimer is CLAIM + MOVE only and never fights, so the enemy and tower
+    // terms stay empty; the producer keeps it out of defended rooms instead.
+    let ctx = doctrine::EngagementContext {
+        objective: doctrine::DoctrineObjective::Declaim,
+        coordination: doctrine::EnemyCoordination::Individual,
+        defense: DefenseProfile::default(),
+        enemy_force: None,
+        importance: 0.0,
+        member_energy,
+        target_value: 1_000_000.0,
+        onsite_window: CREEP_LIFE_TIME,
+        params: CompositionParams {
+            member_energy,
+            ..Default::default()
+        },
+        defense_intel_reliable: false,
+    };
+    let doctrines = doctrine::default_doctrines();
+
+    doctrine::decide_doctrine(&ctx, &doctrines).and_then(|d| doctrine::plan_engagement(d, &ctx, None).composition)
+}
+
+/// The shape of an attack wave, in the terms escalation changes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WavePlan {
+    pub members: u32,
+    pub healers: u32,
+    pub drain: bool,
+    pub boosted: bool,
+}
+
+impl WavePlan {
+    pub fn of(force: &ForceRequirement, drain: bool, boosted: bool) -> WavePlan {
+        let slots = || force.squads.iter().flat_map(|squad| squad.slots.iter());
+
+        WavePlan {
+            members: slots().count() as u32,
+            healers: slots().filter(|slot| slot.role == SquadRole::Healer).count() as u32,
+            drain,
+            boosted,
+        }
+    }
+}
+
+impl fmt::Display for WavePlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} members ({} healers)", self.members, self.healers)?;
+        if self.drain {
+            write!(f, ", drain")?;
+        }
+        if self.boosted {
+            write!(f, ", boosted")?;
+        }
+        Ok(())
+    }
+}
+
+/// A wiped wave: what it fielded and what it ran into.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WaveReport {
+    pub plan: WavePlan,
+    /// Energized hostile towers in the room when the wave was lost.
+    pub towers_alive: u32,
+    /// Hostile creeps with HEAL parts in the room.
+    pub hostile_healers: u32,
+    pub killed_by_towers: u32,
+    pub killed_by_creeps: u32,
+}
+
+impl WaveReport {
+    pub fn new(plan: WavePlan, towers_alive: u32, hostile_healers: u32, casualties: &[SquadCasualty]) -> WaveReport {
+        let killed_by = |cause: CauseOfDeath| casualties.iter().filter(|c| c.cause == cause).count() as u32;
+
+        WaveReport {
+            plan,
+            towers_alive,
+            hostile_healers,
+            killed_by_towers: killed_by(CauseOfDeath::Towers),
+            killed_by_creeps: killed_by(CauseOfDeath::Creeps),
+        }
+    }
+
+    fn towers_killed_most(&self) -> bool {
+        self.towers_alive > 0 && self.killed_by_towers > 0 && self.killed_by_towers >= self.killed_by_creeps
+    }
+}
+
+/// One change made to an attack's force after a lost wave.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WaveEscalation {
+    /// Towers did the killing: drain them before breaching.
+    DrainFirst,
+    /// Add a healer to the squad.
+    AddHealer,
+    /// Boost every member's combat parts with T3 compounds.
+    Boost,
+}
+
+impl fmt::Display for WaveEscalation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            WaveEscalation::DrainFirst => "drain first",
+            WaveEscalation::AddHealer => "+healer",
+            WaveEscalation::Boost => "boosted",
+        })
+    }
+}
+
+/// The attack waves against one room since it was last won. Session-only, like the harass tallies.
+#[derive(Clone, Debug, Default)]
+pub struct AttackWaves {
+    pub lost: Vec<WaveReport>,
+    /// The plan the war operation last fielded for the next wave, and the escalations it carries.
+    pub next: Option<(WavePlan, Vec<WaveEscalation>)>,
+}
+
+/// A force after escalation.
+#[derive(Clone, Debug)]
+pub struct EscalatedForce {
+    pub force: ForceRequirement,
+    pub drain: bool,
+    /// Per-member T3 boosts `(compound, parts)`; empty unless boosted.
+    pub boosts: Vec<(ResourceType, u32)>,
+    pub steps: Vec<WaveEscalation>,
+    /// Compounds a wanted boost was short of.
+    pub missing_boosts: Vec<(ResourceType, u32)>,
+}
+
+fn spec_parts(spec: &CombatBodySpec, part: Part) -> u32 {
+    match part {
+        Part::Attack => spec.attack,
+        Part::RangedAttack => spec.ranged_attack,
+        Part::Heal => spec.heal,
+        Part::Tough => spec.tough,
+        _ => 0,
+    }
+}
+
+/// Per-member boosts covering every combat part in `force`, and what `stock` is short of to boost them all.
+fn boost_plan(force: &ForceRequirement, stock: &HashMap<ResourceType, u32>) -> (Vec<(ResourceType, u32)>, Vec<(ResourceType, u32)>) {
+    let specs: Vec<&CombatBodySpec> = force
+        .squads
+        .iter()
+        .flat_map(|squad| squad.slots.iter())
+        .map(|slot| {
+            let BodyType::Sized(spec) = &slot.body_type;
+            spec
+        })
+        .collect();
+    let members = specs.len() as u32;
+
+    let boosts: Vec<(ResourceType, u32)> = DEFENDER_BOOSTS
+        .iter()
+        .chain(ASSAULT_BOOSTS.iter())
+        .map(|(part, compound)| (*compound, specs.iter().map(|spec| spec_parts(spec, *part)).max().unwrap_or(0)))
+        .filter(|(_, parts)| *parts > 0)
+        .collect();
+    let missing = boosts
+        .iter()
+        .filter_map(|(compound, parts)| {
+            let needed = members * parts * LAB_BOOST_MINERAL;
+            let short = needed.saturating_sub(stock.get(compound).copied().unwrap_or(0));
+            (short > 0).then_some((*compound, short))
+        })
+        .collect();
+
+    (boosts, missing)
+}
+
+/// The biggest healer a home with `energy_capacity` builds, up to [`ESCALATION_HEALER_HEAL`] HEAL parts.
+fn escalation_healer(energy_capacity: u32) -> Option<SquadSlot> {
+    let member_energy = energy_capacity.min(PREFERRED_MEMBER_ENERGY);
+
+    (1..=ESCALATION_HEALER_HEAL).rev().find_map(|heal| {
+        let body_type = BodyType::Sized(CombatBodySpec {
+            heal,
+            ..Default::default()
+        });
+
+        body_type.build_body(member_energy, MoveProfile::Plains).map(|_| SquadSlot {
+            role: SquadRole::Healer,
+            body_type,
+        })
+    })
+}
+
+/// Escalate a freshly sized attack force once per wave already lost against the room, in order. Each step
+/// answers what killed that wave: towers that did most of the killing are drained first; a room whose
+/// healers out-healed us gets boosted bodies; otherwise the squad gets another healer. A step the homes
+/// can't afford (no buildable healer at `energy_capacity`, boosts not in `stock`) falls through to the next
+/// option, and once nothing is left the force stays as it is.
+pub fn escalate_force(
+    force: ForceRequirement,
+    mode: AssaultMode,
+    lost: &[WaveReport],
+    energy_capacity: u32,
+    stock: &HashMap<ResourceType, u32>,
+) -> EscalatedForce {
+    let mut escalated = EscalatedForce {
+        force,
+        drain: matches!(mode, AssaultMode::Drain),
+        boosts: Vec::new(),
+        steps: Vec::new(),
+        missing_boosts: Vec::new(),
+    };
+    let mut healers_added = 0;
+
+    for report in lost {
+        let boosted = !escalated.boosts.is_empty();
+        let (boosts, missing) = boost_plan(&escalated.force, stock);
+        let can_boost = !boosted && !boosts.is_empty() && missing.is_empty();
+        let healer = if healers_added < MAX_ESCALATION_HEALERS && !escalated.force.squads.is_empty() {
+            escalation_healer(energy_capacity)
+        } else {
+            None
+        };
+
+        let step = if report.towers_killed_most() && !escalated.drain {
+            Some(WaveEscalation::DrainFirst)
+        } else if report.hostile_healers > 0 && can_boost {
+            Some(WaveEscalation::Boost)
+        } else if healer.is_some() {
+            Some(WaveEscalation::AddHealer)
+        } else if can_boost {
+            Some(WaveEscalation::Boost)
+        } else {
+            if !boosted {
+                escalated.missing_boosts = missing;
+            }
+            None
+        };
+
+        match step {
+            Some(WaveEscalation::DrainFirst) => escalated.drain = true,
+            Some(WaveEscalation::AddHealer) => {
+                if let (Some(slot), Some(squad)) = (healer, escalated.force.squads.first_mut()) {
+                    squad.slots.push(slot);
+                    healers_added += 1;
+                }
+            }
+            Some(WaveEscalation::Boost) => escalated.boosts = boosts,
+            None => {}
+        }
+        escalated.steps.extend(step);
+    }
+
+    // A healer added after boosting still wants its HEAL parts covered.
+    if !escalated.boosts.is_empty() {
+        escalated.boosts = boost_plan(&escalated.force, stock).0;
+    }
+
+    escalated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use screeps_combat_decision::composition::assemble_force;
+    use screeps_combat_decision::force_sizing::RequiredForce;
+
+    fn sized_force() -> ForceRequirement {
+        let required = RequiredForce {
+            heal_parts: 4,
+            immune_struct_parts: 4,
+            ..Default::default()
+        };
+        ForceRequirement::single(assemble_force(&required, PREFERRED_MEMBER_ENERGY).expect("fieldable at the preferred size"))
+    }
+
+    fn lost_wave(towers_alive: u32, hostile_healers: u32, causes: &[CauseOfDeath]) -> WaveReport {
+        let casualties: Vec<SquadCasualty> = causes
+            .iter()
+            .map(|cause| SquadCasualty {
+                role: SquadRole::RangedDPS,
+                cause: *cause,
+            })
+            .collect();
+
+        WaveReport::new(WavePlan::default(), towers_alive, hostile_healers, &casualties)
+    }
+
+    fn full_stock() -> HashMap<ResourceType, u32> {
+        DEFENDER_BOOSTS
+            .iter()
+            .chain(ASSAULT_BOOSTS.iter())
+            .map(|(_, compound)| (*compound, 100_000))
+            .collect()
+    }
+
+    #[test]
+    fn force_plan_cost_sums_squads_at_the_capped_member_size() {
+        let required = RequiredForce {
+            heal_parts: 4,
+            immune_struct_parts: 4,
+            ..Default::default()
+        };
+        let squad = assemble_force(&required, PREFERRED_MEMBER_ENERGY).expect("fieldable at the preferred size");
+        let single = ForceRequirement::single(squad.clone());
+        let double = ForceRequirement {
+            squads: vec![squad.clone(), squad],
+        };
+
+        let cost = force_plan_cost(&single, PREFERRED_MEMBER_ENERGY);
+        assert!(cost > 0);
+        assert_eq!(force_plan_cost(&double, PREFERRED_MEMBER_ENERGY), cost * 2);
+        assert_eq!(force_plan_cost(&single, PREFERRED_MEMBER_ENERGY * 4), cost);
+        assert_eq!(force_plan_cost(&ForceRequirement::default(), PREFERRED_MEMBER_ENERGY), 0);
+    }
+
+    #[test]
+    fn casualties_are_attributed_by_cause() {
+        assert_eq!(CauseOfDeath::classify(1000, 1000, 600.0, 0.0), CauseOfDeath::Expired);
+        assert_eq!(CauseOfDeath::classify(200, 1000, 0.0, 0.0), CauseOfDeath::Unknown);
+        assert_eq!(CauseOfDeath::classify(200, 1000, 600.0, 120.0), CauseOfDeath::Towers);
+        assert_eq!(CauseOfDeath::classify(200, 1000, 150.0, 300.0), CauseOfDeath::Creeps);
+
+        let report = lost_wave(2, 0, &[CauseOfDeath::Towers, CauseOfDeath::Towers, CauseOfDeath::Creeps]);
+        assert_eq!((report.killed_by_towers, report.killed_by_creeps), (2, 1));
+    }
+
+    /// Tower deaths switch to drain-first, then later waves add healers up to the cap.
+    #[test]
+    fn waves_escalate_one_step_each() {
+        let force = sized_force();
+        let healers = WavePlan::of(&force, false, false).healers;
+        let towers = lost_wave(3, 0, &[CauseOfDeath::Towers, CauseOfDeath::Towers]);
+        let creeps = lost_wave(3, 0, &[CauseOfDeath::Creeps]);
+
+        let first = escalate_force(
+            force.clone(),
+            AssaultMode::Breach,
+            std::slice::from_ref(&towers),
+            12_900,
+            &HashMap::new(),
+        );
+        assert_eq!(first.steps, vec![WaveEscalation::DrainFirst]);
+        assert!(first.drain);
+
+        let lost = [towers, creeps.clone(), creeps.clone(), creeps];
+        let later = escalate_force(force, AssaultMode::Breach, &lost, 12_900, &HashMap::new());
+        assert_eq!(
+            later.steps,
+            vec![WaveEscalation::DrainFirst, WaveEscalation::AddHealer, WaveEscalation::AddHealer]
+        );
+        assert_eq!(
+            WavePlan::of(&later.force, later.drain, false).healers,
+            healers + MAX_ESCALATION_HEALERS
+        );
+        assert!(later.boosts.is_empty());
+        assert!(!later.missing_boosts.is_empty());
+    }
+
+    /// Out-healed waves go straight to boosts when the labs have them; a home too small for a healer and
+    /// short of boosts leaves the force as it was.
+    #[test]
+    fn escalation_is_capped_by_capacity_and_stock() {
+        let healed = lost_wave(0, 2, &[CauseOfDeath::Creeps]);
+
+        let boosted = escalate_force(
+            sized_force(),
+            AssaultMode::Breach,
+            std::slice::from_ref(&healed),
+            12_900,
+            &full_stock(),
+        );
+        assert_eq!(boosted.steps, vec![WaveEscalation::Boost]);
+        assert!(boosted
+            .boosts
+            .iter()
+            .any(|(compound, _)| *compound == ResourceType::CatalyzedLemergiumAlkalide));
+
+        let stuck = escalate_force(
+            sized_force(),
+            AssaultMode::Breach,
+            std::slice::from_ref(&healed),
+            200,
+            &HashMap::new(),
+        );
+        assert!(stuck.steps.is_empty());
+        assert_eq!(WavePlan::of(&stuck.force, false, false), WavePlan::of(&sized_force(), false, false));
+    }
+}
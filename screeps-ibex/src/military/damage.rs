@@ -1,18 +1,142 @@
+use crate::combat::{CombatCreepDto, CombatStructureDto, Ownership};
 use screeps::*;
+use serde::{Deserialize, Serialize};
 // The tower attack/heal/repair falloff curve is engine MECHANICS (the ground truth); reached through
 // the decision crate (single source — no duplicated f32 copy). The engine returns u32; cast at use.
 use screeps_combat_decision::damage::tower_attack_damage_at_range;
 
+use screeps_combat_decision::bodies::CombatBodySpec;
+use screeps_combat_decision::composition::{BodyType, FormationShape, SquadComposition, SquadRole, SquadSlot};
+
 // The threat-picture → part-count helpers (`drain_heal_parts_for_dps`, `attack_parts_to_kill`,
 // `KILL_WINDOW_TICKS`, `MAX_OFFENSE_PARTS`, the sized template bodies) moved to
 // `screeps_combat_decision::bodies` (the shared force-sizing body layer). This module keeps the
-// game-coupled tower-over-`Position` damage math + the defender spawn-readiness decision.
+// game-coupled tower-over-`Position` damage math, the defender spawn-readiness decision and the
+// boosted-defender escalation.
+
+/// Fewest adjacent exit tiles a squad needs to file into a room.
+pub const MIN_ENTRY_SEGMENT: u8 = 3;
+
+/// A side of a room.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RoomEdge {
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+impl RoomEdge {
+    pub const ALL: [RoomEdge; 4] = [RoomEdge::Top, RoomEdge::Right, RoomEdge::Bottom, RoomEdge::Left];
+
+    /// The edge tile `offset` tiles along this side.
+    pub fn tile(self, room_name: RoomName, offset: u8) -> Position {
+        let far = ROOM_SIZE - 1;
+        let (x, y) = match self {
+            RoomEdge::Top => (offset, 0),
+            RoomEdge::Right => (far, offset),
+            RoomEdge::Bottom => (offset, far),
+            RoomEdge::Left => (0, offset),
+        };
+
+        Position::new(RoomCoordinate::new(x).unwrap(), RoomCoordinate::new(y).unwrap(), room_name)
+    }
+}
+
+/// A run of adjacent exit tiles on one side of a room, `start..=end` along the edge, where the hostile
+/// towers hit least.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EntrySegment {
+    #[serde(rename = "e")]
+    pub edge: RoomEdge,
+    #[serde(rename = "s")]
+    pub start: u8,
+    #[serde(rename = "n")]
+    pub end: u8,
+    /// Tower damage per tick on the segment's worst tile.
+    #[serde(rename = "d")]
+    pub dps: f32,
+}
+
+impl EntrySegment {
+    pub fn tiles(&self, room_name: RoomName) -> impl Iterator<Item = Position> + '_ {
+        (self.start..=self.end).map(move |offset| self.edge.tile(room_name, offset))
+    }
+
+    pub fn center(&self, room_name: RoomName) -> Position {
+        self.edge.tile(room_name, self.start + (self.end - self.start) / 2)
+    }
+
+    /// Range from `pos` to the nearest tile of the segment.
+    pub fn range_from(&self, room_name: RoomName, pos: Position) -> u32 {
+        self.tiles(room_name).map(|tile| tile.get_range_to(pos)).min().unwrap_or(u32::MAX)
+    }
+}
+
+/// The least-damaged way into `room_name` past the towers at `tower_positions`. Every edge's exit tiles
+/// (`is_exit`) are scored by the towers' falloff damage on them; the best window of [`MIN_ENTRY_SEGMENT`]
+/// adjacent exits is the one whose worst tile takes the least, grown along the edge while the next tile
+/// takes no more; the widest such segment wins a tie. `None` when no edge has enough adjacent exits.
+pub fn safest_entry_segment<F>(room_name: RoomName, tower_positions: &[Position], is_exit: F) -> Option<EntrySegment>
+where
+    F: Fn(Position) -> bool,
+{
+    let window = MIN_ENTRY_SEGMENT as usize;
+    let mut best: Option<EntrySegment> = None;
+
+    for edge in RoomEdge::ALL {
+        // Corners are never exits.
+        let damage: Vec<Option<u32>> = (1..ROOM_SIZE - 1)
+            .map(|offset| {
+                let tile = edge.tile(room_name, offset);
+                is_exit(tile).then(|| total_tower_damage(tower_positions, tile).round() as u32)
+            })
+            .collect();
+
+        for first in 0..damage.len().saturating_sub(window - 1) {
+            let Some(worst) = damage[first..first + window].iter().try_fold(0, |worst, d| d.map(|d| worst.max(d))) else {
+                continue;
+            };
+
+            let (mut start, mut end) = (first, first + window - 1);
+            while start > 0 && damage[start - 1].is_some_and(|d| d <= worst) {
+                start -= 1;
+            }
+            while end + 1 < damage.len() && damage[end + 1].is_some_and(|d| d <= worst) {
+                end += 1;
+            }
+
+            // Past the falloff floor many tiles take the same damage; the wider opening wins the tie.
+            let better = best.as_ref().is_none_or(|b| {
+                let best_worst = b.dps.round() as u32;
+                worst < best_worst || (worst == best_worst && end - start > (b.end - b.start) as usize)
+            });
+            if !better {
+                continue;
+            }
+
+            best = Some(EntrySegment {
+                edge,
+                start: start as u8 + 1,
+                end: end as u8 + 1,
+                dps: worst as f32,
+            });
+        }
+    }
+
+    best
+}
 
-/// Tower DPS at a typical drain position (room edge, north side).
-/// Drains sit at the edge to maximize range from towers; this approximates that.
-pub fn tower_dps_at_room_edge(room_name: RoomName, tower_positions: &[Position]) -> f32 {
-    let edge_pos = Position::new(RoomCoordinate::new(25).unwrap(), RoomCoordinate::new(0).unwrap(), room_name);
-    total_tower_damage(tower_positions, edge_pos)
+/// Order-independent hash of a tower layout, to tell when a cached [`EntrySegment`] is stale.
+pub fn tower_layout_hash(tower_positions: &[Position]) -> u64 {
+    use std::hash::{Hash, Hasher};
+
+    let mut packed: Vec<u32> = tower_positions.iter().map(|pos| pos.packed_repr()).collect();
+    packed.sort_unstable();
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    packed.hash(&mut hasher);
+    hasher.finish()
 }
 
 /// Calculate total tower damage from multiple towers against a target at a given position.
@@ -26,6 +150,40 @@ pub fn total_tower_damage(tower_positions: &[Position], target_pos: Position) ->
         .sum()
 }
 
+/// Damage that could land on `pos` this tick from the hostile creeps and energized hostile towers in
+/// view: ATTACK parts within range 1, RANGED_ATTACK parts within range 3, and each tower at its falloff
+/// range. Same-room only, and boosts are ignored, so it is a floor against boosted attackers.
+pub fn projected_damage_at(hostiles: &[CombatCreepDto], structures: &[CombatStructureDto], pos: Position) -> u32 {
+    let creeps: u32 = hostiles
+        .iter()
+        .filter(|h| h.pos.room_name() == pos.room_name())
+        .map(|h| {
+            let range = h.pos.get_range_to(pos);
+            let active = |part: Part| h.body.iter().filter(|p| p.part == part && p.hits > 0).count() as u32;
+            let melee = if range <= 1 { active(Part::Attack) * ATTACK_POWER } else { 0 };
+            let ranged = if range <= 3 {
+                active(Part::RangedAttack) * RANGED_ATTACK_POWER
+            } else {
+                0
+            };
+            melee + ranged
+        })
+        .sum();
+
+    let towers: u32 = structures
+        .iter()
+        .filter(|s| {
+            s.structure_type == StructureType::Tower
+                && s.ownership == Ownership::Hostile
+                && s.energy >= TOWER_ENERGY_COST
+                && s.pos.room_name() == pos.room_name()
+        })
+        .map(|s| tower_attack_damage_at_range(s.pos.get_range_to(pos)))
+        .sum();
+
+    creeps + towers
+}
+
 /// Calculate net damage (tower damage minus enemy healing) for a target.
 /// Returns positive if towers can overcome healing, negative if not.
 pub fn net_tower_damage(tower_positions: &[Position], target_pos: Position, enemy_heal_per_tick: f32) -> f32 {
@@ -125,6 +283,136 @@ pub fn defender_spawn_readiness(
     }
 }
 
+// ── Boosted defender escalation ──────────────────────────────────────────────
+//
+// Whether a defense can be won by the strongest unboosted defender at all, and
+// if not, which T3 boosts a heavier defender needs. The hostile side is read as
+// live parts with a boosted flag; boosted parts are assumed T3, the
+// conservative end of what `room::hostilesummary` would parse.
+
+/// T3 compound for each boosted defender part: XUH2O attack, XGHO2 tough, XLHO2 heal.
+pub const DEFENDER_BOOSTS: [(Part, ResourceType); 3] = [
+    (Part::Attack, ResourceType::CatalyzedUtriumAcid),
+    (Part::Tough, ResourceType::CatalyzedGhodiumAlkalide),
+    (Part::Heal, ResourceType::CatalyzedLemergiumAlkalide),
+];
+
+/// ATTACK parts on the strongest unboosted defender a member-energy budget affords (20 ATTACK + 20 MOVE).
+pub const UNBOOSTED_DEFENDER_ATTACK: u32 = 20;
+/// The boosted defender body: parts before MOVE. Fits the same member-energy budget as the unboosted one.
+pub const BOOSTED_DEFENDER_ATTACK: u32 = 15;
+pub const BOOSTED_DEFENDER_TOUGH: u32 = 4;
+pub const BOOSTED_DEFENDER_HEAL: u32 = 2;
+/// Most boosted defenders a single defense fields.
+pub const MAX_BOOSTED_DEFENDERS: u32 = 2;
+
+/// T3 multiplier on ATTACK / RANGED_ATTACK / HEAL output.
+const T3_POWER_MULTIPLIER: u32 = 4;
+/// Share of our damage that lands through boosted T3 TOUGH.
+const T3_TOUGH_DAMAGE_TAKEN: f32 = 0.3;
+
+/// The escalation verdict for one defense.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BoostEscalation {
+    /// The strongest unboosted defender plus the towers already out-damages the attackers' heal.
+    Unboosted,
+    /// Boosted defenders win and the compounds are in stock: field `defenders` members, each boosted with
+    /// `(compound, parts)`.
+    Boosted { defenders: u32, boosts: Vec<(ResourceType, u32)> },
+    /// Boosted defenders would win but stock is short by these amounts.
+    ShortOfBoosts { missing: Vec<(ResourceType, u32)> },
+    /// Not even the boosted ceiling out-damages their heal.
+    Outmatched,
+}
+
+/// The squad a boosted defense fields: `defenders` melee members with the boosted defender body, walking as a
+/// pair when there are two.
+pub fn boosted_defender_composition(defenders: u32) -> SquadComposition {
+    let defender = SquadSlot {
+        role: SquadRole::MeleeDPS,
+        body_type: BodyType::Sized(CombatBodySpec {
+            attack: BOOSTED_DEFENDER_ATTACK,
+            tough: BOOSTED_DEFENDER_TOUGH,
+            heal: BOOSTED_DEFENDER_HEAL,
+            ..Default::default()
+        }),
+    };
+
+    SquadComposition {
+        label: "Boosted defense".into(),
+        slots: vec![defender; defenders.max(1) as usize],
+        formation_shape: if defenders > 1 {
+            FormationShape::Line
+        } else {
+            FormationShape::None
+        },
+        formation_mode: Default::default(),
+        retreat_threshold: 0.3,
+    }
+}
+
+/// Decide whether a defense needs boosted defenders. `hostiles` holds each attacker's live parts with their
+/// boosted flag, `tower_dps` our energized towers' damage on them, and `available` the compound stock.
+///
+/// Our damage is focused on one attacker while the whole group heals it, so the test is the summed hostile
+/// heal against one defender's damage plus the towers, cut to what lands through boosted TOUGH.
+pub fn defender_boost_escalation(
+    hostiles: &[Vec<(Part, bool)>],
+    tower_dps: f32,
+    available: &std::collections::HashMap<ResourceType, u32>,
+) -> BoostEscalation {
+    let parts = hostiles.iter().flatten();
+    let heal: u32 = parts
+        .clone()
+        .filter(|(p, _)| *p == Part::Heal)
+        .map(|(_, boosted)| if *boosted { HEAL_POWER * T3_POWER_MULTIPLIER } else { HEAL_POWER })
+        .sum();
+    let landed = if parts.clone().any(|(p, boosted)| *p == Part::Tough && *boosted) {
+        T3_TOUGH_DAMAGE_TAKEN
+    } else {
+        1.0
+    };
+    let out_damages = |defender_dps: u32| (defender_dps as f32 + tower_dps) * landed > heal as f32;
+
+    if out_damages(UNBOOSTED_DEFENDER_ATTACK * ATTACK_POWER) {
+        return BoostEscalation::Unboosted;
+    }
+
+    let boosted_dps = BOOSTED_DEFENDER_ATTACK * ATTACK_POWER * T3_POWER_MULTIPLIER;
+    let Some(defenders) = (1..=MAX_BOOSTED_DEFENDERS).find(|n| out_damages(n * boosted_dps)) else {
+        return BoostEscalation::Outmatched;
+    };
+
+    let boosts: Vec<(ResourceType, u32)> = DEFENDER_BOOSTS
+        .iter()
+        .map(|(part, compound)| {
+            let parts = match part {
+                Part::Attack => BOOSTED_DEFENDER_ATTACK,
+                Part::Tough => BOOSTED_DEFENDER_TOUGH,
+                _ => BOOSTED_DEFENDER_HEAL,
+            };
+            (*compound, parts)
+        })
+        .collect();
+    let missing: Vec<(ResourceType, u32)> = boosts
+        .iter()
+        .filter_map(|(compound, parts)| {
+            let needed = defenders * parts * LAB_BOOST_MINERAL;
+            let stocked = available.get(compound).copied().unwrap_or(0);
+            needed
+                .checked_sub(stocked)
+                .filter(|short| *short > 0)
+                .map(|short| (*compound, short))
+        })
+        .collect();
+
+    if missing.is_empty() {
+        BoostEscalation::Boosted { defenders, boosts }
+    } else {
+        BoostEscalation::ShortOfBoosts { missing }
+    }
+}
+
 #[cfg(test)]
 mod readiness_tests {
     use super::*;
@@ -158,3 +446,110 @@ mod readiness_tests {
         );
     }
 }
+
+#[cfg(test)]
+mod escalation_tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn creep(parts: &[(Part, u32)], boosted: bool) -> Vec<(Part, bool)> {
+        parts
+            .iter()
+            .flat_map(|&(part, n)| std::iter::repeat_n((part, boosted), n as usize))
+            .collect()
+    }
+
+    fn stock(amount: u32) -> HashMap<ResourceType, u32> {
+        DEFENDER_BOOSTS.iter().map(|(_, compound)| (*compound, amount)).collect()
+    }
+
+    /// A lone unboosted healer pair is beaten by the strongest unboosted defender.
+    #[test]
+    fn light_attackers_need_no_boosts() {
+        let raider = creep(&[(Part::Attack, 10), (Part::Heal, 5), (Part::Move, 15)], false);
+        assert_eq!(
+            defender_boost_escalation(&[raider.clone(), raider], 0.0, &HashMap::new()),
+            BoostEscalation::Unboosted
+        );
+    }
+
+    /// A boosted heal quad out-heals every unboosted body, but one boosted defender breaks it.
+    #[test]
+    fn boosted_healers_escalate_to_boosted_defenders() {
+        let healer = creep(&[(Part::Heal, 10), (Part::Move, 10)], true);
+        let quad = vec![healer.clone(), healer.clone(), healer.clone(), healer];
+
+        match defender_boost_escalation(&quad, 300.0, &stock(10_000)) {
+            BoostEscalation::Boosted { defenders, boosts } => {
+                assert_eq!(defenders, 1);
+                assert!(boosts.contains(&(ResourceType::CatalyzedUtriumAcid, BOOSTED_DEFENDER_ATTACK)));
+            }
+            other => panic!("expected a boosted defense, got {:?}", other),
+        }
+
+        match defender_boost_escalation(&quad, 300.0, &stock(100)) {
+            BoostEscalation::ShortOfBoosts { missing } => {
+                let attack = missing.iter().find(|(c, _)| *c == ResourceType::CatalyzedUtriumAcid).unwrap();
+                assert_eq!(attack.1, BOOSTED_DEFENDER_ATTACK * LAB_BOOST_MINERAL - 100);
+            }
+            other => panic!("expected a boost shortfall, got {:?}", other),
+        }
+    }
+
+    /// Boosted TOUGH cuts what lands, so even two boosted defenders can't break a heavy boosted group.
+    #[test]
+    fn armoured_heal_wall_is_outmatched() {
+        let tank = creep(&[(Part::Tough, 10), (Part::Heal, 30), (Part::Move, 10)], true);
+        assert_eq!(
+            defender_boost_escalation(&[tank.clone(), tank], 600.0, &stock(10_000)),
+            BoostEscalation::Outmatched
+        );
+    }
+}
+
+#[cfg(test)]
+mod entry_tests {
+    use super::*;
+
+    fn room() -> RoomName {
+        "W1N1".parse().unwrap()
+    }
+
+    fn pos(x: u8, y: u8) -> Position {
+        Position::new(RoomCoordinate::new(x).unwrap(), RoomCoordinate::new(y).unwrap(), room())
+    }
+
+    /// Towers clustered near the north-west corner: the entry is the whole east edge, out of their
+    /// falloff, not the north-edge midpoint a single scalar assumed.
+    #[test]
+    fn entry_avoids_clustered_towers() {
+        let towers = [pos(10, 10), pos(11, 10), pos(10, 11)];
+        let segment = safest_entry_segment(room(), &towers, |_| true).unwrap();
+
+        assert_eq!((segment.edge, segment.start, segment.end), (RoomEdge::Right, 1, ROOM_SIZE - 2));
+        assert_eq!(segment.dps, 3.0 * tower_attack_damage_at_range(ROOM_SIZE as u32) as f32);
+        assert!(segment.dps < total_tower_damage(&towers, pos(25, 0)));
+    }
+
+    /// A two-tile gap is too narrow to enter by, however far from the towers it is.
+    #[test]
+    fn entry_needs_enough_adjacent_exits() {
+        let towers = [pos(25, 25)];
+        let is_exit = |tile: Position| tile.y().u8() == 0 && matches!(tile.x().u8(), 2..=3 | 20..=24);
+        let segment = safest_entry_segment(room(), &towers, is_exit).unwrap();
+
+        assert_eq!((segment.edge, segment.start, segment.end), (RoomEdge::Top, 20, 24));
+        assert_eq!(segment.center(room()), pos(22, 0));
+        assert_eq!(segment.range_from(room(), pos(30, 10)), 10);
+        assert!(safest_entry_segment(room(), &towers, |tile| tile.x().u8() == 2 && tile.y().u8() == 0).is_none());
+    }
+
+    #[test]
+    fn layout_hash_ignores_tower_order() {
+        assert_eq!(
+            tower_layout_hash(&[pos(5, 5), pos(40, 40)]),
+            tower_layout_hash(&[pos(40, 40), pos(5, 5)])
+        );
+        assert_ne!(tower_layout_hash(&[pos(5, 5)]), tower_layout_hash(&[pos(5, 6)]));
+    }
+}
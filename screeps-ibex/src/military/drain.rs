@@ -0,0 +1,147 @@
+//! Tower-drain micro at the room edge.
+//!
+//! A drain squad does not try to kill anything: each member holds a post one
+//! tile inside the target room beside an exit, soaking tower fire so the
+//! towers burn their energy. Before the next volley could take a member below
+//! the safety margin it steps back across the exit, heals in the neighbouring
+//! room, and re-enters once topped up. A drain objective is judged by how long
+//! the squad has held its posts ([`DRAIN_DURATION_TICKS`]), not by kills.
+
+use super::damage::total_tower_damage;
+use screeps::*;
+
+/// Step out when the HP projected after the next tower volley would fall below this fraction of max.
+pub const DRAIN_SAFETY_MARGIN: f32 = 0.5;
+/// Step back in once healed to at least this fraction of max (above the margin, so members don't
+/// bounce across the exit every tick).
+pub const DRAIN_REENTER_FRACTION: f32 = 0.9;
+/// Ticks a drain squad holds its posts before the objective counts as done.
+pub const DRAIN_DURATION_TICKS: u32 = 1_500;
+
+/// What a drain member should do this tick.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DrainStep {
+    /// Stay on whichever side of the exit the member is on.
+    Hold,
+    /// Cross back out of the target room to heal.
+    StepOut,
+    /// Healed — walk back onto the inside post.
+    StepIn,
+}
+
+/// One member's drain post: `inside` is one tile into the target room next to an exit, `outside`
+/// is the tile in the neighbouring room that exit leads to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DrainPost {
+    pub inside: Position,
+    pub outside: Position,
+}
+
+/// Decide whether a member holds, steps out, or steps back in. Inside the target room the member
+/// steps out when `hits + heal_per_tick - tower_dps` drops below [`DRAIN_SAFETY_MARGIN`] of max;
+/// outside it steps in once at [`DRAIN_REENTER_FRACTION`] of max.
+pub fn drain_step(in_target_room: bool, hits: u32, hits_max: u32, tower_dps: u32, heal_per_tick: u32) -> DrainStep {
+    let hits_max = hits_max.max(1) as f32;
+
+    if in_target_room {
+        let projected = hits as f32 + heal_per_tick as f32 - tower_dps as f32;
+        if projected < hits_max * DRAIN_SAFETY_MARGIN {
+            DrainStep::StepOut
+        } else {
+            DrainStep::Hold
+        }
+    } else if hits as f32 >= hits_max * DRAIN_REENTER_FRACTION {
+        DrainStep::StepIn
+    } else {
+        DrainStep::Hold
+    }
+}
+
+/// Up to `count` drain posts on the side of `target_room` facing `toward` (the squad's position),
+/// best first: least damage from `towers` on the inside tile, then nearest to `toward`. A post is
+/// skipped when its inside tile, the exit tile, or the outside tile is `blocked`.
+pub fn drain_posts<F>(target_room: RoomName, toward: Position, towers: &[Position], count: usize, blocked: F) -> Vec<DrainPost>
+where
+    F: Fn(Position) -> bool,
+{
+    let (ox, oy) = Position::new(RoomCoordinate::MIN, RoomCoordinate::MIN, target_room).world_coords();
+    let (tx, ty) = toward.world_coords();
+    let (dx, dy) = (tx - (ox + ROOM_SIZE as i32 / 2), ty - (oy + ROOM_SIZE as i32 / 2));
+
+    // Unit step from an inside tile across its exit.
+    let out = if dx.abs() >= dy.abs() { (dx.signum(), 0) } else { (0, dy.signum()) };
+    if out == (0, 0) {
+        return Vec::new();
+    }
+
+    let far = ROOM_SIZE - 2;
+    let mut candidates: Vec<(DrainPost, u32, u32)> = (1..ROOM_SIZE - 1)
+        .filter_map(|i| {
+            let (x, y) = match out {
+                (-1, _) => (1, i),
+                (1, _) => (far, i),
+                (_, -1) => (i, 1),
+                _ => (i, far),
+            };
+            let inside = Position::new(RoomCoordinate::new(x).ok()?, RoomCoordinate::new(y).ok()?, target_room);
+            let (wx, wy) = inside.world_coords();
+            let exit = Position::from_world_coords(wx + out.0, wy + out.1);
+            let outside = Position::from_world_coords(wx + 2 * out.0, wy + 2 * out.1);
+
+            if blocked(inside) || blocked(exit) || blocked(outside) {
+                return None;
+            }
+
+            let damage = total_tower_damage(towers, inside).round() as u32;
+            Some((DrainPost { inside, outside }, damage, inside.get_range_to(toward)))
+        })
+        .collect();
+
+    candidates.sort_by_key(|(_, damage, range)| (*damage, *range));
+
+    candidates.into_iter().take(count).map(|(post, _, _)| post).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(room: &str, x: u8, y: u8) -> Position {
+        Position::new(
+            RoomCoordinate::new(x).unwrap(),
+            RoomCoordinate::new(y).unwrap(),
+            room.parse().unwrap(),
+        )
+    }
+
+    /// Full HP holds under fire; a volley that would cross the margin steps out; outside, the member
+    /// waits for the re-enter threshold before stepping back in.
+    #[test]
+    fn drain_step_steps_out_before_margin_and_back_when_healed() {
+        assert_eq!(drain_step(true, 1000, 1000, 300, 0), DrainStep::Hold);
+        assert_eq!(drain_step(true, 700, 1000, 300, 0), DrainStep::StepOut);
+        assert_eq!(drain_step(true, 700, 1000, 300, 120), DrainStep::Hold);
+
+        assert_eq!(drain_step(false, 600, 1000, 0, 120), DrainStep::Hold);
+        assert_eq!(drain_step(false, 900, 1000, 0, 120), DrainStep::StepIn);
+    }
+
+    /// Posts sit on the side facing the squad, out of the worst tower fire, and lead into the
+    /// neighbouring room; a fully walled exit yields none.
+    #[test]
+    fn drain_posts_face_the_squad_and_avoid_towers() {
+        let toward = pos("W2N1", 40, 45);
+        let towers = [pos("W1N1", 5, 25)];
+
+        let posts = drain_posts("W1N1".parse().unwrap(), toward, &towers, 2, |_| false);
+        assert_eq!(posts.len(), 2);
+        assert_eq!(posts[0].inside, pos("W1N1", 1, 45));
+        assert_eq!(posts[0].outside, pos("W2N1", 49, 45));
+        assert!(posts
+            .iter()
+            .all(|p| p.inside.x().u8() == 1 && p.inside.get_range_to(towers[0]) >= 20));
+
+        let walled = drain_posts("W1N1".parse().unwrap(), toward, &towers, 2, |p| p.x().u8() == 0);
+        assert!(walled.is_empty());
+    }
+}
@@ -1,9 +1,19 @@
 use screeps::*;
 use specs::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
+use crate::ledger::ResourceLedger;
+use crate::operations::budget::OperationBudget;
 use crate::room::data::*;
 
+/// Ticks spawn utilization is averaged over (one creep lifetime).
+pub const SPAWN_UTILIZATION_WINDOW: u32 = CREEP_LIFE_TIME;
+/// Granularity of the spawn utilization window.
+const SPAWN_UTILIZATION_BUCKET_TICKS: u32 = 100;
+/// Spawn utilization at which a room's spawns count as saturated: new
+/// military or expansion work would only queue behind its own creeps.
+pub const SATURATED_SPAWN_UTILIZATION: f32 = 0.85;
+
 // ---------------------------------------------------------------------------
 // SpawnQueueSnapshot
 // ---------------------------------------------------------------------------
@@ -38,6 +48,15 @@ pub struct RoomEconomyData {
     /// Number of spawns currently idle (not actively spawning a creep).
     /// Derived from game state: spawn.spawning().is_none(). Always current.
     pub free_spawns: u32,
+    /// Rolling harvest income per tick from the ledger (`ledger::LEDGER_WINDOW`).
+    /// Harvests in remotes land on the remote, so this is the room's own
+    /// sources.
+    pub income_rate: f32,
+    /// Share of spawn-ticks any spawn was busy over the last
+    /// [`SPAWN_UTILIZATION_WINDOW`] ticks, 0–1.
+    pub spawn_utilization: f32,
+    /// Energy the operation budget has committed to launches from this room.
+    pub committed_energy: u32,
     /// Pending spawn requests from previous tick (one tick stale).
     /// Read from SpawnQueueSnapshot. Good enough for strategic decisions.
     pub prev_tick_queue_depth: u32,
@@ -50,6 +69,95 @@ pub struct RoomEconomyData {
     pub available_boosts: HashMap<ResourceType, u32>,
 }
 
+impl RoomEconomyData {
+    /// Stored energy held back from discretionary spending: 20% of stored,
+    /// clamped to 5k–30k.
+    pub fn reserve(&self) -> u32 {
+        (self.stored_energy / 5).clamp(5_000, 30_000)
+    }
+
+    /// Stored energy left after the reserve and the budget's commitments.
+    pub fn spendable_energy(&self) -> u32 {
+        self.stored_energy
+            .saturating_sub(self.reserve())
+            .saturating_sub(self.committed_energy)
+    }
+
+    /// Whether the spawns have been busy enough that more work would only
+    /// queue behind the room's own creeps.
+    pub fn spawns_saturated(&self) -> bool {
+        self.spawn_count > 0 && self.spawn_utilization >= SATURATED_SPAWN_UTILIZATION
+    }
+}
+
+// ---------------------------------------------------------------------------
+// SpawnUtilization
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Debug)]
+struct SpawnUtilizationBucket {
+    start: u32,
+    busy: u32,
+    total: u32,
+}
+
+/// Busy and total spawn-ticks per room over the last
+/// [`SPAWN_UTILIZATION_WINDOW`] ticks, kept in fixed buckets like the energy
+/// ledger. Ephemeral: a VM reload restarts the window.
+#[derive(Default)]
+pub struct SpawnUtilization {
+    rooms: HashMap<Entity, VecDeque<SpawnUtilizationBucket>>,
+}
+
+impl SpawnUtilization {
+    /// Record one tick of a room with `total` spawns, `busy` of them spawning.
+    pub fn record(&mut self, room: Entity, tick: u32, busy: u32, total: u32) {
+        let start = tick - tick % SPAWN_UTILIZATION_BUCKET_TICKS;
+        let buckets = self.rooms.entry(room).or_default();
+
+        if buckets.back().map(|b| b.start != start).unwrap_or(true) {
+            buckets.push_back(SpawnUtilizationBucket { start, busy: 0, total: 0 });
+        }
+
+        if let Some(bucket) = buckets.back_mut() {
+            bucket.busy += busy;
+            bucket.total += total;
+        }
+    }
+
+    /// Drop buckets that fell out of the window ending at `tick`, and rooms
+    /// with none left.
+    pub fn prune(&mut self, tick: u32) {
+        for buckets in self.rooms.values_mut() {
+            while buckets
+                .front()
+                .is_some_and(|b| tick.saturating_sub(b.start) >= SPAWN_UTILIZATION_WINDOW)
+            {
+                buckets.pop_front();
+            }
+        }
+
+        self.rooms.retain(|_, buckets| !buckets.is_empty());
+    }
+
+    /// Busy spawn-ticks over total spawn-ticks observed in the window; 0 for
+    /// a room with no spawns recorded.
+    pub fn utilization(&self, room: Entity) -> f32 {
+        let Some(buckets) = self.rooms.get(&room) else {
+            return 0.0;
+        };
+
+        let busy: u32 = buckets.iter().map(|b| b.busy).sum();
+        let total: u32 = buckets.iter().map(|b| b.total).sum();
+
+        if total == 0 {
+            0.0
+        } else {
+            busy as f32 / total as f32
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // EconomySnapshot
 // ---------------------------------------------------------------------------
@@ -80,7 +188,7 @@ impl EconomySnapshot {
     /// 5k–30k) so low-RCL rooms with little storage don't inflate
     /// the threshold, and mature rooms keep a reasonable buffer.
     pub fn can_afford_military(&self, amount: u32) -> bool {
-        let reserve: u32 = self.rooms.values().map(|r| (r.stored_energy / 5).clamp(5_000, 30_000)).sum();
+        let reserve: u32 = self.rooms.values().map(|r| r.reserve()).sum();
         self.total_stored_energy > reserve + amount
     }
 
@@ -92,10 +200,7 @@ impl EconomySnapshot {
         let surplus: u32 = rooms
             .iter()
             .filter_map(|e| self.rooms.get(e))
-            .map(|r| {
-                let reserve = (r.stored_energy / 5).clamp(5_000, 30_000);
-                r.stored_energy.saturating_sub(reserve)
-            })
+            .map(|r| r.stored_energy.saturating_sub(r.reserve()))
             .sum();
         surplus >= amount
     }
@@ -106,13 +211,16 @@ impl EconomySnapshot {
         rooms
             .iter()
             .filter_map(|e| self.rooms.get(e))
-            .map(|r| {
-                let reserve = (r.stored_energy / 5).clamp(5_000, 30_000);
-                r.stored_energy.saturating_sub(reserve)
-            })
+            .map(|r| r.stored_energy.saturating_sub(r.reserve()))
             .sum()
     }
 
+    /// Total stored energy (storage + terminal + containers) across specific
+    /// rooms, with no reserve held back.
+    pub fn rooms_stored_energy(&self, rooms: &[Entity]) -> u32 {
+        rooms.iter().filter_map(|e| self.rooms.get(e)).map(|r| r.stored_energy).sum()
+    }
+
     /// Get mutable room data for within-tick coordination
     /// (incrementing military_spawns_claimed).
     pub fn room_mut(&mut self, entity: &Entity) -> Option<&mut RoomEconomyData> {
@@ -139,6 +247,13 @@ impl EconomySnapshot {
             .sum()
     }
 
+    /// Whether a room can stage military or expansion launches: visible,
+    /// owned, and with spawns that aren't saturated. A room rich in stored
+    /// energy but with busy spawns would only queue the launch.
+    pub fn can_stage(&self, entity: &Entity) -> bool {
+        self.rooms.get(entity).is_some_and(|r| !r.spawns_saturated())
+    }
+
     /// Maximum spawn energy capacity across all rooms.
     pub fn max_spawn_capacity(&self) -> u32 {
         self.rooms.values().map(|r| r.spawn_energy_capacity).max().unwrap_or(0)
@@ -160,9 +275,18 @@ impl<'a> System<'a> for EconomyAssessmentSystem {
         ReadStorage<'a, RoomData>,
         Write<'a, EconomySnapshot>,
         Read<'a, SpawnQueueSnapshot>,
+        Write<'a, SpawnUtilization>,
+        Read<'a, ResourceLedger>,
+        Read<'a, OperationBudget>,
     );
 
-    fn run(&mut self, (entities, room_data, mut economy, spawn_snapshot): Self::SystemData) {
+    fn run(
+        &mut self,
+        (entities, room_data, mut economy, spawn_snapshot, mut spawn_utilization, ledger, operation_budget): Self::SystemData,
+    ) {
+        let now = game::time();
+        spawn_utilization.prune(now);
+
         // Reset the snapshot.
         economy.rooms.clear();
         economy.total_stored_energy = 0;
@@ -187,6 +311,7 @@ impl<'a> System<'a> for EconomyAssessmentSystem {
             let mut stored_energy: u32 = 0;
             let mut spawn_count: u32 = 0;
             let mut free_spawns: u32 = 0;
+            let mut available_boosts: HashMap<ResourceType, u32> = HashMap::new();
 
             if let Some(structures) = room.get_structures() {
                 if let Some(storage) = structures.storages().first() {
@@ -198,6 +323,18 @@ impl<'a> System<'a> for EconomyAssessmentSystem {
                 for container in structures.containers() {
                     stored_energy += container.store().get_used_capacity(Some(ResourceType::Energy));
                 }
+                for (_, compound) in crate::military::damage::DEFENDER_BOOSTS {
+                    let stores = structures
+                        .storages()
+                        .iter()
+                        .map(|s| s.store())
+                        .chain(structures.terminals().iter().map(|t| t.store()))
+                        .chain(structures.labs().iter().map(|l| l.store()));
+                    let amount: u32 = stores.map(|store| store.get_used_capacity(Some(compound))).sum();
+                    if amount > 0 {
+                        available_boosts.insert(compound, amount);
+                    }
+                }
                 for spawn in structures.spawns() {
                     spawn_count += 1;
                     if spawn.spawning().is_none() {
@@ -214,6 +351,10 @@ impl<'a> System<'a> for EconomyAssessmentSystem {
 
             let prev_tick_queue_depth = spawn_snapshot.queue_depth_per_room.get(&entity).copied().unwrap_or(0);
 
+            spawn_utilization.record(entity, now, spawn_count - free_spawns, spawn_count);
+
+            let income_rate = ledger.averages(room.name).map(|averages| averages.income as f32).unwrap_or(0.0);
+
             let room_econ = RoomEconomyData {
                 stored_energy,
                 energy_income,
@@ -221,9 +362,12 @@ impl<'a> System<'a> for EconomyAssessmentSystem {
                 spawn_energy_capacity: game_room.energy_capacity_available(),
                 spawn_count,
                 free_spawns,
+                income_rate,
+                spawn_utilization: spawn_utilization.utilization(entity),
+                committed_energy: operation_budget.committed(entity).0.energy,
                 prev_tick_queue_depth,
                 military_spawns_claimed: 0,
-                available_boosts: HashMap::new(),
+                available_boosts,
             };
 
             // Aggregate totals.
@@ -237,3 +381,62 @@ impl<'a> System<'a> for EconomyAssessmentSystem {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utilization_averages_busy_over_observed_spawn_ticks() {
+        let mut world = World::new();
+        let room = world.create_entity().build();
+        let mut utilization = SpawnUtilization::default();
+
+        assert_eq!(utilization.utilization(room), 0.0);
+
+        utilization.record(room, 1_000, 2, 2);
+        utilization.record(room, 1_001, 1, 2);
+        utilization.record(room, 1_150, 0, 2);
+
+        assert_eq!(utilization.utilization(room), 0.5);
+
+        utilization.prune(1_000 + SPAWN_UTILIZATION_WINDOW);
+        assert_eq!(utilization.utilization(room), 0.0);
+
+        utilization.prune(1_100 + SPAWN_UTILIZATION_WINDOW);
+        assert_eq!(utilization.utilization(room), 0.0);
+        assert!(utilization.rooms.is_empty());
+    }
+
+    #[test]
+    fn saturated_spawns_block_staging_whatever_is_stored() {
+        let mut world = World::new();
+        let rich = world.create_entity().build();
+        let idle = world.create_entity().build();
+
+        let mut economy = EconomySnapshot::default();
+        economy.rooms.insert(
+            rich,
+            RoomEconomyData {
+                stored_energy: 500_000,
+                spawn_count: 3,
+                spawn_utilization: 0.95,
+                ..Default::default()
+            },
+        );
+        economy.rooms.insert(
+            idle,
+            RoomEconomyData {
+                stored_energy: 50_000,
+                spawn_count: 1,
+                spawn_utilization: 0.3,
+                committed_energy: 20_000,
+                ..Default::default()
+            },
+        );
+
+        assert!(!economy.can_stage(&rich));
+        assert!(economy.can_stage(&idle));
+        assert_eq!(economy.room(&idle).unwrap().spendable_energy(), 20_000);
+    }
+}
@@ -0,0 +1,189 @@
+//! Escorts for haulers crossing dangerous rooms.
+//!
+//! A long-range haul mission's route home runs through unowned corridor rooms
+//! where invaders and harassers pick off lone haulers. When a room on that
+//! route has had a recent hostile sighting, the haul mission files a
+//! [`Caravan`] on the [`EscortRequest`] resource. The war operation turns each
+//! request into a `Caravan` objective fielding one ranged escort, and the squad
+//! manager has it follow whichever of the mission's haulers is closest to the
+//! danger. Once every sighting on the route ages out the mission withdraws the
+//! request, the war operation withdraws the objective, and the retired escort
+//! walks home and recycles.
+//!
+//! Requests are ephemeral (a runtime resource, never serialized): the haul
+//! missions re-file them on their first tick after a VM reload.
+
+use screeps::*;
+use screeps_combat_decision::bodies::CombatBodySpec;
+use screeps_combat_decision::composition::{BodyType, FormationShape, SquadComposition, SquadRole, SquadSlot};
+use specs::Entity;
+use std::collections::BTreeMap;
+
+/// A hostile sighting older than this no longer warrants an escort.
+pub const ESCORT_THREAT_MAX_AGE: u32 = 1000;
+/// How close the escort keeps to the hauler it follows.
+pub const ESCORT_FOLLOW_RANGE: u32 = 2;
+/// How far from its hauler the escort will chase a hostile before falling back to it.
+pub const ESCORT_LEASH_RANGE: u32 = 6;
+/// Ticks a request stands without its haul mission re-filing it (the mission was removed).
+const ESCORT_REQUEST_TTL: u32 = 20;
+/// Ranged parts on the escort — enough to see off an invader scout or a lone harasser.
+const ESCORT_RANGED_PARTS: u32 = 4;
+/// Heal parts on the escort, to patch up itself and the hauler between fights.
+pub const ESCORT_HEAL_PARTS: u32 = 2;
+
+/// The solo ranged escort a caravan objective requests.
+pub fn escort_composition() -> SquadComposition {
+    SquadComposition {
+        label: "Escort".into(),
+        slots: vec![SquadSlot {
+            role: SquadRole::RangedDPS,
+            body_type: BodyType::Sized(CombatBodySpec {
+                ranged_attack: ESCORT_RANGED_PARTS,
+                heal: ESCORT_HEAL_PARTS,
+                ..Default::default()
+            }),
+        }],
+        formation_shape: FormationShape::None,
+        formation_mode: Default::default(),
+        retreat_threshold: 0.5,
+    }
+}
+
+/// Whether a room's last observation counts as a recent threat to haulers passing through. Source
+/// keepers guard their lairs rather than roam, so a keeper room alone doesn't call for an escort.
+pub fn is_recent_threat(hostile_creeps: bool, source_keeper: bool, age: u32) -> bool {
+    hostile_creeps && !source_keeper && age <= ESCORT_THREAT_MAX_AGE
+}
+
+/// A haul mission's haulers and the rooms on their route with a recent hostile sighting.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Caravan {
+    /// The room the mission hauls from; the caravan objective is keyed on it.
+    pub pickup_room: RoomName,
+    pub threatened_rooms: Vec<RoomName>,
+    pub haulers: Vec<Entity>,
+}
+
+/// Escorts wanted by haul missions, keyed by the mission entity. Runtime resource; see the module docs.
+#[derive(Default)]
+pub struct EscortRequest {
+    caravans: BTreeMap<Entity, (Caravan, u32)>,
+}
+
+impl EscortRequest {
+    /// File or refresh the mission's request.
+    pub fn request(&mut self, mission: Entity, caravan: Caravan, now: u32) {
+        self.caravans.insert(mission, (caravan, now));
+    }
+
+    pub fn withdraw(&mut self, mission: Entity) {
+        self.caravans.remove(&mission);
+    }
+
+    /// Drop requests their mission has stopped re-filing.
+    pub fn expire(&mut self, now: u32) {
+        self.caravans
+            .retain(|_, (_, requested_at)| now.saturating_sub(*requested_at) <= ESCORT_REQUEST_TTL);
+    }
+
+    /// The caravans wanting an escort, in mission order.
+    pub fn caravans(&self) -> impl Iterator<Item = &Caravan> {
+        self.caravans.values().map(|(caravan, _)| caravan)
+    }
+}
+
+fn room_distance(a: RoomName, b: RoomName) -> u32 {
+    let (dx, dy) = a - b;
+
+    dx.unsigned_abs().max(dy.unsigned_abs())
+}
+
+/// The hauler to follow: the one nearest (in rooms) to a threatened room, staying with `current` on a tie.
+pub fn pick_escorted(haulers: &[(Entity, Position)], threatened_rooms: &[RoomName], current: Option<Entity>) -> Option<Entity> {
+    haulers
+        .iter()
+        .min_by_key(|(entity, pos)| {
+            let distance = threatened_rooms
+                .iter()
+                .map(|room| room_distance(pos.room_name(), *room))
+                .min()
+                .unwrap_or(u32::MAX);
+
+            (distance, Some(*entity) != current)
+        })
+        .map(|(entity, _)| *entity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specs::prelude::*;
+
+    fn pos(room: &str) -> Position {
+        Position::new(
+            RoomCoordinate::new(25).expect("valid coordinate"),
+            RoomCoordinate::new(25).expect("valid coordinate"),
+            room.parse().expect("valid room name"),
+        )
+    }
+
+    fn caravan(pickup_room: &str) -> Caravan {
+        Caravan {
+            pickup_room: pickup_room.parse().expect("valid room name"),
+            threatened_rooms: Vec::new(),
+            haulers: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn only_fresh_non_keeper_sightings_are_threats() {
+        assert!(is_recent_threat(true, false, 0));
+        assert!(is_recent_threat(true, false, ESCORT_THREAT_MAX_AGE));
+        assert!(!is_recent_threat(true, false, ESCORT_THREAT_MAX_AGE + 1));
+        assert!(!is_recent_threat(false, false, 0));
+        assert!(!is_recent_threat(true, true, 0));
+    }
+
+    /// The escort sticks with its hauler until another is strictly closer to the danger.
+    #[test]
+    fn follows_the_hauler_nearest_the_threat() {
+        let mut world = World::new();
+        let near = world.create_entity().build();
+        let far = world.create_entity().build();
+        let also_near = world.create_entity().build();
+        let threatened = ["W3N1".parse().expect("valid room name")];
+
+        let haulers = [(far, pos("W1N1")), (near, pos("W2N1")), (also_near, pos("W4N1"))];
+
+        assert_eq!(pick_escorted(&haulers, &threatened, None), Some(near));
+        assert_eq!(pick_escorted(&haulers, &threatened, Some(also_near)), Some(also_near));
+        assert_eq!(pick_escorted(&haulers, &threatened, Some(far)), Some(near));
+        assert_eq!(pick_escorted(&[], &threatened, None), None);
+    }
+
+    #[test]
+    fn requests_lapse_unless_refiled() {
+        let mut world = World::new();
+        let kept = world.create_entity().build();
+        let dropped = world.create_entity().build();
+        let withdrawn = world.create_entity().build();
+
+        let mut requests = EscortRequest::default();
+        requests.request(dropped, caravan("W1N1"), 100);
+        requests.request(withdrawn, caravan("W2N1"), 100);
+        requests.request(kept, caravan("W3N1"), 100 + ESCORT_REQUEST_TTL);
+        requests.withdraw(withdrawn);
+        requests.expire(101 + ESCORT_REQUEST_TTL);
+
+        assert_eq!(requests.caravans().cloned().collect::<Vec<_>>(), vec![caravan("W3N1")]);
+    }
+
+    #[test]
+    fn escort_is_one_ranged_creep() {
+        let composition = escort_composition();
+
+        assert_eq!(composition.slots.len(), 1);
+        assert_eq!(composition.slots[0].role, SquadRole::RangedDPS);
+    }
+}
@@ -1,4 +1,6 @@
+use super::damage::{safest_entry_segment, EntrySegment, RoomEdge};
 use super::squad::*;
+use crate::pathing::pathfinderservice::PathfinderService;
 use screeps_combat_decision::composition::*;
 use screeps::*;
 use screeps_rover::*;
@@ -99,7 +101,25 @@ pub fn standoff_one_tile(structure: Position, toward: Position) -> Position {
 /// (`squad_manager`, `advance_squad_virtual_position`) are unchanged.
 pub use screeps_combat_decision::rally::{ready_to_depart_gate, should_hold_at_boundary, target_is_uncontested};
 
-pub fn advance_squad_virtual_position(squad: &mut SquadContext, destination: Position) {
+/// What the anchor needs to cross rooms: the shared room-route cache, each room's safest entry as last
+/// seen, and the pathing counter.
+pub struct SquadRouting<'a> {
+    pub pathfinder: &'a mut PathfinderService,
+    pub entry_segment: &'a dyn Fn(RoomName) -> Option<EntrySegment>,
+    pub pathing: &'a mut SquadPathing,
+    pub now: u32,
+}
+
+/// Squad anchor pathing since the VM started: anchor advances, the CPU they took (searches included) and
+/// the route waypoints picked. Runtime resource, for stats.
+#[derive(Default)]
+pub struct SquadPathing {
+    pub advances: u32,
+    pub cpu: f64,
+    pub waypoints: u32,
+}
+
+pub fn advance_squad_virtual_position(squad: &mut SquadContext, destination: Position, routing: &mut SquadRouting) {
     // P-OBJ #23 invader no-engage ROOT CAUSE: count ONLY members with a resolved position. A still-
     // spawning member carries `position: None` (no body in the world yet) for the whole ~body*3-tick
     // spawn; including it inflated `living_count` AND failed every cohesion quorum, so `boundary_hold`
@@ -117,7 +137,7 @@ pub fn advance_squad_virtual_position(squad: &mut SquadContext, destination: Pos
         None => {
             // No layout -- just advance directly.
             init_squad_path_if_needed(squad, &living_members, destination);
-            advance_virtual_pos(squad, destination);
+            advance_virtual_pos(squad, destination, routing);
             return;
         }
     };
@@ -125,9 +145,10 @@ pub fn advance_squad_virtual_position(squad: &mut SquadContext, destination: Pos
     // Initialize squad path if needed.
     init_squad_path_if_needed(squad, &living_members, destination);
 
-    // Update destination if changed (the anchor re-paths on a destination change).
+    // Update destination if changed (the anchor re-paths on a destination change). Rooms away, the
+    // anchor heads for the next room's entry rather than the destination itself.
     if let Some(path) = squad.squad_path.as_mut() {
-        path.anchor.destination = destination;
+        path.anchor.destination = route_target(path, destination, routing);
     }
 
     let virtual_pos = squad.squad_path.as_ref().map(|p| p.anchor.virtual_pos).unwrap_or(destination);
@@ -227,8 +248,125 @@ pub fn advance_squad_virtual_position(squad: &mut SquadContext, destination: Pos
     };
 
     if should_advance {
-        advance_virtual_pos(squad, destination);
+        advance_virtual_pos(squad, destination, routing);
+    }
+}
+
+/// Where the anchor paths to this tick. In the destination's room that is the destination; rooms away it
+/// is the waypoint into the next room of the route, so every search stays within the room the anchor is
+/// in and the next waypoint is picked once the anchor crosses. The route is taken from the pathfinder's
+/// route cache once per destination, and again only if the anchor strays off it.
+fn route_target(path: &mut SquadPath, destination: Position, routing: &mut SquadRouting) -> Position {
+    let room = path.anchor.virtual_pos.room_name();
+    let destination_room = destination.room_name();
+
+    if room == destination_room {
+        path.waypoint = None;
+        return destination;
+    }
+
+    let next = match next_route_room(&path.room_route, room, destination_room) {
+        Some(next) => next,
+        None => {
+            path.room_route = routing.pathfinder.route_rooms(room, destination_room, routing.now).to_vec();
+            path.waypoint = None;
+
+            match next_route_room(&path.room_route, room, destination_room) {
+                Some(next) => next,
+                None => return destination,
+            }
+        }
+    };
+
+    let waypoint = match path.waypoint.take().filter(|w| w.room == next) {
+        Some(waypoint) => Some(waypoint),
+        None => pick_waypoint(room, next, Vec::new(), routing),
+    };
+    let target = waypoint.as_ref().map(RouteWaypoint::pos).unwrap_or(destination);
+    path.waypoint = waypoint;
+
+    target
+}
+
+/// The room after `room` on `route` (which leaves out its start and ends at `destination_room`), or `None`
+/// when `room` is off the route.
+fn next_route_room(route: &[RoomName], room: RoomName, destination_room: RoomName) -> Option<RoomName> {
+    if route.last() != Some(&destination_room) {
+        return None;
+    }
+
+    match route.iter().position(|r| *r == room) {
+        Some(index) => route.get(index + 1).copied(),
+        None => route.first().copied().filter(|first| entry_edge(room, *first).is_some()),
+    }
+}
+
+/// The waypoint into `next` from `room`, skipping the `blocked` segments, with the room's terrain walls
+/// ruling out exits. Counted in [`SquadPathing::waypoints`].
+fn pick_waypoint(room: RoomName, next: RoomName, blocked: Vec<EntrySegment>, routing: &mut SquadRouting) -> Option<RouteWaypoint> {
+    let terrain = game::map::get_room_terrain(next);
+    let is_exit = |tile: Position| {
+        terrain
+            .as_ref()
+            .is_none_or(|t| t.get(tile.x().u8(), tile.y().u8()) != Terrain::Wall)
+    };
+
+    let segment = select_entry_segment(room, next, (routing.entry_segment)(next).as_ref(), &blocked, is_exit)?;
+    routing.pathing.waypoints += 1;
+
+    Some(RouteWaypoint {
+        room: next,
+        segment,
+        blocked,
+    })
+}
+
+/// The edge of `next` a squad crosses onto from the adjacent room `from`; `None` when they don't touch.
+fn entry_edge(from: RoomName, next: RoomName) -> Option<RoomEdge> {
+    match next - from {
+        (1, 0) => Some(RoomEdge::Left),
+        (-1, 0) => Some(RoomEdge::Right),
+        (0, 1) => Some(RoomEdge::Top),
+        (0, -1) => Some(RoomEdge::Bottom),
+        _ => None,
+    }
+}
+
+/// The run of exits a squad coming from `from` enters `next` by. `known` is `next`'s least-damaged entry
+/// past its towers (`damage::safest_entry_segment`, as last seen), taken when it lies on the edge `from`
+/// leads to and none of it is blocked. Otherwise the widest open run on that edge: the towers themselves
+/// aren't at hand here. Exits inside a `blocked` segment are skipped. `None` when the rooms don't touch
+/// or the edge has no run of [`super::damage::MIN_ENTRY_SEGMENT`] open exits.
+pub fn select_entry_segment<F>(
+    from: RoomName,
+    next: RoomName,
+    known: Option<&EntrySegment>,
+    blocked: &[EntrySegment],
+    is_exit: F,
+) -> Option<EntrySegment>
+where
+    F: Fn(Position) -> bool,
+{
+    let edge = entry_edge(from, next)?;
+    let far = ROOM_SIZE - 1;
+    let open = |tile: Position| {
+        let on_edge = match edge {
+            RoomEdge::Top => tile.y().u8() == 0,
+            RoomEdge::Right => tile.x().u8() == far,
+            RoomEdge::Bottom => tile.y().u8() == far,
+            RoomEdge::Left => tile.x().u8() == 0,
+        };
+
+        on_edge && is_exit(tile) && !blocked.iter().any(|segment| segment.tiles(next).any(|t| t == tile))
+    };
+
+    if let Some(known) = known.filter(|k| k.edge == edge) {
+        if known.tiles(next).all(open) {
+            return Some(known.clone());
+        }
     }
+
+    safest_entry_segment(next, &[], open)
 }
 
 /// Initialize the squad path if it doesn't exist yet.
@@ -239,6 +377,7 @@ fn init_squad_path_if_needed(squad: &mut SquadContext, living_members: &[(usize,
         squad.squad_path = Some(SquadPath {
             anchor: AnchorPath::new(start_pos, destination),
             room_route: Vec::new(),
+            waypoint: None,
         });
     }
 }
@@ -304,7 +443,11 @@ fn squad_footprint(squad: &SquadContext) -> (u8, u8) {
 /// them (the server PathFinder applies terrain per-tile, which would otherwise dodge the
 /// footprint expansion). Cost-matrix/source/pathfinder are built ad-hoc (they read `game::*`
 /// lazily). Validate behavior on the private server before relying on it live.
-fn advance_virtual_pos(squad: &mut SquadContext, destination: Position) {
+///
+/// Rooms away from `destination` the anchor advances toward its route waypoint instead
+/// ([`route_target`]); a waypoint the anchor can't reach even single-file is swapped for another entry
+/// segment on the same edge.
+fn advance_virtual_pos(squad: &mut SquadContext, destination: Position, routing: &mut SquadRouting) {
     // The cohesive footprint we WANT to route as. For a ≥3-member blob this is the COMPACT box that
     // holds all members (`box_footprint`, ADR 0031 D14 — N=4→2×2, 5-6→3×2, 7-8→3×3) even when the member
     // layout is temporarily collapsed to a line for a corridor; for a duo/solo it is just the current
@@ -346,15 +489,34 @@ fn advance_virtual_pos(squad: &mut SquadContext, destination: Position) {
             Some(matrix)
         };
 
-        let outcome = path.anchor.advance(destination, tight_footprint, &mut pf, &mut room_cb);
-        if outcome == AnchorOutcome::Blocked && tight_footprint != (1, 1) {
+        let target = route_target(path, destination, routing);
+        let room = path.anchor.virtual_pos.room_name();
+        let started = game::cpu::get_used();
+
+        let outcome = path.anchor.advance(target, tight_footprint, &mut pf, &mut room_cb);
+        let (tight_blocked, outcome) = if outcome == AnchorOutcome::Blocked && tight_footprint != (1, 1) {
             // Corridor relax (P2.M3): the tight box can't fit → thread single-file (width-1).
-            let _ = path.anchor.advance(destination, (1, 1), &mut pf, &mut room_cb);
-            true
+            (true, path.anchor.advance(target, (1, 1), &mut pf, &mut room_cb))
         } else {
             // A still-`Blocked` width-1 anchor holds (stuck_ticks rises) for the manager to respond to.
-            false
+            (false, outcome)
+        };
+
+        routing.pathing.advances += 1;
+        routing.pathing.cpu += game::cpu::get_used() - started;
+
+        // Walls or ramparts behind the entry: try another segment of the same edge next tick. Once every
+        // segment has been tried the list starts over.
+        if outcome == AnchorOutcome::Blocked {
+            if let Some(waypoint) = path.waypoint.take() {
+                let mut blocked = waypoint.blocked;
+                blocked.push(waypoint.segment);
+
+                path.waypoint = pick_waypoint(room, waypoint.room, blocked, routing);
+            }
         }
+
+        tight_blocked
     };
 
     // Member-layout corridor switch (P2.M3): collapse a stuck box to single-file so members thread
@@ -412,10 +574,129 @@ pub fn issue_virtual_anchor_flee(
     }
 }
 
+/// A ranged-only member kites once a melee hostile is this close (one step from striking range).
+pub const KITE_TRIGGER_RANGE: u32 = 2;
+/// A kite step never leaves ranged-attack range of the member's focus.
+pub const KITE_FOCUS_RANGE: u32 = 3;
+/// Hostiles within ranged-attack range before `rangedMassAttack` is used over a single ranged attack.
+pub const MASS_ATTACK_MIN_TARGETS: usize = 2;
+
+/// The adjacent tile a ranged-only member at `from` should step to so it stays out of reach of the
+/// `melee` hostiles, or `None` when none is within [`KITE_TRIGGER_RANGE`] or no step improves on
+/// standing still. Tiles are ranked by the distance to the nearest melee hostile, then the summed
+/// distance to all of them. Steps that leave [`KITE_FOCUS_RANGE`] of `focus`, land on a room edge
+/// (an exit), or are `blocked` are never taken. Pure so the choice is unit-testable.
+pub fn kite_step<F>(from: Position, melee: &[Position], focus: Option<Position>, blocked: F) -> Option<Position>
+where
+    F: Fn(Position) -> bool,
+{
+    let melee: Vec<Position> = melee.iter().copied().filter(|m| m.room_name() == from.room_name()).collect();
+
+    if !melee.iter().any(|m| from.get_range_to(*m) <= KITE_TRIGGER_RANGE) {
+        return None;
+    }
+
+    let score = |pos: Position| {
+        melee.iter().fold((u32::MAX, 0u32), |(nearest, total), m| {
+            let range = pos.get_range_to(*m);
+            (nearest.min(range), total + range)
+        })
+    };
+
+    let mut best: Option<(Position, (u32, u32))> = None;
+    let mut current = score(from);
+
+    for dx in -1i32..=1 {
+        for dy in -1i32..=1 {
+            if dx == 0 && dy == 0 {
+                continue;
+            }
+
+            let x = from.x().u8() as i32 + dx;
+            let y = from.y().u8() as i32 + dy;
+            if !(1..(ROOM_SIZE as i32 - 1)).contains(&x) || !(1..(ROOM_SIZE as i32 - 1)).contains(&y) {
+                continue;
+            }
+
+            let pos = Position::new(
+                RoomCoordinate::new(x as u8).expect("1..=48 is a valid room coordinate"),
+                RoomCoordinate::new(y as u8).expect("1..=48 is a valid room coordinate"),
+                from.room_name(),
+            );
+
+            if blocked(pos) || melee.contains(&pos) || focus.is_some_and(|f| pos.get_range_to(f) > KITE_FOCUS_RANGE) {
+                continue;
+            }
+
+            let candidate = score(pos);
+            if candidate > current {
+                current = candidate;
+                best = Some((pos, candidate));
+            }
+        }
+    }
+
+    best.map(|(pos, _)| pos)
+}
+
+/// Whether `rangedMassAttack` should replace a single ranged attack: at least
+/// [`MASS_ATTACK_MIN_TARGETS`] hostiles within range 1-3 of `from`.
+pub fn mass_attack_worthwhile(from: Position, hostiles: &[Position]) -> bool {
+    hostiles
+        .iter()
+        .filter(|h| h.room_name() == from.room_name() && (1..=3).contains(&from.get_range_to(**h)))
+        .count()
+        >= MASS_ATTACK_MIN_TARGETS
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn pos(x: u8, y: u8) -> Position {
+        Position::new(RoomCoordinate::new(x).unwrap(), RoomCoordinate::new(y).unwrap(), "W1N1".parse().unwrap())
+    }
+
+    /// A ranged-only member steps straight away from an adjacent melee hostile, but not out of range
+    /// of its focus and not onto a blocked tile.
+    #[test]
+    fn kite_step_backs_away_within_focus_range() {
+        let from = pos(25, 25);
+        let melee = [pos(26, 25)];
+
+        let free = kite_step(from, &melee, None, |_| false).expect("should kite");
+        assert_eq!(free.get_range_to(melee[0]), 2);
+        assert_eq!(free.x().u8(), 24);
+
+        // Focus at (28,25): every step west leaves range 3, so nothing improves within range.
+        assert_eq!(kite_step(from, &melee, Some(pos(28, 25)), |_| false), None);
+
+        // Focus at (24,28): a step west-and-south keeps it in range.
+        let in_range = kite_step(from, &melee, Some(pos(24, 28)), |_| false).expect("should kite");
+        assert!(in_range.get_range_to(pos(24, 28)) <= KITE_FOCUS_RANGE);
+        assert_eq!(in_range.get_range_to(melee[0]), 2);
+
+        // West column walled off: the only escapes are blocked.
+        assert_eq!(kite_step(from, &melee, None, |p| p.x().u8() == 24), None);
+    }
+
+    #[test]
+    fn kite_step_ignores_distant_melee_and_room_edges() {
+        assert_eq!(kite_step(pos(25, 25), &[pos(28, 25)], None, |_| false), None);
+
+        // Pinned against the west edge: stepping onto x=0 would leave the room.
+        let step = kite_step(pos(1, 25), &[pos(2, 25)], None, |_| false);
+        assert!(step.is_none_or(|p| p.x().u8() >= 1));
+    }
+
+    #[test]
+    fn mass_attack_needs_two_hostiles_in_range() {
+        let from = pos(25, 25);
+        assert!(!mass_attack_worthwhile(from, &[pos(26, 25)]));
+        assert!(!mass_attack_worthwhile(from, &[pos(26, 25), pos(30, 25)]));
+        assert!(mass_attack_worthwhile(from, &[pos(26, 25), pos(22, 22)]));
+    }
+
     /// A stuck quad box collapses to single-file, then snaps back to a box the moment the corridor
     /// opens — the loose→tight transition the movement overhaul is meant to make ASAP.
     #[test]
@@ -542,4 +823,52 @@ mod tests {
         let none: Vec<(usize, Option<Position>)> = vec![(0, None), (1, None)];
         assert_eq!(anchor_start_pos(&none, dest), dest, "no body → destination fallback (unchanged)");
     }
+
+    fn room(name: &str) -> RoomName {
+        name.parse().unwrap()
+    }
+
+    /// The route's next room follows the anchor along it, and a room off the route asks for a new one.
+    #[test]
+    fn next_route_room_follows_the_anchor() {
+        let route = [room("W1N1"), room("W0N1"), room("E0N1")];
+
+        assert_eq!(next_route_room(&route, room("W2N1"), room("E0N1")), Some(room("W1N1")));
+        assert_eq!(next_route_room(&route, room("W0N1"), room("E0N1")), Some(room("E0N1")));
+        assert_eq!(next_route_room(&route, room("W2N3"), room("E0N1")), None, "off the route");
+        assert_eq!(next_route_room(&route, room("W2N1"), room("E1N1")), None, "route to another room");
+    }
+
+    /// A squad enters by the room's known safest segment when it is on the edge the route crosses, and
+    /// otherwise by the widest open run on that edge, skipping segments it found blocked.
+    #[test]
+    fn entry_segment_is_picked_on_the_crossed_edge() {
+        let (from, next) = (room("W2N1"), room("W1N1"));
+        let segment = |edge, start, end| EntrySegment {
+            edge,
+            start,
+            end,
+            dps: 0.0,
+        };
+        let known = EntrySegment {
+            dps: 120.0,
+            ..segment(RoomEdge::Left, 10, 14)
+        };
+
+        assert_eq!(select_entry_segment(from, next, Some(&known), &[], |_| true), Some(known.clone()));
+
+        let elsewhere = segment(RoomEdge::Top, 10, 14);
+        assert_eq!(
+            select_entry_segment(from, next, Some(&elsewhere), &[], |_| true),
+            Some(segment(RoomEdge::Left, 1, 48))
+        );
+
+        assert_eq!(
+            select_entry_segment(from, next, Some(&known), std::slice::from_ref(&known), |_| true),
+            Some(segment(RoomEdge::Left, 15, 48))
+        );
+
+        assert_eq!(select_entry_segment(room("W3N1"), next, None, &[], |_| true), None, "rooms don't touch");
+        assert_eq!(select_entry_segment(from, next, None, &[], |t| t.y().u8() % 3 == 0), None, "no run wide enough");
+    }
 }
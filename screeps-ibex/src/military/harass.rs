@@ -0,0 +1,219 @@
+//! Harassment of a hostile player's remote mining.
+//!
+//! A harass objective fields one or two cheap ranged raiders instead of a
+//! sized raid. They patrol the player's reserved remotes (the war operation
+//! supplies the rooms), moving on to the next room on a timer, shoot miners
+//! and haulers that have no armed escort, and pull out of any room whose armed
+//! hostiles out-damage them. Kills and losses are tallied per room so the war
+//! operation can back off remotes that turn out to be defended.
+
+use crate::combat::{CombatCreepDto, CombatStructureDto};
+use screeps::*;
+use screeps_combat_decision::bodies::CombatBodySpec;
+use screeps_combat_decision::composition::{BodyType, FormationShape, SquadComposition, SquadRole, SquadSlot};
+
+/// Ticks spent patrolling one room before moving on to the next.
+pub const HARASS_ROTATE_TICKS: u32 = 150;
+/// Ranged parts per raider — enough to kill a miner in a few volleys while staying cheap.
+const HARASS_RANGED_PARTS: u32 = 4;
+/// Heal parts per raider, to recover from stray hits between rooms.
+const HARASS_HEAL_PARTS: u32 = 1;
+/// A soft target is unescorted when no armed hostile stands within this range of it.
+const HARASS_ESCORT_RANGE: u32 = 5;
+/// A room that has driven the raiders off this many times counts as defended.
+const HARASS_DRIVEN_OFF_LIMIT: u32 = 3;
+
+/// The cheap raider force a harass objective requests: one ranged creep, or two walking as a pair.
+pub fn harass_composition(duo: bool) -> SquadComposition {
+    let raider = SquadSlot {
+        role: SquadRole::RangedDPS,
+        body_type: BodyType::Sized(CombatBodySpec {
+            ranged_attack: HARASS_RANGED_PARTS,
+            heal: HARASS_HEAL_PARTS,
+            ..Default::default()
+        }),
+    };
+
+    SquadComposition {
+        label: "Harass".into(),
+        slots: vec![raider; if duo { 2 } else { 1 }],
+        formation_shape: if duo { FormationShape::Line } else { FormationShape::None },
+        formation_mode: Default::default(),
+        retreat_threshold: 0.5,
+    }
+}
+
+/// Kills and losses a harass patrol has recorded against one room.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HarassTally {
+    pub kills: u32,
+    pub losses: u32,
+    /// Visits cut short because the room's armed hostiles out-damaged the raiders.
+    pub driven_off: u32,
+}
+
+impl HarassTally {
+    /// A room is defended once it has cost more raiders than it has given up, or has driven the
+    /// raiders off repeatedly.
+    pub fn is_defended(&self) -> bool {
+        self.losses > self.kills || self.driven_off >= HARASS_DRIVEN_OFF_LIMIT
+    }
+}
+
+fn active_parts(creep: &CombatCreepDto, part: Part) -> u32 {
+    creep.body.iter().filter(|p| p.part == part && p.hits > 0).count() as u32
+}
+
+/// Damage per tick a hostile can deal at its best range.
+fn creep_dps(creep: &CombatCreepDto) -> u32 {
+    active_parts(creep, Part::Attack) * ATTACK_POWER + active_parts(creep, Part::RangedAttack) * RANGED_ATTACK_POWER
+}
+
+fn is_armed(creep: &CombatCreepDto) -> bool {
+    creep_dps(creep) > 0
+}
+
+/// Whether the armed hostiles and energized towers in `room` out-damage raiders dealing `our_dps`.
+pub fn is_outmatched(room: RoomName, hostiles: &[CombatCreepDto], structures: &[CombatStructureDto], our_dps: u32) -> bool {
+    let creeps: u32 = hostiles.iter().filter(|h| h.pos.room_name() == room).map(creep_dps).sum();
+    let towers = structures
+        .iter()
+        .filter(|s| {
+            s.structure_type == StructureType::Tower
+                && s.ownership == screeps_combat_decision::Ownership::Hostile
+                && s.energy >= TOWER_ENERGY_COST
+                && s.pos.room_name() == room
+        })
+        .count() as u32;
+
+    towers > 0 || creeps > our_dps
+}
+
+/// The nearest unarmed hostile in `room` with no armed hostile within escort range of it.
+pub fn pick_soft_target<'a>(room: RoomName, hostiles: &'a [CombatCreepDto], from: Position) -> Option<&'a CombatCreepDto> {
+    let in_room: Vec<&CombatCreepDto> = hostiles.iter().filter(|h| h.pos.room_name() == room).collect();
+
+    in_room
+        .iter()
+        .copied()
+        .filter(|h| !is_armed(h))
+        .filter(|h| {
+            !in_room
+                .iter()
+                .any(|e| is_armed(e) && e.pos.get_range_to(h.pos) <= HARASS_ESCORT_RANGE)
+        })
+        .min_by_key(|h| (h.pos.get_range_to(from), h.hits))
+}
+
+/// Index of the room to patrol after `current`, skipping nothing; wraps around.
+pub fn next_patrol_index(current: usize, room_count: usize) -> usize {
+    if room_count == 0 {
+        0
+    } else {
+        (current + 1) % room_count
+    }
+}
+
+/// A focused creep that vanished from a visible room was killed, unless it was standing on an exit
+/// tile last tick (it may simply have left).
+pub fn target_was_killed(last_pos: Position, still_present: bool) -> bool {
+    let on_edge = |c: u8| c == 0 || c == ROOM_SIZE - 1;
+    !still_present && !on_edge(last_pos.x().u8()) && !on_edge(last_pos.y().u8())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::CombatBodyPart;
+
+    fn pos(x: u8, y: u8) -> Position {
+        Position::new(
+            RoomCoordinate::new(x).unwrap(),
+            RoomCoordinate::new(y).unwrap(),
+            "W1N1".parse().unwrap(),
+        )
+    }
+
+    fn creep(at: Position, parts: &[Part]) -> CombatCreepDto {
+        CombatCreepDto {
+            id: None,
+            pos: at,
+            hits: 100 * parts.len() as u32,
+            hits_max: 100 * parts.len() as u32,
+            body: parts.iter().map(|&part| CombatBodyPart { part, hits: 100 }).collect(),
+        }
+    }
+
+    /// Miners and haulers are fair game only while no armed hostile is close enough to cover them.
+    #[test]
+    fn soft_targets_are_unarmed_and_unescorted() {
+        let miner = creep(pos(10, 10), &[Part::Work, Part::Work, Part::Move]);
+        let hauler = creep(pos(30, 30), &[Part::Carry, Part::Move]);
+        let guard = creep(pos(12, 10), &[Part::Attack, Part::Move]);
+        let room = "W1N1".parse().unwrap();
+
+        let hostiles = [miner.clone(), hauler.clone(), guard];
+        assert_eq!(pick_soft_target(room, &hostiles, pos(11, 11)).map(|c| c.pos), Some(hauler.pos));
+        assert_eq!(
+            pick_soft_target(room, &[miner.clone()], pos(40, 40)).map(|c| c.pos),
+            Some(miner.pos)
+        );
+        assert!(pick_soft_target(room, &[creep(pos(5, 5), &[Part::RangedAttack])], pos(6, 6)).is_none());
+    }
+
+    #[test]
+    fn outmatched_when_hostiles_out_damage_the_raiders() {
+        let room = "W1N1".parse().unwrap();
+        let defender = creep(
+            pos(10, 10),
+            &[Part::RangedAttack, Part::RangedAttack, Part::RangedAttack, Part::Move],
+        );
+
+        assert!(!is_outmatched(room, &[defender.clone()], &[], 40));
+        assert!(is_outmatched(room, &[defender.clone(), defender], &[], 40));
+        assert!(!is_outmatched(room, &[creep(pos(10, 10), &[Part::Work])], &[], 10));
+    }
+
+    #[test]
+    fn tally_flags_rooms_that_cost_more_than_they_yield() {
+        assert!(!HarassTally::default().is_defended());
+        assert!(!HarassTally {
+            kills: 2,
+            losses: 1,
+            driven_off: 0
+        }
+        .is_defended());
+        assert!(HarassTally {
+            kills: 0,
+            losses: 1,
+            driven_off: 0
+        }
+        .is_defended());
+        assert!(HarassTally {
+            kills: 3,
+            losses: 0,
+            driven_off: HARASS_DRIVEN_OFF_LIMIT
+        }
+        .is_defended());
+    }
+
+    #[test]
+    fn patrol_wraps_and_exit_tile_vanish_is_not_a_kill() {
+        assert_eq!(next_patrol_index(0, 3), 1);
+        assert_eq!(next_patrol_index(2, 3), 0);
+        assert_eq!(next_patrol_index(0, 0), 0);
+
+        assert!(target_was_killed(pos(20, 20), false));
+        assert!(!target_was_killed(pos(20, 20), true));
+        assert!(!target_was_killed(pos(0, 20), false));
+        assert!(!target_was_killed(pos(20, 49), false));
+    }
+
+    #[test]
+    fn harass_force_is_one_or_two_ranged_raiders() {
+        assert_eq!(harass_composition(false).slots.len(), 1);
+        let duo = harass_composition(true);
+        assert_eq!(duo.slots.len(), 2);
+        assert!(duo.slots.iter().all(|s| s.role == SquadRole::RangedDPS));
+    }
+}
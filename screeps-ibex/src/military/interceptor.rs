@@ -0,0 +1,82 @@
+//! Interception of players' claimers.
+//!
+//! A CLAIM creep attacking one of our controllers, or the reservation on one
+//! of our remotes, carries no weapons: a single small melee creep catches and
+//! kills it. The war operation fields [`interceptor_composition`] for a remote
+//! a claimer turns up in, and every defense squad drops whatever else it was
+//! shooting for the nearest claimer in its room ([`pick_claimer`]).
+
+use crate::combat::CombatCreepDto;
+use screeps::*;
+use screeps_combat_decision::bodies::CombatBodySpec;
+use screeps_combat_decision::composition::{BodyType, FormationShape, SquadComposition, SquadRole, SquadSlot};
+
+/// Attack parts on the interceptor: a claimer's CLAIM and MOVE parts go down in a few hits.
+const INTERCEPTOR_ATTACK_PARTS: u32 = 3;
+
+/// The one melee creep an intercept objective requests.
+pub fn interceptor_composition() -> SquadComposition {
+    SquadComposition {
+        label: "Interceptor".into(),
+        slots: vec![SquadSlot {
+            role: SquadRole::MeleeDPS,
+            body_type: BodyType::Sized(CombatBodySpec {
+                attack: INTERCEPTOR_ATTACK_PARTS,
+                ..Default::default()
+            }),
+        }],
+        formation_shape: FormationShape::None,
+        formation_mode: Default::default(),
+        retreat_threshold: 0.5,
+    }
+}
+
+fn is_claimer(creep: &CombatCreepDto) -> bool {
+    creep.body.iter().any(|p| p.part == Part::Claim && p.hits > 0)
+}
+
+/// The claimer in `room` nearest `from`, if any hostile there still has a working CLAIM part.
+pub fn pick_claimer(room: RoomName, hostiles: &[CombatCreepDto], from: Position) -> Option<&CombatCreepDto> {
+    hostiles
+        .iter()
+        .filter(|h| h.pos.room_name() == room && is_claimer(h))
+        .min_by_key(|h| (h.pos.get_range_to(from), h.hits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::CombatBodyPart;
+
+    fn pos(room: &str, x: u8, y: u8) -> Position {
+        Position::new(
+            RoomCoordinate::new(x).unwrap(),
+            RoomCoordinate::new(y).unwrap(),
+            room.parse().unwrap(),
+        )
+    }
+
+    fn creep(at: Position, parts: &[(Part, u32)]) -> CombatCreepDto {
+        CombatCreepDto {
+            id: None,
+            pos: at,
+            hits: parts.iter().map(|(_, hits)| hits).sum(),
+            hits_max: 100 * parts.len() as u32,
+            body: parts.iter().map(|&(part, hits)| CombatBodyPart { part, hits }).collect(),
+        }
+    }
+
+    /// Claimers come before armed hostiles, however close those are.
+    #[test]
+    fn the_nearest_working_claimer_is_picked() {
+        let room = "W1N1".parse().unwrap();
+        let guard = creep(pos("W1N1", 11, 11), &[(Part::Attack, 100), (Part::Move, 100)]);
+        let claimer = creep(pos("W1N1", 30, 30), &[(Part::Claim, 100), (Part::Move, 100)]);
+        let spent = creep(pos("W1N1", 12, 12), &[(Part::Claim, 0), (Part::Move, 100)]);
+        let elsewhere = creep(pos("W2N1", 10, 10), &[(Part::Claim, 100), (Part::Move, 100)]);
+
+        let hostiles = [guard.clone(), claimer.clone(), spent, elsewhere];
+        assert_eq!(pick_claimer(room, &hostiles, pos("W1N1", 10, 10)).map(|c| c.pos), Some(claimer.pos));
+        assert!(pick_claimer(room, &[guard], pos("W1N1", 10, 10)).is_none());
+    }
+}
@@ -1,17 +1,36 @@
+pub mod arrival;
 pub mod boostqueue;
+pub mod borderwatch;
+pub mod composition;
 pub mod damage;
+pub mod drain;
 pub mod economy;
+pub mod escort;
 pub mod formation;
+pub mod harass;
+pub mod interceptor;
 pub mod objective_queue;
+pub mod posture;
+pub mod rampartdefense;
 pub mod squad;
 pub mod squad_manager;
 pub mod threatmap;
+pub mod threatmapvisualizesystem;
+pub mod wartargets;
 
 /// Screeps NPC owner usernames. Use these constants instead of hardcoding
 /// string literals in functional code.
 pub const NPC_INVADER: &str = "Invader";
 pub const NPC_SOURCE_KEEPER: &str = "Source Keeper";
 
+/// Players we never treat as hostile. Rooms they own read as friendly.
+pub const ALLIES: &[&str] = &[];
+
+/// Returns true if the given username is on the ally list.
+pub fn is_ally_owner(username: &str) -> bool {
+    ALLIES.contains(&username)
+}
+
 /// Returns true if the given username belongs to an NPC (Invader or Source Keeper).
 pub fn is_npc_owner(username: &str) -> bool {
     username == NPC_INVADER || username == NPC_SOURCE_KEEPER
@@ -30,6 +30,10 @@
 //! `SquadStore`/`SquadId` lands (P2.I1) the claim key becomes a `SquadId`; until
 //! then the runtime `Entity` handle is the natural ephemeral key.
 
+use super::arrival::ArrivalPlan;
+use super::composition::{AttackWaves, WaveEscalation, WavePlan, WaveReport};
+use super::escort::Caravan;
+use super::harass::HarassTally;
 use crate::serialize::*;
 use screeps_combat_decision::composition::SquadComposition;
 use screeps_combat_decision::force_sizing::AssaultMode;
@@ -117,6 +121,10 @@ pub enum ObjectiveKind {
     /// fielded as a CLAIM `SquadRole::Declaimer` squad by the `DeclaimAttack` always-field doctrine. Emitted
     /// by `SalvageMission` once the corridor is open (`ControllerAccess::ReachableNow`).
     Declaim { room: RoomName, controller: Position },
+    /// Escort a haul mission's haulers through rooms with recent hostile sightings (`escort`). `room` is the
+    /// mission's pickup room; the haulers and threatened rooms ride on the runtime entry. Emitted by the war
+    /// operation from the haul missions' `EscortRequest`s.
+    Caravan { room: RoomName },
 }
 
 impl ObjectiveKind {
@@ -129,7 +137,8 @@ impl ObjectiveKind {
             | ObjectiveKind::Harass { room }
             | ObjectiveKind::Farm { room, .. }
             | ObjectiveKind::Escort { room }
-            | ObjectiveKind::Declaim { room, .. } => *room,
+            | ObjectiveKind::Declaim { room, .. }
+            | ObjectiveKind::Caravan { room } => *room,
         }
     }
 }
@@ -166,6 +175,35 @@ impl ForceRequirement {
     }
 }
 
+/// When the manager may field a new squad for an objective. Without a condition a claimable objective is
+/// fielded as soon as it wins the EV ranking; back-to-back squads then drain their home rooms to zero.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DeployCondition {
+    #[default]
+    Immediate,
+    /// Field only once the in-range home rooms together hold at least `amount` stored energy
+    /// ([`EconomySnapshot::rooms_stored_energy`](super::economy::EconomySnapshot::rooms_stored_energy)).
+    AfterEnergyAvailable { amount: u32 },
+}
+
+impl DeployCondition {
+    /// Whether the condition holds given the home rooms' summed `stored_energy`.
+    pub fn is_met(self, stored_energy: u32) -> bool {
+        match self {
+            DeployCondition::Immediate => true,
+            DeployCondition::AfterEnergyAvailable { amount } => stored_energy >= amount,
+        }
+    }
+
+    /// Energy the condition asks the home rooms to hold (0 for `Immediate`).
+    pub fn energy_required(self) -> u32 {
+        match self {
+            DeployCondition::Immediate => 0,
+            DeployCondition::AfterEnergyAvailable { amount } => amount,
+        }
+    }
+}
+
 // ─── Persistent layer: CombatObjectiveData (serialized component) ────────────
 
 /// A single persistent combat objective entry. Durable facts only — the
@@ -251,6 +289,24 @@ pub struct ObjectiveRuntimeEntry {
     /// strategy + sets the squad's drain stance. `None` (no producer ran the oracle) ⇒ the byte-unchanged
     /// direct breach/engage path.
     pub assault_mode: Option<AssaultMode>,
+    /// When the manager may field a squad for this objective. Transient like `assault_mode` — the producer
+    /// re-attaches it every scan; `Immediate` until it does.
+    pub deploy_condition: DeployCondition,
+    /// Rooms a harass squad rotates through (the hostile player's remotes). Transient like
+    /// `assault_mode`; empty for every other objective.
+    pub patrol_rooms: Vec<RoomName>,
+    /// Where a defending squad waits for a predicted inbound group (the border watch's staging tile).
+    /// Transient; re-attached every defense scan while the group is inbound.
+    pub stage_at: Option<Position>,
+    /// T3 boosts (compound, parts) each member takes at the labs before engaging, when the defense scan
+    /// escalated to boosted defenders. Transient; re-attached every defense scan, empty otherwise.
+    pub boosts: Vec<(ResourceType, u32)>,
+    /// The haulers and threatened rooms a caravan escort covers. Transient; re-attached every scan while
+    /// the haul mission's request stands, `None` for every other objective.
+    pub caravan: Option<Caravan>,
+    /// When each squad of the force is expected at the target, stamped by the manager when it fields the
+    /// squad. Transient; a reloaded squad simply has no plan to be measured against.
+    pub arrival: Option<ArrivalPlan>,
 }
 
 /// Runtime combat objective queue resource. Holds a working copy of the
@@ -267,6 +323,12 @@ pub struct CombatObjectiveQueue {
     pub next_id: u32,
     /// Per-tick assignment, keyed by objective id.
     pub runtime: HashMap<ObjectiveId, ObjectiveRuntimeEntry>,
+    /// Harass kills/losses per patrolled room. Session-only (never serialized); a room judged defended is
+    /// also marked unwinnable, which is what persists.
+    pub harass_tally: HashMap<RoomName, HarassTally>,
+    /// Attack waves lost per target room, and the escalated plan for the next. Session-only (never
+    /// serialized); cleared with the room's give-up record when it is won.
+    pub wave_history: HashMap<RoomName, AttackWaves>,
 }
 
 impl CombatObjectiveQueue {
@@ -352,12 +414,110 @@ impl CombatObjectiveQueue {
         self.runtime.entry(id).or_default().assault_mode = Some(mode);
     }
 
+    /// Attach the arrival timeline the manager planned when it fielded this objective's squad.
+    pub fn set_arrival_plan(&mut self, id: ObjectiveId, plan: ArrivalPlan) {
+        self.runtime.entry(id).or_default().arrival = Some(plan);
+    }
+
+    /// The arrival timeline attached to this objective, if a squad was fielded for it this session.
+    pub fn arrival_plan(&self, id: ObjectiveId) -> Option<&ArrivalPlan> {
+        self.runtime.get(&id).and_then(|r| r.arrival.as_ref())
+    }
+
+    /// Mutable access to the arrival timeline, to record when the squad actually arrived.
+    pub fn arrival_plan_mut(&mut self, id: ObjectiveId) -> Option<&mut ArrivalPlan> {
+        self.runtime.get_mut(&id).and_then(|r| r.arrival.as_mut())
+    }
+
     /// The transient assault mode (if any) the producer attached to this objective. `None` ⇒ no oracle ran →
     /// the manager takes the byte-unchanged direct breach/engage path.
     pub fn assault_mode(&self, id: ObjectiveId) -> Option<AssaultMode> {
         self.runtime.get(&id).and_then(|r| r.assault_mode)
     }
 
+    /// Gate fielding a new squad for this objective on a [`DeployCondition`]. Transient (never serialized);
+    /// re-attached every scan, so a reloaded objective fields `Immediate` until the producer runs again.
+    pub fn set_deploy_condition(&mut self, id: ObjectiveId, condition: DeployCondition) {
+        self.runtime.entry(id).or_default().deploy_condition = condition;
+    }
+
+    /// The deploy condition the producer attached to this objective (`Immediate` if none).
+    pub fn deploy_condition(&self, id: ObjectiveId) -> DeployCondition {
+        self.runtime.get(&id).map(|r| r.deploy_condition).unwrap_or_default()
+    }
+
+    /// Attach the rooms a harass squad patrols for this objective. Transient; re-attached every scan.
+    pub fn set_patrol_rooms(&mut self, id: ObjectiveId, rooms: Vec<RoomName>) {
+        self.runtime.entry(id).or_default().patrol_rooms = rooms;
+    }
+
+    /// The patrol rooms attached to this objective (empty unless it is a harass objective).
+    pub fn patrol_rooms(&self, id: ObjectiveId) -> &[RoomName] {
+        self.runtime.get(&id).map(|r| r.patrol_rooms.as_slice()).unwrap_or(&[])
+    }
+
+    /// Attach the tile a defending squad should hold while it waits for an inbound group.
+    pub fn set_stage_position(&mut self, id: ObjectiveId, pos: Position) {
+        self.runtime.entry(id).or_default().stage_at = Some(pos);
+    }
+
+    /// Drop every staging tile; the defense scan re-attaches the ones still inbound.
+    pub fn clear_stage_positions(&mut self) {
+        for entry in self.runtime.values_mut() {
+            entry.stage_at = None;
+        }
+    }
+
+    /// The staging tile attached to this objective, if any.
+    pub fn stage_position(&self, id: ObjectiveId) -> Option<Position> {
+        self.runtime.get(&id).and_then(|r| r.stage_at)
+    }
+
+    /// Attach the per-member boosts a squad for this objective takes before engaging (empty to clear).
+    pub fn set_boost_plan(&mut self, id: ObjectiveId, boosts: Vec<(ResourceType, u32)>) {
+        self.runtime.entry(id).or_default().boosts = boosts;
+    }
+
+    /// The per-member boosts attached to this objective (empty unless the defense escalated).
+    pub fn boost_plan(&self, id: ObjectiveId) -> &[(ResourceType, u32)] {
+        self.runtime.get(&id).map(|r| r.boosts.as_slice()).unwrap_or(&[])
+    }
+
+    /// Attach the caravan a caravan escort covers. Transient; re-attached every scan.
+    pub fn set_caravan(&mut self, id: ObjectiveId, caravan: Caravan) {
+        self.runtime.entry(id).or_default().caravan = Some(caravan);
+    }
+
+    /// The caravan attached to this objective (`None` unless it is a caravan escort).
+    pub fn caravan(&self, id: ObjectiveId) -> Option<&Caravan> {
+        self.runtime.get(&id).and_then(|r| r.caravan.as_ref())
+    }
+
+    /// The mutable kills/losses tally for a harassed room.
+    pub fn harass_tally_mut(&mut self, room: RoomName) -> &mut HarassTally {
+        self.harass_tally.entry(room).or_default()
+    }
+
+    /// The kills/losses recorded against a harassed room so far.
+    pub fn harass_tally(&self, room: RoomName) -> HarassTally {
+        self.harass_tally.get(&room).copied().unwrap_or_default()
+    }
+
+    /// Record an attack wave wiped in `room`.
+    pub fn record_lost_wave(&mut self, room: RoomName, report: WaveReport) {
+        self.wave_history.entry(room).or_default().lost.push(report);
+    }
+
+    /// The attack waves lost against `room` since it was last won, oldest first.
+    pub fn lost_waves(&self, room: RoomName) -> &[WaveReport] {
+        self.wave_history.get(&room).map(|w| w.lost.as_slice()).unwrap_or(&[])
+    }
+
+    /// Note the plan the next wave against `room` is fielded with, for the war summary.
+    pub fn set_next_wave(&mut self, room: RoomName, plan: WavePlan, steps: Vec<WaveEscalation>) {
+        self.wave_history.entry(room).or_default().next = Some((plan, steps));
+    }
+
     /// True if the objective is currently claimed by a (live) squad.
     pub fn is_claimed(&self, id: ObjectiveId) -> bool {
         self.claimed_by(id).is_some()
@@ -408,9 +568,10 @@ impl CombatObjectiveQueue {
         self.unwinnable.iter().any(|u| u.room == room && u.retry_after > now)
     }
 
-    /// Clear any give-up record for `room` — call when the target becomes winnable.
+    /// Clear any give-up record and lost-wave history for `room` — call when the target becomes winnable.
     pub fn clear_unwinnable(&mut self, room: RoomName) {
         self.unwinnable.retain(|u| u.room != room);
+        self.wave_history.remove(&room);
     }
 
     /// Remove objectives that have expired, pruning their runtime entries. An objective is kept past its
@@ -845,4 +1006,19 @@ mod tests {
         assert!(q.get(id).is_none());
         assert!(!q.runtime.contains_key(&id));
     }
+
+    #[test]
+    fn deploy_condition_gates_on_stored_energy_and_defaults_to_immediate() {
+        let mut q = CombatObjectiveQueue::default();
+        let id = q.request(farm_request("W1N1", OBJECTIVE_PRIORITY_LOW), 100);
+        assert_eq!(q.deploy_condition(id), DeployCondition::Immediate);
+        assert!(q.deploy_condition(id).is_met(0));
+
+        let gated = DeployCondition::AfterEnergyAvailable { amount: 10_000 };
+        q.set_deploy_condition(id, gated);
+        assert_eq!(q.deploy_condition(id), gated);
+        assert!(!gated.is_met(9_999));
+        assert!(gated.is_met(10_000));
+        assert_eq!(gated.energy_required(), 10_000);
+    }
 }
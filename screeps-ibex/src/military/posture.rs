@@ -0,0 +1,342 @@
+//! Per-room defense posture.
+//!
+//! One knob per owned room that missions consult, instead of each reading the
+//! threat map its own way:
+//!
+//! - `Peaceful` — business as usual.
+//! - `Alert` — hostiles were sighted in the room or are predicted to cross
+//!   into it, or a player's claimer is in it: the war operation keeps one
+//!   defender standing by and the tower mission tops the towers up to
+//!   [`ALERT_TOWER_ENERGY`] of capacity at high priority.
+//! - `Lockdown` — armed hostiles are in a room whose rampart perimeter has a
+//!   breach: mining outposts stop sending creeps out through exits hostiles
+//!   are near, the room's hauler pool calls its haulers home, the spawn queue
+//!   holds civilian creeps bound for other rooms, and defenders spawn at
+//!   critical priority.
+//!
+//! [`DefensePostureSystem`] escalates straight away (a sighting to Alert, the
+//! breach detector to Lockdown) and steps down one level at a time once
+//! [`POSTURE_HOLD_TICKS`] pass without the trigger. The `posture <room>
+//! <level|auto>` console command pins a level through
+//! `_features.rooms.<room>.posture`. The component is not serialized: the
+//! threat data it is derived from is, and a reload re-derives it.
+
+use super::borderwatch::BorderWatch;
+use super::threatmap::RoomThreatData;
+use crate::features::FeatureOverrides;
+use crate::room::data::RoomData;
+use crate::spawnsystem::SPAWN_PRIORITY_CRITICAL;
+use log::*;
+use screeps::*;
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use specs::Component;
+
+/// Ticks without a trigger before the posture steps down a level.
+pub const POSTURE_HOLD_TICKS: u32 = 300;
+/// Fraction of capacity towers are kept topped up to on alert.
+pub const ALERT_TOWER_ENERGY: f32 = 0.8;
+/// A hostile this close to an exit in lockdown closes it to departures.
+pub const LOCKDOWN_CORRIDOR_RANGE: u32 = 10;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PostureLevel {
+    #[default]
+    Peaceful,
+    Alert,
+    Lockdown,
+}
+
+impl PostureLevel {
+    pub fn name(self) -> &'static str {
+        match self {
+            PostureLevel::Peaceful => "peaceful",
+            PostureLevel::Alert => "alert",
+            PostureLevel::Lockdown => "lockdown",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<PostureLevel> {
+        match name {
+            "peaceful" => Some(PostureLevel::Peaceful),
+            "alert" => Some(PostureLevel::Alert),
+            "lockdown" => Some(PostureLevel::Lockdown),
+            _ => None,
+        }
+    }
+
+    fn lower(self) -> PostureLevel {
+        match self {
+            PostureLevel::Lockdown => PostureLevel::Alert,
+            _ => PostureLevel::Peaceful,
+        }
+    }
+}
+
+/// The level the escalation rules call for given the room's threat data and
+/// whether a hostile group is predicted to cross into it, with the reason.
+pub fn posture_trigger(threat: Option<&RoomThreatData>, inbound: bool) -> (PostureLevel, &'static str) {
+    let Some(threat) = threat.filter(|threat| threat.warrants_attention()) else {
+        return if inbound {
+            (PostureLevel::Alert, "inbound")
+        } else {
+            (PostureLevel::Peaceful, "")
+        };
+    };
+
+    let armed = threat
+        .hostile_creeps
+        .iter()
+        .any(|hostile| hostile.melee_dps + hostile.ranged_dps > 0.0 || hostile.work_parts > 0);
+
+    if armed && !threat.breach_points.is_empty() {
+        (PostureLevel::Lockdown, "breach")
+    } else if !threat.claimers.is_empty() {
+        (PostureLevel::Alert, "claimer")
+    } else {
+        (PostureLevel::Alert, "hostiles")
+    }
+}
+
+/// One owned room's posture, kept on its room entity.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[storage(DenseVecStorage)]
+pub struct DefensePosture {
+    /// The level missions act on: the pinned level when the operator set one, else `automatic`.
+    pub level: PostureLevel,
+    /// The level the escalation rules hold the room at.
+    pub automatic: PostureLevel,
+    /// Why `automatic` is above peaceful.
+    pub reason: &'static str,
+    /// Whether `level` was pinned from the console.
+    pub pinned: bool,
+    /// The last tick a trigger held `automatic` at its level, or it stepped down.
+    triggered_at: u32,
+}
+
+impl DefensePosture {
+    /// Apply this tick's `trigger` (see [`posture_trigger`]) and the operator's `pinned` level.
+    pub fn update(&mut self, trigger: (PostureLevel, &'static str), pinned: Option<PostureLevel>, now: u32) {
+        let (wanted, reason) = trigger;
+
+        if wanted >= self.automatic {
+            if wanted > PostureLevel::Peaceful {
+                self.reason = reason;
+            }
+            self.automatic = wanted;
+            self.triggered_at = now;
+        } else if now.saturating_sub(self.triggered_at) >= POSTURE_HOLD_TICKS {
+            self.automatic = self.automatic.lower().max(wanted);
+            self.reason = if self.automatic > PostureLevel::Peaceful {
+                "standing down"
+            } else {
+                ""
+            };
+            self.triggered_at = now;
+        }
+
+        self.pinned = pinned.is_some();
+        self.level = pinned.unwrap_or(self.automatic);
+    }
+
+    pub fn is_lockdown(&self) -> bool {
+        self.level == PostureLevel::Lockdown
+    }
+
+    /// Whether the war operation keeps a defender fielded for the room.
+    pub fn keeps_standby_defender(&self) -> bool {
+        self.level >= PostureLevel::Alert
+    }
+
+    /// Fraction of capacity the towers are topped up to at high priority.
+    pub fn tower_energy_floor(&self) -> f32 {
+        if self.level >= PostureLevel::Alert {
+            ALERT_TOWER_ENERGY
+        } else {
+            0.0
+        }
+    }
+
+    /// Spawn priority for the room's defenders, given what they would otherwise get.
+    pub fn defender_spawn_priority(&self, priority: f32) -> f32 {
+        if self.is_lockdown() {
+            SPAWN_PRIORITY_CRITICAL
+        } else {
+            priority
+        }
+    }
+
+    /// Whether creeps leaving by `exits` (the room's exit tiles they path through) are held: in lockdown,
+    /// when a hostile is within [`LOCKDOWN_CORRIDOR_RANGE`] of one of them. With no exits known every
+    /// departure is held.
+    pub fn holds_departure(&self, exits: &[Position], threat: Option<&RoomThreatData>) -> bool {
+        if !self.is_lockdown() {
+            return false;
+        }
+
+        if exits.is_empty() {
+            return true;
+        }
+
+        let hostiles = threat.map(|threat| threat.hostile_creeps.as_slice()).unwrap_or(&[]);
+
+        exits.iter().any(|exit| {
+            hostiles.iter().any(|hostile| {
+                hostile.position.room_name() == exit.room_name() && hostile.position.get_range_to(*exit) <= LOCKDOWN_CORRIDOR_RANGE
+            })
+        })
+    }
+}
+
+/// Re-derives each owned room's [`DefensePosture`] every tick, after the threat assessment.
+pub struct DefensePostureSystem;
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+impl<'a> System<'a> for DefensePostureSystem {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, RoomData>,
+        ReadStorage<'a, RoomThreatData>,
+        Read<'a, BorderWatch>,
+        Read<'a, FeatureOverrides>,
+        WriteStorage<'a, DefensePosture>,
+    );
+
+    fn run(&mut self, (entities, room_data, threat_data, border_watch, feature_overrides, mut postures): Self::SystemData) {
+        let now = game::time();
+
+        for (entity, room_data) in (&entities, &room_data).join() {
+            let owned = room_data.get_dynamic_visibility_data().map(|d| d.owner().mine()).unwrap_or(false);
+
+            if !owned {
+                postures.remove(entity);
+                continue;
+            }
+
+            let trigger = posture_trigger(threat_data.get(entity), border_watch.inbound(room_data.name).is_some());
+            let mut posture = postures.get(entity).copied().unwrap_or_default();
+            let before = posture.level;
+
+            posture.update(trigger, feature_overrides.room(room_data.name).posture, now);
+
+            if posture.level != before {
+                info!(
+                    "[Posture] {} {} -> {}{}",
+                    room_data.name,
+                    before.name(),
+                    posture.level.name(),
+                    if posture.pinned { " (pinned)" } else { "" }
+                );
+            }
+
+            let _ = postures.insert(entity, posture);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::military::threatmap::{HostileCreepInfo, ThreatLevel};
+
+    fn hostile(x: u8, y: u8, melee_dps: f32) -> HostileCreepInfo {
+        HostileCreepInfo {
+            position: crate::testing::position("W1N1".parse().unwrap(), x, y),
+            owner: "raider".to_string(),
+            hits: 1_000,
+            hits_max: 1_000,
+            melee_dps,
+            ranged_dps: 0.0,
+            heal_per_tick: 0.0,
+            tough_hp: 0.0,
+            work_parts: 0,
+            boosted: false,
+        }
+    }
+
+    fn threat(hostiles: Vec<HostileCreepInfo>, breached: bool) -> RoomThreatData {
+        RoomThreatData {
+            threat_level: ThreatLevel::PlayerRaid,
+            hostile_creeps: hostiles,
+            breach_points: if breached {
+                vec![crate::testing::position("W1N1".parse().unwrap(), 20, 20)]
+            } else {
+                Vec::new()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sightings_alert_and_armed_breaches_lock_down() {
+        assert_eq!(posture_trigger(None, false).0, PostureLevel::Peaceful);
+        assert_eq!(posture_trigger(None, true).0, PostureLevel::Alert);
+        assert_eq!(
+            posture_trigger(Some(&threat(vec![hostile(5, 5, 30.0)], false)), false).0,
+            PostureLevel::Alert
+        );
+        assert_eq!(
+            posture_trigger(Some(&threat(vec![hostile(5, 5, 0.0)], true)), false).0,
+            PostureLevel::Alert
+        );
+        assert_eq!(
+            posture_trigger(Some(&threat(vec![hostile(5, 5, 30.0)], true)), false),
+            (PostureLevel::Lockdown, "breach")
+        );
+    }
+
+    #[test]
+    fn claimers_alert_with_their_own_reason() {
+        let mut claimed = threat(vec![hostile(5, 5, 0.0)], false);
+        claimed.claimers = vec![claimed.hostile_creeps[0].position];
+
+        assert_eq!(posture_trigger(Some(&claimed), false), (PostureLevel::Alert, "claimer"));
+
+        claimed.hostile_creeps.push(hostile(6, 6, 30.0));
+        claimed.breach_points = vec![crate::testing::position("W1N1".parse().unwrap(), 20, 20)];
+        assert_eq!(posture_trigger(Some(&claimed), false).0, PostureLevel::Lockdown);
+    }
+
+    #[test]
+    fn posture_escalates_at_once_and_steps_down_a_level_per_hold() {
+        let mut posture = DefensePosture::default();
+
+        posture.update((PostureLevel::Lockdown, "breach"), None, 100);
+        assert_eq!(posture.level, PostureLevel::Lockdown);
+
+        posture.update((PostureLevel::Peaceful, ""), None, 100 + POSTURE_HOLD_TICKS - 1);
+        assert_eq!(posture.level, PostureLevel::Lockdown);
+
+        posture.update((PostureLevel::Peaceful, ""), None, 100 + POSTURE_HOLD_TICKS);
+        assert_eq!(posture.level, PostureLevel::Alert);
+
+        posture.update((PostureLevel::Peaceful, ""), None, 100 + 2 * POSTURE_HOLD_TICKS);
+        assert_eq!((posture.level, posture.reason), (PostureLevel::Peaceful, ""));
+
+        posture.update(
+            (PostureLevel::Peaceful, ""),
+            Some(PostureLevel::Lockdown),
+            100 + 2 * POSTURE_HOLD_TICKS,
+        );
+        assert!(posture.pinned && posture.is_lockdown());
+        assert_eq!(posture.automatic, PostureLevel::Peaceful);
+    }
+
+    #[test]
+    fn lockdown_holds_departures_through_exits_hostiles_are_near() {
+        let room = "W1N1".parse().unwrap();
+        let near = [crate::testing::position(room, 0, 10)];
+        let far = [crate::testing::position(room, 49, 40)];
+        let threat = threat(vec![hostile(5, 12, 30.0)], true);
+
+        let mut posture = DefensePosture::default();
+        assert!(!posture.holds_departure(&near, Some(&threat)));
+
+        posture.update((PostureLevel::Lockdown, "breach"), None, 1);
+        assert!(posture.holds_departure(&near, Some(&threat)));
+        assert!(!posture.holds_departure(&far, Some(&threat)));
+        assert!(posture.holds_departure(&[], None));
+        assert_eq!(posture.defender_spawn_priority(85.0), SPAWN_PRIORITY_CRITICAL);
+    }
+}
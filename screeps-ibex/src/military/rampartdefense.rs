@@ -0,0 +1,145 @@
+//! Rampart-aware defense: in our own rooms defenders fight from ramparts, where
+//! melee can't reach them, instead of chasing hostiles into open ground.
+//!
+//! [`RampartIndex`] holds the standable ramparts of a room (ours, with nothing
+//! impassable built on the tile). A defender posts to the free rampart nearest
+//! it that is within its weapon range of a hostile — range 1 for melee, 3 for
+//! ranged — and only leaves the ramparts when no rampart reaches a hostile or
+//! the hostiles are fleeing.
+
+use crate::room::data::*;
+use screeps::*;
+use std::collections::HashSet;
+
+/// Hostiles this close to the room edge are taken to be leaving.
+pub const FLEEING_EDGE_RANGE: u8 = 3;
+
+/// Room tile coordinates.
+pub type Tile = (u8, u8);
+
+fn range(a: Tile, b: Tile) -> u8 {
+    a.0.abs_diff(b.0).max(a.1.abs_diff(b.1))
+}
+
+fn edge_range(tile: Tile) -> u8 {
+    let (x, y) = tile;
+    x.min(y).min(ROOM_SIZE - 1 - x).min(ROOM_SIZE - 1 - y)
+}
+
+/// The ramparts a defender can stand on, per room.
+#[derive(Debug, Clone, Default)]
+pub struct RampartIndex {
+    tiles: Vec<Tile>,
+}
+
+impl RampartIndex {
+    pub fn new(tiles: Vec<Tile>) -> RampartIndex {
+        RampartIndex { tiles }
+    }
+
+    /// Our ramparts from the room's structure cache, minus the ones over
+    /// spawns, towers and other impassable structures.
+    pub fn from_room(room_data: &RoomData) -> RampartIndex {
+        let Some(structures) = room_data.get_structures() else {
+            return RampartIndex::default();
+        };
+
+        let blocked: HashSet<Tile> = structures
+            .all()
+            .iter()
+            .filter(|structure| {
+                !matches!(
+                    structure.structure_type(),
+                    StructureType::Rampart | StructureType::Road | StructureType::Container
+                )
+            })
+            .map(|structure| (structure.pos().x().u8(), structure.pos().y().u8()))
+            .collect();
+
+        let tiles = structures
+            .ramparts()
+            .iter()
+            .filter(|rampart| rampart.my())
+            .map(|rampart| (rampart.pos().x().u8(), rampart.pos().y().u8()))
+            .filter(|tile| !blocked.contains(tile))
+            .collect();
+
+        RampartIndex { tiles }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    pub fn contains(&self, tile: Tile) -> bool {
+        self.tiles.contains(&tile)
+    }
+
+    /// Ramparts within `reach` of any of `hostiles`.
+    pub fn covering<'a>(&'a self, hostiles: &'a [Tile], reach: u8) -> impl Iterator<Item = Tile> + 'a {
+        self.tiles
+            .iter()
+            .copied()
+            .filter(move |tile| hostiles.iter().any(|hostile| range(*tile, *hostile) <= reach))
+    }
+
+    /// The rampart a defender at `defender` with weapon `reach` should hold:
+    /// the one it stands on if that still reaches a hostile, else the nearest
+    /// free one that does. `None` when no rampart reaches any hostile.
+    pub fn post(&self, defender: Tile, reach: u8, hostiles: &[Tile], occupied: &HashSet<Tile>) -> Option<Tile> {
+        let covering: Vec<Tile> = self.covering(hostiles, reach).collect();
+
+        if covering.contains(&defender) {
+            return Some(defender);
+        }
+
+        covering
+            .into_iter()
+            .filter(|tile| !occupied.contains(tile))
+            .min_by_key(|tile| (range(*tile, defender), *tile))
+    }
+}
+
+/// Whether the hostiles are on their way out: every one is at the room edge.
+/// Defenders may leave the ramparts to chase them down.
+pub fn hostiles_fleeing(hostiles: &[Tile]) -> bool {
+    !hostiles.is_empty() && hostiles.iter().all(|hostile| edge_range(*hostile) <= FLEEING_EDGE_RANGE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn melee_defenders_post_next_to_the_hostile_and_ranged_within_three() {
+        let index = RampartIndex::new(vec![(20, 20), (21, 20), (24, 20)]);
+        let hostiles = [(22, 21)];
+        let free = HashSet::new();
+
+        assert_eq!(index.post((10, 10), 1, &hostiles, &free), Some((21, 20)));
+        // Ranged reach lets the nearer rampart serve.
+        assert_eq!(index.post((10, 10), 3, &hostiles, &free), Some((20, 20)));
+        // Nothing reaches a hostile across the room.
+        assert_eq!(index.post((10, 10), 1, &[(40, 40)], &free), None);
+    }
+
+    #[test]
+    fn defenders_keep_their_rampart_and_skip_occupied_ones() {
+        let index = RampartIndex::new(vec![(21, 20), (23, 20)]);
+        let hostiles = [(22, 21)];
+        let occupied: HashSet<Tile> = [(21, 20)].into_iter().collect();
+
+        assert_eq!(index.post((21, 20), 1, &hostiles, &occupied), Some((21, 20)));
+        assert_eq!(index.post((10, 20), 1, &hostiles, &occupied), Some((23, 20)));
+
+        let full: HashSet<Tile> = [(21, 20), (23, 20)].into_iter().collect();
+        assert_eq!(index.post((10, 20), 1, &hostiles, &full), None);
+    }
+
+    #[test]
+    fn hostiles_at_the_edge_are_fleeing() {
+        assert!(hostiles_fleeing(&[(1, 25), (48, 3)]));
+        assert!(!hostiles_fleeing(&[(1, 25), (25, 25)]));
+        assert!(!hostiles_fleeing(&[]));
+    }
+}
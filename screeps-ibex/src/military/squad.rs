@@ -1,4 +1,7 @@
 use crate::creep::{CreepOwner, CreepSpawning};
+use crate::entitymappingsystem::EntityMappingData;
+use crate::gameview::{GameView, LiveGame};
+use crate::military::threatmap::RoomThreatData;
 use crate::serialize::*;
 use screeps_combat_decision::composition::*;
 use screeps::*;
@@ -7,6 +10,7 @@ use serde::{Deserialize, Serialize};
 use specs::error::NoError;
 use specs::saveload::*;
 use specs::*;
+use std::collections::HashMap;
 
 /// High-level squad lifecycle state.
 /// Ordered by lifecycle progression for comparison (Forming < Rallying < ... < Complete).
@@ -25,6 +29,17 @@ pub enum SquadState {
     Retreating,
     /// Squad objective is complete.
     Complete,
+    /// Squad pulled back depleted after contact: regrouping and healing at the rally while its dead
+    /// slots respawn, then re-rallying as the same squad. Appended last so existing serialized states
+    /// keep their variant index, which means it sorts after `Complete`.
+    Reinforcing,
+}
+
+impl SquadState {
+    /// Whether members should be falling back rather than fighting (retreating or reinforcing).
+    pub fn is_falling_back(self) -> bool {
+        matches!(self, SquadState::Retreating | SquadState::Reinforcing)
+    }
 }
 
 /// A serialize-safe, generation-checked reference to a squad entity.
@@ -77,7 +92,8 @@ pub enum SquadTarget {
     AttackStructure { position: Position },
     /// Collect dropped resources in a room (post-destruction exploitation).
     CollectResources { room: RoomName },
-    /// Escort/defend another squad or position (power bank defense).
+    /// Escort/defend another squad or position (power bank defense). A caravan escort targets the haul
+    /// mission's pickup room and follows its haulers (`TickMovement::Follow`).
     EscortPosition { position: Position },
     /// Neutralize a derelict controller via `attackController` (ADR 0027 v1.1 P2). `position` is the
     /// controller tile; a `SquadRole::Declaimer` member strikes it on the 1000-tick upgrade-block cadence.
@@ -97,8 +113,27 @@ pub enum SquadTarget {
 pub struct SquadPath {
     /// The footprint-aware anchor mover (virtual position, destination, cached path, stuck counter).
     pub anchor: screeps_rover::AnchorPath,
-    /// Room-level route to the destination (from find_route / RoomRouteCache).
+    /// Room-level route to the destination, destination last (`PathfinderService::route_rooms`).
+    /// Taken once per destination room; the anchor only pathfinds within the room it is in.
     pub room_route: Vec<RoomName>,
+    /// Where the anchor is heading in the next room of `room_route`; `None` in the destination room.
+    pub waypoint: Option<RouteWaypoint>,
+}
+
+/// The entry into the next room of a squad's route: the anchor paths to the middle of `segment` and picks
+/// the next waypoint once it crosses.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RouteWaypoint {
+    pub room: RoomName,
+    pub segment: crate::military::damage::EntrySegment,
+    /// Segments of `room` the anchor found no way onto, skipped when re-selecting.
+    pub blocked: Vec<crate::military::damage::EntrySegment>,
+}
+
+impl RouteWaypoint {
+    pub fn pos(&self) -> Position {
+        self.segment.center(self.room)
+    }
 }
 
 // ─── Dynamic formation layout ───────────────────────────────────────────────
@@ -237,6 +272,18 @@ pub enum TickMovement {
     Flee,
     /// Stay put.
     Hold,
+    /// Step onto this exact tile, overriding formation movement — a ranged-only
+    /// member backing out of melee reach (`formation::kite_step`).
+    Kite(Position),
+    /// Stand on this exact tile of a drain post — the inside tile to soak tower fire or the outside
+    /// tile to heal (`drain::drain_step`). Overrides the job's low-HP retreat and room checks.
+    Drain(Position),
+    /// Patrol a room that may not be the squad's target room (`harass`): chase the focus target if
+    /// one is set, else walk toward this waypoint. Overrides the job's left-the-room check.
+    Patrol(Position),
+    /// Follow an escorted creep (`escort`): keep close to this tile, where the manager found the creep this
+    /// tick, and only break off to chase a focus target near it. Overrides the job's left-the-room check.
+    Follow(Position),
 }
 
 /// What the squad should focus fire on.
@@ -285,33 +332,28 @@ impl AttackTarget {
     }
 }
 
-/// Per-creep orders from the mission to the job for a single tick.
+/// Per-creep orders from the squad manager to the job for a single tick.
 ///
-/// Ephemeral: cleared every tick by `PreRunSquadUpdateSystem` and repopulated
-/// by the mission before jobs run. Always `None` at serialization time, so
-/// non-serializable fields are fine (skipped via `serde(skip)`).
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Ephemeral: held in [`SquadOrders`], never on the serialized `SquadContext`.
+#[derive(Clone, Debug)]
 pub struct TickOrders {
     /// Focus fire target for this tick. Resolved by the job to get the exact
     /// game object (creep) or position (structure).
-    #[serde(skip)]
     pub attack_target: Option<AttackTarget>,
     /// Friendly creep to heal this tick. Resolved by the job via
     /// `ObjectId::resolve()` for direct API calls.
-    #[serde(skip)]
     pub heal_target: Option<ObjectId<Creep>>,
     /// Movement intent for this tick.
     pub movement: TickMovement,
     /// The squad's shared movement directive (P2.G3-tail) — the manager computes it (incl. the
-    /// pathfinding-scored kite goal); the job feeds it to the pure `decide_movement`. Ephemeral.
-    #[serde(skip)]
+    /// pathfinding-scored kite goal); the job feeds it to the pure `decide_movement`.
     pub squad_movement: crate::combat::SquadMovement,
-    /// The squad's real centroid this tick (the cohesion frame). Ephemeral.
-    #[serde(skip)]
+    /// The squad's real centroid this tick (the cohesion frame).
     pub squad_center: Option<Position>,
-    /// Loose-centroid cohesion radius K (0 ⇒ no squad goal → the per-creep fallback). Ephemeral.
-    #[serde(skip)]
+    /// Loose-centroid cohesion radius K (0 ⇒ no squad goal → the per-creep fallback).
     pub squad_cohesion_radius: u32,
+    /// Fire `rangedMassAttack` instead of a single ranged attack (2+ hostiles in range).
+    pub ranged_mass_attack: bool,
 }
 
 impl Default for TickOrders {
@@ -323,6 +365,47 @@ impl Default for TickOrders {
             squad_movement: crate::combat::SquadMovement::Hold,
             squad_center: None,
             squad_cohesion_radius: 0,
+            ranged_mass_attack: false,
+        }
+    }
+}
+
+/// This tick's orders for every squad member, keyed by the member's creep entity. The per-tick scratch
+/// half of the squad state: a runtime resource, never serialized, cleared by `PreRunSquadUpdateSystem`
+/// and repopulated by the squad manager before jobs run — so no order outlives its tick or a reload.
+#[derive(Default)]
+pub struct SquadOrders {
+    members: HashMap<Entity, TickOrders>,
+}
+
+impl SquadOrders {
+    pub fn clear(&mut self) {
+        self.members.clear();
+    }
+
+    pub fn get(&self, member: Entity) -> Option<&TickOrders> {
+        self.members.get(&member)
+    }
+
+    pub fn get_mut(&mut self, member: Entity) -> Option<&mut TickOrders> {
+        self.members.get_mut(&member)
+    }
+
+    /// Replace the member's orders for this tick.
+    pub fn set(&mut self, member: Entity, orders: TickOrders) {
+        self.members.insert(member, orders);
+    }
+
+    /// The member's orders for this tick, starting from the defaults if it has none yet.
+    pub fn entry(&mut self, member: Entity) -> &mut TickOrders {
+        self.members.entry(member).or_default()
+    }
+
+    /// Apply computed heal assignments on top of the healers' orders.
+    /// Call after `SquadContext::compute_heal_assignments()`.
+    pub fn apply_heal_assignments(&mut self, assignments: &[HealAssignment]) {
+        for assignment in assignments {
+            self.entry(assignment.healer).heal_target = assignment.target_id;
         }
     }
 }
@@ -364,8 +447,6 @@ pub struct SquadMember {
     /// the formation offset array). Can be reassigned each tick by the
     /// mission to rotate creeps within the formation.
     pub formation_slot: usize,
-    /// Per-tick orders from the mission (populated during pre_run).
-    pub tick_orders: Option<TickOrders>,
     /// Number of active HEAL body parts (updated when member is added or refreshed).
     /// Used for heal assignment optimization.
     pub heal_power: u32,
@@ -374,6 +455,71 @@ pub struct SquadMember {
     pub damage_taken_last_tick: u32,
 }
 
+// ─── Casualties ─────────────────────────────────────────────────────────────
+
+/// What killed a squad member, judged from the last tick it was seen alive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CauseOfDeath {
+    /// Energized hostile towers in reach out-damaged the hostile creeps.
+    Towers,
+    /// Hostile creeps in reach did most of the damage.
+    Creeps,
+    /// Died at full health: aged out or recycled.
+    Expired,
+    /// Nothing hostile known in reach of its last position.
+    Unknown,
+}
+
+impl CauseOfDeath {
+    /// Classify a death from the member's last reading and the damage the room could deal at its last
+    /// position. A creep last seen at full health almost never dies to one tick of fire.
+    pub fn classify(last_hits: u32, max_hits: u32, tower_damage: f32, creep_damage: f32) -> CauseOfDeath {
+        if max_hits > 0 && last_hits >= max_hits {
+            CauseOfDeath::Expired
+        } else if tower_damage <= 0.0 && creep_damage <= 0.0 {
+            CauseOfDeath::Unknown
+        } else if tower_damage >= creep_damage {
+            CauseOfDeath::Towers
+        } else {
+            CauseOfDeath::Creeps
+        }
+    }
+
+    /// Judge a member's death against the room's last threat assessment.
+    fn observe(member: &SquadMember, threat: Option<&RoomThreatData>) -> CauseOfDeath {
+        let (Some(pos), Some(threat)) = (member.position, threat) else {
+            return CauseOfDeath::classify(member.current_hits, member.max_hits, 0.0, 0.0);
+        };
+
+        let towers: Vec<Position> = threat
+            .hostile_tower_positions
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| threat.tower_energy.get(*i).is_none_or(|energy| *energy >= TOWER_ENERGY_COST))
+            .map(|(_, tower)| *tower)
+            .collect();
+        let tower_damage = crate::military::damage::total_tower_damage(&towers, pos);
+        let creep_damage: f32 = threat
+            .hostile_creeps
+            .iter()
+            .map(|hostile| match hostile.position.get_range_to(pos) {
+                0..=1 => hostile.melee_dps + hostile.ranged_dps,
+                2..=3 => hostile.ranged_dps,
+                _ => 0.0,
+            })
+            .sum();
+
+        CauseOfDeath::classify(member.current_hits, member.max_hits, tower_damage, creep_damage)
+    }
+}
+
+/// A member the squad lost, and what killed it.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SquadCasualty {
+    pub role: SquadRole,
+    pub cause: CauseOfDeath,
+}
+
 // ─── Squad context ──────────────────────────────────────────────────────────
 
 /// Anti-deadlock: max ticks to wait for stragglers in strict mode before quorum.
@@ -437,6 +583,13 @@ pub struct SquadContext {
     /// per-tick `state`/`focus_target` cannot make alone (both read "in-room, no focus" on the arrival
     /// tick before Phase B2 finds the target and on the post-clear tick after it is gone).
     pub engaged_once: bool,
+    /// Energy spent spawning this squad's members, summed from each registered member's body cost by
+    /// the spawn callback (replacements included).
+    pub energy_invested: u32,
+    /// Members lost in the field, in order of death, with what killed each. Recorded by
+    /// `PreRunSquadUpdateSystem`; the squad manager reads them when the squad is wiped to escalate the
+    /// next wave.
+    pub casualties: Vec<SquadCasualty>,
 }
 
 impl SquadContext {
@@ -463,6 +616,8 @@ impl SquadContext {
             total_members_added: 0,
             objective_id: None,
             engaged_once: false,
+            energy_invested: 0,
+            casualties: Vec::new(),
         }
     }
 
@@ -478,7 +633,6 @@ impl SquadContext {
             max_hits: 0,
             position: None,
             formation_slot,
-            tick_orders: None,
             heal_power: 0,
             damage_taken_last_tick: 0,
         });
@@ -576,144 +730,91 @@ impl SquadContext {
         }
     }
 
-    /// Compute optimal heal assignments for this tick.
+    /// Compute heal assignments for this tick, solved jointly across the squad's healers.
     ///
     /// Algorithm:
-    /// 1. Collect all members that need healing, sorted by urgency.
-    ///    Urgency = damage deficit + predicted incoming damage (from last tick).
-    /// 2. Collect all healers with their heal capacity and position.
-    /// 3. Greedily assign healers to the most urgent targets, preferring
-    ///    adjacent heal (12 HP/part) over ranged heal (4 HP/part).
-    /// 4. Avoid over-healing: once a target's deficit is covered, move on.
-    pub fn compute_heal_assignments(&self, creep_owners: Option<&ReadStorage<'_, CreepOwner>>) -> Vec<HealAssignment> {
-        let mut assignments = Vec::new();
-
-        // Collect healers.
-        let healers: Vec<_> = self.members.iter().filter(|m| m.heal_power > 0 && m.position.is_some()).collect();
+    /// 1. Each member's need is its HP deficit plus the damage projected to land on it this tick —
+    ///    `incoming` at its position (towers / hostiles in reach), or last tick's damage if higher —
+    ///    so healers pre-heal a member under focused fire instead of reacting a tick late.
+    /// 2. Repeatedly pick the free healer → member pair that lands the most heal, capped at the
+    ///    member's remaining need. Adjacent heal (12 HP/part) beats ranged heal (4 HP/part), so a
+    ///    healer only heals at range when it is not adjacent to anyone in need. Ties go to adjacency,
+    ///    then to the healer with the fewest members in reach, then to the neediest member.
+    /// 3. A member whose need is covered gets no further healers, so two healers never double up
+    ///    when one suffices. Healers left with nothing to cover stay unassigned.
+    pub fn compute_heal_assignments<F>(&self, creep_owners: Option<&ReadStorage<'_, CreepOwner>>, incoming: F) -> Vec<HealAssignment>
+    where
+        F: Fn(Position) -> u32,
+    {
+        let healers: Vec<(&SquadMember, Position)> = self
+            .members
+            .iter()
+            .filter(|m| m.heal_power > 0)
+            .filter_map(|m| m.position.map(|pos| (m, pos)))
+            .collect();
 
         if healers.is_empty() {
-            return assignments;
-        }
-
-        // Collect targets needing healing, with their urgency score.
-        struct HealTarget {
-            entity: Entity,
-            creep_id: Option<ObjectId<Creep>>,
-            pos: Position,
-            deficit: u32,
-            predicted_damage: u32,
-            remaining_deficit: u32,
+            return Vec::new();
         }
 
-        let mut targets: Vec<HealTarget> = self
+        // (member, position, remaining need)
+        let mut needs: Vec<(&SquadMember, Position, u32)> = self
             .members
             .iter()
-            .filter(|m| m.max_hits > 0 && m.position.is_some())
-            .filter(|m| m.current_hits < m.max_hits || m.damage_taken_last_tick > 0)
-            .map(|m| {
-                let deficit = m.max_hits - m.current_hits;
-                let predicted = m.damage_taken_last_tick;
-                let creep_id = creep_owners.and_then(|co| co.get(m.entity)).map(|co| co.owner);
-                HealTarget {
-                    entity: m.entity,
-                    creep_id,
-                    pos: m.position.unwrap(),
-                    deficit,
-                    predicted_damage: predicted,
-                    remaining_deficit: deficit + predicted,
-                }
+            .filter(|m| m.max_hits > 0)
+            .filter_map(|m| {
+                let pos = m.position?;
+                let projected = incoming(pos).max(m.damage_taken_last_tick);
+                let need = m.max_hits.saturating_sub(m.current_hits) + projected;
+                (need > 0).then_some((m, pos, need))
             })
             .collect();
 
-        // Sort by urgency: highest remaining deficit first.
-        targets.sort_by_key(|t| std::cmp::Reverse(t.remaining_deficit));
-
-        // Track which healers have been assigned.
-        let mut assigned_healers: Vec<bool> = vec![false; healers.len()];
-
-        for target in targets.iter_mut() {
-            if target.remaining_deficit == 0 {
-                continue;
-            }
+        let heal_at = |healer: &SquadMember, range: u32| match range {
+            0..=1 => Some(healer.heal_power * HEAL_POWER),
+            2..=3 => Some(healer.heal_power * RANGED_HEAL_POWER),
+            _ => None,
+        };
 
-            // Find the best available healer for this target.
-            let mut best_healer_idx: Option<usize> = None;
-            let mut best_heal_amount: u32 = 0;
-            let mut best_ranged = false;
+        let mut free = vec![true; healers.len()];
+        let mut assignments = Vec::new();
 
-            for (i, healer) in healers.iter().enumerate() {
-                if assigned_healers[i] {
-                    continue;
-                }
+        loop {
+            let mut best: Option<((u32, bool, std::cmp::Reverse<usize>, u32), usize, usize)> = None;
 
-                let healer_pos = healer.position.unwrap();
-                let range = healer_pos.get_range_to(target.pos);
+            for (h, (healer, healer_pos)) in healers.iter().enumerate().filter(|(h, _)| free[*h]) {
+                let in_reach = needs
+                    .iter()
+                    .filter(|(_, pos, need)| *need > 0 && healer_pos.get_range_to(*pos) <= 3)
+                    .count();
 
-                let (heal_amount, ranged) = if range <= 1 {
-                    (healer.heal_power * 12, false)
-                } else if range <= 3 {
-                    (healer.heal_power * 4, true)
-                } else {
-                    continue; // Out of range.
-                };
+                for (t, (_, pos, need)) in needs.iter().enumerate().filter(|(_, (_, _, need))| *need > 0) {
+                    let range = healer_pos.get_range_to(*pos);
+                    let Some(heal) = heal_at(healer, range) else {
+                        continue;
+                    };
 
-                // Prefer the healer that provides the most healing.
-                // Break ties by preferring adjacent heal.
-                if heal_amount > best_heal_amount || (heal_amount == best_heal_amount && !ranged && best_ranged) {
-                    best_healer_idx = Some(i);
-                    best_heal_amount = heal_amount;
-                    best_ranged = ranged;
+                    let key = (heal.min(*need), range <= 1, std::cmp::Reverse(in_reach), *need);
+                    if best.is_none_or(|(best_key, _, _)| key > best_key) {
+                        best = Some((key, h, t));
+                    }
                 }
             }
 
-            if let Some(idx) = best_healer_idx {
-                assigned_healers[idx] = true;
-
-                // Cap heal amount to avoid over-healing.
-                let effective_heal = best_heal_amount.min(target.remaining_deficit);
-                target.remaining_deficit = target.remaining_deficit.saturating_sub(effective_heal);
-
-                assignments.push(HealAssignment {
-                    healer: healers[idx].entity,
-                    target: target.entity,
-                    target_id: target.creep_id,
-                    expected_heal: effective_heal,
-                });
-            }
-        }
+            let Some(((expected_heal, ..), h, t)) = best else {
+                break;
+            };
 
-        // Any unassigned healers with heal power should pre-heal the member
-        // taking the most predicted damage (proactive healing).
-        for (i, healer) in healers.iter().enumerate() {
-            if assigned_healers[i] {
-                continue;
-            }
+            free[h] = false;
+            needs[t].2 -= expected_heal;
 
-            // Find the member taking the most predicted damage that isn't fully healed.
-            let best_preemptive = self
-                .members
-                .iter()
-                .filter(|m| m.position.is_some() && m.entity != healer.entity)
-                .filter(|m| {
-                    let healer_pos = healer.position.unwrap();
-                    healer_pos.get_range_to(m.position.unwrap()) <= 3
-                })
-                .max_by_key(|m| m.damage_taken_last_tick);
-
-            if let Some(target) = best_preemptive {
-                if target.damage_taken_last_tick > 0 || target.current_hits < target.max_hits {
-                    let healer_pos = healer.position.unwrap();
-                    let range = healer_pos.get_range_to(target.position.unwrap());
-                    let creep_id = creep_owners.and_then(|co| co.get(target.entity)).map(|co| co.owner);
-
-                    assignments.push(HealAssignment {
-                        healer: healer.entity,
-                        target: target.entity,
-                        target_id: creep_id,
-                        expected_heal: if range > 1 { healer.heal_power * 4 } else { healer.heal_power * 12 },
-                    });
-                }
-            }
+            let target = needs[t].0;
+            assignments.push(HealAssignment {
+                healer: healers[h].0.entity,
+                target: target.entity,
+                target_id: creep_owners.and_then(|co| co.get(target.entity)).map(|co| co.owner),
+                expected_heal,
+            });
         }
 
         assignments
@@ -734,24 +835,6 @@ impl SquadContext {
         *self.heal_priority = lowest.map(|m| m.entity);
     }
 
-    /// Apply computed heal assignments to member tick orders.
-    /// Call after `compute_heal_assignments()` and after tick orders have been
-    /// initialized for all members.
-    pub fn apply_heal_assignments(&mut self, assignments: &[HealAssignment]) {
-        for assignment in assignments {
-            if let Some(member) = self.members.iter_mut().find(|m| m.entity == assignment.healer) {
-                if let Some(ref mut orders) = member.tick_orders {
-                    orders.heal_target = assignment.target_id;
-                } else {
-                    member.tick_orders = Some(TickOrders {
-                        heal_target: assignment.target_id,
-                        ..Default::default()
-                    });
-                }
-            }
-        }
-    }
-
     /// Remove members whose entity is no longer alive.
     pub fn cleanup_dead(&mut self, entities: &specs::Entities) {
         self.members.retain(|m| entities.is_alive(m.entity));
@@ -804,28 +887,40 @@ impl SquadContext {
     /// Issue retreat tick orders for all members.
     /// Members move toward the retreat rally point (or centroid) to stay together,
     /// with heal assignments applied so healers prioritize damaged squad members.
-    pub fn issue_retreat_orders(&mut self, rally_point: Option<Position>, creep_owners: Option<&ReadStorage<'_, CreepOwner>>) {
+    /// `incoming` projects the damage landing on a tile this tick (see `compute_heal_assignments`).
+    pub fn issue_retreat_orders<F>(
+        &mut self,
+        orders: &mut SquadOrders,
+        rally_point: Option<Position>,
+        creep_owners: Option<&ReadStorage<'_, CreepOwner>>,
+        incoming: F,
+    ) where
+        F: Fn(Position) -> u32,
+    {
         let retreat_pos = rally_point.or(self.rally_point).or_else(|| self.compute_retreat_centroid());
 
         // Compute heal assignments for the retreat.
-        let heal_assignments = self.compute_heal_assignments(creep_owners);
+        let heal_assignments = self.compute_heal_assignments(creep_owners, incoming);
 
         // Set movement orders: all members move toward the retreat position.
-        for member in self.members.iter_mut() {
+        for member in self.members.iter() {
             let movement = if let Some(pos) = retreat_pos {
                 TickMovement::MoveTo(pos)
             } else {
                 TickMovement::Flee
             };
 
-            member.tick_orders = Some(TickOrders {
-                movement,
-                ..Default::default()
-            });
+            orders.set(
+                member.entity,
+                TickOrders {
+                    movement,
+                    ..Default::default()
+                },
+            );
         }
 
         // Apply heal assignments on top of movement orders.
-        self.apply_heal_assignments(&heal_assignments);
+        orders.apply_heal_assignments(&heal_assignments);
     }
 
     // ─── Formation management ───────────────────────────────────────────
@@ -951,6 +1046,49 @@ fn direction_delta(dir: Direction) -> (i32, i32) {
 
 // ─── Squad update systems ───────────────────────────────────────────────────
 
+/// Remove dead members (entity deleted or creep gone), recording each one's cause of death. This is
+/// what empties a wiped squad for the manager's wave-wipe check.
+///
+/// Members still physically spawning (`CreepSpawning` but no `CreepOwner` yet) are kept -- removing
+/// them would cause the mission to re-queue the slot and produce duplicate creeps.
+pub fn prune_dead_members(
+    view: &dyn GameView,
+    squad_ctx: &mut SquadContext,
+    entities: &Entities,
+    creep_owners: &ReadStorage<CreepOwner>,
+    creep_spawning: &ReadStorage<CreepSpawning>,
+    mapping: &EntityMappingData,
+    threat_data: &ReadStorage<RoomThreatData>,
+) {
+    let is_live = |m: &SquadMember| {
+        if !entities.is_alive(m.entity) {
+            return false;
+        }
+        if creep_spawning.get(m.entity).is_some() {
+            return true;
+        }
+        creep_owners.get(m.entity).is_some_and(|creep_owner| view.creep_exists(creep_owner.id()))
+    };
+
+    // Members never seen alive (max_hits still 0) died spawning, not in the field.
+    let casualties: Vec<SquadCasualty> = squad_ctx
+        .members
+        .iter()
+        .filter(|m| m.max_hits > 0 && !is_live(m))
+        .map(|m| {
+            let threat = m.position.and_then(|pos| mapping.get_room(&pos.room_name())).and_then(|e| threat_data.get(e));
+
+            SquadCasualty {
+                role: m.role,
+                cause: CauseOfDeath::observe(m, threat),
+            }
+        })
+        .collect();
+
+    squad_ctx.members.retain(|m| is_live(m));
+    squad_ctx.casualties.extend(casualties);
+}
+
 /// Pre-run pass: gather fresh state from live game objects so that missions
 /// see accurate HP, position, and alive status when they compute tick orders.
 ///
@@ -958,7 +1096,8 @@ fn direction_delta(dir: Direction) -> (i32, i32) {
 ///
 /// Responsibilities:
 /// - Clear stale tick orders from the previous tick.
-/// - Mark members as dead when their entity is deleted or creep is gone.
+/// - Mark members as dead when their entity is deleted or creep is gone, recording
+///   each one's cause of death.
 /// - Update `position`, `current_hits`, `max_hits`, `damage_taken_last_tick`.
 /// - Initialize `heal_power` from body parts (once, when first seen alive).
 pub struct PreRunSquadUpdateSystem;
@@ -970,33 +1109,19 @@ impl<'a> System<'a> for PreRunSquadUpdateSystem {
         WriteStorage<'a, SquadContext>,
         ReadStorage<'a, CreepOwner>,
         ReadStorage<'a, CreepSpawning>,
+        Read<'a, EntityMappingData>,
+        ReadStorage<'a, RoomThreatData>,
+        Write<'a, SquadOrders>,
     );
 
-    fn run(&mut self, (entities, mut squad_contexts, creep_owners, creep_spawning): Self::SystemData) {
-        for (_, squad_ctx) in (&entities, &mut squad_contexts).join() {
-            // Clear previous tick's orders so missions start from a clean slate.
-            for member in squad_ctx.members.iter_mut() {
-                member.tick_orders = None;
-            }
+    fn run(&mut self, (entities, mut squad_contexts, creep_owners, creep_spawning, mapping, threat_data, mut orders): Self::SystemData) {
+        // Clear previous tick's orders so the squad manager starts from a clean slate.
+        orders.clear();
 
-            // Remove dead members (entity deleted or creep gone).
-            // Keep members that are still physically spawning (have
-            // CreepSpawning but no CreepOwner yet) -- removing them
-            // would cause the mission to re-queue the slot and produce
-            // duplicate creeps.
-            squad_ctx.members.retain(|m| {
-                if !entities.is_alive(m.entity) {
-                    return false;
-                }
-                if creep_spawning.get(m.entity).is_some() {
-                    return true;
-                }
-                if let Some(creep_owner) = creep_owners.get(m.entity) {
-                    creep_owner.owner.resolve().is_some()
-                } else {
-                    false
-                }
-            });
+        let view = LiveGame;
+
+        for (_, squad_ctx) in (&entities, &mut squad_contexts).join() {
+            prune_dead_members(&view, squad_ctx, &entities, &creep_owners, &creep_spawning, &mapping, &threat_data);
 
             // Update live member state from the game world.
             for member in squad_ctx.members.iter_mut() {
@@ -1097,6 +1222,63 @@ mod tests {
         // squad.rs:282 is literally that expression — and exercised end-to-end by the seam tests.
     }
 
+    /// Healers cover the damage projected to land this tick (not just last tick's), spread across the
+    /// squad instead of doubling up on a member one healer already covers, and heal at range only when
+    /// no one in need is adjacent.
+    #[test]
+    fn heal_assignments_preheal_projected_damage_without_doubling_up() {
+        let heal = BodyType::Sized(CombatBodySpec { heal: 4, ..Default::default() });
+        let ranged = BodyType::Sized(CombatBodySpec { ranged_attack: 4, ..Default::default() });
+        let comp = SquadComposition {
+            label: "Duo".into(),
+            slots: vec![
+                SquadSlot { role: SquadRole::RangedDPS, body_type: ranged },
+                SquadSlot { role: SquadRole::RangedDPS, body_type: ranged },
+                SquadSlot { role: SquadRole::Healer, body_type: heal },
+                SquadSlot { role: SquadRole::Healer, body_type: heal },
+            ],
+            formation_shape: FormationShape::Line,
+            formation_mode: FormationMode::Loose,
+            retreat_threshold: 0.3,
+        };
+        let room: RoomName = "W1N1".parse().unwrap();
+        let at = |x: u8, y: u8| Position::new(RoomCoordinate::new(x).unwrap(), RoomCoordinate::new(y).unwrap(), room);
+
+        let mut world = World::new();
+        let mut ctx = SquadContext::from_composition(&comp);
+        for (slot, role) in [SquadRole::RangedDPS, SquadRole::RangedDPS, SquadRole::Healer, SquadRole::Healer].into_iter().enumerate() {
+            ctx.add_member(world.create_entity().build(), role, slot);
+        }
+        // Tank at full HP standing in tower fire, a lightly scratched member, and two healers.
+        for (member, (pos, hits, heal_power)) in ctx
+            .members
+            .iter_mut()
+            .zip([(at(11, 10), 1000, 0), (at(13, 10), 960, 0), (at(10, 10), 1000, 4), (at(12, 10), 1000, 4)])
+        {
+            member.position = Some(pos);
+            member.current_hits = hits;
+            member.max_hits = 1000;
+            member.heal_power = heal_power;
+        }
+        let (tank, scratched, h1, h2) = (ctx.members[0].entity, ctx.members[1].entity, ctx.members[2].entity, ctx.members[3].entity);
+
+        // 40 projected on the tank: one healer covers it, the other takes the scratched member.
+        let pairs = |assignments: Vec<HealAssignment>| {
+            assignments.iter().map(|a| (a.healer, a.target, a.expected_heal)).collect::<Vec<_>>()
+        };
+        let light = ctx.compute_heal_assignments(None, |p| if p == at(11, 10) { 40 } else { 0 });
+        assert_eq!(pairs(light), vec![(h1, tank, 40), (h2, scratched, 40)]);
+
+        // 300 projected: both healers pre-heal the tank ahead of the scratch.
+        let heavy = ctx.compute_heal_assignments(None, |p| if p == at(11, 10) { 300 } else { 0 });
+        assert_eq!(pairs(heavy), vec![(h1, tank, 48), (h2, tank, 48)]);
+
+        // Nothing projected and the only wounded member out of adjacency: ranged heal from h1.
+        ctx.members[3].position = Some(at(20, 20));
+        let ranged_only = ctx.compute_heal_assignments(None, |_| 0);
+        assert_eq!(pairs(ranged_only), vec![(h1, scratched, 16)]);
+    }
+
     /// L5 (ADR 0026 §9.10): the box formation scales to an N-blob — `count == 4` is the 2×2, larger
     /// force-sized squads fill a compact square with DISTINCT offsets (no member stacks on the anchor as
     /// the old fixed-4 `box_2x2` left them), and the count drives both `from_shape` and the death-degrade
@@ -1179,4 +1361,143 @@ mod tests {
         let front: std::collections::HashSet<usize> = ctx.slots_front_to_back()[..2].iter().copied().collect();
         assert_eq!(front, [0, 1].into_iter().collect(), "default front = low-Y edge");
     }
+
+    /// The `SquadContext` fields ahead of `members`, in order.
+    type SquadContextHead = (
+        Option<FormationLayout>,
+        Option<SquadPath>,
+        FormationMode,
+        FormationMode,
+        u16,
+        u32,
+        Option<Direction>,
+        Option<u32>,
+        Option<SquadTarget>,
+        Option<Position>,
+        SquadState,
+    );
+
+    /// A saved `SquadMember`.
+    #[derive(Serialize, Deserialize)]
+    struct SavedMember {
+        entity: SerializeMarker,
+        role: SquadRole,
+        slot_index: usize,
+        current_hits: u32,
+        max_hits: u32,
+        position: Option<Position>,
+        formation_slot: usize,
+        heal_power: u32,
+        damage_taken_last_tick: u32,
+    }
+
+    /// A saved `SquadMember` from before the split, carrying its orders' movement.
+    #[derive(Serialize, Deserialize)]
+    struct SavedMemberWithOrders {
+        entity: SerializeMarker,
+        role: SquadRole,
+        slot_index: usize,
+        current_hits: u32,
+        max_hits: u32,
+        position: Option<Position>,
+        formation_slot: usize,
+        tick_orders: Option<TickMovement>,
+        heal_power: u32,
+        damage_taken_last_tick: u32,
+    }
+
+    /// A 4-member squad saves without its members' orders, smaller than the shape that carried them, and
+    /// the reloaded squad leaves `SquadOrders` empty.
+    #[test]
+    fn tick_orders_are_not_saved() {
+        use crate::worldformat::{decode_value, encode_value};
+        use bincode::Options;
+        use screeps_combat_decision::bodies::CombatBodySpec;
+        use specs::saveload::MarkedBuilder;
+
+        let mut world = World::new();
+        world.register::<SerializeMarker>();
+        world.insert(SerializeMarkerAllocator::new());
+        let members: Vec<Entity> = (0..4).map(|_| world.create_entity().marked::<SerializeMarker>().build()).collect();
+
+        let ranged = BodyType::Sized(CombatBodySpec {
+            ranged_attack: 4,
+            ..Default::default()
+        });
+        let comp = SquadComposition {
+            label: "Quad Ranged".into(),
+            slots: vec![
+                SquadSlot {
+                    role: SquadRole::RangedDPS,
+                    body_type: ranged
+                };
+                4
+            ],
+            formation_shape: FormationShape::Box2x2,
+            formation_mode: FormationMode::Strict,
+            retreat_threshold: 0.3,
+        };
+        let mut ctx = SquadContext::from_composition(&comp);
+        let mut orders = SquadOrders::default();
+        let rally = Position::new(
+            RoomCoordinate::new(25).unwrap(),
+            RoomCoordinate::new(25).unwrap(),
+            "W5N5".parse().unwrap(),
+        );
+        for (slot, member) in members.iter().enumerate() {
+            ctx.add_member(*member, SquadRole::RangedDPS, slot);
+            orders.set(
+                *member,
+                TickOrders {
+                    movement: TickMovement::MoveTo(rally),
+                    ..Default::default()
+                },
+            );
+        }
+
+        let markers = world.read_storage::<SerializeMarker>();
+        let data = ctx.convert_into(|e| markers.get(e).cloned()).unwrap();
+        let saved = encode_value(&data).unwrap();
+
+        // The same squad with each member carrying its orders' movement, as it was saved before.
+        let options = bincode::DefaultOptions::new();
+        let mut rest = saved.as_slice();
+        options.deserialize_from::<_, SquadContextHead>(&mut rest).unwrap();
+        let head = &saved[..saved.len() - rest.len()];
+        let saved_members: Vec<SavedMember> = options.deserialize_from(&mut rest).unwrap();
+        let legacy_members: Vec<SavedMemberWithOrders> = saved_members
+            .into_iter()
+            .map(|m| SavedMemberWithOrders {
+                entity: m.entity,
+                role: m.role,
+                slot_index: m.slot_index,
+                current_hits: m.current_hits,
+                max_hits: m.max_hits,
+                position: m.position,
+                formation_slot: m.formation_slot,
+                tick_orders: orders.get(members[m.slot_index]).map(|o| o.movement.clone()),
+                heal_power: m.heal_power,
+                damage_taken_last_tick: m.damage_taken_last_tick,
+            })
+            .collect();
+        let mut legacy = head.to_vec();
+        legacy.extend(encode_value(&legacy_members).unwrap());
+        legacy.extend_from_slice(rest);
+
+        assert!(
+            saved.len() < legacy.len(),
+            "orders no longer ride in the save ({} vs {} bytes)",
+            saved.len(),
+            legacy.len()
+        );
+
+        let decoded: <SquadContext as ConvertSaveload<SerializeMarker>>::Data = decode_value(&saved).unwrap();
+        let reloaded = SquadContext::convert_from(decoded, |m| members.iter().copied().find(|e| markers.get(*e) == Some(&m))).unwrap();
+        let reloaded_orders = SquadOrders::default();
+        assert_eq!(reloaded.members.len(), 4);
+        assert!(
+            reloaded.members.iter().all(|m| reloaded_orders.get(m.entity).is_none()),
+            "no order survives a reload"
+        );
+    }
 }
@@ -27,10 +27,15 @@
 //! `SquadCombatJob` fallback (no dangling `SquadContext` — no leak) until the general
 //! `Recall` terminal state (P2.M0) lands.
 
-use super::objective_queue::{CombatObjectiveQueue, EconomicIntel, ObjectiveId, ObjectiveKind, OBJECTIVE_PRIORITY_MEDIUM};
+use super::arrival::{ArrivalEstimate, ArrivalPlan, ARRIVAL_DEVIATION_TICKS};
+use super::boostqueue::{BoostPriority, BoostQueue, BoostRequest};
+use super::composition::{WavePlan, WaveReport};
+use super::objective_queue::{
+    CombatObjectiveQueue, DeployCondition, EconomicIntel, ObjectiveId, ObjectiveKind, ObjectiveOwner, OBJECTIVE_PRIORITY_MEDIUM,
+};
 use screeps_combat_decision::composition::{SquadComposition, SquadSlot};
 use screeps_combat_decision::lifecycle; // P-OBJ #23 / ADR 0027 — the pure reconcile kernel (shared, tested offline)
-use super::squad::{AttackTarget, SquadContext, SquadState, SquadTarget, TickMovement, TickOrders};
+use super::squad::{AttackTarget, SquadContext, SquadOrders, SquadState, SquadTarget, TickMovement, TickOrders};
 use crate::combat::kite::{PositionLayers, ThreatField, MAX_KITE_OPS};
 use crate::combat::{
     build_room_layers, build_room_threat_field, decide_squad_with_pathing, CombatCreepDto, CombatStructureDto,
@@ -42,11 +47,13 @@ use crate::combat::strategy::{decide_strategy, default_strategies, CombatObjecti
 use std::collections::HashMap;
 use crate::creep::{spawning, CreepOwner};
 use crate::entitymappingsystem::EntityMappingData;
+use crate::gameview::{GameView, LiveGame};
 use crate::jobs::squad_combat::{creep_to_dto, structure_to_dto};
 use crate::room::data::RoomData;
 use crate::room::visibilitysystem::{VisibilityQueue, VisibilityRequest, VisibilityRequestFlags, VISIBILITY_PRIORITY_HIGH};
 use crate::serialize::SerializeMarker;
 use crate::spawnsystem::*;
+use crate::structureidentifier::find_known_structures;
 use screeps::*;
 use screeps_rover::{CostMatrixCache, CostMatrixOptions, CostMatrixSystem};
 use specs::prelude::*;
@@ -118,6 +125,38 @@ pub struct SquadFormingProgress {
     /// "stalled" (the old single MIN signal) and one moving lead can't mask a stuck bulk. Ephemeral (NOT
     /// serialized — no WFV bump). Cleared on retire.
     member_target_dist: std::collections::BTreeMap<(ObjectiveId, u32), u32>,
+    /// objective id → the drain squad's posts and the tick it first took them. Stamped by Phase B once a
+    /// `Drain`-mode squad has engaged; Phase A reads the clock to retire the squad as a clean win after
+    /// `DRAIN_DURATION_TICKS` (drain success is time-based, not kill-based). Ephemeral (NOT serialized — no
+    /// WFV bump): on a VM reload the clock restarts. Cleared on retire/reassign.
+    drain: std::collections::BTreeMap<ObjectiveId, DrainProgress>,
+    /// objective id → a harass squad's place in its patrol rotation and what it last saw. Stamped by Phase
+    /// B2 once the squad has left home; Phase A reads its presence to hold the lease while patrolling.
+    /// Ephemeral (NOT serialized — no WFV bump): on a VM reload the rotation restarts at the first room.
+    /// Cleared on retire/reassign.
+    harass: std::collections::BTreeMap<ObjectiveId, HarassProgress>,
+    /// objective id → the hauler a caravan escort is following. Stamped by Phase B2 once the escort has left
+    /// home; Phase A reads its presence to hold the lease while escorting. Ephemeral (NOT serialized — no WFV
+    /// bump): on a VM reload the escort picks its hauler afresh. Cleared on retire/reassign.
+    escort: std::collections::BTreeMap<ObjectiveId, Entity>,
+}
+
+/// A drain squad's posts (one per member, best first) and when it first took them.
+struct DrainProgress {
+    started_at: u32,
+    posts: Vec<crate::military::drain::DrainPost>,
+}
+
+/// A harass squad's patrol rotation plus last tick's observations, for the per-room kills/losses tally.
+struct HarassProgress {
+    /// Index into the objective's patrol rooms.
+    room_index: usize,
+    /// Tick the squad started patrolling the current room.
+    since: u32,
+    /// Present members last tick; a drop is a loss charged to the room being patrolled.
+    last_members: usize,
+    /// Last tick's focus creep and where it stood; its vanishing from a visible room is a kill.
+    last_target: Option<(RawObjectId, Position)>,
 }
 
 /// ADR 0034 D8 (RC-8): the TIGHTER per-member solo-travel stall window — consecutive ticks a member makes no
@@ -208,6 +247,10 @@ const MAX_FORMING_BUDGET: u32 = 3000;
 /// multi-room hop (MAX_SPAWN_DISTANCE=10 rooms ≈ 500 tiles) with margin.
 const MAX_TRAVEL_BUDGET: u32 = 1000;
 
+/// A reinforcing squad (fell back from a losing fight to refill its dead slots) re-rallies only once its
+/// average HP is back above this fraction, so it does not walk straight back into the same fight half-dead.
+const REFORM_HP_FRACTION: f32 = 0.9;
+
 /// Chebyshev distance between two rooms.
 fn room_distance(a: RoomName, b: RoomName) -> u32 {
     let delta = a - b;
@@ -279,6 +322,19 @@ fn squad_is_wiped(total_members_added: u32, living_members: usize) -> bool {
     total_members_added > 0 && living_members == 0
 }
 
+/// A squad that fell back from a losing fight with dead slots (`Reinforcing`) rejoins the assault once the
+/// FULL roster — the survivors plus the refilled slots — is present and gathered within
+/// `RALLY_GATHER_RADIUS` of the reinforcement rally, and the squad's average HP is back above
+/// [`REFORM_HP_FRACTION`]. Pure so it's host-testable without an ECS world.
+fn squad_reformed(positions: &[Option<Position>], rally: Position, requested_slots: usize, avg_hp: f32) -> bool {
+    let gathered = positions
+        .iter()
+        .flatten()
+        .filter(|p| p.get_range_to(rally) <= screeps_combat_decision::rally::RALLY_GATHER_RADIUS)
+        .count();
+    requested_slots > 0 && gathered >= requested_slots && avg_hp >= REFORM_HP_FRACTION
+}
+
 /// FIX 2 (rally-stall): classify whether a squad is still FORMING its roster and whether it made spawn
 /// PROGRESS since the previous reconcile. Pure so it's host-testable without an ECS world.
 ///
@@ -354,7 +410,8 @@ fn capability_class(kind: &ObjectiveKind) -> CapabilityClass {
     match kind {
         // The threat-centric defense arm (ADR 0027 Option B): `Secure` is how defense is now emitted (at the
         // threat's room), alongside the optional preemptive `Defend` hold.
-        ObjectiveKind::Defend { .. } | ObjectiveKind::Secure { .. } => CapabilityClass::Defense,
+        // A caravan escort protects our own haulers.
+        ObjectiveKind::Defend { .. } | ObjectiveKind::Secure { .. } | ObjectiveKind::Caravan { .. } => CapabilityClass::Defense,
         ObjectiveKind::Harass { .. } | ObjectiveKind::Dismantle { .. } | ObjectiveKind::Farm { .. } | ObjectiveKind::Escort { .. } => {
             CapabilityClass::Offense
         }
@@ -424,7 +481,9 @@ fn project_value_kind(kind: &ObjectiveKind, economic: Option<EconomicIntel>) ->
         return ObjectiveValueKind::FarmCore;
     }
     match kind {
-        ObjectiveKind::Defend { .. } | ObjectiveKind::Secure { .. } | ObjectiveKind::Escort { .. } => ObjectiveValueKind::Defend,
+        ObjectiveKind::Defend { .. } | ObjectiveKind::Secure { .. } | ObjectiveKind::Escort { .. } | ObjectiveKind::Caravan { .. } => {
+            ObjectiveValueKind::Defend
+        }
         ObjectiveKind::Farm { kind: FarmKind::Core, .. } => ObjectiveValueKind::FarmCore,
         ObjectiveKind::Farm { kind: FarmKind::SourceKeeper, .. } => ObjectiveValueKind::FarmSourceKeeper,
         ObjectiveKind::Farm { kind: FarmKind::PowerBank, .. } => ObjectiveValueKind::FarmPowerBank,
@@ -918,6 +977,11 @@ fn objective_target(kind: &ObjectiveKind) -> (SquadTarget, RoomName) {
         ObjectiveKind::Dismantle { room, pos } => (SquadTarget::AttackStructure { position: *pos }, *room),
         // ADR 0027 v1.1 P2: a declaim squad travels to the room and `attackController`s the controller tile.
         ObjectiveKind::Declaim { room, controller } => (SquadTarget::AttackController { position: *controller }, *room),
+        // A caravan escort heads for the haul mission's pickup room; once out it follows the haulers instead.
+        ObjectiveKind::Caravan { room } => {
+            let position = Position::new(RoomCoordinate::new(25).unwrap(), RoomCoordinate::new(25).unwrap(), *room);
+            (SquadTarget::EscortPosition { position }, *room)
+        }
         // Secure / Farm / Escort all reduce to "go to the room and clear it";
         // the SquadCombatJob self-drives there and engages whatever is hostile.
         ObjectiveKind::Secure { room } | ObjectiveKind::Farm { room, .. } | ObjectiveKind::Escort { room } => {
@@ -954,6 +1018,8 @@ fn create_spawn_callback(
     slot_index: usize,
     target_room: RoomName,
     squad_entity: Entity,
+    body_cost: u32,
+    boosts: Vec<ResourceType>,
 ) -> SpawnQueueCallback {
     Box::new(move |system_data, name| {
         let name = name.to_string();
@@ -982,10 +1048,9 @@ fn create_spawn_callback(
             // carries the zero-orphan recall machinery (ADR 0027 §(d)) — and THEN decide registration. A creep
             // we do NOT register (squad dead, or its slot already filled by a merge transfer) is a surplus that
             // must still be cleaned up: its job recalls it home to recycle rather than orphaning it in-world.
-            let creep_job = crate::jobs::data::JobData::SquadCombat(crate::jobs::squad_combat::SquadCombatJob::new_with_squad(
-                target_room,
-                squad_entity,
-            ));
+            let creep_job = crate::jobs::data::JobData::SquadCombat(
+                crate::jobs::squad_combat::SquadCombatJob::new_with_squad(target_room, squad_entity).with_boosts(boosts),
+            );
             let creep_entity = spawning::build(world.create_entity(), &name).with(creep_job).build();
 
             if !should_register_spawned_member(squad_alive, slot_already_filled) {
@@ -1002,6 +1067,7 @@ fn create_spawn_callback(
 
             if let Some(squad_ctx) = world.write_storage::<SquadContext>().get_mut(squad_entity) {
                 squad_ctx.add_member(creep_entity, role, slot_index);
+                squad_ctx.energy_invested = squad_ctx.energy_invested.saturating_add(body_cost);
             } else {
                 log::warn!(
                     "[SquadManager] Spawn callback: SquadContext missing for {:?}, creep {} (slot {}) not registered",
@@ -1023,15 +1089,22 @@ pub struct SquadManagerSystemData<'a> {
     objective_queue: Write<'a, CombatObjectiveQueue>,
     forming_progress: Write<'a, SquadFormingProgress>,
     squad_contexts: WriteStorage<'a, SquadContext>,
+    squad_orders: Write<'a, SquadOrders>,
     spawn_queue: Write<'a, SpawnQueue>,
     room_data: ReadStorage<'a, RoomData>,
     // ADR 0032 v1.1: the per-room scouted intel the EV-of-pairing helper reads (threat danger → value_e for a
     // defense objective; towers/dps/safe-mode → the `DefenseProfile` P(win) judges against). Read-only.
     threat_data: ReadStorage<'a, crate::military::threatmap::RoomThreatData>,
+    // A home in lockdown spawns its defenders at critical priority (`military::posture`).
+    defense_postures: ReadStorage<'a, crate::military::posture::DefensePosture>,
     mapping: Read<'a, EntityMappingData>,
     creep_owner: ReadStorage<'a, CreepOwner>,
     visibility: Write<'a, VisibilityQueue>,
     features: Read<'a, crate::features::Features>,
+    economy: Read<'a, crate::military::economy::EconomySnapshot>,
+    boost_queue: Write<'a, BoostQueue>,
+    pathfinder: Write<'a, crate::pathing::pathfinderservice::PathfinderService>,
+    squad_pathing: Write<'a, crate::military::formation::SquadPathing>,
 }
 
 /// A home room that can act as a spawn source for a squad.
@@ -1039,6 +1112,8 @@ struct HomeRoom {
     entity: Entity,
     name: RoomName,
     energy_capacity: u32,
+    /// Our spawns in the room.
+    spawns: u32,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -1046,7 +1121,8 @@ impl<'a> System<'a> for SquadManagerSystem {
     type SystemData = SquadManagerSystemData<'a>;
 
     fn run(&mut self, mut data: Self::SystemData) {
-        let now = game::time();
+        let view = LiveGame;
+        let now = view.time();
         // P-OBJ #23 lifecycle introspection: reuse the war debug flag for low-noise, per-event squad/
         // objective lifecycle logs (field / reach / engage / retire-reason) so a live capture pinpoints
         // WHICH stage a squad fails at, instead of guessing from Docker.
@@ -1061,10 +1137,11 @@ impl<'a> System<'a> for SquadManagerSystem {
                     return None;
                 }
                 let structures = rd.get_structures()?;
-                if structures.spawns().iter().all(|s| !s.my()) {
+                let spawns = structures.spawns().iter().filter(|s| s.my()).count() as u32;
+                if spawns == 0 {
                     return None;
                 }
-                let energy_capacity = game::rooms().get(rd.name).map(|r| r.energy_capacity_available()).unwrap_or(0);
+                let energy_capacity = view.room_energy(rd.name).map(|e| e.capacity).unwrap_or(0);
                 if energy_capacity == 0 {
                     return None;
                 }
@@ -1072,6 +1149,7 @@ impl<'a> System<'a> for SquadManagerSystem {
                     entity,
                     name: rd.name,
                     energy_capacity,
+                    spawns,
                 })
             })
             .collect();
@@ -1120,7 +1198,28 @@ impl<'a> System<'a> for SquadManagerSystem {
                 .objective_queue
                 .get(obj_id)
                 .map(|o| (o.kind.room(), matches!(o.kind, ObjectiveKind::Defend { .. }), o.deadline, capability_class(&o.kind)));
-            let objective_gone = obj_info.is_none();
+            // Drain squads win on time, not kills: once the posts have been held for `DRAIN_DURATION_TICKS`
+            // withdraw the objective as a clean win (and clear any give-up backoff on the room), so the kernel
+            // sees it gone and reassigns or retires the squad like any other resolved objective.
+            let drain_done = obj_info.is_some()
+                && data
+                    .forming_progress
+                    .drain
+                    .get(&obj_id)
+                    .is_some_and(|d| now.saturating_sub(d.started_at) >= crate::military::drain::DRAIN_DURATION_TICKS);
+            if drain_done {
+                data.objective_queue.withdraw(obj_id);
+                if let Some((room, _, _, _)) = obj_info {
+                    data.objective_queue.clear_unwinnable(room);
+                }
+                if debug {
+                    log::info!(
+                        "[Lifecycle] DRAIN-COMPLETE squad={:?} obj={:?} (held the drain posts for the full duration)",
+                        squad_entity, obj_id
+                    );
+                }
+            }
+            let objective_gone = obj_info.is_none() || drain_done;
             let squad_room = obj_info.map(|(r, _, _, _)| r);
             let is_defend = obj_info.map(|(_, d, _, _)| d).unwrap_or(false);
             let cur_class = obj_info.map(|(_, _, _, c)| c);
@@ -1205,12 +1304,43 @@ impl<'a> System<'a> for SquadManagerSystem {
             let forming_started_at = *data.forming_progress.forming_started_at.entry(obj_id).or_insert(now);
             let forming_budget_remaining = now.saturating_sub(forming_started_at) < MAX_FORMING_BUDGET;
 
+            // A REINFORCING squad (pulled back from a losing fight to refill its dead slots and heal) holds its
+            // lease on the forming clock Phase B restarted when it fell back — it has no focus and may already
+            // be at full roster while it heals, so neither the forming nor the travel lease covers it. Past
+            // MAX_FORMING_BUDGET the hold stops and the kernel gives up as usual.
+            let reinforcing = data.squad_contexts.get(squad_entity).is_some_and(|c| c.state == SquadState::Reinforcing);
+            let reinforce_hold = reinforcing && !objective_gone && forming_budget_remaining;
+            let deadline_lapsed = deadline_lapsed && !reinforce_hold;
+
             // ── Deep-reach fix (Break #2 travel half, the travel-lease): a FULL-ROSTER squad that has departed
             // home but not yet engaged / arrived is TRAVELING — it has no focus and is not forming, so the
             // base lease lapses mid-hop (the W7N7 1-slot lapse). Refresh while it is closing distance on the
             // target room (positional progress), BOUNDED by an absolute travel clock from the departure tick.
             let full_roster = requested_slots_for_form > 0 && present_count >= requested_slots_for_form;
             let traveling = full_roster && !engaged_once && !in_target_room && has_members;
+            // The first tick the squad is in the target room, measure it against the arrival plan.
+            if in_target_room && has_members {
+                if let Some(plan) = data
+                    .objective_queue
+                    .arrival_plan_mut(obj_id)
+                    .filter(|plan| plan.arrived_at.is_none())
+                {
+                    plan.arrived_at = Some(now);
+                    if let (Some(deviation), Some(expected)) = (plan.deviation(0, now), plan.expected_arrival(0)) {
+                        if deviation.unsigned_abs() > ARRIVAL_DEVIATION_TICKS as u64 {
+                            log::warn!(
+                                "[Arrival] squad={:?} obj={:?} arrived at {} ({} ticks {} the planned {})",
+                                squad_entity,
+                                obj_id,
+                                now,
+                                deviation.unsigned_abs(),
+                                if deviation > 0 { "after" } else { "before" },
+                                expected
+                            );
+                        }
+                    }
+                }
+            }
             let departed_at = if traveling {
                 *data.forming_progress.departed_at.entry(obj_id).or_insert(now)
             } else {
@@ -1281,7 +1411,13 @@ impl<'a> System<'a> for SquadManagerSystem {
                 // FIX B2: a Defend squad garrisoning its CLEAR owned room (arrived, no in-room focus) holds
                 // its lease while the Defend objective persists, instead of GaveUp+refield (Gen churn). The
                 // owned-room threat roams a NEIGHBOUR room, so the owned room itself shows no in-room focus.
-                holding_station: is_defend && in_target_room && !has_focus,
+                // A harass squad out on patrol roams rooms other than its objective room with no standing
+                // focus; it holds until the war operation withdraws the objective (all remotes defended).
+                // A caravan escort follows its hauler wherever it walks and holds the same way until the haul
+                // mission's request lapses.
+                holding_station: (is_defend && in_target_room && !has_focus)
+                    || (has_members && data.forming_progress.harass.contains_key(&obj_id))
+                    || (has_members && data.forming_progress.escort.contains_key(&obj_id)),
                 // ADR 0027 v1.1 P2: an in-room declaimer is HOLDING (striking on the 1000-tick cadence), so
                 // refresh its lease + block the false Resolve while it neutralizes the controller. Bounded by
                 // the objective lifecycle: the producer withdraws on controller-neutral / re-arm → objective_gone.
@@ -1302,9 +1438,10 @@ impl<'a> System<'a> for SquadManagerSystem {
             let action = lifecycle::reconcile(snapshot);
             if let lifecycle::ReconcileAction::Retire { reason, withdraw, mark_unwinnable } = action {
                 if debug {
+                    let invested = data.squad_contexts.get(squad_entity).map(|c| c.energy_invested).unwrap_or(0);
                     log::info!(
-                        "[Lifecycle] RETIRE squad={:?} obj={:?} reason={:?} engaged_once={} in_room={} focus={} deadline_lapsed={} members={}",
-                        squad_entity, obj_id, reason, engaged_once, in_target_room, has_focus, deadline_lapsed, has_members
+                        "[Lifecycle] RETIRE squad={:?} obj={:?} reason={:?} engaged_once={} in_room={} focus={} deadline_lapsed={} members={} invested={}",
+                        squad_entity, obj_id, reason, engaged_once, in_target_room, has_focus, deadline_lapsed, has_members, invested
                     );
                     // GIVE-UP BREAKDOWN (introspection only): spell out WHICH bound tripped + the raw clock
                     // values so a `reason=GaveUp` is self-explaining (deadline lapse vs forming-budget vs
@@ -1344,6 +1481,11 @@ impl<'a> System<'a> for SquadManagerSystem {
                         data.objective_queue.mark_unwinnable(room, now);
                     }
                 }
+                if wiped && !is_defend {
+                    if let Some(room) = squad_room {
+                        record_wave_wipe(&mut data, squad_entity, obj_id, room, debug);
+                    }
+                }
                 retire_squad(&data.updater, &data.entities, squad_entity);
                 data.objective_queue.release_entity(squad_entity);
                 // Drop ALL per-objective lifecycle trackers so a RE-FIELD (new generation claiming the same
@@ -1364,6 +1506,10 @@ impl<'a> System<'a> for SquadManagerSystem {
                 // ADR 0034 D4/D5/D8: clear the per-member rally/target distance + solo-stall trackers so a
                 // RE-FIELD re-derives them (a new generation's members must not inherit a stale block streak).
                 clear_member_trackers(&mut data.forming_progress, obj_id);
+                // A re-field drains for the full duration again, from freshly picked posts.
+                data.forming_progress.drain.remove(&obj_id);
+                data.forming_progress.harass.remove(&obj_id);
+                data.forming_progress.escort.remove(&obj_id);
                 continue;
             }
             // ── ADR 0027 v1 (whole-squad REASSIGN): a non-loss terminal (Resolved/ObjectiveGone) with a
@@ -1421,6 +1567,9 @@ impl<'a> System<'a> for SquadManagerSystem {
                 // ADR 0034 D4/D5/D8: a reassigned squad gets fresh per-member rally/target/stall trackers at
                 // the new target (the old block streak is meaningless against the new rally corridor).
                 clear_member_trackers(&mut data.forming_progress, obj_id);
+                data.forming_progress.drain.remove(&obj_id);
+                data.forming_progress.harass.remove(&obj_id);
+                data.forming_progress.escort.remove(&obj_id);
                 data.forming_progress.forming_started_at.insert(new_id, now);
                 data.forming_progress.last_present.insert(new_id, 0);
                 if debug {
@@ -1437,7 +1586,7 @@ impl<'a> System<'a> for SquadManagerSystem {
             // actively engaging (a long fight / vision gap) AND while a FORMING squad is still making spawn
             // progress (FIX 2 — so a squad assembling its roster is not retired mid-form → re-field churn).
             data.objective_queue.claim(obj_id, squad_entity);
-            if action == lifecycle::ReconcileAction::KeepRefreshLease {
+            if action == lifecycle::ReconcileAction::KeepRefreshLease || reinforce_hold {
                 data.objective_queue.set_deadline(obj_id, Some(now + COMMITMENT_BUDGET));
             }
             // Intel coverage: keep eyes on a committed objective's room so its intel never goes stale
@@ -1456,11 +1605,37 @@ impl<'a> System<'a> for SquadManagerSystem {
             // Read the composition off the objective each tick (the producer owns it).
             let (slots, target_room, spawn_priority) = match data.objective_queue.get(*obj_id) {
                 Some(obj) => match obj.force.squads.first() {
-                    Some(comp) => (comp.slots.clone(), objective_target(&obj.kind).1, spawn_priority_for(obj.priority)),
+                    Some(comp) => {
+                        let target_room = objective_target(&obj.kind).1;
+                        let mut spawn_priority = spawn_priority_for(obj.priority);
+                        if obj.owner == ObjectiveOwner::Defense {
+                            if let Some(posture) = data.mapping.get_room(&target_room).and_then(|e| data.defense_postures.get(e)) {
+                                spawn_priority = posture.defender_spawn_priority(spawn_priority);
+                            }
+                        }
+                        (comp.slots.clone(), target_room, spawn_priority)
+                    }
                     None => continue,
                 },
                 None => continue,
             };
+            // A boosted defense takes its boosts from the defended room's labs, so its members spawn there
+            // when the room can spawn at all.
+            let boosts: Vec<ResourceType> = data.objective_queue.boost_plan(*obj_id).iter().map(|(compound, _)| *compound).collect();
+            // A squad staggered behind the rest of its force holds its spawns until its planned start.
+            if data
+                .objective_queue
+                .arrival_plan(*obj_id)
+                .is_some_and(|plan| now < plan.start_at(0))
+            {
+                continue;
+            }
+            let boost_homes: Vec<&HomeRoom> = homes.iter().filter(|h| h.name == target_room).collect();
+            let spawn_homes: Vec<&HomeRoom> = if boosts.is_empty() || boost_homes.is_empty() {
+                homes.iter().collect()
+            } else {
+                boost_homes
+            };
 
             // FIGHTER-FIRST spawn order (deep-reach fix — Break #1): attempt the FIGHTER slots
             // (RangedDPS / Dismantler / MeleeDPS) BEFORE the Healer / Tank / Hauler slots, so a roster that
@@ -1479,7 +1654,17 @@ impl<'a> System<'a> for SquadManagerSystem {
                 if already_filled {
                     continue;
                 }
-                queue_slot_spawn(&mut data.spawn_queue, &homes, slot, slot_index, target_room, *squad_entity, spawn_priority, debug);
+                queue_slot_spawn(
+                    &mut data.spawn_queue,
+                    &spawn_homes,
+                    slot,
+                    slot_index,
+                    target_room,
+                    *squad_entity,
+                    &boosts,
+                    spawn_priority,
+                    debug,
+                );
             }
         }
 
@@ -1531,6 +1716,40 @@ impl<'a> System<'a> for SquadManagerSystem {
             }
         }
 
+        // ── Phase B-boost: ask the labs for the boosts a boosted defense's (or escalated attack's) members still lack. ──
+        // Re-posted every tick from the live bodies while the squad is still at home (forming, rallying or
+        // reinforcing); the labs mission serves last tick's requests, so clearing here drops fulfilled ones.
+        data.boost_queue.clear();
+        for (squad_entity, obj_id) in &live_managed {
+            let plan = data.objective_queue.boost_plan(*obj_id);
+            if plan.is_empty() {
+                continue;
+            }
+            let Some(ctx) = data.squad_contexts.get(*squad_entity) else {
+                continue;
+            };
+            if !matches!(ctx.state, SquadState::Forming | SquadState::Rallying | SquadState::Reinforcing) {
+                continue;
+            }
+            for member in &ctx.members {
+                let Some(creep) = data.creep_owner.get(member.entity).and_then(|co| co.owner.resolve()) else {
+                    continue;
+                };
+                let room = creep.pos().room_name();
+                let boosts = crate::military::damage::DEFENDER_BOOSTS.into_iter().chain(super::composition::ASSAULT_BOOSTS);
+                for (part, compound) in boosts {
+                    if !plan.iter().any(|(c, _)| *c == compound) {
+                        continue;
+                    }
+                    let unboosted = creep.body().iter().filter(|p| p.part() == part && p.boost().is_none()).count() as u32;
+                    if unboosted > 0 {
+                        data.boost_queue
+                            .request(BoostRequest::new(member.entity, compound, unboosted, room, BoostPriority::Critical));
+                    }
+                }
+            }
+        }
+
         // ── Phase B2: compute per-squad tactical orders. ──
         // The *tactics* are the pure `decide_squad` (focus + engage/retreat hysteresis,
         // ADR 0008 §4 / P2.G3) — the SAME code the sim runs. The manager is only the
@@ -1555,10 +1774,27 @@ impl<'a> System<'a> for SquadManagerSystem {
             // it to the ephemeral runtime entry). `Some(Drain)` → the drive fires the `DrainBreach` strategy +
             // sets the squad's drain stance; `None`/`Some(Breach)` → the byte-unchanged direct breach/engage.
             let assault_mode = data.objective_queue.assault_mode(*obj_id);
+            // A harass squad fights wherever its patrol has taken it, not in the objective's anchor room.
+            let patrol_rooms = data.objective_queue.patrol_rooms(*obj_id).to_vec();
+            let target_room = match data.forming_progress.harass.get(obj_id) {
+                Some(progress) if !patrol_rooms.is_empty() => patrol_rooms[progress.room_index % patrol_rooms.len()],
+                _ => target_room,
+            };
+            // A caravan escort fights wherever the hauler it follows has got to.
+            let caravan = data.objective_queue.caravan(*obj_id).cloned();
+            let target_room = data
+                .forming_progress
+                .escort
+                .get(obj_id)
+                .and_then(|hauler| data.creep_owner.get(*hauler))
+                .and_then(|owner| owner.owner.resolve())
+                .map(|hauler| hauler.pos().room_name())
+                .unwrap_or(target_room);
             compute_squad_orders(
                 &data.room_data,
                 &data.mapping,
                 &mut data.squad_contexts,
+                &mut data.squad_orders,
                 &data.creep_owner,
                 *squad_entity,
                 *obj_id,
@@ -1571,7 +1807,62 @@ impl<'a> System<'a> for SquadManagerSystem {
                 now,
                 deadline,
                 &mut data.forming_progress,
+                &mut data.pathfinder,
+                &mut data.squad_pathing,
             );
+            // A defender fielded ahead of a predicted inbound group waits on its staging tile until the group
+            // shows up (the decision then has a focus and its own orders stand).
+            if let Some(stage) = data.objective_queue.stage_position(*obj_id) {
+                if let Some(ctx) = data.squad_contexts.get_mut(*squad_entity) {
+                    if matches!(ctx.state, SquadState::Moving | SquadState::Engaged) && ctx.focus_target.is_none() {
+                        for member in ctx.members.iter() {
+                            data.squad_orders.entry(member.entity).movement = TickMovement::MoveTo(stage);
+                        }
+                    }
+                }
+            }
+            // A defense squad drops everything else for a claimer in its room (`military::interceptor`).
+            let defense = data
+                .objective_queue
+                .get(*obj_id)
+                .is_some_and(|o| o.owner == ObjectiveOwner::Defense);
+            if defense {
+                apply_claimer_focus(
+                    &data.room_data,
+                    &data.mapping,
+                    &mut data.squad_contexts,
+                    &mut data.squad_orders,
+                    *squad_entity,
+                    target_room,
+                );
+            }
+            if !patrol_rooms.is_empty() {
+                apply_harass_orders(
+                    &data.room_data,
+                    &data.mapping,
+                    &mut data.squad_contexts,
+                    &mut data.squad_orders,
+                    &data.creep_owner,
+                    &mut data.objective_queue,
+                    *squad_entity,
+                    *obj_id,
+                    &patrol_rooms,
+                    now,
+                    debug,
+                    &mut data.forming_progress,
+                );
+            }
+            if let Some(caravan) = caravan {
+                apply_escort_orders(
+                    &mut data.squad_contexts,
+                    &mut data.squad_orders,
+                    &data.creep_owner,
+                    *squad_entity,
+                    *obj_id,
+                    &caravan,
+                    &mut data.forming_progress,
+                );
+            }
         }
 
         // ── Phase C: claim new objectives up to the global cap. ──
@@ -1646,6 +1937,9 @@ impl<'a> System<'a> for SquadManagerSystem {
         ranked_claims.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0 .0.cmp(&b.0 .0)));
 
         let mut claim_iter = ranked_claims.into_iter();
+        // Energy the squads fielded earlier this tick asked their homes to hold, so two energy-gated fields in
+        // one tick can't both pass on the same stored energy.
+        let mut energy_committed: u32 = 0;
         while active < MAX_CONCURRENT_SQUADS && forming < MAX_FORMING_SQUADS {
             let Some((obj_id, _ev_q)) = claim_iter.next() else {
                 break; // ran out of EV-positive claimable objectives
@@ -1672,16 +1966,73 @@ impl<'a> System<'a> for SquadManagerSystem {
                 continue;
             }
 
+            // Deploy condition: an energy-gated objective waits until its in-range homes hold its spawn cost on
+            // top of what this tick's earlier fields already committed. Unclaimed, it is re-ranked next tick.
+            let condition = data.objective_queue.deploy_condition(obj_id);
+            if let Err(available) = check_deploy_condition(condition, &data.economy, &homes, target.1, energy_committed) {
+                if debug {
+                    log::info!(
+                        "[Lifecycle] SKIP obj={:?} room={} reason=deploy_condition ({:?}, available={})",
+                        obj_id, target.1, condition, available
+                    );
+                }
+                continue;
+            }
+            energy_committed = energy_committed.saturating_add(condition.energy_required());
+
             if debug {
                 log::info!("[Lifecycle] FIELD obj={:?} room={} members={}", obj_id, target.1, composition.member_count());
             }
             field_new_squad(&data.updater, &data.entities, &mut data.objective_queue, obj_id, &composition, target, now);
+            // Plan when each squad of the force arrives. The lead squad's forming clock starts at its
+            // staggered spawn start, so a held-back lead doesn't burn its forming budget waiting.
+            let estimates: Vec<ArrivalEstimate> = data
+                .objective_queue
+                .get(obj_id)
+                .map(|obj| {
+                    obj.force
+                        .squads
+                        .iter()
+                        .map(|squad| estimate_arrival(squad, &homes, target.1))
+                        .collect()
+                })
+                .unwrap_or_default();
+            let plan = ArrivalPlan::new(now, &estimates, data.features.military.wave_window_ticks);
+            data.forming_progress.forming_started_at.insert(obj_id, plan.start_at(0));
+            if debug {
+                log::info!("[Lifecycle] ARRIVAL obj={:?} room={} plan={:?}", obj_id, target.1, plan.describe());
+            }
+            data.objective_queue.set_arrival_plan(obj_id, plan);
             active += 1;
             forming += 1; // the newly-claimed squad starts forming (slot 0 spawns next tick)
         }
     }
 }
 
+/// Evaluate an objective's deploy condition against the stored energy of the homes in spawn range of
+/// `target_room`, less what this tick's earlier fields already `committed`. `Err` carries the energy that
+/// was available, for the skip log.
+fn check_deploy_condition(
+    condition: DeployCondition,
+    economy: &crate::military::economy::EconomySnapshot,
+    homes: &[HomeRoom],
+    target_room: RoomName,
+    committed: u32,
+) -> Result<(), u32> {
+    let in_range_homes: Vec<Entity> = homes
+        .iter()
+        .filter(|h| room_distance(h.name, target_room) <= MAX_SPAWN_DISTANCE)
+        .map(|h| h.entity)
+        .collect();
+    let available = economy.rooms_stored_energy(&in_range_homes).saturating_sub(committed);
+
+    if condition.is_met(available) {
+        Ok(())
+    } else {
+        Err(available)
+    }
+}
+
 /// Delete a squad entity (retire). Orphaned members detach via the job fallback.
 fn retire_squad(updater: &Read<LazyUpdate>, entities: &Entities, squad_entity: Entity) {
     if entities.is_alive(squad_entity) {
@@ -1693,6 +2044,40 @@ fn retire_squad(updater: &Read<LazyUpdate>, entities: &Entities, squad_entity: E
     }
 }
 
+/// File a wiped attack wave against its room: the plan it fielded, the energized towers and healers it ran
+/// into, and what killed each member. The war operation escalates the room's next wave from these.
+fn record_wave_wipe(data: &mut SquadManagerSystemData, squad_entity: Entity, obj_id: ObjectiveId, room: RoomName, debug: bool) {
+    let Some(objective) = data.objective_queue.get(obj_id).filter(|o| o.owner == ObjectiveOwner::Attack) else {
+        return;
+    };
+    let drain = data
+        .objective_queue
+        .assault_mode(obj_id)
+        .is_some_and(|mode| matches!(mode, screeps_combat_decision::force_sizing::AssaultMode::Drain));
+    let plan = WavePlan::of(&objective.force, drain, !data.objective_queue.boost_plan(obj_id).is_empty());
+    let threat = data.mapping.get_room(&room).and_then(|entity| data.threat_data.get(entity));
+    let towers_alive = threat
+        .map(|t| {
+            (0..t.hostile_tower_positions.len())
+                .filter(|i| t.tower_energy.get(*i).is_none_or(|energy| *energy >= TOWER_ENERGY_COST))
+                .count() as u32
+        })
+        .unwrap_or(0);
+    let hostile_healers = threat
+        .map(|t| t.hostile_creeps.iter().filter(|c| c.heal_per_tick > 0.0).count() as u32)
+        .unwrap_or(0);
+    let casualties = data.squad_contexts.get(squad_entity).map(|ctx| ctx.casualties.as_slice()).unwrap_or(&[]);
+    let report = WaveReport::new(plan, towers_alive, hostile_healers, casualties);
+
+    if debug {
+        log::info!(
+            "[Lifecycle] WAVE-LOST squad={:?} obj={:?} room={} plan=[{}] towers={} healers={} killed_by_towers={} killed_by_creeps={}",
+            squad_entity, obj_id, room, report.plan, towers_alive, hostile_healers, report.killed_by_towers, report.killed_by_creeps
+        );
+    }
+    data.objective_queue.record_lost_wave(room, report);
+}
+
 /// ADR 0034 D4/D5/D8: drop the PER-MEMBER ephemeral travel trackers (rally-distance, target-distance, and
 /// solo-stall) for one objective on retire/reassign. These are keyed by `(ObjectiveId, member-entity-id)`,
 /// so a per-objective sweep retains only the entries for OTHER objectives. Ephemeral runtime state — no
@@ -1708,11 +2093,12 @@ fn clear_member_trackers(fp: &mut SquadFormingProgress, obj_id: ObjectiveId) {
 #[allow(clippy::too_many_arguments)]
 fn queue_slot_spawn(
     spawn_queue: &mut SpawnQueue,
-    homes: &[HomeRoom],
+    homes: &[&HomeRoom],
     slot: &SquadSlot,
     slot_index: usize,
     target_room: RoomName,
     squad_entity: Entity,
+    boosts: &[ResourceType],
     priority: f32,
     debug: bool,
 ) {
@@ -1760,11 +2146,12 @@ fn queue_slot_spawn(
         }
     };
 
+    let cost: u32 = body.iter().map(|p| p.cost()).sum();
+
     // Observability: dump the ACTUAL body queued for this slot so we can confirm sizing live (e.g. is the
     // whole force piled onto one member, vs split across members). Behind features.military.debug_log.
     if debug {
         let n = |p: Part| body.iter().filter(|b| **b == p).count();
-        let cost: u32 = body.iter().map(|p| p.cost()).sum();
         let in_range = homes.iter().filter(|h| room_distance(h.name, target_room) <= MAX_SPAWN_DISTANCE).count();
         log::info!(
             "[SpawnQueue] slot={} role={:?} target={} parts={} (rng={} heal={} atk={} work={} tough={} carry={} move={}) cost={} prio={} homes_in_range={} (best_cap={})",
@@ -1786,6 +2173,9 @@ fn queue_slot_spawn(
         );
     }
 
+    // Members bound for another room spawn on the side facing its exit.
+    let target_centre = Position::new(RoomCoordinate::new(25).unwrap(), RoomCoordinate::new(25).unwrap(), target_room);
+
     let token = spawn_queue.token();
     for home in homes.iter().filter(|h| room_distance(h.name, target_room) <= MAX_SPAWN_DISTANCE) {
         let request = SpawnRequest::new(
@@ -1793,12 +2183,41 @@ fn queue_slot_spawn(
             &body,
             priority,
             Some(token),
-            create_spawn_callback(slot.role, slot_index, target_room, squad_entity),
-        );
+            create_spawn_callback(slot.role, slot_index, target_room, squad_entity, cost, boosts.to_vec()),
+        )
+        .requested_by(squad_entity);
+        let request = if home.name != target_room {
+            request.toward(target_centre)
+        } else {
+            request
+        };
         spawn_queue.request(home.entity, request);
     }
 }
 
+/// Estimate how long `composition` takes to spawn on the in-range homes and walk to `target_room`, with
+/// members sized the way [`queue_slot_spawn`] sizes them.
+fn estimate_arrival(composition: &SquadComposition, homes: &[HomeRoom], target_room: RoomName) -> ArrivalEstimate {
+    use screeps_combat_decision::bodies::MoveProfile;
+
+    let in_range: Vec<&HomeRoom> = homes
+        .iter()
+        .filter(|h| room_distance(h.name, target_room) <= MAX_SPAWN_DISTANCE)
+        .collect();
+    let best_capacity = in_range.iter().map(|h| h.energy_capacity).max().unwrap_or(0);
+    let build_energy = best_capacity.min(screeps_combat_decision::composition::PREFERRED_MEMBER_ENERGY);
+    let member_parts: Vec<u32> = composition
+        .slots
+        .iter()
+        .filter_map(|slot| slot.body_type.build_body(build_energy, MoveProfile::Plains))
+        .map(|body| body.len() as u32)
+        .collect();
+    let spawns = in_range.iter().map(|h| h.spawns).sum();
+    let rooms = in_range.iter().map(|h| room_distance(h.name, target_room)).min().unwrap_or(0);
+
+    ArrivalEstimate::new(&member_parts, spawns, rooms)
+}
+
 /// Mint a `SquadContext` bound to the objective and claim it. Members spawn next
 /// tick once the lazily-created component exists (the AttackMission create-then-
 /// wait discipline).
@@ -1833,7 +2252,7 @@ fn squad_state_to_order(state: SquadState) -> SquadOrderState {
         SquadState::Forming | SquadState::Rallying => SquadOrderState::Forming,
         SquadState::Moving => SquadOrderState::Moving,
         SquadState::Engaged => SquadOrderState::Engaged,
-        SquadState::Retreating => SquadOrderState::Retreating,
+        SquadState::Retreating | SquadState::Reinforcing => SquadOrderState::Retreating,
         SquadState::Complete => SquadOrderState::Moving,
     }
 }
@@ -1910,7 +2329,7 @@ fn build_room_combat_dtos(
             .iter()
             .map(creep_to_dto)
             .collect();
-        let structures = live.find(find::STRUCTURES, None).iter().map(structure_to_dto).collect();
+        let structures = find_known_structures(&live).iter().map(structure_to_dto).collect();
         return (hostiles, structures, CombatIntelSource::LiveVisible);
     }
 
@@ -1984,6 +2403,7 @@ fn compute_squad_orders(
     room_data: &ReadStorage<RoomData>,
     mapping: &EntityMappingData,
     squad_contexts: &mut WriteStorage<SquadContext>,
+    orders: &mut SquadOrders,
     creep_owner: &ReadStorage<CreepOwner>,
     squad_entity: Entity,
     obj_id: ObjectiveId,
@@ -1996,7 +2416,24 @@ fn compute_squad_orders(
     now: u32,
     deadline: Option<u32>,
     forming_progress: &mut SquadFormingProgress,
+    pathfinder: &mut crate::pathing::pathfinderservice::PathfinderService,
+    squad_pathing: &mut crate::military::formation::SquadPathing,
 ) {
+    // Rooms the anchor crosses on the way are entered by their least-damaged segment, as last seen.
+    let entry_segment = |room: RoomName| {
+        mapping
+            .get_room(&room)
+            .and_then(|e| room_data.get(e))
+            .and_then(|rd| rd.get_dynamic_visibility_data())
+            .and_then(|d| d.entry_segment().cloned())
+    };
+    let mut routing = crate::military::formation::SquadRouting {
+        pathfinder,
+        entry_segment: &entry_segment,
+        pathing: squad_pathing,
+        now,
+    };
+
     // Read the roster's cached status (immutable). `pos`/`has_ranged` feed the centroid + the kite
     // plan; `has_ranged` resolves the creep body (the adapter's job — the pure crate stays JS-free).
     let (member_views, current_state, retreat_threshold) = match squad_contexts.get(squad_entity) {
@@ -2063,6 +2500,20 @@ fn compute_squad_orders(
     let (hostiles, structures, intel_source) = build_room_combat_dtos(room_data, mapping, target_room);
     let dto_from_live_fallback = intel_source == CombatIntelSource::LiveVisible;
 
+    // Retreat-and-reform: a REINFORCING squad skips the assault decision entirely — it holds at its pinned
+    // rally, healing, while Phase B refills the dead slots, and re-enters the normal flow (`Moving`) once
+    // it has re-formed. Not a loss while it waits, so the lose carrier is cleared.
+    if squad_contexts.get(squad_entity).is_some_and(|c| c.state == SquadState::Reinforcing) {
+        forming_progress.lost_in_room.remove(&obj_id);
+        if let Some(ctx) = squad_contexts.get_mut(squad_entity) {
+            let incoming = |pos: Position| crate::military::damage::projected_damage_at(&hostiles, &structures, pos);
+            if apply_reinforce_orders(ctx, orders, target_room, requested_slots, &member_views, creep_owner, incoming) && debug {
+                log::info!("[SquadTrace] REFORMED squad={:?} obj={:?} — re-rallying", squad_entity, obj_id);
+            }
+        }
+        return;
+    }
+
     // Enemy safe mode → all our combat in the room is nullified (engage-veto, ADR 0020 §8). Only known
     // when the room is visible; default false otherwise (we discover + retreat on arrival).
     let enemy_safe_mode = game::rooms()
@@ -2267,8 +2718,8 @@ fn compute_squad_orders(
             // each freshly-spawned member simply HOLDS next to its own home spawn (renewable) until the rally
             // gate releases, at which point the SOLO-travel-to-shared-rally phase (below) takes over.
             ctx.squad_path = None;
-            for member in ctx.members.iter_mut() {
-                member.tick_orders = Some(TickOrders { movement: TickMovement::Hold, ..Default::default() });
+            for member in ctx.members.iter() {
+                orders.set(member.entity, TickOrders { movement: TickMovement::Hold, ..Default::default() });
             }
             if debug {
                 log::info!(
@@ -2437,7 +2888,7 @@ fn compute_squad_orders(
             if gathered {
                 // ASSAULT: members are massed at the rally → advance the box-formation anchor rally→target
                 // (cohesion on the short final leg). The job's `MoveToRoom`/`squad_has_anchor` follows it.
-                crate::military::formation::advance_squad_virtual_position(ctx, assault_target);
+                crate::military::formation::advance_squad_virtual_position(ctx, assault_target, &mut routing);
             } else {
                 // SOLO TRAVEL: drop the formation anchor (no cross-room box cohesion during transit) and
                 // send each member INDIVIDUALLY to the shared rally. Setting per-member MoveTo orders here
@@ -2488,12 +2939,15 @@ fn compute_squad_orders(
                             }
                         }
                     }
-                    member.tick_orders = Some(TickOrders {
-                        // Insufficient TTL → HOLD (next to the home spawn the renew pass tops it up at);
-                        // otherwise solo-travel to the shared rally.
-                        movement: if hold_for_renew { TickMovement::Hold } else { TickMovement::MoveTo(rally) },
-                        ..Default::default()
-                    });
+                    orders.set(
+                        member.entity,
+                        TickOrders {
+                            // Insufficient TTL → HOLD (next to the home spawn the renew pass tops it up at);
+                            // otherwise solo-travel to the shared rally.
+                            movement: if hold_for_renew { TickMovement::Hold } else { TickMovement::MoveTo(rally) },
+                            ..Default::default()
+                        },
+                    );
                 }
             }
             if debug {
@@ -2519,7 +2973,7 @@ fn compute_squad_orders(
                     (None, Some(center)) => crate::military::formation::standoff_one_tile(focus.pos, center),
                     _ => focus.pos,
                 };
-                crate::military::formation::advance_squad_virtual_position(ctx, dest);
+                crate::military::formation::advance_squad_virtual_position(ctx, dest, &mut routing);
             }
             ctx.threat_direction = decision.orientation;
             ctx.reassign_slots();
@@ -2527,9 +2981,67 @@ fn compute_squad_orders(
             // Arrived + SKIRMISH: drop the anchor so `Engaged` kites via `decide_movement` (O1).
             ctx.squad_path = None;
         }
-        apply_squad_decision(ctx, &decision, creep_owner, in_room_any);
+        // The retreat heal assignment pre-heals against the damage projected from the target room's
+        // hostiles and towers this tick, not just what landed last tick.
+        let incoming = |pos: Position| crate::military::damage::projected_damage_at(&hostiles, &structures, pos);
+        apply_squad_decision(ctx, orders, &decision, creep_owner, in_room_any, incoming);
+        // Retreat-and-reform: a squad that retreats from a fight it already committed to with slots lost
+        // falls back to REINFORCE instead of dying in place and re-fielding a whole new generation. Unlatch
+        // the engagement so the dead slots refill through the normal unfilled-slot spawns (Phase B) on a
+        // fresh forming clock, and pin the rally the survivors and reinforcements gather at. Only a full
+        // wipe (`squad_is_wiped`) still retires the squad.
+        let present = member_views.iter().filter(|m| m.pos.is_some()).count();
+        if ctx.state == SquadState::Retreating && ctx.engaged_once && present < requested_slots {
+            ctx.state = SquadState::Reinforcing;
+            ctx.engaged_once = false;
+            ctx.focus_target = None;
+            ctx.squad_path = None;
+            ctx.rally_point = Some(screeps_combat_decision::rally::shared_rally_point_for_members(
+                &member_positions,
+                Position::new(RoomCoordinate::new(25).unwrap(), RoomCoordinate::new(25).unwrap(), target_room),
+                false,
+            ));
+            forming_progress.assault_latched.remove(&obj_id);
+            forming_progress.forming_started_at.insert(obj_id, now);
+            if debug {
+                log::info!(
+                    "[SquadTrace] REINFORCE squad={:?} obj={:?} present={}/{} rally={:?} (fall back + refill, no re-field)",
+                    squad_entity, obj_id, present, requested_slots, ctx.rally_point
+                );
+            }
+        }
+        // Ranged-only kiting: decided here, AFTER the decision is stamped, so the kite step replaces
+        // the member's formation/decide_movement order for this tick instead of fighting the anchor.
+        if ctx.state == SquadState::Engaged {
+            let matrix = room_layers.get(&target_room).map(|(matrix, _)| matrix);
+            apply_ranged_kiting(ctx, orders, &decision, &member_views, &hostiles, matrix);
+        }
+        // Drain squads don't fight for kills once engaged: each member works a post at the room edge,
+        // soaking tower fire inside and stepping back across the exit to heal (`drain::drain_step`).
+        let drain_mode = matches!(assault_mode, Some(screeps_combat_decision::force_sizing::AssaultMode::Drain));
+        if drain_mode && ctx.engaged_once && !ctx.state.is_falling_back() {
+            let matrix = room_layers.get(&target_room).map(|(matrix, _)| matrix);
+            let entry = mapping
+                .get_room(&target_room)
+                .and_then(|e| room_data.get(e))
+                .and_then(|rd| rd.get_dynamic_visibility_data())
+                .and_then(|d| d.entry_segment().cloned());
+            apply_drain_orders(
+                ctx,
+                orders,
+                obj_id,
+                target_room,
+                &decision,
+                &member_views,
+                &structures,
+                matrix,
+                entry.as_ref(),
+                now,
+                forming_progress,
+            );
+        }
         // ADR 0031 §2(g) FOLLOW-UP 1b — LIVE DRAIN WIRING. The drain tank-forward / healers-behind
-        // per-member goals (`decision.member_goals`, stamped onto each member's `tick_orders.squad_movement`
+        // per-member goals (`decision.member_goals`, stamped onto each member's `TickOrders::squad_movement`
         // in `apply_squad_decision` above) are honored IN-SIM but INERT on the live bot when a Dismantle is
         // in its FORMATION (anchor) phase: with an anchor the job takes `execute_formation_movement`
         // (slot-based), which IGNORES `squad_movement`; only the ANCHORLESS `execute_decide_movement` path
@@ -2727,7 +3239,7 @@ fn compute_squad_orders(
 /// ADR 0031 §2(g) FOLLOW-UP 1b — should the formation anchor be dropped this tick because the squad is
 /// in an ACTIVE drain? When `decide_squad` emits a `SquadMovement::Drain` directive, the per-member drain
 /// goals (tank forward at the standoff, healers one tile behind) are stamped onto each member's
-/// `tick_orders.squad_movement`, but the live job only READS `squad_movement` on the ANCHORLESS movement
+/// `TickOrders::squad_movement`, but the live job only READS `squad_movement` on the ANCHORLESS movement
 /// path. Dropping the anchor for a `Drain` directive (and ONLY for `Drain`) forces that path so the goals
 /// are honored live. Pure + testable so the drain-only scoping is provable offline without a live job.
 fn should_drop_anchor_for_drain(decision: &SquadDecision) -> bool {
@@ -2756,9 +3268,19 @@ fn should_drop_anchor_for_structure_siege(decision: &SquadDecision) -> bool {
 /// orders. The per-member `movement` stays `Formation` — for a manager squad (no anchor) the job
 /// routes it through the pure `decide_movement` (§5 ⚑ job-owns-movement), reading the squad's shared
 /// directive (`squad_movement`/`squad_center`/`squad_cohesion_radius`) the manager stamps here so the
-/// block kites/advances as one. Heal *assignment* still reuses `SquadContext::compute_heal_assignments`
-/// until that migrates into `decide_squad` (Step 7).
-fn apply_squad_decision(ctx: &mut SquadContext, decision: &SquadDecision, creep_owner: &ReadStorage<CreepOwner>, in_room_any: bool) {
+/// block kites/advances as one. Engaged heal assignment comes from `decide_squad` (Step 7); a retreating
+/// squad still uses `SquadContext::compute_heal_assignments`, with `incoming` projecting the damage that
+/// lands on each member's tile this tick.
+fn apply_squad_decision<F>(
+    ctx: &mut SquadContext,
+    orders: &mut SquadOrders,
+    decision: &SquadDecision,
+    creep_owner: &ReadStorage<CreepOwner>,
+    in_room_any: bool,
+    incoming: F,
+) where
+    F: Fn(Position) -> u32,
+{
     ctx.state = order_state_to_squad(decision.state);
     // FIX B1: latch `engaged_once` ONLY when the squad is Engaged AND a member is actually IN the target
     // room. `decide_squad` sets `Engaged` purely from `focus.is_some()` with NO proximity gate (lib.rs), so a
@@ -2773,12 +3295,12 @@ fn apply_squad_decision(ctx: &mut SquadContext, decision: &SquadDecision, creep_
 
     match decision.state {
         SquadOrderState::Retreating => {
-            ctx.issue_retreat_orders(None, Some(creep_owner));
+            ctx.issue_retreat_orders(orders, None, Some(creep_owner), incoming);
         }
         SquadOrderState::Engaged => {
             // Per-member focus with damage spill (ADR 0020 §4.2); index aligns with view.members
             // (built from ctx.members in order). `None` ⇒ the shared focus.
-            for (i, member) in ctx.members.iter_mut().enumerate() {
+            for (i, member) in ctx.members.iter().enumerate() {
                 let focus = decision.focus_assignments.get(i).copied().flatten().or(decision.focus);
                 let attack_target = focus.map(|f| f.id.map(AttackTarget::Creep).unwrap_or(AttackTarget::Structure(f.pos)));
                 // ADR 0019 §8: a member with its own goal (a pure-support healer's heal-coverage tile)
@@ -2792,29 +3314,25 @@ fn apply_squad_decision(ctx: &mut SquadContext, decision: &SquadDecision, creep_
                     .flatten()
                     .map(|goal| SquadMovement::Advance { goal, range: 0 })
                     .unwrap_or(decision.movement);
-                member.tick_orders = Some(TickOrders {
-                    attack_target,
-                    movement: TickMovement::Formation,
-                    squad_movement,
-                    squad_center: decision.center,
-                    squad_cohesion_radius: decision.cohesion_radius,
-                    ..Default::default()
-                });
+                orders.set(
+                    member.entity,
+                    TickOrders {
+                        attack_target,
+                        movement: TickMovement::Formation,
+                        squad_movement,
+                        squad_center: decision.center,
+                        squad_cohesion_radius: decision.cohesion_radius,
+                        ..Default::default()
+                    },
+                );
             }
             // Apply the pure heal assignments (Step 7): resolve member indices → the target's creep
             // ObjectId, then set each assigned healer's heal_target. (Indices match `member_views`,
             // built in the same order as `ctx.members`.) Resolve first to avoid an aliasing borrow.
-            let heal_targets: Vec<(usize, Option<ObjectId<Creep>>)> = decision
-                .heal_assignments
-                .iter()
-                .map(|a| {
-                    let target_id = ctx.members.get(a.target_idx).and_then(|m| creep_owner.get(m.entity)).map(|co| co.owner);
-                    (a.healer_idx, target_id)
-                })
-                .collect();
-            for (healer_idx, target_id) in heal_targets {
-                if let Some(orders) = ctx.members.get_mut(healer_idx).and_then(|m| m.tick_orders.as_mut()) {
-                    orders.heal_target = target_id;
+            for a in &decision.heal_assignments {
+                let target_id = ctx.members.get(a.target_idx).and_then(|m| creep_owner.get(m.entity)).map(|co| co.owner);
+                if let Some(healer_orders) = ctx.members.get(a.healer_idx).and_then(|m| orders.get_mut(m.entity)) {
+                    healer_orders.heal_target = target_id;
                 }
             }
         }
@@ -2824,17 +3342,366 @@ fn apply_squad_decision(ctx: &mut SquadContext, decision: &SquadDecision, creep_
         // / no path) this is a no-op and the job falls back to plain room navigation.
         _ => {
             if ctx.squad_path.is_some() {
-                for member in ctx.members.iter_mut() {
-                    member.tick_orders = Some(TickOrders {
-                        movement: TickMovement::Formation,
-                        ..Default::default()
-                    });
+                for member in ctx.members.iter() {
+                    orders.set(
+                        member.entity,
+                        TickOrders {
+                            movement: TickMovement::Formation,
+                            ..Default::default()
+                        },
+                    );
                 }
             }
         }
     }
 }
 
+/// Engaged-tick ranged orders, applied on top of `apply_squad_decision`. A ranged-only member (RANGED_ATTACK,
+/// no ATTACK) with a melee hostile within `KITE_TRIGGER_RANGE` gets a `Kite` order to the adjacent tile that
+/// best opens the distance while keeping its focus within range 3 (`formation::kite_step`); every ranged
+/// member with 2+ hostiles in range 1-3 fires `rangedMassAttack`. Indices align with `member_views`
+/// (built from `ctx.members` in order). Walls and impassable structures come from the target room's
+/// movement matrix; other members' tiles are treated as taken.
+fn apply_ranged_kiting(
+    ctx: &SquadContext,
+    orders: &mut SquadOrders,
+    decision: &SquadDecision,
+    member_views: &[SquadMemberView],
+    hostiles: &[CombatCreepDto],
+    matrix: Option<&LocalCostMatrix>,
+) {
+    use crate::military::formation::{kite_step, mass_attack_worthwhile};
+
+    let hostile_positions: Vec<Position> = hostiles.iter().map(|h| h.pos).collect();
+    let melee_positions: Vec<Position> = hostiles
+        .iter()
+        .filter(|h| h.body.iter().any(|p| p.part == Part::Attack && p.hits > 0))
+        .map(|h| h.pos)
+        .collect();
+    let member_positions: Vec<Position> = member_views.iter().filter_map(|m| m.pos).collect();
+
+    for (i, (member, view)) in ctx.members.iter().zip(member_views.iter()).enumerate() {
+        let (Some(pos), true) = (view.pos, view.has_ranged) else {
+            continue;
+        };
+        let Some(orders) = orders.get_mut(member.entity) else {
+            continue;
+        };
+
+        orders.ranged_mass_attack = mass_attack_worthwhile(pos, &hostile_positions);
+
+        if view.melee_power > 0 {
+            continue;
+        }
+
+        let focus = decision.focus_assignments.get(i).copied().flatten().or(decision.focus).map(|f| f.pos);
+        let blocked = |tile: Position| {
+            member_positions.contains(&tile)
+                || hostile_positions.contains(&tile)
+                || matrix.is_some_and(|m| m.get(tile.xy()) == u8::MAX)
+        };
+
+        if let Some(step) = kite_step(pos, &melee_positions, focus, blocked) {
+            orders.movement = TickMovement::Kite(step);
+        }
+    }
+}
+
+/// Orders for a REINFORCING squad: every member (survivors and freshly spawned reinforcements alike) moves
+/// to the rally pinned when it fell back, with heals assigned against the damage projected there. Once
+/// [`squad_reformed`] holds the squad goes back to `Moving` and rejoins the normal rally/assault flow.
+/// Returns whether the squad re-formed this tick.
+fn apply_reinforce_orders<F>(
+    ctx: &mut SquadContext,
+    orders: &mut SquadOrders,
+    target_room: RoomName,
+    requested_slots: usize,
+    member_views: &[SquadMemberView],
+    creep_owner: &ReadStorage<CreepOwner>,
+    incoming: F,
+) -> bool
+where
+    F: Fn(Position) -> u32,
+{
+    let positions: Vec<Option<Position>> = member_views.iter().map(|m| m.pos).collect();
+    let rally = *ctx.rally_point.get_or_insert_with(|| {
+        let centre = Position::new(RoomCoordinate::new(25).unwrap(), RoomCoordinate::new(25).unwrap(), target_room);
+        screeps_combat_decision::rally::shared_rally_point_for_members(&positions, centre, false)
+    });
+
+    if squad_reformed(&positions, rally, requested_slots, ctx.average_hp_fraction()) {
+        ctx.state = SquadState::Moving;
+        ctx.rally_point = None;
+        return true;
+    }
+
+    ctx.issue_retreat_orders(orders, Some(rally), Some(creep_owner), incoming);
+    false
+}
+
+/// Drain-mode orders, applied on top of `apply_squad_decision` once the squad has engaged. Posts are picked
+/// once per objective on the side of the target room holding its safest entry past the towers (`entry`, from
+/// the room's last sighting), else the side facing the squad (`drain::drain_posts`), and the drain clock
+/// starts with them. Each member then gets a `Drain` order to its post's inside tile, or to the outside
+/// tile when the energized hostile towers' damage there would take it below the safety margin next tick (or
+/// while it is still healing out there). Indices align with `member_views`.
+#[allow(clippy::too_many_arguments)]
+fn apply_drain_orders(
+    ctx: &SquadContext,
+    orders: &mut SquadOrders,
+    obj_id: ObjectiveId,
+    target_room: RoomName,
+    decision: &SquadDecision,
+    member_views: &[SquadMemberView],
+    structures: &[CombatStructureDto],
+    matrix: Option<&LocalCostMatrix>,
+    entry: Option<&crate::military::damage::EntrySegment>,
+    now: u32,
+    forming_progress: &mut SquadFormingProgress,
+) {
+    use crate::military::drain::{drain_posts, drain_step, DrainStep};
+
+    let towers: Vec<Position> = structures
+        .iter()
+        .filter(|s| {
+            s.structure_type == StructureType::Tower
+                && s.ownership == screeps_combat_decision::Ownership::Hostile
+                && s.energy >= TOWER_ENERGY_COST
+        })
+        .map(|s| s.pos)
+        .collect();
+
+    let drain = forming_progress.drain.entry(obj_id).or_insert_with(|| DrainProgress {
+        started_at: now,
+        posts: Vec::new(),
+    });
+    if drain.posts.len() < ctx.members.len() {
+        let Some(toward) = entry
+            .map(|segment| segment.center(target_room))
+            .or(decision.center)
+            .or_else(|| member_views.iter().find_map(|m| m.pos))
+        else {
+            return;
+        };
+        let blocked = |tile: Position| {
+            if tile.room_name() == target_room && matrix.is_some_and(|m| m.get(tile.xy()) == u8::MAX) {
+                return true;
+            }
+            game::map::get_room_terrain(tile.room_name()).is_some_and(|t| t.get(tile.x().u8(), tile.y().u8()) == Terrain::Wall)
+        };
+        drain.posts = drain_posts(target_room, toward, &towers, ctx.members.len(), blocked);
+    }
+
+    for ((member, view), post) in ctx.members.iter().zip(member_views.iter()).zip(drain.posts.iter()) {
+        let (Some(pos), Some(orders)) = (view.pos, orders.get_mut(member.entity)) else {
+            continue;
+        };
+
+        let in_target_room = pos.room_name() == target_room;
+        let tower_dps = crate::military::damage::total_tower_damage(&towers, post.inside).round() as u32;
+        let tile = match drain_step(in_target_room, view.hits, view.hits_max, tower_dps, view.heal_power) {
+            DrainStep::StepOut => post.outside,
+            DrainStep::StepIn => post.inside,
+            DrainStep::Hold if in_target_room => post.inside,
+            DrainStep::Hold => post.outside,
+        };
+        orders.movement = TickMovement::Drain(tile);
+    }
+}
+
+/// Harass orders, applied on top of the normal rally/travel flow once the squad has left home. The squad
+/// patrols one of the objective's rooms at a time, moving on after `HARASS_ROTATE_TICKS` or as soon as the
+/// room out-damages it (the room's armed hostiles, or a member the damage model would push below the retreat
+/// threshold). It focuses the nearest unescorted miner/hauler, else walks the room. Last tick's losses and
+/// kills are tallied against the room on the objective queue, which the war operation reads to drop
+/// defended remotes.
+#[allow(clippy::too_many_arguments)]
+fn apply_harass_orders(
+    room_data: &ReadStorage<RoomData>,
+    mapping: &EntityMappingData,
+    squad_contexts: &mut WriteStorage<SquadContext>,
+    orders: &mut SquadOrders,
+    creep_owner: &ReadStorage<CreepOwner>,
+    queue: &mut CombatObjectiveQueue,
+    squad_entity: Entity,
+    obj_id: ObjectiveId,
+    patrol_rooms: &[RoomName],
+    now: u32,
+    debug: bool,
+    forming_progress: &mut SquadFormingProgress,
+) {
+    use crate::military::harass::{is_outmatched, next_patrol_index, pick_soft_target, target_was_killed, HARASS_ROTATE_TICKS};
+
+    let Some(ctx) = squad_contexts.get_mut(squad_entity) else {
+        return;
+    };
+    // Still gathering at home, or falling back: the normal flow's orders stand.
+    if !matches!(ctx.state, SquadState::Moving | SquadState::Engaged) || ctx.members.is_empty() {
+        return;
+    }
+
+    let present = ctx.members.len();
+    let progress = forming_progress.harass.entry(obj_id).or_insert_with(|| HarassProgress {
+        room_index: 0,
+        since: now,
+        last_members: present,
+        last_target: None,
+    });
+    // The war operation may have dropped a defended room from the list since last tick.
+    progress.room_index %= patrol_rooms.len();
+    let room = patrol_rooms[progress.room_index];
+    let (hostiles, structures, _) = build_room_combat_dtos(room_data, mapping, room);
+
+    // Charge last tick's outcome to the room being patrolled.
+    if present < progress.last_members {
+        queue.harass_tally_mut(room).losses += (progress.last_members - present) as u32;
+    }
+    progress.last_members = present;
+    if let Some((id, last_pos)) = progress.last_target.take() {
+        if last_pos.room_name() == room && game::rooms().get(room).is_some() {
+            let still_present = hostiles.iter().any(|h| h.id == Some(id));
+            if target_was_killed(last_pos, still_present) {
+                queue.harass_tally_mut(room).kills += 1;
+            }
+        }
+    }
+
+    let our_dps: u32 = ctx
+        .members
+        .iter()
+        .filter_map(|m| creep_owner.get(m.entity).and_then(|co| co.owner.resolve()))
+        .map(|c| c.body().iter().filter(|p| p.part() == Part::RangedAttack && p.hits() > 0).count() as u32)
+        .sum::<u32>()
+        * RANGED_ATTACK_POWER;
+    let threatened = ctx.members.iter().any(|m| {
+        m.position.is_some_and(|pos| {
+            let incoming = crate::military::damage::projected_damage_at(&hostiles, &structures, pos);
+            (m.current_hits.saturating_sub(incoming) as f32) < m.max_hits as f32 * ctx.retreat_threshold
+        })
+    });
+    let outmatched = threatened || is_outmatched(room, &hostiles, &structures, our_dps);
+    let timed_out = now.saturating_sub(progress.since) >= HARASS_ROTATE_TICKS;
+
+    if outmatched || timed_out {
+        if outmatched {
+            queue.harass_tally_mut(room).driven_off += 1;
+        }
+        let mut next = next_patrol_index(progress.room_index, patrol_rooms.len());
+        for _ in 1..patrol_rooms.len() {
+            if !queue.harass_tally(patrol_rooms[next]).is_defended() {
+                break;
+            }
+            next = next_patrol_index(next, patrol_rooms.len());
+        }
+        progress.room_index = next;
+        progress.since = now;
+        if debug {
+            log::info!(
+                "[SquadTrace] HARASS-ROTATE squad={:?} obj={:?} from={} to={} outmatched={} tally={:?}",
+                squad_entity, obj_id, room, patrol_rooms[next], outmatched, queue.harass_tally(room)
+            );
+        }
+    } else {
+        let lead = ctx.members.iter().find_map(|m| m.position);
+        progress.last_target = lead
+            .and_then(|from| pick_soft_target(room, &hostiles, from))
+            .and_then(|c| c.id.map(|id| (id, c.pos)));
+    }
+
+    let waypoint = Position::new(
+        RoomCoordinate::new(25).unwrap(),
+        RoomCoordinate::new(25).unwrap(),
+        patrol_rooms[progress.room_index],
+    );
+    let focus = progress.last_target.map(|(id, _)| AttackTarget::Creep(id));
+    for member in ctx.members.iter() {
+        let orders = orders.entry(member.entity);
+        orders.movement = TickMovement::Patrol(waypoint);
+        orders.attack_target = focus;
+    }
+}
+
+/// Claimer-first focus for defense squads, applied on top of the decision's orders. Once a member is in
+/// `target_room`, the squad drops its other targets for the nearest claimer there and chases it down: a
+/// claimer is unarmed, but every tick it stands at the controller costs us downgrade time or reservation.
+fn apply_claimer_focus(
+    room_data: &ReadStorage<RoomData>,
+    mapping: &EntityMappingData,
+    squad_contexts: &mut WriteStorage<SquadContext>,
+    orders: &mut SquadOrders,
+    squad_entity: Entity,
+    target_room: RoomName,
+) {
+    use crate::military::interceptor::pick_claimer;
+
+    let Some(ctx) = squad_contexts.get_mut(squad_entity) else {
+        return;
+    };
+    if !matches!(ctx.state, SquadState::Moving | SquadState::Engaged) {
+        return;
+    }
+    let Some(lead) = ctx
+        .members
+        .iter()
+        .filter_map(|m| m.position)
+        .find(|pos| pos.room_name() == target_room)
+    else {
+        return;
+    };
+    let (hostiles, _, _) = build_room_combat_dtos(room_data, mapping, target_room);
+    let Some((id, pos)) = pick_claimer(target_room, &hostiles, lead).and_then(|c| c.id.map(|id| (id, c.pos))) else {
+        return;
+    };
+
+    ctx.focus_target = Some(pos);
+    for member in ctx.members.iter() {
+        let orders = orders.entry(member.entity);
+        orders.movement = TickMovement::Patrol(pos);
+        orders.attack_target = Some(AttackTarget::Creep(id));
+    }
+}
+
+/// Caravan escort orders, applied on top of the normal rally/travel flow once the escort has left home. The
+/// escort follows whichever of the caravan's haulers is nearest the threatened rooms, keeping the focus the
+/// normal flow picked so it shoots whatever closes on the hauler. With no hauler out (all dead or still
+/// spawning) the normal flow's orders stand and the escort makes for the pickup room.
+fn apply_escort_orders(
+    squad_contexts: &mut WriteStorage<SquadContext>,
+    orders: &mut SquadOrders,
+    creep_owner: &ReadStorage<CreepOwner>,
+    squad_entity: Entity,
+    obj_id: ObjectiveId,
+    caravan: &crate::military::escort::Caravan,
+    forming_progress: &mut SquadFormingProgress,
+) {
+    use crate::military::escort::pick_escorted;
+
+    let Some(ctx) = squad_contexts.get_mut(squad_entity) else {
+        return;
+    };
+    // Still gathering at home, or falling back: the normal flow's orders stand.
+    if !matches!(ctx.state, SquadState::Moving | SquadState::Engaged) || ctx.members.is_empty() {
+        return;
+    }
+
+    let haulers: Vec<(Entity, Position)> = caravan
+        .haulers
+        .iter()
+        .filter_map(|hauler| creep_owner.get(*hauler).and_then(|co| co.owner.resolve()).map(|c| (*hauler, c.pos())))
+        .collect();
+    let current = forming_progress.escort.get(&obj_id).copied();
+    let Some((escorted, escorted_pos)) =
+        pick_escorted(&haulers, &caravan.threatened_rooms, current).and_then(|e| haulers.iter().find(|(h, _)| *h == e).copied())
+    else {
+        return;
+    };
+    forming_progress.escort.insert(obj_id, escorted);
+
+    for member in ctx.members.iter() {
+        orders.entry(member.entity).movement = TickMovement::Follow(escorted_pos);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2898,6 +3765,12 @@ mod tests {
         let (t, travel) = objective_target(&ObjectiveKind::Declaim { room: r, controller: ctrl });
         assert!(matches!(t, SquadTarget::AttackController { position } if position == ctrl));
         assert_eq!(travel, r);
+
+        // A caravan escort heads for the pickup room before it takes up its hauler.
+        let (t, travel) = objective_target(&ObjectiveKind::Caravan { room: r });
+        assert!(matches!(t, SquadTarget::EscortPosition { position } if position.room_name() == r));
+        assert_eq!(travel, r);
+        assert_eq!(capability_class(&ObjectiveKind::Caravan { room: r }), CapabilityClass::Defense);
     }
 
     /// ADR 0027 v1.1 P2: a Declaim objective is its OWN capability class — a CLAIM declaimer is never
@@ -3034,6 +3907,129 @@ mod tests {
         assert!(squad_is_wiped(4, 0), "spawned members and all are gone → wiped");
     }
 
+    /// Run the squad pre-run's dead-member prune against `view` and report (members left, casualties
+    /// recorded, wiped).
+    fn prune_squad(world: &World, view: &crate::testing::MockGame, squad: Entity) -> (usize, usize, bool) {
+        use crate::creep::CreepSpawning;
+        use crate::military::squad::prune_dead_members;
+        use crate::military::threatmap::RoomThreatData;
+
+        let (entities, mut contexts, creep_owners, creep_spawning, threat_data, mapping) = world.system_data::<(
+            Entities,
+            WriteStorage<SquadContext>,
+            ReadStorage<CreepOwner>,
+            ReadStorage<CreepSpawning>,
+            ReadStorage<RoomThreatData>,
+            Read<EntityMappingData>,
+        )>();
+        let ctx = contexts.get_mut(squad).expect("squad context");
+        prune_dead_members(view, ctx, &entities, &creep_owners, &creep_spawning, &mapping, &threat_data);
+
+        (ctx.members.len(), ctx.casualties.len(), squad_is_wiped(ctx.total_members_added, ctx.members.len()))
+    }
+
+    /// P2.G4-O4 over the game seam: members drop out as their creeps stop resolving, each field death is
+    /// filed as a casualty, and a member still in the spawn holds off the wave-wipe until it is gone too.
+    #[test]
+    fn wave_wipes_once_every_member_stops_resolving() {
+        use crate::testing::*;
+        use screeps_combat_decision::bodies::CombatBodySpec;
+        use screeps_combat_decision::composition::{BodyType, FormationShape, SquadRole};
+
+        let ranged = BodyType::Sized(CombatBodySpec { ranged_attack: 2, ..Default::default() });
+        let comp = SquadComposition {
+            label: "Trio".into(),
+            slots: vec![SquadSlot { role: SquadRole::RangedDPS, body_type: ranged }; 3],
+            formation_shape: FormationShape::Line,
+            formation_mode: Default::default(),
+            retreat_threshold: 0.3,
+        };
+
+        let mut test_world = TestWorld::new();
+        let (first_id, second_id) = (object_id(1), object_id(2));
+        let first = test_world.creep(first_id);
+        let second = test_world.creep(second_id);
+        let spawning = test_world.spawning_creep("trio-3");
+
+        let mut ctx = SquadContext::from_composition(&comp);
+        for (slot, member) in [first, second, spawning].into_iter().enumerate() {
+            ctx.add_member(member, SquadRole::RangedDPS, slot);
+        }
+        for member in ctx.members.iter_mut().take(2) {
+            member.current_hits = 800;
+            member.max_hits = 1000;
+        }
+        let squad = test_world.squad(ctx);
+
+        let mut view = MockGame::at(100);
+        view.creeps.extend([first_id, second_id]);
+        assert_eq!(prune_squad(&test_world.world, &view, squad), (3, 0, false), "everyone resolves");
+
+        view.creeps.remove(&first_id);
+        assert_eq!(prune_squad(&test_world.world, &view, squad), (2, 1, false), "one field death");
+
+        view.creeps.clear();
+        assert_eq!(prune_squad(&test_world.world, &view, squad), (1, 2, false), "the spawning member holds the wave");
+
+        test_world.world.delete_entity(spawning).unwrap();
+        test_world.world.maintain();
+        assert_eq!(prune_squad(&test_world.world, &view, squad), (0, 2, true), "nothing left → wiped, no spawn-death casualty");
+    }
+
+    /// The energy gate sums only the homes in spawn range of the target, net of what earlier fields this
+    /// tick committed; an `Immediate` objective always deploys.
+    #[test]
+    fn deploy_condition_counts_in_range_homes_net_of_committed_energy() {
+        use crate::gameview::RoomEnergy;
+        use crate::military::economy::{EconomySnapshot, RoomEconomyData};
+        use crate::testing::*;
+
+        let mut test_world = TestWorld::new();
+        let near = test_world.room("W1N1");
+        let far = test_world.room("W20N1");
+
+        let mut view = MockGame::at(100);
+        view.rooms.insert(room("W1N1"), RoomEnergy { available: 800, capacity: 1_300 });
+        view.rooms.insert(room("W20N1"), RoomEnergy { available: 5_600, capacity: 5_600 });
+
+        let homes: Vec<HomeRoom> = [(near, "W1N1"), (far, "W20N1")]
+            .into_iter()
+            .map(|(entity, name)| HomeRoom {
+                entity,
+                name: room(name),
+                energy_capacity: view.room_energy(room(name)).map(|e| e.capacity).unwrap_or(0),
+                spawns: 1,
+            })
+            .collect();
+
+        let mut economy = EconomySnapshot::default();
+        for (entity, stored_energy) in [(near, 50_000), (far, 500_000)] {
+            economy.rooms.insert(entity, RoomEconomyData { stored_energy, ..Default::default() });
+        }
+
+        let target = room("W2N1");
+        let gated = DeployCondition::AfterEnergyAvailable { amount: 40_000 };
+
+        assert_eq!(check_deploy_condition(gated, &economy, &homes, target, 0), Ok(()));
+        assert_eq!(
+            check_deploy_condition(gated, &economy, &homes, target, 20_000),
+            Err(30_000),
+            "the far home's energy never counts"
+        );
+        assert_eq!(check_deploy_condition(DeployCondition::Immediate, &economy, &homes, target, 50_000), Ok(()));
+    }
+
+    #[test]
+    fn reinforcing_squad_reforms_only_when_refilled_gathered_and_healed() {
+        let at = |x: u8| Some(Position::new(RoomCoordinate::new(x).unwrap(), RoomCoordinate::new(25).unwrap(), room("W6N5")));
+        let rally = at(25).unwrap();
+        assert!(!squad_reformed(&[at(25), at(26), None], rally, 3, 1.0), "a dead slot not yet refilled → keep waiting");
+        assert!(!squad_reformed(&[at(25), at(26), at(45)], rally, 3, 1.0), "the reinforcement is still en route → keep waiting");
+        assert!(!squad_reformed(&[at(25), at(26), at(24)], rally, 3, 0.6), "gathered but still hurt → keep healing");
+        assert!(squad_reformed(&[at(25), at(26), at(24)], rally, 3, 0.95), "full roster gathered and healed → re-rally");
+        assert!(!squad_reformed(&[], rally, 0, 1.0), "unknown roster never counts as re-formed");
+    }
+
     #[test]
     fn rally_gate_picks_quorum_only_for_visible_clear_rooms() {
         // FIX 1: the manager composes `target_is_uncontested` (with the live `game::rooms()` visibility
@@ -3349,7 +4345,7 @@ mod tests {
     /// behavior. A Dismantle squad in an ACTIVE drain (`movement = Drain`, per-member `member_goals` = tank
     /// forward at the standoff, healers one tile behind, `squad_path` = Some(anchor)) must, after the gate:
     ///   1. drop its anchor (`squad_path == None` → `squad_has_anchor()` false → anchorless routing), AND
-    ///   2. carry each member's drain goal as its `tick_orders.squad_movement == Advance{goal, range:0}`
+    ///   2. carry each member's drain goal as its `TickOrders::squad_movement == Advance{goal, range:0}`
     ///      (the directive the anchorless `decide_movement` reads → tank closes to standoff, healers hold a
     ///      tile back) — exactly what the sim proves.
     /// Control: a NON-drain Dismantle (`movement = Advance`, anchor set) KEEPS its anchor (formation slots
@@ -3418,11 +4414,13 @@ mod tests {
         ctx.squad_path = Some(SquadPath {
             anchor: AnchorPath::new(nest, nest),
             room_route: vec![r],
+            waypoint: None,
         });
         assert!(ctx.squad_path.is_some(), "precondition: the squad holds a formation anchor");
 
         // Reproduce the reconcile drain-gate exactly: stamp the decision, THEN the drain anchor-drop.
-        apply_squad_decision(&mut ctx, &drain_decision, &creep_owner, true);
+        let mut orders = SquadOrders::default();
+        apply_squad_decision(&mut ctx, &mut orders, &drain_decision, &creep_owner, true, |_| 0);
         if should_drop_anchor_for_drain(&drain_decision) {
             ctx.squad_path = None;
         }
@@ -3431,7 +4429,7 @@ mod tests {
         assert!(ctx.squad_path.is_none(), "drain drops the formation anchor → anchorless routing");
         // (2) Each member carries its OWN drain goal as Advance{goal, range:0} (what decide_movement reads).
         for (member, goal) in ctx.members.iter().zip(member_goals.iter()) {
-            let orders = member.tick_orders.as_ref().expect("a drain member has tick_orders");
+            let orders = orders.get(member.entity).expect("a drain member has tick orders");
             match orders.squad_movement {
                 SquadMovement::Advance { goal: g, range } => {
                     assert_eq!(Some(g), *goal, "the member moves to its own drain goal");
@@ -3454,8 +4452,9 @@ mod tests {
         ctx2.squad_path = Some(SquadPath {
             anchor: AnchorPath::new(nest, nest),
             room_route: vec![r],
+            waypoint: None,
         });
-        apply_squad_decision(&mut ctx2, &advance_decision, &creep_owner, true);
+        apply_squad_decision(&mut ctx2, &mut SquadOrders::default(), &advance_decision, &creep_owner, true, |_| 0);
         if should_drop_anchor_for_drain(&advance_decision) {
             ctx2.squad_path = None;
         }
@@ -3483,13 +4482,15 @@ mod tests {
         ctx3.squad_path = Some(SquadPath {
             anchor: AnchorPath::new(nest, nest),
             room_route: vec![r],
+            waypoint: None,
         });
-        apply_squad_decision(&mut ctx3, &solo_decision, &creep_owner, true);
+        let mut solo = SquadOrders::default();
+        apply_squad_decision(&mut ctx3, &mut solo, &solo_decision, &creep_owner, true, |_| 0);
         if should_drop_anchor_for_drain(&solo_decision) {
             ctx3.squad_path = None;
         }
         assert!(ctx3.squad_path.is_none(), "single-member drain still drops the anchor (harmless)");
-        let solo_orders = ctx3.members[0].tick_orders.as_ref().expect("solo drain member has tick_orders");
+        let solo_orders = solo.get(ctx3.members[0].entity).expect("solo drain member has tick orders");
         assert!(
             matches!(solo_orders.squad_movement, SquadMovement::Advance { goal, range: 0 } if goal == tank_goal),
             "the solo drain member routes its own goal"
@@ -3503,7 +4504,7 @@ mod tests {
     ///   1. D4 REACH: `apply_squad_decision` then `should_drop_anchor_for_structure_siege` drops the anchor
     ///      (`ctx.squad_path == None`) so the job routes ANCHORLESS to each member's `member_goal` (the
     ///      approach gradient closes to weapon range — the ADR 0026 §9 standoff-park fix).
-    ///   2. D3 STAMP: every present member's `tick_orders.attack_target == AttackTarget::Structure(pos)` —
+    ///   2. D3 STAMP: every present member's `TickOrders::attack_target == AttackTarget::Structure(pos)` —
     ///      the position-only (`id: None`) focus the job's `resolve_focus` keeps + `translate_intents`
     ///      focus-fires by position (NOT the old `resolve_creep()` drop → undirected fire).
     /// RED-ability (both revert to master's 0-damage bug): (1) delete the `should_drop_anchor_for_structure_
@@ -3583,13 +4584,15 @@ mod tests {
         ctx.squad_path = Some(SquadPath {
             anchor: AnchorPath::new(core, core),
             room_route: vec![r],
+            waypoint: None,
         });
         assert!(ctx.squad_path.is_some(), "precondition: the siege holds a formation (standoff) anchor");
 
         // Reproduce the reconcile Engaged arm EXACTLY: stamp the decision (D3 attack_target), THEN the D4
         // structure-siege anchor-drop (squad_manager.rs:2537-2539). The drain drop above does not fire here
         // (`movement` is Advance, not Drain), so this covers the NORMAL (non-drain) structure siege.
-        apply_squad_decision(&mut ctx, &decision, &creep_owner, true);
+        let mut orders = SquadOrders::default();
+        apply_squad_decision(&mut ctx, &mut orders, &decision, &creep_owner, true, |_| 0);
         if should_drop_anchor_for_drain(&decision) {
             ctx.squad_path = None;
         }
@@ -3602,7 +4605,7 @@ mod tests {
         // (2) D3 STAMP: EVERY present member fires the SAME position-only structure focus (directed raze).
         assert_eq!(ctx.members.len(), 3, "all three ranged members present");
         for member in ctx.members.iter() {
-            let orders = member.tick_orders.as_ref().expect("an Engaged member has tick_orders");
+            let orders = orders.get(member.entity).expect("an Engaged member has tick orders");
             // `AttackTarget` is Copy/Debug but not PartialEq (production; not touched here), so match it.
             assert!(
                 matches!(orders.attack_target, Some(AttackTarget::Structure(t)) if t == core),
@@ -3612,7 +4615,7 @@ mod tests {
         }
         // Each member also carries its own kernel approach goal (the anchorless mover reads this to close).
         for (member, goal) in ctx.members.iter().zip(member_goals.iter()) {
-            let orders = member.tick_orders.as_ref().unwrap();
+            let orders = orders.get(member.entity).unwrap();
             assert!(
                 matches!(orders.squad_movement, SquadMovement::Advance { goal: g, range: 0 } if Some(g) == *goal),
                 "the member routes its own kernel member_goal toward weapon range"
@@ -3633,8 +4636,10 @@ mod tests {
         ctx2.squad_path = Some(SquadPath {
             anchor: AnchorPath::new(core, core),
             room_route: vec![r],
+            waypoint: None,
         });
-        apply_squad_decision(&mut ctx2, &creep_decision, &creep_owner, true);
+        let mut creep_orders = SquadOrders::default();
+        apply_squad_decision(&mut ctx2, &mut creep_orders, &creep_decision, &creep_owner, true, |_| 0);
         if should_drop_anchor_for_drain(&creep_decision) {
             ctx2.squad_path = None;
         }
@@ -3644,7 +4649,7 @@ mod tests {
         assert!(ctx2.squad_path.is_some(), "a CREEP formation KEEPS its anchor (D4 scoped to id.is_none())");
         // `AttackTarget` is Copy/Debug but not PartialEq (production; not touched here), so match it.
         assert!(
-            matches!(ctx2.members[0].tick_orders.as_ref().unwrap().attack_target, Some(AttackTarget::Creep(id)) if id == live_creep),
+            matches!(creep_orders.get(ctx2.members[0].entity).unwrap().attack_target, Some(AttackTarget::Creep(id)) if id == live_creep),
             "a creep focus stamps a Creep attack_target (creep-fights untouched)"
         );
     }
@@ -1,11 +1,13 @@
-use crate::jobs::utility::dismantle::breach_path_total_hits;
+use crate::jobs::utility::dismantle::{breach_path, breach_path_total_hits, BreachBlocker};
 use crate::jobs::utility::dismantlebehavior::breach_blockers;
 use crate::room::data::{RoomData, RoomStructureData};
+use crate::room::hostilesummary::HostileBody;
 use screeps::*;
 use screeps_foreman::terrain::FastRoomTerrain;
 use serde::{Deserialize, Serialize};
 use specs::prelude::*;
 use specs::Component;
+use std::collections::HashMap;
 
 /// Analyzed information about a single hostile creep.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -14,11 +16,11 @@ pub struct HostileCreepInfo {
     pub owner: String,
     pub hits: u32,
     pub hits_max: u32,
-    /// Melee damage per tick (ATTACK parts * 30).
+    /// Melee damage per tick (ATTACK parts * 30, scaled by each part's boost).
     pub melee_dps: f32,
-    /// Ranged damage per tick (RANGED_ATTACK parts * 10).
+    /// Ranged damage per tick (RANGED_ATTACK parts * 10, scaled by each part's boost).
     pub ranged_dps: f32,
-    /// Heal per tick (HEAL parts * 12 for adjacent, * 4 for ranged).
+    /// Heal per tick (HEAL parts * 12 for adjacent, * 4 for ranged; scaled by each part's boost).
     pub heal_per_tick: f32,
     /// Total effective HP from TOUGH parts (accounting for boosts).
     pub tough_hp: f32,
@@ -67,7 +69,7 @@ pub enum ThreatLevel {
 /// The component is removed from a room entity when the room is confirmed
 /// safe (visible with no threats) or when the data expires.
 #[derive(Clone, Debug, Default, Serialize, Deserialize, Component)]
-#[storage(DenseVecStorage)]
+#[storage(FlaggedStorage)]
 pub struct RoomThreatData {
     /// The CANONICAL kind/harmlessness signal for this room (`ThreatLevel::None` == no threat;
     /// `PlayerScout`/`PlayerRaid`/`Invader`/… == a threat). Prefer this (or [`Self::warrants_attention`])
@@ -117,6 +119,17 @@ pub struct RoomThreatData {
     /// a later phase (P5). Reserved now so adding player-repair modelling later needs no further WFV bump.
     #[serde(default)]
     pub repair_per_tick: u32,
+    /// Tiles inside our rampart perimeter that hostiles can walk to (see [`defense_gaps`]). Owned
+    /// rooms only; recomputed every visible tick, so skipped by serde (no `WORLD_FORMAT_VERSION` bump).
+    #[serde(skip)]
+    pub defense_gaps: Vec<Position>,
+    /// The gap tiles where hostiles cross the perimeter — where defenders should be stationed.
+    #[serde(skip)]
+    pub breach_points: Vec<Position>,
+    /// Where players' claimers stand (see [`HostileBody::claimer`]); they attack controllers and
+    /// reservations unarmed, so they're kept apart from the combat numbers. Recomputed every visible tick.
+    #[serde(skip)]
+    pub claimers: Vec<Position>,
 }
 
 impl RoomThreatData {
@@ -130,72 +143,512 @@ impl RoomThreatData {
     }
 }
 
-/// Analyze a hostile creep's body to produce a `HostileCreepInfo`.
-pub fn analyze_hostile_creep(creep: &Creep) -> HostileCreepInfo {
-    let body = creep.body();
+pub const ROOM_AREA: usize = (ROOM_SIZE as usize) * (ROOM_SIZE as usize);
 
-    let mut melee_dps: f32 = 0.0;
-    let mut ranged_dps: f32 = 0.0;
-    let mut heal_per_tick: f32 = 0.0;
-    let mut tough_hp: f32 = 0.0;
-    let mut work_parts: u32 = 0;
-    let mut boosted = false;
+/// Row-major tile index (`y * 50 + x`) into the per-tile grids below.
+pub fn tile_index(x: u8, y: u8) -> usize {
+    (y as usize) * (ROOM_SIZE as usize) + (x as usize)
+}
+
+/// Hostile damage per tick that could land on each tile of `room` (indexed by [`tile_index`]):
+/// melee within range 1 and ranged within range 3 of each hostile creep, plus every energized
+/// hostile tower at its falloff range. A snapshot of current positions, not a movement forecast.
+pub fn incoming_dps_grid(room: RoomName, threat: &RoomThreatData) -> Vec<f32> {
+    let towers: Vec<Position> = threat
+        .hostile_tower_positions
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| threat.tower_energy.get(*i).is_none_or(|energy| *energy >= TOWER_ENERGY_COST))
+        .map(|(_, pos)| *pos)
+        .collect();
+
+    let mut grid = vec![0.0; ROOM_AREA];
 
-    for part_info in body.iter() {
-        // Only count parts that still have HP.
-        if part_info.hits() == 0 {
-            continue;
+    for y in 0..ROOM_SIZE {
+        for x in 0..ROOM_SIZE {
+            let pos = Position::new(RoomCoordinate::new(x).unwrap(), RoomCoordinate::new(y).unwrap(), room);
+
+            let mut dps = if towers.is_empty() {
+                0.0
+            } else {
+                crate::military::damage::total_tower_damage(&towers, pos)
+            };
+
+            for hostile in threat.hostile_creeps.iter() {
+                let range = hostile.position.get_range_to(pos);
+                if range <= 1 {
+                    dps += hostile.melee_dps;
+                }
+                if range <= 3 {
+                    dps += hostile.ranged_dps;
+                }
+            }
+
+            grid[tile_index(x, y)] = dps;
         }
+    }
 
-        let boost_multiplier = part_info
-            .boost()
-            .map(|_| {
-                boosted = true;
-                // Conservative estimate: assume T3 boosts for threat assessment.
-                // Real boost detection would require checking the specific compound.
-                4.0_f32
-            })
-            .unwrap_or(1.0);
+    grid
+}
+
+/// Result of [`defense_gaps`]; tiles are `(x, y)` in row-major order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DefenseGaps {
+    /// Interior tiles reachable from an exit without crossing a rampart.
+    pub tiles: Vec<(u8, u8)>,
+    /// Gap tiles next to a reachable tile outside the perimeter: the entry points.
+    pub breaches: Vec<(u8, u8)>,
+}
 
-        match part_info.part() {
-            Part::Attack => {
-                melee_dps += 30.0 * boost_multiplier;
+/// Find the holes in a rampart perimeter. The interior is every non-rampart tile with a rampart
+/// somewhere on at least three of its four sides (left, right, above, below), so a ring with a hole
+/// still has an interior. Hostiles flood-fill from the exit tiles through tiles that are neither `is_blocked`
+/// (terrain walls, obstacle structures) nor `is_rampart`; any interior tile they reach is a gap.
+pub fn defense_gaps<B, R>(is_blocked: B, is_rampart: R) -> DefenseGaps
+where
+    B: Fn(u8, u8) -> bool,
+    R: Fn(u8, u8) -> bool,
+{
+    let size = ROOM_SIZE as usize;
+    let mut row_span = vec![(u8::MAX, 0u8); size];
+    let mut col_span = vec![(u8::MAX, 0u8); size];
+
+    for y in 0..ROOM_SIZE {
+        for x in 0..ROOM_SIZE {
+            if is_rampart(x, y) {
+                let row = &mut row_span[y as usize];
+                *row = (row.0.min(x), row.1.max(x));
+                let col = &mut col_span[x as usize];
+                *col = (col.0.min(y), col.1.max(y));
             }
-            Part::RangedAttack => {
-                ranged_dps += 10.0 * boost_multiplier;
+        }
+    }
+
+    // Three of four sides is enough so a hole does not also drop its own row/column out of the hull.
+    let inside = |x: u8, y: u8| {
+        let (row, col) = (row_span[y as usize], col_span[x as usize]);
+        let sides = [row.0 < x, x < row.1, col.0 < y, y < col.1];
+        sides.iter().filter(|side| **side).count() >= 3 && !is_rampart(x, y)
+    };
+    let reachable = exit_reachable(|x, y| !is_blocked(x, y) && !is_rampart(x, y));
+
+    let mut gaps = DefenseGaps::default();
+
+    for y in 0..ROOM_SIZE {
+        for x in 0..ROOM_SIZE {
+            if reachable[tile_index(x, y)] && inside(x, y) {
+                gaps.tiles.push((x, y));
+
+                if neighbours(x, y).any(|(nx, ny)| reachable[tile_index(nx, ny)] && !inside(nx, ny)) {
+                    gaps.breaches.push((x, y));
+                }
             }
-            Part::Heal => {
-                heal_per_tick += 12.0 * boost_multiplier;
+        }
+    }
+
+    gaps
+}
+
+/// The up to eight tiles around `(x, y)` inside the room.
+fn neighbours(x: u8, y: u8) -> impl Iterator<Item = (u8, u8)> {
+    (-1i16..=1)
+        .flat_map(move |dy| (-1i16..=1).map(move |dx| (x as i16 + dx, y as i16 + dy)))
+        .filter(move |(nx, ny)| (*nx, *ny) != (x as i16, y as i16))
+        .filter(|(nx, ny)| (0..ROOM_SIZE as i16).contains(nx) && (0..ROOM_SIZE as i16).contains(ny))
+        .map(|(nx, ny)| (nx as u8, ny as u8))
+}
+
+/// Flood-fill from the exit tiles over `passable` tiles; indexed by [`tile_index`].
+fn exit_reachable<P>(passable: P) -> Vec<bool>
+where
+    P: Fn(u8, u8) -> bool,
+{
+    let mut reachable = vec![false; ROOM_AREA];
+    let mut pending: Vec<(u8, u8)> = Vec::new();
+
+    for i in 0..ROOM_SIZE {
+        for (x, y) in [(i, 0), (i, ROOM_SIZE - 1), (0, i), (ROOM_SIZE - 1, i)] {
+            if passable(x, y) && !reachable[tile_index(x, y)] {
+                reachable[tile_index(x, y)] = true;
+                pending.push((x, y));
             }
-            Part::Tough => {
-                // Boosted tough reduces damage taken. T3 XGHO2 = 70% reduction.
-                // Effective HP of the tough part itself.
-                if part_info.boost().is_some() {
-                    tough_hp += 100.0 / 0.3; // ~333 effective HP per boosted tough
-                } else {
-                    tough_hp += 100.0;
-                }
+        }
+    }
+
+    while let Some((x, y)) = pending.pop() {
+        for (nx, ny) in neighbours(x, y) {
+            if !reachable[tile_index(nx, ny)] && passable(nx, ny) {
+                reachable[tile_index(nx, ny)] = true;
+                pending.push((nx, ny));
             }
-            Part::Work => {
-                work_parts += 1;
+        }
+    }
+
+    reachable
+}
+
+/// Tiles next to the `core` structures (spawns, storage) that hostiles can walk to from an exit without
+/// crossing a tile that `is_blocked` (terrain walls, obstacle structures) or `is_rampart` (our ramparts).
+/// Empty while the perimeter holds.
+pub fn core_breaches<B, R>(is_blocked: B, is_rampart: R, core: &[(u8, u8)]) -> Vec<(u8, u8)>
+where
+    B: Fn(u8, u8) -> bool,
+    R: Fn(u8, u8) -> bool,
+{
+    let reachable = exit_reachable(|x, y| !is_blocked(x, y) && !is_rampart(x, y));
+
+    let mut breaches: Vec<(u8, u8)> = core
+        .iter()
+        .flat_map(|(x, y)| neighbours(*x, *y))
+        .filter(|(x, y)| reachable[tile_index(*x, *y)])
+        .collect();
+    breaches.sort_unstable();
+    breaches.dedup();
+
+    breaches
+}
+
+/// The tile to rampart first to close [`core_breaches`]: of the `candidates` (the room plan's rampart
+/// tiles without a rampart), the one leaving the fewest core tiles reachable once ramparted, then the one
+/// nearest the core. `None` when no candidate is on a hostile path.
+pub fn breach_choke<B, R>(is_blocked: B, is_rampart: R, core: &[(u8, u8)], candidates: &[(u8, u8)]) -> Option<(u8, u8)>
+where
+    B: Fn(u8, u8) -> bool,
+    R: Fn(u8, u8) -> bool,
+{
+    let reachable = exit_reachable(|x, y| !is_blocked(x, y) && !is_rampart(x, y));
+    let core_range = |(x, y): (u8, u8)| {
+        core.iter()
+            .map(|(cx, cy)| cx.abs_diff(x).max(cy.abs_diff(y)))
+            .min()
+            .unwrap_or(u8::MAX)
+    };
+
+    candidates
+        .iter()
+        .copied()
+        .filter(|(x, y)| reachable[tile_index(*x, *y)])
+        .map(|choke| {
+            let left = core_breaches(&is_blocked, |x, y| (x, y) == choke || is_rampart(x, y), core).len();
+
+            ((left, core_range(choke), choke), choke)
+        })
+        .min_by_key(|(rank, _)| *rank)
+        .map(|(_, choke)| choke)
+}
+
+/// A room's terrain and cached structures as the perimeter flood-fills see them: our non-public ramparts
+/// are the perimeter, roads and containers are walkable and every other structure is an obstacle.
+pub struct BarrierGrid {
+    terrain: FastRoomTerrain,
+    ramparts: Vec<bool>,
+    obstacles: Vec<bool>,
+}
+
+impl BarrierGrid {
+    /// `None` without visibility of the room.
+    pub fn new(room_name: RoomName, structures: &RoomStructureData) -> Option<BarrierGrid> {
+        let room = game::rooms().get(room_name)?;
+        let terrain = FastRoomTerrain::new(room.get_terrain().get_raw_buffer().to_vec());
+
+        let mut ramparts = vec![false; ROOM_AREA];
+        let mut obstacles = vec![false; ROOM_AREA];
+
+        for structure in structures.all() {
+            let pos = structure.pos();
+            let index = tile_index(pos.x().u8(), pos.y().u8());
+
+            match structure {
+                StructureObject::StructureRampart(rampart) => {
+                    if rampart.my() && !rampart.is_public() {
+                        ramparts[index] = true;
+                    }
+                }
+                StructureObject::StructureRoad(_) | StructureObject::StructureContainer(_) => {}
+                _ => obstacles[index] = true,
             }
+        }
+
+        Some(BarrierGrid {
+            terrain,
+            ramparts,
+            obstacles,
+        })
+    }
+
+    pub fn is_blocked(&self, x: u8, y: u8) -> bool {
+        self.terrain.is_wall(x, y) || self.obstacles[tile_index(x, y)]
+    }
+
+    pub fn is_rampart(&self, x: u8, y: u8) -> bool {
+        self.ramparts[tile_index(x, y)]
+    }
+}
+
+/// Perimeter gaps for one of our rooms from its cached structures.
+fn room_defense_gaps(room_data: &RoomData, structures: &RoomStructureData) -> DefenseGaps {
+    let Some(grid) = BarrierGrid::new(room_data.name, structures) else {
+        return DefenseGaps::default();
+    };
+
+    defense_gaps(|x, y| grid.is_blocked(x, y), |x, y| grid.is_rampart(x, y))
+}
+
+/// Paths costing up to this multiple of the cheapest one's barrier hits count as likely breaches.
+const LIKELY_BREACH_MARGIN: f64 = 1.25;
+/// Hits bands per doubling in the forecast cache key, about 19% apart: a barrier has to gain or lose
+/// that much before the forecast is searched again.
+const HITS_BANDS_PER_DOUBLING: f32 = 4.0;
+
+/// One predicted way from a room exit to the spawn and storage cluster.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BreachPath {
+    /// The exit tile the path starts from.
+    pub exit: (u8, u8),
+    /// The barrier tiles the attackers have to break, in walk order from the exit.
+    pub barriers: Vec<(u8, u8)>,
+    /// Total hits of those barriers.
+    pub hits: u32,
+    /// The first open tile past the last barrier, where the attackers come through. `None` when the
+    /// path crosses no barrier or its last barrier borders the core.
+    pub inside: Option<(u8, u8)>,
+}
+
+/// Where attackers will most likely break into one of our rooms: the search from each exit to the
+/// core with every barrier priced by its hits (see [`predict_breaches`]).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BreachForecast {
+    /// The likely paths, cheapest first, one per distinct barrier set.
+    pub paths: Vec<BreachPath>,
+}
+
+impl BreachForecast {
+    /// The barriers on the cheapest path: the segment to reinforce first.
+    pub fn weakest_segment(&self) -> &[(u8, u8)] {
+        self.paths.first().map(|path| path.barriers.as_slice()).unwrap_or(&[])
+    }
+
+    /// Whether `tile` holds a barrier on any likely path.
+    pub fn is_on_likely_path(&self, tile: (u8, u8)) -> bool {
+        self.paths.iter().any(|path| path.barriers.contains(&tile))
+    }
+}
+
+/// The middle tile of each run of open tiles along the room edges: one start per exit.
+pub fn exit_midpoints<W>(is_wall: W) -> Vec<(u8, u8)>
+where
+    W: Fn(u8, u8) -> bool,
+{
+    let last = ROOM_SIZE - 1;
+    let edges: [Vec<(u8, u8)>; 4] = [
+        (0..ROOM_SIZE).map(|x| (x, 0)).collect(),
+        (0..ROOM_SIZE).map(|y| (last, y)).collect(),
+        (0..ROOM_SIZE).map(|x| (x, last)).collect(),
+        (0..ROOM_SIZE).map(|y| (0, y)).collect(),
+    ];
+
+    let is_wall = &is_wall;
+
+    edges
+        .iter()
+        .flat_map(move |edge| edge.split(move |(x, y)| is_wall(*x, *y)).filter(|run| !run.is_empty()))
+        .map(|run| run[run.len() / 2])
+        .collect()
+}
+
+/// Search from every exit to the nearest `core` tile (spawns, storage) through `blockers` priced by
+/// their hits, and keep the paths within [`LIKELY_BREACH_MARGIN`] of the cheapest. The search is
+/// the breach corridor kernel hostile dismantlers would follow (`jobs::utility::dismantle`).
+pub fn predict_breaches(
+    is_wall: &dyn Fn(u8, u8) -> bool,
+    blockers: &HashMap<(u8, u8), BreachBlocker>,
+    exits: &[(u8, u8)],
+    core: &[(u8, u8)],
+) -> BreachForecast {
+    let range = |a: (u8, u8), b: (u8, u8)| a.0.abs_diff(b.0).max(a.1.abs_diff(b.1));
+
+    let mut paths: Vec<BreachPath> = exits
+        .iter()
+        .filter_map(|exit| {
+            let goal = core.iter().copied().min_by_key(|tile| range(*tile, *exit))?;
+            let walk = breach_path(is_wall, blockers, *exit, goal)?;
+
+            let hits_on = |tile: &(u8, u8)| match blockers.get(tile) {
+                Some(BreachBlocker::Dismantlable(hits)) => Some(*hits),
+                _ => None,
+            };
+
+            let barriers: Vec<(u8, u8)> = walk.iter().filter(|tile| hits_on(tile).is_some()).copied().collect();
+            let hits = walk.iter().filter_map(hits_on).fold(0u32, |acc, hits| acc.saturating_add(hits));
+            let inside = walk
+                .iter()
+                .rposition(|tile| hits_on(tile).is_some())
+                .and_then(|last| walk.get(last + 1))
+                .copied();
+
+            Some(BreachPath {
+                exit: *exit,
+                barriers,
+                hits,
+                inside,
+            })
+        })
+        .collect();
+
+    paths.sort_by_key(|path| (path.hits, path.barriers.len()));
+
+    let mut seen: Vec<Vec<(u8, u8)>> = Vec::new();
+    paths.retain(|path| {
+        if seen.contains(&path.barriers) {
+            return false;
+        }
+
+        seen.push(path.barriers.clone());
+        true
+    });
+
+    if let Some(cheapest) = paths.first().map(|path| path.hits) {
+        let limit = (cheapest as f64 * LIKELY_BREACH_MARGIN).ceil() as u32;
+
+        paths.retain(|path| path.hits <= limit);
+    }
+
+    BreachForecast { paths }
+}
+
+/// What stands in an attacker's way in one of our rooms: every structure but roads, containers and
+/// public ramparts, priced by its hits and summed where they stack. Structures without hits (the
+/// controller) are impassable.
+fn defense_blockers(structures: &[StructureObject]) -> HashMap<(u8, u8), BreachBlocker> {
+    let mut blockers: HashMap<(u8, u8), BreachBlocker> = HashMap::new();
+
+    for structure in structures {
+        match structure {
+            StructureObject::StructureRoad(_) | StructureObject::StructureContainer(_) => continue,
+            StructureObject::StructureRampart(rampart) if rampart.is_public() => continue,
             _ => {}
         }
+
+        let blocker = match structure.as_attackable().map(|a| a.hits()) {
+            Some(hits) if hits > 0 => BreachBlocker::Dismantlable(hits),
+            _ => BreachBlocker::Impassable,
+        };
+
+        let pos = structure.pos();
+        let tile = (pos.x().u8(), pos.y().u8());
+
+        let merged = match (blockers.get(&tile), blocker) {
+            (Some(BreachBlocker::Dismantlable(existing)), BreachBlocker::Dismantlable(hits)) => {
+                BreachBlocker::Dismantlable(existing.saturating_add(hits))
+            }
+            (Some(BreachBlocker::Impassable), _) => BreachBlocker::Impassable,
+            (_, blocker) => blocker,
+        };
+
+        blockers.insert(tile, merged);
+    }
+
+    blockers
+}
+
+/// The hits band of a barrier for the forecast cache key; see [`HITS_BANDS_PER_DOUBLING`].
+fn hits_band(hits: u32) -> u8 {
+    ((hits.max(1) as f32).log2() * HITS_BANDS_PER_DOUBLING) as u8
+}
+
+/// FNV-1a over the blocker tiles and their hits bands: the forecast is searched again only when a
+/// barrier appears, falls, or moves to another band.
+pub fn blocker_bands_fingerprint(blockers: &HashMap<(u8, u8), BreachBlocker>) -> u64 {
+    let mut tiles: Vec<(u8, u8, u8)> = blockers
+        .iter()
+        .map(|((x, y), blocker)| match blocker {
+            BreachBlocker::Dismantlable(hits) => (*x, *y, hits_band(*hits)),
+            BreachBlocker::Impassable => (*x, *y, u8::MAX),
+        })
+        .collect();
+
+    tiles.sort_unstable();
+
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for (x, y, band) in tiles {
+        for byte in [x, y, band] {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
     }
 
-    let owner = creep.owner().username();
+    hash
+}
+
+/// Cached breach forecasts per owned room. Refreshed by the wall repair mission while a room is
+/// under siege, read by the repair priorities there and by defenders choosing where to wait.
+/// Ephemeral: recomputed after a VM reload.
+#[derive(Default)]
+pub struct BreachForecasts {
+    rooms: HashMap<RoomName, (u64, BreachForecast)>,
+}
+
+impl BreachForecasts {
+    pub fn get(&self, room: RoomName) -> Option<&BreachForecast> {
+        self.rooms.get(&room).map(|(_, forecast)| forecast)
+    }
 
+    /// The room's forecast, searched again only when its barriers changed band. `None` without
+    /// visibility of the room or without a spawn or storage to defend.
+    pub fn update(&mut self, room_name: RoomName, structures: &RoomStructureData) -> Option<&BreachForecast> {
+        let blockers = defense_blockers(structures.all());
+        let fingerprint = blocker_bands_fingerprint(&blockers);
+
+        let cached = self
+            .rooms
+            .get(&room_name)
+            .map(|(cached, _)| *cached == fingerprint)
+            .unwrap_or(false);
+
+        if !cached {
+            let room = game::rooms().get(room_name)?;
+            let terrain = FastRoomTerrain::new(room.get_terrain().get_raw_buffer().to_vec());
+            let is_wall = |x: u8, y: u8| terrain.is_wall(x, y);
+
+            let core: Vec<(u8, u8)> = structures
+                .spawns()
+                .iter()
+                .filter(|s| s.my())
+                .map(|s| s.pos())
+                .chain(structures.storages().iter().filter(|s| s.my()).map(|s| s.pos()))
+                .map(|pos| (pos.x().u8(), pos.y().u8()))
+                .collect();
+
+            if core.is_empty() {
+                return None;
+            }
+
+            let forecast = predict_breaches(&is_wall, &blockers, &exit_midpoints(&is_wall), &core);
+
+            self.rooms.insert(room_name, (fingerprint, forecast));
+        }
+
+        self.get(room_name)
+    }
+
+    pub fn forget(&mut self, room: RoomName) {
+        self.rooms.remove(&room);
+    }
+}
+
+/// Build a `HostileCreepInfo` from a hostile creep and its body summary (see `room::hostilesummary`).
+pub fn analyze_hostile_creep(creep: &Creep, body: &HostileBody) -> HostileCreepInfo {
     HostileCreepInfo {
         position: creep.pos(),
-        owner,
+        owner: creep.owner().username(),
         hits: creep.hits(),
         hits_max: creep.hits_max(),
-        melee_dps,
-        ranged_dps,
-        heal_per_tick,
-        tough_hp,
-        work_parts,
-        boosted,
+        melee_dps: body.melee_dps,
+        ranged_dps: body.ranged_dps,
+        heal_per_tick: body.heal_per_tick,
+        tough_hp: body.tough_hp,
+        work_parts: body.work,
+        boosted: body.boosted,
     }
 }
 
@@ -314,10 +767,15 @@ impl<'a> System<'a> for ThreatAssessmentSystem {
             let mut estimated_attack_dps: f32 = 0.0;
             let mut estimated_heal: f32 = 0.0;
             let mut estimated_repair: u32 = 0;
+            let mut claimers = Vec::new();
 
             if let Some(creeps) = room_data.get_creeps() {
-                for hostile in creeps.hostile() {
-                    let info = analyze_hostile_creep(hostile);
+                for (hostile, body) in creeps.hostile_bodies() {
+                    if body.claimer() {
+                        claimers.push(hostile.pos());
+                    }
+
+                    let info = analyze_hostile_creep(hostile, body);
                     estimated_attack_dps += info.melee_dps + info.ranged_dps;
                     estimated_heal += info.heal_per_tick;
                     // Defenders repair the breach target (e.g. invader-stronghold creeps repairing
@@ -378,6 +836,20 @@ impl<'a> System<'a> for ThreatAssessmentSystem {
 
             let threat_level = classify_threat(&hostile_creep_infos, has_nukes, has_invader_core);
 
+            // Perimeter gaps matter only in our own rooms, and only while something is threatening them.
+            let owned = dynamic_vis.owner().mine();
+            let gaps = if owned && threat_level != ThreatLevel::None {
+                room_data.get_structures().map(|s| room_defense_gaps(room_data, &s)).unwrap_or_default()
+            } else {
+                DefenseGaps::default()
+            };
+            let to_positions = |tiles: Vec<(u8, u8)>| -> Vec<Position> {
+                tiles
+                    .into_iter()
+                    .map(|(x, y)| Position::new(RoomCoordinate::new(x).unwrap(), RoomCoordinate::new(y).unwrap(), room_data.name))
+                    .collect()
+            };
+
             // Persist when there are threats, nukes, invader cores, or an
             // enemy room has safe mode (relevant for attack planning even if
             // no hostiles are currently present).
@@ -404,6 +876,9 @@ impl<'a> System<'a> for ThreatAssessmentSystem {
                         estimated_heal,
                         safe_mode_active,
                         safe_mode_available,
+                        defense_gaps: to_positions(gaps.tiles),
+                        breach_points: to_positions(gaps.breaches),
+                        claimers,
                     },
                 );
             } else {
@@ -539,4 +1014,171 @@ mod tests {
         let four: Vec<_> = (0..4).map(|_| hostile("somePlayer", 30.0, 0.0, 0.0, false)).collect();
         assert_eq!(classify_threat(&four, false, false), ThreatLevel::PlayerSiege);
     }
+
+    /// A 10x10 rampart ring on open terrain, optionally missing one tile.
+    fn ring_gaps(hole: Option<(u8, u8)>) -> DefenseGaps {
+        defense_gaps(
+            |_, _| false,
+            |x, y| {
+                let on_ring = ((x == 10 || x == 20) && (10..=20).contains(&y)) || ((y == 10 || y == 20) && (10..=20).contains(&x));
+                on_ring && Some((x, y)) != hole
+            },
+        )
+    }
+
+    #[test]
+    fn sealed_perimeter_has_no_gaps() {
+        assert_eq!(ring_gaps(None), DefenseGaps::default());
+        // No ramparts at all: nothing is inside, so nothing is a gap.
+        assert_eq!(defense_gaps(|_, _| false, |_, _| false), DefenseGaps::default());
+    }
+
+    #[test]
+    fn hole_in_perimeter_exposes_the_interior() {
+        let gaps = ring_gaps(Some((15, 10)));
+
+        // The 9x9 interior plus the hole itself.
+        assert_eq!(gaps.tiles.len(), 82);
+        assert!(gaps.tiles.contains(&(15, 15)));
+        assert_eq!(gaps.breaches, vec![(15, 10)]);
+    }
+
+    #[test]
+    fn blocked_hole_is_not_a_gap() {
+        let gaps = defense_gaps(
+            |x, y| (x, y) == (15, 10),
+            |x, y| ((x == 10 || x == 20) && (10..=20).contains(&y)) || ((y == 10 || y == 20) && (10..=20).contains(&x) && x != 15),
+        );
+
+        assert_eq!(gaps, DefenseGaps::default());
+    }
+
+    /// A spawn at (15, 15) inside the 10x10 ring.
+    const CORE: [(u8, u8); 1] = [(15, 15)];
+
+    fn on_ring(x: u8, y: u8) -> bool {
+        ((x == 10 || x == 20) && (10..=20).contains(&y)) || ((y == 10 || y == 20) && (10..=20).contains(&x))
+    }
+
+    #[test]
+    fn core_is_breached_only_through_a_hole() {
+        let blocked = |x, y| CORE.contains(&(x, y));
+
+        assert!(core_breaches(blocked, on_ring, &CORE).is_empty());
+
+        let breaches = core_breaches(blocked, |x, y| on_ring(x, y) && (x, y) != (15, 10), &CORE);
+        assert_eq!(breaches.len(), 8, "every tile around the spawn");
+        assert!(breaches.contains(&(15, 14)));
+    }
+
+    #[test]
+    fn choke_is_the_missing_ring_tile_that_seals_the_core() {
+        let blocked = |x, y| CORE.contains(&(x, y));
+        let holed = |x, y| on_ring(x, y) && (x, y) != (15, 10);
+
+        // (30, 30) is an unbuilt plan tile hostiles can reach but that seals nothing.
+        assert_eq!(breach_choke(blocked, holed, &CORE, &[(30, 30), (15, 10)]), Some((15, 10)));
+        assert_eq!(
+            breach_choke(blocked, on_ring, &CORE, &[(15, 15)]),
+            None,
+            "nothing reaches the core tile"
+        );
+    }
+
+    /// The room edge is wall but for an exit on the top edge and one on the left.
+    fn two_exits(x: u8, y: u8) -> bool {
+        let on_edge = x == 0 || y == 0 || x == ROOM_SIZE - 1 || y == ROOM_SIZE - 1;
+        let exit = (y == 0 && (20..=30).contains(&x)) || (x == 0 && (20..=30).contains(&y));
+        on_edge && !exit
+    }
+
+    /// The ring's ramparts at a million hits, with `weak` tiles at their own hits.
+    fn ring_blockers(weak: &[((u8, u8), u32)]) -> HashMap<(u8, u8), BreachBlocker> {
+        let mut blockers: HashMap<(u8, u8), BreachBlocker> = (0..ROOM_SIZE)
+            .flat_map(|x| (0..ROOM_SIZE).map(move |y| (x, y)))
+            .filter(|(x, y)| on_ring(*x, *y))
+            .map(|tile| (tile, BreachBlocker::Dismantlable(1_000_000)))
+            .collect();
+
+        for (tile, hits) in weak {
+            blockers.insert(*tile, BreachBlocker::Dismantlable(*hits));
+        }
+
+        blockers
+    }
+
+    #[test]
+    fn exits_start_from_the_middle_of_each_open_run() {
+        assert_eq!(exit_midpoints(two_exits), vec![(25, 0), (0, 25)]);
+    }
+
+    #[test]
+    fn breach_forecast_finds_the_weakest_rampart_from_every_exit() {
+        let exits = exit_midpoints(two_exits);
+        let forecast = predict_breaches(&two_exits, &ring_blockers(&[((15, 10), 100_000)]), &exits, &CORE);
+
+        // Both exits break in through the same rampart: one path.
+        assert_eq!(forecast.paths.len(), 1);
+        assert_eq!(forecast.weakest_segment(), &[(15, 10)]);
+        assert_eq!(forecast.paths[0].hits, 100_000);
+        assert!(
+            forecast.paths[0].inside.is_some_and(|(_, y)| y == 11),
+            "just inside the top of the ring"
+        );
+        assert!(forecast.is_on_likely_path((15, 10)));
+        assert!(!forecast.is_on_likely_path((10, 15)));
+    }
+
+    #[test]
+    fn breach_forecast_keeps_near_equal_paths_and_drops_strong_ones() {
+        // A third exit on the right, cut off by a terrain wall down x = 25 whose one gap at
+        // (25, 30) is walled off too: that exit has to break two barriers.
+        let divided = |x: u8, y: u8| {
+            let right_exit = x == ROOM_SIZE - 1 && (20..=30).contains(&y);
+            let divider = x == 25 && y >= 1 && y != 30;
+            (two_exits(x, y) && !right_exit) || divider
+        };
+
+        let exits = exit_midpoints(divided);
+        assert_eq!(exits, vec![(25, 0), (49, 25), (0, 25)]);
+
+        let blockers = ring_blockers(&[((15, 10), 100_000), ((10, 15), 100_000), ((25, 30), 1_000_000)]);
+        let forecast = predict_breaches(&divided, &blockers, &exits, &CORE);
+
+        let segments: Vec<&[(u8, u8)]> = forecast.paths.iter().map(|path| path.barriers.as_slice()).collect();
+        assert_eq!(segments, vec![&[(15, 10)][..], &[(10, 15)][..]]);
+    }
+
+    #[test]
+    fn fingerprint_moves_only_when_a_barrier_changes_band() {
+        let fingerprint = |hits| blocker_bands_fingerprint(&ring_blockers(&[((15, 10), hits)]));
+
+        assert_eq!(fingerprint(1_000_000), fingerprint(1_010_000));
+        assert_ne!(fingerprint(1_000_000), fingerprint(1_300_000));
+        assert_ne!(
+            blocker_bands_fingerprint(&ring_blockers(&[])),
+            blocker_bands_fingerprint(&HashMap::new())
+        );
+    }
+
+    #[test]
+    fn incoming_dps_covers_melee_and_ranged_reach() {
+        let mut attacker = hostile("somePlayer", 30.0, 10.0, 0.0, false);
+        attacker.position = Position::new(
+            RoomCoordinate::new(25).unwrap(),
+            RoomCoordinate::new(25).unwrap(),
+            "E0N0".parse().unwrap(),
+        );
+        let threat = RoomThreatData {
+            hostile_creeps: vec![attacker],
+            ..Default::default()
+        };
+
+        let grid = incoming_dps_grid("E0N0".parse().unwrap(), &threat);
+
+        assert_eq!(grid[tile_index(25, 25)], 40.0);
+        assert_eq!(grid[tile_index(26, 24)], 40.0);
+        assert_eq!(grid[tile_index(28, 25)], 10.0);
+        assert_eq!(grid[tile_index(29, 25)], 0.0);
+    }
 }
@@ -0,0 +1,104 @@
+use super::threatmap::*;
+use crate::room::data::*;
+use crate::visualize::*;
+use screeps::*;
+use specs::prelude::*;
+
+// ---------------------------------------------------------------------------
+// ThreatMapVisualizeSystem — incoming DPS heat map and perimeter gaps
+// ---------------------------------------------------------------------------
+
+/// Heat ramp end points: no damage -> the room's peak damage.
+const HEAT_COLD: (u8, u8, u8) = (0x3f, 0xb9, 0x50);
+const HEAT_HOT: (u8, u8, u8) = (0xf8, 0x51, 0x49);
+const HEAT_OPACITY: f32 = 0.3;
+
+const COLOR_GAP: &str = "#d29922";
+const COLOR_BREACH: &str = "#f85149";
+
+/// Green -> red by `fraction` (clamped to 0..=1).
+pub fn heat_color(fraction: f32) -> String {
+    let t = if fraction.is_finite() { fraction.clamp(0.0, 1.0) } else { 0.0 };
+    let mix = |cold: u8, hot: u8| (cold as f32 + (hot as f32 - cold as f32) * t).round() as u8;
+
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        mix(HEAT_COLD.0, HEAT_HOT.0),
+        mix(HEAT_COLD.1, HEAT_HOT.1),
+        mix(HEAT_COLD.2, HEAT_HOT.2)
+    )
+}
+
+#[derive(SystemData)]
+pub struct ThreatMapVisualizeSystemData<'a> {
+    room_data: ReadStorage<'a, RoomData>,
+    threat_data: ReadStorage<'a, RoomThreatData>,
+    visualizer: Option<Write<'a, Visualizer>>,
+    features: Read<'a, crate::features::Features>,
+}
+
+/// Renders each threatened room's incoming DPS as a heat map (tiles shaded
+/// green to red relative to the room's peak) and outlines the perimeter gaps
+/// hostiles can walk through, with the breach points circled.
+///
+/// Only runs when the `military.visualize_threat` feature flag is enabled.
+pub struct ThreatMapVisualizeSystem;
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+impl<'a> System<'a> for ThreatMapVisualizeSystem {
+    type SystemData = ThreatMapVisualizeSystemData<'a>;
+
+    fn run(&mut self, mut data: Self::SystemData) {
+        if !data.features.military.visualize_threat {
+            return;
+        }
+
+        let Some(visualizer) = data.visualizer.as_deref_mut() else {
+            return;
+        };
+
+        for (room_data, threat) in (&data.room_data, &data.threat_data).join() {
+            let grid = incoming_dps_grid(room_data.name, threat);
+            let peak = grid.iter().copied().fold(0.0f32, f32::max);
+            let room_vis = visualizer.get_room(room_data.name, VisualLayer::Threat);
+
+            if peak > 0.0 {
+                for y in 0..ROOM_SIZE {
+                    for x in 0..ROOM_SIZE {
+                        let dps = grid[tile_index(x, y)];
+                        if dps <= 0.0 {
+                            continue;
+                        }
+
+                        let style = RectStyle::default().fill(&heat_color(dps / peak)).opacity(HEAT_OPACITY);
+                        room_vis.rect(x as f32 - 0.5, y as f32 - 0.5, 1.0, 1.0, Some(style));
+                    }
+                }
+            }
+
+            for gap in threat.defense_gaps.iter() {
+                let style = RectStyle::default().fill("transparent").stroke(COLOR_GAP).stroke_width(0.08);
+                room_vis.rect(gap.x().u8() as f32 - 0.45, gap.y().u8() as f32 - 0.45, 0.9, 0.9, Some(style));
+            }
+
+            for breach in threat.breach_points.iter() {
+                let style = CircleStyle::default().radius(0.3).fill(COLOR_BREACH).opacity(0.8);
+                room_vis.circle(breach.x().u8() as f32, breach.y().u8() as f32, Some(style));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heat_color_ramps_green_to_red() {
+        assert_eq!(heat_color(0.0), "#3fb950");
+        assert_eq!(heat_color(1.0), "#f85149");
+        assert_eq!(heat_color(2.0), "#f85149");
+        assert_eq!(heat_color(f32::NAN), "#3fb950");
+        assert_ne!(heat_color(0.5), heat_color(0.0));
+    }
+}
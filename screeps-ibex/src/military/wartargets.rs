@@ -0,0 +1,295 @@
+//! Standing war declarations: the players and rooms the war operation
+//! attacks without an attack flag.
+//!
+//! The operator declares war from the console (`declare_war <player|room>`)
+//! and lifts it with `cease_fire <player|room>`; the declarations are kept
+//! in `Memory._war` so they survive VM restarts. Every offense scan the war
+//! operation (`operations::war`) turns each declaration into at most one
+//! attack candidate: a declared room as it stands, a declared player's
+//! weakest reachable room by [`DeclaredRoom::strength`]. A pick whose intel
+//! is older than [`DECLARED_INTEL_MAX_AGE`] is re-scouted instead of
+//! committed. The candidates then go through the same winnability, ROI and
+//! operation budget gates as every other offense target.
+//!
+//! A cease-fire stands the declaration's rooms down for
+//! [`STAND_DOWN_TICKS`]: the war operation withdraws their objectives, the
+//! squad manager retires the squads, and the members walk home to recycle
+//! even under fire, instead of fighting on where they stand.
+
+use log::*;
+use screeps::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const MEMORY_PATH: &str = "_war";
+
+/// Ticks a declared target's scouted intel is trusted before it is
+/// re-scouted; the offense scan's own staleness bound.
+pub const DECLARED_INTEL_MAX_AGE: u32 = 200;
+/// Ticks a cease-fire holds its rooms: long enough for every squad member
+/// fielded against them to have died of age.
+pub const STAND_DOWN_TICKS: u32 = CREEP_LIFE_TIME;
+/// Damage per tick an active spawn counts for in a room's strength: about
+/// the defender it fields before a squad can finish the job.
+const SPAWN_DEFENDER_DPS: f32 = 300.0;
+
+/// A declared enemy: every room a player owns, or one room.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum WarTarget {
+    Player(String),
+    Room(RoomName),
+}
+
+impl WarTarget {
+    /// A room name is a room; anything else is a player name.
+    pub fn parse(arg: &str) -> WarTarget {
+        match RoomName::new(arg) {
+            Ok(room) => WarTarget::Room(room),
+            Err(_) => WarTarget::Player(arg.to_string()),
+        }
+    }
+}
+
+impl std::fmt::Display for WarTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WarTarget::Player(player) => write!(f, "player {}", player),
+            WarTarget::Room(room) => write!(f, "room {}", room),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Declarations {
+    #[serde(default)]
+    players: Vec<String>,
+    #[serde(default)]
+    rooms: Vec<RoomName>,
+}
+
+/// World resource: the persisted declarations, plus the rooms pursued for
+/// them and the rooms standing down this VM.
+#[derive(Default)]
+pub struct WarTargets {
+    declared: Declarations,
+    /// Rooms picked for a declaration, with the declaration they serve.
+    engaged: HashMap<RoomName, WarTarget>,
+    /// Rooms under a cease-fire, with the declaration lifted and the tick
+    /// it was called.
+    standing_down: HashMap<RoomName, (WarTarget, u32)>,
+}
+
+impl WarTargets {
+    /// Read the persisted declarations back from Memory.
+    pub fn load() -> WarTargets {
+        let declared = serde_wasm_bindgen::from_value(crate::memory_helper::path_get(MEMORY_PATH)).unwrap_or_default();
+
+        WarTargets {
+            declared,
+            ..Default::default()
+        }
+    }
+
+    pub fn save(&self) {
+        let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+
+        match self.declared.serialize(&serializer) {
+            Ok(value) => crate::memory_helper::path_set(MEMORY_PATH, value),
+            Err(err) => warn!("Failed to save war declarations: {}", err),
+        }
+    }
+
+    /// Declare war on `target`, lifting any cease-fire on its rooms. Returns
+    /// false if it was already declared.
+    pub fn declare(&mut self, target: WarTarget) -> bool {
+        self.standing_down
+            .retain(|room, (lifted, _)| *lifted != target && target != WarTarget::Room(*room));
+
+        match target {
+            WarTarget::Player(player) if !self.declared.players.contains(&player) => self.declared.players.push(player),
+            WarTarget::Room(room) if !self.declared.rooms.contains(&room) => self.declared.rooms.push(room),
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// Lift the declaration on `target` and stand its rooms down. Returns
+    /// the rooms standing down, or `None` if `target` was not declared.
+    pub fn cease_fire(&mut self, target: &WarTarget, now: u32) -> Option<Vec<RoomName>> {
+        let declared = match target {
+            WarTarget::Player(player) => remove(&mut self.declared.players, player),
+            WarTarget::Room(room) => remove(&mut self.declared.rooms, room),
+        };
+
+        if !declared {
+            return None;
+        }
+
+        let mut rooms: Vec<RoomName> = self
+            .engaged
+            .iter()
+            .filter(|(_, engaged_for)| *engaged_for == target)
+            .map(|(room, _)| *room)
+            .collect();
+
+        if let WarTarget::Room(room) = target {
+            if !rooms.contains(room) {
+                rooms.push(*room);
+            }
+        }
+
+        for room in &rooms {
+            self.engaged.remove(room);
+            self.standing_down.insert(*room, (target.clone(), now));
+        }
+
+        rooms.sort();
+
+        Some(rooms)
+    }
+
+    /// Every declaration, players first.
+    pub fn declarations(&self) -> Vec<WarTarget> {
+        self.declared
+            .players
+            .iter()
+            .cloned()
+            .map(WarTarget::Player)
+            .chain(self.declared.rooms.iter().copied().map(WarTarget::Room))
+            .collect()
+    }
+
+    /// Record that `room` is being pursued for `target`, so a cease-fire on
+    /// `target` knows to stand it down.
+    pub fn engage(&mut self, room: RoomName, target: WarTarget) {
+        self.engaged.insert(room, target);
+    }
+
+    /// Forget the stand-downs older than [`STAND_DOWN_TICKS`].
+    pub fn expire(&mut self, now: u32) {
+        self.standing_down
+            .retain(|_, (_, called_at)| now.saturating_sub(*called_at) < STAND_DOWN_TICKS);
+    }
+
+    pub fn is_standing_down(&self, room: RoomName) -> bool {
+        self.standing_down.contains_key(&room)
+    }
+
+    pub fn standing_down(&self) -> impl Iterator<Item = RoomName> + '_ {
+        self.standing_down.keys().copied()
+    }
+}
+
+fn remove<T: PartialEq>(list: &mut Vec<T>, item: &T) -> bool {
+    let before = list.len();
+    list.retain(|x| x != item);
+    list.len() != before
+}
+
+/// A declared room, or one of a declared player's rooms, as last scouted.
+#[derive(Clone, Debug)]
+pub struct DeclaredRoom {
+    pub room: RoomName,
+    /// Room hops from the nearest home.
+    pub distance: u32,
+    /// Damage per tick of the room's energized towers.
+    pub tower_dps: f32,
+    pub creep_dps: f32,
+    pub creep_heal: f32,
+    pub active_spawns: bool,
+    pub safe_mode: bool,
+    pub last_seen: u32,
+}
+
+impl DeclaredRoom {
+    /// What a squad has to out-heal and out-damage to take the room.
+    pub fn strength(&self) -> f32 {
+        let spawns = if self.active_spawns { SPAWN_DEFENDER_DPS } else { 0.0 };
+
+        self.tower_dps + self.creep_dps + self.creep_heal + spawns
+    }
+
+    pub fn is_stale(&self, now: u32) -> bool {
+        now.saturating_sub(self.last_seen) > DECLARED_INTEL_MAX_AGE
+    }
+}
+
+/// The weakest room to attack, the nearer on a tie. Rooms in safe mode
+/// can't be attacked and are never picked.
+pub fn weakest_room(rooms: &[DeclaredRoom]) -> Option<&DeclaredRoom> {
+    rooms.iter().filter(|r| !r.safe_mode).min_by(|a, b| {
+        a.strength()
+            .partial_cmp(&b.strength())
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.distance.cmp(&b.distance))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn room(name: &str) -> RoomName {
+        name.parse().expect("valid room name")
+    }
+
+    fn scouted(name: &str, distance: u32, tower_dps: f32, active_spawns: bool) -> DeclaredRoom {
+        DeclaredRoom {
+            room: room(name),
+            distance,
+            tower_dps,
+            creep_dps: 0.0,
+            creep_heal: 0.0,
+            active_spawns,
+            safe_mode: false,
+            last_seen: 1_000,
+        }
+    }
+
+    #[test]
+    fn weakest_room_weighs_towers_and_spawns_then_distance() {
+        let rooms = [
+            scouted("W1N1", 2, 600.0, true),
+            scouted("W2N1", 5, 0.0, true),
+            scouted("W3N1", 3, 0.0, true),
+            scouted("W4N1", 1, 150.0, true),
+        ];
+        assert_eq!(weakest_room(&rooms).map(|r| r.room), Some(room("W3N1")));
+
+        let mut safe = scouted("W5N1", 1, 0.0, false);
+        safe.safe_mode = true;
+        assert_eq!(weakest_room(&[safe]).map(|r| r.room), None);
+        assert!(scouted("W3N1", 3, 0.0, true).is_stale(1_000 + DECLARED_INTEL_MAX_AGE + 1));
+    }
+
+    #[test]
+    fn cease_fire_stands_down_the_rooms_pursued_for_the_target() {
+        let mut targets = WarTargets::default();
+
+        assert!(targets.declare(WarTarget::parse("Enemy")));
+        assert!(!targets.declare(WarTarget::parse("Enemy")));
+        assert!(targets.declare(WarTarget::parse("W9N9")));
+        assert_eq!(
+            targets.declarations(),
+            vec![WarTarget::Player("Enemy".to_string()), WarTarget::Room(room("W9N9"))]
+        );
+
+        targets.engage(room("W1N1"), WarTarget::Player("Enemy".to_string()));
+        targets.engage(room("W9N9"), WarTarget::Room(room("W9N9")));
+
+        assert_eq!(targets.cease_fire(&WarTarget::parse("Enemy"), 100), Some(vec![room("W1N1")]));
+        assert_eq!(targets.cease_fire(&WarTarget::parse("Enemy"), 100), None);
+        assert!(targets.is_standing_down(room("W1N1")));
+        assert!(!targets.is_standing_down(room("W9N9")));
+
+        // Declaring again lifts the stand-down at once; otherwise it lapses.
+        targets.declare(WarTarget::parse("Enemy"));
+        assert!(!targets.is_standing_down(room("W1N1")));
+
+        targets.cease_fire(&WarTarget::parse("W9N9"), 200);
+        assert!(targets.is_standing_down(room("W9N9")));
+        targets.expire(200 + STAND_DOWN_TICKS);
+        assert!(!targets.is_standing_down(room("W9N9")));
+    }
+}
@@ -0,0 +1,363 @@
+//! World-map overlay of the operation -> mission -> child mission hierarchy.
+//!
+//! With `visualize.mission_map` on, `RunMissionSystem` records each
+//! mission's `describe_state` and how its run went ([`MissionMapState`]),
+//! the aggregate pass groups every room's missions under the operation that
+//! owns them, and the render pass writes one indented block of text per room
+//! through the map visualizer. Rooms with more lines than fit collapse to
+//! one count line per operation.
+
+use crate::missions::data::*;
+use crate::operations::data::*;
+use crate::visualization::truncate_content;
+use crate::visualize::MapVisualizer;
+use screeps::*;
+use specs::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// Lines that fit in one room on the map at the font size below.
+pub const MAX_MAP_LINES: usize = 15;
+
+const MAX_MAP_LINE_CHARS: usize = 32;
+
+const MAP_LEFT: u8 = 1;
+const MAP_TOP: u8 = 3;
+const MAP_LINE_STEP: u8 = 3;
+const MAP_INDENT_STEP: u8 = 2;
+const MAP_FONT_SIZE: f32 = 2.4;
+
+/// Guards the owner walk against a corrupt owner cycle.
+const MAX_OWNER_DEPTH: usize = 16;
+
+const COLOR_OPERATION: &str = "#58a6ff";
+const COLOR_RUNNING: &str = "#3fb950";
+const COLOR_FAILING: &str = "#f85149";
+const COLOR_COMPLETE: &str = "#8b949e";
+
+/// How a mission's last run went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissionHealth {
+    Running,
+    /// The run or pre-run returned an error; the mission is being aborted.
+    Failing,
+    /// The mission finished this tick and is being cleaned up.
+    Complete,
+}
+
+impl MissionHealth {
+    pub fn color(self) -> &'static str {
+        match self {
+            MissionHealth::Running => COLOR_RUNNING,
+            MissionHealth::Failing => COLOR_FAILING,
+            MissionHealth::Complete => COLOR_COMPLETE,
+        }
+    }
+}
+
+/// Recorded by the mission systems for each mission that ran this tick.
+#[derive(Debug, Clone)]
+pub struct MissionMapState {
+    pub state: String,
+    pub health: MissionHealth,
+}
+
+#[derive(Debug, Clone)]
+pub struct MissionMapNode {
+    pub label: String,
+    pub health: MissionHealth,
+    pub children: Vec<MissionMapNode>,
+}
+
+impl MissionMapNode {
+    fn count(&self, health: Option<MissionHealth>) -> usize {
+        let own = health.is_none_or(|h| h == self.health) as usize;
+
+        own + self.children.iter().map(|c| c.count(health)).sum::<usize>()
+    }
+}
+
+/// The missions in one room that share an owning operation.
+#[derive(Debug, Clone)]
+pub struct MissionMapGroup {
+    pub operation: String,
+    pub missions: Vec<MissionMapNode>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MissionMapLine {
+    pub indent: u8,
+    pub text: String,
+    pub color: &'static str,
+}
+
+/// `"Type: state"` using only the first line of the state, or just the type
+/// when there is no state.
+pub fn mission_label(type_name: &str, state: &str) -> String {
+    match state.lines().next().map(str::trim).filter(|s| !s.is_empty()) {
+        Some(state) => format!("{}: {}", type_name, state),
+        None => type_name.to_string(),
+    }
+}
+
+/// Group a room's missions by owning operation. Missions owned by another
+/// mission in the same room are nested under it; the rest are roots. A root
+/// owned by a mission elsewhere is grouped under that mission's operation.
+pub fn build_room_groups(
+    room_missions: &[Entity],
+    missions: &ReadStorage<MissionData>,
+    operations: &ReadStorage<OperationData>,
+    states: &HashMap<Entity, MissionMapState>,
+) -> Vec<MissionMapGroup> {
+    let in_room: HashSet<Entity> = room_missions.iter().copied().filter(|e| missions.get(*e).is_some()).collect();
+    let mut visited = HashSet::new();
+    let mut groups: Vec<MissionMapGroup> = Vec::new();
+
+    for entity in room_missions.iter().copied().filter(|e| in_room.contains(e)) {
+        let Some(mission) = missions.get(entity) else {
+            continue;
+        };
+        let owner = *mission.as_mission().get_owner();
+        if owner.is_some_and(|o| in_room.contains(&o)) {
+            continue;
+        }
+
+        let operation = owning_operation(owner, missions, operations);
+        let Some(node) = build_node(entity, &in_room, &mut visited, missions, states) else {
+            continue;
+        };
+
+        match groups.iter_mut().find(|g| g.operation == operation) {
+            Some(group) => group.missions.push(node),
+            None => groups.push(MissionMapGroup {
+                operation,
+                missions: vec![node],
+            }),
+        }
+    }
+
+    groups
+}
+
+fn owning_operation(mut owner: Option<Entity>, missions: &ReadStorage<MissionData>, operations: &ReadStorage<OperationData>) -> String {
+    for _ in 0..MAX_OWNER_DEPTH {
+        let Some(entity) = owner else {
+            break;
+        };
+
+        if let Some(operation) = operations.get(entity) {
+            return operation.type_name().to_string();
+        }
+
+        match missions.get(entity) {
+            Some(mission) => owner = *mission.as_mission().get_owner(),
+            None => break,
+        }
+    }
+
+    "Unowned".to_string()
+}
+
+fn build_node(
+    entity: Entity,
+    in_room: &HashSet<Entity>,
+    visited: &mut HashSet<Entity>,
+    missions: &ReadStorage<MissionData>,
+    states: &HashMap<Entity, MissionMapState>,
+) -> Option<MissionMapNode> {
+    if !visited.insert(entity) {
+        return None;
+    }
+
+    let mission_data = missions.get(entity)?;
+    let state = states.get(&entity);
+    let children = mission_data.as_mission().get_children();
+
+    Some(MissionMapNode {
+        label: mission_label(mission_data.type_name(), state.map_or("", |s| s.state.as_str())),
+        health: state.map_or(MissionHealth::Running, |s| s.health),
+        children: children
+            .into_iter()
+            .filter(|child| in_room.contains(child))
+            .filter_map(|child| build_node(child, in_room, visited, missions, states))
+            .collect(),
+    })
+}
+
+fn push_node_lines(node: &MissionMapNode, indent: u8, lines: &mut Vec<MissionMapLine>) {
+    lines.push(MissionMapLine {
+        indent,
+        text: truncate_content(&node.label, MAX_MAP_LINE_CHARS),
+        color: node.health.color(),
+    });
+
+    for child in node.children.iter() {
+        push_node_lines(child, indent + 1, lines);
+    }
+}
+
+/// Lay out a room's groups as at most `max_lines` lines. Everything is
+/// shown when it fits; otherwise each operation collapses to a count line,
+/// and if even those overflow the tail is folded into a "+N more" line.
+pub fn layout_room(groups: &[MissionMapGroup], max_lines: usize) -> Vec<MissionMapLine> {
+    let mut lines = Vec::new();
+
+    for group in groups {
+        lines.push(MissionMapLine {
+            indent: 0,
+            text: truncate_content(&group.operation, MAX_MAP_LINE_CHARS),
+            color: COLOR_OPERATION,
+        });
+
+        for node in group.missions.iter() {
+            push_node_lines(node, 1, &mut lines);
+        }
+    }
+
+    if lines.len() <= max_lines {
+        return lines;
+    }
+
+    let mut lines: Vec<MissionMapLine> = groups
+        .iter()
+        .map(|group| {
+            let total: usize = group.missions.iter().map(|n| n.count(None)).sum();
+            let failing: usize = group.missions.iter().map(|n| n.count(Some(MissionHealth::Failing))).sum();
+
+            let text = if failing > 0 {
+                format!("{}: {} missions, {} failing", group.operation, total, failing)
+            } else {
+                format!("{}: {} missions", group.operation, total)
+            };
+
+            MissionMapLine {
+                indent: 0,
+                text: truncate_content(&text, MAX_MAP_LINE_CHARS),
+                color: if failing > 0 { COLOR_FAILING } else { COLOR_OPERATION },
+            }
+        })
+        .collect();
+
+    if lines.len() > max_lines {
+        let keep = max_lines.saturating_sub(1);
+        let hidden = lines.len() - keep;
+
+        lines.truncate(keep);
+
+        if max_lines > 0 {
+            lines.push(MissionMapLine {
+                indent: 0,
+                text: format!("+{} more", hidden),
+                color: COLOR_OPERATION,
+            });
+        }
+    }
+
+    lines
+}
+
+/// Write a room's lines down the left edge of the room on the world map.
+pub fn draw_room(map_vis: &mut MapVisualizer, room: RoomName, lines: &[MissionMapLine]) {
+    for (i, line) in lines.iter().enumerate() {
+        let x = MAP_LEFT.saturating_add(line.indent.saturating_mul(MAP_INDENT_STEP));
+        let y = MAP_TOP.saturating_add((i as u8).saturating_mul(MAP_LINE_STEP));
+
+        let (Ok(x), Ok(y)) = (RoomCoordinate::new(x.min(49)), RoomCoordinate::new(y.min(49))) else {
+            continue;
+        };
+
+        let style = MapTextStyle::default()
+            .color(line.color)
+            .font_size(MAP_FONT_SIZE)
+            .align(TextAlign::Left)
+            .opacity(0.9);
+
+        map_vis.text(Position::new(x, y, room), line.text.clone(), style);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(label: &str, health: MissionHealth, children: Vec<MissionMapNode>) -> MissionMapNode {
+        MissionMapNode {
+            label: label.to_string(),
+            health,
+            children,
+        }
+    }
+
+    fn group(operation: &str, missions: Vec<MissionMapNode>) -> MissionMapGroup {
+        MissionMapGroup {
+            operation: operation.to_string(),
+            missions,
+        }
+    }
+
+    fn texts(lines: &[MissionMapLine]) -> Vec<(u8, &str)> {
+        lines.iter().map(|l| (l.indent, l.text.as_str())).collect()
+    }
+
+    #[test]
+    fn label_uses_first_line_of_state() {
+        assert_eq!(mission_label("Colony", "Running\nspawns: 2"), "Colony: Running");
+        assert_eq!(mission_label("Tower", ""), "Tower");
+        assert_eq!(mission_label("Tower", "  \nsecond"), "Tower");
+    }
+
+    #[test]
+    fn full_hierarchy_when_it_fits() {
+        let groups = [group(
+            "Colony",
+            vec![node(
+                "Colony: Running",
+                MissionHealth::Running,
+                vec![node("Haul", MissionHealth::Failing, vec![])],
+            )],
+        )];
+
+        let lines = layout_room(&groups, MAX_MAP_LINES);
+
+        assert_eq!(texts(&lines), vec![(0, "Colony"), (1, "Colony: Running"), (2, "Haul")]);
+        assert_eq!(lines[2].color, COLOR_FAILING);
+    }
+
+    #[test]
+    fn collapses_to_counts_when_too_long() {
+        let groups = [
+            group(
+                "Colony",
+                vec![node(
+                    "Colony",
+                    MissionHealth::Running,
+                    vec![
+                        node("Haul", MissionHealth::Failing, vec![]),
+                        node("Tower", MissionHealth::Running, vec![]),
+                    ],
+                )],
+            ),
+            group("Unowned", vec![node("Scout", MissionHealth::Complete, vec![])]),
+        ];
+
+        let lines = layout_room(&groups, 4);
+
+        assert_eq!(
+            texts(&lines),
+            vec![(0, "Colony: 3 missions, 1 failing"), (0, "Unowned: 1 missions")]
+        );
+        assert_eq!(lines[0].color, COLOR_FAILING);
+        assert_eq!(lines[1].color, COLOR_OPERATION);
+    }
+
+    #[test]
+    fn folds_overflowing_operations() {
+        let groups: Vec<_> = (0..5)
+            .map(|i| group(&format!("Op{}", i), vec![node("Scout", MissionHealth::Running, vec![])]))
+            .collect();
+
+        let lines = layout_room(&groups, 3);
+
+        assert_eq!(texts(&lines), vec![(0, "Op0: 1 missions"), (0, "Op1: 1 missions"), (0, "+3 more")]);
+        assert!(layout_room(&groups, 0).is_empty());
+    }
+}
@@ -31,6 +31,7 @@ pub struct ClaimMission {
     claimer_deaths: u32,
     /// Tick of the last claimer spawn request, for the exponential backoff.
     last_spawn_tick: Option<u32>,
+    paused: bool,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -54,9 +55,14 @@ impl ClaimMission {
             claimers: EntityVec::new(),
             claimer_deaths: 0,
             last_spawn_tick: None,
+            paused: false,
         }
     }
 
+    pub fn room_data(&self) -> Entity {
+        self.room_data
+    }
+
     pub fn home_room_datas(&self) -> &EntityVec<Entity> {
         &self.home_room_datas
     }
@@ -84,6 +90,8 @@ impl ClaimMission {
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl Mission for ClaimMission {
+    impl_mission_pause!();
+
     fn get_owner(&self) -> &Option<Entity> {
         &self.owner
     }
@@ -140,9 +148,14 @@ impl Mission for ClaimMission {
         ))
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, String> {
-        let room_data = system_data.room_data.get(self.room_data).ok_or("Expected room data")?;
-        let dynamic_visibility_data = room_data.get_dynamic_visibility_data().ok_or("Expected dynamic visibility data")?;
+    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, MissionError> {
+        let room_data = system_data
+            .room_data
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
+        let dynamic_visibility_data = room_data
+            .get_dynamic_visibility_data()
+            .ok_or_else(|| MissionError::new(MissionFailure::NoVisibility, "Expected dynamic visibility data"))?;
 
         if dynamic_visibility_data.updated_within(1000) {
             match dynamic_visibility_data.owner() {
@@ -150,7 +163,7 @@ impl Mission for ClaimMission {
                     return Ok(MissionResult::Success);
                 }
                 RoomDisposition::Friendly(_) | RoomDisposition::Hostile(_) => {
-                    return Err("Room already owned".to_string());
+                    return Err(MissionError::new(MissionFailure::TargetInvalid, "Room already owned"));
                 }
                 RoomDisposition::Neutral => {}
             }
@@ -163,13 +176,20 @@ impl Mission for ClaimMission {
             match dynamic_visibility_data.reservation() {
                 RoomDisposition::Mine | RoomDisposition::Neutral => {}
                 RoomDisposition::Friendly(ref name) | RoomDisposition::Hostile(ref name) => {
-                    return Err(format!("Claim target reserved by {} — claimController would fail", name));
+                    return Err(MissionError::new(
+                        MissionFailure::TargetInvalid,
+                        format!("Claim target reserved by {} — claimController would fail", name),
+                    ));
                 }
             }
         }
 
-        let static_visibility_data = room_data.get_static_visibility_data().ok_or("Expected static visibility data")?;
-        let controller = static_visibility_data.controller().ok_or("Expected target controller")?;
+        let static_visibility_data = room_data
+            .get_static_visibility_data()
+            .ok_or_else(|| MissionError::new(MissionFailure::NoVisibility, "Expected static visibility data"))?;
+        let controller = static_visibility_data
+            .controller()
+            .ok_or_else(|| MissionError::new(MissionFailure::TargetInvalid, "Expected target controller"))?;
 
         // Abort if too many claimers were lost reaching this target — it is a
         // losing battle. Tag the room in the avoid-cooldown map so the claim
@@ -177,9 +197,12 @@ impl Mission for ClaimMission {
         if system_data.features.claim.safety_gate && self.claimer_deaths >= system_data.features.claim.max_claimer_deaths {
             let until = game::time().saturating_add(system_data.features.claim.avoid_cooldown_ticks);
             system_data.expansion_avoidance.avoid(room_data.name, until);
-            return Err(format!(
-                "Claim aborted: {} claimer(s) lost reaching {} — avoiding for {} ticks",
-                self.claimer_deaths, room_data.name, system_data.features.claim.avoid_cooldown_ticks
+            return Err(MissionError::new(
+                MissionFailure::Hostile,
+                format!(
+                    "Claim aborted: {} claimer(s) lost reaching {} — avoiding for {} ticks",
+                    self.claimer_deaths, room_data.name, system_data.features.claim.avoid_cooldown_ticks
+                ),
             ));
         }
 
@@ -200,8 +223,13 @@ impl Mission for ClaimMission {
         let mut requested = false;
 
         for home_room_data_entity in self.home_room_datas.iter() {
-            let home_room_data = system_data.room_data.get(*home_room_data_entity).ok_or("Expected home room data")?;
-            let home_room = game::rooms().get(home_room_data.name).ok_or("Expected home room")?;
+            let home_room_data = system_data
+                .room_data
+                .get(*home_room_data_entity)
+                .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected home room data"))?;
+            let home_room = game::rooms()
+                .get(home_room_data.name)
+                .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected home room"))?;
 
             if self.claimers.is_empty() && ready_to_spawn {
                 let body_definition = crate::creep::SpawnBodyDefinition {
@@ -220,7 +248,8 @@ impl Mission for ClaimMission {
                         SPAWN_PRIORITY_HIGH,
                         Some(token),
                         Self::create_handle_claimer_spawn(mission_entity, *controller),
-                    );
+                    )
+                    .requested_by(mission_entity);
 
                     system_data.spawn_queue.request(*home_room_data_entity, spawn_request);
                     requested = true;
@@ -238,10 +267,13 @@ impl Mission for ClaimMission {
         // cleaned up; the claim operation only re-creates it once an affordable
         // home is in reach.
         if self.claimers.is_empty() && !requested && ready_to_spawn {
-            return Err(format!(
-                "No home room can afford a claimer (need {} energy capacity) for target {}",
-                Part::Claim.cost() + Part::Move.cost(),
-                room_data.name
+            return Err(MissionError::new(
+                MissionFailure::SpawnStarved,
+                format!(
+                    "No home room can afford a claimer (need {} energy capacity) for target {}",
+                    Part::Claim.cost() + Part::Move.cost(),
+                    room_data.name
+                ),
             ));
         }
 
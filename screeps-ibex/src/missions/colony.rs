@@ -2,15 +2,20 @@
 
 use super::construction::*;
 use super::data::*;
+use super::emergency::*;
 use super::haul::*;
 use super::labs::*;
 use super::localbuild::*;
+use super::localsupply::body_helpers::*;
+use super::localsupply::source_mining::SourceMiningMission;
 use super::localsupply::*;
 use super::missionsystem::*;
+use super::overflow::*;
 use super::powerspawn::*;
 use super::terminal::*;
 use super::tower::*;
 use super::upgrade::*;
+use crate::jobs::data::JobData;
 use crate::room::data::*;
 use crate::serialize::*;
 use log::*;
@@ -73,7 +78,7 @@ machine!(
 
         * => fn gather_data(&self, _system_data: &MissionExecutionSystemData, _mission_entity: Entity) {}
 
-        _ => fn tick(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity, state_context: &mut ColonyMissionContext) -> Result<Option<ColonyState>, String>;
+        _ => fn tick(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity, state_context: &mut ColonyMissionContext) -> Result<Option<ColonyState>, MissionError>;
     }
 );
 
@@ -137,7 +142,7 @@ impl Incubate {
         system_data: &mut MissionExecutionSystemData,
         mission_entity: Entity,
         state_context: &mut ColonyMissionContext,
-    ) -> Result<Option<ColonyState>, String> {
+    ) -> Result<Option<ColonyState>, MissionError> {
         self.clear_stale_children(system_data);
 
         // ── Expansion no-win abort (ADR 0017) ──────────────────────────────
@@ -212,7 +217,10 @@ impl Incubate {
                     }
                     let until = now.saturating_add(system_data.features.claim.avoid_cooldown_ticks);
                     system_data.expansion_avoidance.avoid(name, until);
-                    return Err(format!("Colony abandoned contested claim {} (no-win)", name));
+                    return Err(MissionError::new(
+                        MissionFailure::Hostile,
+                        format!("Colony abandoned contested claim {} (no-win)", name),
+                    ));
                 }
             }
         }
@@ -220,10 +228,10 @@ impl Incubate {
         let room_data = system_data
             .room_data
             .get_mut(state_context.room_data)
-            .ok_or("Expected colony room data")?;
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected colony room data"))?;
 
         if !ColonyMission::can_run(room_data) {
-            return Err("Colony room not owned!".to_owned());
+            return Err(MissionError::new(MissionFailure::TargetInvalid, "Colony room not owned!"));
         }
 
         if self.construction_mission.is_none() {
@@ -345,8 +353,116 @@ impl Incubate {
             self.labs_mission = Some(mission_entity).into();
         }
 
+        self.update_energy_emergency(system_data, state_context.room_data);
+        Self::update_overflow_sinks(system_data, state_context.room_data);
+
         Ok(None)
     }
+
+    /// Count the colony's live harvesters, miners and haulers from its source mining and haul missions.
+    fn economy_census(&self, system_data: &MissionExecutionSystemData) -> EconomyCensus {
+        let source_mining_creeps = (*self.local_supply_mission)
+            .and_then(|local_supply| system_data.missions.get(local_supply))
+            .map(|local_supply| local_supply.as_mission().get_children())
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|child| {
+                system_data
+                    .missions
+                    .get(child)
+                    .as_mission_type::<SourceMiningMission>()
+                    .map(|mission| mission.get_creeps())
+            })
+            .flatten();
+
+        let haulers = (*self.haul_mission)
+            .and_then(|haul| system_data.missions.get(haul))
+            .map(|haul| haul.as_mission().get_creeps())
+            .unwrap_or_default();
+
+        let spawned = |creep: &Entity| system_data.creep_owner.get(*creep).is_some();
+        let spawning = |creep: &Entity| system_data.creep_spawning.get(*creep).is_some();
+
+        let mut census = EconomyCensus {
+            haulers: haulers.iter().filter(|creep| spawned(creep)).count() as u32,
+            ..Default::default()
+        };
+
+        for creep in source_mining_creeps.filter(|creep| spawned(creep) || spawning(creep)) {
+            census.harvesters += 1;
+
+            if spawned(&creep)
+                && matches!(
+                    system_data.job_data.get(creep),
+                    Some(JobData::StaticMine(_)) | Some(JobData::LinkMine(_))
+                )
+            {
+                census.miners += 1;
+            }
+        }
+
+        census
+    }
+
+    /// Pick the extra energy consumers the room's stored energy pays for (see `missions::overflow`).
+    fn update_overflow_sinks(system_data: &mut MissionExecutionSystemData, room_entity: Entity) {
+        let Some(room_data) = system_data.room_data.get(room_entity) else {
+            return;
+        };
+        let Some(stored_energy) = system_data.economy.room(&room_entity).map(|economy| economy.stored_energy) else {
+            return;
+        };
+
+        let sinks = overflow_sinks(system_data.overflow_sinks.sinks(room_entity), stored_energy);
+
+        system_data.overflow_sinks.set(room_entity, room_data.name, sinks);
+    }
+
+    /// Enter or leave the room's energy emergency (see `missions::emergency`).
+    fn update_energy_emergency(&self, system_data: &mut MissionExecutionSystemData, room_entity: Entity) {
+        let census = self.economy_census(system_data);
+
+        let Some(room_data) = system_data.room_data.get(room_entity) else {
+            return;
+        };
+        let (Some(room), Some(structures), Some(static_visibility_data)) = (
+            game::rooms().get(room_data.name),
+            room_data.get_structures(),
+            room_data.get_static_visibility_data(),
+        ) else {
+            return;
+        };
+
+        let sources = static_visibility_data.sources();
+        let mines_to_containers = structures
+            .containers()
+            .iter()
+            .any(|container| sources.iter().any(|source| container.pos().in_range_to(source.pos(), 1)))
+            || structures
+                .links()
+                .iter()
+                .any(|link| sources.iter().any(|source| link.pos().in_range_to(source.pos(), 2)));
+
+        let capacity = room.energy_capacity_available();
+        let standard_body = if mines_to_containers {
+            source_miner_body(true, capacity, source_work_parts(true), false)
+        } else {
+            harvester_body(capacity)
+        };
+        let standard_body_cost = crate::creep::spawning::create_body(&standard_body)
+            .map(|body| body.iter().map(|part| part.cost()).sum())
+            .unwrap_or(SPAWN_ENERGY_CAPACITY);
+
+        let emergency = is_emergency(
+            system_data.energy_emergency.is_active(room_entity),
+            &census,
+            mines_to_containers,
+            room.energy_available(),
+            standard_body_cost,
+        );
+
+        system_data.energy_emergency.set(room_entity, room_data.name, emergency);
+    }
 }
 
 #[derive(ConvertSaveload)]
@@ -354,6 +470,7 @@ pub struct ColonyMission {
     owner: EntityOption<Entity>,
     context: ColonyMissionContext,
     state: ColonyState,
+    paused: bool,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -385,6 +502,7 @@ impl ColonyMission {
                 None.into(),
                 None,
             ),
+            paused: false,
         }
     }
 
@@ -403,6 +521,8 @@ impl ColonyMission {
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl Mission for ColonyMission {
+    impl_mission_pause!();
+
     fn get_owner(&self) -> &Option<Entity> {
         &self.owner
     }
@@ -433,13 +553,13 @@ impl Mission for ColonyMission {
         crate::visualization::SummaryContent::Text(format!("Colony - {}", self.state.status_description()))
     }
 
-    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<(), String> {
+    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<(), MissionError> {
         self.state.gather_data(system_data, mission_entity);
 
         Ok(())
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, String> {
+    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, MissionError> {
         crate::machine_tick::run_state_machine_result(&mut self.state, "ColonyMission", |state| {
             state.tick(system_data, mission_entity, &mut self.context)
         })?;
@@ -13,6 +13,9 @@ use specs::error::NoError;
 use specs::saveload::*;
 use specs::*;
 
+/// Controller level from which a room with no plan is planned around its existing core structures.
+const KEEP_EXISTING_MIN_RCL: u8 = 4;
+
 /// Game-aware execution filter for plan construction.
 ///
 /// Implements [`ExecutionFilter`] with policy decisions that depend on
@@ -219,6 +222,7 @@ impl CleanupFilter for RemovalFilter {
 pub struct ConstructionMission {
     owner: EntityOption<Entity>,
     room_data: Entity,
+    paused: bool,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -238,12 +242,15 @@ impl ConstructionMission {
         ConstructionMission {
             owner: owner.into(),
             room_data,
+            paused: false,
         }
     }
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl Mission for ConstructionMission {
+    impl_mission_pause!();
+
     fn get_owner(&self) -> &Option<Entity> {
         &self.owner
     }
@@ -266,16 +273,27 @@ impl Mission for ConstructionMission {
         crate::visualization::SummaryContent::Text("Construction".to_string())
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<MissionResult, String> {
-        let room_data = system_data.room_data.get(self.room_data).ok_or("Expected room data")?;
-        let room = game::rooms().get(room_data.name).ok_or("Expected room")?;
+    fn run_mission(
+        &mut self,
+        system_data: &mut MissionExecutionSystemData,
+        _mission_entity: Entity,
+    ) -> Result<MissionResult, MissionError> {
+        let room_data = system_data
+            .room_data
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
+        let room = game::rooms()
+            .get(room_data.name)
+            .ok_or_else(|| MissionError::new(MissionFailure::NoVisibility, "Expected room"))?;
         let room_level = room.controller().map(|c| c.level()).unwrap_or(0);
 
         let request_plan = if let Some(room_plan_data) = system_data.room_plan_data.get(self.room_data) {
             if let Some(plan) = room_plan_data.plan() {
                 if game::time().is_multiple_of(50) {
                     if system_data.features.construction.execute {
-                        let construction_sites = room_data.get_construction_sites().ok_or("Expected construction sites")?;
+                        let construction_sites = room_data
+                            .get_construction_sites()
+                            .ok_or_else(|| MissionError::new(MissionFailure::TargetInvalid, "Expected construction sites"))?;
                         let existing_sites = construction_sites.len();
                         // Success-charged budget: place up to (cap - current) NEW
                         // sites this cycle, skipping (not counting) failures.
@@ -287,6 +305,22 @@ impl Mission for ConstructionMission {
                             .filter(|o| matches!(o, screeps_foreman::plan::PlanOperation::CreateSite { .. }))
                             .count();
                         let created = screeps_foreman::plan::execute_operations(&room, &ops, Some(max_new));
+
+                        // Remote haul exit chokes the mining outposts filed, after the plan's own sites.
+                        let mut choke_sites_left = max_new.saturating_sub(created as u32);
+                        for (pos, structure_type) in system_data.exit_chokes.take(room_data.name) {
+                            if choke_sites_left == 0 {
+                                break;
+                            }
+                            let loc = PlanLocation::from_xy(pos.x().u8(), pos.y().u8());
+                            if structure_or_site_exists(loc, structure_type, &room) {
+                                continue;
+                            }
+                            match pos.create_construction_site(structure_type, None) {
+                                Ok(()) => choke_sites_left -= 1,
+                                Err(err) => log::debug!("Failed to place exit choke site at {:?}: {:?}", pos, err),
+                            }
+                        }
                         // Diagnostic: distinguishes "no build ops generated"
                         // (no plan / everything filtered: RCL gate, site cap,
                         // already-built) from "ops generated but placement
@@ -306,7 +340,7 @@ impl Mission for ConstructionMission {
                         let structures = room_data.get_structures().ok_or_else(|| {
                             let msg = format!("Expected structures - Room: {}", room_data.name);
                             log::warn!("{} at {}:{}", msg, file!(), line!());
-                            msg
+                            MissionError::new(MissionFailure::MissingRoomData, msg)
                         })?;
                         let snapshot = screeps_foreman::plan::snapshot_structures(structures.all());
                         let mut removal_filter = RemovalFilter::new(&room);
@@ -335,7 +369,15 @@ impl Mission for ConstructionMission {
         };
 
         if request_plan || system_data.features.construction.force_plan {
-            system_data.room_plan_queue.request(RoomPlanRequest::new(self.room_data, 1.0));
+            let request = RoomPlanRequest::new(self.room_data, 1.0);
+            // A developed room without a plan already has its core built: plan around it rather than
+            // producing a layout that demolishes the spawns and storage.
+            let request = if request_plan && room_level >= KEEP_EXISTING_MIN_RCL {
+                request.keep_existing()
+            } else {
+                request
+            };
+            system_data.room_plan_queue.request(request);
         }
 
         Ok(MissionResult::Running)
@@ -1,6 +1,6 @@
 use super::missionsystem::*;
 use crate::serialize::*;
-use crate::visualization::SummaryContent;
+use crate::visualization::{MissionCategory, SummaryContent};
 use serde::*;
 #[allow(deprecated)]
 use specs::error::NoError;
@@ -9,96 +9,6 @@ use specs::*;
 use std::cell::*;
 use std::convert::*;
 
-#[derive(Component, ConvertSaveload)]
-pub enum MissionData {
-    LocalSupply(EntityRefCell<super::localsupply::LocalSupplyMission>),
-    Upgrade(EntityRefCell<super::upgrade::UpgradeMission>),
-    LocalBuild(EntityRefCell<super::localbuild::LocalBuildMission>),
-    Tower(EntityRefCell<super::tower::TowerMission>),
-    Scout(EntityRefCell<super::scout::ScoutMission>),
-    Construction(EntityRefCell<super::construction::ConstructionMission>),
-    Reserve(EntityRefCell<super::reserve::ReserveMission>),
-    Claim(EntityRefCell<super::claim::ClaimMission>),
-    RemoteBuild(EntityRefCell<super::remotebuild::RemoteBuildMission>),
-    Haul(EntityRefCell<super::haul::HaulMission>),
-    Terminal(EntityRefCell<super::terminal::TerminalMission>),
-    MiningOutpost(EntityRefCell<super::miningoutpost::MiningOutpostMission>),
-    Colony(EntityRefCell<super::colony::ColonyMission>),
-    PowerSpawn(EntityRefCell<super::powerspawn::PowerSpawnMission>),
-    Labs(EntityRefCell<super::labs::LabsMission>),
-    NukeDefense(EntityRefCell<super::nuke_defense::NukeDefenseMission>),
-    SafeMode(EntityRefCell<super::safe_mode::SafeModeMission>),
-    WallRepair(EntityRefCell<super::wall_repair::WallRepairMission>),
-    SourceMining(EntityRefCell<super::localsupply::source_mining::SourceMiningMission>),
-    MineralMining(EntityRefCell<super::localsupply::mineral_mining::MineralMiningMission>),
-    RoomTransfer(EntityRefCell<super::localsupply::room_transfer::RoomTransferMission>),
-    Salvage(EntityRefCell<super::salvage::SalvageMission>),
-    SourceKeeperFarm(EntityRefCell<super::sourcekeeperfarm::SourceKeeperFarmMission>),
-}
-
-impl MissionData {
-    pub fn as_mission(&self) -> Ref<'_, dyn Mission> {
-        match self {
-            MissionData::LocalSupply(data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),
-            MissionData::Upgrade(ref data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),
-            MissionData::LocalBuild(ref data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),
-            MissionData::Tower(ref data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),
-            MissionData::Scout(ref data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),
-            MissionData::Construction(ref data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),
-            MissionData::Reserve(ref data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),
-            MissionData::Claim(ref data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),
-            MissionData::RemoteBuild(ref data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),
-            MissionData::Haul(ref data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),
-            MissionData::Terminal(ref data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),
-            MissionData::MiningOutpost(ref data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),
-            MissionData::Colony(ref data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),
-            MissionData::PowerSpawn(ref data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),
-            MissionData::Labs(ref data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),
-            MissionData::NukeDefense(ref data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),
-            MissionData::SafeMode(ref data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),
-            MissionData::WallRepair(ref data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),
-            MissionData::SourceMining(ref data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),
-            MissionData::MineralMining(ref data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),
-            MissionData::RoomTransfer(ref data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),
-            MissionData::Salvage(ref data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),
-            MissionData::SourceKeeperFarm(ref data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),
-        }
-    }
-
-    /// Dispatch summarize() to the concrete mission type via the Mission trait.
-    pub fn summarize(&self) -> SummaryContent {
-        self.as_mission().summarize()
-    }
-
-    pub fn as_mission_mut(&self) -> RefMut<'_, dyn Mission> {
-        match self {
-            MissionData::LocalSupply(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),
-            MissionData::Upgrade(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),
-            MissionData::LocalBuild(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),
-            MissionData::Tower(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),
-            MissionData::Scout(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),
-            MissionData::Construction(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),
-            MissionData::Reserve(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),
-            MissionData::Claim(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),
-            MissionData::RemoteBuild(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),
-            MissionData::Haul(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),
-            MissionData::Terminal(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),
-            MissionData::MiningOutpost(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),
-            MissionData::Colony(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),
-            MissionData::PowerSpawn(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),
-            MissionData::Labs(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),
-            MissionData::NukeDefense(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),
-            MissionData::SafeMode(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),
-            MissionData::WallRepair(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),
-            MissionData::SourceMining(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),
-            MissionData::MineralMining(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),
-            MissionData::RoomTransfer(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),
-            MissionData::Salvage(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),
-            MissionData::SourceKeeperFarm(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),
-        }
-    }
-}
-
 //
 // Trait
 //
@@ -223,26 +133,137 @@ macro_rules! mission_type {
     };
 }
 
-mission_type!(super::localsupply::LocalSupplyMission, MissionData::LocalSupply);
-mission_type!(super::upgrade::UpgradeMission, MissionData::Upgrade);
-mission_type!(super::localbuild::LocalBuildMission, MissionData::LocalBuild);
-mission_type!(super::tower::TowerMission, MissionData::Tower);
-mission_type!(super::scout::ScoutMission, MissionData::Scout);
-mission_type!(super::construction::ConstructionMission, MissionData::Construction);
-mission_type!(super::reserve::ReserveMission, MissionData::Reserve);
-mission_type!(super::claim::ClaimMission, MissionData::Claim);
-mission_type!(super::remotebuild::RemoteBuildMission, MissionData::RemoteBuild);
-mission_type!(super::haul::HaulMission, MissionData::Haul);
-mission_type!(super::terminal::TerminalMission, MissionData::Terminal);
-mission_type!(super::miningoutpost::MiningOutpostMission, MissionData::MiningOutpost);
-mission_type!(super::colony::ColonyMission, MissionData::Colony);
-mission_type!(super::powerspawn::PowerSpawnMission, MissionData::PowerSpawn);
-mission_type!(super::labs::LabsMission, MissionData::Labs);
-mission_type!(super::nuke_defense::NukeDefenseMission, MissionData::NukeDefense);
-mission_type!(super::safe_mode::SafeModeMission, MissionData::SafeMode);
-mission_type!(super::wall_repair::WallRepairMission, MissionData::WallRepair);
-mission_type!(super::localsupply::source_mining::SourceMiningMission, MissionData::SourceMining);
-mission_type!(super::localsupply::mineral_mining::MineralMiningMission, MissionData::MineralMining);
-mission_type!(super::localsupply::room_transfer::RoomTransferMission, MissionData::RoomTransfer);
-mission_type!(super::salvage::SalvageMission, MissionData::Salvage);
-mission_type!(super::sourcekeeperfarm::SourceKeeperFarmMission, MissionData::SourceKeeperFarm);
+/// Registers every mission type in one place. Each entry gives the
+/// serialized tag, the `MissionData` variant and the mission type, and the
+/// macro generates the variant, the `as_mission`, `as_mission_mut` and
+/// `type_name` dispatch arms, the `as_mission_type` casts and a
+/// `From<Mission> for MissionData` constructor.
+///
+/// bincode writes the variant position as the tag, so the tags are checked
+/// against the entry order at compile time. A new mission is appended with
+/// the next tag. Removing or reordering one changes the tags after it and
+/// needs a world migration (see `world_migrations` in `game_loop`).
+///
+/// `build` functions wrap a mission with `MissionData::from(mission)`. Older
+/// missions still name the variant directly, which is equivalent; move them
+/// over when they're next touched.
+macro_rules! register_missions {
+    ($($tag:literal => $variant:ident($mission:path)),* $(,)?) => {
+        #[derive(Component, ConvertSaveload)]
+        pub enum MissionData {
+            $($variant(EntityRefCell<$mission>),)*
+        }
+
+        const _: () = {
+            let tags = [$($tag),*];
+            let mut position = 0;
+            while position < tags.len() {
+                assert!(tags[position] as usize == position, "MissionData tags must match their enum positions");
+                position += 1;
+            }
+        };
+
+        impl MissionData {
+            pub fn as_mission(&self) -> Ref<'_, dyn Mission> {
+                match self {
+                    $(MissionData::$variant(ref data) => Ref::map(data.borrow(), |m| -> &dyn Mission { m }),)*
+                }
+            }
+
+            pub fn as_mission_mut(&self) -> RefMut<'_, dyn Mission> {
+                match self {
+                    $(MissionData::$variant(ref data) => RefMut::map(data.borrow_mut(), |m| -> &mut dyn Mission { m }),)*
+                }
+            }
+
+            /// Stable type name of the concrete mission (the variant name). Used as
+            /// the CPU accounting key; does not borrow the mission.
+            pub fn type_name(&self) -> &'static str {
+                match self {
+                    $(MissionData::$variant(_) => stringify!($variant),)*
+                }
+            }
+        }
+
+        $(
+            impl From<$mission> for MissionData {
+                fn from(mission: $mission) -> MissionData {
+                    MissionData::$variant(EntityRefCell::new(mission))
+                }
+            }
+
+            mission_type!($mission, MissionData::$variant);
+        )*
+    };
+}
+
+register_missions! {
+    0 => LocalSupply(super::localsupply::LocalSupplyMission),
+    1 => Upgrade(super::upgrade::UpgradeMission),
+    2 => LocalBuild(super::localbuild::LocalBuildMission),
+    3 => Tower(super::tower::TowerMission),
+    4 => Scout(super::scout::ScoutMission),
+    5 => Construction(super::construction::ConstructionMission),
+    6 => Reserve(super::reserve::ReserveMission),
+    7 => Claim(super::claim::ClaimMission),
+    8 => RemoteBuild(super::remotebuild::RemoteBuildMission),
+    9 => Haul(super::haul::HaulMission),
+    10 => Terminal(super::terminal::TerminalMission),
+    11 => MiningOutpost(super::miningoutpost::MiningOutpostMission),
+    12 => Colony(super::colony::ColonyMission),
+    13 => PowerSpawn(super::powerspawn::PowerSpawnMission),
+    14 => Labs(super::labs::LabsMission),
+    15 => NukeDefense(super::nuke_defense::NukeDefenseMission),
+    16 => SafeMode(super::safe_mode::SafeModeMission),
+    17 => WallRepair(super::wall_repair::WallRepairMission),
+    18 => SourceMining(super::localsupply::source_mining::SourceMiningMission),
+    19 => MineralMining(super::localsupply::mineral_mining::MineralMiningMission),
+    20 => RoomTransfer(super::localsupply::room_transfer::RoomTransferMission),
+    21 => Salvage(super::salvage::SalvageMission),
+    22 => SourceKeeperFarm(super::sourcekeeperfarm::SourceKeeperFarmMission),
+    23 => ControllerDowngrade(super::downgrade::ControllerDowngradeMission),
+}
+
+impl MissionData {
+    /// Dispatch summarize() to the concrete mission type via the Mission trait,
+    /// badged when the mission is paused.
+    pub fn summarize(&self) -> SummaryContent {
+        let mission = self.as_mission();
+        let content = mission.summarize();
+
+        if mission.is_paused() {
+            content.with_badge("PAUSED")
+        } else {
+            content
+        }
+    }
+
+    /// The mission's category in the Missions panel.
+    pub fn category(&self) -> MissionCategory {
+        match self {
+            MissionData::LocalBuild(_) | MissionData::Construction(_) | MissionData::RemoteBuild(_) | MissionData::WallRepair(_) => {
+                MissionCategory::Construction
+            }
+            MissionData::Tower(_)
+            | MissionData::Scout(_)
+            | MissionData::NukeDefense(_)
+            | MissionData::SafeMode(_)
+            | MissionData::ControllerDowngrade(_) => MissionCategory::Military,
+            MissionData::LocalSupply(_)
+            | MissionData::Upgrade(_)
+            | MissionData::Reserve(_)
+            | MissionData::Claim(_)
+            | MissionData::Haul(_)
+            | MissionData::Terminal(_)
+            | MissionData::MiningOutpost(_)
+            | MissionData::Colony(_)
+            | MissionData::PowerSpawn(_)
+            | MissionData::Labs(_)
+            | MissionData::SourceMining(_)
+            | MissionData::MineralMining(_)
+            | MissionData::RoomTransfer(_)
+            | MissionData::Salvage(_)
+            | MissionData::SourceKeeperFarm(_) => MissionCategory::Economy,
+        }
+    }
+}
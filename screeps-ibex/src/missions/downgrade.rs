@@ -0,0 +1,301 @@
+//! Controller downgrade attack on a player room the war operation is sieging.
+//!
+//! `attackController` on an owned controller strips downgrade ticks and blocks
+//! the next strike for `CONTROLLER_ATTACK_BLOCKED_UPGRADE` (1000) ticks, longer
+//! than a CLAIM creep lives. So the mission fields one declaimer per cooldown,
+//! timed to reach the controller as the block runs out, and only while the
+//! siege has the room's towers drained or dead. A reserved controller has no
+//! block: the strike strips the reservation and the declaimer keeps at it.
+//!
+//! Like `SourceKeeperFarmMission` this is a producer: it requests a
+//! `Declaim{room, controller}` objective and the `SquadManager` fields the CLAIM
+//! squad. `jobs::squad_combat` drives the strike, and holds the declaimer in a
+//! neighbouring room while the target is in safe mode.
+
+use super::data::*;
+use super::localsupply::room_transfer::hostile_tower_cover;
+use super::missionsystem::*;
+use super::utility::*;
+use crate::military::composition::declaim_composition;
+use crate::military::is_npc_owner;
+use crate::military::objective_queue::*;
+use crate::room::data::{RoomDisposition, RoomDynamicVisibilityData};
+use crate::serialize::*;
+use log::*;
+use screeps::*;
+use serde::{Deserialize, Serialize};
+#[allow(deprecated)]
+use specs::error::NoError;
+use specs::saveload::*;
+use specs::*;
+
+/// Ticks to spawn a declaimer at its largest CLAIM/MOVE body.
+const DECLAIMER_SPAWN_TICKS: u32 = 50;
+/// Ticks a declaimer takes to cross one room.
+const TICKS_PER_ROOM: u32 = 50;
+/// Slack for the squad manager to pick the objective up and queue the spawn.
+const FIELDING_MARGIN: u32 = 100;
+/// A strike is timed off the controller's block and the room's towers, so no
+/// declaimer is asked for on a sighting older than one upgrade block.
+const DOWNGRADE_DATA_AGE_LIMITS: DataAgeLimits = DataAgeLimits {
+    pause_after: CONTROLLER_ATTACK_BLOCKED_UPGRADE,
+    fail_after: 10_000,
+};
+
+/// Whether a player, not an NPC, holds the room's controller by ownership or
+/// reservation.
+pub fn player_holds_controller(dynamic: &RoomDynamicVisibilityData) -> bool {
+    [dynamic.owner(), dynamic.reservation()]
+        .into_iter()
+        .any(|disposition| matches!(disposition, RoomDisposition::Hostile(player) if !is_npc_owner(player)))
+}
+
+/// Whether the war operation has a siege objective live on `room`: the
+/// Attack-owned Secure, Harass or Dismantle it opens on a player room.
+pub fn under_siege(queue: &CombatObjectiveQueue, room: RoomName) -> bool {
+    queue.objectives.iter().any(|o| {
+        o.owner == ObjectiveOwner::Attack
+            && o.kind.room() == room
+            && matches!(
+                o.kind,
+                ObjectiveKind::Secure { .. } | ObjectiveKind::Harass { .. } | ObjectiveKind::Dismantle { .. }
+            )
+    })
+}
+
+/// Ticks from asking for a declaimer to it standing at a controller
+/// `home_distance` rooms from its spawn.
+fn strike_lead(home_distance: u32) -> u32 {
+    DECLAIMER_SPAWN_TICKS + home_distance * TICKS_PER_ROOM + FIELDING_MARGIN
+}
+
+/// The first tick a strike can land: once both the upgrade block and any safe
+/// mode have run out.
+fn next_strike(blocked_until: Option<u32>, safe_mode_end: Option<u32>) -> u32 {
+    blocked_until.unwrap_or(0).max(safe_mode_end.unwrap_or(0))
+}
+
+/// Whether a declaimer asked for now arrives no earlier than `next_strike`
+/// would have it wait out.
+fn strike_window_open(now: u32, next_strike: u32, lead: u32) -> bool {
+    now.saturating_add(lead) >= next_strike
+}
+
+#[derive(ConvertSaveload)]
+pub struct ControllerDowngradeMission {
+    owner: EntityOption<Entity>,
+    room_data: Entity,
+    home_room_datas: EntityVec<Entity>,
+    /// Tick the controller's upgrade block ends, from the last time it was seen.
+    blocked_until: Option<u32>,
+    paused: bool,
+}
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+impl ControllerDowngradeMission {
+    pub fn build<B>(builder: B, owner: Option<Entity>, room_data: Entity, home_room_datas: &[Entity]) -> B
+    where
+        B: Builder + MarkedBuilder,
+    {
+        let mission = ControllerDowngradeMission::new(owner, room_data, home_room_datas);
+
+        builder
+            .with(MissionData::from(mission))
+            .marked::<SerializeMarker>()
+    }
+
+    pub fn new(owner: Option<Entity>, room_data: Entity, home_room_datas: &[Entity]) -> ControllerDowngradeMission {
+        ControllerDowngradeMission {
+            owner: owner.into(),
+            room_data,
+            home_room_datas: home_room_datas.to_owned().into(),
+            blocked_until: None,
+            paused: false,
+        }
+    }
+
+    /// Withdraw the `Declaim` objective this mission requested, retiring its
+    /// squad. Scoped to the War owner so a salvage declaim is left alone.
+    fn withdraw_objective(system_data: &mut MissionExecutionSystemData, room_name: RoomName) {
+        let stale: Vec<_> = system_data
+            .combat_objective_queue
+            .objectives
+            .iter()
+            .filter(|o| o.owner == ObjectiveOwner::War && matches!(o.kind, ObjectiveKind::Declaim { room, .. } if room == room_name))
+            .map(|o| o.id)
+            .collect();
+        for id in stale {
+            system_data.combat_objective_queue.withdraw(id);
+        }
+    }
+}
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+impl Mission for ControllerDowngradeMission {
+    impl_mission_pause!();
+
+    fn get_owner(&self) -> &Option<Entity> {
+        &self.owner
+    }
+
+    fn owner_complete(&mut self, owner: Entity) {
+        assert!(Some(owner) == *self.owner);
+
+        self.owner.take();
+    }
+
+    fn get_room(&self) -> Option<Entity> {
+        Some(self.room_data)
+    }
+
+    fn data_age_limits(&self) -> Option<DataAgeLimits> {
+        Some(DOWNGRADE_DATA_AGE_LIMITS)
+    }
+
+    fn describe_state(&self, _system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> String {
+        match self.blocked_until {
+            Some(tick) if tick > game::time() => format!("Controller Downgrade - Blocked for {}", tick - game::time()),
+            _ => "Controller Downgrade".to_string(),
+        }
+    }
+
+    fn summarize(&self) -> crate::visualization::SummaryContent {
+        crate::visualization::SummaryContent::Text("Downgrade".to_string())
+    }
+
+    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), MissionError> {
+        self.home_room_datas
+            .retain(|entity| system_data.room_data.get(*entity).map(is_valid_home_room).unwrap_or(false));
+
+        if self.home_room_datas.is_empty() {
+            return Err(MissionError::new(MissionFailure::NoHomeRooms, "No home rooms for controller downgrade mission"));
+        }
+
+        Ok(())
+    }
+
+    fn run_mission(
+        &mut self,
+        system_data: &mut MissionExecutionSystemData,
+        mission_entity: Entity,
+    ) -> Result<MissionResult, MissionError> {
+        let military = system_data.features.military;
+        let room_data = system_data
+            .room_data
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
+        let room_name = room_data.name;
+
+        // Hold off until the room has been seen again; see `data_age_limits`.
+        if system_data.stale_missions.is_stale(mission_entity) {
+            Self::withdraw_objective(system_data, room_name);
+
+            return Ok(MissionResult::Running);
+        }
+        let controller_pos = room_data
+            .get_static_visibility_data()
+            .and_then(|s| s.controller())
+            .map(|c| c.pos())
+            .ok_or_else(|| MissionError::new(MissionFailure::TargetInvalid, "Expected controller"))?;
+        let dynamic_visibility_data = room_data
+            .get_dynamic_visibility_data()
+            .ok_or_else(|| MissionError::new(MissionFailure::NoVisibility, "Expected dynamic visibility data"))?;
+
+        let held = player_holds_controller(dynamic_visibility_data);
+        let safe_mode_end = dynamic_visibility_data.safe_mode_end();
+
+        // The kill-switches stop a running attack as well as new ones, and the
+        // mission ends with the siege or once the controller is no one's.
+        if !(military.offense && military.attack_players && military.downgrade)
+            || !held
+            || !under_siege(system_data.combat_objective_queue, room_name)
+        {
+            Self::withdraw_objective(system_data, room_name);
+
+            return Ok(MissionResult::Success);
+        }
+
+        if let Some(controller) = game::rooms().get(room_name).and_then(|room| room.controller()) {
+            self.blocked_until = controller.upgrade_blocked().map(|ticks| game::time() + ticks);
+        }
+
+        let home_distance = self
+            .home_room_datas
+            .iter()
+            .filter_map(|&e| system_data.room_data.get(e))
+            .map(|rd| game::map::get_room_linear_distance(rd.name, room_name, false))
+            .min()
+            .unwrap_or(0);
+
+        let now = game::time();
+        let strike_at = next_strike(self.blocked_until, safe_mode_end);
+
+        // A declaimer standing in a live tower room dies before it strikes, so
+        // wait for the siege to drain or kill them.
+        let towers_down = hostile_tower_cover(system_data.threat_data.get(self.room_data)).is_empty();
+
+        if !towers_down || !strike_window_open(now, strike_at, strike_lead(home_distance)) {
+            Self::withdraw_objective(system_data, room_name);
+
+            return Ok(MissionResult::Running);
+        }
+
+        let member_energy = self
+            .home_room_datas
+            .iter()
+            .filter_map(|&e| system_data.room_data.get(e))
+            .filter_map(|rd| game::rooms().get(rd.name))
+            .map(|r| r.energy_capacity_available())
+            .max()
+            .unwrap_or(0);
+
+        let Some(composition) = declaim_composition(member_energy) else {
+            return Ok(MissionResult::Running);
+        };
+
+        let kind = ObjectiveKind::Declaim {
+            room: room_name,
+            controller: controller_pos,
+        };
+
+        if system_data.combat_objective_queue.find_by_kind(&kind).is_none() && military.debug_log {
+            info!("[War] Declaimer for {} (strike at {}, now {})", room_name, strike_at, now);
+        }
+
+        let request =
+            ObjectiveRequest::new(kind, OBJECTIVE_PRIORITY_MEDIUM, ForceRequirement::single(composition)).owner(ObjectiveOwner::War);
+        system_data.combat_objective_queue.request(request, now);
+
+        Ok(MissionResult::Running)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_strike_waits_for_the_later_of_block_and_safe_mode() {
+        assert_eq!(next_strike(None, None), 0);
+        assert_eq!(next_strike(Some(1_000), None), 1_000);
+        assert_eq!(next_strike(Some(1_000), Some(4_000)), 4_000);
+        assert_eq!(next_strike(Some(5_000), Some(4_000)), 5_000);
+    }
+
+    #[test]
+    fn window_opens_one_lead_before_the_strike() {
+        let lead = strike_lead(3);
+
+        assert_eq!(lead, 300);
+        assert!(!strike_window_open(10_000, 10_000 + lead + 1, lead));
+        assert!(strike_window_open(10_000, 10_000 + lead, lead));
+        assert!(strike_window_open(10_000, 0, lead));
+    }
+
+    #[test]
+    fn lead_stays_inside_the_upgrade_block() {
+        // A declaimer asked for straight after a strike would idle through
+        // the whole block; the lead must leave most of it unspent.
+        assert!(strike_lead(5) < CONTROLLER_ATTACK_BLOCKED_UPGRADE / 2);
+    }
+}
@@ -0,0 +1,132 @@
+//! Energy emergency — a colony whose economy has collapsed.
+//!
+//! If every harvesting creep dies at once (an invader wave) while the spawns
+//! are low, the standard bodies cost more than the room has and it deadlocks.
+//! The colony mission declares an emergency when it sees no harvester and
+//! `energy_available` below the standard body's cost. While it lasts the
+//! source mining missions spawn a minimal worker at CRITICAL priority, the
+//! upgrade and local build missions hold their spawns, and the towers stop
+//! repairing. It ends once a real miner and a hauler are alive again.
+//!
+//! Ephemeral (a runtime resource, never serialized): after a VM reload the
+//! colony re-detects a still-collapsed room on its next tick.
+
+use log::*;
+use screeps::*;
+use specs::Entity;
+use std::collections::HashMap;
+
+/// The minimal worker spawned in an emergency: 300 energy, so a lone spawn can always afford it.
+pub const EMERGENCY_WORKER_BODY: &[Part] = &[Part::Work, Part::Carry, Part::Carry, Part::Move, Part::Move];
+
+/// A colony's live economy creeps, as the emergency detector counts them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EconomyCensus {
+    /// Creeps harvesting the room's sources of any kind, including ones still spawning.
+    pub harvesters: u32,
+    /// Spawned static or link miners.
+    pub miners: u32,
+    /// Spawned haulers.
+    pub haulers: u32,
+}
+
+/// Whether a room is in emergency this tick, given whether it was last tick. A room that mines into
+/// containers recovers once a miner and a hauler are alive; one that still runs on plain harvesters
+/// recovers once a harvester is alive and the spawns hold a standard body again.
+pub fn is_emergency(
+    active: bool,
+    census: &EconomyCensus,
+    mines_to_containers: bool,
+    energy_available: u32,
+    standard_body_cost: u32,
+) -> bool {
+    if !active {
+        return census.harvesters == 0 && energy_available < standard_body_cost;
+    }
+
+    let recovered = if mines_to_containers {
+        census.miners > 0 && census.haulers > 0
+    } else {
+        census.harvesters > 0 && energy_available >= standard_body_cost
+    };
+
+    !recovered
+}
+
+/// Rooms currently in an energy emergency. Runtime resource; see the module docs.
+#[derive(Default)]
+pub struct EnergyEmergency {
+    /// Rooms in emergency, with the tick each entered it.
+    active: HashMap<Entity, u32>,
+    /// Emergencies entered per room since the VM started, for stats.
+    occurrences: HashMap<RoomName, u32>,
+}
+
+impl EnergyEmergency {
+    pub fn is_active(&self, room: Entity) -> bool {
+        self.active.contains_key(&room)
+    }
+
+    pub fn occurrences(&self, room_name: RoomName) -> u32 {
+        self.occurrences.get(&room_name).copied().unwrap_or(0)
+    }
+
+    /// Enter or leave `room`'s emergency, logging the transition.
+    pub fn set(&mut self, room: Entity, room_name: RoomName, emergency: bool) {
+        match (self.active.get(&room).copied(), emergency) {
+            (None, true) => {
+                self.active.insert(room, game::time());
+                *self.occurrences.entry(room_name).or_insert(0) += 1;
+
+                warn!(
+                    "Energy emergency in {}: no harvesters and spawn energy below a standard body",
+                    room_name
+                );
+            }
+            (Some(since), false) => {
+                self.active.remove(&room);
+
+                info!(
+                    "Energy emergency in {} over after {} ticks",
+                    room_name,
+                    game::time().saturating_sub(since)
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn census(harvesters: u32, miners: u32, haulers: u32) -> EconomyCensus {
+        EconomyCensus {
+            harvesters,
+            miners,
+            haulers,
+        }
+    }
+
+    #[test]
+    fn collapse_needs_no_harvesters_and_low_spawn_energy() {
+        assert!(is_emergency(false, &census(0, 0, 2), true, 120, 550));
+        assert!(!is_emergency(false, &census(0, 0, 0), true, 600, 550));
+        assert!(!is_emergency(false, &census(1, 0, 0), true, 120, 550));
+    }
+
+    /// The emergency worker alone doesn't end it: container rooms wait for a miner and a hauler.
+    #[test]
+    fn recovery_needs_a_miner_and_a_hauler() {
+        assert!(is_emergency(true, &census(1, 0, 0), true, 800, 550));
+        assert!(is_emergency(true, &census(2, 1, 0), true, 800, 550));
+        assert!(!is_emergency(true, &census(2, 1, 1), true, 120, 550));
+    }
+
+    #[test]
+    fn harvester_rooms_recover_once_the_spawns_refill() {
+        assert!(is_emergency(true, &census(1, 0, 0), false, 200, 250));
+        assert!(!is_emergency(true, &census(1, 0, 0), false, 250, 250));
+    }
+}
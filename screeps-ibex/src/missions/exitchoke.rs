@@ -0,0 +1,144 @@
+//! Prepared defensive positions on remote haul exits.
+//!
+//! Haulers to a remote leave the home room through one exit, and raiders
+//! waiting for them come in the same way. Just inside that exit the terrain
+//! usually narrows; ramparts with a road under them across the narrowest line
+//! give defenders a position to hold without planning the whole room again.
+//! Mining outposts find the line from the exit their road path crosses and
+//! file it here; the home room's construction mission places the sites.
+
+use screeps::*;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+/// Nearest line to the exit a choke may sit on: structures other than roads
+/// can't stand beside an exit tile.
+pub const EXIT_CHOKE_MIN_DEPTH: u8 = 2;
+/// Furthest line from the exit searched for a choke.
+pub const EXIT_CHOKE_MAX_DEPTH: u8 = 8;
+/// Widest line worth fortifying; anything wider is open ground, not a choke.
+pub const EXIT_CHOKE_MAX_WIDTH: usize = 3;
+/// Home controller level from which chokes are fortified (ramparts unlock at 2, but a small colony has
+/// better uses for the energy).
+pub const EXIT_CHOKE_MIN_RCL: u8 = 4;
+
+/// How far `(x, y)` lies from the room edge the exit tile `exit` is on, or
+/// `None` if `exit` is not on an edge.
+fn exit_depth(exit: (u8, u8), (x, y): (u8, u8)) -> Option<u8> {
+    let last = ROOM_SIZE - 1;
+
+    match exit {
+        (0, _) => Some(x),
+        (x_edge, _) if x_edge == last => Some(last - x),
+        (_, 0) => Some(y),
+        (_, y_edge) if y_edge == last => Some(last - y),
+        _ => None,
+    }
+}
+
+/// The narrowest line of open tiles across the way in from the exit tile
+/// `exit`, searched between [`EXIT_CHOKE_MIN_DEPTH`] and
+/// [`EXIT_CHOKE_MAX_DEPTH`] tiles in. Every path from that exit's stretch of
+/// edge into the room crosses the line. `None` when the exit is open ground
+/// wider than [`EXIT_CHOKE_MAX_WIDTH`] all the way in.
+pub fn exit_choke(is_wall: impl Fn(u8, u8) -> bool, exit: (u8, u8)) -> Option<Vec<(u8, u8)>> {
+    exit_depth(exit, exit)?;
+
+    let in_band = |tile: (u8, u8)| exit_depth(exit, tile).is_some_and(|depth| depth <= EXIT_CHOKE_MAX_DEPTH);
+    let mut seen: BTreeSet<(u8, u8)> = BTreeSet::new();
+    let mut open: VecDeque<(u8, u8)> = VecDeque::new();
+
+    if !is_wall(exit.0, exit.1) {
+        seen.insert(exit);
+        open.push_back(exit);
+    }
+
+    while let Some((x, y)) = open.pop_front() {
+        for dx in -1i16..=1 {
+            for dy in -1i16..=1 {
+                let (nx, ny) = (x as i16 + dx, y as i16 + dy);
+                if nx < 0 || ny < 0 || nx >= ROOM_SIZE as i16 || ny >= ROOM_SIZE as i16 {
+                    continue;
+                }
+                let next = (nx as u8, ny as u8);
+                if in_band(next) && !is_wall(next.0, next.1) && seen.insert(next) {
+                    open.push_back(next);
+                }
+            }
+        }
+    }
+
+    (EXIT_CHOKE_MIN_DEPTH..=EXIT_CHOKE_MAX_DEPTH)
+        .map(|depth| {
+            seen.iter()
+                .copied()
+                .filter(|tile| exit_depth(exit, *tile) == Some(depth))
+                .collect::<Vec<_>>()
+        })
+        .filter(|line| !line.is_empty())
+        .min_by_key(|line| line.len())
+        .filter(|line| line.len() <= EXIT_CHOKE_MAX_WIDTH)
+}
+
+/// Choke sites filed by mining outposts per home room, until that room's
+/// construction mission takes them.
+#[derive(Default)]
+pub struct ExitChokes {
+    sites: HashMap<RoomName, Vec<(Position, StructureType)>>,
+}
+
+impl ExitChokes {
+    /// File a rampart with a road under it on each choke tile.
+    pub fn request(&mut self, tiles: impl IntoIterator<Item = Position>) {
+        for pos in tiles {
+            let sites = self.sites.entry(pos.room_name()).or_default();
+            for site in [(pos, StructureType::Road), (pos, StructureType::Rampart)] {
+                if !sites.contains(&site) {
+                    sites.push(site);
+                }
+            }
+        }
+    }
+
+    /// The sites filed for `room` since it last took them.
+    pub fn take(&mut self, room: RoomName) -> Vec<(Position, StructureType)> {
+        self.sites.remove(&room).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A left-edge exit opening onto a two-tile gap four tiles in.
+    fn funnel(x: u8, y: u8) -> bool {
+        match x {
+            0..=2 => !(20..=26).contains(&y),
+            4 => !(22..=23).contains(&y),
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn choke_is_the_narrowest_line_in_from_the_exit() {
+        assert_eq!(exit_choke(funnel, (0, 23)), Some(vec![(4, 22), (4, 23)]));
+    }
+
+    #[test]
+    fn open_ground_and_non_exit_tiles_have_no_choke() {
+        assert_eq!(exit_choke(|_, _| false, (0, 23)), None);
+        assert_eq!(exit_choke(funnel, (10, 23)), None);
+        assert_eq!(exit_choke(funnel, (0, 5)), None);
+    }
+
+    #[test]
+    fn filed_sites_are_taken_once_per_room() {
+        let room: RoomName = "W1N1".parse().unwrap();
+        let pos = crate::testing::position(room, 4, 22);
+        let mut chokes = ExitChokes::default();
+
+        chokes.request([pos, pos]);
+
+        assert_eq!(chokes.take(room), vec![(pos, StructureType::Road), (pos, StructureType::Rampart)]);
+        assert!(chokes.take(room).is_empty());
+    }
+}
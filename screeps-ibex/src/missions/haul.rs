@@ -1,8 +1,12 @@
 use super::data::*;
+use super::localsupply::room_transfer::{hostile_tower_cover, request_transfer_for_loot};
+use super::localsupply::source_mining::SourceMiningMission;
 use super::missionsystem::*;
 use super::utility::*;
+use crate::creep::spawning::BodyTemplate;
 use crate::jobs::data::*;
 use crate::jobs::haul::*;
+use crate::military::escort::*;
 use crate::room::data::*;
 use crate::serialize::*;
 use crate::spawnsystem::*;
@@ -14,13 +18,93 @@ use serde::{Deserialize, Serialize};
 use specs::error::NoError;
 use specs::saveload::*;
 use specs::*;
+use std::collections::HashMap;
+
+/// Most haulers a pool keeps for its own room's hauling. Below this a pool
+/// spawns at the priority a room's local haulers always had.
+const LOCAL_MAX_HAULERS: u32 = 3;
+/// Extra haulers a pool may keep per room of distance to each remote it serves.
+const REMOTE_HAULERS_PER_DISTANCE: u32 = 3;
 
 #[derive(Clone, Serialize, Deserialize)]
 struct HaulingStats {
     last_updated: u32,
+    /// Unfulfilled hauling over every room the pool serves, weighted by
+    /// [`trip_legs`].
     unfufilled_hauling: u32,
 }
 
+/// Hauling one remote room asks of its home room's pool this tick.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HaulDemand {
+    pub room_data: Entity,
+    pub room_name: RoomName,
+    /// Rooms between the remote and the pool's home room.
+    pub distance: u32,
+    /// Whether the pool sizes itself for this room. Pool haulers still
+    /// collect from a room that isn't sized, they just aren't spawned for it.
+    pub sized: bool,
+}
+
+/// Remote haul demand filed on each room's hauler pool, keyed by the pool
+/// mission. The remote haul missions file it in their pre-run and the pools
+/// read it when they run; cleared by `PreRunMissionSystem` every tick and
+/// never serialized.
+#[derive(Default)]
+pub struct HaulerPools {
+    demand: HashMap<Entity, Vec<HaulDemand>>,
+}
+
+impl HaulerPools {
+    pub fn clear(&mut self) {
+        self.demand.clear();
+    }
+
+    pub fn file(&mut self, pool: Entity, demand: HaulDemand) {
+        self.demand.entry(pool).or_default().push(demand);
+    }
+
+    pub fn demand(&self, pool: Entity) -> &[HaulDemand] {
+        self.demand.get(&pool).map(|d| d.as_slice()).unwrap_or(&[])
+    }
+}
+
+/// Legs a hauler walks per load from a room `distance` rooms out: out and
+/// back through each room in between, plus the room itself.
+fn trip_legs(distance: u32) -> u32 {
+    distance * 2 + 1
+}
+
+/// How many haulers a pool may keep: its own room's share plus a share for
+/// each sized remote that grows with the remote's distance.
+fn max_pool_haulers(demand: &[HaulDemand]) -> u32 {
+    LOCAL_MAX_HAULERS
+        + demand
+            .iter()
+            .filter(|d| d.sized)
+            .map(|d| LOCAL_MAX_HAULERS + d.distance * REMOTE_HAULERS_PER_DISTANCE)
+            .sum::<u32>()
+}
+
+fn room_distance(a: RoomName, b: RoomName) -> u32 {
+    let (dx, dy) = a - b;
+
+    dx.unsigned_abs() + dy.unsigned_abs()
+}
+
+/// Hauling for a room.
+///
+/// The haul mission a colony runs for its own room is that room's hauler
+/// pool: it owns every general-purpose hauler the room has, sizes the pool
+/// from the unfulfilled transfer demand of its room and of every remote
+/// hauling home to it, and points each hauler at all of those rooms so the
+/// hauler picks the best pickup and delivery pair itself each trip.
+///
+/// A haul mission for a remote room (a mining outpost or a source keeper
+/// farm) spawns nothing. It files the remote's demand with the pool of its
+/// nearest home room, registers the remote's loot and asks for escorts on the
+/// pool's behalf. Haulers it still owns from before the pools existed are
+/// handed to the pool.
 #[derive(ConvertSaveload)]
 pub struct HaulMission {
     owner: EntityOption<Entity>,
@@ -30,6 +114,7 @@ pub struct HaulMission {
     //TODO: Create a room stats component?
     stats: Option<HaulingStats>,
     allow_spawning: bool,
+    paused: bool,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -53,9 +138,12 @@ impl HaulMission {
             haulers: EntityVec::new(),
             stats: None,
             allow_spawning: true,
+            paused: false,
         }
     }
 
+    /// For a pool, whether it spawns; for a remote, whether the pool sizes
+    /// itself for the remote's demand.
     pub fn allow_spawning(&mut self, allow: bool) {
         self.allow_spawning = allow
     }
@@ -66,6 +154,39 @@ impl HaulMission {
         }
     }
 
+    /// Whether this is a room's hauler pool, i.e. the room hauls for itself.
+    pub fn is_pool(&self) -> bool {
+        self.home_room_datas.as_slice() == [self.room_data]
+    }
+
+    /// The hauler pool of the home room nearest `room`, with its distance in
+    /// rooms.
+    fn find_pool(
+        system_data: &MissionExecutionSystemData,
+        mission_entity: Entity,
+        room: RoomName,
+        home_room_datas: &[Entity],
+    ) -> Option<(Entity, u32)> {
+        home_room_datas
+            .iter()
+            .filter_map(|home_entity| {
+                let home_room_data = system_data.room_data.get(*home_entity)?;
+
+                let pool = home_room_data.get_missions().iter().copied().find(|mission| {
+                    *mission != mission_entity
+                        && system_data
+                            .missions
+                            .get(*mission)
+                            .as_mission_type::<HaulMission>()
+                            .map(|haul| haul.is_pool())
+                            .unwrap_or(false)
+                })?;
+
+                Some((pool, room_distance(room, home_room_data.name)))
+            })
+            .min_by_key(|(_, distance)| *distance)
+    }
+
     fn create_handle_hauler_spawn(
         mission_entity: Entity,
         pickup_rooms: &[Entity],
@@ -97,28 +218,218 @@ impl HaulMission {
         })
     }
 
+    /// Source containers in `rooms` the haulers have fallen behind on. The
+    /// pool takes one hauler more than its demand asks for for each of them.
+    fn overflowing_containers(system_data: &MissionExecutionSystemData, rooms: &[Entity]) -> u32 {
+        rooms
+            .iter()
+            .filter_map(|room| system_data.room_data.get(*room))
+            .flat_map(|room_data| room_data.get_missions().iter().copied())
+            .filter_map(|mission| {
+                system_data
+                    .missions
+                    .get(mission)
+                    .as_mission_type::<SourceMiningMission>()
+                    .map(|source_mining| source_mining.overflowing_containers().count() as u32)
+            })
+            .sum()
+    }
+
+    /// Rooms on the haulers' routes home, other than our own rooms and remotes, with a recent hostile sighting.
+    fn threatened_route_rooms(
+        system_data: &mut MissionExecutionSystemData,
+        pickup_room: RoomName,
+        home_rooms: &[RoomName],
+    ) -> Vec<RoomName> {
+        let mut threatened = Vec::new();
+
+        for home_room in home_rooms {
+            let route = system_data.pathfinder.route_rooms(pickup_room, *home_room, game::time()).to_vec();
+
+            for room_name in route {
+                let Some(dynamic_visibility_data) = system_data
+                    .mapping
+                    .get_room(&room_name)
+                    .and_then(|entity| system_data.room_data.get(entity))
+                    .and_then(|room_data| room_data.get_dynamic_visibility_data())
+                else {
+                    continue;
+                };
+
+                if dynamic_visibility_data.owner().mine() || dynamic_visibility_data.reservation().mine() {
+                    continue;
+                }
+
+                let threat = is_recent_threat(
+                    dynamic_visibility_data.hostile_creeps(),
+                    dynamic_visibility_data.source_keeper(),
+                    dynamic_visibility_data.age(),
+                );
+
+                if threat && !threatened.contains(&room_name) {
+                    threatened.push(room_name);
+                }
+            }
+        }
+
+        threatened
+    }
+
+    /// `pickup_rooms` pairs each room the pool serves with its distance from
+    /// the delivery room.
     fn update_stats<'a, 's, RD>(
         transfer_queue: &mut TransferQueue,
         transfer_queue_data: &TransferQueueGeneratorData<'a, 's, RD>,
-        pickup_rooms: &[RoomName],
-        delivery_rooms: &[RoomName],
+        pickup_rooms: &[(RoomName, u32)],
+        delivery_room: RoomName,
     ) -> HaulingStats
     where
         RD: std::ops::Deref<Target = specs::storage::MaskedStorage<RoomData>>,
     {
-        let unfufilled = transfer_queue.total_unfufilled_resources(transfer_queue_data, pickup_rooms, delivery_rooms, TransferType::Haul);
+        let unfufilled_hauling = pickup_rooms
+            .iter()
+            .map(|(pickup_room, distance)| {
+                let unfufilled =
+                    transfer_queue.total_unfufilled_resources(transfer_queue_data, &[*pickup_room], &[delivery_room], TransferType::Haul);
 
-        let total_unfufilled: u32 = unfufilled.values().sum();
+                unfufilled.values().sum::<u32>() * trip_legs(*distance)
+            })
+            .sum();
 
         HaulingStats {
             last_updated: game::time(),
-            unfufilled_hauling: total_unfufilled,
+            unfufilled_hauling,
+        }
+    }
+
+    /// Point every pool hauler at the pool's current rooms. Haulers handed
+    /// over by a remote haul mission are retargeted here on their first tick
+    /// in the pool.
+    fn retarget_haulers(&self, system_data: &MissionExecutionSystemData, pickup_rooms: &[Entity]) {
+        let delivery_rooms = [self.room_data];
+
+        for hauler in self.haulers.iter().copied() {
+            let serves = match system_data.job_data.get(hauler) {
+                Some(JobData::Haul(job)) => job.serves(pickup_rooms, &delivery_rooms),
+                _ => true,
+            };
+
+            if !serves {
+                let pickup_rooms = pickup_rooms.to_vec();
+
+                system_data.updater.exec_mut(move |world| {
+                    if let Some(JobData::Haul(job)) = world.write_storage::<JobData>().get_mut(hauler) {
+                        job.reassign(&pickup_rooms, &delivery_rooms, true, false);
+                    }
+                });
+            }
+        }
+    }
+
+    /// A remote's pre-run: hand any haulers over to the pool, file the
+    /// remote's demand and ask for an escort for the pool's haulers while
+    /// their route home is threatened.
+    fn pre_run_demand(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<(), MissionError> {
+        let room_data = system_data
+            .room_data
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
+        let pickup_room = room_data.name;
+
+        //
+        // Loot tombstones, ruins, dropped and special resources in a pickup room that has no room
+        // transfer mission of its own to register them (e.g. a source keeper room).
+        //
+
+        let has_room_transfer = room_data
+            .get_missions()
+            .iter()
+            .any(|mission| matches!(system_data.missions.get(*mission), Some(MissionData::RoomTransfer(_))));
+
+        if !has_room_transfer {
+            let hostile_towers = hostile_tower_cover(system_data.threat_data.get(self.room_data));
+
+            system_data.transfer_queue.register_generator(
+                pickup_room,
+                TransferTypeFlags::HAUL,
+                Box::new(move |_system, transfer, _room_name| {
+                    if let Some(room) = game::rooms().get(pickup_room) {
+                        request_transfer_for_loot(transfer, &room, &hostile_towers);
+                        crate::transfer::special::request_transfers(transfer, &room, &hostile_towers);
+                    }
+
+                    Ok(())
+                }),
+            );
+        }
+
+        let Some((pool, distance)) = Self::find_pool(system_data, mission_entity, pickup_room, &self.home_room_datas) else {
+            system_data.escort_request.withdraw(mission_entity);
+
+            return Ok(());
+        };
+
+        let pool_haulers: Vec<Entity> = {
+            let Some(mut pool_mission) = system_data.missions.get(pool).as_mission_type_mut::<HaulMission>() else {
+                return Ok(());
+            };
+
+            for hauler in self.haulers.drain(..) {
+                if !pool_mission.haulers.contains(&hauler) {
+                    pool_mission.haulers.push(hauler);
+                }
+            }
+
+            pool_mission.haulers.iter().copied().collect()
+        };
+
+        system_data.hauler_pools.file(
+            pool,
+            HaulDemand {
+                room_data: self.room_data,
+                room_name: pickup_room,
+                distance,
+                sized: self.allow_spawning,
+            },
+        );
+
+        //
+        // Ask for an escort while the pool's route home crosses a room with a recent hostile sighting.
+        //
+
+        let threatened_rooms = if pool_haulers.is_empty() {
+            Vec::new()
+        } else {
+            let home_rooms: Vec<_> = self
+                .home_room_datas
+                .iter()
+                .filter_map(|entity| system_data.room_data.get(*entity))
+                .map(|home_room_data| home_room_data.name)
+                .collect();
+
+            Self::threatened_route_rooms(system_data, pickup_room, &home_rooms)
+        };
+
+        if threatened_rooms.is_empty() {
+            system_data.escort_request.withdraw(mission_entity);
+        } else {
+            let caravan = Caravan {
+                pickup_room,
+                threatened_rooms,
+                haulers: pool_haulers,
+            };
+
+            system_data.escort_request.request(mission_entity, caravan, game::time());
         }
+
+        Ok(())
     }
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl Mission for HaulMission {
+    impl_mission_pause!();
+
     fn get_owner(&self) -> &Option<Entity> {
         &self.owner
     }
@@ -141,15 +452,27 @@ impl Mission for HaulMission {
         self.haulers.iter().copied().collect()
     }
 
-    fn describe_state(&self, _system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> String {
-        format!("Hauler - Haulers: {}", self.haulers.len())
+    fn describe_state(&self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> String {
+        if self.is_pool() {
+            format!(
+                "Hauler pool - Haulers: {} - Remotes: {}",
+                self.haulers.len(),
+                system_data.hauler_pools.demand(mission_entity).len()
+            )
+        } else {
+            "Haul demand".to_string()
+        }
     }
 
     fn summarize(&self) -> crate::visualization::SummaryContent {
-        crate::visualization::SummaryContent::Text(format!("Haul - Haulers: {}", self.haulers.len()))
+        if self.is_pool() {
+            crate::visualization::SummaryContent::Text(format!("Haul - Haulers: {}", self.haulers.len()))
+        } else {
+            crate::visualization::SummaryContent::Text("Haul - Demand".to_string())
+        }
     }
 
-    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), String> {
+    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<(), MissionError> {
         //
         // Cleanup home rooms that no longer exist.
         //
@@ -158,15 +481,43 @@ impl Mission for HaulMission {
             .retain(|entity| system_data.room_data.get(*entity).map(is_valid_home_room).unwrap_or(false));
 
         if self.home_room_datas.is_empty() {
-            return Err("No home rooms for haul mission".to_owned());
+            return Err(MissionError::new(MissionFailure::NoHomeRooms, "No home rooms for haul mission"));
         }
 
-        Ok(())
+        if self.is_pool() {
+            Ok(())
+        } else {
+            self.pre_run_demand(system_data, mission_entity)
+        }
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, String> {
+    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, MissionError> {
+        if !self.is_pool() {
+            return Ok(MissionResult::Running);
+        }
+
+        // A home in lockdown calls its haulers back: no remote is a pickup room until it stands down.
+        let lockdown = system_data
+            .defense_postures
+            .get(self.room_data)
+            .map(|posture| posture.is_lockdown())
+            .unwrap_or(false);
+        let demand = if lockdown {
+            Vec::new()
+        } else {
+            system_data.hauler_pools.demand(mission_entity).to_vec()
+        };
+
+        let pickup_rooms: Vec<Entity> = std::iter::once(self.room_data).chain(demand.iter().map(|d| d.room_data)).collect();
+
+        self.retarget_haulers(system_data, &pickup_rooms);
+
+        let overflowing_containers = Self::overflowing_containers(system_data, &pickup_rooms);
+
         let room_data_storage = &*system_data.room_data;
-        let room_data = room_data_storage.get(self.room_data).ok_or("Expected room data")?;
+        let room_data = room_data_storage
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
 
         let transfer_queue = &mut *system_data.transfer_queue;
         let transfer_queue_data = TransferQueueGeneratorData {
@@ -176,146 +527,157 @@ impl Mission for HaulMission {
 
         let room_visible = room_data.get_dynamic_visibility_data().map(|v| v.visible()).unwrap_or(false);
 
-        let home_room_datas: Vec<_> = self
-            .home_room_datas
-            .iter()
-            .filter_map(|e| room_data_storage.get(*e).map(|d| (e, d)))
+        let sized_rooms: Vec<_> = std::iter::once((room_data.name, 0))
+            .chain(demand.iter().filter(|d| d.sized).map(|d| (d.room_name, d.distance)))
             .collect();
 
-        if home_room_datas.is_empty() {
-            return Err("No home rooms available for hauling".to_owned());
-        }
-
-        let home_room_names: Vec<_> = home_room_datas.iter().map(|(_, r)| r.name).collect();
-
-        let pickup_rooms = &[room_data.name];
-
         let mut stats = self.stats.access(
             |s| game::time().saturating_sub(s.last_updated) >= 20 && room_visible,
-            || Self::update_stats(transfer_queue, &transfer_queue_data, pickup_rooms, &home_room_names),
+            || Self::update_stats(transfer_queue, &transfer_queue_data, &sized_rooms, room_data.name),
         );
         let stats = stats.get();
 
-        //TODO: Use find route plus cache.
-        let home_room_spawn_info: Vec<_> = home_room_datas
-            .iter()
-            .filter_map(|(entity, home_room_data)| {
-                let room_offset_distance = home_room_data.name - room_data.name;
-
-                let room_manhattan_distance = room_offset_distance.0.unsigned_abs() + room_offset_distance.1.unsigned_abs();
-
-                //TODO: Use structure cache?
-                let room = game::rooms().get(home_room_data.name)?;
-                let controller = room.controller()?;
-
-                let current_energy = room.energy_available().max(SPAWN_ENERGY_CAPACITY);
-                let max_energy = room.energy_capacity_available();
-
-                Some((
-                    entity,
-                    room,
-                    room_manhattan_distance,
-                    controller.level(),
-                    current_energy,
-                    max_energy,
-                ))
-            })
-            .collect();
-
-        let is_multi_room = home_room_spawn_info.iter().any(|(_, _, distance, _, _, _)| *distance > 0);
-
-        let token = system_data.spawn_queue.token();
+        //TODO: Use structure cache?
+        let Some(room) = game::rooms().get(room_data.name) else {
+            return Ok(MissionResult::Running);
+        };
 
         let energy_to_use = if self.haulers.is_empty() {
-            home_room_spawn_info
-                .iter()
-                .map(|(_, _, _, _, current_energy, _)| *current_energy)
-                .max()
+            room.energy_available().max(SPAWN_ENERGY_CAPACITY)
         } else {
-            home_room_spawn_info.iter().map(|(_, _, _, _, _, max_energy)| *max_energy).min()
-        }
-        .unwrap_or(SPAWN_ENERGY_CAPACITY);
+            room.energy_capacity_available()
+        };
 
-        let max_distance = home_room_spawn_info
-            .iter()
-            .map(|(_, _, distance, _, _, _)| *distance)
-            .max()
-            .unwrap_or(0);
-
-        let body_definition = if is_multi_room {
-            crate::creep::SpawnBodyDefinition {
-                maximum_energy: energy_to_use,
-                minimum_repeat: Some(1),
-                maximum_repeat: Some(20),
-                pre_body: &[Part::Work, Part::Move],
-                repeat_body: &[Part::Carry, Part::Move],
-                post_body: &[],
-            }
+        // Haulers serving remotes carry a WORK part to repair the roads they travel.
+        let body_template = BodyTemplate::new(&[Part::Carry]).plains().max_repeat(20);
+
+        let body_template = if demand.is_empty() {
+            body_template
         } else {
-            crate::creep::SpawnBodyDefinition {
-                maximum_energy: energy_to_use,
-                minimum_repeat: Some(1),
-                maximum_repeat: Some(20),
-                pre_body: &[],
-                repeat_body: &[Part::Carry, Part::Move],
-                post_body: &[],
-            }
+            body_template.prefix(&[Part::Work])
         };
 
-        if let Ok(body) = crate::creep::spawning::create_body(&body_definition) {
-            let carry_parts = body.iter().filter(|p| **p == Part::Carry).count();
+        if let Some(body) = body_template.build(energy_to_use) {
+            let carry_parts = body.iter().filter(|p| **p == Part::Carry).count() as u32;
 
-            let range_multiplier = 1.0 / ((max_distance as f32 * 2.0) + 1.0);
-            let base_amount = carry_parts as f32 * CARRY_CAPACITY as f32 * range_multiplier;
+            let max_haulers = max_pool_haulers(&demand);
 
-            let max_haulers = 3 + (max_distance * 3);
-
-            let desired_haulers_for_unfufilled = (stats.unfufilled_hauling as f32 / base_amount) as u32;
-            let desired_haulers = desired_haulers_for_unfufilled.min(max_haulers) as usize;
+            let desired_haulers_for_unfufilled = stats.unfufilled_hauling / (carry_parts * CARRY_CAPACITY).max(1);
+            let desired_haulers = (desired_haulers_for_unfufilled.min(max_haulers) + overflowing_containers) as usize;
 
             let should_spawn = self.haulers.len() < desired_haulers && self.allow_spawning;
 
-            if should_spawn {
-                let priority = if (self.haulers.len() as f32) < (desired_haulers_for_unfufilled as f32 * 0.75).ceil() {
-                    if max_distance == 0 {
+            // A pool that is no bigger than its own room's share cannot spare a hauler, so the shortest
+            // lived one is replaced ahead of its death.
+            let replacing = if !should_spawn
+                && self.allow_spawning
+                && self.haulers.len() == desired_haulers
+                && desired_haulers <= LOCAL_MAX_HAULERS as usize
+            {
+                self.haulers
+                    .iter()
+                    .filter_map(|hauler| system_data.creep_owner.get(*hauler)?.owner.resolve()?.ticks_to_live())
+                    .min()
+            } else {
+                None
+            };
+
+            if should_spawn || replacing.is_some() {
+                let local = (self.haulers.len() as u32) < LOCAL_MAX_HAULERS;
+
+                let priority = if replacing.is_some() {
+                    SPAWN_PRIORITY_HIGH
+                } else if (self.haulers.len() as f32) < (desired_haulers_for_unfufilled as f32 * 0.75).ceil() {
+                    if local {
                         SPAWN_PRIORITY_HIGH
                     } else {
                         SPAWN_PRIORITY_MEDIUM
                     }
+                } else if local {
+                    SPAWN_PRIORITY_MEDIUM
                 } else {
-                    if max_distance == 0 {
-                        SPAWN_PRIORITY_MEDIUM
-                    } else {
-                        SPAWN_PRIORITY_LOW
-                    }
+                    SPAWN_PRIORITY_LOW
                 };
 
-                let pickup_rooms = &[self.room_data];
-
-                let allow_repair = max_distance > 0;
-                let storage_delivery_only = max_distance > 0;
-
-                for (entity, _, _, _, _, _) in home_room_spawn_info {
-                    //TODO: Make sure there is handling for starvation/bootstrap mode.
-                    let spawn_request = SpawnRequest::new(
-                        format!("Haul - Target Room: {}", room_data.name),
-                        &body,
-                        priority,
-                        Some(token),
-                        Self::create_handle_hauler_spawn(
-                            mission_entity,
-                            pickup_rooms,
-                            &self.home_room_datas,
-                            allow_repair,
-                            storage_delivery_only,
-                        ),
-                    );
-
-                    system_data.spawn_queue.request(**entity, spawn_request);
-                }
+                let token = system_data.spawn_queue.token();
+
+                //TODO: Make sure there is handling for starvation/bootstrap mode.
+                let spawn_request = SpawnRequest::new(
+                    format!("Haul - Target Room: {}", room_data.name),
+                    &body,
+                    priority,
+                    Some(token),
+                    Self::create_handle_hauler_spawn(mission_entity, &pickup_rooms, &[self.room_data], true, false),
+                )
+                .requested_by(mission_entity);
+
+                let spawn_request = match room.storage() {
+                    Some(storage) => spawn_request.toward(storage.pos()),
+                    None => spawn_request,
+                };
+
+                // Haulers go to work where they spawn; the lead time is the spawn itself.
+                let spawn_request = match replacing {
+                    Some(ticks_to_live) => spawn_request.replacing(ticks_to_live, 0),
+                    None => spawn_request,
+                };
+
+                system_data.spawn_queue.request(self.room_data, spawn_request);
             }
         }
 
         Ok(MissionResult::Running)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn demand(room_data: Entity, distance: u32, sized: bool) -> HaulDemand {
+        HaulDemand {
+            room_data,
+            room_name: "W1N1".parse().expect("valid room name"),
+            distance,
+            sized,
+        }
+    }
+
+    #[test]
+    fn pool_grows_only_for_sized_remotes() {
+        let mut world = World::new();
+        let (near, far, unsafe_room) = (
+            world.create_entity().build(),
+            world.create_entity().build(),
+            world.create_entity().build(),
+        );
+
+        assert_eq!(max_pool_haulers(&[]), LOCAL_MAX_HAULERS);
+        assert_eq!(
+            max_pool_haulers(&[demand(near, 1, true), demand(far, 2, true), demand(unsafe_room, 1, false)]),
+            LOCAL_MAX_HAULERS + (LOCAL_MAX_HAULERS + REMOTE_HAULERS_PER_DISTANCE) + (LOCAL_MAX_HAULERS + 2 * REMOTE_HAULERS_PER_DISTANCE)
+        );
+    }
+
+    #[test]
+    fn filed_demand_is_kept_per_pool_until_cleared() {
+        let mut world = World::new();
+        let (pool, other_pool, remote) = (
+            world.create_entity().build(),
+            world.create_entity().build(),
+            world.create_entity().build(),
+        );
+        let mut pools = HaulerPools::default();
+
+        pools.file(pool, demand(remote, 1, true));
+
+        assert_eq!(pools.demand(pool), &[demand(remote, 1, true)]);
+        assert!(pools.demand(other_pool).is_empty());
+
+        pools.clear();
+
+        assert!(pools.demand(pool).is_empty());
+        assert_eq!(trip_legs(0), 1);
+        assert_eq!(trip_legs(2), 5);
+    }
+}
@@ -1,7 +1,9 @@
 use super::constants::*;
 use super::data::*;
 use super::missionsystem::*;
+use crate::intents::IntentCategory;
 use crate::jobs::utility::waitbehavior::*;
+use crate::military::boostqueue::*;
 use crate::remoteobjectid::*;
 use crate::room::data::*;
 use crate::serialize::*;
@@ -17,7 +19,7 @@ use specs::*;
 use std::collections::HashMap;
 use std::marker::PhantomData;
 
-type LabPairResult = Result<(Vec<ObjectId<StructureLab>>, Vec<ObjectId<StructureLab>>), String>;
+type LabPairResult = Result<(Vec<ObjectId<StructureLab>>, Vec<ObjectId<StructureLab>>), MissionError>;
 
 #[derive(Clone, ConvertSaveload)]
 pub struct LabsMissionContext {
@@ -44,6 +46,9 @@ machine!(
             amount: u32,
             input: Vec<ObjectId<StructureLab>>,
             output: Vec<ObjectId<StructureLab>>,
+        },
+        Boost {
+            labs: Vec<(ObjectId<StructureLab>, ResourceType)>,
         }
     }
 
@@ -58,10 +63,21 @@ machine!(
 
         _ => fn gather_data(&self, _system_data: &mut MissionExecutionSystemData, _mission_entity: Entity, _state_context: &mut LabsMissionContext);
 
-        _ => fn tick(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity, state_context: &mut LabsMissionContext) -> Result<Option<LabsState>, String>;
+        _ => fn tick(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity, state_context: &mut LabsMissionContext) -> Result<Option<LabsState>, MissionError>;
     }
 );
 
+impl LabsState {
+    /// The labs the current reaction runs on, which can't also unboost this tick.
+    fn reacting_labs(&self) -> Vec<ObjectId<StructureLab>> {
+        match self {
+            LabsState::RunReaction(state) => state.output.clone(),
+            LabsState::RunReverseReaction(state) => state.input.clone(),
+            _ => Vec::new(),
+        }
+    }
+}
+
 enum ReactionType {
     Forward,
     Reverse,
@@ -99,6 +115,101 @@ fn unload_labs_transfer_generator(room_entity: Entity) -> TransferQueueGenerator
     })
 }
 
+/// Compound amounts the military has asked this room's labs to boost creeps with.
+fn boost_demand(system_data: &MissionExecutionSystemData, room_name: RoomName) -> HashMap<ResourceType, u32> {
+    let mut demand = HashMap::new();
+
+    for request in system_data.boost_queue.pending_requests() {
+        if request.room == room_name && request.priority >= BoostPriority::High {
+            *demand.entry(request.compound).or_insert(0) += request.amount_needed();
+        }
+    }
+
+    demand
+}
+
+/// Strip the boosts of the creeps asking to be unboosted in this room (see
+/// `jobs::utility::unboostbehavior`): give each a lab off cooldown, and
+/// unboost the ones already beside theirs. Runs alongside whatever the labs
+/// are doing, sparing the labs a reaction runs on this tick (`busy`); an
+/// unboost only costs a lab its cooldown. The compounds drop under the
+/// creep, where the room's dropped resource requests pick them up.
+fn serve_unboosts(
+    system_data: &mut MissionExecutionSystemData,
+    state_context: &LabsMissionContext,
+    busy: &[ObjectId<StructureLab>],
+) -> Result<(), MissionError> {
+    let room_data = system_data
+        .room_data
+        .get(state_context.room_data)
+        .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
+    let room_name = room_data.name;
+
+    let creeps: Vec<(Entity, Creep)> = system_data
+        .boost_queue
+        .unboost_requests
+        .iter()
+        .filter(|request| request.room == room_name)
+        .filter_map(|request| request.creep.resolve().map(|creep| (request.requester, creep)))
+        .collect();
+
+    if creeps.is_empty() {
+        return Ok(());
+    }
+
+    let structures = room_data.get_structures().ok_or_else(|| {
+        let msg = format!("Expected structures - Room: {}", room_data.name);
+        log::warn!("{} at {}:{}", msg, file!(), line!());
+        MissionError::new(MissionFailure::MissingRoomData, msg)
+    })?;
+
+    let labs: Vec<StructureLab> = structures
+        .labs()
+        .iter()
+        .filter(|lab| lab.my() && lab.cooldown() == 0 && !busy.contains(&lab.id()))
+        .cloned()
+        .collect();
+
+    let free_labs: Vec<_> = labs.iter().map(|lab| (lab.id(), lab.pos())).collect();
+    let requesters: Vec<_> = creeps
+        .iter()
+        .map(|(requester, creep)| (*requester, creep.pos(), system_data.boost_queue.unboost_lab(*requester)))
+        .collect();
+
+    for (requester, _, _) in &requesters {
+        system_data.boost_queue.unboost_labs.remove(requester);
+    }
+
+    for (requester, lab_id) in assign_unboost_labs(&requesters, &free_labs) {
+        system_data.boost_queue.unboost_labs.insert(requester, lab_id);
+
+        let lab = labs.iter().find(|lab| lab.id() == lab_id);
+        let creep = creeps.iter().find(|(entity, _)| *entity == requester).map(|(_, creep)| creep);
+
+        if let Some((lab, creep)) = lab.zip(creep) {
+            if creep.pos().is_near_to(lab.pos()) {
+                match system_data
+                    .intent_recorder
+                    .issued(IntentCategory::Structure, lab.unboost_creep(creep))
+                {
+                    Ok(()) => info!("Unboosted creep - Room: {} Creep: {}", room_name, creep.name()),
+                    Err(err) => warn!("Failed to unboost creep: {:?}", err),
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn has_boost_demand(system_data: &MissionExecutionSystemData, state_context: &LabsMissionContext) -> bool {
+    system_data
+        .room_data
+        .get(state_context.room_data)
+        .map(|room_data| !boost_demand(system_data, room_data.name).is_empty())
+        .unwrap_or(false)
+}
+
 impl Idle {
     fn status_description(&self) -> String {
         "Idle".to_string()
@@ -115,12 +226,15 @@ impl Idle {
     }
 
     fn get_labs(system_data: &mut MissionExecutionSystemData, state_context: &mut LabsMissionContext, input_labs: usize) -> LabPairResult {
-        let room_data = system_data.room_data.get(state_context.room_data).ok_or("Expected room data")?;
+        let room_data = system_data
+            .room_data
+            .get(state_context.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
 
         let structures = room_data.get_structures().ok_or_else(|| {
             let msg = format!("Expected structures - Room: {}", room_data.name);
             log::warn!("{} at {}:{}", msg, file!(), line!());
-            msg
+            MissionError::new(MissionFailure::MissingRoomData, msg)
         })?;
 
         let labs = structures.labs();
@@ -137,7 +251,10 @@ impl Idle {
             .collect();
 
         if inputs.len() != input_labs {
-            return Err("Insufficient input labs to run reaction".to_owned());
+            return Err(MissionError::new(
+                MissionFailure::TargetInvalid,
+                "Insufficient input labs to run reaction",
+            ));
         }
 
         let outputs: Vec<_> = labs.iter().filter(|lab| !inputs.contains(&lab.id())).map(|l| l.id()).collect();
@@ -150,15 +267,24 @@ impl Idle {
         system_data: &mut MissionExecutionSystemData,
         _mission_entity: Entity,
         state_context: &mut LabsMissionContext,
-    ) -> Result<Option<LabsState>, String> {
+    ) -> Result<Option<LabsState>, MissionError> {
+        if let Some(labs) = Self::get_boost_labs(system_data, state_context)? {
+            return Ok(Some(LabsState::boost(labs)));
+        }
+
         if let Some((reaction_type, resource_type, amount)) = Self::get_target_reaction(system_data, state_context)? {
-            let components = resource_type.reaction_components().ok_or("Expected reaction components")?;
+            let components = resource_type
+                .reaction_components()
+                .ok_or_else(|| MissionError::new(MissionFailure::TargetInvalid, "Expected reaction components"))?;
 
             if let Ok((inputs, outputs)) = Self::get_labs(system_data, state_context, components.len()) {
                 if !inputs.is_empty() && !outputs.is_empty() {
                     match reaction_type {
                         ReactionType::Forward => {
-                            let room_data = system_data.room_data.get(state_context.room_data).ok_or("Expected room data")?;
+                            let room_data = system_data
+                                .room_data
+                                .get(state_context.room_data)
+                                .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
 
                             info!(
                                 "Selected reaction - Room: {} Resource: {:?} - Amount: {}",
@@ -174,7 +300,10 @@ impl Idle {
                             return Ok(Some(LabsState::run_reaction(resource_type, amount, inputs, outputs)));
                         }
                         ReactionType::Reverse => {
-                            let room_data = system_data.room_data.get(state_context.room_data).ok_or("Expected room data")?;
+                            let room_data = system_data
+                                .room_data
+                                .get(state_context.room_data)
+                                .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
 
                             info!(
                                 "Selected reverse reaction - Room: {} Resource: {:?} - Amount: {}",
@@ -195,6 +324,53 @@ impl Idle {
         Ok(Some(LabsState::wait(20)))
     }
 
+    /// One lab per boost compound in demand, preferring a lab that already holds the compound.
+    fn get_boost_labs(
+        system_data: &mut MissionExecutionSystemData,
+        state_context: &mut LabsMissionContext,
+    ) -> Result<Option<Vec<(ObjectId<StructureLab>, ResourceType)>>, MissionError> {
+        let room_data = system_data
+            .room_data
+            .get(state_context.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
+
+        let compounds: Vec<_> = boost_demand(system_data, room_data.name).into_keys().collect();
+
+        if compounds.is_empty() {
+            return Ok(None);
+        }
+
+        let structures = room_data.get_structures().ok_or_else(|| {
+            let msg = format!("Expected structures - Room: {}", room_data.name);
+            log::warn!("{} at {}:{}", msg, file!(), line!());
+            MissionError::new(MissionFailure::MissingRoomData, msg)
+        })?;
+
+        let mut free_labs: Vec<_> = structures.labs().iter().filter(|lab| lab.my()).collect();
+        let mut assignments = Vec::new();
+
+        for compound in compounds {
+            if free_labs.is_empty() {
+                break;
+            }
+
+            let index = free_labs
+                .iter()
+                .position(|lab| lab.store().get_used_capacity(Some(compound)) > 0)
+                .unwrap_or(0);
+
+            assignments.push((free_labs.remove(index).id(), compound));
+        }
+
+        if assignments.is_empty() {
+            return Ok(None);
+        }
+
+        info!("Boosting - Room: {} Labs: {:?}", room_data.name, assignments);
+
+        Ok(Some(assignments))
+    }
+
     fn desired_resources() -> &'static [ResourceType] {
         &[
             //
@@ -242,8 +418,11 @@ impl Idle {
     fn get_target_reaction(
         system_data: &mut MissionExecutionSystemData,
         state_context: &mut LabsMissionContext,
-    ) -> Result<Option<(ReactionType, ResourceType, u32)>, String> {
-        let room_data = system_data.room_data.get(state_context.room_data).ok_or("Expected room data")?;
+    ) -> Result<Option<(ReactionType, ResourceType, u32)>, MissionError> {
+        let room_data = system_data
+            .room_data
+            .get(state_context.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
 
         let transfer_queue_data = TransferQueueGeneratorData {
             cause: "Labs Idle",
@@ -262,6 +441,20 @@ impl Idle {
             .map(|resource| (*resource, get_desired_storage_amount(*resource)))
             .collect();
 
+        //
+        // Targets are popped from the back, so the empire's shortfalls go last and the largest of them last of all.
+        //
+
+        for (resource, deficit) in system_data.resource_overview.deficits().iter().rev() {
+            if resource.reaction_components().is_some() {
+                let available_amount = available_resources.get(resource).copied().unwrap_or(0);
+                let desired_amount = get_desired_storage_amount(*resource).max(available_amount + deficit);
+
+                target_resources.retain(|(target_resource, _)| target_resource != resource);
+                target_resources.push((*resource, desired_amount));
+            }
+        }
+
         while let Some((target_resource, desired_amount)) = target_resources.pop() {
             let needed_amount = {
                 let available_amount = available_resources.entry(target_resource).or_insert(0);
@@ -366,10 +559,14 @@ impl Wait {
 
     fn tick(
         &mut self,
-        _system_data: &mut MissionExecutionSystemData,
+        system_data: &mut MissionExecutionSystemData,
         _mission_entity: Entity,
-        _state_context: &mut LabsMissionContext,
-    ) -> Result<Option<LabsState>, String> {
+        state_context: &mut LabsMissionContext,
+    ) -> Result<Option<LabsState>, MissionError> {
+        if has_boost_demand(system_data, state_context) {
+            return Ok(Some(LabsState::idle(PhantomData)));
+        }
+
         Ok(tick_wait(&mut self.ticks, || LabsState::idle(PhantomData)))
     }
 }
@@ -472,22 +669,36 @@ impl RunReaction {
 
     fn tick(
         &mut self,
-        _system_data: &mut MissionExecutionSystemData,
+        system_data: &mut MissionExecutionSystemData,
         _mission_entity: Entity,
-        _state_context: &mut LabsMissionContext,
-    ) -> Result<Option<LabsState>, String> {
-        if self.amount < LAB_REACTION_AMOUNT {
+        state_context: &mut LabsMissionContext,
+    ) -> Result<Option<LabsState>, MissionError> {
+        //
+        // NOTE: Boosting for defense takes the labs over from any reaction.
+        //
+
+        if self.amount < LAB_REACTION_AMOUNT || has_boost_demand(system_data, state_context) {
             return Ok(Some(LabsState::idle(PhantomData)));
         }
 
         //TODO: Add stuck detection - (i.e. resources go missing).
 
-        let (input_1, input_1_resource) = self.input.first().ok_or("Expected first input lab")?;
-        let input_1 = input_1.resolve().ok_or("Expected to resolve first input lab")?;
+        let (input_1, input_1_resource) = self
+            .input
+            .first()
+            .ok_or_else(|| MissionError::new(MissionFailure::TargetInvalid, "Expected first input lab"))?;
+        let input_1 = input_1
+            .resolve()
+            .ok_or_else(|| MissionError::new(MissionFailure::TargetInvalid, "Expected to resolve first input lab"))?;
         let mut input_1_resource_amount = input_1.store().get(*input_1_resource).unwrap_or(0);
 
-        let (input_2, input_2_resource) = self.input.get(1).ok_or("Expected second input lab")?;
-        let input_2 = input_2.resolve().ok_or("Expected to resolve second input lab")?;
+        let (input_2, input_2_resource) = self
+            .input
+            .get(1)
+            .ok_or_else(|| MissionError::new(MissionFailure::TargetInvalid, "Expected second input lab"))?;
+        let input_2 = input_2
+            .resolve()
+            .ok_or_else(|| MissionError::new(MissionFailure::TargetInvalid, "Expected to resolve second input lab"))?;
         let mut input_2_resource_amount = input_2.store().get(*input_2_resource).unwrap_or(0);
 
         for output in self.output.iter() {
@@ -495,7 +706,9 @@ impl RunReaction {
                 break;
             }
 
-            let lab = output.resolve().ok_or("Expected lab")?;
+            let lab = output
+                .resolve()
+                .ok_or_else(|| MissionError::new(MissionFailure::TargetInvalid, "Expected lab"))?;
 
             if lab.cooldown() > 0 {
                 continue;
@@ -505,7 +718,10 @@ impl RunReaction {
                 continue;
             }
 
-            match lab.run_reaction(&input_1, &input_2) {
+            match system_data
+                .intent_recorder
+                .issued(IntentCategory::Structure, lab.run_reaction(&input_1, &input_2))
+            {
                 Ok(()) => {
                     self.amount -= LAB_REACTION_AMOUNT;
 
@@ -639,18 +855,27 @@ impl RunReverseReaction {
 
     fn tick(
         &mut self,
-        _system_data: &mut MissionExecutionSystemData,
+        system_data: &mut MissionExecutionSystemData,
         _mission_entity: Entity,
-        _state_context: &mut LabsMissionContext,
-    ) -> Result<Option<LabsState>, String> {
-        if self.amount < LAB_REACTION_AMOUNT {
+        state_context: &mut LabsMissionContext,
+    ) -> Result<Option<LabsState>, MissionError> {
+        //
+        // NOTE: Boosting for defense takes the labs over from any reaction.
+        //
+
+        if self.amount < LAB_REACTION_AMOUNT || has_boost_demand(system_data, state_context) {
             return Ok(Some(LabsState::idle(PhantomData)));
         }
 
         //TODO: Add stuck detection - (i.e. resources go missing).
 
-        let output_1 = self.output.first().ok_or("Expected first output lab")?;
-        let output_1 = output_1.resolve().ok_or("Expected to resolve first output lab")?;
+        let output_1 = self
+            .output
+            .first()
+            .ok_or_else(|| MissionError::new(MissionFailure::TargetInvalid, "Expected first output lab"))?;
+        let output_1 = output_1
+            .resolve()
+            .ok_or_else(|| MissionError::new(MissionFailure::TargetInvalid, "Expected to resolve first output lab"))?;
 
         let output_1_resources = output_1.store().store_types();
         let mut output_1_free_capacity = output_1_resources
@@ -659,8 +884,13 @@ impl RunReverseReaction {
             .map(|r| output_1.store().get_free_capacity(Some(*r)))
             .unwrap_or(LAB_MINERAL_CAPACITY as i32);
 
-        let output_2 = self.output.get(1).ok_or("Expected second output lab")?;
-        let output_2 = output_2.resolve().ok_or("Expected to resolve second output lab")?;
+        let output_2 = self
+            .output
+            .get(1)
+            .ok_or_else(|| MissionError::new(MissionFailure::TargetInvalid, "Expected second output lab"))?;
+        let output_2 = output_2
+            .resolve()
+            .ok_or_else(|| MissionError::new(MissionFailure::TargetInvalid, "Expected to resolve second output lab"))?;
 
         let output_2_resources = output_2.store().store_types();
         let mut output_2_free_capacity = output_2_resources
@@ -674,7 +904,9 @@ impl RunReverseReaction {
                 break;
             }
 
-            let lab = input.resolve().ok_or("Expected lab")?;
+            let lab = input
+                .resolve()
+                .ok_or_else(|| MissionError::new(MissionFailure::TargetInvalid, "Expected lab"))?;
 
             if lab.cooldown() > 0 {
                 continue;
@@ -711,11 +943,138 @@ impl RunReverseReaction {
     }
 }
 
+impl Boost {
+    fn status_description(&self) -> String {
+        format!("Boost - {:?}", self.labs.iter().map(|(_, compound)| compound).collect::<Vec<_>>())
+    }
+
+    fn gather_data(&self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity, state_context: &mut LabsMissionContext) {
+        if let Some(room_data) = system_data.room_data.get(state_context.room_data) {
+            let demand = boost_demand(system_data, room_data.name);
+
+            let labs: Vec<_> = self
+                .labs
+                .iter()
+                .map(|(lab, compound)| (*lab, *compound, demand.get(compound).copied().unwrap_or(0)))
+                .collect();
+
+            system_data
+                .transfer_queue
+                .register_generator(room_data.name, TransferTypeFlags::HAUL, Self::transfer_generator(labs));
+        }
+    }
+
+    fn transfer_generator(labs: Vec<(ObjectId<StructureLab>, ResourceType, u32)>) -> TransferQueueGenerator {
+        Box::new(move |_system, transfer, _room_name| {
+            for (lab, compound, amount) in labs.iter() {
+                let lab = lab.resolve().ok_or("Expected lab")?;
+
+                let current_store = lab.store().store_types();
+
+                for unwanted_resource in current_store.iter().filter(|r| **r != ResourceType::Energy && *r != compound) {
+                    let unwanted_amount = lab.store().get(*unwanted_resource).unwrap_or(0);
+
+                    let transfer_request = TransferWithdrawRequest::new(
+                        TransferTarget::Lab(lab.remote_id()),
+                        *unwanted_resource,
+                        TransferPriority::High,
+                        unwanted_amount,
+                        TransferType::Haul,
+                    );
+
+                    transfer.request_withdraw(transfer_request);
+                }
+
+                //
+                // NOTE: Each boosted part takes LAB_BOOST_MINERAL of the compound and LAB_BOOST_ENERGY energy.
+                //
+
+                let parts = amount / LAB_BOOST_MINERAL;
+
+                for (resource, needed) in [(*compound, *amount), (ResourceType::Energy, parts * LAB_BOOST_ENERGY)] {
+                    let current_amount = lab.store().get(resource).unwrap_or(0);
+                    let free_capacity = lab.store().get_free_capacity(Some(resource));
+
+                    let deposit_amount = (needed as i32 - current_amount as i32).min(free_capacity);
+
+                    if deposit_amount > 0 {
+                        let transfer_request = TransferDepositRequest::new(
+                            TransferTarget::Lab(lab.remote_id()),
+                            Some(resource),
+                            TransferPriority::High,
+                            deposit_amount as u32,
+                            TransferType::Haul,
+                        );
+
+                        transfer.request_deposit(transfer_request);
+                    }
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    fn tick(
+        &mut self,
+        system_data: &mut MissionExecutionSystemData,
+        _mission_entity: Entity,
+        state_context: &mut LabsMissionContext,
+    ) -> Result<Option<LabsState>, MissionError> {
+        let room_name = system_data
+            .room_data
+            .get(state_context.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?
+            .name;
+
+        let demand = boost_demand(system_data, room_name);
+
+        //
+        // NOTE: Go back to idle (which unloads the labs) once the creeps are boosted, or to reassign labs when
+        //       a new compound is asked for.
+        //
+
+        if demand.is_empty() || demand.keys().any(|compound| self.labs.iter().all(|(_, c)| c != compound)) {
+            return Ok(Some(LabsState::idle(PhantomData)));
+        }
+
+        let mut ready = Vec::new();
+
+        for (lab, compound) in self.labs.iter() {
+            let lab = lab
+                .resolve()
+                .ok_or_else(|| MissionError::new(MissionFailure::TargetInvalid, "Expected lab"))?;
+
+            let stocked = lab.store().get(*compound).unwrap_or(0);
+
+            for request in system_data.boost_queue.pending_requests() {
+                if request.room == room_name && request.compound == *compound && stocked >= request.amount_needed() {
+                    ready.push((
+                        request.requester,
+                        BoostAllocation {
+                            compound: *compound,
+                            amount: request.amount_needed(),
+                            room: room_name,
+                        },
+                    ));
+                }
+            }
+        }
+
+        for (requester, allocation) in ready {
+            system_data.boost_queue.mark_ready(requester, allocation);
+        }
+
+        Ok(None)
+    }
+}
+
 #[derive(ConvertSaveload)]
 pub struct LabsMission {
     owner: EntityOption<Entity>,
     context: LabsMissionContext,
     state: LabsState,
+    paused: bool,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -736,6 +1095,7 @@ impl LabsMission {
             owner: owner.into(),
             context: LabsMissionContext { room_data },
             state: LabsState::idle(PhantomData),
+            paused: false,
         }
     }
 
@@ -749,6 +1109,8 @@ impl LabsMission {
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl Mission for LabsMission {
+    impl_mission_pause!();
+
     fn get_owner(&self) -> &Option<Entity> {
         &self.owner
     }
@@ -776,13 +1138,15 @@ impl Mission for LabsMission {
         }
     }
 
-    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<(), String> {
+    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<(), MissionError> {
         self.state.gather_data(system_data, mission_entity, &mut self.context);
 
         Ok(())
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, String> {
+    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, MissionError> {
+        serve_unboosts(system_data, &self.context, &self.state.reacting_labs())?;
+
         crate::machine_tick::run_state_machine_result(&mut self.state, "LabsMission", |state| {
             state.tick(system_data, mission_entity, &mut self.context)
         })?;
@@ -1,7 +1,8 @@
 use super::constants::*;
 use super::data::*;
 use super::missionsystem::*;
-use crate::creep::*;
+use super::overflow::EnergySink;
+use crate::creep::spawning::BodyTemplate;
 use crate::jobs::build::*;
 use crate::jobs::data::*;
 use crate::jobs::utility::repair::*;
@@ -17,11 +18,16 @@ use specs::error::NoError;
 use specs::saveload::*;
 use specs::*;
 
+/// Builders kept on the walls and ramparts while the room runs the fortify
+/// overflow sink (see `missions::overflow`).
+const FORTIFY_BUILDERS: u32 = 2;
+
 #[derive(ConvertSaveload)]
 pub struct LocalBuildMission {
     owner: EntityOption<Entity>,
     room_data: Entity,
     builders: EntityVec<Entity>,
+    paused: bool,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -42,6 +48,7 @@ impl LocalBuildMission {
             owner: owner.into(),
             room_data,
             builders: EntityVec::new(),
+            paused: false,
         }
     }
 
@@ -148,6 +155,8 @@ impl LocalBuildMission {
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl Mission for LocalBuildMission {
+    impl_mission_pause!();
+
     fn get_owner(&self) -> &Option<Entity> {
         &self.owner
     }
@@ -178,7 +187,7 @@ impl Mission for LocalBuildMission {
         crate::visualization::SummaryContent::Text(format!("Local Build - Builders: {}", self.builders.len()))
     }
 
-    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), String> {
+    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), MissionError> {
         //
         // Populate the repair queue with non-wall structures that need repair.
         // This makes the repair queue the single source of truth for repair
@@ -216,11 +225,17 @@ impl Mission for LocalBuildMission {
         Ok(())
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, String> {
+    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, MissionError> {
         let room_data_storage = &*system_data.room_data;
-        let room_data = room_data_storage.get(self.room_data).ok_or("Expected room data")?;
-        let room = game::rooms().get(room_data.name).ok_or("Expected room")?;
-        let structure_data = room_data.get_structures().ok_or("Expected structure data")?;
+        let room_data = room_data_storage
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
+        let room = game::rooms()
+            .get(room_data.name)
+            .ok_or_else(|| MissionError::new(MissionFailure::NoVisibility, "Expected room"))?;
+        let structure_data = room_data
+            .get_structures()
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected structure data"))?;
 
         let desired_storage_energy = get_desired_storage_amount(ResourceType::Energy) / 4;
 
@@ -251,25 +266,32 @@ impl Mission for LocalBuildMission {
             spawn_priority = spawn_priority.max(repair_priority);
         }
 
-        if self.builders.len() < spawn_count as usize {
+        // A room drowning in energy pours it into its walls and ramparts; builders with nothing
+        // to build repair them.
+        let fortify = system_data.overflow_sinks.is_enabled(self.room_data, EnergySink::Fortify);
+
+        if fortify {
+            spawn_count = spawn_count.max(FORTIFY_BUILDERS);
+            spawn_priority = spawn_priority.max(SPAWN_PRIORITY_LOW);
+        }
+
+        // Builders wait out an energy emergency; the spawns are rebuilding the economy.
+        if self.builders.len() < spawn_count as usize && !system_data.energy_emergency.is_active(self.room_data) {
             let use_energy_max = if self.builders.is_empty() && spawn_priority >= SPAWN_PRIORITY_HIGH {
                 room.energy_available().max(SPAWN_ENERGY_CAPACITY)
             } else {
                 room.energy_capacity_available()
             };
 
-            let max_body = if spawn_priority >= SPAWN_PRIORITY_HIGH { None } else { Some(5) };
+            let body_template = BodyTemplate::new(&[Part::Carry, Part::Work]).plains();
 
-            let body_definition = SpawnBodyDefinition {
-                maximum_energy: use_energy_max,
-                minimum_repeat: Some(1),
-                maximum_repeat: max_body,
-                pre_body: &[],
-                repeat_body: &[Part::Carry, Part::Work, Part::Move, Part::Move],
-                post_body: &[],
+            let body_template = if spawn_priority >= SPAWN_PRIORITY_HIGH || fortify {
+                body_template
+            } else {
+                body_template.max_repeat(5)
             };
 
-            if let Ok(body) = crate::creep::spawning::create_body(&body_definition) {
+            if let Some(body) = body_template.build(use_energy_max) {
                 let allow_harvest = room.storage().is_none();
 
                 let spawn_request = SpawnRequest::new(
@@ -278,7 +300,8 @@ impl Mission for LocalBuildMission {
                     spawn_priority,
                     None,
                     Self::create_handle_builder_spawn(mission_entity, self.room_data, allow_harvest),
-                );
+                )
+                .requested_by(mission_entity);
 
                 system_data.spawn_queue.request(self.room_data, spawn_request);
             }
@@ -1,13 +1,6 @@
 use crate::creep::SpawnBodyDefinition;
 use screeps::*;
 
-use super::structure_data::StructureData;
-
-/// Minimum TTL threshold used when we cannot compute a more accurate lead
-/// time (e.g. no spawn positions visible). This is deliberately conservative
-/// — a small overlap is cheaper than losing mining ticks.
-pub const MIN_REPLACEMENT_LEAD_TICKS: u32 = 30;
-
 /// Build a `SpawnBodyDefinition` for a source miner (link or container).
 ///
 /// - `is_local`: true when the source is in the same room as the home room
@@ -96,47 +89,6 @@ pub fn harvester_body(energy: u32) -> SpawnBodyDefinition<'static> {
     }
 }
 
-/// Estimate how many ticks it takes for a creep with the given body to
-/// traverse `distance` tiles, assuming roads are present. Returns the
-/// travel time in ticks.
-///
-/// Screeps movement on roads:
-///   fatigue_per_tile = MOVE_COST_ROAD (1) * non_move_parts
-///   fatigue_removed_per_tick = MOVE_POWER (2) * move_parts
-///   ticks_per_tile = ceil(fatigue_per_tile / fatigue_removed_per_tick)
-///
-/// If the creep has no MOVE parts it cannot move; returns u32::MAX.
-pub fn estimate_travel_ticks(body: &[Part], distance: u32) -> u32 {
-    let move_parts = body.iter().filter(|p| **p == Part::Move).count() as u32;
-    if move_parts == 0 {
-        return u32::MAX;
-    }
-
-    let non_move_parts = body.len() as u32 - move_parts;
-    let fatigue_per_tile = MOVE_COST_ROAD * non_move_parts;
-    let fatigue_removed_per_tick = MOVE_POWER * move_parts;
-
-    // Ceiling division: ticks needed to clear fatigue from one road tile.
-    let ticks_per_tile = fatigue_per_tile.div_ceil(fatigue_removed_per_tick);
-    // At minimum 1 tick per tile (even with excess MOVE parts).
-    let ticks_per_tile = ticks_per_tile.max(1);
-
-    distance * ticks_per_tile
-}
-
-/// Compute the total lead time (in ticks) needed to spawn a replacement
-/// creep and have it walk to `target_pos`. Uses the precomputed pathfinding
-/// distance from the nearest spawn in `structure_data`.
-pub fn miner_lead_ticks(body: &[Part], target_pos: screeps::Position, structure_data: &StructureData) -> u32 {
-    let spawn_ticks = body.len() as u32 * CREEP_SPAWN_TIME;
-
-    let distance = structure_data.nearest_spawn_distances.get(&target_pos).copied().unwrap_or(0);
-
-    let travel_ticks = estimate_travel_ticks(body, distance);
-
-    (spawn_ticks + travel_ticks).max(MIN_REPLACEMENT_LEAD_TICKS)
-}
-
 /// Compute the number of WORK parts needed to fully harvest a source each
 /// regeneration cycle.
 pub fn source_work_parts(likely_owned_room: bool) -> usize {
@@ -1,4 +1,5 @@
 use super::body_helpers::*;
+use super::seats::*;
 use super::structure_data::*;
 use crate::jobs::data::*;
 use crate::jobs::staticmine::*;
@@ -25,6 +26,7 @@ pub struct MineralMiningMission {
     container_miners: EntityVec<Entity>,
     room_name: RoomName,
     allow_spawning: bool,
+    paused: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -42,6 +44,7 @@ where
     container_miners: <EntityVec<Entity> as ConvertSaveload<MA>>::Data,
     room_name: <RoomName as ConvertSaveload<MA>>::Data,
     allow_spawning: <bool as ConvertSaveload<MA>>::Data,
+    paused: <bool as ConvertSaveload<MA>>::Data,
 }
 
 impl<MA> ConvertSaveload<MA> for MineralMiningMission
@@ -66,6 +69,7 @@ where
             container_miners: ConvertSaveload::convert_into(&self.container_miners, &mut ids)?,
             room_name: ConvertSaveload::convert_into(&self.room_name, &mut ids)?,
             allow_spawning: ConvertSaveload::convert_into(&self.allow_spawning, &mut ids)?,
+            paused: ConvertSaveload::convert_into(&self.paused, &mut ids)?,
         })
     }
 
@@ -82,6 +86,7 @@ where
             container_miners: ConvertSaveload::convert_from(data.container_miners, &mut ids)?,
             room_name: ConvertSaveload::convert_from(data.room_name, &mut ids)?,
             allow_spawning: ConvertSaveload::convert_from(data.allow_spawning, &mut ids)?,
+            paused: ConvertSaveload::convert_from(data.paused, &mut ids)?,
         })
     }
 }
@@ -109,6 +114,7 @@ impl MineralMiningMission {
             container_miners: EntityVec::new(),
             room_name,
             allow_spawning: true,
+            paused: false,
         };
 
         builder
@@ -162,15 +168,39 @@ impl MineralMiningMission {
         })
     }
 
-    fn spawn_creeps(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<(), String> {
-        let room_data = system_data.room_data.get(self.room_data).ok_or("Expected room data")?;
+    /// Release each miner whose replacement has arrived beside it.
+    fn hand_off_seats(&mut self, system_data: &mut MissionExecutionSystemData) {
+        let seats = self
+            .container_miners
+            .iter()
+            .filter_map(|miner_entity| match system_data.job_data.get(*miner_entity) {
+                Some(JobData::StaticMine(miner_data)) => Some((miner_data.context.container_target, *miner_entity)),
+                _ => None,
+            })
+            .into_group_map();
+
+        let outgoing: Vec<Entity> = seats
+            .values()
+            .filter_map(|miners| outgoing_holder(&seat_holders(system_data.creep_owner, system_data.creep_spawning, miners)))
+            .collect();
 
-        let has_visibility = room_data.get_dynamic_visibility_data().map(|v| v.visible()).unwrap_or(false);
+        for miner in outgoing {
+            release_creep(system_data, miner, RecyclePolicy::Recycle);
+
+            self.container_miners.retain(|e| *e != miner);
+        }
+    }
+
+    fn spawn_creeps(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<(), MissionError> {
+        let room_data = system_data
+            .room_data
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
 
         let pathfinder = &mut *system_data.pathfinder;
         let structure_data_rc = system_data.supply_structure_cache.get_room(self.room_name);
         let mut structure_data = structure_data_rc.maybe_access(
-            |d| game::time().saturating_sub(d.last_updated) >= 10 && has_visibility,
+            |d| d.needs_rebuild(room_data),
             || create_structure_data(room_data, Some(pathfinder)),
         );
 
@@ -178,7 +208,9 @@ impl MineralMiningMission {
             return Ok(());
         }
 
-        let structure_data = structure_data.get().ok_or("Expected structure data")?;
+        let structure_data = structure_data
+            .get()
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected structure data"))?;
 
         // Check if mineral has resources.
         if let Some(mineral) = self.mineral.resolve() {
@@ -210,57 +242,23 @@ impl MineralMiningMission {
             })
             .into_group_map();
 
-        let mineral_miners: Vec<_> = container_ids
-            .iter()
-            .filter_map(|container| containers_to_miners.get(container))
-            .flat_map(|m| m.iter())
-            .collect();
-
-        // Compute replacement lead time.
-        let mineral_replacement_lead = self
-            .home_room_datas
-            .iter()
-            .filter_map(|home_room_entity| {
-                let home_room_data = system_data.room_data.get(*home_room_entity)?;
-                let home_room = game::rooms().get(home_room_data.name)?;
-                let is_local = self.mineral.pos().room_name() == home_room_data.name;
-
-                let body_definition = mineral_miner_body(is_local, home_room.energy_capacity_available());
-                let body = crate::creep::spawning::create_body(&body_definition).ok()?;
-                Some(miner_lead_ticks(&body, self.mineral.pos(), structure_data))
-            })
-            .min()
-            .unwrap_or(MIN_REPLACEMENT_LEAD_TICKS)
-            .max(MIN_REPLACEMENT_LEAD_TICKS);
-
-        let alive_mineral_miners: Vec<_> = mineral_miners
-            .iter()
-            .filter(|entity| {
-                system_data.creep_spawning.get(***entity).is_some()
-                    || system_data
-                        .creep_owner
-                        .get(***entity)
-                        .and_then(|creep_owner| creep_owner.owner.resolve())
-                        .and_then(|creep| creep.ticks_to_live())
-                        .map(|count| count > mineral_replacement_lead)
-                        .unwrap_or(false)
-            })
-            .map(|entity| **entity)
-            .collect();
+        let seat_distance = structure_data.spawn_distance(self.mineral.pos());
 
-        let available_containers = container_ids.iter().filter(|container| {
-            containers_to_miners
-                .get(container)
-                .map(|miners| !miners.iter().any(|miner| alive_mineral_miners.contains(miner)))
-                .unwrap_or(true)
-        });
+        for container in container_ids {
+            // A held seat's miner is requested as its replacement.
+            let miners = containers_to_miners.get(container).map(|m| m.as_slice()).unwrap_or(&[]);
+            let seat = seat_ticks_to_live(&seat_holders(system_data.creep_owner, system_data.creep_spawning, miners));
 
-        for container in available_containers {
             let token = system_data.spawn_queue.token();
 
             for home_room_entity in self.home_room_datas.iter() {
-                let home_room_data = system_data.room_data.get(*home_room_entity).ok_or("Expected home room data")?;
-                let home_room = game::rooms().get(home_room_data.name).ok_or("Expected home room")?;
+                let home_room_data = system_data
+                    .room_data
+                    .get(*home_room_entity)
+                    .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected home room data"))?;
+                let home_room = game::rooms()
+                    .get(home_room_data.name)
+                    .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected home room"))?;
 
                 let is_local = container.pos().room_name() == home_room_data.name;
                 let body_definition = mineral_miner_body(is_local, home_room.energy_capacity_available());
@@ -272,7 +270,13 @@ impl MineralMiningMission {
                         SPAWN_PRIORITY_LOW,
                         Some(token),
                         Self::create_handle_container_miner_spawn(mission_entity, self.mineral, self.extractor, *container),
-                    );
+                    )
+                    .requested_by(mission_entity);
+
+                    let spawn_request = match seat {
+                        Some(ticks_to_live) => spawn_request.replacing(ticks_to_live, seat_distance),
+                        None => spawn_request,
+                    };
 
                     system_data.spawn_queue.request(*home_room_entity, spawn_request);
                 }
@@ -292,6 +296,8 @@ fn should_teardown(mineral_amount: Option<u32>, live_miner_count: usize) -> bool
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl Mission for MineralMiningMission {
+    impl_mission_pause!();
+
     fn get_owner(&self) -> &Option<Entity> {
         &self.owner
     }
@@ -321,7 +327,7 @@ impl Mission for MineralMiningMission {
         crate::visualization::SummaryContent::Text(format!("Mineral Mining ({})", self.container_miners.len()))
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, String> {
+    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, MissionError> {
         // Idle-suspend rather than linger forever (IBEX-048): once the
         // mineral is visibly exhausted and the last miner has died off, the
         // mission completes through the normal cleanup cascade. The parent's
@@ -332,6 +338,8 @@ impl Mission for MineralMiningMission {
             return Ok(MissionResult::Success);
         }
 
+        self.hand_off_seats(system_data);
+
         if self.allow_spawning {
             self.spawn_creeps(system_data, mission_entity)?;
         }
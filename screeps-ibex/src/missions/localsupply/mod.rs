@@ -1,6 +1,7 @@
 pub mod body_helpers;
 pub mod mineral_mining;
 pub mod room_transfer;
+pub mod seats;
 pub mod source_mining;
 pub mod structure_data;
 
@@ -10,6 +11,7 @@ use self::source_mining::*;
 use self::structure_data::*;
 use super::data::*;
 use super::missionsystem::*;
+use crate::gameview::*;
 use crate::remoteobjectid::*;
 use crate::room::visibilitysystem::*;
 use crate::serialize::*;
@@ -33,6 +35,7 @@ pub struct LocalSupplyMission {
     mineral_mining_missions: EntityVec<Entity>,
     transfer_mission: EntityOption<Entity>,
     allow_spawning: bool,
+    paused: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -49,6 +52,7 @@ where
     mineral_mining_missions: <EntityVec<Entity> as ConvertSaveload<MA>>::Data,
     transfer_mission: <EntityOption<Entity> as ConvertSaveload<MA>>::Data,
     allow_spawning: <bool as ConvertSaveload<MA>>::Data,
+    paused: <bool as ConvertSaveload<MA>>::Data,
 }
 
 impl<MA> ConvertSaveload<MA> for LocalSupplyMission
@@ -72,6 +76,7 @@ where
             mineral_mining_missions: ConvertSaveload::convert_into(&self.mineral_mining_missions, &mut ids)?,
             transfer_mission: ConvertSaveload::convert_into(&self.transfer_mission, &mut ids)?,
             allow_spawning: ConvertSaveload::convert_into(&self.allow_spawning, &mut ids)?,
+            paused: ConvertSaveload::convert_into(&self.paused, &mut ids)?,
         })
     }
 
@@ -87,6 +92,7 @@ where
             mineral_mining_missions: ConvertSaveload::convert_from(data.mineral_mining_missions, &mut ids)?,
             transfer_mission: ConvertSaveload::convert_from(data.transfer_mission, &mut ids)?,
             allow_spawning: ConvertSaveload::convert_from(data.allow_spawning, &mut ids)?,
+            paused: ConvertSaveload::convert_from(data.paused, &mut ids)?,
         })
     }
 }
@@ -113,6 +119,7 @@ impl LocalSupplyMission {
             mineral_mining_missions: EntityVec::new(),
             transfer_mission: None.into(),
             allow_spawning: true,
+            paused: false,
         }
     }
 
@@ -127,37 +134,28 @@ impl LocalSupplyMission {
     }
 
     /// Ensure child missions exist for all sources, minerals, and transfers.
-    fn ensure_children(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<(), String> {
+    fn ensure_children(
+        &mut self,
+        view: &dyn GameView,
+        system_data: &mut MissionExecutionSystemData,
+        mission_entity: Entity,
+    ) -> Result<(), MissionError> {
         // Collect the data we need from room_data, then drop the borrow so we
         // can later get mutable access to add missions.
         let (room_name, sources, mineral_extractor_pairs) = {
-            let room_data = system_data.room_data.get(self.room_data).ok_or("Expected room data")?;
+            let room_data = system_data
+                .room_data
+                .get(self.room_data)
+                .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
             let room_name = room_data.name;
 
-            let has_visibility = room_data.get_dynamic_visibility_data().map(|v| v.visible()).unwrap_or(false);
-
-            // Refresh structure data if stale.
-            {
-                // Hoisted &mut reborrow: the refresh closure carries the
-                // pathfinder for the pool-budgeted spawn-distance
-                // precompute (disjoint field borrows on system_data).
-                let pathfinder = &mut *system_data.pathfinder;
-                let structure_data_rc = system_data.supply_structure_cache.get_room(room_name);
-                let mut sd = structure_data_rc.maybe_access(
-                    |d| game::time().saturating_sub(d.last_updated) >= 10 && has_visibility,
-                    || create_structure_data(room_data, Some(pathfinder)),
-                );
-                let _ = sd.get();
-            }
-
             let static_visibility_data = match room_data.get_static_visibility_data() {
                 Some(svd) => svd,
                 None => {
-                    system_data.visibility.request(VisibilityRequest::new(
-                        room_data.name,
-                        VISIBILITY_PRIORITY_CRITICAL,
-                        VisibilityRequestFlags::ALL,
-                    ));
+                    system_data.visibility.request(
+                        VisibilityRequest::new(room_data.name, VISIBILITY_PRIORITY_CRITICAL, VisibilityRequestFlags::ALL)
+                            .categories(VisibilityCategoryFlags::STATIC),
+                    );
                     return Ok(());
                 }
             };
@@ -177,74 +175,43 @@ impl LocalSupplyMission {
         };
         // room_data borrow is now dropped.
 
+        let (missing_sources, missing_minerals) = self.missing_children(view, system_data.missions, &sources, mineral_extractor_pairs);
+
         // Ensure one SourceMiningMission per source.
-        for source_id in sources.iter() {
-            let already_exists = self.source_mining_missions.iter().any(|&mission_e| {
-                system_data
-                    .missions
-                    .get(mission_e)
-                    .as_mission_type::<SourceMiningMission>()
-                    .map(|m| *m.source() == *source_id)
-                    .unwrap_or(false)
-            });
-
-            if !already_exists {
-                let child_entity = SourceMiningMission::build(
-                    system_data.updater.create_entity(system_data.entities),
-                    Some(mission_entity),
-                    self.room_data,
-                    &self.home_room_datas,
-                    *source_id,
-                    room_name,
-                )
-                .build();
-
-                if let Some(room_data_mut) = system_data.room_data.get_mut(self.room_data) {
-                    room_data_mut.add_mission(child_entity);
-                }
-                self.source_mining_missions.push(child_entity);
+        for source_id in missing_sources {
+            let child_entity = SourceMiningMission::build(
+                system_data.updater.create_entity(system_data.entities),
+                Some(mission_entity),
+                self.room_data,
+                &self.home_room_datas,
+                source_id,
+                room_name,
+            )
+            .build();
+
+            if let Some(room_data_mut) = system_data.room_data.get_mut(self.room_data) {
+                room_data_mut.add_mission(child_entity);
             }
+            self.source_mining_missions.push(child_entity);
         }
 
         // Ensure one MineralMiningMission per mineral/extractor pair.
-        for (mineral_id, extractor_id) in mineral_extractor_pairs {
-            // The recreate half of idle-suspend/recreate (IBEX-048): a
-            // visibly exhausted mineral gets no mission until it regenerates.
-            // The pair key persists in the cached structure data while
-            // depleted, so without this gate the child torn down by
-            // MineralMiningMission's depletion check would be recreated the
-            // very next tick.
-            let depleted = mineral_id.resolve().map(|m| m.mineral_amount() == 0).unwrap_or(false);
-            if depleted {
-                continue;
-            }
+        for (mineral_id, extractor_id) in missing_minerals {
+            let child_entity = MineralMiningMission::build(
+                system_data.updater.create_entity(system_data.entities),
+                Some(mission_entity),
+                self.room_data,
+                &self.home_room_datas,
+                mineral_id,
+                extractor_id,
+                room_name,
+            )
+            .build();
 
-            let already_exists = self.mineral_mining_missions.iter().any(|&mission_e| {
-                system_data
-                    .missions
-                    .get(mission_e)
-                    .as_mission_type::<MineralMiningMission>()
-                    .map(|m| *m.mineral() == mineral_id && *m.extractor() == extractor_id)
-                    .unwrap_or(false)
-            });
-
-            if !already_exists {
-                let child_entity = MineralMiningMission::build(
-                    system_data.updater.create_entity(system_data.entities),
-                    Some(mission_entity),
-                    self.room_data,
-                    &self.home_room_datas,
-                    mineral_id,
-                    extractor_id,
-                    room_name,
-                )
-                .build();
-
-                if let Some(room_data_mut) = system_data.room_data.get_mut(self.room_data) {
-                    room_data_mut.add_mission(child_entity);
-                }
-                self.mineral_mining_missions.push(child_entity);
+            if let Some(room_data_mut) = system_data.room_data.get_mut(self.room_data) {
+                room_data_mut.add_mission(child_entity);
             }
+            self.mineral_mining_missions.push(child_entity);
         }
 
         // Ensure one RoomTransferMission.
@@ -266,6 +233,53 @@ impl LocalSupplyMission {
         Ok(())
     }
 
+    /// The sources and mineral/extractor pairs that have no child mission yet.
+    fn missing_children(
+        &self,
+        view: &dyn GameView,
+        missions: &WriteStorage<MissionData>,
+        sources: &[RemoteObjectId<Source>],
+        mineral_extractor_pairs: Vec<MineralExtractorPair>,
+    ) -> (Vec<RemoteObjectId<Source>>, Vec<MineralExtractorPair>) {
+        let missing_sources = sources
+            .iter()
+            .filter(|source_id| {
+                !self.source_mining_missions.iter().any(|&mission_e| {
+                    missions
+                        .get(mission_e)
+                        .as_mission_type::<SourceMiningMission>()
+                        .map(|m| m.source() == *source_id)
+                        .unwrap_or(false)
+                })
+            })
+            .cloned()
+            .collect();
+
+        let missing_minerals = mineral_extractor_pairs
+            .into_iter()
+            .filter(|(mineral_id, extractor_id)| {
+                // The recreate half of idle-suspend/recreate (IBEX-048): a
+                // visibly exhausted mineral gets no mission until it regenerates.
+                // The pair key persists in the cached structure data while
+                // depleted, so without this gate the child torn down by
+                // MineralMiningMission's depletion check would be recreated the
+                // very next tick.
+                let depleted = view.mineral_amount(mineral_id.id()) == Some(0);
+
+                !depleted
+                    && !self.mineral_mining_missions.iter().any(|&mission_e| {
+                        missions
+                            .get(mission_e)
+                            .as_mission_type::<MineralMiningMission>()
+                            .map(|m| m.mineral() == mineral_id && m.extractor() == extractor_id)
+                            .unwrap_or(false)
+                    })
+            })
+            .collect();
+
+        (missing_sources, missing_minerals)
+    }
+
     /// Push `home_room_datas` and `allow_spawning` down to child missions.
     fn update_children(&self, system_data: &mut MissionExecutionSystemData) {
         for &child_entity in self.source_mining_missions.iter() {
@@ -286,6 +300,8 @@ impl LocalSupplyMission {
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl Mission for LocalSupplyMission {
+    impl_mission_pause!();
+
     fn get_owner(&self) -> &Option<Entity> {
         &self.owner
     }
@@ -337,14 +353,81 @@ impl Mission for LocalSupplyMission {
         }
     }
 
-    fn pre_run_mission(&mut self, _system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), String> {
+    /// Refresh the room's structure data if stale. Owners pre-run before the missions they own (see
+    /// `MissionOrder`), so the room transfer mission's generators and the miners read this tick's data,
+    /// built with the mission pathfinder budget rather than the generators' plain cap.
+    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), MissionError> {
+        let room_data = system_data
+            .room_data
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
+
+        // Hoisted &mut reborrow: the refresh closure carries the
+        // pathfinder for the pool-budgeted spawn-distance
+        // precompute (disjoint field borrows on system_data).
+        let pathfinder = &mut *system_data.pathfinder;
+        let structure_data_rc = system_data.supply_structure_cache.get_room(room_data.name);
+        let mut sd = structure_data_rc.maybe_access(
+            |d| d.needs_rebuild(room_data),
+            || create_structure_data(room_data, Some(pathfinder)),
+        );
+        let _ = sd.get();
+
         Ok(())
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, String> {
-        self.ensure_children(system_data, mission_entity)?;
+    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, MissionError> {
+        self.ensure_children(&LiveGame, system_data, mission_entity)?;
         self.update_children(system_data);
 
         Ok(MissionResult::Running)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+
+    #[test]
+    fn missing_children_skips_existing_missions_and_depleted_minerals() {
+        let mut test_world = TestWorld::new();
+        let room_entity = test_world.room("W1N1");
+        let room_name: RoomName = "W1N1".parse().unwrap();
+
+        let source = |n: u32, x: u8| RemoteObjectId::<Source>::new_from_components(object_id(n), position(room_name, x, 10));
+        let (mined, unmined) = (source(1, 10), source(2, 40));
+
+        let pair = |n: u32, x: u8| {
+            (
+                RemoteObjectId::<Mineral>::new_from_components(object_id(n), position(room_name, x, 30)),
+                RemoteObjectId::<StructureExtractor>::new_from_components(object_id(n + 100), position(room_name, x, 30)),
+            )
+        };
+        let (regenerated, depleted, unseen) = (pair(3, 10), pair(4, 20), pair(5, 30));
+
+        let existing = SourceMiningMission::build(
+            test_world.world.create_entity(),
+            None,
+            room_entity,
+            &[room_entity],
+            mined,
+            room_name,
+        )
+        .build();
+
+        let mut mission = LocalSupplyMission::new(None, room_entity, &[room_entity]);
+        mission.source_mining_missions.push(existing);
+
+        let mut view = MockGame::at(1_000);
+        view.minerals.insert(regenerated.0.id(), 35_000);
+        view.minerals.insert(depleted.0.id(), 0);
+
+        let missions = test_world.world.write_storage::<MissionData>();
+        let (sources, minerals) = mission.missing_children(&view, &missions, &[mined, unmined], vec![regenerated, depleted, unseen]);
+
+        assert_eq!(sources, vec![unmined]);
+        // An unseen mineral is unknown, never depleted.
+        assert_eq!(minerals, vec![regenerated, unseen]);
+    }
+}
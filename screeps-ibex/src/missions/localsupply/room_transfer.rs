@@ -1,8 +1,16 @@
+use super::source_mining::SourceMiningMission;
 use super::structure_data::*;
+use crate::features::ConsolidationFeatures;
+use crate::intents::IntentCategory;
+use crate::ledger::LedgerCategory;
 use crate::missions::data::*;
+use crate::military::threatmap::RoomThreatData;
 use crate::missions::missionsystem::*;
+use crate::missions::terminal::*;
+use crate::powercreepsystem::*;
 use crate::remoteobjectid::*;
 use crate::serialize::*;
+use crate::structureidentifier::RemoteStructureIdentifier;
 use crate::transfer::transfersystem::*;
 use screeps::*;
 use screeps_cache::*;
@@ -12,6 +20,7 @@ use specs::error::NoError;
 use specs::saveload::*;
 use specs::*;
 use std::cell::*;
+use std::collections::HashSet;
 use std::rc::*;
 
 /// The controller link's active-priority intake is gated to a horizon of this
@@ -92,10 +101,25 @@ fn controller_link_deposit(
     Some((priority, deficit))
 }
 
+/// Haul priority for topping up a controller container `fill_fraction` full.
+/// With a controller link feeding the upgraders the container is only a spare
+/// buffer and takes surplus; without one it is their only feed, so its
+/// deliveries escalate as it drains.
+fn controller_container_deposit_priority(fed_by_link: bool, fill_fraction: f32) -> TransferPriority {
+    if fed_by_link || fill_fraction >= 0.75 {
+        TransferPriority::None
+    } else if fill_fraction < 0.25 {
+        TransferPriority::Medium
+    } else {
+        TransferPriority::Low
+    }
+}
+
 pub struct RoomTransferMission {
     owner: EntityOption<Entity>,
     room_data: Entity,
     room_name: RoomName,
+    paused: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -108,6 +132,7 @@ where
     owner: <EntityOption<Entity> as ConvertSaveload<MA>>::Data,
     room_data: <Entity as ConvertSaveload<MA>>::Data,
     room_name: <RoomName as ConvertSaveload<MA>>::Data,
+    paused: <bool as ConvertSaveload<MA>>::Data,
 }
 
 impl<MA> ConvertSaveload<MA> for RoomTransferMission
@@ -127,6 +152,7 @@ where
             owner: ConvertSaveload::convert_into(&self.owner, &mut ids)?,
             room_data: ConvertSaveload::convert_into(&self.room_data, &mut ids)?,
             room_name: ConvertSaveload::convert_into(&self.room_name, &mut ids)?,
+            paused: ConvertSaveload::convert_into(&self.paused, &mut ids)?,
         })
     }
 
@@ -138,6 +164,7 @@ where
             owner: ConvertSaveload::convert_from(data.owner, &mut ids)?,
             room_data: ConvertSaveload::convert_from(data.room_data, &mut ids)?,
             room_name: ConvertSaveload::convert_from(data.room_name, &mut ids)?,
+            paused: ConvertSaveload::convert_from(data.paused, &mut ids)?,
         })
     }
 }
@@ -152,6 +179,7 @@ impl RoomTransferMission {
             owner: owner.into(),
             room_data,
             room_name,
+            paused: false,
         };
 
         builder
@@ -159,17 +187,21 @@ impl RoomTransferMission {
             .marked::<SerializeMarker>()
     }
 
-    fn get_all_links(&mut self, system_data: &mut MissionExecutionSystemData) -> Result<Vec<RemoteObjectId<StructureLink>>, String> {
-        let room_data = system_data.room_data.get(self.room_data).ok_or("Expected room data")?;
-        let has_visibility = room_data.get_dynamic_visibility_data().map(|v| v.visible()).unwrap_or(false);
+    fn get_all_links(&mut self, system_data: &mut MissionExecutionSystemData) -> Result<Vec<RemoteObjectId<StructureLink>>, MissionError> {
+        let room_data = system_data
+            .room_data
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
 
         let pathfinder = &mut *system_data.pathfinder;
         let structure_data_rc = system_data.supply_structure_cache.get_room(self.room_name);
         let mut structure_data = structure_data_rc.maybe_access(
-            |d| game::time().saturating_sub(d.last_updated) >= 10 && has_visibility,
+            |d| d.needs_rebuild(room_data),
             || create_structure_data(room_data, Some(pathfinder)),
         );
-        let structure_data = structure_data.get().ok_or("Expected structure data")?;
+        let structure_data = structure_data
+            .get()
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected structure data"))?;
 
         let all_links = structure_data
             .sources_to_links
@@ -182,7 +214,42 @@ impl RoomTransferMission {
         Ok(all_links)
     }
 
-    fn link_transfer(&mut self, system_data: &mut MissionExecutionSystemData) -> Result<(), String> {
+    /// Ask the room's operator to refill the extensions from storage once they
+    /// are half empty and storage holds a full refill.
+    fn request_extension_power(&self, system_data: &mut MissionExecutionSystemData) {
+        let Some(room) = game::rooms().get(self.room_name) else {
+            return;
+        };
+
+        let capacity = room.energy_capacity_available();
+
+        if room.energy_available() * 2 >= capacity {
+            return;
+        }
+
+        let storage = system_data
+            .room_data
+            .get(self.room_data)
+            .and_then(|room_data| room_data.get_structures())
+            .and_then(|structures| structures.storages().iter().find(|s| s.my()).cloned());
+
+        let Some(storage) = storage else {
+            return;
+        };
+
+        if storage.store().get_used_capacity(Some(ResourceType::Energy)) < capacity {
+            return;
+        }
+
+        system_data.power_requests.request(PowerRequest::new(
+            self.room_name,
+            PowerType::OperateExtension,
+            RemoteStructureIdentifier::new(&StructureObject::from(storage)),
+            PowerPriority::Medium,
+        ));
+    }
+
+    fn link_transfer(&mut self, system_data: &mut MissionExecutionSystemData) -> Result<(), MissionError> {
         if let Ok(all_links) = self.get_all_links(system_data) {
             let transfer_queue = &mut system_data.transfer_queue;
 
@@ -226,7 +293,13 @@ impl RoomTransferMission {
                                 .map(|entries| entries.iter().map(|entry| entry.amount()).sum())
                                 .unwrap_or(0);
 
-                            let _ = delivery.target().link_transfer_energy_amount(&link, transfer_amount);
+                            let sent = delivery.target().link_transfer_energy_amount(&link, transfer_amount);
+
+                            if system_data.intent_recorder.issued(IntentCategory::Transfer, sent).is_ok() {
+                                // LINK_LOSS_RATIO (3%) of every link send is lost in transit.
+                                let loss = (transfer_amount as f64 * LINK_LOSS_RATIO).ceil() as u32;
+                                system_data.ledger.add(room_name, LedgerCategory::LinkLoss, loss);
+                            }
                         }
                     }
                 }
@@ -236,15 +309,20 @@ impl RoomTransferMission {
         Ok(())
     }
 
-    fn transfer_request_haul_generator(room_entity: Entity, structure_data: Rc<RefCell<Option<StructureData>>>) -> TransferQueueGenerator {
+    fn transfer_request_haul_generator(
+        room_entity: Entity,
+        structure_data: Rc<RefCell<Option<StructureData>>>,
+        hostile_towers: Vec<Position>,
+        consolidation: ConsolidationFeatures,
+        overflowing_containers: Vec<RemoteObjectId<StructureContainer>>,
+    ) -> TransferQueueGenerator {
         Box::new(move |system, transfer, _room_name| {
             let room_data = system.get_room_data(room_entity).ok_or("Expected room data")?;
-            let has_visibility = room_data.get_dynamic_visibility_data().map(|v| v.visible()).unwrap_or(false);
 
             // Boxed generator, flushed lazily — no &mut service handle can
             // ride here; None = plain per-search cap (see create_structure_data).
             let mut structure_data = structure_data.maybe_access(
-                |d| game::time().saturating_sub(d.last_updated) >= 10 && has_visibility,
+                |d| d.needs_rebuild(room_data),
                 || create_structure_data(room_data, None),
             );
             let Some(structure_data) = structure_data.get() else {
@@ -253,13 +331,16 @@ impl RoomTransferMission {
 
             Self::request_transfer_for_spawns(transfer, &structure_data.spawns);
             Self::request_transfer_for_extension(transfer, &structure_data.extensions);
-            Self::request_transfer_for_storage(transfer, &structure_data.storage);
-            Self::request_transfer_for_containers(transfer, structure_data);
+            let room = game::rooms().get(room_data.name);
+            let spawn_energy = room.as_ref().map(|room| room.energy_capacity_available()).unwrap_or(0);
+
+            Self::request_transfer_for_storage(transfer, &structure_data.storage, spawn_energy);
+            Self::request_transfer_for_containers(transfer, structure_data, &overflowing_containers);
 
-            if let Some(room) = game::rooms().get(room_data.name) {
-                Self::request_transfer_for_ruins(transfer, &room);
-                Self::request_transfer_for_tombstones(transfer, &room);
-                Self::request_transfer_for_dropped_resources(transfer, &room);
+            if let Some(room) = room {
+                request_transfer_for_loot(transfer, &room, &hostile_towers);
+                crate::transfer::special::request_transfers(transfer, &room, &hostile_towers);
+                Self::request_transfer_for_terminal(transfer, &room, &consolidation);
             }
 
             Ok(())
@@ -269,11 +350,10 @@ impl RoomTransferMission {
     fn transfer_request_link_generator(room_entity: Entity, structure_data: Rc<RefCell<Option<StructureData>>>) -> TransferQueueGenerator {
         Box::new(move |system, transfer, _room_name| {
             let room_data = system.get_room_data(room_entity).ok_or("Expected room data")?;
-            let has_visibility = room_data.get_dynamic_visibility_data().map(|v| v.visible()).unwrap_or(false);
 
             // Boxed generator, flushed lazily — None = plain per-search cap.
             let mut structure_data = structure_data.maybe_access(
-                |d| game::time().saturating_sub(d.last_updated) >= 10 && has_visibility,
+                |d| d.needs_rebuild(room_data),
                 || create_structure_data(room_data, None),
             );
             let Some(structure_data) = structure_data.get() else {
@@ -300,7 +380,14 @@ impl RoomTransferMission {
         })
     }
 
-    fn request_transfer_for_containers(transfer: &mut dyn TransferRequestSystem, structure_data: &StructureData) {
+    /// Withdraw from the source and mineral containers at a priority rising
+    /// with their fill, High for the source containers the haulers have
+    /// fallen behind on.
+    fn request_transfer_for_containers(
+        transfer: &mut dyn TransferRequestSystem,
+        structure_data: &StructureData,
+        overflowing_containers: &[RemoteObjectId<StructureContainer>],
+    ) {
         let provider_containers = structure_data
             .sources_to_containers
             .values()
@@ -314,7 +401,9 @@ impl RoomTransferMission {
                         let container_store_capacity = container.store().get_capacity(None);
 
                         let storage_fraction = (container_used_capacity as f32) / (container_store_capacity as f32);
-                        let priority = if storage_fraction > 0.75 {
+                        let priority = if overflowing_containers.contains(container_id) {
+                            TransferPriority::High
+                        } else if storage_fraction > 0.75 {
                             TransferPriority::Medium
                         } else if storage_fraction > 0.5 {
                             TransferPriority::Low
@@ -339,6 +428,8 @@ impl RoomTransferMission {
             }
         }
 
+        let fed_by_link = !structure_data.controller_links.is_empty();
+
         for containers in structure_data.controllers_to_containers.values() {
             for container_id in containers {
                 if let Some(container) = container_id.resolve() {
@@ -349,11 +440,7 @@ impl RoomTransferMission {
                     let storage_fraction = container_used_capacity as f32 / container_available_capacity as f32;
 
                     if container_free_capacity > 0 {
-                        let priority = if storage_fraction < 0.75 {
-                            TransferPriority::Low
-                        } else {
-                            TransferPriority::None
-                        };
+                        let priority = controller_container_deposit_priority(fed_by_link, storage_fraction);
 
                         let transfer_request = TransferDepositRequest::new(
                             TransferTarget::Container(*container_id),
@@ -461,9 +548,19 @@ impl RoomTransferMission {
         }
     }
 
-    fn request_transfer_for_storage(transfer: &mut dyn TransferRequestSystem, stores: &[RemoteObjectId<StructureStorage>]) {
+    /// Offer the storage's contents and free space, keeping `spawn_energy`
+    /// back for refilling the spawns and extensions.
+    fn request_transfer_for_storage(
+        transfer: &mut dyn TransferRequestSystem,
+        stores: &[RemoteObjectId<StructureStorage>],
+        spawn_energy: u32,
+    ) {
         for storage_id in stores.iter() {
             if let Some(storage) = storage_id.resolve() {
+                if spawn_energy > 0 {
+                    transfer.reserve(TransferTarget::Storage(*storage_id), ResourceType::Energy, spawn_energy);
+                }
+
                 let mut used_capacity = 0;
 
                 for resource in storage.store().store_types() {
@@ -498,6 +595,54 @@ impl RoomTransferMission {
         }
     }
 
+    /// Keep each resource the room holds within its [`TerminalBand`], hauling
+    /// between storage and terminal only once it has drifted past the band's
+    /// hysteresis.
+    fn request_transfer_for_terminal(transfer: &mut dyn TransferRequestSystem, room: &Room, consolidation: &ConsolidationFeatures) {
+        let (Some(storage), Some(terminal)) = (room.storage().filter(|s| s.my()), room.terminal().filter(|t| t.my())) else {
+            return;
+        };
+
+        let resources: HashSet<ResourceType> = storage
+            .store()
+            .store_types()
+            .into_iter()
+            .chain(terminal.store().store_types())
+            .collect();
+
+        for resource in resources {
+            let storage_amount = storage.store().get_used_capacity(Some(resource));
+            let terminal_amount = terminal.store().get_used_capacity(Some(resource));
+            let band = TerminalMission::get_terminal_band(resource, room.name(), consolidation);
+
+            match band.transfer(terminal_amount, storage_amount) {
+                Some(BandTransfer::ToTerminal(amount, priority)) => {
+                    let amount = amount.min(terminal.store().get_free_capacity(None).max(0) as u32);
+
+                    if amount > 0 {
+                        transfer.request_deposit(TransferDepositRequest::new(
+                            TransferTarget::Terminal(terminal.remote_id()),
+                            Some(resource),
+                            priority,
+                            amount,
+                            TransferType::Haul,
+                        ));
+                    }
+                }
+                Some(BandTransfer::ToStorage(amount, priority)) => {
+                    transfer.request_withdraw(TransferWithdrawRequest::new(
+                        TransferTarget::Terminal(terminal.remote_id()),
+                        resource,
+                        priority,
+                        amount,
+                        TransferType::Haul,
+                    ));
+                }
+                None => {}
+            }
+        }
+    }
+
     fn request_transfer_for_storage_links(transfer: &mut dyn TransferRequestSystem, structure_data: &StructureData) {
         for link_id in &structure_data.storage_links {
             if let Some(link) = link_id.resolve() {
@@ -614,40 +759,98 @@ impl RoomTransferMission {
             }
         }
     }
+}
 
-    fn request_transfer_for_ruins(transfer: &mut dyn TransferRequestSystem, room: &Room) {
-        for ruin in room.find(find::RUINS, None) {
-            let ruin_id = ruin.remote_id();
+/// Tombstones and ruins holding less energy than this aren't worth a hauler's trip (minerals always are).
+const LOOT_MIN_ENERGY: u32 = 50;
 
-            for resource in ruin.store().store_types() {
-                let resource_amount = ruin.store().get_used_capacity(Some(resource));
-                let transfer_request = TransferWithdrawRequest::new(
-                    TransferTarget::Ruin(ruin_id),
-                    resource,
-                    TransferPriority::Medium,
-                    resource_amount,
-                    TransferType::Haul,
-                );
+/// Loot this close to decaying is collected at `High` before it vanishes.
+const LOOT_EXPIRING_TICKS: u32 = 50;
+
+/// Loot this close to decaying is raised one priority step.
+const LOOT_DECAYING_TICKS: u32 = 150;
+
+/// An energy pile this large is worth a priority step on its own, as are minerals.
+const LOOT_LARGE_AMOUNT: u32 = 200;
+
+/// Withdraw priority for `amount` of `resource` held by a tombstone or ruin that decays in
+/// `ticks_to_decay`, or `None` when it's below the minimum worth collecting. Rises as decay nears, and a
+/// step sooner for minerals or a large pile.
+fn loot_priority(resource: ResourceType, amount: u32, ticks_to_decay: u32) -> Option<TransferPriority> {
+    let valuable = resource != ResourceType::Energy || amount >= LOOT_LARGE_AMOUNT;
+
+    if amount == 0 || (resource == ResourceType::Energy && amount < LOOT_MIN_ENERGY) {
+        None
+    } else if ticks_to_decay <= LOOT_EXPIRING_TICKS || (valuable && ticks_to_decay <= LOOT_DECAYING_TICKS) {
+        Some(TransferPriority::High)
+    } else if valuable || ticks_to_decay <= LOOT_DECAYING_TICKS {
+        Some(TransferPriority::Medium)
+    } else {
+        Some(TransferPriority::Low)
+    }
+}
+
+/// Positions of the energized hostile towers the threat map knows of in a room. Loot in their falloff
+/// range is left alone: a hauler sent for it dies for the pile.
+pub fn hostile_tower_cover(threat: Option<&RoomThreatData>) -> Vec<Position> {
+    threat
+        .map(|threat| {
+            threat
+                .hostile_tower_positions
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| threat.tower_energy.get(*index).is_none_or(|energy| *energy >= TOWER_ENERGY_COST))
+                .map(|(_, pos)| *pos)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn is_under_hostile_towers(pos: Position, hostile_towers: &[Position]) -> bool {
+    hostile_towers.iter().any(|tower| tower.get_range_to(pos) <= TOWER_FALLOFF_RANGE as u32)
+}
+
+/// Request withdrawls for the ruins, tombstones and dropped resources in `room`, skipping anything
+/// covered by `hostile_towers` (see [`hostile_tower_cover`]).
+pub fn request_transfer_for_loot(transfer: &mut dyn TransferRequestSystem, room: &Room, hostile_towers: &[Position]) {
+    request_transfer_for_ruins(transfer, room, hostile_towers);
+    request_transfer_for_tombstones(transfer, room, hostile_towers);
+    request_transfer_for_dropped_resources(transfer, room, hostile_towers);
+}
+
+fn request_transfer_for_ruins(transfer: &mut dyn TransferRequestSystem, room: &Room, hostile_towers: &[Position]) {
+    for ruin in room.find(find::RUINS, None) {
+        if is_under_hostile_towers(ruin.pos(), hostile_towers) {
+            continue;
+        }
+
+        let ruin_id = ruin.remote_id();
+
+        for resource in ruin.store().store_types() {
+            let resource_amount = ruin.store().get_used_capacity(Some(resource));
+
+            if let Some(priority) = loot_priority(resource, resource_amount, ruin.ticks_to_decay()) {
+                let transfer_request =
+                    TransferWithdrawRequest::new(TransferTarget::Ruin(ruin_id), resource, priority, resource_amount, TransferType::Haul);
 
                 transfer.request_withdraw(transfer_request);
             }
         }
     }
+}
 
-    fn request_transfer_for_tombstones(transfer: &mut dyn TransferRequestSystem, room: &Room) {
-        for tombstone in room.find(find::TOMBSTONES, None) {
-            let tombstone_id = tombstone.remote_id();
+fn request_transfer_for_tombstones(transfer: &mut dyn TransferRequestSystem, room: &Room, hostile_towers: &[Position]) {
+    for tombstone in room.find(find::TOMBSTONES, None) {
+        if is_under_hostile_towers(tombstone.pos(), hostile_towers) {
+            continue;
+        }
 
-            for resource in tombstone.store().store_types() {
-                let resource_amount = tombstone.store().get_used_capacity(Some(resource));
+        let tombstone_id = tombstone.remote_id();
 
-                //TODO: Only apply this if no hostiles in the room?
-                let priority = if resource_amount > 200 || resource != ResourceType::Energy {
-                    TransferPriority::High
-                } else {
-                    TransferPriority::Medium
-                };
+        for resource in tombstone.store().store_types() {
+            let resource_amount = tombstone.store().get_used_capacity(Some(resource));
 
+            if let Some(priority) = loot_priority(resource, resource_amount, tombstone.ticks_to_decay()) {
                 let transfer_request = TransferWithdrawRequest::new(
                     TransferTarget::Tombstone(tombstone_id),
                     resource,
@@ -660,36 +863,41 @@ impl RoomTransferMission {
             }
         }
     }
+}
 
-    fn request_transfer_for_dropped_resources(transfer: &mut dyn TransferRequestSystem, room: &Room) {
-        for dropped_resource in room.find(find::DROPPED_RESOURCES, None) {
-            let dropped_resource_id = dropped_resource.remote_id();
+fn request_transfer_for_dropped_resources(transfer: &mut dyn TransferRequestSystem, room: &Room, hostile_towers: &[Position]) {
+    for dropped_resource in room.find(find::DROPPED_RESOURCES, None) {
+        if is_under_hostile_towers(dropped_resource.pos(), hostile_towers) {
+            continue;
+        }
 
-            let resource = dropped_resource.resource_type();
-            let resource_amount = dropped_resource.amount();
+        let dropped_resource_id = dropped_resource.remote_id();
 
-            //TODO: Only apply this if no hostiles in the room?
-            let priority = if resource_amount > 500 || resource != ResourceType::Energy {
-                TransferPriority::High
-            } else {
-                TransferPriority::Medium
-            };
+        let resource = dropped_resource.resource_type();
+        let resource_amount = dropped_resource.amount();
 
-            let transfer_request = TransferWithdrawRequest::new(
-                TransferTarget::Resource(dropped_resource_id),
-                resource,
-                priority,
-                resource_amount,
-                TransferType::Haul,
-            );
+        let priority = if resource_amount > 500 || resource != ResourceType::Energy {
+            TransferPriority::High
+        } else {
+            TransferPriority::Medium
+        };
 
-            transfer.request_withdraw(transfer_request);
-        }
+        let transfer_request = TransferWithdrawRequest::new(
+            TransferTarget::Resource(dropped_resource_id),
+            resource,
+            priority,
+            resource_amount,
+            TransferType::Haul,
+        );
+
+        transfer.request_withdraw(transfer_request);
     }
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl Mission for RoomTransferMission {
+    impl_mission_pause!();
+
     fn get_owner(&self) -> &Option<Entity> {
         &self.owner
     }
@@ -711,13 +919,28 @@ impl Mission for RoomTransferMission {
         crate::visualization::SummaryContent::Text("Room Transfer".to_string())
     }
 
-    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), String> {
+    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), MissionError> {
         let structure_data_rc = system_data.supply_structure_cache.get_room(self.room_name);
 
+        let overflowing_containers: Vec<_> = system_data
+            .room_data
+            .get(self.room_data)
+            .into_iter()
+            .flat_map(|room_data| room_data.get_missions().iter())
+            .filter_map(|mission| system_data.missions.get(*mission).as_mission_type::<SourceMiningMission>())
+            .flat_map(|source_mining| source_mining.overflowing_containers().collect::<Vec<_>>())
+            .collect();
+
         system_data.transfer_queue.register_generator(
             self.room_name,
             TransferTypeFlags::HAUL | TransferTypeFlags::USE,
-            Self::transfer_request_haul_generator(self.room_data, structure_data_rc.clone()),
+            Self::transfer_request_haul_generator(
+                self.room_data,
+                structure_data_rc.clone(),
+                hostile_tower_cover(system_data.threat_data.get(self.room_data)),
+                system_data.consolidation.clone(),
+                overflowing_containers,
+            ),
         );
 
         system_data.transfer_queue.register_generator(
@@ -729,8 +952,13 @@ impl Mission for RoomTransferMission {
         Ok(())
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<MissionResult, String> {
+    fn run_mission(
+        &mut self,
+        system_data: &mut MissionExecutionSystemData,
+        _mission_entity: Entity,
+    ) -> Result<MissionResult, MissionError> {
         self.link_transfer(system_data)?;
+        self.request_extension_power(system_data);
 
         Ok(MissionResult::Running)
     }
@@ -804,4 +1032,43 @@ mod tests {
         // Same defer behaviour at max RCL: used 400 of the 450 buffer -> None.
         assert_eq!(controller_link_deposit(800, 400, 400, MAX_LEVEL_DRAIN), Some((TransferPriority::None, 50)));
     }
+
+    // A controller container is the upgraders' fallback feed: hauled to
+    // urgently only when no controller link exists.
+    #[test]
+    fn controller_container_is_only_surplus_behind_a_link() {
+        assert_eq!(controller_container_deposit_priority(true, 0.0), TransferPriority::None);
+        assert_eq!(controller_container_deposit_priority(false, 0.1), TransferPriority::Medium);
+        assert_eq!(controller_container_deposit_priority(false, 0.5), TransferPriority::Low);
+        assert_eq!(controller_container_deposit_priority(false, 0.9), TransferPriority::None);
+    }
+
+    /// Loot below the minimum is skipped; the rest climbs to High as decay nears, minerals and big
+    /// piles a step early.
+    #[test]
+    fn loot_priority_rises_as_decay_nears() {
+        assert_eq!(loot_priority(ResourceType::Energy, LOOT_MIN_ENERGY - 1, 10), None);
+        assert_eq!(loot_priority(ResourceType::Energy, 100, 400), Some(TransferPriority::Low));
+        assert_eq!(loot_priority(ResourceType::Energy, 100, 100), Some(TransferPriority::Medium));
+        assert_eq!(loot_priority(ResourceType::Energy, 100, 20), Some(TransferPriority::High));
+        assert_eq!(loot_priority(ResourceType::Energy, 500, 400), Some(TransferPriority::Medium));
+        assert_eq!(loot_priority(ResourceType::Hydrogen, 5, 100), Some(TransferPriority::High));
+    }
+
+    /// Only energized towers cover loot, and only within their falloff range.
+    #[test]
+    fn drained_and_distant_towers_leave_loot_alone() {
+        let pos = |x: u8, y: u8| Position::new(RoomCoordinate::new(x).unwrap(), RoomCoordinate::new(y).unwrap(), "W1N1".parse().unwrap());
+        let threat = RoomThreatData {
+            hostile_tower_positions: vec![pos(10, 10), pos(40, 40)],
+            tower_energy: vec![0, TOWER_ENERGY_COST],
+            ..Default::default()
+        };
+
+        let towers = hostile_tower_cover(Some(&threat));
+        assert_eq!(towers, vec![pos(40, 40)]);
+        assert!(is_under_hostile_towers(pos(30, 30), &towers));
+        assert!(!is_under_hostile_towers(pos(12, 12), &towers));
+        assert!(hostile_tower_cover(None).is_empty());
+    }
 }
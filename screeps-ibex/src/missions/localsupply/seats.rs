@@ -0,0 +1,101 @@
+//! Seated miners (on a container or beside a link) and their replacements.
+//!
+//! A seat's replacement is requested as soon as the seat is held, as a
+//! [`SpawnRequest::replacing`](crate::spawnsystem::SpawnRequest::replacing)
+//! the holder; the spawn queue holds it back until it is due, so the new
+//! miner arrives as the old one dies. Once the replacement stands next to
+//! the old miner, the old one is released to recycle so the two never fight
+//! over the tile.
+
+use crate::creep::{CreepOwner, CreepSpawning};
+use screeps::*;
+use specs::*;
+
+/// A creep on a seat: how long it has left and where it stands. A creep
+/// still spawning has its whole life ahead of it and no position yet.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SeatHolder {
+    pub entity: Entity,
+    pub ticks_to_live: u32,
+    pub pos: Option<Position>,
+}
+
+/// The live creeps among `creeps`, all assigned to the same seat.
+pub fn seat_holders(
+    creep_owner: &ReadStorage<CreepOwner>,
+    creep_spawning: &ReadStorage<CreepSpawning>,
+    creeps: &[Entity],
+) -> Vec<SeatHolder> {
+    creeps
+        .iter()
+        .filter_map(|entity| {
+            if creep_spawning.get(*entity).is_some() {
+                return Some(SeatHolder {
+                    entity: *entity,
+                    ticks_to_live: CREEP_LIFE_TIME,
+                    pos: None,
+                });
+            }
+
+            let creep = creep_owner.get(*entity)?.owner.resolve()?;
+
+            Some(SeatHolder {
+                entity: *entity,
+                ticks_to_live: creep.ticks_to_live().unwrap_or(CREEP_LIFE_TIME),
+                pos: Some(creep.pos()),
+            })
+        })
+        .collect()
+}
+
+/// How long the seat stays held: its longest-lived holder's life. `None`
+/// for an empty seat.
+pub fn seat_ticks_to_live(holders: &[SeatHolder]) -> Option<u32> {
+    holders.iter().map(|h| h.ticks_to_live).max()
+}
+
+/// The holder to release now its replacement, the seat's longest-lived
+/// holder, has arrived next to it.
+pub fn outgoing_holder(holders: &[SeatHolder]) -> Option<Entity> {
+    let replacement = holders.iter().max_by_key(|h| h.ticks_to_live)?;
+    let arrived_at = replacement.pos?;
+
+    holders
+        .iter()
+        .filter(|h| h.entity != replacement.entity)
+        .find(|h| h.pos.is_some_and(|pos| pos.is_near_to(arrived_at)))
+        .map(|h| h.entity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::position;
+
+    #[test]
+    fn old_miner_steps_aside_only_once_its_replacement_is_adjacent() {
+        let mut world = World::new();
+        let (old, new) = (world.create_entity().build(), world.create_entity().build());
+        let room: RoomName = "W1N1".parse().expect("valid room name");
+
+        let holder = |entity, ticks_to_live, pos| SeatHolder {
+            entity,
+            ticks_to_live,
+            pos,
+        };
+        let seated = holder(old, 12, Some(position(room, 10, 10)));
+
+        let spawning = [seated, holder(new, CREEP_LIFE_TIME, None)];
+        assert_eq!(seat_ticks_to_live(&spawning), Some(CREEP_LIFE_TIME));
+        assert_eq!(outgoing_holder(&spawning), None);
+
+        let walking = [seated, holder(new, 1_480, Some(position(room, 12, 10)))];
+        assert_eq!(outgoing_holder(&walking), None);
+
+        let arrived = [seated, holder(new, 1_479, Some(position(room, 11, 11)))];
+        assert_eq!(outgoing_holder(&arrived), Some(old));
+
+        assert_eq!(outgoing_holder(&[seated]), None);
+        assert_eq!(seat_ticks_to_live(&[]), None);
+    }
+}
@@ -1,10 +1,12 @@
 use super::body_helpers::*;
+use super::seats::*;
 use super::structure_data::*;
 use crate::jobs::data::*;
 use crate::jobs::harvest::*;
 use crate::jobs::linkmine::*;
 use crate::jobs::staticmine::*;
 use crate::missions::data::*;
+use crate::missions::emergency::*;
 use crate::missions::missionsystem::*;
 use crate::remoteobjectid::*;
 use crate::room::visibilitysystem::*;
@@ -19,6 +21,46 @@ use serde::{Deserialize, Serialize};
 use specs::error::NoError;
 use specs::saveload::*;
 use specs::*;
+use std::collections::HashMap;
+
+/// Fill fraction above which a source container counts as backing up.
+const BACKLOG_FILL: f32 = 0.9;
+/// Fill fraction below which a backed up container has recovered.
+const BACKLOG_RELAX_FILL: f32 = 0.5;
+/// Consecutive ticks a container has to stay above [`BACKLOG_FILL`] before
+/// its haulers are treated as falling behind, so a miner filling the
+/// container between two pickups doesn't trip it.
+const BACKLOG_TICKS: u32 = 50;
+
+/// How far behind the haulers are on one source container.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ContainerBacklog {
+    /// Fill fraction at the last sample.
+    pub fill: f32,
+    /// Consecutive samples above [`BACKLOG_FILL`].
+    full_ticks: u32,
+    overflowing: bool,
+}
+
+impl ContainerBacklog {
+    /// Record this tick's fill fraction. The container overflows once it has
+    /// stayed above [`BACKLOG_FILL`] for [`BACKLOG_TICKS`] and until it drops
+    /// below [`BACKLOG_RELAX_FILL`].
+    pub fn sample(&mut self, fill: f32) {
+        self.fill = fill;
+        self.full_ticks = if fill >= BACKLOG_FILL { self.full_ticks + 1 } else { 0 };
+
+        if self.full_ticks >= BACKLOG_TICKS {
+            self.overflowing = true;
+        } else if fill < BACKLOG_RELAX_FILL {
+            self.overflowing = false;
+        }
+    }
+
+    pub fn overflowing(&self) -> bool {
+        self.overflowing
+    }
+}
 
 pub struct SourceMiningMission {
     owner: EntityOption<Entity>,
@@ -30,6 +72,10 @@ pub struct SourceMiningMission {
     harvesters: EntityVec<Entity>,
     room_name: RoomName,
     allow_spawning: bool,
+    paused: bool,
+    /// Backlog of each container the miners fill. Not serialized: it is
+    /// sampled again from the first tick after a VM reload.
+    backlog: HashMap<RemoteObjectId<StructureContainer>, ContainerBacklog>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -48,6 +94,7 @@ where
     harvesters: <EntityVec<Entity> as ConvertSaveload<MA>>::Data,
     room_name: <RoomName as ConvertSaveload<MA>>::Data,
     allow_spawning: <bool as ConvertSaveload<MA>>::Data,
+    paused: <bool as ConvertSaveload<MA>>::Data,
 }
 
 impl<MA> ConvertSaveload<MA> for SourceMiningMission
@@ -73,6 +120,7 @@ where
             harvesters: ConvertSaveload::convert_into(&self.harvesters, &mut ids)?,
             room_name: ConvertSaveload::convert_into(&self.room_name, &mut ids)?,
             allow_spawning: ConvertSaveload::convert_into(&self.allow_spawning, &mut ids)?,
+            paused: ConvertSaveload::convert_into(&self.paused, &mut ids)?,
         })
     }
 
@@ -90,6 +138,8 @@ where
             harvesters: ConvertSaveload::convert_from(data.harvesters, &mut ids)?,
             room_name: ConvertSaveload::convert_from(data.room_name, &mut ids)?,
             allow_spawning: ConvertSaveload::convert_from(data.allow_spawning, &mut ids)?,
+            paused: ConvertSaveload::convert_from(data.paused, &mut ids)?,
+            backlog: HashMap::new(),
         })
     }
 }
@@ -117,6 +167,8 @@ impl SourceMiningMission {
             harvesters: EntityVec::new(),
             room_name,
             allow_spawning: true,
+            paused: false,
+            backlog: HashMap::new(),
         };
 
         builder
@@ -138,6 +190,68 @@ impl SourceMiningMission {
         self.allow_spawning = allow;
     }
 
+    /// The source containers the haulers have fallen behind on.
+    pub fn overflowing_containers(&self) -> impl Iterator<Item = RemoteObjectId<StructureContainer>> + '_ {
+        self.backlog
+            .iter()
+            .filter(|(_, backlog)| backlog.overflowing())
+            .map(|(container, _)| *container)
+    }
+
+    /// Sample the fill of every container a miner is seated on.
+    fn update_backlog(&mut self, system_data: &MissionExecutionSystemData) {
+        let containers: Vec<_> = self
+            .container_miners
+            .iter()
+            .filter_map(|miner_entity| match system_data.job_data.get(*miner_entity) {
+                Some(JobData::StaticMine(miner_data)) => Some(miner_data.context.container_target),
+                _ => None,
+            })
+            .unique()
+            .collect();
+
+        self.backlog.retain(|container, _| containers.contains(container));
+
+        for container_id in containers {
+            // Out of sight the last sample stands.
+            let Some(container) = container_id.resolve() else {
+                continue;
+            };
+
+            let capacity = container.store().get_capacity(None).max(1);
+            let fill = container.store().get_used_capacity(None) as f32 / capacity as f32;
+
+            let backlog = self.backlog.entry(container_id).or_default();
+            let was_overflowing = backlog.overflowing();
+
+            backlog.sample(fill);
+
+            if backlog.overflowing() != was_overflowing {
+                log::info!(
+                    "Source container {} in {} {}",
+                    container_id.id(),
+                    self.room_name,
+                    if was_overflowing {
+                        "caught up"
+                    } else {
+                        "is overflowing, haulers are behind"
+                    }
+                );
+            }
+        }
+    }
+
+    fn describe_backlog(&self) -> String {
+        self.backlog
+            .values()
+            .map(|backlog| {
+                let flag = if backlog.overflowing() { "!" } else { "" };
+
+                format!("{:.0}%{}", backlog.fill * 100.0, flag)
+            })
+            .join(" ")
+    }
+
     fn create_handle_link_miner_spawn(
         mission_entity: Entity,
         source_id: RemoteObjectId<Source>,
@@ -211,18 +325,55 @@ impl SourceMiningMission {
         })
     }
 
-    fn spawn_creeps(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<(), String> {
-        let room_data = system_data.room_data.get(self.room_data).ok_or("Expected room data")?;
+    /// Release each seated miner whose replacement has arrived beside it.
+    fn hand_off_seats(&mut self, system_data: &mut MissionExecutionSystemData) {
+        let container_seats = self
+            .container_miners
+            .iter()
+            .filter_map(|miner_entity| match system_data.job_data.get(*miner_entity) {
+                Some(JobData::StaticMine(miner_data)) => Some((miner_data.context.container_target.pos(), *miner_entity)),
+                _ => None,
+            });
+
+        let link_seats = self
+            .link_miners
+            .iter()
+            .filter_map(|miner_entity| match system_data.job_data.get(*miner_entity) {
+                Some(JobData::LinkMine(miner_data)) => Some((miner_data.get_link_target().pos(), *miner_entity)),
+                _ => None,
+            });
+
+        let seats: HashMap<Position, Vec<Entity>> = container_seats.chain(link_seats).into_group_map();
+
+        let outgoing: Vec<Entity> = seats
+            .values()
+            .filter_map(|miners| outgoing_holder(&seat_holders(system_data.creep_owner, system_data.creep_spawning, miners)))
+            .collect();
 
-        let dynamic_visibility_data = room_data.get_dynamic_visibility_data().ok_or("Expected dynamic visibility")?;
+        for miner in outgoing {
+            release_creep(system_data, miner, RecyclePolicy::Recycle);
+
+            self.container_miners.retain(|e| *e != miner);
+            self.link_miners.retain(|e| *e != miner);
+        }
+    }
+
+    fn spawn_creeps(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<(), MissionError> {
+        let room_data = system_data
+            .room_data
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
+
+        let dynamic_visibility_data = room_data
+            .get_dynamic_visibility_data()
+            .ok_or_else(|| MissionError::new(MissionFailure::NoVisibility, "Expected dynamic visibility"))?;
         let likely_owned_room = dynamic_visibility_data.updated_within(2000)
             && (dynamic_visibility_data.owner().mine() || dynamic_visibility_data.reservation().mine());
-        let has_visibility = dynamic_visibility_data.visible();
 
         let pathfinder = &mut *system_data.pathfinder;
         let structure_data_rc = system_data.supply_structure_cache.get_room(self.room_name);
         let mut structure_data = structure_data_rc.maybe_access(
-            |d| game::time().saturating_sub(d.last_updated) >= 10 && has_visibility,
+            |d| d.needs_rebuild(room_data),
             || create_structure_data(room_data, Some(pathfinder)),
         );
 
@@ -235,7 +386,9 @@ impl SourceMiningMission {
             return Ok(());
         }
 
-        let structure_data = structure_data.get().ok_or("Expected structure data")?;
+        let structure_data = structure_data
+            .get()
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected structure data"))?;
 
         let source_id = &self.source;
 
@@ -272,56 +425,33 @@ impl SourceMiningMission {
             })
             .into_group_map();
 
-        let source_container_miners: Vec<_> = source_containers
-            .iter()
-            .filter_map(|container| containers_to_miners.get(container))
-            .flat_map(|m| m.iter())
-            .collect();
-
-        let source_link_miners: Vec<_> = source_links
-            .iter()
-            .filter_map(|link| links_to_miners.get(link))
-            .flat_map(|m| m.iter())
-            .collect();
-
         let total_harvesting_creeps = self.harvesters.len() + self.container_miners.len() + self.link_miners.len();
 
-        // Compute replacement lead time.
         let work_parts = source_work_parts(likely_owned_room);
 
-        let miner_replacement_lead = self
-            .home_room_datas
+        // How long each seat stays held; a held seat's miner is requested as its replacement.
+        let container_seats: HashMap<_, _> = source_containers
             .iter()
-            .filter_map(|home_room_entity| {
-                let home_room_data = system_data.room_data.get(*home_room_entity)?;
-                let home_room = game::rooms().get(home_room_data.name)?;
-                let is_local = source_id.pos().room_name() == home_room_data.name;
-                let has_link = !source_links.is_empty();
+            .map(|container| {
+                let miners = containers_to_miners.get(container).map(|m| m.as_slice()).unwrap_or(&[]);
+                let holders = seat_holders(system_data.creep_owner, system_data.creep_spawning, miners);
 
-                let body_definition = source_miner_body(is_local, home_room.energy_capacity_available(), work_parts, has_link);
-                let body = crate::creep::spawning::create_body(&body_definition).ok()?;
-                Some(miner_lead_ticks(&body, source_id.pos(), structure_data))
+                (*container, seat_ticks_to_live(&holders))
             })
-            .min()
-            .unwrap_or(MIN_REPLACEMENT_LEAD_TICKS)
-            .max(MIN_REPLACEMENT_LEAD_TICKS);
+            .collect();
 
-        let alive_miners: Vec<_> = source_container_miners
+        let link_seats: HashMap<_, _> = source_links
             .iter()
-            .chain(source_link_miners.iter())
-            .filter(|entity| {
-                system_data.creep_spawning.get(***entity).is_some()
-                    || system_data
-                        .creep_owner
-                        .get(***entity)
-                        .and_then(|creep_owner| creep_owner.owner.resolve())
-                        .and_then(|creep| creep.ticks_to_live())
-                        .map(|count| count > miner_replacement_lead)
-                        .unwrap_or(false)
+            .map(|link| {
+                let miners = links_to_miners.get(link).map(|m| m.as_slice()).unwrap_or(&[]);
+                let holders = seat_holders(system_data.creep_owner, system_data.creep_spawning, miners);
+
+                (*link, seat_ticks_to_live(&holders))
             })
-            .map(|entity| **entity)
             .collect();
 
+        let seat_distance = structure_data.spawn_distance(source_id.pos());
+
         // Determine home room properties.
         let any_home_room_has_storage = self
             .home_room_datas
@@ -362,8 +492,13 @@ impl SourceMiningMission {
             .collect();
 
         for home_room_entity in self.home_room_datas.iter() {
-            let home_room_data = system_data.room_data.get(*home_room_entity).ok_or("Expected home room data")?;
-            let home_room = game::rooms().get(home_room_data.name).ok_or("Expected home room")?;
+            let home_room_data = system_data
+                .room_data
+                .get(*home_room_entity)
+                .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected home room data"))?;
+            let home_room = game::rooms()
+                .get(home_room_data.name)
+                .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected home room"))?;
 
             let room_offset_distance = home_room_data.name - source_id.pos().room_name();
             let room_manhattan_distance = room_offset_distance.0.abs() + room_offset_distance.1.abs();
@@ -390,7 +525,21 @@ impl SourceMiningMission {
                 //TODO: Compute correct number of harvesters to use for source.
                 let desired_harvesters = 4;
 
-                if current_source_room_harvesters < desired_harvesters {
+                let emergency = room_manhattan_distance == 0 && system_data.energy_emergency.is_active(*home_room_entity);
+
+                if emergency && current_source_room_harvesters == 0 {
+                    // Economy collapsed: one minimal worker the spawn can always afford restarts it.
+                    let spawn_request = SpawnRequest::new(
+                        format!("Emergency Worker - Source: {}", source_id.id()),
+                        EMERGENCY_WORKER_BODY,
+                        SPAWN_PRIORITY_CRITICAL,
+                        None,
+                        Self::create_handle_harvester_spawn(mission_entity, *source_id, *home_room_entity),
+                    )
+                    .requested_by(mission_entity);
+
+                    system_data.spawn_queue.request(*home_room_entity, spawn_request);
+                } else if current_source_room_harvesters < desired_harvesters {
                     let body_definition = harvester_body(if total_harvesting_creeps == 0 {
                         home_room.energy_available().max(SPAWN_ENERGY_CAPACITY)
                     } else {
@@ -415,7 +564,8 @@ impl SourceMiningMission {
                             priority,
                             None,
                             Self::create_handle_harvester_spawn(mission_entity, *source_id, *home_room_entity),
-                        );
+                        )
+                        .requested_by(mission_entity);
 
                         system_data.spawn_queue.request(*home_room_entity, spawn_request);
                     }
@@ -427,26 +577,22 @@ impl SourceMiningMission {
         // Spawn link miners.
         //
         if !source_links.is_empty() {
-            let mut available_containers = source_containers.iter().filter(|container| {
-                containers_to_miners
-                    .get(container)
-                    .map(|miners| !miners.iter().any(|miner| alive_miners.contains(miner)))
-                    .unwrap_or(true)
-            });
+            let mut available_containers = source_containers
+                .iter()
+                .filter(|container| container_seats.get(container).copied().flatten().is_none());
 
-            let available_links = source_links.iter().filter(|link| {
-                links_to_miners
-                    .get(link)
-                    .map(|miners| !miners.iter().any(|miner| alive_miners.contains(miner)))
-                    .unwrap_or(true)
-            });
-
-            for link in available_links {
+            for link in source_links {
+                let seat = link_seats.get(link).copied().flatten();
                 let token = system_data.spawn_queue.token();
 
                 for home_room_entity in self.home_room_datas.iter() {
-                    let home_room_data = system_data.room_data.get(*home_room_entity).ok_or("Expected home room data")?;
-                    let home_room = game::rooms().get(home_room_data.name).ok_or("Expected home room")?;
+                    let home_room_data = system_data
+                        .room_data
+                        .get(*home_room_entity)
+                        .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected home room data"))?;
+                    let home_room = game::rooms()
+                        .get(home_room_data.name)
+                        .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected home room"))?;
 
                     let is_local = link.pos().room_name() == home_room_data.name;
                     let body_definition = source_miner_body(is_local, home_room.energy_capacity_available(), work_parts, true);
@@ -460,7 +606,13 @@ impl SourceMiningMission {
                             SPAWN_PRIORITY_HIGH,
                             Some(token),
                             Self::create_handle_link_miner_spawn(mission_entity, *source_id, *link, target_container.cloned()),
-                        );
+                        )
+                        .requested_by(mission_entity);
+
+                        let spawn_request = match seat {
+                            Some(ticks_to_live) => spawn_request.replacing(ticks_to_live, seat_distance),
+                            None => spawn_request,
+                        };
 
                         system_data.spawn_queue.request(*home_room_entity, spawn_request);
                     }
@@ -470,19 +622,18 @@ impl SourceMiningMission {
             //
             // Spawn container miners.
             //
-            let available_containers = source_containers.iter().filter(|container| {
-                containers_to_miners
-                    .get(container)
-                    .map(|miners| !miners.iter().any(|miner| alive_miners.contains(miner)))
-                    .unwrap_or(true)
-            });
-
-            for container in available_containers {
+            for container in source_containers {
+                let seat = container_seats.get(container).copied().flatten();
                 let token = system_data.spawn_queue.token();
 
                 for home_room_entity in self.home_room_datas.iter() {
-                    let home_room_data = system_data.room_data.get(*home_room_entity).ok_or("Expected home room data")?;
-                    let home_room = game::rooms().get(home_room_data.name).ok_or("Expected home room")?;
+                    let home_room_data = system_data
+                        .room_data
+                        .get(*home_room_entity)
+                        .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected home room data"))?;
+                    let home_room = game::rooms()
+                        .get(home_room_data.name)
+                        .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected home room"))?;
 
                     let is_local = container.pos().room_name() == home_room_data.name;
                     let body_definition = source_miner_body(is_local, home_room.energy_capacity_available(), work_parts, false);
@@ -494,7 +645,13 @@ impl SourceMiningMission {
                             SPAWN_PRIORITY_HIGH,
                             Some(token),
                             Self::create_handle_container_miner_spawn(mission_entity, *source_id, *container),
-                        );
+                        )
+                        .requested_by(mission_entity);
+
+                        let spawn_request = match seat {
+                            Some(ticks_to_live) => spawn_request.replacing(ticks_to_live, seat_distance),
+                            None => spawn_request,
+                        };
 
                         system_data.spawn_queue.request(*home_room_entity, spawn_request);
                     }
@@ -508,6 +665,8 @@ impl SourceMiningMission {
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl Mission for SourceMiningMission {
+    impl_mission_pause!();
+
     fn get_owner(&self) -> &Option<Entity> {
         &self.owner
     }
@@ -538,23 +697,28 @@ impl Mission for SourceMiningMission {
 
     fn describe_state(&self, _system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> String {
         format!(
-            "Source Mining - Link: {} Container: {} Harvest: {}",
+            "Source Mining - Link: {} Container: {} Harvest: {} Backlog: {}",
             self.link_miners.len(),
             self.container_miners.len(),
-            self.harvesters.len()
+            self.harvesters.len(),
+            self.describe_backlog()
         )
     }
 
     fn summarize(&self) -> crate::visualization::SummaryContent {
         crate::visualization::SummaryContent::Text(format!(
-            "Source Mining (L:{} C:{} H:{})",
+            "Source Mining (L:{} C:{} H:{}) {}",
             self.link_miners.len(),
             self.container_miners.len(),
-            self.harvesters.len()
+            self.harvesters.len(),
+            self.describe_backlog()
         ))
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, String> {
+    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, MissionError> {
+        self.hand_off_seats(system_data);
+        self.update_backlog(system_data);
+
         if self.allow_spawning {
             self.spawn_creeps(system_data, mission_entity)?;
         }
@@ -562,3 +726,34 @@ impl Mission for SourceMiningMission {
         Ok(MissionResult::Running)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backlog_trips_after_a_sustained_fill_and_relaxes_at_half() {
+        let mut backlog = ContainerBacklog::default();
+
+        for _ in 0..BACKLOG_TICKS - 1 {
+            backlog.sample(0.95);
+        }
+        assert!(!backlog.overflowing());
+
+        // A dip below the threshold restarts the count.
+        backlog.sample(0.8);
+        for _ in 0..BACKLOG_TICKS - 1 {
+            backlog.sample(1.0);
+        }
+        assert!(!backlog.overflowing());
+
+        backlog.sample(1.0);
+        assert!(backlog.overflowing());
+
+        // Draining below the full threshold doesn't relax it until half empty.
+        backlog.sample(0.6);
+        assert!(backlog.overflowing());
+        backlog.sample(0.4);
+        assert!(!backlog.overflowing());
+    }
+}
@@ -49,27 +49,183 @@ pub struct StructureData {
     /// `pathfinder::search` so the per-tick lead time calculation is pure
     /// arithmetic.
     pub nearest_spawn_distances: HashMap<screeps::Position, u32>,
+    /// Structures in the room when this data was built.
+    pub structure_count: usize,
+    /// Construction sites in the room when this data was built; one finishing means a rebuild.
+    pub construction_sites: Vec<ObjectId<ConstructionSite>>,
+    /// Set by [`SupplyStructureCache::dirty`] to force a rebuild.
+    #[serde(skip)]
+    pub dirty: bool,
+    /// Tick [`StructureData::needs_rebuild`] last validated against the room.
+    #[serde(skip)]
+    validated: Cell<u32>,
+}
+
+/// Age at which the data is rebuilt even if every check still passes, as a backstop for changes the
+/// per-tick validation can't see (a structure destroyed and another built on the same tick).
+const STRUCTURE_DATA_MAX_AGE: u32 = 1500;
+
+impl StructureData {
+    /// Path length from the nearest spawn to the source or mineral at `pos`;
+    /// zero when it is not known (no spawn, or the search gave up).
+    pub fn spawn_distance(&self, pos: screeps::Position) -> u32 {
+        self.nearest_spawn_distances
+            .get(&pos)
+            .copied()
+            .filter(|distance| *distance != u32::MAX)
+            .unwrap_or(0)
+    }
+
+    /// Whether the data should be rebuilt from a full room scan: a mission marked it dirty, or, at
+    /// most once a tick, a check against the room shows a structure count change, a finished
+    /// construction site or a cached structure that no longer resolves. Only rooms with visibility
+    /// are rebuilt.
+    pub fn needs_rebuild(&self, room_data: &RoomData) -> bool {
+        let has_visibility = room_data.get_dynamic_visibility_data().map(|v| v.visible()).unwrap_or(false);
+
+        if !has_visibility {
+            return false;
+        }
+
+        if self.dirty || game::time().saturating_sub(self.last_updated) >= STRUCTURE_DATA_MAX_AGE {
+            return true;
+        }
+
+        if self.validated.replace(game::time()) == game::time() {
+            return false;
+        }
+
+        let structure_count_changed = room_data
+            .get_structures()
+            .map(|structures| structures.all().len() != self.structure_count)
+            .unwrap_or(false);
+
+        let site_finished = room_data
+            .get_construction_sites()
+            .map(|sites| {
+                self.construction_sites
+                    .iter()
+                    .any(|id| !sites.iter().any(|site| site.try_id() == Some(*id)))
+            })
+            .unwrap_or(false);
+
+        structure_count_changed || site_finished || !self.all_resolve()
+    }
+
+    fn all_resolve(&self) -> bool {
+        fn resolves<T: MaybeHasId + wasm_bindgen::JsCast>(ids: &[RemoteObjectId<T>]) -> bool {
+            ids.iter().all(|id| id.resolve().is_some())
+        }
+
+        self.sources_to_containers.values().all(|ids| resolves(ids))
+            && self.sources_to_links.values().all(|ids| resolves(ids))
+            && self.mineral_extractors_to_containers.values().all(|ids| resolves(ids))
+            && self.controllers_to_containers.values().all(|ids| resolves(ids))
+            && resolves(&self.storage_links)
+            && resolves(&self.controller_links)
+            && resolves(&self.containers)
+            && resolves(&self.spawns)
+            && resolves(&self.extensions)
+            && resolves(&self.storage)
+    }
+
+    /// Follow any container or link that was destroyed and rebuilt on the same tile to its new id
+    /// (see `RemoteObjectId::resolve_or_rebuilt`), so a rebuilt container is used straight away
+    /// rather than after the next full refresh.
+    pub fn revalidate(&mut self) {
+        let containers = self
+            .sources_to_containers
+            .values_mut()
+            .chain(self.mineral_extractors_to_containers.values_mut())
+            .chain(self.controllers_to_containers.values_mut())
+            .flatten()
+            .chain(self.containers.iter_mut());
+
+        for id in containers {
+            revalidate_id(id);
+        }
+
+        let links = self
+            .sources_to_links
+            .values_mut()
+            .flatten()
+            .chain(self.storage_links.iter_mut())
+            .chain(self.controller_links.iter_mut());
+
+        for id in links {
+            revalidate_id(id);
+        }
+    }
+}
+
+fn revalidate_id<T>(id: &mut RemoteObjectId<T>)
+where
+    T: RebuildableStructure + HasId + HasPosition + wasm_bindgen::JsCast,
+{
+    if let Some((_, Some(rebuilt))) = id.resolve_or_rebuilt() {
+        *id = rebuilt;
+    }
 }
 
 /// World resource that caches `StructureData` per room. Each room gets a
 /// single `Rc<RefCell<Option<StructureData>>>` that is shared across all
 /// missions operating in that room. The cache is lazily populated on demand
-/// and refreshed when stale (every 10+ ticks with visibility).
+/// and rebuilt when [`StructureData::needs_rebuild`] finds it out of date.
 #[derive(Default)]
 pub struct SupplyStructureCache {
     rooms: HashMap<RoomName, Rc<RefCell<Option<StructureData>>>>,
+    /// Tick each room's data was last revalidated.
+    revalidated: HashMap<RoomName, u32>,
+    /// Build tick of each room's data when last seen, to count rebuilds.
+    built: HashMap<RoomName, u32>,
+    /// Full rebuilds per room since the VM started, for stats.
+    rebuilds: HashMap<RoomName, u32>,
 }
 
 impl SupplyStructureCache {
     pub fn new() -> Self {
-        Self { rooms: HashMap::new() }
+        Self {
+            rooms: HashMap::new(),
+            revalidated: HashMap::new(),
+            built: HashMap::new(),
+            rebuilds: HashMap::new(),
+        }
+    }
+
+    /// Force a rebuild of the room's data on next access, for missions that know they changed the
+    /// room's structures.
+    pub fn dirty(&mut self, room_name: RoomName) {
+        if let Some(Ok(mut data)) = self.rooms.get(&room_name).map(|data| data.try_borrow_mut()) {
+            if let Some(data) = data.as_mut() {
+                data.dirty = true;
+            }
+        }
+    }
+
+    pub fn rebuilds(&self, room_name: RoomName) -> u32 {
+        self.rebuilds.get(&room_name).copied().unwrap_or(0)
     }
 
     /// Get (or create) the shared `Rc<RefCell<Option<StructureData>>>` for a
     /// room. The returned `Rc` can be captured by generator closures and
-    /// shared across missions.
+    /// shared across missions. Rebuilt containers and links are revalidated
+    /// here, at most once a tick per room.
     pub fn get_room(&mut self, room_name: RoomName) -> Rc<RefCell<Option<StructureData>>> {
-        self.rooms.entry(room_name).or_insert_with(|| Rc::new(RefCell::new(None))).clone()
+        let structure_data = self.rooms.entry(room_name).or_insert_with(|| Rc::new(RefCell::new(None))).clone();
+
+        if self.revalidated.insert(room_name, game::time()) != Some(game::time()) {
+            if let Ok(mut data) = structure_data.try_borrow_mut() {
+                if let Some(data) = data.as_mut() {
+                    if self.built.insert(room_name, data.last_updated) != Some(data.last_updated) {
+                        *self.rebuilds.entry(room_name).or_insert(0) += 1;
+                    }
+
+                    data.revalidate();
+                }
+            }
+        }
+
+        structure_data
     }
 }
 
@@ -78,9 +234,10 @@ impl SupplyStructureCache {
 /// four mission-context refresh sites). The two transfer-generator
 /// refresh sites pass `None` — boxed generators are flushed lazily and
 /// cannot carry a `&mut` service handle; they fall back to the plain
-/// per-search cap, which is bounded (≤ spawns × targets × 1000 ops at
-/// most once per 10 ticks per room) and only fires when no mission
-/// refreshed the room's cache first (missions run before jobs).
+/// per-search cap, which is bounded (≤ spawns × targets × 1000 ops per
+/// rebuild, see `StructureData::needs_rebuild`) and only fires when no mission
+/// refreshed the room's cache first (`LocalSupplyMission` refreshes it in
+/// pre-run, before the missions it owns register generators).
 pub fn create_structure_data(room_data: &RoomData, pathfinder: Option<&mut PathfinderService>) -> Option<StructureData> {
     let structure_data = room_data.get_structures()?;
     let static_visibility_data = room_data.get_static_visibility_data()?;
@@ -201,6 +358,13 @@ pub fn create_structure_data(room_data: &RoomData, pathfinder: Option<&mut Pathf
         extensions: extensions.iter().map(|e| e.remote_id()).collect(),
         storage: storages.iter().map(|s| s.remote_id()).collect(),
         nearest_spawn_distances,
+        structure_count: structure_data.all().len(),
+        construction_sites: room_data
+            .get_construction_sites()
+            .map(|sites| sites.iter().filter_map(|site| site.try_id()).collect())
+            .unwrap_or_default(),
+        dirty: false,
+        validated: Cell::new(game::time()),
     })
 }
 
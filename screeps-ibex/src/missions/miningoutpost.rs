@@ -1,18 +1,117 @@
 use super::data::*;
+use super::exitchoke::*;
 use super::haul::*;
 use super::localsupply::*;
 use super::missionsystem::*;
+use super::remoteroads::*;
 use super::reserve::*;
 use super::utility::*;
+use crate::military::objective_queue::*;
+use crate::room::hostilesummary::HostileOwner;
 use crate::room::visibilitysystem::*;
 use crate::serialize::*;
 use log::*;
+use screeps::*;
+use screeps_combat_decision::composition::CompositionParams;
+use screeps_combat_decision::doctrine::{
+    decide_doctrine, defense_doctrines, plan_engagement, DoctrineObjective, EnemyCoordination, EnemyForce, EngagementContext,
+};
+use screeps_combat_decision::force_sizing::DefenseProfile;
 use screeps_machine::*;
 use serde::{Deserialize, Serialize};
 #[allow(deprecated)]
 use specs::error::NoError;
 use specs::saveload::*;
 use specs::*;
+use std::collections::{HashMap, HashSet};
+
+/// A remote is mined blind no longer than this before its creeps stop
+/// spawning, and dropped once unseen for the fail limit.
+const OUTPOST_DATA_AGE_LIMITS: DataAgeLimits = DataAgeLimits {
+    pause_after: 2_000,
+    fail_after: 20_000,
+};
+
+/// The engine sends invaders into a room once the energy harvested there since
+/// the last raid passes a goal it rolls somewhere between these.
+const INVADER_GOAL_MIN: u32 = 73_000;
+const INVADER_GOAL_MAX: u32 = 120_000;
+
+/// The raid a pre-posted defender is sized against: a pair of small invaders,
+/// one of them healing, as a remote of this size draws.
+const EXPECTED_INVADERS: EnemyForce = EnemyForce {
+    dps: 100.0,
+    heal: 24.0,
+    hits: 0,
+    count: 2,
+    boosted: false,
+};
+
+/// Ticks to spawn a full-size defender.
+const DEFENDER_SPAWN_TICKS: u32 = 150;
+/// Ticks a defender takes to cross one room.
+const DEFENDER_TICKS_PER_ROOM: u32 = 50;
+/// Slack for the squad manager to pick the objective up and queue the spawn.
+const DEFENDER_FIELDING_MARGIN: u32 = 50;
+/// Re-asserted every tick while the window is open; lapses soon after it closes.
+const INVADER_DEFEND_TTL: u32 = 60;
+
+/// Energy harvested out of sources between two sightings: what each one lost.
+/// A source that refilled in between counts nothing.
+fn harvested_between(before: &[u32], after: &[u32]) -> u32 {
+    if before.len() != after.len() {
+        return 0;
+    }
+
+    before.iter().zip(after).map(|(before, after)| before.saturating_sub(*after)).sum()
+}
+
+/// Ticks until `harvested` reaches `goal` mining at `rate` energy per tick.
+fn ticks_until_goal(harvested: u32, goal: u32, rate: f32) -> Option<u32> {
+    if harvested >= goal {
+        return Some(0);
+    }
+
+    (rate > 0.0).then(|| ((goal - harvested) as f32 / rate).ceil() as u32)
+}
+
+/// Ticks from asking for a defender to it standing in a remote `home_distance`
+/// rooms from its spawn.
+fn defender_lead(home_distance: u32) -> u32 {
+    DEFENDER_SPAWN_TICKS + home_distance * DEFENDER_TICKS_PER_ROOM + DEFENDER_FIELDING_MARGIN
+}
+
+/// Energy harvested from a remote since its last invasion, and the invasion
+/// it predicts.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct InvaderWatch {
+    harvested: u32,
+    /// Energy in each source at the last sighting, to diff the next one against.
+    source_energy: Vec<u32>,
+    /// Tick the invasion window is predicted to open at the current harvest rate.
+    window_opens_at: Option<u32>,
+    /// Whether a defender objective is standing for the window.
+    posted: bool,
+}
+
+impl InvaderWatch {
+    fn observe(&mut self, source_energy: Vec<u32>) {
+        self.harvested = self
+            .harvested
+            .saturating_add(harvested_between(&self.source_energy, &source_energy));
+        self.source_energy = source_energy;
+    }
+
+    fn reset(&mut self) {
+        self.harvested = 0;
+        self.window_opens_at = None;
+    }
+
+    /// Ticks until the predicted window opens, zero once it has.
+    fn countdown(&self, now: u32) -> Option<u32> {
+        self.window_opens_at.map(|tick| tick.saturating_sub(now))
+    }
+}
 
 #[derive(Clone, ConvertSaveload)]
 pub struct MiningOutpostMissionContext {
@@ -60,7 +159,7 @@ machine!(
 
         * => fn gather_data(&self, _system_data: &MissionExecutionSystemData, _mission_entity: Entity) {}
 
-        _ => fn tick(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity, state_context: &mut MiningOutpostMissionContext) -> Result<Option<MiningOutpostState>, String>;
+        _ => fn tick(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity, state_context: &mut MiningOutpostMissionContext) -> Result<Option<MiningOutpostState>, MissionError>;
     }
 );
 
@@ -68,12 +167,12 @@ fn can_run_mission(
     system_data: &mut MissionExecutionSystemData,
     _mission_entity: Entity,
     state_context: &mut MiningOutpostMissionContext,
-) -> Result<bool, String> {
+) -> Result<bool, MissionError> {
     let derelict_features = system_data.features.derelict;
     let outpost_room_data = system_data
         .room_data
         .get(state_context.outpost_room_data)
-        .ok_or("Expected outpost room data")?;
+        .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected outpost room data"))?;
 
     if let Some(dynamic_visibility_data) = outpost_room_data.get_dynamic_visibility_data() {
         // A confirmed-derelict (hostile-owned but dead) room is minable
@@ -82,10 +181,10 @@ fn can_run_mission(
         let confirmed_derelict = derelict_features.on
             && dynamic_visibility_data.confirmed_derelict(derelict_features.confirm_ticks, derelict_features.path_max_age);
 
+        // Another player reserving a remote we mine contests it rather than ends it: the reserve mission
+        // wears the reservation down and flags the room for harassment, giving up only if that fails.
         if dynamic_visibility_data.updated_within(1000)
-            && (!(dynamic_visibility_data.owner().neutral() || confirmed_derelict)
-                || dynamic_visibility_data.reservation().hostile()
-                || dynamic_visibility_data.reservation().friendly())
+            && (!(dynamic_visibility_data.owner().neutral() || confirmed_derelict) || dynamic_visibility_data.reservation().friendly())
         {
             return Ok(false);
         }
@@ -112,15 +211,18 @@ impl Scout {
         system_data: &mut MissionExecutionSystemData,
         _mission_entity: Entity,
         state_context: &mut MiningOutpostMissionContext,
-    ) -> Result<Option<MiningOutpostState>, String> {
+    ) -> Result<Option<MiningOutpostState>, MissionError> {
         let outpost_room_data = system_data
             .room_data
             .get(state_context.outpost_room_data)
-            .ok_or("Expected outpost room data")?;
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected outpost room data"))?;
 
         if let Some(static_visibility_data) = outpost_room_data.get_static_visibility_data() {
             if static_visibility_data.sources().is_empty() {
-                return Err("No sources available for mining outpost, aborting mission.".to_string());
+                return Err(MissionError::new(
+                    MissionFailure::TargetInvalid,
+                    "No sources available for mining outpost, aborting mission.",
+                ));
             }
         }
 
@@ -129,11 +231,10 @@ impl Scout {
         // Keep intel fresh while evaluating — or while waiting out a derelict
         // owner's controller decay below.
         if dynamic_visibility_data.map(|v| !v.updated_within(1000)).unwrap_or(true) {
-            system_data.visibility.request(VisibilityRequest::new(
-                outpost_room_data.name,
-                VISIBILITY_PRIORITY_MEDIUM,
-                VisibilityRequestFlags::ALL,
-            ));
+            system_data.visibility.request(
+                VisibilityRequest::new(outpost_room_data.name, VISIBILITY_PRIORITY_MEDIUM, VisibilityRequestFlags::ALL)
+                    .categories(VisibilityCategoryFlags::STRUCTURES),
+            );
         }
 
         let Some(dynamic_visibility_data) = dynamic_visibility_data else {
@@ -145,7 +246,10 @@ impl Scout {
         }
 
         if dynamic_visibility_data.reservation().hostile() || dynamic_visibility_data.reservation().friendly() {
-            return Err("Mission cannot run in current room state".to_string());
+            return Err(MissionError::new(
+                MissionFailure::TargetInvalid,
+                "Mission cannot run in current room state",
+            ));
         }
 
         if dynamic_visibility_data.owner().neutral() {
@@ -155,7 +259,10 @@ impl Scout {
         }
 
         if dynamic_visibility_data.owner().mine() || dynamic_visibility_data.owner().friendly() {
-            return Err("Mission cannot run in current room state".to_string());
+            return Err(MissionError::new(
+                MissionFailure::TargetInvalid,
+                "Mission cannot run in current room state",
+            ));
         }
 
         // Hostile owner. A CONFIRMED-derelict one (dead: no spawns / armed
@@ -177,7 +284,10 @@ impl Scout {
 
             Ok(Some(MiningOutpostState::mine(None.into(), None.into(), None.into())))
         } else {
-            Err("Mission cannot run in current room state".to_string())
+            Err(MissionError::new(
+                MissionFailure::TargetInvalid,
+                "Mission cannot run in current room state",
+            ))
         }
     }
 }
@@ -222,9 +332,12 @@ impl Mine {
         system_data: &mut MissionExecutionSystemData,
         mission_entity: Entity,
         state_context: &mut MiningOutpostMissionContext,
-    ) -> Result<Option<MiningOutpostState>, String> {
+    ) -> Result<Option<MiningOutpostState>, MissionError> {
         if !can_run_mission(system_data, mission_entity, state_context)? {
-            return Err("Mission cannot run in current room state".to_string());
+            return Err(MissionError::new(
+                MissionFailure::TargetInvalid,
+                "Mission cannot run in current room state",
+            ));
         }
 
         // Reservation requires a NEUTRAL controller (engine: reserveController
@@ -250,7 +363,7 @@ impl Mine {
             let outpost_room_data = system_data
                 .room_data
                 .get_mut(state_context.outpost_room_data)
-                .ok_or("Expected outpost room data")?;
+                .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected outpost room data"))?;
 
             let mission_entity = LocalSupplyMission::build(
                 system_data.updater.create_entity(system_data.entities),
@@ -275,7 +388,7 @@ impl Mine {
             let outpost_room_data = system_data
                 .room_data
                 .get_mut(state_context.outpost_room_data)
-                .ok_or("Expected outpost room data")?;
+                .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected outpost room data"))?;
 
             let mission_entity = HaulMission::build(
                 system_data.updater.create_entity(system_data.entities),
@@ -300,7 +413,7 @@ impl Mine {
             let outpost_room_data = system_data
                 .room_data
                 .get_mut(state_context.outpost_room_data)
-                .ok_or("Expected outpost room data")?;
+                .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected outpost room data"))?;
 
             let mission_entity = ReserveMission::build(
                 system_data.updater.create_entity(system_data.entities),
@@ -321,19 +434,27 @@ impl Mine {
         // visibility intel. Stop spawning economy creeps into a remote that is
         // currently militarily active (combat creeps / active spawns / armed
         // towers); resume the moment we observe the threat is gone.
-        let room_is_safe = is_remote_room_safe(
-            system_data
-                .room_data
-                .get(state_context.outpost_room_data)
-                .and_then(|rd| rd.get_dynamic_visibility_data()),
-        );
+        let outpost_room_data = system_data.room_data.get(state_context.outpost_room_data);
+        let room_is_safe = is_remote_room_safe(outpost_room_data.and_then(|rd| rd.get_dynamic_visibility_data()));
+        // Nor into one unseen for too long; the mission system has asked for a look.
+        let room_is_safe = room_is_safe && !system_data.stale_missions.is_stale(mission_entity);
+        // Sources in a room another player has reserved can't be harvested until the reservation is ours again.
+        let contested = outpost_room_data
+            .and_then(|rd| rd.get_dynamic_visibility_data())
+            .map(|dvd| dvd.reservation().hostile())
+            .unwrap_or(false);
+
+        // Remote-mining flags as seen from the outpost room (per-room overrides).
+        let remote_mine = outpost_room_data
+            .map(|rd| system_data.features.for_room(system_data.feature_overrides, rd.name).remote_mine)
+            .unwrap_or(system_data.features.remote_mine);
 
         if let Some(mut supply_mission) = self
             .supply_mission
             .and_then(|e| system_data.missions.get(e))
             .as_mission_type_mut::<LocalSupplyMission>()
         {
-            supply_mission.allow_spawning(room_is_safe);
+            supply_mission.allow_spawning(room_is_safe && !contested && remote_mine.harvest);
         }
 
         if let Some(mut haul_mission) = self
@@ -341,7 +462,7 @@ impl Mine {
             .and_then(|e| system_data.missions.get(e))
             .as_mission_type_mut::<HaulMission>()
         {
-            haul_mission.allow_spawning(room_is_safe);
+            haul_mission.allow_spawning(room_is_safe && !contested && remote_mine.harvest);
         }
 
         if let Some(mut reserve_mission) = self
@@ -349,7 +470,7 @@ impl Mine {
             .and_then(|e| system_data.missions.get(e))
             .as_mission_type_mut::<ReserveMission>()
         {
-            reserve_mission.allow_spawning(room_is_safe);
+            reserve_mission.allow_spawning(room_is_safe && remote_mine.reserve);
         }
 
         Ok(None)
@@ -361,6 +482,12 @@ pub struct MiningOutpostMission {
     owner: EntityOption<Entity>,
     context: MiningOutpostMissionContext,
     state: MiningOutpostState,
+    invader_watch: InvaderWatch,
+    roads: RemoteRoads,
+    /// Dropped by the operation to free spawn time: no more creeps spawn, and the mission ends once the last
+    /// of them has expired.
+    winding_down: bool,
+    paused: bool,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -384,6 +511,10 @@ impl MiningOutpostMission {
                 outpost_room_data,
             },
             state: MiningOutpostState::scout(std::marker::PhantomData),
+            invader_watch: InvaderWatch::default(),
+            roads: RemoteRoads::default(),
+            winding_down: false,
+            paused: false,
         }
     }
 
@@ -392,10 +523,382 @@ impl MiningOutpostMission {
             self.context.home_room_datas = home_room_datas.to_owned().into();
         }
     }
+
+    pub fn outpost_room(&self) -> Entity {
+        self.context.outpost_room_data
+    }
+
+    pub fn home_rooms(&self) -> &[Entity] {
+        self.context.home_room_datas.as_slice()
+    }
+
+    pub fn is_winding_down(&self) -> bool {
+        self.winding_down
+    }
+
+    /// Stop (or, with `false`, resume) spawning for the remote, letting the creeps it has run out their lives.
+    pub fn wind_down(&mut self, winding_down: bool) {
+        self.winding_down = winding_down;
+    }
+
+    /// Invader raids on a remote come once enough energy has been harvested
+    /// from it, so count the harvest while the room is in view, predict when
+    /// the raid window opens and post a defender to arrive just before it,
+    /// instead of answering after the miners are dead. An invasion restarts
+    /// the count.
+    fn watch_for_invaders(&mut self, system_data: &mut MissionExecutionSystemData) {
+        let Some(room_data) = system_data.room_data.get(self.context.outpost_room_data) else {
+            return;
+        };
+        let room_name = room_data.name;
+
+        // Out of view the count stands still; miners keep the room in view
+        // whenever there is harvesting to count.
+        if game::rooms().get(room_name).is_none() {
+            return;
+        }
+
+        let sources: Vec<Source> = room_data
+            .get_static_visibility_data()
+            .map(|s| s.sources().iter().filter_map(|source| source.resolve()).collect())
+            .unwrap_or_default();
+        let invaded = room_data.get_creeps().is_some_and(|creeps| {
+            creeps
+                .hostile_summary()
+                .bodies()
+                .iter()
+                .any(|body| body.owner == HostileOwner::Invader)
+        });
+
+        let now = game::time();
+        let watch = &mut self.invader_watch;
+
+        watch.observe(sources.iter().map(|source| source.energy()).collect());
+
+        if invaded {
+            // The war operation's remote defense answers the raid itself.
+            watch.reset();
+            return;
+        }
+
+        // Past the highest goal without a raid seen, the count missed harvest
+        // while out of view; start over rather than hold a defender forever.
+        if watch.harvested > INVADER_GOAL_MAX {
+            watch.reset();
+        }
+
+        let rate = sources.iter().map(|source| source.energy_capacity()).sum::<u32>() as f32 / ENERGY_REGEN_TIME as f32;
+        watch.window_opens_at = ticks_until_goal(watch.harvested, INVADER_GOAL_MIN, rate).map(|ticks| now + ticks);
+
+        let home_distance = self
+            .context
+            .home_room_datas
+            .iter()
+            .filter_map(|&e| system_data.room_data.get(e))
+            .map(|rd| game::map::get_room_linear_distance(rd.name, room_name, false))
+            .min()
+            .unwrap_or(0);
+
+        let defend = system_data
+            .features
+            .for_room(system_data.feature_overrides, room_name)
+            .military
+            .defense
+            && matches!(self.state, MiningOutpostState::Mine(_))
+            && watch.countdown(now).is_some_and(|ticks| ticks <= defender_lead(home_distance));
+
+        let kind = ObjectiveKind::Defend { room: room_name };
+
+        if !defend {
+            if watch.posted {
+                let queue = &mut system_data.combat_objective_queue;
+                if let Some(id) = queue
+                    .find_by_kind(&kind)
+                    .filter(|id| queue.get(*id).is_some_and(|o| o.owner == ObjectiveOwner::Defense))
+                {
+                    queue.withdraw(id);
+                }
+                watch.posted = false;
+            }
+            return;
+        }
+
+        let member_energy = self
+            .context
+            .home_room_datas
+            .iter()
+            .filter_map(|&e| system_data.room_data.get(e))
+            .filter_map(|rd| game::rooms().get(rd.name))
+            .map(|r| r.energy_capacity_available())
+            .max()
+            .unwrap_or(0);
+
+        let ctx = EngagementContext {
+            objective: DoctrineObjective::ClearCreeps,
+            coordination: EnemyCoordination::Individual,
+            defense: DefenseProfile::default(),
+            enemy_force: Some(EXPECTED_INVADERS),
+            importance: 0.0,
+            member_energy,
+            target_value: 1_000_000.0,
+            onsite_window: CREEP_LIFE_TIME,
+            params: CompositionParams {
+                member_energy,
+                ..Default::default()
+            },
+            defense_intel_reliable: false,
+        };
+        let Some(composition) = decide_doctrine(&ctx, &defense_doctrines()).and_then(|d| plan_engagement(d, &ctx, None).composition) else {
+            return;
+        };
+
+        if !watch.posted {
+            info!(
+                "Mining outpost {} - {} energy harvested, posting a defender ahead of the invader window",
+                room_name, watch.harvested
+            );
+        }
+
+        // Below the remote defense the war operation raises for invaders
+        // actually in the room, which upserts the same objective.
+        system_data.combat_objective_queue.request(
+            ObjectiveRequest::new(kind, OBJECTIVE_PRIORITY_LOW, ForceRequirement::single(composition))
+                .owner(ObjectiveOwner::Defense)
+                .ttl(INVADER_DEFEND_TTL),
+            now,
+        );
+        watch.posted = true;
+    }
+
+    /// Stop the children spawning while a home in lockdown has hostiles near
+    /// the exit the haul road leaves it by, overriding the state's own gate
+    /// for this tick. Children run after this mission, so they see it.
+    fn hold_departures(&self, system_data: &mut MissionExecutionSystemData) {
+        let exits = self.roads.exits();
+
+        let held = self.context.home_room_datas.iter().any(|&home| {
+            let (Some(room_data), Some(posture)) = (system_data.room_data.get(home), system_data.defense_postures.get(home)) else {
+                return false;
+            };
+            let home_exits: Vec<Position> = exits.iter().filter(|exit| exit.room_name() == room_data.name).copied().collect();
+
+            // The road leaves by another home's exits; this one's lockdown doesn't cross it.
+            if home_exits.is_empty() && !exits.is_empty() {
+                return false;
+            }
+
+            posture.holds_departure(&home_exits, system_data.threat_data.get(home))
+        });
+
+        if held {
+            self.stop_spawning(system_data);
+        }
+    }
+
+    /// Stop the children spawning this tick, overriding the state's own gate.
+    fn stop_spawning(&self, system_data: &mut MissionExecutionSystemData) {
+        for child in self.state.get_children() {
+            let child = system_data.missions.get(child);
+
+            if let Some(mut supply_mission) = child.as_mission_type_mut::<LocalSupplyMission>() {
+                supply_mission.allow_spawning(false);
+            } else if let Some(mut haul_mission) = child.as_mission_type_mut::<HaulMission>() {
+                haul_mission.allow_spawning(false);
+            } else if let Some(mut reserve_mission) = child.as_mission_type_mut::<ReserveMission>() {
+                reserve_mission.allow_spawning(false);
+            }
+        }
+    }
+
+    /// Road the haul from the nearest home storage to each source once the
+    /// room is being mined, placing sites on the planned tiles that have
+    /// neither road nor site, within each room's site cap.
+    fn maintain_roads(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) {
+        let now = game::time();
+
+        if !matches!(self.state, MiningOutpostState::Mine(_)) || (now + mission_entity.id()) % ROAD_CHECK_INTERVAL != 0 {
+            return;
+        }
+
+        let Some(outpost_room_data) = system_data.room_data.get(self.context.outpost_room_data) else {
+            return;
+        };
+        let outpost_room = outpost_room_data.name;
+
+        let features = system_data.features.for_room(system_data.feature_overrides, outpost_room);
+
+        // A derelict owner's controller still blocks building, as it defers
+        // the containers.
+        let neutral = outpost_room_data
+            .get_dynamic_visibility_data()
+            .map(|d| d.owner().neutral())
+            .unwrap_or(false);
+
+        if !features.remote_mine.roads || !neutral {
+            return;
+        }
+
+        let home_rooms: Vec<RoomName> = self
+            .context
+            .home_room_datas
+            .iter()
+            .filter_map(|e| system_data.room_data.get(*e))
+            .map(|rd| rd.name)
+            .collect();
+
+        if self.roads.needs_plan(now) {
+            if let Some((tiles, exits)) = Self::plan_roads(system_data, self.context.outpost_room_data, &home_rooms) {
+                self.roads.set_plan(tiles, now);
+                self.roads.set_exits(exits);
+            }
+        }
+
+        if features.remote_mine.exit_chokes {
+            self.request_exit_chokes(system_data);
+        }
+
+        let mut tiles_by_room: HashMap<RoomName, Vec<Position>> = HashMap::new();
+
+        for tile in self.roads.tiles() {
+            tiles_by_room.entry(tile.pos.room_name()).or_default().push(tile.pos);
+        }
+
+        let mut global_sites_left = MAX_CONSTRUCTION_SITES.saturating_sub(game::construction_sites().keys().count() as u32);
+
+        for (room_name, tiles) in tiles_by_room {
+            let Some(room_data) = system_data.mapping.get_room(&room_name).and_then(|e| system_data.room_data.get(e)) else {
+                continue;
+            };
+
+            // Out of view there is nothing to check or place against.
+            let (Some(structures), Some(sites)) = (room_data.get_structures(), room_data.get_construction_sites()) else {
+                continue;
+            };
+
+            let roads: HashSet<Position> = structures.roads().iter().map(|r| r.pos()).collect();
+            let site_tiles: HashSet<Position> = sites.iter().map(|s| s.pos()).collect();
+
+            self.roads.observe(room_name, &roads);
+
+            let room_sites_left = (features.construction.max_construction_sites - sites.len() as i32).max(0) as u32;
+
+            let missing = tiles.into_iter().filter(|pos| !roads.contains(pos) && !site_tiles.contains(pos));
+
+            for pos in missing.take(room_sites_left.min(global_sites_left) as usize) {
+                match pos.create_construction_site(StructureType::Road, None) {
+                    Ok(()) => global_sites_left -= 1,
+                    Err(err) => debug!("Failed to place remote road site at {:?}: {:?}", pos, err),
+                }
+            }
+        }
+    }
+
+    /// File the choke inside each home exit the roads leave by with the home
+    /// room's construction mission, once the home room is developed enough to
+    /// spend on it.
+    fn request_exit_chokes(&self, system_data: &mut MissionExecutionSystemData) {
+        for exit in self.roads.exits() {
+            let home_level = game::rooms()
+                .get(exit.room_name())
+                .and_then(|room| room.controller())
+                .map(|controller| controller.level())
+                .unwrap_or(0);
+            if home_level < EXIT_CHOKE_MIN_RCL {
+                continue;
+            }
+
+            let Some(terrain) = game::map::get_room_terrain(exit.room_name()) else {
+                continue;
+            };
+            let is_wall = |x: u8, y: u8| terrain.get(x, y) == Terrain::Wall;
+
+            if let Some(choke) = exit_choke(is_wall, (exit.x().u8(), exit.y().u8())) {
+                let tiles = choke.into_iter().filter_map(|(x, y)| {
+                    Some(Position::new(
+                        RoomCoordinate::new(x).ok()?,
+                        RoomCoordinate::new(y).ok()?,
+                        exit.room_name(),
+                    ))
+                });
+                system_data.exit_chokes.request(tiles);
+            }
+        }
+    }
+
+    /// Road tiles from the home storage nearest the outpost to each of its
+    /// sources, ending beside the source's container where one stands, and
+    /// the home exits those roads leave by. `None` when a path could not be
+    /// found this time.
+    fn plan_roads(
+        system_data: &mut MissionExecutionSystemData,
+        outpost_room_data: Entity,
+        home_rooms: &[RoomName],
+    ) -> Option<(Vec<Position>, Vec<Position>)> {
+        let outpost_room_data = system_data.room_data.get(outpost_room_data)?;
+        let outpost_room = outpost_room_data.name;
+
+        let storage = home_rooms
+            .iter()
+            .filter_map(|name| system_data.mapping.get_room(name).and_then(|e| system_data.room_data.get(e)))
+            .filter_map(|rd| rd.get_structures()?.storages().first().map(|s| s.pos()))
+            .min_by_key(|pos| game::map::get_room_linear_distance(pos.room_name(), outpost_room, false))?;
+
+        let containers: Vec<Position> = outpost_room_data
+            .get_structures()
+            .map(|s| s.containers().iter().map(|c| c.pos()).collect())
+            .unwrap_or_default();
+
+        let targets: Vec<Position> = outpost_room_data
+            .get_static_visibility_data()?
+            .sources()
+            .iter()
+            .map(|source| {
+                containers
+                    .iter()
+                    .copied()
+                    .find(|container| container.in_range_to(source.pos(), 1))
+                    .unwrap_or(source.pos())
+            })
+            .collect();
+
+        let paths = targets
+            .into_iter()
+            .map(|target| system_data.pathfinder.road_path(storage, target, 1))
+            .collect::<Option<Vec<_>>>()?;
+
+        let exits = home_exits(&paths, home_rooms);
+
+        Some((road_tiles(paths, home_rooms), exits))
+    }
+
+    fn road_status(&self) -> Option<String> {
+        self.roads
+            .completion()
+            .map(|completion| format!("roads {:.0}%", completion * 100.0))
+    }
+
+    /// Whether the children still have creeps out.
+    fn has_creeps(&self, system_data: &MissionExecutionSystemData) -> bool {
+        self.state.get_children().into_iter().any(|child| {
+            system_data
+                .missions
+                .get(child)
+                .is_some_and(|mission| !mission.as_mission().get_creeps().is_empty())
+        })
+    }
+
+    fn invader_status(&self) -> Option<String> {
+        match self.invader_watch.countdown(game::time())? {
+            0 => Some("invaders due".to_string()),
+            ticks => Some(format!("invaders in {}", ticks)),
+        }
+    }
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl Mission for MiningOutpostMission {
+    impl_mission_pause!();
+
     fn get_owner(&self) -> &Option<Entity> {
         &self.owner
     }
@@ -410,6 +913,10 @@ impl Mission for MiningOutpostMission {
         Some(self.context.outpost_room_data)
     }
 
+    fn data_age_limits(&self) -> Option<DataAgeLimits> {
+        Some(OUTPOST_DATA_AGE_LIMITS)
+    }
+
     fn get_children(&self) -> Vec<Entity> {
         self.state.get_children()
     }
@@ -419,20 +926,37 @@ impl Mission for MiningOutpostMission {
     }
 
     fn describe_state(&self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> String {
-        self.state.describe_state(system_data, mission_entity, &self.context)
+        let state = self.state.describe_state(system_data, mission_entity, &self.context);
+
+        std::iter::once(state)
+            .chain(self.winding_down.then(|| "winding down".to_string()))
+            .chain(self.road_status())
+            .chain(self.invader_status())
+            .collect::<Vec<_>>()
+            .join(" - ")
     }
 
     fn summarize(&self) -> crate::visualization::SummaryContent {
-        crate::visualization::SummaryContent::Text(format!("Mining Outpost - {}", self.state.status_description()))
+        let status = self.state.status_description();
+
+        crate::visualization::SummaryContent::Text(
+            ["Mining Outpost".to_string(), status]
+                .into_iter()
+                .chain(self.winding_down.then(|| "winding down".to_string()))
+                .chain(self.road_status())
+                .chain(self.invader_status())
+                .collect::<Vec<_>>()
+                .join(" - "),
+        )
     }
 
-    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<(), String> {
+    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<(), MissionError> {
         self.context
             .home_room_datas
             .retain(|entity| system_data.room_data.get(*entity).map(is_valid_home_room).unwrap_or(false));
 
         if self.context.home_room_datas.is_empty() {
-            return Err("No home rooms available for mining outpost".to_owned());
+            return Err(MissionError::new(MissionFailure::NoHomeRooms, "No home rooms available for mining outpost"));
         }
 
         self.state.gather_data(system_data, mission_entity);
@@ -440,13 +964,63 @@ impl Mission for MiningOutpostMission {
         Ok(())
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, String> {
+    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, MissionError> {
         crate::machine_tick::run_state_machine_result(&mut self.state, "MiningOutpostMission", |state| {
             state.tick(system_data, mission_entity, &mut self.context)
         })?;
 
+        if self.winding_down {
+            self.stop_spawning(system_data);
+
+            if !self.has_creeps(system_data) {
+                if let Some(room_data) = system_data.room_data.get(self.context.outpost_room_data) {
+                    info!("Mining outpost wound down, no creeps left. Room: {}", room_data.name);
+                }
+
+                return Ok(MissionResult::Success);
+            }
+
+            self.state.visualize(system_data, mission_entity);
+
+            return Ok(MissionResult::Running);
+        }
+
+        self.hold_departures(system_data);
+
+        self.watch_for_invaders(system_data);
+
+        self.maintain_roads(system_data, mission_entity);
+
         self.state.visualize(system_data, mission_entity);
 
         Ok(MissionResult::Running)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn harvest_counts_what_each_source_lost() {
+        assert_eq!(harvested_between(&[3000, 3000], &[2800, 2990]), 210);
+        // A refilled source counts nothing, and a changed source list is skipped.
+        assert_eq!(harvested_between(&[100, 3000], &[3000, 2000]), 1000);
+        assert_eq!(harvested_between(&[], &[3000, 3000]), 0);
+    }
+
+    #[test]
+    fn goal_countdown_follows_the_harvest_rate() {
+        // Two reserved sources: 20 energy per tick.
+        assert_eq!(ticks_until_goal(70_000, INVADER_GOAL_MIN, 20.0), Some(150));
+        assert_eq!(ticks_until_goal(INVADER_GOAL_MIN, INVADER_GOAL_MIN, 20.0), Some(0));
+        assert_eq!(ticks_until_goal(0, INVADER_GOAL_MIN, 0.0), None);
+    }
+
+    #[test]
+    fn defender_is_posted_by_distance_not_hundreds_early() {
+        assert_eq!(defender_lead(1), 250);
+        assert!(defender_lead(3) < defender_lead(4));
+        assert!(defender_lead(2) < CREEP_LIFE_TIME / 4);
+    }
+}
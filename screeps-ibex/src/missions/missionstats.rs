@@ -0,0 +1,251 @@
+//! Mission outcomes per mission type: completions, failures by
+//! [`MissionFailure`], and how long the missions lived.
+//!
+//! The counts are kept in `Memory._mission_stats` so they survive VM
+//! restarts, and exported with the live stats. Lifetimes are measured from
+//! the tick a mission was first seen. Start ticks are saved alongside the
+//! counts, keyed by the mission's serialize marker, so a restart doesn't
+//! lose them; a mission already alive when tracking first began has no
+//! known start and is counted without a lifetime.
+//!
+//! Every terminal state also logs one `[Mission] end` line, so
+//! `[Mission] end type=Claim outcome=failed` is enough to find them in the
+//! console.
+
+use super::missionsystem::*;
+use log::*;
+use serde::{Deserialize, Serialize};
+use specs::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+
+const MEMORY_PATH: &str = "_mission_stats";
+
+/// Outcome counts for one mission type.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MissionTypeStats {
+    pub completed: u32,
+    /// Failures keyed by [`MissionFailure::as_str`].
+    pub failed: BTreeMap<String, u32>,
+    /// Summed lifetime of the ended missions whose start was seen.
+    pub lifetime_ticks: u64,
+    /// How many ended missions `lifetime_ticks` covers.
+    pub lifetimes: u32,
+}
+
+impl MissionTypeStats {
+    pub fn failures(&self) -> u32 {
+        self.failed.values().sum()
+    }
+
+    pub fn average_lifetime(&self) -> Option<f64> {
+        (self.lifetimes > 0).then(|| self.lifetime_ticks as f64 / self.lifetimes as f64)
+    }
+}
+
+/// When a live mission was first seen, by its serialize marker id. `since`
+/// is `None` for missions that were already alive when tracking began.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct MissionStart {
+    mission: u64,
+    since: Option<u32>,
+}
+
+/// The form kept in Memory.
+#[derive(Serialize, Deserialize)]
+struct SavedMissionStats {
+    types: BTreeMap<String, MissionTypeStats>,
+    started: Vec<MissionStart>,
+}
+
+/// World resource: persisted per-type outcome counts plus the start tick of
+/// each live mission.
+#[derive(Default)]
+pub struct MissionStats {
+    types: BTreeMap<String, MissionTypeStats>,
+    /// First tick each live mission was seen, by marker id.
+    started: BTreeMap<u64, Option<u32>>,
+    /// Marker id of each live mission, rebuilt every tick.
+    live: HashMap<Entity, u64>,
+    tracking: bool,
+    dirty: bool,
+}
+
+impl MissionStats {
+    /// Read the persisted counts and start ticks back from Memory. Memory
+    /// written before the start ticks were kept holds just the counts.
+    pub fn load() -> MissionStats {
+        let value = crate::memory_helper::path_get(MEMORY_PATH);
+
+        match serde_wasm_bindgen::from_value::<SavedMissionStats>(value.clone()) {
+            Ok(saved) => MissionStats::restore(saved),
+            Err(_) => MissionStats {
+                types: serde_wasm_bindgen::from_value(value).unwrap_or_default(),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn restore(saved: SavedMissionStats) -> MissionStats {
+        MissionStats {
+            types: saved.types,
+            started: saved.started.into_iter().map(|s| (s.mission, s.since)).collect(),
+            tracking: true,
+            ..Default::default()
+        }
+    }
+
+    fn saved(&self) -> SavedMissionStats {
+        SavedMissionStats {
+            types: self.types.clone(),
+            started: self
+                .started
+                .iter()
+                .map(|(mission, since)| MissionStart {
+                    mission: *mission,
+                    since: *since,
+                })
+                .collect(),
+        }
+    }
+
+    /// Write the counts to Memory if a mission started or ended since the
+    /// last save.
+    pub fn save(&mut self) {
+        if !self.dirty {
+            return;
+        }
+
+        let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+
+        match self.saved().serialize(&serializer) {
+            Ok(value) => crate::memory_helper::path_set(MEMORY_PATH, value),
+            Err(err) => warn!("Failed to save mission stats: {}", err),
+        }
+
+        self.dirty = false;
+    }
+
+    /// Note the live missions this tick, with their marker ids: new ones
+    /// start their lifetime now, and deleted ones that never reported an end
+    /// are dropped.
+    pub fn track(&mut self, missions: impl Iterator<Item = (Entity, u64)>, now: u32) {
+        let start = self.tracking.then_some(now);
+        let mut started = BTreeMap::new();
+
+        self.live.clear();
+
+        for (mission, marker) in missions {
+            started.insert(marker, self.started.get(&marker).copied().unwrap_or(start));
+            self.live.insert(mission, marker);
+        }
+
+        if started != self.started {
+            self.started = started;
+            self.dirty = true;
+        }
+
+        self.tracking = true;
+    }
+
+    /// Count a mission's end and log it. `Ok` is a completion.
+    pub fn record_end(&mut self, mission: Entity, type_name: &str, outcome: Result<(), &MissionError>, now: u32) {
+        let lifetime = self
+            .live
+            .remove(&mission)
+            .and_then(|marker| self.started.remove(&marker))
+            .flatten()
+            .map(|start| now.saturating_sub(start));
+        let stats = self.types.entry(type_name.to_string()).or_default();
+
+        match outcome {
+            Ok(()) => stats.completed += 1,
+            Err(error) => *stats.failed.entry(error.reason.as_str().to_string()).or_default() += 1,
+        }
+
+        if let Some(lifetime) = lifetime {
+            stats.lifetime_ticks += lifetime as u64;
+            stats.lifetimes += 1;
+        }
+
+        self.dirty = true;
+
+        let lifetime = lifetime.map(|l| l.to_string()).unwrap_or_else(|| "?".to_string());
+
+        match outcome {
+            Ok(()) => info!(
+                "[Mission] end type={} entity={} outcome=complete lifetime={}",
+                type_name,
+                mission.id(),
+                lifetime
+            ),
+            Err(error) => info!(
+                "[Mission] end type={} entity={} outcome=failed reason={} lifetime={} message=\"{}\"",
+                type_name,
+                mission.id(),
+                error.reason.as_str(),
+                lifetime,
+                error.message
+            ),
+        }
+    }
+
+    pub fn types(&self) -> &BTreeMap<String, MissionTypeStats> {
+        &self.types
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lifetimes_count_only_missions_seen_starting() {
+        let mut world = World::new();
+        let (old, new) = (world.create_entity().build(), world.create_entity().build());
+        let mut stats = MissionStats::default();
+
+        stats.track([(old, 1)].into_iter(), 100);
+        stats.track([(old, 1), (new, 2)].into_iter(), 101);
+
+        stats.record_end(old, "Scout", Ok(()), 500);
+        stats.record_end(new, "Scout", Err(&MissionError::new(MissionFailure::Timeout, "gave up")), 401);
+
+        let scout = &stats.types()["Scout"];
+        assert_eq!(scout.completed, 1);
+        assert_eq!(scout.failures(), 1);
+        assert_eq!(scout.failed["timeout"], 1);
+        assert_eq!(scout.average_lifetime(), Some(300.0));
+    }
+
+    /// A restart rebuilds the world with new entities but the same markers.
+    #[test]
+    fn start_ticks_survive_a_restart() {
+        let mut world = World::new();
+        let (before, after) = (world.create_entity().build(), world.create_entity().build());
+        let mut stats = MissionStats::default();
+
+        stats.track([].into_iter(), 100);
+        stats.track([(before, 7)].into_iter(), 200);
+
+        let mut restarted = MissionStats::restore(stats.saved());
+        restarted.track([(after, 7)].into_iter(), 250);
+        restarted.record_end(after, "Claim", Ok(()), 300);
+
+        assert_eq!(restarted.types()["Claim"].average_lifetime(), Some(100.0));
+    }
+
+    #[test]
+    fn untyped_errors_count_as_other() {
+        let mut world = World::new();
+        let (typed, untyped) = (world.create_entity().build(), world.create_entity().build());
+        let mut stats = MissionStats::default();
+
+        let missing = MissionError::new(MissionFailure::MissingRoomData, "Expected room data");
+        stats.record_end(typed, "Reserve", Err(&missing), 10);
+        stats.record_end(untyped, "Reserve", Err(&MissionError::from("Expected room data")), 10);
+
+        let reserve = &stats.types()["Reserve"];
+        assert_eq!(reserve.failed["missing_room_data"], 1);
+        assert_eq!(reserve.failed["other"], 1);
+    }
+}
@@ -6,22 +6,28 @@ use crate::creep::*;
 use crate::entitymappingsystem::EntityMappingData;
 use crate::expansion::ExpansionAvoidance;
 use crate::jobs::data::*;
+use crate::jobs::recycle::RecycleJob;
 use crate::military::boostqueue::*;
 use crate::military::economy::*;
 use crate::military::objective_queue::CombatObjectiveQueue;
 use crate::military::squad::SquadContext;
 use crate::military::threatmap::RoomThreatData;
+use crate::mission_map::{MissionHealth, MissionMapState};
 use crate::pathing::pathfinderservice::PathfinderService;
 use crate::repairqueue::*;
 use crate::room::data::*;
 use crate::room::roomplansystem::*;
 use crate::room::visibilitysystem::*;
+use crate::serialize::SerializeMarker;
 use crate::spawnsystem::*;
 use crate::transfer::ordersystem::*;
 use crate::transfer::transfersystem::*;
-use crate::visualization::SummaryContent;
+use crate::visualization::{SummaryContent, VisualizationData};
 use log::*;
+use screeps::*;
 use specs::prelude::*;
+use specs::saveload::Marker;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 
 #[derive(SystemData)]
 pub struct MissionSystemData<'a> {
@@ -31,6 +37,7 @@ pub struct MissionSystemData<'a> {
     room_plan_data: ReadStorage<'a, RoomPlanData>,
     room_plan_queue: Write<'a, RoomPlanQueue>,
     entities: Entities<'a>,
+    markers: ReadStorage<'a, SerializeMarker>,
     spawn_queue: Write<'a, SpawnQueue>,
     creep_owner: ReadStorage<'a, CreepOwner>,
     creep_spawning: ReadStorage<'a, CreepSpawning>,
@@ -46,12 +53,38 @@ pub struct MissionSystemData<'a> {
     pathfinder: Write<'a, PathfinderService>,
     governor: Read<'a, GovernorSnapshot>,
     features: Read<'a, crate::features::Features>,
+    feature_overrides: Read<'a, crate::features::FeatureOverrides>,
     squad_contexts: WriteStorage<'a, SquadContext>,
     mapping: Read<'a, EntityMappingData>,
     threat_data: ReadStorage<'a, RoomThreatData>,
+    defense_postures: ReadStorage<'a, crate::military::posture::DefensePosture>,
     expansion_avoidance: Write<'a, ExpansionAvoidance>,
     combat_objective_queue: Write<'a, CombatObjectiveQueue>,
     salvage_breach_tracker: Write<'a, crate::missions::salvage::SalvageBreachTracker>,
+    border_watch: Read<'a, crate::military::borderwatch::BorderWatch>,
+    energy_emergency: Write<'a, super::emergency::EnergyEmergency>,
+    overflow_sinks: Write<'a, super::overflow::OverflowSinks>,
+    breach_forecasts: Write<'a, crate::military::threatmap::BreachForecasts>,
+    escort_request: Write<'a, crate::military::escort::EscortRequest>,
+    hauler_pools: Write<'a, super::haul::HaulerPools>,
+    upgrade_seating: Write<'a, super::upgrade::UpgradeSeating>,
+    emergency_ramparts: Write<'a, super::wall_repair::EmergencyRamparts>,
+    exit_chokes: Write<'a, super::exitchoke::ExitChokes>,
+    contested_reservations: Write<'a, super::reserve::ContestedReservations>,
+    stale_missions: Write<'a, StaleMissions>,
+    mission_order: Write<'a, MissionOrder>,
+    consolidation: Read<'a, crate::features::ConsolidationFeatures>,
+    consolidation_volume: Write<'a, super::terminal::ConsolidationVolume>,
+    terminal_deals: Write<'a, super::terminal::TerminalDeals>,
+    energy_arbitrage_volume: Write<'a, crate::transfer::energyarbitrage::EnergyArbitrageVolume>,
+    resource_overview: Read<'a, crate::resource_overview::ResourceOverview>,
+    upgrade_focus: Read<'a, crate::upgrade_focus::UpgradeFocus>,
+    power_requests: Write<'a, crate::powercreepsystem::PowerRequests>,
+    ledger: Write<'a, crate::ledger::ResourceLedger>,
+    intent_recorder: Write<'a, crate::intents::IntentRecorder>,
+    cpu_accounting: Write<'a, crate::cpu_accounting::CpuAccounting>,
+    mission_stats: Write<'a, super::missionstats::MissionStats>,
+    visualization_data: Option<Write<'a, VisualizationData>>,
 }
 
 pub struct MissionExecutionSystemData<'a, 'b> {
@@ -78,11 +111,15 @@ pub struct MissionExecutionSystemData<'a, 'b> {
     pub governor: GovernorSnapshot,
     /// The tick's feature flags (Copy — read freely).
     pub features: crate::features::Features,
+    /// Per-room feature overrides; resolve with `features.for_room`.
+    pub feature_overrides: &'b crate::features::FeatureOverrides,
     pub squad_contexts: &'b mut WriteStorage<'a, SquadContext>,
     pub mapping: &'b Read<'a, EntityMappingData>,
     /// Per-room threat intelligence (`military::threatmap`). Used by the colony
     /// lifecycle's no-win abort predicate (ADR 0017).
     pub threat_data: &'b ReadStorage<'a, RoomThreatData>,
+    /// Owned rooms' defense posture (`military::posture`), on the room entity.
+    pub defense_postures: &'b ReadStorage<'a, crate::military::posture::DefensePosture>,
     /// Avoid-cooldown map for abandoned/failed claim targets (ADR 0017).
     pub expansion_avoidance: &'b mut ExpansionAvoidance,
     /// The combat objective queue (ADR 0008 §2 / P2.G1). Missions that are
@@ -92,6 +129,174 @@ pub struct MissionExecutionSystemData<'a, 'b> {
     /// stamps the v1 breach `Dismantle` pos it emits here so its withdraw is pos-scoped
     /// to its own objective (never clobbers war's InvaderCore `Dismantle`). Not serialized.
     pub salvage_breach_tracker: &'b mut crate::missions::salvage::SalvageBreachTracker,
+    /// Per-room energy ledger; missions report tower, terminal-fee and link-loss spend.
+    pub ledger: &'b mut crate::ledger::ResourceLedger,
+    /// The tick's intent counts and soft caps; structure intents go through it.
+    pub intent_recorder: &'b mut crate::intents::IntentRecorder,
+    /// Hostiles predicted to cross into our rooms (the war operation's border watch).
+    pub border_watch: &'b crate::military::borderwatch::BorderWatch,
+    /// Colonies whose economy has collapsed; see `missions::emergency`.
+    pub energy_emergency: &'b mut super::emergency::EnergyEmergency,
+    /// Extra energy consumers each overflowing colony runs; see `missions::overflow`.
+    pub overflow_sinks: &'b mut super::overflow::OverflowSinks,
+    /// Where attackers would break into each besieged colony; see `military::threatmap`.
+    pub breach_forecasts: &'b mut crate::military::threatmap::BreachForecasts,
+    /// Escorts haul missions want for their haulers; see `military::escort`.
+    pub escort_request: &'b mut crate::military::escort::EscortRequest,
+    /// Remote haul demand filed with each room's hauler pool; see `missions::haul`.
+    pub hauler_pools: &'b mut super::haul::HaulerPools,
+    /// This tick's upgrader seats; see `missions::upgrade`.
+    pub upgrade_seating: &'b mut super::upgrade::UpgradeSeating,
+    /// This tick's emergency rampart per breached room; see `missions::wall_repair`.
+    pub emergency_ramparts: &'b mut super::wall_repair::EmergencyRamparts,
+    /// Remote haul exit chokes filed for each home room; see `missions::exitchoke`.
+    pub exit_chokes: &'b mut super::exitchoke::ExitChokes,
+    /// Remotes another player has reserved, flagged for the war operation; see `missions::reserve`.
+    pub contested_reservations: &'b mut super::reserve::ContestedReservations,
+    /// Missions whose room data is older than their [`DataAgeLimits`] allow.
+    pub stale_missions: &'b StaleMissions,
+    /// The resource hub terminals ship their surplus to.
+    pub consolidation: &'b crate::features::ConsolidationFeatures,
+    /// What each room has shipped to the hub, for stats.
+    pub consolidation_volume: &'b mut super::terminal::ConsolidationVolume,
+    /// Market deals the order queue filed for each room's terminal schedule.
+    pub terminal_deals: &'b mut super::terminal::TerminalDeals,
+    /// Energy arbitrage totals; terminal missions record the deals they make.
+    pub energy_arbitrage_volume: &'b mut crate::transfer::energyarbitrage::EnergyArbitrageVolume,
+    /// The empire's holdings and shortfalls; see `resource_overview`.
+    pub resource_overview: &'b crate::resource_overview::ResourceOverview,
+    /// Controller projections and the room surplus energy is focused on; see `upgrade_focus`.
+    pub upgrade_focus: &'b crate::upgrade_focus::UpgradeFocus,
+    /// Powers the room's operator should use this tick.
+    pub power_requests: &'b mut crate::powercreepsystem::PowerRequests,
+}
+
+/// How old a mission lets its room's dynamic visibility data get. Past
+/// `pause_after` ticks the mission system asks for the room to be seen
+/// and the mission holds its spawning; past `fail_after` it fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DataAgeLimits {
+    pub pause_after: u32,
+    pub fail_after: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DataAge {
+    Fresh,
+    Stale,
+    Expired,
+}
+
+impl DataAgeLimits {
+    /// Judge data `age` ticks old; `None` for a room never seen, which is
+    /// stale but has no age to expire by.
+    pub fn check(&self, age: Option<u32>) -> DataAge {
+        match age {
+            Some(age) if age > self.fail_after => DataAge::Expired,
+            Some(age) if age <= self.pause_after => DataAge::Fresh,
+            _ => DataAge::Stale,
+        }
+    }
+}
+
+/// The missions found stale by this tick's pre-run. Rebuilt every tick, so a
+/// mission resumes spawning the tick after its room is seen again.
+#[derive(Default)]
+pub struct StaleMissions {
+    missions: HashSet<Entity>,
+}
+
+impl StaleMissions {
+    pub fn is_stale(&self, mission: Entity) -> bool {
+        self.missions.contains(&mission)
+    }
+}
+
+/// Order `missions` (each with its owner and children) so that every mission
+/// comes after the missions that own it. Links to entities outside the set
+/// (operations, missions created this tick) impose nothing. Ties go to the
+/// lower entity, so the order depends only on the missions and their links;
+/// missions caught in an ownership cycle come last, in entity order.
+pub fn execution_order<K: Copy + Ord>(missions: &[(K, Option<K>, Vec<K>)]) -> Vec<K> {
+    let members: BTreeSet<K> = missions.iter().map(|(mission, _, _)| *mission).collect();
+    let mut parents: BTreeMap<K, BTreeSet<K>> = members.iter().map(|mission| (*mission, BTreeSet::new())).collect();
+    let mut children: BTreeMap<K, BTreeSet<K>> = members.iter().map(|mission| (*mission, BTreeSet::new())).collect();
+
+    let mut link = |parent: K, child: K| {
+        if parent != child && members.contains(&parent) && members.contains(&child) {
+            parents.entry(child).or_default().insert(parent);
+            children.entry(parent).or_default().insert(child);
+        }
+    };
+
+    for (mission, owner, owned) in missions {
+        if let Some(owner) = owner {
+            link(*owner, *mission);
+        }
+        for child in owned {
+            link(*mission, *child);
+        }
+    }
+
+    let mut ready: BTreeSet<K> = parents
+        .iter()
+        .filter(|(_, waiting_on)| waiting_on.is_empty())
+        .map(|(mission, _)| *mission)
+        .collect();
+    let mut order = Vec::with_capacity(members.len());
+
+    while let Some(next) = ready.pop_first() {
+        order.push(next);
+
+        for child in children.get(&next).into_iter().flatten() {
+            if let Some(waiting_on) = parents.get_mut(child) {
+                waiting_on.remove(&next);
+                if waiting_on.is_empty() {
+                    ready.insert(*child);
+                }
+            }
+        }
+    }
+
+    if order.len() < members.len() {
+        let placed: BTreeSet<K> = order.iter().copied().collect();
+        order.extend(members.into_iter().filter(|mission| !placed.contains(mission)));
+    }
+
+    order
+}
+
+/// The order both mission systems run missions in: owners before the
+/// missions they own, so a parent refreshes shared state (the supply
+/// structure cache, say) before its children read it in the same pass. Built
+/// by [`execution_order`] and kept until the set of missions changes.
+#[derive(Default)]
+pub struct MissionOrder {
+    missions: Vec<Entity>,
+    order: Vec<Entity>,
+}
+
+impl MissionOrder {
+    /// This tick's execution order, rebuilt if missions were added or removed since it was last built.
+    pub fn refresh(&mut self, entities: &Entities, missions: &WriteStorage<MissionData>) -> Vec<Entity> {
+        let current: Vec<Entity> = (entities, missions).join().map(|(entity, _)| entity).collect();
+
+        if current != self.missions {
+            let links: Vec<(Entity, Option<Entity>, Vec<Entity>)> = (entities, missions)
+                .join()
+                .map(|(entity, mission_data)| {
+                    let mission = mission_data.as_mission();
+
+                    (entity, *mission.get_owner(), mission.get_children())
+                })
+                .collect();
+
+            self.order = execution_order(&links);
+            self.missions = current;
+        }
+
+        self.order.clone()
+    }
 }
 
 /// Queue a mission for cleanup via the `EntityCleanupQueue`.
@@ -141,11 +346,200 @@ fn queue_mission_abort(system_data: &mut MissionExecutionSystemData, mission_ent
     }
 }
 
+/// Released creeps with no more ticks to live than this are recycled rather than rehomed.
+const REHOME_MIN_TICKS_TO_LIVE: u32 = 300;
+
+/// Furthest (linear room distance) a released creep may be from the room of a spawn request it takes over.
+const REHOME_MAX_ROOM_DISTANCE: u32 = 2;
+
+/// What [`release_creep`] does with a creep its mission no longer needs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecyclePolicy {
+    /// Recycle it at the nearest spawn.
+    Recycle,
+    /// Hand it to a pending spawn request for a compatible body when it has the life left to be
+    /// useful, recycling it otherwise.
+    Rehome,
+}
+
+/// Release a creep the calling mission no longer needs, instead of leaving it to idle out its life.
+/// The caller drops the creep from its own tracking.
+pub fn release_creep(system_data: &mut MissionExecutionSystemData, creep_entity: Entity, policy: RecyclePolicy) {
+    let name = match (
+        system_data.creep_owner.get(creep_entity),
+        system_data.creep_spawning.get(creep_entity),
+    ) {
+        (Some(owner), _) => owner.owner.resolve().map(|creep| creep.name()),
+        (None, Some(spawning)) => Some(spawning.name.clone()),
+        (None, None) => None,
+    };
+
+    let creep = match name.and_then(|name| game::creeps().get(name)) {
+        Some(creep) => creep,
+        None => return,
+    };
+
+    if policy == RecyclePolicy::Rehome && rehome_creep(system_data, creep_entity, &creep) {
+        return;
+    }
+
+    system_data.updater.insert(creep_entity, JobData::Recycle(RecycleJob::new()));
+}
+
+/// Give a released creep to the best pending spawn request nearby that its body can fill. The request's
+/// callback builds the requester's entity for the live creep, and the old entity goes through cleanup.
+fn rehome_creep(system_data: &mut MissionExecutionSystemData, creep_entity: Entity, creep: &Creep) -> bool {
+    // A spawning creep reports no ticks to live yet; it has its whole life ahead of it.
+    if creep.ticks_to_live().unwrap_or(CREEP_LIFE_TIME) <= REHOME_MIN_TICKS_TO_LIVE {
+        return false;
+    }
+
+    let body: Vec<Part> = creep.body().iter().map(|p| p.part()).collect();
+    let creep_room = creep.pos().room_name();
+    let room_data = &*system_data.room_data;
+
+    let taken = system_data.spawn_queue.take_request(game::time(), |room, request| {
+        room_data
+            .get(room)
+            .is_some_and(|data| game::map::get_room_linear_distance(creep_room, data.name, false) <= REHOME_MAX_ROOM_DISTANCE)
+            && is_compatible_body(&body, request.body())
+    });
+
+    match taken {
+        Some((_, request)) => {
+            let name = creep.name();
+
+            info!("Rehoming released creep {} as: {}", name, request.description());
+
+            system_data.cleanup_queue.delete_creep(creep_entity);
+            request.fulfill(
+                &SpawnQueueExecutionSystemData {
+                    updater: system_data.updater,
+                },
+                &name,
+            );
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Implements the [`Mission`] pause accessors over the mission's
+/// `paused: bool` field. Every mission carries that field as its LAST
+/// serialized member (`WORLD_FORMAT_VERSION` 26 appends it to old saves).
+macro_rules! impl_mission_pause {
+    () => {
+        fn is_paused(&self) -> bool {
+            self.paused
+        }
+
+        fn set_paused(&mut self, paused: bool) {
+            self.paused = paused;
+        }
+    };
+}
+
+pub(crate) use impl_mission_pause;
+
 pub enum MissionResult {
     Running,
     Success,
 }
 
+/// Why a mission ended without succeeding, for the per-type outcome counts
+/// in [`MissionStats`](super::missionstats::MissionStats).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MissionFailure {
+    /// The room data of the mission's room or a home room is gone.
+    MissingRoomData,
+    /// A room or object the mission needs is out of sight.
+    NoVisibility,
+    /// No home room is left to spawn from.
+    NoHomeRooms,
+    /// The home rooms can't afford or fit the creeps the mission needs.
+    SpawnStarved,
+    /// The target is gone or no longer fits the mission (owned, reserved,
+    /// missing structures).
+    TargetInvalid,
+    /// The mission gave up after running out of time or attempts.
+    Timeout,
+    /// Hostiles made the target too dangerous, or killed the mission's creeps.
+    Hostile,
+    /// A feature flag turned the mission off.
+    Disabled,
+    /// The integrity check deleted the mission as orphaned.
+    IntegrityRepair,
+    Other,
+}
+
+impl MissionFailure {
+    pub const ALL: [MissionFailure; 10] = [
+        MissionFailure::MissingRoomData,
+        MissionFailure::NoVisibility,
+        MissionFailure::NoHomeRooms,
+        MissionFailure::SpawnStarved,
+        MissionFailure::TargetInvalid,
+        MissionFailure::Timeout,
+        MissionFailure::Hostile,
+        MissionFailure::Disabled,
+        MissionFailure::IntegrityRepair,
+        MissionFailure::Other,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MissionFailure::MissingRoomData => "missing_room_data",
+            MissionFailure::NoVisibility => "no_visibility",
+            MissionFailure::NoHomeRooms => "no_home_rooms",
+            MissionFailure::SpawnStarved => "spawn_starved",
+            MissionFailure::TargetInvalid => "target_invalid",
+            MissionFailure::Timeout => "timeout",
+            MissionFailure::Hostile => "hostile",
+            MissionFailure::Disabled => "disabled",
+            MissionFailure::IntegrityRepair => "integrity_repair",
+            MissionFailure::Other => "other",
+        }
+    }
+}
+
+/// Why a mission failed: the [`MissionFailure`] it is counted under and the
+/// message that is logged. Missions say why they fail; a plain string that
+/// reaches a mission's `?` has no reason and counts as
+/// [`MissionFailure::Other`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MissionError {
+    pub reason: MissionFailure,
+    pub message: String,
+}
+
+impl MissionError {
+    pub fn new(reason: MissionFailure, message: impl Into<String>) -> MissionError {
+        MissionError {
+            reason,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for MissionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.message, self.reason.as_str())
+    }
+}
+
+impl From<String> for MissionError {
+    fn from(message: String) -> MissionError {
+        MissionError::new(MissionFailure::Other, message)
+    }
+}
+
+impl From<&str> for MissionError {
+    fn from(message: &str) -> MissionError {
+        MissionError::from(message.to_owned())
+    }
+}
+
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 pub trait Mission {
     fn get_owner(&self) -> &Option<Entity>;
@@ -185,19 +579,48 @@ pub trait Mission {
         Vec::new()
     }
 
+    /// Operator pause flag (`console` commands). Provided by
+    /// [`impl_mission_pause!`].
+    fn is_paused(&self) -> bool;
+
+    fn set_paused(&mut self, paused: bool);
+
+    /// Freeze the mission without deleting it: `RunMissionSystem` skips
+    /// `run_mission` while `pre_run_mission` keeps its data fresh.
+    fn pause(&mut self) {
+        self.set_paused(true);
+    }
+
+    fn resume(&mut self) {
+        self.set_paused(false);
+    }
+
     fn describe_state(&self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> String;
 
+    /// How stale [`Self::get_room`]'s data may get before the mission stops
+    /// spawning toward it; see [`StaleMissions`]. `None` opts out.
+    fn data_age_limits(&self) -> Option<DataAgeLimits> {
+        None
+    }
+
     /// Produce a structured summary for the visualization overlay.
     /// Reads only `self`; no system data required. Override in concrete missions for richer detail.
     fn summarize(&self) -> SummaryContent {
         SummaryContent::Text("Mission".to_string())
     }
 
-    fn pre_run_mission(&mut self, _system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), String> {
+    fn pre_run_mission(&mut self, _system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), MissionError> {
         Ok(())
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, String>;
+    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, MissionError>;
+}
+
+/// Record a mission's state for the map overlay (`visualize.mission_map`).
+fn record_map_state(visualization_data: &mut Option<Write<VisualizationData>>, entity: Entity, state: String, health: MissionHealth) {
+    if let Some(viz) = visualization_data.as_deref_mut() {
+        viz.map.mission_states.insert(entity, MissionMapState { state, health });
+    }
 }
 
 pub struct PreRunMissionSystem;
@@ -207,9 +630,50 @@ impl<'a> System<'a> for PreRunMissionSystem {
     type SystemData = MissionSystemData<'a>;
 
     fn run(&mut self, mut data: Self::SystemData) {
-        let mission_entities: Vec<Entity> = (&data.entities, &data.missions).join().map(|(e, _)| e).collect();
+        // Seats are republished by the upgrade missions that run this tick.
+        data.upgrade_seating.clear();
+        // Emergency ramparts are republished by the wall repair missions of breached rooms.
+        data.emergency_ramparts.clear_sites();
+        // Remote haul demand is refiled by the remote haul missions every pre-run.
+        data.hauler_pools.clear();
+        data.stale_missions.missions.clear();
+
+        let mission_entities = data.mission_order.refresh(&data.entities, &data.missions);
+        let now = game::time();
+
+        let marked_missions = mission_entities.iter().filter_map(|e| Some((*e, data.markers.get(*e)?.id())));
+        data.mission_stats.track(marked_missions, now);
 
         for entity in mission_entities {
+            let data_age = data.missions.get(entity).and_then(|mission_data| {
+                let mission = mission_data.as_mission();
+                let limits = mission.data_age_limits()?;
+                let room_data = data.room_data.get(mission.get_room()?)?;
+
+                Some((
+                    room_data.name,
+                    limits.check(room_data.get_dynamic_visibility_data().map(|d| d.age())),
+                ))
+            });
+
+            let expired = match data_age {
+                Some((room_name, DataAge::Stale)) => {
+                    data.visibility.request(VisibilityRequest::new(
+                        room_name,
+                        VISIBILITY_PRIORITY_HIGH,
+                        VisibilityRequestFlags::ALL,
+                    ));
+                    data.stale_missions.missions.insert(entity);
+
+                    None
+                }
+                Some((room_name, DataAge::Expired)) => Some(MissionError::new(
+                    MissionFailure::NoVisibility,
+                    format!("No visibility of {} within the mission's data age limit", room_name),
+                )),
+                _ => None,
+            };
+
             let mut system_data = MissionExecutionSystemData {
                 updater: &data.updater,
                 entities: &data.entities,
@@ -232,21 +696,58 @@ impl<'a> System<'a> for PreRunMissionSystem {
                 pathfinder: &mut data.pathfinder,
                 governor: *data.governor,
                 features: *data.features,
+                feature_overrides: &data.feature_overrides,
                 squad_contexts: &mut data.squad_contexts,
                 mapping: &data.mapping,
                 threat_data: &data.threat_data,
+                defense_postures: &data.defense_postures,
                 expansion_avoidance: &mut data.expansion_avoidance,
                 combat_objective_queue: &mut data.combat_objective_queue,
                 salvage_breach_tracker: &mut data.salvage_breach_tracker,
+                ledger: &mut data.ledger,
+                intent_recorder: &mut data.intent_recorder,
+                border_watch: &data.border_watch,
+                energy_emergency: &mut data.energy_emergency,
+                overflow_sinks: &mut data.overflow_sinks,
+                breach_forecasts: &mut data.breach_forecasts,
+                escort_request: &mut data.escort_request,
+                hauler_pools: &mut data.hauler_pools,
+                upgrade_seating: &mut data.upgrade_seating,
+                emergency_ramparts: &mut data.emergency_ramparts,
+                exit_chokes: &mut data.exit_chokes,
+                contested_reservations: &mut data.contested_reservations,
+                stale_missions: &data.stale_missions,
+                consolidation: &data.consolidation,
+                consolidation_volume: &mut data.consolidation_volume,
+                terminal_deals: &mut data.terminal_deals,
+                energy_arbitrage_volume: &mut data.energy_arbitrage_volume,
+                resource_overview: &data.resource_overview,
+                upgrade_focus: &data.upgrade_focus,
+                power_requests: &mut data.power_requests,
             };
 
             if let Some(mission_data) = data.missions.get(entity) {
                 let mut mission = mission_data.as_mission_mut();
 
-                let cleanup_mission = match mission.pre_run_mission(&mut system_data, entity) {
+                let pre_run_result = match expired {
+                    Some(error) => Err(error),
+                    None => {
+                        let cpu_start = data.cpu_accounting.start();
+                        let pre_run_result = mission.pre_run_mission(&mut system_data, entity);
+                        data.cpu_accounting.finish_mission(cpu_start, mission_data.type_name());
+
+                        pre_run_result
+                    }
+                };
+
+                let cleanup_mission = match pre_run_result {
                     Ok(()) => false,
                     Err(error) => {
-                        info!("Mission pre-run failed, cleaning up. Error: {}", error);
+                        data.mission_stats.record_end(entity, mission_data.type_name(), Err(&error), now);
+
+                        if data.features.visualize.mission_map {
+                            record_map_state(&mut data.visualization_data, entity, error.message, MissionHealth::Failing);
+                        }
 
                         true
                     }
@@ -268,7 +769,10 @@ impl<'a> System<'a> for RunMissionSystem {
     type SystemData = MissionSystemData<'a>;
 
     fn run(&mut self, mut data: Self::SystemData) {
-        let mission_entities: Vec<Entity> = (&data.entities, &data.missions).join().map(|(e, _)| e).collect();
+        // Same order as the pre-run; rebuilt only if missions were created or removed in between.
+        let mission_entities = data.mission_order.refresh(&data.entities, &data.missions);
+        let record_map_states = data.features.visualize.mission_map && data.visualization_data.is_some();
+        let now = game::time();
 
         for entity in mission_entities {
             let mut system_data = MissionExecutionSystemData {
@@ -293,31 +797,133 @@ impl<'a> System<'a> for RunMissionSystem {
                 pathfinder: &mut data.pathfinder,
                 governor: *data.governor,
                 features: *data.features,
+                feature_overrides: &data.feature_overrides,
                 squad_contexts: &mut data.squad_contexts,
                 mapping: &data.mapping,
                 threat_data: &data.threat_data,
+                defense_postures: &data.defense_postures,
                 expansion_avoidance: &mut data.expansion_avoidance,
                 combat_objective_queue: &mut data.combat_objective_queue,
                 salvage_breach_tracker: &mut data.salvage_breach_tracker,
+                ledger: &mut data.ledger,
+                intent_recorder: &mut data.intent_recorder,
+                border_watch: &data.border_watch,
+                energy_emergency: &mut data.energy_emergency,
+                overflow_sinks: &mut data.overflow_sinks,
+                breach_forecasts: &mut data.breach_forecasts,
+                escort_request: &mut data.escort_request,
+                hauler_pools: &mut data.hauler_pools,
+                upgrade_seating: &mut data.upgrade_seating,
+                emergency_ramparts: &mut data.emergency_ramparts,
+                exit_chokes: &mut data.exit_chokes,
+                contested_reservations: &mut data.contested_reservations,
+                stale_missions: &data.stale_missions,
+                consolidation: &data.consolidation,
+                consolidation_volume: &mut data.consolidation_volume,
+                terminal_deals: &mut data.terminal_deals,
+                energy_arbitrage_volume: &mut data.energy_arbitrage_volume,
+                resource_overview: &data.resource_overview,
+                upgrade_focus: &data.upgrade_focus,
+                power_requests: &mut data.power_requests,
             };
 
             if let Some(mission_data) = data.missions.get(entity) {
                 let mut mission = mission_data.as_mission_mut();
 
-                let cleanup_mission = match mission.run_mission(&mut system_data, entity) {
+                if mission.is_paused() {
+                    if record_map_states {
+                        record_map_state(&mut data.visualization_data, entity, "paused".to_string(), MissionHealth::Running);
+                    }
+                    continue;
+                }
+
+                let cpu_start = data.cpu_accounting.start();
+                let run_result = mission.run_mission(&mut system_data, entity);
+                data.cpu_accounting.finish_mission(cpu_start, mission_data.type_name());
+
+                if record_map_states {
+                    let (state, health) = match &run_result {
+                        Ok(MissionResult::Running) => (mission.describe_state(&mut system_data, entity), MissionHealth::Running),
+                        Ok(MissionResult::Success) => ("complete".to_string(), MissionHealth::Complete),
+                        Err(error) => (error.message.clone(), MissionHealth::Failing),
+                    };
+                    record_map_state(&mut data.visualization_data, entity, state, health);
+                }
+
+                let completed = matches!(run_result, Ok(MissionResult::Success));
+
+                let cleanup_mission = match run_result {
                     Ok(MissionResult::Running) => false,
-                    Ok(MissionResult::Success) => true,
+                    Ok(MissionResult::Success) => {
+                        data.mission_stats.record_end(entity, mission_data.type_name(), Ok(()), now);
+                        true
+                    }
                     Err(error) => {
-                        info!("Mission run failed, cleaning up. Error: {}", error);
+                        data.mission_stats.record_end(entity, mission_data.type_name(), Err(&error), now);
                         true
                     }
                 };
 
                 if cleanup_mission {
+                    // A completed mission's creeps have served their purpose; put them to use elsewhere
+                    // or recycle them rather than leaving them idle.
+                    let released = if completed { mission.get_creeps() } else { Vec::new() };
+
                     drop(mission);
+
+                    for creep_entity in released {
+                        release_creep(&mut system_data, creep_entity, RecyclePolicy::Rehome);
+                    }
+
                     queue_mission_abort(&mut system_data, entity);
                 }
             }
         }
+
+        data.mission_stats.save();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn data_age_pauses_then_expires() {
+        let limits = DataAgeLimits {
+            pause_after: 1_000,
+            fail_after: 5_000,
+        };
+
+        assert_eq!(limits.check(Some(1_000)), DataAge::Fresh);
+        assert_eq!(limits.check(Some(1_001)), DataAge::Stale);
+        assert_eq!(limits.check(Some(5_000)), DataAge::Stale);
+        assert_eq!(limits.check(Some(5_001)), DataAge::Expired);
+        assert_eq!(limits.check(None), DataAge::Stale);
+    }
+
+    #[test]
+    fn owners_run_before_the_missions_they_own() {
+        // 5 owns 2 (by its children); 2 owns 1 and 4 (by their owners); 3 has an operation for an owner.
+        let missions = vec![
+            (1, Some(2), vec![]),
+            (2, None, vec![]),
+            (3, Some(99), vec![]),
+            (4, Some(2), vec![]),
+            (5, None, vec![2]),
+        ];
+
+        assert_eq!(execution_order(&missions), vec![3, 5, 2, 1, 4]);
+
+        let mut reversed = missions.clone();
+        reversed.reverse();
+        assert_eq!(execution_order(&reversed), execution_order(&missions));
+    }
+
+    #[test]
+    fn ownership_cycles_run_last_in_entity_order() {
+        let missions = vec![(1, Some(2), vec![]), (2, Some(1), vec![]), (3, None, vec![1])];
+
+        assert_eq!(execution_order(&missions), vec![3, 1, 2]);
     }
 }
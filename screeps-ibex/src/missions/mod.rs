@@ -3,15 +3,21 @@ pub mod colony;
 pub mod constants;
 pub mod construction;
 pub mod data;
+pub mod downgrade;
+pub mod emergency;
+pub mod exitchoke;
 pub mod haul;
 pub mod labs;
 pub mod localbuild;
 pub mod localsupply;
 pub mod miningoutpost;
+pub mod missionstats;
 pub mod missionsystem;
 pub mod nuke_defense;
+pub mod overflow;
 pub mod powerspawn;
 pub mod remotebuild;
+pub mod remoteroads;
 pub mod reserve;
 pub mod safe_mode;
 pub mod salvage;
@@ -36,6 +36,7 @@ pub struct NukeDefenseMission {
     room_data: Entity,
     /// Tick when we last ran the nuke scan (avoid scanning every tick).
     last_scan_tick: u32,
+    paused: bool,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -48,6 +49,7 @@ impl NukeDefenseMission {
             owner: owner.into(),
             room_data,
             last_scan_tick: 0,
+            paused: false,
         };
 
         builder
@@ -58,6 +60,8 @@ impl NukeDefenseMission {
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl Mission for NukeDefenseMission {
+    impl_mission_pause!();
+
     fn get_owner(&self) -> &Option<Entity> {
         &self.owner
     }
@@ -79,7 +83,11 @@ impl Mission for NukeDefenseMission {
         crate::visualization::SummaryContent::Text("NukeDefense".to_string())
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<MissionResult, String> {
+    fn run_mission(
+        &mut self,
+        system_data: &mut MissionExecutionSystemData,
+        _mission_entity: Entity,
+    ) -> Result<MissionResult, MissionError> {
         let features = system_data.features;
 
         if !features.military.nuke_defense {
@@ -93,7 +101,10 @@ impl Mission for NukeDefenseMission {
         }
         self.last_scan_tick = current_tick;
 
-        let room_data = system_data.room_data.get(self.room_data).ok_or("Expected room data")?;
+        let room_data = system_data
+            .room_data
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
 
         let room = match game::rooms().get(room_data.name) {
             Some(r) => r,
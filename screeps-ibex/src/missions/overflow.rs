@@ -0,0 +1,135 @@
+//! Energy overflow sinks — somewhere for a full storage's income to go.
+//!
+//! At RCL8 the controller takes at most 15 energy a tick and storage fills
+//! towards its million. The colony mission asks [`overflow_sinks`] each tick
+//! which extra consumers the room's stored energy pays for and records the
+//! answer here; the missions behind each sink only query it:
+//!
+//! - [`EnergySink::Fortify`]: the local build mission keeps full-size
+//!   builders on the walls and ramparts.
+//! - [`EnergySink::PowerProcessing`]: the power spawn is refilled ahead of
+//!   other hauls and an operator with `PWR_OPERATE_POWER` is asked to use it.
+//! - [`EnergySink::TerminalSales`]: energy arbitrage sells down to
+//!   `market.overflow_sell_floor` instead of `energy_sell_floor`.
+//! - [`EnergySink::RemoteBuilder`]: remote build missions the room helps
+//!   field one builder more.
+//!
+//! Each sink turns on above its own threshold and off only once the energy
+//! falls below a lower one, so a room hovering at a threshold doesn't flap.
+//! Ephemeral, like `missions::emergency`: the colony re-derives the sinks on
+//! its first tick after a VM reload.
+
+use log::*;
+use screeps::*;
+use specs::Entity;
+use std::collections::HashMap;
+
+/// An extra energy consumer enabled while storage overflows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EnergySink {
+    Fortify,
+    PowerProcessing,
+    TerminalSales,
+    RemoteBuilder,
+}
+
+impl EnergySink {
+    pub fn label(self) -> &'static str {
+        match self {
+            EnergySink::Fortify => "fortify",
+            EnergySink::PowerProcessing => "power",
+            EnergySink::TerminalSales => "sell",
+            EnergySink::RemoteBuilder => "remote builder",
+        }
+    }
+}
+
+/// Every sink in priority order, with the stored energy that turns it on and
+/// the energy below which it turns off again.
+pub const SINK_THRESHOLDS: [(EnergySink, u32, u32); 4] = [
+    (EnergySink::Fortify, 700_000, 600_000),
+    (EnergySink::PowerProcessing, 750_000, 650_000),
+    (EnergySink::TerminalSales, 800_000, 700_000),
+    (EnergySink::RemoteBuilder, 850_000, 750_000),
+];
+
+/// The sinks a room holding `stored_energy` runs, given the ones it ran last
+/// tick, in priority order.
+pub fn overflow_sinks(active: &[EnergySink], stored_energy: u32) -> Vec<EnergySink> {
+    SINK_THRESHOLDS
+        .iter()
+        .filter(|(sink, on, off)| {
+            let threshold = if active.contains(sink) { *off } else { *on };
+
+            stored_energy >= threshold
+        })
+        .map(|(sink, _, _)| *sink)
+        .collect()
+}
+
+/// The sinks each colony runs. Runtime resource; see the module docs.
+#[derive(Default)]
+pub struct OverflowSinks {
+    active: HashMap<Entity, Vec<EnergySink>>,
+}
+
+impl OverflowSinks {
+    /// `room`'s sinks, in priority order.
+    pub fn sinks(&self, room: Entity) -> &[EnergySink] {
+        self.active.get(&room).map(|sinks| sinks.as_slice()).unwrap_or(&[])
+    }
+
+    pub fn is_enabled(&self, room: Entity, sink: EnergySink) -> bool {
+        self.sinks(room).contains(&sink)
+    }
+
+    /// Replace `room`'s sinks, logging the ones turned on or off.
+    pub fn set(&mut self, room: Entity, room_name: RoomName, sinks: Vec<EnergySink>) {
+        let previous = self.sinks(room);
+
+        for sink in sinks.iter().filter(|sink| !previous.contains(sink)) {
+            info!("Energy overflow in {}: enabling {} sink", room_name, sink.label());
+        }
+
+        for sink in previous.iter().filter(|sink| !sinks.contains(sink)) {
+            info!("Energy overflow in {}: disabling {} sink", room_name, sink.label());
+        }
+
+        if sinks.is_empty() {
+            self.active.remove(&room);
+        } else {
+            self.active.insert(room, sinks);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sinks_enable_in_priority_order_as_storage_fills() {
+        assert!(overflow_sinks(&[], 650_000).is_empty());
+        assert_eq!(overflow_sinks(&[], 700_000), vec![EnergySink::Fortify]);
+        assert_eq!(
+            overflow_sinks(&[], 820_000),
+            vec![EnergySink::Fortify, EnergySink::PowerProcessing, EnergySink::TerminalSales]
+        );
+        assert_eq!(overflow_sinks(&[], 1_000_000).len(), SINK_THRESHOLDS.len());
+    }
+
+    #[test]
+    fn enabled_sinks_hold_until_the_lower_threshold() {
+        let all = overflow_sinks(&[], 1_000_000);
+
+        // Between the thresholds a sink keeps whatever state it had.
+        assert_eq!(overflow_sinks(&all, 760_000), all);
+        assert_eq!(overflow_sinks(&[], 760_000), vec![EnergySink::Fortify, EnergySink::PowerProcessing]);
+
+        assert_eq!(
+            overflow_sinks(&all, 690_000),
+            vec![EnergySink::Fortify, EnergySink::PowerProcessing]
+        );
+        assert!(overflow_sinks(&all, 599_999).is_empty());
+    }
+}
@@ -1,8 +1,12 @@
 use super::data::*;
 use super::missionsystem::*;
+use super::overflow::EnergySink;
+use crate::intents::IntentCategory;
+use crate::powercreepsystem::{has_power_effect, PowerPriority, PowerRequest};
 use crate::remoteobjectid::*;
 use crate::room::data::*;
 use crate::serialize::*;
+use crate::structureidentifier::RemoteStructureIdentifier;
 use crate::transfer::transfersystem::*;
 use screeps::*;
 use serde::{Deserialize, Serialize};
@@ -15,6 +19,7 @@ use specs::*;
 pub struct PowerSpawnMission {
     owner: EntityOption<Entity>,
     room_data: Entity,
+    paused: bool,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -34,6 +39,7 @@ impl PowerSpawnMission {
         PowerSpawnMission {
             owner: owner.into(),
             room_data,
+            paused: false,
         }
     }
 
@@ -47,6 +53,8 @@ impl PowerSpawnMission {
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl Mission for PowerSpawnMission {
+    impl_mission_pause!();
+
     fn get_owner(&self) -> &Option<Entity> {
         &self.owner
     }
@@ -69,20 +77,25 @@ impl Mission for PowerSpawnMission {
         crate::visualization::SummaryContent::Text("PowerSpawn".to_string())
     }
 
-    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), String> {
-        let room_data = system_data.room_data.get(self.room_data).ok_or("Expected room data")?;
+    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), MissionError> {
+        let room_data = system_data
+            .room_data
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
 
         let structures = room_data.get_structures().ok_or_else(|| {
             let msg = format!("Expected structures - Room: {}", room_data.name);
             log::warn!("{} at {}:{}", msg, file!(), line!());
-            msg
+            MissionError::new(MissionFailure::MissingRoomData, msg)
         })?;
 
         if structures.power_spawns().is_empty() {
-            return Err("No power spawns in room".to_owned());
+            return Err(MissionError::new(MissionFailure::TargetInvalid, "No power spawns in room"));
         }
 
         let room_data_entity = self.room_data;
+        // An overflowing room keeps the power spawn topped up so it never idles for want of a haul.
+        let overflowing = system_data.overflow_sinks.is_enabled(self.room_data, EnergySink::PowerProcessing);
 
         system_data.transfer_queue.register_generator(
             room_data.name,
@@ -100,7 +113,7 @@ impl Mission for PowerSpawnMission {
                     let required_energy = power_spawn.store().get_free_capacity(Some(ResourceType::Energy));
 
                     let map_priority = |fraction: f32| {
-                        if fraction < 0.25 {
+                        if overflowing || fraction < 0.25 {
                             TransferPriority::High
                         } else if fraction < 0.5 {
                             TransferPriority::Medium
@@ -149,26 +162,46 @@ impl Mission for PowerSpawnMission {
         Ok(())
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<MissionResult, String> {
-        let room_data = system_data.room_data.get(self.room_data).ok_or("Expected room data")?;
+    fn run_mission(
+        &mut self,
+        system_data: &mut MissionExecutionSystemData,
+        _mission_entity: Entity,
+    ) -> Result<MissionResult, MissionError> {
+        let room_data = system_data
+            .room_data
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
 
         let structures = room_data.get_structures().ok_or_else(|| {
             let msg = format!("Expected structures - Room: {}", room_data.name);
             log::warn!("{} at {}:{}", msg, file!(), line!());
-            msg
+            MissionError::new(MissionFailure::MissingRoomData, msg)
         })?;
         let power_spawns = structures.power_spawns();
 
         if power_spawns.is_empty() {
-            return Err("No power spawns in room".to_owned());
+            return Err(MissionError::new(MissionFailure::TargetInvalid, "No power spawns in room"));
         }
 
+        let overflowing = system_data.overflow_sinks.is_enabled(self.room_data, EnergySink::PowerProcessing);
+
         for power_spawn in power_spawns.iter() {
+            if overflowing && !has_power_effect(power_spawn, PowerType::OperatePower) {
+                system_data.power_requests.request(PowerRequest::new(
+                    room_data.name,
+                    PowerType::OperatePower,
+                    RemoteStructureIdentifier::new(&StructureObject::from(power_spawn.clone())),
+                    PowerPriority::Low,
+                ));
+            }
+
             let available_energy = power_spawn.store().get(ResourceType::Energy).unwrap_or(0);
             let available_power = power_spawn.store().get(ResourceType::Power).unwrap_or(0);
 
             if available_energy > POWER_SPAWN_ENERGY_RATIO && available_power > 0 {
-                let _ = power_spawn.process_power();
+                let _ = system_data
+                    .intent_recorder
+                    .issued(IntentCategory::Structure, power_spawn.process_power());
             }
         }
 
@@ -1,12 +1,18 @@
 use super::data::*;
 use super::missionsystem::*;
+use super::overflow::EnergySink;
 use super::utility::*;
+use crate::creep::spawning::BodyTemplate;
 use crate::creep::*;
 use crate::jobs::build::*;
 use crate::jobs::data::*;
+use crate::jobs::haul::*;
+use crate::ledger::{LedgerCategory, LEDGER_WINDOW};
+use crate::remoteobjectid::*;
 use crate::room::data::*;
 use crate::serialize::*;
 use crate::spawnsystem::*;
+use crate::transfer::transfersystem::*;
 use itertools::*;
 use lerp::*;
 use screeps::*;
@@ -16,12 +22,79 @@ use specs::error::NoError;
 use specs::saveload::*;
 use specs::*;
 
+/// Help a new colony until its controller reaches this level.
+const REMOTE_BUILD_UNTIL_LEVEL: u8 = 3;
+const MAX_REMOTE_BUILDERS: usize = 6;
+/// Spare home energy per tick that pays for one more builder.
+const BUILDER_SURPLUS_PER_TICK: f64 = 2.0;
+/// Harvest income per tick (one source mined out) at which the colony no
+/// longer needs builders from its homes.
+const SELF_SUFFICIENT_INCOME: f64 = 10.0;
+/// Storage energy a home keeps back from ferrying.
+const HOME_STORAGE_RESERVE: u32 = 20_000;
+
+/// Builders worth keeping on a new colony: one per `BUILDER_SURPLUS_PER_TICK`
+/// of spare home energy, tapering to none as the colony's own harvest income
+/// approaches `SELF_SUFFICIENT_INCOME`.
+fn desired_builders(home_surplus: f64, own_income: f64) -> usize {
+    let funded = ((home_surplus / BUILDER_SURPLUS_PER_TICK).floor() as usize).clamp(1, MAX_REMOTE_BUILDERS);
+    let taper = (1.0 - own_income / SELF_SUFFICIENT_INCOME).clamp(0.0, 1.0);
+
+    (funded as f64 * taper).ceil() as usize
+}
+
+/// Ferry haulers to go with the builders: one per two, and only when a home
+/// has storage energy to spare.
+fn desired_haulers(builders: usize, ferry_homes: usize) -> usize {
+    if ferry_homes == 0 {
+        0
+    } else {
+        builders.div_ceil(2)
+    }
+}
+
+/// Stockpile deposits for a colony that has no spawn yet: every container in
+/// the room takes energy at low priority, so ferry haulers can pair home
+/// storage with it. Once the spawn stands, its room transfer mission asks for
+/// spawn and extension energy itself, and the room's own haulers would churn
+/// container stock against these deposits.
+fn colony_stockpile_generator(room_entity: Entity) -> TransferQueueGenerator {
+    Box::new(move |system, transfer, _room_name| {
+        let room_data = system.get_room_data(room_entity).ok_or("Expected room data")?;
+        let structures = room_data.get_structures().ok_or("Expected structures")?;
+
+        if !structures.spawns().is_empty() {
+            return Ok(());
+        }
+
+        for container in structures.containers() {
+            let free_capacity = container.store().get_free_capacity(Some(ResourceType::Energy)).max(0) as u32;
+
+            if free_capacity > 0 {
+                let transfer_request = TransferDepositRequest::new(
+                    TransferTarget::Container(container.remote_id()),
+                    Some(ResourceType::Energy),
+                    TransferPriority::Low,
+                    free_capacity,
+                    TransferType::Haul,
+                );
+
+                transfer.request_deposit(transfer_request);
+            }
+        }
+
+        Ok(())
+    })
+}
+
 #[derive(ConvertSaveload)]
 pub struct RemoteBuildMission {
     owner: EntityOption<Entity>,
     room_data: Entity,
     home_room_datas: EntityVec<Entity>,
     builders: EntityVec<Entity>,
+    haulers: EntityVec<Entity>,
+    paused: bool,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -43,6 +116,8 @@ impl RemoteBuildMission {
             room_data,
             home_room_datas: home_room_datas.to_owned().into(),
             builders: EntityVec::new(),
+            haulers: EntityVec::new(),
+            paused: false,
         }
     }
 
@@ -81,29 +156,73 @@ impl RemoteBuildMission {
         })
     }
 
+    fn create_handle_hauler_spawn(
+        mission_entity: Entity,
+        pickup_rooms: &[Entity],
+        build_room_entity: Entity,
+    ) -> crate::spawnsystem::SpawnQueueCallback {
+        let pickup_rooms = pickup_rooms.to_vec();
+
+        Box::new(move |spawn_system_data, name| {
+            let name = name.to_string();
+            let pickup_rooms = pickup_rooms.clone();
+
+            spawn_system_data.updater.exec_mut(move |world| {
+                let creep_job = JobData::Haul(HaulJob::new(&pickup_rooms, &[build_room_entity], false, false));
+
+                let creep_entity = crate::creep::spawning::build(world.create_entity(), &name).with(creep_job).build();
+
+                if let Some(mut mission_data) = world
+                    .write_storage::<MissionData>()
+                    .get_mut(mission_entity)
+                    .as_mission_type_mut::<RemoteBuildMission>()
+                {
+                    mission_data.haulers.push(creep_entity);
+                }
+            });
+        })
+    }
+
+    /// A new colony needs help while its spawn is still a construction site,
+    /// and after that until its controller reaches `REMOTE_BUILD_UNTIL_LEVEL`.
     pub fn can_run(room_data: &RoomData) -> bool {
-        let has_spawns = room_data
-            .get_structures()
-            .map(|structures| !structures.spawns().is_empty())
-            .unwrap_or(false);
-
-        if !has_spawns {
-            if let Some(construction_sites) = room_data.get_construction_sites() {
-                let has_pending_spawn = construction_sites
+        let Some(structures) = room_data.get_structures() else {
+            return false;
+        };
+
+        if !structures.spawns().is_empty() {
+            return structures
+                .controllers()
+                .iter()
+                .any(|controller| controller.my() && controller.level() < REMOTE_BUILD_UNTIL_LEVEL);
+        }
+
+        room_data
+            .get_construction_sites()
+            .map(|construction_sites| {
+                construction_sites
                     .iter()
                     .filter(|s| s.my())
-                    .any(|s| s.structure_type() == StructureType::Spawn);
-
-                return has_pending_spawn;
-            }
-        }
+                    .any(|s| s.structure_type() == StructureType::Spawn)
+            })
+            .unwrap_or(false)
+    }
 
-        false
+    /// Storage energy `home_name` can spare beyond `HOME_STORAGE_RESERVE`.
+    fn storage_surplus(home_name: RoomName) -> u32 {
+        game::rooms()
+            .get(home_name)
+            .and_then(|room| room.storage())
+            .map(|storage| storage.store().get_used_capacity(Some(ResourceType::Energy)))
+            .unwrap_or(0)
+            .saturating_sub(HOME_STORAGE_RESERVE)
     }
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl Mission for RemoteBuildMission {
+    impl_mission_pause!();
+
     fn get_owner(&self) -> &Option<Entity> {
         &self.owner
     }
@@ -120,10 +239,11 @@ impl Mission for RemoteBuildMission {
 
     fn remove_creep(&mut self, entity: Entity) {
         self.builders.retain(|e| *e != entity);
+        self.haulers.retain(|e| *e != entity);
     }
 
     fn get_creeps(&self) -> Vec<Entity> {
-        self.builders.iter().copied().collect()
+        self.builders.iter().chain(self.haulers.iter()).copied().collect()
     }
 
     fn describe_state(&self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> String {
@@ -134,30 +254,55 @@ impl Mission for RemoteBuildMission {
             .map(|d| d.name.to_string())
             .join("/");
 
-        format!("Remote Build - Builders: {} - Home rooms: {}", self.builders.len(), home_room_names)
+        format!(
+            "Remote Build - Builders: {} - Haulers: {} - Home rooms: {}",
+            self.builders.len(),
+            self.haulers.len(),
+            home_room_names
+        )
     }
 
     fn summarize(&self) -> crate::visualization::SummaryContent {
-        crate::visualization::SummaryContent::Text(format!("Remote Build - Builders: {}", self.builders.len()))
+        crate::visualization::SummaryContent::Text(format!(
+            "Remote Build - Builders: {} - Haulers: {}",
+            self.builders.len(),
+            self.haulers.len()
+        ))
     }
 
-    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), String> {
+    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), MissionError> {
         //
-        // Cleanup home rooms that no longer exist.
+        // Cleanup home rooms that no longer exist. The colony never homes its own build.
         //
 
+        let room_entity = self.room_data;
+
         self.home_room_datas
-            .retain(|entity| system_data.room_data.get(*entity).map(is_valid_home_room).unwrap_or(false));
+            .retain(|entity| *entity != room_entity && system_data.room_data.get(*entity).map(is_valid_home_room).unwrap_or(false));
 
         if self.home_room_datas.is_empty() {
-            return Err("No home rooms for remote build mission".to_owned());
+            return Err(MissionError::new(
+                MissionFailure::NoHomeRooms,
+                "No home rooms for remote build mission",
+            ));
+        }
+
+        if let Some(room_data) = system_data.room_data.get(self.room_data) {
+            system_data.transfer_queue.register_generator(
+                room_data.name,
+                TransferTypeFlags::HAUL,
+                colony_stockpile_generator(self.room_data),
+            );
         }
 
         Ok(())
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, String> {
-        let room_data = system_data.room_data.get(self.room_data).ok_or("Expected room data")?;
+    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, MissionError> {
+        let room_data = system_data
+            .room_data
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
 
         if !Self::can_run(room_data) {
             return Ok(MissionResult::Success);
@@ -183,27 +328,83 @@ impl Mission for RemoteBuildMission {
             }
         }
 
-        let desired_builders = 4;
+        //
+        // Size the effort to what the homes can spare, and wind it down as the colony's own
+        // harvest comes up. Remote builders harvest in the colony too, so its income only
+        // counts once the spawn stands and the room mines for itself.
+        //
+
+        let has_spawn = room_data.get_structures().map(|s| !s.spawns().is_empty()).unwrap_or(false);
+
+        let own_income = if has_spawn {
+            system_data
+                .ledger
+                .averages(target_name)
+                .map(|averages| averages.get(LedgerCategory::Harvest))
+                .unwrap_or(0.0)
+        } else {
+            0.0
+        };
+
+        let mut homes = Vec::new();
+
+        for home_room_entity in self.home_room_datas.iter() {
+            let home_name = system_data
+                .room_data
+                .get(*home_room_entity)
+                .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected home room data"))?
+                .name;
+
+            // Don't spawn a builder that can't reach the target with enough
+            // life left to gather + build — it would waste spawn capacity.
+            // (The home set is feasibility-filtered at creation; this is
+            // defensive against RCL/position drift.)
+            if !crate::missions::utility::is_build_feasible(system_data.pathfinder, home_name, target_name) {
+                continue;
+            }
+
+            let storage_surplus = Self::storage_surplus(home_name);
+
+            let net = system_data
+                .ledger
+                .averages(home_name)
+                .map(|averages| averages.net().max(0.0))
+                .unwrap_or(0.0);
+
+            let surplus = net + storage_surplus as f64 / LEDGER_WINDOW as f64;
+
+            homes.push((*home_room_entity, home_name, surplus, storage_surplus));
+        }
+
+        let home_surplus: f64 = homes.iter().map(|(_, _, surplus, _)| *surplus).sum();
+
+        // A home whose storage overflows fields one builder more while the colony still wants them.
+        let overflowing = homes
+            .iter()
+            .any(|(home_room_entity, _, _, _)| system_data.overflow_sinks.is_enabled(*home_room_entity, EnergySink::RemoteBuilder));
+
+        let desired_builders = desired_builders(home_surplus, own_income);
+        let desired_builders = desired_builders + usize::from(overflowing && desired_builders > 0);
 
         if self.builders.len() < desired_builders {
             let interp = (self.builders.len() as f32) / (desired_builders as f32);
 
             let priority = SPAWN_PRIORITY_MEDIUM.lerp_bounded(SPAWN_PRIORITY_LOW, interp);
 
+            // One token across the homes: whichever has a spawn and energy free takes it.
             let token = system_data.spawn_queue.token();
 
-            for home_room_entity in self.home_room_datas.iter() {
-                let home_name = system_data.room_data.get(*home_room_entity).ok_or("Expected home room data")?.name;
+            // Homes with energy to spare build; if none do, any home keeps the colony going.
+            let has_surplus = homes.iter().any(|(_, _, surplus, _)| *surplus > 0.0);
 
-                // Don't spawn a builder that can't reach the target with enough
-                // life left to gather + build — it would waste spawn capacity.
-                // (The home set is feasibility-filtered at creation; this is
-                // defensive against RCL/position drift.)
-                if !crate::missions::utility::is_build_feasible(system_data.pathfinder, home_name, target_name) {
+            for (home_room_entity, home_name, surplus, _) in homes.iter() {
+                if has_surplus && *surplus <= 0.0 {
                     continue;
                 }
 
-                let home_room = game::rooms().get(home_name).ok_or("Expected home room")?;
+                let home_room = game::rooms()
+                    .get(*home_name)
+                    .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected home room"))?;
 
                 let body_definition = SpawnBodyDefinition {
                     maximum_energy: home_room.energy_capacity_available(),
@@ -222,7 +423,53 @@ impl Mission for RemoteBuildMission {
                         priority,
                         Some(token),
                         Self::create_handle_builder_spawn(mission_entity, self.room_data, true),
-                    );
+                    )
+                    .requested_by(mission_entity);
+
+                    system_data.spawn_queue.request(*home_room_entity, spawn_request);
+                }
+            }
+        }
+
+        //
+        // Ferry energy in from homes with storage to spare, so builders draw it in the colony
+        // instead of walking home for it.
+        //
+
+        let ferry_homes: Vec<_> = homes
+            .iter()
+            .filter(|(_, _, _, storage_surplus)| *storage_surplus > 0)
+            .map(|(entity, name, _, _)| (*entity, *name))
+            .collect();
+
+        let desired_haulers = desired_haulers(desired_builders, ferry_homes.len());
+
+        if self.haulers.len() < desired_haulers {
+            let token = system_data.spawn_queue.token();
+
+            let pickup_rooms: Vec<_> = ferry_homes.iter().map(|(entity, _)| *entity).collect();
+
+            let centre = Position::new(RoomCoordinate::new(25).unwrap(), RoomCoordinate::new(25).unwrap(), target_name);
+
+            for (home_room_entity, home_name) in ferry_homes.iter() {
+                let home_room = game::rooms()
+                    .get(*home_name)
+                    .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected home room"))?;
+
+                if let Some(body) = BodyTemplate::new(&[Part::Carry])
+                    .plains()
+                    .max_repeat(16)
+                    .build(home_room.energy_capacity_available())
+                {
+                    let spawn_request = SpawnRequest::new(
+                        format!("Remote Build Haul - Target Room: {}", target_name),
+                        &body,
+                        SPAWN_PRIORITY_LOW,
+                        Some(token),
+                        Self::create_handle_hauler_spawn(mission_entity, &pickup_rooms, self.room_data),
+                    )
+                    .requested_by(mission_entity)
+                    .toward(centre);
 
                     system_data.spawn_queue.request(*home_room_entity, spawn_request);
                 }
@@ -232,3 +479,24 @@ impl Mission for RemoteBuildMission {
         Ok(MissionResult::Running)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builders_scale_with_home_surplus() {
+        assert_eq!(desired_builders(0.0, 0.0), 1);
+        assert_eq!(desired_builders(5.0, 0.0), 2);
+        assert_eq!(desired_builders(100.0, 0.0), MAX_REMOTE_BUILDERS);
+    }
+
+    #[test]
+    fn builders_wind_down_as_colony_income_ramps() {
+        assert_eq!(desired_builders(100.0, SELF_SUFFICIENT_INCOME / 2.0), MAX_REMOTE_BUILDERS / 2);
+        assert_eq!(desired_builders(100.0, SELF_SUFFICIENT_INCOME), 0);
+        assert_eq!(desired_haulers(0, 2), 0);
+        assert_eq!(desired_haulers(5, 2), 3);
+        assert_eq!(desired_haulers(5, 0), 0);
+    }
+}
@@ -0,0 +1,190 @@
+//! Roads from a home room's storage out to a remote's sources.
+//!
+//! A mining outpost plans one road per source, from the nearest home
+//! storage to the source's container (or the source itself before the
+//! container stands), keeps the tiles outside its home rooms, and places
+//! road sites on them a few at a time. The home rooms' own roads are the
+//! room plan's business. Haulers with a WORK part build the sites and top
+//! up decayed roads as they pass; see `jobs::utility::buildbehavior` and
+//! `jobs::utility::repairbehavior`.
+
+use screeps::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Ticks a road plan stands before it is searched again, picking up
+/// containers built since and home storage that moved.
+pub const ROAD_REPLAN_TICKS: u32 = 5_000;
+/// Ticks between checks of the laid roads and placing the next sites.
+pub const ROAD_CHECK_INTERVAL: u32 = 100;
+
+/// One planned road tile and whether a road stood on it when its room was
+/// last seen.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RoadTile {
+    pub pos: Position,
+    pub built: bool,
+}
+
+/// A remote's planned roads, saved with its mining outpost mission.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct RemoteRoads {
+    tiles: Vec<RoadTile>,
+    /// Tick the tiles were planned; `None` until the first plan.
+    planned_at: Option<u32>,
+    /// Home room exit tiles the planned paths leave by.
+    exits: Vec<Position>,
+}
+
+impl RemoteRoads {
+    pub fn needs_plan(&self, now: u32) -> bool {
+        self.planned_at
+            .map(|planned_at| now.saturating_sub(planned_at) >= ROAD_REPLAN_TICKS)
+            .unwrap_or(true)
+    }
+
+    /// Replace the plan. Tiles kept from the old plan keep their state.
+    pub fn set_plan(&mut self, tiles: Vec<Position>, now: u32) {
+        let built: HashSet<Position> = self.tiles.iter().filter(|t| t.built).map(|t| t.pos).collect();
+
+        self.tiles = tiles
+            .into_iter()
+            .map(|pos| RoadTile {
+                pos,
+                built: built.contains(&pos),
+            })
+            .collect();
+        self.planned_at = Some(now);
+    }
+
+    pub fn tiles(&self) -> &[RoadTile] {
+        &self.tiles
+    }
+
+    pub fn set_exits(&mut self, exits: Vec<Position>) {
+        self.exits = exits;
+    }
+
+    pub fn exits(&self) -> &[Position] {
+        &self.exits
+    }
+
+    /// Record which of `room`'s tiles have a road, from a fresh look at it.
+    pub fn observe(&mut self, room: RoomName, roads: &HashSet<Position>) {
+        for tile in self.tiles.iter_mut().filter(|t| t.pos.room_name() == room) {
+            tile.built = roads.contains(&tile.pos);
+        }
+    }
+
+    /// Share of the planned tiles with a road, or `None` before a plan.
+    pub fn completion(&self) -> Option<f32> {
+        if self.tiles.is_empty() {
+            return None;
+        }
+
+        Some(self.tiles.iter().filter(|t| t.built).count() as f32 / self.tiles.len() as f32)
+    }
+}
+
+/// The tiles to road along `paths`: each tile once, in path order, leaving
+/// out the home rooms and the room edges (no structure stands on an exit).
+pub fn road_tiles(paths: impl IntoIterator<Item = Vec<Position>>, home_rooms: &[RoomName]) -> Vec<Position> {
+    let mut seen = HashSet::new();
+
+    paths
+        .into_iter()
+        .flatten()
+        .filter(|pos| !home_rooms.contains(&pos.room_name()))
+        .filter(|pos| {
+            let (x, y) = (pos.x().u8(), pos.y().u8());
+
+            x > 0 && x < ROOM_SIZE - 1 && y > 0 && y < ROOM_SIZE - 1
+        })
+        .filter(|pos| seen.insert(*pos))
+        .collect()
+}
+
+/// The edge tiles of the home rooms that `paths` leave by, each once.
+pub fn home_exits(paths: &[Vec<Position>], home_rooms: &[RoomName]) -> Vec<Position> {
+    let mut exits = Vec::new();
+
+    for pos in paths.iter().flatten().filter(|pos| home_rooms.contains(&pos.room_name())) {
+        let (x, y) = (pos.x().u8(), pos.y().u8());
+        let on_edge = x == 0 || x == ROOM_SIZE - 1 || y == 0 || y == ROOM_SIZE - 1;
+
+        if on_edge && !exits.contains(pos) {
+            exits.push(*pos);
+        }
+    }
+
+    exits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::position;
+
+    fn room(name: &str) -> RoomName {
+        name.parse().expect("valid room name")
+    }
+
+    #[test]
+    fn road_tiles_skip_home_rooms_edges_and_shared_tiles() {
+        let (home, remote) = (room("W1N1"), room("W2N1"));
+
+        let to_first = vec![
+            position(home, 1, 20),
+            position(remote, 49, 20),
+            position(remote, 48, 20),
+            position(remote, 47, 21),
+        ];
+        let to_second = vec![position(remote, 48, 20), position(remote, 47, 19)];
+
+        assert_eq!(
+            road_tiles([to_first, to_second], &[home]),
+            vec![position(remote, 48, 20), position(remote, 47, 21), position(remote, 47, 19)]
+        );
+    }
+
+    #[test]
+    fn home_exits_are_the_home_edge_tiles_paths_cross() {
+        let (home, remote) = (room("W1N1"), room("W2N1"));
+
+        let paths = vec![
+            vec![
+                position(home, 2, 20),
+                position(home, 1, 20),
+                position(home, 0, 20),
+                position(remote, 49, 20),
+            ],
+            vec![position(home, 1, 20), position(home, 0, 20), position(remote, 49, 21)],
+        ];
+
+        assert_eq!(home_exits(&paths, &[home]), vec![position(home, 0, 20)]);
+        assert!(home_exits(&paths, &[]).is_empty());
+    }
+
+    #[test]
+    fn completion_follows_observed_rooms_and_survives_a_replan() {
+        let (remote, corridor) = (room("W2N1"), room("W3N1"));
+        let mut roads = RemoteRoads::default();
+
+        assert!(roads.needs_plan(100));
+        assert_eq!(roads.completion(), None);
+
+        roads.set_plan(
+            vec![position(remote, 10, 10), position(remote, 11, 10), position(corridor, 40, 10)],
+            100,
+        );
+        roads.observe(remote, &[position(remote, 10, 10)].into_iter().collect());
+
+        assert_eq!(roads.completion(), Some(1.0 / 3.0));
+        assert!(!roads.needs_plan(100 + ROAD_REPLAN_TICKS - 1));
+        assert!(roads.needs_plan(100 + ROAD_REPLAN_TICKS));
+
+        roads.set_plan(vec![position(remote, 10, 10), position(remote, 12, 10)], 100 + ROAD_REPLAN_TICKS);
+
+        assert_eq!(roads.completion(), Some(0.5));
+    }
+}
@@ -1,18 +1,130 @@
+//! Remote controller reservation.
+//!
+//! A reserver adds one reservation tick per CLAIM part per tick while the
+//! reservation itself counts down by one, up to `CONTROLLER_RESERVE_MAX`.
+//! Rather than keep reservers fielded on a cadence, the mission spawns the
+//! next one when the reservation left (extrapolated from the room's last
+//! sighting) would run out before a reserver spawned now could reach the
+//! controller, plus [`RESERVE_DISPATCH_MARGIN`]. Reservers carry two CLAIM
+//! parts, or four where the reservation has tended to run far below the cap.
+//!
+//! A remote another player has reserved is contested: the mission fields the
+//! larger reserver, which attacks the foreign reservation down, and flags the
+//! room in [`ContestedReservations`] for the war operation's harassment. A
+//! reservation held against us for [`CONTESTED_GIVE_UP_TICKS`] is given up.
+//!
+//! A remote under attack — a player's claimer in the room, or a reservation
+//! that has ended up shorter than it stood at the last sighting — gets the
+//! same treatment straight away, without waiting for the reserver to come
+//! due: `attackController` takes reservation off faster than one reserver
+//! puts it back.
+
 use super::constants::*;
 use super::data::*;
 use super::missionsystem::*;
 use super::utility::*;
 use crate::jobs::data::*;
 use crate::jobs::reserve::*;
+use crate::military::arrival::TRAVEL_TICKS_PER_ROOM;
 use crate::remoteobjectid::*;
+use crate::room::data::RoomDisposition;
 use crate::serialize::*;
 use crate::spawnsystem::*;
+use log::*;
 use screeps::*;
 use serde::{Deserialize, Serialize};
 #[allow(deprecated)]
 use specs::error::NoError;
 use specs::saveload::*;
 use specs::*;
+use std::collections::HashMap;
+
+/// Reservation ticks kept in hand on top of a reserver's spawn and travel time, for a busy spawn or a
+/// detour on the way.
+pub const RESERVE_DISPATCH_MARGIN: u32 = 100;
+/// CLAIM parts on a routine reserver.
+const RESERVER_CLAIM_PARTS: u32 = 2;
+/// CLAIM parts on a reserver for a remote whose reservation runs low, or one that is contested.
+const RESERVER_LARGE_CLAIM_PARTS: u32 = 4;
+/// A reservation that tends to run further than this below `CONTROLLER_RESERVE_MAX` gets the larger
+/// reserver.
+const RESERVATION_SHORTFALL: u32 = 3_000;
+/// Ticks between samples of the reservation level while the room is visible.
+const RESERVATION_SAMPLE_INTERVAL: u32 = 100;
+/// A contested flag not refreshed for this long has lapsed.
+pub const CONTESTED_FLAG_TTL: u32 = 200;
+/// A remote contested for this long is given up.
+pub const CONTESTED_GIVE_UP_TICKS: u32 = 10_000;
+
+/// Whether the next reserver is due: `remaining` reservation ticks would run out before one spawned now
+/// (`spawn_ticks`, then `travel_ticks` to the controller) got there with [`RESERVE_DISPATCH_MARGIN`] to spare.
+pub fn reserver_due(remaining: u32, travel_ticks: u32, spawn_ticks: u32) -> bool {
+    remaining <= travel_ticks + spawn_ticks + RESERVE_DISPATCH_MARGIN
+}
+
+/// CLAIM parts for the next reserver, given the remote's running reservation level.
+pub fn reserver_claim_parts(reservation_level: Option<u32>, contested: bool) -> u32 {
+    let runs_low = reservation_level
+        .map(|level| level + RESERVATION_SHORTFALL < CONTROLLER_RESERVE_MAX)
+        .unwrap_or(false);
+
+    if contested || runs_low {
+        RESERVER_LARGE_CLAIM_PARTS
+    } else {
+        RESERVER_CLAIM_PARTS
+    }
+}
+
+/// Whether a reservation sighted to end at `until` was knocked down since an earlier sighting had it ending at
+/// `previous`: between sightings a reservation only counts down or is extended, so an end that moved earlier
+/// was attacked.
+pub fn reservation_knocked_down(previous: Option<u32>, until: u32) -> bool {
+    previous.is_some_and(|previous| until < previous)
+}
+
+/// Fold a sighting of `remaining` reservation ticks into the running level: a tenth of each sample, so the
+/// level follows whole reserver cycles rather than where the current one stands.
+fn sample_reservation(level: Option<u32>, remaining: u32) -> u32 {
+    level.map(|level| (level * 9 + remaining) / 10).unwrap_or(remaining)
+}
+
+/// Remotes another player holds a reservation on, flagged by their reserve missions each tick they see it,
+/// for the war operation to harass.
+#[derive(Default)]
+pub struct ContestedReservations {
+    /// Room → the player holding it, and the ticks it was first and last flagged.
+    flagged: HashMap<RoomName, (String, u32, u32)>,
+}
+
+impl ContestedReservations {
+    /// Flag `room` as reserved by `player` at `now`.
+    pub fn flag(&mut self, room: RoomName, player: &str, now: u32) {
+        let since = self
+            .flagged
+            .get(&room)
+            .filter(|(holder, _, last)| holder == player && now.saturating_sub(*last) <= CONTESTED_FLAG_TTL)
+            .map(|(_, since, _)| *since)
+            .unwrap_or(now);
+
+        self.flagged.insert(room, (player.to_string(), since, now));
+    }
+
+    /// Ticks `room` has been contested as of `now`, if it is.
+    pub fn contested_for(&self, room: RoomName, now: u32) -> Option<u32> {
+        self.flagged
+            .get(&room)
+            .filter(|(_, _, last)| now.saturating_sub(*last) <= CONTESTED_FLAG_TTL)
+            .map(|(_, since, _)| now.saturating_sub(*since))
+    }
+
+    /// The contested rooms as of `now`, with the player holding each.
+    pub fn contested(&self, now: u32) -> impl Iterator<Item = (RoomName, &str)> + '_ {
+        self.flagged
+            .iter()
+            .filter(move |(_, (_, _, last))| now.saturating_sub(*last) <= CONTESTED_FLAG_TTL)
+            .map(|(room, (player, _, _))| (*room, player.as_str()))
+    }
+}
 
 #[derive(ConvertSaveload)]
 pub struct ReserveMission {
@@ -21,8 +133,20 @@ pub struct ReserveMission {
     home_room_datas: EntityVec<Entity>,
     reservers: EntityVec<Entity>,
     allow_spawning: bool,
+    /// Running level of the reservation, sampled while the room is visible; sizes the reservers.
+    reservation_level: Option<u32>,
+    /// Where the reservation was last sighted to end; held while it is knocked down, until it grows back.
+    reserved_until: Option<u32>,
+    paused: bool,
 }
 
+/// Reservers walk a long way to a controller; they aren't sent on data older
+/// than this.
+const RESERVE_DATA_AGE_LIMITS: DataAgeLimits = DataAgeLimits {
+    pause_after: 2_000,
+    fail_after: 20_000,
+};
+
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl ReserveMission {
     pub fn build<B>(builder: B, owner: Option<Entity>, room_data: Entity, home_room_datas: &[Entity]) -> B
@@ -43,6 +167,9 @@ impl ReserveMission {
             home_room_datas: home_room_datas.to_owned().into(),
             reservers: EntityVec::new(),
             allow_spawning: true,
+            reservation_level: None,
+            reserved_until: None,
+            paused: false,
         }
     }
 
@@ -82,6 +209,8 @@ impl ReserveMission {
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl Mission for ReserveMission {
+    impl_mission_pause!();
+
     fn get_owner(&self) -> &Option<Entity> {
         &self.owner
     }
@@ -96,6 +225,10 @@ impl Mission for ReserveMission {
         Some(self.room_data)
     }
 
+    fn data_age_limits(&self) -> Option<DataAgeLimits> {
+        Some(RESERVE_DATA_AGE_LIMITS)
+    }
+
     fn remove_creep(&mut self, entity: Entity) {
         self.reservers.retain(|e| *e != entity);
     }
@@ -109,10 +242,15 @@ impl Mission for ReserveMission {
     }
 
     fn summarize(&self) -> crate::visualization::SummaryContent {
-        crate::visualization::SummaryContent::Text(format!("Reserve - Reservers: {}", self.reservers.len()))
+        let level = self
+            .reservation_level
+            .map(|level| format!(" - Level: {}", level))
+            .unwrap_or_default();
+
+        crate::visualization::SummaryContent::Text(format!("Reserve - Reservers: {}{}", self.reservers.len(), level))
     }
 
-    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), String> {
+    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), MissionError> {
         //
         // Cleanup home rooms that no longer exist.
         //
@@ -121,52 +259,95 @@ impl Mission for ReserveMission {
             .retain(|entity| system_data.room_data.get(*entity).map(is_valid_home_room).unwrap_or(false));
 
         if self.home_room_datas.is_empty() {
-            return Err("No home rooms for reserve mission".to_owned());
+            return Err(MissionError::new(MissionFailure::NoHomeRooms, "No home rooms for reserve mission"));
         }
 
         Ok(())
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, String> {
-        let room_data = system_data.room_data.get(self.room_data).ok_or("Expected room data")?;
-        let dynamic_visibility_data = room_data.get_dynamic_visibility_data().ok_or("Expected dynamic visibility data")?;
+    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, MissionError> {
+        let room_data = system_data
+            .room_data
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
+        let dynamic_visibility_data = room_data
+            .get_dynamic_visibility_data()
+            .ok_or_else(|| MissionError::new(MissionFailure::NoVisibility, "Expected dynamic visibility data"))?;
+
+        let now = game::time();
 
         if dynamic_visibility_data.updated_within(1000) {
             if dynamic_visibility_data.owner().mine() {
                 return Ok(MissionResult::Success);
             }
 
-            if !dynamic_visibility_data.owner().neutral()
-                || dynamic_visibility_data.reservation().hostile()
-                || dynamic_visibility_data.reservation().friendly()
-            {
-                return Err("Room is owned or reserved".to_string());
+            if !dynamic_visibility_data.owner().neutral() || dynamic_visibility_data.reservation().friendly() {
+                return Err(MissionError::new(MissionFailure::TargetInvalid, "Room is owned or reserved"));
+            }
+        }
+
+        let contested = match dynamic_visibility_data.reservation() {
+            RoomDisposition::Hostile(player) => {
+                system_data.contested_reservations.flag(room_data.name, player, now);
+
+                true
+            }
+            _ => false,
+        };
+
+        if let Some(contested_for) = system_data.contested_reservations.contested_for(room_data.name, now) {
+            if contested_for >= CONTESTED_GIVE_UP_TICKS {
+                return Err(MissionError::new(
+                    MissionFailure::TargetInvalid,
+                    "Reservation lost to another player",
+                ));
             }
         }
 
-        let static_visibility_data = room_data.get_static_visibility_data().ok_or("Expected static visibility data")?;
-        let controller_id = static_visibility_data.controller().ok_or("Expected a controller")?;
+        let remaining = if dynamic_visibility_data.reservation().mine() {
+            dynamic_visibility_data.reservation_ticks_remaining(now)
+        } else {
+            0
+        };
 
-        let can_spawn =
-            system_data.governor.can_execute_cpu(CpuBar::MediumPriority) && system_data.features.remote_mine.reserve && self.allow_spawning;
+        if dynamic_visibility_data.visible() && (now + mission_entity.id()).is_multiple_of(RESERVATION_SAMPLE_INTERVAL) {
+            self.reservation_level = Some(sample_reservation(self.reservation_level, remaining));
+        }
 
-        if !can_spawn {
-            return Ok(MissionResult::Running);
+        let knocked_down = reservation_knocked_down(self.reserved_until, now + remaining);
+
+        if dynamic_visibility_data.visible() && !knocked_down {
+            self.reserved_until = Some(now + remaining);
         }
 
-        //TODO: Use visibility data to estimate amount thas has ticked down.
-        let controller_has_sufficient_reservation = game::rooms()
-            .get(room_data.name)
-            .and_then(|r| r.controller())
-            .and_then(|c| c.reservation())
-            .map(|r| r.ticks_to_end() > 1000)
-            .unwrap_or(false);
+        let claimer_present = dynamic_visibility_data.visible()
+            && system_data
+                .threat_data
+                .get(self.room_data)
+                .is_some_and(|threat| !threat.claimers.is_empty());
+        let attacked = knocked_down || claimer_present;
+
+        let static_visibility_data = room_data
+            .get_static_visibility_data()
+            .ok_or_else(|| MissionError::new(MissionFailure::NoVisibility, "Expected static visibility data"))?;
+        let controller_id = static_visibility_data
+            .controller()
+            .ok_or_else(|| MissionError::new(MissionFailure::TargetInvalid, "Expected a controller"))?;
+
+        let can_spawn = system_data.governor.can_execute_cpu(CpuBar::MediumPriority)
+            && system_data
+                .features
+                .for_room(system_data.feature_overrides, room_data.name)
+                .remote_mine
+                .reserve
+            && self.allow_spawning
+            && !system_data.stale_missions.is_stale(mission_entity);
 
-        if controller_has_sufficient_reservation {
+        if !can_spawn {
             return Ok(MissionResult::Running);
         }
 
-        let alive_reservers: Vec<_> = self
+        let alive_reservers = self
             .reservers
             .iter()
             .filter(|entity| {
@@ -179,49 +360,135 @@ impl Mission for ReserveMission {
                         .map(|count| count > 100)
                         .unwrap_or(false)
             })
-            .collect();
-
-        // TODO: Total claim parts - target > 2 total.
-        //let claim_parts = body.iter().filter(|p| **p == Part::Claim).count();
-
-        let desired_reservers = 2;
-
-        if alive_reservers.len() < desired_reservers {
-            let token = system_data.spawn_queue.token();
-
-            for home_room_entity in self.home_room_datas.iter() {
-                let home_room_data = system_data.room_data.get(*home_room_entity).ok_or("Expected home room data")?;
-                let home_room = game::rooms().get(home_room_data.name).ok_or("Expected home room")?;
-
-                let body_definition = crate::creep::SpawnBodyDefinition {
-                    maximum_energy: home_room.energy_capacity_available(),
-                    minimum_repeat: Some(1),
-                    maximum_repeat: Some(2),
-                    pre_body: &[],
-                    repeat_body: &[Part::Claim, Part::Move],
-                    post_body: &[],
-                };
-
-                if let Ok(body) = crate::creep::spawning::create_body(&body_definition) {
-                    let priority = if alive_reservers.is_empty() {
-                        SPAWN_PRIORITY_MEDIUM
-                    } else {
-                        SPAWN_PRIORITY_LOW
-                    };
-
-                    let spawn_request = SpawnRequest::new(
-                        format!("Reserver - Target Room: {}", room_data.name),
-                        &body,
-                        priority,
-                        Some(token),
-                        Self::create_handle_reserver_spawn(mission_entity, *controller_id),
-                    );
-
-                    system_data.spawn_queue.request(*home_room_entity, spawn_request);
-                }
+            .count();
+
+        // One reserver keeps a reservation up; a contested or attacked one gets a second to wear the other
+        // down faster, or out-reserve the claimer until the interceptor gets there.
+        let desired_reservers = if contested || attacked { 2 } else { 1 };
+
+        if alive_reservers >= desired_reservers {
+            return Ok(MissionResult::Running);
+        }
+
+        let claim_parts = reserver_claim_parts(self.reservation_level, contested || attacked);
+
+        let priority = if contested || attacked {
+            SPAWN_PRIORITY_HIGH
+        } else if remaining == 0 {
+            SPAWN_PRIORITY_MEDIUM
+        } else {
+            SPAWN_PRIORITY_LOW
+        };
+
+        let token = system_data.spawn_queue.token();
+
+        for home_room_entity in self.home_room_datas.iter() {
+            let home_room_data = system_data
+                .room_data
+                .get(*home_room_entity)
+                .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected home room data"))?;
+            let home_room = game::rooms()
+                .get(home_room_data.name)
+                .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected home room"))?;
+
+            let body_definition = crate::creep::SpawnBodyDefinition {
+                maximum_energy: home_room.energy_capacity_available(),
+                minimum_repeat: Some(1),
+                maximum_repeat: Some(claim_parts as usize),
+                pre_body: &[],
+                repeat_body: &[Part::Claim, Part::Move],
+                post_body: &[],
+            };
+
+            let Ok(body) = crate::creep::spawning::create_body(&body_definition) else {
+                continue;
+            };
+
+            let travel_ticks =
+                (game::map::get_room_linear_distance(home_room_data.name, room_data.name, false) + 1) * TRAVEL_TICKS_PER_ROOM;
+            let spawn_ticks = body.len() as u32 * CREEP_SPAWN_TIME;
+
+            if !contested && !attacked && !reserver_due(remaining, travel_ticks, spawn_ticks) {
+                continue;
             }
+
+            debug!(
+                "Reserver due for {} from {}: {} reserved, {} travel, {} spawn{}",
+                room_data.name,
+                home_room_data.name,
+                remaining,
+                travel_ticks,
+                spawn_ticks,
+                if contested {
+                    " (contested)"
+                } else if attacked {
+                    " (attacked)"
+                } else {
+                    ""
+                }
+            );
+
+            let spawn_request = SpawnRequest::new(
+                format!("Reserver - Target Room: {}", room_data.name),
+                &body,
+                priority,
+                Some(token),
+                Self::create_handle_reserver_spawn(mission_entity, *controller_id),
+            )
+            .requested_by(mission_entity);
+
+            system_data.spawn_queue.request(*home_room_entity, spawn_request);
         }
 
         Ok(MissionResult::Running)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_reserver_is_due_when_the_reservation_would_lapse_before_it_arrives() {
+        // 100 ticks on the road, 12 spawning, 100 margin.
+        assert!(!reserver_due(213, 100, 12));
+        assert!(reserver_due(212, 100, 12));
+        assert!(reserver_due(0, 100, 12));
+    }
+
+    #[test]
+    fn reservers_grow_where_the_reservation_runs_low_or_is_contested() {
+        assert_eq!(reserver_claim_parts(None, false), RESERVER_CLAIM_PARTS);
+        assert_eq!(reserver_claim_parts(Some(4_500), false), RESERVER_CLAIM_PARTS);
+        assert_eq!(reserver_claim_parts(Some(1_000), false), RESERVER_LARGE_CLAIM_PARTS);
+        assert_eq!(reserver_claim_parts(Some(4_500), true), RESERVER_LARGE_CLAIM_PARTS);
+
+        assert_eq!(sample_reservation(None, 3_000), 3_000);
+        assert_eq!(sample_reservation(Some(3_000), 1_000), 2_800);
+    }
+
+    #[test]
+    fn a_reservation_ending_earlier_than_last_sighted_was_knocked_down() {
+        assert!(!reservation_knocked_down(None, 5_000));
+        assert!(!reservation_knocked_down(Some(5_000), 5_000));
+        assert!(!reservation_knocked_down(Some(5_000), 5_400));
+        assert!(reservation_knocked_down(Some(5_000), 4_990));
+    }
+
+    #[test]
+    fn contested_flags_count_from_the_first_sighting_and_lapse() {
+        let room: RoomName = "W1N1".parse().unwrap();
+        let mut contested = ContestedReservations::default();
+
+        contested.flag(room, "rival", 100);
+        contested.flag(room, "rival", 300);
+        assert_eq!(contested.contested_for(room, 300), Some(200));
+        assert_eq!(contested.contested(300).collect::<Vec<_>>(), vec![(room, "rival")]);
+
+        assert_eq!(contested.contested_for(room, 300 + CONTESTED_FLAG_TTL + 1), None);
+
+        // A different player, or a flag after a lapse, starts the count again.
+        contested.flag(room, "other", 350);
+        assert_eq!(contested.contested_for(room, 350), Some(0));
+    }
+}
@@ -1,5 +1,6 @@
 use super::data::*;
 use super::missionsystem::*;
+use crate::intents::IntentCategory;
 use crate::serialize::*;
 use log::*;
 use screeps::*;
@@ -38,6 +39,7 @@ pub struct SafeModeMission {
     last_eval_tick: u32,
     /// Whether safe mode has been activated by this mission (to avoid re-triggering).
     activated: bool,
+    paused: bool,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -51,6 +53,7 @@ impl SafeModeMission {
             room_data,
             last_eval_tick: 0,
             activated: false,
+            paused: false,
         };
 
         builder
@@ -61,6 +64,8 @@ impl SafeModeMission {
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl Mission for SafeModeMission {
+    impl_mission_pause!();
+
     fn get_owner(&self) -> &Option<Entity> {
         &self.owner
     }
@@ -90,7 +95,11 @@ impl Mission for SafeModeMission {
         }
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<MissionResult, String> {
+    fn run_mission(
+        &mut self,
+        system_data: &mut MissionExecutionSystemData,
+        _mission_entity: Entity,
+    ) -> Result<MissionResult, MissionError> {
         let features = system_data.features;
 
         if !features.military.safe_mode {
@@ -108,7 +117,10 @@ impl Mission for SafeModeMission {
         }
         self.last_eval_tick = current_tick;
 
-        let room_data = system_data.room_data.get(self.room_data).ok_or("Expected room data")?;
+        let room_data = system_data
+            .room_data
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
 
         let room = match game::rooms().get(room_data.name) {
             Some(r) => r,
@@ -130,28 +142,10 @@ impl Mission for SafeModeMission {
             return Ok(MissionResult::Running);
         }
 
-        // Calculate total hostile DPS.
-        let mut total_hostile_dps: f32 = 0.0;
-        let mut has_work_parts = false;
-
-        for hostile in hostiles {
-            for part_info in hostile.body().iter() {
-                if part_info.hits() == 0 {
-                    continue;
-                }
-                let boost_mult = if part_info.boost().is_some() { 4.0 } else { 1.0 };
-                match part_info.part() {
-                    Part::Attack => total_hostile_dps += 30.0 * boost_mult,
-                    Part::RangedAttack => total_hostile_dps += 10.0 * boost_mult,
-                    Part::Work => {
-                        has_work_parts = true;
-                        // Dismantle damage: 50 per WORK part per tick.
-                        total_hostile_dps += 50.0 * boost_mult;
-                    }
-                    _ => {}
-                }
-            }
-        }
+        // Calculate total hostile DPS, dismantling included.
+        let bodies = creeps.hostile_summary().bodies();
+        let total_hostile_dps: f32 = bodies.iter().map(|b| b.dps() + b.dismantle_per_tick).sum();
+        let has_work_parts = bodies.iter().any(|b| b.work > 0);
 
         // Check if any critical structure is in danger.
         let mut critical_in_danger = false;
@@ -172,9 +166,8 @@ impl Mission for SafeModeMission {
         // Check if hostiles are dismantling (WORK parts near structures).
         if has_work_parts && total_hostile_dps > SAFE_MODE_DPS_THRESHOLD {
             // Check if any hostile with WORK parts is adjacent to a critical structure.
-            for hostile in hostiles {
-                let has_work = hostile.body().iter().any(|p| p.part() == Part::Work && p.hits() > 0);
-                if !has_work {
+            for (hostile, body) in creeps.hostile_bodies() {
+                if body.work == 0 {
                     continue;
                 }
 
@@ -239,7 +232,10 @@ impl Mission for SafeModeMission {
             room_data.name, total_hostile_dps
         );
 
-        match controller.activate_safe_mode() {
+        match system_data
+            .intent_recorder
+            .issued(IntentCategory::Structure, controller.activate_safe_mode())
+        {
             Ok(()) => {
                 warn!("[SafeMode] Safe mode activated successfully in room {}", room_data.name);
                 self.activated = true;
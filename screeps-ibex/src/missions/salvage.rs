@@ -1,13 +1,18 @@
 use super::constants::*;
 use super::data::*;
+use super::localsupply::room_transfer::hostile_tower_cover;
 use super::missionsystem::*;
 use super::utility::*;
+use crate::creep::spawning::BodyTemplate;
 use crate::creep::*;
 use crate::jobs::data::*;
 use crate::jobs::dismantle::*;
 use crate::jobs::haul::*;
 use crate::jobs::utility::dismantle::*;
 use crate::jobs::utility::dismantlebehavior::*;
+use crate::military::composition::declaim_composition;
+use crate::military::damage::total_tower_damage;
+use crate::military::escort::*;
 use crate::military::objective_queue::*;
 use crate::remoteobjectid::*;
 use crate::room::data::*;
@@ -31,12 +36,133 @@ const MAX_RAIDERS: usize = 3;
 /// Dismantle hit pool above which a second dismantler is worth spawning.
 const SECOND_DISMANTLER_HITS: u32 = 1_000_000;
 
+/// Hostile sightings older than this don't count against the raid.
+const RAID_THREAT_MAX_AGE: u32 = ESCORT_THREAT_MAX_AGE;
+
+/// What a looted resource is worth to us, most valuable first. Raiders
+/// withdraw a higher class before a lower one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LootClass {
+    Boost,
+    Power,
+    Mineral,
+    Energy,
+}
+
+impl LootClass {
+    pub fn of(resource: ResourceType) -> LootClass {
+        match resource {
+            ResourceType::Energy => LootClass::Energy,
+            ResourceType::Power | ResourceType::Ops => LootClass::Power,
+            _ if boost_tier(resource).is_some() => LootClass::Boost,
+            _ => LootClass::Mineral,
+        }
+    }
+
+    /// The transfer queue has three active priorities, so minerals and energy
+    /// share the lowest one.
+    fn withdraw_priority(self) -> TransferPriority {
+        match self {
+            LootClass::Boost => TransferPriority::High,
+            LootClass::Power => TransferPriority::Medium,
+            LootClass::Mineral | LootClass::Energy => TransferPriority::Low,
+        }
+    }
+}
+
+/// 1 for a boost made straight from minerals, 2 for its acid/alkalide, 3 for
+/// the catalyzed one. `None` for anything that boosts nothing, including the
+/// intermediate compounds (OH, ZK, UL, G).
+fn boost_tier(resource: ResourceType) -> Option<u32> {
+    let components = resource.reaction_components()?;
+
+    if components.contains(&ResourceType::Catalyst) {
+        Some(3)
+    } else if components.contains(&ResourceType::Hydroxide) {
+        Some(2)
+    } else if matches!(
+        resource,
+        ResourceType::Hydroxide | ResourceType::ZynthiumKeanite | ResourceType::UtriumLemergite | ResourceType::Ghodium
+    ) {
+        None
+    } else {
+        Some(1)
+    }
+}
+
+/// Value of one unit of `resource`, in energy: the table raid creep costs are
+/// weighed against. Boosts > power > minerals > energy; catalyst, ghodium and
+/// commodities rate above the base minerals.
+pub fn loot_value(resource: ResourceType) -> u32 {
+    match LootClass::of(resource) {
+        LootClass::Boost => match boost_tier(resource) {
+            Some(3) => 16,
+            Some(2) => 10,
+            _ => 6,
+        },
+        LootClass::Power => 5,
+        LootClass::Mineral => match resource {
+            ResourceType::Hydrogen
+            | ResourceType::Oxygen
+            | ResourceType::Utrium
+            | ResourceType::Lemergium
+            | ResourceType::Keanium
+            | ResourceType::Zynthium => 2,
+            _ => 3,
+        },
+        LootClass::Energy => 1,
+    }
+}
+
+/// Energy value one raider can expect to bring home from `amount` units of
+/// loot worth `value` in total: all of it if it fits in a lifetime of trips,
+/// otherwise a lifetime's share at the loot's average value.
+pub fn expected_raider_haul(amount: u32, value: u32) -> u32 {
+    if amount == 0 {
+        return 0;
+    }
+
+    (value as u64 * amount.min(RAIDER_LOOT_PER_LIFETIME) as u64 / amount as u64) as u32
+}
+
+/// Raiders worth sending at `amount` units of loot worth `value`: none once
+/// a raider's expected haul is below `raider_cost`.
+pub fn raiders_for_loot(amount: u32, value: u32, raider_cost: u32) -> usize {
+    if amount == 0 || expected_raider_haul(amount, value) < raider_cost {
+        0
+    } else {
+        (amount.div_ceil(RAIDER_LOOT_PER_LIFETIME) as usize).clamp(1, MAX_RAIDERS)
+    }
+}
+
+/// Whether the defense at the loot is more than the raid's escort can heal
+/// through: the damage model's tower damage at the worst-placed store plus
+/// the hostile creeps' attack.
+pub fn loot_defended(tower_positions: &[Position], hostile_attack_dps: f32, loot_sites: &[Position]) -> bool {
+    let tower_damage = loot_sites
+        .iter()
+        .map(|pos| total_tower_damage(tower_positions, *pos))
+        .fold(0.0, f32::max);
+
+    tower_damage + hostile_attack_dps > (ESCORT_HEAL_PARTS * HEAL_POWER) as f32
+}
+
+/// The loot a raid last saw, carried between sightings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LootEstimate {
+    pub amount: u32,
+    pub value: u32,
+    pub seen_at: u32,
+}
+
 /// Salvage work observed in a room. `dismantle_hits` is decay-adjusted and
-/// horizon-filtered; loot quantities are raw store contents in scope.
+/// horizon-filtered; loot quantities are raw store contents in scope, and
+/// `loot_value` is their worth by [`loot_value`].
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct SalvageWork {
     pub loot_energy: u32,
     pub loot_other: u32,
+    pub loot_value: u32,
     pub dismantle_hits: u32,
 }
 
@@ -66,6 +192,7 @@ pub fn assess_salvage_work(
             if let Some(store) = structure.as_has_store() {
                 for resource in store.store().store_types() {
                     let amount = store.store().get_used_capacity(Some(resource));
+                    work.loot_value += amount * loot_value(resource);
                     if resource == ResourceType::Energy {
                         work.loot_energy += amount;
                     } else {
@@ -114,6 +241,13 @@ pub fn assess_salvage_work(
 /// takeover is deliberately NOT this mission's job — once the owner's
 /// controller decays to neutral, the mining-outpost pipeline takes the room
 /// over through its normal candidate flow.
+///
+/// Raids on a player's stores run the same way: raiders withdraw by
+/// [`LootClass`] (boosts first), deliver to the home nearest the target and
+/// go back until the loot is gone or the room is [`loot_defended`]; a player
+/// room also gets a caravan escort while raiders are out. `loot_estimate` is
+/// the loot last seen, so raiders keep cycling between sightings; raiding
+/// stops once a raider's expected haul is below its body cost.
 #[derive(ConvertSaveload)]
 pub struct SalvageMission {
     owner: EntityOption<Entity>,
@@ -121,6 +255,8 @@ pub struct SalvageMission {
     home_room_datas: EntityVec<Entity>,
     raiders: EntityVec<Entity>,
     dismantlers: EntityVec<Entity>,
+    loot_estimate: Option<LootEstimate>,
+    paused: bool,
 }
 
 /// EPHEMERAL (ADR 0027 v1.1 P1): the breach-blocker pos of the v1 `Dismantle`
@@ -161,7 +297,7 @@ impl SalvageMission {
         let mission = SalvageMission::new(owner, room_data, home_room_datas);
 
         builder
-            .with(MissionData::Salvage(EntityRefCell::new(mission)))
+            .with(MissionData::from(mission))
             .marked::<SerializeMarker>()
     }
 
@@ -172,22 +308,21 @@ impl SalvageMission {
             home_room_datas: home_room_datas.to_owned().into(),
             raiders: EntityVec::new(),
             dismantlers: EntityVec::new(),
+            loot_estimate: None,
+            paused: false,
         }
     }
 
     fn create_handle_raider_spawn(
         mission_entity: Entity,
         raid_room: Entity,
-        delivery_rooms: &[Entity],
+        delivery_room: Entity,
     ) -> crate::spawnsystem::SpawnQueueCallback {
-        let delivery_rooms = delivery_rooms.to_owned();
-
         Box::new(move |spawn_system_data, name| {
             let name = name.to_string();
-            let delivery_rooms = delivery_rooms.clone();
 
             spawn_system_data.updater.exec_mut(move |world| {
-                let creep_job = JobData::Haul(HaulJob::new(&[raid_room], &delivery_rooms, false, false));
+                let creep_job = JobData::Haul(HaulJob::new(&[raid_room], &[delivery_room], false, false));
 
                 let creep_entity = crate::creep::spawning::build(world.create_entity(), &name).with(creep_job).build();
 
@@ -261,7 +396,7 @@ impl SalvageMission {
                             let transfer_request = TransferWithdrawRequest::new(
                                 transfer_target,
                                 resource,
-                                TransferPriority::Low,
+                                LootClass::of(resource).withdraw_priority(),
                                 resource_amount,
                                 TransferType::Haul,
                             );
@@ -276,35 +411,61 @@ impl SalvageMission {
         Ok(())
     }
 
+    /// Carry-heavy raider body: CARRY with MOVE to keep full speed off-road.
+    fn raider_body(energy: u32) -> Option<Vec<Part>> {
+        BodyTemplate::new(&[Part::Carry]).plains().build(energy)
+    }
+
+    /// Body cost of the largest raider any home can spawn.
+    fn raider_cost(&self, system_data: &MissionExecutionSystemData) -> Option<u32> {
+        self.home_room_datas
+            .iter()
+            .filter_map(|&e| system_data.room_data.get(e))
+            .filter_map(|rd| game::rooms().get(rd.name))
+            .map(|r| r.energy_capacity_available())
+            .max()
+            .and_then(Self::raider_body)
+            .map(|body| body.iter().map(|part| part.cost()).sum())
+    }
+
+    /// The home raiders deliver to: the nearest to the target.
+    fn nearest_home(&self, system_data: &MissionExecutionSystemData, room_name: RoomName) -> Option<Entity> {
+        self.home_room_datas
+            .iter()
+            .filter_map(|&e| system_data.room_data.get(e).map(|rd| (e, rd.name)))
+            .min_by_key(|(_, name)| game::map::get_room_linear_distance(room_name, *name, false))
+            .map(|(e, _)| e)
+    }
+
     fn spawn_raiders(
         &self,
         system_data: &mut MissionExecutionSystemData,
         mission_entity: Entity,
         room_name: RoomName,
-    ) -> Result<(), String> {
+    ) -> Result<(), MissionError> {
         let token = system_data.spawn_queue.token();
+        let delivery_room = self
+            .nearest_home(system_data, room_name)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected home room"))?;
 
         for home_room_entity in self.home_room_datas.iter() {
-            let home_room_data = system_data.room_data.get(*home_room_entity).ok_or("Expected home room data")?;
-            let home_room = game::rooms().get(home_room_data.name).ok_or("Expected home room")?;
-
-            let body_definition = SpawnBodyDefinition {
-                maximum_energy: home_room.energy_capacity_available(),
-                minimum_repeat: Some(1),
-                maximum_repeat: None,
-                pre_body: &[],
-                repeat_body: &[Part::Carry, Part::Move],
-                post_body: &[],
-            };
+            let home_room_data = system_data
+                .room_data
+                .get(*home_room_entity)
+                .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected home room data"))?;
+            let home_room = game::rooms()
+                .get(home_room_data.name)
+                .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected home room"))?;
 
-            if let Ok(body) = crate::creep::spawning::create_body(&body_definition) {
+            if let Some(body) = Self::raider_body(home_room.energy_capacity_available()) {
                 let spawn_request = SpawnRequest::new(
                     format!("Raider - Target Room: {}", room_name),
                     &body,
                     SPAWN_PRIORITY_LOW,
                     Some(token),
-                    Self::create_handle_raider_spawn(mission_entity, self.room_data, &self.home_room_datas),
-                );
+                    Self::create_handle_raider_spawn(mission_entity, self.room_data, delivery_room),
+                )
+                .requested_by(mission_entity);
 
                 system_data.spawn_queue.request(*home_room_entity, spawn_request);
             }
@@ -313,18 +474,65 @@ impl SalvageMission {
         Ok(())
     }
 
+    /// Keep a caravan escort on the raiders while they work a player's room;
+    /// withdraw it otherwise.
+    fn update_escort(&self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity, room_name: RoomName, player_room: bool) {
+        if player_room && !self.raiders.is_empty() {
+            let caravan = Caravan {
+                pickup_room: room_name,
+                threatened_rooms: vec![room_name],
+                haulers: self.raiders.iter().copied().collect(),
+            };
+
+            system_data.escort_request.request(mission_entity, caravan, game::time());
+        } else {
+            system_data.escort_request.withdraw(mission_entity);
+        }
+    }
+
+    /// Between sightings the raid runs on `loot_estimate`: raiders keep
+    /// cycling while the loot last seen is worth their cost, and bring vision
+    /// back when they arrive.
+    fn run_unseen_raid(
+        &mut self,
+        system_data: &mut MissionExecutionSystemData,
+        mission_entity: Entity,
+        room_name: RoomName,
+        player_room: bool,
+    ) -> Result<MissionResult, MissionError> {
+        let desired_raiders = match (self.loot_estimate, self.raider_cost(system_data)) {
+            (Some(estimate), Some(raider_cost)) if system_data.features.raid => {
+                raiders_for_loot(estimate.amount, estimate.value, raider_cost)
+            }
+            _ => 0,
+        };
+
+        self.update_escort(system_data, mission_entity, room_name, player_room);
+
+        if self.raiders.len() < desired_raiders && system_data.governor.can_execute_cpu(CpuBar::LowPriority) {
+            self.spawn_raiders(system_data, mission_entity, room_name)?;
+        }
+
+        Ok(MissionResult::Running)
+    }
+
     fn spawn_dismantlers(
         &self,
         system_data: &mut MissionExecutionSystemData,
         mission_entity: Entity,
         max_structure_hits: u32,
         priority: f32,
-    ) -> Result<(), String> {
+    ) -> Result<(), MissionError> {
         let token = system_data.spawn_queue.token();
 
         for home_room_entity in self.home_room_datas.iter() {
-            let home_room_data = system_data.room_data.get(*home_room_entity).ok_or("Expected home room data")?;
-            let home_room = game::rooms().get(home_room_data.name).ok_or("Expected home room")?;
+            let home_room_data = system_data
+                .room_data
+                .get(*home_room_entity)
+                .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected home room data"))?;
+            let home_room = game::rooms()
+                .get(home_room_data.name)
+                .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected home room"))?;
 
             let body_definition = if home_room_data.get_structures().map(|s| !s.storages().is_empty()).unwrap_or(false) {
                 SpawnBodyDefinition {
@@ -366,7 +574,8 @@ impl SalvageMission {
                     priority,
                     Some(token),
                     Self::create_handle_dismantler_spawn(mission_entity, self.room_data, *home_room_entity, max_structure_hits),
-                );
+                )
+                .requested_by(mission_entity);
 
                 system_data.spawn_queue.request(*home_room_entity, spawn_request);
             }
@@ -415,10 +624,6 @@ impl SalvageMission {
     /// against a walled-in controller. The EV/admit decision stays in `SalvageOperation` (the mission only
     /// reaches here for a strategic, hostile-owned, sourced derelict room).
     fn request_declaim_objective(&self, system_data: &mut MissionExecutionSystemData, room_name: RoomName, controller_pos: Position) {
-        use screeps_combat_decision::composition::CompositionParams;
-        use screeps_combat_decision::doctrine;
-        use screeps_combat_decision::force_sizing::DefenseProfile;
-
         let member_energy = self
             .home_room_datas
             .iter()
@@ -428,26 +633,9 @@ impl SalvageMission {
             .max()
             .unwrap_or(0);
 
-        // Project into the doctrine registry: a `Declaim` objective → the always-field `DeclaimAttack`
-        // doctrine, which sizes a CLAIM declaimer squad directly (a derelict controller is undefended by
-        // construction — no scouted enemy/towers; the mission aborts on re-arm). No EV gate here (the gate is
-        // upstream in SalvageOperation).
-        let ctx = doctrine::EngagementContext {
-            objective: doctrine::DoctrineObjective::Declaim,
-            coordination: doctrine::EnemyCoordination::Individual,
-            defense: DefenseProfile::default(),
-            enemy_force: None,
-            importance: 0.0,
-            member_energy,
-            target_value: 1_000_000.0,
-            onsite_window: CREEP_LIFE_TIME,
-            params: CompositionParams { member_energy, ..Default::default() },
-            // Inert: Declaim is direct-assembled (CLAIM + MOVE only, no combat floor) — never reaches the
-            // always-field floor. Left false (the conservative default).
-            defense_intel_reliable: false,
-        };
-        let doctrines = doctrine::default_doctrines();
-        let Some(comp) = doctrine::decide_doctrine(&ctx, &doctrines).and_then(|d| doctrine::plan_engagement(d, &ctx, None).composition) else {
+        // A derelict controller is undefended by construction (the mission aborts on re-arm), and the EV
+        // gate is upstream in SalvageOperation.
+        let Some(comp) = declaim_composition(member_energy) else {
             // No home affords even one declaimer at this energy → don't emit (the manager could not field it).
             return;
         };
@@ -582,6 +770,8 @@ impl SalvageMission {
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl Mission for SalvageMission {
+    impl_mission_pause!();
+
     fn get_owner(&self) -> &Option<Entity> {
         &self.owner
     }
@@ -619,15 +809,18 @@ impl Mission for SalvageMission {
         ))
     }
 
-    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), String> {
+    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), MissionError> {
         self.home_room_datas
             .retain(|entity| system_data.room_data.get(*entity).map(is_valid_home_room).unwrap_or(false));
 
         if self.home_room_datas.is_empty() {
-            return Err("No home rooms for salvage mission".to_owned());
+            return Err(MissionError::new(MissionFailure::NoHomeRooms, "No home rooms for salvage mission"));
         }
 
-        let room_data = system_data.room_data.get(self.room_data).ok_or("Expected room data")?;
+        let room_data = system_data
+            .room_data
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
         let room_data_entity = self.room_data;
 
         system_data.transfer_queue.register_generator(
@@ -645,12 +838,15 @@ impl Mission for SalvageMission {
         Ok(())
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, String> {
+    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, MissionError> {
         let features = system_data.features;
         let derelict_features = features.derelict;
 
         if !derelict_features.on {
-            return Err("Derelict-room handling disabled - aborting salvage".to_string());
+            return Err(MissionError::new(
+                MissionFailure::Disabled,
+                "Derelict-room handling disabled - aborting salvage",
+            ));
         }
 
         // Phase 1: gates and work survey against an immutable room borrow.
@@ -663,31 +859,71 @@ impl Mission for SalvageMission {
         // `system_data.room_data`, which conflicts with the `&mut self`/`&mut system_data`
         // the withdraw needs, so the standdown decision is DEFERRED out of the borrow:
         // we capture it as `standdown` and handle withdraw+return after the block closes.
-        let mut standdown: Option<Result<MissionResult, String>> = None;
+        let mut standdown: Option<Result<MissionResult, MissionError>> = None;
+        let mut unseen = false;
+        let player_room;
         let survey_tuple = {
-            let room_data = system_data.room_data.get(self.room_data).ok_or("Expected room data")?;
-            let dynamic_visibility_data = room_data.get_dynamic_visibility_data().ok_or("Expected dynamic visibility data")?;
+            let room_data = system_data
+                .room_data
+                .get(self.room_data)
+                .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
+            let dynamic_visibility_data = room_data
+                .get_dynamic_visibility_data()
+                .ok_or_else(|| MissionError::new(MissionFailure::NoVisibility, "Expected dynamic visibility data"))?;
+            player_room = dynamic_visibility_data.owner().hostile();
 
             if dynamic_visibility_data.updated_within(1000) {
                 if dynamic_visibility_data.owner().mine() || dynamic_visibility_data.owner().friendly() {
                     // Claimed - colony/outpost machinery owns the room now.
                     standdown = Some(Ok(MissionResult::Success));
                 } else if dynamic_visibility_data.militarily_active() {
-                    standdown = Some(Err("Salvage target re-armed (spawn/tower/combat creeps) - aborting".to_string()));
+                    standdown = Some(Err(MissionError::new(
+                        MissionFailure::Hostile,
+                        "Salvage target re-armed (spawn/tower/combat creeps) - aborting",
+                    )));
                 } else if dynamic_visibility_data.owner().hostile() && !dynamic_visibility_data.derelict() {
                     // For hostile-owned targets, any threat-capable creep sighting
                     // (haulers refilling towers, claimers, healers) breaks the
                     // derelict classification even though it is not "militarised".
-                    standdown = Some(Err("Salvage target no longer derelict (hostile activity sighted) - aborting".to_string()));
+                    standdown = Some(Err(MissionError::new(
+                        MissionFailure::Hostile,
+                        "Salvage target no longer derelict (hostile activity sighted) - aborting",
+                    )));
                 }
             } else if !dynamic_visibility_data.updated_within(derelict_features.action_max_age) {
-                standdown = Some(Err("Salvage intel too stale - aborting".to_string()));
+                standdown = Some(Err(MissionError::new(MissionFailure::NoVisibility, "Salvage intel too stale - aborting")));
             }
 
             if standdown.is_none() && dynamic_visibility_data.safe_mode_active() {
                 // Safe mode blocks withdraw/dismantle for us; abort and let the
                 // operation re-admit once it has expired.
-                standdown = Some(Err("Salvage target under safe mode - aborting".to_string()));
+                standdown = Some(Err(MissionError::new(MissionFailure::TargetInvalid, "Salvage target under safe mode - aborting")));
+            }
+
+            if standdown.is_none() {
+                // A player's stores are only raided while the damage model
+                // says the escort can heal through what defends them.
+                if let Some(structures) = room_data.get_structures() {
+                    let threat = system_data
+                        .threat_data
+                        .get(self.room_data)
+                        .filter(|threat| game::time().saturating_sub(threat.last_seen) <= RAID_THREAT_MAX_AGE);
+                    let loot_sites: Vec<Position> = structures
+                        .storages()
+                        .iter()
+                        .filter(|s| !s.my())
+                        .map(|s| s.pos())
+                        .chain(structures.terminals().iter().filter(|t| !t.my()).map(|t| t.pos()))
+                        .collect();
+                    let attack_dps = threat.map(|threat| threat.estimated_attack_dps).unwrap_or(0.0);
+
+                    if loot_defended(&hostile_tower_cover(threat), attack_dps, &loot_sites) {
+                        standdown = Some(Err(MissionError::new(
+                            MissionFailure::Hostile,
+                            "Salvage target defended (towers/creeps out-damage the escort) - aborting",
+                        )));
+                    }
+                }
             }
 
             if standdown.is_some() {
@@ -792,7 +1028,8 @@ impl Mission for SalvageMission {
                         VisibilityRequestFlags::ALL,
                     ));
 
-                    return Ok(MissionResult::Running);
+                    unseen = true;
+                    None
                 }
             }
             } // end else (no standdown)
@@ -804,23 +1041,44 @@ impl Mission for SalvageMission {
         // under the manager's deadline lease (the TTL alone would not retire it).
         // Mirrors the completion withdraw at the end of the tick. (ADR 0027 v1.1 P1+P2.)
         if let Some(result) = standdown {
+            system_data.escort_request.withdraw(mission_entity);
+
             if let Some(name) = system_data.room_data.get(self.room_data).map(|rd| rd.name) {
                 self.withdraw_breach_objective(system_data, name);
                 self.withdraw_declaim_objective(system_data, name);
+
+                // Claimed: the colony's supply missions take over a room we've been dismantling.
+                if matches!(result, Ok(MissionResult::Success)) {
+                    system_data.supply_structure_cache.dirty(name);
+                }
             }
             return result;
         }
-        // Safe: `None` standdown ⇒ `survey_tuple` is `Some` (the visibility-needed path
-        // returned above).
+        if unseen {
+            let room_name = system_data
+                .room_data
+                .get(self.room_data)
+                .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?
+                .name;
+            return self.run_unseen_raid(system_data, mission_entity, room_name, player_room);
+        }
+        // Safe: `None` standdown and not `unseen` ⇒ `survey_tuple` is `Some`.
         let (room_name, work, dismantle_ready, declaim_target, declaim_access, breach_possible, breach_target) =
             survey_tuple.expect("survey tuple present when not standing down");
 
+        self.loot_estimate = Some(LootEstimate {
+            amount: work.loot_total(),
+            value: work.loot_value,
+            seen_at: game::time(),
+        });
+
         // Per-role desired rosters from observed work. Disabled feature flags
         // zero the role; live creeps finish their jobs and expire naturally.
-        let desired_raiders = if features.raid && work.loot_total() > 0 {
-            (work.loot_total().div_ceil(RAIDER_LOOT_PER_LIFETIME) as usize).clamp(1, MAX_RAIDERS)
-        } else {
-            0
+        // Raiding stops once a raider's expected haul is below its cost.
+        let raider_cost = self.raider_cost(system_data);
+        let desired_raiders = match raider_cost {
+            Some(raider_cost) if features.raid => raiders_for_loot(work.loot_total(), work.loot_value, raider_cost),
+            _ => 0,
         };
 
         // Breach: when an objective (source for mining, or the controller for
@@ -912,10 +1170,12 @@ impl Mission for SalvageMission {
                 .unwrap_or((0, 0));
 
             info!(
-                "[salvage-mission-diag] {} loot(e={},o={}) dismantle_hits={} dismantle_ready={} declaim_target={} declaim_access={:?} declaim_obj_live={} declaim_wanted={} breach_possible={} breach_needed={} breach_surplus={} breach_obj_live={} breach_target={:?} sources_reachable={}/{} -> desired raiders={} dismantlers={} alive r={} d={}",
+                "[salvage-mission-diag] {} loot(e={},o={},value={}) raider_cost={:?} dismantle_hits={} dismantle_ready={} declaim_target={} declaim_access={:?} declaim_obj_live={} declaim_wanted={} breach_possible={} breach_needed={} breach_surplus={} breach_obj_live={} breach_target={:?} sources_reachable={}/{} -> desired raiders={} dismantlers={} alive r={} d={}",
                 room_name,
                 work.loot_energy,
                 work.loot_other,
+                work.loot_value,
+                raider_cost,
                 work.dismantle_hits,
                 dismantle_ready,
                 declaim_target.is_some(),
@@ -980,9 +1240,10 @@ impl Mission for SalvageMission {
         // loop. (`breach_needed` ⊇ `breach_objective_live`.)
         if desired_raiders == 0 && desired_dismantlers == 0 && !declaim_wanted && !breach_needed {
             info!(
-                "Salvage of room {} complete - no enabled work remains (loot={}, within-horizon dismantle hits={}, declaim_access={:?}, breach_possible={})",
+                "Salvage of room {} complete - no enabled work remains (loot={}, value={}, within-horizon dismantle hits={}, declaim_access={:?}, breach_possible={})",
                 room_name,
                 work.loot_total(),
+                work.loot_value,
                 work.dismantle_hits,
                 declaim_access,
                 breach_possible
@@ -991,9 +1252,12 @@ impl Mission for SalvageMission {
             // Defensive: never leave a breach / declaim objective behind on completion.
             self.withdraw_breach_objective(system_data, room_name);
             self.withdraw_declaim_objective(system_data, room_name);
+            system_data.escort_request.withdraw(mission_entity);
             return Ok(MissionResult::Success);
         }
 
+        self.update_escort(system_data, mission_entity, room_name, player_room);
+
         if !system_data.governor.can_execute_cpu(CpuBar::LowPriority) {
             return Ok(MissionResult::Running);
         }
@@ -1019,3 +1283,56 @@ impl Mission for SalvageMission {
         Ok(MissionResult::Running)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loot_value_ranks_boosts_over_power_over_minerals_over_energy() {
+        assert_eq!(LootClass::of(ResourceType::CatalyzedGhodiumAcid), LootClass::Boost);
+        assert_eq!(LootClass::of(ResourceType::UtriumHydride), LootClass::Boost);
+        assert_eq!(LootClass::of(ResourceType::Power), LootClass::Power);
+        assert_eq!(LootClass::of(ResourceType::Hydroxide), LootClass::Mineral);
+        assert_eq!(LootClass::of(ResourceType::Catalyst), LootClass::Mineral);
+        assert_eq!(LootClass::of(ResourceType::Energy), LootClass::Energy);
+
+        assert!(loot_value(ResourceType::CatalyzedGhodiumAcid) > loot_value(ResourceType::GhodiumAcid));
+        assert!(loot_value(ResourceType::GhodiumAcid) > loot_value(ResourceType::GhodiumHydride));
+        assert!(loot_value(ResourceType::GhodiumHydride) > loot_value(ResourceType::Power));
+        assert!(loot_value(ResourceType::Power) > loot_value(ResourceType::Catalyst));
+        assert!(loot_value(ResourceType::Catalyst) > loot_value(ResourceType::Hydrogen));
+        assert!(loot_value(ResourceType::Hydrogen) > loot_value(ResourceType::Energy));
+    }
+
+    #[test]
+    fn raiding_stops_when_the_expected_haul_is_below_the_raider_cost() {
+        // 1000 energy-worth of loot is worth a 500-energy raider...
+        assert_eq!(raiders_for_loot(1_000, 1_000, 500), 1);
+        // ...but not a 1500-energy one.
+        assert_eq!(raiders_for_loot(1_000, 1_000, 1_500), 0);
+        assert_eq!(raiders_for_loot(0, 0, 100), 0);
+
+        // A raider only hauls a lifetime's share of a big stockpile.
+        assert_eq!(expected_raider_haul(RAIDER_LOOT_PER_LIFETIME * 4, 400_000), 100_000);
+        assert_eq!(raiders_for_loot(RAIDER_LOOT_PER_LIFETIME * 4, 400_000, 2_500), MAX_RAIDERS);
+    }
+
+    #[test]
+    fn loot_is_defended_once_towers_or_creeps_outdamage_the_escort() {
+        let pos = |x: u8, y: u8| {
+            Position::new(
+                RoomCoordinate::new(x).unwrap(),
+                RoomCoordinate::new(y).unwrap(),
+                "W1N1".parse().unwrap(),
+            )
+        };
+        let storage = [pos(25, 25)];
+
+        assert!(!loot_defended(&[], 0.0, &storage));
+        assert!(!loot_defended(&[], 20.0, &storage));
+        assert!(loot_defended(&[], 60.0, &storage));
+        // Even a far tower out-damages what the escort heals.
+        assert!(loot_defended(&[pos(2, 2)], 0.0, &storage));
+    }
+}
@@ -25,6 +25,7 @@ pub struct ScoutMission {
     scouts: EntityVec<Entity>,
     next_spawn: Option<u32>,
     spawned_scouts: u32,
+    paused: bool,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -49,6 +50,7 @@ impl ScoutMission {
             scouts: EntityVec::new(),
             next_spawn: None,
             spawned_scouts: 0,
+            paused: false,
         }
     }
 
@@ -102,6 +104,8 @@ impl ScoutMission {
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl Mission for ScoutMission {
+    impl_mission_pause!();
+
     fn get_owner(&self) -> &Option<Entity> {
         &self.owner
     }
@@ -154,7 +158,7 @@ impl Mission for ScoutMission {
         crate::visualization::SummaryContent::Text(format!("Scout - Scouts: {} - Priority: {:.0}", self.scouts.len(), self.priority))
     }
 
-    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), String> {
+    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), MissionError> {
         //
         // Cleanup home rooms that no longer exist.
         //
@@ -163,14 +167,17 @@ impl Mission for ScoutMission {
             .retain(|entity| system_data.room_data.get(*entity).map(is_valid_home_room).unwrap_or(false));
 
         if self.home_room_datas.is_empty() {
-            return Err("No home rooms for scout mission".to_owned());
+            return Err(MissionError::new(MissionFailure::NoHomeRooms, "No home rooms for scout mission"));
         }
 
         Ok(())
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, String> {
-        let room_data = system_data.room_data.get(self.room_data).ok_or("Expected room data")?;
+    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, MissionError> {
+        let room_data = system_data
+            .room_data
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
 
         let data_is_fresh = room_data
             .get_dynamic_visibility_data()
@@ -200,9 +207,9 @@ impl Mission for ScoutMission {
             // recovers on its own (the backoff clears on fresh visibility).
             system_data.visibility.mark_unreachable(room_data.name, game::time());
 
-            return Err(format!(
-                "Failed scout mission - unable to scout room after {} attempts",
-                self.spawned_scouts
+            return Err(MissionError::new(
+                MissionFailure::Timeout,
+                format!("Failed scout mission - unable to scout room after {} attempts", self.spawned_scouts),
             ));
         }
 
@@ -212,8 +219,13 @@ impl Mission for ScoutMission {
         let token = system_data.spawn_queue.token();
 
         for home_room_entity in self.home_room_datas.iter() {
-            let home_room_data = system_data.room_data.get(*home_room_entity).ok_or("Expected home room data")?;
-            let home_room = game::rooms().get(home_room_data.name).ok_or("Expected home room")?;
+            let home_room_data = system_data
+                .room_data
+                .get(*home_room_entity)
+                .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected home room data"))?;
+            let home_room = game::rooms()
+                .get(home_room_data.name)
+                .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected home room"))?;
 
             if self.scouts.is_empty() && should_spawn {
                 //TODO: Compute best body parts to use.
@@ -245,7 +257,8 @@ impl Mission for ScoutMission {
                         priority,
                         Some(token),
                         Self::create_handle_scout_spawn(mission_entity),
-                    );
+                    )
+                    .requested_by(mission_entity);
 
                     system_data.spawn_queue.request(*home_room_entity, spawn_request);
                 }
@@ -12,7 +12,8 @@
 //! on the [`CombatObjectiveQueue`](crate::military::objective_queue) — the
 //! `SquadManager` fields the `duo_sk_farmer` that suppresses the keepers — and (2)
 //! owns the **K3 mining**: a per-source [`SourceMiningMission`] child + a long-haul
-//! [`HaulMission`] child, each gated on a **per-source suppression signal** (no live
+//! [`HaulMission`] child (demand on the home room's hauler pool, which does the
+//! hauling), each gated on a **per-source suppression signal** (no live
 //! keeper near that source). The duo creates the dead-keeper windows; mining
 //! exploits them per source. Miners self-protect via the K0 `Flee` reflex, so a
 //! keeper that reappears costs a flee, not a death.
@@ -139,6 +140,7 @@ pub struct SourceKeeperFarmMission {
     source_mining_missions: EntityVec<Entity>,
     /// K3: the long-haul-home child for the SK room.
     haul_mission: EntityOption<Entity>,
+    paused: bool,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -150,7 +152,7 @@ impl SourceKeeperFarmMission {
         let mission = SourceKeeperFarmMission::new(owner, sk_room_data, home_room_datas);
 
         builder
-            .with(MissionData::SourceKeeperFarm(EntityRefCell::new(mission)))
+            .with(MissionData::from(mission))
             .marked::<SerializeMarker>()
     }
 
@@ -161,15 +163,19 @@ impl SourceKeeperFarmMission {
             home_room_datas: home_room_datas.to_owned().into(),
             source_mining_missions: EntityVec::new(),
             haul_mission: None.into(),
+            paused: false,
         }
     }
 
     /// Ensure a `SourceMiningMission` exists per source in the SK room plus one
     /// `HaulMission` (long-haul home). Mirrors `LocalSupplyMission::ensure_children`
     /// / `MiningOutpostMission`'s child creation, but per-source-gated below.
-    fn ensure_mining_children(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<(), String> {
+    fn ensure_mining_children(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<(), MissionError> {
         let (room_name, sources): (screeps::RoomName, Vec<RemoteObjectId<Source>>) = {
-            let room_data = system_data.room_data.get(self.sk_room_data).ok_or("Expected SK room data")?;
+            let room_data = system_data
+                .room_data
+                .get(self.sk_room_data)
+                .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected SK room data"))?;
             match room_data.get_static_visibility_data() {
                 // Not yet scouted; the mining child requests visibility when it exists.
                 Some(svd) => (room_data.name, svd.sources().clone()),
@@ -282,6 +288,8 @@ impl SourceKeeperFarmMission {
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl Mission for SourceKeeperFarmMission {
+    impl_mission_pause!();
+
     fn get_owner(&self) -> &Option<Entity> {
         &self.owner
     }
@@ -304,20 +312,20 @@ impl Mission for SourceKeeperFarmMission {
         crate::visualization::SummaryContent::Text("SK Farm".to_string())
     }
 
-    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), String> {
+    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), MissionError> {
         // Drop home rooms that no longer qualify; the operation re-creates the
         // mission with fresh homes if it still wants the farm.
         self.home_room_datas
             .retain(|entity| system_data.room_data.get(*entity).map(is_valid_home_room).unwrap_or(false));
 
         if self.home_room_datas.is_empty() {
-            return Err("No home rooms for source keeper farm".to_owned());
+            return Err(MissionError::new(MissionFailure::NoHomeRooms, "No home rooms for source keeper farm"));
         }
 
         Ok(())
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, String> {
+    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, MissionError> {
         // Self-cancel when PERMANENTLY no longer viable (ADR 0018 §3.5). The
         // operation cannot retire us (its `mission_data` is read-only), so we
         // release the farm (→ cleanup) ourselves. `Success` here is "withdrawn",
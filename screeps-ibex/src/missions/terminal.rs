@@ -1,9 +1,14 @@
 use super::constants::*;
 use super::data::*;
 use super::missionsystem::*;
+use super::overflow::EnergySink;
+use crate::features::ConsolidationFeatures;
+use crate::intents::{IntentCategory, IntentRecorder};
+use crate::ledger::{LedgerCategory, ResourceLedger};
 use crate::remoteobjectid::*;
 use crate::room::data::*;
 use crate::serialize::*;
+use crate::transfer::energyarbitrage::EnergyArbitrageVolume;
 use crate::transfer::ordersystem::*;
 use crate::transfer::transfersystem::*;
 use crate::transfer::utility::*;
@@ -14,12 +19,276 @@ use serde::{Deserialize, Serialize};
 use specs::error::NoError;
 use specs::saveload::*;
 use specs::*;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+
+/// Ticks between consolidation sends from one terminal.
+const CONSOLIDATION_INTERVAL: u32 = 25;
+
+/// Ticks a deferred terminal action waits for the terminal before it is
+/// dropped: a cooldown plus an order queue pass, so a deal passed over once
+/// still gets its turn.
+pub const TERMINAL_ACTION_TTL: u32 = 50;
+/// Score of a market deal before its proceeds: above routine balancing
+/// (low or no transfer priority), below shipments something is waiting on.
+const DEAL_SCORE: f32 = 150.0;
+/// Deal proceeds (credits) worth one point of score, up to [`DEAL_SCORE_SPAN`].
+const DEAL_CREDITS_PER_POINT: f64 = 1_000.0;
+const DEAL_SCORE_SPAN: f32 = 49.0;
+/// Score of a consolidation send; it goes when nothing else wants the terminal.
+const CONSOLIDATION_SCORE: f32 = 25.0;
+
+/// Score of a terminal-to-terminal transfer the transfer queue asked for at
+/// `priority`.
+fn transfer_score(priority: TransferPriority) -> f32 {
+    match priority {
+        TransferPriority::High => 300.0,
+        TransferPriority::Medium => 200.0,
+        TransferPriority::Low => 100.0,
+        TransferPriority::None => 50.0,
+    }
+}
+
+/// Score of a market deal bringing in (or, for a purchase, landing goods worth) `proceeds` credits.
+pub fn deal_score(proceeds: f64) -> f32 {
+    DEAL_SCORE + ((proceeds.max(0.0) / DEAL_CREDITS_PER_POINT) as f32).min(DEAL_SCORE_SPAN)
+}
+
+/// Engine `Game.market.calcTransactionCost`: the energy a terminal pays to
+/// send `amount` across `distance` rooms (`ceil(amount · (1 − e^(−d/30)))`).
+fn terminal_send_cost(amount: u32, distance: u32) -> u32 {
+    (amount as f64 * (1.0 - (-(distance as f64) / 30.0).exp())).ceil() as u32
+}
+
+/// How much of a resource to ship to the hub: what the room holds above
+/// `reserve`, limited to what is already in the terminal and to what the
+/// terminal's energy can pay the fee on. `None` below `min_batch`.
+fn consolidation_amount(
+    terminal_amount: u32,
+    total_amount: u32,
+    reserve: u32,
+    min_batch: u32,
+    terminal_energy: u32,
+    distance: u32,
+) -> Option<u32> {
+    let surplus = total_amount.saturating_sub(reserve).min(terminal_amount);
+
+    let fee_rate = 1.0 - (-(distance as f64) / 30.0).exp();
+    let affordable = if fee_rate > 0.0 {
+        (terminal_energy as f64 / fee_rate).floor().min(u32::MAX as f64) as u32
+    } else {
+        u32::MAX
+    };
+
+    let amount = surplus.min(affordable);
+
+    (amount > 0 && amount >= min_batch).then_some(amount)
+}
+
+/// Where a resource held in both storage and terminal belongs. The terminal
+/// keeps between `low` and `high`, filling past `low` only from what storage
+/// holds beyond `storage_floor`; storage holds everything else.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TerminalBand {
+    pub low: u32,
+    pub high: u32,
+    pub storage_floor: u32,
+    /// How far the terminal may drift from its target before it is hauled
+    /// back, so small swings in either store don't start round trips.
+    pub hysteresis: u32,
+}
+
+/// A haul between storage and terminal, with its amount and priority.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BandTransfer {
+    ToTerminal(u32, TransferPriority),
+    ToStorage(u32, TransferPriority),
+}
+
+impl TerminalBand {
+    /// What the terminal should hold out of the `total` the room holds.
+    pub fn target(&self, total: u32) -> u32 {
+        total
+            .saturating_sub(self.storage_floor)
+            .clamp(self.low, self.high.max(self.low))
+            .min(total)
+    }
+
+    /// The haul that brings the terminal back to its target, or `None` while
+    /// it is within the hysteresis. The further outside the band the
+    /// terminal is, the higher the priority.
+    pub fn transfer(&self, terminal_amount: u32, storage_amount: u32) -> Option<BandTransfer> {
+        let target = self.target(terminal_amount + storage_amount);
+
+        if terminal_amount + self.hysteresis < target {
+            let priority = Self::priority(self.low.saturating_sub(terminal_amount), self.low);
+
+            Some(BandTransfer::ToTerminal(target - terminal_amount, priority))
+        } else if terminal_amount > target + self.hysteresis {
+            let priority = Self::priority(terminal_amount.saturating_sub(self.high), self.high);
+
+            Some(BandTransfer::ToStorage(terminal_amount - target, priority))
+        } else {
+            None
+        }
+    }
+
+    /// `Medium` once the terminal is `outside` the band by half its `edge`.
+    /// Never `None`: storage's own requests are, and two `None` ends are
+    /// never paired.
+    fn priority(outside: u32, edge: u32) -> TransferPriority {
+        if outside > 0 && outside * 2 >= edge {
+            TransferPriority::Medium
+        } else {
+            TransferPriority::Low
+        }
+    }
+}
+
+/// Resources each room has shipped to the consolidation hub since the VM
+/// started. Runtime resource, for stats.
+#[derive(Default)]
+pub struct ConsolidationVolume {
+    sent: HashMap<RoomName, u32>,
+}
+
+impl ConsolidationVolume {
+    fn record(&mut self, room: RoomName, amount: u32) {
+        *self.sent.entry(room).or_insert(0) += amount;
+    }
+
+    pub fn sent(&self, room: RoomName) -> u32 {
+        self.sent.get(&room).copied().unwrap_or(0)
+    }
+}
+
+/// Which side of the market a filed deal is on; arbitrage deals count
+/// toward [`EnergyArbitrageVolume`] once made.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DealKind {
+    Sale,
+    ArbitrageSale,
+    ArbitragePurchase,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TerminalActionKind {
+    /// A send to another of our terminals the transfer queue asked for:
+    /// balancing and compound (boost) shipments. Re-filed every tick, as
+    /// making it needs that tick's pickup and delivery.
+    Transfer,
+    /// A deal into a market order, filed by the order queue.
+    Deal { order_id: String, price: f64, kind: DealKind },
+    /// Surplus bound for the consolidation hub.
+    Consolidate,
+}
+
+/// One thing the terminal could do with its next free tick.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TerminalAction {
+    pub kind: TerminalActionKind,
+    pub resource: ResourceType,
+    pub amount: u32,
+    /// Where a send goes; deals go wherever their order is.
+    pub destination: Option<RoomName>,
+    pub score: f32,
+    pub filed_at: u32,
+}
+
+impl TerminalAction {
+    fn same_slot(&self, other: &TerminalAction) -> bool {
+        match (&self.kind, &other.kind) {
+            (TerminalActionKind::Deal { order_id: a, .. }, TerminalActionKind::Deal { order_id: b, .. }) => a == b,
+            (TerminalActionKind::Transfer, TerminalActionKind::Transfer) => true,
+            (TerminalActionKind::Consolidate, TerminalActionKind::Consolidate) => true,
+            _ => false,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let destination = self.destination.map(|room| format!(" -> {}", room)).unwrap_or_default();
+
+        match &self.kind {
+            TerminalActionKind::Transfer => format!("{:.0} transfer {} {:?}{}", self.score, self.amount, self.resource, destination),
+            TerminalActionKind::Deal { price, kind, .. } => {
+                format!("{:.0} {:?} {} {:?} @ {:.3}", self.score, kind, self.amount, self.resource, price)
+            }
+            TerminalActionKind::Consolidate => format!("{:.0} consolidate {} {:?}{}", self.score, self.amount, self.resource, destination),
+        }
+    }
+}
+
+/// The terminal's pending actions. A terminal acts once per cooldown, so
+/// everything that wants it this tick is filed here and only the best
+/// scored action runs; the rest wait, score intact, for the next free tick
+/// until [`TERMINAL_ACTION_TTL`] passes.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TerminalSchedule {
+    pending: Vec<TerminalAction>,
+}
+
+impl TerminalSchedule {
+    /// File `action`, replacing a pending one in the same slot (the same
+    /// order, or the one transfer or consolidation send).
+    pub fn file(&mut self, action: TerminalAction) {
+        match self.pending.iter_mut().find(|pending| pending.same_slot(&action)) {
+            Some(pending) => *pending = action,
+            None => self.pending.push(action),
+        }
+    }
+
+    /// Drop actions filed more than [`TERMINAL_ACTION_TTL`] ticks ago, and
+    /// the transfer, which is filed afresh each tick the terminal is free.
+    pub fn expire(&mut self, now: u32) {
+        self.pending
+            .retain(|action| action.kind != TerminalActionKind::Transfer && now.saturating_sub(action.filed_at) < TERMINAL_ACTION_TTL);
+    }
+
+    /// Take the best scored action; the earliest filed wins a tie.
+    pub fn take_best(&mut self) -> Option<TerminalAction> {
+        let best = self
+            .pending
+            .iter()
+            .enumerate()
+            .max_by(|(a_index, a), (b_index, b)| {
+                a.score
+                    .partial_cmp(&b.score)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| b.filed_at.cmp(&a.filed_at))
+                    .then_with(|| b_index.cmp(a_index))
+            })
+            .map(|(index, _)| index)?;
+
+        Some(self.pending.remove(best))
+    }
+
+    pub fn pending(&self) -> &[TerminalAction] {
+        &self.pending
+    }
+}
+
+/// Deals the order queue found for each room's terminal, until that room's
+/// terminal mission takes them into its schedule. Runtime resource.
+#[derive(Default)]
+pub struct TerminalDeals {
+    filed: HashMap<RoomName, Vec<TerminalAction>>,
+}
+
+impl TerminalDeals {
+    pub fn file(&mut self, room: RoomName, action: TerminalAction) {
+        self.filed.entry(room).or_default().push(action);
+    }
+
+    pub fn take(&mut self, room: RoomName) -> Vec<TerminalAction> {
+        self.filed.remove(&room).unwrap_or_default()
+    }
+}
 
 #[derive(ConvertSaveload)]
 pub struct TerminalMission {
     owner: EntityOption<Entity>,
     room_data: Entity,
+    schedule: TerminalSchedule,
+    paused: bool,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -39,6 +308,8 @@ impl TerminalMission {
         TerminalMission {
             owner: owner.into(),
             room_data,
+            schedule: TerminalSchedule::default(),
+            paused: false,
         }
     }
 
@@ -81,6 +352,13 @@ impl TerminalMission {
         }
     }
 
+    fn get_terminal_hysteresis(resource: ResourceType) -> u32 {
+        match resource {
+            ResourceType::Energy => 5_000,
+            _ => 500,
+        }
+    }
+
     //TODO: Add filter for selling resources.
 
     fn can_purchase_resource(resource: ResourceType, market: &crate::features::MarketFeatures) -> bool {
@@ -125,6 +403,132 @@ impl TerminalMission {
         }
     }
 
+    /// [`Self::get_resource_thresholds`] for `room`: a room shipping `resource`
+    /// to the hub keeps only the consolidation reserve in storage, so the rest
+    /// flows to the terminal to be sent.
+    fn get_room_resource_thresholds(resource: ResourceType, room: RoomName, consolidation: &ConsolidationFeatures) -> ResourceThresholds {
+        let mut thresholds = Self::get_resource_thresholds(resource);
+
+        if consolidation.hub_for(room, resource).is_some() {
+            thresholds.desired_storage_amount = thresholds.desired_storage_amount.min(consolidation.reserve);
+        }
+
+        thresholds
+    }
+
+    /// Where `resource` sits between `room`'s storage and terminal: the
+    /// terminal keeps its reserve, fills through the market tiers from what
+    /// storage holds past its own share, and returns anything beyond them.
+    pub fn get_terminal_band(resource: ResourceType, room: RoomName, consolidation: &ConsolidationFeatures) -> TerminalBand {
+        let thresholds = Self::get_room_resource_thresholds(resource, room, consolidation);
+
+        TerminalBand {
+            low: *thresholds.terminal_reserve_threshold.end(),
+            high: *thresholds.terminal_active_threshold.end(),
+            storage_floor: thresholds.desired_storage_amount,
+            hysteresis: Self::get_terminal_hysteresis(resource),
+        }
+    }
+
+    /// The room's largest surplus above the consolidation reserve, as a send
+    /// to the hub, one resource per send. The hub itself never ships.
+    fn consolidation_action(
+        room: &Room,
+        terminal: &StructureTerminal,
+        consolidation: &ConsolidationFeatures,
+        now: u32,
+    ) -> Option<TerminalAction> {
+        let room_name = room.name();
+        let hub = consolidation.hub.filter(|hub| *hub != room_name)?;
+
+        let distance = game::map::get_room_linear_distance(room_name, hub, true);
+        let terminal_energy = terminal.store().get_used_capacity(Some(ResourceType::Energy));
+        let storage = room.storage();
+
+        let (resource, amount) = terminal
+            .store()
+            .store_types()
+            .into_iter()
+            .filter(|resource| consolidation.hub_for(room_name, *resource).is_some())
+            .filter_map(|resource| {
+                let terminal_amount = terminal.store().get_used_capacity(Some(resource));
+                let storage_amount = storage
+                    .as_ref()
+                    .map(|storage| storage.store().get_used_capacity(Some(resource)))
+                    .unwrap_or(0);
+
+                consolidation_amount(
+                    terminal_amount,
+                    terminal_amount + storage_amount,
+                    consolidation.reserve,
+                    consolidation.min_batch,
+                    terminal_energy,
+                    distance,
+                )
+                .map(|amount| (resource, amount))
+            })
+            .max_by_key(|(_, amount)| *amount)?;
+
+        Some(TerminalAction {
+            kind: TerminalActionKind::Consolidate,
+            resource,
+            amount,
+            destination: Some(hub),
+            score: CONSOLIDATION_SCORE,
+            filed_at: now,
+        })
+    }
+
+    /// The resource and amount a terminal delivery ticket moves (the largest
+    /// of its resources) and the most urgent priority among them.
+    fn delivery_transfer(delivery: &TransferDepositTicket) -> Option<(ResourceType, u32, TransferPriority)> {
+        delivery
+            .resources()
+            .iter()
+            .map(|(resource, entries)| {
+                let amount = entries.iter().map(|e| e.amount()).sum::<u32>();
+                let priority = entries.iter().map(|e| e.priority()).min().unwrap_or(TransferPriority::None);
+
+                (*resource, amount, priority)
+            })
+            .max_by_key(|(_, amount, _)| *amount)
+    }
+
+    /// Send `amount` of `resource` to `destination`, booking the fee.
+    fn send(
+        room_name: RoomName,
+        terminal: &StructureTerminal,
+        resource: ResourceType,
+        amount: u32,
+        destination: RoomName,
+        ledger: &mut ResourceLedger,
+        intent_recorder: &mut IntentRecorder,
+    ) -> bool {
+        let sent = intent_recorder
+            .issued(IntentCategory::Structure, terminal.send(resource, amount, destination, None))
+            .is_ok();
+
+        if sent {
+            let distance = game::map::get_room_linear_distance(room_name, destination, true);
+
+            ledger.add(room_name, LedgerCategory::TerminalFee, terminal_send_cost(amount, distance));
+        }
+
+        sent
+    }
+
+    /// Energy above the room's high-water mark (all it keeps in storage plus
+    /// every terminal tier) and short of its low-water mark (half its reserve,
+    /// where it also starts asking to buy).
+    fn energy_position(stored_energy: u32) -> (u32, u32) {
+        let thresholds = Self::get_resource_thresholds(ResourceType::Energy);
+
+        let high_water = thresholds.desired_storage_amount + thresholds.terminal_active_threshold.end();
+        let low_water = (thresholds.desired_storage_amount + thresholds.terminal_reserve_threshold.end()) / 2;
+
+        (stored_energy.saturating_sub(high_water), low_water.saturating_sub(stored_energy))
+    }
+
     fn get_known_resources_types(
         storage_resource_types: &[ResourceType],
         terminal_resource_types: &[ResourceType],
@@ -168,6 +572,8 @@ struct ResourceThresholds {
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl Mission for TerminalMission {
+    impl_mission_pause!();
+
     fn get_owner(&self) -> &Option<Entity> {
         &self.owner
     }
@@ -187,13 +593,27 @@ impl Mission for TerminalMission {
     }
 
     fn summarize(&self) -> crate::visualization::SummaryContent {
-        crate::visualization::SummaryContent::Text("Terminal".to_string())
+        if self.schedule.pending().is_empty() {
+            crate::visualization::SummaryContent::Text("Terminal".to_string())
+        } else {
+            crate::visualization::SummaryContent::Lines {
+                header: format!("Terminal - Pending: {}", self.schedule.pending().len()),
+                items: self.schedule.pending().iter().map(|action| action.describe()).collect(),
+            }
+        }
     }
 
-    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), String> {
-        let room_data = system_data.room_data.get(self.room_data).ok_or("Expected room data")?;
-        let room = game::rooms().get(room_data.name).ok_or("Expected room")?;
-        let terminal = room.terminal().ok_or("Expected terminal")?;
+    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), MissionError> {
+        let room_data = system_data
+            .room_data
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
+        let room = game::rooms()
+            .get(room_data.name)
+            .ok_or_else(|| MissionError::new(MissionFailure::NoVisibility, "Expected room"))?;
+        let terminal = room
+            .terminal()
+            .ok_or_else(|| MissionError::new(MissionFailure::TargetInvalid, "Expected terminal"))?;
 
         if let Some(storage) = room.storage() {
             let current_terminal_energy = terminal.store().get(ResourceType::Energy).unwrap_or(0);
@@ -201,6 +621,33 @@ impl Mission for TerminalMission {
 
             let known_resource_types = Self::get_known_resources_types(&storage.store().store_types(), &terminal.store().store_types());
 
+            let stored_energy = storage.store().get_used_capacity(Some(ResourceType::Energy)) + current_terminal_energy;
+            let (surplus, deficit) = Self::energy_position(stored_energy);
+
+            let overflowing = system_data.overflow_sinks.is_enabled(self.room_data, EnergySink::TerminalSales);
+
+            system_data
+                .order_queue
+                .report_energy_position(room_data.name, surplus, deficit, overflowing);
+
+            //
+            // Buy what the empire is short of, if this is the room purchases land in.
+            //
+
+            if system_data.features.market.buy_deficits {
+                let terminal_free_amount = terminal.store().get_free_capacity(None).max(0) as u32;
+
+                for (resource_type, deficit) in system_data.resource_overview.purchases(room_data.name) {
+                    let purchase_amount = (*deficit).min(terminal_free_amount);
+
+                    if purchase_amount > 0 {
+                        system_data
+                            .order_queue
+                            .request_passive_purchase(room_data.name, *resource_type, purchase_amount);
+                    }
+                }
+            }
+
             //TODO: Include resources that are requested by transport system but don't exist in the room.
 
             //
@@ -212,13 +659,24 @@ impl Mission for TerminalMission {
                 let current_terminal_amount = terminal.store().get_used_capacity(Some(resource_type));
                 let current_total_amount = current_storage_amount + current_terminal_amount;
 
-                let thresholds = Self::get_resource_thresholds(resource_type);
+                let thresholds = Self::get_room_resource_thresholds(resource_type, room_data.name, system_data.consolidation);
+                let consolidating = system_data.consolidation.hub_for(room_data.name, resource_type).is_some();
 
                 //
                 // Request transfer of resources in.
                 //
 
-                let total_reserve_amount = thresholds.desired_storage_amount + thresholds.terminal_reserve_threshold.end();
+                let base_reserve_amount = thresholds.desired_storage_amount + thresholds.terminal_reserve_threshold.end();
+
+                // The upgrade focus room draws the other terminals' surplus energy
+                // on top of its reserve, but only buys to cover the reserve.
+                let focus_amount = if resource_type == ResourceType::Energy {
+                    system_data.upgrade_focus.extra_energy(room_data.name)
+                } else {
+                    0
+                };
+
+                let total_reserve_amount = base_reserve_amount + focus_amount;
 
                 if current_total_amount < total_reserve_amount {
                     let transfer_amount = total_reserve_amount - current_total_amount;
@@ -227,7 +685,7 @@ impl Mission for TerminalMission {
 
                     if transfer_amount > 0 {
                         if Self::can_purchase_resource(resource_type, &system_data.features.market)
-                            && current_total_amount < total_reserve_amount / 2
+                            && current_total_amount < base_reserve_amount / 2
                         {
                             //TODO: Only purchase when transfer is not available.
                             //TODO: Need to correctly figure out how much
@@ -275,7 +733,8 @@ impl Mission for TerminalMission {
                         }
                     }
 
-                    if effective_terminal_amount >= *thresholds.terminal_passive_threshold.start() {
+                    // Surplus bound for the hub isn't sold on the way.
+                    if !consolidating && effective_terminal_amount >= *thresholds.terminal_passive_threshold.start() {
                         let passive_amount = effective_terminal_amount - thresholds.terminal_passive_threshold.start();
                         let passive_amount =
                             passive_amount.min(thresholds.terminal_passive_threshold.end() - thresholds.terminal_passive_threshold.start());
@@ -287,7 +746,7 @@ impl Mission for TerminalMission {
                         }
                     }
 
-                    if effective_terminal_amount >= *thresholds.terminal_active_threshold.start() {
+                    if !consolidating && effective_terminal_amount >= *thresholds.terminal_active_threshold.start() {
                         let active_amount = effective_terminal_amount - thresholds.terminal_active_threshold.start();
                         let active_amount =
                             active_amount.min(thresholds.terminal_active_threshold.end() - thresholds.terminal_active_threshold.start());
@@ -305,228 +764,319 @@ impl Mission for TerminalMission {
             }
         }
 
-        system_data.transfer_queue.register_generator(
-            room_data.name,
-            TransferTypeFlags::HAUL,
-            Box::new(move |_system, transfer, room_name| {
-                let room = game::rooms().get(room_name).ok_or("Expected room")?;
-                let terminal = room.terminal().ok_or("Expected terminal")?;
+        Ok(())
+    }
 
-                let terminal_id = terminal.remote_id();
+    fn run_mission(
+        &mut self,
+        system_data: &mut MissionExecutionSystemData,
+        _mission_entity: Entity,
+    ) -> Result<MissionResult, MissionError> {
+        let room_data = system_data
+            .room_data
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
+        let room = game::rooms()
+            .get(room_data.name)
+            .ok_or_else(|| MissionError::new(MissionFailure::NoVisibility, "Expected room"))?;
+
+        let terminal = room
+            .terminal()
+            .ok_or_else(|| MissionError::new(MissionFailure::TargetInvalid, "Expected terminal"))?;
+
+        let now = game::time();
+
+        for deal in system_data.terminal_deals.take(room_data.name) {
+            self.schedule.file(deal);
+        }
 
-                if let Some(storage) = room.storage() {
-                    let known_resource_types =
-                        Self::get_known_resources_types(&storage.store().store_types(), &terminal.store().store_types());
+        self.schedule.expire(now);
 
-                    for resource_type in known_resource_types {
-                        let current_storage_amount = storage.store().get_used_capacity(Some(resource_type));
-                        let current_terminal_amount = terminal.store().get_used_capacity(Some(resource_type));
+        if terminal.cooldown() > 0 {
+            return Ok(MissionResult::Running);
+        }
 
-                        let thresholds = Self::get_resource_thresholds(resource_type);
+        let transfer_queue = &mut system_data.transfer_queue;
 
-                        //
-                        // Ensure a reserve amount of the resource is held in the terminal
-                        //
+        let transfer_queue_data = TransferQueueGeneratorData {
+            cause: "Terminal Transfer",
+            room_data: &*system_data.room_data,
+        };
+
+        let thresholds = Self::get_resource_thresholds(ResourceType::Energy);
+        let current_terminal_energy = terminal.store().get(ResourceType::Energy).unwrap_or(0);
+
+        let available_transfer_energy = if current_terminal_energy >= *thresholds.terminal_reserve_threshold.start() {
+            let max_amount = *thresholds.terminal_reserve_threshold.end() - *thresholds.terminal_reserve_threshold.start();
+            (current_terminal_energy - *thresholds.terminal_reserve_threshold.start()).min(max_amount)
+        } else {
+            0
+        };
+
+        let priorities = generate_active_priorities(TransferPriorityFlags::ALL, TransferPriorityFlags::ALL);
+
+        let best_transfer = priorities
+            .filter_map(|(pickup_priority, delivery_priority)| {
+                transfer_queue.get_terminal_delivery_from_target(
+                    &transfer_queue_data,
+                    &TransferTarget::Terminal(terminal.remote_id()),
+                    pickup_priority,
+                    delivery_priority,
+                    TransferType::Terminal,
+                    available_transfer_energy,
+                    TransferCapacity::Infinite,
+                )
+            })
+            .next();
+
+        if let Some((_, delivery)) = &best_transfer {
+            if let Some((resource, amount, priority)) = Self::delivery_transfer(delivery) {
+                self.schedule.file(TerminalAction {
+                    kind: TerminalActionKind::Transfer,
+                    resource,
+                    amount,
+                    destination: Some(delivery.target().pos().room_name()),
+                    score: transfer_score(priority),
+                    filed_at: now,
+                });
+            }
+        }
 
-                        if current_terminal_amount < *thresholds.terminal_reserve_threshold.end() {
-                            let max_transfer = thresholds.terminal_reserve_threshold.end() - thresholds.terminal_reserve_threshold.start();
-                            let transfer_amount =
-                                (*thresholds.terminal_reserve_threshold.end() - current_terminal_amount).min(max_transfer);
+        if now.is_multiple_of(CONSOLIDATION_INTERVAL) {
+            if let Some(action) = Self::consolidation_action(&room, &terminal, system_data.consolidation, now) {
+                self.schedule.file(action);
+            }
+        }
 
-                            let transfer_request = TransferDepositRequest::new(
-                                TransferTarget::Terminal(terminal_id),
-                                Some(resource_type),
-                                TransferPriority::Medium,
-                                transfer_amount,
-                                TransferType::Haul,
-                            );
+        let Some(action) = self.schedule.take_best() else {
+            return Ok(MissionResult::Running);
+        };
+
+        if !self.schedule.pending().is_empty() {
+            debug!(
+                "Terminal {} runs {}; deferred: {}",
+                room_data.name,
+                action.describe(),
+                self.schedule.pending().len()
+            );
+        }
 
-                            transfer.request_deposit(transfer_request);
-                        }
+        match action.kind {
+            TerminalActionKind::Transfer => {
+                let (Some((pickup, delivery)), Some(destination)) = (best_transfer, action.destination) else {
+                    return Ok(MissionResult::Running);
+                };
 
-                        //
-                        // If there is excess resources in storage and a shortage in the terminal, request transfer of
-                        // those resources.
-                        //
-
-                        if current_storage_amount > thresholds.desired_storage_amount {
-                            if current_terminal_amount < *thresholds.terminal_transfer_outgoing_threshold.end() {
-                                let max_transfer = thresholds.terminal_transfer_outgoing_threshold.end()
-                                    - thresholds.terminal_transfer_outgoing_threshold.start();
-                                let transfer_amount =
-                                    (*thresholds.terminal_transfer_outgoing_threshold.end() - current_terminal_amount).min(max_transfer);
-
-                                let transfer_request = TransferDepositRequest::new(
-                                    TransferTarget::Terminal(terminal_id),
-                                    Some(resource_type),
-                                    TransferPriority::Low,
-                                    transfer_amount,
-                                    TransferType::Haul,
-                                );
-
-                                transfer.request_deposit(transfer_request);
-                            }
+                transfer_queue.register_pickup(&pickup);
+                transfer_queue.register_delivery(&delivery);
 
-                            if current_terminal_amount < *thresholds.terminal_passive_threshold.end() {
-                                let max_transfer =
-                                    thresholds.terminal_passive_threshold.end() - thresholds.terminal_passive_threshold.start();
-                                let transfer_amount =
-                                    (*thresholds.terminal_passive_threshold.end() - current_terminal_amount).min(max_transfer);
-
-                                let transfer_request = TransferDepositRequest::new(
-                                    TransferTarget::Terminal(terminal_id),
-                                    Some(resource_type),
-                                    TransferPriority::Low,
-                                    transfer_amount,
-                                    TransferType::Haul,
-                                );
-
-                                transfer.request_deposit(transfer_request);
-                            }
+                info!(
+                    "Terminal transfer: {} -> {} - Resource: {:?} - Amount: {}",
+                    room_data.name, destination, action.resource, action.amount
+                );
+
+                Self::send(
+                    room_data.name,
+                    &terminal,
+                    action.resource,
+                    action.amount,
+                    destination,
+                    system_data.ledger,
+                    system_data.intent_recorder,
+                );
+            }
+            TerminalActionKind::Consolidate => {
+                let Some(hub) = action.destination else {
+                    return Ok(MissionResult::Running);
+                };
+
+                if Self::send(
+                    room_data.name,
+                    &terminal,
+                    action.resource,
+                    action.amount,
+                    hub,
+                    system_data.ledger,
+                    system_data.intent_recorder,
+                ) {
+                    info!(
+                        "Terminal consolidation: {} -> {} - Resource: {:?} - Amount: {}",
+                        room_data.name, hub, action.resource, action.amount
+                    );
 
-                            if current_terminal_amount < *thresholds.terminal_active_threshold.end() {
-                                let max_transfer =
-                                    thresholds.terminal_active_threshold.end() - thresholds.terminal_active_threshold.start();
-                                let transfer_amount =
-                                    (*thresholds.terminal_active_threshold.end() - current_terminal_amount).min(max_transfer);
-
-                                let transfer_request = TransferDepositRequest::new(
-                                    TransferTarget::Terminal(terminal_id),
-                                    Some(resource_type),
-                                    TransferPriority::Low,
-                                    transfer_amount,
-                                    TransferType::Haul,
-                                );
-
-                                transfer.request_deposit(transfer_request);
-                            }
+                    system_data.consolidation_volume.record(room_data.name, action.amount);
+                }
+            }
+            TerminalActionKind::Deal { order_id, price, kind } => {
+                let order = js_sys::JsString::from(order_id.as_str());
+
+                match system_data.intent_recorder.issued(
+                    IntentCategory::Structure,
+                    game::market::deal(&order, action.amount, Some(room_data.name)),
+                ) {
+                    Ok(()) => {
+                        match kind {
+                            DealKind::Sale => {}
+                            DealKind::ArbitrageSale => system_data.energy_arbitrage_volume.record_sale(action.amount, price),
+                            DealKind::ArbitragePurchase => system_data.energy_arbitrage_volume.record_purchase(action.amount, price),
                         }
 
-                        //
-                        // Make available any resources that are in the terminal and there is not sufficient amount in storage.
-                        //
-
-                        //TODO: This should likely prevent sellling, not just make them available. Factor our amount storage needs in calculation above.
-                        let made_available_amount = if current_storage_amount < thresholds.desired_storage_amount {
-                            if current_terminal_amount > *thresholds.terminal_reserve_threshold.end() {
-                                let available_terminal_amount = current_terminal_amount - *thresholds.terminal_reserve_threshold.end();
-                                let transfer_amount =
-                                    (thresholds.desired_storage_amount - current_storage_amount).min(available_terminal_amount);
-
-                                if transfer_amount > 0 {
-                                    let transfer_request = TransferWithdrawRequest::new(
-                                        TransferTarget::Terminal(terminal_id),
-                                        resource_type,
-                                        TransferPriority::None,
-                                        transfer_amount,
-                                        TransferType::Haul,
-                                    );
-
-                                    transfer.request_withdraw(transfer_request);
-                                }
-
-                                transfer_amount
-                            } else {
-                                0
-                            }
-                        } else {
-                            0
-                        };
-
-                        //
-                        // Actively transfer any resources that are in excess of the desired terminal amount (reserve, active and passive)
-                        // and are not already being made avaiable due to storage shortage.
-                        //
-
-                        if current_terminal_amount > *thresholds.terminal_active_threshold.end() {
-                            let terminal_excess = current_terminal_amount - *thresholds.terminal_active_threshold.end();
-                            let transfer_amount = (terminal_excess as i32) - (made_available_amount as i32);
-
-                            if transfer_amount > 0 {
-                                let transfer_request = TransferWithdrawRequest::new(
-                                    TransferTarget::Terminal(terminal_id),
-                                    resource_type,
-                                    TransferPriority::Medium,
-                                    transfer_amount as u32,
-                                    TransferType::Haul,
-                                );
-
-                                transfer.request_withdraw(transfer_request);
-                            }
-                        }
+                        info!(
+                            "Completed deal! Room: {} Kind: {:?} Resource: {:?} Amount: {} Price: {} Id: {}",
+                            room_data.name, kind, action.resource, action.amount, price, order_id
+                        );
+                    }
+                    Err(err) => {
+                        info!(
+                            "Failed to complete deal! Error: {:?} Room: {} Kind: {:?} Resource: {:?} Amount: {} Price: {} Id: {}",
+                            err, room_data.name, kind, action.resource, action.amount, price, order_id
+                        );
                     }
                 }
+            }
+        }
+
+        Ok(MissionResult::Running)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consolidation_ships_the_terminal_surplus_above_the_reserve() {
+        // 8000 held, 3000 kept: 5000 surplus, all of it in the terminal.
+        assert_eq!(consolidation_amount(6_000, 8_000, 3_000, 2_000, 10_000, 5), Some(5_000));
+        // Only what already sits in the terminal can go.
+        assert_eq!(consolidation_amount(2_500, 8_000, 3_000, 2_000, 10_000, 5), Some(2_500));
+        // Below the batch size it waits for more.
+        assert_eq!(consolidation_amount(1_500, 4_500, 3_000, 2_000, 10_000, 5), None);
+        assert_eq!(consolidation_amount(0, 2_000, 3_000, 0, 10_000, 5), None);
+    }
+
+    #[test]
+    fn consolidation_sends_no_more_than_the_terminal_can_pay_for() {
+        let amount = consolidation_amount(50_000, 50_000, 0, 100, 1_000, 10).unwrap();
+
+        assert!(amount < 50_000);
+        assert!(terminal_send_cost(amount, 10) <= 1_000);
+        assert!(terminal_send_cost(amount + 10, 10) > 1_000);
+    }
 
-                Ok(())
-            }),
+    #[test]
+    fn terminal_band_hauls_only_past_the_hysteresis() {
+        let band = TerminalBand {
+            low: 20_000,
+            high: 50_000,
+            storage_floor: 100_000,
+            hysteresis: 5_000,
+        };
+
+        // Storage covers its floor with 30k over: the terminal fills to the top of the band.
+        assert_eq!(
+            band.transfer(20_000, 130_000),
+            Some(BandTransfer::ToTerminal(30_000, TransferPriority::Low))
         );
+        // A few thousand either side of the target stays put.
+        assert_eq!(band.transfer(47_000, 103_000), None);
+        assert_eq!(band.transfer(50_000, 96_000), None);
+        // Storage short of its floor takes the terminal back down to the bottom of the band.
+        assert_eq!(
+            band.transfer(50_000, 60_000),
+            Some(BandTransfer::ToStorage(30_000, TransferPriority::Low))
+        );
+        // An empty terminal is refilled ahead of other hauls, even from a short storage.
+        assert_eq!(
+            band.transfer(0, 60_000),
+            Some(BandTransfer::ToTerminal(20_000, TransferPriority::Medium))
+        );
+        assert_eq!(band.transfer(15_000, 60_000), None);
+        // Far above the band is emptied first.
+        assert_eq!(
+            band.transfer(90_000, 200_000),
+            Some(BandTransfer::ToStorage(40_000, TransferPriority::Medium))
+        );
+        // With too little to go round, the terminal holds it all.
+        assert_eq!(band.target(12_000), 12_000);
+    }
 
-        Ok(())
+    #[test]
+    fn energy_position_reads_the_marks_from_the_terminal_tiers() {
+        // 200k kept in storage and 80k across the terminal tiers.
+        assert_eq!(TerminalMission::energy_position(300_000), (20_000, 0));
+        // Half of the 210k reserve.
+        assert_eq!(TerminalMission::energy_position(50_000), (0, 55_000));
+        assert_eq!(TerminalMission::energy_position(150_000), (0, 0));
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<MissionResult, String> {
-        let room_data = system_data.room_data.get(self.room_data).ok_or("Expected room data")?;
-        let room = game::rooms().get(room_data.name).ok_or("Expected room")?;
+    fn action(kind: TerminalActionKind, score: f32, filed_at: u32) -> TerminalAction {
+        TerminalAction {
+            kind,
+            resource: ResourceType::Energy,
+            amount: 1_000,
+            destination: None,
+            score,
+            filed_at,
+        }
+    }
 
-        let terminal = room.terminal().ok_or("Expected terminal")?;
+    fn deal(order_id: &str, score: f32, filed_at: u32) -> TerminalAction {
+        action(
+            TerminalActionKind::Deal {
+                order_id: order_id.to_string(),
+                price: 1.0,
+                kind: DealKind::Sale,
+            },
+            score,
+            filed_at,
+        )
+    }
 
-        let transfer_queue = &mut system_data.transfer_queue;
+    #[test]
+    fn schedule_runs_the_best_action_and_keeps_the_rest() {
+        let mut schedule = TerminalSchedule::default();
+
+        schedule.file(action(TerminalActionKind::Consolidate, CONSOLIDATION_SCORE, 10));
+        schedule.file(deal("a", deal_score(20_000.0), 10));
+        schedule.file(action(TerminalActionKind::Transfer, transfer_score(TransferPriority::High), 10));
+
+        assert_eq!(schedule.take_best().map(|action| action.kind), Some(TerminalActionKind::Transfer));
+        assert_eq!(schedule.pending().len(), 2);
+        assert!(matches!(
+            schedule.take_best().map(|action| action.kind),
+            Some(TerminalActionKind::Deal { .. })
+        ));
+
+        // A tie goes to whatever waited longest.
+        schedule.file(deal("b", 200.0, 12));
+        schedule.file(deal("c", 200.0, 11));
+        assert_eq!(schedule.take_best().map(|action| action.filed_at), Some(11));
+    }
 
-        if terminal.cooldown() == 0 {
-            let transfer_queue_data = TransferQueueGeneratorData {
-                cause: "Terminal Transfer",
-                room_data: &*system_data.room_data,
-            };
+    #[test]
+    fn filing_replaces_the_pending_action_in_the_same_slot() {
+        let mut schedule = TerminalSchedule::default();
 
-            let thresholds = Self::get_resource_thresholds(ResourceType::Energy);
-            let current_terminal_energy = terminal.store().get(ResourceType::Energy).unwrap_or(0);
+        schedule.file(deal("a", 160.0, 10));
+        schedule.file(deal("a", 170.0, 11));
+        schedule.file(deal("b", 155.0, 11));
 
-            let available_transfer_energy = if current_terminal_energy >= *thresholds.terminal_reserve_threshold.start() {
-                let max_amount = *thresholds.terminal_reserve_threshold.end() - *thresholds.terminal_reserve_threshold.start();
-                (current_terminal_energy - *thresholds.terminal_reserve_threshold.start()).min(max_amount)
-            } else {
-                0
-            };
-
-            let priorities = generate_active_priorities(TransferPriorityFlags::ALL, TransferPriorityFlags::ALL);
-
-            let best_transfer = priorities
-                .filter_map(|(pickup_priority, delivery_priority)| {
-                    transfer_queue.get_terminal_delivery_from_target(
-                        &transfer_queue_data,
-                        &TransferTarget::Terminal(terminal.remote_id()),
-                        pickup_priority,
-                        delivery_priority,
-                        TransferType::Terminal,
-                        available_transfer_energy,
-                        TransferCapacity::Infinite,
-                    )
-                })
-                .next();
-
-            if let Some((pickup, delivery)) = best_transfer {
-                transfer_queue.register_pickup(&pickup);
-                transfer_queue.register_delivery(&delivery);
+        assert_eq!(schedule.pending().len(), 2);
+        assert_eq!(schedule.take_best().map(|action| action.score), Some(170.0));
+    }
 
-                if let Some((transfer_resource, transfer_amount)) = delivery
-                    .resources()
-                    .iter()
-                    .map(|(resource, entries)| (resource, entries.iter().map(|e| e.amount()).sum::<u32>()))
-                    .max_by_key(|(_, amount)| *amount)
-                {
-                    info!(
-                        "Terminal transfer: {} -> {} - Resource: {:?} - Amount: {}",
-                        room_data.name,
-                        delivery.target().pos().room_name(),
-                        transfer_resource,
-                        transfer_amount
-                    );
+    #[test]
+    fn deferred_actions_expire_after_their_ttl_and_transfers_every_tick() {
+        let mut schedule = TerminalSchedule::default();
 
-                    let _ = terminal.send(*transfer_resource, transfer_amount, delivery.target().pos().room_name(), None);
-                }
-            }
-        }
+        schedule.file(deal("a", 160.0, 10));
+        schedule.file(action(TerminalActionKind::Transfer, 300.0, 10));
 
-        Ok(MissionResult::Running)
+        schedule.expire(10 + TERMINAL_ACTION_TTL - 1);
+        assert_eq!(schedule.pending().len(), 1);
+
+        schedule.expire(10 + TERMINAL_ACTION_TTL);
+        assert!(schedule.pending().is_empty());
     }
 }
@@ -1,8 +1,12 @@
 use super::data::*;
 use super::missionsystem::*;
+use crate::intents::IntentCategory;
 use crate::jobs::utility::repair::*;
+use crate::ledger::LedgerCategory;
+use crate::powercreepsystem::*;
 use crate::remoteobjectid::*;
 use crate::serialize::*;
+use crate::structureidentifier::RemoteStructureIdentifier;
 use crate::transfer::transfersystem::*;
 use log::*;
 use screeps::*;
@@ -35,6 +39,11 @@ const PROBE_COOLDOWN: u32 = 20;
 /// shooting: to pass, the creep must actually be dying fast enough to finish.
 const MIN_PROBE_PROGRESS: u32 = 200;
 
+/// Energy per tower kept back in storage from everything but high priority
+/// deliveries, so upgraders and builders can't drain the refills the towers
+/// need once an attack starts.
+const TOWER_ENERGY_RESERVE: u32 = 2 * TOWER_CAPACITY;
+
 /// Tracks a hostile creep suspected of tower draining.
 ///
 /// Detection keys on the hitpoint *sawtooth* a drainer produces, NOT on the
@@ -87,6 +96,7 @@ pub struct TowerMission {
     drain_trackers: EntityHashMap<ObjectId<Creep>, DrainTracker>,
     /// Last tick when stale drain trackers were cleaned up.
     last_drain_cleanup: u32,
+    paused: bool,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -108,6 +118,7 @@ impl TowerMission {
             room_data,
             drain_trackers: EntityHashMap::new(),
             last_drain_cleanup: 0,
+            paused: false,
         }
     }
 
@@ -125,6 +136,8 @@ impl TowerMission {
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl Mission for TowerMission {
+    impl_mission_pause!();
+
     fn get_owner(&self) -> &Option<Entity> {
         &self.owner
     }
@@ -147,10 +160,23 @@ impl Mission for TowerMission {
         crate::visualization::SummaryContent::Text("Tower".to_string())
     }
 
-    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), String> {
-        let room_data = system_data.room_data.get(self.room_data).ok_or("Expected room data")?;
+    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), MissionError> {
+        let room_data = system_data
+            .room_data
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
 
         let room_data_entity = self.room_data;
+        // Top the towers off at high priority while a hostile group is predicted to cross into the room, so
+        // they start the fight full instead of refilling under fire.
+        let inbound = system_data.border_watch.inbound(room_data.name).is_some();
+        // On alert, any tower below the posture's floor is topped up at high priority too.
+        let energy_floor = system_data
+            .defense_postures
+            .get(room_data_entity)
+            .map(|posture| posture.tower_energy_floor())
+            .unwrap_or(0.0);
+        let floor_energy = (TOWER_CAPACITY as f32 * energy_floor) as u32;
 
         system_data.transfer_queue.register_generator(
             room_data.name,
@@ -173,7 +199,7 @@ impl Mission for TowerMission {
                 let hostile_creeps = creeps.hostile();
                 let are_hostile_creeps = !hostile_creeps.is_empty();
 
-                let priority = if are_hostile_creeps {
+                let priority = if are_hostile_creeps || inbound {
                     TransferPriority::High
                 } else {
                     TransferPriority::Low
@@ -182,10 +208,11 @@ impl Mission for TowerMission {
                 for tower in towers {
                     let tower_free_capacity = tower.store().get_free_capacity(Some(ResourceType::Energy));
                     if tower_free_capacity > 0 {
+                        let below_floor = tower.store().get_used_capacity(Some(ResourceType::Energy)) < floor_energy;
                         let transfer_request = TransferDepositRequest::new(
                             TransferTarget::Tower(tower.remote_id()),
                             Some(ResourceType::Energy),
-                            priority,
+                            if below_floor { TransferPriority::High } else { priority },
                             tower_free_capacity as u32,
                             TransferType::Haul,
                         );
@@ -194,6 +221,14 @@ impl Mission for TowerMission {
                     }
                 }
 
+                if !towers.is_empty() {
+                    let reserve = towers.len() as u32 * TOWER_ENERGY_RESERVE;
+
+                    for storage in structures.storages().iter().filter(|s| s.my()) {
+                        transfer.reserve(TransferTarget::Storage(storage.remote_id()), ResourceType::Energy, reserve);
+                    }
+                }
+
                 Ok(())
             }),
         );
@@ -201,18 +236,27 @@ impl Mission for TowerMission {
         Ok(())
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<MissionResult, String> {
-        let room_data = system_data.room_data.get(self.room_data).ok_or("Expected room data")?;
+    fn run_mission(
+        &mut self,
+        system_data: &mut MissionExecutionSystemData,
+        _mission_entity: Entity,
+    ) -> Result<MissionResult, MissionError> {
+        let room_data = system_data
+            .room_data
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
         let structures = room_data.get_structures().ok_or_else(|| {
             let msg = format!("Expected structures - Room: {}", room_data.name);
             log::warn!("{} at {}:{}", msg, file!(), line!());
-            msg
+            MissionError::new(MissionFailure::MissingRoomData, msg)
         })?;
-        let dynamic_visibility_data = room_data.get_dynamic_visibility_data().ok_or("Expected dynamic visibility data")?;
+        let dynamic_visibility_data = room_data
+            .get_dynamic_visibility_data()
+            .ok_or_else(|| MissionError::new(MissionFailure::NoVisibility, "Expected dynamic visibility data"))?;
         let creeps = room_data.get_creeps().ok_or_else(|| {
             let msg = format!("Expected creeps - Room: {}", room_data.name);
             log::warn!("{} at {}:{}", msg, file!(), line!());
-            msg
+            MissionError::new(MissionFailure::MissingRoomData, msg)
         })?;
 
         let towers = structures.towers();
@@ -236,6 +280,8 @@ impl Mission for TowerMission {
         // exhibits damage-then-heal-then-return, so it is never confirmed.
         let current_tick = game::time();
         let room_name = room_data.name;
+        // Successful tower intents this tick, reported to the energy ledger.
+        let mut tower_actions = 0u32;
 
         // Hostiles present this tick, keyed by stable object id.
         let mut present_ids: std::collections::HashSet<ObjectId<Creep>> = std::collections::HashSet::new();
@@ -319,17 +365,11 @@ impl Mission for TowerMission {
 
         if !hostile_creeps.is_empty() {
             // Calculate per-hostile heal rate for net damage assessment.
-            let hostile_infos: Vec<_> = hostile_creeps
-                .iter()
-                .map(|c| {
-                    let heal_per_tick: f32 = c
-                        .body()
-                        .iter()
-                        .filter(|p| p.hits() > 0 && p.part() == Part::Heal)
-                        .map(|p| if p.boost().is_some() { 48.0 } else { 12.0 })
-                        .sum();
+            let hostile_infos: Vec<_> = creeps
+                .hostile_bodies()
+                .map(|(c, body)| {
                     let is_confirmed_drainer = c.try_id().map(|id| confirmed_drainers.contains(&id)).unwrap_or(false);
-                    (c, heal_per_tick, is_confirmed_drainer)
+                    (c, body, is_confirmed_drainer)
                 })
                 .collect();
 
@@ -338,23 +378,14 @@ impl Mission for TowerMission {
             let best_target = hostile_infos
                 .iter()
                 .filter(|(_, _, is_drainer)| !is_drainer)
-                .filter(|(c, heal, _)| {
+                .filter(|(c, body, _)| {
                     // Only fire if we can do net damage (overcome healing).
                     let total_damage = crate::military::damage::total_tower_damage(&tower_positions, c.pos());
-                    total_damage > *heal
+                    total_damage > body.heal_per_tick
                 })
-                .min_by(|(a, _, _), (b, _, _)| {
+                .min_by(|(a, a_body, _), (b, b_body, _)| {
                     // Prefer dangerous creeps first.
-                    let a_dangerous = a
-                        .body()
-                        .iter()
-                        .any(|p| matches!(p.part(), Part::Attack | Part::RangedAttack | Part::Work));
-                    let b_dangerous = b
-                        .body()
-                        .iter()
-                        .any(|p| matches!(p.part(), Part::Attack | Part::RangedAttack | Part::Work));
-
-                    match (a_dangerous, b_dangerous) {
+                    match (a_body.armed(), b_body.armed()) {
                         (true, false) => std::cmp::Ordering::Less,
                         (false, true) => std::cmp::Ordering::Greater,
                         _ => a.hits().cmp(&b.hits()),
@@ -365,8 +396,8 @@ impl Mission for TowerMission {
             // Detect tower drain: hostile at room edge that can heal through all tower damage,
             // OR confirmed drainer based on enter/exit tracking.
             let is_drain = best_target.is_none()
-                && hostile_infos.iter().any(|(c, heal, is_drainer)| {
-                    *is_drainer || crate::military::damage::is_likely_tower_drain(c.pos(), *heal, &tower_positions)
+                && hostile_infos.iter().any(|(c, body, is_drainer)| {
+                    *is_drainer || crate::military::damage::is_likely_tower_drain(c.pos(), body.heal_per_tick, &tower_positions)
                 });
 
             if is_drain {
@@ -389,7 +420,13 @@ impl Mission for TowerMission {
 
                 if let Some(target) = target {
                     for tower in &my_towers {
-                        let _ = tower.attack(target);
+                        if system_data
+                            .intent_recorder
+                            .issued(IntentCategory::Tower, tower.attack(target))
+                            .is_ok()
+                        {
+                            tower_actions += 1;
+                        }
                     }
                     // Record a probe volley so next tick can judge the result.
                     if let Some(tid) = target.try_id() {
@@ -404,7 +441,24 @@ impl Mission for TowerMission {
             } else if let Some(target) = best_target {
                 // Coordinated fire: all towers focus the same target.
                 for tower in &my_towers {
-                    let _ = tower.attack(target);
+                    if system_data
+                        .intent_recorder
+                        .issued(IntentCategory::Tower, tower.attack(target))
+                        .is_ok()
+                    {
+                        tower_actions += 1;
+                    }
+                }
+
+                // A fight we can win is worth operating the towers for; a
+                // drain is not.
+                for tower in my_towers.iter().filter(|t| !has_power_effect(t, PowerType::OperateTower)) {
+                    system_data.power_requests.request(PowerRequest::new(
+                        room_name,
+                        PowerType::OperateTower,
+                        RemoteStructureIdentifier::new(&StructureObject::from((*tower).clone())),
+                        PowerPriority::High,
+                    ));
                 }
             } else {
                 // No target where we can do net damage. Check for any hostile we should still shoot.
@@ -415,11 +469,21 @@ impl Mission for TowerMission {
                     .min_by_key(|c| c.hits());
                 if let Some(target) = weakest {
                     for tower in &my_towers {
-                        let _ = tower.attack(target);
+                        if system_data
+                            .intent_recorder
+                            .issued(IntentCategory::Tower, tower.attack(target))
+                            .is_ok()
+                        {
+                            tower_actions += 1;
+                        }
                     }
                 }
             }
 
+            system_data
+                .ledger
+                .add(room_name, LedgerCategory::Tower, tower_actions * TOWER_ENERGY_COST);
+
             return Ok(MissionResult::Running);
         }
 
@@ -436,23 +500,43 @@ impl Mission for TowerMission {
             Some(RepairPriority::Low)
         };
 
-        let repair_structure =
-            select_repair_structure(room_data, system_data.repair_queue, minimum_repair_priority, false).and_then(|id| id.resolve());
+        // In an energy emergency the tower energy is kept for defence and healing.
+        let repair_structure = if system_data.energy_emergency.is_active(self.room_data) {
+            None
+        } else {
+            select_repair_structure(room_data, system_data.repair_queue, minimum_repair_priority, false).and_then(|id| id.resolve())
+        };
 
         for tower in &my_towers {
             if let Some(creep) = weakest_friendly_creep {
-                let _ = tower.heal(creep);
+                if system_data.intent_recorder.issued(IntentCategory::Tower, tower.heal(creep)).is_ok() {
+                    tower_actions += 1;
+                }
                 continue;
             }
 
-            if let Some(structure) = repair_structure.as_ref() {
+            // Tower repairs are optional: the low-bucket soft cap stops them.
+            if let Some(structure) = repair_structure
+                .as_ref()
+                .filter(|_| system_data.intent_recorder.allow(IntentCategory::Repair))
+            {
                 if let Some(repairable) = structure.as_repairable() {
-                    let _ = tower.repair(repairable);
+                    if system_data
+                        .intent_recorder
+                        .issued(IntentCategory::Repair, tower.repair(repairable))
+                        .is_ok()
+                    {
+                        tower_actions += 1;
+                    }
                 }
                 continue;
             }
         }
 
+        system_data
+            .ledger
+            .add(room_name, LedgerCategory::Tower, tower_actions * TOWER_ENERGY_COST);
+
         Ok(MissionResult::Running)
     }
 }
@@ -1,24 +1,108 @@
 use super::constants::*;
 use super::data::*;
+use super::localsupply::structure_data::*;
 use super::missionsystem::*;
+use crate::creep::spawning::BodyTemplate;
 use crate::jobs::data::*;
 use crate::jobs::upgrade::*;
 use crate::room::data::*;
 use crate::serialize::*;
 use crate::spawnsystem::*;
+use crate::transfer::transfersystem::TransferTarget;
 use lerp::*;
 use screeps::*;
+use screeps_cache::*;
 use serde::{Deserialize, Serialize};
 #[allow(deprecated)]
 use specs::error::NoError;
 use specs::saveload::*;
 use specs::*;
+use std::collections::HashMap;
+
+/// From this level upgraders get the slow `[W, C, M, M] + N*[W]` body, so
+/// they sit in seats by the controller's link or container instead of
+/// walking for energy.
+const SEATED_UPGRADE_MIN_LEVEL: u8 = 4;
+
+/// Where a seated upgrader works this tick and what it refills from.
+#[derive(Clone, Copy)]
+pub struct UpgradeSeatOrder {
+    pub seat: Position,
+    pub feed: TransferTarget,
+}
+
+/// This tick's seat for each seated upgrader, keyed by creep entity. The
+/// mission owns the assignments and republishes them every run; cleared by
+/// `PreRunMissionSystem` so a seat never outlives its mission.
+#[derive(Default)]
+pub struct UpgradeSeating {
+    upgraders: HashMap<Entity, UpgradeSeatOrder>,
+}
+
+impl UpgradeSeating {
+    pub fn clear(&mut self) {
+        self.upgraders.clear();
+    }
+
+    pub fn get(&self, upgrader: Entity) -> Option<&UpgradeSeatOrder> {
+        self.upgraders.get(&upgrader)
+    }
+
+    pub fn set(&mut self, upgrader: Entity, order: UpgradeSeatOrder) {
+        self.upgraders.insert(upgrader, order);
+    }
+}
+
+/// Tiles an upgrader can work from without moving: in upgrade range of the
+/// controller, next to the feed and off the room edge.
+fn upgrade_seats<F>(controller: (u8, u8), feed: (u8, u8), walkable: F) -> Vec<(u8, u8)>
+where
+    F: Fn(u8, u8) -> bool,
+{
+    let (fx, fy) = feed;
+    let controller_range = |x: u8, y: u8| controller.0.abs_diff(x).max(controller.1.abs_diff(y));
+
+    let mut seats: Vec<_> = (fx.saturating_sub(1).max(1)..=(fx + 1).min(48))
+        .flat_map(|x| (fy.saturating_sub(1).max(1)..=(fy + 1).min(48)).map(move |y| (x, y)))
+        .filter(|(x, y)| (1..=3).contains(&controller_range(*x, *y)) && walkable(*x, *y))
+        .collect();
+
+    seats.sort_by_key(|(x, y)| (controller_range(*x, *y), *x, *y));
+
+    seats
+}
+
+/// The controller's feed: its link when it has one, its container otherwise.
+fn upgrade_feed(structure_data: &StructureData) -> Option<(TransferTarget, Position)> {
+    structure_data
+        .controller_links
+        .first()
+        .map(|link| (TransferTarget::Link(*link), link.pos()))
+        .or_else(|| {
+            structure_data
+                .controllers_to_containers
+                .values()
+                .flatten()
+                .next()
+                .map(|container| (TransferTarget::Container(*container), container.pos()))
+        })
+}
+
+#[derive(Clone, ConvertSaveload)]
+pub struct UpgradeSeat {
+    position: Position,
+    upgrader: EntityOption<Entity>,
+}
 
 #[derive(ConvertSaveload)]
 pub struct UpgradeMission {
     owner: EntityOption<Entity>,
     room_data: Entity,
     upgraders: EntityVec<Entity>,
+    /// Fixed work tiles around the controller's feed. Held here rather than
+    /// in the jobs so a replacement upgrader takes over its predecessor's seat.
+    seats: EntityVec<UpgradeSeat>,
+    paused: bool,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -39,6 +123,8 @@ impl UpgradeMission {
             owner: owner.into(),
             room_data,
             upgraders: EntityVec::new(),
+            seats: EntityVec::new(),
+            paused: false,
         }
     }
 
@@ -69,6 +155,87 @@ impl UpgradeMission {
         })
     }
 
+    /// Seat the upgraders around `feed` and publish their seats for this
+    /// tick. Seats are re-derived when the feed moves; upgraders keep seats
+    /// that survive, and free seats go to unseated upgraders in order.
+    fn seat_upgraders(
+        &mut self,
+        seating: &mut UpgradeSeating,
+        controller: Position,
+        feed: TransferTarget,
+        feed_pos: Position,
+        structures: &RoomStructureData,
+    ) {
+        let current = !self.seats.is_empty()
+            && self
+                .seats
+                .iter()
+                .all(|seat| seat.position.is_near_to(feed_pos) && seat.position.in_range_to(controller, 3));
+
+        if !current {
+            if let Some(terrain) = game::map::get_room_terrain(controller.room_name()) {
+                let obstacles: Vec<_> = structures
+                    .all()
+                    .iter()
+                    .filter(|structure| {
+                        !matches!(
+                            structure.structure_type(),
+                            StructureType::Road | StructureType::Container | StructureType::Rampart
+                        )
+                    })
+                    .map(|structure| structure.pos())
+                    .map(|pos| (pos.x().u8(), pos.y().u8()))
+                    .collect();
+
+                let positions = upgrade_seats(
+                    (controller.x().u8(), controller.y().u8()),
+                    (feed_pos.x().u8(), feed_pos.y().u8()),
+                    |x, y| terrain.get(x, y) != Terrain::Wall && !obstacles.contains(&(x, y)),
+                );
+
+                let previous = std::mem::take(&mut *self.seats);
+
+                *self.seats = positions
+                    .into_iter()
+                    .filter_map(|(x, y)| {
+                        let position = Position::new(RoomCoordinate::new(x).ok()?, RoomCoordinate::new(y).ok()?, controller.room_name());
+                        let upgrader = previous
+                            .iter()
+                            .find(|seat| seat.position == position)
+                            .and_then(|seat| *seat.upgrader);
+
+                        Some(UpgradeSeat {
+                            position,
+                            upgrader: upgrader.into(),
+                        })
+                    })
+                    .collect();
+            }
+        }
+
+        for seat in self.seats.iter_mut() {
+            if seat.upgrader.is_some_and(|upgrader| !self.upgraders.contains(&upgrader)) {
+                seat.upgrader.take();
+            }
+        }
+
+        for upgrader in self.upgraders.iter() {
+            if self.seats.iter().any(|seat| *seat.upgrader == Some(*upgrader)) {
+                continue;
+            }
+
+            if let Some(seat) = self.seats.iter_mut().find(|seat| seat.upgrader.is_none()) {
+                *seat.upgrader = Some(*upgrader);
+            }
+        }
+
+        for seat in self.seats.iter() {
+            if let Some(upgrader) = *seat.upgrader {
+                seating.set(upgrader, UpgradeSeatOrder { seat: seat.position, feed });
+            }
+        }
+    }
+
     /// Compute the minimum number of WORK parts needed for an upgrader to
     /// restore the controller's downgrade timer from `current_ttd` back to
     /// the safe threshold (`max_ticks / 2`) within one creep lifetime.
@@ -132,6 +299,8 @@ impl UpgradeMission {
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl Mission for UpgradeMission {
+    impl_mission_pause!();
+
     fn get_owner(&self) -> &Option<Entity> {
         &self.owner
     }
@@ -148,6 +317,12 @@ impl Mission for UpgradeMission {
 
     fn remove_creep(&mut self, entity: Entity) {
         self.upgraders.retain(|e| *e != entity);
+
+        for seat in self.seats.iter_mut() {
+            if *seat.upgrader == Some(entity) {
+                seat.upgrader.take();
+            }
+        }
     }
 
     fn get_creeps(&self) -> Vec<Entity> {
@@ -162,33 +337,47 @@ impl Mission for UpgradeMission {
         crate::visualization::SummaryContent::Text(format!("Upgrade - Upgraders: {}", self.upgraders.len()))
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, String> {
+    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, MissionError> {
         //TODO: Limit upgraders to CONTROLLER_MAX_UPGRADE_PER_TICK total work parts at max level.
 
-        let room_data = system_data.room_data.get(self.room_data).ok_or("Expected room data")?;
-        let room = game::rooms().get(room_data.name).ok_or("Expected room")?;
-        let structures = room_data.get_structures().ok_or("Expected structure data")?;
-        let creeps = room_data.get_creeps().ok_or("Expected creeps")?;
-        let static_visibility_data = room_data.get_static_visibility_data().ok_or("Expected static visibility data")?;
+        let room_data = system_data
+            .room_data
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
+        let room = game::rooms()
+            .get(room_data.name)
+            .ok_or_else(|| MissionError::new(MissionFailure::NoVisibility, "Expected room"))?;
+        let structures = room_data
+            .get_structures()
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected structure data"))?;
+        let creeps = room_data
+            .get_creeps()
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected creeps"))?;
+        let static_visibility_data = room_data
+            .get_static_visibility_data()
+            .ok_or_else(|| MissionError::new(MissionFailure::NoVisibility, "Expected static visibility data"))?;
 
         let controllers = structures.controllers();
         let storages = structures.storages();
 
         if !Self::can_run(room_data) {
-            return Err("Upgrade room not owned by user".to_string());
+            return Err(MissionError::new(MissionFailure::TargetInvalid, "Upgrade room not owned by user"));
         }
 
-        let controller_level = controllers.iter().map(|c| c.level()).max().ok_or("Expected controller level")?;
+        let controller_level = controllers
+            .iter()
+            .map(|c| c.level())
+            .max()
+            .ok_or_else(|| MissionError::new(MissionFailure::TargetInvalid, "Expected controller level"))?;
 
+        // With storage, the surplus is what's left after the reserve and the
+        // operation budget's commitments, and a room whose spawns are
+        // saturated holds back: extra upgraders would only queue.
         let has_excess_energy = {
             if !structures.storages().is_empty() {
-                let energy: u32 = structures
-                    .storages()
-                    .iter()
-                    .map(|storage| storage.store().get(ResourceType::Energy).unwrap_or(0))
-                    .sum();
-
-                energy >= get_desired_storage_amount(ResourceType::Energy) / 2
+                system_data.economy.room(&self.room_data).is_some_and(|economy| {
+                    economy.spendable_energy() >= get_desired_storage_amount(ResourceType::Energy) / 2 && !economy.spawns_saturated()
+                })
             } else if !structures.containers().is_empty() {
                 structures
                     .containers()
@@ -201,6 +390,25 @@ impl Mission for UpgradeMission {
 
         let are_hostile_creeps = !creeps.hostile().is_empty();
 
+        // From mid RCL upgraders work from seats by the controller's link or
+        // container; with neither built yet they fetch energy as before.
+        let feed = if controller_level >= SEATED_UPGRADE_MIN_LEVEL {
+            let structure_data_rc = system_data.supply_structure_cache.get_room(room_data.name);
+            let mut structure_data =
+                structure_data_rc.maybe_access(|d| d.needs_rebuild(room_data), || create_structure_data(room_data, None));
+
+            structure_data.get().and_then(upgrade_feed)
+        } else {
+            None
+        };
+
+        match (feed, static_visibility_data.controller()) {
+            (Some((feed, feed_pos)), Some(controller)) => {
+                self.seat_upgraders(system_data.upgrade_seating, controller.pos(), feed, feed_pos, &structures);
+            }
+            _ => self.seats.clear(),
+        }
+
         // Detect downgrade risk at any RCL. When the downgrade timer falls
         // below half of max we spawn an upkeep upgrader at critical priority,
         // sized so that it can restore the timer back to the safe threshold
@@ -240,6 +448,24 @@ impl Mission for UpgradeMission {
             1
         };
 
+        // The upgrade focus room burns the surplus sent its way with a spare upgrader.
+        let focused = system_data.upgrade_focus.is_focus(room_data.name);
+
+        let max_upgraders =
+            if focused && !are_hostile_creeps && !at_max_level && system_data.governor.can_execute_cpu(CpuBar::MediumPriority) {
+                max_upgraders + 1
+            } else {
+                max_upgraders
+            };
+
+        // Seated upgraders never leave their seats, so more than there are
+        // seats would only queue behind them.
+        let max_upgraders = if self.seats.is_empty() {
+            max_upgraders
+        } else {
+            max_upgraders.min(self.seats.len())
+        };
+
         let alive_upgraders = self
             .upgraders
             .iter()
@@ -255,7 +481,10 @@ impl Mission for UpgradeMission {
             })
             .count();
 
-        if alive_upgraders < max_upgraders {
+        // An energy emergency holds upgraders back, unless the controller is about to downgrade with none.
+        let suspended = system_data.energy_emergency.is_active(self.room_data) && !(downgrade_risk && self.upgraders.is_empty());
+
+        if alive_upgraders < max_upgraders && !suspended {
             let work_parts_per_upgrader = if let Some(upkeep_parts) = downgrade_upkeep_parts {
                 if self.upgraders.is_empty() {
                     // Downgrade risk with no upgrader at all: size the body
@@ -295,27 +524,22 @@ impl Mission for UpgradeMission {
                 room.energy_capacity_available()
             };
 
-            let body_definition = if controller_level <= 3 {
-                crate::creep::SpawnBodyDefinition {
-                    maximum_energy,
-                    minimum_repeat: Some(0),
-                    maximum_repeat: work_parts_per_upgrader,
-                    pre_body: &[Part::Work, Part::Carry, Part::Move, Part::Move],
-                    repeat_body: &[Part::Work, Part::Move],
-                    post_body: &[],
-                }
+            // Upgraders at RCL 4+ sit by their feed, so they carry just enough MOVE to get there.
+            let body_template = if controller_level <= 3 {
+                BodyTemplate::new(&[Part::Work])
+                    .prefix(&[Part::Work, Part::Carry])
+                    .plains()
+                    .min_repeat(0)
             } else {
-                crate::creep::SpawnBodyDefinition {
-                    maximum_energy,
-                    minimum_repeat: Some(1),
-                    maximum_repeat: work_parts_per_upgrader.map(|p| p - 1),
-                    pre_body: &[Part::Work, Part::Carry, Part::Move, Part::Move],
-                    repeat_body: &[Part::Work],
-                    post_body: &[],
-                }
+                BodyTemplate::new(&[Part::Work]).prefix(&[Part::Work, Part::Carry, Part::Move, Part::Move])
+            };
+
+            let body_template = match work_parts_per_upgrader {
+                Some(work_parts) => body_template.max_parts(Part::Work, work_parts),
+                None => body_template,
             };
 
-            if let Ok(body) = crate::creep::spawning::create_body(&body_definition) {
+            if let Some(body) = body_template.build(maximum_energy) {
                 let priority = if downgrade_risk && self.upgraders.is_empty() {
                     // Downgrade risk with no upgrader at all — override
                     // everything else and get a creep out immediately.
@@ -340,7 +564,8 @@ impl Mission for UpgradeMission {
                     priority,
                     None,
                     Self::create_handle_upgrader_spawn(mission_entity, self.room_data),
-                );
+                )
+                .requested_by(mission_entity);
 
                 system_data.spawn_queue.request(self.room_data, spawn_request);
             }
@@ -349,3 +574,25 @@ impl Mission for UpgradeMission {
         Ok(MissionResult::Running)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seats_are_next_to_the_feed_and_in_upgrade_range() {
+        // Controller at (10, 10), link three tiles east: only the feed's
+        // western column is within range 3 of the controller.
+        let seats = upgrade_seats((10, 10), (13, 10), |x, y| (x, y) != (13, 10));
+
+        assert_eq!(seats, vec![(12, 9), (12, 10), (12, 11), (13, 9), (13, 11)]);
+    }
+
+    #[test]
+    fn seats_skip_walls_and_the_room_edge() {
+        let seats = upgrade_seats((3, 3), (1, 2), |x, _| x != 2);
+
+        assert!(seats.iter().all(|(x, y)| *x >= 1 && *y >= 1 && *x != 2));
+        assert_eq!(seats, vec![(1, 1), (1, 2), (1, 3)]);
+    }
+}
@@ -1,9 +1,14 @@
 use super::data::*;
 use super::missionsystem::*;
+use crate::creep::spawning::BodyTemplate;
+use crate::jobs::build::*;
+use crate::jobs::data::*;
 use crate::jobs::utility::repair::RepairPriority;
+use crate::military::threatmap::{breach_choke, core_breaches, BarrierGrid};
 use crate::remoteobjectid::*;
 use crate::repairqueue::RepairRequest;
 use crate::serialize::*;
+use crate::spawnsystem::*;
 use crate::structureidentifier::RemoteStructureIdentifier;
 use crate::transfer::transfersystem::*;
 use log::*;
@@ -13,6 +18,7 @@ use serde::{Deserialize, Serialize};
 use specs::error::NoError;
 use specs::saveload::*;
 use specs::*;
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 /// Minimum wall/rampart hits to consider "safe" during a siege.
 /// Structures below this threshold get priority repair.
@@ -24,6 +30,56 @@ const MODERATE_WALL_HITS: u32 = 1_000_000;
 /// Ticks with no hostiles before the mission completes (avoids restart flip-flop).
 const IDLE_TICKS_BEFORE_COMPLETE: u32 = 100;
 
+/// What the breach detector remembers about one besieged room between ticks.
+#[derive(Default)]
+struct BreachWatch {
+    /// Every barrier seen standing in the room during this siege.
+    known: HashSet<RawObjectId>,
+    /// The fallen barriers the room was last found sealed with; `None` until
+    /// a detection finds no breach.
+    sealed_with: Option<BTreeSet<RawObjectId>>,
+    /// The last choke announced, so a lasting breach is logged once.
+    choke: Option<Position>,
+}
+
+impl BreachWatch {
+    /// Note the barriers standing now and return the ones seen before that
+    /// have fallen since.
+    fn destroyed(&mut self, standing: impl IntoIterator<Item = RawObjectId>) -> BTreeSet<RawObjectId> {
+        let standing: HashSet<RawObjectId> = standing.into_iter().collect();
+
+        self.known.extend(standing.iter().copied());
+        self.known.difference(&standing).copied().collect()
+    }
+
+    /// Whether the last detection still holds. Only a fallen barrier can open
+    /// a path, so a room sealed with the same barriers down is sealed still.
+    fn is_sealed(&self, destroyed: &BTreeSet<RawObjectId>) -> bool {
+        self.sealed_with.as_ref() == Some(destroyed)
+    }
+}
+
+/// Emergency ramparts for rooms under siege. `sites` holds this tick's site
+/// per breached room, republished by the wall repair missions and cleared by
+/// `PreRunMissionSystem`; builders put it ahead of every other site. The
+/// watches cache each room's last detection for the length of its siege.
+#[derive(Default)]
+pub struct EmergencyRamparts {
+    sites: HashMap<RoomName, Position>,
+    watches: HashMap<RoomName, BreachWatch>,
+}
+
+impl EmergencyRamparts {
+    pub fn clear_sites(&mut self) {
+        self.sites.clear();
+    }
+
+    /// The rampart that closes the breach into `room`, if it has one.
+    pub fn site(&self, room: RoomName) -> Option<Position> {
+        self.sites.get(&room).copied()
+    }
+}
+
 /// Mission to prioritize wall and rampart repair during siege.
 ///
 /// When hostiles are present and attacking walls/ramparts, this mission
@@ -34,6 +90,15 @@ const IDLE_TICKS_BEFORE_COMPLETE: u32 = 100;
 /// Uses an idle/active state: when hostiles leave, the mission stays running
 /// (idle) for IDLE_TICKS_BEFORE_COMPLETE before completing, so we don't
 /// restart the mission every other tick if hostiles flicker.
+///
+/// While hostiles are present it also checks every tick for a path from the
+/// exits to the spawns and storage that crosses none of our ramparts. When
+/// one opens, it places a rampart site on the plan's rampart ring where it
+/// best closes the path and spawns a builder for it at critical priority.
+///
+/// Each scan under siege also forecasts where attackers would break in (see
+/// `military::threatmap::predict_breaches`) and repairs the barriers on the
+/// likely breach paths at critical priority, whatever their hits.
 #[derive(ConvertSaveload)]
 pub struct WallRepairMission {
     owner: EntityOption<Entity>,
@@ -42,6 +107,8 @@ pub struct WallRepairMission {
     last_scan_tick: u32,
     /// Ticks since we last saw hostiles. When >= IDLE_TICKS_BEFORE_COMPLETE we complete.
     ticks_since_hostiles: u32,
+    builders: EntityVec<Entity>,
+    paused: bool,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -55,16 +122,170 @@ impl WallRepairMission {
             room_data,
             last_scan_tick: 0,
             ticks_since_hostiles: 0,
+            builders: EntityVec::new(),
+            paused: false,
         };
 
         builder
             .with(MissionData::WallRepair(EntityRefCell::new(mission)))
             .marked::<SerializeMarker>()
     }
+
+    fn create_handle_builder_spawn(
+        mission_entity: Entity,
+        room_entity: Entity,
+        allow_harvest: bool,
+    ) -> crate::spawnsystem::SpawnQueueCallback {
+        Box::new(move |spawn_system_data, name| {
+            let name = name.to_string();
+
+            spawn_system_data.updater.exec_mut(move |world| {
+                let creep_job = JobData::Build(BuildJob::new(room_entity, room_entity, allow_harvest));
+
+                let creep_entity = crate::creep::spawning::build(world.create_entity(), &name).with(creep_job).build();
+
+                if let Some(mut mission_data) = world
+                    .write_storage::<MissionData>()
+                    .get_mut(mission_entity)
+                    .as_mission_type_mut::<WallRepairMission>()
+                {
+                    mission_data.builders.push(creep_entity);
+                }
+            });
+        })
+    }
+
+    /// Find a hostile path to the spawns and storage and the plan rampart
+    /// tile that closes it. Skips the flood fill while the barriers down are
+    /// the ones the room was last found sealed with.
+    fn detect_breach(&self, system_data: &mut MissionExecutionSystemData) -> Option<Position> {
+        let room_data = system_data.room_data.get(self.room_data)?;
+        let structures = room_data.get_structures()?;
+        let room_name = room_data.name;
+
+        let barriers = structures
+            .all()
+            .iter()
+            .filter(|s| !matches!(s, StructureObject::StructureRoad(_) | StructureObject::StructureContainer(_)))
+            .filter_map(|s| s.as_structure().try_raw_id());
+
+        let watch = system_data.emergency_ramparts.watches.entry(room_name).or_default();
+        let destroyed = watch.destroyed(barriers);
+
+        if watch.is_sealed(&destroyed) {
+            return None;
+        }
+
+        let grid = BarrierGrid::new(room_name, &structures)?;
+        let core: Vec<(u8, u8)> = structures
+            .spawns()
+            .iter()
+            .filter(|s| s.my())
+            .map(|s| s.pos())
+            .chain(structures.storages().iter().filter(|s| s.my()).map(|s| s.pos()))
+            .map(|pos| (pos.x().u8(), pos.y().u8()))
+            .collect();
+
+        let breaches = core_breaches(|x, y| grid.is_blocked(x, y), |x, y| grid.is_rampart(x, y), &core);
+
+        if breaches.is_empty() {
+            if watch.choke.take().is_some() {
+                info!("[WallRepair] Room {} breach closed", room_name);
+            }
+
+            watch.sealed_with = Some(destroyed);
+
+            return None;
+        }
+
+        watch.sealed_with = None;
+
+        let candidates: Vec<(u8, u8)> = system_data
+            .room_plan_data
+            .get(self.room_data)
+            .and_then(|d| d.plan())
+            .map(|plan| {
+                plan.structures
+                    .iter()
+                    .filter(|(_, item)| item.structure_type == StructureType::Rampart)
+                    .map(|(location, _)| (location.x(), location.y()))
+                    .filter(|(x, y)| !grid.is_rampart(*x, *y))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let choke = breach_choke(|x, y| grid.is_blocked(x, y), |x, y| grid.is_rampart(x, y), &core, &candidates)
+            .and_then(|(x, y)| Some(Position::new(RoomCoordinate::new(x).ok()?, RoomCoordinate::new(y).ok()?, room_name)));
+
+        if watch.choke != choke {
+            match choke {
+                Some(pos) => warn!(
+                    "[WallRepair] Room {} breached: {} tiles by the core reachable, emergency rampart at {}",
+                    room_name,
+                    breaches.len(),
+                    pos
+                ),
+                None => warn!(
+                    "[WallRepair] Room {} breached: {} tiles by the core reachable, no plan rampart closes it",
+                    room_name,
+                    breaches.len()
+                ),
+            }
+
+            watch.choke = choke;
+        }
+
+        choke
+    }
+
+    /// Keep a rampart site on the breach choke and a builder on its way to it.
+    fn seal_breach(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity, choke: Position) {
+        let Some(room_data) = system_data.room_data.get(self.room_data) else {
+            return;
+        };
+
+        system_data.emergency_ramparts.sites.insert(room_data.name, choke);
+
+        let has_site = room_data
+            .get_construction_sites()
+            .map(|sites| sites.iter().any(|s| s.pos() == choke))
+            .unwrap_or(false);
+
+        if !has_site {
+            if let Err(err) = choke.create_construction_site(StructureType::Rampart, None) {
+                debug!("[WallRepair] Failed to place emergency rampart at {}: {:?}", choke, err);
+            }
+        }
+
+        if !self.builders.is_empty() {
+            return;
+        }
+
+        let Some(room) = game::rooms().get(room_data.name) else {
+            return;
+        };
+
+        let body_template = BodyTemplate::new(&[Part::Carry, Part::Work]).plains();
+
+        if let Some(body) = body_template.build(room.energy_available().max(SPAWN_ENERGY_CAPACITY)) {
+            let spawn_request = SpawnRequest::new(
+                "Emergency Builder".to_string(),
+                &body,
+                SPAWN_PRIORITY_CRITICAL,
+                None,
+                Self::create_handle_builder_spawn(mission_entity, self.room_data, room.storage().is_none()),
+            )
+            .requested_by(mission_entity);
+
+            system_data.spawn_queue.request(self.room_data, spawn_request);
+        }
+    }
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl Mission for WallRepairMission {
+    impl_mission_pause!();
+
     fn get_owner(&self) -> &Option<Entity> {
         &self.owner
     }
@@ -78,6 +299,14 @@ impl Mission for WallRepairMission {
         Some(self.room_data)
     }
 
+    fn remove_creep(&mut self, entity: Entity) {
+        self.builders.retain(|e| *e != entity);
+    }
+
+    fn get_creeps(&self) -> Vec<Entity> {
+        self.builders.iter().copied().collect()
+    }
+
     fn describe_state(&self, _system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> String {
         "WallRepair".to_string()
     }
@@ -86,8 +315,11 @@ impl Mission for WallRepairMission {
         crate::visualization::SummaryContent::Text("WallRepair".to_string())
     }
 
-    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), String> {
-        let room_data = system_data.room_data.get(self.room_data).ok_or("Expected room data")?;
+    fn pre_run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<(), MissionError> {
+        let room_data = system_data
+            .room_data
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
 
         let room_data_entity = self.room_data;
 
@@ -147,9 +379,26 @@ impl Mission for WallRepairMission {
         Ok(())
     }
 
-    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, _mission_entity: Entity) -> Result<MissionResult, String> {
+    fn run_mission(&mut self, system_data: &mut MissionExecutionSystemData, mission_entity: Entity) -> Result<MissionResult, MissionError> {
         let current_tick = game::time();
 
+        // Breaches are looked for every tick of a siege; a melee creep through a gap needs no more.
+        if system_data.features.military.emergency_ramparts {
+            let room_data = system_data
+                .room_data
+                .get(self.room_data)
+                .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
+            let room_name = room_data.name;
+
+            if room_data.get_creeps().map(|c| !c.hostile().is_empty()).unwrap_or(false) {
+                if let Some(choke) = self.detect_breach(system_data) {
+                    self.seal_breach(system_data, mission_entity, choke);
+                }
+            } else {
+                system_data.emergency_ramparts.watches.remove(&room_name);
+            }
+        }
+
         // Scan every 20 ticks to save CPU.
         if current_tick.saturating_sub(self.last_scan_tick) < 20 {
             return Ok(MissionResult::Running);
@@ -157,7 +406,10 @@ impl Mission for WallRepairMission {
         let elapsed = current_tick.saturating_sub(self.last_scan_tick);
         self.last_scan_tick = current_tick;
 
-        let room_data = system_data.room_data.get(self.room_data).ok_or("Expected room data")?;
+        let room_data = system_data
+            .room_data
+            .get(self.room_data)
+            .ok_or_else(|| MissionError::new(MissionFailure::MissingRoomData, "Expected room data"))?;
 
         let structures = match room_data.get_structures() {
             Some(s) => s,
@@ -166,16 +418,42 @@ impl Mission for WallRepairMission {
 
         let has_hostiles = room_data.get_creeps().map(|c| !c.hostile().is_empty()).unwrap_or(false);
 
+        let room_name = room_data.name;
+
         if has_hostiles {
             self.ticks_since_hostiles = 0;
         } else {
             self.ticks_since_hostiles = self.ticks_since_hostiles.saturating_add(elapsed);
             if self.ticks_since_hostiles >= IDLE_TICKS_BEFORE_COMPLETE {
+                system_data.breach_forecasts.forget(room_name);
+
                 return Ok(MissionResult::Success);
             }
         }
 
-        let room_name = room_data.name;
+        let forecast = if has_hostiles {
+            system_data.breach_forecasts.update(room_name, &structures)
+        } else {
+            system_data.breach_forecasts.get(room_name)
+        };
+
+        if let Some(forecast) = forecast {
+            if let Some(path) = forecast.paths.first() {
+                debug!(
+                    "[WallRepair] Room {} likely breach from {:?}: {} barriers, {} hits",
+                    room_name,
+                    path.exit,
+                    path.barriers.len(),
+                    path.hits
+                );
+            }
+        }
+
+        let on_breach_path = |pos: Position| {
+            forecast
+                .map(|forecast| forecast.is_on_likely_path((pos.x().u8(), pos.y().u8())))
+                .unwrap_or(false)
+        };
 
         // Analyze wall and rampart health and populate the repair queue.
         let mut weakest_rampart_hits: u32 = u32::MAX;
@@ -194,6 +472,8 @@ impl Mission for WallRepairMission {
             let priority = if hits < EMERGENCY_WALL_HITS {
                 emergency_count += 1;
                 RepairPriority::Critical
+            } else if hits < hits_max && on_breach_path(rampart.pos()) {
+                RepairPriority::Critical
             } else if hits < MODERATE_WALL_HITS {
                 moderate_count += 1;
                 RepairPriority::High
@@ -220,6 +500,8 @@ impl Mission for WallRepairMission {
             let priority = if hits < EMERGENCY_WALL_HITS {
                 emergency_count += 1;
                 RepairPriority::Critical
+            } else if hits < hits_max && on_breach_path(wall.pos()) {
+                RepairPriority::Critical
             } else if hits < MODERATE_WALL_HITS {
                 moderate_count += 1;
                 RepairPriority::High
@@ -273,3 +555,30 @@ impl Mission for WallRepairMission {
         Ok(MissionResult::Running)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(n: u32) -> RawObjectId {
+        format!("{:024x}", n).parse().expect("valid object id")
+    }
+
+    #[test]
+    fn sealed_result_holds_until_another_barrier_falls() {
+        let mut watch = BreachWatch::default();
+
+        let destroyed = watch.destroyed([id(1), id(2)]);
+        assert!(destroyed.is_empty());
+        assert!(!watch.is_sealed(&destroyed), "nothing has been detected yet");
+        watch.sealed_with = Some(destroyed);
+
+        // A rampart built since cannot open a path.
+        let destroyed = watch.destroyed([id(1), id(2), id(3)]);
+        assert!(watch.is_sealed(&destroyed));
+
+        let destroyed = watch.destroyed([id(1), id(3)]);
+        assert_eq!(destroyed, [id(2)].into_iter().collect());
+        assert!(!watch.is_sealed(&destroyed));
+    }
+}
@@ -0,0 +1,435 @@
+//! Energy and spawn-time budget shared by the expensive operations.
+//!
+//! Claims, remote mining outposts, source keeper farms and offense each launch
+//! on their own cadence, and together they can promise a home room far more
+//! creeps than it can pay for or spawn. Before launching, an operation
+//! [`reserve`](OperationBudget::reserve)s the launch's estimated cost against
+//! its home rooms. A reservation that isn't granted yet is left pending; once
+//! the tick's operations have run, [`allocate`](OperationBudget::allocate)
+//! grants the pending requests greedily by expected value against each home
+//! room's headroom and defers the rest. The operation picks its grant up on
+//! its next run (see [`has_grant`](OperationBudget::has_grant)).
+//!
+//! A granted launch becomes a commitment on one home room, released when the
+//! mission it was bound to is gone (completed or failed) or, for launches with
+//! no mission, when the operation stops re-reserving it. The budget is a
+//! runtime resource: after a VM reload operations re-[`hold`](OperationBudget::hold)
+//! their running missions on their next scan.
+
+use crate::military::economy::EconomySnapshot;
+use log::*;
+use screeps::*;
+use specs::Entity;
+use std::collections::HashMap;
+
+/// Share of a home room's spawn time over a creep lifetime that operations may commit; the rest is left
+/// to the room's own economy.
+pub const OPERATION_SPAWN_SHARE: f32 = 0.5;
+/// Share of a home room's source income over a creep lifetime that operations may commit, on top of the
+/// stored surplus.
+pub const OPERATION_INCOME_SHARE: f32 = 0.5;
+/// Rough energy per body part, to estimate spawn time from a cost when the body isn't known.
+const AVERAGE_PART_COST: u32 = 80;
+/// Ticks a commitment with no bound mission stands without being re-reserved.
+const UNBOUND_COMMITMENT_TTL: u32 = 200;
+/// Ticks between commitment summaries in the log.
+const SUMMARY_LOG_INTERVAL: u32 = 500;
+
+/// Energy and spawn time, over a creep lifetime.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OperationCost {
+    pub energy: u32,
+    pub spawn_ticks: u32,
+}
+
+impl OperationCost {
+    pub fn new(energy: u32, spawn_ticks: u32) -> OperationCost {
+        OperationCost { energy, spawn_ticks }
+    }
+
+    /// A cost whose bodies aren't known, with spawn time estimated from the energy.
+    pub fn from_energy(energy: u32) -> OperationCost {
+        OperationCost::new(energy, energy.div_ceil(AVERAGE_PART_COST) * CREEP_SPAWN_TIME)
+    }
+
+    fn fits(&self, headroom: &OperationCost) -> bool {
+        self.energy <= headroom.energy && self.spawn_ticks <= headroom.spawn_ticks
+    }
+
+    fn accumulate(&mut self, other: &OperationCost) {
+        self.energy += other.energy;
+        self.spawn_ticks += other.spawn_ticks;
+    }
+
+    fn saturating_sub(&self, other: &OperationCost) -> OperationCost {
+        OperationCost::new(
+            self.energy.saturating_sub(other.energy),
+            self.spawn_ticks.saturating_sub(other.spawn_ticks),
+        )
+    }
+}
+
+/// The operations that reserve from the budget.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum BudgetOperation {
+    Claim,
+    MiningOutpost,
+    SourceKeeper,
+    Attack,
+}
+
+/// One launch: an operation's mission or objective for a target room.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BudgetKey {
+    pub operation: BudgetOperation,
+    pub room: RoomName,
+}
+
+impl BudgetKey {
+    pub fn new(operation: BudgetOperation, room: RoomName) -> BudgetKey {
+        BudgetKey { operation, room }
+    }
+}
+
+/// A launch an operation wants to make.
+#[derive(Clone, Debug)]
+pub struct BudgetRequest {
+    pub key: BudgetKey,
+    /// Home rooms that could pay for it; the commitment lands on one of them.
+    pub homes: Vec<Entity>,
+    pub cost: OperationCost,
+    /// Expected net energy per tick the launch returns — the allocation order.
+    pub value: f32,
+    /// Ticks the request stands without being re-filed; at least the operation's scan cadence, so a grant
+    /// made after one scan is still there for the next.
+    pub ttl: u32,
+}
+
+impl BudgetRequest {
+    pub fn new(key: BudgetKey, homes: Vec<Entity>, cost: OperationCost, value: f32, ttl: u32) -> BudgetRequest {
+        BudgetRequest {
+            key,
+            homes,
+            cost,
+            value,
+            ttl,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Pending {
+    request: BudgetRequest,
+    expires_at: u32,
+    /// The home room the grant was charged to, once granted.
+    granted: Option<Entity>,
+}
+
+#[derive(Clone, Debug)]
+struct Commitment {
+    home: Entity,
+    cost: OperationCost,
+    /// The mission whose lifetime the commitment follows; `None` lapses at `expires_at` unless re-reserved.
+    mission: Option<Entity>,
+    expires_at: u32,
+}
+
+/// Committed and granted operation costs per home room. Runtime resource; see the module docs.
+#[derive(Default)]
+pub struct OperationBudget {
+    pending: HashMap<BudgetKey, Pending>,
+    commitments: HashMap<BudgetKey, Commitment>,
+}
+
+impl OperationBudget {
+    /// Ask to launch. `true` when the launch is committed (already, or from a grant made since the last
+    /// request) and may go ahead; `false` leaves it pending for the next allocation.
+    pub fn reserve(&mut self, request: BudgetRequest, now: u32) -> bool {
+        let key = request.key;
+
+        if let Some(commitment) = self.commitments.get_mut(&key) {
+            commitment.expires_at = now + UNBOUND_COMMITMENT_TTL.max(request.ttl);
+            return true;
+        }
+
+        if let Some(home) = self.pending.get(&key).and_then(|p| p.granted) {
+            self.pending.remove(&key);
+            self.commitments.insert(
+                key,
+                Commitment {
+                    home,
+                    cost: request.cost,
+                    mission: None,
+                    expires_at: now + UNBOUND_COMMITMENT_TTL.max(request.ttl),
+                },
+            );
+            return true;
+        }
+
+        let expires_at = now + request.ttl;
+        self.pending.insert(
+            key,
+            Pending {
+                request,
+                expires_at,
+                granted: None,
+            },
+        );
+
+        false
+    }
+
+    /// Tie a commitment to the mission it launched, so it lasts exactly as long as the mission.
+    pub fn bind(&mut self, key: &BudgetKey, mission: Entity) {
+        if let Some(commitment) = self.commitments.get_mut(key) {
+            commitment.mission = Some(mission);
+        }
+    }
+
+    /// Commit a mission that is already running, without waiting for a grant (re-seeding after a reload).
+    pub fn hold(&mut self, request: BudgetRequest, mission: Entity, now: u32) {
+        if let Some(commitment) = self.commitments.get_mut(&request.key) {
+            commitment.mission = Some(mission);
+            return;
+        }
+
+        let Some(home) = request.homes.first().copied() else {
+            return;
+        };
+
+        self.pending.remove(&request.key);
+        self.commitments.insert(
+            request.key,
+            Commitment {
+                home,
+                cost: request.cost,
+                mission: Some(mission),
+                expires_at: now,
+            },
+        );
+    }
+
+    /// Drop a launch the operation no longer wants, committed or not.
+    pub fn release(&mut self, key: &BudgetKey) {
+        self.pending.remove(key);
+        self.commitments.remove(key);
+    }
+
+    /// Whether a grant is waiting for the operation to pick it up.
+    pub fn has_grant(&self, operation: BudgetOperation) -> bool {
+        self.pending
+            .iter()
+            .any(|(key, p)| key.operation == operation && p.granted.is_some())
+    }
+
+    /// Committed cost and launch count charged to a home room, grants included.
+    pub fn committed(&self, home: Entity) -> (OperationCost, usize) {
+        let mut total = OperationCost::default();
+        let mut count = 0;
+
+        for (cost, _) in self.charges().filter(|(_, h)| *h == home) {
+            total.accumulate(cost);
+            count += 1;
+        }
+
+        (total, count)
+    }
+
+    fn charges(&self) -> impl Iterator<Item = (&OperationCost, Entity)> {
+        let commitments = self.commitments.values().map(|c| (&c.cost, c.home));
+        let grants = self.pending.values().filter_map(|p| p.granted.map(|home| (&p.request.cost, home)));
+
+        commitments.chain(grants)
+    }
+
+    /// Release finished launches, then grant pending requests by expected value against each home room's
+    /// remaining headroom. `capacity` is what operations may commit per home room in total.
+    pub fn allocate<F>(&mut self, capacity: &HashMap<Entity, OperationCost>, is_alive: F, now: u32)
+    where
+        F: Fn(Entity) -> bool,
+    {
+        self.commitments.retain(|key, c| {
+            let live = match c.mission {
+                Some(mission) => is_alive(mission),
+                None => now <= c.expires_at,
+            };
+            if !live {
+                info!(
+                    "[Budget] Released {:?} {} ({}e, {}t)",
+                    key.operation, key.room, c.cost.energy, c.cost.spawn_ticks
+                );
+            }
+            live
+        });
+        self.pending.retain(|_, p| now <= p.expires_at);
+
+        let mut headroom: HashMap<Entity, OperationCost> = capacity.clone();
+        for (cost, home) in self.charges() {
+            if let Some(room) = headroom.get_mut(&home) {
+                *room = room.saturating_sub(cost);
+            }
+        }
+
+        let mut waiting: Vec<&mut Pending> = self.pending.values_mut().filter(|p| p.granted.is_none()).collect();
+        waiting.sort_by(|a, b| {
+            b.request
+                .value
+                .total_cmp(&a.request.value)
+                .then_with(|| a.request.key.cmp(&b.request.key))
+        });
+
+        for pending in waiting {
+            let request = &pending.request;
+            let home = request
+                .homes
+                .iter()
+                .filter(|home| headroom.get(*home).is_some_and(|room| request.cost.fits(room)))
+                .max_by_key(|home| headroom.get(*home).map(|room| room.energy).unwrap_or(0))
+                .copied();
+
+            match home {
+                Some(home) => {
+                    if let Some(room) = headroom.get_mut(&home) {
+                        *room = room.saturating_sub(&request.cost);
+                    }
+                    pending.granted = Some(home);
+                    info!(
+                        "[Budget] Granted {:?} {} ({}e, {}t, value {:.1}/t)",
+                        request.key.operation, request.key.room, request.cost.energy, request.cost.spawn_ticks, request.value
+                    );
+                }
+                None => {
+                    debug!(
+                        "[Budget] Deferred {:?} {} ({}e, {}t, value {:.1}/t) -- no home room has the headroom",
+                        request.key.operation, request.key.room, request.cost.energy, request.cost.spawn_ticks, request.value
+                    );
+                }
+            }
+        }
+    }
+
+    /// Log each home room's commitments every [`SUMMARY_LOG_INTERVAL`] ticks.
+    pub fn log_summary(&self, room_name: impl Fn(Entity) -> Option<RoomName>, now: u32) {
+        if !now.is_multiple_of(SUMMARY_LOG_INTERVAL) {
+            return;
+        }
+
+        let mut homes: Vec<Entity> = self.charges().map(|(_, home)| home).collect();
+        homes.sort();
+        homes.dedup();
+
+        for home in homes {
+            let (cost, count) = self.committed(home);
+            let name = room_name(home).map(|n| n.to_string()).unwrap_or_else(|| "?".to_string());
+            info!(
+                "[Budget] {}: {} launches committed ({}e, {}t)",
+                name, count, cost.energy, cost.spawn_ticks
+            );
+        }
+    }
+}
+
+/// What operations may commit per home room in total: the stored surplus above the room's reserve plus a
+/// share of its income, and a share of its spawn time, over a creep lifetime.
+pub fn operation_capacity(economy: &EconomySnapshot) -> HashMap<Entity, OperationCost> {
+    economy
+        .rooms
+        .iter()
+        .map(|(entity, room)| {
+            let reserve = (room.stored_energy / 5).clamp(5_000, 30_000);
+            let income = room.energy_income * CREEP_LIFE_TIME as f32 * OPERATION_INCOME_SHARE;
+            let energy = room.stored_energy.saturating_sub(reserve) + income as u32;
+            let spawn_ticks = (room.spawn_count * CREEP_LIFE_TIME) as f32 * OPERATION_SPAWN_SHARE;
+
+            (*entity, OperationCost::new(energy, spawn_ticks as u32))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specs::prelude::*;
+
+    fn key(operation: BudgetOperation, room: &str) -> BudgetKey {
+        BudgetKey::new(operation, room.parse().unwrap())
+    }
+
+    fn request(key: BudgetKey, home: Entity, energy: u32, value: f32) -> BudgetRequest {
+        BudgetRequest::new(key, vec![home], OperationCost::new(energy, 100), value, 50)
+    }
+
+    /// Two launches compete for one home room: the more valuable is granted, the other deferred until the
+    /// first's mission ends.
+    #[test]
+    fn grants_by_value_and_releases_with_the_mission() {
+        let mut world = World::new();
+        let home = world.create_entity().build();
+        let mission = world.create_entity().build();
+        let capacity = HashMap::from([(home, OperationCost::new(6_000, 1_000))]);
+
+        let outpost = key(BudgetOperation::MiningOutpost, "W2N1");
+        let claim = key(BudgetOperation::Claim, "W3N1");
+        let mut budget = OperationBudget::default();
+
+        assert!(!budget.reserve(request(outpost, home, 4_000, 8.0), 10));
+        assert!(!budget.reserve(request(claim, home, 4_000, 20.0), 10));
+        budget.allocate(&capacity, |_| true, 10);
+
+        assert!(budget.has_grant(BudgetOperation::Claim));
+        assert!(!budget.has_grant(BudgetOperation::MiningOutpost));
+        assert!(budget.reserve(request(claim, home, 4_000, 20.0), 11));
+        budget.bind(&claim, mission);
+        assert_eq!(budget.committed(home), (OperationCost::new(4_000, 100), 1));
+
+        budget.allocate(&capacity, |_| true, 12);
+        assert!(!budget.has_grant(BudgetOperation::MiningOutpost));
+
+        world.delete_entity(mission).unwrap();
+        world.maintain();
+        budget.allocate(&capacity, |e| world.entities().is_alive(e), 13);
+        assert!(budget.has_grant(BudgetOperation::MiningOutpost));
+        assert_eq!(budget.committed(home), (OperationCost::new(4_000, 100), 1));
+    }
+
+    /// The grant lands on whichever home room has the headroom; requests nobody re-files lapse.
+    #[test]
+    fn grants_fall_to_a_home_with_headroom_and_requests_lapse() {
+        let mut world = World::new();
+        let small = world.create_entity().build();
+        let large = world.create_entity().build();
+        let capacity = HashMap::from([(small, OperationCost::new(1_000, 1_000)), (large, OperationCost::new(9_000, 1_000))]);
+
+        let farm = key(BudgetOperation::SourceKeeper, "W5N5");
+        let mut budget = OperationBudget::default();
+        let both = BudgetRequest::new(farm, vec![small, large], OperationCost::new(5_000, 300), 30.0, 50);
+
+        assert!(!budget.reserve(both.clone(), 0));
+        budget.allocate(&capacity, |_| true, 0);
+        assert!(budget.reserve(both, 1));
+        assert_eq!(budget.committed(large).1, 1);
+        assert_eq!(budget.committed(small).1, 0);
+
+        let attack = key(BudgetOperation::Attack, "W6N6");
+        assert!(!budget.reserve(request(attack, large, 50_000, 99.0), 2));
+        budget.allocate(&capacity, |_| true, 53);
+        assert!(!budget.has_grant(BudgetOperation::Attack));
+        budget.allocate(&capacity, |_| true, 300);
+        assert_eq!(budget.committed(large).1, 0);
+    }
+
+    #[test]
+    fn hold_reseeds_running_missions() {
+        let mut world = World::new();
+        let home = world.create_entity().build();
+        let mission = world.create_entity().build();
+        let outpost = key(BudgetOperation::MiningOutpost, "W2N1");
+        let mut budget = OperationBudget::default();
+
+        budget.hold(request(outpost, home, 3_000, 5.0), mission, 0);
+        budget.allocate(&HashMap::new(), |_| true, 10_000);
+
+        assert_eq!(budget.committed(home), (OperationCost::new(3_000, 100), 1));
+        assert!(budget.reserve(request(outpost, home, 3_000, 5.0), 10_001));
+    }
+}
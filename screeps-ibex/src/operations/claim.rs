@@ -1,3 +1,4 @@
+use super::budget::*;
 use super::data::*;
 use super::operationsystem::*;
 use crate::missions::claim::*;
@@ -6,6 +7,7 @@ use crate::missions::remotebuild::*;
 use crate::room::gather::*;
 use crate::room::roomplansystem::*;
 use crate::room::visibilitysystem::*;
+use crate::room_economics::DEFAULT_HOLD_HORIZON;
 use crate::serialize::*;
 use crate::visualization::{CandidateSubScores, SummaryContent};
 use log::*;
@@ -17,6 +19,11 @@ use specs::saveload::*;
 use specs::*;
 use std::collections::HashSet;
 
+/// Body energy of a claim launch: the claimer plus the pioneers that build the first spawn.
+const CLAIM_LAUNCH_COST: u32 = 3650;
+/// Ticks a claim budget request stands; Select re-runs as soon as the grant lands.
+const CLAIM_BUDGET_TTL: u32 = 10;
+
 /// Phase of the claim pipeline state machine.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 enum ClaimPhase {
@@ -138,9 +145,12 @@ impl ClaimOperation {
             return None;
         }
 
+        // Highway, center and SK rooms are out by class, which was settled
+        // the first time the room was scouted.
         let can_claim = dynamic_visibility_data.owner().neutral()
             && (dynamic_visibility_data.reservation().mine() || dynamic_visibility_data.reservation().neutral())
-            && !dynamic_visibility_data.source_keeper();
+            && !dynamic_visibility_data.source_keeper()
+            && static_visibility_data.room_class().claimable();
         let hostile = dynamic_visibility_data.owner().hostile();
 
         let can_plan = gather_system_data
@@ -173,6 +183,17 @@ impl ClaimOperation {
         Some(plan.score.total)
     }
 
+    /// A claim launch on `room_name`, paid for by one of `homes`.
+    fn budget_request(room_name: RoomName, homes: Vec<Entity>, value: f32) -> BudgetRequest {
+        BudgetRequest::new(
+            BudgetKey::new(BudgetOperation::Claim, room_name),
+            homes,
+            OperationCost::from_energy(CLAIM_LAUNCH_COST),
+            value,
+            CLAIM_BUDGET_TTL,
+        )
+    }
+
     /// Score a candidate room via the unified economic value (ADR 0038 §2 Part B):
     /// `intrinsic owned-colony net-ROI × unlock_fraction(distance) × support_decay(distance) × plan_quality`.
     /// The intrinsic ROI is distance-INDEPENDENT (a claimed room self-hauls internally); distance enters only
@@ -667,7 +688,8 @@ impl ClaimOperation {
                 })
                 .collect();
 
-            // Build set of home rooms already committed to active claim missions.
+            // Build set of home rooms already committed to active claim missions, and keep their budget
+            // commitments held.
             let mut used_home_rooms: HashSet<Entity> = HashSet::new();
             for mission_entity in self.claim_missions.iter() {
                 if let Some(mission) = system_data.mission_data.get(*mission_entity) {
@@ -675,6 +697,11 @@ impl ClaimOperation {
                         for home_entity in claim_mission.home_room_datas().iter() {
                             used_home_rooms.insert(*home_entity);
                         }
+
+                        if let Some(room_data) = system_data.room_data.get(claim_mission.room_data()) {
+                            let request = Self::budget_request(room_data.name, claim_mission.home_room_datas().to_vec(), 0.0);
+                            system_data.operation_budget.hold(request, *mission_entity, game::time());
+                        }
                     }
                 }
             }
@@ -774,9 +801,10 @@ impl ClaimOperation {
                 } else {
                     // Eligible homes: not already committed, able to AFFORD a
                     // claimer ([Claim, Move] = 650 energy ⇒ ~RCL 3 capacity —
-                    // an RCL 2 home would silently fail create_body), and within
-                    // CLAIM-creep reach (travel-time feasibility; claim
-                    // feasibility implies the colony is also build-feasible).
+                    // an RCL 2 home would silently fail create_body), with
+                    // spawns that aren't saturated, and within CLAIM-creep
+                    // reach (travel-time feasibility; claim feasibility
+                    // implies the colony is also build-feasible).
                     let candidate_name = candidate.room_name;
                     let claimer_cost = Part::Claim.cost() + Part::Move.cost();
                     let mut home_room_entities: Vec<Entity> = Vec::new();
@@ -791,6 +819,9 @@ impl ClaimOperation {
                         if energy_capacity < claimer_cost {
                             continue;
                         }
+                        if !system_data.economy.can_stage(entity) {
+                            continue;
+                        }
                         if crate::missions::utility::is_claim_feasible(system_data.pathfinder, *home_room_name, candidate_name) {
                             home_room_entities.push(*entity);
                         }
@@ -798,10 +829,24 @@ impl ClaimOperation {
 
                     if home_room_entities.is_empty() {
                         info!(
-                            "ClaimOp [Select]: top candidate {} has no eligible home rooms (all used, can't afford a claimer, or not claim-reachable)",
+                            "ClaimOp [Select]: top candidate {} has no eligible home rooms (all used, can't afford a claimer, spawns saturated, or not claim-reachable)",
                             room_data.name
                         );
                     } else {
+                        // Expected income of the claimed room: the score is the net-ROI normalised by
+                        // `roi_reference`, over the hold horizon.
+                        let value = candidate_score * features.roi_reference / DEFAULT_HOLD_HORIZON as f32;
+                        let request = Self::budget_request(room_data.name, home_room_entities.clone(), value);
+                        let budget_key = request.key;
+
+                        if !system_data.operation_budget.reserve(request, game::time()) {
+                            info!(
+                                "ClaimOp [Select]: candidate {} waiting on the operation budget (value={:.1}/t)",
+                                room_data.name, value
+                            );
+                            continue;
+                        }
+
                         info!(
                             "ClaimOp [Select]: creating claim mission for {} (score={:.3})",
                             room_data.name,
@@ -816,6 +861,8 @@ impl ClaimOperation {
                         )
                         .build();
 
+                        system_data.operation_budget.bind(&budget_key, mission_entity);
+
                         room_data.add_mission(mission_entity);
 
                         self.claim_missions.push(mission_entity);
@@ -969,7 +1016,8 @@ impl ClaimOperation {
                     let target_name = room_data.name;
                     let mut home_room_entities: Vec<Entity> = Vec::new();
                     for (entity, home_room_name, max_level) in home_room_data.iter() {
-                        if *max_level < 2 {
+                        // A colony that has its own spawn is never a home for its own build.
+                        if *max_level < 2 || *entity == room_entity {
                             continue;
                         }
                         if crate::missions::utility::is_build_feasible(system_data.pathfinder, *home_room_name, target_name) {
@@ -1008,10 +1056,24 @@ impl Operation for ClaimOperation {
         self.owner.take();
     }
 
+    fn get_children(&self) -> Vec<Entity> {
+        self.claim_missions.iter().copied().collect()
+    }
+
     fn child_complete(&mut self, child: Entity) {
         self.claim_missions.retain(|e| *e != child);
     }
 
+    /// A lost claim mission frees its slot; an idle pipeline rediscovers at once so the target is claimed
+    /// again if it still scores, rather than after the full discover interval.
+    fn child_lost(&mut self, child: Entity) {
+        self.claim_missions.retain(|e| *e != child);
+
+        if self.phase == ClaimPhase::Idle {
+            self.phase_tick = None;
+        }
+    }
+
     fn repair_entity_refs(&mut self, is_valid: &dyn Fn(Entity) -> bool) {
         self.claim_missions.retain(|e| {
             let ok = is_valid(*e);
@@ -1127,7 +1189,10 @@ impl Operation for ClaimOperation {
             ClaimPhase::Idle => {
                 let elapsed = self.phase_tick.map(|t| game::time().saturating_sub(t)).unwrap_or(u32::MAX);
 
-                if elapsed >= self.discover_interval_eff(&features.claim) {
+                if system_data.operation_budget.has_grant(BudgetOperation::Claim) {
+                    // A launch deferred by the last Select was granted; pick it up from the cached candidates.
+                    self.run_select(system_data, runtime_data, maximum_rooms, currently_owned_rooms, &features.claim);
+                } else if elapsed >= self.discover_interval_eff(&features.claim) {
                     // Readiness gate: all owned rooms must be RCL >= 2.
                     if min_rcl >= 2 {
                         self.run_discover(system_data);
@@ -1182,6 +1247,7 @@ mod tests {
         let budget = CpuBudget {
             cpu_used_estimate: None,
             cpu_limit: 100.0,
+            tick_allowance: 0.0,
         };
         // est_room_cpu = fallback 10 → estimate_cap = floor(100*0.85/10) = 8.
         // owned 3, healthy → structural = max(8, 4) = 8, min gcl 10 = 8.
@@ -1195,6 +1261,7 @@ mod tests {
         let budget = CpuBudget {
             cpu_used_estimate: Some(60.0),
             cpu_limit: 100.0,
+            tick_allowance: 0.0,
         };
         // est_room_cpu = 60/3 = 20 → estimate_cap = floor(100*0.85/20) = 4.
         let cap = ClaimOperation::compute_maximum_rooms(&f, budget, healthy_governor(), 3, 10);
@@ -1207,6 +1274,7 @@ mod tests {
         let budget = CpuBudget {
             cpu_used_estimate: Some(90.0),
             cpu_limit: 100.0,
+            tick_allowance: 0.0,
         };
         // est_room_cpu = 90/9 = 10 → estimate_cap = floor(85/10) = 8.
         // Draining/low bucket → Conserve tier → no probe. owned 9 → cap stays 8
@@ -1229,6 +1297,7 @@ mod tests {
         let budget = CpuBudget {
             cpu_used_estimate: Some(90.0),
             cpu_limit: 100.0,
+            tick_allowance: 0.0,
         };
         // tier Normal (bucket 9000 ≥ 4000, trend −1 ≥ −5), bucket ≥ 8000 floor.
         let mildly_draining_but_full = GovernorSnapshot::compute(9_000, -1.0, 500.0);
@@ -1243,6 +1312,7 @@ mod tests {
         let budget = CpuBudget {
             cpu_used_estimate: None,
             cpu_limit: 10_000.0, // estimate_cap would be huge
+            tick_allowance: 0.0,
         };
         // GCL is the hard ceiling.
         assert_eq!(ClaimOperation::compute_maximum_rooms(&f, budget, healthy_governor(), 2, 5), 5);
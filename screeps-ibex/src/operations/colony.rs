@@ -198,7 +198,11 @@ impl Operation for ColonyOperation {
 
         self.last_run = Some(game::time());
 
-        for (entity, room_data) in (system_data.entities, &mut *system_data.room_data).join() {
+        // Read-only scan, then attach: a mutable join would mark every
+        // room's data changed for the incremental world save.
+        let mut new_missions = Vec::new();
+
+        for (entity, room_data) in (system_data.entities, &*system_data.room_data).join() {
             let needs_colony = ColonyMission::can_run(room_data);
 
             if needs_colony {
@@ -228,11 +232,17 @@ impl Operation for ColonyOperation {
                     )
                     .build();
 
-                    room_data.add_mission(mission_entity);
+                    new_missions.push((entity, mission_entity));
                 }
             }
         }
 
+        for (entity, mission_entity) in new_missions {
+            if let Some(room_data) = system_data.room_data.get_mut(entity) {
+                room_data.add_mission(mission_entity);
+            }
+        }
+
         // Detect rooms with spawns but an unclaimed controller and attempt
         // to reclaim them by sending a claimer from a nearby home room.
         Self::run_reclaim(system_data, runtime_data);
@@ -30,6 +30,19 @@ impl OperationData {
         }
     }
 
+    /// Stable type name of the concrete operation (the variant name).
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            OperationData::MiningOutpost(_) => "MiningOutpost",
+            OperationData::Claim(_) => "Claim",
+            OperationData::Colony(_) => "Colony",
+            OperationData::Scout(_) => "Scout",
+            OperationData::War(_) => "War",
+            OperationData::Salvage(_) => "Salvage",
+            OperationData::SourceKeeper(_) => "SourceKeeper",
+        }
+    }
+
     /// Dispatch describe_operation to the concrete operation type (read-only).
     pub fn describe_operation(&self, ctx: &OperationDescribeContext) -> SummaryContent {
         match self {
@@ -1,9 +1,14 @@
+use super::budget::*;
 use super::data::*;
 use super::operationsystem::*;
+use super::remoteradius::*;
+use crate::military::economy::EconomySnapshot;
 use crate::missions::data::*;
 use crate::missions::miningoutpost::*;
+use crate::room::data::*;
 use crate::room::gather::*;
 use crate::room::visibilitysystem::*;
+use crate::room_economics::{room_net_roi, RoomEconomyFacts, TILES_PER_ROOM};
 use crate::serialize::*;
 use crate::visualization::SummaryContent;
 use log::*;
@@ -13,10 +18,33 @@ use serde::{Deserialize, Serialize};
 use specs::error::NoError;
 use specs::saveload::*;
 use specs::*;
+use std::collections::{HashMap, HashSet};
+
+/// Body energy of the outpost's reserver (2 CLAIM + 2 MOVE).
+const OUTPOST_RESERVER_COST: u32 = 1300;
+/// Body energy of a source's miner and its share of the haulers.
+const OUTPOST_SOURCE_COST: u32 = 1650;
+/// Ticks an outpost budget request stands; outlasts the 50 tick scan.
+const OUTPOST_BUDGET_TTL: u32 = 60;
 
 #[derive(Clone, ConvertSaveload)]
 pub struct MiningOutpostOperation {
     owner: EntityOption<Entity>,
+    /// The outpost missions this operation launched.
+    outposts: EntityVec<Entity>,
+    /// Scan on the next run instead of waiting for the scan tick: an outpost mission was lost.
+    rescan: bool,
+    /// When the radius plan last added or dropped a remote; see [`REMOTE_RESIZE_SETTLE_TICKS`].
+    resized_at: Option<u32>,
+}
+
+/// How a remote the radius plan adds gets going.
+#[derive(Clone, Copy)]
+enum RemoteStart {
+    /// Launch an outpost mission for the gathered candidate at this index.
+    Launch(usize),
+    /// Resume an outpost mission that was winding down.
+    Resume(Entity),
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -31,7 +59,12 @@ impl MiningOutpostOperation {
     }
 
     pub fn new(owner: Option<Entity>) -> MiningOutpostOperation {
-        MiningOutpostOperation { owner: owner.into() }
+        MiningOutpostOperation {
+            owner: owner.into(),
+            outposts: EntityVec::new(),
+            rescan: false,
+            resized_at: None,
+        }
     }
 
     fn gather_candidate_room_data(gather_system_data: &GatherSystemData, room_name: RoomName) -> Option<CandidateRoomData> {
@@ -69,6 +102,60 @@ impl MiningOutpostOperation {
 
         Some(candidate_room_data)
     }
+
+    fn source_count(room_data: &RoomData) -> u32 {
+        room_data
+            .get_static_visibility_data()
+            .map(|s| s.sources().len() as u32)
+            .unwrap_or(0)
+    }
+
+    /// The net income a remote `distance` rooms from home should return once mined and reserved.
+    fn remote_value(sources: u32, distance: u32) -> f32 {
+        let haul_tiles = distance.saturating_mul(TILES_PER_ROOM);
+        // Outposts road their haul (see `RemoteMineFeatures::roads`), so price the roaded trip and its upkeep.
+        let facts = RoomEconomyFacts::reservable_remote(sources, haul_tiles).with_roads(haul_tiles);
+
+        room_net_roi(&facts).net_per_tick as f32
+    }
+
+    fn remote_load(room_data: &RoomData, distance: u32) -> RemoteLoad {
+        let sources = Self::source_count(room_data);
+
+        RemoteLoad {
+            spawn_ticks: remote_spawn_ticks(sources, distance),
+            value: Self::remote_value(sources, distance),
+        }
+    }
+
+    /// The home whose spawns a remote's creeps are charged to: the least busy one that has spawns.
+    fn charge_home(homes: &[Entity], economy: &EconomySnapshot) -> Option<Entity> {
+        homes
+            .iter()
+            .filter_map(|home| economy.rooms.get(home).map(|data| (*home, data)))
+            .filter(|(_, data)| data.spawn_count > 0)
+            .min_by(|(a, a_data), (b, b_data)| {
+                a_data
+                    .spawn_utilization
+                    .total_cmp(&b_data.spawn_utilization)
+                    .then(a.id().cmp(&b.id()))
+            })
+            .map(|(home, _)| home)
+    }
+
+    /// The outpost's launch cost and the net income it should return once mined and reserved.
+    fn budget_request(room_data: &RoomData, candidate_room: &CandidateRoom) -> BudgetRequest {
+        let sources = Self::source_count(room_data);
+        let value = Self::remote_value(sources, candidate_room.distance());
+
+        BudgetRequest::new(
+            BudgetKey::new(BudgetOperation::MiningOutpost, room_data.name),
+            candidate_room.home_room_data_entities().clone(),
+            OperationCost::from_energy(OUTPOST_RESERVER_COST + sources * OUTPOST_SOURCE_COST),
+            value,
+            OUTPOST_BUDGET_TTL,
+        )
+    }
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -83,8 +170,62 @@ impl Operation for MiningOutpostOperation {
         self.owner.take();
     }
 
-    fn describe_operation(&self, _ctx: &OperationDescribeContext) -> SummaryContent {
-        SummaryContent::Text("Remote Mine".to_string())
+    fn get_children(&self) -> Vec<Entity> {
+        self.outposts.iter().copied().collect()
+    }
+
+    fn child_complete(&mut self, child: Entity) {
+        self.outposts.retain(|e| *e != child);
+    }
+
+    /// The next run's scan relaunches the outpost if its room is still a candidate.
+    fn child_lost(&mut self, child: Entity) {
+        self.outposts.retain(|e| *e != child);
+        self.rescan = true;
+    }
+
+    fn repair_entity_refs(&mut self, is_valid: &dyn Fn(Entity) -> bool) {
+        self.outposts.retain(|e| {
+            let ok = is_valid(*e);
+            if !ok {
+                error!("INTEGRITY: dead outpost mission entity {:?} removed from MiningOutpostOperation", e);
+            }
+            ok
+        });
+    }
+
+    fn describe_operation(&self, ctx: &OperationDescribeContext) -> SummaryContent {
+        let outposts: Vec<(RoomName, bool)> = self
+            .outposts
+            .iter()
+            .filter_map(|mission_entity| {
+                let outpost = ctx.mission_data.get(*mission_entity).as_mission_type::<MiningOutpostMission>()?;
+                let room_data = ctx.room_data.get(outpost.outpost_room())?;
+
+                Some((room_data.name, outpost.is_winding_down()))
+            })
+            .collect();
+
+        if outposts.is_empty() {
+            return SummaryContent::Text("Remote Mine".to_string());
+        }
+
+        let active = outposts.iter().filter(|(_, winding_down)| !winding_down).count();
+        let children = outposts
+            .into_iter()
+            .map(|(room, winding_down)| {
+                if winding_down {
+                    SummaryContent::Text(format!("-> {} (winding down)", room))
+                } else {
+                    SummaryContent::Text(format!("-> {}", room))
+                }
+            })
+            .collect();
+
+        SummaryContent::Tree {
+            label: format!("Remote Mine ({} active)", active),
+            children,
+        }
     }
 
     fn run_operation(
@@ -92,10 +233,12 @@ impl Operation for MiningOutpostOperation {
         system_data: &mut OperationExecutionSystemData,
         runtime_data: &mut OperationExecutionRuntimeData,
     ) -> Result<OperationResult, ()> {
-        if game::time() % 50 != 25 {
+        if game::time() % 50 != 25 && !self.rescan && !system_data.operation_budget.has_grant(BudgetOperation::MiningOutpost) {
             return Ok(OperationResult::Running);
         }
 
+        self.rescan = false;
+
         let gather_system_data = GatherSystemData {
             entities: system_data.entities,
             mapping: system_data.mapping,
@@ -107,7 +250,12 @@ impl Operation for MiningOutpostOperation {
 
         let home_rooms = gather_home_rooms(&gather_system_data, 2);
 
-        let gathered_data = gather_candidate_rooms(&gather_system_data, &home_rooms, 1, Self::gather_candidate_room_data);
+        let gathered_data = gather_candidate_rooms(
+            &gather_system_data,
+            &home_rooms,
+            REMOTE_MAX_DISTANCE,
+            Self::gather_candidate_room_data,
+        );
 
         for unknown_room in gathered_data.unknown_rooms().iter() {
             system_data.visibility.request(VisibilityRequest::new(
@@ -117,63 +265,250 @@ impl Operation for MiningOutpostOperation {
             ));
         }
 
-        for candidate_room in gathered_data.candidate_rooms().iter() {
-            let room_data_storage = &mut *system_data.room_data;
-            let Some(room_data) = room_data_storage.get_mut(candidate_room.room_data_entity()) else {
+        let now = game::time();
+
+        // Remotes that could be added, by the home their creeps would be charged to.
+        let mut candidates: HashMap<Entity, Vec<(RemoteLoad, RemoteStart)>> = HashMap::new();
+
+        for (index, candidate_room) in gathered_data.candidate_rooms().iter().enumerate() {
+            let Some(room_data) = system_data.room_data.get(candidate_room.room_data_entity()) else {
                 continue;
             };
-            let dynamic_visibility_data = room_data.get_dynamic_visibility_data();
 
             //
-            // Spawn mining outpost missions for rooms that are not hostile and have recent visibility.
+            // Consider rooms that are not hostile and have recent visibility.
             //
 
-            if let Some(dynamic_visibility_data) = dynamic_visibility_data {
-                let derelict_features = system_data.features.derelict;
-                let confirmed_derelict = derelict_features.on
-                    && dynamic_visibility_data.confirmed_derelict(derelict_features.confirm_ticks, derelict_features.path_max_age);
-
-                if !dynamic_visibility_data.updated_within(1000)
-                    || !(dynamic_visibility_data.owner().neutral() || confirmed_derelict)
-                    || dynamic_visibility_data.reservation().friendly()
-                    || dynamic_visibility_data.reservation().hostile()
-                    || dynamic_visibility_data.source_keeper()
-                {
-                    continue;
+            let Some(dynamic_visibility_data) = room_data.get_dynamic_visibility_data() else {
+                continue;
+            };
+
+            let derelict_features = system_data.features.derelict;
+            let confirmed_derelict = derelict_features.on
+                && dynamic_visibility_data.confirmed_derelict(derelict_features.confirm_ticks, derelict_features.path_max_age);
+
+            if !dynamic_visibility_data.updated_within(1000)
+                || !(dynamic_visibility_data.owner().neutral() || confirmed_derelict)
+                || dynamic_visibility_data.reservation().friendly()
+                || dynamic_visibility_data.reservation().hostile()
+                || dynamic_visibility_data.source_keeper()
+            {
+                continue;
+            }
+
+            //TODO: Check path finding and accessibility to room.
+
+            //TODO: wiarchbe: Use trait instead of match.
+            let mission_data = system_data.mission_data;
+
+            let mining_outpost_mission = room_data.get_missions().iter().copied().find(|mission_entity| {
+                mission_data
+                    .get(*mission_entity)
+                    .as_mission_type::<MiningOutpostMission>()
+                    .is_some()
+            });
+
+            let load = Self::remote_load(room_data, candidate_room.distance());
+
+            if let Some(mission_entity) = mining_outpost_mission {
+                system_data
+                    .operation_budget
+                    .hold(Self::budget_request(room_data, candidate_room), mission_entity, now);
+
+                // Missions launched before the operation kept its list are adopted on sight.
+                let owned = mission_data
+                    .get(mission_entity)
+                    .is_some_and(|mission| *mission.as_mission().get_owner() == Some(runtime_data.entity));
+
+                if owned && !self.outposts.contains(&mission_entity) {
+                    self.outposts.push(mission_entity);
                 }
 
-                //TODO: Check path finding and accessibility to room.
+                // One winding down can be picked up again until its last creep is gone.
+                let resumable = mission_data
+                    .get(mission_entity)
+                    .as_mission_type::<MiningOutpostMission>()
+                    .filter(|outpost| owned && outpost.is_winding_down())
+                    .and_then(|outpost| Self::charge_home(outpost.home_rooms(), system_data.economy));
+
+                if let Some(home) = resumable {
+                    candidates
+                        .entry(home)
+                        .or_default()
+                        .push((load, RemoteStart::Resume(mission_entity)));
+                }
 
-                //TODO: wiarchbe: Use trait instead of match.
-                let mission_data = system_data.mission_data;
+                continue;
+            }
 
-                let has_mining_outpost_mission = room_data.get_missions().iter().any(|mission_entity| {
-                    mission_data
-                        .get(*mission_entity)
-                        .as_mission_type::<MiningOutpostMission>()
-                        .is_some()
-                });
+            if let Some(home) = Self::charge_home(candidate_room.home_room_data_entities(), system_data.economy) {
+                candidates.entry(home).or_default().push((load, RemoteStart::Launch(index)));
+            }
+        }
 
-                //
-                // Spawn a new mission to fill the mining outpost role if missing.
-                //
+        let settled = self
+            .resized_at
+            .map(|resized_at| now.saturating_sub(resized_at) >= REMOTE_RESIZE_SETTLE_TICKS)
+            .unwrap_or(true);
 
-                if !has_mining_outpost_mission {
-                    info!("Starting mining outpost mission for room. Room: {}", room_data.name);
+        if !settled {
+            return Ok(OperationResult::Running);
+        }
+
+        // Remotes being mined, by the home their creeps are charged to.
+        let mut active: HashMap<Entity, Vec<(RemoteLoad, Entity)>> = HashMap::new();
+
+        for mission_entity in self.outposts.iter().copied() {
+            let Some(outpost) = system_data
+                .mission_data
+                .get(mission_entity)
+                .as_mission_type::<MiningOutpostMission>()
+            else {
+                continue;
+            };
 
-                    let mission_entity = MiningOutpostMission::build(
-                        system_data.updater.create_entity(system_data.entities),
-                        Some(runtime_data.entity),
-                        candidate_room.room_data_entity(),
-                        candidate_room.home_room_data_entities(),
-                    )
-                    .build();
+            if outpost.is_winding_down() {
+                continue;
+            }
 
-                    room_data.add_mission(mission_entity);
+            let Some(home) = Self::charge_home(outpost.home_rooms(), system_data.economy) else {
+                continue;
+            };
+
+            let room_data_storage = &*system_data.room_data;
+            let (Some(home_data), Some(room_data)) = (room_data_storage.get(home), room_data_storage.get(outpost.outpost_room())) else {
+                continue;
+            };
+
+            let distance = game::map::get_room_linear_distance(home_data.name, room_data.name, false);
+
+            active
+                .entry(home)
+                .or_default()
+                .push((Self::remote_load(room_data, distance), mission_entity));
+        }
+
+        //
+        // Size each home's remotes to its spare spawn time.
+        //
+
+        let mut starts = Vec::new();
+        let mut drops = Vec::new();
+        let homes: HashSet<Entity> = active.keys().chain(candidates.keys()).copied().collect();
+
+        for home in homes {
+            let Some(economy) = system_data.economy.rooms.get(&home) else {
+                continue;
+            };
+
+            let home_active = active.remove(&home).unwrap_or_default();
+            let home_candidates = candidates.remove(&home).unwrap_or_default();
+            let active_loads: Vec<RemoteLoad> = home_active.iter().map(|(load, _)| *load).collect();
+            let candidate_loads: Vec<RemoteLoad> = home_candidates.iter().map(|(load, _)| *load).collect();
+
+            match plan_radius(economy.spawn_utilization, economy.spawn_count, &active_loads, &candidate_loads) {
+                RadiusChange::Hold => {}
+                RadiusChange::Grow(indices) => starts.extend(indices.into_iter().map(|index| home_candidates[index].1)),
+                RadiusChange::Shrink(index) => drops.push(home_active[index].1),
+            }
+        }
+
+        let mut resized = false;
+
+        for mission_entity in drops {
+            if let Some(mut outpost) = system_data
+                .mission_data
+                .get(mission_entity)
+                .as_mission_type_mut::<MiningOutpostMission>()
+            {
+                if let Some(room_data) = system_data.room_data.get(outpost.outpost_room()) {
+                    info!("Winding down mining outpost to free spawn time. Room: {}", room_data.name);
                 }
+
+                outpost.wind_down(true);
+                resized = true;
             }
         }
 
+        for start in starts {
+            let candidate_room = match start {
+                RemoteStart::Resume(mission_entity) => {
+                    if let Some(mut outpost) = system_data
+                        .mission_data
+                        .get(mission_entity)
+                        .as_mission_type_mut::<MiningOutpostMission>()
+                    {
+                        if let Some(room_data) = system_data.room_data.get(outpost.outpost_room()) {
+                            info!("Resuming mining outpost. Room: {}", room_data.name);
+                        }
+
+                        outpost.wind_down(false);
+                        resized = true;
+                    }
+
+                    continue;
+                }
+                RemoteStart::Launch(index) => &gathered_data.candidate_rooms()[index],
+            };
+
+            //
+            // Spawn a new mission to fill the mining outpost role.
+            //
+
+            let room_data_storage = &mut *system_data.room_data;
+            let Some(room_data) = room_data_storage.get_mut(candidate_room.room_data_entity()) else {
+                continue;
+            };
+
+            let budget_request = Self::budget_request(room_data, candidate_room);
+            let budget_key = budget_request.key;
+
+            if !system_data.operation_budget.reserve(budget_request, now) {
+                continue;
+            }
+
+            info!("Starting mining outpost mission for room. Room: {}", room_data.name);
+
+            let mission_entity = MiningOutpostMission::build(
+                system_data.updater.create_entity(system_data.entities),
+                Some(runtime_data.entity),
+                candidate_room.room_data_entity(),
+                candidate_room.home_room_data_entities(),
+            )
+            .build();
+
+            system_data.operation_budget.bind(&budget_key, mission_entity);
+
+            room_data.add_mission(mission_entity);
+            self.outposts.push(mission_entity);
+            resized = true;
+        }
+
+        if resized {
+            self.resized_at = Some(now);
+        }
+
         Ok(OperationResult::Running)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_lost_outpost_is_rescanned() {
+        let mut world = World::new();
+        let (kept, lost) = (world.create_entity().build(), world.create_entity().build());
+        let mut operation = MiningOutpostOperation::new(None);
+        operation.outposts.push(kept);
+        operation.outposts.push(lost);
+
+        operation.child_complete(kept);
+        assert!(!operation.rescan);
+
+        operation.child_lost(lost);
+        assert!(operation.get_children().is_empty());
+        assert!(operation.rescan);
+    }
+}
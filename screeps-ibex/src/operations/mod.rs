@@ -1,9 +1,11 @@
+pub mod budget;
 pub mod claim;
 pub mod colony;
 pub mod data;
 pub mod managersystem;
 pub mod miningoutpost;
 pub mod operationsystem;
+pub mod remoteradius;
 pub mod salvage;
 pub mod scout;
 pub mod sourcekeeper;
@@ -1,22 +1,33 @@
+use super::budget::*;
 use super::data::*;
 use crate::cleanup::*;
 use crate::cpugovernor::GovernorSnapshot;
 use crate::entitymappingsystem::EntityMappingData;
 use crate::expansion::ExpansionAvoidance;
 use crate::metrics::CpuBudget;
+use crate::military::borderwatch::BorderWatch;
 use crate::military::economy::*;
+use crate::military::escort::EscortRequest;
 use crate::military::objective_queue::CombatObjectiveQueue;
+use crate::military::posture::DefensePosture;
 use crate::military::threatmap::RoomThreatData;
+use crate::military::wartargets::WarTargets;
 use crate::missions::data::*;
+use crate::missions::reserve::ContestedReservations;
 use crate::pathing::pathfinderservice::PathfinderService;
 use crate::room::data::*;
 use crate::room::room_status_cache::RoomStatusCache;
 use crate::room::roomplansystem::*;
 use crate::room::visibilitysystem::*;
+use crate::transfer::ordersystem::OrderQueue;
 use crate::visualization::{MapVisualizationData, SummaryContent, VisualizationData};
 use log::*;
+use screeps::game;
 use specs::prelude::*;
 
+/// Ticks between checks that an operation's child missions still exist, staggered by operation.
+const CHILD_LIVENESS_INTERVAL: u32 = 20;
+
 #[derive(SystemData)]
 pub struct OperationSystemData<'a> {
     operations: WriteStorage<'a, OperationData>,
@@ -36,9 +47,17 @@ pub struct OperationSystemData<'a> {
     governor: Read<'a, GovernorSnapshot>,
     cpu_budget: Read<'a, CpuBudget>,
     features: Read<'a, crate::features::Features>,
+    feature_overrides: Read<'a, crate::features::FeatureOverrides>,
     room_status_cache: Write<'a, RoomStatusCache>,
     threat_data: ReadStorage<'a, RoomThreatData>,
+    defense_postures: ReadStorage<'a, DefensePosture>,
     expansion_avoidance: Write<'a, ExpansionAvoidance>,
+    border_watch: Write<'a, BorderWatch>,
+    order_queue: Write<'a, OrderQueue>,
+    escort_request: Write<'a, EscortRequest>,
+    operation_budget: Write<'a, OperationBudget>,
+    war_targets: Write<'a, WarTargets>,
+    contested_reservations: Read<'a, ContestedReservations>,
 }
 
 pub struct OperationExecutionSystemData<'a, 'b> {
@@ -63,10 +82,27 @@ pub struct OperationExecutionSystemData<'a, 'b> {
     pub cpu_budget: CpuBudget,
     /// The tick's feature flags (Copy — read freely).
     pub features: crate::features::Features,
+    /// Per-room feature overrides; resolve with `features.for_room`.
+    pub feature_overrides: &'b crate::features::FeatureOverrides,
     pub room_status_cache: &'b RoomStatusCache,
     pub threat_data: &'b ReadStorage<'a, RoomThreatData>,
+    /// Owned rooms' defense posture, on the room entity.
+    pub defense_postures: &'b ReadStorage<'a, DefensePosture>,
     /// Avoid-cooldown map for abandoned/failed claim targets (ADR 0017).
     pub expansion_avoidance: &'b mut ExpansionAvoidance,
+    /// Hostiles predicted to cross into our rooms; refreshed by the war operation's defense scan.
+    pub border_watch: &'b mut BorderWatch,
+    /// Market purchase requests; the war operation asks for defender boosts it is short of.
+    pub order_queue: &'b mut OrderQueue,
+    /// Escorts haul missions want for their haulers; the war operation fields them.
+    pub escort_request: &'b mut EscortRequest,
+    /// Energy and spawn time committed to expensive launches per home room; reserve before launching.
+    pub operation_budget: &'b mut OperationBudget,
+    /// The operator's war declarations; the war operation attacks them and stands their rooms down on a
+    /// cease-fire.
+    pub war_targets: &'b mut WarTargets,
+    /// Our remotes another player has reserved; the war operation harasses them.
+    pub contested_reservations: &'b ContestedReservations,
 }
 
 pub struct OperationExecutionRuntimeData {
@@ -84,6 +120,8 @@ pub struct OperationDescribeContext<'a> {
     pub room_data: &'a ReadStorage<'a, RoomData>,
     /// The tick's feature flags (Copy).
     pub features: crate::features::Features,
+    /// The combat objective queue, for the war summary's per-room attack waves.
+    pub objective_queue: &'a crate::military::objective_queue::CombatObjectiveQueue,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -92,8 +130,23 @@ pub trait Operation {
 
     fn owner_complete(&mut self, owner: Entity);
 
+    /// Missions this operation keeps a list of. Operations that find their
+    /// missions through the rooms instead return none; the integrity pass
+    /// only checks the lists that exist.
+    fn get_children(&self) -> Vec<Entity> {
+        Vec::new()
+    }
+
     fn child_complete(&mut self, _child: Entity) {}
 
+    /// A child mission that is gone without having completed: deleted by the
+    /// integrity pass, or lost to a failed deserialize. The operation decides
+    /// whether to launch it again or give its phase up; the default treats
+    /// it as complete.
+    fn child_lost(&mut self, child: Entity) {
+        self.child_complete(child);
+    }
+
     /// Remove any internal entity references that fail the validity check.
     ///
     /// Called by `repair_entity_integrity` before serialization to prevent
@@ -117,6 +170,41 @@ pub trait Operation {
     ) -> Result<OperationResult, ()>;
 }
 
+/// The children `operation` lists whose mission no longer exists.
+pub fn lost_children(operation: &dyn Operation, is_mission: impl Fn(Entity) -> bool) -> Vec<Entity> {
+    operation.get_children().into_iter().filter(|child| !is_mission(*child)).collect()
+}
+
+/// Hand the operation's lost children to `Operation::child_lost`, dropping them from the rooms that
+/// still list them so room scans don't find them either. Every operation that keeps a child list gets
+/// this from the run system.
+fn reap_lost_children(operation: &mut dyn Operation, entity: Entity, system_data: &mut OperationExecutionSystemData) {
+    let entities = system_data.entities;
+    let mission_data = system_data.mission_data;
+    let lost = lost_children(operation, |child| entities.is_alive(child) && mission_data.get(child).is_some());
+
+    for child in lost {
+        warn!(
+            "Operation {:?} lost mission {:?} without a completion, re-evaluating",
+            entity, child
+        );
+
+        let rooms: Vec<Entity> = (entities, &*system_data.room_data)
+            .join()
+            .filter(|(_, room_data)| room_data.get_missions().contains(&child))
+            .map(|(room, _)| room)
+            .collect();
+
+        for room in rooms {
+            if let Some(room_data) = system_data.room_data.get_mut(room) {
+                room_data.remove_mission(child);
+            }
+        }
+
+        operation.child_lost(child);
+    }
+}
+
 pub struct PreRunOperationSystem;
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -142,9 +230,17 @@ impl<'a> System<'a> for PreRunOperationSystem {
             governor: *data.governor,
             cpu_budget: *data.cpu_budget,
             features: *data.features,
+            feature_overrides: &data.feature_overrides,
             room_status_cache: &data.room_status_cache,
             threat_data: &data.threat_data,
+            defense_postures: &data.defense_postures,
             expansion_avoidance: &mut data.expansion_avoidance,
+            border_watch: &mut data.border_watch,
+            order_queue: &mut data.order_queue,
+            escort_request: &mut data.escort_request,
+            operation_budget: &mut data.operation_budget,
+            war_targets: &mut data.war_targets,
+            contested_reservations: &data.contested_reservations,
         };
 
         for (entity, operation_data) in (&data.entities, &mut data.operations).join() {
@@ -182,16 +278,30 @@ impl<'a> System<'a> for RunOperationSystem {
             governor: *data.governor,
             cpu_budget: *data.cpu_budget,
             features: *data.features,
+            feature_overrides: &data.feature_overrides,
             room_status_cache: &data.room_status_cache,
             threat_data: &data.threat_data,
+            defense_postures: &data.defense_postures,
             expansion_avoidance: &mut data.expansion_avoidance,
+            border_watch: &mut data.border_watch,
+            order_queue: &mut data.order_queue,
+            escort_request: &mut data.escort_request,
+            operation_budget: &mut data.operation_budget,
+            war_targets: &mut data.war_targets,
+            contested_reservations: &data.contested_reservations,
         };
 
+        let now = game::time();
+
         for (entity, operation_data) in (&data.entities, &mut data.operations).join() {
             let mut runtime_data = OperationExecutionRuntimeData { entity };
 
             let operation = operation_data.as_operation();
 
+            if now.wrapping_add(entity.id()).is_multiple_of(CHILD_LIVENESS_INTERVAL) {
+                reap_lost_children(operation, entity, &mut system_data);
+            }
+
             let cleanup_operation = match operation.run_operation(&mut system_data, &mut runtime_data) {
                 Ok(OperationResult::Running) => false,
                 Ok(OperationResult::Success) => {
@@ -213,5 +323,78 @@ impl<'a> System<'a> for RunOperationSystem {
                 });
             }
         }
+
+        // Grant this tick's launch requests; operations pick the grants up on their next run.
+        let capacity = operation_capacity(&data.economy);
+        data.operation_budget.allocate(&capacity, |e| data.entities.is_alive(e), now);
+        data.operation_budget
+            .log_summary(|e| data.room_data.get(e).map(|room_data| room_data.name), now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An operation that keeps a child list and the default `child_lost`.
+    struct Listed {
+        owner: Option<Entity>,
+        children: Vec<Entity>,
+        completed: Vec<Entity>,
+    }
+
+    impl Operation for Listed {
+        fn get_owner(&self) -> &Option<Entity> {
+            &self.owner
+        }
+
+        fn owner_complete(&mut self, _owner: Entity) {}
+
+        fn get_children(&self) -> Vec<Entity> {
+            self.children.clone()
+        }
+
+        fn child_complete(&mut self, child: Entity) {
+            self.children.retain(|e| *e != child);
+            self.completed.push(child);
+        }
+
+        fn run_operation(
+            &mut self,
+            _system_data: &mut OperationExecutionSystemData,
+            _runtime_data: &mut OperationExecutionRuntimeData,
+        ) -> Result<OperationResult, ()> {
+            Ok(OperationResult::Running)
+        }
+    }
+
+    #[test]
+    fn lost_children_are_the_listed_missions_gone() {
+        let mut world = World::new();
+        let (kept, lost) = (world.create_entity().build(), world.create_entity().build());
+        let operation = Listed {
+            owner: None,
+            children: vec![kept, lost],
+            completed: Vec::new(),
+        };
+
+        assert_eq!(lost_children(&operation, |e| e == kept), vec![lost]);
+        assert!(lost_children(&operation, |_| true).is_empty());
+    }
+
+    #[test]
+    fn a_lost_child_completes_by_default() {
+        let mut world = World::new();
+        let (kept, lost) = (world.create_entity().build(), world.create_entity().build());
+        let mut operation = Listed {
+            owner: None,
+            children: vec![kept, lost],
+            completed: Vec::new(),
+        };
+
+        operation.child_lost(lost);
+
+        assert_eq!(operation.children, vec![kept]);
+        assert_eq!(operation.completed, vec![lost]);
     }
 }
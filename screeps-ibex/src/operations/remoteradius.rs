@@ -0,0 +1,153 @@
+//! Remote mining radius.
+//!
+//! How many remotes a colony can mine is set by its spawns, not by a fixed
+//! distance: at low RCL they can't keep more than a room or two of miners,
+//! haulers and reservers alive, while a mature colony's spawns idle on the
+//! same set. Each remote is costed in the spawn-ticks per creep lifetime its
+//! creeps take to replace ([`remote_spawn_ticks`]), and each home's measured
+//! spawn utilization says how much of that it has to spare. The mining outpost
+//! operation adds remotes, best yield per spawn-tick first, while a home is
+//! under [`REMOTE_UTILIZATION_LOW`] and the projection stays under
+//! [`REMOTE_UTILIZATION_HIGH`], and winds the worst one down once the home
+//! runs above the band.
+
+use crate::room_economics::TILES_PER_ROOM;
+use screeps::constants::{CARRY_CAPACITY, CREEP_LIFE_TIME, CREEP_SPAWN_TIME, ENERGY_REGEN_TIME, SOURCE_ENERGY_CAPACITY};
+
+/// Spawn utilization under which a home takes on more remotes.
+pub const REMOTE_UTILIZATION_LOW: f32 = 0.6;
+/// Spawn utilization above which a home drops a remote; new remotes are only added while the projection stays
+/// under it.
+pub const REMOTE_UTILIZATION_HIGH: f32 = crate::military::economy::SATURATED_SPAWN_UTILIZATION;
+/// Ticks after the active set changes before it changes again: utilization is averaged over a creep
+/// lifetime, so it takes that long to show the change.
+pub const REMOTE_RESIZE_SETTLE_TICKS: u32 = CREEP_LIFE_TIME;
+/// Furthest a remote is searched for, in rooms from its home.
+pub const REMOTE_MAX_DISTANCE: u32 = 2;
+/// Parts on a source's miner (5 WORK, 1 CARRY, 3 MOVE).
+const MINER_PARTS: u32 = 9;
+/// A reserver lives on the claim lifetime, not the creep one.
+const CREEP_CLAIM_LIFE_TIME: u32 = 600;
+
+/// Spawn-ticks per creep lifetime to keep a reserved remote of `sources` sources `distance` rooms from its home
+/// mined: a miner per source, roaded haulers (two CARRY per MOVE) for the round trip and a reserver
+/// renewed each claim lifetime.
+pub fn remote_spawn_ticks(sources: u32, distance: u32) -> u32 {
+    let source_rate = SOURCE_ENERGY_CAPACITY / ENERGY_REGEN_TIME;
+    let round_trip = 2 * distance.max(1) * TILES_PER_ROOM;
+    let carry_parts = (source_rate * round_trip).div_ceil(CARRY_CAPACITY);
+    let hauler_parts = carry_parts + carry_parts.div_ceil(2);
+
+    let reserver_parts = 2 * crate::missions::reserve::reserver_claim_parts(None, false);
+    let reserver_ticks = reserver_parts * CREEP_SPAWN_TIME * CREEP_LIFE_TIME / CREEP_CLAIM_LIFE_TIME;
+
+    sources * (MINER_PARTS + hauler_parts) * CREEP_SPAWN_TIME + reserver_ticks
+}
+
+/// What a remote costs its home's spawns and what it returns.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RemoteLoad {
+    /// See [`remote_spawn_ticks`].
+    pub spawn_ticks: u32,
+    /// Net energy per tick once mined.
+    pub value: f32,
+}
+
+impl RemoteLoad {
+    fn yield_per_spawn_tick(&self) -> f32 {
+        self.value / self.spawn_ticks.max(1) as f32
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RadiusChange {
+    Hold,
+    /// Add these candidates, by index.
+    Grow(Vec<usize>),
+    /// Wind down this active remote, by index.
+    Shrink(usize),
+}
+
+/// How one home's remotes should change given its spawn `utilization` over `spawn_count` spawns, the remotes
+/// it mines (`active`) and the ones it could (`candidates`).
+pub fn plan_radius(utilization: f32, spawn_count: u32, active: &[RemoteLoad], candidates: &[RemoteLoad]) -> RadiusChange {
+    if spawn_count == 0 {
+        return RadiusChange::Hold;
+    }
+
+    if utilization > REMOTE_UTILIZATION_HIGH {
+        return active
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.yield_per_spawn_tick().total_cmp(&b.yield_per_spawn_tick()))
+            .map(|(index, _)| RadiusChange::Shrink(index))
+            .unwrap_or(RadiusChange::Hold);
+    }
+
+    if utilization >= REMOTE_UTILIZATION_LOW {
+        return RadiusChange::Hold;
+    }
+
+    let capacity = (spawn_count * CREEP_LIFE_TIME) as f32;
+    let mut order: Vec<usize> = (0..candidates.len()).filter(|index| candidates[*index].value > 0.0).collect();
+    order.sort_by(|a, b| {
+        candidates[*b]
+            .yield_per_spawn_tick()
+            .total_cmp(&candidates[*a].yield_per_spawn_tick())
+    });
+
+    let mut projected = utilization;
+    let mut grow = Vec::new();
+
+    for index in order {
+        let load = candidates[index].spawn_ticks as f32 / capacity;
+
+        if projected + load <= REMOTE_UTILIZATION_HIGH {
+            projected += load;
+            grow.push(index);
+        }
+    }
+
+    if grow.is_empty() {
+        RadiusChange::Hold
+    } else {
+        RadiusChange::Grow(grow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(spawn_ticks: u32, value: f32) -> RemoteLoad {
+        RemoteLoad { spawn_ticks, value }
+    }
+
+    #[test]
+    fn further_and_larger_remotes_cost_more_spawn_time() {
+        let near = remote_spawn_ticks(1, 1);
+
+        assert!(remote_spawn_ticks(2, 1) > near);
+        assert!(remote_spawn_ticks(1, 2) > near);
+        assert_eq!(remote_spawn_ticks(1, 0), near);
+    }
+
+    #[test]
+    fn idle_spawns_take_the_best_remotes_that_fit() {
+        let candidates = [load(600, 6.0), load(300, 6.0), load(300, 0.0), load(450, 9.0)];
+
+        // One spawn at 20%: 1500 spawn-ticks a lifetime, 975 of them free below the band's top.
+        assert_eq!(plan_radius(0.2, 1, &[], &candidates), RadiusChange::Grow(vec![1, 3]));
+        assert_eq!(plan_radius(0.7, 1, &[], &candidates), RadiusChange::Hold);
+        assert_eq!(plan_radius(0.2, 0, &[], &candidates), RadiusChange::Hold);
+    }
+
+    #[test]
+    fn busy_spawns_drop_the_worst_remote() {
+        let active = [load(300, 6.0), load(600, 3.0), load(300, 4.0)];
+
+        assert_eq!(plan_radius(0.95, 1, &active, &[]), RadiusChange::Shrink(1));
+        assert_eq!(plan_radius(0.8, 1, &active, &[]), RadiusChange::Hold);
+        assert_eq!(plan_radius(0.95, 1, &[], &[]), RadiusChange::Hold);
+    }
+}
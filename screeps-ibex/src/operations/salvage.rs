@@ -137,13 +137,14 @@ pub(crate) fn salvage_worthwhile(
         return true;
     }
 
-    // Value: stores at face value (minerals weighted as energy — they are at
-    // least terminal-sellable), dismantled hits refund DISMANTLE_COST each.
+    // Value: stores by the raid's loot value table (energy at face value,
+    // minerals, power and boosts above it), dismantled hits refund
+    // DISMANTLE_COST each.
     let mut value = 0.0f32;
     let mut cost = 0.0f32;
 
     if lootable {
-        value += loot_total as f32;
+        value += work.loot_value as f32;
 
         let round_trip_ticks = 2 * travel_ticks + 50;
         let trips = loot_total.div_ceil(ASSUMED_RAIDER_CAPACITY);
@@ -407,6 +408,10 @@ impl Operation for SalvageOperation {
         self.owner.take();
     }
 
+    fn get_children(&self) -> Vec<Entity> {
+        self.salvage_missions.iter().copied().collect()
+    }
+
     fn child_complete(&mut self, child: Entity) {
         self.salvage_missions.retain(|e| *e != child);
     }
@@ -798,6 +803,7 @@ mod tests {
         SalvageWork {
             loot_energy,
             loot_other,
+            loot_value: loot_energy + loot_other,
             dismantle_hits,
         }
     }
@@ -222,6 +222,10 @@ impl Operation for ScoutOperation {
         self.owner.take();
     }
 
+    fn get_children(&self) -> Vec<Entity> {
+        self.scout_missions.iter().copied().collect()
+    }
+
     fn child_complete(&mut self, child: Entity) {
         self.scout_missions.retain(|e| *e != child);
     }
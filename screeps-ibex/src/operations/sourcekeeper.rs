@@ -133,6 +133,7 @@ pub fn score_sk_farm(inp: &SkRoiInputs) -> SkRoiScore {
 
 // ─── Operation (P2.K2b) ─────────────────────────────────────────────────────
 
+use super::budget::*;
 use super::data::*;
 use super::operationsystem::*;
 use crate::missions::data::*;
@@ -160,6 +161,10 @@ const SK_DUO_MAX_BODY_COST: u32 = 3100;
 const SK_HOME_MIN_RCL: u32 = 6;
 /// Scan cadence offset (spread CPU vs other throttled operations).
 const SK_SCAN_OFFSET: u32 = 35;
+/// Body energy of a keeper source's miner and its share of the haulers, for the budget request.
+const SK_SOURCE_MINING_COST: u32 = 2000;
+/// Ticks an SK farm budget request stands; outlasts the 50 tick scan.
+const SK_BUDGET_TTL: u32 = 60;
 /// Tiles per room-hop, for the haul-distance estimate.
 const TILES_PER_ROOM: u32 = 50;
 
@@ -235,7 +240,7 @@ impl Operation for SourceKeeperOperation {
             }
             return Ok(OperationResult::Running);
         }
-        if game::time() % 50 != SK_SCAN_OFFSET {
+        if game::time() % 50 != SK_SCAN_OFFSET && !system_data.operation_budget.has_grant(BudgetOperation::SourceKeeper) {
             return Ok(OperationResult::Running);
         }
 
@@ -316,10 +321,11 @@ impl Operation for SourceKeeperOperation {
                     .map(|d| d.owner().hostile() || d.reservation().hostile())
                     .unwrap_or(false);
                 let stronghold = sk_room_has_stronghold(room_data);
-                let already_committed = room_data
+                let farm_mission = room_data
                     .get_missions()
                     .iter()
-                    .any(|m| system_data.mission_data.get(*m).as_mission_type::<SourceKeeperFarmMission>().is_some());
+                    .copied()
+                    .find(|m| system_data.mission_data.get(*m).as_mission_type::<SourceKeeperFarmMission>().is_some());
                 let home_capacity = candidate
                     .home_room_data_entities()
                     .iter()
@@ -329,11 +335,12 @@ impl Operation for SourceKeeperOperation {
                     .max()
                     .unwrap_or(0);
 
-                Some((room_data.name, live_sources, contested, stronghold, home_capacity, already_committed))
+                Some((room_data.name, live_sources, contested, stronghold, home_capacity, farm_mission))
             })();
-            let Some((room_name, live_sources, contested, stronghold, home_capacity, already_committed)) = intel else {
+            let Some((room_name, live_sources, contested, stronghold, home_capacity, farm_mission)) = intel else {
                 continue;
             };
+            let already_committed = farm_mission.is_some();
 
             let inputs = SkRoiInputs {
                 live_sources,
@@ -367,10 +374,33 @@ impl Operation for SourceKeeperOperation {
                 );
             }
 
+            let budget_request = BudgetRequest::new(
+                BudgetKey::new(BudgetOperation::SourceKeeper, room_name),
+                candidate.home_room_data_entities().clone(),
+                OperationCost::from_energy(SK_DUO_BODY_COST + live_sources * SK_SOURCE_MINING_COST),
+                score.net_per_tick as f32,
+                SK_BUDGET_TTL,
+            );
+
+            if let Some(mission_entity) = farm_mission {
+                system_data.operation_budget.hold(budget_request, mission_entity, game::time());
+
+                continue;
+            }
+
             // Commit → create the persistent farm mission (idempotent: only if the
-            // room has none yet). Withhold/Veto retirement of an existing farm is
-            // the next increment (K2c-2).
-            if score.decision == SkRoiDecision::Commit && !already_committed {
+            // room has none yet) once the operation budget grants it. Withhold/Veto
+            // retirement of an existing farm is the next increment (K2c-2).
+            if score.decision == SkRoiDecision::Commit {
+                let budget_key = budget_request.key;
+
+                if !system_data.operation_budget.reserve(budget_request, game::time()) {
+                    if sk_debug {
+                        info!("[SK] candidate {}: waiting on the operation budget", room_name);
+                    }
+                    continue;
+                }
+
                 info!("Starting source keeper farm for room {}", room_name);
 
                 let mission_entity = SourceKeeperFarmMission::build(
@@ -381,6 +411,8 @@ impl Operation for SourceKeeperOperation {
                 )
                 .build();
 
+                system_data.operation_budget.bind(&budget_key, mission_entity);
+
                 if let Some(room_data) = system_data.room_data.get_mut(candidate_entity) {
                     room_data.add_mission(mission_entity);
                 }
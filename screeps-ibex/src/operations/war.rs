@@ -1,3 +1,4 @@
+use super::budget::*;
 use super::data::*;
 use super::operationsystem::*;
 use screeps_combat_decision::composition::{CompositionParams, SquadComposition, SquadRole};
@@ -7,15 +8,25 @@ use screeps_combat_decision::doctrine::{
 use screeps_combat_decision::force_sizing::{
     should_defer_offense_commit, tower_intel_from, win_probability, AssaultMode, DefenseProfile, TowerIntel, TowerThreat, HOLD_MARGIN,
 };
+use crate::military::borderwatch::{predict_inbound, staging_tile, InboundThreat, WatchedHostile};
+use crate::military::composition::{escalate_force, force_plan_cost, WaveEscalation, WavePlan};
+use crate::military::damage::{boosted_defender_composition, defender_boost_escalation, BoostEscalation, EntrySegment};
+use crate::military::economy::EconomySnapshot;
+use crate::military::escort::{escort_composition, Caravan};
+use crate::military::harass::harass_composition;
+use crate::military::interceptor::interceptor_composition;
 use crate::military::objective_queue::{
-    ForceRequirement, ObjectiveKind, ObjectiveOwner, ObjectiveRequest, OBJECTIVE_PRIORITY_CRITICAL, OBJECTIVE_PRIORITY_HIGH,
-    OBJECTIVE_PRIORITY_LOW, OBJECTIVE_PRIORITY_MEDIUM,
+    DeployCondition, ForceRequirement, ObjectiveId, ObjectiveKind, ObjectiveOwner, ObjectiveRequest, OBJECTIVE_PRIORITY_CRITICAL,
+    OBJECTIVE_PRIORITY_HIGH, OBJECTIVE_PRIORITY_LOW, OBJECTIVE_PRIORITY_MEDIUM,
 };
 use crate::military::threatmap::*;
+use crate::military::wartargets::*;
 use crate::missions::data::*;
+use crate::missions::downgrade::*;
 use crate::missions::nuke_defense::*;
 use crate::missions::safe_mode::*;
 use crate::missions::wall_repair::*;
+use crate::room::hostilesummary::HostileOwner;
 use crate::room::visibilitysystem::*;
 use crate::serialize::*;
 use crate::visualization::SummaryContent;
@@ -39,6 +50,12 @@ const DEFEND_OBJECTIVE_TTL: u32 = 60;
 /// (no core ⇒ no upsert) then lapses and the manager retires the siege squad.
 const OFFENSE_OBJECTIVE_TTL: u32 = 100;
 
+/// Farthest (in room hops from the nearest home) a hostile player's remote is harassed.
+const HARASS_MAX_DISTANCE: u32 = 4;
+
+/// Farthest (in linear rooms from a home) a sieged room's controller is attacked by declaimers.
+const DOWNGRADE_MAX_DISTANCE: u32 = 5;
+
 /// The EV optimizer's `target_value` for always-field DEFENSE / operator-intent engagements (ADR 0031
 /// D16): high so the always-field doctrine always commits the EV-best force (you can't skip defending an
 /// owned room / honoring an operator flag). The optimizer's always-field path also floors at the default
@@ -55,6 +72,13 @@ const DEFENSE_ONSITE_WINDOW: u32 = 1400;
 /// caps the spawn cost against the affordable military surplus.
 const OFFENSE_TARGET_VALUE_SCALE: f32 = 10_000.0;
 
+/// Score of a declared war target: operator intent, below an attack flag (100) but above the automatic
+/// resource-denial raid (≤ 40), so a declared room wins its room's dedup.
+const DECLARED_WAR_SCORE: f32 = 60.0;
+
+/// Room hops from the nearest home beyond which a declared player's room is not pursued.
+const DECLARED_WAR_MAX_DISTANCE: u32 = 10;
+
 /// ADR 0037 T1: the representative hostile-TOWER threat (Σ per-tower attack DPS at an optimal-ish range)
 /// for a SCOUTED neighbour room, from the SAME signal offense uses — `RoomThreatData.hostile_tower_positions`
 /// paired with `tower_energy`. Only ENERGIZED towers (energy ≥ one shot, `TOWER_ENERGY_COST`) contribute; a
@@ -77,6 +101,46 @@ fn neighbour_tower_dps(threat_data: &RoomThreatData) -> f32 {
         .sum()
 }
 
+/// The defense a drain squad faces: the same towers, ranged to the nearest tile of the entry segment it
+/// holds instead of the assault tile. `tower_positions` is index-aligned with `defense.towers`.
+fn drain_defense(defense: &DefenseProfile, tower_positions: &[Position], room: RoomName, segment: &EntrySegment) -> DefenseProfile {
+    let towers = defense
+        .towers
+        .iter()
+        .zip(tower_positions)
+        .map(|(tower, pos)| TowerThreat {
+            range_to_assault: segment.range_from(room, *pos),
+            energy: tower.energy,
+        })
+        .collect();
+
+    DefenseProfile { towers, ..defense.clone() }
+}
+
+/// The boosts the homes could put into an attack wave: each compound at the most any one home holds.
+fn boost_stock(economy: &EconomySnapshot, homes: &[(Entity, RoomName)]) -> std::collections::HashMap<ResourceType, u32> {
+    let mut stock = std::collections::HashMap::new();
+
+    for (compound, amount) in homes.iter().filter_map(|(entity, _)| economy.room(entity)).flat_map(|r| &r.available_boosts) {
+        let held = stock.entry(*compound).or_default();
+        *held = (*held).max(*amount);
+    }
+
+    stock
+}
+
+/// An offense candidate ready to request: kind, priority, force, assault mode, spawn cost, per-member
+/// boosts, and the escalated plan for the summary when the room has lost waves.
+type OffenseObjective = (
+    ObjectiveKind,
+    f32,
+    ForceRequirement,
+    AssaultMode,
+    u32,
+    Vec<(ResourceType, u32)>,
+    Option<(WavePlan, Vec<WaveEscalation>)>,
+);
+
 // ---------------------------------------------------------------------------
 // Target scoring
 // ---------------------------------------------------------------------------
@@ -102,6 +166,8 @@ pub enum TargetSource {
     PowerBank { power: u32, ticks_to_decay: u32 },
     /// Proactive: enemy activity detected near owned rooms.
     ProactiveDefense,
+    /// A player or room the operator declared war on (`military::wartargets`).
+    WarDeclaration,
 }
 
 /// A scored attack candidate.
@@ -185,6 +251,10 @@ pub struct WarOperation {
 
     /// Maximum concurrent attack operations (scales with economy).
     max_concurrent_attacks: u32,
+
+    /// The room missions the scans launched: nuke defense, safe mode, wall repair and controller
+    /// downgrade.
+    missions: EntityVec<Entity>,
 }
 
 // Cadence constants (ticks) — P1.B6 / IBEX-021: every tier ran at 1,
@@ -229,6 +299,7 @@ impl WarOperation {
             last_recompute_tick: None,
             defend_flag_rooms: Vec::new(),
             max_concurrent_attacks: 1,
+            missions: EntityVec::new(),
         }
     }
 
@@ -236,8 +307,10 @@ impl WarOperation {
 
     fn run_defense_scan(&mut self, system_data: &mut OperationExecutionSystemData, runtime_data: &mut OperationExecutionRuntimeData) {
         let features = system_data.features;
+        let feature_overrides = system_data.feature_overrides;
 
-        if !features.military.defense {
+        // Off globally, the scan still runs for rooms that opt back in.
+        if !features.military.defense && !feature_overrides.any(|o| o.defense == Some(true)) {
             return;
         }
 
@@ -266,6 +339,50 @@ impl WarOperation {
             .max()
             .unwrap_or(0);
 
+        // ── Border watch ───────────────────────────────────────────────────
+        // Armed hostiles in a visible neighbour, close to the exit into one of our homes, are predicted to
+        // cross it. Refreshed wholesale every scan; read below (defender sizing + staging) and by the tower
+        // mission (top-off priority).
+
+        let home_names: Vec<RoomName> = home_rooms
+            .iter()
+            .filter_map(|&e| system_data.room_data.get(e))
+            .map(|rd| rd.name)
+            .filter(|&name| features.for_room(feature_overrides, name).military.defense)
+            .collect();
+        let now = game::time();
+        let mut predictions: Vec<(RoomName, InboundThreat)> = Vec::new();
+        for room_data in (&*system_data.room_data).join() {
+            let Some(dynamic_vis) = room_data.get_dynamic_visibility_data() else {
+                continue;
+            };
+            if !dynamic_vis.visible() || dynamic_vis.owner().mine() {
+                continue;
+            }
+            let Some(creeps) = room_data.get_creeps() else {
+                continue;
+            };
+            let hostiles: Vec<WatchedHostile> = creeps
+                .hostile()
+                .iter()
+                .filter(|c| !crate::military::is_source_keeper_owner(&c.owner().username()))
+                .map(|c| WatchedHostile {
+                    pos: c.pos(),
+                    parts: c.body().iter().filter(|p| p.hits() > 0).map(|p| p.part()).collect(),
+                    boosted: c.body().iter().any(|p| p.boost().is_some()),
+                })
+                .collect();
+            if hostiles.is_empty() {
+                continue;
+            }
+            for &home in &home_names {
+                if let Some(threat) = predict_inbound(room_data.name, home, &hostiles, now) {
+                    predictions.push((home, threat));
+                }
+            }
+        }
+        system_data.border_watch.refresh(predictions);
+
         // ── Collect rooms needing defense ──────────────────────────────────
 
         struct DefenseNeed {
@@ -274,6 +391,10 @@ impl WarOperation {
             estimated_heal: f32,
             hostile_count: usize,
             any_boosted: bool,
+            /// Each hostile's live parts with their boosted flag, for the boost escalation.
+            hostile_parts: Vec<Vec<(Part, bool)>>,
+            /// Our energized towers' damage on the worst-placed hostile.
+            tower_dps: f32,
         }
 
         struct RoomDefenseState {
@@ -296,6 +417,10 @@ impl WarOperation {
                     return None;
                 }
 
+                if !features.for_room(feature_overrides, room_data.name).military.defense {
+                    return None;
+                }
+
                 let has_hostiles = dynamic_vis.hostile_creeps();
                 // `hostile_creeps()` only flags Attack/RangedAttack/Work, so an
                 // enemy CLAIM creep neutralising the controller (or a lone
@@ -389,31 +514,47 @@ impl WarOperation {
                 let mut any_boosted = false;
                 let mut work_parts: usize = 0;
 
-                for hostile in &hostiles {
-                    for part_info in hostile.body().iter() {
-                        if part_info.hits() == 0 {
-                            continue;
-                        }
-                        if part_info.boost().is_some() {
-                            any_boosted = true;
-                        }
-                        match part_info.part() {
-                            Part::Attack => estimated_dps += 30.0,
-                            Part::RangedAttack => estimated_dps += 10.0,
-                            Part::Heal => estimated_heal += 12.0,
-                            Part::Work => work_parts += 1,
-                            _ => {}
-                        }
-                    }
+                for (_, body) in creeps.hostile_bodies().filter(|(_, body)| body.owner != HostileOwner::SourceKeeper) {
+                    estimated_dps += body.dps();
+                    estimated_heal += body.heal_per_tick;
+                    any_boosted |= body.boosted;
+                    work_parts += body.work as usize;
                 }
                 estimated_dps += screeps_combat_decision::war_decision::dismantle_danger(work_parts, has_our_structures);
 
+                let hostile_parts = hostiles
+                    .iter()
+                    .map(|c| {
+                        c.body()
+                            .iter()
+                            .filter(|p| p.hits() > 0)
+                            .map(|p| (p.part(), p.boost().is_some()))
+                            .collect()
+                    })
+                    .collect();
+                let tower_positions: Vec<Position> = room_data
+                    .get_structures()
+                    .map(|s| {
+                        s.towers()
+                            .iter()
+                            .filter(|t| t.my() && t.store().get_used_capacity(Some(ResourceType::Energy)) >= TOWER_ENERGY_COST)
+                            .map(|t| t.pos())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                let tower_dps = hostiles
+                    .iter()
+                    .map(|c| crate::military::damage::total_tower_damage(&tower_positions, c.pos()))
+                    .fold(f32::INFINITY, f32::min);
+
                 Some(DefenseNeed {
                     room_entity: entity,
                     estimated_dps,
                     estimated_heal,
                     hostile_count: hostiles.len(),
                     any_boosted,
+                    hostile_parts,
+                    tower_dps,
                 })
             })
             .collect();
@@ -564,12 +705,14 @@ impl WarOperation {
             // UNIFIED defender selection (ADR 0026 §9.10 L3): the `GarrisonDefense` doctrine selects the
             // shape from the threat (the former `DefenseEscalation::from_threat` thresholds, now on the
             // registry). An owned-room attacker may be a player → `Coordinated` (the Q1 safe default).
+            // A group still crossing in from a neighbour joins the fight here, so size for it too.
+            let inbound = system_data.border_watch.inbound(room_name);
             let threat = EnemyForce {
-                dps: need.estimated_dps,
-                heal: need.estimated_heal,
+                dps: need.estimated_dps + inbound.map_or(0.0, |t| t.dps),
+                heal: need.estimated_heal + inbound.map_or(0.0, |t| t.heal),
                 hits: 0,
-                count: need.hostile_count as u32,
-                boosted: need.any_boosted,
+                count: need.hostile_count as u32 + inbound.map_or(0, |t| t.count),
+                boosted: need.any_boosted || inbound.is_some_and(|t| t.boosted),
             };
             let ctx = EngagementContext {
                 objective: DoctrineObjective::ClearCreeps,
@@ -596,6 +739,27 @@ impl WarOperation {
             let Some(composition) = decide_doctrine(&ctx, &defense_docs).and_then(|d| screeps_combat_decision::doctrine::plan_engagement(d, &ctx, None).composition) else {
                 continue;
             };
+            // When even the strongest unboosted defender plus our towers can't out-damage the attackers' heal,
+            // field boosted defenders instead, boosted from this room's labs; short of compounds, buy them and
+            // hold the line unboosted meanwhile.
+            let (composition, boosts) = if features.for_room(feature_overrides, room_name).military.boost_military {
+                let stock = system_data.economy.room(&need.room_entity).map(|r| r.available_boosts.clone()).unwrap_or_default();
+                match defender_boost_escalation(&need.hostile_parts, need.tower_dps, &stock) {
+                    BoostEscalation::Boosted { defenders, boosts } => {
+                        info!("[War] Boosted defense for {} ({} defenders, heal={:.0})", room_name, defenders, need.estimated_heal);
+                        (boosted_defender_composition(defenders), boosts)
+                    }
+                    BoostEscalation::ShortOfBoosts { missing } => {
+                        for (compound, amount) in missing {
+                            system_data.order_queue.request_passive_purchase(room_name, compound, amount);
+                        }
+                        (composition, Vec::new())
+                    }
+                    BoostEscalation::Unboosted | BoostEscalation::Outmatched => (composition, Vec::new()),
+                }
+            } else {
+                (composition, Vec::new())
+            };
             // ADR 0027 v1: emit `Secure{threat_room}` at the kernel-decided priority (the asset-priority
             // boost already folded in). The threat's room IS `room_name` here (the owned room where hostiles
             // were observed); when the threat roams a neighbour the next scan re-emits at the neighbour's
@@ -615,7 +779,7 @@ impl WarOperation {
             // `Secure` (an intercept is mechanically "go to room X + clear its hostiles" = Secure). The
             // asset-priority boost already ranks an in-base CRITICAL above an adjacent HIGH above a leashed
             // MEDIUM, so a far owned room under attack still out-prioritises a roamer.
-            system_data.combat_objective_queue.request(
+            let obj_id = system_data.combat_objective_queue.request(
                 ObjectiveRequest::new(
                     ObjectiveKind::Secure { room: room_name },
                     priority,
@@ -625,6 +789,7 @@ impl WarOperation {
                 .ttl(DEFEND_OBJECTIVE_TTL),
                 game::time(),
             );
+            system_data.combat_objective_queue.set_boost_plan(obj_id, boosts);
         }
 
         // ── ADR 0027 v1 LIVE SEAM: emit Secure for NEIGHBOUR threats ───────────────────────────────────
@@ -639,6 +804,11 @@ impl WarOperation {
             let Some(emission) = defense_emissions.iter().find(|e| e.room == nbr.room) else {
                 continue; // beyond the leash (shouldn't happen — we pre-filtered) → not chased
             };
+            // A group heading into one of our rooms is met on our side of the exit (below), under our towers,
+            // rather than chased into its own room.
+            if system_data.border_watch.iter().any(|(_, t)| t.from_room == nbr.room) {
+                continue;
+            }
             // The neighbour has no spawn; size the defender to the strongest home's capacity so the oracle
             // sizes a real blob (0 → bare template). `danger` is the summed DPS; heal/count are unknown from
             // the coarse neighbour gather, so use the dps with a single-creep count (the GarrisonDefense
@@ -688,6 +858,125 @@ impl WarOperation {
             );
         }
 
+        // ── Pre-position defenders for inbound groups ──────────────────────
+        // A home with an inbound group but no hostiles inside yet gets a `Defend` objective sized against the
+        // incoming parts, fielded now so the defenders are out before the group arrives, and staged a few
+        // tiles inside the predicted entry exit. A home already under attack is covered by its `Secure` above
+        // (whose sizing already folds the inbound group in).
+        system_data.combat_objective_queue.clear_stage_positions();
+        let under_attack: Vec<RoomName> = rooms_needing_defense
+            .iter()
+            .filter_map(|need| system_data.room_data.get(need.room_entity).map(|rd| rd.name))
+            .collect();
+        let inbound: Vec<(RoomName, InboundThreat)> = system_data
+            .border_watch
+            .iter()
+            .filter(|(room, _)| !under_attack.contains(room))
+            .map(|(room, threat)| (*room, threat.clone()))
+            .collect();
+        for (room_name, threat) in inbound {
+            let member_energy = game::rooms().get(room_name).map(|r| r.energy_capacity_available()).unwrap_or(max_home_energy);
+            let ctx = EngagementContext {
+                objective: DoctrineObjective::ClearCreeps,
+                coordination: EnemyCoordination::Coordinated,
+                defense: DefenseProfile::default(),
+                enemy_force: Some(EnemyForce {
+                    dps: threat.dps,
+                    heal: threat.heal,
+                    hits: 0,
+                    count: threat.count,
+                    boosted: threat.boosted,
+                }),
+                importance: 0.0,
+                member_energy,
+                target_value: DEFENSE_TARGET_VALUE,
+                onsite_window: DEFENSE_ONSITE_WINDOW,
+                params: CompositionParams { member_energy, ..Default::default() },
+                // A predicted, not yet present, threat — keep the always-field floor.
+                defense_intel_reliable: false,
+            };
+            let Some(composition) = decide_doctrine(&ctx, &defense_docs).and_then(|d| screeps_combat_decision::doctrine::plan_engagement(d, &ctx, None).composition) else {
+                continue;
+            };
+            let stage = staging_tile(threat.entry, |tile| {
+                game::map::get_room_terrain(tile.room_name()).is_some_and(|t| t.get(tile.x().u8(), tile.y().u8()) == Terrain::Wall)
+            });
+            info!(
+                "[War] Defend objective for INBOUND group {} -> {} eta={} entry={} (dps={:.0}, heal={:.0}, count={})",
+                threat.from_room, room_name, threat.arrival_tick.saturating_sub(now), threat.entry, threat.dps, threat.heal, threat.count
+            );
+            let obj_id = system_data.combat_objective_queue.request(
+                ObjectiveRequest::new(
+                    ObjectiveKind::Defend { room: room_name },
+                    OBJECTIVE_PRIORITY_HIGH,
+                    ForceRequirement::single(composition),
+                )
+                .owner(ObjectiveOwner::Defense)
+                .ttl(DEFEND_OBJECTIVE_TTL),
+                now,
+            );
+            system_data.combat_objective_queue.set_stage_position(obj_id, stage);
+        }
+
+        // ── Standby defenders for homes on alert ────────────────────────────
+        // A home whose posture is Alert or Lockdown (`military::posture`) with nothing inside and nothing
+        // inbound still keeps one floor-sized defender fielded, so the next sighting isn't answered from an
+        // empty spawn queue. Homes with a live or predicted threat are sized by the objectives above.
+        for &home in home_rooms.iter() {
+            let Some(room_name) = system_data.room_data.get(home).map(|rd| rd.name) else {
+                continue;
+            };
+            let standby = system_data
+                .defense_postures
+                .get(home)
+                .is_some_and(|posture| posture.keeps_standby_defender());
+            if !standby || under_attack.contains(&room_name) || system_data.border_watch.inbound(room_name).is_some() {
+                continue;
+            }
+            let member_energy = game::rooms()
+                .get(room_name)
+                .map(|r| r.energy_capacity_available())
+                .unwrap_or(max_home_energy);
+            let ctx = EngagementContext {
+                objective: DoctrineObjective::ClearCreeps,
+                coordination: EnemyCoordination::Coordinated,
+                defense: DefenseProfile::default(),
+                enemy_force: Some(EnemyForce {
+                    dps: 0.0,
+                    heal: 0.0,
+                    hits: 0,
+                    count: 1,
+                    boosted: false,
+                }),
+                importance: 0.0,
+                member_energy,
+                target_value: DEFENSE_TARGET_VALUE,
+                onsite_window: DEFENSE_ONSITE_WINDOW,
+                params: CompositionParams {
+                    member_energy,
+                    ..Default::default()
+                },
+                // No enemy to size against — the always-field floor is the standby defender.
+                defense_intel_reliable: false,
+            };
+            let Some(composition) = decide_doctrine(&ctx, &defense_docs)
+                .and_then(|d| screeps_combat_decision::doctrine::plan_engagement(d, &ctx, None).composition)
+            else {
+                continue;
+            };
+            debug!("[War] Standby Defend objective for {} on alert", room_name);
+            system_data.combat_objective_queue.request(
+                ObjectiveRequest::new(
+                    ObjectiveKind::Defend { room: room_name },
+                    OBJECTIVE_PRIORITY_MEDIUM,
+                    ForceRequirement::single(composition),
+                )
+                .owner(ObjectiveOwner::Defense)
+                .ttl(DEFEND_OBJECTIVE_TTL),
+                now,
+            );
+        }
+
         // ── Nuke defense, safe mode, wall repair (home rooms only) ──────────
         // Only create these missions for rooms we control (have spawns). This
         // avoids running wall repair / safe mode / nuke defense in owned rooms
@@ -713,6 +1002,7 @@ impl WarOperation {
                 )
                 .build();
                 room_data.add_mission(mission_entity);
+                self.missions.push(mission_entity);
             }
 
             if state.has_hostiles && !state.has_safe_mode_mission && features.military.safe_mode {
@@ -724,6 +1014,7 @@ impl WarOperation {
                 )
                 .build();
                 room_data.add_mission(mission_entity);
+                self.missions.push(mission_entity);
             }
 
             if state.has_hostiles && !state.has_wall_repair_mission {
@@ -735,6 +1026,7 @@ impl WarOperation {
                 )
                 .build();
                 room_data.add_mission(mission_entity);
+                self.missions.push(mission_entity);
             }
         }
 
@@ -743,8 +1035,9 @@ impl WarOperation {
         let mut defend_rooms: Vec<RoomName> = Vec::new();
         for flag in game::flags().values() {
             let name = flag.name();
-            if name.to_lowercase().starts_with("defend") {
-                defend_rooms.push(flag.pos().room_name());
+            let room = flag.pos().room_name();
+            if name.to_lowercase().starts_with("defend") && features.for_room(feature_overrides, room).military.defense {
+                defend_rooms.push(room);
             }
         }
         self.defend_flag_rooms = defend_rooms;
@@ -799,6 +1092,10 @@ impl WarOperation {
                     return None;
                 }
 
+                if !features.for_room(feature_overrides, room_data.name).military.defense {
+                    return None;
+                }
+
                 if !dynamic_vis.hostile_creeps() {
                     return None;
                 }
@@ -810,30 +1107,18 @@ impl WarOperation {
                 // Source Keepers are permanent residents and should not
                 // trigger defensive responses.
                 let invaders: Vec<_> = creeps
-                    .hostile()
+                    .hostile_summary()
+                    .bodies()
                     .iter()
-                    .filter(|c| crate::military::is_invader_owner(&c.owner().username()))
+                    .filter(|body| body.owner == HostileOwner::Invader)
                     .collect();
 
                 if invaders.is_empty() {
                     return None;
                 }
 
-                let mut dps: f32 = 0.0;
-                let mut heal: f32 = 0.0;
-                for inv in &invaders {
-                    for part_info in inv.body().iter() {
-                        if part_info.hits() == 0 {
-                            continue;
-                        }
-                        match part_info.part() {
-                            Part::Attack => dps += 30.0,
-                            Part::RangedAttack => dps += 10.0,
-                            Part::Heal => heal += 12.0,
-                            _ => {}
-                        }
-                    }
-                }
+                let dps: f32 = invaders.iter().map(|body| body.dps()).sum();
+                let heal: f32 = invaders.iter().map(|body| body.heal_per_tick).sum();
 
                 Some((entity, dps, heal, invaders.len()))
             })
@@ -896,6 +1181,39 @@ impl WarOperation {
                 game::time(),
             );
         }
+
+        // ── Remote claimer interception ────────────────────────────────────
+        // A player's claimer in one of our remotes — still reserved by us, or already flipped to a
+        // contested reservation — is attacking the reservation down. It carries no weapons, so one small
+        // melee interceptor is enough, at HIGH: above remote-invader cleanup, since every tick it works is
+        // reservation the reserve mission has to spawn back. Claimers in owned rooms are covered by the
+        // owned-room `Secure` above; either way the squad manager focuses the claimer first.
+        let contested: Vec<RoomName> = system_data.contested_reservations.contested(now).map(|(room, _)| room).collect();
+        let remotes_with_claimers: Vec<RoomName> = (system_data.entities, &*system_data.room_data, system_data.threat_data)
+            .join()
+            .filter(|(_, room_data, threat)| {
+                let ours =
+                    room_data.get_dynamic_visibility_data().is_some_and(|d| d.reservation().mine()) || contested.contains(&room_data.name);
+
+                ours && !threat.claimers.is_empty()
+                    && now.saturating_sub(threat.last_seen) <= DEFEND_OBJECTIVE_TTL
+                    && features.for_room(feature_overrides, room_data.name).military.defense
+            })
+            .map(|(_, room_data, _)| room_data.name)
+            .collect();
+        for room_name in remotes_with_claimers {
+            info!("[War] Intercept objective for claimer in remote room {}", room_name);
+            system_data.combat_objective_queue.request(
+                ObjectiveRequest::new(
+                    ObjectiveKind::Secure { room: room_name },
+                    OBJECTIVE_PRIORITY_HIGH,
+                    ForceRequirement::single(interceptor_composition()),
+                )
+                .owner(ObjectiveOwner::Defense)
+                .ttl(DEFEND_OBJECTIVE_TTL),
+                now,
+            );
+        }
     }
 
     // ── Offense evaluation (every 10-20 ticks) ────────────────────────────
@@ -921,12 +1239,33 @@ impl WarOperation {
             info!("[War] Offense scan continues despite no free spawns (objectives upsert; ROI gate protects spawning)");
         }
 
-        // Collect home rooms (entity + name) for distance scoring and spawn assignment.
+        // A cease-fire withdraws the objectives against its rooms: the squad manager retires their squads and
+        // the members walk home to recycle, under fire or not (`jobs::squad_combat`).
+        system_data.war_targets.expire(game::time());
+
+        let ceased: Vec<ObjectiveId> = system_data
+            .combat_objective_queue
+            .objectives
+            .iter()
+            .filter(|o| o.owner == ObjectiveOwner::Attack && system_data.war_targets.is_standing_down(o.kind.room()))
+            .map(|o| o.id)
+            .collect();
+
+        for id in ceased {
+            info!("[War] Cease-fire: withdrawing offense objective {:?}", id);
+            system_data.combat_objective_queue.withdraw(id);
+        }
+
+        // Collect home rooms (entity + name) for distance scoring and spawn assignment. A room whose spawns
+        // are saturated can't stage an attack however much it has stored: the squad would queue behind its
+        // own creeps.
+        let economy = &*system_data.economy;
         let home_room_entries: Vec<(Entity, RoomName)> = (system_data.entities, &*system_data.room_data)
             .join()
-            .filter(|(_, rd)| {
+            .filter(|(e, rd)| {
                 rd.get_dynamic_visibility_data().map(|d| d.owner().mine()).unwrap_or(false)
                     && rd.get_structures().map(|s| !s.spawns().is_empty()).unwrap_or(false)
+                    && economy.can_stage(e)
             })
             .map(|(e, rd)| (e, rd.name))
             .collect();
@@ -977,6 +1316,16 @@ impl WarOperation {
                 .map(|(e, rd, td)| (e, rd.name, td.clone()))
                 .collect();
 
+        // The safest way in past each threat room's towers, with the tower positions the room's defense
+        // profile is index-aligned with — a drain squad sizes against the fire there.
+        let entry_segments: std::collections::HashMap<RoomName, (Vec<Position>, EntrySegment)> = threat_rooms
+            .iter()
+            .filter_map(|(e, room_name, td)| {
+                let segment = system_data.room_data.get(*e)?.get_dynamic_visibility_data()?.entry_segment()?.clone();
+                Some((*room_name, (td.hostile_tower_positions.clone(), segment)))
+            })
+            .collect();
+
         // L4-activate (ADR 0026 §9.10): enrich AttackFlag candidates with the flag room's SCOUTED threat
         // (creeps + TOWERS) so the `PlayerRaid` doctrine sizes the raid to out-power + out-heal the real
         // defense. AttackFlag candidates are built above (before the scan) with zeros; cross-reference each
@@ -1421,6 +1770,14 @@ impl WarOperation {
             }
         }
 
+        // ── Declared war targets ─────────────────────────────────────────
+
+        let declared = self.declared_war_candidates(system_data, &threat_rooms, &home_rooms, current_tick, war_debug);
+        candidates.extend(declared);
+
+        let war_targets = &*system_data.war_targets;
+        candidates.retain(|c| !war_targets.is_standing_down(c.room));
+
         // ── 3. Deduplicate: keep highest-scored candidate per room ───────
 
         candidates.sort_by(|a, b| {
@@ -1535,6 +1892,14 @@ impl WarOperation {
                     OBJECTIVE_PRIORITY_LOW,
                     0.0,
                 )),
+                // Declared war → the same sized + gated raid, but holding the room (MEDIUM: operator intent
+                // without a flag's urgency). Winnability, ROI and the operation budget still decide.
+                TargetSource::WarDeclaration => Some((
+                    DoctrineObjective::RaidCreeps,
+                    ObjectiveKind::Secure { room: candidate.room },
+                    OBJECTIVE_PRIORITY_MEDIUM,
+                    0.0,
+                )),
                 _ => None,
             };
             let Some((doc_obj, kind, priority, importance)) = mapped else {
@@ -1626,7 +1991,7 @@ impl WarOperation {
                 }
             }
 
-            let objective: Option<(ObjectiveKind, f32, SquadComposition, AssaultMode)> = if doctrine.honor_verdict() && candidate.defense.is_none() {
+            let objective: Option<OffenseObjective> = if doctrine.honor_verdict() && candidate.defense.is_none() {
                 // A gated doctrine needs the scouted defense to judge winnability; without it, don't commit.
                 None
             } else {
@@ -1642,7 +2007,20 @@ impl WarOperation {
                             params: CompositionParams { member_energy, ..Default::default() },
                             ..base_ctx.clone()
                         };
-                        let plan = plan_engagement(doctrine, &ctx, None);
+                        let mut plan = plan_engagement(doctrine, &ctx, None);
+                        // A drain holds the safest entry, not the assault tile: re-size it to the towers' fire
+                        // there, unless that would talk the oracle out of draining.
+                        let entry = entry_segments.get(&candidate.room);
+                        if let (AssaultMode::Drain, Some((towers, segment))) = (&plan.assessment.mode, entry) {
+                            let drain_ctx = EngagementContext {
+                                defense: drain_defense(&ctx.defense, towers, candidate.room, segment),
+                                ..ctx.clone()
+                            };
+                            let drain_plan = plan_engagement(doctrine, &drain_ctx, None);
+                            if matches!(drain_plan.assessment.mode, AssaultMode::Drain) {
+                                plan = drain_plan;
+                            }
+                        }
                         if doctrine.honor_verdict() && !plan.winnable() {
                             info!(
                                 "[War]   Skip {} -- force oracle: not winnable for one squad ({})",
@@ -1654,7 +2032,36 @@ impl WarOperation {
                             // The assembler can grow a winnable squad large, so a per-tick-affordable
                             // composition can still be globally unsustainable. Defer if the spawn cost exceeds
                             // the reserve-protected military surplus.
-                            let spawn_cost = sized.estimated_cost(member_energy);
+                            // Each wave already lost against the room escalates this one a step past the plan
+                            // that died: drain the towers first, add a healer, or boost the bodies.
+                            let lost_waves = system_data.combat_objective_queue.lost_waves(candidate.room);
+                            let stock = if features.military.boost_military {
+                                boost_stock(&system_data.economy, &home_room_entries)
+                            } else {
+                                std::collections::HashMap::new()
+                            };
+                            let escalated =
+                                escalate_force(ForceRequirement::single(sized), plan.assessment.mode, lost_waves, member_energy, &stock);
+                            if !lost_waves.is_empty() {
+                                info!(
+                                    "[War]   {} escalated after {} lost wave(s): {:?}",
+                                    candidate.room,
+                                    lost_waves.len(),
+                                    escalated.steps
+                                );
+                            }
+                            for (compound, amount) in &escalated.missing_boosts {
+                                if let Some((_, home)) = home_room_entries.first() {
+                                    system_data.order_queue.request_passive_purchase(*home, *compound, *amount);
+                                }
+                            }
+                            let assault_mode = if escalated.drain { AssaultMode::Drain } else { plan.assessment.mode };
+                            let next_wave = (!lost_waves.is_empty()).then(|| {
+                                let wave = WavePlan::of(&escalated.force, escalated.drain, !escalated.boosts.is_empty());
+                                (wave, escalated.steps.clone())
+                            });
+                            let force = escalated.force;
+                            let spawn_cost = force_plan_cost(&force, member_energy);
                             if !system_data.economy.can_afford_military(spawn_cost) {
                                 info!(
                                     "[War]   Skip {} -- ROI: squad spawn cost {} exceeds affordable military surplus; defer",
@@ -1669,10 +2076,10 @@ impl WarOperation {
                                 );
                                 info!(
                                     "[War]   {} via {:?} (~{} ticks): {} sized to {} ranged + {} heal parts, P(win)~{:.0}% (cost {}, {})",
-                                    candidate.room, plan.assessment.mode, plan.assessment.est_ticks, doctrine.name(),
+                                    candidate.room, assault_mode, plan.assessment.est_ticks, doctrine.name(),
                                     plan.required.immune_struct_parts + plan.required.anti_creep_parts, plan.required.heal_parts, pwin * 100.0, spawn_cost, plan.assessment.reason
                                 );
-                                Some((kind, priority, sized, plan.assessment.mode))
+                                Some((kind, priority, force, assault_mode, spawn_cost, escalated.boosts, next_wave))
                             }
                         } else {
                             info!("[War]   Skip {} -- can't field the required force at {} energy; defer", candidate.room, member_energy);
@@ -1682,7 +2089,7 @@ impl WarOperation {
                 }
             };
 
-            let Some((kind, priority, composition, assault_mode)) = objective else {
+            let Some((kind, priority, force, assault_mode, spawn_cost, boosts, next_wave)) = objective else {
                 continue;
             };
 
@@ -1698,12 +2105,33 @@ impl WarOperation {
             if is_new && !offense_cap_allows_new(&candidate.source, offense_count, self.max_concurrent_attacks) {
                 continue;
             }
+            // New offense waits on the operation budget; an existing objective re-reserves to keep its
+            // commitment alive (and re-seeds it after a reload). Threat-driven targets carry no income
+            // estimate, so their score ranks them ahead of the economic launches.
+            let budget_value = candidate
+                .economic_roi
+                .map(|roi| roi / crate::room_economics::DEFAULT_HOLD_HORIZON as f32)
+                .unwrap_or(candidate.score);
+            let budget_request = BudgetRequest::new(
+                BudgetKey::new(BudgetOperation::Attack, candidate.room),
+                home_room_entries.iter().map(|(entity, _)| *entity).collect(),
+                OperationCost::from_energy(spawn_cost),
+                budget_value,
+                OFFENSE_OBJECTIVE_TTL,
+            );
+            if !system_data.operation_budget.reserve(budget_request, current_tick) && is_new {
+                info!(
+                    "[War] Offense {:?} for {} waiting on the operation budget ({}e)",
+                    kind, candidate.room, spawn_cost
+                );
+                continue;
+            }
             info!(
                 "[War] Offense objective {:?} for {} (source={:?}, score={:.1})",
                 kind, candidate.room, candidate.source, candidate.score
             );
             let obj_id = system_data.combat_objective_queue.request(
-                ObjectiveRequest::new(kind, priority, ForceRequirement::single(composition))
+                ObjectiveRequest::new(kind, priority, force)
                     .owner(ObjectiveOwner::Attack)
                     .ttl(OFFENSE_OBJECTIVE_TTL),
                 game::time(),
@@ -1715,6 +2143,17 @@ impl WarOperation {
             // attached every scan (no WFV bump); `Breach` is attached too (a no-op for the strategy/stance,
             // which key on `Drain`), so a re-assessed room that flips OUT of drain clears the stance next scan.
             system_data.combat_objective_queue.set_assault_mode(obj_id, assault_mode);
+            // An escalated wave's boosts ride on the runtime entry like a boosted defense's, and the plan it
+            // escalated to is kept for the summary. Both transient, re-attached every scan.
+            system_data.combat_objective_queue.set_boost_plan(obj_id, boosts);
+            if let Some((plan, steps)) = next_wave {
+                system_data.combat_objective_queue.set_next_wave(candidate.room, plan, steps);
+            }
+            // The ROI gate above judged the whole colony's surplus; the manager re-checks the in-range homes at
+            // fielding time, so two objectives passing on the same surplus don't both field and drain them.
+            system_data
+                .combat_objective_queue
+                .set_deploy_condition(obj_id, DeployCondition::AfterEnergyAvailable { amount: spawn_cost });
             // Attach the COMPUTED economic value of CONTROLLING the room (reach-bug #3, ADR 0032
             // §economic-value-unlocked) so the EV auction values a winnable economic core by the remote it
             // unlocks (the `value_e` FarmCore/economic arm) instead of the ~0 `Denial` proxy. Transient —
@@ -1732,6 +2171,264 @@ impl WarOperation {
                 offense_count += 1;
             }
         }
+
+        // ── 5. Harass hostile players' remote mining ──
+        // A player-reserved (not owned) room without towers is a remote: cheap raiders patrolling those
+        // remotes and shooting the unescorted miners/haulers cost the player far more than they cost us. One
+        // Harass objective per player, anchored on its first remote, carries the whole set as patrol rooms.
+        // A remote the raiders' kills/losses tally judges defended is backed off like an unwinnable target.
+        // Our own remotes another player has reserved are harassed even when attacking players is off: the
+        // reserve mission is wearing the reservation down, and the raiders see off its reservers.
+
+        let contested: Vec<(String, RoomName)> = system_data
+            .contested_reservations
+            .contested(current_tick)
+            .filter(|(_, player)| !crate::military::is_npc_owner(player))
+            .map(|(room_name, player)| (player.to_string(), room_name))
+            .collect();
+
+        if !features.military.attack_players && contested.is_empty() {
+            return;
+        }
+
+        let mut remotes: Vec<(String, RoomName)> = Vec::new();
+        for (room_entity, room_name, threat_data) in threat_rooms.iter().filter(|_| features.military.attack_players) {
+            let Some(dynamic) = system_data.room_data.get(*room_entity).and_then(|rd| rd.get_dynamic_visibility_data()) else {
+                continue;
+            };
+            let crate::room::data::RoomDisposition::Hostile(player) = dynamic.reservation() else {
+                continue;
+            };
+            if crate::military::is_npc_owner(player)
+                || !threat_data.hostile_tower_positions.is_empty()
+                || system_data.war_targets.is_standing_down(*room_name)
+            {
+                continue;
+            }
+            if self.min_distance_to_homes(*room_name, &home_rooms, system_data.pathfinder, current_tick) > HARASS_MAX_DISTANCE {
+                continue;
+            }
+            let queue = &mut system_data.combat_objective_queue;
+            if queue.harass_tally(*room_name).is_defended() {
+                info!("[War]   Harass {} -- defended ({:?}); backing off", room_name, queue.harass_tally(*room_name));
+                queue.mark_unwinnable(*room_name, current_tick);
+                queue.harass_tally.remove(room_name);
+                continue;
+            }
+            if queue.is_unwinnable_now(*room_name, current_tick) {
+                continue;
+            }
+            remotes.push((player.clone(), *room_name));
+        }
+        for (player, room_name) in contested {
+            if remotes.iter().any(|(_, remote)| *remote == room_name)
+                || system_data.war_targets.is_standing_down(room_name)
+                || system_data.combat_objective_queue.is_unwinnable_now(room_name, current_tick)
+            {
+                continue;
+            }
+            if war_debug {
+                info!("[War]   Harass {} -- our remote, reserved by {}", room_name, player);
+            }
+            remotes.push((player, room_name));
+        }
+        let patrols = group_harass_remotes(remotes);
+
+        // Withdraw a patrol whose anchor remote dropped out (defended, or no longer reserved) — a squad on a
+        // claimed objective is never expired underneath it, so the producer has to let go explicitly.
+        let stale: Vec<_> = system_data
+            .combat_objective_queue
+            .objectives
+            .iter()
+            .filter(|o| matches!(o.kind, ObjectiveKind::Harass { .. }))
+            .filter(|o| !system_data.combat_objective_queue.patrol_rooms(o.id).is_empty())
+            .filter(|o| !patrols.iter().any(|(_, rooms)| rooms.first() == Some(&o.kind.room())))
+            .map(|o| o.id)
+            .collect();
+        for id in stale {
+            system_data.combat_objective_queue.withdraw(id);
+        }
+
+        for (player, rooms) in patrols {
+            let kind = ObjectiveKind::Harass { room: rooms[0] };
+            let is_new = system_data.combat_objective_queue.find_by_kind(&kind).is_none();
+            if is_new && offense_count >= self.max_concurrent_attacks {
+                continue;
+            }
+            let Some((_, member_energy)) = best_force_budget(SquadRole::RangedDPS, &home_rooms, rooms[0], system_data.pathfinder) else {
+                continue;
+            };
+            // A second raider only where the player has been seen fielding armed creeps.
+            let duo = threat_rooms
+                .iter()
+                .any(|(_, name, td)| rooms.contains(name) && td.estimated_attack_dps > 0.0);
+            let force = ForceRequirement::single(harass_composition(duo));
+            let spawn_cost = force_plan_cost(&force, member_energy);
+            if is_new && !system_data.economy.can_afford_military(spawn_cost) {
+                continue;
+            }
+            if war_debug {
+                info!("[War] Harass objective for {}'s remotes {:?} (duo={})", player, rooms, duo);
+            }
+            let obj_id = system_data.combat_objective_queue.request(
+                ObjectiveRequest::new(kind, OBJECTIVE_PRIORITY_LOW, force)
+                    .owner(ObjectiveOwner::Attack)
+                    .ttl(OFFENSE_OBJECTIVE_TTL),
+                current_tick,
+            );
+            system_data
+                .combat_objective_queue
+                .set_deploy_condition(obj_id, DeployCondition::AfterEnergyAvailable { amount: spawn_cost });
+            system_data.combat_objective_queue.set_patrol_rooms(obj_id, rooms);
+            if is_new {
+                offense_count += 1;
+            }
+        }
+    }
+
+    // ── Caravan escorts (defense cadence) ─────────────────────────────────
+
+    /// Field a ranged escort for each haul mission whose haulers cross rooms with recent hostile sightings
+    /// (`military::escort`), and withdraw it once the mission stops asking.
+    fn run_caravan_escorts(&mut self, system_data: &mut OperationExecutionSystemData) {
+        let features = system_data.features;
+        let current_tick = game::time();
+
+        system_data.escort_request.expire(current_tick);
+
+        let caravans: Vec<Caravan> = if features.military.defense {
+            system_data.escort_request.caravans().cloned().collect()
+        } else {
+            Vec::new()
+        };
+
+        // A claimed objective is never expired underneath its squad, so let go explicitly once the caravan
+        // stops asking; the manager retires the escort and it walks home to recycle.
+        let stale: Vec<_> = system_data
+            .combat_objective_queue
+            .objectives
+            .iter()
+            .filter(|o| matches!(o.kind, ObjectiveKind::Caravan { .. }))
+            .filter(|o| !caravans.iter().any(|c| c.pickup_room == o.kind.room()))
+            .map(|o| o.id)
+            .collect();
+        for id in stale {
+            system_data.combat_objective_queue.withdraw(id);
+        }
+
+        if caravans.is_empty() {
+            return;
+        }
+
+        let home_rooms: Vec<RoomName> = (system_data.entities, &*system_data.room_data)
+            .join()
+            .filter(|(_, rd)| {
+                rd.get_dynamic_visibility_data().map(|d| d.owner().mine()).unwrap_or(false)
+                    && rd.get_structures().map(|s| !s.spawns().is_empty()).unwrap_or(false)
+            })
+            .map(|(_, rd)| rd.name)
+            .collect();
+
+        for caravan in caravans {
+            let kind = ObjectiveKind::Caravan { room: caravan.pickup_room };
+            let force = ForceRequirement::single(escort_composition());
+
+            if system_data.combat_objective_queue.find_by_kind(&kind).is_none() {
+                let Some((_, member_energy)) =
+                    best_force_budget(SquadRole::RangedDPS, &home_rooms, caravan.pickup_room, system_data.pathfinder)
+                else {
+                    continue;
+                };
+                if !system_data.economy.can_afford_military(force_plan_cost(&force, member_energy)) {
+                    continue;
+                }
+                if features.military.debug_log {
+                    info!(
+                        "[War] Escort for haulers from {} through {:?}",
+                        caravan.pickup_room, caravan.threatened_rooms
+                    );
+                }
+            }
+
+            let obj_id = system_data.combat_objective_queue.request(
+                ObjectiveRequest::new(kind, OBJECTIVE_PRIORITY_MEDIUM, force)
+                    .owner(ObjectiveOwner::War)
+                    .ttl(DEFEND_OBJECTIVE_TTL),
+                current_tick,
+            );
+            system_data.combat_objective_queue.set_caravan(obj_id, caravan);
+        }
+    }
+
+    // ── Controller downgrades (offense cadence) ───────────────────────────
+
+    /// Open a controller downgrade mission on each player room we are sieging, so declaimers follow the
+    /// siege in on the upgrade-block cadence (`missions::downgrade`). The mission times and gates the
+    /// strikes itself and ends with the siege.
+    fn run_controller_downgrades(
+        &mut self,
+        system_data: &mut OperationExecutionSystemData,
+        runtime_data: &mut OperationExecutionRuntimeData,
+    ) {
+        let military = system_data.features.military;
+
+        if !(military.offense && military.attack_players && military.downgrade) {
+            return;
+        }
+
+        let homes: Vec<(Entity, RoomName)> = (system_data.entities, &*system_data.room_data)
+            .join()
+            .filter(|(_, rd)| {
+                rd.get_dynamic_visibility_data().map(|d| d.owner().mine()).unwrap_or(false)
+                    && rd.get_structures().map(|s| !s.spawns().is_empty()).unwrap_or(false)
+            })
+            .map(|(e, rd)| (e, rd.name))
+            .collect();
+
+        let queue = &*system_data.combat_objective_queue;
+        let targets: Vec<(Entity, RoomName)> = (system_data.entities, &*system_data.room_data)
+            .join()
+            .filter(|(_, rd)| under_siege(queue, rd.name))
+            .filter(|(_, rd)| rd.get_dynamic_visibility_data().is_some_and(player_holds_controller))
+            .filter(|(_, rd)| rd.get_static_visibility_data().is_some_and(|s| s.controller().is_some()))
+            .filter(|(_, rd)| {
+                !rd.get_missions().iter().any(|me| {
+                    system_data
+                        .mission_data
+                        .get(*me)
+                        .as_mission_type::<ControllerDowngradeMission>()
+                        .is_some()
+                })
+            })
+            .map(|(e, rd)| (e, rd.name))
+            .collect();
+
+        for (room_entity, room_name) in targets {
+            let home_room_datas: Vec<Entity> = homes
+                .iter()
+                .filter(|(_, home)| game::map::get_room_linear_distance(*home, room_name, false) <= DOWNGRADE_MAX_DISTANCE)
+                .map(|(e, _)| *e)
+                .collect();
+
+            if home_room_datas.is_empty() {
+                continue;
+            }
+
+            let Some(room_data) = system_data.room_data.get_mut(room_entity) else {
+                continue;
+            };
+
+            info!("[War] Creating ControllerDowngradeMission for sieged room: {}", room_name);
+            let mission_entity = ControllerDowngradeMission::build(
+                system_data.updater.create_entity(system_data.entities),
+                Some(runtime_data.entity),
+                room_entity,
+                &home_room_datas,
+            )
+            .build();
+            room_data.add_mission(mission_entity);
+            self.missions.push(mission_entity);
+        }
     }
 
     // ── Heavy recompute (every 50+ ticks) ─────────────────────────────────
@@ -1794,6 +2491,146 @@ impl WarOperation {
 
     // ── Helpers ────────────────────────────────────────────────────────────
 
+    /// One candidate per war declaration: the declared room, or the declared player's weakest room in
+    /// reach. A pick on stale intel is re-scouted instead.
+    fn declared_war_candidates(
+        &self,
+        system_data: &mut OperationExecutionSystemData,
+        threat_rooms: &[(Entity, RoomName, RoomThreatData)],
+        home_rooms: &[RoomName],
+        current_tick: u32,
+        war_debug: bool,
+    ) -> Vec<AttackCandidate> {
+        let mut candidates = Vec::new();
+
+        for target in system_data.war_targets.declarations() {
+            let mut rooms = Vec::new();
+
+            for (room_entity, room_name, threat_data) in threat_rooms {
+                let Some(dynamic) = system_data
+                    .room_data
+                    .get(*room_entity)
+                    .and_then(|rd| rd.get_dynamic_visibility_data())
+                else {
+                    continue;
+                };
+                let declared = match &target {
+                    WarTarget::Player(player) => {
+                        matches!(dynamic.owner(), crate::room::data::RoomDisposition::Hostile(owner) if owner == player)
+                    }
+                    WarTarget::Room(room) => room == room_name,
+                };
+                if !declared {
+                    continue;
+                }
+
+                let distance = self.min_distance_to_homes(*room_name, home_rooms, system_data.pathfinder, current_tick);
+                if distance > DECLARED_WAR_MAX_DISTANCE {
+                    continue;
+                }
+
+                rooms.push(DeclaredRoom {
+                    room: *room_name,
+                    distance,
+                    tower_dps: neighbour_tower_dps(threat_data),
+                    creep_dps: threat_data.estimated_attack_dps,
+                    creep_heal: threat_data.estimated_heal,
+                    active_spawns: dynamic.hostile_spawns(),
+                    safe_mode: threat_data.safe_mode_active,
+                    last_seen: threat_data.last_seen,
+                });
+            }
+
+            let Some(pick) = weakest_room(&rooms) else {
+                // A declared room never scouted is looked at; a player with no known room waits for intel.
+                if let WarTarget::Room(room) = &target {
+                    system_data
+                        .visibility
+                        .request(VisibilityRequest::new(*room, VISIBILITY_PRIORITY_HIGH, VisibilityRequestFlags::ALL));
+                }
+                if war_debug {
+                    info!("[War]   War on {}: no attackable room known", target);
+                }
+                continue;
+            };
+
+            if pick.is_stale(current_tick) {
+                system_data.visibility.request(VisibilityRequest::new(
+                    pick.room,
+                    offense_rescout_priority(true),
+                    VisibilityRequestFlags::ALL,
+                ));
+                if war_debug {
+                    info!(
+                        "[War]   War on {}: {} picked but stale (age={}); requested re-scout",
+                        target,
+                        pick.room,
+                        current_tick.saturating_sub(pick.last_seen)
+                    );
+                }
+                continue;
+            }
+
+            let Some((_, _, threat_data)) = threat_rooms.iter().find(|(_, room, _)| *room == pick.room) else {
+                continue;
+            };
+
+            // No flag tile: range the towers to the room centre, as the resource-denial arm does.
+            let assault = Position::new(
+                RoomCoordinate::new(25).expect("valid coordinate"),
+                RoomCoordinate::new(25).expect("valid coordinate"),
+                pick.room,
+            );
+            let towers: Vec<TowerThreat> = threat_data
+                .hostile_tower_positions
+                .iter()
+                .enumerate()
+                .map(|(i, tpos)| TowerThreat {
+                    range_to_assault: tpos.get_range_to(assault),
+                    energy: threat_data.tower_energy.get(i).copied().unwrap_or(1000),
+                })
+                .collect();
+            let defense = DefenseProfile {
+                towers,
+                breach_hits: 0,
+                objective_hits: 0,
+                repair_per_tick: threat_data.repair_per_tick as f32,
+                safe_mode: threat_data.safe_mode_active,
+                tower_intel: tower_intel_from(threat_data.hostile_tower_positions.is_empty(), true),
+            };
+
+            if war_debug {
+                info!(
+                    "[War]   War on {}: {} (dist={}, strength={:.0}) of {} known room(s)",
+                    target,
+                    pick.room,
+                    pick.distance,
+                    pick.strength(),
+                    rooms.len()
+                );
+            }
+
+            system_data.war_targets.engage(pick.room, target.clone());
+
+            candidates.push(AttackCandidate {
+                room: pick.room,
+                source: TargetSource::WarDeclaration,
+                score: DECLARED_WAR_SCORE,
+                tower_count: threat_data.hostile_tower_positions.len() as u32,
+                estimated_enemy_dps: threat_data.estimated_attack_dps,
+                estimated_enemy_heal: threat_data.estimated_heal,
+                has_safe_mode: threat_data.safe_mode_active || threat_data.safe_mode_available,
+                estimated_roi: None,
+                target_pos: None,
+                defense: Some(defense),
+                economic_roi: None,
+                defense_last_seen: threat_data.last_seen,
+            });
+        }
+
+        candidates
+    }
+
     /// Compute the minimum route distance from any home room to a target room.
     fn min_distance_to_homes(
         &self,
@@ -2025,6 +2862,20 @@ fn classify_coordination(candidate: &AttackCandidate) -> EnemyCoordination {
     }
 }
 
+/// Group harassable remotes by the reserving player: players in name order, each with its rooms in name
+/// order, so the anchor room (the first) and the patrol order are stable scan to scan.
+fn group_harass_remotes(remotes: Vec<(String, RoomName)>) -> Vec<(String, Vec<RoomName>)> {
+    let mut by_player: std::collections::BTreeMap<String, Vec<RoomName>> = std::collections::BTreeMap::new();
+    for (player, room) in remotes {
+        by_player.entry(player).or_default().push(room);
+    }
+    for rooms in by_player.values_mut() {
+        rooms.sort_by_key(|r| r.to_string());
+        rooms.dedup();
+    }
+    by_player.into_iter().collect()
+}
+
 /// The best (longest on-site) launch window for a squad attacking `target` from any home room (ADR 0020
 /// §12.2 / ADR 0031 D16): returns `(onsite_window, member_energy)` — the EV optimizer
 /// ([`optimize_composition`], called via [`plan_engagement`]) presumes NO reference squad, so this no
@@ -2093,6 +2944,32 @@ impl Operation for WarOperation {
         self.owner.take();
     }
 
+    fn get_children(&self) -> Vec<Entity> {
+        self.missions.iter().copied().collect()
+    }
+
+    fn child_complete(&mut self, child: Entity) {
+        self.missions.retain(|e| *e != child);
+    }
+
+    /// The defense and offense scans run on the next tick and relaunch the lost mission if its room still
+    /// needs it.
+    fn child_lost(&mut self, child: Entity) {
+        self.missions.retain(|e| *e != child);
+        self.last_defense_tick = None;
+        self.last_offense_tick = None;
+    }
+
+    fn repair_entity_refs(&mut self, is_valid: &dyn Fn(Entity) -> bool) {
+        self.missions.retain(|e| {
+            let ok = is_valid(*e);
+            if !ok {
+                error!("INTEGRITY: dead war mission entity {:?} removed from WarOperation", e);
+            }
+            ok
+        });
+    }
+
     fn describe_operation(&self, ctx: &OperationDescribeContext) -> SummaryContent {
         let features = ctx.features;
         let mut children = Vec::new();
@@ -2109,6 +2986,54 @@ impl Operation for WarOperation {
             children.push(SummaryContent::Text(label));
         }
 
+        // Attack waves lost per room since it was last won, and the plan the next one escalated to.
+        {
+            let mut rooms: Vec<_> = ctx.objective_queue.wave_history.iter().filter(|(_, waves)| !waves.lost.is_empty()).collect();
+            rooms.sort_by_key(|(room, _)| room.to_string());
+            let items: Vec<String> = rooms
+                .into_iter()
+                .map(|(room, waves)| {
+                    let next = match &waves.next {
+                        Some((plan, steps)) => {
+                            let steps: Vec<String> = steps.iter().map(|step| step.to_string()).collect();
+                            format!("next {} [{}]", plan, steps.join(", "))
+                        }
+                        None => "next not sized yet".to_string(),
+                    };
+                    format!("{}: {} lost, {}", room, waves.lost.len(), next)
+                })
+                .collect();
+            if !items.is_empty() {
+                children.push(SummaryContent::Lines {
+                    header: "Waves".to_string(),
+                    items,
+                });
+            }
+        }
+
+        // Arrival timeline of each fielded objective's force, and how the lead squad kept to it.
+        {
+            let items: Vec<String> = ctx
+                .objective_queue
+                .objectives
+                .iter()
+                .filter_map(|objective| {
+                    let plan = ctx.objective_queue.arrival_plan(objective.id)?;
+                    let arrived = match (plan.arrived_at, plan.arrived_at.and_then(|at| plan.deviation(0, at))) {
+                        (Some(at), Some(deviation)) => format!("arrived {} ({:+})", at, deviation),
+                        _ => "en route".to_string(),
+                    };
+                    Some(format!("{}: {}, {}", objective.kind.room(), plan.describe().join("; "), arrived))
+                })
+                .collect();
+            if !items.is_empty() {
+                children.push(SummaryContent::Lines {
+                    header: "Arrivals".to_string(),
+                    items,
+                });
+            }
+        }
+
         // Defense section.
         {
             let mut defense_items = Vec::new();
@@ -2147,12 +3072,14 @@ impl Operation for WarOperation {
         if self.should_run_tier(self.last_defense_tick, effective_cadence(DEFENSE_CADENCE, tier, true)) {
             self.last_defense_tick = Some(game::time());
             self.run_defense_scan(system_data, runtime_data);
+            self.run_caravan_escorts(system_data);
         }
 
         // Offense evaluation: sheddable — stretches under pressure.
         if self.should_run_tier(self.last_offense_tick, effective_cadence(OFFENSE_CADENCE, tier, false)) {
             self.last_offense_tick = Some(game::time());
             self.run_offense_evaluation(system_data, runtime_data);
+            self.run_controller_downgrades(system_data, runtime_data);
         }
 
         // Heavy recompute: sheddable — stretches under pressure.
@@ -2635,4 +3562,37 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn harass_remotes_group_per_player_in_stable_order() {
+        let r = |n: &str| n.parse::<RoomName>().unwrap();
+        let grouped = group_harass_remotes(vec![
+            ("bob".to_string(), r("W3N1")),
+            ("alice".to_string(), r("W2N2")),
+            ("bob".to_string(), r("W1N1")),
+            ("bob".to_string(), r("W3N1")),
+        ]);
+
+        assert_eq!(grouped, vec![("alice".to_string(), vec![r("W2N2")]), ("bob".to_string(), vec![r("W1N1"), r("W3N1")])]);
+    }
+
+    /// A lost room mission leaves the list and brings both scans forward, so
+    /// the room gets it back on the next run if it still needs it.
+    #[test]
+    fn a_lost_mission_reruns_the_scans() {
+        let mut world = World::new();
+        let (kept, lost) = (world.create_entity().build(), world.create_entity().build());
+        let mut war = WarOperation::new(None);
+        war.missions.push(kept);
+        war.missions.push(lost);
+        war.last_defense_tick = Some(100);
+        war.last_offense_tick = Some(100);
+
+        assert_eq!(lost_children(&war, |e| e == kept), vec![lost]);
+
+        war.child_lost(lost);
+
+        assert_eq!(war.get_children(), vec![kept]);
+        assert_eq!((war.last_defense_tick, war.last_offense_tick), (None, None));
+    }
 }
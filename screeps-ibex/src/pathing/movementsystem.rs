@@ -2,7 +2,7 @@ use crate::creep::*;
 use crate::entitymappingsystem::*;
 use crate::room::data::*;
 use crate::room::room_status_cache::RoomStatusCache;
-use crate::visualize::Visualizer;
+use crate::visualize::{VisualLayer, Visualizer};
 use screeps::*;
 use screeps_rover::screeps_impl::{ScreepsCostMatrixDataSource, ScreepsPathfinder};
 use screeps_rover::*;
@@ -31,6 +31,7 @@ pub struct MovementUpdateSystemData<'a> {
     visualizer: Option<Write<'a, Visualizer>>,
     governor: Read<'a, crate::cpugovernor::GovernorSnapshot>,
     metrics: Write<'a, crate::metrics::MetricsState>,
+    intent_recorder: Write<'a, crate::intents::IntentRecorder>,
     features: Read<'a, crate::features::Features>,
 }
 
@@ -43,7 +44,7 @@ struct IbexMovementVisualizer<'a> {
 impl<'a> MovementVisualizer for IbexMovementVisualizer<'a> {
     fn visualize_path(&mut self, creep_pos: Position, path: &[Position]) {
         let room = creep_pos.room_name();
-        let room_vis = self.visualizer.get_room(room);
+        let room_vis = self.visualizer.get_room(room, VisualLayer::Creeps);
         let points: Vec<(f32, f32)> = path.iter().map(|p| (p.x().u8() as f32, p.y().u8() as f32)).collect();
         let style = PolyStyle::default().stroke("blue").stroke_width(0.2).opacity(0.5);
         room_vis.poly(points, Some(style));
@@ -51,7 +52,7 @@ impl<'a> MovementVisualizer for IbexMovementVisualizer<'a> {
 
     fn visualize_anchor(&mut self, creep_pos: Position, anchor_pos: Position) {
         let room = creep_pos.room_name();
-        let room_vis = self.visualizer.get_room(room);
+        let room_vis = self.visualizer.get_room(room, VisualLayer::Creeps);
         let cx = creep_pos.x().u8() as f32;
         let cy = creep_pos.y().u8() as f32;
 
@@ -73,7 +74,7 @@ impl<'a> MovementVisualizer for IbexMovementVisualizer<'a> {
 
     fn visualize_immovable(&mut self, creep_pos: Position) {
         let room = creep_pos.room_name();
-        let room_vis = self.visualizer.get_room(room);
+        let room_vis = self.visualizer.get_room(room, VisualLayer::Creeps);
         let cx = creep_pos.x().u8() as f32;
         let cy = creep_pos.y().u8() as f32;
         let d = 0.15;
@@ -84,7 +85,7 @@ impl<'a> MovementVisualizer for IbexMovementVisualizer<'a> {
 
     fn visualize_stuck(&mut self, creep_pos: Position, ticks: u16) {
         let room = creep_pos.room_name();
-        let room_vis = self.visualizer.get_room(room);
+        let room_vis = self.visualizer.get_room(room, VisualLayer::Creeps);
         let cx = creep_pos.x().u8() as f32;
         let cy = creep_pos.y().u8() as f32;
 
@@ -102,7 +103,7 @@ impl<'a> MovementVisualizer for IbexMovementVisualizer<'a> {
 
     fn visualize_failed(&mut self, creep_pos: Position) {
         let room = creep_pos.room_name();
-        let room_vis = self.visualizer.get_room(room);
+        let room_vis = self.visualizer.get_room(room, VisualLayer::Creeps);
         let cx = creep_pos.x().u8() as f32;
         let cy = creep_pos.y().u8() as f32;
 
@@ -413,6 +414,13 @@ impl<'a> System<'a> for MovementUpdateSystem {
             .count() as u32;
         data.metrics.record_movement_failures(move_failures);
 
+        // The rover issues the move intents itself; count every request it
+        // didn't give up on. An upper bound: holds and arrivals are included.
+        data.intent_recorder.tally(
+            crate::intents::IntentCategory::Move,
+            (request_count as u32).saturating_sub(move_failures),
+        );
+
         let movement_cpu_used = get_cpu() - movement_start_cpu;
         if movement_cpu_used > 80.0 {
             log::info!("movement: {:.1} CPU, {} requests", movement_cpu_used, request_count);
@@ -17,6 +17,7 @@
 //! input is [`GovernorSnapshot`]'s tier, not a shared pool.
 
 use crate::cpugovernor::Tier;
+use crate::room::data::{ExitTiles, RoomClass, RoomStaticVisibilityData};
 use screeps::local::Position;
 use screeps::pathfinder;
 use screeps::*;
@@ -40,6 +41,19 @@ pub const FIND_ROUTE_NOMINAL_OPS: u32 = 2_000;
 /// path, which every caller already treats as "no path".
 pub const SAME_ROOM_MAX_OPS: u32 = 500;
 
+/// Ops cap for one road path search ([`PathfinderService::road_path`]).
+/// A remote sits a room or two from home; a capped-out search yields no
+/// path and the caller plans again later.
+pub const ROAD_PATH_MAX_OPS: u32 = 4_000;
+
+/// TTL for path-length cache entries in ticks: long enough for a creep
+/// standing at a work site to re-pick its targets without searching
+/// again, short enough that new walls and ramparts are seen.
+const PATH_LENGTH_TTL: u32 = 100;
+
+/// Path-length cache entries kept before the expired ones are swept.
+const PATH_LENGTH_CACHE_CAP: usize = 4_096;
+
 /// Pool size for a tier (pure; fixture-tested).
 pub fn pool_for_tier(tier: Tier) -> u32 {
     match tier {
@@ -75,6 +89,64 @@ pub struct CachedRoute {
 /// is a reasonable TTL — ownership changes are infrequent.
 const ROUTE_TTL: u32 = 1_000;
 
+/// Route cost of a room nothing better is known about.
+const DEFAULT_ROOM_COST: f64 = 2.0;
+
+/// What scouting recorded about a room, as the route callback sees it
+/// (copied off `RoomStaticVisibilityData` so the callback needs no storage).
+#[derive(Debug, Clone, Copy)]
+pub struct RouteIntel {
+    pub class: RoomClass,
+    pub exits: Option<ExitTiles>,
+}
+
+impl RouteIntel {
+    pub fn of(static_data: &RoomStaticVisibilityData) -> RouteIntel {
+        RouteIntel {
+            class: static_data.room_class(),
+            exits: static_data.exit_tiles().copied(),
+        }
+    }
+
+    /// Cost of entering a room of this class, or `None` to leave it to the
+    /// default. Keepers attack anything walking past, so an SK room costs as
+    /// much as a hostile-owned one; highways are open corridors.
+    fn entry_cost(&self) -> Option<f64> {
+        match self.class {
+            RoomClass::SourceKeeper => Some(10.0),
+            RoomClass::Highway => Some(1.5),
+            RoomClass::Normal | RoomClass::Center => None,
+        }
+    }
+}
+
+/// The edge of `from` that leads into the adjacent room `to`.
+fn edge_toward(from: RoomName, to: RoomName) -> Option<Direction> {
+    match (to.x_coord() - from.x_coord(), to.y_coord() - from.y_coord()) {
+        (0, -1) => Some(Direction::Top),
+        (1, 0) => Some(Direction::Right),
+        (0, 1) => Some(Direction::Bottom),
+        (-1, 0) => Some(Direction::Left),
+        _ => None,
+    }
+}
+
+/// Route cost of stepping from `from_room` into `room_name` from scouted
+/// intel alone: infinite when terrain walls off the edge between them,
+/// the class cost otherwise, `None` when scouting has nothing to say.
+fn scouted_room_cost(room_intel: &HashMap<RoomName, RouteIntel>, room_name: RoomName, from_room: RoomName) -> Option<f64> {
+    let sealed = room_intel
+        .get(&from_room)
+        .and_then(|intel| intel.exits)
+        .zip(edge_toward(from_room, room_name))
+        .is_some_and(|(exits, edge)| !exits.is_open(edge));
+    if sealed {
+        return Some(f64::INFINITY);
+    }
+
+    room_intel.get(&room_name).and_then(RouteIntel::entry_cost)
+}
+
 /// The single mission-side pathfinding instance. specs Resource;
 /// `Default` = full Normal pool (first-tick parity with the old static
 /// init: the first VM tick must not shed pathfinding spuriously).
@@ -89,9 +161,22 @@ pub struct PathfinderService {
     /// ADR 0004 step 2; internal until the seg-57 schema gains a
     /// field — additive bump, EP-5.5).
     denied: u32,
+    /// The tick's `CpuBudget::tick_allowance`; once `get_used()` passes
+    /// it, searches are refused like an exhausted pool. `None` (the
+    /// default, and host tests) disables the CPU check.
+    cpu_allowance: Option<f64>,
     /// Inter-room route cache (ephemeral — survives within a VM
     /// lifecycle, not across resets; entries lazily populated, TTL'd).
     routes: HashMap<(RoomName, RoomName), CachedRoute>,
+    /// The rooms each cached route passes through, destination last
+    /// (same keys and lifetime as `routes`).
+    route_rooms: HashMap<(RoomName, RoomName), Vec<RoomName>>,
+    /// Scouted room class and exit tiles, kept current by
+    /// `UpdateRoomDataSystem` and read by the route callback.
+    room_intel: HashMap<RoomName, RouteIntel>,
+    /// Same-room path lengths by (from, to, range), with the tick each was
+    /// searched (ephemeral like `routes`; `None` = no path).
+    path_lengths: HashMap<(Position, Position, u32), (Option<u32>, u32)>,
 }
 
 impl Default for PathfinderService {
@@ -101,7 +186,11 @@ impl Default for PathfinderService {
             pool: BASE_MISSION_OPS,
             remaining: BASE_MISSION_OPS,
             denied: 0,
+            cpu_allowance: None,
             routes: HashMap::new(),
+            route_rooms: HashMap::new(),
+            room_intel: HashMap::new(),
+            path_lengths: HashMap::new(),
         }
     }
 }
@@ -118,10 +207,27 @@ impl PathfinderService {
         self.denied = 0;
     }
 
+    /// Arm the tick's CPU allowance (`metrics::tick_start`, after the
+    /// `CpuBudget` is computed).
+    pub fn set_cpu_allowance(&mut self, allowance: Option<f64>) {
+        self.cpu_allowance = allowance;
+    }
+
+    /// Whether the tick has already spent its CPU allowance.
+    fn cpu_exhausted(&self) -> bool {
+        self.cpu_allowance
+            .map(|allowance| game::cpu::get_used() >= allowance)
+            .unwrap_or(false)
+    }
+
     /// Take up to `want` ops from the pool; returns the grant (0 when
-    /// the pool is exhausted — callers degrade like a capped-out
-    /// search).
+    /// the pool is exhausted or the tick's CPU allowance is spent —
+    /// callers degrade like a capped-out search).
     pub fn take_ops(&mut self, want: u32) -> u32 {
+        if self.cpu_exhausted() {
+            self.denied += 1;
+            return 0;
+        }
         let grant = want.min(self.remaining);
         if grant == 0 {
             self.denied += 1;
@@ -169,6 +275,74 @@ impl PathfinderService {
             .map(|(_, candidate)| candidate)
     }
 
+    /// Same-room path length from `from` to within `range` of `to`, cached
+    /// for [`PATH_LENGTH_TTL`] ticks — the source behind `findnearest`'s
+    /// `Distance::Path`. `None` for a target in another room, one with no
+    /// path, or a miss the pool can't pay for; callers fall back to range.
+    pub fn cached_path_length(&mut self, from: Position, to: Position, range: u32, current_tick: u32) -> Option<u32> {
+        if from.room_name() != to.room_name() {
+            return None;
+        }
+
+        let key = (from, to, range);
+
+        if let Some((length, cached_at)) = self.path_lengths.get(&key) {
+            if current_tick.saturating_sub(*cached_at) <= PATH_LENGTH_TTL {
+                return *length;
+            }
+        }
+
+        let ops = self.take_ops(SAME_ROOM_MAX_OPS);
+        if ops == 0 {
+            return None;
+        }
+
+        // Same search as `nearest_by_path`: structures and creeps uncosted.
+        let options = pathfinder::SearchOptions::default().max_rooms(1).max_ops(ops);
+        let result = pathfinder::search(from, to, range, Some(options));
+        let length = if result.incomplete() {
+            None
+        } else {
+            Some(result.path().len() as u32)
+        };
+
+        if self.path_lengths.len() >= PATH_LENGTH_CACHE_CAP {
+            self.path_lengths
+                .retain(|_, (_, cached_at)| current_tick.saturating_sub(*cached_at) <= PATH_LENGTH_TTL);
+
+            if self.path_lengths.len() >= PATH_LENGTH_CACHE_CAP {
+                self.path_lengths.clear();
+            }
+        }
+
+        self.path_lengths.insert(key, (length, current_tick));
+
+        length
+    }
+
+    /// A complete path to lay road along, from `from` to within `range` of
+    /// `to` across rooms, preferring plains over swamps (a swamp road
+    /// costs five times as much to build). Structures and existing roads are not
+    /// costed, as in [`Self::nearest_by_path`]. `None` when the pool is
+    /// exhausted or the search did not complete.
+    pub fn road_path(&mut self, from: Position, to: Position, range: u32) -> Option<Vec<Position>> {
+        let ops = self.take_ops(ROAD_PATH_MAX_OPS);
+        if ops == 0 {
+            return None;
+        }
+        let options = pathfinder::SearchOptions::default()
+            .plain_cost(2)
+            .swamp_cost(10)
+            .max_rooms(8)
+            .max_ops(ops);
+        let result = pathfinder::search(from, to, range, Some(options));
+        if result.incomplete() {
+            None
+        } else {
+            Some(result.path())
+        }
+    }
+
     /// Cached inter-room route distance, computing on miss.
     ///
     /// Bucket-guarded (P1.B1 / ADR 0004 step 1): under a Critical tier,
@@ -190,14 +364,25 @@ impl PathfinderService {
         if should_recompute_route(missing, expired, self.tier) {
             let granted = self.take_ops(FIND_ROUTE_NOMINAL_OPS);
             if missing || granted > 0 {
-                let route = Self::compute_route(from, to, current_tick);
+                let (route, rooms) = Self::compute_route(&self.room_intel, from, to, current_tick);
                 self.routes.insert((from, to), route);
+                self.route_rooms.insert((from, to), rooms);
             }
         }
 
         *self.routes.get(&(from, to)).expect("route entry just ensured")
     }
 
+    /// Record what scouting knows about `room_name` for route costs.
+    pub fn note_room(&mut self, room_name: RoomName, intel: RouteIntel) {
+        self.room_intel.insert(room_name, intel);
+    }
+
+    /// Whether any room has been noted yet this VM.
+    pub fn has_room_intel(&self) -> bool {
+        !self.room_intel.is_empty()
+    }
+
     /// Convenience: estimated travel ticks, or None if unreachable.
     pub fn travel_ticks(&mut self, from: RoomName, to: RoomName, current_tick: u32) -> Option<u32> {
         let entry = self.route_distance(from, to, current_tick);
@@ -208,18 +393,34 @@ impl PathfinderService {
         }
     }
 
-    fn compute_route(from: RoomName, to: RoomName, tick: u32) -> CachedRoute {
+    /// The rooms the cached route from `from` to `to` passes through,
+    /// destination last (empty when unreachable or `from == to`).
+    /// Computed and refreshed like [`Self::route_distance`].
+    pub fn route_rooms(&mut self, from: RoomName, to: RoomName, current_tick: u32) -> &[RoomName] {
+        self.route_distance(from, to, current_tick);
+
+        self.route_rooms.get(&(from, to)).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    fn compute_route(room_intel: &HashMap<RoomName, RouteIntel>, from: RoomName, to: RoomName, tick: u32) -> (CachedRoute, Vec<RoomName>) {
         if from == to {
-            return CachedRoute {
+            let route = CachedRoute {
                 hops: 0,
                 travel_ticks: 0,
                 cached_at: tick,
                 reachable: true,
             };
+            return (route, Vec::new());
         }
 
         // Use find_route with a room cost callback that avoids hostile rooms.
-        let options = game::map::FindRouteOptions::new().room_callback(|room_name, _from_room| {
+        let options = game::map::FindRouteOptions::new().room_callback(|room_name, from_room| {
+            // A walled-off edge can't be crossed whatever the room beyond.
+            let scouted = scouted_room_cost(room_intel, room_name, from_room);
+            if scouted == Some(f64::INFINITY) {
+                return f64::INFINITY;
+            }
+
             // High cost for hostile rooms, normal for others.
             // Closed rooms are handled internally by find_route.
             if let Some(room) = game::rooms().get(room_name) {
@@ -236,26 +437,30 @@ impl PathfinderService {
                     }
                 }
             }
-            // Default cost for unknown/neutral rooms.
-            2.0
+            // Scouted class cost, else the default for unknown/neutral rooms.
+            scouted.unwrap_or(DEFAULT_ROOM_COST)
         });
 
         match game::map::find_route(from, to, Some(options)) {
             Ok(steps) => {
                 let hops = steps.len() as u32;
-                CachedRoute {
+                let route = CachedRoute {
                     hops,
                     travel_ticks: hops * 50,
                     cached_at: tick,
                     reachable: true,
-                }
+                };
+                (route, steps.iter().map(|step| step.room).collect())
+            }
+            Err(_) => {
+                let route = CachedRoute {
+                    hops: u32::MAX,
+                    travel_ticks: u32::MAX,
+                    cached_at: tick,
+                    reachable: false,
+                };
+                (route, Vec::new())
             }
-            Err(_) => CachedRoute {
-                hops: u32::MAX,
-                travel_ticks: u32::MAX,
-                cached_at: tick,
-                reachable: false,
-            },
         }
     }
 }
@@ -320,4 +525,29 @@ mod tests {
         assert!(should_recompute_route(false, true, Tier::Conserve));
         assert!(!should_recompute_route(false, true, Tier::Critical));
     }
+
+    #[test]
+    fn scouted_class_prices_rooms_and_unknown_edges_stay_open() {
+        let room = |name| RoomName::new(name).unwrap();
+        assert_eq!(edge_toward(room("E3N3"), room("E3N4")), Some(Direction::Top));
+        assert_eq!(edge_toward(room("E0N3"), room("W0N3")), Some(Direction::Left));
+        assert_eq!(edge_toward(room("E3N0"), room("E3S0")), Some(Direction::Bottom));
+        assert_eq!(edge_toward(room("E3N3"), room("E5N3")), None);
+
+        let mut intel = HashMap::new();
+        for (name, class) in [
+            ("E4N5", RoomClass::SourceKeeper),
+            ("E3N0", RoomClass::Highway),
+            ("E3N3", RoomClass::Normal),
+        ] {
+            intel.insert(room(name), RouteIntel { class, exits: None });
+        }
+
+        assert_eq!(scouted_room_cost(&intel, room("E4N5"), room("E3N5")), Some(10.0));
+        assert_eq!(scouted_room_cost(&intel, room("E3N0"), room("E3N1")), Some(1.5));
+        // Normal and unscouted rooms fall through to the live/default costs,
+        // and a room without recorded exit tiles never seals an edge.
+        assert_eq!(scouted_room_cost(&intel, room("E3N4"), room("E3N3")), None);
+        assert_eq!(scouted_room_cost(&intel, room("E3N3"), room("E9N9")), None);
+    }
 }
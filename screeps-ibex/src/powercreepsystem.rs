@@ -0,0 +1,494 @@
+//! Operator power creeps.
+//!
+//! The GPL the power spawns earn (see `missions::powerspawn`) is spent here,
+//! one level a tick: a free level creates an operator while a room with a
+//! power spawn has none, and otherwise upgrades the lowest operator along
+//! [`UPGRADE_ORDER`]. An unspawned operator spawns at a power spawn in a room
+//! no other operator serves, and a live one serves the room it stands in:
+//!
+//! - renews at the room's power spawn before its TTL runs low,
+//! - enables power on the room's controller,
+//! - uses the most urgent power asked for in the room this tick (see
+//!   [`PowerRequests`]), fetching ops first when it carries too few,
+//! - otherwise generates ops, and unloads them once its store fills.
+//!
+//! Ops are kept in storage and the terminal. The operator picks them up from,
+//! and drops them off at, the withdrawals and deposits those structures post
+//! to the transfer queue, so terminal balancing moves them like any other
+//! resource.
+//!
+//! Missions own the question of when a power is worth its ops: the tower
+//! mission asks for `operate_tower` in a fight, room transfer for
+//! `operate_extension` when the extensions run dry, and the spawn queue for
+//! `operate_spawn` when requests back up behind busy spawns.
+
+use crate::entitymappingsystem::EntityMappingData;
+use crate::intents::{IntentCategory, IntentRecorder};
+use crate::room::data::*;
+use crate::structureidentifier::RemoteStructureIdentifier;
+use crate::transfer::transfersystem::*;
+use log::*;
+use screeps::*;
+use specs::prelude::*;
+use std::collections::HashMap;
+
+/// Powers an operator learns, in the order levels are spent on them.
+pub const UPGRADE_ORDER: [PowerType; 4] = [
+    PowerType::GenerateOps,
+    PowerType::OperateExtension,
+    PowerType::OperateSpawn,
+    PowerType::OperateTower,
+];
+
+/// Creep level each power level requires (`POWER_INFO[power].level`, the same
+/// for every operator power used here).
+const POWER_LEVEL_REQUIREMENTS: [u32; 5] = [0, 2, 7, 14, 22];
+
+const POWER_CREEP_MAX_LEVEL: u32 = 25;
+
+/// Range of the operate powers.
+const POWER_RANGE: u32 = 3;
+
+/// Renew below this many ticks to live (a power creep lives 5000).
+const RENEW_TTL: u32 = 1_000;
+
+/// Ops an operator keeps on hand when it unloads.
+const OPS_KEEP: u32 = 200;
+
+/// How urgently a mission wants a power used. Requests are served highest
+/// first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PowerPriority {
+    Low,
+    Medium,
+    High,
+}
+
+/// One power a mission wants used on a structure this tick.
+#[derive(Clone, Copy)]
+pub struct PowerRequest {
+    room: RoomName,
+    power: PowerType,
+    target: RemoteStructureIdentifier,
+    priority: PowerPriority,
+}
+
+impl PowerRequest {
+    pub fn new(room: RoomName, power: PowerType, target: RemoteStructureIdentifier, priority: PowerPriority) -> PowerRequest {
+        PowerRequest {
+            room,
+            power,
+            target,
+            priority,
+        }
+    }
+}
+
+/// Powers asked for this tick. Filled by missions and the spawn queue, and
+/// drained by [`PowerCreepSystem`], which runs after both.
+#[derive(Default)]
+pub struct PowerRequests {
+    requests: Vec<PowerRequest>,
+}
+
+impl PowerRequests {
+    pub fn request(&mut self, request: PowerRequest) {
+        self.requests.push(request);
+    }
+
+    /// The room's requests, most urgent first.
+    fn for_room(&self, room: RoomName) -> Vec<PowerRequest> {
+        let mut requests: Vec<_> = self.requests.iter().filter(|r| r.room == room).copied().collect();
+        requests.sort_by(|a, b| b.priority.cmp(&a.priority));
+        requests
+    }
+}
+
+/// Whether `object` is already under `power`'s effect, so asking again would
+/// waste ops.
+pub fn has_power_effect(object: &RoomObject, power: PowerType) -> bool {
+    object
+        .effects()
+        .iter()
+        .any(|effect| matches!(effect.effect(), EffectType::PowerEffect(active) if active == power))
+}
+
+/// Ops one use of `power` costs.
+fn ops_cost(power: PowerType) -> u32 {
+    match power {
+        PowerType::OperateSpawn => 100,
+        PowerType::OperatePower => 200,
+        PowerType::OperateTower => 10,
+        PowerType::OperateExtension => 2,
+        _ => 0,
+    }
+}
+
+/// GPL levels not yet spent: each operator costs one to create and one per
+/// level.
+fn free_gpl_levels(gpl: u32, creep_levels: impl Iterator<Item = u32>) -> u32 {
+    gpl.saturating_sub(creep_levels.map(|level| level + 1).sum())
+}
+
+/// The power an operator at `creep_level` should learn next: the lowest one in
+/// [`UPGRADE_ORDER`] whose next level it qualifies for, earlier powers first.
+fn next_upgrade(creep_level: u32, power_level: impl Fn(PowerType) -> u32) -> Option<PowerType> {
+    if creep_level >= POWER_CREEP_MAX_LEVEL {
+        return None;
+    }
+
+    UPGRADE_ORDER
+        .iter()
+        .copied()
+        .filter(|power| {
+            POWER_LEVEL_REQUIREMENTS
+                .get(power_level(*power) as usize)
+                .is_some_and(|required| creep_level >= *required)
+        })
+        .min_by_key(|power| power_level(*power))
+}
+
+fn ops_store_withdraw(operator: &PowerCreep, target: &TransferTarget, amount: u32) -> Result<(), ErrorCode> {
+    match target {
+        TransferTarget::Storage(id) => id
+            .resolve()
+            .ok_or(ErrorCode::InvalidTarget)
+            .and_then(|storage| operator.withdraw(&storage, ResourceType::Ops, Some(amount))),
+        TransferTarget::Terminal(id) => id
+            .resolve()
+            .ok_or(ErrorCode::InvalidTarget)
+            .and_then(|terminal| operator.withdraw(&terminal, ResourceType::Ops, Some(amount))),
+        _ => Err(ErrorCode::InvalidTarget),
+    }
+}
+
+fn ops_store_transfer(operator: &PowerCreep, target: &TransferTarget, amount: u32) -> Result<(), ErrorCode> {
+    match target {
+        TransferTarget::Storage(id) => id
+            .resolve()
+            .ok_or(ErrorCode::InvalidTarget)
+            .and_then(|storage| operator.transfer(&storage, ResourceType::Ops, Some(amount))),
+        TransferTarget::Terminal(id) => id
+            .resolve()
+            .ok_or(ErrorCode::InvalidTarget)
+            .and_then(|terminal| operator.transfer(&terminal, ResourceType::Ops, Some(amount))),
+        _ => Err(ErrorCode::InvalidTarget),
+    }
+}
+
+fn is_ops_store(target: &TransferTarget) -> bool {
+    matches!(target, TransferTarget::Storage(_) | TransferTarget::Terminal(_))
+}
+
+#[derive(SystemData)]
+pub struct PowerCreepSystemData<'a> {
+    room_data: ReadStorage<'a, RoomData>,
+    mapping: Read<'a, EntityMappingData>,
+    transfer_queue: Write<'a, TransferQueue>,
+    power_requests: Write<'a, PowerRequests>,
+    intents: Write<'a, IntentRecorder>,
+    features: Read<'a, crate::features::Features>,
+}
+
+/// Creates, levels, spawns and runs operator power creeps.
+pub struct PowerCreepSystem;
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+impl PowerCreepSystem {
+    /// Owned rooms with a power spawn to field an operator from.
+    fn power_spawn_rooms(data: &PowerCreepSystemData) -> Vec<(RoomName, StructurePowerSpawn)> {
+        (&data.room_data)
+            .join()
+            .filter(|room_data| {
+                room_data
+                    .get_dynamic_visibility_data()
+                    .is_some_and(|dynamic| dynamic.owner().mine())
+            })
+            .filter_map(|room_data| {
+                let structures = room_data.get_structures()?;
+                let power_spawn = structures.power_spawns().iter().find(|s| s.my() && s.is_active())?;
+
+                Some((room_data.name, power_spawn.clone()))
+            })
+            .collect()
+    }
+
+    /// Spend one free GPL level: create an operator while a power spawn room
+    /// is unserved, else upgrade the lowest-level operator.
+    fn spend_gpl(accounts: &[AccountPowerCreep], power_spawn_rooms: usize) {
+        let free = free_gpl_levels(game::gpl::level(), accounts.iter().map(|account| account.level()));
+
+        if free == 0 {
+            return;
+        }
+
+        if accounts.len() < power_spawn_rooms {
+            let name = format!("operator-{}", game::time());
+
+            match AccountPowerCreep::create(&name.as_str().into(), PowerCreepClass::Operator) {
+                Ok(()) => info!("[PowerCreep] Created operator {}", name),
+                Err(err) => warn!("[PowerCreep] Failed to create operator {}: {:?}", name, err),
+            }
+
+            return;
+        }
+
+        let Some(account) = accounts.iter().min_by_key(|account| account.level()) else {
+            return;
+        };
+
+        let powers = account.powers();
+        let power_level = |power: PowerType| powers.get(power).map(|info| info.level() as u32).unwrap_or(0);
+
+        if let Some(power) = next_upgrade(account.level(), power_level) {
+            match account.upgrade(power) {
+                Ok(()) => info!("[PowerCreep] Upgraded {} with {:?}", account.name(), power),
+                Err(err) => warn!("[PowerCreep] Failed to upgrade {} with {:?}: {:?}", account.name(), power, err),
+            }
+        }
+    }
+
+    /// Walk toward `pos` unless already within `range`. Returns whether the
+    /// operator is in range.
+    fn approach(operator: &PowerCreep, pos: Position, range: u32, intents: &mut IntentRecorder) -> bool {
+        if operator.pos().in_range_to(pos, range) {
+            return true;
+        }
+
+        let _ = intents.issued(IntentCategory::Move, operator.move_to(pos));
+
+        false
+    }
+
+    /// Fetch at least `amount` ops from a storage or terminal withdrawal.
+    fn fetch_ops(data: &mut PowerCreepSystemData, operator: &PowerCreep, room: RoomName, amount: u32) {
+        let transfer_data = TransferQueueGeneratorData {
+            cause: "Power Creep",
+            room_data: &data.room_data,
+        };
+
+        let free = operator.store().get_free_capacity(Some(ResourceType::Ops)).max(0) as u32;
+        let desired = HashMap::from([(Some(ResourceType::Ops), free)]);
+
+        let ticket = data
+            .transfer_queue
+            .select_pickups(
+                &transfer_data,
+                &[room],
+                TransferPriorityFlags::ALL,
+                TransferTypeFlags::HAUL | TransferTypeFlags::TERMINAL,
+                &desired,
+                TransferCapacity::Finite(free),
+                false,
+            )
+            .into_iter()
+            .filter(|ticket| is_ops_store(ticket.target()))
+            .filter(|ticket| ticket.get_next_withdrawl().is_some_and(|(_, available)| available >= amount))
+            .min_by_key(|ticket| operator.pos().get_range_to(Position::from(ticket.target().pos())));
+
+        let Some(ticket) = ticket else {
+            return;
+        };
+
+        data.transfer_queue.register_pickup(&ticket);
+
+        if Self::approach(operator, ticket.target().pos().into(), 1, &mut data.intents) {
+            let (_, available) = ticket.get_next_withdrawl().unwrap_or((ResourceType::Ops, amount));
+            let _ = data.intents.issued(
+                IntentCategory::Transfer,
+                ops_store_withdraw(operator, ticket.target(), available.min(free)),
+            );
+        }
+    }
+
+    /// Drop ops above [`OPS_KEEP`] at a storage or terminal deposit.
+    fn unload_ops(data: &mut PowerCreepSystemData, operator: &PowerCreep, room: RoomName, surplus: u32) {
+        let transfer_data = TransferQueueGeneratorData {
+            cause: "Power Creep",
+            room_data: &data.room_data,
+        };
+
+        let available = HashMap::from([(ResourceType::Ops, surplus)]);
+
+        let ticket = data
+            .transfer_queue
+            .select_deliveries(
+                &transfer_data,
+                &[room],
+                TransferPriorityFlags::ALL,
+                TransferTypeFlags::HAUL | TransferTypeFlags::TERMINAL,
+                &available,
+                TransferCapacity::Finite(surplus),
+                is_ops_store,
+            )
+            .into_iter()
+            .min_by_key(|ticket| operator.pos().get_range_to(Position::from(ticket.target().pos())));
+
+        let Some(ticket) = ticket else {
+            return;
+        };
+
+        data.transfer_queue.register_delivery(&ticket);
+
+        if Self::approach(operator, ticket.target().pos().into(), 1, &mut data.intents) {
+            let _ = data
+                .intents
+                .issued(IntentCategory::Transfer, ops_store_transfer(operator, ticket.target(), surplus));
+        }
+    }
+
+    /// One tick of a spawned operator. Each step that acts ends the tick.
+    fn run_operator(data: &mut PowerCreepSystemData, operator: &PowerCreep) {
+        let Some(room) = operator.room() else {
+            return;
+        };
+        let room_name = room.name();
+
+        let power_spawn = data
+            .mapping
+            .get_room(&room_name)
+            .and_then(|entity| data.room_data.get(entity))
+            .and_then(|room_data| room_data.get_structures())
+            .and_then(|structures| structures.power_spawns().iter().find(|s| s.my()).cloned());
+
+        if let Some(power_spawn) = power_spawn.as_ref() {
+            if operator.ticks_to_live().is_some_and(|ttl| ttl < RENEW_TTL) {
+                if Self::approach(operator, power_spawn.pos(), 1, &mut data.intents) {
+                    let _ = data.intents.issued(IntentCategory::Spawn, operator.renew(power_spawn));
+                }
+                return;
+            }
+        }
+
+        if let Some(controller) = room.controller().filter(|c| c.my() && !c.is_power_enabled()) {
+            if Self::approach(operator, controller.pos(), 1, &mut data.intents) {
+                let _ = data.intents.issued(IntentCategory::Controller, operator.enable_room(&controller));
+            }
+            return;
+        }
+
+        let powers = operator.powers();
+        let ready = |power: PowerType| powers.get(power).is_some_and(|info| info.cooldown() == 0);
+        let ops = operator.store().get_used_capacity(Some(ResourceType::Ops));
+
+        let request = data
+            .power_requests
+            .for_room(room_name)
+            .into_iter()
+            .find(|request| ready(request.power));
+
+        if let Some(request) = request {
+            let cost = ops_cost(request.power);
+
+            if ops < cost {
+                Self::fetch_ops(data, operator, room_name, cost - ops);
+                return;
+            }
+
+            if Self::approach(operator, request.target.pos(), POWER_RANGE, &mut data.intents) {
+                if let Some(target) = request.target.resolve() {
+                    let object: &RoomObject = target.as_structure();
+                    let _ = data
+                        .intents
+                        .issued(IntentCategory::Structure, operator.use_power(request.power, Some(object)));
+                }
+            }
+            return;
+        }
+
+        if ready(PowerType::GenerateOps) {
+            let _ = data
+                .intents
+                .issued(IntentCategory::Structure, operator.use_power(PowerType::GenerateOps, None));
+            return;
+        }
+
+        if operator.store().get_free_capacity(Some(ResourceType::Ops)) <= 0 && ops > OPS_KEEP {
+            Self::unload_ops(data, operator, room_name, ops - OPS_KEEP);
+        }
+    }
+}
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+impl<'a> System<'a> for PowerCreepSystem {
+    type SystemData = PowerCreepSystemData<'a>;
+
+    fn run(&mut self, mut data: Self::SystemData) {
+        if data.features.power_creeps {
+            let accounts: Vec<AccountPowerCreep> = game::power_creeps()
+                .values()
+                .filter(|account| account.class() == PowerCreepClass::Operator)
+                .collect();
+
+            let power_spawn_rooms = Self::power_spawn_rooms(&data);
+
+            Self::spend_gpl(&accounts, power_spawn_rooms.len());
+
+            let mut operators = Vec::new();
+            let mut idle = Vec::new();
+
+            for account in accounts {
+                match PowerCreep::try_from(account) {
+                    Ok(operator) => operators.push(operator),
+                    Err(account) => idle.push(account),
+                }
+            }
+
+            let mut unserved = power_spawn_rooms
+                .into_iter()
+                .filter(|(room, _)| !operators.iter().any(|operator| operator.room().is_some_and(|r| r.name() == *room)));
+
+            for account in idle {
+                let Some((room, power_spawn)) = unserved.next() else {
+                    break;
+                };
+
+                // Fails quietly while the account creep's spawn cooldown runs.
+                if account.spawn(&power_spawn).is_ok() {
+                    info!("[PowerCreep] Spawning {} in {}", account.name(), room);
+                }
+            }
+
+            for operator in &operators {
+                Self::run_operator(&mut data, operator);
+            }
+        }
+
+        data.power_requests.requests.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_operator_costs_a_level_plus_its_levels() {
+        assert_eq!(free_gpl_levels(0, std::iter::empty()), 0);
+        assert_eq!(free_gpl_levels(3, std::iter::empty()), 3);
+        assert_eq!(free_gpl_levels(3, [0].into_iter()), 2);
+        assert_eq!(free_gpl_levels(5, [2, 1].into_iter()), 0);
+        assert_eq!(free_gpl_levels(2, [4].into_iter()), 0);
+    }
+
+    #[test]
+    fn upgrades_spread_across_powers_in_order() {
+        let levels =
+            |levels: [u32; 4]| move |power: PowerType| UPGRADE_ORDER.iter().position(|p| *p == power).map(|i| levels[i]).unwrap_or(0);
+
+        assert_eq!(next_upgrade(0, levels([0, 0, 0, 0])), Some(PowerType::GenerateOps));
+        assert_eq!(next_upgrade(1, levels([1, 0, 0, 0])), Some(PowerType::OperateExtension));
+        assert_eq!(next_upgrade(4, levels([1, 1, 1, 1])), Some(PowerType::GenerateOps));
+    }
+
+    #[test]
+    fn upgrades_wait_for_the_creep_level_requirement() {
+        let all_at = |level: u32| move |_: PowerType| level;
+
+        // Second power levels need creep level 2.
+        assert_eq!(next_upgrade(1, all_at(1)), None);
+        assert_eq!(next_upgrade(2, all_at(1)), Some(PowerType::GenerateOps));
+        assert_eq!(next_upgrade(20, all_at(5)), None);
+        assert_eq!(next_upgrade(POWER_CREEP_MAX_LEVEL, all_at(0)), None);
+    }
+}
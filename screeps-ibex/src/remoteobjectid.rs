@@ -38,8 +38,71 @@ impl<T> RemoteObjectId<T> {
     {
         self.id.resolve()
     }
+
+    /// Resolve the structure, or if it is gone from a visible room, the single structure of the same
+    /// type standing at the stored position. A container that decays and is rebuilt on the same tile
+    /// comes back under a new id; the second value carries that id so the caller can store it.
+    pub fn resolve_or_rebuilt(self) -> Option<(T, Option<RemoteObjectId<T>>)>
+    where
+        T: RebuildableStructure + MaybeHasId + HasId + HasPosition + JsCast,
+    {
+        if let Some(obj) = self.resolve() {
+            return Some((obj, None));
+        }
+
+        let room = game::rooms().get(self.position.room_name())?;
+
+        let mut rebuilt = room
+            .look_for_at(look::STRUCTURES, &self.position)
+            .into_iter()
+            .filter_map(T::from_structure);
+
+        match (rebuilt.next(), rebuilt.next()) {
+            (Some(obj), None) => {
+                let id = RemoteObjectId::new(&obj);
+
+                Some((obj, Some(id)))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Structure types a stale `RemoteObjectId` can be re-found for by position
+/// (see `RemoteObjectId::resolve_or_rebuilt`).
+pub trait RebuildableStructure: Sized {
+    fn from_structure(structure: StructureObject) -> Option<Self>;
 }
 
+macro_rules! rebuildable_structures {
+    ($($structure:ident),*) => {
+        $(
+            impl RebuildableStructure for $structure {
+                fn from_structure(structure: StructureObject) -> Option<Self> {
+                    match structure {
+                        StructureObject::$structure(structure) => Some(structure),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+rebuildable_structures!(
+    StructureContainer,
+    StructureSpawn,
+    StructureExtension,
+    StructureStorage,
+    StructureTower,
+    StructureLink,
+    StructureTerminal,
+    StructureLab,
+    StructureFactory,
+    StructureNuker,
+    StructurePowerSpawn
+);
+
 impl<T> std::fmt::Debug for RemoteObjectId<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "({:?}, {:?})", self.id, self.position)
@@ -0,0 +1,270 @@
+//! What the empire owns, and what it is short of.
+//!
+//! Every [`RESOURCE_OVERVIEW_INTERVAL`] ticks [`ResourceOverviewSystem`]
+//! sums the storage, terminal and factory stores of every owned room, per
+//! room and in total, and measures the totals against the operator's
+//! targets (`Memory._features.resources.targets`, e.g. `{ XGHO2: 10000 }`).
+//!
+//! The deficits feed the labs' production goals (`missions::labs`) and,
+//! with `features.market.buy_deficits`, the market purchases of the room
+//! with the most free terminal space (`missions::terminal`). The
+//! `resources` console command logs the table, and the stats export
+//! carries it for dashboards.
+
+use crate::features::ResourceFeatures;
+use crate::room::data::*;
+use log::*;
+use screeps::*;
+use specs::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+
+/// Ticks between recounts of the empire's stores.
+pub const RESOURCE_OVERVIEW_INTERVAL: u32 = 100;
+
+/// Runtime resource: the last count of the empire's stores.
+#[derive(Default)]
+pub struct ResourceOverview {
+    rooms: BTreeMap<RoomName, HashMap<ResourceType, u32>>,
+    totals: HashMap<ResourceType, u32>,
+    targets: HashMap<ResourceType, u32>,
+    /// Shortfalls against the targets, largest first.
+    deficits: Vec<(ResourceType, u32)>,
+    /// The room purchases for the deficits land in.
+    buyer: Option<RoomName>,
+    updated_at: Option<u32>,
+    /// Recount and log the table on the next run (the `resources` command).
+    report_requested: bool,
+}
+
+impl ResourceOverview {
+    pub fn needs_update(&self, now: u32) -> bool {
+        self.report_requested
+            || self
+                .updated_at
+                .map(|updated_at| now.saturating_sub(updated_at) >= RESOURCE_OVERVIEW_INTERVAL)
+                .unwrap_or(true)
+    }
+
+    /// Replace the count with `rooms`' holdings, measured against `targets`.
+    pub fn update(
+        &mut self,
+        rooms: BTreeMap<RoomName, HashMap<ResourceType, u32>>,
+        targets: &HashMap<ResourceType, u32>,
+        buyer: Option<RoomName>,
+        now: u32,
+    ) {
+        let mut totals: HashMap<ResourceType, u32> = HashMap::new();
+
+        for (resource, amount) in rooms.values().flatten() {
+            *totals.entry(*resource).or_insert(0) += amount;
+        }
+
+        let mut deficits: Vec<_> = targets
+            .iter()
+            .map(|(resource, target)| (*resource, target.saturating_sub(totals.get(resource).copied().unwrap_or(0))))
+            .filter(|(_, deficit)| *deficit > 0)
+            .collect();
+
+        deficits.sort_by_cached_key(|(resource, deficit)| (std::cmp::Reverse(*deficit), format!("{:?}", resource)));
+
+        self.rooms = rooms;
+        self.totals = totals;
+        self.targets = targets.clone();
+        self.deficits = deficits;
+        self.buyer = buyer;
+        self.updated_at = Some(now);
+    }
+
+    pub fn request_report(&mut self) {
+        self.report_requested = true;
+    }
+
+    /// Whether a report was requested, clearing the request.
+    pub fn take_report_request(&mut self) -> bool {
+        std::mem::take(&mut self.report_requested)
+    }
+
+    pub fn rooms(&self) -> &BTreeMap<RoomName, HashMap<ResourceType, u32>> {
+        &self.rooms
+    }
+
+    pub fn totals(&self) -> &HashMap<ResourceType, u32> {
+        &self.totals
+    }
+
+    pub fn deficits(&self) -> &[(ResourceType, u32)] {
+        &self.deficits
+    }
+
+    /// The deficits `room` should buy: all of them for the buyer room, none
+    /// for the others, so one shortfall isn't bought once per terminal.
+    pub fn purchases(&self, room: RoomName) -> &[(ResourceType, u32)] {
+        if self.buyer == Some(room) {
+            &self.deficits
+        } else {
+            &[]
+        }
+    }
+
+    /// The overview as log lines: one per resource held or targeted, with
+    /// its total, target, deficit and the rooms holding it.
+    pub fn report(&self) -> Vec<String> {
+        let mut resources: Vec<ResourceType> = self.totals.keys().chain(self.targets.keys()).copied().collect();
+
+        resources.sort_by_cached_key(|resource| format!("{:?}", resource));
+        resources.dedup();
+
+        let mut lines = vec![format!(
+            "{:<28} {:>10} {:>10} {:>10}  rooms",
+            "resource", "total", "target", "deficit"
+        )];
+
+        for resource in resources {
+            let total = self.totals.get(&resource).copied().unwrap_or(0);
+            let target = self.targets.get(&resource).copied();
+            let deficit = target.map(|target| target.saturating_sub(total)).unwrap_or(0);
+
+            let holders: Vec<String> = self
+                .rooms
+                .iter()
+                .filter_map(|(room, held)| {
+                    held.get(&resource)
+                        .filter(|amount| **amount > 0)
+                        .map(|amount| format!("{}={}", room, amount))
+                })
+                .collect();
+
+            lines.push(format!(
+                "{:<28} {:>10} {:>10} {:>10}  {}",
+                format!("{:?}", resource),
+                total,
+                target.map(|target| target.to_string()).unwrap_or_else(|| "-".to_string()),
+                deficit,
+                holders.join(" ")
+            ));
+        }
+
+        lines
+    }
+}
+
+/// Recounts the empire's stores on the overview's interval, or at once when
+/// the `resources` command asks, which it then answers in the log.
+pub struct ResourceOverviewSystem;
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+impl<'a> System<'a> for ResourceOverviewSystem {
+    type SystemData = (ReadStorage<'a, RoomData>, Read<'a, ResourceFeatures>, Write<'a, ResourceOverview>);
+
+    fn run(&mut self, (room_data, resources, mut overview): Self::SystemData) {
+        let now = game::time();
+
+        if !overview.needs_update(now) {
+            return;
+        }
+
+        let mut rooms = BTreeMap::new();
+        let mut buyer: Option<(RoomName, i32)> = None;
+
+        for room in room_data.join() {
+            if !room.get_dynamic_visibility_data().map(|d| d.owner().mine()).unwrap_or(false) {
+                continue;
+            }
+
+            let Some(structures) = room.get_structures() else {
+                continue;
+            };
+
+            let stores = structures
+                .storages()
+                .iter()
+                .filter(|s| s.my())
+                .map(|s| s.store())
+                .chain(structures.terminals().iter().filter(|t| t.my()).map(|t| t.store()))
+                .chain(structures.factories().iter().filter(|f| f.my()).map(|f| f.store()));
+
+            let mut held: HashMap<ResourceType, u32> = HashMap::new();
+
+            for store in stores {
+                for resource in store.store_types() {
+                    *held.entry(resource).or_insert(0) += store.get_used_capacity(Some(resource));
+                }
+            }
+
+            if let Some(terminal) = structures.terminals().iter().find(|t| t.my()) {
+                let free = terminal.store().get_free_capacity(None);
+
+                if buyer.map(|(_, most)| free > most).unwrap_or(true) {
+                    buyer = Some((room.name, free));
+                }
+            }
+
+            rooms.insert(room.name, held);
+        }
+
+        overview.update(rooms, &resources.targets, buyer.map(|(room, _)| room), now);
+
+        if overview.take_report_request() {
+            for line in overview.report() {
+                info!("[Resources] {}", line);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn room(name: &str) -> RoomName {
+        name.parse().expect("valid room name")
+    }
+
+    #[test]
+    fn deficits_measure_the_empire_total_against_each_target() {
+        let rooms = BTreeMap::from([
+            (
+                room("W1N1"),
+                HashMap::from([(ResourceType::CatalyzedGhodiumAlkalide, 4_000), (ResourceType::Energy, 300_000)]),
+            ),
+            (room("W2N1"), HashMap::from([(ResourceType::CatalyzedGhodiumAlkalide, 1_000)])),
+        ]);
+        let targets = HashMap::from([
+            (ResourceType::CatalyzedGhodiumAlkalide, 10_000),
+            (ResourceType::CatalyzedUtriumAcid, 3_000),
+            (ResourceType::Energy, 100_000),
+        ]);
+
+        let mut overview = ResourceOverview::default();
+        overview.update(rooms, &targets, Some(room("W2N1")), 100);
+
+        assert_eq!(overview.totals()[&ResourceType::CatalyzedGhodiumAlkalide], 5_000);
+        assert_eq!(
+            overview.deficits(),
+            &[
+                (ResourceType::CatalyzedGhodiumAlkalide, 5_000),
+                (ResourceType::CatalyzedUtriumAcid, 3_000)
+            ]
+        );
+        assert_eq!(overview.purchases(room("W2N1")).len(), 2);
+        assert!(overview.purchases(room("W1N1")).is_empty());
+
+        assert!(!overview.needs_update(100 + RESOURCE_OVERVIEW_INTERVAL - 1));
+        assert!(overview.needs_update(100 + RESOURCE_OVERVIEW_INTERVAL));
+    }
+
+    #[test]
+    fn report_lists_held_and_targeted_resources_with_their_holders() {
+        let rooms = BTreeMap::from([(room("W1N1"), HashMap::from([(ResourceType::Oxygen, 2_500)]))]);
+        let targets = HashMap::from([(ResourceType::Hydrogen, 1_000)]);
+
+        let mut overview = ResourceOverview::default();
+        overview.update(rooms, &targets, None, 0);
+
+        let report = overview.report();
+
+        assert_eq!(report.len(), 3);
+        assert!(report[1].starts_with("Hydrogen") && report[1].trim_end().ends_with("1000"));
+        assert!(report[2].starts_with("Oxygen") && report[2].ends_with("W1N1=2500"));
+    }
+}
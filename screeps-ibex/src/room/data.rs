@@ -1,5 +1,8 @@
+use super::hostilesummary::*;
+use super::visibilitysystem::{categories_overdue, VisibilityCategory, VisibilityCategoryFlags};
 use crate::remoteobjectid::*;
 use crate::serialize::EntityVec;
+use crate::structureidentifier::find_known_structures;
 use screeps::*;
 use screeps_cache::*;
 use screeps_foreman::constants::*;
@@ -69,6 +72,104 @@ impl RoomTerrainStatistics {
     }
 }
 
+/// What kind of room this is on the world map. Highway and center rooms
+/// follow from the name alone; a Source Keeper room is confirmed by the
+/// lairs a scout saw.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RoomClass {
+    #[serde(rename = "n")]
+    Normal,
+    #[serde(rename = "h")]
+    Highway,
+    #[serde(rename = "c")]
+    Center,
+    #[serde(rename = "k")]
+    SourceKeeper,
+}
+
+impl RoomClass {
+    pub fn classify(room_name: RoomName, has_keeper_lairs: bool) -> RoomClass {
+        // Offset within the 10x10 sector; W0/S0 sit at -1.
+        let sector_offset = |coord: i32| if coord < 0 { (-coord - 1) % 10 } else { coord % 10 };
+        let x = sector_offset(room_name.x_coord());
+        let y = sector_offset(room_name.y_coord());
+
+        if x == 0 || y == 0 {
+            RoomClass::Highway
+        } else if x == 5 && y == 5 {
+            RoomClass::Center
+        } else if has_keeper_lairs {
+            RoomClass::SourceKeeper
+        } else {
+            RoomClass::Normal
+        }
+    }
+
+    /// Only a normal room carries a controller a claimer can take.
+    pub fn claimable(self) -> bool {
+        self == RoomClass::Normal
+    }
+}
+
+/// Walkable tiles along each room edge. An edge with none is walled off by
+/// terrain and can't be crossed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExitTiles {
+    #[serde(rename = "t")]
+    top: u8,
+    #[serde(rename = "r")]
+    right: u8,
+    #[serde(rename = "b")]
+    bottom: u8,
+    #[serde(rename = "l")]
+    left: u8,
+}
+
+impl ExitTiles {
+    fn from_terrain(terrain: &FastRoomTerrain) -> ExitTiles {
+        let walkable = |x, y| u8::from(!terrain.get_xy(x, y).contains(TerrainFlags::WALL));
+        let mut tiles = ExitTiles {
+            top: 0,
+            right: 0,
+            bottom: 0,
+            left: 0,
+        };
+
+        for i in 0..ROOM_WIDTH {
+            tiles.top += walkable(i, 0);
+            tiles.bottom += walkable(i, ROOM_HEIGHT - 1);
+        }
+
+        for i in 0..ROOM_HEIGHT {
+            tiles.left += walkable(0, i);
+            tiles.right += walkable(ROOM_WIDTH - 1, i);
+        }
+
+        tiles
+    }
+
+    /// Walkable tiles on the edge facing `direction`; diagonals have no edge.
+    pub fn tiles(&self, direction: Direction) -> u8 {
+        match direction {
+            Direction::Top => self.top,
+            Direction::Right => self.right,
+            Direction::Bottom => self.bottom,
+            Direction::Left => self.left,
+            _ => 0,
+        }
+    }
+
+    pub fn is_open(&self, direction: Direction) -> bool {
+        self.tiles(direction) > 0
+    }
+
+    pub fn open_exits(&self) -> impl Iterator<Item = Direction> + '_ {
+        [Direction::Top, Direction::Right, Direction::Bottom, Direction::Left]
+            .into_iter()
+            .filter(|direction| self.is_open(*direction))
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct RoomStaticVisibilityData {
     #[serde(rename = "c")]
@@ -90,6 +191,12 @@ pub struct RoomStaticVisibilityData {
     /// (`RoomDynamicVisibilityData`), which only holds while we have eyes.
     #[serde(default, rename = "kl")]
     keeper_lairs: Vec<Position>,
+    #[serde(rename = "rc")]
+    room_class: RoomClass,
+    /// Walkable tiles per edge. `None` for rooms scouted before this was
+    /// recorded; filled in the next time the room is visible.
+    #[serde(default, rename = "et")]
+    exit_tiles: Option<ExitTiles>,
 }
 
 impl RoomStaticVisibilityData {
@@ -128,6 +235,20 @@ impl RoomStaticVisibilityData {
     pub fn is_source_keeper(&self) -> bool {
         !self.keeper_lairs.is_empty()
     }
+
+    pub fn room_class(&self) -> RoomClass {
+        self.room_class
+    }
+
+    pub fn exit_tiles(&self) -> Option<&ExitTiles> {
+        self.exit_tiles.as_ref()
+    }
+
+    /// Whether the edge facing `direction` can be crossed. An edge we have
+    /// no terrain for yet counts as open.
+    pub fn exit_open(&self, direction: Direction) -> bool {
+        self.exit_tiles.is_none_or(|tiles| tiles.is_open(direction))
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -205,9 +326,17 @@ pub struct RoomDynamicVisibilityData {
     hostile_creeps: bool,
     #[serde(rename = "h")]
     hostile_structures: bool,
-    /// Tower DPS at room edge (hostile towers only). Set when we have visibility; used to size drain bodies.
+    /// Tower DPS on the safest entry into the room (hostile towers only). Set when we have visibility;
+    /// used to size drain bodies.
     #[serde(default, rename = "td")]
     tower_dps_at_edge: Option<f32>,
+    /// The least-damaged run of exit tiles past the hostile towers (`damage::safest_entry_segment`).
+    /// Recomputed only when the tower layout changes.
+    #[serde(default, rename = "tes")]
+    entry_segment: Option<crate::military::damage::EntrySegment>,
+    /// `damage::tower_layout_hash` of the hostile towers `entry_segment` was computed for.
+    #[serde(default, rename = "tlh")]
+    tower_layout_hash: u64,
     /// Non-my ACTIVE spawns present (derelict classification input: a working
     /// spawn means the owner can produce defenders). Inactive spawns — RCL
     /// decayed below their tier, or the room lost its owner entirely — are
@@ -248,6 +377,11 @@ pub struct RoomDynamicVisibilityData {
     /// classification holds. None = not currently derelict.
     #[serde(default, rename = "dsi")]
     derelict_since: Option<u32>,
+    /// Absolute tick at which the controller's reservation runs out, whoever
+    /// holds it, as of the last observation. A reservation counts down in
+    /// real time unless a reserver is working it, so this is a lower bound.
+    #[serde(default, rename = "re")]
+    reservation_end: Option<u32>,
 }
 
 impl RoomDynamicVisibilityData {
@@ -267,11 +401,29 @@ impl RoomDynamicVisibilityData {
         self.age() <= ticks
     }
 
-    /// Tower DPS at room edge from last time we had visibility (hostile towers only). Used for drain body sizing.
+    /// Whether intel in `category` is still within its refresh TTL. Everything
+    /// dynamic is observed together, so this is the age against that
+    /// category's TTL.
+    pub fn fresh(&self, category: VisibilityCategory, has_missions: bool) -> bool {
+        self.overdue(category, has_missions) == 0
+    }
+
+    /// Ticks `category` is past its refresh TTL (0 while fresh).
+    pub fn overdue(&self, category: VisibilityCategory, has_missions: bool) -> u32 {
+        category.ttl(has_missions).map(|ttl| self.age().saturating_sub(ttl)).unwrap_or(0)
+    }
+
+    /// Tower DPS on the safest entry from last time we had visibility (hostile towers only). Used for drain
+    /// body sizing.
     pub fn tower_dps_at_edge(&self) -> Option<f32> {
         self.tower_dps_at_edge
     }
 
+    /// The safest entry past the hostile towers from last time we had visibility.
+    pub fn entry_segment(&self) -> Option<&crate::military::damage::EntrySegment> {
+        self.entry_segment.as_ref()
+    }
+
     pub fn owner(&self) -> &RoomDisposition {
         &self.owner
     }
@@ -315,6 +467,11 @@ impl RoomDynamicVisibilityData {
         self.safe_mode_end.map(|end| game::time() < end).unwrap_or(false)
     }
 
+    /// Tick the observed safe mode runs out, if one was active when last seen.
+    pub fn safe_mode_end(&self) -> Option<u32> {
+        self.safe_mode_end
+    }
+
     pub fn controller_level(&self) -> Option<u8> {
         self.controller_level
     }
@@ -323,6 +480,16 @@ impl RoomDynamicVisibilityData {
         self.controller_ticks_to_downgrade
     }
 
+    /// Tick the observed reservation runs out, if the controller was reserved when last seen.
+    pub fn reservation_end(&self) -> Option<u32> {
+        self.reservation_end
+    }
+
+    /// Reservation ticks left at `now`, extrapolated from the last observation (0 when unreserved).
+    pub fn reservation_ticks_remaining(&self, now: u32) -> u32 {
+        self.reservation_end.map(|end| end.saturating_sub(now)).unwrap_or(0)
+    }
+
     /// Earliest tick at which the controller could drop a level, extrapolated
     /// from the last observed downgrade timer. Exact for an abandoned room
     /// (nothing is feeding the timer); a lower bound for a maintained one.
@@ -392,6 +559,7 @@ impl RoomDynamicVisibilityData {
 }
 
 #[derive(Component)]
+#[storage(FlaggedStorage)]
 pub struct RoomData {
     pub name: RoomName,
     missions: EntityVec<Entity>,
@@ -497,8 +665,13 @@ impl RoomData {
     }
 
     pub fn update(&mut self, room: &Room, username: &str) {
-        if self.static_visibility_data.is_none() {
-            self.static_visibility_data = Some(Self::create_static_visibility_data(room));
+        match self.static_visibility_data.as_mut() {
+            None => self.static_visibility_data = Some(Self::create_static_visibility_data(room)),
+            Some(static_data) if static_data.exit_tiles.is_none() => {
+                let terrain = FastRoomTerrain::new(room.get_terrain().get_raw_buffer().to_vec());
+                static_data.exit_tiles = Some(ExitTiles::from_terrain(&terrain));
+            }
+            Some(_) => {}
         }
 
         self.dynamic_visibility_data = Some(self.create_dynamic_visibility_data(room, username));
@@ -512,18 +685,19 @@ impl RoomData {
         let terrain = room.get_terrain();
         let terrain = FastRoomTerrain::new(terrain.get_raw_buffer().to_vec());
         let terrain_statistics = RoomTerrainStatistics::from_terrain(&terrain);
+        let exit_tiles = ExitTiles::from_terrain(&terrain);
 
         // Cache room exits (static, never changes).
         let exits = game::map::describe_exits(room.name());
         let exit_list: Vec<(Direction, RoomName)> = exits.entries().collect();
 
         // Source-Keeper lair positions (permanent — recorded once, see RoomStaticVisibilityData).
-        let keeper_lairs = room
-            .find(find::STRUCTURES, None)
+        let keeper_lairs = find_known_structures(room)
             .into_iter()
             .filter(|s| s.structure_type() == StructureType::KeeperLair)
             .map(|s| s.pos())
-            .collect();
+            .collect::<Vec<_>>();
+        let room_class = RoomClass::classify(room.name(), !keeper_lairs.is_empty());
 
         RoomStaticVisibilityData {
             controller: controller_id,
@@ -532,6 +706,8 @@ impl RoomData {
             terrain_statistics,
             exits: Some(exit_list),
             keeper_lairs,
+            room_class,
+            exit_tiles: Some(exit_tiles),
         }
     }
 
@@ -544,11 +720,9 @@ impl RoomData {
     /// (`username` from the [`crate::identity::BotIdentity`] Resource —
     /// statics-review M6).
     fn name_to_disposition(name: String, username: &str) -> RoomDisposition {
-        let friends: &[String] = &[];
-
         if name == username {
             RoomDisposition::Mine
-        } else if friends.iter().any(|friend_name| &name == friend_name) {
+        } else if crate::military::is_ally_owner(&name) {
             RoomDisposition::Friendly(name)
         } else {
             RoomDisposition::Hostile(name)
@@ -561,7 +735,11 @@ impl RoomData {
         let controller_owner_name = controller.as_ref().and_then(|c| c.owner().map(|o| o.username()));
         let controller_owner_disposition = Self::name_option_to_disposition(controller_owner_name, username);
 
-        let controller_reservation_name = controller.as_ref().and_then(|c| c.reservation()).map(|r| r.username());
+        let controller_reservation = controller.as_ref().and_then(|c| c.reservation());
+        let reservation_end = controller_reservation
+            .as_ref()
+            .map(|r| game::time().saturating_add(r.ticks_to_end()));
+        let controller_reservation_name = controller_reservation.map(|r| r.username());
         let controller_reservation_disposition = Self::name_option_to_disposition(controller_reservation_name, username);
 
         let sign = controller.as_ref().and_then(|c| c.sign()).map(|s| RoomSign {
@@ -589,19 +767,45 @@ impl RoomData {
             .filter_map(|s| s.as_owned())
             .any(|s| s.owner().is_some() && !s.my());
 
-        let tower_dps_at_edge = structures.as_ref().and_then(|s| {
-            let positions: Vec<Position> = s.towers().iter().filter(|t| !t.my()).map(|t| t.pos()).collect();
-            // None when there are NO hostile towers — NOT Some(0.0). With
-            // `.map()` this was Some(0.0) for any room we had structure
-            // visibility of, and `.is_some()` consumers (notably
-            // is_claim_target_safe) read that as "hostile towers present",
-            // vetoing the claim of every scouted neutral room — so no bot
-            // could ever expand. Some(dps) iff there is real tower DPS.
-            if positions.is_empty() {
-                None
-            } else {
-                Some(crate::military::damage::tower_dps_at_room_edge(self.name, &positions))
+        let tower_positions: Vec<Position> = structures
+            .iter()
+            .flat_map(|s| s.towers())
+            .filter(|t| !t.my())
+            .map(|t| t.pos())
+            .collect();
+        let tower_layout_hash = crate::military::damage::tower_layout_hash(&tower_positions);
+
+        // The per-exit-tile scan only reruns when the towers move (built, destroyed).
+        let entry_segment = match self.dynamic_visibility_data.as_ref() {
+            Some(previous) if previous.tower_layout_hash == tower_layout_hash && previous.tower_dps_at_edge.is_some() => {
+                previous.entry_segment.clone()
+            }
+            _ if tower_positions.is_empty() => None,
+            _ => {
+                let terrain = game::map::get_room_terrain(self.name);
+                let is_exit = |tile: Position| {
+                    terrain
+                        .as_ref()
+                        .is_none_or(|t| t.get(tile.x().u8(), tile.y().u8()) != Terrain::Wall)
+                };
+
+                crate::military::damage::safest_entry_segment(self.name, &tower_positions, is_exit)
             }
+        };
+
+        // None when there are NO hostile towers — NOT Some(0.0). With
+        // `.map()` this was Some(0.0) for any room we had structure
+        // visibility of, and `.is_some()` consumers (notably
+        // is_claim_target_safe) read that as "hostile towers present",
+        // vetoing the claim of every scouted neutral room — so no bot
+        // could ever expand. Some(dps) iff there is real tower DPS. A
+        // towered room with no usable entry at all reads as the damage at
+        // its centre.
+        let tower_dps_at_edge = (!tower_positions.is_empty()).then(|| {
+            entry_segment.as_ref().map(|segment| segment.dps).unwrap_or_else(|| {
+                let centre = Position::new(RoomCoordinate::new(25).unwrap(), RoomCoordinate::new(25).unwrap(), self.name);
+                crate::military::damage::total_tower_damage(&tower_positions, centre)
+            })
         });
 
         // is_active() filters out RCL-decayed / ownerless husks: a spawn that
@@ -654,6 +858,8 @@ impl RoomData {
             hostile_creeps,
             hostile_structures,
             tower_dps_at_edge,
+            entry_segment,
+            tower_layout_hash,
             hostile_spawns,
             hostile_towers,
             hostile_threat_creeps,
@@ -661,6 +867,7 @@ impl RoomData {
             controller_level,
             controller_ticks_to_downgrade,
             derelict_since,
+            reservation_end,
         }
     }
 
@@ -698,6 +905,17 @@ impl RoomData {
         self.dynamic_visibility_data.as_ref()
     }
 
+    /// How many ticks past its refresh TTL the stalest of `categories` is, or
+    /// `None` if all of them are fresh.
+    pub fn visibility_overdue(&self, categories: VisibilityCategoryFlags) -> Option<u32> {
+        categories_overdue(
+            categories,
+            self.dynamic_visibility_data.as_ref().map(|data| data.age()),
+            self.static_visibility_data.is_some(),
+            !self.missions.is_empty(),
+        )
+    }
+
     pub fn get_structures(&self) -> Option<Ref<'_, RoomStructureData>> {
         let name = self.name;
 
@@ -808,7 +1026,9 @@ pub struct RoomStructureData {
 
 impl RoomStructureData {
     fn new(room: &Room) -> RoomStructureData {
-        let structures = room.find(find::STRUCTURES, None);
+        // Structures of types this build doesn't know are left out (see
+        // `structureidentifier`).
+        let structures = find_known_structures(room);
 
         let mut containers = Vec::new();
         let mut controllers = Vec::new();
@@ -1006,19 +1226,24 @@ pub struct CreepData {
     friendly: Vec<Creep>,
     #[serde(skip)]
     hostile: Vec<Creep>,
+    #[serde(skip)]
+    hostile_summary: HostileSummary,
 }
 
 impl CreepData {
     fn new(room: &Room) -> CreepData {
         let creeps = room.find(find::CREEPS, None);
 
-        let (friendly, hostile) = creeps.iter().cloned().partition(|c| c.my());
+        let (friendly, hostile): (Vec<_>, Vec<_>) = creeps.iter().cloned().partition(|c| c.my());
+
+        let hostile_summary = HostileSummary::new(hostile.iter().map(HostileBody::from_creep).collect());
 
         CreepData {
             last_updated: game::time(),
             creeps,
             friendly,
             hostile,
+            hostile_summary,
         }
     }
 
@@ -1033,6 +1258,15 @@ impl CreepData {
     pub fn hostile(&self) -> &[Creep] {
         &self.hostile
     }
+
+    pub fn hostile_summary(&self) -> &HostileSummary {
+        &self.hostile_summary
+    }
+
+    /// Each hostile creep with its summarized body.
+    pub fn hostile_bodies(&self) -> impl Iterator<Item = (&Creep, &HostileBody)> {
+        self.hostile.iter().zip(self.hostile_summary.bodies())
+    }
 }
 
 // ─── Dropped resources, tombstones, ruins ───────────────────────────────────
@@ -1152,6 +1386,8 @@ mod tests {
             hostile_creeps: false,
             hostile_structures: false,
             tower_dps_at_edge: None,
+            entry_segment: None,
+            tower_layout_hash: 0,
             hostile_spawns: false,
             hostile_towers: false,
             hostile_threat_creeps: false,
@@ -1159,6 +1395,7 @@ mod tests {
             controller_level: Some(3),
             controller_ticks_to_downgrade: Some(10_000),
             derelict_since,
+            reservation_end: None,
         }
     }
 
@@ -1320,4 +1557,51 @@ mod tests {
         // Not derelict: no mark, regardless of history.
         assert_eq!(RoomData::next_derelict_since(false, &enemy, Some(&enemy), Some(100), 2_500), None);
     }
+
+    fn room(name: &str) -> RoomName {
+        RoomName::new(name).unwrap()
+    }
+
+    #[test]
+    fn room_class_follows_sector_position() {
+        for name in ["E0N3", "W10S4", "E23N0", "W0N0"] {
+            assert_eq!(RoomClass::classify(room(name), false), RoomClass::Highway, "{}", name);
+        }
+        assert_eq!(RoomClass::classify(room("E5N5"), false), RoomClass::Center);
+        assert_eq!(RoomClass::classify(room("W15S25"), false), RoomClass::Center);
+        assert_eq!(RoomClass::classify(room("E4N5"), true), RoomClass::SourceKeeper);
+        assert_eq!(RoomClass::classify(room("E3N7"), false), RoomClass::Normal);
+
+        // A highway stays a highway even if something lair-like were recorded.
+        assert_eq!(RoomClass::classify(room("E10N4"), true), RoomClass::Highway);
+        assert!(RoomClass::Normal.claimable());
+        assert!(!RoomClass::SourceKeeper.claimable());
+    }
+
+    #[test]
+    fn exit_tiles_count_walkable_edge_tiles() {
+        // Walls everywhere except three tiles on the right edge and one on the top.
+        let mut buffer = vec![TerrainFlags::WALL.bits(); 2500];
+        for y in [10, 11, 12] {
+            buffer[y * 50 + 49] = 0;
+        }
+        buffer[20] = TerrainFlags::SWAMP.bits();
+        let tiles = ExitTiles::from_terrain(&FastRoomTerrain::new(buffer));
+
+        assert_eq!(tiles.tiles(Direction::Right), 3);
+        assert_eq!(tiles.tiles(Direction::Top), 1);
+        assert!(!tiles.is_open(Direction::Bottom));
+        assert!(!tiles.is_open(Direction::Left));
+        assert_eq!(tiles.open_exits().collect::<Vec<_>>(), vec![Direction::Top, Direction::Right]);
+    }
+
+    #[test]
+    fn reservation_counts_down_from_the_last_sighting() {
+        let mut data = dvd(1_000, RoomDisposition::Neutral, None);
+        assert_eq!(data.reservation_ticks_remaining(1_000), 0);
+
+        data.reservation_end = Some(4_000);
+        assert_eq!(data.reservation_ticks_remaining(1_500), 2_500);
+        assert_eq!(data.reservation_ticks_remaining(5_000), 0);
+    }
 }
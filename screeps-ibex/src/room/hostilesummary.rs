@@ -0,0 +1,281 @@
+//! Per-tick summary of the hostile creeps in a room.
+//!
+//! Built once when a room's `CreepData` is gathered, so the tower mission, the safe mode mission,
+//! the threat map and the war operation's defend scan share one body scan per hostile instead of
+//! each re-walking `creep.body()`. Boosts are read from the compound on each part, not assumed T3.
+
+use crate::military::*;
+use screeps::*;
+
+/// Who a hostile creep belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HostileOwner {
+    Invader,
+    SourceKeeper,
+    /// A player on the ally list (see `military::ALLIES`).
+    Ally,
+    Player,
+}
+
+impl HostileOwner {
+    pub fn classify(username: &str) -> HostileOwner {
+        if is_invader_owner(username) {
+            HostileOwner::Invader
+        } else if is_source_keeper_owner(username) {
+            HostileOwner::SourceKeeper
+        } else if is_ally_owner(username) {
+            HostileOwner::Ally
+        } else {
+            HostileOwner::Player
+        }
+    }
+
+    pub fn npc(self) -> bool {
+        matches!(self, HostileOwner::Invader | HostileOwner::SourceKeeper)
+    }
+}
+
+/// How much a compound multiplies the output of the part it boosts. Tough boosts reduce damage taken
+/// instead (see [`tough_damage_factor`]); WORK counts its dismantle boost only, as that is the one
+/// that threatens us.
+pub fn boost_multiplier(part: Part, compound: ResourceType) -> f32 {
+    match (part, compound) {
+        (Part::Attack, ResourceType::UtriumHydride) => 2.0,
+        (Part::Attack, ResourceType::UtriumAcid) => 3.0,
+        (Part::Attack, ResourceType::CatalyzedUtriumAcid) => 4.0,
+        (Part::RangedAttack, ResourceType::KeaniumOxide) => 2.0,
+        (Part::RangedAttack, ResourceType::KeaniumAlkalide) => 3.0,
+        (Part::RangedAttack, ResourceType::CatalyzedKeaniumAlkalide) => 4.0,
+        (Part::Heal, ResourceType::LemergiumOxide) => 2.0,
+        (Part::Heal, ResourceType::LemergiumAlkalide) => 3.0,
+        (Part::Heal, ResourceType::CatalyzedLemergiumAlkalide) => 4.0,
+        (Part::Work, ResourceType::ZynthiumHydride) => 2.0,
+        (Part::Work, ResourceType::ZynthiumAcid) => 3.0,
+        (Part::Work, ResourceType::CatalyzedZynthiumAcid) => 4.0,
+        _ => 1.0,
+    }
+}
+
+/// Fraction of incoming damage a boosted TOUGH part lets through.
+pub fn tough_damage_factor(compound: ResourceType) -> f32 {
+    match compound {
+        ResourceType::GhodiumOxide => 0.7,
+        ResourceType::GhodiumAlkalide => 0.5,
+        ResourceType::CatalyzedGhodiumAlkalide => 0.3,
+        _ => 1.0,
+    }
+}
+
+/// One hostile creep's live body: active part counts and boost-adjusted output.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HostileBody {
+    pub owner: HostileOwner,
+    pub attack: u32,
+    pub ranged_attack: u32,
+    pub heal: u32,
+    pub tough: u32,
+    pub work: u32,
+    pub claim: u32,
+    /// Melee damage per tick at range 1.
+    pub melee_dps: f32,
+    /// Ranged damage per tick (single target, range 3).
+    pub ranged_dps: f32,
+    /// Heal per tick on an adjacent target.
+    pub heal_per_tick: f32,
+    /// Dismantle damage per tick against a structure.
+    pub dismantle_per_tick: f32,
+    /// Hits the TOUGH parts soak before the rest of the body takes damage.
+    pub tough_hp: f32,
+    pub boosted: bool,
+}
+
+impl HostileBody {
+    /// Summarize a body given as `(part, hits, boost)`. Parts with no hits left do nothing and are skipped.
+    pub fn analyze<I>(owner: HostileOwner, parts: I) -> HostileBody
+    where
+        I: IntoIterator<Item = (Part, u32, Option<ResourceType>)>,
+    {
+        let mut body = HostileBody {
+            owner,
+            attack: 0,
+            ranged_attack: 0,
+            heal: 0,
+            tough: 0,
+            work: 0,
+            claim: 0,
+            melee_dps: 0.0,
+            ranged_dps: 0.0,
+            heal_per_tick: 0.0,
+            dismantle_per_tick: 0.0,
+            tough_hp: 0.0,
+            boosted: false,
+        };
+
+        for (part, hits, boost) in parts {
+            if hits == 0 {
+                continue;
+            }
+
+            body.boosted |= boost.is_some();
+
+            let multiplier = boost.map(|compound| boost_multiplier(part, compound)).unwrap_or(1.0);
+
+            match part {
+                Part::Attack => {
+                    body.attack += 1;
+                    body.melee_dps += ATTACK_POWER as f32 * multiplier;
+                }
+                Part::RangedAttack => {
+                    body.ranged_attack += 1;
+                    body.ranged_dps += RANGED_ATTACK_POWER as f32 * multiplier;
+                }
+                Part::Heal => {
+                    body.heal += 1;
+                    body.heal_per_tick += HEAL_POWER as f32 * multiplier;
+                }
+                Part::Tough => {
+                    body.tough += 1;
+                    body.tough_hp += hits as f32 / boost.map(tough_damage_factor).unwrap_or(1.0);
+                }
+                Part::Work => {
+                    body.work += 1;
+                    body.dismantle_per_tick += DISMANTLE_POWER as f32 * multiplier;
+                }
+                Part::Claim => {
+                    body.claim += 1;
+                }
+                _ => {}
+            }
+        }
+
+        body
+    }
+
+    pub fn from_creep(creep: &Creep) -> HostileBody {
+        let owner = HostileOwner::classify(&creep.owner().username());
+
+        HostileBody::analyze(owner, creep.body().iter().map(|p| (p.part(), p.hits(), p.boost())))
+    }
+
+    /// Creep damage per tick: melee plus ranged.
+    pub fn dps(&self) -> f32 {
+        self.melee_dps + self.ranged_dps
+    }
+
+    /// Whether the creep can damage creeps or structures.
+    pub fn armed(&self) -> bool {
+        self.attack > 0 || self.ranged_attack > 0 || self.work > 0
+    }
+
+    /// Whether the creep is a player's claimer: CLAIM parts can attack our controllers' downgrade timers or
+    /// strip our reservations, and nothing else in the body has to be armed to do it.
+    pub fn claimer(&self) -> bool {
+        self.claim > 0 && self.owner == HostileOwner::Player
+    }
+}
+
+/// The hostile creeps of a room, summarized. `bodies` is parallel to `CreepData::hostile`.
+#[derive(Clone, Debug, Default)]
+pub struct HostileSummary {
+    bodies: Vec<HostileBody>,
+    total_dps: f32,
+    total_heal: f32,
+}
+
+impl HostileSummary {
+    pub fn new(bodies: Vec<HostileBody>) -> HostileSummary {
+        let total_dps = bodies.iter().map(|b| b.dps()).sum();
+        let total_heal = bodies.iter().map(|b| b.heal_per_tick).sum();
+
+        HostileSummary {
+            bodies,
+            total_dps,
+            total_heal,
+        }
+    }
+
+    pub fn bodies(&self) -> &[HostileBody] {
+        &self.bodies
+    }
+
+    /// Melee plus ranged damage per tick across every hostile.
+    pub fn total_dps(&self) -> f32 {
+        self.total_dps
+    }
+
+    /// Heal per tick across every hostile.
+    pub fn total_heal(&self) -> f32 {
+        self.total_heal
+    }
+
+    pub fn any_boosted(&self) -> bool {
+        self.bodies.iter().any(|b| b.boosted)
+    }
+
+    /// Whether any hostile belongs to a player that isn't an ally.
+    pub fn any_player(&self) -> bool {
+        self.bodies.iter().any(|b| b.owner == HostileOwner::Player)
+    }
+
+    /// Whether any hostile is a player's claimer (see [`HostileBody::claimer`]).
+    pub fn any_claimer(&self) -> bool {
+        self.bodies.iter().any(|b| b.claimer())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boosts_scale_by_compound_tier() {
+        let body = HostileBody::analyze(
+            HostileOwner::Player,
+            [
+                (Part::Attack, 100, None),
+                (Part::Attack, 100, Some(ResourceType::UtriumHydride)),
+                (Part::Heal, 100, Some(ResourceType::LemergiumAlkalide)),
+                (Part::Work, 100, Some(ResourceType::CatalyzedZynthiumAcid)),
+                (Part::Tough, 100, Some(ResourceType::CatalyzedGhodiumAlkalide)),
+            ],
+        );
+
+        assert_eq!(body.melee_dps, 30.0 + 60.0);
+        assert_eq!(body.heal_per_tick, 36.0);
+        assert_eq!(body.dismantle_per_tick, 200.0);
+        assert!((body.tough_hp - 100.0 / 0.3).abs() < 0.01);
+        assert!(body.boosted);
+    }
+
+    /// A harvest boost on WORK doesn't make the creep a better dismantler.
+    #[test]
+    fn boosts_for_other_actions_do_not_count() {
+        let body = HostileBody::analyze(
+            HostileOwner::Player,
+            [(Part::Work, 100, Some(ResourceType::UtriumOxide)), (Part::RangedAttack, 0, None)],
+        );
+
+        assert_eq!(body.dismantle_per_tick, DISMANTLE_POWER as f32);
+        assert_eq!(body.ranged_attack, 0);
+        assert!(body.armed());
+    }
+
+    #[test]
+    fn player_claim_parts_make_a_claimer() {
+        let claimer = HostileBody::analyze(HostileOwner::Player, [(Part::Claim, 100, None), (Part::Move, 100, None)]);
+        let invader = HostileBody::analyze(HostileOwner::Invader, [(Part::Claim, 100, None)]);
+        let spent = HostileBody::analyze(HostileOwner::Player, [(Part::Claim, 0, None), (Part::Move, 100, None)]);
+
+        assert!(claimer.claimer() && !claimer.armed());
+        assert!(!invader.claimer());
+        assert!(!spent.claimer());
+        assert!(HostileSummary::new(vec![invader, claimer]).any_claimer());
+    }
+
+    #[test]
+    fn owners_are_classified() {
+        assert_eq!(HostileOwner::classify(NPC_INVADER), HostileOwner::Invader);
+        assert_eq!(HostileOwner::classify(NPC_SOURCE_KEEPER), HostileOwner::SourceKeeper);
+        assert_eq!(HostileOwner::classify("somePlayer"), HostileOwner::Player);
+    }
+}
@@ -1,6 +1,7 @@
 pub mod createroomsystem;
 pub mod data;
 pub mod gather;
+pub mod hostilesummary;
 pub mod room_status_cache;
 pub mod roomplansystem;
 pub mod roomplanvisualizesystem;
@@ -21,6 +21,7 @@ use specs::*;
 pub struct RoomPlanRequest {
     room: Entity,
     priority: f32,
+    keep_existing: bool,
 }
 
 impl RoomPlanRequest {
@@ -29,10 +30,71 @@ impl RoomPlanRequest {
         // assert finiteness where the priority is produced instead.
         debug_assert!(priority.is_finite(), "room plan request priority not finite: {priority}");
 
-        RoomPlanRequest { room, priority }
+        RoomPlanRequest {
+            room,
+            priority,
+            keep_existing: false,
+        }
+    }
+
+    /// Plan around the room's existing spawns, storage and terminal: a layout
+    /// that would demolish them is only accepted once no escalation beam finds
+    /// one that keeps more of them.
+    pub fn keep_existing(mut self) -> RoomPlanRequest {
+        self.keep_existing = true;
+        self
     }
 }
 
+/// Structures a replan of a developed room treats as fixed: moving any of
+/// them means tearing down (and rebuilding) the room's core.
+pub const FIXED_STRUCTURE_TYPES: &[StructureType] = &[StructureType::Spawn, StructureType::Storage, StructureType::Terminal];
+
+/// A built structure a plan is expected to keep in place.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FixedStructure {
+    pub structure_type: StructureType,
+    pub x: u8,
+    pub y: u8,
+}
+
+/// The room's own spawns, storage and terminal.
+pub fn fixed_structures(structures: &RoomStructureData) -> Vec<FixedStructure> {
+    structures
+        .all()
+        .iter()
+        .filter(|structure| FIXED_STRUCTURE_TYPES.contains(&structure.structure_type()))
+        .filter(|structure| structure.as_owned().map(|owned| owned.my()).unwrap_or(false))
+        .map(|structure| {
+            let pos = structure.pos();
+
+            FixedStructure {
+                structure_type: structure.structure_type(),
+                x: pos.x().u8(),
+                y: pos.y().u8(),
+            }
+        })
+        .collect()
+}
+
+/// Construction cost of rebuilding every existing structure that `planned`
+/// does not keep at the same tile.
+pub fn demolition_cost(fixed: &[FixedStructure], planned: impl Fn(StructureType, u8, u8) -> bool) -> u32 {
+    fixed
+        .iter()
+        .filter(|structure| !planned(structure.structure_type, structure.x, structure.y))
+        .map(|structure| structure.structure_type.construction_cost().unwrap_or(0))
+        .sum()
+}
+
+fn plan_demolition_cost(plan: &Plan, fixed: &[FixedStructure]) -> u32 {
+    demolition_cost(fixed, |structure_type, x, y| {
+        plan.structures
+            .iter()
+            .any(|(location, item)| item.structure_type == structure_type && location.x() == x && location.y() == y)
+    })
+}
+
 #[derive(Default)]
 pub struct RoomPlanQueue {
     pub requests: Vec<RoomPlanRequest>,
@@ -85,6 +147,7 @@ impl RoomPlanState {
 }
 
 #[derive(Clone, Deserialize, Serialize, Component)]
+#[storage(FlaggedStorage)]
 pub struct RoomPlanData {
     state: RoomPlanState,
 }
@@ -189,6 +252,14 @@ pub struct RoomPlannerRunningData {
     /// heuristic. `serde(default)` => 0 for state serialized before this field.
     #[serde(default)]
     beam_level: usize,
+    /// Fixed structures the plan should keep, when the request asked to plan
+    /// around them. Empty for a fresh room.
+    #[serde(default)]
+    fixed: Vec<FixedStructure>,
+    /// The cheapest-to-adopt plan found so far at a narrower beam, and its
+    /// demolition cost, while wider beams look for one that keeps more.
+    #[serde(default)]
+    best: Option<(u32, Plan)>,
 }
 
 impl RoomPlannerRunningData {
@@ -198,19 +269,53 @@ impl RoomPlannerRunningData {
         ESCALATION_BEAMS[self.beam_level.min(last)]
     }
 
-    fn start(room_data: &RoomData) -> Result<Self, String> {
+    fn start(room_data: &RoomData, keep_existing: bool) -> Result<Self, String> {
         let static_visibility_data = room_data.get_static_visibility_data().ok_or("Expected static visibility")?;
         let _data_source = RoomDataPlannerDataSource::new(room_data.name, static_visibility_data);
 
+        let fixed = if keep_existing {
+            room_data
+                .get_structures()
+                .map(|structures| fixed_structures(&structures))
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+
         let state = PlannerBuilder::with_beam(ESCALATION_BEAMS[0]).build();
 
         Ok(RoomPlannerRunningData {
             room_name: room_data.name,
             planner_state: state,
             beam_level: 0,
+            fixed,
+            best: None,
         })
     }
 
+    /// Keep the cheaper of `plan` and the best plan so far. Returns the plan to
+    /// adopt once no wider beam is left to try, or when it keeps everything.
+    fn settle(&mut self, plan: Plan) -> Option<Plan> {
+        let cost = plan_demolition_cost(&plan, &self.fixed);
+        let best = match self.best.take() {
+            Some((best_cost, best)) if best_cost <= cost => (best_cost, best),
+            _ => (cost, plan),
+        };
+
+        if best.0 == 0 || self.beam_level + 1 >= ESCALATION_BEAMS.len() {
+            if best.0 > 0 {
+                info!(
+                    "Plan for {} moves existing core structures (demolition cost {})",
+                    self.room_name, best.0
+                );
+            }
+            return Some(best.1);
+        }
+
+        self.best = Some(best);
+        None
+    }
+
     fn process(&mut self, room_data: &RoomData, budget_cpu: f64, tick_limit: f64) -> Result<PlanTickResult, String> {
         let static_visibility_data = room_data.get_static_visibility_data().ok_or("Expected static visibility")?;
         let data_source = RoomDataPlannerDataSource::new(room_data.name, static_visibility_data);
@@ -241,8 +346,30 @@ impl RoomPlannerRunningData {
 
         match new_state {
             PlanningState::Complete(plan) => {
-                self.planner_state = PlanningState::Complete(plan.clone());
-                Ok(PlanTickResult::Complete(Some(plan)))
+                if self.fixed.is_empty() {
+                    self.planner_state = PlanningState::Complete(plan.clone());
+                    return Ok(PlanTickResult::Complete(Some(plan)));
+                }
+
+                // Planning around existing structures: a layout that would
+                // demolish some of them sends the search to the next, wider
+                // beam, falling back to the cheapest layout seen.
+                match self.settle(plan) {
+                    Some(plan) => {
+                        self.planner_state = PlanningState::Complete(plan.clone());
+                        Ok(PlanTickResult::Complete(Some(plan)))
+                    }
+                    None => {
+                        self.beam_level += 1;
+                        let wider = self.current_beam();
+                        info!(
+                            "Plan at beam {} for {} moves existing core structures; trying beam {}",
+                            current_beam, room_data.name, wider
+                        );
+                        self.planner_state = PlannerBuilder::with_beam(wider).build();
+                        Ok(PlanTickResult::Running)
+                    }
+                }
             }
             PlanningState::Failed(msg) => {
                 // No-plan-loss fallback: before giving up, escalate the anchor
@@ -257,6 +384,11 @@ impl RoomPlannerRunningData {
                     );
                     self.planner_state = PlannerBuilder::with_beam(wider).build();
                     Ok(PlanTickResult::Running)
+                } else if let Some((_, plan)) = self.best.take() {
+                    // The widest beam found nothing, but a narrower one found a
+                    // layout that moves some existing structures: adopt it.
+                    self.planner_state = PlanningState::Complete(plan.clone());
+                    Ok(PlanTickResult::Complete(Some(plan)))
                 } else {
                     self.planner_state = PlanningState::Failed(msg.clone());
                     Ok(PlanTickResult::Failed(msg))
@@ -295,6 +427,7 @@ pub struct RoomPlanSystemData<'a> {
     room_plan_data: WriteStorage<'a, RoomPlanData>,
     room_plan_queue: Write<'a, RoomPlanQueue>,
     governor: Read<'a, crate::cpugovernor::GovernorSnapshot>,
+    cpu_budget: Read<'a, crate::metrics::CpuBudget>,
     features: Read<'a, crate::features::Features>,
 }
 
@@ -302,19 +435,25 @@ pub struct RoomPlanSystem;
 
 impl RoomPlanSystem {
     /// Returns (budget_cpu, tick_limit) when room planning may run. Budget is derived from
-    /// remaining CPU and a reserve (like movement), never exceeds the tick limit, and
+    /// remaining CPU and a reserve (like movement), never exceeds the tick's allowance, and
     /// ensures at least some work when there are pending requests to avoid deadlock.
     /// Planning runs only when bucket is at or above bucket_threshold (0 = always allow).
-    /// `bucket` comes from the governor snapshot (M1: the one CPU-pressure truth).
-    fn get_cpu_budget(has_pending_request: bool, bucket: i32, construction: &crate::features::ConstructionFeatures) -> Option<(f64, f64)> {
+    /// `bucket` comes from the governor snapshot (M1: the one CPU-pressure truth); the
+    /// ceiling is the tier-scaled `CpuBudget::tick_allowance`, so the planner bails early
+    /// under pressure instead of burning to the engine's hard limit.
+    fn get_cpu_budget(
+        has_pending_request: bool,
+        bucket: i32,
+        cpu_budget: &crate::metrics::CpuBudget,
+        construction: &crate::features::ConstructionFeatures,
+    ) -> Option<(f64, f64)> {
         if construction.bucket_threshold > 0 && bucket < construction.bucket_threshold {
             return None;
         }
 
-        let tick_limit = game::cpu::tick_limit();
-        let cpu_limit = game::cpu::limit() as f64;
-        let used = game::cpu::get_used();
-        let remaining = (tick_limit - used).max(0.0);
+        let tick_limit = cpu_budget.tick_allowance;
+        let cpu_limit = cpu_budget.cpu_limit;
+        let remaining = cpu_budget.remaining();
 
         // Under normal conditions use GCL limit; when bucket is at/above threshold allow burst.
         let budget_ceiling = if construction.bucket_threshold == 0 || bucket >= construction.bucket_threshold {
@@ -407,7 +546,7 @@ impl<'a> System<'a> for RoomPlanSystem {
                     .get(PLANNER_MEMORY_SEGMENT)
                     .map(|d| !d.is_empty())
                     .unwrap_or(false);
-            if let Some((max_cpu, tick_limit)) = Self::get_cpu_budget(has_pending, data.governor.bucket, &construction) {
+            if let Some((max_cpu, tick_limit)) = Self::get_cpu_budget(has_pending, data.governor.bucket, &data.cpu_budget, &construction) {
                 if data.memory_arbiter.is_active(PLANNER_MEMORY_SEGMENT) {
                     let Some(planner_data) = data.memory_arbiter.get(PLANNER_MEMORY_SEGMENT) else {
                         return;
@@ -459,7 +598,7 @@ impl<'a> System<'a> for RoomPlanSystem {
 
                         if let Some(request) = request {
                             if let Some(room_data) = data.room_data.get(request.room) {
-                                match RoomPlannerRunningData::start(room_data) {
+                                match RoomPlannerRunningData::start(room_data, request.keep_existing) {
                                     Ok(running_data) => {
                                         info!("Started planning for room: {}", room_data.name);
                                         planner_state.running_state = Some(running_data);
@@ -568,3 +707,34 @@ mod plan_backoff_tests {
         assert_eq!(replan_backoff_ticks(u32::MAX), 32_000);
     }
 }
+
+#[cfg(test)]
+mod fixed_structure_tests {
+    use super::{demolition_cost, FixedStructure};
+    use screeps::StructureType;
+
+    fn fixed(structure_type: StructureType, x: u8, y: u8) -> FixedStructure {
+        FixedStructure { structure_type, x, y }
+    }
+
+    /// Only structures the plan moves (or retypes) count, at their rebuild cost.
+    #[test]
+    fn demolition_cost_counts_structures_the_plan_moves() {
+        let core = [
+            fixed(StructureType::Spawn, 20, 20),
+            fixed(StructureType::Storage, 22, 20),
+            fixed(StructureType::Terminal, 22, 22),
+        ];
+
+        assert_eq!(demolition_cost(&core, |_, _, _| true), 0);
+        assert_eq!(
+            demolition_cost(&core, |structure_type, _, _| structure_type != StructureType::Storage),
+            StructureType::Storage.construction_cost().unwrap()
+        );
+        assert_eq!(
+            demolition_cost(&core, |_, x, y| (x, y) == (20, 20)),
+            StructureType::Storage.construction_cost().unwrap() + StructureType::Terminal.construction_cost().unwrap()
+        );
+        assert_eq!(demolition_cost(&[], |_, _, _| false), 0);
+    }
+}
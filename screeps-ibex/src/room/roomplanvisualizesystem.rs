@@ -1,7 +1,56 @@
 use super::data::*;
 use super::roomplansystem::*;
 use crate::visualize::*;
+use screeps::*;
+use screeps_foreman::plan::visualize_room_items;
 use specs::prelude::*;
+use std::collections::HashMap;
+
+/// Opacity of plan entries that unlock above the RCL being shown.
+const ABOVE_STAGE_OPACITY: f32 = 0.25;
+
+// ---------------------------------------------------------------------------
+// PlanPreview — RCL stages picked through the `show_plan` console command
+// ---------------------------------------------------------------------------
+
+/// Rooms whose plan is previewed at a chosen RCL stage. Filled by the
+/// `show_plan` console command and kept until cleared or the environment
+/// resets.
+#[derive(Default)]
+pub struct PlanPreview {
+    rooms: HashMap<RoomName, u8>,
+}
+
+impl PlanPreview {
+    /// Preview `room` at `rcl`, or stop previewing it with `None`.
+    pub fn show(&mut self, room: RoomName, rcl: Option<u8>) {
+        match rcl {
+            Some(rcl) => {
+                self.rooms.insert(room, rcl);
+            }
+            None => {
+                self.rooms.remove(&room);
+            }
+        }
+    }
+
+    pub fn rcl(&self, room: RoomName) -> Option<u8> {
+        self.rooms.get(&room).copied()
+    }
+}
+
+/// Draws plan entries at a fixed opacity, so one stage's entries can be
+/// drawn solid and the rest dimmed.
+struct StageVisualizer<'a> {
+    room_vis: &'a mut RoomVisualizer,
+    opacity: f32,
+}
+
+impl screeps_foreman::RoomVisualizer for StageVisualizer<'_> {
+    fn render(&mut self, location: screeps_common::Location, structure: StructureType) {
+        screeps_visual::render::render_structure(self.room_vis, location.x() as f32, location.y() as f32, structure, self.opacity);
+    }
+}
 
 // ---------------------------------------------------------------------------
 // RoomPlanVisualizeSystem — renders completed room plans
@@ -13,11 +62,16 @@ pub struct RoomPlanVisualizeSystemData<'a> {
     room_plan_data: ReadStorage<'a, RoomPlanData>,
     visualizer: Option<Write<'a, Visualizer>>,
     features: Read<'a, crate::features::Features>,
+    preview: Read<'a, PlanPreview>,
 }
 
 /// Renders completed room plans using the screeps-visual structure visuals.
 ///
-/// Only runs when the `construction.visualize.plan` feature flag is enabled.
+/// Draws the persisted plan, not the planner state: entries the room's RCL
+/// has unlocked are drawn solid and the rest dimmed. A room picked with
+/// `show_plan <room> <rcl>` is drawn at that stage instead, and is drawn even
+/// when the `construction.visualize.plan` feature flag is off.
+///
 /// Inserted into the dispatcher after `RoomPlanSystem` and before
 /// `RenderSystem` / `ApplyVisualsSystem`.
 pub struct RoomPlanVisualizeSystem;
@@ -27,19 +81,38 @@ impl<'a> System<'a> for RoomPlanVisualizeSystem {
     type SystemData = RoomPlanVisualizeSystemData<'a>;
 
     fn run(&mut self, mut data: Self::SystemData) {
-        if !data.features.construction.visualize.plan() {
-            return;
-        }
+        let show_all = data.features.construction.visualize.plan();
 
         let Some(visualizer) = data.visualizer.as_deref_mut() else {
             return;
         };
 
         for (room_data, room_plan_data) in (&data.room_data, &data.room_plan_data).join() {
-            if let Some(plan) = room_plan_data.plan() {
-                let room_vis = visualizer.get_room(room_data.name);
-                plan.visualize(room_vis);
-            }
+            let stage = match data.preview.rcl(room_data.name) {
+                Some(rcl) => rcl,
+                None if show_all => room_data
+                    .get_dynamic_visibility_data()
+                    .and_then(|d| d.controller_level())
+                    .unwrap_or(0),
+                None => continue,
+            };
+
+            let Some(plan) = room_plan_data.plan() else {
+                continue;
+            };
+
+            let (unlocked, locked): (Vec<_>, Vec<_>) = plan.structures.iter().partition(|(_, item)| item.required_rcl <= stage);
+
+            let room_vis = visualizer.get_room(room_data.name, VisualLayer::Plan);
+
+            visualize_room_items(
+                locked.into_iter(),
+                &mut StageVisualizer {
+                    room_vis,
+                    opacity: ABOVE_STAGE_OPACITY,
+                },
+            );
+            visualize_room_items(unlocked.into_iter(), &mut StageVisualizer { room_vis, opacity: 1.0 });
         }
     }
 }
@@ -1,4 +1,5 @@
 use super::data::*;
+use crate::pathing::pathfinderservice::{PathfinderService, RouteIntel};
 use screeps::*;
 use specs::prelude::*;
 
@@ -8,6 +9,7 @@ pub struct UpdateRoomDataSystemData<'a> {
     room_data: WriteStorage<'a, RoomData>,
     updater: Read<'a, LazyUpdate>,
     identity: Read<'a, crate::identity::BotIdentity>,
+    pathfinder: Write<'a, PathfinderService>,
 }
 
 pub struct UpdateRoomDataSystem;
@@ -19,9 +21,30 @@ impl<'a> System<'a> for UpdateRoomDataSystem {
     fn run(&mut self, mut data: Self::SystemData) {
         let rooms = game::rooms();
 
-        for (_entity, room_data) in (&data.entities, &mut data.room_data).join() {
-            if let Some(room) = rooms.get(room_data.name) {
+        // Rooms scouted in earlier VMs reach the route callback once, on the
+        // first tick; after that only newly visible rooms can change.
+        if !data.pathfinder.has_room_intel() {
+            for room_data in (&data.room_data).join() {
+                if let Some(static_data) = room_data.get_static_visibility_data() {
+                    data.pathfinder.note_room(room_data.name, RouteIntel::of(static_data));
+                }
+            }
+        }
+
+        // Only visible rooms are touched mutably, so rooms out of sight
+        // stay clean for the incremental world save.
+        let visible: Vec<(Entity, Room)> = (&data.entities, &data.room_data)
+            .join()
+            .filter_map(|(entity, room_data)| rooms.get(room_data.name).map(|room| (entity, room)))
+            .collect();
+
+        for (entity, room) in visible {
+            if let Some(room_data) = data.room_data.get_mut(entity) {
                 room_data.update(&room, &data.identity.username);
+
+                if let Some(static_data) = room_data.get_static_visibility_data() {
+                    data.pathfinder.note_room(room_data.name, RouteIntel::of(static_data));
+                }
             }
         }
     }
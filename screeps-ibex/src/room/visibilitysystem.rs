@@ -1,5 +1,6 @@
 use super::data::*;
 use crate::entitymappingsystem::*;
+use crate::intents::{IntentCategory, IntentRecorder};
 use crate::serialize::*;
 use bitflags::*;
 use log::*;
@@ -22,6 +23,13 @@ pub const VISIBILITY_PRIORITY_NONE: f32 = 0.0;
 /// ticks, so 100 gives a comfortable margin).
 const DEFAULT_VISIBILITY_TTL: u32 = 100;
 
+/// Structures change slowly: intel this old is still good enough.
+const STRUCTURES_REFRESH_TTL: u32 = 500;
+/// Hostiles in a room we are working need to be current.
+const HOSTILES_REFRESH_TTL_ACTIVE: u32 = 20;
+/// Hostiles in a room we only watch: the old request cadence.
+const HOSTILES_REFRESH_TTL_IDLE: u32 = DEFAULT_VISIBILITY_TTL;
+
 bitflags! {
     #[derive(Copy, Clone, Debug)]
     pub struct VisibilityRequestFlags: u8 {
@@ -34,6 +42,78 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// Kinds of room intel a request needs refreshed. Each goes stale on its
+    /// own clock (see [`VisibilityCategory::ttl`]).
+    #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+    pub struct VisibilityCategoryFlags: u8 {
+        const STATIC = 1u8;
+        const STRUCTURES = 1u8 << 1;
+        const HOSTILES = 1u8 << 2;
+
+        const ALL = Self::STATIC.bits() | Self::STRUCTURES.bits() | Self::HOSTILES.bits();
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VisibilityCategory {
+    /// Terrain, sources, minerals, controller position: never changes.
+    Static,
+    /// Structures, owner and reservation.
+    Structures,
+    /// Hostile creeps.
+    Hostiles,
+}
+
+impl VisibilityCategory {
+    pub const ALL: [VisibilityCategory; 3] = [
+        VisibilityCategory::Static,
+        VisibilityCategory::Structures,
+        VisibilityCategory::Hostiles,
+    ];
+
+    pub fn flag(self) -> VisibilityCategoryFlags {
+        match self {
+            VisibilityCategory::Static => VisibilityCategoryFlags::STATIC,
+            VisibilityCategory::Structures => VisibilityCategoryFlags::STRUCTURES,
+            VisibilityCategory::Hostiles => VisibilityCategoryFlags::HOSTILES,
+        }
+    }
+
+    /// Ticks intel in this category stays fresh, or `None` if once seen is
+    /// enough. Hostiles go stale fastest in rooms with missions running.
+    pub fn ttl(self, has_missions: bool) -> Option<u32> {
+        match self {
+            VisibilityCategory::Static => None,
+            VisibilityCategory::Structures => Some(STRUCTURES_REFRESH_TTL),
+            VisibilityCategory::Hostiles if has_missions => Some(HOSTILES_REFRESH_TTL_ACTIVE),
+            VisibilityCategory::Hostiles => Some(HOSTILES_REFRESH_TTL_IDLE),
+        }
+    }
+}
+
+/// How many ticks past its TTL the stalest of `categories` is, or `None` if
+/// every one is fresh. `age` is the dynamic data's age (`None` = never seen);
+/// a category that was never seen is as overdue as it gets. No categories
+/// means all of them.
+pub fn categories_overdue(categories: VisibilityCategoryFlags, age: Option<u32>, has_static: bool, has_missions: bool) -> Option<u32> {
+    let categories = if categories.is_empty() {
+        VisibilityCategoryFlags::ALL
+    } else {
+        categories
+    };
+
+    VisibilityCategory::ALL
+        .iter()
+        .filter(|category| categories.contains(category.flag()))
+        .filter_map(|category| match (category.ttl(has_missions), age) {
+            (None, _) => (!has_static).then_some(u32::MAX),
+            (Some(_), None) => Some(u32::MAX),
+            (Some(ttl), Some(age)) => (age > ttl).then(|| age - ttl),
+        })
+        .max()
+}
+
 impl Serialize for VisibilityRequestFlags {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         self.bits().serialize(serializer)
@@ -137,6 +217,13 @@ pub struct VisibilityRuntimeEntry {
     pub observer_serviced: bool,
     /// Scout creep entity currently moving toward this room (if any).
     pub claimed_by: Option<Entity>,
+    /// Intel categories requested for this room (empty = all). Not
+    /// persisted: requesters re-push well inside the entry TTL.
+    pub categories: VisibilityCategoryFlags,
+    /// Every requested category was fresh at tick start: nothing to service.
+    pub fresh: bool,
+    /// Ticks the stalest requested category is past its TTL.
+    pub overdue: u32,
 }
 
 /// Snapshot entry for a single visibility request (for visualization).
@@ -193,6 +280,7 @@ impl VisibilityQueue {
         let priority = request.priority;
         let allowed_types = request.allowed_types;
         let opportunistic = request.opportunistic;
+        let categories = request.categories;
         let expires_at = game::time() + DEFAULT_VISIBILITY_TTL;
 
         if let Some(existing) = self.entries.iter_mut().find(|e| e.room_name == room_name) {
@@ -213,7 +301,31 @@ impl VisibilityQueue {
             });
         }
 
-        self.runtime.entry(room_name).or_default();
+        self.runtime.entry(room_name).or_default().categories |= categories;
+    }
+
+    /// Record how overdue each entry's requested categories are, from
+    /// `overdue(room, categories)` (see [`categories_overdue`]). Called at tick
+    /// start; entries requested later in the tick count as due.
+    pub fn assess<F>(&mut self, overdue: F)
+    where
+        F: Fn(RoomName, VisibilityCategoryFlags) -> Option<u32>,
+    {
+        for entry in &self.entries {
+            let runtime = self.runtime.entry(entry.room_name).or_default();
+            let due = overdue(entry.room_name, runtime.categories);
+
+            runtime.fresh = due.is_none();
+            runtime.overdue = due.unwrap_or(0);
+        }
+    }
+
+    fn overdue(&self, room_name: RoomName) -> u32 {
+        self.runtime.get(&room_name).map(|r| r.overdue).unwrap_or(0)
+    }
+
+    fn is_fresh(&self, room_name: RoomName) -> bool {
+        self.runtime.get(&room_name).map(|r| r.fresh).unwrap_or(false)
     }
 
     /// Mark a room as claimed by a scout creep entity.
@@ -296,8 +408,9 @@ impl VisibilityQueue {
         self.entries.iter().any(|e| e.room_name == room_name)
     }
 
-    /// Find the best unclaimed, non-observer-serviced entry with SCOUT flag,
-    /// preferring highest priority then closest distance to `creep_pos`.
+    /// Find the best unclaimed, non-observer-serviced entry with SCOUT flag
+    /// whose intel is due, preferring highest priority, then the most
+    /// overdue, then closest distance to `creep_pos`.
     pub fn best_unclaimed_for(&self, creep_pos: Position) -> Option<RoomName> {
         let creep_room = creep_pos.room_name();
         let now = game::time();
@@ -306,6 +419,7 @@ impl VisibilityQueue {
             .iter()
             .filter(|e| e.allowed_types.contains(VisibilityRequestFlags::SCOUT))
             .filter(|e| !self.is_unreachable_now(e.room_name, now))
+            .filter(|e| !self.is_fresh(e.room_name))
             .filter(|e| {
                 let rt = self.runtime.get(&e.room_name);
                 let claimed = rt.map(|r| r.claimed_by.is_some()).unwrap_or(false);
@@ -318,23 +432,29 @@ impl VisibilityQueue {
                 a.priority
                     .partial_cmp(&b.priority)
                     .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| self.overdue(a.room_name).cmp(&self.overdue(b.room_name)))
                     .then_with(|| dist_b.cmp(&dist_a)) // prefer closer
             })
             .map(|e| e.room_name)
     }
 
-    /// Check if there are any unclaimed, non-opportunistic, scout-eligible entries.
+    /// Check if there are any unclaimed, non-opportunistic, scout-eligible
+    /// entries whose intel is due.
     ///
     /// Opportunistic entries (created by idle scouts for proactive exploration)
     /// are excluded — they should not trigger new scout mission spawns.
     pub fn has_unclaimed_scout_eligible(&self) -> bool {
         let now = game::time();
         self.entries.iter().any(|e| {
-            e.allowed_types.contains(VisibilityRequestFlags::SCOUT) && !e.opportunistic && !self.is_unreachable_now(e.room_name, now) && {
-                let rt = self.runtime.get(&e.room_name);
-                let claimed = rt.map(|r| r.claimed_by.is_some()).unwrap_or(false);
-                !claimed
-            }
+            e.allowed_types.contains(VisibilityRequestFlags::SCOUT)
+                && !e.opportunistic
+                && !self.is_unreachable_now(e.room_name, now)
+                && !self.is_fresh(e.room_name)
+                && {
+                    let rt = self.runtime.get(&e.room_name);
+                    let claimed = rt.map(|r| r.claimed_by.is_some()).unwrap_or(false);
+                    !claimed
+                }
         })
     }
 
@@ -371,6 +491,7 @@ pub struct VisibilityRequest {
     priority: f32,
     allowed_types: VisibilityRequestFlags,
     opportunistic: bool,
+    categories: VisibilityCategoryFlags,
 }
 
 impl VisibilityRequest {
@@ -384,6 +505,7 @@ impl VisibilityRequest {
             priority,
             allowed_types,
             opportunistic: false,
+            categories: VisibilityCategoryFlags::ALL,
         }
     }
 
@@ -398,8 +520,16 @@ impl VisibilityRequest {
             priority,
             allowed_types,
             opportunistic: true,
+            categories: VisibilityCategoryFlags::ALL,
         }
     }
+
+    /// Only the intel categories the requester needs; the request is not
+    /// serviced while all of them are fresh. Defaults to all.
+    pub fn categories(mut self, categories: VisibilityCategoryFlags) -> Self {
+        self.categories = categories;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -424,6 +554,51 @@ mod tests {
         let _ = VisibilityRequest::new_opportunistic(room, f32::INFINITY, VisibilityRequestFlags::SCOUT);
     }
 
+    // ── Per-category freshness ──────────────────────────────────────────────
+
+    #[test]
+    fn static_intel_never_goes_stale_once_seen() {
+        let categories = VisibilityCategoryFlags::STATIC;
+
+        assert_eq!(categories_overdue(categories, None, false, true), Some(u32::MAX));
+        assert_eq!(categories_overdue(categories, Some(100_000), true, true), None);
+    }
+
+    #[test]
+    fn hostiles_go_stale_faster_in_rooms_with_missions() {
+        let categories = VisibilityCategoryFlags::HOSTILES;
+
+        assert_eq!(categories_overdue(categories, Some(HOSTILES_REFRESH_TTL_ACTIVE), true, true), None);
+        assert_eq!(
+            categories_overdue(categories, Some(HOSTILES_REFRESH_TTL_ACTIVE + 5), true, true),
+            Some(5)
+        );
+        assert_eq!(
+            categories_overdue(categories, Some(HOSTILES_REFRESH_TTL_ACTIVE + 5), true, false),
+            None
+        );
+    }
+
+    #[test]
+    fn overdue_is_the_stalest_requested_category() {
+        let age = Some(STRUCTURES_REFRESH_TTL + 10);
+
+        assert_eq!(categories_overdue(VisibilityCategoryFlags::STRUCTURES, age, true, false), Some(10));
+        assert_eq!(
+            categories_overdue(VisibilityCategoryFlags::ALL, age, true, false),
+            Some(STRUCTURES_REFRESH_TTL + 10 - HOSTILES_REFRESH_TTL_IDLE)
+        );
+        // No categories asks for everything.
+        assert_eq!(
+            categories_overdue(VisibilityCategoryFlags::empty(), age, true, false),
+            categories_overdue(VisibilityCategoryFlags::ALL, age, true, false)
+        );
+        assert_eq!(
+            categories_overdue(VisibilityCategoryFlags::STRUCTURES, None, true, false),
+            Some(u32::MAX)
+        );
+    }
+
     // ── Scout give-up backoff (reachability) ────────────────────────────────
 
     #[test]
@@ -518,6 +693,18 @@ impl<'a> System<'a> for VisibilityQueueCleanupSystem {
         // Release claims for dead scout creeps.
         data.visibility_queue.release_dead(&data.entities);
 
+        // Work out which requests still need servicing, and how urgently.
+        let room_data = &data.room_data;
+        let mapping = &data.mapping;
+
+        data.visibility_queue.assess(|room_name, categories| {
+            mapping
+                .get_room(&room_name)
+                .and_then(|entity| room_data.get(entity))
+                .map(|room_data| room_data.visibility_overdue(categories))
+                .unwrap_or(Some(u32::MAX))
+        });
+
         // Create RoomData entities for rooms in the queue that don't have one yet.
         let existing_rooms: std::collections::HashSet<RoomName> = (&data.entities, &data.room_data).join().map(|(_, rd)| rd.name).collect();
 
@@ -577,6 +764,7 @@ pub struct ObserverSystemData<'a> {
     entities: Entities<'a>,
     room_data: ReadStorage<'a, RoomData>,
     mapping: Read<'a, EntityMappingData>,
+    intent_recorder: Write<'a, IntentRecorder>,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -588,12 +776,13 @@ impl<'a> System<'a> for ObserverSystem {
             return;
         }
 
-        // Collect entries that want OBSERVE.
-        let mut observe_entries: Vec<(RoomName, f32, bool)> = data
+        // Collect entries that want OBSERVE and have intel due.
+        let mut observe_entries: Vec<(RoomName, f32, bool, u32)> = data
             .visibility_queue
             .entries
             .iter()
             .filter(|e| e.allowed_types.contains(VisibilityRequestFlags::OBSERVE))
+            .filter(|e| !data.visibility_queue.is_fresh(e.room_name))
             .map(|e| {
                 let claimed = data
                     .visibility_queue
@@ -601,14 +790,15 @@ impl<'a> System<'a> for ObserverSystem {
                     .get(&e.room_name)
                     .map(|r| r.claimed_by.is_some())
                     .unwrap_or(false);
-                (e.room_name, e.priority, claimed)
+                (e.room_name, e.priority, claimed, data.visibility_queue.overdue(e.room_name))
             })
             .collect();
 
-        // Sort: unclaimed first, then by priority descending.
+        // Sort: unclaimed first, then by priority descending, then most overdue.
         observe_entries.sort_by(|a, b| {
             a.2.cmp(&b.2) // false (unclaimed) < true (claimed) — unclaimed first
                 .then_with(|| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal).reverse())
+                .then_with(|| b.3.cmp(&a.3))
         });
 
         // Gather available observers from home rooms.
@@ -632,7 +822,7 @@ impl<'a> System<'a> for ObserverSystem {
             .collect();
 
         // Assign observers to entries.
-        for (room_name, _priority, _claimed) in &observe_entries {
+        for (room_name, _priority, _claimed, _overdue) in &observe_entries {
             let observer = home_room_observers
                 .iter_mut()
                 .filter(|(_, obs)| !obs.is_empty())
@@ -646,7 +836,10 @@ impl<'a> System<'a> for ObserverSystem {
                 .and_then(|(_, obs, _)| obs.pop());
 
             if let Some(observer) = observer {
-                match observer.observe_room(*room_name) {
+                match data
+                    .intent_recorder
+                    .issued(IntentCategory::Structure, observer.observe_room(*room_name))
+                {
                     Ok(()) => {
                         data.visibility_queue.mark_observer_serviced(*room_name);
                     }
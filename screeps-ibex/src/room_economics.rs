@@ -53,6 +53,9 @@ const MOVE_COST: f64 = 50.0;
 /// `BODYPART_COST[CLAIM]` — a reserver is one CLAIM (600) + one MOVE (50); the hold cost of *controlling* a
 /// reservable remote (keeping the reservation up so the sources yield the reserved 3000/cycle).
 const CLAIM_COST: f64 = 600.0;
+/// Road upkeep per tile (e/t): a plain road loses `ROAD_DECAY_AMOUNT` (100) hits every `ROAD_DECAY_TIME`
+/// (1000) ticks and `REPAIR_POWER` puts back 100 hits per energy.
+const ROAD_UPKEEP_PER_TILE: f64 = 100.0 / 1000.0 / 100.0;
 /// Rough per-tile pathfinding-CPU charge (in energy-equivalent e/t) so distant rooms are penalised even
 /// when the haul body alone would pencil out (ADR 0018 §3.2 — same figure the SK scorer uses).
 const CPU_PENALTY_PER_TILE: f64 = 0.02;
@@ -103,6 +106,9 @@ pub struct RoomEconomyFacts {
     /// Horizon (ticks) the net e/t accrues over for the total energy-equivalent value. `<= 0` ⇒
     /// [`DEFAULT_HOLD_HORIZON`].
     pub horizon: f64,
+    /// Road tiles the holder keeps repaired on the haul path. Each costs [`ROAD_UPKEEP_PER_TILE`], and the
+    /// roaded share of `haul_tiles` halves the haulers' MOVE. 0 ⇒ an unroaded haul.
+    pub road_tiles: u32,
 }
 
 impl RoomEconomyFacts {
@@ -117,9 +123,15 @@ impl RoomEconomyFacts {
             hold_model: HoldModel::Reserve,
             hold_body_cost: 0,
             horizon: DEFAULT_HOLD_HORIZON,
+            road_tiles: 0,
         }
     }
 
+    /// The same facts with `road_tiles` of road kept up on the haul path (a remote mine roads its haul).
+    pub fn with_roads(self, road_tiles: u32) -> Self {
+        Self { road_tiles, ..self }
+    }
+
     /// An OWNED (claimed) colony's own room: sources restore to the owned yield (3000/cycle, identical to a
     /// reserved room — the upside claiming unlocks over a neutral room's 1500), are mined and hauled
     /// **INTERNALLY** (a small intra-room `internal_haul_tiles`, **distance-independent** — a claimed room
@@ -140,6 +152,7 @@ impl RoomEconomyFacts {
             hold_model: HoldModel::None,
             hold_body_cost: 0,
             horizon: DEFAULT_HOLD_HORIZON,
+            road_tiles: 0,
         }
     }
 }
@@ -150,7 +163,7 @@ impl RoomEconomyFacts {
 pub struct RoomEconomyValue {
     /// Gross energy/tick the room's sources yield (before any cost).
     pub gross_per_tick: f64,
-    /// Net energy/tick = gross − hold − mining − haul − cpu, floored at 0 (a net-negative room is worth 0).
+    /// Net energy/tick = gross − hold − mining − haul − roads − cpu, floored at 0 (a net-negative room is worth 0).
     pub net_per_tick: f64,
     /// Total energy-equivalent net-ROI = `net_per_tick × horizon` (floored at 0) — the value the EV layer
     /// weighs by `P(win)`. THIS is "the economic value unlocked by controlling the room."
@@ -159,13 +172,15 @@ pub struct RoomEconomyValue {
 
 /// THE pure room-economics net-ROI kernel (ADR 0032). Generalizes the SK net model to ANY controlled room.
 ///
-/// `net e/t = gross − hold − mining − haul − cpu`, then `net_roi = max(net, 0) × horizon`:
+/// `net e/t = gross − hold − mining − haul − roads − cpu`, then `net_roi = max(net, 0) × horizon`:
 /// - **gross** = `source_count × source_capacity / regen` (reserved 3000/cycle for a reserved remote).
 /// - **hold** = reserver upkeep (CLAIM+MOVE / lifetime) for `Reserve`, the suppression body / lifetime for
 ///   `Suppress`, 0 for `None`.
 /// - **mining** = WORK to saturate the gross yield (`gross / HARVEST_POWER`) + 2 MOVE/source, amortized.
 /// - **haul** = CARRY (+ matched MOVE) to move `gross` over a `2 × haul_tiles` round trip, amortized — the
-///   term that grows with distance and kills far rooms.
+///   term that grows with distance and kills far rooms. On the roaded share of the path a MOVE carries two
+///   CARRY, so roads halve that share's MOVE.
+/// - **roads** = `road_tiles × ROAD_UPKEEP_PER_TILE`, the repair energy to hold the roads against decay.
 /// - **cpu** = `haul_tiles × CPU_PENALTY_PER_TILE` (the distance penalty even when the body pencils out).
 ///
 /// A room with no exploitable economy (`source_count == 0`) ⇒ gross 0 ⇒ net floored at 0 ⇒ `net_roi 0`.
@@ -187,14 +202,17 @@ pub fn room_net_roi(facts: &RoomEconomyFacts) -> RoomEconomyValue {
     let mining_body = work_parts * WORK_COST + n * 2.0 * MOVE_COST;
     let mining = mining_body / CREEP_LIFETIME;
 
-    // Haul: CARRY (+ matched MOVE) to move `gross` over a `2 × dist` round trip, amortized.
+    // Haul: CARRY (+ matched MOVE, halved where roaded) to move `gross` over a `2 × dist` round trip, amortized.
     let carry_parts = gross * 2.0 * facts.haul_tiles as f64 / CARRY_CAPACITY;
-    let haul_body = carry_parts * (CARRY_COST + MOVE_COST);
+    let roaded = if facts.haul_tiles > 0 { (facts.road_tiles as f64 / facts.haul_tiles as f64).min(1.0) } else { 0.0 };
+    let haul_body = carry_parts * (CARRY_COST + MOVE_COST * (1.0 - 0.5 * roaded));
     let haul = haul_body / CREEP_LIFETIME;
 
+    let roads = facts.road_tiles as f64 * ROAD_UPKEEP_PER_TILE;
+
     let cpu_penalty = facts.haul_tiles as f64 * CPU_PENALTY_PER_TILE;
 
-    let net = gross - hold - mining - haul - cpu_penalty;
+    let net = gross - hold - mining - haul - roads - cpu_penalty;
     let net_floored = net.max(0.0);
 
     let horizon = if facts.horizon > 0.0 { facts.horizon } else { DEFAULT_HOLD_HORIZON };
@@ -234,7 +252,15 @@ mod tests {
     /// Determinism: same facts → byte-identical value (no HashMap, no time/world).
     #[test]
     fn room_net_roi_is_deterministic() {
-        let facts = RoomEconomyFacts { source_count: 3, source_capacity: 2500.0, haul_tiles: 7, hold_model: HoldModel::Reserve, hold_body_cost: 0, horizon: 1200.0 };
+        let facts = RoomEconomyFacts {
+            source_count: 3,
+            source_capacity: 2500.0,
+            haul_tiles: 7,
+            hold_model: HoldModel::Reserve,
+            hold_body_cost: 0,
+            horizon: 1200.0,
+            road_tiles: 0,
+        };
         let a = room_net_roi(&facts);
         let b = room_net_roi(&facts);
         assert_eq!(a, b);
@@ -252,6 +278,7 @@ mod tests {
             hold_model: HoldModel::Reserve,
             hold_body_cost: 0,
             horizon: DEFAULT_HOLD_HORIZON,
+            road_tiles: 0,
         });
         let suppress = room_net_roi(&RoomEconomyFacts {
             source_count: 3,
@@ -260,7 +287,17 @@ mod tests {
             hold_model: HoldModel::Suppress,
             hold_body_cost: 5350, // the SK duo body cost (ops::sourcekeeper SK_DUO_BODY_COST)
             horizon: DEFAULT_HOLD_HORIZON,
+            road_tiles: 0,
         });
         assert!(suppress.net_per_tick < reserve.net_per_tick, "suppression hold is dearer than a reserver");
     }
+
+    /// Roads pay their upkeep back in haul MOVE: a roaded remote nets more than the same remote unroaded.
+    #[test]
+    fn roaded_haul_nets_more_despite_upkeep() {
+        let unroaded = room_net_roi(&RoomEconomyFacts::reservable_remote(2, 80));
+        let roaded = room_net_roi(&RoomEconomyFacts::reservable_remote(2, 80).with_roads(80));
+
+        assert!(roaded.net_per_tick > unroaded.net_per_tick, "roaded ({}) > unroaded ({})", roaded.net_per_tick, unroaded.net_per_tick);
+    }
 }
@@ -14,7 +14,7 @@
 //! | Seg   | Owner / contents                                            |
 //! |-------|-------------------------------------------------------------|
 //! | 50–53 | ECS component payload (`COMPONENT_SEGMENTS`)                |
-//! | 54    | *freed 2026-06-12* (former 5th component chunk — shrunk to fund the always-active market segment; stale chunk data may linger server-side until reused) |
+//! | 54    | corrupt-world backup (`RECOVERY_BACKUP_SEGMENT`; former 5th component chunk, freed 2026-06-12) |
 //! | 55    | cost-matrix cache (`COST_MATRIX_SEGMENT`)                   |
 //! | 56    | stats history (`STATS_HISTORY_SEGMENT`)                     |
 //! | 57    | metrics block (`METRICS_SEGMENT`, ADR 0006 / P1.A1)         |
@@ -69,17 +69,25 @@ pub const METRICS_SEGMENT: u32 = 57;
 /// that stay outside the active set.
 pub const MARKET_SEGMENT: u32 = 58;
 
+/// Backup of an unreadable world payload (`memorysystem::MemoryRecovery`),
+/// written once per recovery through the arbiter's `queue_write` — never
+/// active otherwise, so it costs no slot in steady state. Holds the raw
+/// encoded prefix (first segment's worth) for offline diagnosis; nothing
+/// reads it back.
+pub const RECOVERY_BACKUP_SEGMENT: u32 = 54;
+
 /// Room-planner resume state (`room::roomplansystem`).
 pub const PLANNER_MEMORY_SEGMENT: u32 = 60;
 
-/// Live stats consumed by external tooling (`statssystem`; legacy JSON).
+/// Live stats consumed by external tooling (`statssystem`; legacy JSON),
+/// written through the `memorysystem::SegmentManager`.
 pub const LIVE_STATS_SEGMENT: u32 = 99;
 
 /// Every registered non-component id, including the reserved-but-unbuilt
 /// ones, so the uniqueness check covers the WHOLE ADR 0002 table. When a
 /// reserved id gets implemented, replace the literal with its new constant.
 const OTHER_SEGMENT_IDS: &[u32] = &[
-    54, // freed: former 5th component chunk (shrunk 2026-06-12); reusable by a future allocation
+    RECOVERY_BACKUP_SEGMENT,
     COST_MATRIX_SEGMENT,
     STATS_HISTORY_SEGMENT,
     METRICS_SEGMENT,
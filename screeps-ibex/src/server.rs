@@ -0,0 +1,183 @@
+//! What the server we run on supports.
+//!
+//! Private servers may run without the market mod, in which case every
+//! `Game.market` call throws, and may not report a shard at all. The bot
+//! probes the `Game` object once per environment and keeps the answer in the
+//! [`ServerCapabilities`] resource; market trading, the market stats and the
+//! screepspl.us stats export read it instead of assuming an official shard.
+//! Terminal balancing needs nothing from the market (`Terminal.send` and the
+//! transaction cost are core API), so it runs everywhere.
+//!
+//! `Memory._features.server` overrides what was detected, e.g.
+//! `Memory._features.server.market = false` to try the market-free mode on an
+//! official shard.
+
+use crate::features::ServerFeatures;
+use log::*;
+use wasm_bindgen::JsValue;
+
+/// What the `Game` object answered when the environment was created.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServerProbe {
+    /// `Game.market` is present with its credits and order API.
+    pub market: bool,
+    /// `Game.shard.name`, when the server reports one.
+    pub shard: Option<String>,
+}
+
+impl ServerProbe {
+    pub fn run() -> ServerProbe {
+        let game = js_get(&js_sys::global(), "Game");
+        let market = js_get(&game, "market");
+        let shard = js_get(&js_get(&game, "shard"), "name");
+
+        ServerProbe {
+            market: js_get(&market, "credits").as_f64().is_some() && js_get(&market, "getAllOrders").is_function(),
+            shard: shard.as_string(),
+        }
+    }
+}
+
+/// Whether `name` is one of the official servers' shards: the numbered MMO
+/// shards or the seasonal one.
+fn is_official_shard(name: &str) -> bool {
+    name == "shardSeason"
+        || name
+            .strip_prefix("shard")
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// World resource: what the bot may rely on, after overrides.
+#[derive(Debug, Clone, Default)]
+pub struct ServerCapabilities {
+    pub probe: ServerProbe,
+    /// Market orders, history and credits can be used.
+    pub market: bool,
+    /// An official shard; the default for `stats_export`.
+    pub official: bool,
+    /// Write the stats segment the screepspl.us agent reads.
+    pub stats_export: bool,
+}
+
+impl ServerCapabilities {
+    pub fn detect() -> ServerCapabilities {
+        let capabilities = ServerCapabilities::from_probe(ServerProbe::run(), &ServerFeatures::default());
+
+        info!(
+            "Server capabilities: shard={} market={} official={}",
+            capabilities.probe.shard.as_deref().unwrap_or("<none>"),
+            capabilities.market,
+            capabilities.official
+        );
+
+        capabilities
+    }
+
+    pub fn from_probe(probe: ServerProbe, overrides: &ServerFeatures) -> ServerCapabilities {
+        let mut capabilities = ServerCapabilities {
+            probe,
+            ..Default::default()
+        };
+
+        capabilities.apply(overrides);
+
+        capabilities
+    }
+
+    /// Recompute the capabilities from the probe with this tick's overrides.
+    /// An override can hide the market but not conjure one, as the calls
+    /// would throw.
+    pub fn apply(&mut self, overrides: &ServerFeatures) {
+        let detected_official = self.probe.market && self.probe.shard.as_deref().is_some_and(is_official_shard);
+
+        self.market = self.probe.market && overrides.market.unwrap_or(true);
+        self.official = overrides.official.unwrap_or(detected_official);
+        self.stats_export = overrides.stats_export.unwrap_or(self.official);
+    }
+
+    /// Key for this shard's stats; a server without shards has just the one.
+    pub fn shard_name(&self) -> &str {
+        self.probe.shard.as_deref().unwrap_or("shard0")
+    }
+}
+
+#[inline]
+fn js_get(parent: &JsValue, key: &str) -> JsValue {
+    if parent.is_undefined() || parent.is_null() {
+        return JsValue::UNDEFINED;
+    }
+
+    js_sys::Reflect::get(parent, &JsValue::from_str(key)).unwrap_or(JsValue::UNDEFINED)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(market: bool, shard: Option<&str>) -> ServerProbe {
+        ServerProbe {
+            market,
+            shard: shard.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn official_shards_are_numbered_or_seasonal() {
+        assert!(is_official_shard("shard0"));
+        assert!(is_official_shard("shard3"));
+        assert!(is_official_shard("shardSeason"));
+        assert!(!is_official_shard("shard"));
+        assert!(!is_official_shard("shardX"));
+        assert!(!is_official_shard("private"));
+    }
+
+    #[test]
+    fn a_private_server_without_market_exports_nothing() {
+        let capabilities = ServerCapabilities::from_probe(probe(false, None), &ServerFeatures::default());
+
+        assert!(!capabilities.market);
+        assert!(!capabilities.official);
+        assert!(!capabilities.stats_export);
+        assert_eq!(capabilities.shard_name(), "shard0");
+    }
+
+    #[test]
+    fn an_official_shard_trades_and_exports() {
+        let capabilities = ServerCapabilities::from_probe(probe(true, Some("shard2")), &ServerFeatures::default());
+
+        assert!(capabilities.market);
+        assert!(capabilities.official);
+        assert!(capabilities.stats_export);
+        assert_eq!(capabilities.shard_name(), "shard2");
+    }
+
+    #[test]
+    fn overrides_can_hide_the_market_but_not_add_one() {
+        let hide = ServerFeatures {
+            market: Some(false),
+            ..Default::default()
+        };
+        let add = ServerFeatures {
+            market: Some(true),
+            ..Default::default()
+        };
+
+        assert!(!ServerCapabilities::from_probe(probe(true, Some("shard0")), &hide).market);
+        assert!(!ServerCapabilities::from_probe(probe(false, Some("shard0")), &add).market);
+    }
+
+    #[test]
+    fn stats_export_follows_official_unless_overridden() {
+        let private_export = ServerFeatures {
+            stats_export: Some(true),
+            ..Default::default()
+        };
+        let official_quiet = ServerFeatures {
+            stats_export: Some(false),
+            ..Default::default()
+        };
+
+        assert!(ServerCapabilities::from_probe(probe(true, Some("screepsplus")), &private_export).stats_export);
+        assert!(!ServerCapabilities::from_probe(probe(true, Some("shard1")), &official_quiet).stats_export);
+    }
+}
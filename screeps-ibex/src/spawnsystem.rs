@@ -1,7 +1,12 @@
 use crate::creep::CreepOwner;
+use crate::intents::{IntentCategory, IntentRecorder};
+use crate::ledger::{LedgerCategory, ResourceLedger};
 use crate::military::economy::{EconomySnapshot, SpawnQueueSnapshot};
+use crate::military::posture::DefensePosture;
+use crate::powercreepsystem::*;
 use crate::room::data::*;
 use crate::room::roomplansystem::RoomPlanData;
+use crate::structureidentifier::RemoteStructureIdentifier;
 // The unsigned 0..49 room-tile type the planner stores in `Plan::spawn_approaches`.
 // Aliased away from the bare name to avoid confusion with the distinct signed
 // `screeps_common::PlanLocation` (i8, supports negative stamp offsets).
@@ -38,6 +43,14 @@ pub const SPAWN_PRIORITY_MEDIUM: f32 = 50.0;
 pub const SPAWN_PRIORITY_LOW: f32 = 25.0;
 pub const SPAWN_PRIORITY_NONE: f32 = 0.0;
 
+/// Slack on a replacement's lead time for the spawn being busy or short of
+/// energy when the request comes due.
+pub const REPLACEMENT_MARGIN_TICKS: u32 = 10;
+
+/// Ticks the queue remembers serving a requester. A requester unserved for
+/// longer ranks as never served, which is where it would sort anyway.
+pub const SPAWN_FAIRNESS_MEMORY_TICKS: u32 = CREEP_LIFE_TIME;
+
 /// Exclusive upper bound on a room tile coordinate (rooms are 50x50, 0..=49).
 const ROOM_COORD_MAX: i32 = 50;
 /// 8-directional neighbour offsets, ordered to match [`SpawnQueueSystem::delta_to_direction`].
@@ -77,6 +90,9 @@ struct LiveSpawnContext {
     terrain: FastRoomTerrain,
     creep_tiles: HashSet<(u8, u8)>,
     blocked_tiles: HashSet<(u8, u8)>,
+    /// Standable but poor landing tiles: containers (a miner's or upgrader's
+    /// seat) and roads (hub traffic), built or planned as a site.
+    crowded_tiles: HashSet<(u8, u8)>,
 }
 
 impl LiveSpawnContext {
@@ -109,16 +125,26 @@ impl LiveSpawnContext {
         // sites here lets `safe_spawn_directions` exclude those tiles in Tier 1
         // and Tier 2 and fall through to the unconstrained Tier 3, where the
         // engine itself picks a genuinely free tile.
+        let mut crowded_tiles: HashSet<(u8, u8)> = structures
+            .containers()
+            .iter()
+            .map(|c| c.pos())
+            .chain(structures.roads().iter().map(|r| r.pos()))
+            .map(|p| (p.x().u8(), p.y().u8()))
+            .collect();
         for site in construction_sites {
+            let p = site.pos();
             if site_blocks_spawn(site.structure_type()) {
-                let p = site.pos();
                 blocked_tiles.insert((p.x().u8(), p.y().u8()));
+            } else if matches!(site.structure_type(), StructureType::Container | StructureType::Road) {
+                crowded_tiles.insert((p.x().u8(), p.y().u8()));
             }
         }
         LiveSpawnContext {
             terrain,
             creep_tiles,
             blocked_tiles,
+            crowded_tiles,
         }
     }
 
@@ -148,12 +174,23 @@ impl LiveSpawnContext {
 #[derive(Copy, Clone, Eq, PartialEq, Hash)]
 pub struct SpawnToken(u32);
 
+/// The creep a spawn request replaces: how long it has left and how far
+/// the replacement walks from the spawn to take over.
+#[derive(Clone, Copy, Debug)]
+struct Replacement {
+    ticks_to_live: u32,
+    distance: u32,
+}
+
 pub struct SpawnRequest {
     description: String,
     body: Vec<Part>,
     priority: f32,
     token: Option<SpawnToken>,
     callback: SpawnQueueCallback,
+    toward: Option<Position>,
+    replacing: Option<Replacement>,
+    owner: Option<Entity>,
 }
 
 impl SpawnRequest {
@@ -168,9 +205,44 @@ impl SpawnRequest {
             priority,
             token,
             callback,
+            toward: None,
+            replacing: None,
+            owner: None,
         }
     }
 
+    /// Make this the replacement for a creep with `ticks_to_live` left,
+    /// working `distance` tiles (by cached path) from the spawn. The queue
+    /// takes it only once [`replacement_lead_time`] reaches the old creep's
+    /// life, so the new creep arrives as the old one dies.
+    pub fn replacing(mut self, ticks_to_live: u32, distance: u32) -> SpawnRequest {
+        self.replacing = Some(Replacement { ticks_to_live, distance });
+        self
+    }
+
+    /// Whether the request should spawn now: always, unless it replaces a
+    /// creep that will outlive the replacement's spawn and walk.
+    pub fn is_due(&self) -> bool {
+        self.replacing
+            .map(|r| r.ticks_to_live <= replacement_lead_time(&self.body, r.distance))
+            .unwrap_or(true)
+    }
+
+    /// Prefer spawning the creep on the side of the spawn nearest `target`
+    /// (e.g. storage for a hauler, the threatened room for a defender).
+    pub fn toward(mut self, target: Position) -> SpawnRequest {
+        self.toward = Some(target);
+        self
+    }
+
+    /// Attribute the request to `owner` (the mission or squad asking), so
+    /// requesters of the same priority take turns instead of the first to
+    /// queue winning every tick.
+    pub fn requested_by(mut self, owner: Entity) -> SpawnRequest {
+        self.owner = Some(owner);
+        self
+    }
+
     pub fn cost(&self) -> u32 {
         self.body.iter().map(|p| p.cost()).sum()
     }
@@ -182,6 +254,61 @@ impl SpawnRequest {
     pub fn description(&self) -> &str {
         &self.description
     }
+
+    pub fn body(&self) -> &[Part] {
+        &self.body
+    }
+
+    /// Run the request's spawn callback for an already-living creep `name`, handing it to the requester
+    /// in place of a fresh spawn.
+    pub fn fulfill(&self, system_data: &SpawnQueueExecutionSystemData, name: &str) {
+        (*self.callback)(system_data, name);
+    }
+}
+
+/// Estimate how many ticks it takes for a creep with the given body to
+/// traverse `distance` tiles, assuming roads are present. Returns the
+/// travel time in ticks.
+///
+/// Screeps movement on roads:
+///   fatigue_per_tile = MOVE_COST_ROAD (1) * non_move_parts
+///   fatigue_removed_per_tick = MOVE_POWER (2) * move_parts
+///   ticks_per_tile = ceil(fatigue_per_tile / fatigue_removed_per_tick)
+///
+/// If the creep has no MOVE parts it cannot move; returns u32::MAX.
+pub fn estimate_travel_ticks(body: &[Part], distance: u32) -> u32 {
+    let move_parts = body.iter().filter(|p| **p == Part::Move).count() as u32;
+    if move_parts == 0 {
+        return u32::MAX;
+    }
+
+    let non_move_parts = body.len() as u32 - move_parts;
+    let fatigue_per_tile = MOVE_COST_ROAD * non_move_parts;
+    let fatigue_removed_per_tick = MOVE_POWER * move_parts;
+
+    // Ceiling division: ticks needed to clear fatigue from one road tile.
+    let ticks_per_tile = fatigue_per_tile.div_ceil(fatigue_removed_per_tick);
+    // At minimum 1 tick per tile (even with excess MOVE parts).
+    let ticks_per_tile = ticks_per_tile.max(1);
+
+    distance * ticks_per_tile
+}
+
+/// Ticks before a creep dies that its replacement with `body` must be
+/// spawned to arrive `distance` tiles from the spawn in time: the spawn
+/// time, the walk and [`REPLACEMENT_MARGIN_TICKS`].
+pub fn replacement_lead_time(body: &[Part], distance: u32) -> u32 {
+    (body.len() as u32 * CREEP_SPAWN_TIME)
+        .saturating_add(estimate_travel_ticks(body, distance))
+        .saturating_add(REPLACEMENT_MARGIN_TICKS)
+}
+
+/// Whether a creep with `body` can stand in for a spawn request for `requested`: it has exactly the
+/// requested part types, and at least as many of each.
+pub fn is_compatible_body(body: &[Part], requested: &[Part]) -> bool {
+    let count = |parts: &[Part], part: Part| parts.iter().filter(|p| **p == part).count();
+
+    !requested.is_empty() && body.iter().all(|p| requested.contains(p)) && requested.iter().all(|p| count(body, *p) >= count(requested, *p))
 }
 
 /// Ephemeral renew request for one creep in a room. Cleared when queue is processed.
@@ -197,6 +324,15 @@ pub struct SpawnQueue {
     requests: HashMap<Entity, Vec<SpawnRequest>>,
     /// Per-room renew requests; ephemeral, cleared when queue is processed.
     renew_requests: HashMap<Entity, Vec<RenewRequest>>,
+    /// Tick each requester last had a request spawned or fulfilled. Kept
+    /// across ticks so equal-priority requesters are served round-robin.
+    last_serviced: HashMap<Entity, u32>,
+}
+
+/// Where `request` queues among requests of its priority: requesters served
+/// longest ago (or never, including unattributed requests) first.
+fn service_rank(last_serviced: &HashMap<Entity, u32>, request: &SpawnRequest) -> Option<u32> {
+    request.owner.and_then(|owner| last_serviced.get(&owner).copied())
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -209,7 +345,16 @@ impl SpawnQueue {
         token
     }
 
+    /// Queue `spawn_request` for `room`, behind requests of the same
+    /// priority from requesters served less recently. A replacement that is
+    /// not due yet is dropped; its mission asks again next tick.
     pub fn request(&mut self, room: Entity, spawn_request: SpawnRequest) {
+        if !spawn_request.is_due() {
+            return;
+        }
+
+        let last_serviced = &self.last_serviced;
+        let rank = service_rank(last_serviced, &spawn_request);
         let requests = self.requests.entry(room).or_default();
 
         let pos = requests
@@ -218,6 +363,7 @@ impl SpawnQueue {
                     .priority
                     .partial_cmp(&probe.priority)
                     .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| service_rank(last_serviced, probe).cmp(&rank))
             })
             .unwrap_or_else(|e| e);
 
@@ -238,12 +384,314 @@ impl SpawnQueue {
         self.renew_requests.clear();
     }
 
+    /// Record that `owner` had a request served at `now`, forgetting
+    /// requesters unserved for [`SPAWN_FAIRNESS_MEMORY_TICKS`].
+    pub fn serviced(&mut self, owner: Entity, now: u32) {
+        self.last_serviced
+            .retain(|_, tick| now.saturating_sub(*tick) < SPAWN_FAIRNESS_MEMORY_TICKS);
+        self.last_serviced.insert(owner, now);
+    }
+
+    /// Remove and return the highest-priority request `accept` takes, with the room it was queued in,
+    /// preferring the least recently served requester among equals. Requests sharing its token are
+    /// dropped too - the token is spent.
+    pub fn take_request(&mut self, now: u32, accept: impl Fn(Entity, &SpawnRequest) -> bool) -> Option<(Entity, SpawnRequest)> {
+        let last_serviced = &self.last_serviced;
+        let (room, index) = self
+            .requests
+            .iter()
+            .flat_map(|(room, requests)| requests.iter().enumerate().map(move |(index, request)| (*room, index, request)))
+            .filter(|(room, _, request)| accept(*room, request))
+            .max_by(|(_, _, a), (_, _, b)| {
+                a.priority
+                    .partial_cmp(&b.priority)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| service_rank(last_serviced, b).cmp(&service_rank(last_serviced, a)))
+            })
+            .map(|(room, index, _)| (room, index))?;
+
+        let request = self.requests.get_mut(&room)?.remove(index);
+
+        if let Some(owner) = request.owner {
+            self.serviced(owner, now);
+        }
+
+        if let Some(token) = request.token {
+            for requests in self.requests.values_mut() {
+                requests.retain(|r| r.token != Some(token));
+            }
+        }
+
+        Some((room, request))
+    }
+
     /// Iterate over (room_entity, requests) for visualization/gather systems.
     pub fn iter_requests(&self) -> std::collections::hash_map::Iter<'_, Entity, Vec<SpawnRequest>> {
         self.requests.iter()
     }
 }
 
+/// Ticks a request may wait before the spawn system logs a warning about it.
+pub const SPAWN_WAIT_WARN_TICKS: u32 = 300;
+
+/// Requests held back by busy spawns before the room asks its operator to
+/// speed up a spawn.
+const SPAWN_POWER_BUSY_REQUESTS: usize = 2;
+
+/// Why a queued request did not spawn this tick.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpawnBlockReason {
+    /// The room has the capacity but not the energy right now.
+    InsufficientEnergy,
+    /// The body costs more than the room's full energy capacity.
+    ExceedsCapacity,
+    /// Every active spawn is already spawning.
+    SpawnsBusy,
+    /// A request sharing its token spawned earlier this tick.
+    TokenConsumed,
+    /// A higher-priority request stopped the queue (it is holding the energy).
+    QueuedBehind,
+    /// `spawnCreep` returned an error other than not enough energy.
+    SpawnFailed,
+    /// The `spawning` feature is off for the room.
+    SpawningDisabled,
+    /// The room is in lockdown and the creep would head out of it (`military::posture`).
+    Lockdown,
+}
+
+impl SpawnBlockReason {
+    pub fn label(self) -> &'static str {
+        match self {
+            SpawnBlockReason::InsufficientEnergy => "energy",
+            SpawnBlockReason::ExceedsCapacity => "over capacity",
+            SpawnBlockReason::SpawnsBusy => "spawns busy",
+            SpawnBlockReason::TokenConsumed => "token used",
+            SpawnBlockReason::QueuedBehind => "queued",
+            SpawnBlockReason::SpawningDisabled => "disabled",
+            SpawnBlockReason::SpawnFailed => "failed",
+            SpawnBlockReason::Lockdown => "lockdown",
+        }
+    }
+}
+
+/// Outcome of one unsatisfied request on the tick it was last evaluated.
+#[derive(Clone, Debug)]
+pub struct SpawnRequestStatus {
+    pub description: String,
+    /// Index among the room's requests with the same description.
+    pub occurrence: usize,
+    pub priority: f32,
+    pub cost: u32,
+    pub energy_available: u32,
+    pub ticks_waiting: u32,
+    pub reason: SpawnBlockReason,
+}
+
+/// Identifies a request across ticks. Missions re-enqueue every tick, so a
+/// request is the same one for as long as its room keeps asking for the same
+/// description; repeated descriptions are told apart by their queue order.
+type SpawnWaitKey = (Entity, String, usize);
+
+struct SpawnWait {
+    enqueued: u32,
+    warned: bool,
+    seen: bool,
+}
+
+/// Why each queued request was not satisfied, and how long it has been
+/// waiting. Written by `SpawnQueueSystem` as it walks the queue, so readers
+/// earlier in the tick see the previous tick's outcome.
+#[derive(Default)]
+pub struct SpawnDiagnostics {
+    waits: HashMap<SpawnWaitKey, SpawnWait>,
+    rooms: HashMap<Entity, Vec<SpawnRequestStatus>>,
+}
+
+impl SpawnDiagnostics {
+    fn begin_tick(&mut self) {
+        self.rooms.clear();
+
+        for wait in self.waits.values_mut() {
+            wait.seen = false;
+        }
+    }
+
+    /// Record a request that did not spawn. Returns the ticks it has waited
+    /// the first time that passes [`SPAWN_WAIT_WARN_TICKS`].
+    fn record(
+        &mut self,
+        room: Entity,
+        request: &SpawnRequest,
+        occurrence: usize,
+        energy_available: u32,
+        reason: SpawnBlockReason,
+        now: u32,
+    ) -> Option<u32> {
+        let wait = self
+            .waits
+            .entry((room, request.description.clone(), occurrence))
+            .or_insert(SpawnWait {
+                enqueued: now,
+                warned: false,
+                seen: false,
+            });
+
+        wait.seen = true;
+
+        let ticks_waiting = now.saturating_sub(wait.enqueued);
+        let overdue = ticks_waiting > SPAWN_WAIT_WARN_TICKS && !wait.warned;
+        wait.warned |= overdue;
+
+        self.rooms.entry(room).or_default().push(SpawnRequestStatus {
+            description: request.description.clone(),
+            occurrence,
+            priority: request.priority,
+            cost: request.cost(),
+            energy_available,
+            ticks_waiting,
+            reason,
+        });
+
+        overdue.then_some(ticks_waiting)
+    }
+
+    /// Forget a request once it spawns so its next instance starts fresh.
+    fn spawned(&mut self, room: Entity, description: &str, occurrence: usize) {
+        self.waits.remove(&(room, description.to_string(), occurrence));
+    }
+
+    /// Drop requests that were not enqueued this tick.
+    fn end_tick(&mut self) {
+        self.waits.retain(|_, wait| wait.seen);
+    }
+
+    /// Unsatisfied requests for a room, in queue order.
+    pub fn room(&self, room: Entity) -> &[SpawnRequestStatus] {
+        self.rooms.get(&room).map(|v| v.as_slice()).unwrap_or(&[])
+    }
+
+    pub fn status(&self, room: Entity, description: &str, occurrence: usize) -> Option<&SpawnRequestStatus> {
+        self.room(room)
+            .iter()
+            .find(|s| s.occurrence == occurrence && s.description == description)
+    }
+}
+
+/// Queue position of each request among those sharing its description.
+pub fn request_occurrences<'a, I>(descriptions: I) -> Vec<usize>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+
+    descriptions
+        .into_iter()
+        .map(|description| {
+            let count = counts.entry(description).or_insert(0);
+            *count += 1;
+            *count - 1
+        })
+        .collect()
+}
+
+/// The queue walk's rules, kept free of game calls so the live spawn pass
+/// and the dry-run [`spawn_report`](SpawnQueueSystem::spawn_report) decide
+/// requests the same way. The caller reports each spawn back with
+/// [`SpawnWalk::spawned`].
+struct SpawnWalk {
+    free_spawns: usize,
+    available_energy: u32,
+    energy_capacity: u32,
+    /// Once set, every later request is blocked for this reason.
+    blocked: Option<SpawnBlockReason>,
+}
+
+impl SpawnWalk {
+    fn new(free_spawns: usize, available_energy: u32, energy_capacity: u32) -> SpawnWalk {
+        SpawnWalk {
+            free_spawns,
+            available_energy,
+            energy_capacity,
+            blocked: None,
+        }
+    }
+
+    /// Whether `request` spawns next, or why not.
+    fn select(&mut self, request: &SpawnRequest, spawned_tokens: &HashSet<SpawnToken>) -> Result<(), SpawnBlockReason> {
+        if let Some(reason) = self.blocked {
+            return Err(reason);
+        }
+
+        if request.token.is_some_and(|t| spawned_tokens.contains(&t)) {
+            return Err(SpawnBlockReason::TokenConsumed);
+        }
+
+        if self.free_spawns == 0 {
+            self.blocked = Some(SpawnBlockReason::SpawnsBusy);
+            return Err(SpawnBlockReason::SpawnsBusy);
+        }
+
+        let cost = request.cost();
+
+        if cost > self.energy_capacity {
+            Err(SpawnBlockReason::ExceedsCapacity)
+        } else if cost > self.available_energy {
+            self.out_of_energy();
+            Err(SpawnBlockReason::InsufficientEnergy)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// The head of the queue is holding the room's energy; stop the walk.
+    fn out_of_energy(&mut self) {
+        self.blocked = Some(SpawnBlockReason::QueuedBehind);
+    }
+
+    fn spawned(&mut self, cost: u32) {
+        self.free_spawns = self.free_spawns.saturating_sub(1);
+        self.available_energy = self.available_energy.saturating_sub(cost);
+    }
+}
+
+/// Energy capacities the dry-run report replays a room's queue against:
+/// each RCL's full extension capacity from RCL 1 to 8.
+const SPAWN_REPORT_CAPACITIES: [u32; 7] = [300, 550, 800, 1300, 1800, 2300, 5600];
+
+/// Rooms whose queue should be replayed in dry-run on the next spawn pass.
+/// Filled by the `spawn_report` console command, which runs before the
+/// missions have queued this tick's requests.
+#[derive(Default)]
+pub struct SpawnReportRequests {
+    rooms: Vec<Entity>,
+}
+
+impl SpawnReportRequests {
+    pub fn request(&mut self, room: Entity) {
+        if !self.rooms.contains(&room) {
+            self.rooms.push(room);
+        }
+    }
+}
+
+/// "2xWork 1xCarry 3xMove", in the order each part first appears.
+fn body_summary(body: &[Part]) -> String {
+    let mut counts: Vec<(Part, usize)> = Vec::new();
+
+    for part in body {
+        match counts.iter_mut().find(|(counted, _)| counted == part) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((*part, 1)),
+        }
+    }
+
+    counts
+        .iter()
+        .map(|(part, count)| format!("{}x{:?}", count, part))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 #[derive(SystemData)]
 pub struct SpawnQueueSystemData<'a> {
     spawn_queue: Write<'a, SpawnQueue>,
@@ -254,6 +702,10 @@ pub struct SpawnQueueSystemData<'a> {
     room_plan_data: ReadStorage<'a, RoomPlanData>,
     creep_owner: ReadStorage<'a, CreepOwner>,
     economy: Read<'a, EconomySnapshot>,
+    features: Read<'a, crate::features::Features>,
+    feature_overrides: Read<'a, crate::features::FeatureOverrides>,
+    spawn_reports: Write<'a, SpawnReportRequests>,
+    defense_postures: ReadStorage<'a, DefensePosture>,
 }
 
 pub struct SpawnQueueExecutionSystemData<'a, 'b> {
@@ -350,6 +802,32 @@ impl SpawnQueueSystem {
         safe
     }
 
+    /// Order `directions` for `spawnCreep`, which places the creep on the first
+    /// free tile in the list. Containers and roads are dropped while any other
+    /// direction remains, so a newborn doesn't sit on a miner's seat or in hub
+    /// traffic; the rest are sorted nearest-first to the request's `toward`
+    /// target. An empty (unconstrained) list is left alone.
+    fn preferred_spawn_directions(
+        spawn_pos: Position,
+        mut directions: Vec<Direction>,
+        live: &LiveSpawnContext,
+        toward: Option<Position>,
+    ) -> Vec<Direction> {
+        let landing = |direction: Direction| spawn_pos.checked_add_direction(direction).ok();
+        let crowded =
+            |direction: Direction| landing(direction).is_some_and(|pos| live.crowded_tiles.contains(&(pos.x().u8(), pos.y().u8())));
+
+        if directions.iter().any(|d| !crowded(*d)) {
+            directions.retain(|d| !crowded(*d));
+        }
+
+        if let Some(target) = toward {
+            directions.sort_by_key(|d| landing(*d).map(|pos| pos.get_range_to(target)).unwrap_or(u32::MAX));
+        }
+
+        directions
+    }
+
     /// Map an adjacent (dx, dy) offset to a `Direction`; `None` if the tiles are
     /// not 8-adjacent (so non-adjacent approaches are filtered out).
     fn delta_to_direction(dx: i32, dy: i32) -> Option<Direction> {
@@ -377,14 +855,144 @@ impl SpawnQueueSystem {
         0
     }
 
+    /// Replay `requests` against each of `capacities` with every one of
+    /// `spawns` idle and the room full, returning per capacity whether each
+    /// request would spawn or why not. Issues no intents.
+    fn dry_run(requests: &[SpawnRequest], spawns: usize, capacities: &[u32]) -> Vec<(u32, Vec<Result<(), SpawnBlockReason>>)> {
+        capacities
+            .iter()
+            .map(|capacity| {
+                let mut walk = SpawnWalk::new(spawns, *capacity, *capacity);
+                let mut spawned_tokens = HashSet::new();
+
+                let outcomes = requests
+                    .iter()
+                    .map(|request| {
+                        let outcome = walk.select(request, &spawned_tokens);
+
+                        if outcome.is_ok() {
+                            walk.spawned(request.cost());
+                            spawned_tokens.extend(request.token);
+                        }
+
+                        outcome
+                    })
+                    .collect();
+
+                (*capacity, outcomes)
+            })
+            .collect()
+    }
+
+    /// Log what the room's current queue would spawn at each RCL's energy
+    /// capacity (the `spawn_report` console command).
+    fn spawn_report(data: &SpawnQueueSystemData, room_entity: Entity) {
+        let Some(room_data) = data.room_data.get(room_entity) else {
+            return;
+        };
+
+        let requests = data.spawn_queue.requests.get(&room_entity).map(|v| v.as_slice()).unwrap_or(&[]);
+
+        let spawns = room_data
+            .get_structures()
+            .map(|structures| structures.spawns().iter().filter(|spawn| spawn.is_active()).count())
+            .unwrap_or(0);
+
+        info!(
+            "[SpawnReport] {}: {} queued request(s), {} active spawn(s)",
+            room_data.name,
+            requests.len(),
+            spawns
+        );
+
+        for request in requests {
+            info!(
+                "[SpawnReport] {}: {} (priority {:.0}, cost {}): {}",
+                room_data.name,
+                request.description,
+                request.priority,
+                request.cost(),
+                body_summary(&request.body)
+            );
+        }
+
+        for (capacity, outcomes) in Self::dry_run(requests, spawns, &SPAWN_REPORT_CAPACITIES) {
+            let lines: Vec<String> = requests
+                .iter()
+                .zip(outcomes)
+                .map(|(request, outcome)| match outcome {
+                    Ok(()) => format!("{}: spawns", request.description),
+                    Err(reason) => format!("{}: {}", request.description, reason.label()),
+                })
+                .collect();
+
+            info!("[SpawnReport] {} @ {} energy: {}", room_data.name, capacity, lines.join("; "));
+        }
+    }
+
+    /// Ask the room's operator to speed up a busy spawn once enough requests
+    /// wait on busy spawns.
+    fn request_spawn_power(
+        data: &SpawnQueueSystemData,
+        diagnostics: &SpawnDiagnostics,
+        room_entity: Entity,
+        power_requests: &mut PowerRequests,
+    ) {
+        let busy = diagnostics
+            .room(room_entity)
+            .iter()
+            .filter(|status| status.reason == SpawnBlockReason::SpawnsBusy)
+            .count();
+
+        if busy < SPAWN_POWER_BUSY_REQUESTS {
+            return;
+        }
+
+        let Some(room_data) = data.room_data.get(room_entity) else {
+            return;
+        };
+
+        let spawn = room_data.get_structures().and_then(|structures| {
+            structures
+                .spawns()
+                .iter()
+                .find(|spawn| spawn.my() && !has_power_effect(spawn, PowerType::OperateSpawn))
+                .cloned()
+        });
+
+        if let Some(spawn) = spawn {
+            power_requests.request(PowerRequest::new(
+                room_data.name,
+                PowerType::OperateSpawn,
+                RemoteStructureIdentifier::new(&StructureObject::from(spawn)),
+                PowerPriority::Medium,
+            ));
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn process_room_spawns(
         data: &SpawnQueueSystemData,
         room_entity: Entity,
         requests: &[SpawnRequest],
         renew_requests: &[RenewRequest],
         spawned_tokens: &mut HashSet<SpawnToken>,
+        serviced: &mut Vec<Entity>,
+        ledger: &mut ResourceLedger,
+        diagnostics: &mut SpawnDiagnostics,
+        intents: &mut IntentRecorder,
+        now: u32,
     ) -> Result<(), String> {
         let room_data = data.room_data.get(room_entity).ok_or("Expected room data")?;
+        let occurrences = request_occurrences(requests.iter().map(|r| r.description()));
+
+        if !data.features.for_room(&data.feature_overrides, room_data.name).spawning {
+            for (request, occurrence) in requests.iter().zip(occurrences) {
+                diagnostics.record(room_entity, request, occurrence, 0, SpawnBlockReason::SpawningDisabled, now);
+            }
+            return Ok(());
+        }
+
         let room = game::rooms().get(room_data.name).ok_or("Expected room")?;
         let structures = room_data.get_structures().ok_or_else(|| {
             let msg = format!("Expected structures - Room: {}", room_data.name);
@@ -401,7 +1009,7 @@ impl SpawnQueueSystem {
             .and_then(|d| d.plan())
             .map(|p| p.spawn_approaches.clone())
             .unwrap_or_default();
-        let mut available_energy = room.energy_available();
+        let available_energy = room.energy_available();
         let energy_capacity = room.energy_capacity_available();
 
         let room_has_energy_for_renew = data
@@ -420,20 +1028,37 @@ impl SpawnQueueSystem {
         // per tick on first actual spawn (skipped entirely if nothing spawns).
         let mut live_ctx: Option<LiveSpawnContext> = None;
 
-        for request in requests {
-            if request.token.map(|t| !spawned_tokens.contains(&t)).unwrap_or(true) {
-                if let Some(pos) = spawns.iter().position(|spawn| spawn.is_active() && spawn.spawning().is_none()) {
-                    let spawn = &spawns[pos];
-
-                    let body_cost: u32 = request.body.iter().map(|p| p.cost()).sum();
-
-                    if body_cost > energy_capacity {
-                        continue;
-                    }
+        let idle_spawn = |spawn: &StructureSpawn| spawn.is_active() && spawn.spawning().is_none();
+        let mut walk = SpawnWalk::new(
+            spawns.iter().filter(|spawn| idle_spawn(spawn)).count(),
+            available_energy,
+            energy_capacity,
+        );
+
+        // In lockdown, creeps that would head out of the room wait at home unless they are combat creeps;
+        // they are skipped rather than stopping the walk, so defenders behind them still spawn.
+        let lockdown = data.defense_postures.get(room_entity).is_some_and(|posture| posture.is_lockdown());
+
+        for (request, occurrence) in requests.iter().zip(occurrences) {
+            let leaves_room = request.toward.is_some_and(|pos| pos.room_name() != room_data.name);
+            if lockdown && leaves_room && request.priority < SPAWN_PRIORITY_COMBAT_FORMING {
+                diagnostics.record(
+                    room_entity,
+                    request,
+                    occurrence,
+                    walk.available_energy,
+                    SpawnBlockReason::Lockdown,
+                    now,
+                );
+                continue;
+            }
 
-                    if body_cost > available_energy {
-                        break;
-                    }
+            let outcome = match walk.select(request, spawned_tokens) {
+                Err(reason) => Err(reason),
+                Ok(()) => {
+                    let pos = spawns.iter().position(idle_spawn).ok_or("Expected an idle spawn")?;
+                    let spawn = &spawns[pos];
+                    let body_cost = request.cost();
 
                     let live = live_ctx.get_or_insert_with(|| {
                         // Construction sites are needed so obstacle-type sites on a
@@ -445,30 +1070,59 @@ impl SpawnQueueSystem {
                         LiveSpawnContext::build(&room, &structures, sites)
                     });
                     let directions = Self::safe_spawn_directions(spawn.pos(), &spawn_approaches, live);
+                    let directions = Self::preferred_spawn_directions(spawn.pos(), directions, live, request.toward);
 
-                    match Self::spawn_creep(spawn, &request.body, &directions) {
+                    match intents.issued(IntentCategory::Spawn, Self::spawn_creep(spawn, &request.body, &directions)) {
                         Ok(name) => {
-                            (*request.callback)(&system_data, &name);
+                            request.fulfill(&system_data, &name);
 
                             spawns.remove(pos);
+                            walk.spawned(body_cost);
 
                             if let Some(token) = request.token {
                                 spawned_tokens.insert(token);
                             }
 
-                            available_energy -= body_cost;
+                            serviced.extend(request.owner);
+
+                            ledger.add(room_data.name, LedgerCategory::Spawn, body_cost);
+
+                            Ok(())
                         }
                         Err(SpawnCreepErrorCode::NotEnoughEnergy) => {
-                            break;
+                            walk.out_of_energy();
+                            Err(SpawnBlockReason::InsufficientEnergy)
                         }
-                        Err(_) => {}
-                    };
-                } else {
-                    break;
+                        Err(err) => {
+                            debug!("[SpawnQueue] spawn_creep failed for {}: {:?}", request.description, err);
+                            Err(SpawnBlockReason::SpawnFailed)
+                        }
+                    }
+                }
+            };
+
+            match outcome {
+                Ok(()) => diagnostics.spawned(room_entity, &request.description, occurrence),
+                Err(reason) => {
+                    if let Some(waited) = diagnostics.record(room_entity, request, occurrence, walk.available_energy, reason, now) {
+                        warn!(
+                            "[SpawnQueue] {} has waited {} ticks in {} (priority {:.0}, cost {}, energy {}/{}): {}",
+                            request.description,
+                            waited,
+                            room_data.name,
+                            request.priority,
+                            request.cost(),
+                            walk.available_energy,
+                            energy_capacity,
+                            reason.label()
+                        );
+                    }
                 }
             }
         }
 
+        let mut available_energy = walk.available_energy;
+
         // Renew pass — BEHIND the priority gate (P1.D4 / ADR 0011 step
         // 0): spawn requests are priority-sorted and take their lanes
         // first; renew only uses spawns no pending request claimed, so
@@ -491,7 +1145,7 @@ impl SpawnQueueSystem {
                     .iter()
                     .position(|s| s.is_active() && s.spawning().is_none() && creep_pos.get_range_to(s.pos()) <= 1)
                 {
-                    match spawns[idx].renew_creep(&creep) {
+                    match intents.issued(IntentCategory::Spawn, spawns[idx].renew_creep(&creep)) {
                         Ok(()) => {
                             debug!(
                                 "[SpawnQueue] Renewed {} (ttl={}) at {}",
@@ -502,6 +1156,7 @@ impl SpawnQueueSystem {
                             let body_cost: u32 = creep.body().iter().map(|p| p.part().cost()).sum();
                             let renew_cost = renew_energy_cost(body_cost, creep.body().len());
                             available_energy = available_energy.saturating_sub(renew_cost);
+                            ledger.add(room_data.name, LedgerCategory::Renew, renew_cost);
                             spawns.remove(idx);
                         }
                         Err(e) => {
@@ -531,10 +1186,24 @@ pub fn renew_energy_cost(body_cost: u32, body_len: usize) -> u32 {
 }
 
 impl<'a> System<'a> for SpawnQueueSystem {
-    type SystemData = SpawnQueueSystemData<'a>;
-
-    fn run(&mut self, mut data: Self::SystemData) {
+    type SystemData = (
+        SpawnQueueSystemData<'a>,
+        Write<'a, ResourceLedger>,
+        Write<'a, SpawnDiagnostics>,
+        Write<'a, IntentRecorder>,
+        Write<'a, PowerRequests>,
+    );
+
+    fn run(&mut self, (mut data, mut ledger, mut diagnostics, mut intents, mut power_requests): Self::SystemData) {
         let mut spawned_tokens = HashSet::new();
+        let mut serviced = Vec::new();
+        let now = game::time();
+
+        diagnostics.begin_tick();
+
+        for room_entity in std::mem::take(&mut data.spawn_reports.rooms) {
+            Self::spawn_report(&data, room_entity);
+        }
 
         let mut all_rooms: HashSet<Entity> = data.spawn_queue.requests.keys().copied().collect();
         for room in data.spawn_queue.renew_requests.keys() {
@@ -549,12 +1218,25 @@ impl<'a> System<'a> for SpawnQueueSystem {
                 .get(&room_entity)
                 .map(|v| v.as_slice())
                 .unwrap_or(&[]);
-            match Self::process_room_spawns(&data, room_entity, requests, renew_requests, &mut spawned_tokens) {
-                Ok(()) => {}
+            match Self::process_room_spawns(
+                &data,
+                room_entity,
+                requests,
+                renew_requests,
+                &mut spawned_tokens,
+                &mut serviced,
+                &mut ledger,
+                &mut diagnostics,
+                &mut intents,
+                now,
+            ) {
+                Ok(()) => Self::request_spawn_power(&data, &diagnostics, room_entity, &mut power_requests),
                 Err(err) => warn!("Failed spawning for room: {}", err),
             }
         }
 
+        diagnostics.end_tick();
+
         // Snapshot the queue depth before clearing, so EconomyAssessmentSystem
         // can read it next tick.
         let mut snapshot = SpawnQueueSnapshot::default();
@@ -565,6 +1247,10 @@ impl<'a> System<'a> for SpawnQueueSystem {
         }
         *data.spawn_queue_snapshot = snapshot;
 
+        for owner in serviced {
+            data.spawn_queue.serviced(owner, now);
+        }
+
         data.spawn_queue.clear();
     }
 }
@@ -618,6 +1304,27 @@ mod tests {
         let _ = test_request(f32::NAN);
     }
 
+    #[test]
+    fn replacement_is_queued_once_spawn_and_walk_catch_up_with_the_old_creep() {
+        let mut world = specs::World::new();
+        let room = world.create_entity().build();
+
+        // 5 WORK + 1 MOVE: 18 ticks to spawn, 3 ticks per road tile.
+        let body = [Part::Work, Part::Work, Part::Work, Part::Work, Part::Work, Part::Move];
+        let lead = replacement_lead_time(&body, 10);
+        assert_eq!(lead, 18 + 30 + REPLACEMENT_MARGIN_TICKS);
+
+        let miner =
+            |ticks_to_live| SpawnRequest::new("miner".to_string(), &body, 100.0, None, Box::new(|_, _| {})).replacing(ticks_to_live, 10);
+
+        let mut queue = SpawnQueue::default();
+        queue.request(room, miner(lead + 1));
+        assert!(queue.iter_requests().all(|(_, requests)| requests.is_empty()));
+
+        queue.request(room, miner(lead));
+        assert_eq!(queue.iter_requests().map(|(_, requests)| requests.len()).sum::<usize>(), 1);
+    }
+
     /// Engine-true renew cost: ceil(1.2·cost/3/len) — pinned against
     /// the engine's renew-creep intent formula (P1.D4 / ADR 0011).
     #[test]
@@ -655,6 +1362,114 @@ mod tests {
         assert_eq!(priorities, vec![100.0, 75.0, 25.0, 25.0]);
     }
 
+    /// Taking a request spends its token, so the same creep isn't also queued in the other rooms.
+    #[test]
+    fn take_request_drops_requests_sharing_the_token() {
+        let mut world = specs::World::new();
+        let (room_a, room_b) = (world.create_entity().build(), world.create_entity().build());
+
+        let mut queue = SpawnQueue::default();
+        let token = queue.token();
+        let builder = |priority: f32| {
+            SpawnRequest::new(
+                "builder".into(),
+                &[Part::Work, Part::Carry, Part::Move],
+                priority,
+                Some(token),
+                Box::new(|_, _| {}),
+            )
+        };
+        queue.request(room_a, builder(50.0));
+        queue.request(room_b, builder(50.0));
+        queue.request(room_b, test_request(100.0));
+
+        let (_, taken) = queue
+            .take_request(0, |_, r| {
+                is_compatible_body(&[Part::Work, Part::Work, Part::Carry, Part::Move], r.body())
+            })
+            .expect("compatible request");
+        assert_eq!(taken.description(), "builder");
+
+        let remaining: usize = queue.iter_requests().map(|(_, requests)| requests.len()).sum();
+        assert_eq!(remaining, 1, "only the unrelated request is left");
+    }
+
+    /// Three missions queue a MEDIUM request every tick and one spawns per
+    /// tick: each is served in turn rather than the first to queue winning.
+    #[test]
+    fn equal_priority_requesters_are_served_round_robin() {
+        let mut world = specs::World::new();
+        let room = world.create_entity().build();
+        let missions: Vec<Entity> = (0..3).map(|_| world.create_entity().build()).collect();
+
+        let mut queue = SpawnQueue::default();
+        let mut served = Vec::new();
+
+        for tick in 0..6 {
+            for mission in &missions {
+                queue.request(room, test_request(SPAWN_PRIORITY_MEDIUM).requested_by(*mission));
+            }
+
+            let head = queue.iter_requests().next().and_then(|(_, requests)| requests[0].owner);
+            let owner = head.expect("attributed request");
+            queue.serviced(owner, tick);
+            served.push(owner);
+
+            queue.clear();
+        }
+
+        for mission in &missions {
+            assert_eq!(served.iter().filter(|owner| *owner == mission).count(), 2);
+        }
+        assert_eq!(served[..3], served[3..], "the rotation repeats");
+    }
+
+    /// Fairness only orders requests within a priority: a higher-priority
+    /// request still goes first, and `take_request` rotates the same way.
+    #[test]
+    fn fairness_never_overrides_priority() {
+        let mut world = specs::World::new();
+        let room = world.create_entity().build();
+        let (miner, hauler, upgrader) = (
+            world.create_entity().build(),
+            world.create_entity().build(),
+            world.create_entity().build(),
+        );
+
+        let mut queue = SpawnQueue::default();
+        queue.serviced(miner, 10);
+        queue.serviced(hauler, 5);
+
+        queue.request(room, test_request(SPAWN_PRIORITY_CRITICAL).requested_by(miner));
+        queue.request(room, test_request(SPAWN_PRIORITY_MEDIUM).requested_by(hauler));
+        queue.request(room, test_request(SPAWN_PRIORITY_MEDIUM).requested_by(upgrader));
+
+        let take = |queue: &mut SpawnQueue, now| queue.take_request(now, |_, _| true).and_then(|(_, r)| r.owner);
+
+        assert_eq!(take(&mut queue, 11), Some(miner));
+        assert_eq!(take(&mut queue, 11), Some(upgrader), "never served goes before served at tick 5");
+        assert_eq!(take(&mut queue, 11), Some(hauler));
+
+        // A requester unserved past the memory ranks as never served again.
+        queue.serviced(upgrader, 11 + SPAWN_FAIRNESS_MEMORY_TICKS);
+        assert_eq!(queue.last_serviced.len(), 1);
+    }
+
+    #[test]
+    fn compatible_bodies_cover_the_request_with_no_foreign_parts() {
+        let requested = [Part::Work, Part::Carry, Part::Move, Part::Move];
+        assert!(is_compatible_body(
+            &[Part::Work, Part::Work, Part::Carry, Part::Move, Part::Move],
+            &requested
+        ));
+        assert!(!is_compatible_body(&[Part::Work, Part::Carry, Part::Move], &requested));
+        assert!(!is_compatible_body(
+            &[Part::Work, Part::Carry, Part::Move, Part::Move, Part::Claim],
+            &requested
+        ));
+        assert!(!is_compatible_body(&[Part::Move], &[]));
+    }
+
     /// Pin: the construction-site obstacle predicate matches the engine's
     /// `OBSTACLE_OBJECT_TYPES` test in `_born-creep.js` — road/container/
     /// rampart/extractor sites are standable; everything else blocks.
@@ -680,6 +1495,7 @@ mod tests {
             terrain: FastRoomTerrain::new(vec![0u8; ROOM_COORD_MAX as usize * ROOM_COORD_MAX as usize]),
             creep_tiles: HashSet::new(),
             blocked_tiles: blocked.iter().copied().collect(),
+            crowded_tiles: HashSet::new(),
         }
     }
 
@@ -720,4 +1536,150 @@ mod tests {
         assert!(!dirs.is_empty(), "falls through to Tier-2 interior tiles (open terrain)");
         assert!(!dirs.contains(&Direction::Top), "the blocked tile is never offered as a direction");
     }
+
+    /// Containers and roads are skipped while another direction is open, and
+    /// the rest lead with the side nearest the request's target.
+    #[test]
+    fn preferred_spawn_directions_skip_containers_and_face_the_target() {
+        let spawn = spawn_pos_25_25();
+        let mut live = live_ctx_with_blocked(&[]);
+        // A container on the Left tile.
+        live.crowded_tiles.insert((24, 25));
+        let directions = vec![Direction::Left, Direction::Top, Direction::Bottom];
+
+        let untargeted = SpawnQueueSystem::preferred_spawn_directions(spawn, directions.clone(), &live, None);
+        assert_eq!(untargeted, vec![Direction::Top, Direction::Bottom]);
+
+        let storage = Position::new(
+            RoomCoordinate::new(25).unwrap(),
+            RoomCoordinate::new(30).unwrap(),
+            spawn.room_name(),
+        );
+        let targeted = SpawnQueueSystem::preferred_spawn_directions(spawn, directions, &live, Some(storage));
+        assert_eq!(targeted, vec![Direction::Bottom, Direction::Top]);
+
+        let only_container = SpawnQueueSystem::preferred_spawn_directions(spawn, vec![Direction::Left], &live, None);
+        assert_eq!(only_container, vec![Direction::Left], "a lone container tile is still offered");
+    }
+
+    fn request_costing(description: &str, move_parts: usize, token: Option<SpawnToken>) -> SpawnRequest {
+        SpawnRequest::new(
+            description.to_string(),
+            &vec![Part::Move; move_parts],
+            SPAWN_PRIORITY_HIGH,
+            token,
+            Box::new(|_, _| {}),
+        )
+    }
+
+    /// The dry run holds the queue behind a head it can't yet afford, skips
+    /// bodies over capacity, and spends each spawn once.
+    #[test]
+    fn dry_run_replays_the_queue_at_each_capacity() {
+        let mut queue = SpawnQueue::default();
+        let token = queue.token();
+        let requests = vec![
+            // 500 energy.
+            request_costing("hauler", 10, None),
+            // 1000 energy; two homes share the token.
+            request_costing("defender", 20, Some(token)),
+            request_costing("defender", 20, Some(token)),
+            // 100 energy.
+            request_costing("scout", 2, None),
+        ];
+
+        let report = SpawnQueueSystem::dry_run(&requests, 2, &[300, 550, 1300]);
+
+        let at = |capacity: u32| report.iter().find(|(c, _)| *c == capacity).map(|(_, o)| o.clone()).unwrap();
+
+        assert_eq!(
+            at(300),
+            vec![
+                Err(SpawnBlockReason::ExceedsCapacity),
+                Err(SpawnBlockReason::ExceedsCapacity),
+                Err(SpawnBlockReason::ExceedsCapacity),
+                Ok(()),
+            ]
+        );
+        // The hauler spends all but 50 of 550, so the scout waits for energy.
+        assert_eq!(
+            at(550),
+            vec![
+                Ok(()),
+                Err(SpawnBlockReason::ExceedsCapacity),
+                Err(SpawnBlockReason::ExceedsCapacity),
+                Err(SpawnBlockReason::InsufficientEnergy),
+            ]
+        );
+        // The hauler leaves 800: the defender waits for energy and holds the rest.
+        assert_eq!(
+            at(1300),
+            vec![
+                Ok(()),
+                Err(SpawnBlockReason::InsufficientEnergy),
+                Err(SpawnBlockReason::QueuedBehind),
+                Err(SpawnBlockReason::QueuedBehind),
+            ]
+        );
+
+        // With one spawn the second request finds it busy.
+        let report = SpawnQueueSystem::dry_run(&requests[2..], 1, &[1300]);
+        assert_eq!(report[0].1, vec![Ok(()), Err(SpawnBlockReason::SpawnsBusy)]);
+    }
+
+    #[test]
+    fn body_summary_counts_parts_in_order() {
+        assert_eq!(
+            body_summary(&[Part::Work, Part::Carry, Part::Work, Part::Move]),
+            "2xWork 1xCarry 1xMove"
+        );
+        assert_eq!(body_summary(&[]), "");
+    }
+
+    #[test]
+    fn occurrences_number_repeated_descriptions() {
+        assert_eq!(request_occurrences(["miner", "hauler", "miner", "miner"]), vec![0, 0, 1, 2]);
+    }
+
+    /// A request keeps its enqueue tick while it is re-requested each tick,
+    /// warns once past the threshold, and starts over after it spawns or
+    /// stops being requested.
+    #[test]
+    fn diagnostics_track_wait_until_spawned_or_dropped() {
+        let mut world = specs::World::new();
+        let room = world.create_entity().build();
+        let request = test_request(SPAWN_PRIORITY_HIGH);
+        let mut diagnostics = SpawnDiagnostics::default();
+
+        let tick = |diagnostics: &mut SpawnDiagnostics, now: u32| {
+            diagnostics.begin_tick();
+            let warned = diagnostics.record(room, &request, 0, 100, SpawnBlockReason::InsufficientEnergy, now);
+            diagnostics.end_tick();
+            warned
+        };
+
+        assert_eq!(tick(&mut diagnostics, 1_000), None);
+        assert_eq!(tick(&mut diagnostics, 1_000 + SPAWN_WAIT_WARN_TICKS), None);
+        assert_eq!(
+            tick(&mut diagnostics, 1_001 + SPAWN_WAIT_WARN_TICKS),
+            Some(SPAWN_WAIT_WARN_TICKS + 1)
+        );
+        assert_eq!(tick(&mut diagnostics, 1_002 + SPAWN_WAIT_WARN_TICKS), None, "warns once");
+
+        let status = diagnostics.status(room, request.description(), 0).unwrap();
+        assert_eq!(status.ticks_waiting, SPAWN_WAIT_WARN_TICKS + 2);
+        assert_eq!(status.reason, SpawnBlockReason::InsufficientEnergy);
+        assert!(diagnostics.status(room, request.description(), 1).is_none());
+
+        diagnostics.spawned(room, request.description(), 0);
+        tick(&mut diagnostics, 2_000);
+        assert_eq!(diagnostics.room(room)[0].ticks_waiting, 0);
+
+        // A tick without the request forgets it.
+        diagnostics.begin_tick();
+        diagnostics.end_tick();
+        assert!(diagnostics.room(room).is_empty());
+        tick(&mut diagnostics, 3_000);
+        assert_eq!(diagnostics.room(room)[0].ticks_waiting, 0);
+    }
 }
@@ -1,4 +1,5 @@
 use super::memorysystem::*;
+use crate::ledger::{LedgerCategory, ResourceLedger};
 use crate::room::data::*;
 use crate::segments::LIVE_STATS_SEGMENT;
 use screeps::*;
@@ -174,6 +175,15 @@ impl Serialize for StorageStructure {
     }
 }
 
+/// Rolling per-tick energy averages from the `ResourceLedger`.
+#[derive(Serialize)]
+pub struct EnergyLedgerStats {
+    income: f64,
+    expense: f64,
+    net: f64,
+    categories: HashMap<&'static str, f64>,
+}
+
 #[derive(Serialize)]
 pub struct RoomStats {
     energy_available: u32,
@@ -184,6 +194,21 @@ pub struct RoomStats {
     controller_progress: u32,
     controller_progress_total: u32,
     controller_level: u32,
+    /// Controller progress per tick, and the ticks to the next level at
+    /// that rate (see `upgrade_focus`).
+    upgrade_rate: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ticks_to_next_level: Option<u32>,
+
+    /// Energy emergencies entered since the VM started (see `missions::emergency`).
+    energy_emergencies: u32,
+    /// Full supply structure cache rebuilds since the VM started (see `StructureData::needs_rebuild`).
+    structure_cache_rebuilds: u32,
+    /// Resources shipped to the consolidation hub since the VM started (see `missions::terminal`).
+    consolidated: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    energy_ledger: Option<EnergyLedgerStats>,
 }
 
 #[derive(Serialize)]
@@ -191,6 +216,9 @@ pub struct GclStats {
     progress: f64,
     progress_total: f64,
     level: u32,
+    rate: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ticks_to_next: Option<u32>,
 }
 
 #[derive(Serialize)]
@@ -198,11 +226,76 @@ pub struct GplStats {
     progress: f64,
     progress_total: f64,
     level: u32,
+    rate: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ticks_to_next: Option<u32>,
 }
 
 #[derive(Serialize)]
 pub struct MarketStats {
     credits: f64,
+    /// Energy dealt by the arbitrage mode since the VM started, and the
+    /// credits it brought or cost (see `transfer::energyarbitrage`).
+    energy_sold: u32,
+    energy_sale_credits: f64,
+    energy_bought: u32,
+    energy_purchase_credits: f64,
+}
+
+/// Previous tick's CPU split, present only while
+/// `features.cpu_accounting.on` (see `cpu_accounting`).
+#[derive(Serialize)]
+pub struct CpuBreakdownStats {
+    systems: HashMap<&'static str, f64>,
+    missions: HashMap<&'static str, f64>,
+    jobs: HashMap<&'static str, f64>,
+}
+
+/// Last world save (see `worldformat::WorldSaveStats`).
+#[derive(Serialize)]
+pub struct WorldSaveStatsExport {
+    full: bool,
+    encoded: u32,
+    reused: u32,
+    encode_cpu: f64,
+    cpu_saved: f64,
+}
+
+/// Squad anchor pathing since the VM started (see `military::formation::SquadPathing`).
+#[derive(Serialize)]
+pub struct SquadPathingStats {
+    advances: u32,
+    cpu: f64,
+    waypoints: u32,
+}
+
+/// Outcomes of one mission type since the counts were first kept (see
+/// `missions::missionstats`).
+#[derive(Serialize)]
+pub struct MissionTypeStatsExport {
+    completed: u32,
+    failed: u32,
+    failed_by_reason: std::collections::BTreeMap<String, u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    average_lifetime: Option<f64>,
+}
+
+/// The empire's holdings by resource, in total and per room, and its
+/// shortfalls against the resource targets (see `resource_overview`).
+#[derive(Serialize)]
+pub struct ResourceOverviewStats {
+    totals: HashMap<String, u32>,
+    deficits: HashMap<String, u32>,
+    rooms: HashMap<RoomName, HashMap<String, u32>>,
+}
+
+/// Memory recoveries recorded in `Memory._recovery` (see
+/// `memorysystem::MemoryRecovery`); present once one has happened.
+#[derive(Serialize)]
+pub struct RecoveryStats {
+    count: u32,
+    tick: Option<u32>,
+    cause: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -211,8 +304,24 @@ pub struct ShardStats {
     gcl: GclStats,
     gpl: GplStats,
     cpu: CpuStats,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cpu_breakdown: Option<CpuBreakdownStats>,
+    world_save: WorldSaveStatsExport,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recovery: Option<RecoveryStats>,
     room: HashMap<RoomName, RoomStats>,
-    market: MarketStats,
+    /// The room surplus energy is concentrated on (see `upgrade_focus`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    upgrade_focus: Option<RoomName>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    missions: HashMap<String, MissionTypeStatsExport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    market: Option<MarketStats>,
+    squad_pathing: SquadPathingStats,
+    resources: ResourceOverviewStats,
+    /// The last warnings and errors logged (see `logging`).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    recent_logs: Vec<crate::logging::RecentLog>,
 }
 
 #[derive(Serialize)]
@@ -224,19 +333,27 @@ pub struct StatsSystem;
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl StatsSystem {
-    fn get_gcl_stats() -> GclStats {
+    fn get_gcl_stats(data: &StatsSystemData) -> GclStats {
+        let projection = data.upgrade_focus.gcl();
+
         GclStats {
             progress: game::gcl::progress(),
             progress_total: game::gcl::progress_total(),
             level: game::gcl::level(),
+            rate: projection.rate,
+            ticks_to_next: projection.ticks_to_next,
         }
     }
 
-    fn get_gpl_stats() -> GplStats {
+    fn get_gpl_stats(data: &StatsSystemData) -> GplStats {
+        let projection = data.upgrade_focus.gpl();
+
         GplStats {
             progress: game::gpl::progress(),
             progress_total: game::gpl::progress_total(),
             level: game::gpl::level(),
+            rate: projection.rate,
+            ticks_to_next: projection.ticks_to_next,
         }
     }
 
@@ -281,6 +398,8 @@ impl StatsSystem {
                         }
                     }
 
+                    let projection = data.upgrade_focus.room(room_data.name);
+
                     let stats = RoomStats {
                         energy_available: room.energy_available(),
                         energy_capacity_available: room.energy_capacity_available(),
@@ -290,6 +409,19 @@ impl StatsSystem {
                         controller_progress: controller.progress().unwrap_or(0),
                         controller_progress_total: controller.progress_total().unwrap_or(0),
                         controller_level: controller.level() as u32,
+                        upgrade_rate: projection.map(|p| p.rate).unwrap_or(0.0),
+                        ticks_to_next_level: projection.and_then(|p| p.ticks_to_next()),
+
+                        energy_emergencies: data.energy_emergency.occurrences(room_data.name),
+                        structure_cache_rebuilds: data.supply_structure_cache.rebuilds(room_data.name),
+                        consolidated: data.consolidation_volume.sent(room_data.name),
+
+                        energy_ledger: data.ledger.averages(room_data.name).map(|averages| EnergyLedgerStats {
+                            income: averages.income,
+                            expense: averages.expense,
+                            net: averages.net(),
+                            categories: LedgerCategory::ALL.iter().map(|c| (c.as_str(), averages.get(*c))).collect(),
+                        }),
                     };
 
                     Some((room_data.name, stats))
@@ -300,27 +432,120 @@ impl StatsSystem {
             .collect()
     }
 
-    fn get_market_stats() -> MarketStats {
-        MarketStats {
+    fn get_cpu_breakdown_stats(data: &StatsSystemData) -> Option<CpuBreakdownStats> {
+        data.cpu_accounting.last().map(|breakdown| CpuBreakdownStats {
+            systems: breakdown.systems.clone(),
+            missions: breakdown.missions.clone(),
+            jobs: breakdown.jobs.clone(),
+        })
+    }
+
+    fn get_world_save_stats(data: &StatsSystemData) -> WorldSaveStatsExport {
+        let save = &*data.world_save;
+
+        WorldSaveStatsExport {
+            full: save.full,
+            encoded: save.encoded,
+            reused: save.reused,
+            encode_cpu: save.encode_cpu,
+            cpu_saved: save.cpu_saved(),
+        }
+    }
+
+    fn get_recovery_stats(data: &StatsSystemData) -> Option<RecoveryStats> {
+        let recovery = &*data.recovery;
+
+        if recovery.count == 0 {
+            return None;
+        }
+
+        Some(RecoveryStats {
+            count: recovery.count,
+            tick: recovery.last_tick,
+            cause: recovery.last_cause.clone(),
+        })
+    }
+
+    fn get_mission_stats(data: &StatsSystemData) -> HashMap<String, MissionTypeStatsExport> {
+        data.mission_stats
+            .types()
+            .iter()
+            .map(|(type_name, stats)| {
+                let export = MissionTypeStatsExport {
+                    completed: stats.completed,
+                    failed: stats.failures(),
+                    failed_by_reason: stats.failed.clone(),
+                    average_lifetime: stats.average_lifetime(),
+                };
+
+                (type_name.clone(), export)
+            })
+            .collect()
+    }
+
+    fn get_market_stats(data: &StatsSystemData) -> Option<MarketStats> {
+        data.capabilities.market.then(|| MarketStats {
             credits: game::market::credits(),
+            energy_sold: data.energy_arbitrage_volume.sold,
+            energy_sale_credits: data.energy_arbitrage_volume.sale_credits,
+            energy_bought: data.energy_arbitrage_volume.bought,
+            energy_purchase_credits: data.energy_arbitrage_volume.purchase_credits,
+        })
+    }
+
+    fn get_squad_pathing_stats(data: &StatsSystemData) -> SquadPathingStats {
+        SquadPathingStats {
+            advances: data.squad_pathing.advances,
+            cpu: data.squad_pathing.cpu,
+            waypoints: data.squad_pathing.waypoints,
+        }
+    }
+
+    fn get_resource_overview_stats(data: &StatsSystemData) -> ResourceOverviewStats {
+        let names = |held: &HashMap<ResourceType, u32>| -> HashMap<String, u32> {
+            held.iter().map(|(resource, amount)| (format!("{:?}", resource), *amount)).collect()
+        };
+
+        ResourceOverviewStats {
+            totals: names(data.resource_overview.totals()),
+            deficits: data
+                .resource_overview
+                .deficits()
+                .iter()
+                .map(|(resource, deficit)| (format!("{:?}", resource), *deficit))
+                .collect(),
+            rooms: data
+                .resource_overview
+                .rooms()
+                .iter()
+                .map(|(room, held)| (*room, names(held)))
+                .collect(),
         }
     }
 
     fn get_shard_stats(data: &StatsSystemData) -> ShardStats {
         ShardStats {
             time: game::time(),
-            gcl: Self::get_gcl_stats(),
-            gpl: Self::get_gpl_stats(),
+            gcl: Self::get_gcl_stats(data),
+            gpl: Self::get_gpl_stats(data),
             cpu: Self::get_cpu_stats(),
+            cpu_breakdown: Self::get_cpu_breakdown_stats(data),
+            world_save: Self::get_world_save_stats(data),
+            recovery: Self::get_recovery_stats(data),
             room: Self::get_room_stats(data),
-            market: Self::get_market_stats(),
+            upgrade_focus: data.upgrade_focus.focus(),
+            missions: Self::get_mission_stats(data),
+            market: Self::get_market_stats(data),
+            squad_pathing: Self::get_squad_pathing_stats(data),
+            resources: Self::get_resource_overview_stats(data),
+            recent_logs: crate::logging::recent(),
         }
     }
 
     fn get_shards_stats(data: &StatsSystemData) -> HashMap<String, ShardStats> {
         let mut shards = HashMap::new();
 
-        shards.insert(game::shard::name(), Self::get_shard_stats(data));
+        shards.insert(data.capabilities.shard_name().to_string(), Self::get_shard_stats(data));
 
         shards
     }
@@ -330,7 +555,20 @@ impl StatsSystem {
 pub struct StatsSystemData<'a> {
     entities: Entities<'a>,
     room_data: ReadStorage<'a, RoomData>,
-    memory_arbiter: WriteExpect<'a, MemoryArbiter>,
+    segment_manager: WriteExpect<'a, SegmentManager>,
+    ledger: Read<'a, ResourceLedger>,
+    cpu_accounting: Read<'a, crate::cpu_accounting::CpuAccounting>,
+    world_save: Read<'a, crate::worldformat::WorldSaveStats>,
+    recovery: Read<'a, crate::memorysystem::MemoryRecovery>,
+    energy_emergency: Read<'a, crate::missions::emergency::EnergyEmergency>,
+    supply_structure_cache: Read<'a, crate::missions::localsupply::structure_data::SupplyStructureCache>,
+    consolidation_volume: Read<'a, crate::missions::terminal::ConsolidationVolume>,
+    energy_arbitrage_volume: Read<'a, crate::transfer::energyarbitrage::EnergyArbitrageVolume>,
+    squad_pathing: Read<'a, crate::military::formation::SquadPathing>,
+    capabilities: Read<'a, crate::server::ServerCapabilities>,
+    mission_stats: Read<'a, crate::missions::missionstats::MissionStats>,
+    resource_overview: Read<'a, crate::resource_overview::ResourceOverview>,
+    upgrade_focus: Read<'a, crate::upgrade_focus::UpgradeFocus>,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -338,16 +576,19 @@ impl<'a> System<'a> for StatsSystem {
     type SystemData = StatsSystemData<'a>;
 
     fn run(&mut self, mut data: Self::SystemData) {
-        data.memory_arbiter.request(LIVE_STATS_SEGMENT);
+        // The segment is for the screepspl.us agent.
+        if !data.capabilities.stats_export {
+            return;
+        }
 
-        if data.memory_arbiter.is_active(LIVE_STATS_SEGMENT) {
-            let stats = Stats {
-                shard: Self::get_shards_stats(&data),
-            };
+        // Written whenever the segment manager next has the segment active;
+        // a newer export replaces one still waiting.
+        let stats = Stats {
+            shard: Self::get_shards_stats(&data),
+        };
 
-            if let Ok(stats_data) = serde_json::to_string(&stats) {
-                data.memory_arbiter.set(LIVE_STATS_SEGMENT, &stats_data);
-            }
+        if let Ok(stats_data) = serde_json::to_string(&stats) {
+            data.segment_manager.write(LIVE_STATS_SEGMENT, stats_data);
         }
     }
 }
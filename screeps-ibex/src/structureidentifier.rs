@@ -1,9 +1,121 @@
+//! Serializable handles on structures of any type.
+//!
+//! The variants cover every [`StructureType`] the game API exposes
+//! ([`KNOWN_STRUCTURE_TYPES`]). A structure of a type this build doesn't
+//! know — a new game structure, a season's, or a private-server mod's —
+//! would panic converting to a `StructureObject`, so the structure cache
+//! finds structures through [`find_known_structures`], which drops them
+//! with a rate-limited warning. Only [`StructureIdentifier`] can name one,
+//! as [`StructureIdentifier::Unknown`]; remote identifiers are made from
+//! `StructureObject`s and never see them.
+
+use log::*;
+use screeps::constants::find::FindConstant;
 use screeps::*;
 use serde::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use wasm_bindgen::{JsCast, JsValue};
 
 use crate::remoteobjectid::*;
 
-#[derive(Clone, Copy, Deserialize, Serialize)]
+/// Every structure type the identifiers have a variant for.
+pub const KNOWN_STRUCTURE_TYPES: [StructureType; 21] = [
+    StructureType::Container,
+    StructureType::Controller,
+    StructureType::Extension,
+    StructureType::Extractor,
+    StructureType::Factory,
+    StructureType::InvaderCore,
+    StructureType::KeeperLair,
+    StructureType::Lab,
+    StructureType::Link,
+    StructureType::Nuker,
+    StructureType::Observer,
+    StructureType::PowerBank,
+    StructureType::PowerSpawn,
+    StructureType::Portal,
+    StructureType::Rampart,
+    StructureType::Road,
+    StructureType::Spawn,
+    StructureType::Storage,
+    StructureType::Terminal,
+    StructureType::Tower,
+    StructureType::Wall,
+];
+
+/// Ticks between repeats of the warning for one unknown structure type.
+const UNKNOWN_WARNING_INTERVAL: u32 = 10_000;
+
+thread_local! {
+    /// Tick each unknown structure type was last warned about.
+    static UNKNOWN_WARNINGS: RefCell<HashMap<String, u32>> = RefCell::new(HashMap::new());
+}
+
+fn warning_due(last_warned: Option<u32>, now: u32) -> bool {
+    last_warned
+        .map(|last_warned| now.saturating_sub(last_warned) >= UNKNOWN_WARNING_INTERVAL)
+        .unwrap_or(true)
+}
+
+/// Warn that structures of `structure_type` are being skipped, at most once
+/// per [`UNKNOWN_WARNING_INTERVAL`] ticks per type.
+pub fn warn_unknown_structure(structure_type: &str) {
+    let now = game::time();
+
+    UNKNOWN_WARNINGS.with(|warnings| {
+        let mut warnings = warnings.borrow_mut();
+
+        if warning_due(warnings.get(structure_type).copied(), now) {
+            warn!("Skipping structures of unknown type '{}'", structure_type);
+
+            warnings.insert(structure_type.to_string(), now);
+        }
+    });
+}
+
+/// The structure type as the game names it, e.g. `"scoreCollector"`.
+fn raw_structure_type(structure: &Structure) -> String {
+    js_sys::Reflect::get(structure.as_ref(), &JsValue::from_str("structureType"))
+        .ok()
+        .and_then(|value| value.as_string())
+        .unwrap_or_default()
+}
+
+/// `FIND_STRUCTURES` as plain `Structure`s, leaving the conversion to
+/// `StructureObject` (which panics on an unknown type) to the caller.
+struct AnyStructures;
+
+impl FindConstant for AnyStructures {
+    type Item = Structure;
+
+    fn convert_and_check_item(reference: JsValue) -> Structure {
+        reference.unchecked_into()
+    }
+
+    fn find_code(&self) -> i16 {
+        find::STRUCTURES.find_code()
+    }
+}
+
+/// The structure as a `StructureObject`, or `None` (warning) for a type
+/// this build doesn't know.
+pub fn known_structure(structure: Structure) -> Option<StructureObject> {
+    if KNOWN_STRUCTURE_TYPES.contains(&structure.structure_type()) {
+        Some(StructureObject::from(structure))
+    } else {
+        warn_unknown_structure(&raw_structure_type(&structure));
+
+        None
+    }
+}
+
+/// `room.find(find::STRUCTURES)`, skipping the structures of unknown types.
+pub fn find_known_structures(room: &Room) -> Vec<StructureObject> {
+    room.find(AnyStructures, None).into_iter().filter_map(known_structure).collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum StructureIdentifier {
     Container(ObjectId<StructureContainer>),
     Controller(ObjectId<StructureController>),
@@ -26,9 +138,81 @@ pub enum StructureIdentifier {
     Terminal(ObjectId<StructureTerminal>),
     Tower(ObjectId<StructureTower>),
     Wall(ObjectId<StructureWall>),
+    /// A structure of a type this build doesn't know, by its type's name.
+    Unknown(String),
 }
 
 impl StructureIdentifier {
+    /// Identify a structure of any type, unknown ones included.
+    pub fn from_structure(structure: Structure) -> StructureIdentifier {
+        if KNOWN_STRUCTURE_TYPES.contains(&structure.structure_type()) {
+            StructureIdentifier::new(&StructureObject::from(structure))
+        } else {
+            StructureIdentifier::Unknown(raw_structure_type(&structure))
+        }
+    }
+
+    /// An identifier from a type and a raw id. `None` for a type without a
+    /// variant; unknown types have no id to pair with.
+    pub fn from_raw(structure_type: StructureType, id: RawObjectId) -> Option<StructureIdentifier> {
+        let identifier = match structure_type {
+            StructureType::Container => StructureIdentifier::Container(id.into()),
+            StructureType::Controller => StructureIdentifier::Controller(id.into()),
+            StructureType::Extension => StructureIdentifier::Extension(id.into()),
+            StructureType::Extractor => StructureIdentifier::Extractor(id.into()),
+            StructureType::Factory => StructureIdentifier::Factory(id.into()),
+            StructureType::InvaderCore => StructureIdentifier::InvaderCore(id.into()),
+            StructureType::KeeperLair => StructureIdentifier::KeeperLair(id.into()),
+            StructureType::Lab => StructureIdentifier::Lab(id.into()),
+            StructureType::Link => StructureIdentifier::Link(id.into()),
+            StructureType::Nuker => StructureIdentifier::Nuker(id.into()),
+            StructureType::Observer => StructureIdentifier::Observer(id.into()),
+            StructureType::PowerBank => StructureIdentifier::PowerBank(id.into()),
+            StructureType::PowerSpawn => StructureIdentifier::PowerSpawn(id.into()),
+            StructureType::Portal => StructureIdentifier::Portal(id.into()),
+            StructureType::Rampart => StructureIdentifier::Rampart(id.into()),
+            StructureType::Road => StructureIdentifier::Road(id.into()),
+            StructureType::Spawn => StructureIdentifier::Spawn(id.into()),
+            StructureType::Storage => StructureIdentifier::Storage(id.into()),
+            StructureType::Terminal => StructureIdentifier::Terminal(id.into()),
+            StructureType::Tower => StructureIdentifier::Tower(id.into()),
+            StructureType::Wall => StructureIdentifier::Wall(id.into()),
+            _ => return None,
+        };
+
+        Some(identifier)
+    }
+
+    /// The identified structure's type; `None` for an unknown one.
+    pub fn structure_type(&self) -> Option<StructureType> {
+        let structure_type = match self {
+            StructureIdentifier::Container(_) => StructureType::Container,
+            StructureIdentifier::Controller(_) => StructureType::Controller,
+            StructureIdentifier::Extension(_) => StructureType::Extension,
+            StructureIdentifier::Extractor(_) => StructureType::Extractor,
+            StructureIdentifier::Factory(_) => StructureType::Factory,
+            StructureIdentifier::InvaderCore(_) => StructureType::InvaderCore,
+            StructureIdentifier::KeeperLair(_) => StructureType::KeeperLair,
+            StructureIdentifier::Lab(_) => StructureType::Lab,
+            StructureIdentifier::Link(_) => StructureType::Link,
+            StructureIdentifier::Nuker(_) => StructureType::Nuker,
+            StructureIdentifier::Observer(_) => StructureType::Observer,
+            StructureIdentifier::PowerBank(_) => StructureType::PowerBank,
+            StructureIdentifier::PowerSpawn(_) => StructureType::PowerSpawn,
+            StructureIdentifier::Portal(_) => StructureType::Portal,
+            StructureIdentifier::Rampart(_) => StructureType::Rampart,
+            StructureIdentifier::Road(_) => StructureType::Road,
+            StructureIdentifier::Spawn(_) => StructureType::Spawn,
+            StructureIdentifier::Storage(_) => StructureType::Storage,
+            StructureIdentifier::Terminal(_) => StructureType::Terminal,
+            StructureIdentifier::Tower(_) => StructureType::Tower,
+            StructureIdentifier::Wall(_) => StructureType::Wall,
+            StructureIdentifier::Unknown(_) => return None,
+        };
+
+        Some(structure_type)
+    }
+
     pub fn new(structure: &StructureObject) -> StructureIdentifier {
         match structure {
             StructureObject::StructureContainer(v) => StructureIdentifier::Container(v.id()),
@@ -78,6 +262,7 @@ impl StructureIdentifier {
             StructureIdentifier::Terminal(id) => id.resolve().map(|s| s.into()),
             StructureIdentifier::Tower(id) => id.resolve().map(|s| s.into()),
             StructureIdentifier::Wall(id) => id.resolve().map(|s| s.into()),
+            StructureIdentifier::Unknown(_) => None,
         }
     }
 }
@@ -186,3 +371,42 @@ impl RemoteStructureIdentifier {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::object_id;
+
+    #[test]
+    fn every_known_type_round_trips_through_serialization() {
+        for (n, structure_type) in KNOWN_STRUCTURE_TYPES.iter().enumerate() {
+            let id: ObjectId<StructureContainer> = object_id(n as u32 + 1);
+            let identifier = StructureIdentifier::from_raw(*structure_type, id.into()).expect("known structure type");
+
+            assert_eq!(identifier.structure_type(), Some(*structure_type));
+
+            let json = serde_json::to_string(&identifier).expect("serializes");
+            let back: StructureIdentifier = serde_json::from_str(&json).expect("deserializes");
+
+            assert_eq!(back, identifier);
+        }
+    }
+
+    #[test]
+    fn unknown_types_round_trip_by_name() {
+        let unknown = StructureIdentifier::Unknown("scoreCollector".to_string());
+
+        let json = serde_json::to_string(&unknown).expect("serializes");
+        let back: StructureIdentifier = serde_json::from_str(&json).expect("deserializes");
+
+        assert_eq!(back, unknown);
+        assert_eq!(back.structure_type(), None);
+    }
+
+    #[test]
+    fn unknown_type_warnings_repeat_only_after_the_interval() {
+        assert!(warning_due(None, 100));
+        assert!(!warning_due(Some(100), 100 + UNKNOWN_WARNING_INTERVAL - 1));
+        assert!(warning_due(Some(100), 100 + UNKNOWN_WARNING_INTERVAL));
+    }
+}
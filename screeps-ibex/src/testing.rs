@@ -0,0 +1,130 @@
+//! Host-side fixtures for the decision paths behind the [`GameView`] seam.
+//!
+//! [`MockGame`] answers the seam from plain maps, and [`TestWorld`] builds
+//! a specs `World` with the storages and resources those paths read, so a
+//! test sets up rooms, creeps, squads and missions without the Screeps
+//! runtime. Nothing here calls into the game API; a fixture that would
+//! need a live game object (structures, visibility data) is not offered.
+
+use crate::creep::{CreepOwner, CreepSpawning};
+use crate::entitymappingsystem::EntityMappingData;
+use crate::gameview::*;
+use crate::military::squad::SquadContext;
+use crate::military::threatmap::RoomThreatData;
+use crate::missions::data::MissionData;
+use crate::room::data::RoomData;
+use crate::serialize::*;
+use screeps::*;
+use specs::*;
+use std::collections::{HashMap, HashSet};
+
+/// A game the test fills in: the CPU figures of a [`FixedGameView`] plus
+/// the rooms, creeps and minerals the migrated paths look up.
+#[derive(Default)]
+pub struct MockGame {
+    pub view: FixedGameView,
+    pub rooms: HashMap<RoomName, RoomEnergy>,
+    pub creeps: HashSet<ObjectId<Creep>>,
+    pub minerals: HashMap<ObjectId<Mineral>, u32>,
+}
+
+impl MockGame {
+    pub fn at(time: u32) -> MockGame {
+        MockGame {
+            view: FixedGameView {
+                time,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+}
+
+impl GameView for MockGame {
+    fn time(&self) -> u32 {
+        self.view.time
+    }
+    fn cpu_used(&self) -> f64 {
+        self.view.cpu_used
+    }
+    fn cpu_limit(&self) -> f64 {
+        self.view.cpu_limit
+    }
+    fn cpu_tick_limit(&self) -> f64 {
+        self.view.cpu_tick_limit
+    }
+    fn bucket(&self) -> i32 {
+        self.view.bucket
+    }
+    fn room_energy(&self, room: RoomName) -> Option<RoomEnergy> {
+        self.rooms.get(&room).copied()
+    }
+    fn creep_exists(&self, creep: ObjectId<Creep>) -> bool {
+        self.creeps.contains(&creep)
+    }
+    fn mineral_amount(&self, mineral: ObjectId<Mineral>) -> Option<u32> {
+        self.minerals.get(&mineral).copied()
+    }
+}
+
+/// A distinct object id per `n`, for fixtures that key on ids.
+pub fn object_id<T>(n: u32) -> ObjectId<T> {
+    format!("{:024x}", n).parse().expect("valid object id")
+}
+
+pub fn position(room: RoomName, x: u8, y: u8) -> Position {
+    Position::new(RoomCoordinate::new(x).unwrap(), RoomCoordinate::new(y).unwrap(), room)
+}
+
+/// A specs `World` with the components and resources the migrated
+/// decision paths read already registered.
+pub struct TestWorld {
+    pub world: World,
+}
+
+impl Default for TestWorld {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestWorld {
+    pub fn new() -> TestWorld {
+        let mut world = World::new();
+
+        world.register::<SerializeMarker>();
+        world.register::<RoomData>();
+        world.register::<RoomThreatData>();
+        world.register::<CreepOwner>();
+        world.register::<CreepSpawning>();
+        world.register::<SquadContext>();
+        world.register::<MissionData>();
+
+        world.insert(SerializeMarkerAllocator::new());
+        world.insert(EntityMappingData::default());
+
+        TestWorld { world }
+    }
+
+    /// A room entity carrying a fresh `RoomData`, with no visibility yet.
+    pub fn room(&mut self, name: &str) -> Entity {
+        let name: RoomName = name.parse().expect("valid room name");
+
+        self.world.create_entity().with(RoomData::new(name)).build()
+    }
+
+    /// A spawned creep entity owning `id`. Whether it is alive is up to
+    /// the [`MockGame`].
+    pub fn creep(&mut self, id: ObjectId<Creep>) -> Entity {
+        self.world.create_entity().with(CreepOwner::new(id)).build()
+    }
+
+    /// A creep entity still in the spawn, with no game object yet.
+    pub fn spawning_creep(&mut self, name: &str) -> Entity {
+        self.world.create_entity().with(CreepSpawning::new(name)).build()
+    }
+
+    pub fn squad(&mut self, squad: SquadContext) -> Entity {
+        self.world.create_entity().with(squad).build()
+    }
+}
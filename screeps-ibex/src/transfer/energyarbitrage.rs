@@ -0,0 +1,108 @@
+//! Energy-for-credits arbitrage (`features.market.energy_arbitrage`).
+//!
+//! Rooms holding energy above their high-water mark deal it into buy orders
+//! paying at least `energy_sell_floor` credits per energy once the terminal
+//! fee is counted; rooms below their low-water mark deal sell orders costing
+//! at most `energy_buy_ceiling` per energy actually landed. A room whose
+//! storage overflows (`missions::overflow`) sells down to the lower
+//! `overflow_sell_floor`. The sizing here is pure so it stays host-testable;
+//! deals are picked by the
+//! [`OrderQueueSystem`](super::ordersystem::OrderQueueSystem) and made by the
+//! room's terminal mission when they win its terminal schedule.
+//!
+//! `cost` throughout is the fee per unit dealt, as a fraction of the unit
+//! (`calc_transaction_cost_fractional`).
+
+/// Credits per energy spent selling into a buy order at `price`: the fee
+/// leaves the same terminal, so every unit sold costs `1 + cost` energy.
+pub fn sale_credits_per_energy(price: f64, cost: f64) -> f64 {
+    price / (1.0 + cost)
+}
+
+/// Credits per energy landed buying from a sell order at `price`: the
+/// dealer pays the fee in energy, so only `1 - cost` of each unit is a gain.
+/// `None` when the fee eats the whole unit.
+pub fn purchase_credits_per_energy(price: f64, cost: f64) -> Option<f64> {
+    (cost < 1.0).then(|| price / (1.0 - cost))
+}
+
+/// Units to sell into an order with `remaining` units: no more than the
+/// `surplus` above the high-water mark, the `max_units` per deal, and what
+/// `terminal_energy` covers with its fee.
+pub fn sale_amount(remaining: u32, surplus: u32, max_units: u32, terminal_energy: u32, cost: f64) -> u32 {
+    let affordable = (terminal_energy as f64 / (1.0 + cost)).floor() as u32;
+
+    remaining.min(surplus).min(max_units).min(affordable)
+}
+
+/// Units to buy from an order with `remaining` units: enough to cover the
+/// `deficit` below the low-water mark once the fee is paid, no more than
+/// `max_units` per deal, and within what `terminal_energy` pays in fees and
+/// `credits` pays for.
+pub fn purchase_amount(remaining: u32, deficit: u32, max_units: u32, terminal_energy: u32, credits: f64, price: f64, cost: f64) -> u32 {
+    if cost >= 1.0 || price <= 0.0 {
+        return 0;
+    }
+
+    let wanted = (deficit as f64 / (1.0 - cost)).ceil() as u32;
+    let fee_affordable = if cost > 0.0 {
+        (terminal_energy as f64 / cost).floor().min(u32::MAX as f64) as u32
+    } else {
+        u32::MAX
+    };
+    let credit_affordable = (credits.max(0.0) / price).floor().min(u32::MAX as f64) as u32;
+
+    remaining.min(wanted).min(max_units).min(fee_affordable).min(credit_affordable)
+}
+
+/// Energy dealt by the arbitrage mode since the VM started. Runtime
+/// resource, for stats.
+#[derive(Default)]
+pub struct EnergyArbitrageVolume {
+    pub sold: u32,
+    pub sale_credits: f64,
+    pub bought: u32,
+    pub purchase_credits: f64,
+}
+
+impl EnergyArbitrageVolume {
+    pub fn record_sale(&mut self, amount: u32, price: f64) {
+        self.sold += amount;
+        self.sale_credits += amount as f64 * price;
+    }
+
+    pub fn record_purchase(&mut self, amount: u32, price: f64) {
+        self.bought += amount;
+        self.purchase_credits += amount as f64 * price;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fees_lower_sale_credits_and_raise_purchase_costs() {
+        assert_eq!(sale_credits_per_energy(10.0, 0.25), 8.0);
+        assert_eq!(purchase_credits_per_energy(3.0, 0.25), Some(4.0));
+        assert_eq!(purchase_credits_per_energy(3.0, 1.0), None);
+    }
+
+    #[test]
+    fn sales_stop_at_surplus_and_the_terminal_fee() {
+        // 10k energy in the terminal covers 8k units at a quarter fee.
+        assert_eq!(sale_amount(50_000, 20_000, 10_000, 10_000, 0.25), 8_000);
+        assert_eq!(sale_amount(50_000, 3_000, 10_000, 10_000, 0.25), 3_000);
+        assert_eq!(sale_amount(1_500, 20_000, 10_000, 10_000, 0.25), 1_500);
+    }
+
+    #[test]
+    fn purchases_cover_the_deficit_after_fees_within_credits() {
+        // 3k short at a quarter fee needs 4k units dealt.
+        assert_eq!(purchase_amount(50_000, 3_000, 10_000, 10_000, 1_000_000.0, 1.0, 0.25), 4_000);
+        // 500 energy pays the fee on 2k units.
+        assert_eq!(purchase_amount(50_000, 3_000, 10_000, 500, 1_000_000.0, 1.0, 0.25), 2_000);
+        assert_eq!(purchase_amount(50_000, 3_000, 10_000, 10_000, 1_000.0, 2.0, 0.25), 500);
+        assert_eq!(purchase_amount(50_000, 3_000, 10_000, 10_000, 1_000.0, 0.0, 0.25), 0);
+    }
+}
@@ -0,0 +1,184 @@
+//! Transfer flow visualization.
+//!
+//! Haulers pick a pickup and one or more deliveries together; the queue
+//! records each matched pair as a [`TransferFlow`] until it is cleared at the
+//! end of the tick. Drawing the pairs as arrows (color = resource, width =
+//! amount) shows at a glance when haulers are crisscrossing a room.
+
+use crate::visualize::RoomVisualizer;
+use screeps::*;
+use std::collections::HashMap;
+
+/// Line width for the smallest and the largest flows.
+const MIN_FLOW_WIDTH: f32 = 0.05;
+const MAX_FLOW_WIDTH: f32 = 0.4;
+
+/// Amount at (and above) which a flow is drawn at full width.
+const FULL_FLOW_AMOUNT: u32 = 1_000;
+
+/// Length of the arrowhead barbs, in tiles.
+const ARROW_HEAD_SIZE: f32 = 0.4;
+
+const FLOW_OPACITY: f32 = 0.6;
+
+/// One matched pickup -> delivery pair for a single resource.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransferFlow {
+    pub from: Position,
+    pub to: Position,
+    pub resource: ResourceType,
+    pub amount: u32,
+}
+
+/// Sum flows that share endpoints and resource so several haulers on the same
+/// route draw as one thicker arrow. Output is sorted by amount, largest last,
+/// so the big flows draw on top.
+pub fn merge_flows(flows: &[TransferFlow]) -> Vec<TransferFlow> {
+    let mut merged: HashMap<(Position, Position, ResourceType), u32> = HashMap::new();
+
+    for flow in flows {
+        *merged.entry((flow.from, flow.to, flow.resource)).or_insert(0) += flow.amount;
+    }
+
+    let mut flows: Vec<_> = merged
+        .into_iter()
+        .filter(|(_, amount)| *amount > 0)
+        .map(|((from, to, resource), amount)| TransferFlow {
+            from,
+            to,
+            resource,
+            amount,
+        })
+        .collect();
+
+    flows.sort_by_key(|flow| flow.amount);
+
+    flows
+}
+
+/// Line width for a flow of `amount`, linear between the min and max widths.
+pub fn flow_width(amount: u32) -> f32 {
+    let fraction = amount.min(FULL_FLOW_AMOUNT) as f32 / FULL_FLOW_AMOUNT as f32;
+
+    MIN_FLOW_WIDTH + (MAX_FLOW_WIDTH - MIN_FLOW_WIDTH) * fraction
+}
+
+/// The two barb end points of an arrowhead at `to`, or `None` when the
+/// segment has no length.
+pub fn arrow_head(from: (f32, f32), to: (f32, f32), size: f32) -> Option<[(f32, f32); 2]> {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let length = (dx * dx + dy * dy).sqrt();
+
+    if length <= f32::EPSILON {
+        return None;
+    }
+
+    // Unit vector pointing back along the segment, rotated +/- 30 degrees.
+    let (bx, by) = (-dx / length, -dy / length);
+    let (sin, cos) = std::f32::consts::FRAC_PI_6.sin_cos();
+
+    Some([
+        (to.0 + size * (bx * cos - by * sin), to.1 + size * (bx * sin + by * cos)),
+        (to.0 + size * (bx * cos + by * sin), to.1 + size * (-bx * sin + by * cos)),
+    ])
+}
+
+pub fn resource_color(resource: ResourceType) -> &'static str {
+    match resource {
+        ResourceType::Energy => "#f0c040",
+        ResourceType::Power => "#f41f33",
+        ResourceType::Hydrogen | ResourceType::Oxygen => "#cccccc",
+        ResourceType::Utrium => "#50d7f9",
+        ResourceType::Lemergium => "#00f4a2",
+        ResourceType::Keanium => "#a071ff",
+        ResourceType::Zynthium => "#fdd388",
+        ResourceType::Catalyst => "#ff7b7b",
+        ResourceType::Ghodium => "#ffffff",
+        _ => "#58a6ff",
+    }
+}
+
+/// Draw the flows that start and end in `room`. Flows that leave the room
+/// cannot be drawn with a room visual and are skipped.
+pub fn draw_flows(visualizer: &mut RoomVisualizer, room: RoomName, flows: &[TransferFlow]) {
+    for flow in flows.iter().filter(|f| f.from.room_name() == room && f.to.room_name() == room) {
+        let from = (flow.from.x().u8() as f32, flow.from.y().u8() as f32);
+        let to = (flow.to.x().u8() as f32, flow.to.y().u8() as f32);
+
+        let style = LineStyle::default()
+            .color(resource_color(flow.resource))
+            .width(flow_width(flow.amount))
+            .opacity(FLOW_OPACITY);
+
+        visualizer.line(from, to, Some(style.clone()));
+
+        if let Some(barbs) = arrow_head(from, to, ARROW_HEAD_SIZE) {
+            for barb in barbs {
+                visualizer.line(to, barb, Some(style.clone()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(x: u8, y: u8) -> Position {
+        Position::new(
+            RoomCoordinate::new(x).unwrap(),
+            RoomCoordinate::new(y).unwrap(),
+            RoomName::new("W1N1").unwrap(),
+        )
+    }
+
+    fn flow(from: Position, to: Position, resource: ResourceType, amount: u32) -> TransferFlow {
+        TransferFlow {
+            from,
+            to,
+            resource,
+            amount,
+        }
+    }
+
+    #[test]
+    fn merge_sums_matching_routes() {
+        let flows = [
+            flow(pos(10, 10), pos(20, 20), ResourceType::Energy, 300),
+            flow(pos(10, 10), pos(20, 20), ResourceType::Energy, 200),
+            flow(pos(10, 10), pos(20, 20), ResourceType::Hydrogen, 100),
+            flow(pos(20, 20), pos(10, 10), ResourceType::Energy, 50),
+            flow(pos(30, 30), pos(10, 10), ResourceType::Energy, 0),
+        ];
+
+        assert_eq!(
+            merge_flows(&flows),
+            vec![
+                flow(pos(20, 20), pos(10, 10), ResourceType::Energy, 50),
+                flow(pos(10, 10), pos(20, 20), ResourceType::Hydrogen, 100),
+                flow(pos(10, 10), pos(20, 20), ResourceType::Energy, 500),
+            ]
+        );
+    }
+
+    #[test]
+    fn width_scales_with_amount_and_clamps() {
+        assert_eq!(flow_width(0), MIN_FLOW_WIDTH);
+        assert_eq!(flow_width(FULL_FLOW_AMOUNT), MAX_FLOW_WIDTH);
+        assert_eq!(flow_width(FULL_FLOW_AMOUNT * 10), MAX_FLOW_WIDTH);
+        assert!(flow_width(250) < flow_width(500));
+    }
+
+    #[test]
+    fn arrow_head_points_back_along_segment() {
+        assert_eq!(arrow_head((5.0, 5.0), (5.0, 5.0), 1.0), None);
+
+        let [a, b] = arrow_head((0.0, 0.0), (10.0, 0.0), 1.0).unwrap();
+        for barb in [a, b] {
+            assert!(barb.0 < 10.0);
+            assert!(((barb.0 - 10.0).powi(2) + barb.1.powi(2) - 1.0).abs() < 1e-5);
+        }
+        assert!((a.1 + b.1).abs() < 1e-5);
+        assert!(a.1 != b.1);
+    }
+}
@@ -1,4 +1,9 @@
+pub mod energyarbitrage;
 pub mod fairvalue;
+pub mod flows;
 pub mod ordersystem;
+#[cfg(feature = "season")]
+pub mod score;
+pub mod special;
 pub mod transfersystem;
 pub mod utility;
@@ -1,14 +1,16 @@
+use super::energyarbitrage::*;
 use super::fairvalue::*;
 use super::utility::*;
 use crate::memorysystem::MemoryArbiter;
 use crate::missions::constants::*;
+use crate::missions::terminal::{deal_score, DealKind, TerminalAction, TerminalActionKind, TerminalDeals};
 use crate::room::data::*;
 use crate::segments::MARKET_SEGMENT;
 use log::*;
 use screeps::game::market::*;
 use screeps::*;
 use specs::prelude::{Entities, LazyUpdate, Read, ResourceId, System, SystemData, World, Write, WriteExpect, WriteStorage};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 /// Passive orders are placed (and active deals sized) in blocks of this many
 /// units — the per-order damage cap that predates ADR 0012 and survives it.
@@ -25,11 +27,24 @@ pub struct OrderQueueActiveRequest {
     available_transfer_energy: u32,
 }
 
+/// A room's stored energy against its marks, for the energy arbitrage pass.
+pub struct OrderQueueEnergyPosition {
+    /// Energy above the room's high-water mark.
+    surplus: u32,
+    /// Energy short of the room's low-water mark.
+    deficit: u32,
+    /// The room runs the terminal-sales overflow sink (`missions::overflow`)
+    /// and sells down to `overflow_sell_floor`.
+    overflowing: bool,
+}
+
 pub struct OrderQueueRoomData {
     outgoing_passive_requests: Vec<OrderQueuePassiveRequest>,
     outgoing_active_requests: Vec<OrderQueueActiveRequest>,
 
     incoming_passive_requests: Vec<OrderQueuePassiveRequest>,
+
+    energy_position: Option<OrderQueueEnergyPosition>,
 }
 
 impl OrderQueueRoomData {
@@ -39,6 +54,8 @@ impl OrderQueueRoomData {
             outgoing_active_requests: Vec::new(),
 
             incoming_passive_requests: Vec::new(),
+
+            energy_position: None,
         }
     }
 }
@@ -83,6 +100,16 @@ impl OrderQueue {
         room.incoming_passive_requests.push(OrderQueuePassiveRequest { resource, amount });
     }
 
+    pub fn report_energy_position(&mut self, room: RoomName, surplus: u32, deficit: u32, overflowing: bool) {
+        let room = self.get_room(room);
+
+        room.energy_position = Some(OrderQueueEnergyPosition {
+            surplus,
+            deficit,
+            overflowing,
+        });
+    }
+
     pub fn clear(&mut self) {
         self.rooms.clear();
     }
@@ -98,6 +125,8 @@ pub struct OrderQueueSystemData<'a> {
     features: Read<'a, crate::features::Features>,
     market_memory: Write<'a, MarketMemory>,
     memory_arbiter: WriteExpect<'a, MemoryArbiter>,
+    capabilities: Read<'a, crate::server::ServerCapabilities>,
+    terminal_deals: Write<'a, TerminalDeals>,
 }
 
 /// Decode the market segment into the world's [`MarketMemory`] resource,
@@ -191,6 +220,14 @@ struct ActiveSellOrderParameters {
     energy_cost: f64,
 }
 
+/// The best energy deal found for one room this pass.
+struct EnergyDeal {
+    order_id: js_sys::JsString,
+    price: f64,
+    amount: u32,
+    credits_per_energy: f64,
+}
+
 pub struct OrderQueueSystem;
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -325,6 +362,7 @@ impl OrderQueueSystem {
         active_orders: &[ActiveSellOrderParameters],
         my_orders: &JsHashMap<String, MyOrder>,
         exposure: &mut ExposureLedger,
+        deals: &mut TerminalDeals,
     ) -> bool {
         if terminal.cooldown() > 0 {
             return true;
@@ -415,24 +453,268 @@ impl OrderQueueSystem {
             });
 
         if let Some((order_id, order_price, resource, transferable_units, transfer_cost, effective_price_per_unit)) = best_deal {
-            match deal(&order_id, transferable_units, Some(source_room_name)) {
-                Ok(()) => {
-                    exposure.commit_volume(resource, transferable_units);
-                    info!(
-                        "Completed deal! Room: {} Resource: {:?} Amount: {} Transfer Cost: {} Price: {} Effective Price: {} Id: {}",
-                        source_room_name, resource, transferable_units, transfer_cost, order_price, effective_price_per_unit, order_id
-                    );
-                }
-                Err(err) => {
-                    info!("Failed to complete deal! Error: {:?} Room: {} Resource: {:?} Amount: {} Transfer Cost: {} Price: {} Effective Price: {} Id: {}", err, source_room_name, resource, transferable_units, transfer_cost, order_price, effective_price_per_unit, order_id);
-                }
-            };
+            // The room's terminal mission makes the deal when it wins the terminal (`missions::terminal`); the
+            // volume counts against the window from the moment it is filed, as a placed order's does.
+            exposure.commit_volume(resource, transferable_units);
+            info!(
+                "Filed deal! Room: {} Resource: {:?} Amount: {} Transfer Cost: {} Price: {} Effective Price: {} Id: {}",
+                source_room_name, resource, transferable_units, transfer_cost, order_price, effective_price_per_unit, order_id
+            );
+
+            deals.file(
+                source_room_name,
+                TerminalAction {
+                    kind: TerminalActionKind::Deal {
+                        order_id: String::from(order_id),
+                        price: order_price,
+                        kind: DealKind::Sale,
+                    },
+                    resource,
+                    amount: transferable_units,
+                    destination: None,
+                    score: deal_score(transferable_units as f64 * effective_price_per_unit),
+                    filed_at: game::time(),
+                },
+            );
 
             true
         } else {
             false
         }
     }
+
+    /// Sell `surplus` energy from `room_name` into the buy order paying the
+    /// most credits per energy spent, if that clears `floor`.
+    fn sell_energy(
+        room_name: RoomName,
+        terminal: &StructureTerminal,
+        surplus: u32,
+        floor: f64,
+        order_cache: &mut OrderCache,
+        my_orders: &JsHashMap<String, MyOrder>,
+        dealt_orders: &HashSet<String>,
+    ) -> Option<EnergyDeal> {
+        let terminal_energy = terminal.store().get_used_capacity(Some(ResourceType::Energy));
+
+        order_cache
+            .get_orders(MarketResourceType::Resource(ResourceType::Energy))
+            .iter()
+            .filter(|o| o.order_type() == OrderType::Buy)
+            .filter(|o| my_orders.get(String::from(o.id())).is_none() && !dealt_orders.contains(&String::from(o.id())))
+            .filter_map(|o| {
+                let order_room_name: RoomName = o.room_name()?.as_string()?.parse().ok()?;
+                let cost = calc_transaction_cost_fractional(room_name, order_room_name);
+
+                // Same distance ceiling as every deal we initiate (ADR 0012 §3).
+                if cost > MAX_DEAL_COST_PER_UNIT {
+                    return None;
+                }
+
+                let credits_per_energy = sale_credits_per_energy(o.price(), cost);
+                let amount = sale_amount(
+                    o.remaining_amount(),
+                    surplus,
+                    OrderQueue::maximum_transfer_energy(),
+                    terminal_energy,
+                    cost,
+                );
+
+                (credits_per_energy >= floor && amount > 0).then(|| EnergyDeal {
+                    order_id: o.id(),
+                    price: o.price(),
+                    amount,
+                    credits_per_energy,
+                })
+            })
+            .max_by(|a, b| {
+                a.credits_per_energy
+                    .partial_cmp(&b.credits_per_energy)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+    }
+
+    /// Buy energy to cover `deficit` in `room_name` from the sell order
+    /// costing the fewest credits per energy landed, if that is within
+    /// `ceiling` and the window's buy caps.
+    #[allow(clippy::too_many_arguments)]
+    fn buy_energy(
+        room_name: RoomName,
+        terminal: &StructureTerminal,
+        deficit: u32,
+        ceiling: f64,
+        credits: f64,
+        credit_reserve: f64,
+        exposure: &ExposureLedger,
+        order_cache: &mut OrderCache,
+        my_orders: &JsHashMap<String, MyOrder>,
+        dealt_orders: &HashSet<String>,
+    ) -> Option<EnergyDeal> {
+        let terminal_energy = terminal.store().get_used_capacity(Some(ResourceType::Energy));
+        let spendable = (credits - credit_reserve).max(0.0);
+
+        order_cache
+            .get_orders(MarketResourceType::Resource(ResourceType::Energy))
+            .iter()
+            .filter(|o| o.order_type() == OrderType::Sell)
+            .filter(|o| my_orders.get(String::from(o.id())).is_none() && !dealt_orders.contains(&String::from(o.id())))
+            .filter_map(|o| {
+                let order_room_name: RoomName = o.room_name()?.as_string()?.parse().ok()?;
+                let cost = calc_transaction_cost_fractional(room_name, order_room_name);
+
+                if cost > MAX_DEAL_COST_PER_UNIT {
+                    return None;
+                }
+
+                let credits_per_energy = purchase_credits_per_energy(o.price(), cost)?;
+                let amount = purchase_amount(
+                    o.remaining_amount(),
+                    deficit,
+                    OrderQueue::maximum_transfer_energy(),
+                    terminal_energy,
+                    spendable,
+                    o.price(),
+                    cost,
+                );
+
+                (credits_per_energy <= ceiling
+                    && amount > 0
+                    && exposure.buy_within_caps(o.price() * amount as f64, amount, ResourceType::Energy, credits, credit_reserve))
+                .then(|| EnergyDeal {
+                    order_id: o.id(),
+                    price: o.price(),
+                    amount,
+                    credits_per_energy,
+                })
+            })
+            .min_by(|a, b| {
+                a.credits_per_energy
+                    .partial_cmp(&b.credits_per_energy)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+    }
+
+    /// One arbitrage pass: at most one deal filed per idle terminal, and no
+    /// more than `energy_deals_per_tick` in all.
+    fn arbitrage_energy(
+        rooms: &BTreeMap<RoomName, OrderQueueRoomData>,
+        busy_terminals: &HashSet<RoomName>,
+        market: &crate::features::MarketFeatures,
+        order_cache: &mut OrderCache,
+        my_orders: &JsHashMap<String, MyOrder>,
+        exposure: &mut ExposureLedger,
+        deals: &mut TerminalDeals,
+    ) {
+        let mut deals_left = market.energy_deals_per_tick;
+        let mut dealt_orders = HashSet::new();
+        let mut credits = game::market::credits();
+
+        for (room_name, room_data) in rooms {
+            if deals_left == 0 {
+                break;
+            }
+
+            let Some(position) = &room_data.energy_position else {
+                continue;
+            };
+
+            if busy_terminals.contains(room_name) {
+                continue;
+            }
+
+            let Some(terminal) = game::rooms().get(*room_name).and_then(|r| r.terminal()) else {
+                continue;
+            };
+
+            if terminal.cooldown() > 0 {
+                continue;
+            }
+
+            let (selling, deal) = if position.surplus > 0 {
+                let sell_floor = if position.overflowing {
+                    market.energy_sell_floor.min(market.overflow_sell_floor)
+                } else {
+                    market.energy_sell_floor
+                };
+
+                let deal = Self::sell_energy(
+                    *room_name,
+                    &terminal,
+                    position.surplus,
+                    sell_floor,
+                    order_cache,
+                    my_orders,
+                    &dealt_orders,
+                );
+
+                (true, deal)
+            } else if position.deficit > 0 {
+                let deal = Self::buy_energy(
+                    *room_name,
+                    &terminal,
+                    position.deficit,
+                    market.energy_buy_ceiling,
+                    credits,
+                    market.credit_reserve,
+                    exposure,
+                    order_cache,
+                    my_orders,
+                    &dealt_orders,
+                );
+
+                (false, deal)
+            } else {
+                continue;
+            };
+
+            let Some(deal) = deal else {
+                continue;
+            };
+
+            // Filed with the room's terminal schedule; the terminal mission makes the deal and records its volume.
+            // A purchase's notional counts against the caps and credits from now.
+            if !selling {
+                let notional = deal.price * deal.amount as f64;
+
+                exposure.commit_buy(notional, deal.amount, ResourceType::Energy);
+                credits -= notional;
+            }
+
+            info!(
+                "Energy arbitrage {} filed! Room: {} Amount: {} Price: {} Credits per energy: {:.3} Id: {}",
+                if selling { "sale" } else { "purchase" },
+                room_name,
+                deal.amount,
+                deal.price,
+                deal.credits_per_energy,
+                deal.order_id
+            );
+
+            let order_id = String::from(deal.order_id);
+
+            deals.file(
+                *room_name,
+                TerminalAction {
+                    kind: TerminalActionKind::Deal {
+                        order_id: order_id.clone(),
+                        price: deal.price,
+                        kind: if selling {
+                            DealKind::ArbitrageSale
+                        } else {
+                            DealKind::ArbitragePurchase
+                        },
+                    },
+                    resource: ResourceType::Energy,
+                    amount: deal.amount,
+                    destination: None,
+                    score: deal_score(deal.price * deal.amount as f64),
+                    filed_at: game::time(),
+                },
+            );
+
+            dealt_orders.insert(order_id);
+            deals_left -= 1;
+        }
+    }
 }
 
 struct OrderCache {
@@ -459,8 +741,11 @@ impl<'a> System<'a> for OrderQueueSystem {
 
     fn run(&mut self, mut data: Self::SystemData) {
         let features = *data.features;
-        let can_buy = features.market.buy && game::market::credits() > features.market.credit_reserve;
-        let can_sell = features.market.sell;
+        // Without a market every call below throws.
+        let market = data.capabilities.market;
+        let can_buy = market && features.market.buy && game::market::credits() > features.market.credit_reserve;
+        let can_sell = market && features.market.sell;
+        let can_arbitrage = market && features.market.energy_arbitrage;
 
         // The market segment is always-active; its `on_load` callback fills
         // the MarketMemory resource on the first available tick. Gating
@@ -468,7 +753,7 @@ impl<'a> System<'a> for OrderQueueSystem {
         // (and so be overwritten by) the load.
         let can_run = game::time().is_multiple_of(20)
             && data.governor.can_execute_cpu(CpuBar::HighPriority)
-            && (can_buy || can_sell)
+            && (can_buy || can_sell || can_arbitrage)
             && data.market_memory.loaded;
 
         if can_run {
@@ -497,7 +782,11 @@ impl<'a> System<'a> for OrderQueueSystem {
                 // Anomalous-day skips aggregate into ONE warn per pass — a
                 // painted market day lasts a real-time day, and a per-site
                 // warn would repeat across rooms x resources every pass.
-                let mut anomalous_resources: std::collections::HashSet<ResourceType> = std::collections::HashSet::new();
+                let mut anomalous_resources: HashSet<ResourceType> = HashSet::new();
+
+                // Terminals that dealt (or tried to) this pass; a terminal
+                // makes one deal per tick.
+                let mut busy_terminals: HashSet<RoomName> = HashSet::new();
 
                 for (room_name, room_data) in &data.order_queue.rooms {
                     if let Some(terminal) = game::rooms().get(*room_name).and_then(|r| r.terminal()) {
@@ -556,14 +845,19 @@ impl<'a> System<'a> for OrderQueueSystem {
                                 })
                                 .collect();
 
-                            let _terminal_busy = Self::sell_active_orders(
+                            let terminal_busy = Self::sell_active_orders(
                                 *room_name,
                                 &terminal,
                                 &mut order_cache,
                                 &active_orders,
                                 &my_orders,
                                 &mut data.market_memory.exposure,
+                                &mut data.terminal_deals,
                             );
+
+                            if terminal_busy {
+                                busy_terminals.insert(*room_name);
+                            }
                         }
 
                         if can_buy {
@@ -602,6 +896,18 @@ impl<'a> System<'a> for OrderQueueSystem {
                     }
                 }
 
+                if can_arbitrage {
+                    Self::arbitrage_energy(
+                        &data.order_queue.rooms,
+                        &busy_terminals,
+                        &features.market,
+                        &mut order_cache,
+                        &my_orders,
+                        &mut data.market_memory.exposure,
+                        &mut data.terminal_deals,
+                    );
+                }
+
                 if !anomalous_resources.is_empty() {
                     // Genuine attack signal (deviation/z gate hits), one
                     // aggregated line per pass; per-room detail is at debug.
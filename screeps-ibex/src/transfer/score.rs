@@ -0,0 +1,56 @@
+//! Seasonal score: score containers appear in rooms holding `Score` until
+//! they decay, and score counts once delivered to a score collector.
+//!
+//! Both are found by scanning the room, as neither is a structure the
+//! structure cache knows. Built only with the `season` feature.
+
+use super::transfersystem::*;
+use crate::missions::localsupply::room_transfer::is_under_hostile_towers;
+use crate::remoteobjectid::*;
+use screeps::*;
+
+/// Score containers decay, so they are emptied ahead of the economy's own
+/// pickups.
+const SCORE_CONTAINER_PRIORITY: TransferPriority = TransferPriority::High;
+
+/// Collectors out-rank storage's `None` deposit, so score carried home
+/// goes on to a collector in the room rather than into storage.
+const SCORE_COLLECTOR_PRIORITY: TransferPriority = TransferPriority::Medium;
+
+pub fn request_transfers(transfer: &mut dyn TransferRequestSystem, room: &Room, hostile_towers: &[Position]) {
+    for container in room.find(find::SCORE_CONTAINERS, None) {
+        if is_under_hostile_towers(container.pos(), hostile_towers) {
+            continue;
+        }
+
+        let amount = container.store().get_used_capacity(Some(ResourceType::Score));
+
+        if amount > 0 {
+            let transfer_request = TransferWithdrawRequest::new(
+                TransferTarget::ScoreContainer(container.remote_id()),
+                ResourceType::Score,
+                SCORE_CONTAINER_PRIORITY,
+                amount,
+                TransferType::Haul,
+            );
+
+            transfer.request_withdraw(transfer_request);
+        }
+    }
+
+    for collector in room.find(find::SCORE_COLLECTORS, None) {
+        let free = collector.store().get_free_capacity(Some(ResourceType::Score)).max(0) as u32;
+
+        if free > 0 {
+            let transfer_request = TransferDepositRequest::new(
+                TransferTarget::ScoreCollector(collector.remote_id()),
+                Some(ResourceType::Score),
+                SCORE_COLLECTOR_PRIORITY,
+                free,
+                TransferType::Haul,
+            );
+
+            transfer.request_deposit(transfer_request);
+        }
+    }
+}
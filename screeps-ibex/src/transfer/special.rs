@@ -0,0 +1,19 @@
+//! Special resources: collectibles the standard economy ignores, such as
+//! the seasonal servers' score.
+//!
+//! Each case registers its own withdraw sources and deposit sinks from the
+//! room transfer generators through [`request_transfers`]; from there they
+//! are plain transfer requests, so the haulers carry and count them like
+//! any other resource. A case is built only with its feature (`season` for
+//! [`super::score`]), and without one the hook is empty.
+
+use super::transfersystem::*;
+use screeps::*;
+
+/// Register the special resources' withdraw sources and deposit sinks in
+/// `room`, skipping anything covered by `hostile_towers`.
+#[cfg_attr(not(feature = "season"), allow(unused_variables))]
+pub fn request_transfers(transfer: &mut dyn TransferRequestSystem, room: &Room, hostile_towers: &[Position]) {
+    #[cfg(feature = "season")]
+    super::score::request_transfers(transfer, room, hostile_towers);
+}
@@ -1,4 +1,6 @@
+use super::flows::*;
 use super::utility::*;
+use crate::features::TransferAgingFeatures;
 use crate::remoteobjectid::*;
 use crate::room::data::*;
 use crate::visualize::*;
@@ -121,6 +123,11 @@ pub enum TransferTarget {
     Factory(RemoteObjectId<StructureFactory>),
     Nuker(RemoteObjectId<StructureNuker>),
     PowerSpawn(RemoteObjectId<StructurePowerSpawn>),
+    /// Seasonal score sources and sinks (see `transfer::score`).
+    #[cfg(feature = "season")]
+    ScoreContainer(RemoteObjectId<ScoreContainer>),
+    #[cfg(feature = "season")]
+    ScoreCollector(RemoteObjectId<ScoreCollector>),
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -152,25 +159,79 @@ impl TransferTarget {
             TransferTarget::Factory(id) => Self::is_valid_from_id(id),
             TransferTarget::Nuker(id) => Self::is_valid_from_id(id),
             TransferTarget::PowerSpawn(id) => Self::is_valid_from_id(id),
+            #[cfg(feature = "season")]
+            TransferTarget::ScoreContainer(id) => Self::is_valid_from_id(id),
+            #[cfg(feature = "season")]
+            TransferTarget::ScoreCollector(id) => Self::is_valid_from_id(id),
+        }
+    }
+
+    fn revalidate_id<T>(target: &mut RemoteObjectId<T>) -> bool
+    where
+        T: RebuildableStructure + HasId + HasPosition + wasm_bindgen::JsCast,
+    {
+        if game::rooms().get(target.pos().room_name()).is_some() {
+            match target.resolve_or_rebuilt() {
+                Some((_, rebuilt)) => {
+                    if let Some(rebuilt) = rebuilt {
+                        *target = rebuilt;
+                    }
+
+                    true
+                }
+                None => false,
+            }
+        } else {
+            true
+        }
+    }
+
+    /// Like `is_valid`, but a structure that was destroyed and rebuilt on the same tile is followed
+    /// to its new id, which replaces the stored one.
+    pub fn revalidate(&mut self) -> bool {
+        match self {
+            TransferTarget::Container(id) => Self::revalidate_id(id),
+            TransferTarget::Spawn(id) => Self::revalidate_id(id),
+            TransferTarget::Extension(id) => Self::revalidate_id(id),
+            TransferTarget::Storage(id) => Self::revalidate_id(id),
+            TransferTarget::Tower(id) => Self::revalidate_id(id),
+            TransferTarget::Link(id) => Self::revalidate_id(id),
+            TransferTarget::Terminal(id) => Self::revalidate_id(id),
+            TransferTarget::Lab(id) => Self::revalidate_id(id),
+            TransferTarget::Factory(id) => Self::revalidate_id(id),
+            TransferTarget::Nuker(id) => Self::revalidate_id(id),
+            TransferTarget::PowerSpawn(id) => Self::revalidate_id(id),
+            TransferTarget::Ruin(_) | TransferTarget::Tombstone(_) | TransferTarget::Resource(_) => self.is_valid(),
+            #[cfg(feature = "season")]
+            TransferTarget::ScoreContainer(_) | TransferTarget::ScoreCollector(_) => self.is_valid(),
         }
     }
 
     pub fn pos(&self) -> RoomPosition {
+        self.position().into()
+    }
+
+    /// The target's position, without going through the game API.
+    pub fn position(&self) -> Position {
         match self {
-            TransferTarget::Container(id) => id.pos().into(),
-            TransferTarget::Spawn(id) => id.pos().into(),
-            TransferTarget::Extension(id) => id.pos().into(),
-            TransferTarget::Storage(id) => id.pos().into(),
-            TransferTarget::Tower(id) => id.pos().into(),
-            TransferTarget::Link(id) => id.pos().into(),
-            TransferTarget::Ruin(id) => id.pos().into(),
-            TransferTarget::Tombstone(id) => id.pos().into(),
-            TransferTarget::Resource(id) => id.pos().into(),
-            TransferTarget::Terminal(id) => id.pos().into(),
-            TransferTarget::Lab(id) => id.pos().into(),
-            TransferTarget::Factory(id) => id.pos().into(),
-            TransferTarget::Nuker(id) => id.pos().into(),
-            TransferTarget::PowerSpawn(id) => id.pos().into(),
+            TransferTarget::Container(id) => id.pos(),
+            TransferTarget::Spawn(id) => id.pos(),
+            TransferTarget::Extension(id) => id.pos(),
+            TransferTarget::Storage(id) => id.pos(),
+            TransferTarget::Tower(id) => id.pos(),
+            TransferTarget::Link(id) => id.pos(),
+            TransferTarget::Ruin(id) => id.pos(),
+            TransferTarget::Tombstone(id) => id.pos(),
+            TransferTarget::Resource(id) => id.pos(),
+            TransferTarget::Terminal(id) => id.pos(),
+            TransferTarget::Lab(id) => id.pos(),
+            TransferTarget::Factory(id) => id.pos(),
+            TransferTarget::Nuker(id) => id.pos(),
+            TransferTarget::PowerSpawn(id) => id.pos(),
+            #[cfg(feature = "season")]
+            TransferTarget::ScoreContainer(id) => id.pos(),
+            #[cfg(feature = "season")]
+            TransferTarget::ScoreCollector(id) => id.pos(),
         }
     }
 
@@ -240,6 +301,10 @@ impl TransferTarget {
                 Err(ErrorCode::InvalidArgs)
             }
             TransferTarget::PowerSpawn(id) => Self::withdraw_resource_amount_from_id(id, creep, resource, amount),
+            #[cfg(feature = "season")]
+            TransferTarget::ScoreContainer(id) => Self::withdraw_resource_amount_from_id(id, creep, resource, amount),
+            #[cfg(feature = "season")]
+            TransferTarget::ScoreCollector(_) => Err(ErrorCode::InvalidArgs),
         }
     }
 
@@ -278,6 +343,10 @@ impl TransferTarget {
             TransferTarget::Factory(id) => Self::creep_transfer_resource_amount_to_id(id, creep, resource, amount),
             TransferTarget::Nuker(id) => Self::creep_transfer_resource_amount_to_id(id, creep, resource, amount),
             TransferTarget::PowerSpawn(id) => Self::creep_transfer_resource_amount_to_id(id, creep, resource, amount),
+            #[cfg(feature = "season")]
+            TransferTarget::ScoreCollector(id) => Self::creep_transfer_resource_amount_to_id(id, creep, resource, amount),
+            #[cfg(feature = "season")]
+            TransferTarget::ScoreContainer(_) => panic!("Attempting to transfer resources to a score container."),
             //TODO: Split pickup and deposit targets.
             TransferTarget::Ruin(_) => panic!("Attempting to transfer resources to a ruin."),
             TransferTarget::Tombstone(_) => panic!("Attempting to transfer resources to a tombstone."),
@@ -320,6 +389,10 @@ impl TransferTarget {
             TransferTarget::Ruin(_) => panic!("Attempting to link transfer resources to a ruin!"),
             TransferTarget::Tombstone(_) => panic!("Attempting to link transfer resources to a tombstone!"),
             TransferTarget::Resource(_) => panic!("Attempting to link transfer resources to a resource!"),
+            #[cfg(feature = "season")]
+            TransferTarget::ScoreContainer(_) => panic!("Attempting to link transfer resources to a score container!"),
+            #[cfg(feature = "season")]
+            TransferTarget::ScoreCollector(_) => panic!("Attempting to link transfer resources to a score collector!"),
         }
     }
 }
@@ -347,6 +420,9 @@ pub mod target_filters {
     }
 }
 
+/// Structures that hold no resources a transfer moves are `Err`. Structures
+/// of types this build doesn't know never reach here: the structure cache
+/// drops them (`structureidentifier::find_known_structures`).
 impl std::convert::TryFrom<&StructureObject> for TransferTarget {
     type Error = ();
 
@@ -492,6 +568,9 @@ pub struct TransferNode {
     pending_withdrawls: HashMap<TransferWithdrawlKey, u32>,
     deposits: HashMap<TransferDepositKey, u32>,
     pending_deposits: HashMap<TransferDepositKey, u32>,
+    /// Amounts of each resource the node keeps back: only pickups allowed to
+    /// draw reserves may take the node below them.
+    reservations: HashMap<ResourceType, u32>,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -502,6 +581,7 @@ impl TransferNode {
             pending_withdrawls: HashMap::new(),
             deposits: HashMap::new(),
             pending_deposits: HashMap::new(),
+            reservations: HashMap::new(),
         }
     }
 
@@ -517,6 +597,23 @@ impl TransferNode {
         ((self.get_withdrawl(key) as i32) - (self.get_pending_withdrawl(key) as i32)).max(0) as u32
     }
 
+    pub fn get_reserved(&self, resource: ResourceType) -> u32 {
+        self.reservations.get(&resource).copied().unwrap_or(0)
+    }
+
+    /// Amount of `resource` that can still be picked up without dipping into
+    /// the node's reservation.
+    pub fn get_unreserved_withdrawl(&self, resource: ResourceType) -> u32 {
+        let available: u32 = self
+            .withdrawls
+            .keys()
+            .filter(|key| key.resource == resource)
+            .map(|key| self.get_available_withdrawl(key))
+            .sum();
+
+        available.saturating_sub(self.get_reserved(resource))
+    }
+
     pub fn get_deposit(&self, key: &TransferDepositKey) -> u32 {
         self.deposits.get(key).copied().unwrap_or(0)
     }
@@ -572,6 +669,14 @@ impl TransferNode {
         available_resources
     }
 
+    /// Whether the node has haul deposit demand at `priority` that no delivery is on its way to meet.
+    fn has_unserviced_deposit(&self, priority: TransferPriority) -> bool {
+        let wanted = |key: &TransferDepositKey| key.priority == priority && key.allowed_type == TransferType::Haul;
+
+        !self.pending_deposits.iter().any(|(key, amount)| wanted(key) && *amount > 0)
+            && self.deposits.keys().any(|key| wanted(key) && self.get_available_deposit(key) > 0)
+    }
+
     pub fn request_withdraw(&mut self, key: TransferWithdrawlKey, amount: u32) {
         let current = self.withdrawls.entry(key).or_insert(0);
 
@@ -584,6 +689,12 @@ impl TransferNode {
         *current += amount;
     }
 
+    pub fn reserve(&mut self, resource: ResourceType, amount: u32) {
+        let current = self.reservations.entry(resource).or_insert(0);
+
+        *current += amount;
+    }
+
     pub fn register_pickup(&mut self, withdrawls: &HashMap<ResourceType, Vec<TransferWithdrawlTicketResourceEntry>>) {
         for (resource, resource_entries) in withdrawls {
             for resource_entry in resource_entries {
@@ -616,17 +727,23 @@ impl TransferNode {
         }
     }
 
+    /// Pick up the desired resources within `available_capacity`. Unless
+    /// `draw_reserved` is set, the pickup leaves the node's reservations in
+    /// place.
     pub fn select_pickup(
         &self,
         allowed_priorities: TransferPriorityFlags,
         pickup_types: TransferTypeFlags,
         desired_resources: &HashMap<Option<ResourceType>, u32>,
         available_capacity: TransferCapacity,
+        draw_reserved: bool,
     ) -> HashMap<ResourceType, Vec<TransferWithdrawlTicketResourceEntry>> {
         let mut pickup_resources: HashMap<ResourceType, Vec<TransferWithdrawlTicketResourceEntry>> = HashMap::new();
 
         let mut remaining_capacity = available_capacity;
 
+        let mut unreserved: HashMap<ResourceType, TransferCapacity> = HashMap::new();
+
         let mut fill_none = None;
 
         for (desired_resource, amount) in desired_resources {
@@ -636,9 +753,10 @@ impl TransferNode {
                         //TODO: This does a double look up on the key...
                         let remaining_amount = self.get_available_withdrawl(key);
 
-                        if remaining_amount > 0 {
-                            let pickup_amount = remaining_capacity.clamp(remaining_amount.min(*amount));
+                        let resource_capacity = self.unreserved_capacity(&mut unreserved, *resource, draw_reserved);
+                        let pickup_amount = resource_capacity.clamp(remaining_capacity.clamp(remaining_amount.min(*amount)));
 
+                        if pickup_amount > 0 {
                             pickup_resources
                                 .entry(*resource)
                                 .or_default()
@@ -648,6 +766,7 @@ impl TransferNode {
                                     priority: key.priority,
                                 });
 
+                            resource_capacity.consume(pickup_amount);
                             remaining_capacity.consume(pickup_amount);
 
                             if remaining_capacity.empty() {
@@ -680,9 +799,11 @@ impl TransferNode {
 
                         let unconsumed_remaining_amount = remaining_amount - pickedup_resources;
 
-                        if unconsumed_remaining_amount > 0 {
-                            let pickup_amount = remaining_none_amount.clamp(remaining_capacity.clamp(unconsumed_remaining_amount));
+                        let resource_capacity = self.unreserved_capacity(&mut unreserved, key.resource, draw_reserved);
+                        let pickup_amount =
+                            resource_capacity.clamp(remaining_none_amount.clamp(remaining_capacity.clamp(unconsumed_remaining_amount)));
 
+                        if pickup_amount > 0 {
                             pickup_resources
                                 .entry(key.resource)
                                 .or_default()
@@ -692,6 +813,7 @@ impl TransferNode {
                                     priority: key.priority,
                                 });
 
+                            resource_capacity.consume(pickup_amount);
                             remaining_capacity.consume(pickup_amount);
                             remaining_none_amount.consume(pickup_amount);
 
@@ -707,6 +829,21 @@ impl TransferNode {
         pickup_resources
     }
 
+    fn unreserved_capacity<'a>(
+        &self,
+        unreserved: &'a mut HashMap<ResourceType, TransferCapacity>,
+        resource: ResourceType,
+        draw_reserved: bool,
+    ) -> &'a mut TransferCapacity {
+        unreserved.entry(resource).or_insert_with(|| {
+            if draw_reserved || !self.reservations.contains_key(&resource) {
+                TransferCapacity::Infinite
+            } else {
+                TransferCapacity::Finite(self.get_unreserved_withdrawl(resource))
+            }
+        })
+    }
+
     pub fn select_delivery(
         &self,
         allowed_priorities: TransferPriorityFlags,
@@ -873,10 +1010,16 @@ impl TransferNode {
             .iter()
             .map(|(key, amount)| format!("{:?} {:?} {:?} {:?}", key.resource, key.priority, key.allowed_type, amount));
 
+        let reserved_text = self
+            .reservations
+            .iter()
+            .map(|(resource, amount)| format!("{:?} Reserved {:?}", resource, amount));
+
         let full_text = withdraw_text
             .chain(pending_withdraw_text)
             .chain(deposit_text)
             .chain(pending_deposit_text)
+            .chain(reserved_text)
             .join("\n");
 
         //TODO: Use priority and color to visualize.
@@ -943,6 +1086,11 @@ impl TransferWithdrawTicket {
         &self.target
     }
 
+    /// Check the target is still there, following it to its new id if it was rebuilt (see `TransferTarget::revalidate`).
+    pub fn revalidate_target(&mut self) -> bool {
+        self.target.revalidate()
+    }
+
     pub fn resources(&self) -> &HashMap<ResourceType, Vec<TransferWithdrawlTicketResourceEntry>> {
         &self.resources
     }
@@ -1063,10 +1211,25 @@ impl TransferDepositTicket {
         &self.target
     }
 
+    /// Check the target is still there, following it to its new id if it was rebuilt (see `TransferTarget::revalidate`).
+    pub fn revalidate_target(&mut self) -> bool {
+        self.target.revalidate()
+    }
+
     pub fn resources(&self) -> &HashMap<ResourceType, Vec<TransferDepositTicketResourceEntry>> {
         &self.resources
     }
 
+    /// Whether the pickup for this delivery may draw on reserved resources:
+    /// only high priority deliveries (spawns, extensions, towers under attack)
+    /// can.
+    pub fn draws_reserves(&self) -> bool {
+        self.resources
+            .values()
+            .flatten()
+            .all(|entry| entry.priority == TransferPriority::High)
+    }
+
     pub fn combine_with(&mut self, other: &TransferDepositTicket) {
         for (resource, entries) in other.resources.iter() {
             self.resources
@@ -1168,6 +1331,7 @@ pub struct TransferQueueRoomStatsData {
     total_active_deposit: u32,
     deposit_resource_stats: HashMap<TransferDepositKey, TransferQueueResourceStatsData>,
     deposit_priorities: TransferPriorityFlags,
+    reserved: HashMap<ResourceType, u32>,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -1182,6 +1346,7 @@ impl TransferQueueRoomStatsData {
             total_active_deposit: 0,
             deposit_resource_stats: HashMap::new(),
             deposit_priorities: TransferPriorityFlags::UNSET,
+            reserved: HashMap::new(),
         }
     }
 
@@ -1200,6 +1365,10 @@ impl TransferQueueRoomStatsData {
     pub fn total_active_deposit(&self) -> u32 {
         self.total_active_deposit
     }
+
+    pub fn reserved(&self, resource: ResourceType) -> u32 {
+        self.reserved.get(&resource).copied().unwrap_or(0)
+    }
 }
 
 pub struct TransferQueueRoomData {
@@ -1230,6 +1399,14 @@ impl TransferQueueRoomData {
         self.nodes.get(target)
     }
 
+    /// Nodes whose target is within `range` of `pos`. Only sees the nodes
+    /// already generated this tick; it never runs the room's generators.
+    pub fn nodes_in_range(&self, pos: Position, range: u32) -> impl Iterator<Item = (&TransferTarget, &TransferNode)> {
+        self.nodes
+            .iter()
+            .filter(move |(target, _)| Position::from(target.pos()).in_range_to(pos, range))
+    }
+
     fn get_mut_withdrawl_stats(&mut self, key: TransferWithdrawlKey) -> &mut TransferQueueResourceStatsData {
         self.stats
             .withdrawl_resource_stats
@@ -1316,6 +1493,10 @@ pub trait TransferRequestSystem {
     fn register_pickup(&mut self, ticket: &TransferWithdrawTicket);
 
     fn register_delivery(&mut self, ticket: &TransferDepositTicket);
+
+    /// Keep `amount` of `resource` at `target`: pickups that don't serve a
+    /// high priority delivery stop short of it. Creates no deposit.
+    fn reserve(&mut self, target: TransferTarget, resource: ResourceType, amount: u32);
 }
 
 pub struct TransferQueueGeneratorData<'a, 's, RD>
@@ -1354,6 +1535,15 @@ struct GeneratorEntry {
     generator: TransferQueueGenerator,
 }
 
+/// Most generators one flush of a room runs. A room registers a few dozen at
+/// most, so hitting this means generators are being registered as fast as
+/// they run; the rest are dropped rather than looping.
+const MAX_GENERATORS_PER_FLUSH: usize = 256;
+
+/// CPU a single generator may take before it is logged (profile builds only).
+#[cfg(feature = "profile")]
+const GENERATOR_CPU_WARN: f64 = 1.0;
+
 #[derive(Default)]
 struct LazyTransferQueueRooms {
     generators: HashMap<RoomName, Vec<GeneratorEntry>>,
@@ -1370,14 +1560,62 @@ impl LazyTransferQueueRooms {
     }
 
     fn flush_generators(&mut self, data: &dyn TransferRequestSystemData, room: RoomName, transfer_types: TransferTypeFlags) {
+        let mut flushed = 0;
+
         while let Some(entry) = self.get_next_generator(room, transfer_types) {
+            if flushed == MAX_GENERATORS_PER_FLUSH {
+                let dropped = 1 + self.drop_generators(room, transfer_types);
+                warn!(
+                    "Transfer generators for {} (cause: {}) still pending after {} ran; dropped {}",
+                    room,
+                    data.get_cause(),
+                    flushed,
+                    dropped
+                );
+                break;
+            }
+            flushed += 1;
+
+            // A generator only files requests; one that registers generators for the room being flushed
+            // would keep this loop running.
+            #[cfg(debug_assertions)]
+            let pending = self.generators.get(&room).map(|g| g.len()).unwrap_or(0);
+            #[cfg(feature = "profile")]
+            let start_cpu = game::cpu::get_used();
+
             match (entry.generator)(data, self, room) {
                 Ok(_) => {}
                 Err(err) => info!("Transfer information generator error: {}", err),
             }
+
+            #[cfg(feature = "profile")]
+            {
+                let cpu = game::cpu::get_used() - start_cpu;
+                if cpu > GENERATOR_CPU_WARN {
+                    warn!("Transfer generator for {} (cause: {}) took {:.2} CPU", room, data.get_cause(), cpu);
+                }
+            }
+            #[cfg(debug_assertions)]
+            debug_assert!(
+                self.generators.get(&room).map(|g| g.len()).unwrap_or(0) <= pending,
+                "transfer generator for {} (cause: {}) registered generators for the room being flushed",
+                room,
+                data.get_cause()
+            );
         }
     }
 
+    /// Drop the room's generators of `transfer_types`, returning how many there were.
+    fn drop_generators(&mut self, room: RoomName, transfer_types: TransferTypeFlags) -> usize {
+        let Some(generators) = self.generators.get_mut(&room) else {
+            return 0;
+        };
+        let before = generators.len();
+        generators.retain(|entry| !entry.transfer_types.intersects(transfer_types));
+
+        before - generators.len()
+    }
+
     fn get_next_generator(&mut self, room: RoomName, transfer_types: TransferTypeFlags) -> Option<GeneratorEntry> {
         if let Some(generators) = self.generators.get_mut(&room) {
             if let Some((index, _)) = generators.iter().find_position(|d| d.transfer_types.intersects(transfer_types)) {
@@ -1525,11 +1763,148 @@ impl TransferRequestSystem for LazyTransferQueueRooms {
         let node = room.get_node(&ticket.target);
         node.register_delivery(&ticket.resources);
     }
+
+    fn reserve(&mut self, target: TransferTarget, resource: ResourceType, amount: u32) {
+        let room = self.get_room_no_flush(target.pos().room_name());
+
+        let reserved = room.stats.reserved.entry(resource).or_insert(0);
+        *reserved += amount;
+
+        let node = room.get_node(&target);
+        node.reserve(resource, amount);
+    }
+}
+
+/// Where [`TransferAging`] keeps the ages across VM restarts.
+const AGING_MEMORY_PATH: &str = "_transfer_aging";
+
+/// A starved target as kept in Memory: when its Low demand was first seen unserviced.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct StarvedTarget {
+    target: TransferTarget,
+    since: u32,
+}
+
+/// How long each target's Low deposit demand has gone unserviced. The queue is rebuilt every tick, so the
+/// ages are kept beside it and refreshed from it just before it is cleared; they are kept in
+/// `Memory._transfer_aging` so a VM restart doesn't start them over. A target starved for `promote_after`
+/// ticks has its Low demand selected wherever Medium is in its room, so nuker fills and wall repair buffers
+/// aren't put off forever by a colony that always has Medium work. `None` demand is never promoted.
+#[derive(Default)]
+struct TransferAging {
+    /// Target -> the tick its Low demand was first seen unserviced, and the last tick it was.
+    starved: HashMap<TransferTarget, (u32, u32)>,
+    /// Targets starved past `promote_after` at the last update.
+    promoted: HashSet<TransferTarget>,
+    /// Rooms holding a promoted target.
+    promoted_rooms: HashSet<RoomName>,
+    /// Whether a target started or stopped starving since the last save.
+    dirty: bool,
+}
+
+impl TransferAging {
+    /// Rebuild the ages from their saved form. The last sighting isn't kept, so a target counts as seen at
+    /// `now` and is forgotten `forget_after` ticks on unless its room's demand is generated again.
+    fn restore(saved: Vec<StarvedTarget>, now: u32) -> TransferAging {
+        TransferAging {
+            starved: saved.into_iter().map(|s| (s.target, (s.since, now))).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn saved(&self) -> Vec<StarvedTarget> {
+        self.starved
+            .iter()
+            .map(|(target, (since, _))| StarvedTarget {
+                target: *target,
+                since: *since,
+            })
+            .collect()
+    }
+
+    /// Read the persisted ages back from Memory.
+    fn load(now: u32) -> TransferAging {
+        let saved = serde_wasm_bindgen::from_value(crate::memory_helper::path_get(AGING_MEMORY_PATH)).unwrap_or_default();
+
+        TransferAging::restore(saved, now)
+    }
+
+    /// Write the ages to Memory if a target started or stopped starving since the last save.
+    fn save(&mut self) {
+        if !self.dirty {
+            return;
+        }
+
+        let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+
+        match self.saved().serialize(&serializer) {
+            Ok(value) => crate::memory_helper::path_set(AGING_MEMORY_PATH, value),
+            Err(err) => warn!("Failed to save transfer aging: {}", err),
+        }
+
+        self.dirty = false;
+    }
+
+    fn update<'a, I>(&mut self, nodes: I, now: u32, config: TransferAgingFeatures)
+    where
+        I: Iterator<Item = (&'a TransferTarget, &'a TransferNode)>,
+    {
+        let before = self.starved.len();
+
+        for (target, node) in nodes {
+            if node.has_unserviced_deposit(TransferPriority::Low) {
+                match self.starved.entry(*target) {
+                    Entry::Occupied(mut entry) => entry.get_mut().1 = now,
+                    Entry::Vacant(entry) => {
+                        entry.insert((now, now));
+                        self.dirty = true;
+                    }
+                }
+            } else if self.starved.remove(target).is_some() {
+                self.dirty = true;
+            }
+        }
+
+        // A target whose room's demand hasn't been generated lately can't be told apart from a serviced one.
+        self.starved.retain(|_, (_, last)| now.saturating_sub(*last) <= config.forget_after);
+        self.dirty |= self.starved.len() != before;
+
+        self.promoted = if config.on {
+            self.starved
+                .iter()
+                .filter(|(_, (since, _))| now.saturating_sub(*since) >= config.promote_after)
+                .map(|(target, _)| *target)
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        self.promoted_rooms = self.promoted.iter().map(|target| target.position().room_name()).collect();
+    }
+
+    /// The priorities selection admits across `room`: Low as well as Medium once a target there is promoted.
+    fn admitted_in_room(&self, room: RoomName, allowed_priorities: TransferPriorityFlags) -> TransferPriorityFlags {
+        if self.promoted_rooms.contains(&room) && allowed_priorities.contains(TransferPriorityFlags::MEDIUM) {
+            allowed_priorities | TransferPriorityFlags::LOW
+        } else {
+            allowed_priorities
+        }
+    }
+
+    /// The priorities selection admits at `target`: a promoted target's Low demand goes with Medium.
+    fn admitted(&self, target: &TransferTarget, allowed_priorities: TransferPriorityFlags) -> TransferPriorityFlags {
+        if self.promoted.contains(target) {
+            self.admitted_in_room(target.position().room_name(), allowed_priorities)
+        } else {
+            allowed_priorities
+        }
+    }
 }
 
 #[derive(Default)]
 pub struct TransferQueue {
     rooms: LazyTransferQueueRooms,
+    flows: Vec<TransferFlow>,
+    aging: TransferAging,
 }
 
 impl TransferRequestSystem for TransferQueue {
@@ -1548,9 +1923,21 @@ impl TransferRequestSystem for TransferQueue {
     fn register_delivery(&mut self, ticket: &TransferDepositTicket) {
         self.rooms.register_delivery(ticket)
     }
+
+    fn reserve(&mut self, target: TransferTarget, resource: ResourceType, amount: u32) {
+        self.rooms.reserve(target, resource, amount)
+    }
 }
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl TransferQueue {
+    /// An empty queue carrying the persisted transfer ages.
+    pub fn load() -> TransferQueue {
+        TransferQueue {
+            aging: TransferAging::load(game::time()),
+            ..Default::default()
+        }
+    }
+
     pub fn register_generator(&mut self, room: RoomName, transfer_types: TransferTypeFlags, generator: TransferQueueGenerator) {
         self.rooms.register_generator(room, transfer_types, generator)
     }
@@ -1590,6 +1977,7 @@ impl TransferQueue {
         pickup_types: TransferTypeFlags,
         desired_resources: &HashMap<Option<ResourceType>, u32>,
         available_capacity: TransferCapacity,
+        draw_reserved: bool,
     ) -> Vec<TransferWithdrawTicket> {
         let mut tickets = Vec::new();
 
@@ -1597,7 +1985,13 @@ impl TransferQueue {
             if let Some(room) = self.try_get_room(data, *pickup_room, pickup_types) {
                 if room.stats.withdrawl_priorities.intersects(allowed_priorities) {
                     for (target, node) in room.nodes.iter() {
-                        let pickup_resources = node.select_pickup(allowed_priorities, pickup_types, desired_resources, available_capacity);
+                        let pickup_resources = node.select_pickup(
+                            allowed_priorities,
+                            pickup_types,
+                            desired_resources,
+                            available_capacity,
+                            draw_reserved,
+                        );
 
                         if !pickup_resources.is_empty() {
                             tickets.push(TransferWithdrawTicket {
@@ -1648,9 +2042,13 @@ impl TransferQueue {
     ) -> Vec<TransferDepositTicket> {
         let mut tickets = Vec::new();
 
-        if let Some(room) = self.try_get_room(data, delivery_room, delivery_types) {
-            if room.stats.deposit_priorities.intersects(allowed_priorities) {
+        let room_priorities = self.aging.admitted_in_room(delivery_room, allowed_priorities);
+
+        if let Some(room) = self.rooms.try_get_room(data, delivery_room, delivery_types) {
+            if room.stats.deposit_priorities.intersects(room_priorities) {
                 for (target, node) in room.nodes.iter() {
+                    let allowed_priorities = self.aging.admitted(target, allowed_priorities);
+
                     if let Some((delivery_resource, delivery_entries)) =
                         node.select_single_delivery(allowed_priorities, delivery_types, available_resources, available_capacity)
                     {
@@ -1687,10 +2085,13 @@ impl TransferQueue {
         let mut tickets = Vec::new();
 
         for delivery_room in delivery_rooms.iter() {
-            if let Some(room) = self.try_get_room(data, *delivery_room, delivery_types) {
-                if room.stats.deposit_priorities.intersects(allowed_priorities) {
+            let room_priorities = self.aging.admitted_in_room(*delivery_room, allowed_priorities);
+
+            if let Some(room) = self.rooms.try_get_room(data, *delivery_room, delivery_types) {
+                if room.stats.deposit_priorities.intersects(room_priorities) {
                     for (target, node) in room.nodes.iter() {
                         if target_filter(target) {
+                            let allowed_priorities = self.aging.admitted(target, allowed_priorities);
                             let delivery_resources =
                                 node.select_delivery(allowed_priorities, delivery_types, available_resources, available_capacity);
 
@@ -1719,17 +2120,29 @@ impl TransferQueue {
 
         for room_name in rooms {
             if let Some(room) = self.try_get_room(data, *room_name, transfer_type.into()) {
+                let mut room_resources: HashMap<_, u32> = HashMap::new();
+
                 for (key, stats) in &room.stats().withdrawl_resource_stats {
                     if key.allowed_type == transfer_type {
                         let unfufilled_amount = stats.unfufilled_amount();
 
                         if unfufilled_amount > 0 {
-                            let current_amount = available_resources.entry(key.resource).or_insert(0);
+                            let current_amount = room_resources.entry(key.resource).or_insert(0);
 
                             *current_amount += unfufilled_amount as u32;
                         }
                     }
                 }
+
+                for (resource, amount) in room_resources {
+                    let unreserved_amount = amount.saturating_sub(room.stats().reserved(resource));
+
+                    if unreserved_amount > 0 {
+                        let current_amount = available_resources.entry(resource).or_insert(0);
+
+                        *current_amount += unreserved_amount;
+                    }
+                }
             }
         }
 
@@ -1848,6 +2261,7 @@ impl TransferQueue {
                 transfer_type.into(),
                 &delivery_resources,
                 available_capacity,
+                delivery.draws_reserves(),
             );
 
             (pickups, delivery)
@@ -1933,14 +2347,21 @@ impl TransferQueue {
             .try_get_room(data, target.pos().room_name(), delivery_type.into())
             .and_then(|r| r.try_get_node(target))?;
 
+        let pickup_resources = node.select_pickup(
+            allowed_pickup_priorities,
+            delivery_type.into(),
+            &delivery_resources,
+            available_capacity,
+            delivery.draws_reserves(),
+        );
+
+        if pickup_resources.is_empty() {
+            return None;
+        }
+
         let pickup = TransferWithdrawTicket {
             target: *target,
-            resources: node.select_pickup(
-                allowed_pickup_priorities,
-                delivery_type.into(),
-                &delivery_resources,
-                available_capacity,
-            ),
+            resources: pickup_resources,
         };
 
         Some((pickup, delivery))
@@ -1969,7 +2390,13 @@ impl TransferQueue {
 
         desired_resources.insert(Some(resource_type), resource_amount);
 
-        let pickup_resources = node.select_pickup(allowed_pickup_priorities, transfer_types, &desired_resources, available_capacity);
+        let pickup_resources = node.select_pickup(
+            allowed_pickup_priorities,
+            transfer_types,
+            &desired_resources,
+            available_capacity,
+            false,
+        );
 
         if pickup_resources.is_empty() {
             return None;
@@ -2037,14 +2464,21 @@ impl TransferQueue {
             .try_get_room(data, target.pos().room_name(), delivery_type.into())
             .and_then(|r| r.try_get_node(target))?;
 
+        let pickup_resources = node.select_pickup(
+            allowed_pickup_priorities,
+            delivery_type.into(),
+            &delivery_resources,
+            available_capacity,
+            delivery.draws_reserves(),
+        );
+
+        if pickup_resources.is_empty() {
+            return None;
+        }
+
         let pickup = TransferWithdrawTicket {
             target: *target,
-            resources: node.select_pickup(
-                allowed_pickup_priorities,
-                delivery_type.into(),
-                &delivery_resources,
-                available_capacity,
-            ),
+            resources: pickup_resources,
         };
 
         Some((pickup, delivery))
@@ -2339,8 +2773,47 @@ impl TransferQueue {
         total_pickup
     }
 
+    /// Record the pickup -> delivery pairs a hauler was just matched with so
+    /// they can be drawn as flows before the queue is cleared.
+    pub fn record_flows(&mut self, pickup: &TransferWithdrawTicket, deliveries: &[TransferDepositTicket]) {
+        let from = Position::from(pickup.target().pos());
+
+        for delivery in deliveries {
+            let to = Position::from(delivery.target().pos());
+
+            for (resource, entries) in delivery.resources() {
+                let amount = entries.iter().map(|e| e.amount()).sum::<u32>();
+
+                self.flows.push(TransferFlow {
+                    from,
+                    to,
+                    resource: *resource,
+                    amount,
+                });
+            }
+        }
+    }
+
+    pub fn flows(&self) -> &[TransferFlow] {
+        &self.flows
+    }
+
+    /// Refresh how long each target's Low demand has gone unserviced from this tick's queue. Runs just before
+    /// [`Self::clear`]; the ages outlive it.
+    pub fn age(&mut self, now: u32, config: TransferAgingFeatures) {
+        let nodes = self.rooms.rooms.values().flat_map(|room| room.nodes.iter());
+
+        self.aging.update(nodes, now, config);
+    }
+
+    /// Write the ages to Memory if they changed.
+    pub fn save_aging(&mut self) {
+        self.aging.save();
+    }
+
     pub fn clear(&mut self) {
         self.rooms.clear();
+        self.flows.clear();
     }
 
     /// Build a snapshot of transfer queue state for visualization (does not clear the queue).
@@ -2452,6 +2925,8 @@ pub struct TransferQueueUpdateSystemData<'a> {
     updater: Read<'a, LazyUpdate>,
     entities: Entities<'a>,
     room_data: WriteStorage<'a, RoomData>,
+    features: Read<'a, crate::features::Features>,
+    visualizer: Option<Write<'a, Visualizer>>,
 }
 
 pub struct TransferQueueUpdateSystem;
@@ -2461,6 +2936,32 @@ impl<'a> System<'a> for TransferQueueUpdateSystem {
     type SystemData = TransferQueueUpdateSystemData<'a>;
 
     fn run(&mut self, mut data: Self::SystemData) {
+        // Draw from the queue before it is cleared; the visualizer only exists when `visualize.on`.
+        if let Some(visualizer) = data.visualizer.as_mut() {
+            let transfer = data.features.transfer;
+
+            if transfer.visualize.demand() {
+                for (room_name, room) in data.transfer_queue.rooms.rooms.iter() {
+                    let room_visualizer = visualizer.get_room(*room_name, VisualLayer::Transfer);
+
+                    for (target, node) in room.nodes.iter() {
+                        node.visualize(room_visualizer, target.pos());
+                    }
+                }
+            }
+
+            if transfer.visualize_flows {
+                let flows = merge_flows(data.transfer_queue.flows());
+                let rooms: HashSet<RoomName> = flows.iter().map(|flow| flow.from.room_name()).collect();
+
+                for room_name in rooms {
+                    draw_flows(visualizer.get_room(room_name, VisualLayer::Transfer), room_name, &flows);
+                }
+            }
+        }
+
+        data.transfer_queue.age(game::time(), data.features.transfer.aging);
+        data.transfer_queue.save_aging();
         data.transfer_queue.clear();
     }
 }
@@ -2473,6 +2974,43 @@ mod tests {
     // zero length/cost cannot produce NaN or infinity for the priority
     // comparators.
 
+    struct TestGeneratorData;
+
+    impl TransferRequestSystemData for TestGeneratorData {
+        fn get_cause(&self) -> &str {
+            "test"
+        }
+
+        fn get_room_data(&self, _entity: Entity) -> Option<&RoomData> {
+            None
+        }
+    }
+
+    #[test]
+    fn flush_runs_at_most_the_cap_and_drops_the_rest() {
+        let room: RoomName = "W1N1".parse().unwrap();
+        let ran = std::rc::Rc::new(std::cell::Cell::new(0));
+        let mut rooms = LazyTransferQueueRooms::default();
+
+        for _ in 0..MAX_GENERATORS_PER_FLUSH + 10 {
+            let ran = ran.clone();
+            rooms.register_generator(
+                room,
+                TransferTypeFlags::HAUL,
+                Box::new(move |_, _, _| {
+                    ran.set(ran.get() + 1);
+                    Ok(())
+                }),
+            );
+        }
+        rooms.register_generator(room, TransferTypeFlags::USE, Box::new(|_, _, _| Ok(())));
+
+        rooms.flush_generators(&TestGeneratorData, room, TransferTypeFlags::HAUL);
+
+        assert_eq!(ran.get(), MAX_GENERATORS_PER_FLUSH);
+        assert_eq!(rooms.generators[&room].len(), 1, "other transfer types stay registered");
+    }
+
     #[test]
     fn finite_transfer_value_guards_zero_divisor() {
         assert_eq!(finite_transfer_value(0, 0.0), 0.0);
@@ -2495,4 +3033,213 @@ mod tests {
             }
         }
     }
+
+    fn picked_up(pickup: &HashMap<ResourceType, Vec<TransferWithdrawlTicketResourceEntry>>, resource: ResourceType) -> u32 {
+        pickup
+            .get(&resource)
+            .map(|entries| entries.iter().map(|e| e.amount).sum())
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn reservations_hold_back_all_but_reserve_drawing_pickups() {
+        let mut node = TransferNode::new();
+
+        let key = TransferWithdrawlKey {
+            resource: ResourceType::Energy,
+            priority: TransferPriority::None,
+            allowed_type: TransferType::Haul,
+        };
+
+        node.request_withdraw(key, 10_000);
+        node.reserve(ResourceType::Energy, 6_000);
+        node.reserve(ResourceType::Energy, 2_000);
+
+        assert_eq!(node.get_unreserved_withdrawl(ResourceType::Energy), 2_000);
+
+        let specific = HashMap::from([(Some(ResourceType::Energy), 5_000)]);
+        let any = HashMap::from([(None, 5_000)]);
+        let capacity = TransferCapacity::Infinite;
+
+        for desired in [&specific, &any] {
+            let held_back = node.select_pickup(TransferPriorityFlags::ALL, TransferTypeFlags::HAUL, desired, capacity, false);
+            let drawn = node.select_pickup(TransferPriorityFlags::ALL, TransferTypeFlags::HAUL, desired, capacity, true);
+
+            assert_eq!(picked_up(&held_back, ResourceType::Energy), 2_000);
+            assert_eq!(picked_up(&drawn, ResourceType::Energy), 5_000);
+        }
+
+        // Once pickups are pending against the node, the reserve is all that is left.
+        node.pending_withdrawls.insert(key, 3_000);
+
+        let held_back = node.select_pickup(TransferPriorityFlags::ALL, TransferTypeFlags::HAUL, &specific, capacity, false);
+
+        assert!(held_back.is_empty());
+    }
+
+    fn container(n: u32) -> TransferTarget {
+        let pos = Position::new(
+            RoomCoordinate::new(10).unwrap(),
+            RoomCoordinate::new(10 + n as u8).unwrap(),
+            "W1N1".parse().unwrap(),
+        );
+        let id = format!("{:024x}", n).parse().unwrap();
+
+        TransferTarget::Container(RemoteObjectId::new_from_components(id, pos))
+    }
+
+    fn deposit_key(priority: TransferPriority) -> TransferDepositKey {
+        TransferDepositKey {
+            resource: Some(ResourceType::Energy),
+            priority,
+            allowed_type: TransferType::Haul,
+        }
+    }
+
+    const AGING: TransferAgingFeatures = TransferAgingFeatures {
+        on: true,
+        promote_after: 10,
+        forget_after: 5,
+    };
+
+    /// A queue rebuilt every tick with Medium demand at one target and `starved_priority` demand at another.
+    fn starving_queue(starved_priority: TransferPriority) -> HashMap<TransferTarget, TransferNode> {
+        let mut nodes = HashMap::new();
+
+        nodes
+            .entry(container(1))
+            .or_insert_with(TransferNode::new)
+            .request_deposit(deposit_key(TransferPriority::Medium), 1_000);
+        nodes
+            .entry(container(2))
+            .or_insert_with(TransferNode::new)
+            .request_deposit(deposit_key(starved_priority), 1_000);
+
+        nodes
+    }
+
+    /// The targets a hauler's Medium pass finds deliveries at.
+    fn medium_deliveries(aging: &TransferAging, nodes: &HashMap<TransferTarget, TransferNode>) -> Vec<TransferTarget> {
+        let energy = HashMap::from([(ResourceType::Energy, 500)]);
+
+        nodes
+            .iter()
+            .filter(|(target, node)| {
+                let allowed = aging.admitted(target, TransferPriorityFlags::MEDIUM);
+
+                !node
+                    .select_delivery(allowed, TransferTypeFlags::HAUL, &energy, TransferCapacity::Infinite)
+                    .is_empty()
+            })
+            .map(|(target, _)| *target)
+            .collect()
+    }
+
+    /// With Medium demand every tick the Low pass is never reached; the starved target is picked up in the
+    /// Medium pass once promoted.
+    #[test]
+    fn starved_low_deposits_are_eventually_serviced() {
+        let mut aging = TransferAging::default();
+        let mut serviced = None;
+
+        for now in 0..3 * AGING.promote_after {
+            let nodes = starving_queue(TransferPriority::Low);
+
+            if medium_deliveries(&aging, &nodes).contains(&container(2)) {
+                serviced = Some(now);
+                break;
+            }
+
+            aging.update(nodes.iter(), now, AGING);
+        }
+
+        assert_eq!(serviced, Some(AGING.promote_after + 1));
+
+        // Once a delivery is on its way the age starts over.
+        let mut nodes = starving_queue(TransferPriority::Low);
+        nodes
+            .get_mut(&container(2))
+            .unwrap()
+            .pending_deposits
+            .insert(deposit_key(TransferPriority::Low), 500);
+        aging.update(nodes.iter(), 3 * AGING.promote_after, AGING);
+
+        assert_eq!(
+            medium_deliveries(&aging, &starving_queue(TransferPriority::Low)),
+            vec![container(1)]
+        );
+    }
+
+    #[test]
+    fn none_demand_and_disabled_aging_are_never_promoted() {
+        let mut none_aging = TransferAging::default();
+        let mut off_aging = TransferAging::default();
+        let off = TransferAgingFeatures { on: false, ..AGING };
+
+        for now in 0..3 * AGING.promote_after {
+            none_aging.update(starving_queue(TransferPriority::None).iter(), now, AGING);
+            off_aging.update(starving_queue(TransferPriority::Low).iter(), now, off);
+        }
+
+        assert_eq!(
+            medium_deliveries(&none_aging, &starving_queue(TransferPriority::None)),
+            vec![container(1)]
+        );
+        assert_eq!(
+            medium_deliveries(&off_aging, &starving_queue(TransferPriority::Low)),
+            vec![container(1)]
+        );
+    }
+
+    #[test]
+    fn ages_lapse_when_the_room_is_not_generated() {
+        let mut aging = TransferAging::default();
+
+        aging.update(starving_queue(TransferPriority::Low).iter(), 0, AGING);
+        aging.update(std::iter::empty(), AGING.forget_after + 1, AGING);
+
+        assert!(aging.starved.is_empty());
+    }
+
+    /// A promoted target opens the Low pass in its own room only.
+    #[test]
+    fn promotion_is_kept_to_the_starved_room() {
+        let mut aging = TransferAging::default();
+
+        for now in 0..=AGING.promote_after {
+            aging.update(starving_queue(TransferPriority::Low).iter(), now, AGING);
+        }
+
+        let starved_room = container(2).position().room_name();
+        let other_room: RoomName = "W2N1".parse().unwrap();
+
+        assert!(aging
+            .admitted_in_room(starved_room, TransferPriorityFlags::MEDIUM)
+            .contains(TransferPriorityFlags::LOW));
+        assert_eq!(
+            aging.admitted_in_room(other_room, TransferPriorityFlags::MEDIUM),
+            TransferPriorityFlags::MEDIUM
+        );
+    }
+
+    /// Ages saved before a VM restart promote on the tick they would have without it.
+    #[test]
+    fn restored_ages_keep_their_start() {
+        let mut aging = TransferAging::default();
+
+        for now in 0..AGING.promote_after {
+            aging.update(starving_queue(TransferPriority::Low).iter(), now, AGING);
+        }
+
+        assert!(aging.dirty, "a newly starved target is saved");
+        let mut restored = TransferAging::restore(aging.saved(), AGING.promote_after);
+        assert_eq!(
+            medium_deliveries(&restored, &starving_queue(TransferPriority::Low)),
+            vec![container(1)]
+        );
+
+        restored.update(starving_queue(TransferPriority::Low).iter(), AGING.promote_after, AGING);
+        assert!(medium_deliveries(&restored, &starving_queue(TransferPriority::Low)).contains(&container(2)));
+        assert!(!restored.dirty, "a target still starving doesn't need saving again");
+    }
 }
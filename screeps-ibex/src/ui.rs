@@ -1,3 +1,6 @@
+use crate::military::posture::{DefensePosture, PostureLevel};
+use crate::military::threatmap::ThreatLevel;
+use crate::visualization::{truncate_content, SpawnQueueEntry, VisStyles, CHAR_WIDTH, LINE_HEIGHT, PAD};
 use crate::visualize::*;
 use screeps::*;
 
@@ -26,11 +29,317 @@ impl UISystem {
         callback(global_visualizer);
     }
 
-    pub fn with_room<T>(&mut self, room: RoomName, visualizer: &mut Visualizer, callback: T)
+    /// Draw into `layer` of `room`'s visuals; see [`VisualLayer`] for which layers give way when the room
+    /// runs over its budget.
+    pub fn with_room<T>(&mut self, room: RoomName, layer: VisualLayer, visualizer: &mut Visualizer, callback: T)
     where
         T: FnOnce(&mut RoomVisualizer),
     {
-        let room_visualizer = visualizer.get_room(room);
+        let room_visualizer = visualizer.get_room(room, layer);
         callback(room_visualizer);
     }
 }
+
+// ─── Room sidebar ────────────────────────────────────────────────────────────
+
+/// Spawn requests listed by name under the queue depth.
+const SIDEBAR_NEXT_SPAWNS: usize = 3;
+
+const COLOR_ALERT: &str = "#f85149";
+
+/// At-a-glance dashboard for one owned room, drawn in its top-left corner.
+/// Built by `AggregateSummarySystem` from resources already cached this
+/// tick (economy snapshot, ledger, spawn queue, room data, threat data,
+/// summary components) — rendering makes no game API calls.
+#[derive(Debug, Clone)]
+pub struct RoomSidebar {
+    pub room: RoomName,
+    /// Storage + terminal + containers.
+    pub stored_energy: u32,
+    /// Net energy per tick from the `ResourceLedger`.
+    pub energy_delta: Option<f64>,
+    pub controller_level: Option<u8>,
+    /// `(progress, progress_total)`; no total at max level.
+    pub controller_progress: Option<(u32, Option<u32>)>,
+    pub ticks_to_downgrade: Option<u32>,
+    pub spawn_queue_depth: usize,
+    /// Highest-priority requests first, at most [`SIDEBAR_NEXT_SPAWNS`].
+    pub next_spawns: Vec<String>,
+    /// Creeps in the room by job type.
+    pub creeps_by_role: Vec<(&'static str, u32)>,
+    pub active_missions: usize,
+    pub paused_missions: usize,
+    /// `(launches, energy, spawn ticks)` the operation budget has committed to this room, when any.
+    pub operation_commitments: Option<(usize, u32, u32)>,
+    /// Overflow sinks the room runs (`missions::overflow`), in priority order.
+    pub overflow_sinks: Vec<&'static str>,
+    /// The room's defense posture; listed when above peaceful or pinned.
+    pub posture: Option<DefensePosture>,
+    /// Threat classification and hostile count, when the room has a threat.
+    pub hostiles: Option<(ThreatLevel, usize)>,
+}
+
+impl RoomSidebar {
+    pub fn new(room: RoomName) -> RoomSidebar {
+        RoomSidebar {
+            room,
+            stored_energy: 0,
+            energy_delta: None,
+            controller_level: None,
+            controller_progress: None,
+            ticks_to_downgrade: None,
+            spawn_queue_depth: 0,
+            next_spawns: Vec::new(),
+            creeps_by_role: Vec::new(),
+            active_missions: 0,
+            paused_missions: 0,
+            operation_commitments: None,
+            overflow_sinks: Vec::new(),
+            posture: None,
+            hostiles: None,
+        }
+    }
+
+    /// Record the room's spawn queue, given in queue (priority) order.
+    pub fn set_spawn_queue<'a, I>(&mut self, descriptions: I)
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        self.spawn_queue_depth = 0;
+        self.next_spawns.clear();
+
+        for description in descriptions {
+            if self.next_spawns.len() < SIDEBAR_NEXT_SPAWNS {
+                self.next_spawns.push(description.to_string());
+            }
+            self.spawn_queue_depth += 1;
+        }
+    }
+
+    /// Count one creep doing `role`. Roles list in first-seen order until
+    /// [`lines`](Self::lines) sorts them.
+    pub fn add_creep(&mut self, role: &'static str) {
+        match self.creeps_by_role.iter_mut().find(|(r, _)| *r == role) {
+            Some((_, count)) => *count += 1,
+            None => self.creeps_by_role.push((role, 1)),
+        }
+    }
+
+    /// Header followed by one line per dashboard row. The hostile alert is
+    /// always the last line.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::with_capacity(8);
+
+        lines.push(match self.controller_level {
+            Some(level) => format!("{} · RCL {}", self.room, level),
+            None => self.room.to_string(),
+        });
+
+        let delta = self.energy_delta.map(|d| format!(" ({:+.1}/t)", d)).unwrap_or_default();
+        lines.push(format!("Energy: {}{}", self.stored_energy, delta));
+
+        let progress = match self.controller_progress {
+            Some((progress, Some(total))) if total > 0 => format!("{:.1}%", progress as f64 * 100.0 / total as f64),
+            Some(_) => "max".to_string(),
+            None => "—".to_string(),
+        };
+        let downgrade = self.ticks_to_downgrade.map(|t| format!(" · downgrade {}", t)).unwrap_or_default();
+        lines.push(format!("Controller: {}{}", progress, downgrade));
+
+        if self.next_spawns.is_empty() {
+            lines.push("Spawn queue: 0".to_string());
+        } else {
+            lines.push(format!("Spawn queue: {} — {}", self.spawn_queue_depth, self.next_spawns.join(", ")));
+        }
+
+        let mut roles = self.creeps_by_role.clone();
+        roles.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        let total: u32 = roles.iter().map(|(_, count)| count).sum();
+        let role_list: Vec<String> = roles.iter().map(|(role, count)| format!("{} {}", role, count)).collect();
+        if role_list.is_empty() {
+            lines.push("Creeps: 0".to_string());
+        } else {
+            lines.push(format!("Creeps: {} — {}", total, role_list.join(" ")));
+        }
+
+        if self.paused_missions > 0 {
+            lines.push(format!("Missions: {} ({} paused)", self.active_missions, self.paused_missions));
+        } else {
+            lines.push(format!("Missions: {}", self.active_missions));
+        }
+
+        if let Some((launches, energy, spawn_ticks)) = self.operation_commitments {
+            lines.push(format!("Ops: {} — {}e · {}t", launches, energy, spawn_ticks));
+        }
+
+        if !self.overflow_sinks.is_empty() {
+            lines.push(format!("Overflow: {}", self.overflow_sinks.join(", ")));
+        }
+
+        if let Some(posture) = self.posture.filter(|p| p.level != PostureLevel::Peaceful || p.pinned) {
+            let why = if posture.pinned { "pinned" } else { posture.reason };
+            lines.push(format!("Posture: {} ({})", posture.level.name(), why));
+        }
+
+        lines.push(match self.hostiles {
+            Some((level, count)) => format!("HOSTILE: {:?} ×{}", level, count),
+            None => "Hostiles: none".to_string(),
+        });
+
+        lines
+    }
+
+    /// Draw at `(x, y)` with the overlay's panel styles; returns the height
+    /// used so callers can stack panels below.
+    pub fn draw(&self, vis: &mut RoomVisualizer, x: f32, y: f32, width: f32, styles: &VisStyles) -> f32 {
+        let max_chars = ((width - 2.0 * PAD) / CHAR_WIDTH).floor().max(4.0) as usize;
+        let lines = self.lines();
+        let height = lines.len() as f32 * LINE_HEIGHT + 2.0 * PAD;
+
+        vis.rect(x, y, width, height, Some(styles.rect.clone()));
+        vis.line((x, y), (x, y + height), Some(styles.accent.clone()));
+        let header_y = y + PAD + LINE_HEIGHT;
+        vis.line((x + PAD, header_y), (x + width - PAD, header_y), Some(styles.sep.clone()));
+
+        let last = lines.len() - 1;
+        for (i, line) in lines.into_iter().enumerate() {
+            let style = if i == 0 {
+                styles.header.clone()
+            } else if i == last && self.hostiles.is_some() {
+                styles.text.clone().color(COLOR_ALERT)
+            } else {
+                styles.text.clone()
+            };
+            vis.text(
+                x + PAD,
+                y + PAD + (i as f32) * LINE_HEIGHT,
+                truncate_content(&line, max_chars),
+                Some(style),
+            );
+        }
+
+        height
+    }
+}
+
+// ─── Spawn queue ─────────────────────────────────────────────────────────────
+
+/// One line per queued request: priority, name, body cost against the energy
+/// it saw, ticks waiting and why it did not spawn. Requests without a status
+/// (new this tick, or spawned) show only priority, name and cost.
+pub fn spawn_queue_lines(entries: &[SpawnQueueEntry]) -> Vec<String> {
+    entries
+        .iter()
+        .map(|entry| match entry.status {
+            Some(ref status) => format!(
+                "{:.0} · {} · {}/{}e · {}t · {}",
+                entry.priority,
+                entry.description,
+                entry.cost,
+                status.energy_available,
+                status.ticks_waiting,
+                status.reason.label()
+            ),
+            None => format!("{:.0} · {} · {}e", entry.priority, entry.description, entry.cost),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sidebar() -> RoomSidebar {
+        RoomSidebar::new(RoomName::new("W1N1").unwrap())
+    }
+
+    #[test]
+    fn spawn_queue_keeps_depth_and_first_three() {
+        let mut sidebar = sidebar();
+        sidebar.set_spawn_queue(["miner", "hauler", "upgrader", "builder", "scout"]);
+
+        assert_eq!(sidebar.spawn_queue_depth, 5);
+        assert_eq!(sidebar.next_spawns, vec!["miner", "hauler", "upgrader"]);
+        assert_eq!(sidebar.lines()[3], "Spawn queue: 5 — miner, hauler, upgrader");
+    }
+
+    #[test]
+    fn lines_cover_every_row() {
+        let mut sidebar = sidebar();
+        sidebar.controller_level = Some(4);
+        sidebar.stored_energy = 12_500;
+        sidebar.energy_delta = Some(-2.5);
+        sidebar.controller_progress = Some((25, Some(200)));
+        sidebar.ticks_to_downgrade = Some(9_000);
+        sidebar.add_creep("Haul");
+        sidebar.add_creep("Harvest");
+        sidebar.add_creep("Haul");
+        sidebar.active_missions = 6;
+        sidebar.paused_missions = 1;
+        sidebar.operation_commitments = Some((2, 5_000, 630));
+        sidebar.overflow_sinks = vec!["fortify", "power"];
+        let mut posture = DefensePosture::default();
+        posture.update((PostureLevel::Lockdown, "breach"), None, 1);
+        sidebar.posture = Some(posture);
+        sidebar.hostiles = Some((ThreatLevel::PlayerRaid, 2));
+
+        assert_eq!(
+            sidebar.lines(),
+            vec![
+                "W1N1 · RCL 4",
+                "Energy: 12500 (-2.5/t)",
+                "Controller: 12.5% · downgrade 9000",
+                "Spawn queue: 0",
+                "Creeps: 3 — Haul 2 Harvest 1",
+                "Missions: 6 (1 paused)",
+                "Ops: 2 — 5000e · 630t",
+                "Overflow: fortify, power",
+                "Posture: lockdown (breach)",
+                "HOSTILE: PlayerRaid ×2",
+            ]
+        );
+    }
+
+    #[test]
+    fn max_level_controller_and_quiet_room() {
+        let mut sidebar = sidebar();
+        sidebar.controller_progress = Some((0, None));
+        sidebar.posture = Some(DefensePosture::default());
+
+        let lines = sidebar.lines();
+        assert_eq!(lines[0], "W1N1");
+        assert_eq!(lines[2], "Controller: max");
+        assert_eq!(lines[6], "Hostiles: none");
+    }
+
+    #[test]
+    fn spawn_queue_lines_show_wait_and_reason() {
+        use crate::spawnsystem::{SpawnBlockReason, SpawnRequestStatus};
+
+        let waiting = SpawnQueueEntry {
+            priority: 75.0,
+            cost: 550,
+            description: "Hauler".to_string(),
+            status: Some(SpawnRequestStatus {
+                description: "Hauler".to_string(),
+                occurrence: 0,
+                priority: 75.0,
+                cost: 550,
+                energy_available: 300,
+                ticks_waiting: 42,
+                reason: SpawnBlockReason::InsufficientEnergy,
+            }),
+        };
+        let fresh = SpawnQueueEntry {
+            priority: 25.0,
+            cost: 50,
+            description: "Scout".to_string(),
+            status: None,
+        };
+
+        assert_eq!(
+            spawn_queue_lines(&[waiting, fresh]),
+            vec!["75 · Hauler · 550/300e · 42t · energy", "25 · Scout · 50e"]
+        );
+    }
+}
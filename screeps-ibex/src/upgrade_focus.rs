@@ -0,0 +1,352 @@
+//! How long until the next controller, GCL and GPL level, and which room
+//! the empire's surplus energy should push there first.
+//!
+//! Every [`UPGRADE_FOCUS_INTERVAL`] ticks [`UpgradeFocusSystem`] projects
+//! each owned room's ticks to its next RCL from the upgrade rate in the
+//! `ResourceLedger`, the GCL from the same rates summed, and the GPL from
+//! its own sampled progress.
+//!
+//! While the empire holds at least [`UPGRADE_FOCUS_MIN_SURPLUS`] energy
+//! above its rooms' storage reserves, the room projected to reach a
+//! milestone level ([`MILESTONE_LEVELS`]) soonest becomes the focus, and
+//! stays it until it gets there. The focus room's terminal asks for
+//! [`UPGRADE_FOCUS_ENERGY`] more energy than its reserve, which the other
+//! terminals' surplus fills (`missions::terminal`), and its upgrade
+//! mission spawns a spare upgrader to burn it (`missions::upgrade`).
+//!
+//! The stats export carries the projections and the focus, and the map
+//! overlay labels each owned room with its projection.
+
+use crate::ledger::{LedgerCategory, ResourceLedger};
+use crate::missions::constants::get_desired_storage_amount;
+use crate::resource_overview::ResourceOverview;
+use crate::room::data::*;
+use log::*;
+use screeps::*;
+use specs::prelude::*;
+use std::collections::BTreeMap;
+
+/// Ticks between projections.
+pub const UPGRADE_FOCUS_INTERVAL: u32 = 100;
+
+/// Levels worth racing to: the first tower (3), storage (4), the terminal
+/// and first labs (6), the second spawn and more labs (7), and the full
+/// lab cluster with the power spawn (8).
+pub const MILESTONE_LEVELS: [u8; 5] = [3, 4, 6, 7, 8];
+
+/// Energy held above the rooms' storage reserves before any room is
+/// focused; below it there is no surplus to concentrate.
+pub const UPGRADE_FOCUS_MIN_SURPLUS: u32 = 50_000;
+
+/// Energy the focus room's terminal asks for on top of its reserve.
+pub const UPGRADE_FOCUS_ENERGY: u32 = 50_000;
+
+/// Weight of the newest GPL sample in its rate average.
+const GPL_RATE_WEIGHT: f64 = 0.3;
+
+/// One room's controller, and how fast it is being upgraded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ControllerProjection {
+    pub level: u8,
+    pub progress: u32,
+    pub progress_total: u32,
+    /// Controller progress per tick over the ledger's window.
+    pub rate: f64,
+}
+
+impl ControllerProjection {
+    /// Ticks until the next level at the current rate; `None` at max level
+    /// or with no upgrading.
+    pub fn ticks_to_next(&self) -> Option<u32> {
+        ticks_to(self.progress_total.saturating_sub(self.progress) as f64, self.rate)
+    }
+
+    /// Whether the next level is one of the [`MILESTONE_LEVELS`].
+    pub fn next_is_milestone(&self) -> bool {
+        self.progress_total > 0 && MILESTONE_LEVELS.contains(&(self.level + 1))
+    }
+}
+
+/// A global level (GCL or GPL) and its projection.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct LevelProjection {
+    pub level: u32,
+    pub rate: f64,
+    pub ticks_to_next: Option<u32>,
+}
+
+fn ticks_to(remaining: f64, rate: f64) -> Option<u32> {
+    if rate > 0.0 {
+        Some((remaining.max(0.0) / rate).ceil().min(u32::MAX as f64) as u32)
+    } else {
+        None
+    }
+}
+
+/// The room to focus: the current focus while it is still short of the
+/// milestone it was picked for, otherwise the milestone-bound room with the
+/// fewest ticks to go. `None` without enough `surplus` to concentrate.
+pub fn select_focus(
+    current: Option<(RoomName, u8)>,
+    projections: &BTreeMap<RoomName, ControllerProjection>,
+    surplus: u32,
+) -> Option<(RoomName, u8)> {
+    if surplus < UPGRADE_FOCUS_MIN_SURPLUS {
+        return None;
+    }
+
+    let still_short = current.filter(|(room, milestone)| {
+        projections
+            .get(room)
+            .map(|projection| projection.level + 1 == *milestone)
+            .unwrap_or(false)
+    });
+
+    if still_short.is_some() {
+        return still_short;
+    }
+
+    projections
+        .iter()
+        .filter(|(_, projection)| projection.next_is_milestone())
+        .filter_map(|(room, projection)| projection.ticks_to_next().map(|ticks| (ticks, *room, projection.level + 1)))
+        .min_by_key(|(ticks, _, _)| *ticks)
+        .map(|(_, room, milestone)| (room, milestone))
+}
+
+/// Runtime resource: the last projections and the focus room.
+#[derive(Default)]
+pub struct UpgradeFocus {
+    rooms: BTreeMap<RoomName, ControllerProjection>,
+    gcl: LevelProjection,
+    gpl: LevelProjection,
+    /// The focus room and the milestone level it was picked to reach.
+    focus: Option<(RoomName, u8)>,
+    /// Last GPL sample, (tick, level, progress).
+    gpl_sample: Option<(u32, u32, f64)>,
+    updated_at: Option<u32>,
+}
+
+impl UpgradeFocus {
+    pub fn needs_update(&self, now: u32) -> bool {
+        self.updated_at
+            .map(|updated_at| now.saturating_sub(updated_at) >= UPGRADE_FOCUS_INTERVAL)
+            .unwrap_or(true)
+    }
+
+    /// Fold a GPL reading into its rate. A level-up restarts the sample, as
+    /// progress counts from zero again.
+    pub fn sample_gpl(&mut self, now: u32, level: u32, progress: f64) {
+        if let Some((tick, last_level, last_progress)) = self.gpl_sample {
+            if level == last_level && now > tick && progress >= last_progress {
+                let rate = (progress - last_progress) / (now - tick) as f64;
+
+                self.gpl.rate = if self.gpl.rate > 0.0 {
+                    self.gpl.rate + (rate - self.gpl.rate) * GPL_RATE_WEIGHT
+                } else {
+                    rate
+                };
+            }
+        }
+
+        self.gpl_sample = Some((now, level, progress));
+    }
+
+    pub fn update(&mut self, rooms: BTreeMap<RoomName, ControllerProjection>, gcl: LevelProjection, surplus: u32, now: u32) {
+        let focus = select_focus(self.focus, &rooms, surplus);
+
+        if focus != self.focus {
+            match (focus, self.focus) {
+                (Some((room, milestone)), _) => {
+                    let ticks = rooms.get(&room).and_then(|projection| projection.ticks_to_next());
+
+                    info!(
+                        "Upgrade focus: {} toward RCL {} (~{} ticks, {} surplus energy)",
+                        room,
+                        milestone,
+                        ticks.map(|ticks| ticks.to_string()).unwrap_or_else(|| "?".to_string()),
+                        surplus
+                    );
+                }
+                (None, Some((room, _))) => info!("Upgrade focus: released {}", room),
+                (None, None) => {}
+            }
+        }
+
+        self.rooms = rooms;
+        self.gcl = gcl;
+        self.focus = focus;
+        self.updated_at = Some(now);
+    }
+
+    pub fn rooms(&self) -> &BTreeMap<RoomName, ControllerProjection> {
+        &self.rooms
+    }
+
+    pub fn room(&self, room: RoomName) -> Option<&ControllerProjection> {
+        self.rooms.get(&room)
+    }
+
+    pub fn gcl(&self) -> &LevelProjection {
+        &self.gcl
+    }
+
+    pub fn gpl(&self) -> &LevelProjection {
+        &self.gpl
+    }
+
+    pub fn focus(&self) -> Option<RoomName> {
+        self.focus.map(|(room, _)| room)
+    }
+
+    pub fn is_focus(&self, room: RoomName) -> bool {
+        self.focus() == Some(room)
+    }
+
+    /// Energy `room`'s terminal asks for beyond its reserve.
+    pub fn extra_energy(&self, room: RoomName) -> u32 {
+        if self.is_focus(room) {
+            UPGRADE_FOCUS_ENERGY
+        } else {
+            0
+        }
+    }
+}
+
+/// Projects controller, GCL and GPL progress on the focus interval and
+/// picks the focus room.
+pub struct UpgradeFocusSystem;
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+impl<'a> System<'a> for UpgradeFocusSystem {
+    type SystemData = (
+        ReadStorage<'a, RoomData>,
+        Read<'a, ResourceLedger>,
+        Read<'a, ResourceOverview>,
+        Write<'a, UpgradeFocus>,
+    );
+
+    fn run(&mut self, (room_data, ledger, overview, mut upgrade_focus): Self::SystemData) {
+        let now = game::time();
+
+        if !upgrade_focus.needs_update(now) {
+            return;
+        }
+
+        let mut rooms = BTreeMap::new();
+
+        for room in room_data.join() {
+            if !room.get_dynamic_visibility_data().map(|d| d.owner().mine()).unwrap_or(false) {
+                continue;
+            }
+
+            let Some(structures) = room.get_structures() else {
+                continue;
+            };
+
+            let Some(controller) = structures.controllers().iter().find(|c| c.my()) else {
+                continue;
+            };
+
+            let rate = ledger
+                .averages(room.name)
+                .map(|averages| averages.get(LedgerCategory::Upgrade))
+                .unwrap_or(0.0);
+
+            rooms.insert(
+                room.name,
+                ControllerProjection {
+                    level: controller.level(),
+                    progress: controller.progress().unwrap_or(0),
+                    progress_total: controller.progress_total().unwrap_or(0),
+                    rate,
+                },
+            );
+        }
+
+        // Every point of upgrading is a point of GCL.
+        let gcl_rate: f64 = rooms.values().map(|projection| projection.rate).sum();
+        let gcl = LevelProjection {
+            level: game::gcl::level(),
+            rate: gcl_rate,
+            ticks_to_next: ticks_to(game::gcl::progress_total() - game::gcl::progress(), gcl_rate),
+        };
+
+        upgrade_focus.sample_gpl(now, game::gpl::level(), game::gpl::progress());
+        upgrade_focus.gpl.level = game::gpl::level();
+        upgrade_focus.gpl.ticks_to_next = ticks_to(game::gpl::progress_total() - game::gpl::progress(), upgrade_focus.gpl.rate);
+
+        let reserve = get_desired_storage_amount(ResourceType::Energy);
+        let surplus = overview
+            .rooms()
+            .values()
+            .map(|held| held.get(&ResourceType::Energy).copied().unwrap_or(0).saturating_sub(reserve))
+            .sum();
+
+        upgrade_focus.update(rooms, gcl, surplus, now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn room(name: &str) -> RoomName {
+        name.parse().expect("valid room name")
+    }
+
+    fn projection(level: u8, progress: u32, progress_total: u32, rate: f64) -> ControllerProjection {
+        ControllerProjection {
+            level,
+            progress,
+            progress_total,
+            rate,
+        }
+    }
+
+    #[test]
+    fn projection_counts_the_remaining_progress_at_the_current_rate() {
+        assert_eq!(projection(5, 1_000_000, 1_215_000, 20.0).ticks_to_next(), Some(10_750));
+        assert_eq!(projection(5, 1_000_000, 1_215_000, 0.0).ticks_to_next(), None);
+        assert!(projection(5, 0, 1_215_000, 20.0).next_is_milestone());
+        assert!(!projection(4, 0, 405_000, 20.0).next_is_milestone());
+        assert!(!projection(8, 0, 0, 15.0).next_is_milestone());
+    }
+
+    #[test]
+    fn focus_goes_to_the_nearest_milestone_and_holds_until_it_is_reached() {
+        let mut rooms = BTreeMap::from([
+            (room("W1N1"), projection(5, 1_000_000, 1_215_000, 20.0)),
+            (room("W2N1"), projection(3, 100_000, 135_000, 10.0)),
+            (room("W3N1"), projection(4, 400_000, 405_000, 50.0)),
+        ]);
+
+        assert_eq!(select_focus(None, &rooms, UPGRADE_FOCUS_MIN_SURPLUS - 1), None);
+
+        // W3N1 is closest, but RCL 5 isn't a milestone.
+        let focus = select_focus(None, &rooms, UPGRADE_FOCUS_MIN_SURPLUS);
+        assert_eq!(focus, Some((room("W2N1"), 4)));
+
+        // A closer room doesn't take the focus before W2N1 gets there.
+        rooms.insert(room("W1N1"), projection(5, 1_214_000, 1_215_000, 20.0));
+        assert_eq!(select_focus(focus, &rooms, UPGRADE_FOCUS_MIN_SURPLUS), focus);
+
+        rooms.insert(room("W2N1"), projection(4, 0, 405_000, 10.0));
+        assert_eq!(select_focus(focus, &rooms, UPGRADE_FOCUS_MIN_SURPLUS), Some((room("W1N1"), 6)));
+    }
+
+    #[test]
+    fn gpl_rate_averages_samples_and_restarts_on_a_level_up() {
+        let mut upgrade_focus = UpgradeFocus::default();
+
+        upgrade_focus.sample_gpl(0, 1, 0.0);
+        upgrade_focus.sample_gpl(100, 1, 1_000.0);
+        assert_eq!(upgrade_focus.gpl().rate, 10.0);
+
+        upgrade_focus.sample_gpl(200, 1, 3_000.0);
+        assert_eq!(upgrade_focus.gpl().rate, 13.0);
+
+        upgrade_focus.sample_gpl(300, 2, 500.0);
+        assert_eq!(upgrade_focus.gpl().rate, 13.0);
+    }
+}
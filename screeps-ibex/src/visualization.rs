@@ -11,15 +11,15 @@ use crate::jobs::data::JobData;
 use crate::missions::data::MissionData;
 use crate::operations::data::OperationData;
 use crate::room::data::RoomData;
-use crate::spawnsystem::SpawnQueue;
-use crate::visualize::Visualizer;
+use crate::spawnsystem::{request_occurrences, SpawnDiagnostics, SpawnQueue, SpawnRequestStatus};
+use crate::visualize::{VisualLayer, Visualizer};
 use screeps::game;
 use screeps::traits::SharedCreepProperties;
 use screeps::{LineDrawStyle, LineStyle, PolyStyle, RectStyle, ResourceType, RoomName, TextAlign, TextStyle};
 use serde::{Deserialize, Serialize};
 use specs::prelude::*;
 use specs::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 // ─── Structured summary content ──────────────────────────────────────────────
 
@@ -36,6 +36,45 @@ pub enum SummaryContent {
 }
 
 impl SummaryContent {
+    /// Prefix the first line with `[badge]`.
+    pub fn with_badge(self, badge: &str) -> SummaryContent {
+        match self {
+            SummaryContent::Text(s) => SummaryContent::Text(format!("[{}] {}", badge, s)),
+            SummaryContent::Lines { header, items } => SummaryContent::Lines {
+                header: format!("[{}] {}", badge, header),
+                items,
+            },
+            SummaryContent::Tree { label, children } => SummaryContent::Tree {
+                label: format!("[{}] {}", badge, label),
+                children,
+            },
+        }
+    }
+
+    /// Cut the content to `depth` levels (a line is one level, a header and
+    /// its items two); a cut level is collapsed into a `(+n)` count on its
+    /// parent. 0 keeps every level.
+    pub fn limit_depth(self, depth: u8) -> SummaryContent {
+        if depth == 0 {
+            return self;
+        }
+
+        match self {
+            SummaryContent::Text(s) => SummaryContent::Text(s),
+            SummaryContent::Lines { header, items } if depth == 1 && !items.is_empty() => {
+                SummaryContent::Text(format!("{} (+{})", header, items.len()))
+            }
+            SummaryContent::Tree { label, children } if depth == 1 && !children.is_empty() => {
+                SummaryContent::Text(format!("{} (+{})", label, children.len()))
+            }
+            SummaryContent::Tree { label, children } => SummaryContent::Tree {
+                label,
+                children: children.into_iter().map(|child| child.limit_depth(depth - 1)).collect(),
+            },
+            lines => lines,
+        }
+    }
+
     /// Flatten content into lines for panel rendering.
     pub fn to_lines(&self) -> Vec<String> {
         match self {
@@ -62,6 +101,60 @@ impl SummaryContent {
     }
 }
 
+/// What a mission is for, as far as the Missions panel is concerned. Each
+/// category can be hidden per room (`visualize.economy_missions` etc.).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissionCategory {
+    /// Mining, hauling, upgrading, trade, labs and expansion.
+    Economy,
+    /// Defense, intel and attacks.
+    Military,
+    /// Building and walls.
+    Construction,
+}
+
+impl MissionCategory {
+    pub const ALL: [MissionCategory; 3] = [MissionCategory::Economy, MissionCategory::Military, MissionCategory::Construction];
+
+    /// The name in the console and in the feature flag (`<name>_missions`).
+    pub fn name(self) -> &'static str {
+        match self {
+            MissionCategory::Economy => "economy",
+            MissionCategory::Military => "military",
+            MissionCategory::Construction => "construction",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<MissionCategory> {
+        Self::ALL.into_iter().find(|category| category.name() == name)
+    }
+
+    pub fn shown(self, visualize: &crate::features::VisualizeFeatures) -> bool {
+        match self {
+            MissionCategory::Economy => visualize.economy_missions,
+            MissionCategory::Military => visualize.military_missions,
+            MissionCategory::Construction => visualize.construction_missions,
+        }
+    }
+}
+
+/// The rooms on this tick's page: `per_page` of `rooms` (by name), the next
+/// page every `page_ticks`. `None` when every room fits, or paging is off.
+pub fn room_page(mut rooms: Vec<RoomName>, per_page: u32, page_ticks: u32, tick: u32) -> Option<HashSet<RoomName>> {
+    let per_page = per_page as usize;
+
+    if per_page == 0 || rooms.len() <= per_page {
+        return None;
+    }
+
+    rooms.sort_by_cached_key(|room| room.to_string());
+
+    let pages = rooms.len().div_ceil(per_page);
+    let page = (tick / page_ticks.max(1)) as usize % pages;
+
+    Some(rooms.into_iter().skip(page * per_page).take(per_page).collect())
+}
+
 // ─── Per-entity summary components (not serialized) ──────────────────────────
 //
 // Each entity type that appears in the overlay carries its own summary component.
@@ -80,6 +173,7 @@ pub struct OperationSummaryComponent {
 #[storage(DenseVecStorage)]
 pub struct MissionSummaryComponent {
     pub content: SummaryContent,
+    pub paused: bool,
 }
 
 /// Summary of a creep's job for the per-room jobs list.
@@ -87,6 +181,8 @@ pub struct MissionSummaryComponent {
 #[storage(DenseVecStorage)]
 pub struct JobSummaryComponent {
     pub creep_name: String,
+    /// The job's type name, used as the creep's role.
+    pub role: &'static str,
     pub content: SummaryContent,
 }
 
@@ -117,6 +213,7 @@ pub struct SummarizeOperationSystemData<'a> {
     room_data: ReadStorage<'a, RoomData>,
     op_summary: WriteStorage<'a, OperationSummaryComponent>,
     features: Read<'a, crate::features::Features>,
+    objective_queue: Read<'a, crate::military::objective_queue::CombatObjectiveQueue>,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -132,6 +229,7 @@ impl<'a> System<'a> for SummarizeOperationSystem {
             mission_data: &data.mission_data,
             room_data: &data.room_data,
             features: *data.features,
+            objective_queue: &data.objective_queue,
         };
 
         for (entity, op_data) in (&data.entities, &data.operation_data).join() {
@@ -149,7 +247,10 @@ pub struct SummarizeMissionSystemData<'a> {
     viz_gate: Option<Read<'a, VisualizationData>>,
     entities: Entities<'a>,
     mission_data: ReadStorage<'a, MissionData>,
+    room_data: ReadStorage<'a, RoomData>,
     mission_summary: WriteStorage<'a, MissionSummaryComponent>,
+    features: Read<'a, crate::features::Features>,
+    feature_overrides: Read<'a, crate::features::FeatureOverrides>,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -157,13 +258,31 @@ impl<'a> System<'a> for SummarizeMissionSystem {
     type SystemData = SummarizeMissionSystemData<'a>;
 
     fn run(&mut self, mut data: Self::SystemData) {
-        if data.viz_gate.is_none() {
+        let Some(viz) = data.viz_gate.as_ref() else {
             return;
-        }
+        };
 
         for (entity, mission_data) in (&data.entities, &data.mission_data).join() {
-            let content = mission_data.summarize();
-            let _ = data.mission_summary.insert(entity, MissionSummaryComponent { content });
+            let room = mission_data
+                .as_mission()
+                .get_room()
+                .and_then(|room_entity| data.room_data.get(room_entity))
+                .map(|room_data| room_data.name);
+
+            let visualize = match room {
+                Some(room) => data.features.for_room(&data.feature_overrides, room).visualize,
+                None => data.features.visualize,
+            };
+
+            // Filtered-out missions aren't summarized, and lose last tick's summary.
+            if !room.map(|room| viz.is_drawn(room)).unwrap_or(true) || !mission_data.category().shown(&visualize) {
+                data.mission_summary.remove(entity);
+                continue;
+            }
+
+            let content = mission_data.summarize().limit_depth(visualize.max_depth);
+            let paused = mission_data.as_mission().is_paused();
+            let _ = data.mission_summary.insert(entity, MissionSummaryComponent { content, paused });
         }
     }
 }
@@ -193,7 +312,14 @@ impl<'a> System<'a> for SummarizeJobSystem {
             let creep_name = creep_owner.owner.resolve().map(|c| c.name()).unwrap_or_default();
 
             let content = job_data.summarize();
-            let _ = data.job_summary.insert(entity, JobSummaryComponent { creep_name, content });
+            let _ = data.job_summary.insert(
+                entity,
+                JobSummaryComponent {
+                    creep_name,
+                    role: job_data.type_name(),
+                    content,
+                },
+            );
         }
     }
 }
@@ -302,12 +428,14 @@ pub struct OperationSummary {
 #[derive(Debug, Clone)]
 pub struct MissionSummary {
     pub content: SummaryContent,
+    pub paused: bool,
 }
 
 /// One job entry for a room (creep name + content).
 #[derive(Debug, Clone)]
 pub struct JobSummary {
     pub creep_name: String,
+    pub role: &'static str,
     pub content: SummaryContent,
 }
 
@@ -329,6 +457,8 @@ pub struct SpawnQueueEntry {
     pub priority: f32,
     pub cost: u32,
     pub description: String,
+    /// How the request fared last tick; `None` if it spawned or is new.
+    pub status: Option<SpawnRequestStatus>,
 }
 
 /// Per-room visualization data (missions, jobs, spawn queue, room info, stats history, transfer stats).
@@ -342,6 +472,10 @@ pub struct RoomVisualizationData {
     pub stats_history: Option<Vec<crate::stats_history::RoomStatsSnapshot>>,
     /// Current-tick transfer queue snapshot for this room.
     pub transfer_stats: Option<crate::transfer::transfersystem::TransferRoomSnapshot>,
+    /// Rolling energy income/expense averages from the `ResourceLedger`.
+    pub energy_ledger: Option<crate::ledger::LedgerAverages>,
+    /// Dashboard for owned rooms with `visualize.sidebar` on.
+    pub sidebar: Option<crate::ui::RoomSidebar>,
 }
 
 impl RoomVisualizationData {
@@ -397,6 +531,11 @@ pub struct ClaimVisualizationData {
 pub struct MapVisualizationData {
     /// Claim system debug visuals.
     pub claim: ClaimVisualizationData,
+    /// Per-mission state and health, recorded by the mission systems while
+    /// `visualize.mission_map` is on.
+    pub mission_states: HashMap<Entity, crate::mission_map::MissionMapState>,
+    /// Per-room operation -> mission hierarchy built from `mission_states`.
+    pub missions: Vec<(RoomName, Vec<crate::mission_map::MissionMapGroup>)>,
 }
 
 /// Global (non-room-specific) visualization data for the overlay panels.
@@ -405,6 +544,12 @@ pub struct MapVisualizationData {
 pub struct GlobalVisualizationData {
     pub operations: Vec<OperationSummary>,
     pub visibility_queue: Vec<VisibilityQueueSummaryEntry>,
+    /// Previous tick's most expensive systems/missions/jobs (label, cpu);
+    /// empty unless `features.cpu_accounting` is on and visualized.
+    pub cpu_breakdown: Vec<(String, f64)>,
+    /// Feature flags that differ from their defaults, then per-room
+    /// overrides (`features::non_default_flags`).
+    pub non_default_flags: Vec<String>,
 }
 
 /// All visualization summary data for one tick.
@@ -416,6 +561,9 @@ pub struct VisualizationData {
     pub map: MapVisualizationData,
     pub global: GlobalVisualizationData,
     pub rooms: HashMap<RoomName, RoomVisualizationData>,
+    /// The rooms drawn this tick when `visualize.page_rooms` pages them;
+    /// `None` draws every room.
+    pub page: Option<HashSet<RoomName>>,
 }
 
 impl VisualizationData {
@@ -423,6 +571,11 @@ impl VisualizationData {
         Self::default()
     }
 
+    /// Whether `room` is on this tick's page.
+    pub fn is_drawn(&self, room: RoomName) -> bool {
+        self.page.as_ref().map(|page| page.contains(&room)).unwrap_or(true)
+    }
+
     pub fn get_or_create_room(&mut self, room: RoomName) -> &mut RoomVisualizationData {
         self.rooms.entry(room).or_default()
     }
@@ -431,17 +584,33 @@ impl VisualizationData {
 // ─── Clear visualization system ──────────────────────────────────────────────
 
 /// Resets all visualization data at the start of the tick so that systems
-/// can populate it fresh. Runs early in the main pass, before operations
-/// and summarize systems.
+/// can populate it fresh, and picks the page of rooms drawn this tick. Runs
+/// early in the main pass, before operations and summarize systems.
 pub struct ClearVisualizationSystem;
 
+#[derive(SystemData)]
+pub struct ClearVisualizationSystemData<'a> {
+    visualization_data: Option<Write<'a, VisualizationData>>,
+    room_data: ReadStorage<'a, RoomData>,
+    cpu_history: Option<Read<'a, CpuHistory>>,
+    features: Read<'a, crate::features::Features>,
+}
+
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
 impl<'a> System<'a> for ClearVisualizationSystem {
-    type SystemData = Option<Write<'a, VisualizationData>>;
+    type SystemData = ClearVisualizationSystemData<'a>;
 
     fn run(&mut self, mut data: Self::SystemData) {
-        if let Some(ref mut viz) = data {
+        if let Some(ref mut viz) = data.visualization_data {
             **viz = VisualizationData::default();
+
+            // The tick after the last CPU sample: the only game time the
+            // visualization path reads (see `CpuTrackingSystem`).
+            let tick = data.cpu_history.as_ref().map(|h| h.tick + 1).unwrap_or(0);
+            let visualize = &data.features.visualize;
+            let rooms = data.room_data.join().map(|room_data| room_data.name).collect();
+
+            viz.page = room_page(rooms, visualize.page_rooms, visualize.page_ticks, tick);
         }
     }
 }
@@ -455,14 +624,25 @@ pub struct AggregateSummarySystemData<'a> {
     room_data: ReadStorage<'a, RoomData>,
     op_summary: ReadStorage<'a, OperationSummaryComponent>,
     mission_summary: ReadStorage<'a, MissionSummaryComponent>,
+    operations: ReadStorage<'a, OperationData>,
+    missions: ReadStorage<'a, MissionData>,
     job_summary: ReadStorage<'a, JobSummaryComponent>,
     creep_owner: ReadStorage<'a, CreepOwner>,
     vis_summary: ReadStorage<'a, RoomVisibilitySummaryComponent>,
     spawn_queue: Read<'a, SpawnQueue>,
+    spawn_diagnostics: Read<'a, SpawnDiagnostics>,
     stats_history: Option<Read<'a, crate::stats_history::StatsHistoryData>>,
     transfer_stats: Option<Read<'a, crate::transfer::transfersystem::TransferStatsSnapshot>>,
     visibility_snapshot: Read<'a, crate::room::visibilitysystem::VisibilityQueueSnapshot>,
+    ledger: Read<'a, crate::ledger::ResourceLedger>,
+    economy: Read<'a, crate::military::economy::EconomySnapshot>,
+    operation_budget: Read<'a, crate::operations::budget::OperationBudget>,
+    overflow_sinks: Read<'a, crate::missions::overflow::OverflowSinks>,
+    threat_data: ReadStorage<'a, crate::military::threatmap::RoomThreatData>,
+    defense_postures: ReadStorage<'a, crate::military::posture::DefensePosture>,
+    cpu_accounting: Read<'a, crate::cpu_accounting::CpuAccounting>,
     features: Read<'a, crate::features::Features>,
+    feature_overrides: Read<'a, crate::features::FeatureOverrides>,
 }
 
 pub struct AggregateSummarySystem;
@@ -492,11 +672,13 @@ impl<'a> System<'a> for AggregateSummarySystem {
         for (room_entity, requests) in data.spawn_queue.iter_requests() {
             if let Some(room) = data.room_data.get(*room_entity) {
                 let room_viz = viz.get_or_create_room(room.name);
-                for req in requests.iter() {
+                let occurrences = request_occurrences(requests.iter().map(|r| r.description()));
+                for (req, occurrence) in requests.iter().zip(occurrences) {
                     room_viz.spawn_queue.push(SpawnQueueEntry {
                         priority: req.priority(),
                         cost: req.cost(),
                         description: req.description().to_string(),
+                        status: data.spawn_diagnostics.status(*room_entity, req.description(), occurrence).cloned(),
                     });
                 }
             }
@@ -509,6 +691,7 @@ impl<'a> System<'a> for AggregateSummarySystem {
                 if let Some(ms) = data.mission_summary.get(*mission_entity) {
                     room_viz.missions.push(MissionSummary {
                         content: ms.content.clone(),
+                        paused: ms.paused,
                     });
                 }
             }
@@ -521,6 +704,7 @@ impl<'a> System<'a> for AggregateSummarySystem {
                     let room_viz = viz.get_or_create_room(room.name());
                     room_viz.jobs.push(JobSummary {
                         creep_name: job_sum.creep_name.clone(),
+                        role: job_sum.role,
                         content: job_sum.content.clone(),
                     });
                 }
@@ -559,6 +743,74 @@ impl<'a> System<'a> for AggregateSummarySystem {
             }
         }
 
+        // Energy ledger (per room) — from ResourceLedger resource
+        for (room_name, averages) in data.ledger.rooms() {
+            let room_viz = viz.get_or_create_room(*room_name);
+            room_viz.energy_ledger = Some(averages);
+        }
+
+        // Room sidebar (owned rooms) — built last from the per-room data
+        // gathered above plus the economy snapshot and threat data.
+        for (room_entity, room_data) in (&data.entities, &data.room_data).join() {
+            let owned = room_data.get_dynamic_visibility_data().map(|d| d.owner().mine()).unwrap_or(false);
+            if !owned || !data.features.for_room(&data.feature_overrides, room_data.name).visualize.sidebar {
+                continue;
+            }
+
+            let room_viz = viz.get_or_create_room(room_data.name);
+            let mut sidebar = crate::ui::RoomSidebar::new(room_data.name);
+
+            if let Some(economy) = data.economy.room(&room_entity) {
+                sidebar.stored_energy = economy.stored_energy;
+            }
+            sidebar.energy_delta = room_viz.energy_ledger.map(|l| l.net());
+
+            if let Some(dynamic) = room_data.get_dynamic_visibility_data() {
+                sidebar.controller_level = dynamic.controller_level();
+                sidebar.ticks_to_downgrade = dynamic.controller_ticks_to_downgrade();
+            }
+            if let Some(structures) = room_data.get_structures() {
+                sidebar.controller_progress = structures
+                    .controllers()
+                    .first()
+                    .map(|c| (c.progress().unwrap_or(0), c.progress_total()));
+            }
+
+            sidebar.set_spawn_queue(room_viz.spawn_queue.iter().map(|s| s.description.as_str()));
+            for job in room_viz.jobs.iter() {
+                sidebar.add_creep(job.role);
+            }
+            sidebar.paused_missions = room_viz.missions.iter().filter(|m| m.paused).count();
+            sidebar.active_missions = room_viz.missions.len() - sidebar.paused_missions;
+
+            let (committed, launches) = data.operation_budget.committed(room_entity);
+            sidebar.operation_commitments = (launches > 0).then_some((launches, committed.energy, committed.spawn_ticks));
+            sidebar.overflow_sinks = data.overflow_sinks.sinks(room_entity).iter().map(|sink| sink.label()).collect();
+            sidebar.posture = data.defense_postures.get(room_entity).copied();
+
+            sidebar.hostiles = data
+                .threat_data
+                .get(room_entity)
+                .filter(|t| t.warrants_attention())
+                .map(|t| (t.threat_level, t.hostile_creeps.len()));
+
+            room_viz.sidebar = Some(sidebar);
+        }
+
+        // Mission hierarchy (map) — from the states the mission systems recorded.
+        if data.features.visualize.mission_map {
+            for (_entity, room_data) in (&data.entities, &data.room_data).join() {
+                let room_missions: Vec<Entity> = room_data.get_missions().iter().copied().collect();
+                if room_missions.is_empty() {
+                    continue;
+                }
+
+                let groups =
+                    crate::mission_map::build_room_groups(&room_missions, &data.missions, &data.operations, &viz.map.mission_states);
+                viz.map.missions.push((room_data.name, groups));
+            }
+        }
+
         // Visibility queue (global) — from VisibilityQueueSnapshot resource.
         // VisualizationData presence already implies features.visualize.on;
         // only the sub-feature flag needs checking.
@@ -572,6 +824,17 @@ impl<'a> System<'a> for AggregateSummarySystem {
                 });
             }
         }
+
+        // CPU breakdown (global) — previous tick's top entries from CpuAccounting.
+        if data.features.cpu_accounting.visualize(data.features.visualize.on) {
+            const MAX_CPU_BREAKDOWN_ENTRIES: usize = 10;
+            if let Some(breakdown) = data.cpu_accounting.last() {
+                viz.global.cpu_breakdown = breakdown.top(MAX_CPU_BREAKDOWN_ENTRIES);
+            }
+        }
+
+        // Toggled feature flags (global) — so a switched-off flag is obvious.
+        viz.global.non_default_flags = crate::features::non_default_flags(&data.features, &data.feature_overrides);
     }
 }
 
@@ -585,16 +848,16 @@ impl<'a> System<'a> for AggregateSummarySystem {
 // - Bottom strip (fixed size): CPU histogram (global), then room resources / transfer / trade (per-room, placeholders for now).
 
 /// Approximate character width in room units (no text measurement API).
-const CHAR_WIDTH: f32 = 0.30;
-const LINE_HEIGHT: f32 = 1.05;
-const PAD: f32 = 0.45;
-const FONT_SIZE: f32 = 0.55;
+pub(crate) const CHAR_WIDTH: f32 = 0.30;
+pub(crate) const LINE_HEIGHT: f32 = 1.05;
+pub(crate) const PAD: f32 = 0.45;
+pub(crate) const FONT_SIZE: f32 = 0.55;
 
 /// Max characters per line to keep layout bounded.
 const MAX_LINE_CHARS: usize = 34;
 
 /// Truncate content per line to max chars (Screeps doesn't reliably wrap or honour \n in one text call).
-fn truncate_content(s: &str, max_chars: usize) -> String {
+pub(crate) fn truncate_content(s: &str, max_chars: usize) -> String {
     let lines: Vec<String> = s
         .lines()
         .map(|l| {
@@ -704,13 +967,13 @@ fn grid_line_style() -> LineStyle {
 }
 
 /// Bundled visual styles used by the global and room drawing functions.
-struct VisStyles {
-    rect: RectStyle,
-    header: TextStyle,
-    text: TextStyle,
-    accent: LineStyle,
-    sep: LineStyle,
-    grid: LineStyle,
+pub(crate) struct VisStyles {
+    pub(crate) rect: RectStyle,
+    pub(crate) header: TextStyle,
+    pub(crate) text: TextStyle,
+    pub(crate) accent: LineStyle,
+    pub(crate) sep: LineStyle,
+    pub(crate) grid: LineStyle,
 }
 
 impl VisStyles {
@@ -769,12 +1032,19 @@ fn left_column_max_chars(width: f32) -> usize {
     (width / CHAR_WIDTH - 2.0 * PAD / CHAR_WIDTH).floor().max(4.0) as usize
 }
 
-/// Left column (room): Room state, Missions, Jobs, Spawn stacked vertically. All panels use the same fixed width for alignment.
-fn layout_room_left_panels(right_column_left_x: f32, room_content: Option<&str>, missions: &str, jobs: &str, spawn: &str) -> Vec<Panel> {
+/// Left column (room): Room state, Missions, Jobs, Spawn stacked vertically from `top_y`. All panels use the same fixed width for alignment.
+fn layout_room_left_panels(
+    right_column_left_x: f32,
+    top_y: f32,
+    room_content: Option<&str>,
+    missions: &str,
+    jobs: &str,
+    spawn: &str,
+) -> Vec<Panel> {
     let w = left_column_width(right_column_left_x);
     let max_chars = left_column_max_chars(w).min(MAX_LINE_CHARS);
     let mut panels = Vec::with_capacity(4);
-    let mut y = TOP_Y;
+    let mut y = top_y;
 
     if let Some(rc) = room_content {
         let mut room = Panel::from_content(rc, max_chars);
@@ -809,12 +1079,12 @@ fn right_column_left_x(ops_panel: &Panel) -> f32 {
     RIGHT_EDGE - RIGHT_MARGIN - ops_panel.width()
 }
 
-/// Draw global layer: right column (Ops + optional Visibility Queue) + top-center CPU histogram.
+/// Draw global layer: right column (Ops + optional Visibility Queue / CPU breakdown) + top-center CPU histogram.
 /// Same in every room; draw to global() and to each room_vis.
 fn draw_global_layer(
     vis: &mut crate::visualize::RoomVisualizer,
     ops_panel: &Panel,
-    visibility_panel: Option<&Panel>,
+    right_panels: &[Panel],
     styles: &VisStyles,
     cpu_samples: Option<&[f32]>,
     cpu_limit: f32,
@@ -845,30 +1115,21 @@ fn draw_global_layer(
         );
     }
 
-    // Visibility Queue panel (below Ops, same right column) — only when feature is on.
-    if let Some(visibility_panel) = visibility_panel {
-        let vw = visibility_panel.width();
-        let vh = visibility_panel.height();
-        vis.rect(visibility_panel.x, visibility_panel.y, vw, vh, Some(styles.rect.clone()));
+    // Optional panels stacked below Ops in the same right column — only when their feature is on.
+    for panel in right_panels {
+        let vw = panel.width();
+        let vh = panel.height();
+        vis.rect(panel.x, panel.y, vw, vh, Some(styles.rect.clone()));
+        vis.line((panel.x + vw, panel.y), (panel.x + vw, panel.y + vh), Some(styles.accent.clone()));
+        let vp_header_y = panel.y + PAD + LINE_HEIGHT;
         vis.line(
-            (visibility_panel.x + vw, visibility_panel.y),
-            (visibility_panel.x + vw, visibility_panel.y + vh),
-            Some(styles.accent.clone()),
-        );
-        let vp_header_y = visibility_panel.y + PAD + LINE_HEIGHT;
-        vis.line(
-            (visibility_panel.x + PAD, vp_header_y),
-            (visibility_panel.x + vw - PAD, vp_header_y),
+            (panel.x + PAD, vp_header_y),
+            (panel.x + vw - PAD, vp_header_y),
             Some(styles.sep.clone()),
         );
-        for (i, line) in visibility_panel.lines.iter().enumerate() {
+        for (i, line) in panel.lines.iter().enumerate() {
             let style = if i == 0 { styles.header.clone() } else { styles.text.clone() };
-            vis.text(
-                visibility_panel.x + PAD,
-                visibility_panel.y + PAD + (i as f32) * LINE_HEIGHT,
-                line.clone(),
-                Some(style),
-            );
+            vis.text(panel.x + PAD, panel.y + PAD + (i as f32) * LINE_HEIGHT, line.clone(), Some(style));
         }
     }
 
@@ -1229,6 +1490,7 @@ pub struct RenderSystemData<'a> {
     visualizer: Option<Write<'a, Visualizer>>,
     cpu_history: Option<Read<'a, CpuHistory>>,
     features: Read<'a, crate::features::Features>,
+    upgrade_focus: Read<'a, crate::upgrade_focus::UpgradeFocus>,
 }
 
 pub struct RenderSystem;
@@ -1243,10 +1505,16 @@ impl<'a> System<'a> for RenderSystem {
             return;
         };
 
-        // Map visuals use the global MapVisual API (string-based to avoid
-        // serde_wasm_bindgen corruption). Data is only populated when the
-        // relevant sub-feature flags are on.
-        draw_claim_map_visuals(&viz.map.claim);
+        // Map visuals go through the map visualizer (flushed by
+        // ApplyVisualsSystem). Data is only populated when the relevant
+        // sub-feature flags are on.
+        draw_claim_map_visuals(visualizer.map(), &viz.map.claim);
+        draw_upgrade_focus_map_visuals(visualizer.map(), &data.upgrade_focus);
+
+        for (room_name, groups) in viz.map.missions.iter() {
+            let lines = crate::mission_map::layout_room(groups, crate::mission_map::MAX_MAP_LINES);
+            crate::mission_map::draw_room(visualizer.map(), *room_name, &lines);
+        }
 
         let styles = VisStyles::new();
 
@@ -1266,8 +1534,12 @@ impl<'a> System<'a> for RenderSystem {
         // Build visibility queue panel (below ops panel, same right column) only
         // when the visibility feature flag is on. When on but the queue is empty,
         // still show the panel with 0 entries.
+        let ops_max_chars = (OPS_PANEL_MAX_WIDTH / CHAR_WIDTH - 2.0 * PAD / CHAR_WIDTH).floor().max(4.0) as usize;
+        let mut right_panels: Vec<Panel> = Vec::new();
+        let mut right_y = global_ops_panel.y + global_ops_panel.height() + GAP;
+
         let show_visibility_panel = data.features.visibility.visualize;
-        let visibility_panel = if show_visibility_panel {
+        if show_visibility_panel {
             let vis_lines: Vec<String> = viz
                 .global
                 .visibility_queue
@@ -1275,38 +1547,68 @@ impl<'a> System<'a> for RenderSystem {
                 .map(|e| format!("{} {:.0} [{}]", e.room_name, e.priority, e.types_label))
                 .collect();
             let vis_content = format!("Visibility Queue ({})\n{}", viz.global.visibility_queue.len(), vis_lines.join("\n"));
-            let ops_max_chars = (OPS_PANEL_MAX_WIDTH / CHAR_WIDTH - 2.0 * PAD / CHAR_WIDTH).floor().max(4.0) as usize;
             let mut p = Panel::from_content(&vis_content, ops_max_chars.min(MAX_LINE_CHARS));
             p.x = RIGHT_EDGE - RIGHT_MARGIN - p.width();
-            p.y = global_ops_panel.y + global_ops_panel.height() + GAP;
-            Some(p)
-        } else {
-            None
-        };
+            p.y = right_y;
+            right_y += p.height() + GAP;
+            right_panels.push(p);
+        }
+
+        // CPU breakdown panel (below visibility queue) — previous tick's top
+        // entries. Empty until the first fully measured tick is published.
+        if data.features.cpu_accounting.visualize(data.features.visualize.on) {
+            let cpu_lines: Vec<String> = viz
+                .global
+                .cpu_breakdown
+                .iter()
+                .map(|(label, cpu)| format!("{:.2} {}", cpu, label))
+                .collect();
+            let cpu_content = format!("CPU Top {}\n{}", viz.global.cpu_breakdown.len(), cpu_lines.join("\n"));
+            let mut p = Panel::from_content(&cpu_content, ops_max_chars.min(MAX_LINE_CHARS));
+            p.x = RIGHT_EDGE - RIGHT_MARGIN - p.width();
+            p.y = right_y;
+            right_y += p.height() + GAP;
+            right_panels.push(p);
+        }
+
+        // Non-default feature flags panel (below CPU breakdown) — only when
+        // something is toggled, so its presence is the signal.
+        if !viz.global.non_default_flags.is_empty() {
+            let flags_content = format!(
+                "Flags ({})\n{}",
+                viz.global.non_default_flags.len(),
+                viz.global.non_default_flags.join("\n")
+            );
+            let mut p = Panel::from_content(&flags_content, ops_max_chars.min(MAX_LINE_CHARS));
+            p.x = RIGHT_EDGE - RIGHT_MARGIN - p.width();
+            p.y = right_y;
+            right_panels.push(p);
+        }
 
         let right_column_left_x = right_column_left_x(&global_ops_panel);
 
         {
             let global = visualizer.global();
-            draw_global_layer(
-                global,
-                &global_ops_panel,
-                visibility_panel.as_ref(),
-                &styles,
-                cpu_samples,
-                cpu_limit_f32,
-                tick,
-            );
+            draw_global_layer(global, &global_ops_panel, &right_panels, &styles, cpu_samples, cpu_limit_f32, tick);
         }
 
         // Per-room: draw room layer first (left stack), then global layer (right Ops + bottom CPU) so the histogram is on top and visible.
         for (room_name, room_viz) in &viz.rooms {
-            let room_vis = visualizer.get_room(*room_name);
+            if !viz.is_drawn(*room_name) {
+                continue;
+            }
+
+            let room_vis = visualizer.get_room(*room_name, VisualLayer::Missions);
+
+            let ledger_line = room_viz
+                .energy_ledger
+                .map(|l| format!("\nEnergy/t: +{:.1} -{:.1} ({:+.1})", l.income, l.expense, l.net()))
+                .unwrap_or_default();
 
             let room_content = room_viz.room_visibility.as_ref().map(|rv| {
                 format!(
-                    "Room\nVisible: {}\nAge: {}\nOwner: {}\nReservation: {}\nSource Keeper: {}\nHostile creeps: {}\nHostile structs: {}",
-                    rv.visible, rv.age, rv.owner, rv.reservation, rv.source_keeper, rv.hostile_creeps, rv.hostile_structures
+                    "Room{}\nVisible: {}\nAge: {}\nOwner: {}\nReservation: {}\nSource Keeper: {}\nHostile creeps: {}\nHostile structs: {}",
+                    ledger_line, rv.visible, rv.age, rv.owner, rv.reservation, rv.source_keeper, rv.hostile_creeps, rv.hostile_structures
                 )
             });
 
@@ -1337,22 +1639,25 @@ impl<'a> System<'a> for RenderSystem {
             let spawn_content = if room_viz.spawn_queue.is_empty() {
                 "Spawn".to_string()
             } else {
-                let lines: Vec<String> = room_viz
-                    .spawn_queue
-                    .iter()
-                    .map(|s| format!("{:.0} · {} · {}", s.priority, s.cost, s.description))
-                    .collect();
-                format!("Spawn\n{}", lines.join("\n"))
+                format!("Spawn\n{}", crate::ui::spawn_queue_lines(&room_viz.spawn_queue).join("\n"))
+            };
+
+            let left_w = left_column_width(right_column_left_x);
+
+            // Room sidebar (top-left) — the left stack continues below it.
+            let left_top_y = match room_viz.sidebar {
+                Some(ref sidebar) => TOP_Y + sidebar.draw(room_vis, LEFT_X, TOP_Y, left_w, &styles) + GAP,
+                None => TOP_Y,
             };
 
             let panels = layout_room_left_panels(
                 right_column_left_x,
+                left_top_y,
                 room_content.as_deref(),
                 &missions_content,
                 &jobs_content,
                 &spawn_content,
             );
-            let left_w = left_column_width(right_column_left_x);
 
             for panel in &panels {
                 let h = panel.height();
@@ -1396,7 +1701,7 @@ impl<'a> System<'a> for RenderSystem {
             draw_global_layer(
                 room_vis,
                 &global_ops_panel,
-                visibility_panel.as_ref(),
+                &right_panels,
                 &styles,
                 cpu_samples,
                 cpu_limit_f32,
@@ -1409,11 +1714,11 @@ impl<'a> System<'a> for RenderSystem {
 // ─── Claim map visuals ───────────────────────────────────────────────────────
 
 /// Draw claim system debug visuals on the game map.
-/// Uses MapVisual API to color rooms by suitability and draw arrows for active claims.
-fn draw_claim_map_visuals(claim_data: &ClaimVisualizationData) {
+/// Colors rooms by suitability and draws arrows for active claims.
+fn draw_claim_map_visuals(map_vis: &mut crate::visualize::MapVisualizer, claim_data: &ClaimVisualizationData) {
     use screeps::local::Position;
     use screeps::local::RoomCoordinate;
-    use screeps::{CircleStyle, MapTextStyle, MapVisual};
+    use screeps::{CircleStyle, MapTextStyle};
 
     let center = unsafe { RoomCoordinate::unchecked_new(25) };
 
@@ -1421,16 +1726,16 @@ fn draw_claim_map_visuals(claim_data: &ClaimVisualizationData) {
     for room_name in &claim_data.home_rooms {
         let pos = Position::new(center, center, *room_name);
         let style = CircleStyle::default().fill("#1f6feb").radius(8.0).opacity(0.35);
-        MapVisual::circle(pos, style);
+        map_vis.circle(pos, style);
     }
 
     // Unknown/blocking rooms: purple circle + "?" text
     for room_name in &claim_data.unknown_rooms {
         let pos = Position::new(center, center, *room_name);
         let style = CircleStyle::default().fill("#8b5cf6").radius(8.0).opacity(0.4);
-        MapVisual::circle(pos, style);
+        map_vis.circle(pos, style);
         let text_style = MapTextStyle::default().color("#c4b5fd").font_size(10.0).opacity(0.8);
-        MapVisual::text(pos, "?".to_string(), text_style);
+        map_vis.text(pos, "?".to_string(), text_style);
     }
 
     // Candidate rooms: colored by score, with raw sub-scores displayed
@@ -1444,17 +1749,17 @@ fn draw_claim_map_visuals(claim_data: &ClaimVisualizationData) {
             ("#f85149", 0.30) // red - low score
         };
         let style = CircleStyle::default().fill(color).radius(8.0).opacity(fill_opacity);
-        MapVisual::circle(pos, style);
+        map_vis.circle(pos, style);
 
         // Total score (top line)
         let text_style = MapTextStyle::default().color("#c9d1d9").font_size(6.0).opacity(0.85);
-        MapVisual::text(pos, format!("{:.2}", score), text_style);
+        map_vis.text(pos, format!("{:.2}", score), text_style);
 
         // Raw sub-scores: R=intrinsic-roi U=unlock D=support-decay P=plan (below center)
         let sub_pos = Position::new(center, unsafe { RoomCoordinate::unchecked_new(35) }, *room_name);
         let sub_style = MapTextStyle::default().color("#8b949e").font_size(4.0).opacity(0.75);
         let plan_label = sub.plan.map(|p| format!(" P{:.1}", p)).unwrap_or_default();
-        MapVisual::text(
+        map_vis.text(
             sub_pos,
             format!("R{:.2} U{:.2} D{:.2}{}", sub.roi, sub.unlock, sub.decay, plan_label),
             sub_style,
@@ -1465,16 +1770,103 @@ fn draw_claim_map_visuals(claim_data: &ClaimVisualizationData) {
     for (home_rooms, target_room) in &claim_data.active_claims {
         let target_pos = Position::new(center, center, *target_room);
         let style = CircleStyle::default().fill("#3fb950").radius(10.0).opacity(0.5);
-        MapVisual::circle(target_pos, style);
+        map_vis.circle(target_pos, style);
 
         let text_style = MapTextStyle::default().color("#3fb950").font_size(8.0).opacity(0.9);
-        MapVisual::text(target_pos, "CLAIM".to_string(), text_style);
+        map_vis.text(target_pos, "CLAIM".to_string(), text_style);
 
         // Draw arrows from each home room to the claim target
         for home_room in home_rooms {
             let home_pos = Position::new(center, center, *home_room);
             let line_style = LineStyle::default().color("#58a6ff").width(1.5).opacity(0.7);
-            MapVisual::line(home_pos, target_pos, line_style);
+            map_vis.line(home_pos, target_pos, line_style);
         }
     }
 }
+
+// ─── Upgrade focus map visuals ───────────────────────────────────────────────
+
+/// Label each owned room with its controller projection, and ring the
+/// upgrade focus room.
+fn draw_upgrade_focus_map_visuals(map_vis: &mut crate::visualize::MapVisualizer, upgrade_focus: &crate::upgrade_focus::UpgradeFocus) {
+    use screeps::local::Position;
+    use screeps::local::RoomCoordinate;
+    use screeps::{CircleStyle, MapTextStyle};
+
+    let center = unsafe { RoomCoordinate::unchecked_new(25) };
+    let label_y = unsafe { RoomCoordinate::unchecked_new(42) };
+
+    if let Some(room_name) = upgrade_focus.focus() {
+        let pos = Position::new(center, center, room_name);
+        let style = CircleStyle::default().fill("#f2cc60").radius(12.0).opacity(0.25);
+        map_vis.circle(pos, style);
+    }
+
+    for (room_name, projection) in upgrade_focus.rooms() {
+        let Some(ticks) = projection.ticks_to_next() else {
+            continue;
+        };
+
+        let (color, focus_label) = if upgrade_focus.is_focus(*room_name) {
+            ("#f2cc60", " FOCUS")
+        } else if projection.next_is_milestone() {
+            ("#c9d1d9", "")
+        } else {
+            ("#8b949e", "")
+        };
+
+        let pos = Position::new(center, label_y, *room_name);
+        let text_style = MapTextStyle::default().color(color).font_size(5.0).opacity(0.85);
+        map_vis.text(
+            pos,
+            format!("RCL{} {}t{}", projection.level + 1, compact_number(ticks), focus_label),
+            text_style,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn room(name: &str) -> RoomName {
+        name.parse().expect("valid room name")
+    }
+
+    #[test]
+    fn limit_depth_collapses_deeper_levels_into_counts() {
+        let tree = SummaryContent::Tree {
+            label: "Colony".to_string(),
+            children: vec![
+                SummaryContent::Lines {
+                    header: "Mining".to_string(),
+                    items: vec!["S1".to_string(), "S2".to_string()],
+                },
+                SummaryContent::Text("Upgrade".to_string()),
+            ],
+        };
+
+        assert_eq!(tree.clone().limit_depth(0).to_lines().len(), 5);
+        assert_eq!(tree.clone().limit_depth(1).to_lines(), vec!["Colony (+2)".to_string()]);
+        assert_eq!(
+            tree.limit_depth(2).to_lines(),
+            vec!["Colony".to_string(), "  Mining (+2)".to_string(), "  Upgrade".to_string()]
+        );
+    }
+
+    #[test]
+    fn room_page_rotates_through_the_rooms_by_name() {
+        let rooms = vec![room("W3N1"), room("W1N1"), room("W2N1")];
+
+        assert_eq!(room_page(rooms.clone(), 0, 10, 0), None);
+        assert_eq!(room_page(rooms.clone(), 3, 10, 0), None);
+
+        let first = room_page(rooms.clone(), 2, 10, 5).unwrap();
+        assert_eq!(first, [room("W1N1"), room("W2N1")].into_iter().collect());
+
+        let second = room_page(rooms.clone(), 2, 10, 15).unwrap();
+        assert_eq!(second, [room("W3N1")].into_iter().collect());
+
+        assert_eq!(room_page(rooms, 2, 10, 25), Some(first));
+    }
+}
@@ -1,6 +1,6 @@
 use screeps::*;
 use specs::prelude::*;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Per-target visual cap (P1.C6 / IBEX-008): the server enforces a
 /// ~500 KB serialized-visual limit per target and THROWS past it —
@@ -9,6 +9,14 @@ use std::collections::HashMap;
 /// drops-with-telemetry instead of throwing.
 pub const MAX_VISUALS_PER_TARGET: usize = 4_000;
 
+/// Serialized-visual bytes drawn into one room per tick: the server's ~500 KB
+/// per-target limit less headroom for the payload framing and for estimates
+/// that run short.
+pub const ROOM_VISUAL_BUDGET_BYTES: usize = 450_000;
+
+/// Coordinates closer than this count as the same when merging visuals.
+const MERGE_EPSILON: f32 = 0.001;
+
 /// All coordinates finite? Non-finite values corrupt the whole visual
 /// payload for the target (IBEX-008's "renderer corrupts all
 /// rendering" mode) — droppable at push time.
@@ -16,8 +24,141 @@ fn coords_ok(coords: &[f32]) -> bool {
     coords.iter().all(|c| c.is_finite())
 }
 
+fn near(a: f32, b: f32) -> bool {
+    (a - b).abs() < MERGE_EPSILON
+}
+
+/// Counts the bytes written through it.
+struct ByteCount(usize);
+
+impl std::io::Write for ByteCount {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Bytes `value` takes serialized, as the server measures visuals.
+fn serialized_len<T: serde::Serialize>(value: &T) -> usize {
+    let mut count = ByteCount(0);
+    let _ = serde_json::to_writer(&mut count, value);
+    count.0
+}
+
+/// The layer a room visual is drawn into. When a room's visuals would run
+/// past [`ROOM_VISUAL_BUDGET_BYTES`] the lowest layers are cut first: each
+/// variant gives way to the ones after it. Layers draw in this order, so the
+/// higher ones land on top.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum VisualLayer {
+    /// Transfer queue nodes and haul flows.
+    Transfer,
+    /// Creep paths and job markers.
+    Creeps,
+    /// Room plans.
+    Plan,
+    /// Mission summaries and the room sidebar.
+    Missions,
+    /// The threat overlay.
+    Threat,
+}
+
+/// What is left of one room's visual budget as its layers are admitted,
+/// highest first.
+pub struct RenderBudget {
+    bytes: usize,
+    visuals: usize,
+    /// Layers cut short or dropped, with the visuals they lost.
+    truncated: Vec<(VisualLayer, usize)>,
+}
+
+impl RenderBudget {
+    pub fn new(bytes: usize, visuals: usize) -> RenderBudget {
+        RenderBudget {
+            bytes,
+            visuals,
+            truncated: Vec::new(),
+        }
+    }
+
+    /// How many of `layer`'s visuals, of the given serialized sizes, fit in
+    /// what is left. Once one layer is cut, every later (lower) one is
+    /// dropped whole rather than drawn in pieces.
+    pub fn admit(&mut self, layer: VisualLayer, sizes: &[u32]) -> usize {
+        let mut admitted = 0;
+
+        if self.truncated.is_empty() {
+            for size in sizes {
+                let size = *size as usize;
+                if self.visuals == 0 || size > self.bytes {
+                    break;
+                }
+                self.bytes -= size;
+                self.visuals -= 1;
+                admitted += 1;
+            }
+        }
+
+        if admitted < sizes.len() {
+            self.truncated.push((layer, sizes.len() - admitted));
+        }
+
+        admitted
+    }
+
+    pub fn truncated(&self) -> &[(VisualLayer, usize)] {
+        &self.truncated
+    }
+}
+
+/// A rect or text held back in case the next call extends it.
+enum PendingVisual {
+    Rect {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        style: Option<RectStyle>,
+        key: String,
+    },
+    Text {
+        x: f32,
+        y: f32,
+        text: String,
+        style: Option<TextStyle>,
+        key: String,
+    },
+}
+
+impl PendingVisual {
+    fn into_visual(self) -> Visual {
+        match self {
+            PendingVisual::Rect {
+                x,
+                y,
+                width,
+                height,
+                style,
+                ..
+            } => Visual::rect(x, y, width, height, style),
+            PendingVisual::Text { x, y, text, style, .. } => Visual::text(x, y, text, style),
+        }
+    }
+}
+
+/// Buffers one target's visuals for the tick. Rects of the same style that
+/// continue the previous one along a row or column are merged into it, and
+/// text drawn at the anchor and style of the previous text joins it as
+/// another line, so dense overlays cost fewer visuals.
 pub struct RoomVisualizer {
     visuals: Vec<Visual>,
+    /// Serialized size of each of `visuals`.
+    sizes: Vec<u32>,
+    pending: Option<PendingVisual>,
     dropped_non_finite: u32,
 }
 
@@ -26,21 +167,42 @@ impl RoomVisualizer {
     pub fn new() -> RoomVisualizer {
         RoomVisualizer {
             visuals: vec![],
+            sizes: vec![],
+            pending: None,
             dropped_non_finite: 0,
         }
     }
 
     pub fn clear(&mut self) {
         self.visuals.clear();
+        self.sizes.clear();
+        self.pending = None;
         self.dropped_non_finite = 0;
     }
 
+    fn push(&mut self, visual: Visual) {
+        self.flush();
+        self.push_sized(visual);
+    }
+
+    fn push_sized(&mut self, visual: Visual) {
+        self.sizes.push(serialized_len(&visual) as u32);
+        self.visuals.push(visual);
+    }
+
+    /// Push the held-back rect or text, if any.
+    fn flush(&mut self) {
+        if let Some(pending) = self.pending.take() {
+            self.push_sized(pending.into_visual());
+        }
+    }
+
     pub fn circle(&mut self, x: f32, y: f32, style: Option<CircleStyle>) {
         if !coords_ok(&[x, y]) {
             self.dropped_non_finite += 1;
             return;
         }
-        self.visuals.push(Visual::circle(x, y, style));
+        self.push(Visual::circle(x, y, style));
     }
 
     pub fn line(&mut self, from: (f32, f32), to: (f32, f32), style: Option<LineStyle>) {
@@ -48,7 +210,7 @@ impl RoomVisualizer {
             self.dropped_non_finite += 1;
             return;
         }
-        self.visuals.push(Visual::line(from, to, style));
+        self.push(Visual::line(from, to, style));
     }
 
     pub fn rect(&mut self, x: f32, y: f32, width: f32, height: f32, style: Option<RectStyle>) {
@@ -56,7 +218,39 @@ impl RoomVisualizer {
             self.dropped_non_finite += 1;
             return;
         }
-        self.visuals.push(Visual::rect(x, y, width, height, style));
+
+        let key = serde_json::to_string(&style).unwrap_or_default();
+
+        if let Some(PendingVisual::Rect {
+            x: px,
+            y: py,
+            width: pw,
+            height: ph,
+            key: pending_key,
+            ..
+        }) = &mut self.pending
+        {
+            if *pending_key == key {
+                if near(*py, y) && near(*ph, height) && near(*px + *pw, x) {
+                    *pw += width;
+                    return;
+                }
+                if near(*px, x) && near(*pw, width) && near(*py + *ph, y) {
+                    *ph += height;
+                    return;
+                }
+            }
+        }
+
+        self.flush();
+        self.pending = Some(PendingVisual::Rect {
+            x,
+            y,
+            width,
+            height,
+            style,
+            key,
+        });
     }
 
     pub fn poly(&mut self, points: Vec<(f32, f32)>, style: Option<PolyStyle>) {
@@ -64,7 +258,7 @@ impl RoomVisualizer {
             self.dropped_non_finite += 1;
             return;
         }
-        self.visuals.push(Visual::poly(points, style));
+        self.push(Visual::poly(points, style));
     }
 
     pub fn text(&mut self, x: f32, y: f32, text: String, style: Option<TextStyle>) {
@@ -72,10 +266,30 @@ impl RoomVisualizer {
             self.dropped_non_finite += 1;
             return;
         }
-        self.visuals.push(Visual::text(x, y, text, style));
+
+        let key = serde_json::to_string(&style).unwrap_or_default();
+
+        if let Some(PendingVisual::Text {
+            x: px,
+            y: py,
+            text: pending_text,
+            key: pending_key,
+            ..
+        }) = &mut self.pending
+        {
+            if *pending_key == key && near(*px, x) && near(*py, y) {
+                pending_text.push('\n');
+                pending_text.push_str(&text);
+                return;
+            }
+        }
+
+        self.flush();
+        self.pending = Some(PendingVisual::Text { x, y, text, style, key });
     }
 
-    pub fn apply(&self, room_name: Option<RoomName>) {
+    pub fn apply(&mut self, room_name: Option<RoomName>) {
+        self.flush();
         if self.dropped_non_finite > 0 {
             log::warn!(
                 "visuals: dropped {} non-finite visual(s) for {:?} (IBEX-008 clamp)",
@@ -97,6 +311,44 @@ impl RoomVisualizer {
     }
 }
 
+/// Draw one room's layers as a single batch, cutting the lowest ones to fit
+/// [`ROOM_VISUAL_BUDGET_BYTES`] and the per-target visual cap.
+fn apply_layers(room_name: RoomName, layers: &mut BTreeMap<VisualLayer, RoomVisualizer>) {
+    let mut budget = RenderBudget::new(ROOM_VISUAL_BUDGET_BYTES, MAX_VISUALS_PER_TARGET);
+    let mut admitted = Vec::with_capacity(layers.len());
+
+    for (layer, visualizer) in layers.iter_mut().rev() {
+        visualizer.flush();
+        if visualizer.dropped_non_finite > 0 {
+            log::warn!(
+                "visuals: dropped {} non-finite visual(s) for {} {:?} (IBEX-008 clamp)",
+                visualizer.dropped_non_finite,
+                room_name,
+                layer
+            );
+        }
+        admitted.push((*layer, budget.admit(*layer, &visualizer.sizes)));
+    }
+
+    if !budget.truncated().is_empty() {
+        log::warn!(
+            "visuals: {} over its {} byte budget; cut (layer, visuals): {:?}",
+            room_name,
+            ROOM_VISUAL_BUDGET_BYTES,
+            budget.truncated()
+        );
+    }
+
+    let mut visuals = Vec::new();
+    for (layer, count) in admitted.into_iter().rev() {
+        if let Some(visualizer) = layers.get_mut(&layer) {
+            visuals.extend(visualizer.visuals.drain(..count));
+        }
+    }
+
+    screeps::RoomVisual::new(Some(room_name)).draw_multi(&visuals);
+}
+
 impl screeps_visual::render::VisualBackend for RoomVisualizer {
     fn circle(&mut self, x: f32, y: f32, radius: f32, fill: Option<&str>, stroke: Option<&str>, stroke_width: f32, opacity: f32) {
         let mut style = CircleStyle::default().radius(radius).opacity(opacity);
@@ -142,9 +394,75 @@ impl screeps_visual::render::VisualBackend for RoomVisualizer {
     }
 }
 
+enum MapVisualEntry {
+    Circle(Position, CircleStyle),
+    Line(Position, Position, LineStyle),
+    Text(Position, String, MapTextStyle),
+}
+
+/// World-map counterpart of [`RoomVisualizer`]: buffers `MapVisual` calls
+/// for the tick so they share the per-target cap and are flushed (or
+/// dropped) together by [`ApplyVisualsSystem`].
+pub struct MapVisualizer {
+    visuals: Vec<MapVisualEntry>,
+}
+
+#[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
+impl MapVisualizer {
+    pub fn new() -> MapVisualizer {
+        MapVisualizer { visuals: vec![] }
+    }
+
+    pub fn clear(&mut self) {
+        self.visuals.clear();
+    }
+
+    pub fn circle(&mut self, pos: Position, style: CircleStyle) {
+        self.visuals.push(MapVisualEntry::Circle(pos, style));
+    }
+
+    pub fn line(&mut self, from: Position, to: Position, style: LineStyle) {
+        self.visuals.push(MapVisualEntry::Line(from, to, style));
+    }
+
+    pub fn text(&mut self, pos: Position, text: String, style: MapTextStyle) {
+        self.visuals.push(MapVisualEntry::Text(pos, text, style));
+    }
+
+    /// Draw through the per-call `MapVisual` API (string-based; the batched
+    /// path goes through serde_wasm_bindgen and corrupts styles).
+    pub fn apply(&self) {
+        let visuals = if self.visuals.len() > MAX_VISUALS_PER_TARGET {
+            log::warn!(
+                "visuals: {} map visuals exceed the per-target cap {}; truncating (IBEX-008 size guard)",
+                self.visuals.len(),
+                MAX_VISUALS_PER_TARGET
+            );
+            &self.visuals[..MAX_VISUALS_PER_TARGET]
+        } else {
+            &self.visuals[..]
+        };
+
+        for visual in visuals {
+            match visual {
+                MapVisualEntry::Circle(pos, style) => MapVisual::circle(*pos, style.clone()),
+                MapVisualEntry::Line(from, to, style) => MapVisual::line(*from, *to, style.clone()),
+                MapVisualEntry::Text(pos, text, style) => MapVisual::text(*pos, text.clone(), style.clone()),
+            }
+        }
+    }
+}
+
+impl Default for MapVisualizer {
+    fn default() -> MapVisualizer {
+        MapVisualizer::new()
+    }
+}
+
 pub struct Visualizer {
     global: RoomVisualizer,
-    rooms: HashMap<RoomName, RoomVisualizer>,
+    map: MapVisualizer,
+    rooms: HashMap<RoomName, BTreeMap<VisualLayer, RoomVisualizer>>,
 }
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -152,6 +470,7 @@ impl Visualizer {
     pub fn new() -> Visualizer {
         Visualizer {
             global: RoomVisualizer::new(),
+            map: MapVisualizer::new(),
             rooms: HashMap::new(),
         }
     }
@@ -160,8 +479,13 @@ impl Visualizer {
         &mut self.global
     }
 
-    pub fn get_room(&mut self, room: RoomName) -> &mut RoomVisualizer {
-        self.rooms.entry(room).or_insert_with(RoomVisualizer::new)
+    pub fn map(&mut self) -> &mut MapVisualizer {
+        &mut self.map
+    }
+
+    /// The buffer for `layer` of `room`'s visuals.
+    pub fn get_room(&mut self, room: RoomName, layer: VisualLayer) -> &mut RoomVisualizer {
+        self.rooms.entry(room).or_default().entry(layer).or_insert_with(RoomVisualizer::new)
     }
 }
 
@@ -174,10 +498,15 @@ impl Default for Visualizer {
 #[derive(SystemData)]
 pub struct ApplyVisualsSystemData<'a> {
     visualizer: Option<Write<'a, Visualizer>>,
+    features: Read<'a, crate::features::Features>,
+    feature_overrides: Read<'a, crate::features::FeatureOverrides>,
 }
 
 /// Flushes the Visualizer resource to the game (e.g. console::add_visual).
 /// Named to avoid confusion with "visualization" / RenderSystem.
+/// Rooms whose `visualize` override resolves off are dropped here, and the
+/// global and map layers only draw while the global flag is on. Each room's
+/// layers go out as one batch within its byte budget.
 pub struct ApplyVisualsSystem;
 
 #[cfg_attr(feature = "profile", screeps_timing_annotate::timing)]
@@ -186,12 +515,18 @@ impl<'a> System<'a> for ApplyVisualsSystem {
 
     fn run(&mut self, mut data: Self::SystemData) {
         if let Some(visualizer) = &mut data.visualizer {
-            visualizer.global.apply(None);
+            if data.features.visualize.on {
+                visualizer.global.apply(None);
+                visualizer.map.apply();
+            }
 
             visualizer.global.clear();
+            visualizer.map.clear();
 
-            for (room, room_visualizer) in &visualizer.rooms {
-                room_visualizer.apply(Some(*room));
+            for (room, layers) in visualizer.rooms.iter_mut() {
+                if data.features.for_room(&data.feature_overrides, *room).visualize.on {
+                    apply_layers(*room, layers);
+                }
             }
 
             visualizer.rooms.clear();
@@ -201,7 +536,7 @@ impl<'a> System<'a> for ApplyVisualsSystem {
 
 #[cfg(test)]
 mod visual_guard_tests {
-    use super::coords_ok;
+    use super::*;
 
     /// P1.C6 / IBEX-008: non-finite coordinates are droppable at push
     /// time — one NaN visual corrupts the whole target's payload.
@@ -213,4 +548,56 @@ mod visual_guard_tests {
         assert!(!coords_ok(&[f32::NEG_INFINITY]));
         assert!(coords_ok(&[]));
     }
+
+    #[test]
+    fn adjacent_same_style_rects_merge_along_rows_and_columns() {
+        let fill = || Some(RectStyle::default().fill("#ff0000"));
+        let mut vis = RoomVisualizer::new();
+
+        vis.rect(1.0, 1.0, 1.0, 1.0, fill());
+        vis.rect(2.0, 1.0, 1.0, 1.0, fill());
+        vis.rect(3.0, 1.0, 2.0, 1.0, fill());
+        // Same row, different style.
+        vis.rect(5.0, 1.0, 1.0, 1.0, Some(RectStyle::default().fill("#00ff00")));
+        vis.rect(5.0, 2.0, 1.0, 1.0, Some(RectStyle::default().fill("#00ff00")));
+        // A gap.
+        vis.rect(7.0, 2.0, 1.0, 1.0, Some(RectStyle::default().fill("#00ff00")));
+        vis.flush();
+
+        assert_eq!(vis.visuals.len(), 3);
+        assert_eq!(vis.sizes.len(), 3);
+    }
+
+    #[test]
+    fn text_at_the_same_anchor_joins_the_previous_line() {
+        let style = || Some(TextStyle::default().font(0.3));
+        let mut vis = RoomVisualizer::new();
+
+        vis.text(10.0, 10.0, "a".to_string(), style());
+        vis.text(10.0, 10.0, "b".to_string(), style());
+        vis.circle(10.0, 10.0, None);
+        vis.text(10.0, 10.0, "c".to_string(), style());
+        vis.text(11.0, 10.0, "d".to_string(), style());
+        vis.flush();
+
+        assert_eq!(vis.visuals.len(), 4);
+        assert_eq!(
+            vis.sizes[0] as usize,
+            serialized_len(&Visual::text(10.0, 10.0, "a\nb".to_string(), style()))
+        );
+    }
+
+    #[test]
+    fn budget_cuts_the_lowest_layers_first() {
+        let mut budget = RenderBudget::new(1_000, 100);
+
+        assert_eq!(budget.admit(VisualLayer::Threat, &[300, 300]), 2);
+        assert_eq!(budget.admit(VisualLayer::Missions, &[200, 300]), 1);
+        // Once a layer is cut, the ones below it don't draw in pieces.
+        assert_eq!(budget.admit(VisualLayer::Transfer, &[10]), 0);
+        assert_eq!(budget.truncated(), &[(VisualLayer::Missions, 1), (VisualLayer::Transfer, 1)]);
+
+        let mut budget = RenderBudget::new(1_000, 2);
+        assert_eq!(budget.admit(VisualLayer::Threat, &[1, 1, 1]), 2);
+    }
 }
@@ -0,0 +1,383 @@
+//! Versioned, per-entity framing for the component world save.
+//!
+//! The component payload used to be one positional bincode stream: a
+//! reshaped component misaligned everything after it, so the only safe
+//! answer to a version mismatch was a full reset. Each entity is now its
+//! own length-prefixed record, and each of its components its own
+//! length-prefixed slot (integers little-endian):
+//!
+//! ```text
+//! [u32 version][u32 record count]
+//! record: [u32 len][u32 marker len][marker][u8 slot count] slot*
+//! slot:   [u8 present] ([u32 len][component bytes])?
+//! ```
+//!
+//! Slots are positional within a record, so a new component is appended
+//! as a trailing slot (older records simply lack it). On load, slot bytes
+//! written by an older version pass through the [`MigrationRegistry`]
+//! before the caller decodes them; a record that fails to frame, migrate
+//! or decode drops just that entity. World format v25 is the first written
+//! this way.
+
+use bincode::{DefaultOptions, Options};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// One entity's marker and component slots, each still bincode-encoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityRecord {
+    pub marker: Vec<u8>,
+    pub components: Vec<Option<Vec<u8>>>,
+}
+
+/// bincode-encode one value with the world-save options.
+pub fn encode_value<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    DefaultOptions::new().serialize(value).map_err(|e| e.to_string())
+}
+
+/// Decode one value; trailing bytes are rejected, which catches most
+/// shape changes that slipped past a version bump.
+pub fn decode_value<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, String> {
+    DefaultOptions::new().deserialize(bytes).map_err(|e| e.to_string())
+}
+
+/// Encode an optional component into a slot.
+pub fn encode_slot<T: Serialize>(value: &Option<T>) -> Result<Option<Vec<u8>>, String> {
+    value.as_ref().map(encode_value).transpose()
+}
+
+/// Frame one record, length prefix included.
+pub fn frame_record(record: &EntityRecord) -> Vec<u8> {
+    let slot_bytes: usize = record
+        .components
+        .iter()
+        .map(|slot| 5 + slot.as_ref().map(|b| b.len()).unwrap_or(0))
+        .sum();
+    let mut frame = Vec::with_capacity(9 + record.marker.len() + slot_bytes);
+    frame.extend_from_slice(&0u32.to_le_bytes());
+
+    frame.extend_from_slice(&(record.marker.len() as u32).to_le_bytes());
+    frame.extend_from_slice(&record.marker);
+    frame.push(record.components.len() as u8);
+
+    for slot in record.components.iter() {
+        match slot {
+            Some(bytes) => {
+                frame.push(1);
+                frame.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+                frame.extend_from_slice(bytes);
+            }
+            None => frame.push(0),
+        }
+    }
+
+    let len = (frame.len() - 4) as u32;
+    frame[..4].copy_from_slice(&len.to_le_bytes());
+    frame
+}
+
+/// Builds a framed payload record by record.
+pub struct WorldWriter {
+    buffer: Vec<u8>,
+    count: u32,
+}
+
+impl WorldWriter {
+    pub fn new(version: u32) -> WorldWriter {
+        let mut buffer = Vec::with_capacity(1024 * 50);
+        buffer.extend_from_slice(&version.to_le_bytes());
+        buffer.extend_from_slice(&0u32.to_le_bytes());
+
+        WorldWriter { buffer, count: 0 }
+    }
+
+    pub fn push(&mut self, record: &EntityRecord) {
+        self.push_framed(&frame_record(record));
+    }
+
+    /// Append a record already framed by [`frame_record`] — the
+    /// incremental save reuses the previous tick's frame for clean entities.
+    pub fn push_framed(&mut self, frame: &[u8]) {
+        self.buffer.extend_from_slice(frame);
+        self.count += 1;
+    }
+
+    pub fn finish(mut self) -> Vec<u8> {
+        self.buffer[4..8].copy_from_slice(&self.count.to_le_bytes());
+        self.buffer
+    }
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if self.bytes.len() < len {
+            return Err(format!("truncated: wanted {} byte(s), {} left", len, self.bytes.len()));
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+fn read_record(bytes: &[u8]) -> Result<EntityRecord, String> {
+    let mut cursor = Cursor { bytes };
+
+    let marker_len = cursor.u32()? as usize;
+    let marker = cursor.take(marker_len)?.to_vec();
+    let slot_count = cursor.u8()?;
+
+    let mut components = Vec::with_capacity(slot_count as usize);
+    for _ in 0..slot_count {
+        let slot = match cursor.u8()? {
+            0 => None,
+            1 => {
+                let len = cursor.u32()? as usize;
+                Some(cursor.take(len)?.to_vec())
+            }
+            flag => return Err(format!("bad slot flag {}", flag)),
+        };
+        components.push(slot);
+    }
+
+    if !cursor.bytes.is_empty() {
+        return Err(format!("{} trailing byte(s)", cursor.bytes.len()));
+    }
+
+    Ok(EntityRecord { marker, components })
+}
+
+/// The version a payload was written with, if it is long enough to have one.
+pub fn read_version(bytes: &[u8]) -> Option<u32> {
+    Cursor { bytes }.u32().ok()
+}
+
+/// Split a payload into its version and per-entity records. Only a
+/// missing header fails the whole payload; a malformed record is
+/// returned as an `Err` entry in place, and a record whose length runs
+/// past the end ends the list with one.
+pub fn read_world(bytes: &[u8]) -> Result<(u32, Vec<Result<EntityRecord, String>>), String> {
+    let mut cursor = Cursor { bytes };
+
+    let version = cursor.u32()?;
+    let count = cursor.u32()?;
+
+    let mut records = Vec::new();
+    for index in 0..count {
+        let framed = cursor.u32().and_then(|len| cursor.take(len as usize));
+        match framed {
+            Ok(record) => records.push(read_record(record).map_err(|e| format!("record {}: {}", index, e))),
+            Err(e) => {
+                records.push(Err(format!("record {}: {}", index, e)));
+                break;
+            }
+        }
+    }
+
+    Ok((version, records))
+}
+
+/// One migration step: a component's bytes as written at `from_version`
+/// in, the same component's bytes at `from_version + 1` out. Steps decode
+/// with a private copy of the old shape and re-encode the new one.
+pub type MigrateFn = fn(Vec<u8>) -> Result<Vec<u8>, String>;
+
+/// Per-component migration steps, keyed by component name and the
+/// version they migrate from.
+#[derive(Default)]
+pub struct MigrationRegistry {
+    steps: Vec<(&'static str, u32, MigrateFn)>,
+}
+
+impl MigrationRegistry {
+    pub fn register(&mut self, component: &'static str, from_version: u32, migrate: MigrateFn) -> &mut Self {
+        self.steps.push((component, from_version, migrate));
+        self
+    }
+
+    /// Bring `component`'s bytes from version `from` up to `to`. Versions
+    /// with no step for this component didn't reshape it, so its bytes
+    /// pass through unchanged.
+    pub fn migrate(&self, component: &str, from: u32, to: u32, mut bytes: Vec<u8>) -> Result<Vec<u8>, String> {
+        for version in from..to {
+            for (name, from_version, migrate) in self.steps.iter() {
+                if *name == component && *from_version == version {
+                    bytes = migrate(bytes).map_err(|e| format!("{} v{}->v{}: {}", component, version, version + 1, e))?;
+                }
+            }
+        }
+
+        Ok(bytes)
+    }
+}
+
+/// What the last world save did, for the stats export.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WorldSaveStats {
+    /// Every entity was re-encoded (forced interval, a deletion, or
+    /// incremental saving off).
+    pub full: bool,
+    pub encoded: u32,
+    pub reused: u32,
+    /// CPU spent turning components into records this save.
+    pub encode_cpu: f64,
+    /// Encode CPU of the most recent full save — the baseline an
+    /// incremental save is compared against.
+    pub last_full_cpu: Option<f64>,
+}
+
+impl WorldSaveStats {
+    pub fn record(&mut self, full: bool, encoded: u32, reused: u32, encode_cpu: f64) {
+        self.full = full;
+        self.encoded = encoded;
+        self.reused = reused;
+        self.encode_cpu = encode_cpu;
+        if full {
+            self.last_full_cpu = Some(encode_cpu);
+        }
+    }
+
+    /// Estimated encode CPU the last save avoided versus a full one.
+    pub fn cpu_saved(&self) -> f64 {
+        match self.last_full_cpu {
+            Some(full_cpu) if !self.full => (full_cpu - self.encode_cpu).max(0.0),
+            _ => 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct PointV25 {
+        x: u32,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct PointV26 {
+        x: u32,
+        y: u32,
+    }
+
+    fn point_v25_to_v26(bytes: Vec<u8>) -> Result<Vec<u8>, String> {
+        let old: PointV25 = decode_value(&bytes)?;
+        encode_value(&PointV26 { x: old.x, y: 0 })
+    }
+
+    /// A v25 payload as written to memory: entity marker 1 carries a
+    /// `PointV25 { x: 7 }` and an empty second slot; the second record's
+    /// slot claims 9 bytes but holds 1.
+    const FIXTURE_V25: &[u8] = &[
+        25, 0, 0, 0, 2, 0, 0, 0, //
+        13, 0, 0, 0, 1, 0, 0, 0, 1, 2, 1, 1, 0, 0, 0, 7, 0, //
+        12, 0, 0, 0, 1, 0, 0, 0, 2, 1, 1, 9, 0, 0, 0, 5,
+    ];
+
+    #[test]
+    fn round_trips_records() {
+        let records = vec![
+            EntityRecord {
+                marker: encode_value(&1u64).unwrap(),
+                components: vec![encode_slot(&Some(PointV26 { x: 3, y: 4 })).unwrap(), None],
+            },
+            EntityRecord {
+                marker: encode_value(&2u64).unwrap(),
+                components: vec![],
+            },
+        ];
+
+        let mut writer = WorldWriter::new(26);
+        for record in records.iter() {
+            writer.push(record);
+        }
+        let bytes = writer.finish();
+
+        assert_eq!(read_version(&bytes), Some(26));
+        let (version, read) = read_world(&bytes).unwrap();
+        assert_eq!(version, 26);
+        assert_eq!(read.into_iter().collect::<Result<Vec<_>, _>>().unwrap(), records);
+    }
+
+    /// The prior-version fixture loads through the registry: the intact
+    /// entity migrates, the corrupt one is reported on its own.
+    #[test]
+    fn migrates_fixture_and_isolates_corrupt_entity() {
+        let mut registry = MigrationRegistry::default();
+        registry.register("Point", 25, point_v25_to_v26);
+
+        let (version, records) = read_world(FIXTURE_V25).unwrap();
+        assert_eq!(version, 25);
+        assert_eq!(records.len(), 2);
+        assert!(records[1].is_err());
+
+        let record = records[0].as_ref().unwrap();
+        assert_eq!(decode_value::<u64>(&record.marker).unwrap(), 1);
+        assert_eq!(record.components[1], None);
+
+        let bytes = registry
+            .migrate("Point", version, 26, record.components[0].clone().unwrap())
+            .unwrap();
+        assert_eq!(decode_value::<PointV26>(&bytes).unwrap(), PointV26 { x: 7, y: 0 });
+
+        // Without the step the old bytes no longer decode as the new shape.
+        let raw = record.components[0].clone().unwrap();
+        assert!(decode_value::<PointV26>(&raw).is_err());
+    }
+
+    /// Reused frames splice into the payload exactly like fresh records.
+    #[test]
+    fn framed_records_splice_into_payload() {
+        let record = EntityRecord {
+            marker: encode_value(&5u64).unwrap(),
+            components: vec![None, encode_slot(&Some(PointV25 { x: 9 })).unwrap()],
+        };
+
+        let mut fresh = WorldWriter::new(26);
+        fresh.push(&record);
+
+        let mut reused = WorldWriter::new(26);
+        reused.push_framed(&frame_record(&record));
+
+        assert_eq!(fresh.finish(), reused.finish());
+    }
+
+    #[test]
+    fn save_stats_measure_against_last_full() {
+        let mut stats = WorldSaveStats::default();
+        stats.record(false, 3, 0, 1.0);
+        assert_eq!(stats.cpu_saved(), 0.0);
+
+        stats.record(true, 100, 0, 6.0);
+        assert_eq!(stats.cpu_saved(), 0.0);
+
+        stats.record(false, 10, 90, 1.5);
+        assert_eq!(stats.cpu_saved(), 4.5);
+    }
+
+    #[test]
+    fn unregistered_components_pass_through() {
+        let registry = MigrationRegistry::default();
+        assert_eq!(registry.migrate("Other", 25, 30, vec![1, 2, 3]).unwrap(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn truncated_header_fails_whole_payload() {
+        assert!(read_world(&[25, 0, 0]).is_err());
+        assert_eq!(read_version(&[25, 0]), None);
+    }
+}